@@ -0,0 +1,124 @@
+//! 引擎基准测试（criterion）
+//!
+//! 运行：`cargo bench --bench engine`，HTML 报告位于 `target/criterion/`。
+//! 覆盖词法分析、解析、优化、代表性脚本的求值、两种执行后端，以及 AST 缓存命中路径。
+
+use aether::optimizer::Optimizer;
+use aether::{Aether, Backend, Lexer, Parser, Token};
+use criterion::{BatchSize, Criterion, criterion_group, criterion_main};
+use std::hint::black_box;
+
+const FIB: &str = r#"
+Func FIB(N) {
+    If (N < 2) { Return N }
+    Return FIB(N - 1) + FIB(N - 2)
+}
+FIB(15)
+"#;
+
+const PAYROLL: &str = r#"
+Set EMPLOYEES []
+Set I 0
+While (I < 200) {
+    Set EMPLOYEES PUSH(EMPLOYEES, {"id": I, "base_salary": 8000 + I * 50, "bonus": I % 7 * 100})
+    Set I (I + 1)
+}
+Set RESULT PAYROLL_RUN(EMPLOYEES, {"housing_rate": 0.12})
+RESULT["totals"]["gross"]
+"#;
+
+const STRINGS: &str = r#"
+Set WORDS SPLIT("the quick brown fox jumps over the lazy dog", " ")
+Set OUT []
+For W In WORDS {
+    Set OUT PUSH(OUT, UPPER(TRIM(W)))
+}
+Set TEXT JOIN(OUT, "-")
+Set I 0
+While (I < 50) {
+    Set TEXT REPLACE(TEXT, "-", "_")
+    Set I (I + 1)
+}
+LEN(TEXT)
+"#;
+
+const SCRIPTS: &[(&str, &str)] = &[("fib", FIB), ("payroll", PAYROLL), ("strings", STRINGS)];
+
+fn lex_all(code: &str) -> usize {
+    let mut lexer = Lexer::new(code);
+    let mut count = 0;
+    while lexer.next_token() != Token::EOF {
+        count += 1;
+    }
+    count
+}
+
+fn bench_frontend(c: &mut Criterion) {
+    let mut group = c.benchmark_group("frontend");
+    for (name, code) in SCRIPTS {
+        group.bench_function(format!("lex/{}", name), |b| {
+            b.iter(|| lex_all(black_box(code)))
+        });
+        group.bench_function(format!("parse/{}", name), |b| {
+            b.iter(|| Parser::new(black_box(code)).parse_program().unwrap())
+        });
+        let program = Parser::new(code).parse_program().unwrap();
+        let optimizer = Optimizer::new();
+        group.bench_function(format!("optimize/{}", name), |b| {
+            b.iter(|| optimizer.optimize_program(black_box(&program)))
+        });
+    }
+    group.finish();
+}
+
+fn bench_eval(c: &mut Criterion) {
+    let mut group = c.benchmark_group("eval");
+    for (name, code) in SCRIPTS {
+        // 每次迭代使用新引擎：包含解析和优化，不命中缓存
+        group.bench_function(format!("cold/{}", name), |b| {
+            b.iter_batched(
+                Aether::new,
+                |mut engine| engine.eval(black_box(code)).unwrap(),
+                BatchSize::SmallInput,
+            )
+        });
+        // 复用引擎：AST 缓存命中，只计求值
+        let mut engine = Aether::new();
+        engine.eval(code).unwrap();
+        group.bench_function(format!("cached/{}", name), |b| {
+            b.iter(|| engine.eval(black_box(code)).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn bench_backends(c: &mut Criterion) {
+    let code = "Set TOTAL 0\nSet I 0\nWhile (I < 2000) {\n    Set TOTAL TOTAL + I * I % 7\n    Set I I + 1\n}\nTOTAL";
+    let mut group = c.benchmark_group("backend");
+    for backend in [Backend::Interpreter, Backend::Bytecode] {
+        let mut engine = Aether::new().with_execution_backend(backend);
+        engine.eval(code).unwrap();
+        group.bench_function(format!("{:?}/hot_loop", backend), |b| {
+            b.iter(|| engine.eval(black_box(code)).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn bench_result_cache(c: &mut Criterion) {
+    let code = "Set TOTAL 0\nFor X In RANGE(0, 500) { Set TOTAL (TOTAL + X * X) }\nTOTAL";
+    let mut engine = Aether::new();
+    engine.eval_pure(code, &[]).unwrap();
+    c.bench_function("cache/pure_result_hit", |b| {
+        b.iter(|| engine.eval_pure(black_box(code), &[]).unwrap())
+    });
+}
+
+criterion_group!(
+    benches,
+    bench_frontend,
+    bench_eval,
+    bench_backends,
+    bench_result_cache
+);
+criterion_main!(benches);
@@ -6,6 +6,39 @@
 #include <stdint.h>
 #include <stdlib.h>
 
+/**
+ * Default maximum number of steps kept by a recording
+ */
+#define DEFAULT_MAX_RECORDED_STEPS 100000
+
+/**
+ * Maximum nesting of expressions and blocks
+ *
+ * Parsing and evaluation recurse once per level, so unbounded nesting (e.g. thousands of
+ * `(`) would overflow the stack instead of reporting an error.
+ */
+#define MAX_NESTING_DEPTH 64
+
+/**
+ * 当前插件 ABI 版本
+ */
+#define AETHER_PLUGIN_ABI_VERSION 1
+
+/**
+ * 录制文件格式版本
+ */
+#define TRACE_FORMAT_VERSION 1
+
+/**
+ * 保留的执行时间样本数量（超出后丢弃最早的样本）
+ */
+#define MetricsCollector_MAX_SAMPLES 1024
+
+/**
+ * Aether 语言版本
+ */
+typedef struct LanguageVersion LanguageVersion;
+
 /**
  * Opaque handle for Aether engine
  */
@@ -31,6 +64,10 @@ typedef struct AetherCacheStats {
   int size;
 } AetherCacheStats;
 
+
+
+
+
 #ifdef __cplusplus
 extern "C" {
 #endif // __cplusplus
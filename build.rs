@@ -25,6 +25,36 @@ fn main() {
 
     // 验证标准库文件
     validate_stdlib(&crate_dir);
+
+    #[cfg(feature = "grpc")]
+    compile_grpc_proto();
+}
+
+/// 由 proto/aether/v1/aether.proto 生成 gRPC 消息和服务代码
+///
+/// 用 protobuf-parse 的纯 Rust 解析器得到描述符，交给 tonic-build 生成代码，
+/// 构建环境不需要安装 protoc。
+#[cfg(feature = "grpc")]
+fn compile_grpc_proto() {
+    use protobuf::Message;
+
+    let proto = "proto/aether/v1/aether.proto";
+    println!("cargo:rerun-if-changed={}", proto);
+
+    let descriptors = protobuf_parse::Parser::new()
+        .pure()
+        .include("proto")
+        .input(proto)
+        .file_descriptor_set()
+        .expect("Failed to parse aether.proto");
+    let bytes = descriptors
+        .write_to_bytes()
+        .expect("Failed to encode aether.proto descriptors");
+    let descriptors = <tonic_build::FileDescriptorSet as prost::Message>::decode(bytes.as_slice())
+        .expect("Failed to decode aether.proto descriptors");
+    tonic_build::configure()
+        .compile_fds(descriptors)
+        .expect("Failed to generate gRPC code");
 }
 
 /// 验证标准库文件的语法正确性
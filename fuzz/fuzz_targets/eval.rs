@@ -0,0 +1,19 @@
+#![no_main]
+
+use aether::{Aether, ExecutionLimits, IOPermissions};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|code: &str| {
+    // 禁止 IO，并收紧步数/递归/时长/内存限制，让无限循环、深递归和不断增长的集合以错误结束
+    let mut engine = Aether::with_permissions(IOPermissions::deny_all());
+    engine.set_limits(ExecutionLimits {
+        max_steps: Some(10_000),
+        max_recursion_depth: Some(64),
+        max_duration_ms: Some(1_000),
+        max_loop_iterations: Some(1_000),
+        max_array_len: Some(100_000),
+        max_dict_entries: Some(100_000),
+        max_memory_bytes: Some(64 * 1024 * 1024),
+    });
+    let _ = engine.eval(code);
+});
@@ -0,0 +1,8 @@
+#![no_main]
+
+use aether::lexer::Lexer;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|code: &str| {
+    let _ = Lexer::tokenize_with_spans(code);
+});
@@ -0,0 +1,8 @@
+#![no_main]
+
+use aether::parser::Parser;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|code: &str| {
+    let _ = Parser::new(code).parse_program();
+});
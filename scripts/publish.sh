@@ -31,6 +31,11 @@ cargo test --all || {
     echo "❌ 测试失败"
     exit 1
 }
+# 最小核心构建（default-features = false）也必须通过测试
+cargo test --all --no-default-features || {
+    echo "❌ 最小构建测试失败"
+    exit 1
+}
 
 echo ""
 echo "🔍 步骤 2/7: 检查代码格式..."
@@ -0,0 +1,703 @@
+// src/analysis.rs
+//! 静态分析
+//!
+//! 不执行脚本，只遍历 AST，收集脚本引用和定义的名称、导入的模块和扩展块，
+//! 并据此判断脚本需要哪些权限（`analyze`）以及是否为纯计算（`purity_violations`），
+//! 或找出可疑的代码（`lint`）。
+
+use crate::ast::{Expr, Stmt};
+use crate::builtins::{BuiltInRegistry, IOPermissions};
+use crate::parser::{ParseError, Parser};
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::sync::OnceLock;
+
+/// 内置函数是否会读写外部状态或引入不确定性
+///
+/// 内置函数默认不纯，只有注册时声明为纯函数的才是纯函数（见 [`BuiltInRegistry::is_pure`]）；
+/// 未知的名称也视为不纯。
+pub fn is_impure_builtin(name: &str) -> bool {
+    !crate::builtins::metadata::is_pure_builtin(name)
+}
+
+/// 程序中出现的名称
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProgramSymbols {
+    /// 读取或调用的名称（包括函数体和 Lambda 中的）
+    pub referenced: BTreeSet<String>,
+    /// 脚本自己绑定的名称（变量、函数、参数、循环变量、导入名）
+    pub bound: BTreeSet<String>,
+    /// 通过 Set / 下标赋值 / Lazy 写入的变量
+    pub assigned: BTreeSet<String>,
+    /// 导入的模块路径
+    pub imports: Vec<String>,
+    /// 使用的扩展块标签
+    pub extensions: Vec<String>,
+}
+
+impl ProgramSymbols {
+    /// 收集程序中的名称
+    pub fn collect<'a>(program: impl IntoIterator<Item = &'a Stmt>) -> Self {
+        let mut symbols = ProgramSymbols::default();
+        for stmt in program {
+            symbols.visit_stmt(stmt);
+        }
+        symbols
+    }
+
+    /// 引用了但脚本没有绑定的名称
+    pub fn free_names(&self) -> impl Iterator<Item = &String> {
+        self.referenced
+            .iter()
+            .filter(|name| !self.bound.contains(*name))
+    }
+
+    fn visit_block(&mut self, stmts: &[Stmt]) {
+        for stmt in stmts {
+            self.visit_stmt(stmt);
+        }
+    }
+
+    fn visit_stmt(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::Set { name, value } => {
+                self.bound.insert(name.clone());
+                self.assigned.insert(name.clone());
+                self.visit_expr(value);
+            }
+            Stmt::SetIndex {
+                object,
+                index,
+                value,
+            } => {
+                if let Expr::Identifier(name) = object.as_ref() {
+                    self.assigned.insert(name.clone());
+                }
+                self.visit_expr(object);
+                self.visit_expr(index);
+                self.visit_expr(value);
+            }
+            Stmt::FuncDef { name, params, body } | Stmt::GeneratorDef { name, params, body } => {
+                self.bound.insert(name.clone());
+                self.bound.extend(params.iter().cloned());
+                self.visit_block(body);
+            }
+            Stmt::LazyDef { name, expr } => {
+                self.bound.insert(name.clone());
+                self.assigned.insert(name.clone());
+                self.visit_expr(expr);
+            }
+            Stmt::Return(expr) | Stmt::Yield(expr) | Stmt::Throw(expr) | Stmt::Expression(expr) => {
+                self.visit_expr(expr)
+            }
+            Stmt::Break | Stmt::Continue | Stmt::Export(_) | Stmt::Pragma { .. } => {}
+            Stmt::Located { stmt, .. } => self.visit_stmt(stmt),
+            Stmt::While { condition, body } => {
+                self.visit_expr(condition);
+                self.visit_block(body);
+            }
+            Stmt::For {
+                var,
+                iterable,
+                body,
+            } => {
+                self.bound.insert(var.clone());
+                self.visit_expr(iterable);
+                self.visit_block(body);
+            }
+            Stmt::ForIndexed {
+                index_var,
+                value_var,
+                iterable,
+                body,
+            } => {
+                self.bound.insert(index_var.clone());
+                self.bound.insert(value_var.clone());
+                self.visit_expr(iterable);
+                self.visit_block(body);
+            }
+            Stmt::Switch {
+                expr,
+                cases,
+                default,
+            } => {
+                self.visit_expr(expr);
+                for (case, body) in cases {
+                    self.visit_expr(case);
+                    self.visit_block(body);
+                }
+                if let Some(body) = default {
+                    self.visit_block(body);
+                }
+            }
+            Stmt::Try {
+                body,
+                catch,
+                finally,
+            } => {
+                self.visit_block(body);
+                if let Some((var, handler)) = catch {
+                    self.bound.insert(var.clone());
+                    self.visit_block(handler);
+                }
+                if let Some(cleanup) = finally {
+                    self.visit_block(cleanup);
+                }
+            }
+            Stmt::Import {
+                names,
+                path,
+                aliases,
+                namespace,
+            } => {
+                self.imports.push(path.clone());
+                for (i, name) in names.iter().enumerate() {
+                    let bound = aliases.get(i).cloned().flatten().unwrap_or(name.clone());
+                    self.bound.insert(bound);
+                }
+                if let Some(ns) = namespace {
+                    self.bound.insert(ns.clone());
+                }
+            }
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Number(_)
+            | Expr::Integer(_)
+            | Expr::BigInteger(_)
+            | Expr::String(_)
+            | Expr::Boolean(_)
+            | Expr::Null => {}
+            Expr::Identifier(name) => {
+                self.referenced.insert(name.clone());
+            }
+            Expr::Binary { left, right, .. } => {
+                self.visit_expr(left);
+                self.visit_expr(right);
+            }
+            Expr::Unary { expr, .. } => self.visit_expr(expr),
+            Expr::Call { func, args } => {
+                self.visit_expr(func);
+                args.iter().for_each(|arg| self.visit_expr(arg));
+            }
+            Expr::Array(items) => items.iter().for_each(|item| self.visit_expr(item)),
+            Expr::Dict(pairs) => pairs.iter().for_each(|(_, value)| self.visit_expr(value)),
+            Expr::Index { object, index } => {
+                self.visit_expr(object);
+                self.visit_expr(index);
+            }
+            Expr::If {
+                condition,
+                then_branch,
+                elif_branches,
+                else_branch,
+            } => {
+                self.visit_expr(condition);
+                self.visit_block(then_branch);
+                for (cond, body) in elif_branches {
+                    self.visit_expr(cond);
+                    self.visit_block(body);
+                }
+                if let Some(body) = else_branch {
+                    self.visit_block(body);
+                }
+            }
+            Expr::Lambda { params, body } => {
+                self.bound.extend(params.iter().cloned());
+                self.visit_block(body);
+            }
+            Expr::Extension { tag, .. } => {
+                if !self.extensions.contains(tag) {
+                    self.extensions.push(tag.clone());
+                }
+            }
+        }
+    }
+}
+
+/// 检查程序是否为纯计算，返回所有违反项（为空表示纯）
+///
+/// 纯程序不导入模块、不使用扩展块、不调用不纯的内置函数，
+/// 并且只读取自己定义的名称、`inputs` 中的输入和内置函数。
+/// `is_builtin` 用于判断未绑定的名称是否为内置函数，`is_pure` 判断内置函数是否为纯函数
+/// （通常取自注册表的元数据，见 [`BuiltInRegistry::is_pure`]）。
+///
+/// # 示例
+/// ```
+/// use aether::Parser;
+/// use aether::analysis::purity_violations;
+///
+/// let program = Parser::new("Set Y X * 2\nPRINT(Y)").parse_program().unwrap();
+/// let violations = purity_violations(&program, &["X"], |name| name == "PRINT", |_| false);
+/// assert_eq!(violations, vec!["uses impure builtin 'PRINT'".to_string()]);
+/// ```
+pub fn purity_violations<'a>(
+    program: impl IntoIterator<Item = &'a Stmt>,
+    inputs: &[&str],
+    is_builtin: impl Fn(&str) -> bool,
+    is_pure: impl Fn(&str) -> bool,
+) -> Vec<String> {
+    let symbols = ProgramSymbols::collect(program);
+    let mut violations = Vec::new();
+
+    for path in &symbols.imports {
+        violations.push(format!("imports module '{}'", path));
+    }
+    for tag in &symbols.extensions {
+        violations.push(format!("uses extension block '@{}'", tag));
+    }
+    for name in symbols.free_names() {
+        if inputs.contains(&name.as_str()) {
+            continue;
+        }
+        // 未授予权限时没有注册的 IO 函数同样算作不纯
+        let known_impure = crate::builtins::metadata::is_known_impure_builtin(name);
+        if known_impure || (is_builtin(name) && !is_pure(name)) {
+            violations.push(format!("uses impure builtin '{}'", name));
+        } else if !is_builtin(name) {
+            violations.push(format!(
+                "reads '{}', which is neither an input nor defined by the script",
+                name
+            ));
+        }
+    }
+    violations
+}
+
+/// 脚本运行所需的权限
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Permission {
+    /// 文件系统（READ_FILE、KV_OPEN 等，见 `IOPermissions::filesystem_enabled`）
+    Filesystem,
+    /// 网络（HTTP_GET、WS_CONNECT 等，见 `IOPermissions::network_enabled`）
+    Network,
+}
+
+impl Permission {
+    /// 权限名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Permission::Filesystem => "filesystem",
+            Permission::Network => "network",
+        }
+    }
+
+    /// 内置函数需要的权限（不需要权限时返回 None）
+    ///
+    /// 由注册表推导，见 [`BuiltInRegistry::metadata`]。
+    pub fn required_by(builtin: &str) -> Option<Permission> {
+        crate::builtins::metadata::required_permission(builtin)
+    }
+
+    /// 权限配置是否包含该权限
+    pub fn is_granted(&self, permissions: &IOPermissions) -> bool {
+        match self {
+            Permission::Filesystem => permissions.filesystem_enabled,
+            Permission::Network => permissions.network_enabled,
+        }
+    }
+}
+
+impl fmt::Display for Permission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// 脚本的运行需求报告
+///
+/// 宿主可以在运行脚本之前检查报告，例如向用户展示授权确认，
+/// 或拒绝需要未授予权限的脚本。
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RequirementsReport {
+    /// 需要的权限
+    pub permissions: Vec<Permission>,
+    /// 使用的内置函数
+    pub builtins: Vec<String>,
+    /// 读写外部状态或引入不确定性的内置函数（见 `is_impure_builtin`）
+    pub impure_builtins: Vec<String>,
+    /// 导入的模块
+    pub modules: Vec<String>,
+    /// 使用的扩展块标签
+    pub extensions: Vec<String>,
+    /// 脚本读取、但既不是内置函数也没有在脚本中定义的名称（需要宿主提供）
+    pub unresolved: Vec<String>,
+}
+
+impl RequirementsReport {
+    /// 由程序生成报告，`is_builtin` 判断名称是否为内置函数
+    pub fn from_program<'a>(
+        program: impl IntoIterator<Item = &'a Stmt>,
+        is_builtin: impl Fn(&str) -> bool,
+    ) -> Self {
+        let symbols = ProgramSymbols::collect(program);
+        let mut report = RequirementsReport {
+            modules: symbols.imports.clone(),
+            extensions: symbols.extensions.clone(),
+            ..Default::default()
+        };
+        for name in symbols.free_names() {
+            if is_builtin(name) || Permission::required_by(name).is_some() {
+                report.builtins.push(name.clone());
+                if let Some(permission) = Permission::required_by(name)
+                    && !report.permissions.contains(&permission)
+                {
+                    report.permissions.push(permission);
+                }
+                if is_impure_builtin(name) {
+                    report.impure_builtins.push(name.clone());
+                }
+            } else {
+                report.unresolved.push(name.clone());
+            }
+        }
+        report.permissions.sort();
+        report
+    }
+
+    /// 权限配置缺少的权限
+    pub fn missing_permissions(&self, permissions: &IOPermissions) -> Vec<Permission> {
+        self.permissions
+            .iter()
+            .filter(|p| !p.is_granted(permissions))
+            .copied()
+            .collect()
+    }
+
+    /// 是否为纯计算（不需要权限、不导入模块、不使用扩展块和不纯的内置函数）
+    pub fn is_pure(&self) -> bool {
+        self.permissions.is_empty()
+            && self.impure_builtins.is_empty()
+            && self.modules.is_empty()
+            && self.extensions.is_empty()
+    }
+}
+
+impl fmt::Display for RequirementsReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn line(f: &mut fmt::Formatter<'_>, label: &str, items: &[String]) -> fmt::Result {
+            if items.is_empty() {
+                writeln!(f, "{}: none", label)
+            } else {
+                writeln!(f, "{}: {}", label, items.join(", "))
+            }
+        }
+        let permissions: Vec<String> = self.permissions.iter().map(|p| p.to_string()).collect();
+        line(f, "permissions", &permissions)?;
+        line(f, "builtins", &self.builtins)?;
+        line(f, "impure builtins", &self.impure_builtins)?;
+        line(f, "modules", &self.modules)?;
+        line(f, "extensions", &self.extensions)?;
+        line(f, "unresolved", &self.unresolved)
+    }
+}
+
+/// 所有内置函数的名称（包括需要权限的函数）
+pub(crate) fn builtin_names() -> &'static BTreeSet<String> {
+    static NAMES: OnceLock<BTreeSet<String>> = OnceLock::new();
+    NAMES.get_or_init(|| {
+        BuiltInRegistry::with_permissions(IOPermissions::allow_all())
+            .names()
+            .into_iter()
+            .collect()
+    })
+}
+
+/// 分析脚本的运行需求（权限、内置函数、模块、扩展块），不执行脚本
+///
+/// 使用标准语法解析；使用方言或需要考虑引擎中已定义的全局变量时，
+/// 请使用 `Aether::analyze`。
+///
+/// # 示例
+/// ```
+/// use aether::analysis::{Permission, analyze};
+/// use aether::IOPermissions;
+///
+/// let report = analyze("Set DATA READ_FILE(PATH)\nHTTP_POST(URL, DATA)").unwrap();
+/// assert_eq!(report.permissions, vec![Permission::Filesystem, Permission::Network]);
+/// assert_eq!(report.builtins, vec!["HTTP_POST", "READ_FILE"]);
+/// assert_eq!(report.unresolved, vec!["PATH", "URL"]);
+/// assert_eq!(
+///     report.missing_permissions(&IOPermissions::default()),
+///     vec![Permission::Filesystem, Permission::Network]
+/// );
+/// ```
+pub fn analyze(code: &str) -> Result<RequirementsReport, ParseError> {
+    let program = Parser::new(code).parse_program()?;
+    Ok(RequirementsReport::from_program(&program, |name| {
+        builtin_names().contains(name)
+    }))
+}
+
+/// 调用图中的一个节点：用户函数或顶层代码
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct FunctionNode {
+    /// 函数名（顶层代码为 `CallGraph::MAIN`）
+    pub name: String,
+    /// 参数
+    pub params: Vec<String>,
+    /// 调用或引用的用户函数
+    pub calls: Vec<String>,
+    /// 使用的内置函数
+    pub builtins: Vec<String>,
+    /// 读取的外部变量（既不是参数、局部变量，也不是函数）
+    pub reads: Vec<String>,
+    /// 写入的变量（顶层代码写入的是全局变量，函数中写入的是局部变量）
+    pub writes: Vec<String>,
+}
+
+impl FunctionNode {
+    fn build<'a>(
+        name: &str,
+        params: &[String],
+        body: impl IntoIterator<Item = &'a Stmt>,
+        functions: &BTreeSet<String>,
+        is_builtin: &impl Fn(&str) -> bool,
+    ) -> Self {
+        let mut symbols = ProgramSymbols::collect(body);
+        symbols.bound.extend(params.iter().cloned());
+        let mut node = FunctionNode {
+            name: name.to_string(),
+            params: params.to_vec(),
+            writes: symbols.assigned.iter().cloned().collect(),
+            ..Default::default()
+        };
+        for referenced in &symbols.referenced {
+            if functions.contains(referenced) {
+                node.calls.push(referenced.clone());
+            } else if symbols.bound.contains(referenced) {
+                continue;
+            } else if is_builtin(referenced) || Permission::required_by(referenced).is_some() {
+                node.builtins.push(referenced.clone());
+            } else {
+                node.reads.push(referenced.clone());
+            }
+        }
+        node
+    }
+}
+
+/// 程序的调用图
+///
+/// 节点为顶层定义的函数和生成器，以及顶层代码本身（`CallGraph::MAIN`）。
+/// 嵌套函数和 Lambda 计入外层函数；把函数作为值传递（如 `MAP(XS, F)`）也算作调用。
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CallGraph {
+    /// 顶层代码
+    pub main: FunctionNode,
+    /// 用户函数，按定义顺序
+    pub functions: Vec<FunctionNode>,
+}
+
+impl CallGraph {
+    /// 顶层代码节点的名称
+    pub const MAIN: &'static str = "<main>";
+
+    /// 由程序生成调用图，`is_builtin` 判断名称是否为内置函数
+    pub fn from_program<'a>(
+        program: impl IntoIterator<Item = &'a Stmt> + Clone,
+        is_builtin: impl Fn(&str) -> bool,
+    ) -> Self {
+        let defined: Vec<(&String, &Vec<String>, &Vec<Stmt>)> = program
+            .clone()
+            .into_iter()
+            .filter_map(|stmt| match stmt.node() {
+                Stmt::FuncDef { name, params, body }
+                | Stmt::GeneratorDef { name, params, body } => Some((name, params, body)),
+                _ => None,
+            })
+            .collect();
+        let names: BTreeSet<String> = defined.iter().map(|(name, _, _)| (*name).clone()).collect();
+
+        let top_level = program.into_iter().filter(|stmt| {
+            !matches!(
+                stmt.node(),
+                Stmt::FuncDef { .. } | Stmt::GeneratorDef { .. }
+            )
+        });
+        let main = FunctionNode::build(Self::MAIN, &[], top_level, &names, &is_builtin);
+
+        let mut functions: Vec<FunctionNode> = Vec::new();
+        for (name, params, body) in defined {
+            let node = FunctionNode::build(name, params, body, &names, &is_builtin);
+            // 重复定义时保留最后一次定义
+            functions.retain(|existing| &existing.name != name);
+            functions.push(node);
+        }
+        CallGraph { main, functions }
+    }
+
+    /// 按名称查找节点（`CallGraph::MAIN` 为顶层代码）
+    pub fn node(&self, name: &str) -> Option<&FunctionNode> {
+        if name == Self::MAIN {
+            return Some(&self.main);
+        }
+        self.functions.iter().find(|node| node.name == name)
+    }
+
+    /// 调用（或引用）了指定函数的节点名称
+    pub fn callers(&self, name: &str) -> Vec<String> {
+        std::iter::once(&self.main)
+            .chain(&self.functions)
+            .filter(|node| node.calls.iter().any(|call| call == name))
+            .map(|node| node.name.clone())
+            .collect()
+    }
+
+    /// 从指定节点出发可达的用户函数（不含起点本身，除非存在递归）
+    pub fn reachable_from(&self, roots: &[&str]) -> BTreeSet<String> {
+        let mut reached = BTreeSet::new();
+        let mut pending: Vec<&str> = roots.to_vec();
+        while let Some(name) = pending.pop() {
+            if let Some(node) = self.node(name) {
+                for call in &node.calls {
+                    if reached.insert(call.clone()) {
+                        pending.push(call);
+                    }
+                }
+            }
+        }
+        reached
+    }
+
+    /// 从顶层代码不可达的函数（死函数），按定义顺序
+    pub fn unused_functions(&self) -> Vec<String> {
+        let reached = self.reachable_from(&[Self::MAIN]);
+        self.functions
+            .iter()
+            .filter(|node| !reached.contains(&node.name))
+            .map(|node| node.name.clone())
+            .collect()
+    }
+
+    /// 从指定节点出发实际会用到的内置函数
+    pub fn builtins_reachable_from(&self, roots: &[&str]) -> BTreeSet<String> {
+        let mut nodes: BTreeSet<String> = self.reachable_from(roots);
+        nodes.extend(roots.iter().map(|root| root.to_string()));
+        nodes
+            .iter()
+            .filter_map(|name| self.node(name))
+            .flat_map(|node| node.builtins.iter().cloned())
+            .collect()
+    }
+}
+
+/// 提取程序的调用图（使用标准语法解析）
+///
+/// # 示例
+/// ```
+/// use aether::analysis::call_graph;
+///
+/// let code = "Func AREA(R) { Return PI() * SQUARE(R) }\n\
+///             Func SQUARE(X) { Return X * X }\n\
+///             Func UNUSED() { Return 0 }\n\
+///             PRINTLN(AREA(RADIUS))";
+/// let graph = call_graph(code).unwrap();
+/// assert_eq!(graph.main.calls, vec!["AREA"]);
+/// assert_eq!(graph.main.reads, vec!["RADIUS"]);
+/// assert_eq!(graph.node("AREA").unwrap().calls, vec!["SQUARE"]);
+/// assert_eq!(graph.callers("SQUARE"), vec!["AREA"]);
+/// assert_eq!(graph.unused_functions(), vec!["UNUSED"]);
+/// ```
+pub fn call_graph(code: &str) -> Result<CallGraph, ParseError> {
+    let program = Parser::new(code).parse_program()?;
+    Ok(CallGraph::from_program(&program, |name| {
+        builtin_names().contains(name)
+    }))
+}
+
+/// 静态检查警告的类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LintKind {
+    /// 读取了脚本从未定义、也不是内置函数或标准库函数的名称
+    UndefinedName,
+    /// 定义了但从顶层代码不可达的函数
+    UnusedFunction,
+    /// 变量、函数或参数与内置函数同名
+    ShadowedBuiltin,
+}
+
+/// 静态检查发现的一个问题
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LintWarning {
+    /// 类别
+    pub kind: LintKind,
+    /// 相关的名称
+    pub name: String,
+}
+
+impl fmt::Display for LintWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            LintKind::UndefinedName => write!(f, "'{}' is never defined", self.name),
+            LintKind::UnusedFunction => write!(f, "function '{}' is never used", self.name),
+            LintKind::ShadowedBuiltin => write!(f, "'{}' shadows a builtin function", self.name),
+        }
+    }
+}
+
+/// 检查程序中可疑的代码，`is_builtin` 判断名称是否为内置函数，
+/// `is_known` 判断未定义的名称是否由环境提供（如标准库函数、宿主注入的变量）
+///
+/// 警告按类别排序：未定义的名称、未使用的函数、遮蔽内置函数的名称。
+pub fn lint_program<'a>(
+    program: impl IntoIterator<Item = &'a Stmt> + Clone,
+    is_builtin: impl Fn(&str) -> bool,
+    is_known: impl Fn(&str) -> bool,
+) -> Vec<LintWarning> {
+    let symbols = ProgramSymbols::collect(program.clone());
+    let graph = CallGraph::from_program(program, &is_builtin);
+    let warning = |kind, name: &String| LintWarning {
+        kind,
+        name: name.clone(),
+    };
+
+    let mut warnings: Vec<LintWarning> = symbols
+        .free_names()
+        .filter(|name| {
+            !is_builtin(name) && Permission::required_by(name).is_none() && !is_known(name)
+        })
+        .map(|name| warning(LintKind::UndefinedName, name))
+        .collect();
+    warnings.extend(
+        graph
+            .unused_functions()
+            .iter()
+            .map(|name| warning(LintKind::UnusedFunction, name)),
+    );
+    warnings.extend(
+        symbols
+            .bound
+            .iter()
+            .filter(|name| is_builtin(name) || Permission::required_by(name).is_some())
+            .map(|name| warning(LintKind::ShadowedBuiltin, name)),
+    );
+    warnings
+}
+
+/// 检查脚本中可疑的代码（使用标准语法解析，标准库函数视为已定义）
+///
+/// # 示例
+/// ```
+/// use aether::analysis::{LintKind, lint};
+///
+/// let code = "Func HELPER() { Return 1 }\nSet LEN 3\nPRINTLN(LEN + COUNT)";
+/// let kinds: Vec<LintKind> = lint(code).unwrap().iter().map(|w| w.kind).collect();
+/// assert_eq!(
+///     kinds,
+///     vec![LintKind::UndefinedName, LintKind::UnusedFunction, LintKind::ShadowedBuiltin]
+/// );
+/// ```
+pub fn lint(code: &str) -> Result<Vec<LintWarning>, ParseError> {
+    let program = Parser::new(code).parse_program()?;
+    Ok(lint_program(
+        &program,
+        |name| builtin_names().contains(name),
+        crate::stdlib::is_stdlib_function,
+    ))
+}
@@ -0,0 +1,51 @@
+use super::Aether;
+use crate::analysis::{CallGraph, RequirementsReport};
+
+impl Aether {
+    // ============================================================
+    // 静态分析
+    // ============================================================
+
+    /// 分析脚本的运行需求，不执行脚本
+    ///
+    /// 与 `analysis::analyze` 相同，但使用引擎的方言解析，内置函数别名视为内置函数，
+    /// 引擎全局作用域中已定义的名称（如预加载的标准库）不计入 `unresolved`。
+    ///
+    /// # 示例
+    /// ```
+    /// use aether::{Aether, IOPermissions};
+    ///
+    /// let mut engine = Aether::new();
+    /// engine.eval("Set BASE_URL \"https://example.com\"").unwrap();
+    /// let report = engine.analyze("HTTP_GET(BASE_URL + PATH)").unwrap();
+    /// assert_eq!(report.unresolved, vec!["PATH"]);
+    /// assert!(!report.missing_permissions(&IOPermissions::default()).is_empty());
+    /// ```
+    pub fn analyze(&self, code: &str) -> Result<RequirementsReport, String> {
+        let program = self
+            .evaluator
+            .parser(code)
+            .parse_program()
+            .map_err(|e| format!("Parse error: {}", e))?;
+        let mut report =
+            RequirementsReport::from_program(&program, |name| self.evaluator.is_builtin(name));
+        report
+            .unresolved
+            .retain(|name| self.evaluator.get_global(name).is_none());
+        Ok(report)
+    }
+
+    /// 提取脚本的调用图，不执行脚本
+    ///
+    /// 与 `analysis::call_graph` 相同，但使用引擎的方言解析，内置函数别名视为内置函数。
+    pub fn call_graph(&self, code: &str) -> Result<CallGraph, String> {
+        let program = self
+            .evaluator
+            .parser(code)
+            .parse_program()
+            .map_err(|e| format!("Parse error: {}", e))?;
+        Ok(CallGraph::from_program(&program, |name| {
+            self.evaluator.is_builtin(name)
+        }))
+    }
+}
@@ -0,0 +1,41 @@
+use super::Aether;
+use crate::compile::Backend;
+
+impl Aether {
+    // ============================================================
+    // 执行后端
+    // ============================================================
+
+    /// 使用指定的执行后端创建新的 Aether 引擎
+    pub fn with_execution_backend(mut self, backend: Backend) -> Self {
+        self.evaluator.set_backend(backend);
+        self
+    }
+
+    /// 选择顶层程序的执行后端
+    ///
+    /// `Backend::Bytecode` 把优化后的 AST 编译为字节码，在基于栈的虚拟机上执行，
+    /// 适合包含大量循环的脚本；虚拟机不支持的语句和表达式（以及函数体）仍交给
+    /// 解释器执行，因此两种后端的结果、错误和执行限制完全一致。开启执行录制时
+    /// 始终使用解释器。
+    ///
+    /// # 示例
+    /// ```
+    /// use aether::{Aether, Backend, Value};
+    ///
+    /// let mut engine = Aether::new();
+    /// engine.set_execution_backend(Backend::Bytecode);
+    /// let total = engine
+    ///     .eval("Set TOTAL 0\nFor I In RANGE(0, 100) {\n    Set TOTAL TOTAL + I\n}\nTOTAL")
+    ///     .unwrap();
+    /// assert_eq!(total, Value::Number(4950.0));
+    /// ```
+    pub fn set_execution_backend(&mut self, backend: Backend) {
+        self.evaluator.set_backend(backend);
+    }
+
+    /// 当前的执行后端
+    pub fn execution_backend(&self) -> Backend {
+        self.evaluator.backend()
+    }
+}
@@ -0,0 +1,91 @@
+use super::Aether;
+use crate::value::Value;
+use std::time::{Duration, Instant};
+
+/// `Aether::benchmark` 的测量结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchmarkReport {
+    /// 解析耗时（不经过 AST 缓存）
+    pub parse: Duration,
+    /// AST 优化耗时
+    pub optimize: Duration,
+    /// 求值次数（第一次求值作为预热，不计入）
+    pub iterations: usize,
+    /// 每次求值的平均耗时（命中 AST 缓存）
+    pub mean: Duration,
+    /// 最快一次求值
+    pub min: Duration,
+    /// 最慢一次求值
+    pub max: Duration,
+    /// 最后一次求值的结果
+    pub result: Value,
+}
+
+impl std::fmt::Display for BenchmarkReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "parse:    {:?}", self.parse)?;
+        writeln!(f, "optimize: {:?}", self.optimize)?;
+        write!(
+            f,
+            "eval:     mean {:?}, min {:?}, max {:?} ({} iterations)",
+            self.mean, self.min, self.max, self.iterations
+        )
+    }
+}
+
+impl Aether {
+    /// 每次 `benchmark` 的目标测量时长
+    pub const BENCHMARK_TARGET: Duration = Duration::from_millis(200);
+
+    /// 粗略测量一段代码的解析、优化和求值耗时
+    ///
+    /// 先单独计时解析和优化，再预热求值一次，之后反复求值约 `BENCHMARK_TARGET`
+    /// （至少 3 次、最多 10000 次）。求值在当前引擎上进行，脚本定义的全局变量会保留，
+    /// 需要隔离时请先 `fork()`。精确的回归跟踪请使用 `benches/` 中的 criterion 基准。
+    ///
+    /// # 示例
+    /// ```
+    /// use aether::{Aether, Value};
+    ///
+    /// let mut engine = Aether::new();
+    /// let report = engine.benchmark("Set X 0\nWhile (X < 100) { Set X (X + 1) }\nX").unwrap();
+    /// assert_eq!(report.result, Value::Number(100.0));
+    /// assert!(report.iterations >= 3);
+    /// assert!(report.min <= report.mean && report.mean <= report.max);
+    /// ```
+    pub fn benchmark(&mut self, code: &str) -> Result<BenchmarkReport, String> {
+        let started = Instant::now();
+        let program = self
+            .evaluator
+            .parser(code)
+            .parse_program()
+            .map_err(|e| format!("Parse error: {}", e))?;
+        let parse = started.elapsed();
+
+        let started = Instant::now();
+        self.optimizer.optimize_program(&program);
+        let optimize = started.elapsed();
+
+        let mut result = self.eval(code)?;
+        let mut samples = Vec::new();
+        let budget = Instant::now();
+        while samples.len() < 3
+            || (budget.elapsed() < Self::BENCHMARK_TARGET && samples.len() < 10_000)
+        {
+            let started = Instant::now();
+            result = self.eval(code)?;
+            samples.push(started.elapsed());
+        }
+
+        let total: Duration = samples.iter().sum();
+        Ok(BenchmarkReport {
+            parse,
+            optimize,
+            iterations: samples.len(),
+            mean: total / samples.len() as u32,
+            min: samples.iter().min().copied().unwrap_or_default(),
+            max: samples.iter().max().copied().unwrap_or_default(),
+            result,
+        })
+    }
+}
@@ -1,8 +1,13 @@
 use super::Aether;
 use crate::builtins::IOPermissions;
 use crate::evaluator::Evaluator;
+use crate::module_system::FileSystemModuleResolver;
 use crate::optimizer::Optimizer;
+use crate::result_cache::MemoryResultStore;
+use crate::sandbox::{SandboxConfig, SandboxPolicy};
 use crate::stdlib;
+use std::cell::RefCell;
+use std::rc::Rc;
 
 impl Aether {
     /// 创建新的 Aether 引擎实例
@@ -21,6 +26,7 @@ impl Aether {
             evaluator: Evaluator::with_permissions(permissions),
             cache: crate::cache::ASTCache::new(),
             optimizer: Optimizer::new(),
+            results: Rc::new(RefCell::new(MemoryResultStore::new())),
         }
     }
 
@@ -29,6 +35,37 @@ impl Aether {
         Self::with_permissions(IOPermissions::allow_all())
     }
 
+    /// 当前的 IO 权限
+    pub fn permissions(&self) -> &IOPermissions {
+        self.evaluator.permissions()
+    }
+
+    /// 调整运行中引擎的 IO 权限（例如 REPL 中的 `:grant fs`）
+    ///
+    /// 新允许的内置函数立即可用，撤销的内置函数从全局作用域移除；
+    /// 已定义的变量和函数保持不变。
+    pub fn set_permissions(&mut self, permissions: IOPermissions) {
+        self.evaluator.set_permissions(permissions);
+    }
+
+    /// 按沙箱配置创建新的 Aether 引擎
+    ///
+    /// 应用 IO 权限、执行限制和并发限制；模块策略不是 `Disabled` 时启用文件系统导入，
+    /// 并限制在 `module_restriction` 的根目录内。
+    pub fn with_sandbox(config: &SandboxConfig) -> Self {
+        let mut engine = Self::with_permissions(config.io_permissions.clone())
+            .with_limits(config.execution_limits.clone())
+            .with_concurrency_limits(config.concurrency_limits.clone());
+        if config.module_policy != SandboxPolicy::Disabled {
+            let restriction = config.module_restriction.as_ref();
+            engine.set_module_resolver(Box::new(FileSystemModuleResolver {
+                root_dir: restriction.map(|r| r.root_dir.clone()),
+                allow_absolute: restriction.is_none_or(|r| r.allow_absolute),
+            }));
+        }
+        engine
+    }
+
     /// 创建预加载标准库的新 Aether 引擎
     ///
     /// 这将创建一个具有所有权限的引擎，并自动加载
@@ -40,6 +77,48 @@ impl Aether {
     }
 }
 
+impl Aether {
+    /// 快速复制引擎
+    ///
+    /// 新引擎的全局作用域是当前引擎全局作用域的子作用域：内置函数、预加载的标准库
+    /// 和宿主设置的全局变量直接共享，不会重新加载。新引擎中的 Set / Func 只写入自己的
+    /// 作用域（同名定义会遮蔽共享的值），不会影响当前引擎和其他副本。
+    /// 权限、执行限制、方言、严格模式、扩展处理器、优化选项和 AST 缓存一并复制，
+    /// 纯脚本结果缓存与当前引擎共享。
+    ///
+    /// 适合 Web 服务为每个请求创建独立引擎：启动时准备好一个模板引擎，
+    /// 每个请求调用 `fork()`。
+    ///
+    /// 注意：
+    /// - 当前引擎之后定义的全局变量在副本中同样可见，模板引擎准备好后不应再修改
+    /// - 共享作用域中定义的函数按定义时的作用域查找变量，看不到副本中新定义的全局变量，
+    ///   需要的数据应通过参数传入
+    ///
+    /// # 示例
+    /// ```
+    /// use aether::{Aether, Value};
+    ///
+    /// let mut template = Aether::new();
+    /// template.eval("Func GREET(NAME) { Return \"Hello, \" + NAME }").unwrap();
+    ///
+    /// let mut request = template.fork();
+    /// request.eval("Set USER \"Ada\"").unwrap();
+    /// assert_eq!(
+    ///     request.eval("GREET(USER)").unwrap(),
+    ///     Value::String("Hello, Ada".to_string())
+    /// );
+    /// assert!(template.eval("USER").is_err());
+    /// ```
+    pub fn fork(&self) -> Self {
+        Aether {
+            evaluator: self.evaluator.fork(),
+            cache: self.cache.clone(),
+            optimizer: self.optimizer.clone(),
+            results: Rc::clone(&self.results),
+        }
+    }
+}
+
 impl Default for Aether {
     fn default() -> Self {
         Self::new()
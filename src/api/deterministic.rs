@@ -0,0 +1,42 @@
+use super::Aether;
+use crate::runtime::DeterministicConfig;
+
+impl Aether {
+    // ============================================================
+    // 确定性执行模式
+    // ============================================================
+
+    /// 使用确定性模式创建新的 Aether 引擎
+    pub fn with_deterministic(mut self, config: DeterministicConfig) -> Self {
+        self.set_deterministic(Some(config));
+        self
+    }
+
+    /// 开启（Some）或关闭（None）确定性模式
+    ///
+    /// 确定性模式下同一段脚本在相同输入下每次求值的结果逐字节相同，可用于审计回放和结果缓存：
+    /// - `RANDOM()` 使用配置的种子，每次 `eval` 都从同一序列开始
+    /// - `NOW()` 返回注入的时钟，`VALIDATE_ID_CN` 以注入时钟的日期判断出生日期
+    /// - `KEYS` / `VALUES` 和字典的字符串形式按键名排序
+    /// - 文件、网络、KV、调度、超时、并行任务和通道等内置函数被禁止
+    ///
+    /// # 示例
+    /// ```
+    /// use aether::{Aether, DeterministicConfig};
+    ///
+    /// let mut engine = Aether::new();
+    /// engine.set_deterministic(Some(DeterministicConfig::new(7)));
+    /// let first = engine.eval("[RANDOM(), RANDOM()]").unwrap();
+    /// let second = engine.eval("[RANDOM(), RANDOM()]").unwrap();
+    /// assert_eq!(first, second);
+    /// assert!(engine.eval("SPAWN(Lambda () -> 1)").is_err());
+    /// ```
+    pub fn set_deterministic(&mut self, config: Option<DeterministicConfig>) {
+        self.evaluator.set_deterministic(config);
+    }
+
+    /// 是否开启了确定性模式
+    pub fn is_deterministic(&self) -> bool {
+        self.evaluator.deterministic().is_some()
+    }
+}
@@ -0,0 +1,32 @@
+use super::Aether;
+use crate::dialect::Dialect;
+
+impl Aether {
+    // ============================================================
+    // 关键字方言
+    // ============================================================
+
+    /// 使用关键字方言创建新的 Aether 引擎
+    pub fn with_dialect(mut self, dialect: Dialect) -> Self {
+        self.set_dialect(dialect);
+        self
+    }
+
+    /// 设置关键字方言（同时影响 Import 的模块），标准关键字仍然可用
+    pub fn set_dialect(&mut self, dialect: Dialect) {
+        self.evaluator.set_dialect(Some(dialect));
+        // 同一段代码在不同方言下解析结果不同
+        self.cache.clear();
+    }
+
+    /// 恢复标准关键字
+    pub fn clear_dialect(&mut self) {
+        self.evaluator.set_dialect(None);
+        self.cache.clear();
+    }
+
+    /// 获取当前关键字方言
+    pub fn dialect(&self) -> Option<&Dialect> {
+        self.evaluator.dialect()
+    }
+}
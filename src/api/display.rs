@@ -0,0 +1,30 @@
+use super::Aether;
+use crate::runtime::DisplayOptions;
+use crate::value::Value;
+
+impl Aether {
+    // ============================================================
+    // 值显示配置
+    // ============================================================
+
+    /// 使用显示配置创建新的 Aether 引擎
+    pub fn with_display_options(mut self, options: DisplayOptions) -> Self {
+        self.evaluator.set_display_options(options);
+        self
+    }
+
+    /// 设置值的显示配置（影响 PRINT、TO_STRING 和 REPL 回显）
+    pub fn set_display_options(&mut self, options: DisplayOptions) {
+        self.evaluator.set_display_options(options);
+    }
+
+    /// 获取当前显示配置
+    pub fn display_options(&self) -> &DisplayOptions {
+        self.evaluator.display_options()
+    }
+
+    /// 按引擎的显示配置格式化值（用于在求值之外输出结果，如 REPL 回显）
+    pub fn format_value(&self, value: &Value) -> String {
+        value.display_with(self.evaluator.display_options())
+    }
+}
@@ -0,0 +1,56 @@
+use super::Aether;
+use crate::builtins::json::{FractionJsonMode, json_to_value, value_to_json};
+
+/// 环境导出结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnvExport {
+    /// 序列化后的 JSON 对象（变量名 -> 值）
+    pub json: String,
+    /// 被跳过的变量及原因（函数、生成器等无法序列化的值）
+    pub warnings: Vec<String>,
+}
+
+impl Aether {
+    /// 将全局作用域中的纯数据变量导出为 JSON。
+    ///
+    /// 函数、生成器、惰性值等无法序列化的变量会被跳过，并记录在 `warnings` 中。
+    /// 用于在多次 CLI 运行之间或引擎池的多个 worker 之间持久化会话状态。
+    /// 分数和大整数以 `{"$fraction": ...}` / `{"$bigint": ...}` 形式保存，导入后精度不变。
+    pub fn export_env_json(&self) -> Result<EnvExport, String> {
+        let mut object = serde_json::Map::new();
+        let mut warnings = Vec::new();
+
+        for var in self.evaluator.global_variables() {
+            match value_to_json(&var.value, FractionJsonMode::Tagged) {
+                Ok(json) => {
+                    object.insert(var.name, json);
+                }
+                Err(_) => warnings.push(format!(
+                    "skipped variable '{}': {} values cannot be exported",
+                    var.name, var.type_name
+                )),
+            }
+        }
+
+        let json = serde_json::to_string(&serde_json::Value::Object(object))
+            .map_err(|e| format!("Failed to serialize environment: {}", e))?;
+        Ok(EnvExport { json, warnings })
+    }
+
+    /// 从 `export_env_json()` 生成的 JSON 导入变量到全局作用域。
+    ///
+    /// 已存在的同名变量会被覆盖。返回导入的变量数量。
+    pub fn import_env_json(&mut self, json: &str) -> Result<usize, String> {
+        let parsed: serde_json::Value =
+            serde_json::from_str(json).map_err(|e| format!("Invalid environment JSON: {}", e))?;
+        let object = parsed
+            .as_object()
+            .ok_or_else(|| "Environment JSON must be an object".to_string())?;
+
+        for (name, json_value) in object {
+            let value = json_to_value(json_value).map_err(|e| e.to_string())?;
+            self.evaluator.set_global(name.clone(), value);
+        }
+        Ok(object.len())
+    }
+}
@@ -1,7 +1,12 @@
 use super::Aether;
+use crate::ast::Stmt;
+use crate::builtins::metadata::BuiltinMetadata;
+use crate::cache::SharedProgram;
+use crate::environment::{Environment, VariableInfo};
 use crate::evaluator::ErrorReport;
-use crate::parser::Parser;
 use crate::value::Value;
+use std::cell::RefCell;
+use std::rc::Rc;
 
 impl Aether {
     /// 求值 Aether 代码并返回结果
@@ -9,56 +14,135 @@ impl Aether {
         // 在开始新的顶级求值之前清除任何之前的调用栈帧。
         self.evaluator.clear_call_stack();
         self.evaluator.reset_step_counter();
+        self.evaluator.restart_random_sequence();
 
         // 尝试从缓存获取AST
-        let program = if let Some(cached_program) = self.cache.get(code) {
-            cached_program
+        let program = if let Some(cached_program) = self.cache.get_shared(code) {
+            self.specialize_cached(code, cached_program)
         } else {
             // 解析代码
-            let mut parser = Parser::new(code);
+            let mut parser = self.evaluator.parser(code);
             let program = parser
                 .parse_program()
                 .map_err(|e| format!("Parse error: {}", e))?;
 
             // 优化AST
             let optimized = self.optimizer.optimize_program(&program);
+            self.record_compile_warnings(parser.take_warnings());
 
             // 将优化后的结果存入缓存
-            self.cache.insert(code, optimized.clone());
-            optimized
+            self.cache.insert(code, optimized)
         };
 
         // 求值程序
         self.evaluator
-            .eval_program(&program)
-            .map_err(|e| format!("Runtime error: {}", e))
+            .eval_shared(&program)
+            .map_err(|e| self.evaluator.redact(&format!("Runtime error: {}", e)))
     }
 
     /// 求值 Aether 代码并在失败时返回结构化的错误报告。
     ///
     /// 这适用于需要机器可读诊断的集成。
+    // ErrorReport 是按值返回的公开诊断结构，不装箱
+    #[allow(clippy::result_large_err)]
     pub fn eval_report(&mut self, code: &str) -> Result<Value, ErrorReport> {
         // 在开始新的顶级求值之前清除任何之前的调用栈帧。
         self.evaluator.clear_call_stack();
         self.evaluator.reset_step_counter();
+        self.evaluator.restart_random_sequence();
 
         // 首先尝试 AST 缓存
-        let program = if let Some(cached_program) = self.cache.get(code) {
-            cached_program
+        let program = if let Some(cached_program) = self.cache.get_shared(code) {
+            self.specialize_cached(code, cached_program)
         } else {
-            let mut parser = Parser::new(code);
+            let mut parser = self.evaluator.parser(code);
             let program = parser
                 .parse_program()
-                .map_err(|e| ErrorReport::parse_error(e.to_string()))?;
+                .map_err(|e| ErrorReport::parse_error(e.to_string()).with_position(e.position()))?;
 
             let optimized = self.optimizer.optimize_program(&program);
-            self.cache.insert(code, optimized.clone());
-            optimized
+            self.record_compile_warnings(parser.take_warnings());
+            self.cache.insert(code, optimized)
         };
 
-        self.evaluator
-            .eval_program(&program)
-            .map_err(|e| e.to_error_report())
+        self.evaluator.eval_shared(&program).map_err(|e| {
+            let mut report = e.to_error_report();
+            report.message = self.evaluator.redact(&report.message);
+            report
+        })
+    }
+
+    /// 以数据上下文求值单个表达式（"公式字段"场景）。
+    ///
+    /// `context` 字典的每个键在一个临时子作用域中作为变量可见，求值结束后即丢弃，
+    /// 不影响引擎的全局状态；`Null` 表示空上下文。只接受一个表达式，`Set`、`Func`
+    /// 等语句或多个表达式会报解析错误。解析结果会被缓存，同一公式以不同上下文
+    /// 反复求值时只解析一次。
+    ///
+    /// # 示例
+    ///
+    /// ```
+    /// use aether::{Aether, Value};
+    /// use std::collections::HashMap;
+    ///
+    /// let mut engine = Aether::new();
+    /// let mut row = HashMap::new();
+    /// row.insert("PRICE".to_string(), Value::Number(12.5));
+    /// row.insert("QTY".to_string(), Value::Number(4.0));
+    /// let total = engine.eval_expr("PRICE * QTY", &Value::Dict(row)).unwrap();
+    /// assert_eq!(total, Value::Number(50.0));
+    /// ```
+    pub fn eval_expr(&mut self, expr: &str, context: &Value) -> Result<Value, String> {
+        self.evaluator.clear_call_stack();
+        self.evaluator.reset_step_counter();
+        self.evaluator.restart_random_sequence();
+
+        let program = self.compile_expr(expr)?;
+        let prev_env = self.enter_context(context)?;
+        let result = self.evaluator.eval_shared(&program);
+        self.evaluator.restore_env(prev_env);
+
+        result.map_err(|e| self.evaluator.redact(&format!("Runtime error: {}", e)))
+    }
+
+    /// 解析单个表达式（经过优化并缓存）
+    pub(crate) fn compile_expr(&mut self, expr: &str) -> Result<SharedProgram, String> {
+        // 以 NUL 开头的键不会与程序缓存冲突（这样的源代码无法解析，不会进入缓存）
+        let key = format!("\0expr:{}", expr);
+        if let Some(program) = self.cache.get_shared(&key) {
+            return Ok(program);
+        }
+        let mut parser = self.evaluator.parser(expr);
+        let parsed = parser
+            .parse_single_expression()
+            .map_err(|e| format!("Parse error: {}", e))?;
+        let optimized = self
+            .optimizer
+            .optimize_program(&vec![Stmt::Expression(parsed)]);
+        self.record_compile_warnings(parser.take_warnings());
+        Ok(self.cache.insert(&key, optimized))
+    }
+
+    /// 进入绑定了上下文字典的临时子作用域，返回之前的作用域（用于恢复）
+    pub(crate) fn enter_context(
+        &mut self,
+        context: &Value,
+    ) -> Result<Rc<RefCell<Environment>>, String> {
+        let vars = match context {
+            Value::Dict(map) => Some(map),
+            Value::Null => None,
+            other => {
+                return Err(format!(
+                    "Expression context must be a Dict, got {}",
+                    other.type_name()
+                ));
+            }
+        };
+        let prev_env = self.evaluator.enter_child_scope();
+        for (name, value) in vars.into_iter().flatten() {
+            self.evaluator.set_global(name.clone(), value.clone());
+        }
+        Ok(prev_env)
     }
 
     /// 配置用于 `Import/Export` 的模块解析器。
@@ -104,6 +188,7 @@ impl Aether {
     }
 
     /// 从文件路径求值 Aether 脚本，在失败时返回结构化的错误报告。
+    #[allow(clippy::result_large_err)]
     pub fn eval_file_report(
         &mut self,
         path: impl AsRef<std::path::Path>,
@@ -129,6 +214,178 @@ impl Aether {
         self.evaluator.set_global(name.to_string(), value);
     }
 
+    /// 按名称调用全局作用域中的函数（脚本定义的函数或内置函数）。
+    ///
+    /// 步数和时长限制从本次调用开始计算。
+    ///
+    /// # 示例
+    /// ```
+    /// use aether::{Aether, Value};
+    ///
+    /// let mut engine = Aether::new();
+    /// engine.eval("Func ADD(A, B) { Return A + B }").unwrap();
+    /// let sum = engine.call("ADD", vec![Value::Number(1.0), Value::Number(2.0)]).unwrap();
+    /// assert_eq!(sum, Value::Number(3.0));
+    /// ```
+    pub fn call(&mut self, name: &str, args: Vec<Value>) -> Result<Value, String> {
+        let func = self
+            .evaluator
+            .get_global(name)
+            .ok_or_else(|| format!("Undefined function: {}", name))?;
+        if !matches!(func, Value::Function { .. } | Value::BuiltIn { .. }) {
+            return Err(format!(
+                "'{}' is a {}, not a function",
+                name,
+                func.type_name()
+            ));
+        }
+        let _secrets = self.evaluator.scoped_secrets();
+        self.evaluator
+            .call_value(&func, args)
+            .map_err(|e| self.evaluator.redact(&e.to_string()))
+    }
+
+    /// 封存当前全局作用域中的所有名称（包括已加载的 stdlib 和宿主注入的函数）。
+    ///
+    /// 封存后脚本仍可读取和调用这些名称，但不能通过 `Set`、`Func`、`Import`
+    /// 等方式重新定义（例如覆盖 `CALC_PERSONAL_TAX`），保证规则引擎的完整性。
+    /// 宿主仍可通过 `set_global` 更新它们。
+    pub fn seal_globals(&mut self) {
+        self.evaluator.seal_globals();
+    }
+
+    /// 解除 `seal_globals` 添加的全部封存。
+    pub fn unseal_globals(&mut self) {
+        self.evaluator.unseal_globals();
+    }
+
+    /// 判断名称是否已被封存。
+    pub fn is_sealed(&self, name: &str) -> bool {
+        self.evaluator.is_sealed(name)
+    }
+
+    /// 启用大小写不敏感的内置函数解析（`sum(...)`、`Len(...)` 解析为 `SUM`、`LEN`）。
+    ///
+    /// 仅在名称未定义时回退到内置函数，不会覆盖脚本中同名的变量。
+    /// 通过回退解析的名称会记录在 `builtin_name_warnings()` 中，便于迁移时修正。
+    pub fn with_case_insensitive_builtins(mut self) -> Self {
+        self.evaluator.set_case_insensitive_builtins(true);
+        self
+    }
+
+    /// 启用或禁用大小写不敏感的内置函数解析。
+    pub fn set_case_insensitive_builtins(&mut self, enabled: bool) {
+        self.evaluator.set_case_insensitive_builtins(enabled);
+    }
+
+    /// 为内置函数注册别名，例如 `engine.alias("len", "LEN")`。
+    ///
+    /// 目标必须是已注册的内置函数。
+    pub fn alias(&mut self, alias: &str, target: &str) -> Result<(), String> {
+        self.evaluator
+            .alias_builtin(alias, target)
+            .map_err(|e| e.to_string())
+    }
+
+    /// 通过别名或大小写回退解析的内置函数名称（lint 提示）。
+    pub fn builtin_name_warnings(&self) -> Vec<String> {
+        self.evaluator.builtin_name_warnings().to_vec()
+    }
+
+    /// 清除内置函数名称的 lint 提示。
+    pub fn clear_builtin_name_warnings(&mut self) {
+        self.evaluator.clear_builtin_name_warnings();
+    }
+
+    /// 将内置函数标记为已弃用。
+    ///
+    /// 脚本首次调用该函数时会在 `deprecation_warnings()` 中记录警告，
+    /// 指定 `replacement` 时警告中会给出替代函数。调用本身照常执行。
+    pub fn deprecate_builtin(
+        &mut self,
+        name: &str,
+        replacement: Option<&str>,
+    ) -> Result<(), String> {
+        self.evaluator
+            .deprecate_builtin(name, replacement)
+            .map_err(|e| e.to_string())
+    }
+
+    /// 将内置函数标记为实验性，只有 `enable_experimental(feature)` 之后才能调用。
+    pub fn mark_experimental(&mut self, name: &str, feature: &str) -> Result<(), String> {
+        self.evaluator
+            .mark_builtin_experimental(name, feature)
+            .map_err(|e| e.to_string())
+    }
+
+    /// 将内置函数或宿主函数标记为纯函数（结果只取决于参数），
+    /// 之后 `eval_pure` 的脚本可以调用它，结果会被缓存。
+    ///
+    /// 内置函数只有注册时声明为纯函数的才纯（见 [`crate::builtins::BuiltInRegistry::is_pure`]），宿主函数注册后也不纯。
+    /// 读写外部状态的内置函数（文件、网络、机密等）不能标记为纯函数。
+    pub fn mark_pure(&mut self, name: &str) -> Result<(), String> {
+        self.evaluator
+            .mark_builtin_pure(name)
+            .map_err(|e| e.to_string())
+    }
+
+    /// 所有可调用的内置函数和原生函数的元数据（分类、所需权限、是否纯函数、参数个数范围、
+    /// 文档入口），按名称排序，可用于生成函数白名单界面。
+    ///
+    /// 只包含当前权限下已注册的函数；未授予权限的 IO 函数不会出现。
+    pub fn builtin_metadata(&self) -> Vec<BuiltinMetadata> {
+        self.evaluator.builtin_metadata()
+    }
+
+    /// 启用实验性特性，例如 `engine.enable_experimental("X")`。
+    pub fn enable_experimental(&mut self, feature: &str) {
+        self.evaluator.enable_experimental(feature);
+    }
+
+    /// 禁用实验性特性。
+    pub fn disable_experimental(&mut self, feature: &str) {
+        self.evaluator.disable_experimental(feature);
+    }
+
+    /// 已调用的弃用内置函数的警告（每个函数只记录一次）。
+    pub fn deprecation_warnings(&self) -> Vec<String> {
+        self.evaluator.deprecation_warnings().to_vec()
+    }
+
+    /// 清除弃用警告。
+    pub fn clear_deprecation_warnings(&mut self) {
+        self.evaluator.clear_deprecation_warnings();
+    }
+
+    /// 脚本是否用 `SCHEDULE` 注册了任务但没有调用 `RUN_SCHEDULER`。
+    ///
+    /// CLI 在脚本结束后据此决定是否继续以常驻进程驱动调度器。
+    pub fn has_pending_schedule(&self) -> bool {
+        self.evaluator.has_pending_schedule()
+    }
+
+    /// 运行调度器，按时间执行已注册的任务，直到所有任务被取消。
+    ///
+    /// 与脚本中调用 `RUN_SCHEDULER()` 相同，返回 `{"runs": ..., "errors": ..., "failures": [...]}`，
+    /// 每个失败为 `{"id", "expr", "error"}`。
+    pub fn run_scheduler(&mut self) -> Result<Value, String> {
+        self.evaluator
+            .run_scheduler()
+            .map_err(|e| self.evaluator.redact(&e.to_string()))
+    }
+
+    /// 列出当前作用域可见的用户变量（不含内置函数），按名称排序。
+    ///
+    /// 供 REPL、调试器和宿主程序枚举脚本状态，无需访问内部环境。
+    pub fn variables(&self) -> Vec<VariableInfo> {
+        self.evaluator.variables()
+    }
+
+    /// 列出全局作用域中的用户变量（不含内置函数），按名称排序。
+    pub fn globals(&self) -> Vec<VariableInfo> {
+        self.evaluator.global_variables()
+    }
+
     /// 重置运行时环境（变量/函数），同时保持内置函数注册。
     ///
     /// 注意：这会清除通过 `eval()` 引入的任何内容（包括 stdlib 代码）。
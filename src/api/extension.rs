@@ -0,0 +1,39 @@
+use super::Aether;
+use crate::runtime::ExtensionContext;
+use crate::value::Value;
+use std::rc::Rc;
+
+impl Aether {
+    // ============================================================
+    // 扩展块
+    // ============================================================
+
+    /// 注册扩展块处理器
+    ///
+    /// 脚本中的 `@TAG { ... }` 在求值时调用对应标签的处理器，
+    /// 处理器接收大括号内的原始文本（已去除首尾空白），返回值即表达式的值。
+    /// 返回 `Err` 时作为运行时错误抛出，可被 Try/Catch 捕获。
+    ///
+    /// # 示例
+    /// ```
+    /// use aether::{Aether, Value};
+    ///
+    /// let mut engine = Aether::new();
+    /// engine.register_extension("UPPER", |payload, _ctx| {
+    ///     Ok(Value::String(payload.to_uppercase()))
+    /// });
+    /// let result = engine.eval("@UPPER { hello }").unwrap();
+    /// assert_eq!(result, Value::String("HELLO".to_string()));
+    /// ```
+    pub fn register_extension<F>(&mut self, tag: &str, handler: F)
+    where
+        F: Fn(&str, &ExtensionContext) -> Result<Value, String> + 'static,
+    {
+        self.evaluator.register_extension(tag, Rc::new(handler));
+    }
+
+    /// 移除扩展块处理器，返回该标签此前是否已注册
+    pub fn unregister_extension(&mut self, tag: &str) -> bool {
+        self.evaluator.unregister_extension(tag)
+    }
+}
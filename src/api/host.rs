@@ -0,0 +1,65 @@
+use super::Aether;
+use crate::evaluator::RuntimeError;
+use crate::value::Value;
+use std::rc::Rc;
+
+impl Aether {
+    // ============================================================
+    // 宿主函数
+    // ============================================================
+
+    /// 注册宿主函数，脚本中可以像内置函数一样调用
+    ///
+    /// 与内置函数不同，`func` 可以是捕获状态的闭包（如 `Rc<RefCell<_>>`）。
+    /// 调用时参数个数必须等于 `arity`，否则抛出参数个数错误；`func` 返回的
+    /// `Err` 作为运行时错误抛出，可被 Try/Catch 捕获。脚本中的整数以 `Value::Int`
+    /// 原样传入，不会转换为浮点数。
+    ///
+    /// 名称必须由字母、数字和下划线组成且不以数字开头。与内置函数同名时拒绝注册，
+    /// 避免悄悄替换核心函数；重复注册同名宿主函数会替换之前的实现。
+    ///
+    /// # 示例
+    /// ```
+    /// use aether::{Aether, Value};
+    /// use std::cell::RefCell;
+    /// use std::rc::Rc;
+    ///
+    /// let log = Rc::new(RefCell::new(Vec::new()));
+    /// let sink = Rc::clone(&log);
+    ///
+    /// let mut engine = Aether::new();
+    /// engine
+    ///     .register_function("AUDIT", 1, move |args| {
+    ///         sink.borrow_mut().push(args[0].to_string());
+    ///         Ok(Value::Null)
+    ///     })
+    ///     .unwrap();
+    /// engine.eval(r#"AUDIT("login")"#).unwrap();
+    /// assert_eq!(*log.borrow(), vec!["login".to_string()]);
+    /// ```
+    pub fn register_function<F>(&mut self, name: &str, arity: usize, func: F) -> Result<(), String>
+    where
+        F: Fn(&[Value]) -> Result<Value, RuntimeError> + 'static,
+    {
+        let valid = name.chars().all(|c| c.is_alphanumeric() || c == '_')
+            && name.chars().next().is_some_and(|c| !c.is_ascii_digit());
+        if !valid {
+            return Err(format!("宿主函数名无效: {:?}", name));
+        }
+        if self.evaluator.is_builtin(name) && !self.evaluator.is_native(name) {
+            return Err(format!("宿主函数 {} 与已有内置函数重名", name));
+        }
+
+        let native = move |args: &[Value]| {
+            if args.len() != arity {
+                return Err(RuntimeError::WrongArity {
+                    expected: arity,
+                    got: args.len(),
+                });
+            }
+            func(args)
+        };
+        self.evaluator.register_native(name, Rc::new(native), arity);
+        Ok(())
+    }
+}
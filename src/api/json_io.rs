@@ -0,0 +1,52 @@
+use super::Aether;
+use crate::builtins::json::{FractionJsonMode, json_to_value, value_to_json};
+use crate::value::Value;
+
+/// `--json-io` 模式下输入数据绑定的全局变量名
+const INPUT_DATA: &str = "INPUT_DATA";
+
+impl Aether {
+    // ============================================================
+    // JSON 管道（--json-io）
+    // ============================================================
+
+    /// 解析 JSON 文本并绑定为全局变量 `INPUT_DATA`
+    ///
+    /// 空输入（只含空白）绑定为 Null，便于在管道中不带输入运行。
+    pub fn set_input_json(&mut self, json: &str) -> Result<(), String> {
+        let value = if json.trim().is_empty() {
+            Value::Null
+        } else {
+            let parsed: serde_json::Value =
+                serde_json::from_str(json).map_err(|e| format!("Invalid JSON input: {}", e))?;
+            json_to_value(&parsed).map_err(|e| e.to_string())?
+        };
+        self.evaluator.set_global(INPUT_DATA.to_string(), value);
+        Ok(())
+    }
+
+    /// 将脚本结果序列化为 JSON 文本
+    ///
+    /// 字典的键按字典序输出；分数转换为浮点数，与 `JSON_STRINGIFY` 默认行为一致。
+    /// 函数、生成器等无法表示为 JSON 的值返回错误。
+    ///
+    /// # 示例
+    /// ```
+    /// use aether::Aether;
+    ///
+    /// let mut engine = Aether::new();
+    /// engine.set_input_json(r#"{"items": [1, 2, 3]}"#).unwrap();
+    /// let result = engine.eval("{\"total\": SUM(INPUT_DATA[\"items\"])}").unwrap();
+    /// assert_eq!(engine.to_json_output(&result, false).unwrap(), r#"{"total":6.0}"#);
+    /// ```
+    pub fn to_json_output(&self, value: &Value, pretty: bool) -> Result<String, String> {
+        let json = value_to_json(value, FractionJsonMode::Float)
+            .map_err(|e| format!("Result cannot be converted to JSON: {}", e))?;
+        let text = if pretty {
+            serde_json::to_string_pretty(&json)
+        } else {
+            serde_json::to_string(&json)
+        };
+        text.map_err(|e| format!("Result cannot be converted to JSON: {}", e))
+    }
+}
@@ -0,0 +1,38 @@
+use super::Aether;
+use crate::runtime::LanguageVersion;
+
+impl Aether {
+    // ============================================================
+    // 语言版本
+    // ============================================================
+
+    /// 使用指定的默认语言版本创建新的 Aether 引擎
+    pub fn with_language_version(mut self, version: LanguageVersion) -> Self {
+        self.evaluator.set_language_version(version);
+        self
+    }
+
+    /// 设置默认语言版本（脚本中没有 `#language` 编译指示时使用）
+    ///
+    /// 低于某内置函数或语法的引入版本时，使用它们会报错，
+    /// 嵌入的旧脚本因此不会受语言演进影响。脚本开头的 `#language X.Y`
+    /// 优先于此设置，且只作用于该脚本。
+    ///
+    /// # 示例
+    /// ```
+    /// use aether::{Aether, LanguageVersion};
+    ///
+    /// let mut engine = Aether::new();
+    /// engine.set_language_version(LanguageVersion::V1_0);
+    /// assert!(engine.eval("CUMSUM([1, 2])").is_err());
+    /// assert!(engine.eval("#language 1.1\nCUMSUM([1, 2])").is_ok());
+    /// ```
+    pub fn set_language_version(&mut self, version: LanguageVersion) {
+        self.evaluator.set_language_version(version);
+    }
+
+    /// 默认语言版本
+    pub fn language_version(&self) -> LanguageVersion {
+        self.evaluator.language_version()
+    }
+}
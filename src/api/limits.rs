@@ -1,5 +1,6 @@
 use super::Aether;
-use crate::runtime::ExecutionLimits;
+use crate::runtime::{ConcurrencyLimits, ExecutionLimits};
+use crate::value::Value;
 
 impl Aether {
     // ============================================================
@@ -21,4 +22,48 @@ impl Aether {
     pub fn limits(&self) -> &ExecutionLimits {
         self.evaluator.limits()
     }
+
+    /// 使用临时的执行限制求值代码，结束后（无论成功与否）恢复原有限制
+    ///
+    /// 适合同一个引擎中运行可信度不同的脚本，例如对租户提交的规则收紧限制。
+    ///
+    /// # 示例
+    /// ```
+    /// use aether::{Aether, ExecutionLimits};
+    ///
+    /// let mut engine = Aether::new();
+    /// let tight = ExecutionLimits {
+    ///     max_loop_iterations: Some(10),
+    ///     ..ExecutionLimits::default()
+    /// };
+    /// assert!(engine.eval_with_limits("While (True) { Set X 1 }", tight).is_err());
+    /// assert_eq!(engine.limits().max_loop_iterations, None);
+    /// ```
+    pub fn eval_with_limits(
+        &mut self,
+        code: &str,
+        limits: ExecutionLimits,
+    ) -> Result<Value, String> {
+        let previous = self.evaluator.limits().clone();
+        self.evaluator.set_limits(limits);
+        let result = self.eval(code);
+        self.evaluator.set_limits(previous);
+        result
+    }
+
+    /// 使用并发任务限制创建新的 Aether 引擎
+    pub fn with_concurrency_limits(mut self, limits: ConcurrencyLimits) -> Self {
+        self.evaluator.set_concurrency_limits(limits);
+        self
+    }
+
+    /// 设置并发任务限制（SPAWN / PARALLEL），同时重置已累计的任务时间
+    pub fn set_concurrency_limits(&mut self, limits: ConcurrencyLimits) {
+        self.evaluator.set_concurrency_limits(limits);
+    }
+
+    /// 获取当前并发任务限制
+    pub fn concurrency_limits(&self) -> &ConcurrencyLimits {
+        self.evaluator.concurrency_limits()
+    }
 }
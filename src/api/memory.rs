@@ -0,0 +1,27 @@
+use super::Aether;
+use crate::runtime::MemoryReport;
+
+impl Aether {
+    // ============================================================
+    // 内存检查
+    // ============================================================
+
+    /// 检查当前可达的所有值：按类型汇总数量和估算字节数、最大的集合、
+    /// 作用域链深度和疑似的闭包 Rc 循环，用于排查长时间运行的脚本为何内存增长
+    ///
+    /// 报告实现了 `Display`，CLI 的 `--mem-report` 直接打印它。
+    ///
+    /// # 示例
+    /// ```
+    /// use aether::Aether;
+    ///
+    /// let mut engine = Aether::new();
+    /// engine.eval("Set ORDERS RANGE(0, 1000)").unwrap();
+    /// let report = engine.memory_report();
+    /// assert_eq!(report.largest[0].path, "ORDERS");
+    /// assert_eq!(report.largest[0].len, 1000);
+    /// ```
+    pub fn memory_report(&self) -> MemoryReport {
+        self.evaluator.memory_report()
+    }
+}
@@ -1,17 +1,53 @@
 use crate::cache::ASTCache;
 use crate::evaluator::Evaluator;
 use crate::optimizer::Optimizer;
+use crate::result_cache::ResultStore;
+use std::cell::RefCell;
+use std::rc::Rc;
 
+mod analysis;
+mod backend;
+mod benchmark;
 mod cache;
 mod constructors;
+mod deterministic;
+mod dialect;
+mod display;
+mod env;
 mod eval;
+mod extension;
+mod host;
+mod json_io;
+mod language;
 mod limits;
+mod memory;
+#[cfg(feature = "plugins")]
+mod plugin;
+mod profile;
+mod project;
+mod pure;
+mod recording;
+mod replay;
+mod ruleset;
+mod secrets;
+#[cfg(feature = "signing")]
+mod signing;
+mod snapshot;
 mod stdlib;
+mod strict;
+mod timers;
 mod trace;
+mod warnings;
+
+pub use benchmark::BenchmarkReport;
+pub use env::EnvExport;
+pub use ruleset::{RulePolicy, RuleResult, RulesetReport};
 
 /// 主要的 Aether 引擎结构体
 pub struct Aether {
     pub(crate) evaluator: Evaluator,
     pub(crate) cache: ASTCache,
     pub(crate) optimizer: Optimizer,
+    /// 纯脚本结果缓存（`fork()` 出的引擎共享同一存储）
+    pub(crate) results: Rc<RefCell<dyn ResultStore>>,
 }
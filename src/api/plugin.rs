@@ -0,0 +1,30 @@
+use super::Aether;
+use crate::plugin::PluginInfo;
+use std::path::Path;
+
+impl Aether {
+    // ============================================================
+    // 插件
+    // ============================================================
+
+    /// 加载内置函数包插件（动态库），其中的函数随即可在脚本中调用
+    ///
+    /// 插件需实现 [`crate::plugin`] 中描述的 C ABI。与已有内置函数同名的
+    /// 插件函数会被拒绝，避免插件悄悄替换核心函数。
+    pub fn load_plugin(&mut self, path: impl AsRef<Path>) -> Result<PluginInfo, String> {
+        let (info, functions) = crate::plugin::load(path.as_ref())?;
+        if let Some((name, _, _)) = functions
+            .iter()
+            .find(|(name, _, _)| self.evaluator.is_builtin(name))
+        {
+            return Err(format!(
+                "插件 {} 的函数 {} 与已有内置函数重名",
+                info.name, name
+            ));
+        }
+        for (name, func, arity) in functions {
+            self.evaluator.register_native(&name, func, arity);
+        }
+        Ok(info)
+    }
+}
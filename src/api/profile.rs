@@ -0,0 +1,78 @@
+use super::Aether;
+use crate::cache::SharedProgram;
+use std::collections::HashMap;
+
+impl Aether {
+    // ============================================================
+    // 基于调用次数的优化
+    // ============================================================
+
+    /// 使用基于调用次数的优化创建新的 Aether 引擎
+    pub fn with_profile_guided(mut self, enabled: bool) -> Self {
+        self.set_profile_guided(enabled);
+        self
+    }
+
+    /// 开启或关闭基于调用次数的优化（默认关闭）
+    ///
+    /// 开启后引擎统计每个用户函数的调用次数。再次求值同一段已缓存的代码时，
+    /// 调用次数达到阈值（见 `set_hot_call_threshold`）的简单函数
+    /// （函数体只有一条 `Return 表达式`）会被内联到调用处，缓存中的程序被替换为特化后的版本。
+    ///
+    /// 内联后的调用不再出现在调用栈、递归深度和调用次数中。
+    ///
+    /// # 示例
+    /// ```
+    /// use aether::{Aether, Value};
+    ///
+    /// let mut engine = Aether::new().with_profile_guided(true);
+    /// engine.set_hot_call_threshold(2);
+    /// let code = "Func SQUARE(X) { Return X * X }\nSet A 3\nSQUARE(A) + SQUARE(A)";
+    /// assert_eq!(engine.eval(code).unwrap(), Value::Number(18.0));
+    /// // 第二次求值时 SQUARE 已被内联
+    /// assert_eq!(engine.eval(code).unwrap(), Value::Number(18.0));
+    /// ```
+    pub fn set_profile_guided(&mut self, enabled: bool) {
+        self.optimizer.profile_guided = enabled;
+        self.evaluator.set_call_profiling(enabled);
+    }
+
+    /// 是否开启了基于调用次数的优化
+    pub fn is_profile_guided(&self) -> bool {
+        self.optimizer.profile_guided
+    }
+
+    /// 设置热点函数的调用次数阈值（默认 100）
+    pub fn set_hot_call_threshold(&mut self, threshold: usize) {
+        self.optimizer.hot_call_threshold = threshold.max(1);
+    }
+
+    /// 开启优化后统计到的用户函数调用次数
+    pub fn function_call_counts(&self) -> &HashMap<String, usize> {
+        self.evaluator.function_call_counts()
+    }
+
+    /// 按当前热点函数特化缓存中的程序
+    ///
+    /// 热点函数集合与上次特化时相同则直接返回原程序。
+    pub(crate) fn specialize_cached(
+        &mut self,
+        code: &str,
+        program: SharedProgram,
+    ) -> SharedProgram {
+        if !self.optimizer.profile_guided {
+            return program;
+        }
+        let hot = self
+            .evaluator
+            .hot_functions(self.optimizer.hot_call_threshold);
+        if hot.is_empty() || self.cache.specialized_for(code) == Some(hot.as_slice()) {
+            return program;
+        }
+        let statements: Vec<_> = program.to_vec();
+        let specialized = self.optimizer.inline_hot_functions(&statements, &hot);
+        self.cache
+            .specialize(code, hot, specialized)
+            .unwrap_or(program)
+    }
+}
@@ -0,0 +1,134 @@
+use super::Aether;
+use crate::ast::Stmt;
+use crate::module_system::{
+    ModuleContext, ModuleResolver, ResolvedModule, STDLIB_SPECIFIER_PREFIX,
+};
+use crate::project::Project;
+use crate::value::Value;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+impl Aether {
+    // ============================================================
+    // 多文件项目
+    // ============================================================
+
+    /// 运行项目的入口文件
+    ///
+    /// `entry` 为 None 时使用清单的默认入口。入口顶层 `Import` 的模块（以及它们的依赖）
+    /// 按依赖顺序各加载一次后再执行入口；循环导入在执行任何代码之前报告。
+    pub fn run_project(&mut self, project: &Project, entry: Option<&str>) -> Result<Value, String> {
+        let entry = self.prepare_project(project, entry)?;
+        self.eval_file(entry)
+    }
+
+    /// 为运行项目做准备：安装项目的模块解析器并按依赖顺序预加载模块，返回入口文件路径
+    ///
+    /// 随后用 `eval_file` / `eval_file_report` 执行返回的入口。
+    pub fn prepare_project(
+        &mut self,
+        project: &Project,
+        entry: Option<&str>,
+    ) -> Result<PathBuf, String> {
+        let entry = project.entry_path(entry)?;
+        let order = self.project_load_order(project, &entry)?;
+        self.set_module_resolver(Box::new(project.resolver()));
+        for module in order {
+            self.evaluator
+                .preload_module(module)
+                .map_err(|e| e.to_string())?;
+        }
+        Ok(entry)
+    }
+
+    /// 入口依赖的模块，依赖在前（拓扑序），不含入口本身
+    fn project_load_order(
+        &self,
+        project: &Project,
+        entry: &Path,
+    ) -> Result<Vec<ResolvedModule>, String> {
+        let source = std::fs::read_to_string(entry).map_err(|e| format!("IO error: {}", e))?;
+        let root = ResolvedModule {
+            module_id: entry.display().to_string(),
+            source,
+            base_dir: entry.parent().map(|p| p.to_path_buf()),
+        };
+
+        let resolver = project.resolver();
+        let mut order = Vec::new();
+        let mut done = HashSet::new();
+        let mut visiting = Vec::new();
+        self.visit_project_module(
+            project,
+            &resolver,
+            root,
+            &mut visiting,
+            &mut done,
+            &mut order,
+        )?;
+        // 入口最后加入，由调用方执行
+        order.pop();
+        Ok(order)
+    }
+
+    fn visit_project_module(
+        &self,
+        project: &Project,
+        resolver: &dyn ModuleResolver,
+        module: ResolvedModule,
+        visiting: &mut Vec<String>,
+        done: &mut HashSet<String>,
+        order: &mut Vec<ResolvedModule>,
+    ) -> Result<(), String> {
+        if done.contains(&module.module_id) {
+            return Ok(());
+        }
+        if let Some(start) = visiting.iter().position(|id| *id == module.module_id) {
+            let cycle: Vec<String> = visiting[start..]
+                .iter()
+                .chain(std::iter::once(&module.module_id))
+                .map(|id| project.display_path(id))
+                .collect();
+            return Err(format!("Circular import: {}", cycle.join(" -> ")));
+        }
+
+        let program = self
+            .evaluator
+            .parser(&module.source)
+            .parse_program()
+            .map_err(|e| {
+                format!(
+                    "Parse error in {}: {}",
+                    project.display_path(&module.module_id),
+                    e
+                )
+            })?;
+
+        let context = ModuleContext {
+            module_id: module.module_id.clone(),
+            base_dir: module.base_dir.clone(),
+        };
+        visiting.push(module.module_id.clone());
+        for stmt in &program {
+            let Stmt::Import { path, .. } = stmt.node() else {
+                continue;
+            };
+            if path.starts_with(STDLIB_SPECIFIER_PREFIX) {
+                continue;
+            }
+            let dependency = resolver.resolve(path, Some(&context)).map_err(|e| {
+                format!(
+                    "Import error in {}: {}",
+                    project.display_path(&module.module_id),
+                    e
+                )
+            })?;
+            self.visit_project_module(project, resolver, dependency, visiting, done, order)?;
+        }
+        visiting.pop();
+
+        done.insert(module.module_id.clone());
+        order.push(module);
+        Ok(())
+    }
+}
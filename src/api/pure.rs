@@ -0,0 +1,114 @@
+use super::Aether;
+use crate::builtins::json::{FractionJsonMode, value_to_json};
+use crate::result_cache::{ResultKey, ResultStore, is_cacheable};
+use crate::value::Value;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+impl Aether {
+    // ============================================================
+    // 纯脚本结果缓存
+    // ============================================================
+
+    /// 求值纯脚本并缓存结果
+    ///
+    /// 脚本必须是纯计算：不导入模块、不使用扩展块、只调用注册表中标记为纯函数的内置函数
+    /// （见 [`crate::builtins::BuiltInRegistry::is_pure`] 和 `mark_pure`；IO、网络、机密、时间、
+    /// 随机数、打印等函数都不纯），且只读取自己定义的名称、`inputs` 和内置函数。
+    /// 不满足时返回错误并列出原因，脚本不会执行。
+    ///
+    /// 结果以（代码，输入，薪酬设置）为键保存在结果存储中，相同代码和输入在相同的舍入规则和
+    /// 节假日日历下再次求值时直接返回缓存结果，不再执行。脚本在独立的作用域中执行
+    /// （见 `fork()`），不会在引擎中留下变量。
+    /// 出错的求值、返回函数的求值、结果中含有机密值的求值和包含无法序列化的输入的求值
+    /// 不会被缓存。
+    ///
+    /// 显示选项、严格模式等其他引擎配置不属于缓存键，修改配置后应调用 `clear_result_cache()`。
+    ///
+    /// # 示例
+    /// ```
+    /// use aether::{Aether, Value};
+    ///
+    /// let mut engine = Aether::new();
+    /// let code = "Set RATE 0.1\nPRICE * (1 + RATE)";
+    /// let inputs = [("PRICE", Value::Number(100.0))];
+    /// assert_eq!(engine.eval_pure(code, &inputs).unwrap(), Value::Number(110.00000000000001));
+    /// // 第二次直接返回缓存结果
+    /// assert_eq!(engine.eval_pure(code, &inputs).unwrap(), Value::Number(110.00000000000001));
+    ///
+    /// assert!(engine.eval_pure("PRINT(PRICE)", &inputs).is_err());
+    /// ```
+    pub fn eval_pure(&mut self, code: &str, inputs: &[(&str, Value)]) -> Result<Value, String> {
+        let program = match self.cache.get_shared(code) {
+            Some(program) => program,
+            None => {
+                let mut parser = self.evaluator.parser(code);
+                let program = parser
+                    .parse_program()
+                    .map_err(|e| format!("Parse error: {}", e))?;
+                let optimized = self.optimizer.optimize_program(&program);
+                self.record_compile_warnings(parser.take_warnings());
+                self.cache.insert(code, optimized)
+            }
+        };
+
+        let names: Vec<&str> = inputs.iter().map(|(name, _)| *name).collect();
+        let violations = crate::analysis::purity_violations(
+            program.iter(),
+            &names,
+            |name| self.evaluator.is_builtin(name),
+            |name| self.evaluator.is_pure_builtin(name),
+        );
+        if !violations.is_empty() {
+            return Err(format!("Script is not pure: {}", violations.join("; ")));
+        }
+
+        // 薪酬函数按引擎的舍入规则和节假日日历计算，设置是键的一部分
+        let settings = self.evaluator.settings_fingerprint();
+        let key = Self::canonical_inputs(inputs)
+            .map(|inputs| ResultKey::new(code, &format!("{}\n{}", inputs, settings)));
+        if let Some(key) = &key
+            && let Some(value) = self.results.borrow_mut().get(key)
+        {
+            return Ok(value);
+        }
+
+        let mut evaluator = self.evaluator.fork();
+        for (name, value) in inputs {
+            evaluator.set_global(*name, value.clone());
+        }
+        let result = evaluator
+            .eval_shared(&program)
+            .map_err(|e| evaluator.redact(&format!("Runtime error: {}", e)))?;
+
+        if let Some(key) = key
+            && is_cacheable(&result)
+            && !evaluator.contains_secret(&result)
+        {
+            self.results.borrow_mut().insert(key, result.clone());
+        }
+        Ok(result)
+    }
+
+    /// 替换结果存储（默认为内存存储 `MemoryResultStore`）
+    pub fn set_result_store(&mut self, store: impl ResultStore + 'static) {
+        self.results = Rc::new(RefCell::new(store));
+    }
+
+    /// 清空纯脚本结果缓存
+    pub fn clear_result_cache(&mut self) {
+        self.results.borrow_mut().clear();
+    }
+
+    /// 输入按名称排序后的规范 JSON（包含无法序列化的值时返回 None）
+    fn canonical_inputs(inputs: &[(&str, Value)]) -> Option<String> {
+        let mut sorted: Vec<&(&str, Value)> = inputs.iter().collect();
+        sorted.sort_by(|a, b| a.0.cmp(b.0));
+        let mut entries = Vec::with_capacity(sorted.len());
+        for (name, value) in sorted {
+            let json = value_to_json(value, FractionJsonMode::Tagged).ok()?;
+            entries.push(serde_json::json!([name, json]));
+        }
+        serde_json::to_string(&entries).ok()
+    }
+}
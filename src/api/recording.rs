@@ -0,0 +1,48 @@
+use super::Aether;
+use crate::debugger::{DEFAULT_MAX_RECORDED_STEPS, ExecutionRecording};
+
+impl Aether {
+    // ============================================================
+    // 执行录制（时间回溯调试）
+    // ============================================================
+
+    /// 开始录制：此后每条语句执行前都会记录可见变量的变化，
+    /// 调试器可以据此向后单步并查看变量在任意一步的历史值
+    ///
+    /// 最多保留 [`DEFAULT_MAX_RECORDED_STEPS`] 步，超出后停止录制并标记为截断。
+    /// 录制会复制变量值，只应在调试时开启。
+    ///
+    /// # 示例
+    /// ```
+    /// use aether::Aether;
+    ///
+    /// let mut engine = Aether::new();
+    /// engine.start_recording();
+    /// engine.eval("Set TOTAL 0\nFor X In [10, 20] { Set TOTAL (TOTAL + X) }").unwrap();
+    /// let recording = engine.take_recording().unwrap();
+    /// let totals: Vec<_> = recording
+    ///     .history("TOTAL")
+    ///     .iter()
+    ///     .map(|(_, change)| change.new.clone().unwrap().to_string())
+    ///     .collect();
+    /// assert_eq!(totals, ["0", "10", "30"]);
+    /// ```
+    pub fn start_recording(&mut self) {
+        self.evaluator.start_recording(DEFAULT_MAX_RECORDED_STEPS);
+    }
+
+    /// 开始录制，最多保留 `max_steps` 步
+    pub fn start_recording_with_limit(&mut self, max_steps: usize) {
+        self.evaluator.start_recording(max_steps);
+    }
+
+    /// 是否正在录制
+    pub fn is_recording(&self) -> bool {
+        self.evaluator.is_recording()
+    }
+
+    /// 停止录制并取出录制结果，未在录制时返回 `None`
+    pub fn take_recording(&mut self) -> Option<ExecutionRecording> {
+        self.evaluator.take_recording()
+    }
+}
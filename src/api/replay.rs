@@ -0,0 +1,90 @@
+use super::Aether;
+use crate::runtime::{ExecutionTrace, IoEvent};
+use crate::value::Value;
+use std::path::Path;
+
+impl Aether {
+    // ============================================================
+    // 执行录制与回放（复现线上问题）
+    // ============================================================
+
+    /// 开始录制 IO：此后文件、网络、KV、`INPUT`、`NOW`、`RANDOM` 等内置函数的
+    /// 参数和结果按调用顺序记录，用 [`Aether::take_io_events`] 取出
+    pub fn start_io_recording(&mut self) {
+        self.evaluator.start_io_recording();
+    }
+
+    /// 停止录制或回放并取出事件：录制时为录制到的事件，回放时为尚未回放的事件；
+    /// 未在录制或回放时返回 `None`
+    pub fn take_io_events(&mut self) -> Option<Vec<IoEvent>> {
+        self.evaluator.take_io_events()
+    }
+
+    /// 求值脚本并录制 IO，返回可保存为 JSON 的录制（包含脚本源码和结果）
+    ///
+    /// # 示例
+    /// ```
+    /// use aether::Aether;
+    ///
+    /// let mut engine = Aether::new();
+    /// let trace = engine.eval_recorded("Set R RANDOM()\nR * 0 + 1");
+    /// assert_eq!(trace.events[0].function, "RANDOM");
+    ///
+    /// let json = trace.to_json();
+    /// let trace = aether::ExecutionTrace::from_json(&json).unwrap();
+    /// let replayed = Aether::new().replay(&trace).unwrap();
+    /// assert!(trace.reproduces(&Ok(replayed)));
+    /// ```
+    pub fn eval_recorded(&mut self, code: &str) -> ExecutionTrace {
+        self.evaluator.start_io_recording();
+        let outcome = self.eval(code);
+        let events = self.evaluator.take_io_events().unwrap_or_default();
+        ExecutionTrace::new(code.to_string(), events, &outcome)
+    }
+
+    /// 求值脚本文件并录制 IO，只有文件无法读取时返回 `Err`
+    pub fn eval_file_recorded(&mut self, path: impl AsRef<Path>) -> Result<ExecutionTrace, String> {
+        let path = path.as_ref();
+        let code = std::fs::read_to_string(path).map_err(|e| format!("IO error: {}", e))?;
+
+        self.push_file_import_base(path);
+        let mut trace = self.eval_recorded(&code);
+        self.pop_import_base();
+        trace.file = Some(path.display().to_string());
+        Ok(trace)
+    }
+
+    /// 回放录制：重新执行录制中的脚本，被录制的内置函数按顺序返回录制的结果
+    ///
+    /// 脚本调用这些内置函数的顺序或参数与录制不一致、或执行结束时仍有未回放的事件，
+    /// 返回 `Replay diverged` 错误。用 [`ExecutionTrace::reproduces`] 比较结果是否与录制时一致。
+    pub fn replay(&mut self, trace: &ExecutionTrace) -> Result<Value, String> {
+        self.evaluator.start_io_replay(trace.events.clone());
+        let outcome = match &trace.file {
+            Some(file) => {
+                self.push_file_import_base(Path::new(file));
+                let outcome = self.eval(&trace.script);
+                self.pop_import_base();
+                outcome
+            }
+            None => self.eval(&trace.script),
+        };
+        let remaining = self.evaluator.take_io_events().unwrap_or_default();
+
+        match (outcome, remaining.first()) {
+            (Ok(_), Some(next)) => Err(format!(
+                "Replay diverged: script finished with {} recorded IO events left (next: {})",
+                remaining.len(),
+                next.function
+            )),
+            (outcome, _) => outcome,
+        }
+    }
+
+    /// 以脚本文件所在目录作为相对导入的基准目录
+    fn push_file_import_base(&mut self, path: &Path) {
+        let canon = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        let base_dir = canon.parent().map(|p| p.to_path_buf());
+        self.push_import_base(canon.display().to_string(), base_dir);
+    }
+}
@@ -0,0 +1,200 @@
+use super::Aether;
+use crate::value::Value;
+use std::collections::HashMap;
+use std::fmt;
+
+/// 规则集的短路策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RulePolicy {
+    /// 求值所有规则
+    #[default]
+    EvaluateAll,
+    /// 第一个出错的规则之后停止
+    StopOnError,
+    /// 第一个结果为假或出错的规则之后停止（校验规则表：遇到第一条不通过的规则即停）
+    StopOnFalse,
+    /// 第一个结果为真的规则之后停止（定价规则表：第一条命中的规则生效）
+    StopOnTrue,
+}
+
+impl RulePolicy {
+    fn stops_after(&self, outcome: &Result<Value, String>) -> bool {
+        match (self, outcome) {
+            (RulePolicy::EvaluateAll, _) => false,
+            (RulePolicy::StopOnError | RulePolicy::StopOnFalse, Err(_)) => true,
+            (RulePolicy::StopOnFalse, Ok(value)) => !value.is_truthy(),
+            (RulePolicy::StopOnTrue, Ok(value)) => value.is_truthy(),
+            _ => false,
+        }
+    }
+}
+
+/// 一条规则的结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleResult {
+    /// 规则名
+    pub name: String,
+    /// 求值结果，解析或运行出错时为错误信息
+    pub outcome: Result<Value, String>,
+}
+
+/// `Aether::eval_ruleset` 的结果
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RulesetReport {
+    /// 已求值的规则，按规则表顺序
+    pub results: Vec<RuleResult>,
+    /// 因短路而未求值的规则
+    pub skipped: Vec<String>,
+    /// 触发短路的规则
+    pub stopped_at: Option<String>,
+}
+
+impl RulesetReport {
+    /// 按规则名获取结果（未求值的规则返回 None）
+    pub fn get(&self, name: &str) -> Option<&Result<Value, String>> {
+        self.results
+            .iter()
+            .find(|result| result.name == name)
+            .map(|result| &result.outcome)
+    }
+
+    /// 成功求值的规则：规则名 -> 结果
+    pub fn values(&self) -> HashMap<String, Value> {
+        self.results
+            .iter()
+            .filter_map(|result| match &result.outcome {
+                Ok(value) => Some((result.name.clone(), value.clone())),
+                Err(_) => None,
+            })
+            .collect()
+    }
+
+    /// 出错的规则：`(规则名, 错误信息)`
+    pub fn errors(&self) -> Vec<(&str, &str)> {
+        self.results
+            .iter()
+            .filter_map(|result| match &result.outcome {
+                Ok(_) => None,
+                Err(e) => Some((result.name.as_str(), e.as_str())),
+            })
+            .collect()
+    }
+
+    /// 是否有规则出错
+    pub fn has_errors(&self) -> bool {
+        self.results.iter().any(|result| result.outcome.is_err())
+    }
+}
+
+impl fmt::Display for RulesetReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for result in &self.results {
+            match &result.outcome {
+                Ok(value) => writeln!(f, "{} = {}", result.name, value)?,
+                Err(e) => writeln!(f, "{}: {}", result.name, e)?,
+            }
+        }
+        if let Some(name) = &self.stopped_at {
+            writeln!(f, "stopped at {}", name)?;
+        }
+        if !self.skipped.is_empty() {
+            writeln!(f, "skipped: {}", self.skipped.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl Aether {
+    /// 以同一个数据上下文求值一组命名表达式（规则引擎模式）
+    ///
+    /// 等同于使用 [`RulePolicy::EvaluateAll`] 调用 [`Aether::eval_ruleset_with_policy`]。
+    ///
+    /// # 示例
+    ///
+    /// ```
+    /// use aether::{Aether, Value};
+    /// use std::collections::HashMap;
+    ///
+    /// let mut engine = Aether::new();
+    /// let mut order = HashMap::new();
+    /// order.insert("AMOUNT".to_string(), Value::Number(120.0));
+    /// let rules = [
+    ///     ("SUBTOTAL", "AMOUNT * 0.9"),
+    ///     ("TAX", "SUBTOTAL * 0.1"),
+    ///     ("VALID", "AMOUNT > 0"),
+    /// ];
+    /// let report = engine.eval_ruleset(&rules, &Value::Dict(order)).unwrap();
+    /// assert_eq!(report.get("TAX"), Some(&Ok(Value::Number(10.8))));
+    /// assert_eq!(report.get("VALID"), Some(&Ok(Value::Boolean(true))));
+    /// ```
+    pub fn eval_ruleset<N, E>(
+        &mut self,
+        rules: &[(N, E)],
+        context: &Value,
+    ) -> Result<RulesetReport, String>
+    where
+        N: AsRef<str>,
+        E: AsRef<str>,
+    {
+        self.eval_ruleset_with_policy(rules, context, RulePolicy::EvaluateAll)
+    }
+
+    /// 以同一个数据上下文求值一组命名表达式，并按策略短路
+    ///
+    /// - 所有表达式先统一解析（结果缓存，与 `eval_expr` 共享），解析失败只记为该规则的错误
+    /// - `context` 字典的键在一个共享的临时作用域中作为变量可见；每条规则成功后，其结果
+    ///   以规则名绑定到该作用域，后面的规则可以引用前面规则的结果
+    /// - 规则的错误不会中断其他规则（除非策略要求停止），执行限制对整个规则集计算
+    ///
+    /// 只有 `context` 不是字典（或 `Null`）时返回 `Err`。求值结束后临时作用域被丢弃，
+    /// 不影响引擎的全局状态。
+    pub fn eval_ruleset_with_policy<N, E>(
+        &mut self,
+        rules: &[(N, E)],
+        context: &Value,
+        policy: RulePolicy,
+    ) -> Result<RulesetReport, String>
+    where
+        N: AsRef<str>,
+        E: AsRef<str>,
+    {
+        self.evaluator.clear_call_stack();
+        self.evaluator.reset_step_counter();
+        self.evaluator.restart_random_sequence();
+
+        let compiled: Vec<_> = rules
+            .iter()
+            .map(|(_, expr)| self.compile_expr(expr.as_ref()))
+            .collect();
+
+        let prev_env = self.enter_context(context)?;
+        let mut report = RulesetReport::default();
+        for ((name, _), program) in rules.iter().zip(compiled) {
+            let name = name.as_ref();
+            if report.stopped_at.is_some() {
+                report.skipped.push(name.to_string());
+                continue;
+            }
+
+            let outcome = program.and_then(|program| {
+                self.evaluator.clear_call_stack();
+                self.evaluator
+                    .eval_shared(&program)
+                    .map_err(|e| self.evaluator.redact(&format!("Runtime error: {}", e)))
+            });
+            if let Ok(value) = &outcome {
+                self.evaluator.set_global(name, value.clone());
+            }
+            if policy.stops_after(&outcome) {
+                report.stopped_at = Some(name.to_string());
+            }
+            report.results.push(RuleResult {
+                name: name.to_string(),
+                outcome,
+            });
+        }
+        self.evaluator.restore_env(prev_env);
+
+        Ok(report)
+    }
+}
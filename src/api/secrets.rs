@@ -0,0 +1,47 @@
+use super::Aether;
+use std::rc::Rc;
+
+impl Aether {
+    // ============================================================
+    // 机密
+    // ============================================================
+
+    /// 注册机密提供者，脚本中的 `SECRET("name")` 通过它取值
+    ///
+    /// 提供者对未知名称返回 `None`，脚本会得到运行时错误。
+    /// 取到的值在之后的 TRACE 条目、错误信息和 PRINT / DISPLAY 输出中显示为 `***`。
+    ///
+    /// # 示例
+    /// ```
+    /// use aether::{Aether, Value};
+    ///
+    /// let mut engine = Aether::new();
+    /// engine.set_secrets_provider(|name| match name {
+    ///     "api_key" => Some("s3cr3t".to_string()),
+    ///     _ => None,
+    /// });
+    /// engine.eval(r#"TRACE("key", SECRET("api_key"))"#).unwrap();
+    /// assert_eq!(engine.take_trace(), vec!["#1 [key] ***"]);
+    /// ```
+    pub fn set_secrets_provider<F>(&mut self, provider: F)
+    where
+        F: Fn(&str) -> Option<String> + 'static,
+    {
+        self.evaluator.set_secrets_provider(Some(Rc::new(provider)));
+    }
+
+    /// 移除机密提供者，之后 `SECRET()` 调用会报错
+    pub fn clear_secrets_provider(&mut self) {
+        self.evaluator.set_secrets_provider(None);
+    }
+
+    /// 显式允许从环境变量读取机密：`SECRET("api_key")` 读取 `{prefix}API_KEY`
+    ///
+    /// 默认不会读取环境变量；只有宿主调用本方法后，带指定前缀的变量才对脚本可见。
+    pub fn use_env_secrets(&mut self, prefix: &str) {
+        let prefix = prefix.to_string();
+        self.set_secrets_provider(move |name| {
+            std::env::var(format!("{}{}", prefix, name.to_uppercase())).ok()
+        });
+    }
+}
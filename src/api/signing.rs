@@ -0,0 +1,64 @@
+use super::Aether;
+use crate::signing::{TrustLevel, TrustPolicy};
+use crate::value::Value;
+
+impl Aether {
+    // ============================================================
+    // 脚本签名
+    // ============================================================
+
+    /// 创建具有信任级别对应权限的引擎
+    pub fn with_trust_level(policy: &TrustPolicy, level: TrustLevel) -> Self {
+        Self::with_permissions(policy.permissions_for(level))
+    }
+
+    /// 校验脚本签名后求值
+    ///
+    /// `signature` 为分离签名文本（见 `signing` 模块），没有签名时传 None。
+    /// 以下情况拒绝执行：
+    /// - 签名无效、密钥未登记或脚本内容被修改
+    /// - 引擎拥有的 IO 权限超出脚本信任级别允许的权限
+    ///   （例如未签名的脚本不能在启用了网络的引擎中运行）
+    ///
+    /// # 示例
+    /// ```
+    /// use aether::Aether;
+    /// use aether::signing::{TrustLevel, TrustPolicy, sign_hmac};
+    ///
+    /// let mut policy = TrustPolicy::new();
+    /// policy.add_hmac_key("ops", b"secret", TrustLevel::Privileged);
+    ///
+    /// let script = "1 + 2";
+    /// let signature = sign_hmac("ops", b"secret", script);
+    /// let mut engine = Aether::with_all_permissions();
+    /// assert!(engine.eval_signed(script, Some(&signature), &policy).is_ok());
+    /// // 未签名的脚本不能使用启用了 IO 的引擎
+    /// assert!(engine.eval_signed(script, None, &policy).is_err());
+    /// ```
+    pub fn eval_signed(
+        &mut self,
+        code: &str,
+        signature: Option<&str>,
+        policy: &TrustPolicy,
+    ) -> Result<Value, String> {
+        let level = policy.verify(code, signature).map_err(|e| e.to_string())?;
+        let allowed = policy.permissions_for(level);
+        let granted = self.evaluator.permissions();
+        for (name, granted, allowed) in [
+            (
+                "filesystem",
+                granted.filesystem_enabled,
+                allowed.filesystem_enabled,
+            ),
+            ("network", granted.network_enabled, allowed.network_enabled),
+        ] {
+            if granted && !allowed {
+                return Err(format!(
+                    "Script trust level '{}' does not allow {} access, which this engine grants",
+                    level, name
+                ));
+            }
+        }
+        self.eval(code)
+    }
+}
@@ -0,0 +1,44 @@
+use super::Aether;
+use crate::runtime::SnapshotStats;
+use std::path::PathBuf;
+
+impl Aether {
+    // ============================================================
+    // 快照测试
+    // ============================================================
+
+    /// 设置快照目录，脚本中的 `EXPECT_SNAPSHOT` / `SNAPSHOT_MATCH` 在其中读写
+    /// `<name>.snap.json`，同时清零快照统计
+    ///
+    /// 未设置目录时快照函数会报错；目录不存在时在第一次写入快照时创建。
+    ///
+    /// # 示例
+    /// ```no_run
+    /// use aether::Aether;
+    ///
+    /// let mut engine = Aether::new();
+    /// engine.set_snapshot_dir("tests/__snapshots__");
+    /// engine.eval(r#"SNAPSHOT_MATCH("totals", {"sum": 42})"#).unwrap();
+    /// assert_eq!(engine.snapshot_stats().failed, 0);
+    /// ```
+    pub fn set_snapshot_dir(&mut self, dir: impl Into<PathBuf>) {
+        self.evaluator.set_snapshot_dir(Some(dir.into()));
+    }
+
+    /// 取消快照目录，之后快照函数会报错
+    pub fn clear_snapshot_dir(&mut self) {
+        self.evaluator.set_snapshot_dir(None);
+    }
+
+    /// 更新模式：与快照不一致时覆盖快照而不是报告失败（对应 `aether test --update-snapshots`）
+    ///
+    /// 须在 [`Aether::set_snapshot_dir`] 之后调用。
+    pub fn set_update_snapshots(&mut self, update: bool) {
+        self.evaluator.set_snapshot_update(update);
+    }
+
+    /// 自设置快照目录以来的快照比较统计
+    pub fn snapshot_stats(&self) -> SnapshotStats {
+        self.evaluator.snapshot_stats()
+    }
+}
@@ -1,32 +1,74 @@
 use super::Aether;
+use crate::module_system::STDLIB_SPECIFIER_PREFIX;
 use crate::stdlib;
 
 impl Aether {
-    /// 加载特定的标准库模块
+    /// 加载特定的标准库模块（平铺加载，旧方式）
     ///
-    /// 可用模块："string_utils"、"array_utils"、"validation"、"datetime"、"testing"
+    /// 模块中的所有函数（包括内部辅助函数）和变量都定义在全局环境中。
+    /// 新代码应使用 [`Aether::load_stdlib_module_as`] 或脚本中的
+    /// `Import X From "stdlib:<name>"`，只绑定模块公开的函数。
+    ///
+    /// 可用模块见 [`stdlib::ALL_MODULES`]。
     pub fn load_stdlib_module(&mut self, module_name: &str) -> Result<(), String> {
         if let Some(code) = stdlib::get_module(module_name) {
-            self.eval(code)?;
+            self.eval_stdlib(code)?;
             Ok(())
         } else {
             Err(format!("Unknown stdlib module: {}", module_name))
         }
     }
 
-    /// 加载所有标准库模块
+    /// 以命名空间方式加载标准库模块
+    ///
+    /// 推荐的加载方式。模块在独立环境中执行，其公开函数（见 [`stdlib::get_exports`]）
+    /// 以字典形式绑定到 `namespace`，辅助函数和变量不会进入全局环境。例如加载 "set" 为 `SETS` 后，通过
+    /// `SETS["SET_ADD"](s, 1)` 调用。等价于脚本中的 `Import SETS From "stdlib:set"`。
+    pub fn load_stdlib_module_as(
+        &mut self,
+        module_name: &str,
+        namespace: &str,
+    ) -> Result<(), String> {
+        if stdlib::get_module(module_name).is_none() {
+            return Err(format!("Unknown stdlib module: {}", module_name));
+        }
+        self.evaluator
+            .import_namespace(
+                &format!("{}{}", STDLIB_SPECIFIER_PREFIX, module_name),
+                namespace,
+            )
+            .map(|_| ())
+            .map_err(|e| format!("Runtime error: {}", e))
+    }
+
+    /// 加载所有标准库模块（平铺加载，旧方式，见 [`Aether::load_stdlib_module`]）
     pub fn load_all_stdlib(&mut self) -> Result<(), String> {
         stdlib::preload_stdlib(self)
     }
 
+    /// 执行标准库代码
+    ///
+    /// 标准库会重新定义少量内置函数（如 SORT），加载时不做严格模式的
+    /// 同名检查；加载完成后恢复原来的设置。加载期间定义的函数被标记为
+    /// 标准库代码，调用时同样不受严格模式同名检查和脚本语言版本的限制。
+    pub(crate) fn eval_stdlib(&mut self, code: &str) -> Result<(), String> {
+        let strict = self.evaluator.is_strict();
+        self.evaluator.set_strict(false);
+        self.evaluator.set_loading_library(true);
+        let result = self.eval(code);
+        self.evaluator.set_loading_library(false);
+        self.evaluator.set_strict(strict);
+        result.map(|_| ())
+    }
+
     // ============================================================
-    // 可链式调用的 stdlib 模块加载方法
+    // 可链式调用的 stdlib 模块加载方法（平铺加载，旧方式）
     // ============================================================
 
     /// 加载字符串工具模块（可链式调用）
     pub fn with_stdlib_string_utils(mut self) -> Result<Self, String> {
         if let Some(code) = stdlib::get_module("string_utils") {
-            self.eval(code)?;
+            self.eval_stdlib(code)?;
         }
         Ok(self)
     }
@@ -34,7 +76,7 @@ impl Aether {
     /// 加载数组工具模块（可链式调用）
     pub fn with_stdlib_array_utils(mut self) -> Result<Self, String> {
         if let Some(code) = stdlib::get_module("array_utils") {
-            self.eval(code)?;
+            self.eval_stdlib(code)?;
         }
         Ok(self)
     }
@@ -42,7 +84,7 @@ impl Aether {
     /// 加载验证模块（可链式调用）
     pub fn with_stdlib_validation(mut self) -> Result<Self, String> {
         if let Some(code) = stdlib::get_module("validation") {
-            self.eval(code)?;
+            self.eval_stdlib(code)?;
         }
         Ok(self)
     }
@@ -50,7 +92,7 @@ impl Aether {
     /// 加载日期时间模块（可链式调用）
     pub fn with_stdlib_datetime(mut self) -> Result<Self, String> {
         if let Some(code) = stdlib::get_module("datetime") {
-            self.eval(code)?;
+            self.eval_stdlib(code)?;
         }
         Ok(self)
     }
@@ -58,7 +100,7 @@ impl Aether {
     /// 加载测试框架模块（可链式调用）
     pub fn with_stdlib_testing(mut self) -> Result<Self, String> {
         if let Some(code) = stdlib::get_module("testing") {
-            self.eval(code)?;
+            self.eval_stdlib(code)?;
         }
         Ok(self)
     }
@@ -66,7 +108,7 @@ impl Aether {
     /// 加载集合数据结构模块（可链式调用）
     pub fn with_stdlib_set(mut self) -> Result<Self, String> {
         if let Some(code) = stdlib::get_module("set") {
-            self.eval(code)?;
+            self.eval_stdlib(code)?;
         }
         Ok(self)
     }
@@ -74,7 +116,7 @@ impl Aether {
     /// 加载队列数据结构模块（可链式调用）
     pub fn with_stdlib_queue(mut self) -> Result<Self, String> {
         if let Some(code) = stdlib::get_module("queue") {
-            self.eval(code)?;
+            self.eval_stdlib(code)?;
         }
         Ok(self)
     }
@@ -82,7 +124,7 @@ impl Aether {
     /// 加载栈数据结构模块（可链式调用）
     pub fn with_stdlib_stack(mut self) -> Result<Self, String> {
         if let Some(code) = stdlib::get_module("stack") {
-            self.eval(code)?;
+            self.eval_stdlib(code)?;
         }
         Ok(self)
     }
@@ -90,7 +132,7 @@ impl Aether {
     /// 加载堆数据结构模块（可链式调用）
     pub fn with_stdlib_heap(mut self) -> Result<Self, String> {
         if let Some(code) = stdlib::get_module("heap") {
-            self.eval(code)?;
+            self.eval_stdlib(code)?;
         }
         Ok(self)
     }
@@ -98,7 +140,7 @@ impl Aether {
     /// 加载排序算法模块（可链式调用）
     pub fn with_stdlib_sorting(mut self) -> Result<Self, String> {
         if let Some(code) = stdlib::get_module("sorting") {
-            self.eval(code)?;
+            self.eval_stdlib(code)?;
         }
         Ok(self)
     }
@@ -106,7 +148,7 @@ impl Aether {
     /// 加载 JSON 处理模块（可链式调用）
     pub fn with_stdlib_json(mut self) -> Result<Self, String> {
         if let Some(code) = stdlib::get_module("json") {
-            self.eval(code)?;
+            self.eval_stdlib(code)?;
         }
         Ok(self)
     }
@@ -114,7 +156,7 @@ impl Aether {
     /// 加载 CSV 处理模块（可链式调用）
     pub fn with_stdlib_csv(mut self) -> Result<Self, String> {
         if let Some(code) = stdlib::get_module("csv") {
-            self.eval(code)?;
+            self.eval_stdlib(code)?;
         }
         Ok(self)
     }
@@ -122,7 +164,7 @@ impl Aether {
     /// 加载函数式编程工具模块（可链式调用）
     pub fn with_stdlib_functional(mut self) -> Result<Self, String> {
         if let Some(code) = stdlib::get_module("functional") {
-            self.eval(code)?;
+            self.eval_stdlib(code)?;
         }
         Ok(self)
     }
@@ -130,7 +172,7 @@ impl Aether {
     /// 加载 CLI 工具模块（可链式调用）
     pub fn with_stdlib_cli_utils(mut self) -> Result<Self, String> {
         if let Some(code) = stdlib::get_module("cli_utils") {
-            self.eval(code)?;
+            self.eval_stdlib(code)?;
         }
         Ok(self)
     }
@@ -138,7 +180,7 @@ impl Aether {
     /// 加载文本模板引擎模块（可链式调用）
     pub fn with_stdlib_text_template(mut self) -> Result<Self, String> {
         if let Some(code) = stdlib::get_module("text_template") {
-            self.eval(code)?;
+            self.eval_stdlib(code)?;
         }
         Ok(self)
     }
@@ -146,7 +188,7 @@ impl Aether {
     /// 加载正则表达式工具模块（可链式调用）
     pub fn with_stdlib_regex_utils(mut self) -> Result<Self, String> {
         if let Some(code) = stdlib::get_module("regex_utils") {
-            self.eval(code)?;
+            self.eval_stdlib(code)?;
         }
         Ok(self)
     }
@@ -0,0 +1,40 @@
+use super::Aether;
+
+impl Aether {
+    // ============================================================
+    // 严格模式
+    // ============================================================
+
+    /// 使用严格模式创建新的 Aether 引擎
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.evaluator.set_strict(strict);
+        self
+    }
+
+    /// 开启或关闭严格模式
+    ///
+    /// 严格模式下以下写法会报错，而不是被静默接受：
+    /// - If / Elif / While 的条件不是 Boolean（如 `If (LEN(ITEMS)) { ... }`）
+    /// - Set / Func / Generator / Lazy 定义的名称与内置函数同名
+    ///   （标准库和导入的模块不受此限制，它们会重新定义 SORT 等少数内置函数）
+    ///
+    /// 读取未定义变量、字符串与数字相加在任何模式下都会报错。
+    ///
+    /// # 示例
+    /// ```
+    /// use aether::Aether;
+    ///
+    /// let mut engine = Aether::new();
+    /// engine.set_strict(true);
+    /// assert!(engine.eval("If (1) { 2 }").is_err());
+    /// assert!(engine.eval("If (1 == 1) { 2 }").is_ok());
+    /// ```
+    pub fn set_strict(&mut self, strict: bool) {
+        self.evaluator.set_strict(strict);
+    }
+
+    /// 是否开启了严格模式
+    pub fn is_strict(&self) -> bool {
+        self.evaluator.is_strict()
+    }
+}
@@ -0,0 +1,126 @@
+use super::Aether;
+use crate::runtime::PumpReport;
+use crate::value::Value;
+use std::time::Duration;
+
+impl Aether {
+    // ============================================================
+    // 宿主驱动的定时器（GUI 事件循环 / wasm 嵌入）
+    // ============================================================
+
+    /// 每隔 `interval` 调用一次全局函数 `name`（无参数），返回定时器编号
+    ///
+    /// 定时器不会自行触发：宿主在事件循环中调用 [`Aether::pump`] 执行到期的回调。
+    /// 函数在触发时按名称查找，脚本重新定义该函数后使用新的定义。
+    ///
+    /// # 示例
+    /// ```
+    /// use aether::Aether;
+    /// use std::time::Duration;
+    ///
+    /// let mut engine = Aether::new();
+    /// engine.eval("Func TICK() { TRACE(\"tick\") }").unwrap();
+    /// engine.on_timer(Duration::from_secs(1), "TICK").unwrap();
+    ///
+    /// let report = engine.advance_timers(Duration::from_millis(1500));
+    /// assert_eq!(report.runs, 1);
+    /// assert_eq!(engine.take_trace().len(), 1);
+    /// ```
+    pub fn on_timer(&mut self, interval: Duration, name: &str) -> Result<usize, String> {
+        if interval.is_zero() {
+            return Err("Timer interval must be greater than 0".to_string());
+        }
+        self.timer_callback(name)?;
+        Ok(self
+            .evaluator
+            .timers_mut()
+            .add(None, name.to_string(), interval, Some(interval)))
+    }
+
+    /// `delay` 之后调用一次全局函数 `name`，返回定时器编号
+    pub fn on_timeout(&mut self, delay: Duration, name: &str) -> Result<usize, String> {
+        self.timer_callback(name)?;
+        Ok(self
+            .evaluator
+            .timers_mut()
+            .add(None, name.to_string(), delay, None))
+    }
+
+    /// 防抖调用全局函数 `name`：在 `delay` 内再次调用会重新计时，安静 `delay` 后只执行一次。
+    /// 等待期间返回同一个定时器编号
+    pub fn debounce(&mut self, delay: Duration, name: &str) -> Result<usize, String> {
+        self.timer_callback(name)?;
+        Ok(self
+            .evaluator
+            .timers_mut()
+            .debounce(None, name.to_string(), delay))
+    }
+
+    /// 取消定时器（宿主或脚本注册的），返回定时器是否存在
+    pub fn cancel_timer(&mut self, id: usize) -> bool {
+        self.evaluator.timers_mut().cancel(id)
+    }
+
+    /// 是否还有等待触发的定时器
+    pub fn has_pending_timers(&self) -> bool {
+        self.evaluator.has_pending_timers()
+    }
+
+    /// 距下一个定时器到期还有多久（已有到期的定时器时为零），没有定时器时为 `None`。
+    /// 宿主可据此设置事件循环的唤醒时间
+    pub fn next_timer_delay(&mut self) -> Option<Duration> {
+        self.evaluator.timers_mut().next_delay()
+    }
+
+    /// 执行所有到期的定时器回调，不阻塞、不等待
+    ///
+    /// 回调按到期时间依次执行，单个回调失败不影响其他回调，错误收集在 [`PumpReport::errors`] 中。
+    /// 回调中新注册的定时器最早在下一次 `pump` 时触发。
+    pub fn pump(&mut self) -> PumpReport {
+        let due = self.evaluator.timers_mut().take_due();
+        let mut report = PumpReport::default();
+        for timer in due {
+            let callback = match timer.callback {
+                Some(callback) => Ok(callback),
+                None => self.timer_callback(&timer.label),
+            };
+            let outcome = callback.and_then(|func| {
+                self.evaluator.clear_call_stack();
+                let _secrets = self.evaluator.scoped_secrets();
+                self.evaluator
+                    .call_value(&func, vec![])
+                    .map_err(|e| self.evaluator.redact(&e.to_string()))
+            });
+            match outcome {
+                Ok(_) => report.runs += 1,
+                Err(e) => report.errors.push(format!(
+                    "Timer #{} ({}) failed: {}",
+                    timer.id, timer.label, e
+                )),
+            }
+        }
+        report
+    }
+
+    /// 把定时器时钟推进 `elapsed` 后执行到期的回调
+    ///
+    /// 没有单调时钟的宿主（wasm）用它驱动定时器，例如传入两帧之间的间隔；
+    /// 测试中也可用它模拟时间流逝。
+    pub fn advance_timers(&mut self, elapsed: Duration) -> PumpReport {
+        self.evaluator.timers_mut().advance(elapsed);
+        self.pump()
+    }
+
+    /// 按名称查找定时器回调
+    fn timer_callback(&self, name: &str) -> Result<Value, String> {
+        match self.evaluator.get_global(name) {
+            Some(func @ (Value::Function { .. } | Value::BuiltIn { .. })) => Ok(func),
+            Some(other) => Err(format!(
+                "'{}' is a {}, not a function",
+                name,
+                other.type_name()
+            )),
+            None => Err(format!("Undefined function: {}", name)),
+        }
+    }
+}
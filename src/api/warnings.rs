@@ -0,0 +1,47 @@
+use super::Aether;
+use crate::runtime::{Warning, WarningKind};
+
+impl Aether {
+    // ============================================================
+    // 警告
+    // ============================================================
+
+    /// 取出并清空累计的警告
+    ///
+    /// 警告不会导致求值失败，来源包括：
+    /// - 解析器：字典字面量中的重复键
+    /// - 优化器：因条件恒定而被移除的代码
+    /// - 求值器：重复或覆盖已有变量的导入、弃用的内置函数、
+    ///   内置函数名回退解析、分数转浮点数及超过 2^53 的整数运算
+    ///
+    /// 解析和优化阶段的警告只在代码首次解析时产生（命中 AST 缓存时不会重复）。
+    ///
+    /// # 示例
+    /// ```
+    /// use aether::{Aether, WarningKind};
+    ///
+    /// let mut engine = Aether::new();
+    /// engine.eval(r#"Set D {"a": 1, "a": 2}"#).unwrap();
+    /// let warnings = engine.take_warnings();
+    /// assert_eq!(warnings[0].kind, WarningKind::Parse);
+    /// assert!(engine.take_warnings().is_empty());
+    /// ```
+    pub fn take_warnings(&mut self) -> Vec<Warning> {
+        self.evaluator.take_warnings()
+    }
+
+    /// 查看累计的警告（不清空）
+    pub fn warnings(&self) -> &[Warning] {
+        self.evaluator.warnings()
+    }
+
+    /// 记录解析器和优化器产生的警告
+    pub(crate) fn record_compile_warnings(&mut self, parse_warnings: Vec<String>) {
+        for message in parse_warnings {
+            self.evaluator.warn(WarningKind::Parse, message);
+        }
+        for message in self.optimizer.take_warnings() {
+            self.evaluator.warn(WarningKind::DeadCode, message);
+        }
+    }
+}
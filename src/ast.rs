@@ -3,8 +3,11 @@
 //!
 //! This module defines the structure of Aether programs as a tree of nodes.
 
+use serde::Serialize;
+use std::sync::Arc;
+
 /// Binary operators
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum BinOp {
     // Arithmetic
     Add,      // +
@@ -27,19 +30,20 @@ pub enum BinOp {
 }
 
 /// Unary operators
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum UnaryOp {
     Minus, // -
     Not,   // !
 }
 
 /// Expressions - things that evaluate to values
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum Expr {
     // Literals
     Number(f64),
+    Integer(i64),
     BigInteger(String), // 大整数字面量
-    String(String),
+    String(Arc<str>),   // 缓存中相同的字符串字面量共享存储
     Boolean(bool),
     Null,
 
@@ -90,10 +94,16 @@ pub enum Expr {
         params: Vec<String>,
         body: Vec<Stmt>,
     },
+
+    // Extension block: @TAG { payload } (evaluated by a host-registered handler)
+    Extension {
+        tag: String,
+        payload: String,
+    },
 }
 
 /// Statements - things that perform actions
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum Stmt {
     // Variable assignment: Set NAME value
     Set {
@@ -185,13 +195,450 @@ pub enum Stmt {
     // Throw statement: Throw message
     Throw(Expr),
 
+    // Try statement: Try { body } Catch (ERR) { handler } Finally { cleanup }
+    // At least one of the Catch and Finally clauses is present
+    Try {
+        body: Vec<Stmt>,
+        catch: Option<(String, Vec<Stmt>)>,
+        finally: Option<Vec<Stmt>>,
+    },
+
+    // Pragma: #NAME value (e.g. #language 1.0)
+    Pragma {
+        name: String,
+        value: String,
+    },
+
     // Expression statement (expression as statement)
     Expression(Expr),
+
+    // A statement together with where it appears in the source (added by the parser).
+    // The statement itself may be shared between cached programs (see `cache`).
+    Located {
+        span: SourceSpan,
+        stmt: Arc<Stmt>,
+    },
 }
 
 /// A complete program is a list of statements
 pub type Program = Vec<Stmt>;
 
+/// Source range of a statement: char offsets `start..end` and the 1-based line/column of `start`
+///
+/// Spans take part in equality like any other field. To compare programs regardless of
+/// where their statements appear (e.g. a program and a reformatted copy of it), use
+/// [`eq_ignoring_spans`] or compare [`Stmt::without_spans`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct SourceSpan {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl From<crate::token::Span> for SourceSpan {
+    fn from(span: crate::token::Span) -> Self {
+        SourceSpan {
+            start: span.start,
+            end: span.end,
+            line: span.line,
+            column: span.column,
+        }
+    }
+}
+
+impl std::fmt::Display for SourceSpan {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+impl Stmt {
+    /// Attach a source span to a statement
+    pub fn located(span: SourceSpan, stmt: Stmt) -> Self {
+        Stmt::Located {
+            span,
+            stmt: Arc::new(stmt),
+        }
+    }
+
+    /// The statement itself, without its source span
+    pub fn node(&self) -> &Stmt {
+        match self {
+            Stmt::Located { stmt, .. } => stmt.node(),
+            other => other,
+        }
+    }
+
+    /// Where the statement appears in the source (`None` for statements built in code)
+    pub fn span(&self) -> Option<SourceSpan> {
+        match self {
+            Stmt::Located { span, .. } => Some(*span),
+            _ => None,
+        }
+    }
+
+    /// A copy of the statement with every source span removed, including those of
+    /// statements nested in bodies and lambdas
+    pub fn without_spans(&self) -> Stmt {
+        SpanStripper.fold_stmt(self.clone())
+    }
+
+    /// Rebuild the statement, passing each direct child statement and expression through `folder`
+    pub(crate) fn fold_children<F: Fold + ?Sized>(self, folder: &mut F) -> Stmt {
+        let block = |folder: &mut F, body: Vec<Stmt>| {
+            body.into_iter().map(|s| folder.fold_stmt(s)).collect()
+        };
+        match self {
+            Stmt::Located { span, stmt } => Stmt::Located {
+                span,
+                stmt: Arc::new(folder.fold_stmt(Arc::unwrap_or_clone(stmt))),
+            },
+            Stmt::Set { name, value } => Stmt::Set {
+                name,
+                value: folder.fold_expr(value),
+            },
+            Stmt::SetIndex {
+                object,
+                index,
+                value,
+            } => Stmt::SetIndex {
+                object: Box::new(folder.fold_expr(*object)),
+                index: Box::new(folder.fold_expr(*index)),
+                value: folder.fold_expr(value),
+            },
+            Stmt::FuncDef { name, params, body } => Stmt::FuncDef {
+                name,
+                params,
+                body: block(folder, body),
+            },
+            Stmt::GeneratorDef { name, params, body } => Stmt::GeneratorDef {
+                name,
+                params,
+                body: block(folder, body),
+            },
+            Stmt::LazyDef { name, expr } => Stmt::LazyDef {
+                name,
+                expr: folder.fold_expr(expr),
+            },
+            Stmt::Return(expr) => Stmt::Return(folder.fold_expr(expr)),
+            Stmt::Yield(expr) => Stmt::Yield(folder.fold_expr(expr)),
+            Stmt::Throw(expr) => Stmt::Throw(folder.fold_expr(expr)),
+            Stmt::Expression(expr) => Stmt::Expression(folder.fold_expr(expr)),
+            Stmt::While { condition, body } => Stmt::While {
+                condition: folder.fold_expr(condition),
+                body: block(folder, body),
+            },
+            Stmt::For {
+                var,
+                iterable,
+                body,
+            } => Stmt::For {
+                var,
+                iterable: folder.fold_expr(iterable),
+                body: block(folder, body),
+            },
+            Stmt::ForIndexed {
+                index_var,
+                value_var,
+                iterable,
+                body,
+            } => Stmt::ForIndexed {
+                index_var,
+                value_var,
+                iterable: folder.fold_expr(iterable),
+                body: block(folder, body),
+            },
+            Stmt::Switch {
+                expr,
+                cases,
+                default,
+            } => Stmt::Switch {
+                expr: folder.fold_expr(expr),
+                cases: cases
+                    .into_iter()
+                    .map(|(value, body)| (folder.fold_expr(value), block(folder, body)))
+                    .collect(),
+                default: default.map(|body| block(folder, body)),
+            },
+            Stmt::Try {
+                body,
+                catch,
+                finally,
+            } => Stmt::Try {
+                body: block(folder, body),
+                catch: catch.map(|(name, handler)| (name, block(folder, handler))),
+                finally: finally.map(|body| block(folder, body)),
+            },
+            other @ (Stmt::Break
+            | Stmt::Continue
+            | Stmt::Import { .. }
+            | Stmt::Export(_)
+            | Stmt::Pragma { .. }) => other,
+        }
+    }
+}
+
+impl Expr {
+    /// A copy of the expression with the source spans of nested statements removed
+    pub fn without_spans(&self) -> Expr {
+        SpanStripper.fold_expr(self.clone())
+    }
+
+    /// Rebuild the expression, passing each direct child statement and expression through `folder`
+    pub(crate) fn fold_children<F: Fold + ?Sized>(self, folder: &mut F) -> Expr {
+        let block = |folder: &mut F, body: Vec<Stmt>| {
+            body.into_iter().map(|s| folder.fold_stmt(s)).collect()
+        };
+        match self {
+            Expr::Binary { left, op, right } => Expr::Binary {
+                left: Box::new(folder.fold_expr(*left)),
+                op,
+                right: Box::new(folder.fold_expr(*right)),
+            },
+            Expr::Unary { op, expr } => Expr::Unary {
+                op,
+                expr: Box::new(folder.fold_expr(*expr)),
+            },
+            Expr::Call { func, args } => Expr::Call {
+                func: Box::new(folder.fold_expr(*func)),
+                args: args.into_iter().map(|arg| folder.fold_expr(arg)).collect(),
+            },
+            Expr::Array(items) => Expr::Array(
+                items
+                    .into_iter()
+                    .map(|item| folder.fold_expr(item))
+                    .collect(),
+            ),
+            Expr::Dict(entries) => Expr::Dict(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key, folder.fold_expr(value)))
+                    .collect(),
+            ),
+            Expr::Index { object, index } => Expr::Index {
+                object: Box::new(folder.fold_expr(*object)),
+                index: Box::new(folder.fold_expr(*index)),
+            },
+            Expr::If {
+                condition,
+                then_branch,
+                elif_branches,
+                else_branch,
+            } => Expr::If {
+                condition: Box::new(folder.fold_expr(*condition)),
+                then_branch: block(folder, then_branch),
+                elif_branches: elif_branches
+                    .into_iter()
+                    .map(|(condition, body)| (folder.fold_expr(condition), block(folder, body)))
+                    .collect(),
+                else_branch: else_branch.map(|body| block(folder, body)),
+            },
+            Expr::Lambda { params, body } => Expr::Lambda {
+                params,
+                body: block(folder, body),
+            },
+            other @ (Expr::Number(_)
+            | Expr::Integer(_)
+            | Expr::BigInteger(_)
+            | Expr::String(_)
+            | Expr::Boolean(_)
+            | Expr::Null
+            | Expr::Identifier(_)
+            | Expr::Extension { .. }) => other,
+        }
+    }
+}
+
+/// Rebuilds a tree node by node
+///
+/// The default methods only descend into children; implementors override them to
+/// replace the nodes they care about.
+pub(crate) trait Fold {
+    fn fold_stmt(&mut self, stmt: Stmt) -> Stmt {
+        stmt.fold_children(self)
+    }
+
+    fn fold_expr(&mut self, expr: Expr) -> Expr {
+        expr.fold_children(self)
+    }
+}
+
+/// Removes `Located` wrappers at every depth
+struct SpanStripper;
+
+impl Fold for SpanStripper {
+    fn fold_stmt(&mut self, stmt: Stmt) -> Stmt {
+        match stmt {
+            Stmt::Located { stmt, .. } => self.fold_stmt(Arc::unwrap_or_clone(stmt)),
+            other => other.fold_children(self),
+        }
+    }
+}
+
+/// Compare two programs, ignoring where their statements appear in the source
+pub fn eq_ignoring_spans(a: &[Stmt], b: &[Stmt]) -> bool {
+    a.len() == b.len()
+        && a.iter()
+            .zip(b)
+            .all(|(a, b)| a.without_spans() == b.without_spans())
+}
+
+/// Render a program as pretty-printed JSON
+///
+/// Enum variants use serde's externally tagged form, e.g. `{"Set": {"name": "X", "value": {"Number": 1.0}}}`.
+pub fn program_to_json(program: &[Stmt]) -> String {
+    serde_json::to_string_pretty(program).unwrap_or_else(|_| "[]".to_string())
+}
+
+/// Render a program as a Graphviz DOT digraph
+///
+/// Each statement and expression becomes a node labelled with its variant name and scalar
+/// fields; nested nodes hang off edges labelled with the field they came from.
+pub fn program_to_dot(program: &[Stmt]) -> String {
+    let mut graph = DotGraph::default();
+    graph.line("digraph AST {".to_string());
+    graph.line("  node [shape=box, fontname=\"monospace\"];".to_string());
+    let root = graph.node("Program".to_string());
+    for (i, stmt) in program.iter().enumerate() {
+        let value = serde_json::to_value(stmt).unwrap_or(serde_json::Value::Null);
+        let child = graph.value(&value);
+        graph.edge(root, child, &i.to_string());
+    }
+    graph.line("}".to_string());
+    graph.lines.join("\n") + "\n"
+}
+
+#[derive(Default)]
+struct DotGraph {
+    lines: Vec<String>,
+    next_id: usize,
+}
+
+impl DotGraph {
+    fn line(&mut self, line: String) {
+        self.lines.push(line);
+    }
+
+    fn node(&mut self, label: String) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.line(format!("  n{} [label=\"{}\"];", id, escape_dot(&label)));
+        id
+    }
+
+    fn edge(&mut self, from: usize, to: usize, label: &str) {
+        self.line(format!(
+            "  n{} -> n{} [label=\"{}\"];",
+            from,
+            to,
+            escape_dot(label)
+        ));
+    }
+
+    /// Emit a node for a serialized AST value and return its id
+    fn value(&mut self, value: &serde_json::Value) -> usize {
+        use serde_json::Value as Json;
+        match value {
+            // Enum variant: {"Variant": payload}
+            Json::Object(map) if map.len() == 1 && is_variant(map.keys().next().unwrap()) => {
+                let (variant, payload) = map.iter().next().unwrap();
+                match payload {
+                    Json::Object(fields) if !is_variant_object(payload) => {
+                        let mut label = variant.clone();
+                        let mut children = Vec::new();
+                        for (field, field_value) in fields {
+                            match scalar(field_value) {
+                                Some(text) => label.push_str(&format!("\n{}: {}", field, text)),
+                                None => children.push((field, field_value)),
+                            }
+                        }
+                        let id = self.node(label);
+                        for (field, field_value) in children {
+                            self.children(id, field, field_value);
+                        }
+                        id
+                    }
+                    _ => match scalar(payload) {
+                        Some(text) => self.node(format!("{}\n{}", variant, text)),
+                        None => {
+                            let id = self.node(variant.clone());
+                            self.children(id, "", payload);
+                            id
+                        }
+                    },
+                }
+            }
+            // Unit variant (e.g. "Break") or operator
+            Json::String(name) => self.node(name.clone()),
+            Json::Array(items) => {
+                let id = self.node(String::new());
+                for (i, item) in items.iter().enumerate() {
+                    let child = self.value(item);
+                    self.edge(id, child, &i.to_string());
+                }
+                id
+            }
+            other => self.node(scalar(other).unwrap_or_default()),
+        }
+    }
+
+    /// Attach a field's value to `parent`; arrays get one edge per element
+    fn children(&mut self, parent: usize, field: &str, value: &serde_json::Value) {
+        match value {
+            serde_json::Value::Array(items) => {
+                for (i, item) in items.iter().enumerate() {
+                    let child = self.value(item);
+                    self.edge(parent, child, &format!("{}[{}]", field, i));
+                }
+            }
+            _ => {
+                let child = self.value(value);
+                self.edge(parent, child, field);
+            }
+        }
+    }
+}
+
+fn is_variant(key: &str) -> bool {
+    key.starts_with(|c: char| c.is_ascii_uppercase())
+}
+
+fn is_variant_object(value: &serde_json::Value) -> bool {
+    matches!(value, serde_json::Value::Object(map) if map.len() == 1 && is_variant(map.keys().next().unwrap()))
+}
+
+/// Inline text for leaf values (strings, numbers, operators, null); `None` for nested nodes
+fn scalar(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        serde_json::Value::Bool(b) => Some(b.to_string()),
+        serde_json::Value::Null => Some("null".to_string()),
+        // Source span: {"start", "end", "line", "column"}
+        serde_json::Value::Object(map) if map.contains_key("line") => Some(format!(
+            "{}:{}",
+            map["line"],
+            map.get("column").cloned().unwrap_or_default()
+        )),
+        serde_json::Value::Array(items) if items.is_empty() => Some("[]".to_string()),
+        serde_json::Value::Array(items) if items.iter().all(|item| item.is_string()) => Some(
+            items
+                .iter()
+                .filter_map(|item| item.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+        ),
+        _ => None,
+    }
+}
+
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
 impl Expr {
     /// Helper to create a binary expression
     pub fn binary(left: Expr, op: BinOp, right: Expr) -> Self {
@@ -227,6 +674,111 @@ impl Expr {
     }
 }
 
+impl Expr {
+    /// Variable names referenced by this expression, in evaluation order and without duplicates
+    ///
+    /// Function bodies (Lambda, If branches) are not searched.
+    pub fn referenced_names(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        self.collect_names(&mut names);
+        names
+    }
+
+    fn collect_names(&self, names: &mut Vec<String>) {
+        match self {
+            Expr::Identifier(name) if !names.contains(name) => names.push(name.clone()),
+            Expr::Binary { left, right, .. } => {
+                left.collect_names(names);
+                right.collect_names(names);
+            }
+            Expr::Unary { expr, .. } => expr.collect_names(names),
+            Expr::Call { func, args } => {
+                func.collect_names(names);
+                args.iter().for_each(|arg| arg.collect_names(names));
+            }
+            Expr::Array(items) => items.iter().for_each(|item| item.collect_names(names)),
+            Expr::Dict(pairs) => pairs
+                .iter()
+                .for_each(|(_, value)| value.collect_names(names)),
+            Expr::Index { object, index } => {
+                object.collect_names(names);
+                index.collect_names(names);
+            }
+            Expr::If { condition, .. } => condition.collect_names(names),
+            _ => {}
+        }
+    }
+}
+
+/// Source-like rendering of an expression (statement bodies are elided as `{ ... }`)
+impl std::fmt::Display for Expr {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fn operand(f: &mut std::fmt::Formatter, expr: &Expr) -> std::fmt::Result {
+            match expr {
+                Expr::Binary { .. } => write!(f, "({})", expr),
+                _ => write!(f, "{}", expr),
+            }
+        }
+        fn list(f: &mut std::fmt::Formatter, items: &[Expr]) -> std::fmt::Result {
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{}", item)?;
+            }
+            Ok(())
+        }
+
+        match self {
+            Expr::Number(n) => write!(f, "{}", n),
+            Expr::Integer(n) => write!(f, "{}", n),
+            Expr::BigInteger(s) => write!(f, "{}", s),
+            Expr::String(s) => write!(f, "{:?}", s),
+            Expr::Boolean(true) => write!(f, "True"),
+            Expr::Boolean(false) => write!(f, "False"),
+            Expr::Null => write!(f, "Null"),
+            Expr::Identifier(name) => write!(f, "{}", name),
+            Expr::Binary { left, op, right } => {
+                operand(f, left)?;
+                write!(f, " {} ", op)?;
+                operand(f, right)
+            }
+            Expr::Unary { op, expr } => {
+                write!(f, "{}", op)?;
+                operand(f, expr)
+            }
+            Expr::Call { func, args } => {
+                operand(f, func)?;
+                write!(f, "(")?;
+                list(f, args)?;
+                write!(f, ")")
+            }
+            Expr::Array(items) => {
+                write!(f, "[")?;
+                list(f, items)?;
+                write!(f, "]")
+            }
+            Expr::Dict(pairs) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in pairs.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{:?}: {}", key, value)?;
+                }
+                write!(f, "}}")
+            }
+            Expr::Index { object, index } => {
+                operand(f, object)?;
+                write!(f, "[{}]", index)
+            }
+            Expr::If { condition, .. } => write!(f, "If ({}) {{ ... }}", condition),
+            Expr::Lambda { params, .. } => write!(f, "Lambda ({}) -> ...", params.join(", ")),
+            Expr::Extension { tag, .. } => write!(f, "@{} {{ ... }}", tag),
+        }
+    }
+}
+
 impl std::fmt::Display for BinOp {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
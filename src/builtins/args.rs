@@ -0,0 +1,234 @@
+// src/builtins/args.rs
+//! 内置函数参数校验
+//!
+//! 使用声明式的 `ArgSpec` 描述内置函数的参数（名称、类型、是否可选），
+//! 统一校验参数个数与类型并生成格式一致的错误信息，同时供 HELP() 展示签名。
+
+use crate::evaluator::RuntimeError;
+use crate::value::Value;
+use num_bigint::BigInt;
+use num_rational::Ratio;
+
+/// 参数类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArgType {
+    /// 任意值
+    Any,
+    /// Number
+    Number,
+    /// Number 或 Fraction
+    Numeric,
+    /// String
+    String,
+    /// Boolean
+    Boolean,
+    /// Array
+    Array,
+    /// Dict
+    Dict,
+}
+
+impl ArgType {
+    /// 类型名称（用于签名和错误信息）
+    pub fn name(&self) -> &'static str {
+        match self {
+            ArgType::Any => "Any",
+            ArgType::Number => "Number",
+            ArgType::Numeric => "Number|Fraction",
+            ArgType::String => "String",
+            ArgType::Boolean => "Boolean",
+            ArgType::Array => "Array",
+            ArgType::Dict => "Dict",
+        }
+    }
+
+    /// 判断值是否符合该类型
+    pub fn accepts(&self, value: &Value) -> bool {
+        match self {
+            ArgType::Any => true,
+            ArgType::Number => matches!(value, Value::Number(_)),
+            ArgType::Numeric => matches!(value, Value::Number(_) | Value::Fraction(_)),
+            ArgType::String => matches!(value, Value::String(_)),
+            ArgType::Boolean => matches!(value, Value::Boolean(_)),
+            ArgType::Array => matches!(value, Value::Array(_)),
+            ArgType::Dict => matches!(value, Value::Dict(_)),
+        }
+    }
+}
+
+/// 单个参数的描述
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Param {
+    pub name: &'static str,
+    pub ty: ArgType,
+    pub optional: bool,
+}
+
+impl Param {
+    /// 必填参数
+    pub const fn required(name: &'static str, ty: ArgType) -> Self {
+        Param {
+            name,
+            ty,
+            optional: false,
+        }
+    }
+
+    /// 可选参数（只能出现在必填参数之后）
+    pub const fn optional(name: &'static str, ty: ArgType) -> Self {
+        Param {
+            name,
+            ty,
+            optional: true,
+        }
+    }
+}
+
+/// 内置函数的参数规格
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArgSpec {
+    /// 函数名
+    pub name: &'static str,
+    /// 固定参数
+    pub params: &'static [Param],
+    /// 可变参数（接受任意多个该类型的参数）
+    pub rest: Option<Param>,
+}
+
+impl ArgSpec {
+    /// 创建固定参数的规格
+    pub const fn new(name: &'static str, params: &'static [Param]) -> Self {
+        ArgSpec {
+            name,
+            params,
+            rest: None,
+        }
+    }
+
+    /// 创建带可变参数的规格
+    pub const fn variadic(name: &'static str, params: &'static [Param], rest: Param) -> Self {
+        ArgSpec {
+            name,
+            params,
+            rest: Some(rest),
+        }
+    }
+
+    /// 必填参数个数
+    pub fn required_count(&self) -> usize {
+        self.params.iter().filter(|p| !p.optional).count()
+    }
+
+    /// 函数签名，如 `ROUND_TO(value: Number, decimals?: Number)`
+    pub fn signature(&self) -> String {
+        let mut parts: Vec<String> = self
+            .params
+            .iter()
+            .map(|p| {
+                let marker = if p.optional { "?" } else { "" };
+                format!("{}{}: {}", p.name, marker, p.ty.name())
+            })
+            .collect();
+        if let Some(rest) = &self.rest {
+            parts.push(format!("...{}: {}", rest.name, rest.ty.name()));
+        }
+        format!("{}({})", self.name, parts.join(", "))
+    }
+
+    /// 校验参数个数与类型
+    pub fn check(&self, args: &[Value]) -> Result<(), RuntimeError> {
+        let required = self.required_count();
+        if args.len() < required {
+            return Err(RuntimeError::WrongArity {
+                expected: required,
+                got: args.len(),
+            });
+        }
+        if self.rest.is_none() && args.len() > self.params.len() {
+            return Err(RuntimeError::WrongArity {
+                expected: self.params.len(),
+                got: args.len(),
+            });
+        }
+
+        for (index, arg) in args.iter().enumerate() {
+            let Some(param) = self.params.get(index).or(self.rest.as_ref()) else {
+                break;
+            };
+            if !param.ty.accepts(arg) {
+                return Err(RuntimeError::TypeErrorDetailed {
+                    expected: format!(
+                        "{} for parameter '{}' of {}",
+                        param.ty.name(),
+                        param.name,
+                        self.name
+                    ),
+                    got: arg.type_name().to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 读取已校验的 Number 参数
+///
+/// 只应在 `ArgSpec::check` 之后调用；参数缺失或不是 Number 时返回 None。
+pub fn number(args: &[Value], index: usize) -> Option<f64> {
+    match args.get(index) {
+        Some(Value::Number(n)) => Some(*n),
+        _ => None,
+    }
+}
+
+/// 按规格校验后对单个 Number 参数应用函数
+pub fn unary_number(
+    spec: &ArgSpec,
+    args: &[Value],
+    f: impl Fn(f64) -> f64,
+) -> Result<Value, RuntimeError> {
+    spec.check(args)?;
+    let x = number(args, 0).unwrap_or(f64::NAN);
+    Ok(Value::Number(f(x)))
+}
+
+/// 按规格校验后对单个 Number 或 Fraction 参数应用函数
+///
+/// Fraction 参数使用 `exact` 精确计算，结果仍为 Fraction。
+pub fn unary_numeric(
+    spec: &ArgSpec,
+    args: &[Value],
+    f: impl Fn(f64) -> f64,
+    exact: impl Fn(&Ratio<BigInt>) -> Ratio<BigInt>,
+) -> Result<Value, RuntimeError> {
+    spec.check(args)?;
+    match &args[0] {
+        Value::Fraction(r) => Ok(Value::Fraction(exact(r))),
+        _ => Ok(Value::Number(f(number(args, 0).unwrap_or(f64::NAN)))),
+    }
+}
+
+/// 按规格校验后对两个 Number 参数应用函数
+pub fn binary_number(
+    spec: &ArgSpec,
+    args: &[Value],
+    f: impl Fn(f64, f64) -> f64,
+) -> Result<Value, RuntimeError> {
+    spec.check(args)?;
+    let x = number(args, 0).unwrap_or(f64::NAN);
+    let y = number(args, 1).unwrap_or(f64::NAN);
+    Ok(Value::Number(f(x, y)))
+}
+
+/// 所有声明了参数规格的内置函数
+fn all_specs() -> impl Iterator<Item = &'static ArgSpec> {
+    let specs = super::math::ARG_SPECS.iter().chain(super::io::ARG_SPECS);
+    #[cfg(feature = "payroll")]
+    let specs = specs.chain(super::payroll::basic::ARG_SPECS);
+    specs
+}
+
+/// 按函数名查找参数规格
+pub fn spec_for(name: &str) -> Option<&'static ArgSpec> {
+    all_specs().find(|spec| spec.name == name)
+}
@@ -78,6 +78,12 @@ pub fn range(args: &[Value]) -> Result<Value, RuntimeError> {
         }
     };
 
+    if !(start.is_finite() && end.is_finite() && step.is_finite()) {
+        return Err(RuntimeError::InvalidOperation(
+            "Range bounds and step must be finite numbers".to_string(),
+        ));
+    }
+
     if step == 0.0 {
         return Err(RuntimeError::InvalidOperation(
             "Range step cannot be zero".to_string(),
@@ -211,6 +217,9 @@ pub fn pop(args: &[Value]) -> Result<Value, RuntimeError> {
 /// Set str Join(words, " ")     # "Hello World"
 /// ```
 pub fn join(args: &[Value]) -> Result<Value, RuntimeError> {
+    if let Some(Value::Table(_)) = args.first() {
+        return super::table::join(args);
+    }
     if args.len() != 2 {
         return Err(RuntimeError::WrongArity {
             expected: 2,
@@ -289,6 +298,9 @@ pub fn reverse(args: &[Value]) -> Result<Value, RuntimeError> {
 /// Println(nums)                # [3, 1, 4, 1, 5, 9, 2, 6] (原数组不变)
 /// ```
 pub fn sort(args: &[Value]) -> Result<Value, RuntimeError> {
+    if let Some(Value::Table(_)) = args.first() {
+        return super::table::sort(args);
+    }
     if args.len() != 1 {
         return Err(RuntimeError::WrongArity {
             expected: 1,
@@ -310,7 +322,7 @@ pub fn sort(args: &[Value]) -> Result<Value, RuntimeError> {
                     }
                 }
             }
-            numbers.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            numbers.sort_by(|a, b| a.total_cmp(b));
             Ok(Value::Array(
                 numbers.into_iter().map(Value::Number).collect(),
             ))
@@ -577,3 +589,172 @@ pub fn reduce(_args: &[Value]) -> Result<Value, RuntimeError> {
             .to_string(),
     ))
 }
+
+/// 比较两个元素（用于有序数组操作）
+///
+/// 支持 Number、Fraction（可混合比较）、String、Boolean。
+fn compare_elements(a: &Value, b: &Value) -> Result<std::cmp::Ordering, RuntimeError> {
+    if let Some(ord) = a.compare(b) {
+        return Ok(ord);
+    }
+    match (a, b) {
+        (Value::Number(_) | Value::Fraction(_), Value::Number(_) | Value::Fraction(_)) => {
+            if let (Some(x), Some(y)) = (a.to_number(), b.to_number())
+                && let Some(ord) = x.partial_cmp(&y)
+            {
+                return Ok(ord);
+            }
+            Err(RuntimeError::InvalidOperation(
+                "Cannot compare NaN in sorted array".to_string(),
+            ))
+        }
+        _ => Err(RuntimeError::TypeErrorDetailed {
+            expected: format!("value comparable with {}", a.type_name()),
+            got: b.type_name().to_string(),
+        }),
+    }
+}
+
+/// 解析有序数组操作的参数 (array, value)
+fn sorted_args(args: &[Value]) -> Result<(&Vec<Value>, &Value), RuntimeError> {
+    if args.len() != 2 {
+        return Err(RuntimeError::WrongArity {
+            expected: 2,
+            got: args.len(),
+        });
+    }
+
+    match &args[0] {
+        Value::Array(arr) => Ok((arr, &args[1])),
+        _ => Err(RuntimeError::TypeErrorDetailed {
+            expected: "Array".to_string(),
+            got: args[0].type_name().to_string(),
+        }),
+    }
+}
+
+/// 二分查找第一个满足 `!before(elem)` 的位置
+fn partition_point(
+    arr: &[Value],
+    mut before: impl FnMut(&Value) -> Result<bool, RuntimeError>,
+) -> Result<usize, RuntimeError> {
+    let (mut lo, mut hi) = (0, arr.len());
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if before(&arr[mid])? {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+    Ok(lo)
+}
+
+fn lower_bound_index(arr: &[Value], target: &Value) -> Result<usize, RuntimeError> {
+    partition_point(arr, |elem| {
+        Ok(compare_elements(elem, target)? == std::cmp::Ordering::Less)
+    })
+}
+
+fn upper_bound_index(arr: &[Value], target: &Value) -> Result<usize, RuntimeError> {
+    partition_point(arr, |elem| {
+        Ok(compare_elements(elem, target)? != std::cmp::Ordering::Greater)
+    })
+}
+
+/// 在升序数组中二分查找元素
+///
+/// # 功能
+/// 在已排序（升序）的数组中查找元素，时间复杂度 O(log n)。
+/// 数组未排序时结果不确定。
+///
+/// # 参数
+/// - `array`: Array - 升序数组
+/// - `value`: Any - 要查找的值
+///
+/// # 返回值
+/// Number - 元素的索引（存在重复时返回第一个），不存在时返回 -1
+///
+/// # 示例
+/// ```aether
+/// BSEARCH([1, 3, 5, 7], 5)     # 2
+/// BSEARCH([1, 3, 5, 7], 4)     # -1
+/// ```
+pub fn bsearch(args: &[Value]) -> Result<Value, RuntimeError> {
+    let (arr, target) = sorted_args(args)?;
+    let idx = lower_bound_index(arr, target)?;
+    let found =
+        idx < arr.len() && compare_elements(&arr[idx], target)? == std::cmp::Ordering::Equal;
+    Ok(Value::Number(if found { idx as f64 } else { -1.0 }))
+}
+
+/// 查找第一个不小于给定值的位置
+///
+/// # 功能
+/// 在升序数组中返回第一个 `>= value` 的元素索引，
+/// 所有元素都小于 value 时返回数组长度。
+///
+/// # 参数
+/// - `array`: Array - 升序数组
+/// - `value`: Any - 比较值
+///
+/// # 返回值
+/// Number - 插入位置索引
+///
+/// # 示例
+/// ```aether
+/// LOWER_BOUND([1, 2, 2, 3], 2)     # 1
+/// LOWER_BOUND([1, 2, 2, 3], 9)     # 4
+/// ```
+pub fn lower_bound(args: &[Value]) -> Result<Value, RuntimeError> {
+    let (arr, target) = sorted_args(args)?;
+    Ok(Value::Number(lower_bound_index(arr, target)? as f64))
+}
+
+/// 查找第一个大于给定值的位置
+///
+/// # 功能
+/// 在升序数组中返回第一个 `> value` 的元素索引，
+/// 所有元素都不大于 value 时返回数组长度。
+///
+/// # 参数
+/// - `array`: Array - 升序数组
+/// - `value`: Any - 比较值
+///
+/// # 返回值
+/// Number - 插入位置索引
+///
+/// # 示例
+/// ```aether
+/// UPPER_BOUND([1, 2, 2, 3], 2)     # 3
+/// UPPER_BOUND([1, 2, 2, 3], 0)     # 0
+/// ```
+pub fn upper_bound(args: &[Value]) -> Result<Value, RuntimeError> {
+    let (arr, target) = sorted_args(args)?;
+    Ok(Value::Number(upper_bound_index(arr, target)? as f64))
+}
+
+/// 将元素插入升序数组并保持有序
+///
+/// # 功能
+/// 在升序数组中找到插入位置（相等元素之后）并插入，返回新数组。
+/// 原数组不会被修改。
+///
+/// # 参数
+/// - `array`: Array - 升序数组
+/// - `value`: Any - 要插入的值
+///
+/// # 返回值
+/// Array - 插入后的新数组
+///
+/// # 示例
+/// ```aether
+/// INSERT_SORTED([1, 3, 5], 4)     # [1, 3, 4, 5]
+/// ```
+pub fn insert_sorted(args: &[Value]) -> Result<Value, RuntimeError> {
+    let (arr, value) = sorted_args(args)?;
+    let idx = upper_bound_index(arr, value)?;
+    let mut new_arr = arr.clone();
+    new_arr.insert(idx, value.clone());
+    Ok(Value::Array(new_arr))
+}
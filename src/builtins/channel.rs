@@ -0,0 +1,495 @@
+// src/builtins/channel.rs
+//! 通道
+//!
+//! 在 SPAWN 启动的任务之间传递数据，用于生产者/消费者式的流水线：
+//! - `CHANNEL(cap?)` 创建通道，返回通道句柄
+//! - `SEND(ch, value)` 发送，通道满时阻塞
+//! - `RECV(ch, timeout_ms?)` 接收，通道空时阻塞，超时返回 Null
+//! - `CHANNEL_CLOSE(ch)` 关闭通道，之后不能再发送，剩余数据仍可接收
+//!
+//! 通道属于创建它的引擎：引擎和它启动的任务（含嵌套任务）共享同一张通道表，
+//! 其他引擎看不到这些通道。句柄是普通字符串（如 `"chan:1"`），可以随闭包变量
+//! 一起复制到任务里。通道中传递的是值的副本，函数等无法复制的值不能发送。
+//!
+//! 通道关闭且数据取完后从表中移除；引擎被丢弃时关闭它的全部通道，唤醒仍在
+//! 等待的任务。缓冲中的数据计入引擎的 `max_memory_bytes`，等待时间受引擎的
+//! `max_duration_ms` 和 WITH_TIMEOUT 限制。
+
+use super::parallel::Portable;
+use crate::environment::Environment;
+use crate::evaluator::RuntimeError;
+use crate::value::Value;
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+/// 通道的状态
+#[derive(Default)]
+struct State {
+    /// 缓冲中的值及其估算字节数
+    queue: VecDeque<(Portable, usize)>,
+    closed: bool,
+}
+
+/// 有界（或无界）阻塞队列
+struct Channel {
+    capacity: Option<usize>,
+    state: Mutex<State>,
+    /// 有数据可读或通道关闭
+    readable: Condvar,
+    /// 有空位可写或通道关闭
+    writable: Condvar,
+}
+
+impl Channel {
+    fn lock(&self) -> Result<MutexGuard<'_, State>, RuntimeError> {
+        self.state.lock().map_err(|_| poisoned())
+    }
+}
+
+/// 一个引擎的通道表
+#[derive(Default)]
+pub(crate) struct ChannelTable {
+    channels: Mutex<HashMap<u64, Arc<Channel>>>,
+    /// 已分配的最大通道编号（编号不超过它但不在表中的通道已关闭并移除）
+    last_id: AtomicU64,
+    /// 所有通道缓冲中的值的估算字节数
+    buffered_bytes: AtomicUsize,
+}
+
+impl std::fmt::Debug for ChannelTable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChannelTable")
+            .field("buffered_bytes", &self.buffered_bytes())
+            .finish_non_exhaustive()
+    }
+}
+
+impl ChannelTable {
+    /// 缓冲中的值的估算字节数
+    pub fn buffered_bytes(&self) -> usize {
+        self.buffered_bytes.load(Ordering::SeqCst)
+    }
+
+    fn table(&self) -> Result<MutexGuard<'_, HashMap<u64, Arc<Channel>>>, RuntimeError> {
+        self.channels
+            .lock()
+            .map_err(|_| error("Channel table is poisoned".to_string()))
+    }
+
+    /// 解析通道句柄（如 "chan:1"），已关闭并移除的通道返回 None
+    fn lookup(&self, handle: &Value) -> Result<Option<Arc<Channel>>, RuntimeError> {
+        let text = match handle {
+            Value::String(s) => s,
+            other => {
+                return Err(RuntimeError::TypeErrorDetailed {
+                    expected: "String".to_string(),
+                    got: other.type_name().to_string(),
+                });
+            }
+        };
+        let id: u64 = text
+            .strip_prefix("chan:")
+            .and_then(|id| id.parse().ok())
+            .ok_or_else(|| error(format!("Invalid channel handle '{}'", text)))?;
+        if let Some(channel) = self.table()?.get(&id) {
+            return Ok(Some(Arc::clone(channel)));
+        }
+        if id == 0 || id > self.last_id.load(Ordering::SeqCst) {
+            return Err(error(format!("Channel '{}' does not exist", text)));
+        }
+        Ok(None)
+    }
+
+    /// 关闭且已取完的通道从表中移除
+    fn remove_if_drained(&self, handle: &Value, state: &State) {
+        if state.closed
+            && state.queue.is_empty()
+            && let Value::String(text) = handle
+            && let Some(id) = text.strip_prefix("chan:").and_then(|id| id.parse().ok())
+            && let Ok(mut table) = self.table()
+        {
+            table.remove(&id);
+        }
+    }
+
+    /// 关闭全部通道并清空表（拥有通道表的引擎被丢弃时调用）
+    fn shut_down(&self) {
+        let channels: Vec<_> = match self.channels.lock() {
+            Ok(mut table) => table.drain().map(|(_, channel)| channel).collect(),
+            Err(_) => return,
+        };
+        for channel in channels {
+            if let Ok(mut state) = channel.state.lock() {
+                state.closed = true;
+                state.queue.clear();
+            }
+            channel.readable.notify_all();
+            channel.writable.notify_all();
+        }
+        self.buffered_bytes.store(0, Ordering::SeqCst);
+    }
+}
+
+/// 引擎持有的通道表
+///
+/// 创建通道表的引擎是它的所有者，所有者被丢弃时关闭全部通道；
+/// 工作引擎和派生引擎只是共享这张表。
+pub(crate) struct Channels {
+    table: Arc<ChannelTable>,
+    owner: bool,
+}
+
+impl Channels {
+    /// 新的通道表，由调用方所有
+    pub fn new() -> Self {
+        Channels {
+            table: Arc::default(),
+            owner: true,
+        }
+    }
+
+    /// 共享另一个引擎的通道表
+    pub fn shared(table: Arc<ChannelTable>) -> Self {
+        Channels {
+            table,
+            owner: false,
+        }
+    }
+
+    /// 通道表（用于传给工作引擎）
+    pub fn table(&self) -> &Arc<ChannelTable> {
+        &self.table
+    }
+}
+
+impl Default for Channels {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for Channels {
+    fn drop(&mut self) {
+        if self.owner {
+            self.table.shut_down();
+        }
+    }
+}
+
+fn error(message: String) -> RuntimeError {
+    RuntimeError::CustomError(message)
+}
+
+fn poisoned() -> RuntimeError {
+    error("Channel is poisoned".to_string())
+}
+
+/// 检查参数个数
+fn check_arity(args: &[Value], min: usize, max: usize) -> Result<(), RuntimeError> {
+    if args.len() < min || args.len() > max {
+        return Err(RuntimeError::WrongArity {
+            expected: min,
+            got: args.len(),
+        });
+    }
+    Ok(())
+}
+
+/// 解析毫秒数参数
+fn millis(name: &str, value: &Value) -> Result<u64, RuntimeError> {
+    match value {
+        Value::Number(n) if n.fract() == 0.0 && *n >= 0.0 => Ok(*n as u64),
+        other => Err(RuntimeError::InvalidOperation(format!(
+            "{} expects a non-negative integer number of milliseconds, got {}",
+            name, other
+        ))),
+    }
+}
+
+/// 等待条件变量，最多等到 `deadline`；返回是否已到期
+fn wait<'a>(
+    condvar: &Condvar,
+    state: MutexGuard<'a, State>,
+    deadline: Option<Instant>,
+) -> Result<(MutexGuard<'a, State>, bool), RuntimeError> {
+    match deadline {
+        None => Ok((condvar.wait(state).map_err(|_| poisoned())?, false)),
+        Some(deadline) => {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Ok((state, true));
+            }
+            let (state, _) = condvar
+                .wait_timeout(state, remaining)
+                .map_err(|_| poisoned())?;
+            Ok((state, false))
+        }
+    }
+}
+
+/// 两个可选截止时间中较早的一个
+fn earliest(a: Option<Instant>, b: Option<Instant>) -> Option<Instant> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+impl ChannelTable {
+    /// 创建通道
+    ///
+    /// # 功能
+    /// 创建在任务之间传递数据的通道。
+    ///
+    /// # 参数
+    /// - `cap`: Number - 容量，满时 SEND 阻塞（可选，省略或 Null 表示不限容量）
+    ///
+    /// # 返回值
+    /// String - 通道句柄（如 `"chan:1"`）
+    ///
+    /// # 示例
+    /// ```aether
+    /// Set JOBS CHANNEL(10)
+    /// Set WORKER SPAWN(Lambda () -> RECV(JOBS, 1000))
+    /// SEND(JOBS, {"id": 1})
+    /// AWAIT_ALL([WORKER])
+    /// ```
+    pub fn create(&self, args: &[Value]) -> Result<Value, RuntimeError> {
+        check_arity(args, 0, 1)?;
+        let capacity = match args.first() {
+            None | Some(Value::Null) => None,
+            Some(Value::Number(n)) if n.fract() == 0.0 && *n >= 1.0 => Some(*n as usize),
+            Some(other) => {
+                return Err(RuntimeError::InvalidOperation(format!(
+                    "CHANNEL expects a positive integer capacity, got {}",
+                    other
+                )));
+            }
+        };
+        let channel = Arc::new(Channel {
+            capacity,
+            state: Mutex::new(State::default()),
+            readable: Condvar::new(),
+            writable: Condvar::new(),
+        });
+        let mut table = self.table()?;
+        let id = self.last_id.fetch_add(1, Ordering::SeqCst) + 1;
+        table.insert(id, channel);
+        Ok(Value::String(format!("chan:{}", id)))
+    }
+
+    /// 向通道发送值
+    ///
+    /// # 参数
+    /// - `ch`: String - 通道句柄
+    /// - `value`: Any - 要发送的值（函数、生成器等不能发送）
+    ///
+    /// `reserve` 收到发送后全部缓冲数据的估算字节数，超出内存限制时返回错误。
+    /// 通道满时最多等到 `deadline`（引擎的截止时间），到期返回 false。
+    ///
+    /// # 返回值
+    /// 成功返回 true；通道已关闭时报错
+    ///
+    /// # 示例
+    /// ```aether
+    /// SEND(CH, [1, 2, 3])
+    /// ```
+    pub fn send(
+        &self,
+        args: &[Value],
+        deadline: Option<Instant>,
+        reserve: impl Fn(usize) -> Result<(), RuntimeError>,
+    ) -> Result<Value, RuntimeError> {
+        check_arity(args, 2, 2)?;
+        let channel = self.lookup(&args[0])?;
+        let value = Portable::from_value(&args[1]).map_err(RuntimeError::InvalidOperation)?;
+        if value.contains_function() {
+            return Err(RuntimeError::InvalidOperation(
+                "Functions cannot be sent over a channel".to_string(),
+            ));
+        }
+        let Some(channel) = channel else {
+            return Err(error("Cannot send on a closed channel".to_string()));
+        };
+        let bytes = crate::runtime::memory::value_bytes(&args[1]);
+
+        let mut state = channel.lock()?;
+        while !state.closed && channel.capacity.is_some_and(|cap| state.queue.len() >= cap) {
+            let (next, expired) = wait(&channel.writable, state, deadline)?;
+            if expired {
+                return Ok(Value::Boolean(false));
+            }
+            state = next;
+        }
+        if state.closed {
+            return Err(error("Cannot send on a closed channel".to_string()));
+        }
+        reserve(self.buffered_bytes() + bytes)?;
+        self.buffered_bytes.fetch_add(bytes, Ordering::SeqCst);
+        state.queue.push_back((value, bytes));
+        channel.readable.notify_one();
+        Ok(Value::Boolean(true))
+    }
+
+    /// 从通道接收值
+    ///
+    /// # 参数
+    /// - `ch`: String - 通道句柄
+    /// - `timeout_ms`: Number - 最长等待毫秒数（可选，省略时等到通道关闭）
+    ///
+    /// 无论是否指定超时，最多等到 `deadline`（引擎的截止时间）。
+    ///
+    /// # 返回值
+    /// 收到的值；超时或通道已关闭且没有剩余数据时返回 Null
+    ///
+    /// # 示例
+    /// ```aether
+    /// Set ITEM RECV(CH, 500)
+    /// If (ITEM == Null) {
+    ///     PRINTLN("no data")
+    /// }
+    /// ```
+    pub fn recv(&self, args: &[Value], deadline: Option<Instant>) -> Result<Value, RuntimeError> {
+        check_arity(args, 1, 2)?;
+        let channel = self.lookup(&args[0])?;
+        let timeout = match args.get(1) {
+            None | Some(Value::Null) => None,
+            Some(value) => Some(Instant::now() + Duration::from_millis(millis("RECV", value)?)),
+        };
+        let Some(channel) = channel else {
+            return Ok(Value::Null);
+        };
+        let deadline = earliest(timeout, deadline);
+
+        let mut state = channel.lock()?;
+        loop {
+            if let Some((value, bytes)) = state.queue.pop_front() {
+                self.buffered_bytes.fetch_sub(bytes, Ordering::SeqCst);
+                channel.writable.notify_one();
+                self.remove_if_drained(&args[0], &state);
+                // 通道中只有数据，函数绑定的环境不会被用到
+                let env = Rc::new(RefCell::new(Environment::new()));
+                return Ok(value.into_value(&env));
+            }
+            if state.closed {
+                self.remove_if_drained(&args[0], &state);
+                return Ok(Value::Null);
+            }
+            let (next, expired) = wait(&channel.readable, state, deadline)?;
+            if expired {
+                return Ok(Value::Null);
+            }
+            state = next;
+        }
+    }
+
+    /// 关闭通道
+    ///
+    /// # 功能
+    /// 关闭后 SEND 报错，RECV 取完剩余数据后立即返回 Null，
+    /// 正在等待的发送方和接收方都会被唤醒。
+    ///
+    /// # 参数
+    /// - `ch`: String - 通道句柄
+    ///
+    /// # 返回值
+    /// Boolean - 通道此前是否处于打开状态
+    ///
+    /// # 示例
+    /// ```aether
+    /// CHANNEL_CLOSE(CH)
+    /// ```
+    pub fn close(&self, args: &[Value]) -> Result<Value, RuntimeError> {
+        check_arity(args, 1, 1)?;
+        let Some(channel) = self.lookup(&args[0])? else {
+            return Ok(Value::Boolean(false));
+        };
+        let mut state = channel.lock()?;
+        let was_open = !state.closed;
+        state.closed = true;
+        channel.readable.notify_all();
+        channel.writable.notify_all();
+        self.remove_if_drained(&args[0], &state);
+        Ok(Value::Boolean(was_open))
+    }
+}
+
+/// 占位实现：CHANNEL 创建的通道属于引擎，由求值器处理
+pub fn channel(_args: &[Value]) -> Result<Value, RuntimeError> {
+    Err(context_error("CHANNEL"))
+}
+
+/// 占位实现：SEND 由求值器处理
+pub fn send(_args: &[Value]) -> Result<Value, RuntimeError> {
+    Err(context_error("SEND"))
+}
+
+/// 占位实现：RECV 由求值器处理
+pub fn recv(_args: &[Value]) -> Result<Value, RuntimeError> {
+    Err(context_error("RECV"))
+}
+
+/// 占位实现：CHANNEL_CLOSE 由求值器处理
+pub fn channel_close(_args: &[Value]) -> Result<Value, RuntimeError> {
+    Err(context_error("CHANNEL_CLOSE"))
+}
+
+fn context_error(name: &str) -> RuntimeError {
+    RuntimeError::InvalidOperation(format!("{} requires an engine context", name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn handle(value: &str) -> Value {
+        Value::String(value.to_string())
+    }
+
+    #[test]
+    fn test_closed_channel_is_removed_once_drained() {
+        let channels = Channels::new();
+        let table = channels.table();
+        let ch = table.create(&[]).unwrap();
+        table
+            .send(&[ch.clone(), Value::Number(1.0)], None, |_| Ok(()))
+            .unwrap();
+        assert!(table.buffered_bytes() > 0);
+
+        table.close(std::slice::from_ref(&ch)).unwrap();
+        assert_eq!(table.table().unwrap().len(), 1);
+        assert_eq!(
+            table.recv(std::slice::from_ref(&ch), None).unwrap(),
+            Value::Number(1.0)
+        );
+        assert!(table.table().unwrap().is_empty());
+        assert_eq!(table.buffered_bytes(), 0);
+
+        // 已移除的通道仍按已关闭处理，从未创建的通道报错
+        assert_eq!(table.recv(&[ch], None).unwrap(), Value::Null);
+        assert!(table.recv(&[handle("chan:2")], None).is_err());
+    }
+
+    #[test]
+    fn test_dropping_owner_wakes_waiting_receivers() {
+        let channels = Channels::new();
+        let table = Arc::clone(channels.table());
+        let ch = table.create(&[]).unwrap();
+        let Value::String(ch) = ch else {
+            unreachable!()
+        };
+        let waiter = {
+            let table = Arc::clone(&table);
+            // Value 不能跨线程，只传回是否收到 Null
+            std::thread::spawn(move || matches!(table.recv(&[handle(&ch)], None), Ok(Value::Null)))
+        };
+        std::thread::sleep(Duration::from_millis(50));
+        drop(channels);
+        assert!(waiter.join().unwrap());
+        assert!(table.table().unwrap().is_empty());
+    }
+}
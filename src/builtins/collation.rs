@@ -0,0 +1,146 @@
+// src/builtins/collation.rs
+//! 按语言排序字符串（collation 特性）
+//!
+//! 字符串的 `<` / `>` 按码点比较，中文姓名、带重音的欧洲姓名按此排序后不符合习惯
+//! （如 "Émile" 排在 "Zoe" 之后，"张" 排在 "李" 之前）。这里使用 ICU4X 的 CLDR 排序规则：
+//! - `SORT_LOCALE(arr, locale?)` 按语言排序字符串数组
+//! - `COMPARE_LOCALE(a, b, locale?)` 按语言比较两个字符串，返回 -1 / 0 / 1
+//!
+//! 省略 `locale` 时使用 `SET_LOCALE` 设置的当前语言。中文（`zh`）默认按拼音排序，
+//! `zh-u-co-stroke` 按笔画排序。没有专门规则的语言使用通用的 Unicode 排序规则（root）。
+
+use super::i18n::{current_locale, locale_arg};
+use crate::evaluator::RuntimeError;
+use crate::value::Value;
+use icu_collator::{Collator, CollatorOptions};
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+thread_local! {
+    /// 已创建的排序器（语言 -> 排序器），创建排序器需要加载排序数据
+    static COLLATORS: RefCell<HashMap<String, Collator>> = RefCell::new(HashMap::new());
+}
+
+/// 用 `locale` 的排序器执行 `f`
+fn with_collator<T>(locale: &str, f: impl FnOnce(&Collator) -> T) -> Result<T, RuntimeError> {
+    COLLATORS.with(|collators| {
+        let mut collators = collators.borrow_mut();
+        if !collators.contains_key(locale) {
+            let parsed = icu_locid::Locale::try_from_bytes(locale.as_bytes()).map_err(|e| {
+                RuntimeError::InvalidOperation(format!("Invalid locale '{}': {}", locale, e))
+            })?;
+            let collator =
+                Collator::try_new(&(&parsed).into(), CollatorOptions::new()).map_err(|e| {
+                    RuntimeError::InvalidOperation(format!(
+                        "No collation data for locale '{}': {}",
+                        locale, e
+                    ))
+                })?;
+            collators.insert(locale.to_string(), collator);
+        }
+        Ok(f(&collators[locale]))
+    })
+}
+
+/// 读取可选的语言参数（默认当前语言）
+fn optional_locale(arg: Option<&Value>) -> Result<String, RuntimeError> {
+    match arg {
+        Some(locale) => locale_arg(locale),
+        None => Ok(current_locale()),
+    }
+}
+
+/// 按语言排序字符串数组
+///
+/// # 参数
+/// - `arr`: Array - 字符串数组
+/// - `locale`: String - 可选，语言（默认当前语言）
+///
+/// # 返回值
+/// Array - 排序后的新数组（排序稳定，比较相等的元素保持原顺序）
+///
+/// # 示例
+/// ```aether
+/// SORT_LOCALE(["张伟", "李娜", "王芳"], "zh")       # ["李娜", "王芳", "张伟"]
+/// SORT_LOCALE(["Zoe", "Émile", "adam"], "fr")      # ["adam", "Émile", "Zoe"]
+/// ```
+pub fn sort_locale(args: &[Value]) -> Result<Value, RuntimeError> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(RuntimeError::WrongArity {
+            expected: 1,
+            got: args.len(),
+        });
+    }
+
+    let items = match &args[0] {
+        Value::Array(items) => items,
+        other => {
+            return Err(RuntimeError::TypeErrorDetailed {
+                expected: "Array".to_string(),
+                got: other.type_name().to_string(),
+            });
+        }
+    };
+    let mut strings = Vec::with_capacity(items.len());
+    for item in items {
+        match item {
+            Value::String(s) => strings.push(s.clone()),
+            other => {
+                return Err(RuntimeError::InvalidOperation(format!(
+                    "SORT_LOCALE expects an array of strings, found {}",
+                    other.type_name()
+                )));
+            }
+        }
+    }
+
+    let locale = optional_locale(args.get(1))?;
+    with_collator(&locale, |collator| {
+        strings.sort_by(|a, b| collator.compare(a, b));
+    })?;
+    Ok(Value::Array(
+        strings.into_iter().map(Value::String).collect(),
+    ))
+}
+
+/// 按语言比较两个字符串
+///
+/// # 参数
+/// - `a`: String - 第一个字符串
+/// - `b`: String - 第二个字符串
+/// - `locale`: String - 可选，语言（默认当前语言）
+///
+/// # 返回值
+/// Number - a 排在 b 之前为 -1，相等为 0，之后为 1
+///
+/// # 示例
+/// ```aether
+/// COMPARE_LOCALE("ä", "z", "de")    # -1
+/// COMPARE_LOCALE("ä", "z", "sv")    # 1（瑞典语中 ä 排在 z 之后）
+/// ```
+pub fn compare_locale(args: &[Value]) -> Result<Value, RuntimeError> {
+    if args.len() < 2 || args.len() > 3 {
+        return Err(RuntimeError::WrongArity {
+            expected: 2,
+            got: args.len(),
+        });
+    }
+
+    let (a, b) = match (&args[0], &args[1]) {
+        (Value::String(a), Value::String(b)) => (a, b),
+        (Value::String(_), other) | (other, _) => {
+            return Err(RuntimeError::TypeErrorDetailed {
+                expected: "String".to_string(),
+                got: other.type_name().to_string(),
+            });
+        }
+    };
+    let locale = optional_locale(args.get(2))?;
+    let ordering = with_collator(&locale, |collator| collator.compare(a, b))?;
+    Ok(Value::Number(match ordering {
+        Ordering::Less => -1.0,
+        Ordering::Equal => 0.0,
+        Ordering::Greater => 1.0,
+    }))
+}
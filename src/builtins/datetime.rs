@@ -0,0 +1,408 @@
+// src/builtins/datetime.rs
+//! 日期时间内置函数
+//!
+//! `Value::DateTime` 表示带固定 UTC 偏移的时间点，比较和相等按时间点进行，
+//! 与显示所用的偏移无关。需要日期时间的参数也接受字符串（按 `DATE_PARSE` 的默认格式
+//! 解析）和 Unix 时间戳（秒），因此 `DATE_FORMAT(NOW(), "%Y-%m-%d")` 可以直接使用。
+//! 时区以固定偏移表示（`"+08:00"`、`"UTC"` 或小时数），不包含夏令时规则。
+
+use crate::evaluator::RuntimeError;
+use crate::value::Value;
+use chrono::{
+    DateTime, Datelike, Duration, FixedOffset, Months, NaiveDate, NaiveDateTime, TimeZone, Utc,
+};
+use std::fmt::Write;
+
+/// 未指定格式时 DATE_PARSE 依次尝试的不带偏移的格式（按 UTC 解释）
+const NAIVE_FORMATS: &[&str] = &[
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%dT%H:%M",
+];
+
+/// 时间单位
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Unit {
+    Seconds,
+    Minutes,
+    Hours,
+    Days,
+    Weeks,
+    Months,
+    Years,
+}
+
+impl Unit {
+    fn parse(value: &Value) -> Result<Unit, RuntimeError> {
+        let Value::String(name) = value else {
+            return Err(RuntimeError::TypeErrorDetailed {
+                expected: "String (time unit)".to_string(),
+                got: value.type_name().to_string(),
+            });
+        };
+        let name = name.to_lowercase();
+        Ok(match name.trim_end_matches('s') {
+            "second" => Unit::Seconds,
+            "minute" => Unit::Minutes,
+            "hour" => Unit::Hours,
+            "day" => Unit::Days,
+            "week" => Unit::Weeks,
+            "month" => Unit::Months,
+            "year" => Unit::Years,
+            _ => {
+                return Err(RuntimeError::InvalidOperation(format!(
+                    "Unknown time unit '{}', expected seconds, minutes, hours, days, weeks, months or years",
+                    name
+                )));
+            }
+        })
+    }
+
+    /// 固定长度单位的秒数（月、年的长度取决于日历，返回 None）
+    fn seconds(self) -> Option<i64> {
+        match self {
+            Unit::Seconds => Some(1),
+            Unit::Minutes => Some(60),
+            Unit::Hours => Some(3_600),
+            Unit::Days => Some(86_400),
+            Unit::Weeks => Some(604_800),
+            Unit::Months | Unit::Years => None,
+        }
+    }
+}
+
+fn unit_arg(args: &[Value], index: usize) -> Result<Unit, RuntimeError> {
+    args.get(index).map_or(Ok(Unit::Days), Unit::parse)
+}
+
+fn check_arity(args: &[Value], min: usize, max: usize) -> Result<(), RuntimeError> {
+    if args.len() < min || args.len() > max {
+        return Err(RuntimeError::WrongArity {
+            expected: min,
+            got: args.len(),
+        });
+    }
+    Ok(())
+}
+
+fn out_of_range() -> RuntimeError {
+    RuntimeError::InvalidOperation("Date is out of the supported range".to_string())
+}
+
+/// 将 Unix 时间戳（秒，可带小数）转换为 UTC 时间
+fn from_timestamp(seconds: f64) -> Result<DateTime<FixedOffset>, RuntimeError> {
+    if !seconds.is_finite() {
+        return Err(out_of_range());
+    }
+    DateTime::from_timestamp_millis((seconds * 1000.0).round() as i64)
+        .map(|dt| dt.fixed_offset())
+        .ok_or_else(out_of_range)
+}
+
+/// 按格式解析日期时间文本；未指定格式时接受 RFC 3339、`YYYY-MM-DD HH:MM[:SS]` 和 `YYYY-MM-DD`
+fn parse_text(text: &str, format: Option<&str>) -> Result<DateTime<FixedOffset>, RuntimeError> {
+    let text = text.trim();
+    let parsed = match format {
+        Some(format) => DateTime::parse_from_str(text, format).ok().or_else(|| {
+            NaiveDateTime::parse_from_str(text, format)
+                .ok()
+                .or_else(|| {
+                    NaiveDate::parse_from_str(text, format)
+                        .ok()
+                        .and_then(|date| date.and_hms_opt(0, 0, 0))
+                })
+                .map(|naive| Utc.from_utc_datetime(&naive).fixed_offset())
+        }),
+        None => DateTime::parse_from_rfc3339(text).ok().or_else(|| {
+            NAIVE_FORMATS
+                .iter()
+                .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+                .or_else(|| {
+                    NaiveDate::parse_from_str(text, "%Y-%m-%d")
+                        .ok()
+                        .and_then(|date| date.and_hms_opt(0, 0, 0))
+                })
+                .map(|naive| Utc.from_utc_datetime(&naive).fixed_offset())
+        }),
+    };
+    parsed.ok_or_else(|| {
+        RuntimeError::InvalidOperation(match format {
+            Some(format) => format!("Cannot parse '{}' as a date with format '{}'", text, format),
+            None => format!("Cannot parse '{}' as a date", text),
+        })
+    })
+}
+
+/// 将 DateTime、日期字符串或 Unix 时间戳转换为日期时间
+pub(crate) fn to_datetime(value: &Value) -> Result<DateTime<FixedOffset>, RuntimeError> {
+    match value {
+        Value::DateTime(dt) => Ok(*dt),
+        Value::String(text) => parse_text(text, None),
+        Value::Number(_) | Value::Int(_) | Value::Fraction(_) => {
+            from_timestamp(value.to_number().unwrap_or(f64::NAN))
+        }
+        other => Err(RuntimeError::TypeErrorDetailed {
+            expected: "DateTime, String or Number (Unix timestamp)".to_string(),
+            got: other.type_name().to_string(),
+        }),
+    }
+}
+
+/// 解析时区偏移：`"UTC"` / `"Z"`、`"+08:00"` / `"+0800"` / `"-05"`，或小时数（可带小数）
+fn parse_offset(value: &Value) -> Result<FixedOffset, RuntimeError> {
+    let invalid = || {
+        RuntimeError::InvalidOperation(format!(
+            "Invalid time zone offset '{}', expected \"UTC\", \"+08:00\" or a number of hours",
+            value
+        ))
+    };
+    let seconds = match value {
+        Value::String(text) => {
+            let text = text.trim();
+            if text.eq_ignore_ascii_case("utc") || text.eq_ignore_ascii_case("z") {
+                0
+            } else {
+                let (sign, rest) = match text.as_bytes().first() {
+                    Some(b'+') => (1, &text[1..]),
+                    Some(b'-') => (-1, &text[1..]),
+                    _ => return Err(invalid()),
+                };
+                let digits = rest.replace(':', "");
+                if !digits.bytes().all(|b| b.is_ascii_digit()) {
+                    return Err(invalid());
+                }
+                let (hours, minutes) = match digits.len() {
+                    1 | 2 => (digits.as_str(), "0"),
+                    4 => digits.split_at(2),
+                    _ => return Err(invalid()),
+                };
+                let hours: i32 = hours.parse().map_err(|_| invalid())?;
+                let minutes: i32 = minutes.parse().map_err(|_| invalid())?;
+                if minutes >= 60 {
+                    return Err(invalid());
+                }
+                sign * (hours * 3_600 + minutes * 60)
+            }
+        }
+        Value::Number(hours) if hours.is_finite() => (hours * 3_600.0).round() as i32,
+        _ => return Err(invalid()),
+    };
+    FixedOffset::east_opt(seconds).ok_or_else(invalid)
+}
+
+/// 加上若干个月（月末日期按目标月份的最后一天截断）
+fn add_months(
+    dt: DateTime<FixedOffset>,
+    months: i64,
+) -> Result<DateTime<FixedOffset>, RuntimeError> {
+    let count = Months::new(u32::try_from(months.unsigned_abs()).map_err(|_| out_of_range())?);
+    if months >= 0 {
+        dt.checked_add_months(count)
+    } else {
+        dt.checked_sub_months(count)
+    }
+    .ok_or_else(out_of_range)
+}
+
+/// 自公元 0 年 1 月起的月数，用于计算相差的月份
+fn month_index(dt: &DateTime<FixedOffset>) -> i64 {
+    dt.year() as i64 * 12 + dt.month0() as i64
+}
+
+/// DATE_PARSE - 解析日期时间
+///
+/// # 参数
+/// - `value`: 日期字符串，或 Unix 时间戳（秒）
+/// - `format`: （可选）strftime 格式，如 `"%d/%m/%Y"`；格式中没有 `%z` 时按 UTC 解释
+///
+/// # 返回值
+/// DateTime
+///
+/// # 示例
+/// ```aether
+/// Set D DATE_PARSE("2024-01-31T09:30:00+08:00")
+/// Set E DATE_PARSE("31/01/2024", "%d/%m/%Y")   # 2024-01-31T00:00:00+00:00
+/// Set T DATE_PARSE(NOW())                       # 当前时间（UTC）
+/// ```
+pub fn date_parse(args: &[Value]) -> Result<Value, RuntimeError> {
+    check_arity(args, 1, 2)?;
+    match (&args[0], args.get(1)) {
+        (Value::String(text), Some(Value::String(format))) => {
+            parse_text(text, Some(format)).map(Value::DateTime)
+        }
+        (_, Some(other)) => Err(RuntimeError::TypeErrorDetailed {
+            expected: "String, String".to_string(),
+            got: format!("{}, {}", args[0].type_name(), other.type_name()),
+        }),
+        (value, None) => to_datetime(value).map(Value::DateTime),
+    }
+}
+
+/// DATE_FORMAT - 格式化日期时间
+///
+/// # 参数
+/// - `date`: DateTime（或可转换为日期时间的字符串、时间戳）
+/// - `format`: （可选）strftime 格式，默认 RFC 3339
+///
+/// # 返回值
+/// String
+///
+/// # 示例
+/// ```aether
+/// Set D DATE_PARSE("2024-01-31 09:30:00")
+/// DATE_FORMAT(D, "%Y年%m月%d日")   # "2024年01月31日"
+/// DATE_FORMAT(D)                   # "2024-01-31T09:30:00+00:00"
+/// ```
+pub fn date_format(args: &[Value]) -> Result<Value, RuntimeError> {
+    check_arity(args, 1, 2)?;
+    let dt = to_datetime(&args[0])?;
+    let format = match args.get(1) {
+        None => return Ok(Value::String(dt.to_rfc3339())),
+        Some(Value::String(format)) => format,
+        Some(other) => {
+            return Err(RuntimeError::TypeErrorDetailed {
+                expected: "String (format)".to_string(),
+                got: other.type_name().to_string(),
+            });
+        }
+    };
+    let mut out = String::new();
+    write!(out, "{}", dt.format(format))
+        .map_err(|_| RuntimeError::InvalidOperation(format!("Invalid date format '{}'", format)))?;
+    Ok(Value::String(out))
+}
+
+/// DATE_ADD - 日期时间加减
+///
+/// # 参数
+/// - `date`: DateTime
+/// - `amount`: Number - 数量，负数表示减去；月、年必须是整数
+/// - `unit`: （可选）单位：seconds、minutes、hours、days（默认）、weeks、months、years
+///
+/// # 返回值
+/// DateTime（偏移与 `date` 相同）
+///
+/// # 示例
+/// ```aether
+/// Set D DATE_PARSE("2024-01-31")
+/// DATE_ADD(D, 1, "months")    # 2024-02-29T00:00:00+00:00（月末截断）
+/// DATE_ADD(D, -90, "minutes")
+/// ```
+pub fn date_add(args: &[Value]) -> Result<Value, RuntimeError> {
+    check_arity(args, 2, 3)?;
+    let dt = to_datetime(&args[0])?;
+    let amount = match &args[1] {
+        Value::Number(n) if n.is_finite() => *n,
+        other => {
+            return Err(RuntimeError::TypeErrorDetailed {
+                expected: "Number (amount)".to_string(),
+                got: other.type_name().to_string(),
+            });
+        }
+    };
+    let unit = unit_arg(args, 2)?;
+    let result = match unit.seconds() {
+        Some(seconds) => {
+            let millis = amount * seconds as f64 * 1000.0;
+            if millis.abs() >= i64::MAX as f64 {
+                return Err(out_of_range());
+            }
+            dt.checked_add_signed(Duration::milliseconds(millis.round() as i64))
+                .ok_or_else(out_of_range)?
+        }
+        None => {
+            if amount.fract() != 0.0 {
+                return Err(RuntimeError::InvalidOperation(format!(
+                    "Cannot add a fractional number of months or years: {}",
+                    amount
+                )));
+            }
+            let months = if unit == Unit::Years {
+                amount * 12.0
+            } else {
+                amount
+            };
+            if months.abs() > u32::MAX as f64 {
+                return Err(out_of_range());
+            }
+            add_months(dt, months as i64)?
+        }
+    };
+    Ok(Value::DateTime(result))
+}
+
+/// DATE_DIFF - 两个日期时间之间的完整单位数
+///
+/// # 参数
+/// - `start`: DateTime
+/// - `end`: DateTime
+/// - `unit`: （可选）单位，默认 days；月、年按日历计算
+///
+/// # 返回值
+/// Integer - `end - start` 的完整单位数（向零截断，`end` 早于 `start` 时为负数）
+///
+/// # 示例
+/// ```aether
+/// DATE_DIFF(DATE_PARSE("2024-01-01"), DATE_PARSE("2024-03-15"))            # 74
+/// DATE_DIFF(DATE_PARSE("2024-01-31"), DATE_PARSE("2024-02-29"), "months")  # 1
+/// ```
+pub fn date_diff(args: &[Value]) -> Result<Value, RuntimeError> {
+    check_arity(args, 2, 3)?;
+    let start = to_datetime(&args[0])?;
+    let end = to_datetime(&args[1])?;
+    let unit = unit_arg(args, 2)?;
+    let count = match unit.seconds() {
+        Some(seconds) => (end - start).num_seconds() / seconds,
+        None => {
+            // 在 start 的偏移下按日历比较年月
+            let end_local = end.with_timezone(start.offset());
+            let mut months = month_index(&end_local) - month_index(&start);
+            if months > 0 && add_months(start, months)? > end {
+                months -= 1;
+            } else if months < 0 && add_months(start, months)? < end {
+                months += 1;
+            }
+            if unit == Unit::Years {
+                months / 12
+            } else {
+                months
+            }
+        }
+    };
+    Ok(Value::Int(count))
+}
+
+/// DATE_TO_TZ - 转换时区
+///
+/// # 参数
+/// - `date`: DateTime
+/// - `offset`: 目标偏移：`"UTC"`、`"+08:00"`、`"-0500"` 或小时数（如 `5.5`）
+///
+/// # 返回值
+/// DateTime - 同一时间点，以目标偏移表示
+///
+/// # 示例
+/// ```aether
+/// Set D DATE_PARSE("2024-01-31T01:00:00Z")
+/// DATE_TO_TZ(D, "+08:00")   # 2024-01-31T09:00:00+08:00
+/// DATE_TO_TZ(D, -5)         # 2024-01-30T20:00:00-05:00
+/// ```
+pub fn date_to_tz(args: &[Value]) -> Result<Value, RuntimeError> {
+    check_arity(args, 2, 2)?;
+    let dt = to_datetime(&args[0])?;
+    let offset = parse_offset(&args[1])?;
+    Ok(Value::DateTime(dt.with_timezone(&offset)))
+}
+
+/// DATE_TIMESTAMP - 日期时间对应的 Unix 时间戳（秒，毫秒部分为小数）
+///
+/// # 示例
+/// ```aether
+/// DATE_TIMESTAMP(DATE_PARSE("1970-01-02"))   # 86400
+/// ```
+pub fn date_timestamp(args: &[Value]) -> Result<Value, RuntimeError> {
+    check_arity(args, 1, 1)?;
+    let dt = to_datetime(&args[0])?;
+    Ok(Value::Number(dt.timestamp_millis() as f64 / 1000.0))
+}
@@ -73,8 +73,9 @@ pub fn values(args: &[Value]) -> Result<Value, RuntimeError> {
             let vals: Vec<Value> = dict.values().cloned().collect();
             Ok(Value::Array(vals))
         }
+        Value::Set(set) => Ok(Value::Array(crate::value::set_elements(set))),
         _ => Err(RuntimeError::TypeErrorDetailed {
-            expected: "Dict".to_string(),
+            expected: "Dict or Set".to_string(),
             got: format!("{:?}", args[0]),
         }),
     }
@@ -110,8 +111,9 @@ pub fn has(args: &[Value]) -> Result<Value, RuntimeError> {
 
     match (&args[0], &args[1]) {
         (Value::Dict(dict), Value::String(key)) => Ok(Value::Boolean(dict.contains_key(key))),
+        (Value::Set(set), item) => Ok(Value::Boolean(super::set::set_contains(set, item))),
         _ => Err(RuntimeError::TypeErrorDetailed {
-            expected: "Dict, String".to_string(),
+            expected: "Dict, String or Set, Any".to_string(),
             got: format!("{:?}, {:?}", args[0], args[1]),
         }),
     }
@@ -0,0 +1,89 @@
+// src/builtins/display.rs
+//! 富输出内置函数
+//!
+//! `IMAGE` 构造图片值，`DISPLAY` 把值交给宿主渲染（Jupyter 内核中显示为 display_data，
+//! 表格和字典数组渲染为 HTML 表格）。没有宿主捕获输出时 `DISPLAY` 打印值的文本形式。
+
+use super::io;
+use crate::evaluator::RuntimeError;
+use crate::value::Value;
+use std::collections::HashMap;
+
+/// 图片值的类型标记（`{"type": "image", "mime": ..., "data": ...}`）
+const IMAGE_TYPE: &str = "image";
+
+/// IMAGE - 构造图片值
+///
+/// 用法: IMAGE(data, mime?) -> {"type": "image", "mime": mime, "data": data}
+/// - `data`: 图片内容；SVG 为原始文本，PNG/JPEG 等二进制格式为 base64
+/// - `mime`: MIME 类型，默认 `image/png`
+pub fn image(args: &[Value]) -> Result<Value, RuntimeError> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(RuntimeError::WrongArity {
+            expected: 1,
+            got: args.len(),
+        });
+    }
+    let Value::String(data) = &args[0] else {
+        return Err(RuntimeError::TypeErrorDetailed {
+            expected: "String".to_string(),
+            got: args[0].type_name().to_string(),
+        });
+    };
+    let mime = match args.get(1) {
+        None => "image/png".to_string(),
+        Some(Value::String(mime)) if mime.starts_with("image/") => mime.clone(),
+        Some(Value::String(mime)) => {
+            return Err(RuntimeError::CustomError(format!(
+                "IMAGE: unsupported MIME type '{}', expected image/*",
+                mime
+            )));
+        }
+        Some(other) => {
+            return Err(RuntimeError::TypeErrorDetailed {
+                expected: "String".to_string(),
+                got: other.type_name().to_string(),
+            });
+        }
+    };
+    Ok(Value::Dict(HashMap::from([
+        ("type".to_string(), Value::String(IMAGE_TYPE.to_string())),
+        ("mime".to_string(), Value::String(mime)),
+        ("data".to_string(), Value::String(data.clone())),
+    ])))
+}
+
+/// DISPLAY - 把值交给宿主渲染
+///
+/// 用法: DISPLAY(value) -> Null
+pub fn display(args: &[Value]) -> Result<Value, RuntimeError> {
+    if args.len() != 1 {
+        return Err(RuntimeError::WrongArity {
+            expected: 1,
+            got: args.len(),
+        });
+    }
+    if !io::push_display(&args[0]) {
+        let text = match as_image(&args[0]) {
+            Some((mime, data)) => format!("<{} image, {} bytes>\n", mime, data.len()),
+            None => format!("{}\n", args[0]),
+        };
+        io::write_stdout(&text);
+    }
+    Ok(Value::Null)
+}
+
+/// 识别 `IMAGE` 构造的图片值，返回（MIME 类型，数据）
+pub(crate) fn as_image(value: &Value) -> Option<(&str, &str)> {
+    let Value::Dict(dict) = value else {
+        return None;
+    };
+    match (dict.get("type"), dict.get("mime"), dict.get("data")) {
+        (Some(Value::String(kind)), Some(Value::String(mime)), Some(Value::String(data)))
+            if kind == IMAGE_TYPE && dict.len() == 3 =>
+        {
+            Some((mime, data))
+        }
+        _ => None,
+    }
+}
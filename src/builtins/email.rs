@@ -0,0 +1,565 @@
+// src/builtins/email.rs
+//! 邮件发送内置函数（SMTP）
+//!
+//! `SEND_EMAIL` 需要网络权限，并且只能连接宿主程序通过
+//! [`crate::sandbox::set_smtp_allowlist`] 显式允许的服务器。
+//! 启用 `async` 特性时支持 STARTTLS 和 SMTPS（rustls），否则只能使用明文 SMTP
+//! （适合本机或内网中继）。
+
+use crate::evaluator::RuntimeError;
+use crate::sandbox::is_smtp_server_allowed;
+use crate::value::Value;
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+fn error(message: impl Into<String>) -> RuntimeError {
+    RuntimeError::CustomError(format!("SEND_EMAIL: {}", message.into()))
+}
+
+/// 连接加密方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Security {
+    /// 明文 SMTP
+    None,
+    /// 明文连接后升级（STARTTLS）
+    StartTls,
+    /// 连接即 TLS（SMTPS，通常为 465 端口）
+    Tls,
+}
+
+/// 附件
+struct Attachment {
+    filename: String,
+    mime: String,
+    content: Vec<u8>,
+}
+
+/// 解析后的邮件
+struct Email {
+    from: String,
+    to: Vec<String>,
+    cc: Vec<String>,
+    bcc: Vec<String>,
+    subject: String,
+    body: String,
+    html: bool,
+    attachments: Vec<Attachment>,
+}
+
+/// SEND_EMAIL - 通过 SMTP 发送邮件
+///
+/// 用法: SEND_EMAIL(message) -> Dict
+///
+/// `message` 字段：
+/// - `server`："host:port"（必须在宿主设置的白名单中）
+/// - `from`、`to`（字符串或数组）、`cc`、`bcc`、`subject`、`body`
+/// - `html`：正文是否为 HTML（默认 False）
+/// - `attachments`：`[{"filename", "content", "mime"?}]`，内容为字符串
+/// - `username` / `password`：AUTH PLAIN 认证
+/// - `security`："starttls"（默认）、"tls"（465 端口默认）或 "none"
+/// - `timeout`：秒，默认 30
+///
+/// 返回 `{"accepted": [收件人], "message_id": ...}`
+///
+/// # 示例
+/// ```aether
+/// SEND_EMAIL({
+///     "server": "smtp.example.com:587",
+///     "from": "payroll@example.com",
+///     "to": ["hr@example.com"],
+///     "subject": "2024-05 薪资报告",
+///     "body": REPORT,
+///     "html": True,
+///     "username": "payroll", "password": SMTP_PASSWORD
+/// })
+/// ```
+pub fn send_email(args: &[Value]) -> Result<Value, RuntimeError> {
+    if args.len() != 1 {
+        return Err(RuntimeError::WrongArity {
+            expected: 1,
+            got: args.len(),
+        });
+    }
+    let Value::Dict(message) = &args[0] else {
+        return Err(RuntimeError::TypeErrorDetailed {
+            expected: "Dict (message)".to_string(),
+            got: args[0].type_name().to_string(),
+        });
+    };
+
+    let server = required_string(message, "server")?;
+    let (host, port) = parse_server(&server)?;
+    if !is_smtp_server_allowed(&host, port) {
+        return Err(error(format!(
+            "SMTP server '{}:{}' is not in the allowlist",
+            host, port
+        )));
+    }
+    let security = match optional_string(message, "security")?.as_deref() {
+        None => {
+            if port == 465 {
+                Security::Tls
+            } else {
+                Security::StartTls
+            }
+        }
+        Some("none") => Security::None,
+        Some("starttls") => Security::StartTls,
+        Some("tls") | Some("ssl") => Security::Tls,
+        Some(other) => {
+            return Err(error(format!(
+                "unknown security '{}', expected \"starttls\", \"tls\" or \"none\"",
+                other
+            )));
+        }
+    };
+    let timeout = match message.get("timeout") {
+        None | Some(Value::Null) => Duration::from_secs(30),
+        Some(Value::Number(secs)) if *secs > 0.0 => Duration::from_secs_f64(*secs),
+        Some(_) => return Err(error("'timeout' must be a positive Number")),
+    };
+    let credentials = match (
+        optional_string(message, "username")?,
+        optional_string(message, "password")?,
+    ) {
+        (Some(user), Some(pass)) => Some((user, pass)),
+        (None, None) => None,
+        _ => return Err(error("'username' and 'password' must be given together")),
+    };
+
+    let email = Email {
+        from: required_string(message, "from")?,
+        to: addresses(message, "to")?,
+        cc: addresses(message, "cc")?,
+        bcc: addresses(message, "bcc")?,
+        subject: optional_string(message, "subject")?.unwrap_or_default(),
+        body: optional_string(message, "body")?.unwrap_or_default(),
+        html: message.get("html").is_some_and(Value::is_truthy),
+        attachments: attachments(message)?,
+    };
+    if email.to.is_empty() {
+        return Err(error("'to' must contain at least one address"));
+    }
+    for address in [&email.from]
+        .into_iter()
+        .chain(&email.to)
+        .chain(&email.cc)
+        .chain(&email.bcc)
+    {
+        if !address.contains('@') || address.contains(['\r', '\n', '<', '>']) {
+            return Err(error(format!("invalid address '{}'", address)));
+        }
+    }
+
+    let message_id = format!(
+        "<{}.{}@{}>",
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0),
+        std::process::id(),
+        email.from.rsplit('@').next().unwrap_or("localhost")
+    );
+    let data = email.to_mime(&message_id);
+
+    let mut session = Session::connect(&host, port, security, timeout)?;
+    session.send(&email, &data, credentials.as_ref())?;
+
+    let accepted = email
+        .to
+        .iter()
+        .chain(&email.cc)
+        .chain(&email.bcc)
+        .map(|a| Value::String(a.clone()))
+        .collect();
+    let mut result = HashMap::new();
+    result.insert("accepted".to_string(), Value::Array(accepted));
+    result.insert("message_id".to_string(), Value::String(message_id));
+    Ok(Value::Dict(result))
+}
+
+fn optional_string(
+    message: &HashMap<String, Value>,
+    key: &str,
+) -> Result<Option<String>, RuntimeError> {
+    match message.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(s)) => Ok(Some(s.clone())),
+        Some(other) => Err(error(format!(
+            "'{}' must be a String, got {}",
+            key,
+            other.type_name()
+        ))),
+    }
+}
+
+fn required_string(message: &HashMap<String, Value>, key: &str) -> Result<String, RuntimeError> {
+    optional_string(message, key)?.ok_or_else(|| error(format!("missing '{}'", key)))
+}
+
+fn addresses(message: &HashMap<String, Value>, key: &str) -> Result<Vec<String>, RuntimeError> {
+    match message.get(key) {
+        None | Some(Value::Null) => Ok(Vec::new()),
+        Some(Value::String(s)) => Ok(vec![s.trim().to_string()]),
+        Some(Value::Array(items)) => items
+            .iter()
+            .map(|item| match item {
+                Value::String(s) => Ok(s.trim().to_string()),
+                other => Err(error(format!(
+                    "'{}' must contain Strings, got {}",
+                    key,
+                    other.type_name()
+                ))),
+            })
+            .collect(),
+        Some(other) => Err(error(format!(
+            "'{}' must be a String or Array, got {}",
+            key,
+            other.type_name()
+        ))),
+    }
+}
+
+fn attachments(message: &HashMap<String, Value>) -> Result<Vec<Attachment>, RuntimeError> {
+    let items = match message.get("attachments") {
+        None | Some(Value::Null) => return Ok(Vec::new()),
+        Some(Value::Array(items)) => items,
+        Some(_) => return Err(error("'attachments' must be an Array of Dicts")),
+    };
+    items
+        .iter()
+        .map(|item| {
+            let Value::Dict(item) = item else {
+                return Err(error("'attachments' must be an Array of Dicts"));
+            };
+            let filename = required_string(item, "filename")?;
+            if filename.contains(['\r', '\n', '"']) {
+                return Err(error(format!("invalid attachment filename '{}'", filename)));
+            }
+            let mime =
+                optional_string(item, "mime")?.unwrap_or_else(|| guess_mime(&filename).to_string());
+            let content = match item.get("content") {
+                Some(Value::String(s)) => s.as_bytes().to_vec(),
+                Some(Value::Null) | None => Vec::new(),
+                Some(other) => other.to_string().into_bytes(),
+            };
+            Ok(Attachment {
+                filename,
+                mime,
+                content,
+            })
+        })
+        .collect()
+}
+
+fn guess_mime(filename: &str) -> &'static str {
+    let extension = filename.rsplit('.').next().unwrap_or("").to_lowercase();
+    match extension.as_str() {
+        "html" | "htm" => "text/html",
+        "md" => "text/markdown",
+        "csv" => "text/csv",
+        "json" => "application/json",
+        "svg" => "image/svg+xml",
+        "pdf" => "application/pdf",
+        "txt" => "text/plain",
+        _ => "application/octet-stream",
+    }
+}
+
+fn parse_server(server: &str) -> Result<(String, u16), RuntimeError> {
+    match server.rsplit_once(':') {
+        Some((host, port)) if !host.is_empty() => port
+            .parse()
+            .map(|port| (host.to_string(), port))
+            .map_err(|_| error(format!("invalid port in server '{}'", server))),
+        _ => Err(error(format!(
+            "server must be \"host:port\", got '{}'",
+            server
+        ))),
+    }
+}
+
+/// Base64 编码（标准字母表，带填充）
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let n = (chunk[0] as u32) << 16
+            | (*chunk.get(1).unwrap_or(&0) as u32) << 8
+            | *chunk.get(2).unwrap_or(&0) as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i)) as usize & 63] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// Base64 编码并按 76 列折行（MIME 正文）
+fn base64_lines(data: &[u8]) -> String {
+    let encoded = base64(data);
+    let mut out = String::with_capacity(encoded.len() + encoded.len() / 38);
+    for (i, c) in encoded.chars().enumerate() {
+        if i > 0 && i % 76 == 0 {
+            out.push_str("\r\n");
+        }
+        out.push(c);
+    }
+    out.push_str("\r\n");
+    out
+}
+
+/// 头部字段值：非 ASCII 时使用 RFC 2047 编码
+fn header_value(text: &str) -> String {
+    let text = text.replace(['\r', '\n'], " ");
+    if text.is_ascii() {
+        text
+    } else {
+        format!("=?UTF-8?B?{}?=", base64(text.as_bytes()))
+    }
+}
+
+impl Email {
+    /// 生成 MIME 报文（CRLF 换行，正文和附件均为 base64）
+    fn to_mime(&self, message_id: &str) -> String {
+        let mut out = String::new();
+        out.push_str(&format!("From: {}\r\n", self.from));
+        out.push_str(&format!("To: {}\r\n", self.to.join(", ")));
+        if !self.cc.is_empty() {
+            out.push_str(&format!("Cc: {}\r\n", self.cc.join(", ")));
+        }
+        out.push_str(&format!("Subject: {}\r\n", header_value(&self.subject)));
+        out.push_str(&format!("Message-ID: {}\r\n", message_id));
+        out.push_str("MIME-Version: 1.0\r\n");
+
+        let body_type = if self.html { "text/html" } else { "text/plain" };
+        let body = format!(
+            "Content-Type: {}; charset=utf-8\r\nContent-Transfer-Encoding: base64\r\n\r\n{}",
+            body_type,
+            base64_lines(self.body.as_bytes())
+        );
+        if self.attachments.is_empty() {
+            out.push_str(&body);
+            return out;
+        }
+
+        let boundary = format!("aether-{}", message_id.trim_matches(['<', '>']));
+        out.push_str(&format!(
+            "Content-Type: multipart/mixed; boundary=\"{}\"\r\n\r\n",
+            boundary
+        ));
+        out.push_str(&format!("--{}\r\n{}", boundary, body));
+        for attachment in &self.attachments {
+            out.push_str(&format!(
+                "--{}\r\nContent-Type: {}; name=\"{}\"\r\nContent-Disposition: attachment; filename=\"{}\"\r\nContent-Transfer-Encoding: base64\r\n\r\n{}",
+                boundary,
+                attachment.mime,
+                attachment.filename,
+                attachment.filename,
+                base64_lines(&attachment.content)
+            ));
+        }
+        out.push_str(&format!("--{}--\r\n", boundary));
+        out
+    }
+}
+
+/// 底层连接（明文或 TLS）
+enum Transport {
+    Plain(TcpStream),
+    #[cfg(feature = "async")]
+    Tls(Box<rustls::StreamOwned<rustls::ClientConnection, TcpStream>>),
+}
+
+impl Transport {
+    #[cfg(feature = "async")]
+    fn upgrade(self, host: &str) -> Result<Self, RuntimeError> {
+        let Transport::Plain(tcp) = self else {
+            return Ok(self);
+        };
+        let roots = rustls::RootCertStore {
+            roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+        };
+        let config = rustls::ClientConfig::builder_with_provider(std::sync::Arc::new(
+            rustls::crypto::ring::default_provider(),
+        ))
+        .with_safe_default_protocol_versions()
+        .map_err(|e| error(format!("TLS configuration error: {}", e)))?
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+        let server_name = rustls::pki_types::ServerName::try_from(host.to_string())
+            .map_err(|e| error(format!("invalid TLS server name '{}': {}", host, e)))?;
+        let conn = rustls::ClientConnection::new(std::sync::Arc::new(config), server_name)
+            .map_err(|e| error(format!("TLS error: {}", e)))?;
+        Ok(Transport::Tls(Box::new(rustls::StreamOwned::new(
+            conn, tcp,
+        ))))
+    }
+
+    #[cfg(not(feature = "async"))]
+    fn upgrade(self, _host: &str) -> Result<Self, RuntimeError> {
+        Err(error(
+            "TLS requires the 'async' feature; use \"security\": \"none\" for a plain relay",
+        ))
+    }
+
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            Transport::Plain(tcp) => tcp.read(buf),
+            #[cfg(feature = "async")]
+            Transport::Tls(tls) => tls.read(buf),
+        }
+    }
+
+    fn write_all(&mut self, data: &[u8]) -> std::io::Result<()> {
+        match self {
+            Transport::Plain(tcp) => tcp.write_all(data).and_then(|_| tcp.flush()),
+            #[cfg(feature = "async")]
+            Transport::Tls(tls) => tls.write_all(data).and_then(|_| tls.flush()),
+        }
+    }
+}
+
+/// SMTP 会话
+struct Session {
+    transport: Option<Transport>,
+    buf: Vec<u8>,
+    host: String,
+}
+
+impl Session {
+    fn connect(
+        host: &str,
+        port: u16,
+        security: Security,
+        timeout: Duration,
+    ) -> Result<Self, RuntimeError> {
+        let tcp = TcpStream::connect((host, port))
+            .map_err(|e| error(format!("failed to connect to {}:{}: {}", host, port, e)))?;
+        let _ = tcp.set_read_timeout(Some(timeout));
+        let _ = tcp.set_write_timeout(Some(timeout));
+        let mut transport = Transport::Plain(tcp);
+        if security == Security::Tls {
+            transport = transport.upgrade(host)?;
+        }
+        let mut session = Session {
+            transport: Some(transport),
+            buf: Vec::new(),
+            host: host.to_string(),
+        };
+        session.expect(220)?;
+        let capabilities = session.command("EHLO aether", 250)?;
+        if security == Security::StartTls {
+            if !capabilities
+                .iter()
+                .any(|c| c.eq_ignore_ascii_case("STARTTLS"))
+            {
+                return Err(error(format!("{} does not support STARTTLS", host)));
+            }
+            session.command("STARTTLS", 220)?;
+            let transport = session.transport.take().expect("transport present");
+            session.transport = Some(transport.upgrade(host)?);
+            session.command("EHLO aether", 250)?;
+        }
+        Ok(session)
+    }
+
+    fn send(
+        &mut self,
+        email: &Email,
+        data: &str,
+        credentials: Option<&(String, String)>,
+    ) -> Result<(), RuntimeError> {
+        if let Some((username, password)) = credentials {
+            let token = base64(format!("\0{}\0{}", username, password).as_bytes());
+            self.command(&format!("AUTH PLAIN {}", token), 235)?;
+        }
+        self.command(&format!("MAIL FROM:<{}>", email.from), 250)?;
+        for recipient in email.to.iter().chain(&email.cc).chain(&email.bcc) {
+            self.command(&format!("RCPT TO:<{}>", recipient), 250)?;
+        }
+        self.command("DATA", 354)?;
+        // 点填充：以 "." 开头的行需要加一个 "."
+        let mut payload = String::with_capacity(data.len() + 5);
+        for line in data.split_inclusive("\r\n") {
+            if line.starts_with('.') {
+                payload.push('.');
+            }
+            payload.push_str(line);
+        }
+        payload.push_str(".\r\n");
+        self.write(&payload)?;
+        self.expect(250)?;
+        let _ = self.command("QUIT", 221);
+        Ok(())
+    }
+
+    fn write(&mut self, data: &str) -> Result<(), RuntimeError> {
+        let transport = self.transport.as_mut().expect("transport present");
+        transport
+            .write_all(data.as_bytes())
+            .map_err(|e| error(format!("failed to write to {}: {}", self.host, e)))
+    }
+
+    fn command(&mut self, line: &str, code: u16) -> Result<Vec<String>, RuntimeError> {
+        self.write(&format!("{}\r\n", line))?;
+        self.expect(code).map_err(|e| {
+            // AUTH 命令不回显凭据
+            let command = line.split(' ').next().unwrap_or(line);
+            match e {
+                RuntimeError::CustomError(message) => {
+                    RuntimeError::CustomError(format!("{} (after {})", message, command))
+                }
+                other => other,
+            }
+        })
+    }
+
+    /// 读取一个（可能多行的）响应，检查状态码，返回各行文本
+    fn expect(&mut self, code: u16) -> Result<Vec<String>, RuntimeError> {
+        let mut lines = Vec::new();
+        loop {
+            let line = self.read_line()?;
+            let status: u16 = line
+                .get(..3)
+                .and_then(|s| s.parse().ok())
+                .ok_or_else(|| error(format!("malformed SMTP response '{}'", line)))?;
+            let more = line.as_bytes().get(3) == Some(&b'-');
+            lines.push(line.get(4..).unwrap_or("").to_string());
+            if !more {
+                if status != code {
+                    return Err(error(format!(
+                        "server replied {} {}",
+                        status,
+                        lines.join(" ")
+                    )));
+                }
+                return Ok(lines);
+            }
+        }
+    }
+
+    fn read_line(&mut self) -> Result<String, RuntimeError> {
+        loop {
+            if let Some(pos) = self.buf.windows(2).position(|w| w == b"\r\n") {
+                let line = String::from_utf8_lossy(&self.buf[..pos]).into_owned();
+                self.buf.drain(..pos + 2);
+                return Ok(line);
+            }
+            let mut chunk = [0u8; 1024];
+            let transport = self.transport.as_mut().expect("transport present");
+            let n = transport
+                .read(&mut chunk)
+                .map_err(|e| error(format!("failed to read from {}: {}", self.host, e)))?;
+            if n == 0 {
+                return Err(error(format!("{} closed the connection", self.host)));
+            }
+            self.buf.extend_from_slice(&chunk[..n]);
+        }
+    }
+}
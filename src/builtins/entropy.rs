@@ -0,0 +1,27 @@
+// src/builtins/entropy.rs
+//
+// 时间与随机数内置函数。
+//
+// 注意：这些函数依赖引擎状态（确定性模式下的注入时钟和随机数种子），
+// 在 evaluator 中有特殊处理。
+
+use crate::evaluator::RuntimeError;
+use crate::value::Value;
+
+/// NOW - 当前时间（Unix 时间戳，秒）
+///
+/// 用法: NOW() -> 1700000000.123
+/// 确定性模式下返回注入的时钟。
+pub fn now(_args: &[Value]) -> Result<Value, RuntimeError> {
+    // 在 evaluator 中有特殊处理
+    Ok(Value::Null)
+}
+
+/// RANDOM - [0, 1) 区间内的随机数
+///
+/// 用法: RANDOM() -> 0.7364...
+/// 确定性模式下使用固定种子，每次求值得到相同的序列。
+pub fn random(_args: &[Value]) -> Result<Value, RuntimeError> {
+    // 在 evaluator 中有特殊处理
+    Ok(Value::Null)
+}
@@ -0,0 +1,726 @@
+// src/builtins/export.rs
+//! 流式导出（CSV / Excel）
+//!
+//! 批处理任务导出大量数据时，先拼出完整的表格再 `WRITE_FILE` 会占用与数据量成正比的内存。
+//! 流式写入器逐行写入文件，内存占用与行数无关：
+//! - `CSV_WRITER_OPEN(path, options?)` / `XLSX_WRITER_OPEN(path, options?)` 创建文件，返回写入器句柄
+//! - `WRITER_ROW(w, row)` 写入一行（数组或字典），返回已写入的行数
+//! - `WRITER_CLOSE(w)` 完成文件并关闭写入器
+//!
+//! 选项：`headers`（表头，字典行按表头取值）、`delimiter`（CSV 分隔符，默认 ","）、
+//! `sheet`（Excel 工作表名，默认 "Sheet1"）。未指定表头时，第一个字典行的键（按字典序）作为表头。
+//!
+//! Excel 文件是不压缩的 xlsx（内置最小 zip 写入器，无额外依赖），字符串以内联字符串写入，
+//! 不需要在内存中保留共享字符串表。必须调用 `WRITER_CLOSE` 才能得到完整的 xlsx 文件。
+//!
+//! 打开的写入器属于创建它的引擎，其他引擎无法使用它的句柄；句柄包含随机部分，
+//! 无法被猜出。`reset_env` 或引擎被丢弃时关闭所有未关闭的写入器（xlsx 文件
+//! 不完整）。需要启用文件系统权限。
+
+use super::filesystem::{get_string, validate_path};
+use crate::evaluator::RuntimeError;
+use crate::value::Value;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs::File;
+use std::hash::{BuildHasher, Hasher};
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::rc::Rc;
+
+/// 引擎打开的写入器（句柄 -> 写入器）
+#[derive(Default)]
+pub(crate) struct ExportWriters {
+    writers: HashMap<String, Writer>,
+}
+
+impl ExportWriters {
+    /// 关闭所有写入器
+    pub(crate) fn clear(&mut self) {
+        self.writers.clear();
+    }
+}
+
+/// 引擎持有的写入器
+pub(crate) type SharedExportWriters = Rc<RefCell<ExportWriters>>;
+
+// 线程局部的当前引擎写入器（求值期间有效）
+thread_local! {
+    static ACTIVE_WRITERS: RefCell<Option<SharedExportWriters>> = const { RefCell::new(None) };
+}
+
+/// 在作用域内设置当前引擎的写入器（RAII 模式，结束时恢复之前的设置）
+pub(crate) struct ScopedExportWriters {
+    previous: Option<SharedExportWriters>,
+}
+
+impl ScopedExportWriters {
+    pub(crate) fn set(writers: SharedExportWriters) -> Self {
+        let previous = ACTIVE_WRITERS.with(|w| w.borrow_mut().replace(writers));
+        Self { previous }
+    }
+}
+
+impl Drop for ScopedExportWriters {
+    fn drop(&mut self) {
+        ACTIVE_WRITERS.with(|w| *w.borrow_mut() = self.previous.take());
+    }
+}
+
+/// 读取或修改当前引擎的写入器
+///
+/// 不在求值中时作用于一个临时的空表，打开的写入器不会保留。
+fn with_writers<R>(f: impl FnOnce(&mut HashMap<String, Writer>) -> R) -> R {
+    match ACTIVE_WRITERS.with(|w| w.borrow().clone()) {
+        Some(writers) => f(&mut writers.borrow_mut().writers),
+        None => f(&mut HashMap::new()),
+    }
+}
+
+/// 句柄的随机部分（128 位，使用标准库按进程随机播种的哈希器）
+fn random_token() -> String {
+    let state = std::collections::hash_map::RandomState::new();
+    (0..2u8)
+        .map(|i| {
+            let mut hasher = state.build_hasher();
+            hasher.write_u8(i);
+            hasher.write_u128(
+                std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map(|d| d.as_nanos())
+                    .unwrap_or_default(),
+            );
+            format!("{:016x}", hasher.finish())
+        })
+        .collect()
+}
+
+/// 写入器的输出
+enum Sink {
+    Csv {
+        out: BufWriter<File>,
+        delimiter: char,
+    },
+    Xlsx(xlsx::SheetWriter),
+}
+
+/// 一个打开的写入器
+struct Writer {
+    path: PathBuf,
+    /// 表头；字典行按表头取值
+    headers: Option<Vec<String>>,
+    /// 已写入的数据行数（不含表头）
+    rows: usize,
+    sink: Sink,
+}
+
+impl Writer {
+    /// 写入一行单元格
+    fn write_cells(&mut self, cells: &[Value]) -> Result<(), RuntimeError> {
+        let result = match &mut self.sink {
+            Sink::Csv { out, delimiter } => {
+                let line = cells
+                    .iter()
+                    .map(|cell| csv_field(&cell_text(cell), *delimiter))
+                    .collect::<Vec<_>>()
+                    .join(&delimiter.to_string());
+                out.write_all(line.as_bytes())
+                    .and_then(|_| out.write_all(b"\r\n"))
+            }
+            Sink::Xlsx(sheet) => sheet.write_row(cells),
+        };
+        result.map_err(|e| io_error(&self.path, e))
+    }
+
+    /// 写入表头行
+    fn write_headers(&mut self, headers: Vec<String>) -> Result<(), RuntimeError> {
+        let cells: Vec<Value> = headers.iter().cloned().map(Value::String).collect();
+        self.write_cells(&cells)?;
+        self.headers = Some(headers);
+        Ok(())
+    }
+
+    /// 完成文件
+    fn finish(self) -> Result<usize, RuntimeError> {
+        let result = match self.sink {
+            Sink::Csv { mut out, .. } => out.flush(),
+            Sink::Xlsx(sheet) => sheet.finish(),
+        };
+        result.map_err(|e| io_error(&self.path, e))?;
+        Ok(self.rows)
+    }
+}
+
+fn error(message: impl Into<String>) -> RuntimeError {
+    RuntimeError::InvalidOperation(message.into())
+}
+
+fn io_error(path: &std::path::Path, e: std::io::Error) -> RuntimeError {
+    RuntimeError::CustomError(format!("Failed to write '{}': {}", path.display(), e))
+}
+
+fn check_arity(args: &[Value], min: usize, max: usize) -> Result<(), RuntimeError> {
+    if args.len() < min || args.len() > max {
+        return Err(RuntimeError::WrongArity {
+            expected: min,
+            got: args.len(),
+        });
+    }
+    Ok(())
+}
+
+/// 单元格的文本（CSV）；Fraction 写为小数，Null 写为空
+fn cell_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        Value::Number(n) => n.to_string(),
+        Value::Fraction(_) => value.to_number().unwrap_or(f64::NAN).to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// CSV 字段：包含分隔符、引号或换行时用双引号包裹
+fn csv_field(text: &str, delimiter: char) -> String {
+    if text.contains([delimiter, '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+/// 读取选项字典
+fn options_arg(arg: Option<&Value>) -> Result<HashMap<String, Value>, RuntimeError> {
+    match arg {
+        None | Some(Value::Null) => Ok(HashMap::new()),
+        Some(Value::Dict(options)) => Ok(options.clone()),
+        Some(other) => Err(RuntimeError::TypeErrorDetailed {
+            expected: "Dict (options)".to_string(),
+            got: other.type_name().to_string(),
+        }),
+    }
+}
+
+/// 读取 `headers` 选项
+fn headers_option(options: &HashMap<String, Value>) -> Result<Option<Vec<String>>, RuntimeError> {
+    match options.get("headers") {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::Array(items)) => items
+            .iter()
+            .map(|item| match item {
+                Value::String(s) => Ok(s.clone()),
+                other => Err(error(format!("headers must be strings, got {}", other))),
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(Some),
+        Some(other) => Err(error(format!(
+            "headers must be an array of strings, got {}",
+            other
+        ))),
+    }
+}
+
+/// 注册写入器并写入表头，返回句柄
+fn open(
+    prefix: &str,
+    path: PathBuf,
+    headers: Option<Vec<String>>,
+    sink: Sink,
+) -> Result<Value, RuntimeError> {
+    let mut writer = Writer {
+        path,
+        headers: None,
+        rows: 0,
+        sink,
+    };
+    if let Some(headers) = headers {
+        writer.write_headers(headers)?;
+    }
+    let handle = format!("{}:{}", prefix, random_token());
+    with_writers(|writers| writers.insert(handle.clone(), writer));
+    Ok(Value::String(handle))
+}
+
+/// 校验写入器句柄（如 "csv:3f2a..."）
+fn handle_id(handle: &Value) -> Result<String, RuntimeError> {
+    let text = get_string(handle)?;
+    if text.starts_with("csv:") || text.starts_with("xlsx:") {
+        Ok(text)
+    } else {
+        Err(error(format!("Invalid writer handle '{}'", text)))
+    }
+}
+
+fn not_open(handle: &Value) -> RuntimeError {
+    error(format!(
+        "Writer '{}' is not open (already closed or opened by another engine)",
+        handle
+    ))
+}
+
+/// 创建 CSV 写入器
+///
+/// # 参数
+/// - `path`: String - 文件路径（已存在时覆盖）
+/// - `options`: Dict - 可选：`headers`（表头数组）、`delimiter`（单个字符，默认 ","）
+///
+/// # 返回值
+/// String - 写入器句柄，传给 WRITER_ROW / WRITER_CLOSE
+///
+/// # 示例
+/// ```aether
+/// Set W CSV_WRITER_OPEN("out.csv", {"headers": ["id", "name"]})
+/// WRITER_ROW(W, [1, "Alice"])
+/// WRITER_ROW(W, {"id": 2, "name": "Bob"})
+/// WRITER_CLOSE(W)
+/// ```
+///
+/// # 安全性
+/// 需要启用文件系统权限
+pub fn csv_writer_open(args: &[Value]) -> Result<Value, RuntimeError> {
+    check_arity(args, 1, 2)?;
+    let path = validate_path(&get_string(&args[0])?)?;
+    let options = options_arg(args.get(1))?;
+    let delimiter = match options.get("delimiter") {
+        None => ',',
+        Some(Value::String(s))
+            if s.chars().count() == 1 && !matches!(s.as_str(), "\"" | "\n" | "\r") =>
+        {
+            s.chars().next().unwrap_or(',')
+        }
+        Some(other) => {
+            return Err(error(format!(
+                "delimiter must be a single character other than a quote or line break, got {}",
+                other
+            )));
+        }
+    };
+    let headers = headers_option(&options)?;
+
+    let file = File::create(&path).map_err(|e| io_error(&path, e))?;
+    let sink = Sink::Csv {
+        out: BufWriter::new(file),
+        delimiter,
+    };
+    open("csv", path, headers, sink)
+}
+
+/// 创建 Excel（xlsx）写入器
+///
+/// # 参数
+/// - `path`: String - 文件路径（已存在时覆盖）
+/// - `options`: Dict - 可选：`headers`（表头数组）、`sheet`（工作表名，默认 "Sheet1"）
+///
+/// # 返回值
+/// String - 写入器句柄，传给 WRITER_ROW / WRITER_CLOSE
+///
+/// # 示例
+/// ```aether
+/// Set W XLSX_WRITER_OPEN("payroll.xlsx", {"sheet": "2024-06", "headers": ["工号", "实发"]})
+/// WRITER_ROW(W, ["E001", 8650.5])
+/// WRITER_CLOSE(W)
+/// ```
+///
+/// # 安全性
+/// 需要启用文件系统权限
+pub fn xlsx_writer_open(args: &[Value]) -> Result<Value, RuntimeError> {
+    check_arity(args, 1, 2)?;
+    let path = validate_path(&get_string(&args[0])?)?;
+    let options = options_arg(args.get(1))?;
+    let sheet = match options.get("sheet") {
+        None => "Sheet1".to_string(),
+        Some(Value::String(name)) => xlsx::check_sheet_name(name).map_err(error)?,
+        Some(other) => return Err(error(format!("sheet must be a string, got {}", other))),
+    };
+    let headers = headers_option(&options)?;
+
+    let file = File::create(&path).map_err(|e| io_error(&path, e))?;
+    let sheet = xlsx::SheetWriter::new(file, &sheet).map_err(|e| io_error(&path, e))?;
+    open("xlsx", path, headers, Sink::Xlsx(sheet))
+}
+
+/// 写入一行
+///
+/// # 参数
+/// - `writer`: String - 写入器句柄
+/// - `row`: Array/Dict - 数组按顺序写入；字典按表头取值（缺少的列为空），
+///   没有表头时第一个字典行的键（按字典序）成为表头
+///
+/// # 返回值
+/// Number - 已写入的数据行数（不含表头）
+///
+/// # 说明
+/// 单元格只能是 Number、Fraction、String、Boolean、Null。
+pub fn writer_row(args: &[Value]) -> Result<Value, RuntimeError> {
+    check_arity(args, 2, 2)?;
+    let id = handle_id(&args[0])?;
+    with_writers(|writers| {
+        let writer = writers.get_mut(&id).ok_or_else(|| not_open(&args[0]))?;
+
+        let cells = match &args[1] {
+            Value::Array(items) => items.clone(),
+            Value::Dict(dict) => {
+                if writer.headers.is_none() {
+                    let mut keys: Vec<String> = dict.keys().cloned().collect();
+                    keys.sort();
+                    writer.write_headers(keys)?;
+                }
+                let headers = writer.headers.as_deref().unwrap_or_default();
+                if let Some(extra) = dict.keys().find(|key| !headers.contains(key)) {
+                    return Err(error(format!(
+                        "Row has column '{}' that is not in the headers",
+                        extra
+                    )));
+                }
+                headers
+                    .iter()
+                    .map(|h| dict.get(h).cloned().unwrap_or(Value::Null))
+                    .collect()
+            }
+            other => {
+                return Err(RuntimeError::TypeErrorDetailed {
+                    expected: "Array or Dict (row)".to_string(),
+                    got: other.type_name().to_string(),
+                });
+            }
+        };
+        if let Some(cell) = cells.iter().find(|cell| {
+            !matches!(
+                cell,
+                Value::Number(_)
+                    | Value::Fraction(_)
+                    | Value::String(_)
+                    | Value::Boolean(_)
+                    | Value::Null
+            )
+        }) {
+            return Err(error(format!(
+                "Cells must be Number, Fraction, String, Boolean or Null, got {}",
+                cell.type_name()
+            )));
+        }
+
+        writer.write_cells(&cells)?;
+        writer.rows += 1;
+        Ok(Value::Number(writer.rows as f64))
+    })
+}
+
+/// 完成文件并关闭写入器
+///
+/// # 参数
+/// - `writer`: String - 写入器句柄
+///
+/// # 返回值
+/// Number - 写入的数据行数（不含表头）
+pub fn writer_close(args: &[Value]) -> Result<Value, RuntimeError> {
+    check_arity(args, 1, 1)?;
+    let id = handle_id(&args[0])?;
+    let writer = with_writers(|writers| writers.remove(&id)).ok_or_else(|| not_open(&args[0]))?;
+    writer.finish().map(|rows| Value::Number(rows as f64))
+}
+
+/// 最小 xlsx 输出：不压缩的 zip，单个工作表，内联字符串
+mod xlsx {
+    use crate::value::Value;
+    use std::fs::File;
+    use std::io::{self, BufWriter, Seek, SeekFrom, Write};
+
+    const CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/><Override PartName="/xl/worksheets/sheet1.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/><Override PartName="/xl/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.styles+xml"/></Types>"#;
+
+    const ROOT_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/></Relationships>"#;
+
+    const WORKBOOK_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles" Target="styles.xml"/></Relationships>"#;
+
+    const STYLES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<styleSheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><fonts count="1"><font><sz val="11"/><name val="Calibri"/></font></fonts><fills count="2"><fill><patternFill patternType="none"/></fill><fill><patternFill patternType="gray125"/></fill></fills><borders count="1"><border><left/><right/><top/><bottom/><diagonal/></border></borders><cellStyleXfs count="1"><xf numFmtId="0" fontId="0" fillId="0" borderId="0"/></cellStyleXfs><cellXfs count="1"><xf numFmtId="0" fontId="0" fillId="0" borderId="0" xfId="0"/></cellXfs></styleSheet>"#;
+
+    const SHEET_START: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>"#;
+
+    const SHEET_END: &str = "</sheetData></worksheet>";
+
+    /// 工作表最多行数
+    const MAX_ROWS: usize = 1_048_576;
+
+    /// 校验工作表名（Excel 的限制：1-31 个字符，不含 `[]:*?/\`）
+    pub(super) fn check_sheet_name(name: &str) -> Result<String, String> {
+        let len = name.chars().count();
+        if len == 0 || len > 31 || name.contains(['[', ']', ':', '*', '?', '/', '\\']) {
+            return Err(format!(
+                "Invalid sheet name '{}': must be 1-31 characters without []:*?/\\",
+                name
+            ));
+        }
+        Ok(name.to_string())
+    }
+
+    /// 已写入的 zip 条目（中央目录使用）
+    struct Entry {
+        name: &'static str,
+        offset: u32,
+        crc: u32,
+        size: u32,
+    }
+
+    /// 流式写入工作表的 xlsx 文件
+    pub(super) struct SheetWriter {
+        out: BufWriter<File>,
+        entries: Vec<Entry>,
+        /// 工作表条目：本地文件头的位置、CRC 和已写入的字节数
+        sheet_offset: u64,
+        crc: u32,
+        size: u64,
+        rows: usize,
+    }
+
+    impl SheetWriter {
+        /// 写入工作簿的固定部分，并开始工作表条目
+        pub(super) fn new(file: File, sheet_name: &str) -> io::Result<Self> {
+            let mut writer = SheetWriter {
+                out: BufWriter::new(file),
+                entries: Vec::new(),
+                sheet_offset: 0,
+                crc: 0,
+                size: 0,
+                rows: 0,
+            };
+            let workbook = format!(
+                r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="{}" sheetId="1" r:id="rId1"/></sheets></workbook>"#,
+                escape(sheet_name)
+            );
+            writer.add_entry("[Content_Types].xml", CONTENT_TYPES.as_bytes())?;
+            writer.add_entry("_rels/.rels", ROOT_RELS.as_bytes())?;
+            writer.add_entry("xl/workbook.xml", workbook.as_bytes())?;
+            writer.add_entry("xl/_rels/workbook.xml.rels", WORKBOOK_RELS.as_bytes())?;
+            writer.add_entry("xl/styles.xml", STYLES.as_bytes())?;
+
+            writer.sheet_offset = writer.out.stream_position()?;
+            writer.local_header(SHEET_NAME, 0, 0)?;
+            writer.write_sheet(SHEET_START.as_bytes())?;
+            Ok(writer)
+        }
+
+        /// 写入一行
+        pub(super) fn write_row(&mut self, cells: &[Value]) -> io::Result<()> {
+            if self.rows >= MAX_ROWS {
+                return Err(io::Error::other(format!(
+                    "Excel sheets are limited to {} rows",
+                    MAX_ROWS
+                )));
+            }
+            self.rows += 1;
+            let mut xml = format!("<row r=\"{}\">", self.rows);
+            for (i, cell) in cells.iter().enumerate() {
+                let r = format!("{}{}", column_name(i), self.rows);
+                let number = match cell {
+                    Value::Number(n) => Some(*n),
+                    Value::Fraction(_) => cell.to_number(),
+                    _ => None,
+                };
+                match (cell, number) {
+                    (Value::Null, _) => {}
+                    (_, Some(n)) if n.is_finite() => {
+                        xml.push_str(&format!("<c r=\"{}\"><v>{}</v></c>", r, n));
+                    }
+                    (Value::Boolean(b), _) => {
+                        xml.push_str(&format!("<c r=\"{}\" t=\"b\"><v>{}</v></c>", r, *b as u8));
+                    }
+                    (Value::String(s), _) => xml.push_str(&format!(
+                        "<c r=\"{}\" t=\"inlineStr\"><is><t xml:space=\"preserve\">{}</t></is></c>",
+                        r,
+                        escape(s)
+                    )),
+                    // NaN / Infinity 不是合法的 Excel 数值，按文本写入
+                    (other, _) => xml.push_str(&format!(
+                        "<c r=\"{}\" t=\"inlineStr\"><is><t>{}</t></is></c>",
+                        r,
+                        escape(&other.to_string())
+                    )),
+                }
+            }
+            xml.push_str("</row>");
+            self.write_sheet(xml.as_bytes())
+        }
+
+        /// 结束工作表条目，回填它的本地文件头，写入中央目录
+        pub(super) fn finish(mut self) -> io::Result<()> {
+            self.write_sheet(SHEET_END.as_bytes())?;
+            let size = u32::try_from(self.size)
+                .map_err(|_| io::Error::other("Excel sheet exceeds 4 GiB, use CSV instead"))?;
+            let offset = u32::try_from(self.sheet_offset)
+                .map_err(|_| io::Error::other("Excel file exceeds 4 GiB, use CSV instead"))?;
+            let end = self.out.stream_position()?;
+            self.out.seek(SeekFrom::Start(self.sheet_offset))?;
+            self.local_header(SHEET_NAME, self.crc, size)?;
+            self.out.seek(SeekFrom::Start(end))?;
+            self.entries.push(Entry {
+                name: SHEET_NAME,
+                offset,
+                crc: self.crc,
+                size,
+            });
+
+            let directory_offset = u32::try_from(end)
+                .map_err(|_| io::Error::other("Excel file exceeds 4 GiB, use CSV instead"))?;
+            let mut directory = Vec::new();
+            for entry in &self.entries {
+                directory.extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+                directory.extend_from_slice(&20u16.to_le_bytes()); // made by
+                directory.extend_from_slice(&20u16.to_le_bytes()); // version needed
+                directory.extend_from_slice(&UTF8_FLAG.to_le_bytes());
+                directory.extend_from_slice(&0u16.to_le_bytes()); // stored
+                directory.extend_from_slice(&DOS_TIME.to_le_bytes());
+                directory.extend_from_slice(&DOS_DATE.to_le_bytes());
+                directory.extend_from_slice(&entry.crc.to_le_bytes());
+                directory.extend_from_slice(&entry.size.to_le_bytes());
+                directory.extend_from_slice(&entry.size.to_le_bytes());
+                directory.extend_from_slice(&(entry.name.len() as u16).to_le_bytes());
+                directory.extend_from_slice(&[0; 12]); // extra, comment, disk, attributes
+                directory.extend_from_slice(&entry.offset.to_le_bytes());
+                directory.extend_from_slice(entry.name.as_bytes());
+            }
+            let count = self.entries.len() as u16;
+            let directory_size = directory.len() as u32;
+            directory.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+            directory.extend_from_slice(&[0; 4]); // disk numbers
+            directory.extend_from_slice(&count.to_le_bytes());
+            directory.extend_from_slice(&count.to_le_bytes());
+            directory.extend_from_slice(&directory_size.to_le_bytes());
+            directory.extend_from_slice(&directory_offset.to_le_bytes());
+            directory.extend_from_slice(&0u16.to_le_bytes()); // comment
+            self.out.write_all(&directory)?;
+            self.out.flush()
+        }
+
+        fn write_sheet(&mut self, data: &[u8]) -> io::Result<()> {
+            self.crc = crc32(self.crc, data);
+            self.size += data.len() as u64;
+            self.out.write_all(data)
+        }
+
+        /// 写入完整的小条目
+        fn add_entry(&mut self, name: &'static str, data: &[u8]) -> io::Result<()> {
+            let offset = self.out.stream_position()? as u32;
+            let crc = crc32(0, data);
+            let size = data.len() as u32;
+            self.local_header(name, crc, size)?;
+            self.out.write_all(data)?;
+            self.entries.push(Entry {
+                name,
+                offset,
+                crc,
+                size,
+            });
+            Ok(())
+        }
+
+        fn local_header(&mut self, name: &str, crc: u32, size: u32) -> io::Result<()> {
+            let mut header = Vec::with_capacity(30 + name.len());
+            header.extend_from_slice(&0x0403_4b50u32.to_le_bytes());
+            header.extend_from_slice(&20u16.to_le_bytes()); // version needed
+            header.extend_from_slice(&UTF8_FLAG.to_le_bytes());
+            header.extend_from_slice(&0u16.to_le_bytes()); // stored
+            header.extend_from_slice(&DOS_TIME.to_le_bytes());
+            header.extend_from_slice(&DOS_DATE.to_le_bytes());
+            header.extend_from_slice(&crc.to_le_bytes());
+            header.extend_from_slice(&size.to_le_bytes());
+            header.extend_from_slice(&size.to_le_bytes());
+            header.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            header.extend_from_slice(&0u16.to_le_bytes()); // extra
+            header.extend_from_slice(name.as_bytes());
+            self.out.write_all(&header)
+        }
+    }
+
+    const SHEET_NAME: &str = "xl/worksheets/sheet1.xml";
+    /// 通用标志位：文件名为 UTF-8
+    const UTF8_FLAG: u16 = 0x0800;
+    /// 条目的修改时间固定为 1980-01-01 00:00，相同数据生成相同的文件
+    const DOS_TIME: u16 = 0;
+    const DOS_DATE: u16 = (1 << 5) | 1;
+
+    /// 列名：0 → A，25 → Z，26 → AA
+    fn column_name(mut index: usize) -> String {
+        let mut name = Vec::new();
+        loop {
+            name.push(b'A' + (index % 26) as u8);
+            if index < 26 {
+                break;
+            }
+            index = index / 26 - 1;
+        }
+        name.reverse();
+        String::from_utf8(name).unwrap_or_default()
+    }
+
+    /// XML 转义；XML 中不允许的控制字符被丢弃
+    fn escape(text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        for ch in text.chars() {
+            match ch {
+                '&' => out.push_str("&amp;"),
+                '<' => out.push_str("&lt;"),
+                '>' => out.push_str("&gt;"),
+                '"' => out.push_str("&quot;"),
+                '\t' | '\n' | '\r' => out.push(ch),
+                c if (c as u32) < 0x20 || c == '\u{FFFE}' || c == '\u{FFFF}' => {}
+                c => out.push(c),
+            }
+        }
+        out
+    }
+
+    /// CRC-32（zip 使用的 IEEE 多项式），`crc` 为之前数据的结果
+    fn crc32(crc: u32, data: &[u8]) -> u32 {
+        const TABLE: [u32; 256] = {
+            let mut table = [0u32; 256];
+            let mut i = 0;
+            while i < 256 {
+                let mut c = i as u32;
+                let mut k = 0;
+                while k < 8 {
+                    c = if c & 1 != 0 {
+                        0xEDB8_8320 ^ (c >> 1)
+                    } else {
+                        c >> 1
+                    };
+                    k += 1;
+                }
+                table[i] = c;
+                i += 1;
+            }
+            table
+        };
+        let mut c = !crc;
+        for &byte in data {
+            c = TABLE[((c ^ byte as u32) & 0xFF) as usize] ^ (c >> 8);
+        }
+        !c
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn crc32_matches_reference_values() {
+            assert_eq!(crc32(0, b"123456789"), 0xCBF4_3926);
+            // 分段计算与一次计算结果相同
+            assert_eq!(crc32(crc32(0, b"12345"), b"6789"), 0xCBF4_3926);
+        }
+
+        #[test]
+        fn column_names_roll_over() {
+            assert_eq!(column_name(0), "A");
+            assert_eq!(column_name(25), "Z");
+            assert_eq!(column_name(26), "AA");
+            assert_eq!(column_name(701), "ZZ");
+            assert_eq!(column_name(702), "AAA");
+        }
+    }
+}
@@ -8,7 +8,7 @@ use std::fs;
 use std::path::Path;
 
 /// 辅助函数：安全地获取字符串参数
-fn get_string(val: &Value) -> Result<String, RuntimeError> {
+pub(crate) fn get_string(val: &Value) -> Result<String, RuntimeError> {
     match val {
         Value::String(s) => Ok(s.clone()),
         _ => Err(RuntimeError::TypeErrorDetailed {
@@ -19,7 +19,7 @@ fn get_string(val: &Value) -> Result<String, RuntimeError> {
 }
 
 /// 辅助函数：验证路径（如果配置了验证器）
-fn validate_path(path_str: &str) -> Result<std::path::PathBuf, RuntimeError> {
+pub(crate) fn validate_path(path_str: &str) -> Result<std::path::PathBuf, RuntimeError> {
     // 如果配置了路径验证器，使用它验证路径
     if let Some(validator) = get_filesystem_validator() {
         let path = Path::new(path_str);
@@ -1,6 +1,7 @@
 // src/builtins/help.rs
 //! 帮助文档系统
 
+use super::args::Param;
 use crate::evaluator::RuntimeError;
 use crate::value::Value;
 use std::collections::HashMap;
@@ -24,6 +25,443 @@ pub struct FunctionDocData {
     pub example: Option<String>,
 }
 
+/// 函数分类（HELP() 列表与 DOC() 的 category 字段）
+const CATEGORIES: &[(&str, &[&str])] = &[
+    (
+        "精确计算",
+        &[
+            "TO_FRACTION",
+            "TO_FLOAT",
+            "SIMPLIFY",
+            "FRAC_ADD",
+            "FRAC_SUB",
+            "FRAC_MUL",
+            "FRAC_DIV",
+            "NUMERATOR",
+            "DENOMINATOR",
+            "GCD",
+            "LCM",
+            "MODPOW",
+            "ISQRT",
+            "PRIME_TEST",
+        ],
+    ),
+    ("输入输出", &["PRINT", "PRINTLN", "INPUT"]),
+    ("富输出", &["DISPLAY", "IMAGE"]),
+    ("图表", &["PLOT_LINE", "PLOT_BAR", "PLOT_HIST"]),
+    ("报表", &["REPORT_BUILD"]),
+    ("邮件", &["SEND_EMAIL"]),
+    ("对象存储", &["S3_GET", "S3_PUT", "S3_LIST"]),
+    ("帮助", &["HELP", "DOC"]),
+    (
+        "调试",
+        &[
+            "TRACE",
+            "TRACE_DEBUG",
+            "TRACE_INFO",
+            "TRACE_WARN",
+            "TRACE_ERROR",
+        ],
+    ),
+    ("内省", &["VARS", "GLOBALS"]),
+    (
+        "数组操作",
+        &[
+            "RANGE",
+            "LEN",
+            "PUSH",
+            "POP",
+            "REVERSE",
+            "SORT",
+            "SUM",
+            "MAX",
+            "MIN",
+            "MAP",
+            "FILTER",
+            "REDUCE",
+            "BSEARCH",
+            "LOWER_BOUND",
+            "UPPER_BOUND",
+            "INSERT_SORTED",
+        ],
+    ),
+    (
+        "集合",
+        &["SET", "UNION", "INTERSECT", "DIFFERENCE", "SUBSET"],
+    ),
+    (
+        "字符串操作",
+        &[
+            "SPLIT",
+            "UPPER",
+            "LOWER",
+            "TRIM",
+            "CONTAINS",
+            "STARTS_WITH",
+            "ENDS_WITH",
+            "REPLACE",
+            "REPEAT",
+            "JOIN",
+            "STRSLICE",
+            "STRLEN",
+            "INDEXOF",
+            "CHARAT",
+            "LEVENSHTEIN",
+            "JARO_WINKLER",
+            "SOUNDEX",
+            "FUZZY_MATCH",
+            "RENDER_TEMPLATE",
+        ],
+    ),
+    (
+        "表格",
+        &[
+            "TABLE",
+            "TABLE_FROM_CSV",
+            "SELECT",
+            "WHERE",
+            "GROUP_AGG",
+            "JOIN",
+            "SORT",
+            "TO_DICTS",
+            "COLUMNS",
+        ],
+    ),
+    (
+        "国际化",
+        &[
+            "LOAD_MESSAGES",
+            "TRANSLATE",
+            "PLURAL_CATEGORY",
+            "SET_LOCALE",
+            "GET_LOCALE",
+            "CLEAR_MESSAGES",
+            "SORT_LOCALE",
+            "COMPARE_LOCALE",
+        ],
+    ),
+    (
+        "标识符校验",
+        &[
+            "LUHN_CHECK",
+            "VALIDATE_ID_CN",
+            "VALIDATE_IBAN",
+            "VALIDATE_EMAIL",
+        ],
+    ),
+    ("模式校验", &["VALIDATE"]),
+    (
+        "定时调度",
+        &[
+            "SCHEDULE",
+            "UNSCHEDULE",
+            "RUN_SCHEDULER",
+            "SET_TIMEOUT",
+            "SET_INTERVAL",
+            "DEBOUNCE",
+            "CLEAR_TIMER",
+        ],
+    ),
+    ("时间与随机数", &["NOW", "RANDOM"]),
+    (
+        "日期时间",
+        &[
+            "DATE_PARSE",
+            "DATE_FORMAT",
+            "DATE_ADD",
+            "DATE_DIFF",
+            "DATE_TO_TZ",
+            "DATE_TIMESTAMP",
+        ],
+    ),
+    ("机密", &["SECRET", "MARK_SECRET"]),
+    ("快照测试", &["SNAPSHOT_MATCH"]),
+    ("重试与超时", &["RETRY", "WITH_TIMEOUT"]),
+    (
+        "并行任务",
+        &[
+            "SPAWN",
+            "AWAIT_ALL",
+            "PARALLEL",
+            "CHANNEL",
+            "SEND",
+            "RECV",
+            "CHANNEL_CLOSE",
+        ],
+    ),
+    (
+        "数学函数 - 基础",
+        &["ABS", "SQRT", "POW", "FLOOR", "CEIL", "ROUND"],
+    ),
+    (
+        "数学函数 - 三角",
+        &[
+            "SIN", "COS", "TAN", "ASIN", "ACOS", "ATAN", "ATAN2", "SINH", "COSH", "TANH",
+        ],
+    ),
+    (
+        "数学函数 - 对数",
+        &["LOG", "LN", "LOG2", "EXP", "EXP2", "EXPM1", "LOG1P"],
+    ),
+    (
+        "数学函数 - 特殊",
+        &["FACTORIAL", "GAMMA", "ERF", "HYPOT", "SIGN", "CLAMP"],
+    ),
+    ("数学常数", &["PI", "E", "TAU", "PHI"]),
+    (
+        "统计分析",
+        &["MEAN", "MEDIAN", "VARIANCE", "STD", "QUANTILE"],
+    ),
+    (
+        "序列聚合",
+        &["CUMSUM", "RUNNING_SUM", "CUMPROD", "DIFF", "ROLLING"],
+    ),
+    (
+        "向量运算",
+        &["DOT", "NORM", "CROSS", "DISTANCE", "NORMALIZE"],
+    ),
+    (
+        "矩阵运算",
+        &[
+            "MATMUL",
+            "TRANSPOSE",
+            "DETERMINANT",
+            "INVERSE",
+            "IDENTITY_MATRIX",
+            "ZEROS",
+            "ONES",
+            "RESHAPE",
+            "SOLVE",
+            "RANK",
+            "EIGENVALUES",
+            "EIGENVECTORS",
+        ],
+    ),
+    ("线性回归", &["LINEAR_REGRESSION"]),
+    ("概率分布", &["NORMAL_PDF", "NORMAL_CDF", "POISSON_PMF"]),
+    (
+        "精度计算",
+        &[
+            "ROUND_TO",
+            "ADD_WITH_PRECISION",
+            "SUB_WITH_PRECISION",
+            "MUL_WITH_PRECISION",
+            "DIV_WITH_PRECISION",
+            "ROUND_HALF_EVEN",
+            "ROUND_HALF_UP",
+            "ROUND_DOWN",
+            "ROUND_UP",
+            "ALLOCATE",
+            "SET_PRECISION",
+        ],
+    ),
+    (
+        "类型转换",
+        &["TYPE", "TO_STRING", "TO_NUMBER", "TO_INT", "CLONE"],
+    ),
+    ("字典操作", &["KEYS", "VALUES", "HAS", "MERGE"]),
+    ("结构化比较", &["APPLY_PATCH"]),
+    ("JSON", &["JSON_PARSE", "JSON_STRINGIFY"]),
+    (
+        "文件系统",
+        &[
+            "READ_FILE",
+            "WRITE_FILE",
+            "APPEND_FILE",
+            "DELETE_FILE",
+            "FILE_EXISTS",
+            "LIST_DIR",
+            "CREATE_DIR",
+        ],
+    ),
+    (
+        "键值存储",
+        &["KV_OPEN", "KV_GET", "KV_SET", "KV_DELETE", "KV_KEYS"],
+    ),
+    (
+        "流式导出",
+        &[
+            "CSV_WRITER_OPEN",
+            "XLSX_WRITER_OPEN",
+            "WRITER_ROW",
+            "WRITER_CLOSE",
+        ],
+    ),
+    (
+        "网络",
+        &[
+            "HTTP_GET",
+            "HTTP_POST",
+            "HTTP_PUT",
+            "HTTP_DELETE",
+            "HTTP_SERVE",
+        ],
+    ),
+    (
+        "流式客户端",
+        &[
+            "WS_CONNECT",
+            "WS_SEND",
+            "WS_RECV",
+            "WS_CLOSE",
+            "SSE_CONNECT",
+            "SSE_RECV",
+            "SSE_CLOSE",
+        ],
+    ),
+    (
+        "薪资 - 基本工资",
+        &[
+            "CALC_HOURLY_PAY",
+            "CALC_DAILY_PAY",
+            "CALC_MONTHLY_FROM_HOURLY",
+            "CALC_ANNUAL_SALARY",
+            "CALC_BASE_SALARY",
+            "CALC_GROSS_SALARY",
+            "CALC_NET_SALARY",
+        ],
+    ),
+    (
+        "薪资 - 加班费",
+        &[
+            "CALC_OVERTIME_PAY",
+            "CALC_WEEKDAY_OVERTIME",
+            "CALC_WEEKEND_OVERTIME",
+            "CALC_HOLIDAY_OVERTIME",
+            "CALC_TOTAL_OVERTIME",
+        ],
+    ),
+    (
+        "薪资 - 个人所得税",
+        &[
+            "CALC_PERSONAL_TAX",
+            "CALC_TAXABLE_INCOME",
+            "CALC_ANNUAL_BONUS_TAX",
+            "CALC_EFFECTIVE_TAX_RATE",
+            "CALC_GROSS_FROM_NET",
+            "CALC_TAX_REFUND",
+        ],
+    ),
+    (
+        "薪资 - 社保公积金",
+        &[
+            "CALC_PENSION_INSURANCE",
+            "CALC_MEDICAL_INSURANCE",
+            "CALC_UNEMPLOYMENT_INSURANCE",
+            "CALC_HOUSING_FUND",
+            "CALC_SOCIAL_INSURANCE",
+            "ADJUST_SOCIAL_BASE",
+            "CALC_SOCIAL_BASE_LOWER",
+            "CALC_SOCIAL_BASE_UPPER",
+            "CALC_INJURY_INSURANCE",
+            "CALC_MATERNITY_INSURANCE",
+        ],
+    ),
+    (
+        "薪资 - 考勤",
+        &[
+            "CALC_ATTENDANCE_RATE",
+            "CALC_LATE_DEDUCTION",
+            "CALC_EARLY_LEAVE_DEDUCTION",
+            "CALC_ABSENT_DEDUCTION",
+            "CALC_LEAVE_DEDUCTION",
+            "CALC_SICK_LEAVE_PAY",
+            "CALC_UNPAID_LEAVE_DEDUCTION",
+        ],
+    ),
+    (
+        "薪资 - 奖金",
+        &[
+            "CALC_PERFORMANCE_PAY",
+            "CALC_ANNUAL_BONUS",
+            "CALC_ATTENDANCE_BONUS",
+            "CALC_SALES_COMMISSION",
+            "CALC_PROJECT_BONUS",
+            "CALC_13TH_SALARY",
+        ],
+    ),
+    (
+        "薪资 - 津贴补贴",
+        &[
+            "CALC_MEAL_ALLOWANCE",
+            "CALC_TRANSPORT_ALLOWANCE",
+            "CALC_COMMUNICATION_ALLOWANCE",
+            "CALC_HOUSING_ALLOWANCE",
+            "CALC_HIGH_TEMP_ALLOWANCE",
+            "CALC_NIGHT_SHIFT_ALLOWANCE",
+            "CALC_POSITION_ALLOWANCE",
+        ],
+    ),
+    (
+        "薪资 - 折算",
+        &[
+            "ANNUAL_TO_MONTHLY",
+            "MONTHLY_TO_ANNUAL",
+            "DAILY_TO_MONTHLY",
+            "MONTHLY_TO_DAILY",
+            "HOURLY_TO_MONTHLY",
+            "MONTHLY_TO_HOURLY",
+            "PRORATE_BY_NATURAL_DAYS",
+            "PRORATE_BY_LEGAL_DAYS",
+            "PRORATE_BY_WORKDAYS",
+            "CALC_ONBOARDING_SALARY",
+            "CALC_RESIGNATION_SALARY",
+            "CALC_14TH_SALARY",
+        ],
+    ),
+    (
+        "薪资 - 日期工时",
+        &[
+            "CALC_NATURAL_DAYS",
+            "GET_LEGAL_PAY_DAYS",
+            "CALC_WORKDAYS",
+            "CALC_WEEKEND_DAYS",
+            "CALC_HOLIDAY_DAYS",
+            "IS_WORKDAY",
+            "IS_WEEKEND",
+            "IS_HOLIDAY",
+            "CALC_WORK_HOURS",
+            "CALC_MONTHLY_WORK_HOURS",
+            "CALC_ANNUAL_WORKDAYS",
+            "CALC_ANNUAL_PAY_DAYS",
+        ],
+    ),
+    (
+        "薪资 - 统计",
+        &[
+            "CALC_SALARY_AVERAGE",
+            "CALC_SALARY_MEDIAN",
+            "CALC_SALARY_RANGE",
+            "CALC_PERCENTILE",
+            "CALC_SALARY_STD_DEV",
+            "CALC_SALARY_DISTRIBUTION",
+        ],
+    ),
+    (
+        "薪资 - 节假日日历",
+        &[
+            "SET_HOLIDAY_CALENDAR",
+            "LOAD_HOLIDAY_CALENDAR",
+            "CLEAR_HOLIDAY_CALENDAR",
+        ],
+    ),
+    ("薪资 - 舍入", &["SET_PAYROLL_ROUNDING"]),
+    ("薪资 - 批量计算", &["PAYROLL_RUN"]),
+];
+
+/// 获取函数文档（按名称）
+pub fn function_doc(name: &str) -> Option<&'static FunctionDocData> {
+    FUNCTION_DOCS.get_or_init(init_docs).get(name)
+}
+
+/// 获取函数所属的分类
+pub fn function_category(name: &str) -> Option<&'static str> {
+    CATEGORIES
+        .iter()
+        .find(|(_, funcs)| funcs.contains(&name))
+        .map(|(category, _)| *category)
+}
+
 /// 初始化函数文档
 pub fn init_docs() -> HashMap<String, FunctionDocData> {
     let mut docs = HashMap::new();
@@ -144,10 +582,11 @@ pub fn init_docs() -> HashMap<String, FunctionDocData> {
         "GCD".to_string(),
         FunctionDocData {
             name: "GCD".to_string(),
-            description: "计算两个整数的最大公约数（Greatest Common Divisor）".to_string(),
+            description: "计算两个整数或分数的最大公约数（Greatest Common Divisor），支持大整数"
+                .to_string(),
             params: vec![
-                ("a".to_string(), "第一个整数".to_string()),
-                ("b".to_string(), "第二个整数".to_string()),
+                ("a".to_string(), "第一个整数或分数".to_string()),
+                ("b".to_string(), "第二个整数或分数".to_string()),
             ],
             returns: "两个数的最大公约数".to_string(),
             example: Some("GCD(12, 18)  => 6\nGCD(7, 13)  => 1".to_string()),
@@ -158,16 +597,56 @@ pub fn init_docs() -> HashMap<String, FunctionDocData> {
         "LCM".to_string(),
         FunctionDocData {
             name: "LCM".to_string(),
-            description: "计算两个整数的最小公倍数（Least Common Multiple）".to_string(),
+            description: "计算两个整数或分数的最小公倍数（Least Common Multiple），支持大整数"
+                .to_string(),
             params: vec![
-                ("a".to_string(), "第一个整数".to_string()),
-                ("b".to_string(), "第二个整数".to_string()),
+                ("a".to_string(), "第一个整数或分数".to_string()),
+                ("b".to_string(), "第二个整数或分数".to_string()),
             ],
             returns: "两个数的最小公倍数".to_string(),
             example: Some("LCM(4, 6)  => 12\nLCM(3, 5)  => 15".to_string()),
         },
     );
 
+    docs.insert(
+        "MODPOW".to_string(),
+        FunctionDocData {
+            name: "MODPOW".to_string(),
+            description: "模幂运算 (base ^ exponent) mod modulus，支持大整数".to_string(),
+            params: vec![
+                ("base".to_string(), "底数（整数）".to_string()),
+                ("exponent".to_string(), "指数（非负整数）".to_string()),
+                ("modulus".to_string(), "模数（非零整数）".to_string()),
+            ],
+            returns: "模幂运算的结果".to_string(),
+            example: Some("MODPOW(4, 13, 497)  => 445".to_string()),
+        },
+    );
+
+    docs.insert(
+        "ISQRT".to_string(),
+        FunctionDocData {
+            name: "ISQRT".to_string(),
+            description: "整数平方根，返回不超过 √n 的最大整数，支持大整数".to_string(),
+            params: vec![("n".to_string(), "非负整数".to_string())],
+            returns: "整数平方根".to_string(),
+            example: Some(
+                "ISQRT(17)  => 4\nISQRT(1000000000000000000000000)  => 1000000000000".to_string(),
+            ),
+        },
+    );
+
+    docs.insert(
+        "PRIME_TEST".to_string(),
+        FunctionDocData {
+            name: "PRIME_TEST".to_string(),
+            description: "素数判定（Miller-Rabin，n < 3.3×10^24 时结果确定）".to_string(),
+            params: vec![("n".to_string(), "整数".to_string())],
+            returns: "是否为素数".to_string(),
+            example: Some("PRIME_TEST(97)  => True\nPRIME_TEST(91)  => False".to_string()),
+        },
+    );
+
     // I/O 函数
     docs.insert(
         "PRINT".to_string(),
@@ -547,7 +1026,7 @@ pub fn init_docs() -> HashMap<String, FunctionDocData> {
             description: "获取值的类型".to_string(),
             params: vec![("value".to_string(), "任意值".to_string())],
             returns: "类型名称字符串".to_string(),
-            example: Some("TYPE(123)  => \"Number\"\nTYPE(\"hello\")  => \"String\"".to_string()),
+            example: Some("TYPE(123)  => \"Integer\"\nTYPE(1.5)  => \"Number\"\nTYPE(\"hello\")  => \"String\"".to_string()),
         },
     );
 
@@ -573,6 +1052,116 @@ pub fn init_docs() -> HashMap<String, FunctionDocData> {
         },
     );
 
+    docs.insert(
+        "TO_INT".to_string(),
+        FunctionDocData {
+            name: "TO_INT".to_string(),
+            description: "将值转换为整数，小数部分向零截断".to_string(),
+            params: vec![("value".to_string(), "数字、字符串或布尔值".to_string())],
+            returns: "整数".to_string(),
+            example: Some("TO_INT(3.9)  => 3\nTO_INT(\"42\")  => 42".to_string()),
+        },
+    );
+
+    docs.insert(
+        "DATE_PARSE".to_string(),
+        FunctionDocData {
+            name: "DATE_PARSE".to_string(),
+            description: "解析日期字符串或 Unix 时间戳，未指定偏移时按 UTC".to_string(),
+            params: vec![
+                ("value".to_string(), "日期字符串或时间戳（秒）".to_string()),
+                ("format".to_string(), "可选，strftime 格式".to_string()),
+            ],
+            returns: "日期时间".to_string(),
+            example: Some(
+                "DATE_PARSE(\"2024-01-31 09:30\")  => 2024-01-31T09:30:00+00:00".to_string(),
+            ),
+        },
+    );
+
+    docs.insert(
+        "DATE_FORMAT".to_string(),
+        FunctionDocData {
+            name: "DATE_FORMAT".to_string(),
+            description: "按 strftime 格式格式化日期时间".to_string(),
+            params: vec![
+                ("date".to_string(), "日期时间".to_string()),
+                ("format".to_string(), "可选，默认 RFC 3339".to_string()),
+            ],
+            returns: "字符串".to_string(),
+            example: Some(
+                "DATE_FORMAT(DATE_PARSE(\"2024-01-31\"), \"%d/%m/%Y\")  => \"31/01/2024\""
+                    .to_string(),
+            ),
+        },
+    );
+
+    docs.insert(
+        "DATE_ADD".to_string(),
+        FunctionDocData {
+            name: "DATE_ADD".to_string(),
+            description: "日期时间加上若干个单位，月末按目标月份截断".to_string(),
+            params: vec![
+                ("date".to_string(), "日期时间".to_string()),
+                ("amount".to_string(), "数量，可为负数".to_string()),
+                (
+                    "unit".to_string(),
+                    "可选，seconds/minutes/hours/days/weeks/months/years，默认 days".to_string(),
+                ),
+            ],
+            returns: "日期时间".to_string(),
+            example: Some(
+                "DATE_ADD(DATE_PARSE(\"2024-01-31\"), 1, \"months\")  => 2024-02-29T00:00:00+00:00"
+                    .to_string(),
+            ),
+        },
+    );
+
+    docs.insert(
+        "DATE_DIFF".to_string(),
+        FunctionDocData {
+            name: "DATE_DIFF".to_string(),
+            description: "计算 end - start 的完整单位数".to_string(),
+            params: vec![
+                ("start".to_string(), "开始时间".to_string()),
+                ("end".to_string(), "结束时间".to_string()),
+                ("unit".to_string(), "可选，默认 days".to_string()),
+            ],
+            returns: "整数".to_string(),
+            example: Some("DATE_DIFF(\"2024-01-01\", \"2024-03-15\")  => 74".to_string()),
+        },
+    );
+
+    docs.insert(
+        "DATE_TO_TZ".to_string(),
+        FunctionDocData {
+            name: "DATE_TO_TZ".to_string(),
+            description: "将日期时间转换到另一个时区偏移".to_string(),
+            params: vec![
+                ("date".to_string(), "日期时间".to_string()),
+                (
+                    "offset".to_string(),
+                    "\"UTC\"、\"+08:00\" 或小时数".to_string(),
+                ),
+            ],
+            returns: "日期时间".to_string(),
+            example: Some(
+                "DATE_TO_TZ(\"2024-01-31T01:00:00Z\", 8)  => 2024-01-31T09:00:00+08:00".to_string(),
+            ),
+        },
+    );
+
+    docs.insert(
+        "DATE_TIMESTAMP".to_string(),
+        FunctionDocData {
+            name: "DATE_TIMESTAMP".to_string(),
+            description: "日期时间对应的 Unix 时间戳（秒）".to_string(),
+            params: vec![("date".to_string(), "日期时间".to_string())],
+            returns: "数字".to_string(),
+            example: Some("DATE_TIMESTAMP(\"1970-01-02\")  => 86400".to_string()),
+        },
+    );
+
     docs
 }
 
@@ -590,90 +1179,15 @@ pub fn help(args: &[Value]) -> Result<Value, RuntimeError> {
         let mut output = String::from("=== Aether 内置函数列表 ===\n\n");
 
         // 按类别组织函数
-        let categories = vec![
-            (
-                "精确计算",
-                vec![
-                    "TO_FRACTION",
-                    "TO_FLOAT",
-                    "SIMPLIFY",
-                    "FRAC_ADD",
-                    "FRAC_SUB",
-                    "FRAC_MUL",
-                    "FRAC_DIV",
-                    "NUMERATOR",
-                    "DENOMINATOR",
-                    "GCD",
-                    "LCM",
-                ],
-            ),
-            ("输入输出", vec!["PRINT", "PRINTLN", "INPUT"]),
-            ("调试", vec!["TRACE"]),
-            (
-                "数组操作",
-                vec![
-                    "RANGE", "LEN", "PUSH", "POP", "REVERSE", "SORT", "SUM", "MAX", "MIN",
-                ],
-            ),
-            (
-                "字符串操作",
-                vec![
-                    "SPLIT",
-                    "UPPER",
-                    "LOWER",
-                    "TRIM",
-                    "CONTAINS",
-                    "STARTS_WITH",
-                    "ENDS_WITH",
-                    "REPLACE",
-                    "REPEAT",
-                    "JOIN",
-                ],
-            ),
-            (
-                "数学函数 - 基础",
-                vec!["ABS", "SQRT", "POW", "FLOOR", "CEIL", "ROUND"],
-            ),
-            (
-                "数学函数 - 三角",
-                vec!["SIN", "COS", "TAN", "ASIN", "ACOS", "ATAN", "ATAN2"],
-            ),
-            ("数学函数 - 对数", vec!["LOG", "LN", "LOG2", "EXP", "EXP2"]),
-            ("数学常数", vec!["PI", "E", "TAU", "PHI"]),
-            (
-                "统计分析",
-                vec!["MEAN", "MEDIAN", "VARIANCE", "STD", "QUANTILE"],
-            ),
-            (
-                "向量运算",
-                vec!["DOT", "NORM", "CROSS", "DISTANCE", "NORMALIZE"],
-            ),
-            (
-                "矩阵运算",
-                vec!["MATMUL", "TRANSPOSE", "DETERMINANT", "INVERSE"],
-            ),
-            ("线性回归", vec!["LINEAR_REGRESSION"]),
-            ("概率分布", vec!["NORMAL_PDF", "NORMAL_CDF", "POISSON_PMF"]),
-            (
-                "精度计算",
-                vec![
-                    "ROUND_TO",
-                    "ADD_WITH_PRECISION",
-                    "SUB_WITH_PRECISION",
-                    "MUL_WITH_PRECISION",
-                    "DIV_WITH_PRECISION",
-                ],
-            ),
-            ("类型转换", vec!["TYPE", "TO_STRING", "TO_NUMBER"]),
-            ("字典操作", vec!["KEYS", "VALUES", "HAS", "MERGE"]),
-        ];
-
-        for (category, funcs) in categories {
+        for (category, funcs) in CATEGORIES {
+            let documented: Vec<&FunctionDocData> =
+                funcs.iter().filter_map(|name| docs.get(*name)).collect();
+            if documented.is_empty() {
+                continue;
+            }
             output.push_str(&format!("【{}】\n", category));
-            for func_name in funcs {
-                if let Some(doc) = docs.get(func_name) {
-                    output.push_str(&format!("  {} - {}\n", doc.name, doc.description));
-                }
+            for doc in documented {
+                output.push_str(&format!("  {} - {}\n", doc.name, doc.description));
             }
             output.push('\n');
         }
@@ -694,6 +1208,10 @@ pub fn help(args: &[Value]) -> Result<Value, RuntimeError> {
                     output.push_str(&"=".repeat(50));
                     output.push_str(&format!("\n\n描述:\n  {}\n\n", doc.description));
 
+                    if let Some(spec) = super::args::spec_for(&func_name_upper) {
+                        output.push_str(&format!("签名:\n  {}\n\n", spec.signature()));
+                    }
+
                     if !doc.params.is_empty() {
                         output.push_str("参数:\n");
                         for (param_name, param_desc) in &doc.params {
@@ -714,6 +1232,16 @@ pub fn help(args: &[Value]) -> Result<Value, RuntimeError> {
                     output.push_str(&"=".repeat(50));
                     output.push('\n');
 
+                    Ok(Value::String(output))
+                } else if let Some(spec) = super::args::spec_for(&func_name_upper) {
+                    // 没有详细文档，但声明了参数规格
+                    let mut output = "=".repeat(50);
+                    output.push_str(&format!("\n函数: {}\n", spec.name));
+                    output.push_str(&"=".repeat(50));
+                    output.push_str(&format!("\n\n签名:\n  {}\n", spec.signature()));
+                    output.push_str(&"=".repeat(50));
+                    output.push('\n');
+
                     Ok(Value::String(output))
                 } else {
                     Err(RuntimeError::InvalidOperation(format!(
@@ -734,3 +1262,117 @@ pub fn help(args: &[Value]) -> Result<Value, RuntimeError> {
         })
     }
 }
+
+/// 构造参数描述字典
+fn param_dict(name: &str, description: &str, spec: Option<&Param>) -> Value {
+    let mut param = HashMap::new();
+    param.insert("name".to_string(), Value::String(name.to_string()));
+    param.insert(
+        "description".to_string(),
+        Value::String(description.to_string()),
+    );
+    if let Some(spec) = spec {
+        param.insert(
+            "type".to_string(),
+            Value::String(spec.ty.name().to_string()),
+        );
+        param.insert("optional".to_string(), Value::Boolean(spec.optional));
+    }
+    Value::Dict(param)
+}
+
+/// DOC 函数实现
+///
+/// 以结构化字典返回函数文档，便于 REPL 补全、编辑器悬停提示、
+/// 文档站点等以程序方式渲染。
+///
+/// 用法：
+/// - DOC() - 返回所有有文档的函数名（已排序）
+/// - DOC("函数名") - 返回 `{"name", "description", "params", "returns",
+///   "examples", "category", "signature"}`，函数不存在时返回 Null
+///
+/// 参数字典包含 `name`、`description`，声明了参数规格的函数
+/// 还包含 `type` 与 `optional`。
+pub fn doc(args: &[Value]) -> Result<Value, RuntimeError> {
+    let docs = FUNCTION_DOCS.get_or_init(init_docs);
+
+    let func_name = match args {
+        [] => {
+            let mut names: Vec<&String> = docs.keys().collect();
+            names.sort();
+            return Ok(Value::Array(
+                names
+                    .into_iter()
+                    .map(|n| Value::String(n.clone()))
+                    .collect(),
+            ));
+        }
+        [Value::String(name)] => name.to_uppercase(),
+        [other] => {
+            return Err(RuntimeError::TypeErrorDetailed {
+                expected: "String".to_string(),
+                got: other.type_name().to_string(),
+            });
+        }
+        _ => {
+            return Err(RuntimeError::WrongArity {
+                expected: 1,
+                got: args.len(),
+            });
+        }
+    };
+
+    let spec = super::args::spec_for(&func_name);
+    let doc = docs.get(&func_name);
+    if doc.is_none() && spec.is_none() {
+        return Ok(Value::Null);
+    }
+
+    let params: Vec<Value> = match (doc, spec) {
+        (Some(doc), _) => doc
+            .params
+            .iter()
+            .enumerate()
+            .map(|(i, (name, desc))| param_dict(name, desc, spec.and_then(|s| s.params.get(i))))
+            .collect(),
+        (None, Some(spec)) => spec
+            .params
+            .iter()
+            .chain(spec.rest.as_ref())
+            .map(|p| param_dict(p.name, "", Some(p)))
+            .collect(),
+        (None, None) => Vec::new(),
+    };
+
+    let signature = match spec {
+        Some(spec) => spec.signature(),
+        None => {
+            let names: Vec<&str> = doc
+                .map(|d| d.params.iter().map(|(p, _)| p.as_str()).collect())
+                .unwrap_or_default();
+            format!("{}({})", func_name, names.join(", "))
+        }
+    };
+
+    let text =
+        |f: fn(&FunctionDocData) -> &str| Value::String(doc.map(f).unwrap_or_default().to_string());
+    let examples = doc
+        .and_then(|d| d.example.as_deref())
+        .map(|e| e.lines().map(|l| Value::String(l.to_string())).collect())
+        .unwrap_or_default();
+
+    let mut result = HashMap::new();
+    result.insert("name".to_string(), Value::String(func_name.clone()));
+    result.insert("description".to_string(), text(|d| &d.description));
+    result.insert("params".to_string(), Value::Array(params));
+    result.insert("returns".to_string(), text(|d| &d.returns));
+    result.insert("examples".to_string(), Value::Array(examples));
+    result.insert(
+        "category".to_string(),
+        function_category(&func_name)
+            .map(|c| Value::String(c.to_string()))
+            .unwrap_or(Value::Null),
+    );
+    result.insert("signature".to_string(), Value::String(signature));
+    Ok(Value::Dict(result))
+}
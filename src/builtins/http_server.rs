@@ -0,0 +1,373 @@
+// src/builtins/http_server.rs
+//! 简单的 HTTP 服务（HTTP_SERVE）
+//!
+//! 让 webhook 接收端之类的小服务可以完全用 Aether 编写：
+//! `HTTP_SERVE(port, handler, options?)` 监听端口，把每个请求转换为字典交给
+//! Aether 处理函数，再把处理函数的返回值写回为 HTTP 响应。
+//!
+//! 服务逐个处理请求（每个连接一个请求，响应后关闭连接），默认只监听 127.0.0.1。
+//! 由于需要调用 Aether 函数，循环本身在求值器中实现，本模块负责请求解析与响应编码。
+//!
+//! 需要启用网络权限和 `http-server` 特性。
+
+use super::json::{FractionJsonMode, value_to_json};
+use crate::evaluator::RuntimeError;
+use crate::value::Value;
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::time::Duration;
+
+/// 读取单个请求的超时时间
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 请求头的最大长度
+const MAX_HEADER_LEN: usize = 64 * 1024;
+
+/// 请求体的最大长度
+const MAX_BODY_LEN: usize = 10 * 1024 * 1024;
+
+/// 响应：(状态码, 响应头, 响应体)
+pub(crate) type Response = (u16, Vec<(String, String)>, String);
+
+/// HTTP_SERVE 的选项
+pub(crate) struct ServeOptions {
+    /// 监听地址
+    pub host: String,
+    /// 处理指定数量的请求后返回（None 表示一直运行）
+    pub max_requests: Option<usize>,
+}
+
+/// 占位实现：HTTP_SERVE 需要调用 Aether 函数，由求值器处理
+pub fn http_serve(_args: &[Value]) -> Result<Value, RuntimeError> {
+    Err(RuntimeError::InvalidOperation(
+        "HTTP_SERVE requires function evaluation context".to_string(),
+    ))
+}
+
+/// 校验 HTTP_SERVE 的参数并绑定端口
+///
+/// 参数：端口、处理函数、可选的选项字典 `{"host": "127.0.0.1", "max_requests": N}`
+pub(crate) fn bind(args: &[Value]) -> Result<(TcpListener, ServeOptions), RuntimeError> {
+    if args.len() < 2 || args.len() > 3 {
+        return Err(RuntimeError::WrongArity {
+            expected: 2,
+            got: args.len(),
+        });
+    }
+    let port = match &args[0] {
+        Value::Number(n) if n.fract() == 0.0 && (0.0..=65535.0).contains(n) => *n as u16,
+        Value::Int(i) if (0..=65535).contains(i) => *i as u16,
+        other => {
+            return Err(RuntimeError::InvalidOperation(format!(
+                "Port must be an integer between 0 and 65535, got {}",
+                other
+            )));
+        }
+    };
+    if !matches!(args[1], Value::Function { .. } | Value::BuiltIn { .. }) {
+        return Err(RuntimeError::TypeErrorDetailed {
+            expected: "Function".to_string(),
+            got: format!("{:?}", args[1]),
+        });
+    }
+
+    let mut options = ServeOptions {
+        host: "127.0.0.1".to_string(),
+        max_requests: None,
+    };
+    match args.get(2) {
+        None | Some(Value::Null) => {}
+        Some(Value::Dict(dict)) => {
+            if let Some(host) = dict.get("host") {
+                options.host = match host {
+                    Value::String(s) => s.clone(),
+                    other => {
+                        return Err(RuntimeError::TypeErrorDetailed {
+                            expected: "String for option 'host'".to_string(),
+                            got: other.type_name().to_string(),
+                        });
+                    }
+                };
+            }
+            if let Some(max) = dict.get("max_requests") {
+                options.max_requests = match max {
+                    Value::Number(n) if n.fract() == 0.0 && *n >= 1.0 => Some(*n as usize),
+                    Value::Int(i) if *i >= 1 => Some(*i as usize),
+                    other => {
+                        return Err(RuntimeError::InvalidOperation(format!(
+                            "Option 'max_requests' must be a positive integer, got {}",
+                            other
+                        )));
+                    }
+                };
+            }
+        }
+        Some(other) => {
+            return Err(RuntimeError::TypeErrorDetailed {
+                expected: "Dict".to_string(),
+                got: format!("{:?}", other),
+            });
+        }
+    }
+
+    let listener = TcpListener::bind((options.host.as_str(), port)).map_err(|e| {
+        RuntimeError::CustomError(format!(
+            "Failed to listen on {}:{}: {}",
+            options.host, port, e
+        ))
+    })?;
+    Ok((listener, options))
+}
+
+/// URL 百分号解码（`+` 解码为空格）
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let hex = |b: u8| (b as char).to_digit(16).map(|d| d as u8);
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' if i + 2 < bytes.len() => match (hex(bytes[i + 1]), hex(bytes[i + 2])) {
+                (Some(high), Some(low)) => {
+                    out.push(high * 16 + low);
+                    i += 2;
+                }
+                _ => out.push(b'%'),
+            },
+            byte => out.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// 解析查询字符串为字典
+fn parse_query(query: &str) -> HashMap<String, Value> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(key), Value::String(percent_decode(value)))
+        })
+        .collect()
+}
+
+/// 读取并解析一个请求
+///
+/// 返回请求字典：`{"method", "path", "query", "headers", "body"}`，
+/// 请求头名称为小写。
+pub(crate) fn read_request(stream: &mut TcpStream) -> Result<Value, String> {
+    stream
+        .set_read_timeout(Some(REQUEST_TIMEOUT))
+        .map_err(|e| e.to_string())?;
+
+    let mut data = Vec::new();
+    let mut chunk = [0u8; 4096];
+    let header_end = loop {
+        if let Some(pos) = data.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos;
+        }
+        if data.len() > MAX_HEADER_LEN {
+            return Err("request headers too large".to_string());
+        }
+        match stream.read(&mut chunk) {
+            Ok(0) => return Err("connection closed before request was complete".to_string()),
+            Ok(n) => data.extend_from_slice(&chunk[..n]),
+            Err(e) => return Err(e.to_string()),
+        }
+    };
+
+    let head = String::from_utf8_lossy(&data[..header_end]).into_owned();
+    let mut lines = head.split("\r\n");
+    let request_line = lines.next().unwrap_or_default();
+    let mut parts = request_line.split_whitespace();
+    let (method, target) = match (parts.next(), parts.next()) {
+        (Some(method), Some(target)) => (method.to_uppercase(), target.to_string()),
+        _ => return Err(format!("invalid request line '{}'", request_line)),
+    };
+    let headers: HashMap<String, Value> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| {
+            (
+                name.trim().to_lowercase(),
+                Value::String(value.trim().to_string()),
+            )
+        })
+        .collect();
+
+    let content_length = match headers.get("content-length") {
+        Some(Value::String(len)) => len
+            .parse::<usize>()
+            .map_err(|_| format!("invalid Content-Length '{}'", len))?,
+        _ => 0,
+    };
+    if content_length > MAX_BODY_LEN {
+        return Err("request body too large".to_string());
+    }
+    let mut body = data[header_end + 4..].to_vec();
+    while body.len() < content_length {
+        match stream.read(&mut chunk) {
+            Ok(0) => return Err("connection closed before body was complete".to_string()),
+            Ok(n) => body.extend_from_slice(&chunk[..n]),
+            Err(e) => return Err(e.to_string()),
+        }
+    }
+    body.truncate(content_length);
+
+    let (path, query) = target.split_once('?').unwrap_or((&target, ""));
+    let mut request = HashMap::new();
+    request.insert("method".to_string(), Value::String(method));
+    request.insert("path".to_string(), Value::String(percent_decode(path)));
+    request.insert("query".to_string(), Value::Dict(parse_query(query)));
+    request.insert("headers".to_string(), Value::Dict(headers));
+    request.insert(
+        "body".to_string(),
+        Value::String(String::from_utf8_lossy(&body).into_owned()),
+    );
+    Ok(Value::Dict(request))
+}
+
+/// 状态码的原因短语
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        202 => "Accepted",
+        204 => "No Content",
+        301 => "Moved Permanently",
+        302 => "Found",
+        304 => "Not Modified",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        409 => "Conflict",
+        422 => "Unprocessable Entity",
+        429 => "Too Many Requests",
+        500 => "Internal Server Error",
+        502 => "Bad Gateway",
+        503 => "Service Unavailable",
+        _ => "",
+    }
+}
+
+/// 将值编码为响应体，返回 (内容, 默认 Content-Type)
+fn encode_body(body: &Value) -> Result<(String, &'static str), RuntimeError> {
+    match body {
+        Value::String(s) => Ok((s.clone(), "text/plain; charset=utf-8")),
+        Value::Null => Ok((String::new(), "text/plain; charset=utf-8")),
+        other => Ok((
+            value_to_json(other, FractionJsonMode::Float)?.to_string(),
+            "application/json",
+        )),
+    }
+}
+
+/// 将处理函数的返回值转换为 (状态码, 响应头, 响应体)
+///
+/// - Dict 且包含 `status` / `body` / `headers` 中任意键：按字段构造响应
+/// - String：200 纯文本；Null：204；其他值：200 JSON
+pub(crate) fn build_response(result: &Value) -> Result<Response, RuntimeError> {
+    let (status, body, extra) = match result {
+        Value::Dict(dict)
+            if ["status", "body", "headers"]
+                .iter()
+                .any(|key| dict.contains_key(*key)) =>
+        {
+            let status = match dict.get("status") {
+                None => 200,
+                Some(Value::Number(n)) if n.fract() == 0.0 && (100.0..=599.0).contains(n) => {
+                    *n as u16
+                }
+                Some(Value::Int(i)) if (100..=599).contains(i) => *i as u16,
+                Some(other) => {
+                    return Err(RuntimeError::InvalidOperation(format!(
+                        "Response status must be an integer between 100 and 599, got {}",
+                        other
+                    )));
+                }
+            };
+            let headers = match dict.get("headers") {
+                None | Some(Value::Null) => Vec::new(),
+                Some(Value::Dict(headers)) => {
+                    let mut headers: Vec<(String, String)> = headers
+                        .iter()
+                        .map(|(name, value)| match value {
+                            Value::String(s) => (name.clone(), s.clone()),
+                            other => (name.clone(), other.to_string()),
+                        })
+                        .collect();
+                    headers.sort();
+                    headers
+                }
+                Some(other) => {
+                    return Err(RuntimeError::TypeErrorDetailed {
+                        expected: "Dict for response headers".to_string(),
+                        got: other.type_name().to_string(),
+                    });
+                }
+            };
+            (
+                status,
+                dict.get("body").cloned().unwrap_or(Value::Null),
+                headers,
+            )
+        }
+        Value::Null => (204, Value::Null, Vec::new()),
+        other => (200, other.clone(), Vec::new()),
+    };
+
+    if extra
+        .iter()
+        .any(|(name, value)| name.contains(['\r', '\n']) || value.contains(['\r', '\n']))
+    {
+        return Err(RuntimeError::InvalidOperation(
+            "Response header names and values must not contain line breaks".to_string(),
+        ));
+    }
+
+    let (body, content_type) = encode_body(&body)?;
+    let mut headers = extra;
+    if !headers
+        .iter()
+        .any(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+        && !body.is_empty()
+    {
+        headers.push(("Content-Type".to_string(), content_type.to_string()));
+    }
+    Ok((status, headers, body))
+}
+
+/// 写出响应并关闭连接
+pub(crate) fn write_response(
+    stream: &mut TcpStream,
+    status: u16,
+    headers: &[(String, String)],
+    body: &str,
+) {
+    let mut response = format!("HTTP/1.1 {} {}\r\n", status, reason_phrase(status));
+    for (name, value) in headers {
+        response.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    response.push_str(&format!(
+        "Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+        body.len(),
+        body
+    ));
+    // 客户端可能已经断开，写入失败不影响后续请求
+    let _ = stream.write_all(response.as_bytes());
+    let _ = stream.flush();
+    let _ = stream.shutdown(std::net::Shutdown::Both);
+}
+
+/// 返回 HTTP_SERVE 的运行统计
+pub(crate) fn summary(requests: usize, errors: usize) -> Value {
+    let mut dict = HashMap::new();
+    dict.insert("requests".to_string(), Value::Number(requests as f64));
+    dict.insert("errors".to_string(), Value::Number(errors as f64));
+    Value::Dict(dict)
+}
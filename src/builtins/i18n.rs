@@ -0,0 +1,546 @@
+// src/builtins/i18n.rs
+//! 国际化（i18n）
+//!
+//! 为脚本生成的面向用户的文本（报表、工资条等）提供多语言支持：
+//! - `LOAD_MESSAGES` 从 JSON 加载消息目录
+//! - `TRANSLATE` 按当前语言查找消息，支持 `{name}` 参数替换和复数形式
+//! - `PLURAL_CATEGORY` 按 CLDR 复数规则计算数量对应的复数类别
+//!
+//! 消息目录与当前语言属于各自的引擎，不影响同一线程上的其他引擎，
+//! `reset_env` 时清空。求值期间通过线程局部存储传递给这些函数，
+//! 与薪酬设置（`payroll::settings`）相同；不在求值中调用时使用一份临时的默认设置。
+
+use crate::evaluator::RuntimeError;
+use crate::value::Value;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// 默认语言
+const DEFAULT_LOCALE: &str = "en";
+
+/// CLDR 复数类别
+const PLURAL_CATEGORIES: &[&str] = &["zero", "one", "two", "few", "many", "other"];
+
+/// 单条消息
+#[derive(Debug, Clone, PartialEq)]
+enum Message {
+    /// 普通文本
+    Text(String),
+    /// 复数形式（类别 -> 文本），必须包含 "other"
+    Plural(HashMap<String, String>),
+}
+
+/// 一个引擎的国际化设置
+#[derive(Debug, Clone, PartialEq)]
+pub struct I18nSettings {
+    /// 消息目录（语言 -> 键 -> 消息）
+    catalogs: HashMap<String, HashMap<String, Message>>,
+    /// 当前语言
+    locale: String,
+}
+
+impl Default for I18nSettings {
+    fn default() -> Self {
+        Self {
+            catalogs: HashMap::new(),
+            locale: DEFAULT_LOCALE.to_string(),
+        }
+    }
+}
+
+/// 引擎持有的国际化设置
+pub(crate) type SharedI18nSettings = Rc<RefCell<I18nSettings>>;
+
+// 线程局部的当前引擎设置（求值期间有效）
+thread_local! {
+    static ACTIVE_SETTINGS: RefCell<Option<SharedI18nSettings>> = const { RefCell::new(None) };
+}
+
+/// 在作用域内设置当前引擎的国际化设置（RAII 模式，结束时恢复之前的设置）
+pub(crate) struct ScopedI18nSettings {
+    previous: Option<SharedI18nSettings>,
+}
+
+impl ScopedI18nSettings {
+    pub(crate) fn set(settings: SharedI18nSettings) -> Self {
+        let previous = ACTIVE_SETTINGS.with(|s| s.borrow_mut().replace(settings));
+        Self { previous }
+    }
+}
+
+impl Drop for ScopedI18nSettings {
+    fn drop(&mut self) {
+        ACTIVE_SETTINGS.with(|s| *s.borrow_mut() = self.previous.take());
+    }
+}
+
+/// 读取或修改当前引擎的国际化设置
+///
+/// 不在求值中时作用于一份临时的默认设置，修改不会保留。
+fn with_settings<R>(f: impl FnOnce(&mut I18nSettings) -> R) -> R {
+    match ACTIVE_SETTINGS.with(|s| s.borrow().clone()) {
+        Some(settings) => f(&mut settings.borrow_mut()),
+        None => f(&mut I18nSettings::default()),
+    }
+}
+
+/// 规范化语言标签：`zh_cn` → `zh-CN`
+fn normalize_locale(locale: &str) -> String {
+    let mut parts = locale.trim().split(['-', '_']);
+    let language = parts.next().unwrap_or("").to_lowercase();
+    let rest: Vec<String> = parts
+        .map(|p| {
+            if p.len() == 2 {
+                p.to_uppercase()
+            } else {
+                p.to_string()
+            }
+        })
+        .collect();
+    std::iter::once(language)
+        .chain(rest)
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// 语言回退链：`zh-Hans-CN` → `zh-Hans` → `zh` → 默认语言
+fn fallback_chain(locale: &str) -> Vec<String> {
+    let mut chain = Vec::new();
+    let mut current = locale.to_string();
+    loop {
+        chain.push(current.clone());
+        match current.rfind('-') {
+            Some(pos) => current.truncate(pos),
+            None => break,
+        }
+    }
+    if !chain.iter().any(|l| l == DEFAULT_LOCALE) {
+        chain.push(DEFAULT_LOCALE.to_string());
+    }
+    chain
+}
+
+/// 按 CLDR 规则计算复数类别（覆盖常用语言，其他语言按英语规则）
+pub(crate) fn plural_category(n: f64, locale: &str) -> &'static str {
+    let language = locale.split('-').next().unwrap_or("").to_lowercase();
+    let abs = n.abs();
+    let is_int = abs.fract() == 0.0;
+    let i = abs.trunc() as u64;
+    let mod10 = i % 10;
+    let mod100 = i % 100;
+
+    match language.as_str() {
+        // 无复数变化
+        "zh" | "ja" | "ko" | "vi" | "th" | "id" | "ms" | "lo" | "my" => "other",
+        // 0 和 1 为单数
+        "fr" | "pt" | "hi" | "bn" | "fa" => {
+            if i == 0 || i == 1 {
+                "one"
+            } else {
+                "other"
+            }
+        }
+        "ru" | "uk" | "be" => {
+            if !is_int {
+                "other"
+            } else if mod10 == 1 && mod100 != 11 {
+                "one"
+            } else if (2..=4).contains(&mod10) && !(12..=14).contains(&mod100) {
+                "few"
+            } else {
+                "many"
+            }
+        }
+        "pl" => {
+            if !is_int {
+                "other"
+            } else if i == 1 {
+                "one"
+            } else if (2..=4).contains(&mod10) && !(12..=14).contains(&mod100) {
+                "few"
+            } else {
+                "many"
+            }
+        }
+        "cs" | "sk" => {
+            if !is_int {
+                "many"
+            } else if i == 1 {
+                "one"
+            } else if (2..=4).contains(&i) {
+                "few"
+            } else {
+                "other"
+            }
+        }
+        "ar" => {
+            if !is_int {
+                "other"
+            } else if i == 0 {
+                "zero"
+            } else if i == 1 {
+                "one"
+            } else if i == 2 {
+                "two"
+            } else if (3..=10).contains(&mod100) {
+                "few"
+            } else if (11..=99).contains(&mod100) {
+                "many"
+            } else {
+                "other"
+            }
+        }
+        // 英语、德语、西班牙语等：只有整数 1 为单数
+        _ => {
+            if is_int && i == 1 {
+                "one"
+            } else {
+                "other"
+            }
+        }
+    }
+}
+
+/// 解析单条消息：字符串，或以复数类别为键的字典
+fn parse_message(key: &str, value: &serde_json::Value) -> Result<Option<Message>, RuntimeError> {
+    match value {
+        serde_json::Value::String(s) => Ok(Some(Message::Text(s.clone()))),
+        serde_json::Value::Object(map)
+            if !map.is_empty() && map.keys().all(|k| PLURAL_CATEGORIES.contains(&k.as_str())) =>
+        {
+            if !map.contains_key("other") {
+                return Err(RuntimeError::InvalidOperation(format!(
+                    "Plural message '{}' must define an \"other\" form",
+                    key
+                )));
+            }
+            let forms = map
+                .iter()
+                .map(|(category, text)| match text.as_str() {
+                    Some(s) => Ok((category.clone(), s.to_string())),
+                    None => Err(RuntimeError::InvalidOperation(format!(
+                        "Plural form '{}' of message '{}' must be a string",
+                        category, key
+                    ))),
+                })
+                .collect::<Result<_, _>>()?;
+            Ok(Some(Message::Plural(forms)))
+        }
+        // 嵌套对象作为命名空间展开
+        serde_json::Value::Object(_) => Ok(None),
+        other => Err(RuntimeError::InvalidOperation(format!(
+            "Message '{}' must be a string or an object, got {}",
+            key, other
+        ))),
+    }
+}
+
+/// 展开嵌套的消息对象（`{"menu": {"open": ...}}` → `menu.open`）
+fn flatten_messages(
+    prefix: &str,
+    object: &serde_json::Map<String, serde_json::Value>,
+    out: &mut HashMap<String, Message>,
+) -> Result<(), RuntimeError> {
+    for (key, value) in object {
+        let full_key = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+        match parse_message(&full_key, value)? {
+            Some(message) => {
+                out.insert(full_key, message);
+            }
+            None => {
+                if let serde_json::Value::Object(nested) = value {
+                    flatten_messages(&full_key, nested, out)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 替换消息中的 `{name}` 参数，未提供的参数保持原样
+fn interpolate(template: &str, params: &HashMap<String, Value>) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        match after.find('}') {
+            Some(end) => {
+                let name = after[..end].trim();
+                match params.get(name) {
+                    Some(value) => out.push_str(&value.to_string()),
+                    None => out.push_str(&rest[start..start + end + 2]),
+                }
+                rest = &after[end + 1..];
+            }
+            None => {
+                out.push_str(&rest[start..]);
+                rest = "";
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// 读取语言参数
+pub(crate) fn locale_arg(value: &Value) -> Result<String, RuntimeError> {
+    match value {
+        Value::String(s) if !s.trim().is_empty() => Ok(normalize_locale(s)),
+        other => Err(RuntimeError::TypeErrorDetailed {
+            expected: "String (locale)".to_string(),
+            got: other.type_name().to_string(),
+        }),
+    }
+}
+
+/// 当前语言
+pub fn current_locale() -> String {
+    with_settings(|settings| settings.locale.clone())
+}
+
+/// 加载消息目录
+///
+/// # 功能
+/// 从 JSON 文本或字典加载消息目录，与已加载的同名消息合并（后加载的覆盖）。
+/// 嵌套对象按 `.` 展开为键；以复数类别（zero/one/two/few/many/other）为键的
+/// 对象表示复数消息，必须包含 `other`。
+///
+/// # 参数
+/// - `messages`: String/Dict - JSON 文本或字典
+/// - `locale`: String - 可选。提供时 messages 为该语言的消息；
+///   省略时 messages 顶层以语言为键：`{"en": {...}, "zh-CN": {...}}`
+///
+/// # 返回值
+/// Number - 加载的消息条数
+///
+/// # 示例
+/// ```aether
+/// LOAD_MESSAGES("{\"zh-CN\": {\"greeting\": \"你好，{name}\"}}")
+/// LOAD_MESSAGES({"items": {"one": "{count} item", "other": "{count} items"}}, "en")
+/// ```
+pub fn load_messages(args: &[Value]) -> Result<Value, RuntimeError> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(RuntimeError::WrongArity {
+            expected: 1,
+            got: args.len(),
+        });
+    }
+
+    let json = match &args[0] {
+        Value::String(text) => serde_json::from_str::<serde_json::Value>(text).map_err(|e| {
+            RuntimeError::InvalidOperation(format!("Invalid message catalog JSON: {}", e))
+        })?,
+        value @ Value::Dict(_) => super::json::value_to_json(value, Default::default())?,
+        other => {
+            return Err(RuntimeError::TypeErrorDetailed {
+                expected: "String (JSON) or Dict".to_string(),
+                got: other.type_name().to_string(),
+            });
+        }
+    };
+    let serde_json::Value::Object(root) = json else {
+        return Err(RuntimeError::InvalidOperation(
+            "Message catalog must be a JSON object".to_string(),
+        ));
+    };
+
+    let mut by_locale: Vec<(String, HashMap<String, Message>)> = Vec::new();
+    match args.get(1) {
+        Some(locale) => {
+            let mut messages = HashMap::new();
+            flatten_messages("", &root, &mut messages)?;
+            by_locale.push((locale_arg(locale)?, messages));
+        }
+        None => {
+            for (locale, entries) in &root {
+                let serde_json::Value::Object(entries) = entries else {
+                    return Err(RuntimeError::InvalidOperation(format!(
+                        "Messages for locale '{}' must be an object",
+                        locale
+                    )));
+                };
+                let mut messages = HashMap::new();
+                flatten_messages("", entries, &mut messages)?;
+                by_locale.push((normalize_locale(locale), messages));
+            }
+        }
+    }
+
+    let count: usize = by_locale.iter().map(|(_, m)| m.len()).sum();
+    with_settings(|settings| {
+        for (locale, messages) in by_locale {
+            settings
+                .catalogs
+                .entry(locale)
+                .or_default()
+                .extend(messages);
+        }
+    });
+    Ok(Value::Number(count as f64))
+}
+
+/// 翻译消息
+///
+/// # 功能
+/// 按语言查找消息并替换 `{name}` 参数。查找顺序为语言回退链
+/// （如 `zh-CN` → `zh` → `en`），都找不到时返回键本身。
+/// 参数中包含数字 `count` 且消息为复数消息时，按复数规则选择形式。
+///
+/// # 参数
+/// - `key`: String - 消息键
+/// - `locale`: String - 可选，语言（默认为 SET_LOCALE 设置的当前语言）
+/// - `params`: Dict - 可选，替换参数
+///
+/// 第二个参数为字典时视为 params，使用当前语言。
+///
+/// # 返回值
+/// String - 翻译后的文本
+///
+/// # 示例
+/// ```aether
+/// TRANSLATE("greeting", "zh-CN", {"name": "张三"})    # "你好，张三"
+/// TRANSLATE("items", {"count": 3})                    # "3 items"
+/// ```
+pub fn translate(args: &[Value]) -> Result<Value, RuntimeError> {
+    if args.is_empty() || args.len() > 3 {
+        return Err(RuntimeError::WrongArity {
+            expected: 1,
+            got: args.len(),
+        });
+    }
+
+    let key = match &args[0] {
+        Value::String(k) => k,
+        other => {
+            return Err(RuntimeError::TypeErrorDetailed {
+                expected: "String (message key)".to_string(),
+                got: other.type_name().to_string(),
+            });
+        }
+    };
+
+    let empty = HashMap::new();
+    let (locale, params) = match (args.get(1), args.get(2)) {
+        (None, _) | (Some(Value::Null), None) => (current_locale(), &empty),
+        (Some(Value::Dict(params)), None) => (current_locale(), params),
+        (Some(locale), None) => (locale_arg(locale)?, &empty),
+        (Some(locale), Some(Value::Dict(params))) => {
+            let locale = match locale {
+                Value::Null => current_locale(),
+                other => locale_arg(other)?,
+            };
+            (locale, params)
+        }
+        (Some(_), Some(other)) => {
+            return Err(RuntimeError::TypeErrorDetailed {
+                expected: "Dict (params)".to_string(),
+                got: other.type_name().to_string(),
+            });
+        }
+    };
+
+    let found = with_settings(|settings| {
+        fallback_chain(&locale).into_iter().find_map(|l| {
+            settings
+                .catalogs
+                .get(&l)
+                .and_then(|m| m.get(key))
+                .map(|message| (l, message.clone()))
+        })
+    });
+
+    let text = match found {
+        None => return Ok(Value::String(key.clone())),
+        Some((_, Message::Text(text))) => text,
+        Some((found_locale, Message::Plural(forms))) => {
+            let count = params.get("count").and_then(Value::to_number);
+            let category = count.map_or("other", |n| plural_category(n, &found_locale));
+            forms
+                .get(category)
+                .or_else(|| forms.get("other"))
+                .cloned()
+                .unwrap_or_default()
+        }
+    };
+    Ok(Value::String(interpolate(&text, params)))
+}
+
+/// 复数类别
+///
+/// # 功能
+/// 按 CLDR 复数规则返回数量对应的复数类别。
+///
+/// # 参数
+/// - `n`: Number - 数量
+/// - `locale`: String - 可选，语言（默认当前语言）
+///
+/// # 返回值
+/// String - "zero"、"one"、"two"、"few"、"many" 或 "other"
+///
+/// # 示例
+/// ```aether
+/// PLURAL_CATEGORY(1, "en")     # "one"
+/// PLURAL_CATEGORY(3, "ru")     # "few"
+/// PLURAL_CATEGORY(1, "zh")     # "other"
+/// ```
+pub fn plural(args: &[Value]) -> Result<Value, RuntimeError> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(RuntimeError::WrongArity {
+            expected: 1,
+            got: args.len(),
+        });
+    }
+
+    let n = match &args[0] {
+        Value::Number(n) => *n,
+        Value::Fraction(_) => args[0].to_number().unwrap_or(f64::NAN),
+        other => {
+            return Err(RuntimeError::TypeErrorDetailed {
+                expected: "Number".to_string(),
+                got: other.type_name().to_string(),
+            });
+        }
+    };
+    let locale = match args.get(1) {
+        Some(locale) => locale_arg(locale)?,
+        None => current_locale(),
+    };
+    Ok(Value::String(plural_category(n, &locale).to_string()))
+}
+
+/// 设置当前语言
+///
+/// # 参数
+/// - `locale`: String - 语言标签，如 "zh-CN"、"en"
+///
+/// # 返回值
+/// String - 之前的语言
+pub fn set_locale(args: &[Value]) -> Result<Value, RuntimeError> {
+    if args.len() != 1 {
+        return Err(RuntimeError::WrongArity {
+            expected: 1,
+            got: args.len(),
+        });
+    }
+
+    let locale = locale_arg(&args[0])?;
+    let previous = with_settings(|settings| std::mem::replace(&mut settings.locale, locale));
+    Ok(Value::String(previous))
+}
+
+/// 获取当前语言
+pub fn get_locale(_args: &[Value]) -> Result<Value, RuntimeError> {
+    Ok(Value::String(current_locale()))
+}
+
+/// 清空当前引擎的消息目录
+pub fn clear_messages(_args: &[Value]) -> Result<Value, RuntimeError> {
+    with_settings(|settings| settings.catalogs.clear());
+    Ok(Value::Null)
+}
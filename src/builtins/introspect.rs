@@ -0,0 +1,24 @@
+// src/builtins/introspect.rs
+//
+// 运行时环境自省内置函数。
+//
+// 注意：这些函数需要访问当前环境，在 evaluator 中有特殊处理。
+
+use crate::evaluator::RuntimeError;
+use crate::value::Value;
+
+/// VARS - 返回当前作用域可见的变量（不含内置函数）
+///
+/// 用法: VARS() -> {"NAME": "Number", ...}
+pub fn vars(_args: &[Value]) -> Result<Value, RuntimeError> {
+    // 在 evaluator 中有特殊处理
+    Ok(Value::Null)
+}
+
+/// GLOBALS - 返回全局作用域中的变量（不含内置函数）
+///
+/// 用法: GLOBALS() -> {"NAME": "Number", ...}
+pub fn globals(_args: &[Value]) -> Result<Value, RuntimeError> {
+    // 在 evaluator 中有特殊处理
+    Ok(Value::Null)
+}
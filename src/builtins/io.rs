@@ -3,10 +3,75 @@
 //!
 //! 提供基础的输入输出功能，包括打印和读取用户输入。
 
+use super::args::{ArgSpec, ArgType, Param};
 use crate::evaluator::RuntimeError;
 use crate::value::Value;
+use std::cell::RefCell;
 use std::io::{self, Write};
 
+const PRINT: ArgSpec = ArgSpec::variadic("PRINT", &[], Param::optional("values", ArgType::Any));
+const PRINTLN: ArgSpec = ArgSpec::variadic("PRINTLN", &[], Param::optional("values", ArgType::Any));
+const INPUT: ArgSpec = ArgSpec::new("INPUT", &[Param::required("prompt", ArgType::Any)]);
+
+/// 本模块声明了参数规格的函数
+pub(crate) const ARG_SPECS: &[ArgSpec] = &[PRINT, PRINTLN, INPUT];
+
+/// 宿主捕获的一段输出
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum CapturedOutput {
+    /// PRINT / PRINTLN 写出的文本（相邻的文本会合并）
+    Stdout(String),
+    /// DISPLAY 的值
+    Display(Value),
+}
+
+thread_local! {
+    /// 当前线程的输出捕获（None 表示直接写 stdout）
+    static CAPTURE: RefCell<Option<Vec<CapturedOutput>>> = const { RefCell::new(None) };
+}
+
+/// 开始捕获当前线程的输出（如 Jupyter 内核把输出转发给前端）
+pub(crate) fn begin_capture() {
+    CAPTURE.with(|c| *c.borrow_mut() = Some(Vec::new()));
+}
+
+/// 结束捕获并按输出顺序返回捕获的内容
+pub(crate) fn end_capture() -> Vec<CapturedOutput> {
+    CAPTURE.with(|c| c.borrow_mut().take().unwrap_or_default())
+}
+
+/// 写出文本：捕获中时写入缓冲区，否则写 stdout
+pub(crate) fn write_stdout(text: &str) {
+    let text = &crate::runtime::secrets::redact_output(text);
+    let captured = CAPTURE.with(|c| match c.borrow_mut().as_mut() {
+        Some(outputs) => {
+            match outputs.last_mut() {
+                Some(CapturedOutput::Stdout(buffer)) => buffer.push_str(text),
+                _ => outputs.push(CapturedOutput::Stdout(text.to_string())),
+            }
+            true
+        }
+        None => false,
+    });
+    if !captured {
+        print!("{}", text);
+        io::stdout().flush().unwrap();
+    }
+}
+
+/// 记录一个富输出值；未捕获时返回 false，由调用方自行输出
+pub(crate) fn push_display(value: &Value) -> bool {
+    CAPTURE.with(|c| match c.borrow_mut().as_mut() {
+        Some(outputs) => {
+            outputs.push(CapturedOutput::Display(
+                crate::runtime::secrets::redact_output_value(value),
+            ));
+            true
+        }
+        None => false,
+    })
+}
+
 /// 打印值（不换行）
 ///
 /// # 功能
@@ -38,8 +103,7 @@ pub fn print(args: &[Value]) -> Result<Value, RuntimeError> {
         .collect::<Vec<_>>()
         .join(" ");
 
-    print!("{}", output);
-    io::stdout().flush().unwrap();
+    write_stdout(&output);
     Ok(Value::Null)
 }
 
@@ -64,7 +128,7 @@ pub fn print(args: &[Value]) -> Result<Value, RuntimeError> {
 /// ```
 pub fn println(args: &[Value]) -> Result<Value, RuntimeError> {
     if args.is_empty() {
-        println!();
+        write_stdout("\n");
         return Ok(Value::Null);
     }
 
@@ -75,7 +139,7 @@ pub fn println(args: &[Value]) -> Result<Value, RuntimeError> {
         .collect::<Vec<_>>()
         .join(" ");
 
-    println!("{}", output);
+    write_stdout(&format!("{}\n", output));
     Ok(Value::Null)
 }
 
@@ -97,12 +161,7 @@ pub fn println(args: &[Value]) -> Result<Value, RuntimeError> {
 /// Println("你好, " + NAME)
 /// ```
 pub fn input(args: &[Value]) -> Result<Value, RuntimeError> {
-    if args.is_empty() {
-        return Err(RuntimeError::WrongArity {
-            expected: 1,
-            got: 0,
-        });
-    }
+    INPUT.check(args)?;
 
     // Print prompt
     print!("{}", args[0].to_string());
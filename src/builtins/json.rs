@@ -23,9 +23,12 @@ use std::collections::HashMap;
 /// - JSON object → Dict
 /// - JSON array → Array
 /// - JSON string → String
-/// - JSON number → Number
+/// - JSON 整数（i64 范围内）→ Integer
+/// - 其他 JSON number → Number
 /// - JSON boolean → Boolean
 /// - JSON null → Null
+/// - `{"$fraction": "1/3"}` → Fraction（精确分数）
+/// - `{"$bigint": "123..."}` → 大整数（分母为 1 的 Fraction）
 ///
 /// # 示例
 /// ```aether
@@ -66,7 +69,13 @@ pub fn json_parse(args: &[Value]) -> Result<Value, RuntimeError> {
 ///
 /// # 参数
 /// - `value`: 要序列化的值
-/// - `indent`: （可选）缩进空格数，默认为 0（紧凑格式）
+/// - `indent`: （可选）缩进空格数，默认为 0（紧凑格式）；也可传 `True` 表示格式化输出
+/// - `options`: （可选）选项字典，可代替或跟在 indent 后：
+///   - `indent`: 缩进空格数
+///   - `fraction`: Fraction/大整数的序列化方式
+///     - `"float"`（默认）：转换为浮点数，可能丢失精度
+///     - `"tagged"`：`{"$fraction": "1/3"}` / `{"$bigint": "..."}`，JSON_PARSE 可无损还原
+///     - `"string"`：`"1/3"` / `"123..."`
 ///
 /// # 返回值
 /// JSON 格式的字符串
@@ -79,9 +88,14 @@ pub fn json_parse(args: &[Value]) -> Result<Value, RuntimeError> {
 ///
 /// # 格式化输出（2空格缩进）
 /// Set PRETTY JSON_STRINGIFY(OBJ, 2)
+/// Set PRETTY JSON_STRINGIFY(OBJ, True)
+///
+/// # 精确保存分数
+/// Set S JSON_STRINGIFY({"rate": TO_FRACTION(1/3)}, {"fraction": "tagged"})
+/// # {"rate":{"$fraction":"1/3"}}
 /// ```
 pub fn json_stringify(args: &[Value]) -> Result<Value, RuntimeError> {
-    if args.is_empty() || args.len() > 2 {
+    if args.is_empty() || args.len() > 3 {
         return Err(RuntimeError::WrongArity {
             expected: 1,
             got: args.len(),
@@ -89,22 +103,38 @@ pub fn json_stringify(args: &[Value]) -> Result<Value, RuntimeError> {
     }
 
     let value = &args[0];
-    let indent = if args.len() == 2 {
-        match &args[1] {
-            Value::Number(n) => *n as usize,
+    let mut indent = 0;
+    let mut mode = FractionJsonMode::default();
+    for arg in &args[1..] {
+        match arg {
+            Value::Number(_) | Value::Int(_) => indent = arg.to_number().unwrap_or(0.0) as usize,
+            Value::Boolean(pretty) => indent = if *pretty { 2 } else { 0 },
+            Value::Dict(options) => {
+                if let Some(n @ (Value::Number(_) | Value::Int(_))) = options.get("indent") {
+                    indent = n.to_number().unwrap_or(0.0) as usize;
+                }
+                match options.get("fraction") {
+                    Some(Value::String(name)) => mode = FractionJsonMode::parse(name)?,
+                    Some(other) => {
+                        return Err(RuntimeError::TypeErrorDetailed {
+                            expected: "String for option 'fraction'".to_string(),
+                            got: other.type_name().to_string(),
+                        });
+                    }
+                    None => {}
+                }
+            }
             other => {
                 return Err(RuntimeError::TypeErrorDetailed {
-                    expected: "Number".to_string(),
+                    expected: "Number, Boolean or Dict".to_string(),
                     got: format!("{:?}", other),
                 });
             }
         }
-    } else {
-        0
-    };
+    }
 
     // 转换为 serde_json::Value
-    let json_value = value_to_json(value)?;
+    let json_value = value_to_json(value, mode)?;
 
     // 序列化
     let json_str = if indent > 0 {
@@ -117,14 +147,80 @@ pub fn json_stringify(args: &[Value]) -> Result<Value, RuntimeError> {
     Ok(Value::String(json_str))
 }
 
+/// 标记分数的 JSON 键：`{"$fraction": "1/3"}`
+pub const FRACTION_TAG: &str = "$fraction";
+
+/// 标记大整数的 JSON 键：`{"$bigint": "123456789012345678901234567890"}`
+pub const BIGINT_TAG: &str = "$bigint";
+
+/// Fraction（以及以分母为 1 的分数表示的大整数）的 JSON 序列化方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FractionJsonMode {
+    /// 转换为浮点数（默认，兼容旧行为，可能丢失精度）
+    #[default]
+    Float,
+    /// 带标记的对象 `{"$fraction": "1/3"}` / `{"$bigint": "..."}`，JSON_PARSE 可无损还原
+    Tagged,
+    /// 字符串 `"1/3"` / `"123..."`，便于阅读，但解析后为 String
+    String,
+}
+
+impl FractionJsonMode {
+    fn parse(name: &str) -> Result<Self, RuntimeError> {
+        match name.to_lowercase().as_str() {
+            "float" => Ok(FractionJsonMode::Float),
+            "tagged" => Ok(FractionJsonMode::Tagged),
+            "string" => Ok(FractionJsonMode::String),
+            _ => Err(RuntimeError::InvalidOperation(format!(
+                "Unknown fraction mode '{}', expected float, tagged or string",
+                name
+            ))),
+        }
+    }
+}
+
+/// 解析带标记的分数/大整数对象
+fn tagged_to_value(
+    obj: &serde_json::Map<String, serde_json::Value>,
+) -> Option<Result<Value, RuntimeError>> {
+    if obj.len() != 1 {
+        return None;
+    }
+    let (key, val) = obj.iter().next()?;
+    let text = val.as_str()?;
+    let invalid = || RuntimeError::CustomError(format!("Invalid {} value '{}'", key, text));
+    match key.as_str() {
+        FRACTION_TAG => {
+            let (numer, denom) = text.split_once('/').unwrap_or((text, "1"));
+            let parsed = numer
+                .trim()
+                .parse::<num_bigint::BigInt>()
+                .ok()
+                .zip(denom.trim().parse::<num_bigint::BigInt>().ok());
+            Some(match parsed {
+                Some((_, d)) if num_traits::Zero::is_zero(&d) => Err(invalid()),
+                Some((n, d)) => Ok(Value::Fraction(num_rational::Ratio::new(n, d))),
+                None => Err(invalid()),
+            })
+        }
+        BIGINT_TAG => Some(
+            text.trim()
+                .parse::<num_bigint::BigInt>()
+                .map(|n| Value::Fraction(num_rational::Ratio::from_integer(n)))
+                .map_err(|_| invalid()),
+        ),
+        _ => None,
+    }
+}
+
 /// 将 serde_json::Value 转换为 Aether Value
-fn json_to_value(json: &serde_json::Value) -> Result<Value, RuntimeError> {
+pub(crate) fn json_to_value(json: &serde_json::Value) -> Result<Value, RuntimeError> {
     match json {
         serde_json::Value::Null => Ok(Value::Null),
         serde_json::Value::Bool(b) => Ok(Value::Boolean(*b)),
         serde_json::Value::Number(n) => {
             if let Some(i) = n.as_i64() {
-                Ok(Value::Number(i as f64))
+                Ok(Value::Int(i))
             } else if let Some(u) = n.as_u64() {
                 Ok(Value::Number(u as f64))
             } else if let Some(f) = n.as_f64() {
@@ -142,6 +238,9 @@ fn json_to_value(json: &serde_json::Value) -> Result<Value, RuntimeError> {
             Ok(Value::Array(aether_arr))
         }
         serde_json::Value::Object(obj) => {
+            if let Some(tagged) = tagged_to_value(obj) {
+                return tagged;
+            }
             let mut aether_dict = HashMap::new();
             for (key, val) in obj {
                 aether_dict.insert(key.clone(), json_to_value(val)?);
@@ -151,35 +250,67 @@ fn json_to_value(json: &serde_json::Value) -> Result<Value, RuntimeError> {
     }
 }
 
-/// 将 Aether Value 转换为 serde_json::Value
-fn value_to_json(value: &Value) -> Result<serde_json::Value, RuntimeError> {
+/// 将 Aether Value 转换为 serde_json::Value，指定 Fraction 的序列化方式
+pub(crate) fn value_to_json(
+    value: &Value,
+    mode: FractionJsonMode,
+) -> Result<serde_json::Value, RuntimeError> {
     match value {
         Value::Null => Ok(serde_json::Value::Null),
         Value::Boolean(b) => Ok(serde_json::Value::Bool(*b)),
         Value::Number(n) => Ok(serde_json::json!(n)),
+        Value::Int(i) => Ok(serde_json::json!(i)),
         Value::String(s) => Ok(serde_json::Value::String(s.clone())),
         Value::Array(arr) => {
             let mut json_arr = Vec::new();
             for item in arr {
-                json_arr.push(value_to_json(item)?);
+                json_arr.push(value_to_json(item, mode)?);
             }
             Ok(serde_json::Value::Array(json_arr))
         }
         Value::Dict(dict) => {
             let mut json_obj = serde_json::Map::new();
             for (key, val) in dict {
-                json_obj.insert(key.clone(), value_to_json(val)?);
+                json_obj.insert(key.clone(), value_to_json(val, mode)?);
             }
             Ok(serde_json::Value::Object(json_obj))
         }
+        // 集合序列化为（有序的）数组
+        Value::Set(set) => crate::value::set_elements(set)
+            .iter()
+            .map(|v| value_to_json(v, mode))
+            .collect::<Result<Vec<_>, _>>()
+            .map(serde_json::Value::Array),
+        // 表格序列化为行对象数组
+        Value::Table(table) => table
+            .to_dicts()
+            .iter()
+            .map(|v| value_to_json(v, mode))
+            .collect::<Result<Vec<_>, _>>()
+            .map(serde_json::Value::Array),
+        // 日期时间序列化为 RFC 3339 字符串
+        Value::DateTime(dt) => Ok(serde_json::Value::String(dt.to_rfc3339())),
+        Value::Fraction(f) if mode == FractionJsonMode::Tagged => {
+            let (tag, text) = if f.is_integer() {
+                (BIGINT_TAG, f.numer().to_string())
+            } else {
+                (FRACTION_TAG, format!("{}/{}", f.numer(), f.denom()))
+            };
+            let mut obj = serde_json::Map::new();
+            obj.insert(tag.to_string(), serde_json::Value::String(text));
+            Ok(serde_json::Value::Object(obj))
+        }
+        Value::Fraction(f) if mode == FractionJsonMode::String => Ok(serde_json::Value::String(
+            Value::Fraction(f.clone()).display_with(&Default::default()),
+        )),
         Value::Fraction(f) => {
             // 将分数转换为浮点数
             let float_val = f.numer().to_f64().unwrap_or(0.0) / f.denom().to_f64().unwrap_or(1.0);
             Ok(serde_json::json!(float_val))
         }
         other => Err(RuntimeError::CustomError(format!(
-            "Cannot convert {:?} to JSON",
-            other
+            "Cannot convert {} to JSON",
+            other.type_name()
         ))),
     }
 }
@@ -0,0 +1,264 @@
+// src/builtins/kv.rs
+//! 持久化键值存储
+//!
+//! 为长时间运行或多次运行的 CLI 脚本保存状态（计数器、上次处理的位置等）：
+//! - `KV_OPEN` 打开（不存在时创建）一个 JSON 文件作为存储，返回存储句柄
+//! - `KV_GET` / `KV_SET` / `KV_DELETE` / `KV_KEYS` 读写键值
+//!
+//! 打开的存储属于各自的引擎，`reset_env` 时关闭。存储内容不做缓存：每次操作都
+//! 重新读取文件，写入后立即保存，其他引擎或进程的修改不会被旧内容覆盖。
+//! 写入时先写临时文件再原子地替换原文件，脚本中途失败也不会留下损坏的存储。
+//! 值以 JSON 保存，Fraction 和大整数使用带标记的形式，读回时无损还原。
+//!
+//! 需要启用文件系统权限，路径受沙箱的路径验证器约束。
+
+use super::filesystem::{get_string, validate_path};
+use super::json::{FractionJsonMode, json_to_value, value_to_json};
+use crate::evaluator::RuntimeError;
+use crate::value::Value;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// 单个存储的内容（按键排序，保证文件内容稳定）
+type Store = serde_json::Map<String, serde_json::Value>;
+
+/// 引擎已打开的存储（文件路径）
+pub(crate) type SharedKvStores = Rc<RefCell<HashSet<PathBuf>>>;
+
+// 线程局部的当前引擎已打开的存储（求值期间有效）
+thread_local! {
+    static ACTIVE_STORES: RefCell<Option<SharedKvStores>> = const { RefCell::new(None) };
+}
+
+/// 在作用域内设置当前引擎已打开的存储（RAII 模式，结束时恢复之前的设置）
+pub(crate) struct ScopedKvStores {
+    previous: Option<SharedKvStores>,
+}
+
+impl ScopedKvStores {
+    pub(crate) fn set(stores: SharedKvStores) -> Self {
+        let previous = ACTIVE_STORES.with(|s| s.borrow_mut().replace(stores));
+        Self { previous }
+    }
+}
+
+impl Drop for ScopedKvStores {
+    fn drop(&mut self) {
+        ACTIVE_STORES.with(|s| *s.borrow_mut() = self.previous.take());
+    }
+}
+
+/// 读取或修改当前引擎已打开的存储
+///
+/// 不在求值中时作用于一个临时的空集合，修改不会保留。
+fn with_stores<R>(f: impl FnOnce(&mut HashSet<PathBuf>) -> R) -> R {
+    match ACTIVE_STORES.with(|s| s.borrow().clone()) {
+        Some(stores) => f(&mut stores.borrow_mut()),
+        None => f(&mut HashSet::new()),
+    }
+}
+
+/// 检查参数个数
+fn check_arity(args: &[Value], min: usize, max: usize) -> Result<(), RuntimeError> {
+    if args.len() < min || args.len() > max {
+        return Err(RuntimeError::WrongArity {
+            expected: min,
+            got: args.len(),
+        });
+    }
+    Ok(())
+}
+
+/// 从文件加载存储，文件不存在时返回空存储
+fn load_store(path: &Path) -> Result<Store, RuntimeError> {
+    if !path.exists() {
+        return Ok(Store::new());
+    }
+    let content = fs::read_to_string(path).map_err(|e| {
+        RuntimeError::CustomError(format!(
+            "Failed to read KV store '{}': {}",
+            path.display(),
+            e
+        ))
+    })?;
+    if content.trim().is_empty() {
+        return Ok(Store::new());
+    }
+    match serde_json::from_str(&content) {
+        Ok(serde_json::Value::Object(store)) => Ok(store),
+        Ok(_) => Err(RuntimeError::CustomError(format!(
+            "KV store '{}' must contain a JSON object",
+            path.display()
+        ))),
+        Err(e) => Err(RuntimeError::CustomError(format!(
+            "KV store '{}' is not valid JSON: {}",
+            path.display(),
+            e
+        ))),
+    }
+}
+
+/// 将存储写回文件（先写临时文件再替换）
+fn save_store(path: &Path, store: &Store) -> Result<(), RuntimeError> {
+    let content = serde_json::to_string_pretty(store)
+        .map_err(|e| RuntimeError::CustomError(format!("KV store serialize error: {}", e)))?;
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    fs::write(&tmp, content)
+        .and_then(|_| fs::rename(&tmp, path))
+        .map_err(|e| {
+            RuntimeError::CustomError(format!(
+                "Failed to write KV store '{}': {}",
+                path.display(),
+                e
+            ))
+        })
+}
+
+/// 解析存储句柄，返回已打开存储的路径
+fn store_path(handle: &Value) -> Result<PathBuf, RuntimeError> {
+    let path = validate_path(&get_string(handle)?)?;
+    let opened = with_stores(|stores| stores.contains(&path));
+    if opened {
+        Ok(path)
+    } else {
+        Err(RuntimeError::CustomError(format!(
+            "KV store '{}' is not open, call KV_OPEN first",
+            path.display()
+        )))
+    }
+}
+
+/// 打开键值存储
+///
+/// # 功能
+/// 打开 JSON 文件作为键值存储，文件不存在时创建空存储。
+/// 之后的每次读写都直接访问文件，看到的总是文件的最新内容。
+///
+/// # 参数
+/// - `path`: String - 存储文件路径
+///
+/// # 返回值
+/// String - 存储句柄（规范化后的路径），传给其他 KV_ 函数
+///
+/// # 示例
+/// ```aether
+/// Set DB KV_OPEN("state.json")
+/// Set RUNS KV_GET(DB, "runs", 0) + 1
+/// KV_SET(DB, "runs", RUNS)
+/// ```
+///
+/// # 安全性
+/// 需要启用文件系统权限
+pub fn kv_open(args: &[Value]) -> Result<Value, RuntimeError> {
+    check_arity(args, 1, 1)?;
+    let path = validate_path(&get_string(&args[0])?)?;
+    let store = load_store(&path)?;
+    if !path.exists() {
+        save_store(&path, &store)?;
+    }
+    let handle = path.display().to_string();
+    with_stores(|stores| stores.insert(path));
+    Ok(Value::String(handle))
+}
+
+/// 读取键对应的值
+///
+/// # 参数
+/// - `store`: String - KV_OPEN 返回的句柄
+/// - `key`: String - 键
+/// - `default`: Any - 键不存在时返回的值（可选，默认 Null）
+///
+/// # 返回值
+/// 键对应的值，不存在时返回 `default`
+///
+/// # 示例
+/// ```aether
+/// Set LAST KV_GET(DB, "last_id", 0)
+/// ```
+pub fn kv_get(args: &[Value]) -> Result<Value, RuntimeError> {
+    check_arity(args, 2, 3)?;
+    let path = store_path(&args[0])?;
+    let key = get_string(&args[1])?;
+    match load_store(&path)?.remove(&key) {
+        Some(json) => json_to_value(&json),
+        None => Ok(args.get(2).cloned().unwrap_or(Value::Null)),
+    }
+}
+
+/// 写入键值并立即持久化
+///
+/// # 参数
+/// - `store`: String - KV_OPEN 返回的句柄
+/// - `key`: String - 键
+/// - `value`: Any - 可序列化为 JSON 的值（函数等不可序列化的值会报错）
+///
+/// # 返回值
+/// 成功返回 true
+///
+/// # 示例
+/// ```aether
+/// KV_SET(DB, "config", {"retries": 3})
+/// ```
+pub fn kv_set(args: &[Value]) -> Result<Value, RuntimeError> {
+    check_arity(args, 3, 3)?;
+    let path = store_path(&args[0])?;
+    let key = get_string(&args[1])?;
+    let json = value_to_json(&args[2], FractionJsonMode::Tagged)?;
+    let mut store = load_store(&path)?;
+    store.insert(key, json);
+    save_store(&path, &store)?;
+    Ok(Value::Boolean(true))
+}
+
+/// 删除键并立即持久化
+///
+/// # 参数
+/// - `store`: String - KV_OPEN 返回的句柄
+/// - `key`: String - 键
+///
+/// # 返回值
+/// Boolean - 键是否存在
+///
+/// # 示例
+/// ```aether
+/// KV_DELETE(DB, "session")
+/// ```
+pub fn kv_delete(args: &[Value]) -> Result<Value, RuntimeError> {
+    check_arity(args, 2, 2)?;
+    let path = store_path(&args[0])?;
+    let key = get_string(&args[1])?;
+    let mut store = load_store(&path)?;
+    if store.remove(&key).is_some() {
+        save_store(&path, &store)?;
+        Ok(Value::Boolean(true))
+    } else {
+        Ok(Value::Boolean(false))
+    }
+}
+
+/// 列出所有键
+///
+/// # 参数
+/// - `store`: String - KV_OPEN 返回的句柄
+///
+/// # 返回值
+/// Array - 按字典序排列的键
+///
+/// # 示例
+/// ```aether
+/// For KEY In KV_KEYS(DB) {
+///     PRINTLN(KEY)
+/// }
+/// ```
+pub fn kv_keys(args: &[Value]) -> Result<Value, RuntimeError> {
+    check_arity(args, 1, 1)?;
+    let path = store_path(&args[0])?;
+    let mut keys: Vec<String> = load_store(&path)?.keys().cloned().collect();
+    keys.sort();
+    Ok(Value::Array(keys.into_iter().map(Value::String).collect()))
+}
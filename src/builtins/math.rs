@@ -12,10 +12,72 @@
 //! - Matrix operations: determinant, transpose, matmul
 //! - Constants: PI, E, TAU, PHI
 
+use super::args::{ArgSpec, ArgType, Param, binary_number, unary_number, unary_numeric};
 use crate::evaluator::RuntimeError;
-use crate::value::Value;
+use crate::value::{Value, integer_ratio};
+use num_bigint::BigInt;
+use num_rational::Ratio;
+use num_traits::{One, Signed, ToPrimitive, Zero};
 use std::f64::consts;
 
+/// f64 能精确表示的最大整数（2^53）
+const MAX_SAFE_INTEGER: f64 = 9007199254740992.0;
+
+/// 精确幂运算允许的最大指数绝对值
+const MAX_EXACT_EXPONENT: i64 = 100_000;
+
+/// 精确阶乘允许的最大 n
+const MAX_EXACT_FACTORIAL: u32 = 20_000;
+
+// ============================================================================
+// 参数规格
+// ============================================================================
+
+const X_NUMBER: &[Param] = &[Param::required("x", ArgType::Number)];
+const X_NUMERIC: &[Param] = &[Param::required("x", ArgType::Numeric)];
+
+const ABS: ArgSpec = ArgSpec::new("ABS", X_NUMERIC);
+const FLOOR: ArgSpec = ArgSpec::new("FLOOR", X_NUMERIC);
+const CEIL: ArgSpec = ArgSpec::new("CEIL", X_NUMERIC);
+const ROUND: ArgSpec = ArgSpec::new("ROUND", X_NUMERIC);
+const SIN: ArgSpec = ArgSpec::new("SIN", X_NUMBER);
+const COS: ArgSpec = ArgSpec::new("COS", X_NUMBER);
+const TAN: ArgSpec = ArgSpec::new("TAN", X_NUMBER);
+const EXP: ArgSpec = ArgSpec::new("EXP", X_NUMBER);
+const ATAN: ArgSpec = ArgSpec::new("ATAN", X_NUMBER);
+const SINH: ArgSpec = ArgSpec::new("SINH", X_NUMBER);
+const COSH: ArgSpec = ArgSpec::new("COSH", X_NUMBER);
+const TANH: ArgSpec = ArgSpec::new("TANH", X_NUMBER);
+const EXP2: ArgSpec = ArgSpec::new("EXP2", X_NUMBER);
+const EXPM1: ArgSpec = ArgSpec::new("EXPM1", X_NUMBER);
+const POW: ArgSpec = ArgSpec::new(
+    "POW",
+    &[
+        Param::required("base", ArgType::Numeric),
+        Param::required("exponent", ArgType::Numeric),
+    ],
+);
+const ATAN2: ArgSpec = ArgSpec::new(
+    "ATAN2",
+    &[
+        Param::required("y", ArgType::Number),
+        Param::required("x", ArgType::Number),
+    ],
+);
+const HYPOT: ArgSpec = ArgSpec::new(
+    "HYPOT",
+    &[
+        Param::required("x", ArgType::Number),
+        Param::required("y", ArgType::Number),
+    ],
+);
+
+/// 本模块声明了参数规格的函数
+pub(crate) const ARG_SPECS: &[ArgSpec] = &[
+    ABS, FLOOR, CEIL, ROUND, SIN, COS, TAN, EXP, ATAN, SINH, COSH, TANH, EXP2, EXPM1, POW, ATAN2,
+    HYPOT,
+];
+
 // ============================================================================
 // 基础数学函数
 // ============================================================================
@@ -44,20 +106,7 @@ use std::f64::consts;
 /// Set c Abs(-42.7)        # 42.7
 /// ```
 pub fn abs(args: &[Value]) -> Result<Value, RuntimeError> {
-    if args.len() != 1 {
-        return Err(RuntimeError::WrongArity {
-            expected: 1,
-            got: args.len(),
-        });
-    }
-
-    match &args[0] {
-        Value::Number(n) => Ok(Value::Number(n.abs())),
-        _ => Err(RuntimeError::TypeErrorDetailed {
-            expected: "Number".to_string(),
-            got: format!("{:?}", args[0]),
-        }),
-    }
+    unary_numeric(&ABS, args, f64::abs, |r| r.abs())
 }
 
 /// 向下取整
@@ -78,20 +127,7 @@ pub fn abs(args: &[Value]) -> Result<Value, RuntimeError> {
 /// Set c Floor(5.0)        # 5.0
 /// ```
 pub fn floor(args: &[Value]) -> Result<Value, RuntimeError> {
-    if args.len() != 1 {
-        return Err(RuntimeError::WrongArity {
-            expected: 1,
-            got: args.len(),
-        });
-    }
-
-    match &args[0] {
-        Value::Number(n) => Ok(Value::Number(n.floor())),
-        _ => Err(RuntimeError::TypeErrorDetailed {
-            expected: "Number".to_string(),
-            got: format!("{:?}", args[0]),
-        }),
-    }
+    unary_numeric(&FLOOR, args, f64::floor, |r| r.floor())
 }
 
 /// 向上取整
@@ -112,20 +148,7 @@ pub fn floor(args: &[Value]) -> Result<Value, RuntimeError> {
 /// Set c Ceil(5.0)         # 5.0
 /// ```
 pub fn ceil(args: &[Value]) -> Result<Value, RuntimeError> {
-    if args.len() != 1 {
-        return Err(RuntimeError::WrongArity {
-            expected: 1,
-            got: args.len(),
-        });
-    }
-
-    match &args[0] {
-        Value::Number(n) => Ok(Value::Number(n.ceil())),
-        _ => Err(RuntimeError::TypeErrorDetailed {
-            expected: "Number".to_string(),
-            got: format!("{:?}", args[0]),
-        }),
-    }
+    unary_numeric(&CEIL, args, f64::ceil, |r| r.ceil())
 }
 
 /// 四舍五入
@@ -149,20 +172,7 @@ pub fn ceil(args: &[Value]) -> Result<Value, RuntimeError> {
 /// Set c Round(-2.5)       # -3.0
 /// ```
 pub fn round(args: &[Value]) -> Result<Value, RuntimeError> {
-    if args.len() != 1 {
-        return Err(RuntimeError::WrongArity {
-            expected: 1,
-            got: args.len(),
-        });
-    }
-
-    match &args[0] {
-        Value::Number(n) => Ok(Value::Number(n.round())),
-        _ => Err(RuntimeError::TypeErrorDetailed {
-            expected: "Number".to_string(),
-            got: format!("{:?}", args[0]),
-        }),
-    }
+    unary_numeric(&ROUND, args, f64::round, |r| r.round())
 }
 
 /// 平方根
@@ -221,12 +231,15 @@ pub fn sqrt(args: &[Value]) -> Result<Value, RuntimeError> {
 /// 计算底数的指数次幂。
 ///
 /// # 参数
-/// - `base`: Number - 底数
-/// - `exponent`: Number - 指数
+/// - `base`: Number/Fraction - 底数
+/// - `exponent`: Number/Fraction - 指数
 ///
 /// # 返回值
 /// Number - base^exponent 的结果
 ///
+/// 指数为整数时精确计算：底数为 Fraction 时返回 Fraction，
+/// 整数结果超出 2^53 时返回精确的大整数。
+///
 /// # 公式
 /// ```
 /// pow(base, exp) = base^exp
@@ -238,22 +251,60 @@ pub fn sqrt(args: &[Value]) -> Result<Value, RuntimeError> {
 /// Set b Pow(10, 2)        # 100.0
 /// Set c Pow(4, 0.5)       # 2.0 (√4)
 /// Set d Pow(2, -1)        # 0.5 (1/2)
+/// Set e Pow(2, 100)       # 1267650600228229401496703205376
 /// ```
 pub fn pow(args: &[Value]) -> Result<Value, RuntimeError> {
-    if args.len() != 2 {
-        return Err(RuntimeError::WrongArity {
-            expected: 2,
-            got: args.len(),
-        });
+    POW.check(args)?;
+
+    if let Some(result) = exact_pow(&args[0], &args[1]) {
+        return result;
     }
 
-    match (&args[0], &args[1]) {
-        (Value::Number(base), Value::Number(exp)) => Ok(Value::Number(base.powf(*exp))),
-        _ => Err(RuntimeError::TypeErrorDetailed {
-            expected: "Number, Number".to_string(),
-            got: format!("{:?}, {:?}", args[0], args[1]),
-        }),
+    let to_f64 = |v: &Value| match v {
+        Value::Number(n) => *n,
+        Value::Fraction(f) => f.to_f64().unwrap_or(f64::NAN),
+        _ => f64::NAN,
+    };
+    Ok(Value::Number(to_f64(&args[0]).powf(to_f64(&args[1]))))
+}
+
+/// 整数指数的精确幂运算
+///
+/// - 底数为 Fraction：结果为 Fraction
+/// - 底数为整数 Number：结果在 2^53 以内时返回 Number，否则返回精确的大整数
+///
+/// 指数不是整数（或超出范围）时返回 None，由调用方按浮点计算。
+fn exact_pow(base: &Value, exponent: &Value) -> Option<Result<Value, RuntimeError>> {
+    let exp = match exponent {
+        Value::Number(n) if n.fract() == 0.0 => n.to_i64()?,
+        Value::Fraction(f) if f.is_integer() => f.to_integer().to_i64()?,
+        _ => return None,
+    };
+    if exp.abs() > MAX_EXACT_EXPONENT {
+        return None;
+    }
+    let exp = exp as i32;
+
+    let (base_ratio, is_fraction) = match base {
+        Value::Fraction(f) => (f.clone(), true),
+        Value::Number(n) => {
+            let approx = n.powi(exp);
+            if (approx.is_finite() && approx.abs() <= MAX_SAFE_INTEGER) || exp < 0 {
+                return None;
+            }
+            (integer_ratio(*n)?, false)
+        }
+        _ => return None,
+    };
+
+    if base_ratio.is_zero() && exp < 0 {
+        return Some(Err(RuntimeError::DivisionByZero));
     }
+    let result = base_ratio.pow(exp);
+    if !is_fraction && result.is_integer() && result.numer().to_f64()?.abs() <= MAX_SAFE_INTEGER {
+        return Some(Ok(Value::Number(result.to_f64()?)));
+    }
+    Some(Ok(Value::Fraction(result)))
 }
 
 // ============================================================================
@@ -284,20 +335,7 @@ pub fn pow(args: &[Value]) -> Result<Value, RuntimeError> {
 /// Set c Sin(pi)           # 0.0 (约等于)
 /// ```
 pub fn sin(args: &[Value]) -> Result<Value, RuntimeError> {
-    if args.len() != 1 {
-        return Err(RuntimeError::WrongArity {
-            expected: 1,
-            got: args.len(),
-        });
-    }
-
-    match &args[0] {
-        Value::Number(n) => Ok(Value::Number(n.sin())),
-        _ => Err(RuntimeError::TypeErrorDetailed {
-            expected: "Number".to_string(),
-            got: format!("{:?}", args[0]),
-        }),
-    }
+    unary_number(&SIN, args, f64::sin)
 }
 
 /// 余弦函数
@@ -324,20 +362,7 @@ pub fn sin(args: &[Value]) -> Result<Value, RuntimeError> {
 /// Set c Cos(pi)           # -1.0
 /// ```
 pub fn cos(args: &[Value]) -> Result<Value, RuntimeError> {
-    if args.len() != 1 {
-        return Err(RuntimeError::WrongArity {
-            expected: 1,
-            got: args.len(),
-        });
-    }
-
-    match &args[0] {
-        Value::Number(n) => Ok(Value::Number(n.cos())),
-        _ => Err(RuntimeError::TypeErrorDetailed {
-            expected: "Number".to_string(),
-            got: format!("{:?}", args[0]),
-        }),
-    }
+    unary_number(&COS, args, f64::cos)
 }
 
 /// 正切函数
@@ -364,20 +389,7 @@ pub fn cos(args: &[Value]) -> Result<Value, RuntimeError> {
 /// Set c Tan(pi / 6)       # 0.577... (√3/3)
 /// ```
 pub fn tan(args: &[Value]) -> Result<Value, RuntimeError> {
-    if args.len() != 1 {
-        return Err(RuntimeError::WrongArity {
-            expected: 1,
-            got: args.len(),
-        });
-    }
-
-    match &args[0] {
-        Value::Number(n) => Ok(Value::Number(n.tan())),
-        _ => Err(RuntimeError::TypeErrorDetailed {
-            expected: "Number".to_string(),
-            got: format!("{:?}", args[0]),
-        }),
-    }
+    unary_number(&TAN, args, f64::tan)
 }
 
 // ============================================================================
@@ -508,20 +520,7 @@ pub fn ln(args: &[Value]) -> Result<Value, RuntimeError> {
 /// Set c Exp(2)            # 7.389056... (e²)
 /// ```
 pub fn exp(args: &[Value]) -> Result<Value, RuntimeError> {
-    if args.len() != 1 {
-        return Err(RuntimeError::WrongArity {
-            expected: 1,
-            got: args.len(),
-        });
-    }
-
-    match &args[0] {
-        Value::Number(n) => Ok(Value::Number(n.exp())),
-        _ => Err(RuntimeError::TypeErrorDetailed {
-            expected: "Number".to_string(),
-            got: format!("{:?}", args[0]),
-        }),
-    }
+    unary_number(&EXP, args, f64::exp)
 }
 
 // ============================================================================
@@ -636,20 +635,7 @@ pub fn acos(args: &[Value]) -> Result<Value, RuntimeError> {
 /// Set c Atan(-1)          # -π/4
 /// ```
 pub fn atan(args: &[Value]) -> Result<Value, RuntimeError> {
-    if args.len() != 1 {
-        return Err(RuntimeError::WrongArity {
-            expected: 1,
-            got: args.len(),
-        });
-    }
-
-    match &args[0] {
-        Value::Number(n) => Ok(Value::Number(n.atan())),
-        _ => Err(RuntimeError::TypeErrorDetailed {
-            expected: "Number".to_string(),
-            got: format!("{:?}", args[0]),
-        }),
-    }
+    unary_number(&ATAN, args, f64::atan)
 }
 
 /// 双参数反正切函数
@@ -675,20 +661,7 @@ pub fn atan(args: &[Value]) -> Result<Value, RuntimeError> {
 /// Set d Atan2(-1, 1)      # -π/4 (第四象限)
 /// ```
 pub fn atan2(args: &[Value]) -> Result<Value, RuntimeError> {
-    if args.len() != 2 {
-        return Err(RuntimeError::WrongArity {
-            expected: 2,
-            got: args.len(),
-        });
-    }
-
-    match (&args[0], &args[1]) {
-        (Value::Number(y), Value::Number(x)) => Ok(Value::Number(y.atan2(*x))),
-        _ => Err(RuntimeError::TypeErrorDetailed {
-            expected: "Number, Number".to_string(),
-            got: format!("{:?}, {:?}", args[0], args[1]),
-        }),
-    }
+    binary_number(&ATAN2, args, f64::atan2)
 }
 
 /// 双曲正弦函数
@@ -713,20 +686,7 @@ pub fn atan2(args: &[Value]) -> Result<Value, RuntimeError> {
 /// Set b Sinh(1)           # 1.1752...
 /// ```
 pub fn sinh(args: &[Value]) -> Result<Value, RuntimeError> {
-    if args.len() != 1 {
-        return Err(RuntimeError::WrongArity {
-            expected: 1,
-            got: args.len(),
-        });
-    }
-
-    match &args[0] {
-        Value::Number(n) => Ok(Value::Number(n.sinh())),
-        _ => Err(RuntimeError::TypeErrorDetailed {
-            expected: "Number".to_string(),
-            got: format!("{:?}", args[0]),
-        }),
-    }
+    unary_number(&SINH, args, f64::sinh)
 }
 
 /// 双曲余弦函数
@@ -751,20 +711,7 @@ pub fn sinh(args: &[Value]) -> Result<Value, RuntimeError> {
 /// Set b Cosh(1)           # 1.5431...
 /// ```
 pub fn cosh(args: &[Value]) -> Result<Value, RuntimeError> {
-    if args.len() != 1 {
-        return Err(RuntimeError::WrongArity {
-            expected: 1,
-            got: args.len(),
-        });
-    }
-
-    match &args[0] {
-        Value::Number(n) => Ok(Value::Number(n.cosh())),
-        _ => Err(RuntimeError::TypeErrorDetailed {
-            expected: "Number".to_string(),
-            got: format!("{:?}", args[0]),
-        }),
-    }
+    unary_number(&COSH, args, f64::cosh)
 }
 
 /// 双曲正切函数
@@ -789,20 +736,7 @@ pub fn cosh(args: &[Value]) -> Result<Value, RuntimeError> {
 /// Set b Tanh(1)           # 0.7616...
 /// ```
 pub fn tanh(args: &[Value]) -> Result<Value, RuntimeError> {
-    if args.len() != 1 {
-        return Err(RuntimeError::WrongArity {
-            expected: 1,
-            got: args.len(),
-        });
-    }
-
-    match &args[0] {
-        Value::Number(n) => Ok(Value::Number(n.tanh())),
-        _ => Err(RuntimeError::TypeErrorDetailed {
-            expected: "Number".to_string(),
-            got: format!("{:?}", args[0]),
-        }),
-    }
+    unary_number(&TANH, args, f64::tanh)
 }
 
 // ============================================================================
@@ -882,20 +816,7 @@ pub fn log2(args: &[Value]) -> Result<Value, RuntimeError> {
 /// Set c Exp2(-1)          # 0.5
 /// ```
 pub fn exp2(args: &[Value]) -> Result<Value, RuntimeError> {
-    if args.len() != 1 {
-        return Err(RuntimeError::WrongArity {
-            expected: 1,
-            got: args.len(),
-        });
-    }
-
-    match &args[0] {
-        Value::Number(n) => Ok(Value::Number(n.exp2())),
-        _ => Err(RuntimeError::TypeErrorDetailed {
-            expected: "Number".to_string(),
-            got: format!("{:?}", args[0]),
-        }),
-    }
+    unary_number(&EXP2, args, f64::exp2)
 }
 
 /// exp(x) - 1 精确计算
@@ -918,20 +839,7 @@ pub fn exp2(args: &[Value]) -> Result<Value, RuntimeError> {
 /// Set b Expm1(0.001)      # 0.0010005...
 /// ```
 pub fn expm1(args: &[Value]) -> Result<Value, RuntimeError> {
-    if args.len() != 1 {
-        return Err(RuntimeError::WrongArity {
-            expected: 1,
-            got: args.len(),
-        });
-    }
-
-    match &args[0] {
-        Value::Number(n) => Ok(Value::Number(n.exp_m1())),
-        _ => Err(RuntimeError::TypeErrorDetailed {
-            expected: "Number".to_string(),
-            got: format!("{:?}", args[0]),
-        }),
-    }
+    unary_number(&EXPM1, args, f64::exp_m1)
 }
 
 /// ln(1 + x) 精确计算
@@ -1004,7 +912,9 @@ pub fn log1p(args: &[Value]) -> Result<Value, RuntimeError> {
 ///
 /// # 错误
 /// - 非整数或负数会抛出错误
-/// - n > 170 会溢出
+/// - n > 20000 会抛出错误
+///
+/// n > 170 时结果超出 f64 范围，以精确的大整数（Fraction）返回。
 ///
 /// # 示例
 /// ```aether
@@ -1020,34 +930,45 @@ pub fn factorial(args: &[Value]) -> Result<Value, RuntimeError> {
         });
     }
 
-    match &args[0] {
-        Value::Number(n) => {
-            if *n < 0.0 || n.fract() != 0.0 {
-                return Err(RuntimeError::InvalidOperation(format!(
-                    "Factorial requires non-negative integer, got {}",
-                    n
-                )));
-            }
+    let n = match &args[0] {
+        Value::Number(n) => *n,
+        Value::Fraction(f) if f.is_integer() => f.to_integer().to_f64().unwrap_or(f64::INFINITY),
+        _ => {
+            return Err(RuntimeError::TypeErrorDetailed {
+                expected: "Number".to_string(),
+                got: format!("{:?}", args[0]),
+            });
+        }
+    };
 
-            let n_int = *n as u32;
-            if n_int > 170 {
-                return Err(RuntimeError::InvalidOperation(format!(
-                    "Factorial overflow: {} is too large",
-                    n_int
-                )));
-            }
+    if n < 0.0 || n.fract() != 0.0 {
+        return Err(RuntimeError::InvalidOperation(format!(
+            "Factorial requires non-negative integer, got {}",
+            n
+        )));
+    }
+    if n > MAX_EXACT_FACTORIAL as f64 {
+        return Err(RuntimeError::InvalidOperation(format!(
+            "Factorial overflow: {} is too large (max {})",
+            n, MAX_EXACT_FACTORIAL
+        )));
+    }
 
-            let mut result = 1.0;
-            for i in 2..=n_int {
-                result *= i as f64;
-            }
-            Ok(Value::Number(result))
+    let n_int = n as u32;
+    if n_int <= 170 {
+        let mut result = 1.0;
+        for i in 2..=n_int {
+            result *= i as f64;
         }
-        _ => Err(RuntimeError::TypeErrorDetailed {
-            expected: "Number".to_string(),
-            got: format!("{:?}", args[0]),
-        }),
+        return Ok(Value::Number(result));
+    }
+
+    // 超出 f64 范围时使用大整数精确计算
+    let mut result = BigInt::one();
+    for i in 2..=n_int {
+        result *= i;
     }
+    Ok(Value::Fraction(Ratio::from_integer(result)))
 }
 
 /// Gamma 函数（广义阶乘）
@@ -1199,20 +1120,7 @@ pub fn erf(args: &[Value]) -> Result<Value, RuntimeError> {
 /// Set dist Hypot(1, 1)    # √2 ≈ 1.414
 /// ```
 pub fn hypot(args: &[Value]) -> Result<Value, RuntimeError> {
-    if args.len() != 2 {
-        return Err(RuntimeError::WrongArity {
-            expected: 2,
-            got: args.len(),
-        });
-    }
-
-    match (&args[0], &args[1]) {
-        (Value::Number(x), Value::Number(y)) => Ok(Value::Number(x.hypot(*y))),
-        _ => Err(RuntimeError::TypeErrorDetailed {
-            expected: "Number, Number".to_string(),
-            got: format!("{:?}, {:?}", args[0], args[1]),
-        }),
-    }
+    binary_number(&HYPOT, args, f64::hypot)
 }
 
 /// 符号函数
@@ -1304,7 +1212,7 @@ pub fn clamp(args: &[Value]) -> Result<Value, RuntimeError> {
 
     match (&args[0], &args[1], &args[2]) {
         (Value::Number(x), Value::Number(min), Value::Number(max)) => {
-            if min > max {
+            if min.is_nan() || max.is_nan() || min > max {
                 return Err(RuntimeError::InvalidOperation(format!(
                     "Clamp: min ({}) must be <= max ({})",
                     min, max
@@ -1441,7 +1349,7 @@ pub fn median(args: &[Value]) -> Result<Value, RuntimeError> {
                 }
             }
 
-            numbers.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            numbers.sort_by(|a, b| a.total_cmp(b));
             let mid = numbers.len() / 2;
 
             let result = if numbers.len().is_multiple_of(2) {
@@ -1632,7 +1540,7 @@ pub fn quantile(args: &[Value]) -> Result<Value, RuntimeError> {
                 }
             }
 
-            numbers.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            numbers.sort_by(|a, b| a.total_cmp(b));
 
             let index = q * (numbers.len() - 1) as f64;
             let lower = index.floor() as usize;
@@ -1654,6 +1562,345 @@ pub fn quantile(args: &[Value]) -> Result<Value, RuntimeError> {
     }
 }
 
+// ============================================================================
+// Streaming Aggregation (one pass)
+// ============================================================================
+
+/// 数值序列：全部为 Number 时使用 f64，包含 Fraction 时精确计算
+enum Series {
+    Float(Vec<f64>),
+    Exact(Vec<Ratio<BigInt>>),
+}
+
+impl Series {
+    /// 读取数值数组参数
+    ///
+    /// 包含 Fraction 且其余元素都是整数时精确计算，否则按 f64 计算。
+    fn from_value(value: &Value) -> Result<Self, RuntimeError> {
+        let items = match value {
+            Value::Array(items) => items,
+            other => {
+                return Err(RuntimeError::TypeErrorDetailed {
+                    expected: "Array".to_string(),
+                    got: other.type_name().to_string(),
+                });
+            }
+        };
+        if let Some(bad) = items
+            .iter()
+            .find(|v| !matches!(v, Value::Number(_) | Value::Fraction(_)))
+        {
+            return Err(RuntimeError::TypeErrorDetailed {
+                expected: "Array of Numbers".to_string(),
+                got: format!("Array containing {}", bad.type_name()),
+            });
+        }
+
+        let has_fraction = items.iter().any(|v| matches!(v, Value::Fraction(_)));
+        if has_fraction {
+            let exact: Option<Vec<Ratio<BigInt>>> = items
+                .iter()
+                .map(|v| match v {
+                    Value::Fraction(f) => Some(f.clone()),
+                    Value::Number(n) => integer_ratio(*n),
+                    _ => None,
+                })
+                .collect();
+            if let Some(exact) = exact {
+                return Ok(Series::Exact(exact));
+            }
+        }
+        Ok(Series::Float(
+            items
+                .iter()
+                .map(|v| v.to_number().unwrap_or(f64::NAN))
+                .collect(),
+        ))
+    }
+
+    /// 逐项折叠，返回每一步的累计值
+    fn scan(
+        self,
+        float: impl Fn(f64, f64) -> f64,
+        exact: impl Fn(&Ratio<BigInt>, &Ratio<BigInt>) -> Ratio<BigInt>,
+    ) -> Vec<Value> {
+        match self {
+            Series::Float(values) => {
+                let mut acc: Option<f64> = None;
+                values
+                    .into_iter()
+                    .map(|x| {
+                        let next = acc.map_or(x, |a| float(a, x));
+                        acc = Some(next);
+                        Value::Number(next)
+                    })
+                    .collect()
+            }
+            Series::Exact(values) => {
+                let mut acc: Option<Ratio<BigInt>> = None;
+                values
+                    .into_iter()
+                    .map(|x| {
+                        let next = match &acc {
+                            Some(a) => exact(a, &x),
+                            None => x,
+                        };
+                        acc = Some(next.clone());
+                        Value::Fraction(next)
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// 累计和
+///
+/// # 功能
+/// 一次遍历计算数组的前缀和，第 i 项为前 i+1 个元素之和。
+/// `RUNNING_SUM` 是 `CUMSUM` 的别名。
+///
+/// # 参数
+/// - `array`: Array - 数字数组（包含 Fraction 时精确计算）
+///
+/// # 返回值
+/// Array - 与输入等长的累计和数组
+///
+/// # 示例
+/// ```aether
+/// Set totals Cumsum([1, 2, 3, 4])     # [1, 3, 6, 10]
+/// Set balance Running_sum([100, -20, 35])     # [100, 80, 115]
+/// ```
+pub fn cumsum(args: &[Value]) -> Result<Value, RuntimeError> {
+    if args.len() != 1 {
+        return Err(RuntimeError::WrongArity {
+            expected: 1,
+            got: args.len(),
+        });
+    }
+    let series = Series::from_value(&args[0])?;
+    Ok(Value::Array(series.scan(|a, x| a + x, |a, x| a + x)))
+}
+
+/// 累计积
+///
+/// # 功能
+/// 一次遍历计算数组的前缀积，第 i 项为前 i+1 个元素之积。
+///
+/// # 参数
+/// - `array`: Array - 数字数组（包含 Fraction 时精确计算）
+///
+/// # 返回值
+/// Array - 与输入等长的累计积数组
+///
+/// # 示例
+/// ```aether
+/// Set growth Cumprod([1.1, 1.2, 0.9])     # [1.1, 1.32, 1.188]
+/// ```
+pub fn cumprod(args: &[Value]) -> Result<Value, RuntimeError> {
+    if args.len() != 1 {
+        return Err(RuntimeError::WrongArity {
+            expected: 1,
+            got: args.len(),
+        });
+    }
+    let series = Series::from_value(&args[0])?;
+    Ok(Value::Array(series.scan(|a, x| a * x, |a, x| a * x)))
+}
+
+/// 读取正整数参数（窗口大小、滞后阶数）
+fn positive_count(value: &Value, what: &str) -> Result<usize, RuntimeError> {
+    match value {
+        Value::Number(n) if *n >= 1.0 && n.fract() == 0.0 => Ok(*n as usize),
+        other => Err(RuntimeError::InvalidOperation(format!(
+            "{} must be a positive integer, got {}",
+            what, other
+        ))),
+    }
+}
+
+/// 差分
+///
+/// # 功能
+/// 计算相邻元素之差：第 i 项为 `array[i + lag] - array[i]`。
+///
+/// # 参数
+/// - `array`: Array - 数字数组（包含 Fraction 时精确计算）
+/// - `lag`: Number - 滞后阶数（可选，默认 1）
+///
+/// # 返回值
+/// Array - 长度为 n - lag 的差分数组（lag ≥ n 时为空数组）
+///
+/// # 示例
+/// ```aether
+/// Set changes Diff([10, 12, 11, 15])      # [2, -1, 4]
+/// Set yoy Diff([1, 2, 4, 8, 16], 2)       # [3, 6, 12]
+/// ```
+pub fn diff(args: &[Value]) -> Result<Value, RuntimeError> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(RuntimeError::WrongArity {
+            expected: 1,
+            got: args.len(),
+        });
+    }
+    let lag = match args.get(1) {
+        Some(v) => positive_count(v, "Lag")?,
+        None => 1,
+    };
+
+    let result = match Series::from_value(&args[0])? {
+        Series::Float(values) => values
+            .iter()
+            .zip(values.iter().skip(lag))
+            .map(|(a, b)| Value::Number(b - a))
+            .collect(),
+        Series::Exact(values) => values
+            .iter()
+            .zip(values.iter().skip(lag))
+            .map(|(a, b)| Value::Fraction(b - a))
+            .collect(),
+    };
+    Ok(Value::Array(result))
+}
+
+/// 滚动窗口聚合（内置聚合）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RollingAggregate {
+    Sum,
+    Mean,
+    Min,
+    Max,
+}
+
+impl RollingAggregate {
+    fn parse(name: &str) -> Option<Self> {
+        match name.to_uppercase().as_str() {
+            "SUM" => Some(RollingAggregate::Sum),
+            "MEAN" | "AVG" => Some(RollingAggregate::Mean),
+            "MIN" => Some(RollingAggregate::Min),
+            "MAX" => Some(RollingAggregate::Max),
+            _ => None,
+        }
+    }
+}
+
+/// 单调队列求滑动窗口最值，`better(a, b)` 为真时 a 优于 b
+fn rolling_extreme(values: &[f64], window: usize, better: impl Fn(f64, f64) -> bool) -> Vec<f64> {
+    let mut queue: std::collections::VecDeque<usize> = std::collections::VecDeque::new();
+    let mut result = Vec::with_capacity(values.len().saturating_sub(window) + 1);
+    for (i, &x) in values.iter().enumerate() {
+        while queue.back().is_some_and(|&j| !better(values[j], x)) {
+            queue.pop_back();
+        }
+        queue.push_back(i);
+        if queue.front().is_some_and(|&j| j + window <= i) {
+            queue.pop_front();
+        }
+        if i + 1 >= window {
+            result.push(values[queue[0]]);
+        }
+    }
+    result
+}
+
+/// 滚动窗口
+///
+/// # 功能
+/// 对长度为 `window` 的滑动窗口计算聚合值，只输出完整窗口。
+/// 内置聚合（"SUM"、"MEAN"/"AVG"、"MIN"、"MAX"）一次遍历完成，
+/// 复杂度 O(n)，与窗口大小无关；也可以传入函数，以窗口数组调用。
+///
+/// # 参数
+/// - `array`: Array - 数字数组
+/// - `window`: Number - 窗口大小（正整数）
+/// - `fn`: String/Function - 聚合名称或接收窗口数组的函数
+///
+/// # 返回值
+/// Array - 长度为 n - window + 1 的数组（window > n 时为空数组）
+///
+/// # 示例
+/// ```aether
+/// Set ma Rolling([1, 2, 3, 4, 5], 3, "MEAN")      # [2, 3, 4]
+/// Set hi Rolling([3, 1, 4, 1, 5], 2, "MAX")       # [3, 4, 4, 5]
+/// Set spread Rolling(PRICES, 5, Lambda W -> MAX(W) - MIN(W))
+/// ```
+pub fn rolling(args: &[Value]) -> Result<Value, RuntimeError> {
+    if args.len() != 3 {
+        return Err(RuntimeError::WrongArity {
+            expected: 3,
+            got: args.len(),
+        });
+    }
+
+    let window = positive_count(&args[1], "Window")?;
+    let aggregate = match &args[2] {
+        Value::String(name) => RollingAggregate::parse(name).ok_or_else(|| {
+            RuntimeError::InvalidOperation(format!(
+                "Unknown rolling aggregate '{}', expected SUM, MEAN, MIN, MAX or a function",
+                name
+            ))
+        })?,
+        other => {
+            return Err(RuntimeError::TypeErrorDetailed {
+                expected: "String or Function".to_string(),
+                got: other.type_name().to_string(),
+            });
+        }
+    };
+
+    let values = match Series::from_value(&args[0])? {
+        Series::Float(values) => values,
+        Series::Exact(values) => values
+            .iter()
+            .map(|r| r.to_f64().unwrap_or(f64::NAN))
+            .collect(),
+    };
+    if window > values.len() {
+        return Ok(Value::Array(Vec::new()));
+    }
+
+    let result = match aggregate {
+        RollingAggregate::Sum | RollingAggregate::Mean => {
+            let mut sum: f64 = values[..window].iter().sum();
+            let mut sums = vec![sum];
+            for i in window..values.len() {
+                sum += values[i] - values[i - window];
+                sums.push(sum);
+            }
+            if aggregate == RollingAggregate::Mean {
+                sums.iter_mut().for_each(|s| *s /= window as f64);
+            }
+            sums
+        }
+        RollingAggregate::Min => rolling_extreme(&values, window, |a, b| a < b),
+        RollingAggregate::Max => rolling_extreme(&values, window, |a, b| a > b),
+    };
+    Ok(Value::Array(
+        result.into_iter().map(Value::Number).collect(),
+    ))
+}
+
+/// 滑动窗口（供以函数聚合的 ROLLING 使用）
+pub(crate) fn rolling_windows(args: &[Value]) -> Result<Vec<Value>, RuntimeError> {
+    if args.len() != 3 {
+        return Err(RuntimeError::WrongArity {
+            expected: 3,
+            got: args.len(),
+        });
+    }
+    let window = positive_count(&args[1], "Window")?;
+    match &args[0] {
+        Value::Array(items) => Ok(items
+            .windows(window)
+            .map(|w| Value::Array(w.to_vec()))
+            .collect()),
+        other => Err(RuntimeError::TypeErrorDetailed {
+            expected: "Array".to_string(),
+            got: other.type_name().to_string(),
+        }),
+    }
+}
+
 // ============================================================================
 // Vector Operations (NumPy-like)
 // ============================================================================
@@ -2081,53 +2328,12 @@ pub fn matmul(args: &[Value]) -> Result<Value, RuntimeError> {
 
             // Perform multiplication
             let mut result = Vec::new();
-            #[allow(clippy::needless_range_loop)]
             for i in 0..rows_a {
-                let row_a = match &a[i] {
-                    Value::Array(r) => r,
-                    _ => {
-                        return Err(RuntimeError::TypeErrorDetailed {
-                            expected: "2D Array".to_string(),
-                            got: "Non-uniform array structure".to_string(),
-                        });
-                    }
-                };
-
                 let mut result_row = Vec::new();
                 for j in 0..cols_b {
                     let mut sum = 0.0;
                     for k in 0..cols_a {
-                        let a_val = match &row_a[k] {
-                            Value::Number(n) => *n,
-                            _ => {
-                                return Err(RuntimeError::TypeErrorDetailed {
-                                    expected: "Number".to_string(),
-                                    got: format!("{:?}", row_a[k]),
-                                });
-                            }
-                        };
-
-                        let row_b = match &b[k] {
-                            Value::Array(r) => r,
-                            _ => {
-                                return Err(RuntimeError::TypeErrorDetailed {
-                                    expected: "2D Array".to_string(),
-                                    got: "Non-uniform array structure".to_string(),
-                                });
-                            }
-                        };
-
-                        let b_val = match &row_b[j] {
-                            Value::Number(n) => *n,
-                            _ => {
-                                return Err(RuntimeError::TypeErrorDetailed {
-                                    expected: "Number".to_string(),
-                                    got: format!("{:?}", row_b[j]),
-                                });
-                            }
-                        };
-
-                        sum += a_val * b_val;
+                        sum += get_matrix_element(a, i, k)? * get_matrix_element(b, k, j)?;
                     }
                     result_row.push(Value::Number(sum));
                 }
@@ -2563,12 +2769,13 @@ pub fn matrix_inverse(args: &[Value]) -> Result<Value, RuntimeError> {
 
 // Helper function for matrix element access
 fn get_matrix_element(matrix: &[Value], i: usize, j: usize) -> Result<f64, RuntimeError> {
-    match &matrix[i] {
-        Value::Array(row) => match &row[j] {
+    let ragged = || RuntimeError::InvalidOperation("Matrix rows must have the same length".into());
+    match matrix.get(i).ok_or_else(ragged)? {
+        Value::Array(row) => match row.get(j).ok_or_else(ragged)? {
             Value::Number(n) => Ok(*n),
-            _ => Err(RuntimeError::TypeErrorDetailed {
+            other => Err(RuntimeError::TypeErrorDetailed {
                 expected: "Number".to_string(),
-                got: format!("{:?}", row[j]),
+                got: format!("{:?}", other),
             }),
         },
         _ => Err(RuntimeError::TypeErrorDetailed {
@@ -2578,6 +2785,668 @@ fn get_matrix_element(matrix: &[Value], i: usize, j: usize) -> Result<f64, Runti
     }
 }
 
+// ============================================================================
+// Matrix Constructors & Linear Algebra
+// ============================================================================
+
+/// 矩阵计算使用的数值容差
+const MATRIX_EPSILON: f64 = 1e-10;
+
+/// 特征值迭代的最大次数
+const EIGEN_MAX_ITERATIONS: usize = 10_000;
+
+/// 将二维数组转换为数值矩阵（要求每行长度一致）
+fn to_matrix(value: &Value) -> Result<Vec<Vec<f64>>, RuntimeError> {
+    let rows = match value {
+        Value::Array(rows) => rows,
+        other => {
+            return Err(RuntimeError::TypeErrorDetailed {
+                expected: "2D Array".to_string(),
+                got: other.type_name().to_string(),
+            });
+        }
+    };
+    if rows.is_empty() {
+        return Err(RuntimeError::InvalidOperation(
+            "Matrix is empty".to_string(),
+        ));
+    }
+
+    let mut matrix = Vec::with_capacity(rows.len());
+    for row in rows {
+        let Value::Array(row) = row else {
+            return Err(RuntimeError::TypeErrorDetailed {
+                expected: "2D Array".to_string(),
+                got: format!("Array containing {}", row.type_name()),
+            });
+        };
+        let numbers = row
+            .iter()
+            .map(|v| match v {
+                Value::Number(n) => Ok(*n),
+                other => Err(RuntimeError::TypeErrorDetailed {
+                    expected: "Number".to_string(),
+                    got: other.type_name().to_string(),
+                }),
+            })
+            .collect::<Result<Vec<f64>, _>>()?;
+        matrix.push(numbers);
+    }
+
+    let cols = matrix[0].len();
+    if cols == 0 || matrix.iter().any(|row| row.len() != cols) {
+        return Err(RuntimeError::InvalidOperation(
+            "Matrix rows must be non-empty and of equal length".to_string(),
+        ));
+    }
+    Ok(matrix)
+}
+
+/// 将方阵转换为数值矩阵
+fn to_square_matrix(value: &Value) -> Result<Vec<Vec<f64>>, RuntimeError> {
+    let matrix = to_matrix(value)?;
+    if matrix.len() != matrix[0].len() {
+        return Err(RuntimeError::InvalidOperation(
+            "Matrix must be square".to_string(),
+        ));
+    }
+    Ok(matrix)
+}
+
+/// 数值矩阵转换为二维数组
+fn from_matrix(matrix: Vec<Vec<f64>>) -> Value {
+    Value::Array(
+        matrix
+            .into_iter()
+            .map(|row| Value::Array(row.into_iter().map(Value::Number).collect()))
+            .collect(),
+    )
+}
+
+/// 读取维度参数（正整数）
+fn dimension(value: &Value, what: &str) -> Result<usize, RuntimeError> {
+    match value {
+        Value::Number(n) if *n >= 0.0 && n.fract() == 0.0 => Ok(*n as usize),
+        other => Err(RuntimeError::InvalidOperation(format!(
+            "{} must be a non-negative integer, got {}",
+            what, other
+        ))),
+    }
+}
+
+/// 创建填充指定值的向量或矩阵
+fn filled(args: &[Value], fill: f64) -> Result<Value, RuntimeError> {
+    match args {
+        [n] => Ok(Value::Array(vec![
+            Value::Number(fill);
+            dimension(n, "Size")?
+        ])),
+        [rows, cols] => {
+            let rows = dimension(rows, "Rows")?;
+            let cols = dimension(cols, "Columns")?;
+            Ok(from_matrix(vec![vec![fill; cols]; rows]))
+        }
+        _ => Err(RuntimeError::WrongArity {
+            expected: 2,
+            got: args.len(),
+        }),
+    }
+}
+
+/// 单位矩阵
+///
+/// # 功能
+/// 创建 n×n 单位矩阵（对角线为 1，其余为 0）。
+///
+/// # 参数
+/// - `n`: Number - 矩阵阶数
+///
+/// # 返回值
+/// Array - n×n 单位矩阵
+///
+/// # 示例
+/// ```aether
+/// Set I IDENTITY_MATRIX(2)    # [[1, 0], [0, 1]]
+/// ```
+pub fn identity_matrix(args: &[Value]) -> Result<Value, RuntimeError> {
+    if args.len() != 1 {
+        return Err(RuntimeError::WrongArity {
+            expected: 1,
+            got: args.len(),
+        });
+    }
+    let n = dimension(&args[0], "Size")?;
+    Ok(from_matrix(
+        (0..n)
+            .map(|i| (0..n).map(|j| if i == j { 1.0 } else { 0.0 }).collect())
+            .collect(),
+    ))
+}
+
+/// 全零向量或矩阵
+///
+/// # 功能
+/// 创建元素全为 0 的向量或矩阵。
+///
+/// # 参数
+/// - `rows`: Number - 行数（只有一个参数时为向量长度）
+/// - `cols`: Number - 列数（可选）
+///
+/// # 返回值
+/// Array - 全零向量或 rows×cols 矩阵
+///
+/// # 示例
+/// ```aether
+/// Set v Zeros(3)              # [0, 0, 0]
+/// Set M Zeros(2, 3)           # [[0, 0, 0], [0, 0, 0]]
+/// ```
+pub fn zeros(args: &[Value]) -> Result<Value, RuntimeError> {
+    filled(args, 0.0)
+}
+
+/// 全一向量或矩阵
+///
+/// # 功能
+/// 创建元素全为 1 的向量或矩阵。
+///
+/// # 参数
+/// - `rows`: Number - 行数（只有一个参数时为向量长度）
+/// - `cols`: Number - 列数（可选）
+///
+/// # 返回值
+/// Array - 全一向量或 rows×cols 矩阵
+///
+/// # 示例
+/// ```aether
+/// Set v Ones(2)               # [1, 1]
+/// Set M Ones(2, 2)            # [[1, 1], [1, 1]]
+/// ```
+pub fn ones(args: &[Value]) -> Result<Value, RuntimeError> {
+    filled(args, 1.0)
+}
+
+/// 重塑矩阵
+///
+/// # 功能
+/// 按行优先顺序把向量或矩阵的元素重新排列为 rows×cols 矩阵。
+///
+/// # 参数
+/// - `array`: Array - 向量或二维数组
+/// - `rows`: Number - 新的行数
+/// - `cols`: Number - 新的列数
+///
+/// # 返回值
+/// Array - rows×cols 矩阵
+///
+/// # 错误
+/// - 元素总数与 rows×cols 不一致时抛出错误
+///
+/// # 示例
+/// ```aether
+/// Set M Reshape([1, 2, 3, 4, 5, 6], 2, 3)     # [[1, 2, 3], [4, 5, 6]]
+/// Set N Reshape(M, 3, 2)                      # [[1, 2], [3, 4], [5, 6]]
+/// ```
+pub fn reshape(args: &[Value]) -> Result<Value, RuntimeError> {
+    if args.len() != 3 {
+        return Err(RuntimeError::WrongArity {
+            expected: 3,
+            got: args.len(),
+        });
+    }
+
+    let items = match &args[0] {
+        Value::Array(items) => items,
+        other => {
+            return Err(RuntimeError::TypeErrorDetailed {
+                expected: "Array".to_string(),
+                got: other.type_name().to_string(),
+            });
+        }
+    };
+    let flat: Vec<Value> = items
+        .iter()
+        .flat_map(|item| match item {
+            Value::Array(row) => row.clone(),
+            other => vec![other.clone()],
+        })
+        .collect();
+
+    let rows = dimension(&args[1], "Rows")?;
+    let cols = dimension(&args[2], "Columns")?;
+    if rows * cols != flat.len() {
+        return Err(RuntimeError::InvalidOperation(format!(
+            "Cannot reshape {} elements into {}x{}",
+            flat.len(),
+            rows,
+            cols
+        )));
+    }
+    if cols == 0 {
+        return Ok(Value::Array(vec![Value::Array(Vec::new()); rows]));
+    }
+
+    Ok(Value::Array(
+        flat.chunks(cols)
+            .map(|chunk| Value::Array(chunk.to_vec()))
+            .collect(),
+    ))
+}
+
+/// 解线性方程组
+///
+/// # 功能
+/// 求解 A·x = b（高斯消元，部分主元）。
+///
+/// # 参数
+/// - `A`: Array - n×n 系数矩阵
+/// - `b`: Array - 长度为 n 的向量，或 n×k 矩阵（同时求解 k 组）
+///
+/// # 返回值
+/// Array - 解向量 x（b 为矩阵时返回 n×k 矩阵）
+///
+/// # 错误
+/// - 系数矩阵奇异或维度不匹配时抛出错误
+///
+/// # 示例
+/// ```aether
+/// Set A [[2, 1], [1, 3]]
+/// Set x Solve(A, [3, 5])      # [0.8, 1.4]
+/// ```
+pub fn solve(args: &[Value]) -> Result<Value, RuntimeError> {
+    if args.len() != 2 {
+        return Err(RuntimeError::WrongArity {
+            expected: 2,
+            got: args.len(),
+        });
+    }
+
+    let a = to_square_matrix(&args[0])?;
+    let n = a.len();
+    // 右侧为一维向量时按 n×1 矩阵求解
+    let vector_rhs = match &args[1] {
+        Value::Array(items) if items.iter().all(|v| matches!(v, Value::Number(_))) => Some(
+            items
+                .iter()
+                .map(|v| Value::Array(vec![v.clone()]))
+                .collect(),
+        ),
+        _ => None,
+    };
+    let b = match &vector_rhs {
+        Some(columns) => to_matrix(&Value::Array(Vec::clone(columns)))?,
+        None => to_matrix(&args[1])?,
+    };
+    if b.len() != n {
+        return Err(RuntimeError::InvalidOperation(format!(
+            "Right-hand side has {} rows, expected {}",
+            b.len(),
+            n
+        )));
+    }
+
+    let x = solve_linear(a, b)?;
+    if vector_rhs.is_some() {
+        Ok(Value::Array(
+            x.into_iter().map(|row| Value::Number(row[0])).collect(),
+        ))
+    } else {
+        Ok(from_matrix(x))
+    }
+}
+
+/// 高斯消元求解 A·X = B（部分主元）
+#[allow(clippy::needless_range_loop)]
+fn solve_linear(mut a: Vec<Vec<f64>>, mut b: Vec<Vec<f64>>) -> Result<Vec<Vec<f64>>, RuntimeError> {
+    let n = a.len();
+    for col in 0..n {
+        let pivot = (col..n)
+            .max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))
+            .unwrap_or(col);
+        if a[pivot][col].abs() < MATRIX_EPSILON {
+            return Err(RuntimeError::InvalidOperation(
+                "Matrix is singular (no unique solution)".to_string(),
+            ));
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+
+        for row in (col + 1)..n {
+            let factor = a[row][col] / a[col][col];
+            if factor == 0.0 {
+                continue;
+            }
+            for k in col..n {
+                a[row][k] -= factor * a[col][k];
+            }
+            for k in 0..b[row].len() {
+                b[row][k] -= factor * b[col][k];
+            }
+        }
+    }
+
+    // 回代
+    let width = b[0].len();
+    let mut x = vec![vec![0.0; width]; n];
+    for row in (0..n).rev() {
+        for k in 0..width {
+            let sum: f64 = ((row + 1)..n).map(|j| a[row][j] * x[j][k]).sum();
+            x[row][k] = (b[row][k] - sum) / a[row][row];
+        }
+    }
+    Ok(x)
+}
+
+/// 矩阵的秩
+///
+/// # 功能
+/// 通过行阶梯形计算矩阵的秩（支持非方阵）。
+///
+/// # 参数
+/// - `matrix`: Array - 二维数组
+///
+/// # 返回值
+/// Number - 线性无关的行（列）数
+///
+/// # 示例
+/// ```aether
+/// Set r Rank([[1, 2], [2, 4]])        # 1
+/// Set r Rank([[1, 0], [0, 1]])        # 2
+/// ```
+#[allow(clippy::needless_range_loop)]
+pub fn rank(args: &[Value]) -> Result<Value, RuntimeError> {
+    if args.len() != 1 {
+        return Err(RuntimeError::WrongArity {
+            expected: 1,
+            got: args.len(),
+        });
+    }
+
+    let mut m = to_matrix(&args[0])?;
+    let (rows, cols) = (m.len(), m[0].len());
+    let scale = m
+        .iter()
+        .flatten()
+        .fold(0.0_f64, |acc, v| acc.max(v.abs()))
+        .max(1.0);
+
+    let mut rank = 0;
+    for col in 0..cols {
+        if rank == rows {
+            break;
+        }
+        let pivot = (rank..rows)
+            .max_by(|&i, &j| m[i][col].abs().total_cmp(&m[j][col].abs()))
+            .unwrap_or(rank);
+        if m[pivot][col].abs() <= MATRIX_EPSILON * scale {
+            continue;
+        }
+        m.swap(rank, pivot);
+        for row in (rank + 1)..rows {
+            let factor = m[row][col] / m[rank][col];
+            for k in col..cols {
+                m[row][k] -= factor * m[rank][k];
+            }
+        }
+        rank += 1;
+    }
+    Ok(Value::Number(rank as f64))
+}
+
+/// 判断方阵是否对称
+fn is_symmetric(m: &[Vec<f64>]) -> bool {
+    let n = m.len();
+    (0..n).all(|i| {
+        (0..i).all(|j| (m[i][j] - m[j][i]).abs() <= MATRIX_EPSILON * (1.0 + m[i][j].abs()))
+    })
+}
+
+/// 对称矩阵的特征分解（Jacobi 旋转法），返回 (特征值, 按列排列的特征向量)
+#[allow(clippy::needless_range_loop)]
+fn jacobi_eigen(mut a: Vec<Vec<f64>>) -> (Vec<f64>, Vec<Vec<f64>>) {
+    let n = a.len();
+    let mut v: Vec<Vec<f64>> = (0..n)
+        .map(|i| (0..n).map(|j| if i == j { 1.0 } else { 0.0 }).collect())
+        .collect();
+
+    for _ in 0..EIGEN_MAX_ITERATIONS {
+        // 选取绝对值最大的非对角元
+        let mut p = 0;
+        let mut q = 1;
+        let mut max = 0.0;
+        for i in 0..n {
+            for j in (i + 1)..n {
+                if a[i][j].abs() > max {
+                    max = a[i][j].abs();
+                    p = i;
+                    q = j;
+                }
+            }
+        }
+        if max < MATRIX_EPSILON {
+            break;
+        }
+
+        let theta = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+        let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+        let t = if theta == 0.0 { 1.0 } else { t };
+        let c = 1.0 / (t * t + 1.0).sqrt();
+        let s = t * c;
+
+        for k in 0..n {
+            let (akp, akq) = (a[k][p], a[k][q]);
+            a[k][p] = c * akp - s * akq;
+            a[k][q] = s * akp + c * akq;
+        }
+        for k in 0..n {
+            let (apk, aqk) = (a[p][k], a[q][k]);
+            a[p][k] = c * apk - s * aqk;
+            a[q][k] = s * apk + c * aqk;
+        }
+        for row in v.iter_mut() {
+            let (vkp, vkq) = (row[p], row[q]);
+            row[p] = c * vkp - s * vkq;
+            row[q] = s * vkp + c * vkq;
+        }
+    }
+
+    let values = (0..n).map(|i| a[i][i]).collect();
+    let vectors = (0..n).map(|j| (0..n).map(|i| v[i][j]).collect()).collect();
+    (values, vectors)
+}
+
+/// 一般实矩阵的特征值（QR 迭代）
+fn qr_eigenvalues(mut a: Vec<Vec<f64>>) -> Result<Vec<f64>, RuntimeError> {
+    let n = a.len();
+    for _ in 0..EIGEN_MAX_ITERATIONS {
+        // Gram-Schmidt QR 分解
+        let mut q = vec![vec![0.0; n]; n];
+        let mut r = vec![vec![0.0; n]; n];
+        for j in 0..n {
+            let mut v: Vec<f64> = (0..n).map(|i| a[i][j]).collect();
+            for k in 0..j {
+                let dot: f64 = (0..n).map(|i| q[i][k] * a[i][j]).sum();
+                r[k][j] = dot;
+                for (i, vi) in v.iter_mut().enumerate() {
+                    *vi -= dot * q[i][k];
+                }
+            }
+            let norm = v.iter().map(|x| x * x).sum::<f64>().sqrt();
+            r[j][j] = norm;
+            for i in 0..n {
+                q[i][j] = if norm > 0.0 { v[i] / norm } else { 0.0 };
+            }
+        }
+        // A = R·Q
+        a = (0..n)
+            .map(|i| {
+                (0..n)
+                    .map(|j| (0..n).map(|k| r[i][k] * q[k][j]).sum())
+                    .collect()
+            })
+            .collect();
+
+        let converged = (1..n).all(|i| (0..i).all(|j| a[i][j].abs() < MATRIX_EPSILON));
+        if converged {
+            return Ok((0..n).map(|i| a[i][i]).collect());
+        }
+    }
+    Err(RuntimeError::InvalidOperation(
+        "Eigenvalue iteration did not converge (the matrix may have complex eigenvalues)"
+            .to_string(),
+    ))
+}
+
+/// 通过反迭代求特征值对应的特征向量
+fn inverse_iteration(a: &[Vec<f64>], lambda: f64) -> Vec<f64> {
+    let n = a.len();
+    // 轻微偏移避免 (A - λI) 完全奇异
+    let shift = lambda + MATRIX_EPSILON.sqrt() * (1.0 + lambda.abs());
+    let shifted: Vec<Vec<f64>> = (0..n)
+        .map(|i| {
+            (0..n)
+                .map(|j| a[i][j] - if i == j { shift } else { 0.0 })
+                .collect()
+        })
+        .collect();
+
+    let mut x = vec![1.0; n];
+    for _ in 0..50 {
+        let rhs = x.iter().map(|v| vec![*v]).collect();
+        let Ok(y) = solve_linear(shifted.clone(), rhs) else {
+            break;
+        };
+        let norm = y.iter().map(|r| r[0] * r[0]).sum::<f64>().sqrt();
+        if norm == 0.0 || !norm.is_finite() {
+            break;
+        }
+        x = y.into_iter().map(|r| r[0] / norm).collect();
+    }
+    x
+}
+
+/// 归一化特征向量：单位长度，且绝对值最大的分量为正（保证结果稳定）
+fn normalize_eigenvector(mut v: Vec<f64>) -> Vec<f64> {
+    let norm = v.iter().map(|x| x * x).sum::<f64>().sqrt();
+    if norm > 0.0 {
+        v.iter_mut().for_each(|x| *x /= norm);
+    }
+    let largest = v
+        .iter()
+        .copied()
+        .max_by(|a, b| a.abs().total_cmp(&b.abs()))
+        .unwrap_or(0.0);
+    if largest < 0.0 {
+        v.iter_mut().for_each(|x| *x = -*x);
+    }
+    v
+}
+
+/// 特征分解，特征值按从大到小排列
+fn eigen_decompose(
+    matrix: Vec<Vec<f64>>,
+    with_vectors: bool,
+) -> Result<Vec<(f64, Vec<f64>)>, RuntimeError> {
+    let mut pairs: Vec<(f64, Vec<f64>)> = if is_symmetric(&matrix) {
+        let (values, vectors) = jacobi_eigen(matrix);
+        values.into_iter().zip(vectors).collect()
+    } else {
+        let values = qr_eigenvalues(matrix.clone())?;
+        values
+            .into_iter()
+            .map(|lambda| {
+                let vector = if with_vectors {
+                    inverse_iteration(&matrix, lambda)
+                } else {
+                    Vec::new()
+                };
+                (lambda, vector)
+            })
+            .collect()
+    };
+
+    pairs.sort_by(|a, b| b.0.total_cmp(&a.0));
+    Ok(pairs
+        .into_iter()
+        .map(|(value, vector)| {
+            // 消除 -0 与浮点噪声
+            let value = if value.abs() < MATRIX_EPSILON {
+                0.0
+            } else {
+                value
+            };
+            (value, normalize_eigenvector(vector))
+        })
+        .collect())
+}
+
+/// 特征值
+///
+/// # 功能
+/// 计算方阵的实特征值，按从大到小排列。
+/// 对称矩阵使用 Jacobi 旋转法，其他矩阵使用 QR 迭代。
+///
+/// # 参数
+/// - `matrix`: Array - n×n 方阵
+///
+/// # 返回值
+/// Array - 特征值数组
+///
+/// # 错误
+/// - 矩阵有复数特征值（迭代不收敛）时抛出错误
+///
+/// # 示例
+/// ```aether
+/// Set vals Eigenvalues([[2, 0], [0, 3]])      # [3, 2]
+/// Set vals Eigenvalues([[2, 1], [1, 2]])      # [3, 1]
+/// ```
+pub fn eigenvalues(args: &[Value]) -> Result<Value, RuntimeError> {
+    if args.len() != 1 {
+        return Err(RuntimeError::WrongArity {
+            expected: 1,
+            got: args.len(),
+        });
+    }
+    let matrix = to_square_matrix(&args[0])?;
+    let pairs = eigen_decompose(matrix, false)?;
+    Ok(Value::Array(
+        pairs.into_iter().map(|(v, _)| Value::Number(v)).collect(),
+    ))
+}
+
+/// 特征向量
+///
+/// # 功能
+/// 计算方阵的特征向量，顺序与 `EIGENVALUES` 的结果一致。
+/// 每个特征向量为单位向量，且绝对值最大的分量为正。
+///
+/// # 参数
+/// - `matrix`: Array - n×n 方阵
+///
+/// # 返回值
+/// Array - 特征向量数组（第 i 个元素对应第 i 个特征值）
+///
+/// # 示例
+/// ```aether
+/// Set vecs Eigenvectors([[2, 1], [1, 2]])
+/// # [[0.7071, 0.7071], [-0.7071, 0.7071]]
+/// ```
+pub fn eigenvectors(args: &[Value]) -> Result<Value, RuntimeError> {
+    if args.len() != 1 {
+        return Err(RuntimeError::WrongArity {
+            expected: 1,
+            got: args.len(),
+        });
+    }
+    let matrix = to_square_matrix(&args[0])?;
+    let pairs = eigen_decompose(matrix, true)?;
+    Ok(Value::Array(
+        pairs
+            .into_iter()
+            .map(|(_, vector)| Value::Array(vector.into_iter().map(Value::Number).collect()))
+            .collect(),
+    ))
+}
+
 // ============================================================================
 // Mathematical Constants
 // ============================================================================
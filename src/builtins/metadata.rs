@@ -0,0 +1,173 @@
+// src/builtins/metadata.rs
+//! 内置函数元数据
+//!
+//! [`BuiltInRegistry::metadata`] 为每个已注册的函数汇总分类、所需权限、是否纯函数、
+//! 是否读写外部状态、参数个数范围和文档入口。HELP 列表、静态分析的 IO 检测和宿主的函数白名单界面
+//! 都从这里读取，不再各自维护函数名单。
+//!
+//! 所需权限不是手写的名单，而是由注册表本身推导：只在授予某项权限后才会注册的函数
+//! 即需要该权限（启用文件系统后换成支持文件选项的 PLOT_LINE 等函数不算）。是否纯函数、
+//! 是否读写外部状态在注册时声明，需要权限才注册的函数（包括上述替换版本）都读写外部状态。
+
+use super::{BuiltInRegistry, Deprecation, IOPermissions, args, help};
+use crate::analysis::Permission;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// 一个内置函数的元数据
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BuiltinMetadata {
+    /// 函数名
+    pub name: String,
+    /// 所属分类（与 HELP() 列表和 DOC() 的 category 字段一致）
+    pub category: Option<&'static str>,
+    /// 调用所需的权限
+    pub permission: Option<Permission>,
+    /// 是否为纯函数（结果只取决于参数；注册时声明，默认不纯）
+    pub pure: bool,
+    /// 是否读写外部状态或依赖真实时间（确定性模式下禁止调用）
+    pub io: bool,
+    /// 最少参数个数
+    pub min_args: usize,
+    /// 最多参数个数（`None` 表示不限）
+    pub max_args: Option<usize>,
+    /// 签名，如 `ROUND_TO(value, decimals)`
+    pub signature: String,
+    /// 一句话描述（有文档时）
+    pub summary: Option<String>,
+    /// 查看完整文档的方式，如 `DOC("ROUND_TO")`（有文档时）
+    pub doc_link: Option<String>,
+    /// 弃用信息
+    pub deprecation: Option<Deprecation>,
+    /// 实验性函数所需的特性名
+    pub experimental: Option<String>,
+    /// 是否为宿主或插件注册的原生函数
+    pub native: bool,
+}
+
+impl BuiltinMetadata {
+    /// 是否接受 `count` 个参数
+    pub fn accepts(&self, count: usize) -> bool {
+        count >= self.min_args && self.max_args.is_none_or(|max| count <= max)
+    }
+}
+
+impl BuiltInRegistry {
+    /// 所有已注册函数的元数据，按名称排序
+    pub fn metadata(&self) -> Vec<BuiltinMetadata> {
+        let mut names = self.names();
+        names.sort();
+        names
+            .iter()
+            .filter_map(|name| self.metadata_for(name))
+            .collect()
+    }
+
+    /// 单个函数的元数据（未注册时返回 None）
+    pub fn metadata_for(&self, name: &str) -> Option<BuiltinMetadata> {
+        let arity = self.arity(name)?;
+        let native = self.natives.contains_key(name);
+        let (min_args, max_args) = if native {
+            (arity, Some(arity))
+        } else if let Some(&range) = self.arg_ranges.get(name) {
+            range
+        } else if let Some(spec) = args::spec_for(name) {
+            let max = spec.rest.is_none().then_some(spec.params.len());
+            (spec.required_count(), max)
+        } else {
+            (arity, Some(arity))
+        };
+
+        let summary = match self.docs.get(name) {
+            Some(doc) => Some(doc.description.clone()),
+            None => help::function_doc(name).map(|doc| doc.description.clone()),
+        };
+        let permission = required_permission(name);
+
+        Some(BuiltinMetadata {
+            name: name.to_string(),
+            category: help::function_category(name),
+            permission,
+            pure: self.is_pure(name),
+            io: self.is_io(name),
+            min_args,
+            max_args,
+            signature: self.signature(name)?,
+            doc_link: summary.as_ref().map(|_| format!("DOC(\"{}\")", name)),
+            summary,
+            deprecation: self.deprecations.get(name).cloned(),
+            experimental: self.experimental.get(name).cloned(),
+            native,
+        })
+    }
+}
+
+/// 内置函数所需的权限（不需要权限或不是内置函数时返回 None）
+pub fn required_permission(name: &str) -> Option<Permission> {
+    gated_builtins().get(name).copied()
+}
+
+/// 只有授予权限后才会注册的函数 -> 所需权限
+fn gated_builtins() -> &'static HashMap<String, Permission> {
+    static GATED: OnceLock<HashMap<String, Permission>> = OnceLock::new();
+    GATED.get_or_init(|| {
+        let base = BuiltInRegistry::with_permissions(IOPermissions::deny_all());
+        let mut gated = HashMap::new();
+        for (permission, permissions) in [
+            (
+                Permission::Filesystem,
+                IOPermissions {
+                    filesystem_enabled: true,
+                    network_enabled: false,
+                },
+            ),
+            (
+                Permission::Network,
+                IOPermissions {
+                    filesystem_enabled: false,
+                    network_enabled: true,
+                },
+            ),
+        ] {
+            let registry = BuiltInRegistry::with_permissions(permissions);
+            for name in registry.functions.keys() {
+                if !base.functions.contains_key(name) {
+                    gated.insert(name.clone(), permission);
+                }
+            }
+        }
+        gated
+    })
+}
+
+/// 默认注册表中的内置函数（授予与不授予权限两种情况）-> 是否为纯函数
+///
+/// 静态分析不持有引擎的注册表，由此判断函数的性质；两种情况下都是纯函数才算纯函数。
+fn builtin_purity() -> &'static HashMap<String, bool> {
+    static PURITY: OnceLock<HashMap<String, bool>> = OnceLock::new();
+    PURITY.get_or_init(|| {
+        let mut purity = HashMap::new();
+        for permissions in [IOPermissions::deny_all(), IOPermissions::allow_all()] {
+            let registry = BuiltInRegistry::with_permissions(permissions);
+            for name in registry.functions.keys() {
+                let pure = registry.is_pure(name);
+                purity
+                    .entry(name.clone())
+                    .and_modify(|p: &mut bool| *p &= pure)
+                    .or_insert(pure);
+            }
+        }
+        purity
+    })
+}
+
+/// 名称是否为默认注册表中的纯内置函数
+pub fn is_pure_builtin(name: &str) -> bool {
+    builtin_purity().get(name).copied().unwrap_or(false)
+}
+
+/// 名称是否为默认注册表中的不纯内置函数（未知名称返回 false）
+pub fn is_known_impure_builtin(name: &str) -> bool {
+    builtin_purity().get(name).is_some_and(|pure| !pure)
+}
@@ -3,27 +3,91 @@
 
 use crate::evaluator::RuntimeError;
 use crate::value::Value;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::ops::{Bound, RangeBounds};
+use std::rc::Rc;
 
 // Module declarations
+pub mod args;
 pub mod array;
+pub mod channel;
+#[cfg(feature = "collation")]
+pub mod collation;
+pub mod datetime;
 pub mod dict;
+pub mod display;
+#[cfg(feature = "io")]
+pub mod email;
+pub mod entropy;
+pub mod export;
 pub mod filesystem;
 pub mod help;
+#[cfg(feature = "http-server")]
+pub mod http_server;
+pub mod i18n;
+pub mod introspect;
 pub mod io;
 pub mod json;
+pub mod kv;
 pub mod math;
+pub mod metadata;
+#[cfg(feature = "io")]
 pub mod network;
+pub mod parallel;
+pub mod patch;
+#[cfg(feature = "payroll")]
 pub mod payroll;
+pub mod plot;
 pub mod precise;
 pub mod report;
+pub mod resilience;
+#[cfg(feature = "s3")]
+pub mod s3;
+pub mod schedule;
+pub mod schema;
+pub mod secrets;
+pub mod set;
+pub mod snapshot;
 pub mod string;
+pub mod table;
+pub mod template;
 pub mod trace;
 pub mod types;
+pub mod validation;
+#[cfg(feature = "async")]
+pub mod websocket;
 
 /// Type alias for built-in function implementations
 pub type BuiltInFn = fn(&[Value]) -> Result<Value, RuntimeError>;
 
+/// 携带状态的原生函数（宿主注册的闭包、插件动态库中的函数），与 `BuiltInFn` 不同可以捕获数据
+///
+/// 引擎本身不跨线程，闭包无需 `Send`，可以捕获 `Rc<RefCell<_>>` 等宿主状态。
+pub type NativeFn = Rc<dyn Fn(&[Value]) -> Result<Value, RuntimeError>>;
+
+/// 直接接收 Integer 参数的内置函数
+///
+/// 其余内置函数早于 Integer 类型，参数中的 Integer（包括嵌套在数组、字典、表格中的）
+/// 在调用前转换为相等的 Number。这里只列出查询类型、显示、序列化或原样传递值的函数，
+/// 它们保留整数的精确值。
+const INTEGER_AWARE: &[&str] = &[
+    "TYPE",
+    "TO_STRING",
+    "TO_INT",
+    "PRINT",
+    "PRINTLN",
+    "JSON_STRINGIFY",
+    "LEN",
+    "PUSH",
+    "CLONE",
+    "KEYS",
+    "VALUES",
+    "MAP",
+    "FILTER",
+    "REDUCE",
+    "TRACE",
+];
+
 /// 函数文档信息
 #[derive(Debug, Clone)]
 pub struct FunctionDoc {
@@ -39,8 +103,15 @@ pub struct FunctionDoc {
     pub example: Option<String>,
 }
 
+/// 内置函数的弃用信息
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct Deprecation {
+    /// 推荐使用的替代函数
+    pub replacement: Option<String>,
+}
+
 /// IO 权限配置
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct IOPermissions {
     /// 是否允许文件系统操作
     pub filesystem_enabled: bool,
@@ -64,10 +135,16 @@ impl IOPermissions {
 }
 
 /// Registry of all built-in functions
+#[derive(Clone)]
 pub struct BuiltInRegistry {
     functions: HashMap<String, (BuiltInFn, usize)>, // (function, arity)
+    natives: HashMap<String, (NativeFn, usize)>,    // 原生函数（插件等）
+    arg_ranges: HashMap<String, (usize, Option<usize>)>, // 可变参数函数的参数个数范围
     docs: HashMap<String, FunctionDoc>,             // 函数文档
-    #[allow(dead_code)]
+    deprecations: HashMap<String, Deprecation>,     // 已弃用的函数
+    experimental: HashMap<String, String>,          // 实验性函数 -> 特性名
+    pure: HashSet<String>,                          // 纯函数（见 Registration::pure）
+    io: HashSet<String>,                            // 读写外部状态的函数（见 Registration::io）
     permissions: IOPermissions,
 }
 
@@ -81,501 +158,979 @@ impl BuiltInRegistry {
     pub fn with_permissions(permissions: IOPermissions) -> Self {
         let mut registry = Self {
             functions: HashMap::new(),
+            natives: HashMap::new(),
+            arg_ranges: HashMap::new(),
             docs: HashMap::new(),
+            deprecations: HashMap::new(),
+            experimental: HashMap::new(),
+            pure: HashSet::new(),
+            io: HashSet::new(),
             permissions: permissions.clone(),
         };
 
         // Help function
-        registry.register("HELP", help::help, 0); // Variadic: 0-1 args
+        registry.register_variadic("HELP", help::help, 0, 0..=1);
+        registry.register_variadic("DOC", help::doc, 1, 0..=1);
 
         // IO functions
         registry.register("PRINT", io::print, 1);
         registry.register("PRINTLN", io::println, 1);
-        registry.register("INPUT", io::input, 1);
+        registry.register("INPUT", io::input, 1).io();
+
+        // Rich output (rendered by hosts such as the Jupyter kernel)
+        registry.register("DISPLAY", display::display, 1);
+        registry.register_variadic("IMAGE", display::image, 2, 1..=2);
+
+        // Charts (SVG)
+        registry.register_variadic("PLOT_LINE", plot::plot_line, 3, 2..=3);
+        registry.register_variadic("PLOT_BAR", plot::plot_bar, 3, 2..=3);
+        registry.register_variadic("PLOT_HIST", plot::plot_hist, 2, 1..=2);
+
+        // Report builder
+        registry.register_variadic("REPORT_BUILD", report::report_build, 2, 1..=2);
 
         // Trace (DSL-safe debug buffer; handled by evaluator)
         registry.register("TRACE", trace::trace, 1);
-        registry.register("TRACE_DEBUG", trace::trace_debug, 2); // (category, value, ...)
-        registry.register("TRACE_INFO", trace::trace_info, 2); // (category, value, ...)
-        registry.register("TRACE_WARN", trace::trace_warn, 2); // (category, value, ...)
-        registry.register("TRACE_ERROR", trace::trace_error, 2); // (category, value, ...)
+        registry.register_variadic("TRACE_DEBUG", trace::trace_debug, 2, 2..);
+        registry.register_variadic("TRACE_INFO", trace::trace_info, 2, 2..);
+        registry.register_variadic("TRACE_WARN", trace::trace_warn, 2, 2..);
+        registry.register_variadic("TRACE_ERROR", trace::trace_error, 2, 2..);
+
+        // Introspection (handled by evaluator)
+        registry.register("VARS", introspect::vars, 0);
+        registry.register("GLOBALS", introspect::globals, 0);
 
         // Array functions
-        registry.register("RANGE", array::range, 1); // Variadic: 1-3 args
-        registry.register("LEN", types::len, 1);
-        registry.register("PUSH", array::push, 2);
-        registry.register("POP", array::pop, 1);
-        registry.register("MAP", array::map, 2);
-        registry.register("FILTER", array::filter, 2);
-        registry.register("REDUCE", array::reduce, 3);
-        registry.register("JOIN", array::join, 2);
-        registry.register("REVERSE", array::reverse, 1);
-        registry.register("SORT", array::sort, 1);
-        registry.register("SUM", array::sum, 1);
-        registry.register("MAX", array::max, 1);
-        registry.register("MIN", array::min, 1);
+        registry
+            .register_variadic("RANGE", array::range, 1, 1..=3)
+            .pure();
+        registry.register("LEN", types::len, 1).pure();
+        registry.register("PUSH", array::push, 2).pure();
+        registry.register("POP", array::pop, 1).pure();
+        registry.register("MAP", array::map, 2).pure();
+        registry.register("FILTER", array::filter, 2).pure();
+        registry.register("REDUCE", array::reduce, 3).pure();
+        registry.register("JOIN", array::join, 2).pure();
+        registry.register("REVERSE", array::reverse, 1).pure();
+        registry.register("SORT", array::sort, 1).pure();
+        registry.register("BSEARCH", array::bsearch, 2).pure();
+        registry
+            .register("LOWER_BOUND", array::lower_bound, 2)
+            .pure();
+        registry
+            .register("UPPER_BOUND", array::upper_bound, 2)
+            .pure();
+        registry
+            .register("INSERT_SORTED", array::insert_sorted, 2)
+            .pure();
+        registry.register("SUM", array::sum, 1).pure();
+        registry.register("MAX", array::max, 1).pure();
+        registry.register("MIN", array::min, 1).pure();
 
         // Dict functions
-        registry.register("KEYS", dict::keys, 1);
-        registry.register("VALUES", dict::values, 1);
-        registry.register("HAS", dict::has, 2);
-        registry.register("MERGE", dict::merge, 2);
+        registry.register("KEYS", dict::keys, 1).pure();
+        registry.register("VALUES", dict::values, 1).pure();
+        registry.register("HAS", dict::has, 2).pure();
+        registry.register("MERGE", dict::merge, 2).pure();
+
+        // Structured diff / patch (DIFF 见序列聚合，两个非数字参数时返回补丁)
+        registry
+            .register("APPLY_PATCH", patch::apply_patch, 2)
+            .pure();
+
+        // Set functions
+        registry.register_variadic("SET", set::set, 1, 0..).pure();
+        registry
+            .register_variadic("UNION", set::union, 2, 2..)
+            .pure();
+        registry
+            .register_variadic("INTERSECT", set::intersect, 2, 2..)
+            .pure();
+        registry
+            .register_variadic("DIFFERENCE", set::difference, 2, 2..)
+            .pure();
+        registry.register("SUBSET", set::subset, 2).pure();
+
+        // Table functions (SORT/JOIN dispatch to tables when given one)
+        registry
+            .register_variadic("TABLE", table::table, 1, 1..=2)
+            .pure();
+        registry
+            .register_variadic("TABLE_FROM_CSV", table::table_from_csv, 1, 1..=2)
+            .pure();
+        registry.register("SELECT", table::select, 2).pure();
+        registry.register("WHERE", table::where_, 2).pure();
+        registry.register("GROUP_AGG", table::group_agg, 3).pure();
+        registry.register("TO_DICTS", table::to_dicts, 1).pure();
+        registry.register("COLUMNS", table::columns, 1).pure();
+
+        // Internationalization
+        registry.register_variadic("LOAD_MESSAGES", i18n::load_messages, 1, 1..=2);
+        registry.register_variadic("TRANSLATE", i18n::translate, 1, 1..=3);
+        registry.register_variadic("PLURAL_CATEGORY", i18n::plural, 1, 1..=2);
+        registry.register("SET_LOCALE", i18n::set_locale, 1);
+        registry.register("GET_LOCALE", i18n::get_locale, 0);
+        registry.register("CLEAR_MESSAGES", i18n::clear_messages, 0);
+
+        // Locale-aware collation (ICU rules, collation feature)
+        #[cfg(feature = "collation")]
+        {
+            registry.register_variadic("SORT_LOCALE", collation::sort_locale, 1, 1..=2);
+            registry.register_variadic("COMPARE_LOCALE", collation::compare_locale, 2, 2..=3);
+        }
+
+        // Identifier validation
+        registry
+            .register("LUHN_CHECK", validation::luhn_check, 1)
+            .pure();
+        registry.register("VALIDATE_ID_CN", validation::validate_id_cn, 1);
+        registry
+            .register("VALIDATE_IBAN", validation::validate_iban, 1)
+            .pure();
+        registry
+            .register("VALIDATE_EMAIL", validation::validate_email, 1)
+            .pure();
+
+        // Schema validation
+        registry.register("VALIDATE", schema::validate, 2).pure();
+
+        // Scheduling (handled by evaluator)
+        registry.register("SCHEDULE", schedule::schedule, 2).io();
+        registry
+            .register("UNSCHEDULE", schedule::unschedule, 1)
+            .io();
+        registry
+            .register_variadic("RUN_SCHEDULER", schedule::run_scheduler, 1, 0..=1)
+            .io();
+
+        // Host-driven timers (handled by evaluator, run by Aether::pump)
+        registry
+            .register("SET_TIMEOUT", schedule::set_timeout, 2)
+            .io();
+        registry
+            .register("SET_INTERVAL", schedule::set_interval, 2)
+            .io();
+        registry.register("DEBOUNCE", schedule::debounce, 2).io();
+        registry
+            .register("CLEAR_TIMER", schedule::clear_timer, 1)
+            .io();
+
+        // Time and random numbers (handled by evaluator)
+        registry.register("NOW", entropy::now, 0);
+        registry.register("RANDOM", entropy::random, 0);
+
+        // Date and time
+        registry
+            .register_variadic("DATE_PARSE", datetime::date_parse, 1, 1..=2)
+            .pure();
+        registry
+            .register_variadic("DATE_FORMAT", datetime::date_format, 1, 1..=2)
+            .pure();
+        registry
+            .register_variadic("DATE_ADD", datetime::date_add, 2, 2..=3)
+            .pure();
+        registry
+            .register_variadic("DATE_DIFF", datetime::date_diff, 2, 2..=3)
+            .pure();
+        registry
+            .register("DATE_TO_TZ", datetime::date_to_tz, 2)
+            .pure();
+        registry
+            .register("DATE_TIMESTAMP", datetime::date_timestamp, 1)
+            .pure();
+
+        // Secrets from the host provider (handled by evaluator)
+        registry.register("SECRET", secrets::secret, 1).io();
+        registry
+            .register("MARK_SECRET", secrets::mark_secret, 1)
+            .io();
+
+        // Snapshot testing (handled by evaluator)
+        registry.register("SNAPSHOT_MATCH", snapshot::snapshot_match, 2);
+
+        // Retry and timeout (handled by evaluator)
+        registry.register_variadic("RETRY", resilience::retry, 2, 1..=2);
+        registry
+            .register("WITH_TIMEOUT", resilience::with_timeout, 2)
+            .io();
+
+        // Parallel tasks (handled by evaluator)
+        registry.register("SPAWN", parallel::spawn, 1).io();
+        registry.register("AWAIT_ALL", parallel::await_all, 1).io();
+        registry.register_variadic("PARALLEL", parallel::parallel, 2, 1..=2);
+
+        // Channels between parallel tasks
+        registry
+            .register_variadic("CHANNEL", channel::channel, 1, 0..=1)
+            .io();
+        registry.register("SEND", channel::send, 2).io();
+        registry
+            .register_variadic("RECV", channel::recv, 2, 1..=2)
+            .io();
+        registry
+            .register("CHANNEL_CLOSE", channel::channel_close, 1)
+            .io();
 
         // String functions
-        registry.register("SPLIT", string::split, 2);
-        registry.register("UPPER", string::upper, 1);
-        registry.register("LOWER", string::lower, 1);
-        registry.register("TRIM", string::trim, 1);
-        registry.register("CONTAINS", string::contains, 2);
-        registry.register("STARTS_WITH", string::starts_with, 2);
-        registry.register("ENDS_WITH", string::ends_with, 2);
-        registry.register("REPLACE", string::replace, 3);
-        registry.register("REPEAT", string::repeat, 2);
-        registry.register("STRSLICE", string::substr, 3);
-        registry.register("STRLEN", string::strlen, 1);
-        registry.register("INDEXOF", string::index_of, 2);
-        registry.register("CHARAT", string::char_at, 2);
+        registry.register("SPLIT", string::split, 2).pure();
+        registry.register("UPPER", string::upper, 1).pure();
+        registry.register("LOWER", string::lower, 1).pure();
+        registry.register("TRIM", string::trim, 1).pure();
+        registry.register("CONTAINS", string::contains, 2).pure();
+        registry
+            .register("STARTS_WITH", string::starts_with, 2)
+            .pure();
+        registry.register("ENDS_WITH", string::ends_with, 2).pure();
+        registry.register("REPLACE", string::replace, 3).pure();
+        registry.register("REPEAT", string::repeat, 2).pure();
+        registry.register("STRSLICE", string::substr, 3).pure();
+        registry.register("STRLEN", string::strlen, 1).pure();
+        registry.register("INDEXOF", string::index_of, 2).pure();
+        registry.register("CHARAT", string::char_at, 2).pure();
+        registry
+            .register("LEVENSHTEIN", string::levenshtein, 2)
+            .pure();
+        registry
+            .register("JARO_WINKLER", string::jaro_winkler, 2)
+            .pure();
+        registry.register("SOUNDEX", string::soundex, 1).pure();
+        registry
+            .register_variadic("FUZZY_MATCH", string::fuzzy_match, 3, 2..=3)
+            .pure();
+        registry
+            .register_variadic("RENDER_TEMPLATE", template::render_template, 2, 2..=3)
+            .pure();
 
         // Math functions - Basic
-        registry.register("ABS", math::abs, 1);
-        registry.register("FLOOR", math::floor, 1);
-        registry.register("CEIL", math::ceil, 1);
-        registry.register("ROUND", math::round, 1);
-        registry.register("SQRT", math::sqrt, 1);
-        registry.register("POW", math::pow, 2);
+        registry.register("ABS", math::abs, 1).pure();
+        registry.register("FLOOR", math::floor, 1).pure();
+        registry.register("CEIL", math::ceil, 1).pure();
+        registry.register("ROUND", math::round, 1).pure();
+        registry.register("SQRT", math::sqrt, 1).pure();
+        registry.register("POW", math::pow, 2).pure();
 
         // Math functions - Trigonometry
-        registry.register("SIN", math::sin, 1);
-        registry.register("COS", math::cos, 1);
-        registry.register("TAN", math::tan, 1);
-        registry.register("ASIN", math::asin, 1);
-        registry.register("ACOS", math::acos, 1);
-        registry.register("ATAN", math::atan, 1);
-        registry.register("ATAN2", math::atan2, 2);
-        registry.register("SINH", math::sinh, 1);
-        registry.register("COSH", math::cosh, 1);
-        registry.register("TANH", math::tanh, 1);
+        registry.register("SIN", math::sin, 1).pure();
+        registry.register("COS", math::cos, 1).pure();
+        registry.register("TAN", math::tan, 1).pure();
+        registry.register("ASIN", math::asin, 1).pure();
+        registry.register("ACOS", math::acos, 1).pure();
+        registry.register("ATAN", math::atan, 1).pure();
+        registry.register("ATAN2", math::atan2, 2).pure();
+        registry.register("SINH", math::sinh, 1).pure();
+        registry.register("COSH", math::cosh, 1).pure();
+        registry.register("TANH", math::tanh, 1).pure();
 
         // Math functions - Logarithms & Exponentials
-        registry.register("LOG", math::log, 1);
-        registry.register("LN", math::ln, 1);
-        registry.register("LOG2", math::log2, 1);
-        registry.register("EXP", math::exp, 1);
-        registry.register("EXP2", math::exp2, 1);
-        registry.register("EXPM1", math::expm1, 1);
-        registry.register("LOG1P", math::log1p, 1);
+        registry.register("LOG", math::log, 1).pure();
+        registry.register("LN", math::ln, 1).pure();
+        registry.register("LOG2", math::log2, 1).pure();
+        registry.register("EXP", math::exp, 1).pure();
+        registry.register("EXP2", math::exp2, 1).pure();
+        registry.register("EXPM1", math::expm1, 1).pure();
+        registry.register("LOG1P", math::log1p, 1).pure();
 
         // Math functions - Special
-        registry.register("FACTORIAL", math::factorial, 1);
-        registry.register("GAMMA", math::gamma, 1);
-        registry.register("ERF", math::erf, 1);
-        registry.register("HYPOT", math::hypot, 2);
-        registry.register("SIGN", math::sign, 1);
-        registry.register("CLAMP", math::clamp, 3);
+        registry.register("FACTORIAL", math::factorial, 1).pure();
+        registry.register("GAMMA", math::gamma, 1).pure();
+        registry.register("ERF", math::erf, 1).pure();
+        registry.register("HYPOT", math::hypot, 2).pure();
+        registry.register("SIGN", math::sign, 1).pure();
+        registry.register("CLAMP", math::clamp, 3).pure();
 
         // Math functions - Statistics
-        registry.register("MEAN", math::mean, 1);
-        registry.register("MEDIAN", math::median, 1);
-        registry.register("VARIANCE", math::variance, 1);
-        registry.register("STD", math::std, 1);
-        registry.register("QUANTILE", math::quantile, 2);
+        registry.register("MEAN", math::mean, 1).pure();
+        registry.register("MEDIAN", math::median, 1).pure();
+        registry.register("VARIANCE", math::variance, 1).pure();
+        registry.register("STD", math::std, 1).pure();
+        registry.register("QUANTILE", math::quantile, 2).pure();
+
+        // Streaming aggregation
+        registry.register("CUMSUM", math::cumsum, 1).pure();
+        registry.register("RUNNING_SUM", math::cumsum, 1).pure();
+        registry.register("CUMPROD", math::cumprod, 1).pure();
+        registry
+            .register_variadic("DIFF", patch::diff, 1, 1..=2)
+            .pure();
+        registry.register("ROLLING", math::rolling, 3).pure();
 
         // Math functions - Vector Operations
-        registry.register("DOT", math::dot, 2);
-        registry.register("NORM", math::norm, 1);
-        registry.register("CROSS", math::cross, 2);
-        registry.register("DISTANCE", math::distance, 2);
-        registry.register("NORMALIZE", math::normalize, 1);
+        registry.register("DOT", math::dot, 2).pure();
+        registry.register("NORM", math::norm, 1).pure();
+        registry.register("CROSS", math::cross, 2).pure();
+        registry.register("DISTANCE", math::distance, 2).pure();
+        registry.register("NORMALIZE", math::normalize, 1).pure();
 
         // Math functions - Matrix Operations
-        registry.register("MATMUL", math::matmul, 2);
-        registry.register("TRANSPOSE", math::transpose, 1);
-        registry.register("DETERMINANT", math::determinant, 1);
-        registry.register("INVERSE", math::matrix_inverse, 1);
+        registry.register("MATMUL", math::matmul, 2).pure();
+        registry.register("TRANSPOSE", math::transpose, 1).pure();
+        registry
+            .register("DETERMINANT", math::determinant, 1)
+            .pure();
+        registry.register("INVERSE", math::matrix_inverse, 1).pure();
+        registry
+            .register("IDENTITY_MATRIX", math::identity_matrix, 1)
+            .pure();
+        registry
+            .register_variadic("ZEROS", math::zeros, 1, 1..=2)
+            .pure();
+        registry
+            .register_variadic("ONES", math::ones, 1, 1..=2)
+            .pure();
+        registry.register("RESHAPE", math::reshape, 3).pure();
+        registry.register("SOLVE", math::solve, 2).pure();
+        registry.register("RANK", math::rank, 1).pure();
+        registry
+            .register("EIGENVALUES", math::eigenvalues, 1)
+            .pure();
+        registry
+            .register("EIGENVECTORS", math::eigenvectors, 1)
+            .pure();
 
         // Math functions - Statistics & Regression
-        registry.register("LINEAR_REGRESSION", math::linear_regression, 2);
+        registry
+            .register("LINEAR_REGRESSION", math::linear_regression, 2)
+            .pure();
 
         // Math functions - Probability Distributions
-        registry.register("NORMAL_PDF", math::normal_pdf, 1); // Variadic: 1 or 3
-        registry.register("NORMAL_CDF", math::normal_cdf, 1); // Variadic: 1 or 3
-        registry.register("POISSON_PMF", math::poisson_pmf, 2);
+        registry
+            .register_variadic("NORMAL_PDF", math::normal_pdf, 1, 1..=3)
+            .pure(); // 1 或 3 个参数
+        registry
+            .register_variadic("NORMAL_CDF", math::normal_cdf, 1, 1..=3)
+            .pure(); // 1 或 3 个参数
+        registry
+            .register("POISSON_PMF", math::poisson_pmf, 2)
+            .pure();
 
         // Math constants
-        registry.register("PI", math::pi, 0);
-        registry.register("E", math::e, 0);
-        registry.register("TAU", math::tau, 0);
-        registry.register("PHI", math::phi, 0);
+        registry.register("PI", math::pi, 0).pure();
+        registry.register("E", math::e, 0).pure();
+        registry.register("TAU", math::tau, 0).pure();
+        registry.register("PHI", math::phi, 0).pure();
 
         // Precision arithmetic functions
-        registry.register("ROUND_TO", math::round_to, 2);
-        registry.register("ADD_WITH_PRECISION", math::add_with_precision, 3);
-        registry.register("SUB_WITH_PRECISION", math::sub_with_precision, 3);
-        registry.register("MUL_WITH_PRECISION", math::mul_with_precision, 3);
-        registry.register("DIV_WITH_PRECISION", math::div_with_precision, 3);
-        registry.register("SET_PRECISION", math::set_precision, 2);
+        registry.register("ROUND_TO", math::round_to, 2).pure();
+        registry
+            .register("ADD_WITH_PRECISION", math::add_with_precision, 3)
+            .pure();
+        registry
+            .register("SUB_WITH_PRECISION", math::sub_with_precision, 3)
+            .pure();
+        registry
+            .register("MUL_WITH_PRECISION", math::mul_with_precision, 3)
+            .pure();
+        registry
+            .register("DIV_WITH_PRECISION", math::div_with_precision, 3)
+            .pure();
+        registry
+            .register("SET_PRECISION", math::set_precision, 2)
+            .pure();
 
         // Precise (Fraction) arithmetic functions
-        registry.register("TO_FRACTION", precise::to_fraction, 1);
-        registry.register("TO_FLOAT", precise::to_float, 1);
-        registry.register("SIMPLIFY", precise::simplify, 1);
-        registry.register("FRAC_ADD", precise::frac_add, 2);
-        registry.register("FRAC_SUB", precise::frac_sub, 2);
-        registry.register("FRAC_MUL", precise::frac_mul, 2);
-        registry.register("FRAC_DIV", precise::frac_div, 2);
-        registry.register("NUMERATOR", precise::numerator, 1);
-        registry.register("DENOMINATOR", precise::denominator, 1);
-        registry.register("GCD", precise::gcd, 2);
-        registry.register("LCM", precise::lcm, 2);
+        registry
+            .register("TO_FRACTION", precise::to_fraction, 1)
+            .pure();
+        registry.register("TO_FLOAT", precise::to_float, 1).pure();
+        registry.register("SIMPLIFY", precise::simplify, 1).pure();
+        registry.register("FRAC_ADD", precise::frac_add, 2).pure();
+        registry.register("FRAC_SUB", precise::frac_sub, 2).pure();
+        registry.register("FRAC_MUL", precise::frac_mul, 2).pure();
+        registry.register("FRAC_DIV", precise::frac_div, 2).pure();
+        registry.register("NUMERATOR", precise::numerator, 1).pure();
+        registry
+            .register("DENOMINATOR", precise::denominator, 1)
+            .pure();
+        registry.register("GCD", precise::gcd, 2).pure();
+        registry.register("LCM", precise::lcm, 2).pure();
+        registry.register("MODPOW", precise::modpow, 3).pure();
+        registry.register("ISQRT", precise::isqrt, 1).pure();
+        registry
+            .register("PRIME_TEST", precise::prime_test, 1)
+            .pure();
 
         // Type functions
-        registry.register("TYPE", types::type_of, 1);
-        registry.register("TO_STRING", types::to_string, 1);
-        registry.register("TO_NUMBER", types::to_number, 1);
-        registry.register("CLONE", types::clone, 1);
+        registry.register("TYPE", types::type_of, 1).pure();
+        registry.register("TO_STRING", types::to_string, 1).pure();
+        registry.register("TO_NUMBER", types::to_number, 1).pure();
+        registry.register("TO_INT", types::to_int, 1).pure();
+        registry.register("CLONE", types::clone, 1).pure();
 
         // JSON functions
-        registry.register("JSON_PARSE", json::json_parse, 1);
-        registry.register("JSON_STRINGIFY", json::json_stringify, 1); // Variadic: 1-2 args
+        registry.register("JSON_PARSE", json::json_parse, 1).pure();
+        registry
+            .register_variadic("JSON_STRINGIFY", json::json_stringify, 1, 1..=2)
+            .pure();
+
+        #[cfg(feature = "payroll")]
+        registry.register_payroll(permissions.filesystem_enabled);
+
+        // Filesystem functions (根据权限注册)
+        if permissions.filesystem_enabled {
+            registry.register_gated(|registry| {
+                registry.register("READ_FILE", filesystem::read_file, 1);
+                registry.register("WRITE_FILE", filesystem::write_file, 2);
+                registry.register("APPEND_FILE", filesystem::append_file, 2);
+                registry.register("DELETE_FILE", filesystem::delete_file, 1);
+                registry.register("FILE_EXISTS", filesystem::file_exists, 1);
+                registry.register("LIST_DIR", filesystem::list_dir, 1);
+                registry.register("CREATE_DIR", filesystem::create_dir, 1);
+                // 启用文件系统后图表支持 file 选项
+                registry.register("PLOT_LINE", plot::plot_line_with_files, 3);
+                registry.register("PLOT_BAR", plot::plot_bar_with_files, 3);
+                registry.register("PLOT_HIST", plot::plot_hist_with_files, 2);
+                registry.register("REPORT_BUILD", report::report_build_with_files, 2);
+
+                // 持久化键值存储（JSON 文件）
+                registry.register("KV_OPEN", kv::kv_open, 1);
+                registry.register_variadic("KV_GET", kv::kv_get, 3, 2..=3);
+                registry.register("KV_SET", kv::kv_set, 3);
+                registry.register("KV_DELETE", kv::kv_delete, 2);
+                registry.register("KV_KEYS", kv::kv_keys, 1);
+
+                // 流式导出（CSV / Excel）
+                registry.register_variadic("CSV_WRITER_OPEN", export::csv_writer_open, 2, 1..=2);
+                registry.register_variadic("XLSX_WRITER_OPEN", export::xlsx_writer_open, 2, 1..=2);
+                registry.register("WRITER_ROW", export::writer_row, 2);
+                registry.register("WRITER_CLOSE", export::writer_close, 1);
+            });
+        }
+
+        // Network functions (根据权限注册，需 `io` 特性)
+        #[cfg(feature = "io")]
+        if permissions.network_enabled {
+            registry.register_gated(|registry| {
+                registry.register("HTTP_GET", network::http_get, 1);
+                registry.register_variadic("HTTP_POST", network::http_post, 2, 2..=3);
+                registry.register_variadic("HTTP_PUT", network::http_put, 2, 2..=3);
+                registry.register("HTTP_DELETE", network::http_delete, 1);
+
+                // 邮件发送（还需宿主设置 SMTP 服务器白名单）
+                registry.register("SEND_EMAIL", email::send_email, 1);
+
+                // S3 对象存储（凭据由宿主注入）
+                #[cfg(feature = "s3")]
+                {
+                    registry.register("S3_GET", s3::s3_get, 2);
+                    registry.register_variadic("S3_PUT", s3::s3_put, 4, 3..=4);
+                    registry.register_variadic("S3_LIST", s3::s3_list, 2, 1..=2);
+                }
+
+                // 流式 API 客户端（WebSocket / SSE）
+                #[cfg(feature = "async")]
+                {
+                    registry.register_variadic("WS_CONNECT", websocket::ws_connect, 2, 1..=2);
+                    registry.register("WS_SEND", websocket::ws_send, 2);
+                    registry.register_variadic("WS_RECV", websocket::ws_recv, 2, 1..=2);
+                    registry.register("WS_CLOSE", websocket::ws_close, 1);
+                    registry.register_variadic("SSE_CONNECT", websocket::sse_connect, 2, 1..=2);
+                    registry.register_variadic("SSE_RECV", websocket::sse_recv, 2, 1..=2);
+                    registry.register("SSE_CLOSE", websocket::sse_close, 1);
+                }
+
+                // 简单 HTTP 服务（处理函数由求值器调用）
+                #[cfg(feature = "http-server")]
+                registry.register_variadic("HTTP_SERVE", http_server::http_serve, 3, 2..=3);
+            });
+        }
+
+        registry
+    }
+
+    /// 注册薪资计算函数（`payroll` 特性）
+    ///
+    /// 启用文件系统后 PAYROLL_RUN 支持 CSV 文件路径。
+    #[cfg(feature = "payroll")]
+    fn register_payroll(&mut self, filesystem_enabled: bool) {
+        // Money-safe rounding (exact decimal)
+        self.register_variadic("ROUND_HALF_EVEN", payroll::money::round_half_even, 2, 1..=2)
+            .pure();
+        self.register_variadic("ROUND_HALF_UP", payroll::money::round_half_up, 2, 1..=2)
+            .pure();
+        self.register_variadic("ROUND_DOWN", payroll::money::round_down, 2, 1..=2)
+            .pure();
+        self.register_variadic("ROUND_UP", payroll::money::round_up, 2, 1..=2)
+            .pure();
+        self.register_variadic("ALLOCATE", payroll::money::allocate, 3, 2..=3)
+            .pure();
 
         // Payroll functions - Basic salary calculations (7个)
-        registry.register("CALC_HOURLY_PAY", payroll::basic::calc_hourly_pay, 2);
-        registry.register("CALC_DAILY_PAY", payroll::basic::calc_daily_pay, 2);
-        registry.register(
+        self.register("CALC_HOURLY_PAY", payroll::basic::calc_hourly_pay, 2)
+            .pure();
+        self.register("CALC_DAILY_PAY", payroll::basic::calc_daily_pay, 2)
+            .pure();
+        self.register(
             "CALC_MONTHLY_FROM_HOURLY",
             payroll::basic::calc_monthly_from_hourly,
             1,
-        );
-        registry.register("CALC_ANNUAL_SALARY", payroll::basic::calc_annual_salary, 1);
-        registry.register("CALC_BASE_SALARY", payroll::basic::calc_base_salary, 1);
-        registry.register("CALC_GROSS_SALARY", payroll::basic::calc_gross_salary, 2);
-        registry.register("CALC_NET_SALARY", payroll::basic::calc_net_salary, 2);
+        )
+        .pure();
+        self.register("CALC_ANNUAL_SALARY", payroll::basic::calc_annual_salary, 1)
+            .pure();
+        self.register("CALC_BASE_SALARY", payroll::basic::calc_base_salary, 1)
+            .pure();
+        self.register("CALC_GROSS_SALARY", payroll::basic::calc_gross_salary, 2)
+            .pure();
+        self.register("CALC_NET_SALARY", payroll::basic::calc_net_salary, 2)
+            .pure();
 
         // Payroll functions - Overtime pay (5个)
-        registry.register("CALC_OVERTIME_PAY", payroll::overtime::calc_overtime_pay, 2);
-        registry.register(
+        self.register("CALC_OVERTIME_PAY", payroll::overtime::calc_overtime_pay, 2)
+            .pure();
+        self.register(
             "CALC_WEEKDAY_OVERTIME",
             payroll::overtime::calc_weekday_overtime,
             2,
-        );
-        registry.register(
+        )
+        .pure();
+        self.register(
             "CALC_WEEKEND_OVERTIME",
             payroll::overtime::calc_weekend_overtime,
             2,
-        );
-        registry.register(
+        )
+        .pure();
+        self.register(
             "CALC_HOLIDAY_OVERTIME",
             payroll::overtime::calc_holiday_overtime,
             2,
-        );
-        registry.register(
+        )
+        .pure();
+        self.register(
             "CALC_TOTAL_OVERTIME",
             payroll::overtime::calc_total_overtime,
             4,
-        );
+        )
+        .pure();
 
         // Payroll functions - Personal income tax (6个)
-        registry.register("CALC_PERSONAL_TAX", payroll::tax::calc_personal_tax, 1);
-        registry.register("CALC_TAXABLE_INCOME", payroll::tax::calc_taxable_income, 1);
-        registry.register(
+        self.register("CALC_PERSONAL_TAX", payroll::tax::calc_personal_tax, 1)
+            .pure();
+        self.register("CALC_TAXABLE_INCOME", payroll::tax::calc_taxable_income, 1)
+            .pure();
+        self.register(
             "CALC_ANNUAL_BONUS_TAX",
             payroll::tax::calc_annual_bonus_tax,
             1,
-        );
-        registry.register(
+        )
+        .pure();
+        self.register(
             "CALC_EFFECTIVE_TAX_RATE",
             payroll::tax::calc_effective_tax_rate,
             2,
-        );
-        registry.register("CALC_GROSS_FROM_NET", payroll::tax::calc_gross_from_net, 1);
-        registry.register("CALC_TAX_REFUND", payroll::tax::calc_tax_refund, 2);
+        )
+        .pure();
+        self.register("CALC_GROSS_FROM_NET", payroll::tax::calc_gross_from_net, 1)
+            .pure();
+        self.register("CALC_TAX_REFUND", payroll::tax::calc_tax_refund, 2)
+            .pure();
 
         // Payroll functions - Social insurance (10个)
-        registry.register(
+        self.register(
             "CALC_PENSION_INSURANCE",
             payroll::insurance::calc_pension_insurance,
             1,
-        );
-        registry.register(
+        )
+        .pure();
+        self.register(
             "CALC_MEDICAL_INSURANCE",
             payroll::insurance::calc_medical_insurance,
             1,
-        );
-        registry.register(
+        )
+        .pure();
+        self.register(
             "CALC_UNEMPLOYMENT_INSURANCE",
             payroll::insurance::calc_unemployment_insurance,
             1,
-        );
-        registry.register(
+        )
+        .pure();
+        self.register(
             "CALC_HOUSING_FUND",
             payroll::insurance::calc_housing_fund,
             1,
-        );
-        registry.register(
+        )
+        .pure();
+        self.register(
             "CALC_SOCIAL_INSURANCE",
             payroll::insurance::calc_social_insurance,
             1,
-        );
-        registry.register(
+        )
+        .pure();
+        self.register(
             "ADJUST_SOCIAL_BASE",
             payroll::insurance::adjust_social_base,
             3,
-        );
-        registry.register(
+        )
+        .pure();
+        self.register(
             "CALC_SOCIAL_BASE_LOWER",
             payroll::insurance::calc_social_base_lower,
             2,
-        );
-        registry.register(
+        )
+        .pure();
+        self.register(
             "CALC_SOCIAL_BASE_UPPER",
             payroll::insurance::calc_social_base_upper,
             2,
-        );
-        registry.register(
+        )
+        .pure();
+        self.register(
             "CALC_INJURY_INSURANCE",
             payroll::insurance::calc_injury_insurance,
             1,
-        );
-        registry.register(
+        )
+        .pure();
+        self.register(
             "CALC_MATERNITY_INSURANCE",
             payroll::insurance::calc_maternity_insurance,
             1,
-        );
+        )
+        .pure();
 
         // Payroll functions - Attendance (7个)
-        registry.register(
+        self.register(
             "CALC_ATTENDANCE_RATE",
             payroll::attendance::calc_attendance_rate,
             2,
-        );
-        registry.register(
+        )
+        .pure();
+        self.register(
             "CALC_LATE_DEDUCTION",
             payroll::attendance::calc_late_deduction,
             1,
-        );
-        registry.register(
+        )
+        .pure();
+        self.register(
             "CALC_EARLY_LEAVE_DEDUCTION",
             payroll::attendance::calc_early_leave_deduction,
             1,
-        );
-        registry.register(
+        )
+        .pure();
+        self.register(
             "CALC_ABSENT_DEDUCTION",
             payroll::attendance::calc_absent_deduction,
             2,
-        );
-        registry.register(
+        )
+        .pure();
+        self.register(
             "CALC_LEAVE_DEDUCTION",
             payroll::attendance::calc_leave_deduction,
             2,
-        );
-        registry.register(
+        )
+        .pure();
+        self.register(
             "CALC_SICK_LEAVE_PAY",
             payroll::attendance::calc_sick_leave_pay,
             3,
-        );
-        registry.register(
+        )
+        .pure();
+        self.register(
             "CALC_UNPAID_LEAVE_DEDUCTION",
             payroll::attendance::calc_unpaid_leave_deduction,
             2,
-        );
+        )
+        .pure();
 
         // Payroll functions - Bonus (6个)
-        registry.register(
+        self.register(
             "CALC_PERFORMANCE_PAY",
             payroll::bonus::calc_performance_pay,
             2,
-        );
-        registry.register("CALC_ANNUAL_BONUS", payroll::bonus::calc_annual_bonus, 1);
-        registry.register(
+        )
+        .pure();
+        self.register("CALC_ANNUAL_BONUS", payroll::bonus::calc_annual_bonus, 1)
+            .pure();
+        self.register(
             "CALC_ATTENDANCE_BONUS",
             payroll::bonus::calc_attendance_bonus,
             2,
-        );
-        registry.register(
+        )
+        .pure();
+        self.register(
             "CALC_SALES_COMMISSION",
             payroll::bonus::calc_sales_commission,
             2,
-        );
-        registry.register("CALC_PROJECT_BONUS", payroll::bonus::calc_project_bonus, 2);
-        registry.register("CALC_13TH_SALARY", payroll::bonus::calc_13th_salary, 2);
+        )
+        .pure();
+        self.register("CALC_PROJECT_BONUS", payroll::bonus::calc_project_bonus, 2)
+            .pure();
+        self.register("CALC_13TH_SALARY", payroll::bonus::calc_13th_salary, 2)
+            .pure();
 
         // Payroll functions - Allowance (7个)
-        registry.register(
+        self.register(
             "CALC_MEAL_ALLOWANCE",
             payroll::allowance::calc_meal_allowance,
             2,
-        );
-        registry.register(
+        )
+        .pure();
+        self.register(
             "CALC_TRANSPORT_ALLOWANCE",
             payroll::allowance::calc_transport_allowance,
             2,
-        );
-        registry.register(
+        )
+        .pure();
+        self.register(
             "CALC_COMMUNICATION_ALLOWANCE",
             payroll::allowance::calc_communication_allowance,
             2,
-        );
-        registry.register(
+        )
+        .pure();
+        self.register(
             "CALC_HOUSING_ALLOWANCE",
             payroll::allowance::calc_housing_allowance,
             2,
-        );
-        registry.register(
+        )
+        .pure();
+        self.register(
             "CALC_HIGH_TEMP_ALLOWANCE",
             payroll::allowance::calc_high_temp_allowance,
             2,
-        );
-        registry.register(
+        )
+        .pure();
+        self.register(
             "CALC_NIGHT_SHIFT_ALLOWANCE",
             payroll::allowance::calc_night_shift_allowance,
             2,
-        );
-        registry.register(
+        )
+        .pure();
+        self.register(
             "CALC_POSITION_ALLOWANCE",
             payroll::allowance::calc_position_allowance,
             2,
-        );
+        )
+        .pure();
 
         // Payroll functions - Conversion (12个)
-        registry.register(
+        self.register(
             "ANNUAL_TO_MONTHLY",
             payroll::conversion::annual_to_monthly,
             1,
-        );
-        registry.register(
+        )
+        .pure();
+        self.register(
             "MONTHLY_TO_ANNUAL",
             payroll::conversion::monthly_to_annual,
             1,
-        );
-        registry.register("DAILY_TO_MONTHLY", payroll::conversion::daily_to_monthly, 1);
-        registry.register("MONTHLY_TO_DAILY", payroll::conversion::monthly_to_daily, 1);
-        registry.register(
+        )
+        .pure();
+        self.register("DAILY_TO_MONTHLY", payroll::conversion::daily_to_monthly, 1)
+            .pure();
+        self.register("MONTHLY_TO_DAILY", payroll::conversion::monthly_to_daily, 1)
+            .pure();
+        self.register(
             "HOURLY_TO_MONTHLY",
             payroll::conversion::hourly_to_monthly,
             1,
-        );
-        registry.register(
+        )
+        .pure();
+        self.register(
             "MONTHLY_TO_HOURLY",
             payroll::conversion::monthly_to_hourly,
             1,
-        );
-        registry.register(
+        )
+        .pure();
+        self.register(
             "PRORATE_BY_NATURAL_DAYS",
             payroll::conversion::prorate_by_natural_days,
             3,
-        );
-        registry.register(
+        )
+        .pure();
+        self.register(
             "PRORATE_BY_LEGAL_DAYS",
             payroll::conversion::prorate_by_legal_days,
             2,
-        );
-        registry.register(
+        )
+        .pure();
+        self.register(
             "PRORATE_BY_WORKDAYS",
             payroll::conversion::prorate_by_workdays,
             3,
-        );
-        registry.register(
+        )
+        .pure();
+        self.register(
             "CALC_ONBOARDING_SALARY",
             payroll::conversion::calc_onboarding_salary,
             4,
-        );
-        registry.register(
+        )
+        .pure();
+        self.register(
             "CALC_RESIGNATION_SALARY",
             payroll::conversion::calc_resignation_salary,
             4,
-        );
-        registry.register("CALC_14TH_SALARY", payroll::conversion::calc_14th_salary, 2);
+        )
+        .pure();
+        self.register("CALC_14TH_SALARY", payroll::conversion::calc_14th_salary, 2)
+            .pure();
 
         // Payroll functions - DateTime (12个)
-        registry.register("CALC_NATURAL_DAYS", payroll::datetime::calc_natural_days, 2);
-        registry.register(
+        self.register("CALC_NATURAL_DAYS", payroll::datetime::calc_natural_days, 2)
+            .pure();
+        self.register(
             "GET_LEGAL_PAY_DAYS",
             payroll::datetime::get_legal_pay_days,
             0,
-        );
-        registry.register("CALC_WORKDAYS", payroll::datetime::calc_workdays, 2);
-        registry.register("CALC_WEEKEND_DAYS", payroll::datetime::calc_weekend_days, 2);
-        registry.register("CALC_HOLIDAY_DAYS", payroll::datetime::calc_holiday_days, 1);
-        registry.register("IS_WORKDAY", payroll::datetime::is_workday, 2);
-        registry.register("IS_WEEKEND", payroll::datetime::is_weekend, 1);
-        registry.register("IS_HOLIDAY", payroll::datetime::is_holiday, 2);
-        registry.register("CALC_WORK_HOURS", payroll::datetime::calc_work_hours, 1);
-        registry.register(
+        )
+        .pure();
+        self.register("CALC_WORKDAYS", payroll::datetime::calc_workdays, 2)
+            .pure();
+        self.register("CALC_WEEKEND_DAYS", payroll::datetime::calc_weekend_days, 2)
+            .pure();
+        self.register("CALC_HOLIDAY_DAYS", payroll::datetime::calc_holiday_days, 1)
+            .pure();
+        self.register("IS_WORKDAY", payroll::datetime::is_workday, 2)
+            .pure();
+        self.register("IS_WEEKEND", payroll::datetime::is_weekend, 1)
+            .pure();
+        self.register("IS_HOLIDAY", payroll::datetime::is_holiday, 2)
+            .pure();
+        self.register("CALC_WORK_HOURS", payroll::datetime::calc_work_hours, 1)
+            .pure();
+        self.register(
             "CALC_MONTHLY_WORK_HOURS",
             payroll::datetime::calc_monthly_work_hours,
             0,
-        );
-        registry.register(
+        )
+        .pure();
+        self.register(
             "CALC_ANNUAL_WORKDAYS",
             payroll::datetime::calc_annual_workdays,
             0,
-        );
-        registry.register(
+        )
+        .pure();
+        self.register(
             "CALC_ANNUAL_PAY_DAYS",
             payroll::datetime::calc_annual_pay_days,
             0,
-        );
+        )
+        .pure();
 
         // Payroll functions - Statistics (6个)
-        registry.register(
+        self.register(
             "CALC_SALARY_AVERAGE",
             payroll::statistics::calc_salary_average,
             1,
-        );
-        registry.register(
+        )
+        .pure();
+        self.register(
             "CALC_SALARY_MEDIAN",
             payroll::statistics::calc_salary_median,
             1,
-        );
-        registry.register(
+        )
+        .pure();
+        self.register(
             "CALC_SALARY_RANGE",
             payroll::statistics::calc_salary_range,
             1,
-        );
-        registry.register("CALC_PERCENTILE", payroll::statistics::calc_percentile, 2);
-        registry.register(
+        )
+        .pure();
+        self.register("CALC_PERCENTILE", payroll::statistics::calc_percentile, 2)
+            .pure();
+        self.register(
             "CALC_SALARY_STD_DEV",
             payroll::statistics::calc_salary_std_dev,
             1,
-        );
-        registry.register(
+        )
+        .pure();
+        self.register(
             "CALC_SALARY_DISTRIBUTION",
             payroll::statistics::calc_salary_distribution,
             2,
+        )
+        .pure();
+
+        // Payroll functions - Holiday calendar (3个)
+        self.register_variadic(
+            "SET_HOLIDAY_CALENDAR",
+            payroll::calendar::set_holiday_calendar,
+            2,
+            2..=3,
+        );
+        self.register_variadic(
+            "LOAD_HOLIDAY_CALENDAR",
+            payroll::calendar::load_holiday_calendar,
+            1,
+            1..=2,
+        );
+        self.register(
+            "CLEAR_HOLIDAY_CALENDAR",
+            payroll::calendar::clear_holiday_calendar,
+            0,
+        );
+
+        // Payroll functions - Rounding (1个)
+        self.register_variadic(
+            "SET_PAYROLL_ROUNDING",
+            payroll::money::set_payroll_rounding,
+            2,
+            1..=2,
         );
 
-        // Filesystem functions (根据权限注册)
-        if permissions.filesystem_enabled {
-            registry.register("READ_FILE", filesystem::read_file, 1);
-            registry.register("WRITE_FILE", filesystem::write_file, 2);
-            registry.register("APPEND_FILE", filesystem::append_file, 2);
-            registry.register("DELETE_FILE", filesystem::delete_file, 1);
-            registry.register("FILE_EXISTS", filesystem::file_exists, 1);
-            registry.register("LIST_DIR", filesystem::list_dir, 1);
-            registry.register("CREATE_DIR", filesystem::create_dir, 1);
+        // Payroll functions - Batch (1个)
+        if filesystem_enabled {
+            self.register_gated(|registry| {
+                registry.register_variadic(
+                    "PAYROLL_RUN",
+                    payroll::batch::payroll_run_with_files,
+                    2,
+                    1..=2,
+                );
+            });
+        } else {
+            self.register_variadic("PAYROLL_RUN", payroll::batch::payroll_run, 2, 1..=2);
         }
+    }
 
-        // Network functions (根据权限注册)
-        if permissions.network_enabled {
-            registry.register("HTTP_GET", network::http_get, 1);
-            registry.register("HTTP_POST", network::http_post, 2); // Variadic: 2-3 args
-            registry.register("HTTP_PUT", network::http_put, 2); // Variadic: 2-3 args
-            registry.register("HTTP_DELETE", network::http_delete, 1);
+    /// Register a built-in function (impure unless marked with [`Registration::pure`])
+    fn register(&mut self, name: &str, func: BuiltInFn, arity: usize) -> Registration<'_> {
+        self.functions.insert(name.to_string(), (func, arity));
+        self.pure.remove(name);
+        self.io.remove(name);
+        Registration {
+            registry: self,
+            name: name.to_string(),
         }
+    }
 
-        registry
+    /// Register a built-in function that accepts a range of argument counts
+    fn register_variadic(
+        &mut self,
+        name: &str,
+        func: BuiltInFn,
+        arity: usize,
+        args: impl RangeBounds<usize>,
+    ) -> Registration<'_> {
+        let min = match args.start_bound() {
+            Bound::Included(&n) => n,
+            Bound::Excluded(&n) => n + 1,
+            Bound::Unbounded => 0,
+        };
+        let max = match args.end_bound() {
+            Bound::Included(&n) => Some(n),
+            Bound::Excluded(&n) => Some(n - 1),
+            Bound::Unbounded => None,
+        };
+        self.arg_ranges.insert(name.to_string(), (min, max));
+        self.register(name, func, arity)
     }
 
-    /// Register a built-in function
-    fn register(&mut self, name: &str, func: BuiltInFn, arity: usize) {
-        self.functions.insert(name.to_string(), (func, arity));
+    /// Register functions that are only available once an IO permission is granted
+    ///
+    /// Everything registered here reads or writes external state (see [`Registration::io`]),
+    /// including variants that replace an ungated function, such as PLOT_LINE with a file option.
+    fn register_gated(&mut self, register: impl FnOnce(&mut GatedRegistry<'_>)) {
+        register(&mut GatedRegistry(self));
     }
 
     /// 注册带文档的函数
     #[allow(dead_code)]
     fn register_with_doc(&mut self, name: &str, func: BuiltInFn, arity: usize, doc: FunctionDoc) {
-        self.functions.insert(name.to_string(), (func, arity));
         self.docs.insert(name.to_string(), doc);
+        self.register(name, func, arity);
+    }
+
+    /// The IO permissions this registry was built with
+    pub fn permissions(&self) -> &IOPermissions {
+        &self.permissions
     }
 
     /// Get a built-in function by name
@@ -585,12 +1140,156 @@ impl BuiltInRegistry {
 
     /// Check if a function exists
     pub fn has(&self, name: &str) -> bool {
-        self.functions.contains_key(name)
+        self.functions.contains_key(name) || self.natives.contains_key(name)
+    }
+
+    /// 注册原生函数（宿主或插件提供）
+    ///
+    /// 原生函数默认不纯，需要时用 [`BuiltInRegistry::mark_pure`] 标记。
+    pub fn register_native(&mut self, name: &str, func: NativeFn, arity: usize) {
+        self.natives.insert(name.to_string(), (func, arity));
+        self.pure.remove(name);
+        self.io.remove(name);
+    }
+
+    /// 调用时是否原样接收 Integer 参数（见 [`INTEGER_AWARE`]），原生函数总是原样接收
+    pub fn accepts_integers(&self, name: &str) -> bool {
+        INTEGER_AWARE.contains(&name) || self.natives.contains_key(name)
+    }
+
+    /// 是否为原生函数（宿主或插件提供）
+    pub fn has_native(&self, name: &str) -> bool {
+        self.natives.contains_key(name)
+    }
+
+    /// 获取原生函数
+    pub fn get_native(&self, name: &str) -> Option<(NativeFn, usize)> {
+        self.natives.get(name).cloned()
+    }
+
+    /// 内置函数或原生函数的参数个数
+    pub fn arity(&self, name: &str) -> Option<usize> {
+        self.natives
+            .get(name)
+            .map(|(_, arity)| *arity)
+            .or_else(|| self.functions.get(name).map(|(_, arity)| *arity))
+    }
+
+    /// 将函数标记为已弃用，调用时会给出警告和替代建议
+    pub fn deprecate(&mut self, name: &str, replacement: Option<&str>) -> bool {
+        if !self.has(name) {
+            return false;
+        }
+        self.deprecations.insert(
+            name.to_string(),
+            Deprecation {
+                replacement: replacement.map(|r| r.to_string()),
+            },
+        );
+        true
+    }
+
+    /// 获取函数的弃用信息
+    pub fn deprecation(&self, name: &str) -> Option<&Deprecation> {
+        self.deprecations.get(name)
+    }
+
+    /// 将函数标记为实验性，只有启用对应特性后才能调用
+    pub fn mark_experimental(&mut self, name: &str, feature: &str) -> bool {
+        if !self.has(name) {
+            return false;
+        }
+        self.experimental
+            .insert(name.to_string(), feature.to_string());
+        true
+    }
+
+    /// 获取实验性函数所需的特性名
+    pub fn experimental_feature(&self, name: &str) -> Option<&str> {
+        self.experimental.get(name).map(|f| f.as_str())
+    }
+
+    /// 将函数标记为纯函数（结果只取决于参数），`eval_pure` 的脚本才能调用它
+    ///
+    /// 未注册的函数和读写外部状态的函数（见 [`Self::is_io`]）不能标记，返回 false。
+    pub fn mark_pure(&mut self, name: &str) -> bool {
+        if !self.has(name) || self.is_io(name) {
+            return false;
+        }
+        self.pure.insert(name.to_string());
+        true
+    }
+
+    /// 函数是否为纯函数（注册时用 [`Registration::pure`] 声明，其余默认不纯）
+    pub fn is_pure(&self, name: &str) -> bool {
+        self.pure.contains(name)
+    }
+
+    /// 函数是否读写外部状态或依赖真实时间（文件、网络、机密、调度、并发等），
+    /// 确定性模式下禁止调用（注册时用 [`Registration::io`] 声明，需要权限的函数总是如此）
+    pub fn is_io(&self, name: &str) -> bool {
+        self.io.contains(name)
+    }
+
+    /// 获取函数签名，如 `ROUND_TO(value, decimals)`
+    ///
+    /// 优先使用声明的参数规格（带类型），其次使用文档中的参数名，
+    /// 都没有时按注册的参数个数生成 `arg1, arg2, ...`。
+    pub fn signature(&self, name: &str) -> Option<String> {
+        let arity = self.arity(name)?;
+        if let Some(spec) = args::spec_for(name) {
+            return Some(spec.signature());
+        }
+        let params: Vec<String> = if let Some(doc) = self.docs.get(name) {
+            doc.params.iter().map(|(p, _)| p.clone()).collect()
+        } else if let Some(doc) = help::function_doc(name) {
+            doc.params.iter().map(|(p, _)| p.clone()).collect()
+        } else {
+            (1..=arity).map(|i| format!("arg{}", i)).collect()
+        };
+        Some(format!("{}({})", name, params.join(", ")))
+    }
+
+    /// 用新的权限重建注册表，保留文档、弃用和实验性标记
+    pub fn with_new_permissions(&self, permissions: IOPermissions) -> Self {
+        let mut registry = Self::with_permissions(permissions);
+        registry.natives = self.natives.clone();
+        for (name, doc) in &self.docs {
+            if registry.has(name) {
+                registry.docs.insert(name.clone(), doc.clone());
+            }
+        }
+        for (name, deprecation) in &self.deprecations {
+            if registry.has(name) {
+                registry
+                    .deprecations
+                    .insert(name.clone(), deprecation.clone());
+            }
+        }
+        for (name, feature) in &self.experimental {
+            if registry.has(name) {
+                registry.experimental.insert(name.clone(), feature.clone());
+            }
+        }
+        for name in &self.pure {
+            if registry.natives.contains_key(name) {
+                registry.pure.insert(name.clone());
+            }
+        }
+        registry
     }
 
     /// Get all function names
     pub fn names(&self) -> Vec<String> {
-        self.functions.keys().cloned().collect()
+        self.functions
+            .keys()
+            .chain(
+                self.natives
+                    .keys()
+                    .filter(|name| !self.functions.contains_key(*name)),
+            )
+            .cloned()
+            .collect()
     }
 
     /// 获取函数文档
@@ -604,6 +1303,46 @@ impl BuiltInRegistry {
     }
 }
 
+/// 刚注册的内置函数，用于声明它的性质（默认不纯，但不读写外部状态）
+struct Registration<'a> {
+    registry: &'a mut BuiltInRegistry,
+    name: String,
+}
+
+impl Registration<'_> {
+    /// 纯函数：结果只取决于参数，不读写文件、网络、机密、时钟或引擎状态
+    ///
+    /// 只有纯函数才能在 `eval_pure` 中使用、结果才会被缓存。薪酬函数按引擎的舍入规则
+    /// 和节假日日历计算，`eval_pure` 把这些设置加入缓存键。
+    fn pure(self) {
+        self.registry.pure.insert(self.name);
+    }
+
+    /// 读写外部状态或依赖真实时间（输入、机密、调度、定时器、并行任务等），确定性模式下禁止
+    fn io(self) {
+        self.registry.io.insert(self.name);
+    }
+}
+
+/// 注册需要 IO 权限的函数（见 [`BuiltInRegistry::register_gated`]），注册的函数都标记为 IO
+struct GatedRegistry<'a>(&'a mut BuiltInRegistry);
+
+impl GatedRegistry<'_> {
+    fn register(&mut self, name: &str, func: BuiltInFn, arity: usize) {
+        self.0.register(name, func, arity).io();
+    }
+
+    fn register_variadic(
+        &mut self,
+        name: &str,
+        func: BuiltInFn,
+        arity: usize,
+        args: impl RangeBounds<usize>,
+    ) {
+        self.0.register_variadic(name, func, arity, args).io();
+    }
+}
+
 impl Default for BuiltInRegistry {
     fn default() -> Self {
         Self::new()
@@ -15,6 +15,33 @@ fn get_string(val: &Value) -> Result<String, RuntimeError> {
     }
 }
 
+/// 从 URL 中取出主机名（不含端口和用户信息）
+fn url_host(url: &str) -> Option<&str> {
+    let rest = url.split_once("://").map(|(_, rest)| rest)?;
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let authority = authority.rsplit('@').next()?;
+    let host = if let Some(ipv6) = authority.strip_prefix('[') {
+        ipv6.split(']').next()?
+    } else {
+        authority.split(':').next()?
+    };
+    (!host.is_empty()).then_some(host)
+}
+
+/// 检查 URL 的主机是否在宿主设置的网络白名单中
+pub(crate) fn check_url_allowed(url: &str) -> Result<(), RuntimeError> {
+    let host =
+        url_host(url).ok_or_else(|| RuntimeError::CustomError(format!("Invalid URL: {}", url)))?;
+    if crate::sandbox::is_network_host_allowed(host) {
+        Ok(())
+    } else {
+        Err(RuntimeError::CustomError(format!(
+            "Host '{}' is not in the network allowlist",
+            host
+        )))
+    }
+}
+
 /// HTTP GET 请求
 ///
 /// # 参数
@@ -34,6 +61,7 @@ pub fn http_get(args: &[Value]) -> Result<Value, RuntimeError> {
     }
 
     let url = get_string(&args[0])?;
+    check_url_allowed(&url)?;
 
     // 使用 ureq 进行简单的 HTTP 请求
     match ureq::get(&url).call() {
@@ -72,6 +100,7 @@ pub fn http_post(args: &[Value]) -> Result<Value, RuntimeError> {
     }
 
     let url = get_string(&args[0])?;
+    check_url_allowed(&url)?;
     let body = get_string(&args[1])?;
     let content_type = if args.len() > 2 {
         get_string(&args[2])?
@@ -118,6 +147,7 @@ pub fn http_put(args: &[Value]) -> Result<Value, RuntimeError> {
     }
 
     let url = get_string(&args[0])?;
+    check_url_allowed(&url)?;
     let body = get_string(&args[1])?;
     let content_type = if args.len() > 2 {
         get_string(&args[2])?
@@ -162,6 +192,7 @@ pub fn http_delete(args: &[Value]) -> Result<Value, RuntimeError> {
     }
 
     let url = get_string(&args[0])?;
+    check_url_allowed(&url)?;
 
     match ureq::delete(&url).call() {
         Ok(response) => match response.into_body().read_to_string() {
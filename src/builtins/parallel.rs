@@ -0,0 +1,511 @@
+// src/builtins/parallel.rs
+//! 并行任务
+//!
+//! 让扇出型的 IO 脚本（批量请求、批量读文件）并发执行互不依赖的函数：
+//! - `SPAWN(fn)` 在后台工作线程上启动任务，返回任务句柄
+//! - `AWAIT_ALL(tasks)` 等待任务完成，按顺序返回每个任务的结果
+//! - `PARALLEL(fns, max_workers?)` 用固定大小的工作线程池执行一组函数
+//!
+//! 值不能跨线程共享，因此任务在工作线程自己的引擎上执行：
+//! 函数连同它可见的变量被复制到工作引擎中，任务里的修改不会影响调用方。
+//! 工作引擎使用与调用方相同的 IO 权限、执行限制和沙箱上下文（路径验证器、
+//! 网络和 SMTP 白名单），每个任务开始前重置环境。
+//! 宿主可以通过 `ConcurrencyLimits` 限制任务数、单个任务的步数和任务累计时长。
+//! 生成器和惰性值无法复制，闭包中的这类变量在任务中不可见。
+//!
+//! 每个任务的结果是 `{"ok": true, "value": ...}` 或 `{"ok": false, "error": "..."}`，
+//! 单个任务失败不影响其他任务。
+
+use crate::ast::Stmt;
+use crate::builtins::IOPermissions;
+use crate::builtins::channel::ChannelTable;
+use crate::environment::Environment;
+use crate::evaluator::{Evaluator, RuntimeError};
+use crate::runtime::{ConcurrencyLimits, ExecutionLimitError, ExecutionLimits};
+use crate::sandbox::SandboxContext;
+use crate::value::{SetKey, Table, Value};
+use num_bigint::BigInt;
+use num_rational::Ratio;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicIsize, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, mpsc};
+use std::time::{Duration, Instant};
+
+/// 未指定 max_workers 时的工作线程数上限
+const MAX_DEFAULT_WORKERS: usize = 8;
+
+/// 可以跨线程传递的值
+#[derive(Debug, Clone)]
+pub(crate) enum Portable {
+    Number(f64),
+    Int(i64),
+    Fraction(Ratio<BigInt>),
+    String(String),
+    Boolean(bool),
+    Null,
+    Array(Vec<Portable>),
+    Dict(HashMap<String, Portable>),
+    Set(HashSet<SetKey>),
+    Table(Vec<String>, Vec<Vec<Portable>>),
+    DateTime(chrono::DateTime<chrono::FixedOffset>),
+    Function {
+        name: Option<String>,
+        params: Vec<String>,
+        body: Vec<Stmt>,
+    },
+    BuiltIn {
+        name: String,
+        arity: usize,
+    },
+}
+
+impl Portable {
+    /// 复制值，生成器和惰性值无法跨线程传递
+    pub fn from_value(value: &Value) -> Result<Portable, String> {
+        Ok(match value {
+            Value::Number(n) => Portable::Number(*n),
+            Value::Int(i) => Portable::Int(*i),
+            Value::Fraction(f) => Portable::Fraction(f.clone()),
+            Value::String(s) => Portable::String(s.clone()),
+            Value::Boolean(b) => Portable::Boolean(*b),
+            Value::Null => Portable::Null,
+            Value::Array(items) => Portable::Array(
+                items
+                    .iter()
+                    .map(Portable::from_value)
+                    .collect::<Result<_, _>>()?,
+            ),
+            Value::Dict(dict) => Portable::Dict(
+                dict.iter()
+                    .map(|(k, v)| Ok((k.clone(), Portable::from_value(v)?)))
+                    .collect::<Result<_, String>>()?,
+            ),
+            Value::Set(set) => Portable::Set(set.clone()),
+            Value::DateTime(dt) => Portable::DateTime(*dt),
+            Value::Table(table) => {
+                let (columns, data) = table.clone().into_parts();
+                let data = data
+                    .iter()
+                    .map(|column| column.iter().map(Portable::from_value).collect())
+                    .collect::<Result<_, _>>()?;
+                Portable::Table(columns, data)
+            }
+            Value::Function {
+                name, params, body, ..
+            } => Portable::Function {
+                name: name.clone(),
+                params: params.clone(),
+                body: body.clone(),
+            },
+            Value::BuiltIn { name, arity } => Portable::BuiltIn {
+                name: name.clone(),
+                arity: *arity,
+            },
+            Value::Generator { .. } | Value::Lazy { .. } => {
+                return Err(format!(
+                    "{} values cannot be passed to a parallel task",
+                    value.type_name()
+                ));
+            }
+        })
+    }
+
+    /// 是否包含函数（通道只传递数据）
+    pub fn contains_function(&self) -> bool {
+        match self {
+            Portable::Function { .. } | Portable::BuiltIn { .. } => true,
+            Portable::Array(items) => items.iter().any(Portable::contains_function),
+            Portable::Dict(dict) => dict.values().any(Portable::contains_function),
+            Portable::Table(_, data) => data.iter().flatten().any(Portable::contains_function),
+            _ => false,
+        }
+    }
+
+    /// 还原为值，函数绑定到 `env`
+    pub fn into_value(self, env: &Rc<RefCell<Environment>>) -> Value {
+        match self {
+            Portable::Number(n) => Value::Number(n),
+            Portable::Int(i) => Value::Int(i),
+            Portable::Fraction(f) => Value::Fraction(f),
+            Portable::String(s) => Value::String(s),
+            Portable::Boolean(b) => Value::Boolean(b),
+            Portable::Null => Value::Null,
+            Portable::Array(items) => {
+                Value::Array(items.into_iter().map(|v| v.into_value(env)).collect())
+            }
+            Portable::Dict(dict) => Value::Dict(
+                dict.into_iter()
+                    .map(|(k, v)| (k, v.into_value(env)))
+                    .collect(),
+            ),
+            Portable::Set(set) => Value::Set(set),
+            Portable::DateTime(dt) => Value::DateTime(dt),
+            Portable::Table(columns, data) => {
+                let data = data
+                    .into_iter()
+                    .map(|column| column.into_iter().map(|v| v.into_value(env)).collect())
+                    .collect();
+                Table::new(columns, data)
+                    .map(Value::Table)
+                    .unwrap_or(Value::Null)
+            }
+            Portable::Function { name, params, body } => Value::Function {
+                name,
+                params,
+                body,
+                env: Rc::clone(env),
+            },
+            Portable::BuiltIn { name, arity } => Value::BuiltIn { name, arity },
+        }
+    }
+}
+
+/// 函数可见的用户变量快照
+pub(crate) type Globals = Arc<Vec<(String, Portable)>>;
+
+/// 复制函数闭包中可见的用户变量（无法复制的变量被跳过）
+pub(crate) fn capture_globals(func: &Value) -> Globals {
+    let Value::Function { env, .. } = func else {
+        return Arc::new(Vec::new());
+    };
+    let vars = env
+        .borrow()
+        .visible_variables()
+        .into_iter()
+        .filter_map(|var| Some((var.name, Portable::from_value(&var.value).ok()?)))
+        .collect();
+    Arc::new(vars)
+}
+
+/// 一个待执行的任务
+pub(crate) struct Task {
+    pub func: Portable,
+    pub globals: Globals,
+    /// 是否占用了任务槽位（SPAWN），结束时归还
+    pub counted: bool,
+}
+
+/// 任务结果，错误以消息返回
+pub(crate) type TaskResult = Result<Portable, String>;
+
+/// 工作引擎的配置（与调用方引擎一致）
+#[derive(Debug, Clone)]
+pub(crate) struct WorkerConfig {
+    pub permissions: IOPermissions,
+    pub limits: ExecutionLimits,
+    pub concurrency: ConcurrencyLimits,
+    pub budget: Arc<TaskBudget>,
+    pub channels: Arc<ChannelTable>,
+    /// 调用方线程的沙箱上下文，工作线程不继承线程局部变量，需要显式安装
+    pub sandbox: SandboxContext,
+}
+
+/// 任务槽位和累计时间，在引擎与它启动的所有任务（含嵌套任务）之间共享
+#[derive(Debug, Default)]
+pub(crate) struct TaskBudget {
+    /// 已占用的任务槽位
+    active: AtomicUsize,
+    /// 已结束任务的累计执行毫秒数
+    elapsed_ms: AtomicU64,
+}
+
+impl TaskBudget {
+    /// 时间预算是否已用完
+    pub fn check_time(&self, limits: &ConcurrencyLimits) -> Result<(), RuntimeError> {
+        let elapsed_ms = self.elapsed_ms.load(Ordering::SeqCst);
+        match limits.max_total_task_ms {
+            Some(limit) if elapsed_ms >= limit => Err(RuntimeError::ExecutionLimit(
+                ExecutionLimitError::TaskTimeBudgetExceeded { elapsed_ms, limit },
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// 占用一个任务槽位
+    pub fn acquire(&self, limits: &ConcurrencyLimits) -> Result<(), RuntimeError> {
+        self.check_time(limits)?;
+        let tasks = self.active.fetch_add(1, Ordering::SeqCst) + 1;
+        if let Some(limit) = limits.max_concurrent_tasks
+            && tasks > limit
+        {
+            self.release(1);
+            return Err(RuntimeError::ExecutionLimit(
+                ExecutionLimitError::TaskLimitExceeded { tasks, limit },
+            ));
+        }
+        Ok(())
+    }
+
+    /// 尽量占用 `wanted` 个槽位，返回实际占用数（至少 1 个，否则报错）
+    pub fn acquire_up_to(
+        &self,
+        wanted: usize,
+        limits: &ConcurrencyLimits,
+    ) -> Result<usize, RuntimeError> {
+        self.acquire(limits)?;
+        let extra = match limits.max_concurrent_tasks {
+            Some(limit) => {
+                let active = self.active.load(Ordering::SeqCst);
+                (wanted - 1).min(limit.saturating_sub(active))
+            }
+            None => wanted - 1,
+        };
+        self.active.fetch_add(extra, Ordering::SeqCst);
+        Ok(extra + 1)
+    }
+
+    /// 归还槽位
+    pub fn release(&self, count: usize) {
+        self.active.fetch_sub(count, Ordering::SeqCst);
+    }
+
+    /// 记录一个任务的执行时长
+    fn record(&self, elapsed: Duration) {
+        self.elapsed_ms
+            .fetch_add(elapsed.as_millis() as u64, Ordering::SeqCst);
+    }
+
+    /// 剩余的时间预算
+    fn remaining_ms(&self, limits: &ConcurrencyLimits) -> Option<u64> {
+        limits
+            .max_total_task_ms
+            .map(|limit| limit.saturating_sub(self.elapsed_ms.load(Ordering::SeqCst)))
+    }
+}
+
+type Job = (Task, mpsc::Sender<TaskResult>);
+
+/// 工作线程池，每个线程持有一个复用的引擎
+///
+/// PARALLEL 使用固定大小的线程池；SPAWN 使用按需扩展的线程池：没有空闲线程时
+/// 新建一个，空闲线程会被复用，这样通过通道互相等待的任务不会因线程不足而死锁。
+/// 线程池被丢弃时关闭任务队列，工作线程执行完手上的任务后退出（不等待，
+/// 避免未被 AWAIT_ALL 的长任务阻塞引擎的释放）。
+pub(crate) struct WorkerPool {
+    sender: mpsc::Sender<Job>,
+    receiver: Arc<Mutex<mpsc::Receiver<Job>>>,
+    config: WorkerConfig,
+    /// 等待任务的线程数减去排队中的任务数，不大于 0 时提交任务需要新建线程
+    spare: Arc<AtomicIsize>,
+    /// 没有空闲线程时是否新建线程
+    elastic: bool,
+}
+
+impl WorkerPool {
+    /// 固定 `size` 个工作线程
+    pub fn new(size: usize, config: WorkerConfig) -> Self {
+        let pool = Self::empty(config, false);
+        for _ in 0..size.max(1) {
+            pool.start_worker();
+        }
+        pool
+    }
+
+    /// 按需扩展的线程池
+    pub fn elastic(config: WorkerConfig) -> Self {
+        Self::empty(config, true)
+    }
+
+    fn empty(config: WorkerConfig, elastic: bool) -> Self {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        WorkerPool {
+            sender,
+            receiver: Arc::new(Mutex::new(receiver)),
+            config,
+            spare: Arc::new(AtomicIsize::new(0)),
+            elastic,
+        }
+    }
+
+    fn start_worker(&self) {
+        let receiver = Arc::clone(&self.receiver);
+        let spare = Arc::clone(&self.spare);
+        let config = self.config.clone();
+        std::thread::spawn(move || worker_loop(&receiver, &spare, config));
+    }
+
+    /// 工作线程安装的沙箱上下文
+    pub fn sandbox(&self) -> &SandboxContext {
+        &self.config.sandbox
+    }
+
+    /// 提交任务，返回接收结果的通道
+    pub fn submit(&self, task: Task) -> mpsc::Receiver<TaskResult> {
+        if self.spare.fetch_sub(1, Ordering::SeqCst) <= 0 && self.elastic {
+            self.start_worker();
+        }
+        let (result_tx, result_rx) = mpsc::channel();
+        // 工作线程全部退出时发送失败，接收端会得到断开错误
+        let _ = self.sender.send((task, result_tx));
+        result_rx
+    }
+}
+
+fn worker_loop(receiver: &Mutex<mpsc::Receiver<Job>>, spare: &AtomicIsize, config: WorkerConfig) {
+    let _sandbox = config.sandbox.install();
+    let mut evaluator = Evaluator::with_permissions(config.permissions.clone());
+    evaluator.set_concurrency_budget(config.concurrency.clone(), Arc::clone(&config.budget));
+    evaluator.set_channels(Arc::clone(&config.channels));
+    loop {
+        spare.fetch_add(1, Ordering::SeqCst);
+        let job = match receiver.lock() {
+            Ok(receiver) => receiver.recv(),
+            Err(_) => return,
+        };
+        let Ok((task, result_tx)) = job else {
+            return;
+        };
+        let counted = task.counted;
+        let result = run_task(&mut evaluator, &config, task);
+        if counted {
+            config.budget.release(1);
+        }
+        let _ = result_tx.send(result);
+    }
+}
+
+/// 在工作引擎上执行任务
+fn run_task(evaluator: &mut Evaluator, config: &WorkerConfig, task: Task) -> TaskResult {
+    config
+        .budget
+        .check_time(&config.concurrency)
+        .map_err(|e| e.to_string())?;
+
+    // 单个任务的步数和时长受并发限制进一步约束
+    let mut limits = config.limits.clone();
+    if let Some(steps) = config.concurrency.max_task_steps {
+        limits.max_steps = Some(steps);
+    }
+    if let Some(remaining) = config.budget.remaining_ms(&config.concurrency) {
+        limits.max_duration_ms = Some(
+            limits
+                .max_duration_ms
+                .map_or(remaining, |ms| ms.min(remaining)),
+        );
+    }
+    evaluator.set_limits(limits);
+
+    evaluator.reset_env();
+    let env = evaluator.global_env();
+    for (name, value) in task.globals.iter() {
+        evaluator.set_global(name.clone(), value.clone().into_value(&env));
+    }
+    let func = task.func.into_value(&env);
+    let start = Instant::now();
+    let result = evaluator.call_value(&func, Vec::new());
+    config.budget.record(start.elapsed());
+    Portable::from_value(&result.map_err(|e| e.to_string())?)
+}
+
+/// PARALLEL 默认的工作线程数（CPU 核数，最多 8 个）
+pub(crate) fn default_workers() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .min(MAX_DEFAULT_WORKERS)
+}
+
+/// 校验参数是否可作为任务执行
+pub(crate) fn check_task(value: &Value) -> Result<Portable, RuntimeError> {
+    if !matches!(value, Value::Function { .. } | Value::BuiltIn { .. }) {
+        return Err(RuntimeError::TypeErrorDetailed {
+            expected: "Function".to_string(),
+            got: value.type_name().to_string(),
+        });
+    }
+    Portable::from_value(value).map_err(RuntimeError::InvalidOperation)
+}
+
+/// 解析 PARALLEL 的参数，返回函数列表和工作线程数
+pub(crate) fn parse_parallel(args: &[Value]) -> Result<(&[Value], usize), RuntimeError> {
+    let funcs = match args {
+        [Value::Array(funcs)] | [Value::Array(funcs), _] => funcs.as_slice(),
+        [other] | [other, _] => {
+            return Err(RuntimeError::TypeErrorDetailed {
+                expected: "Array".to_string(),
+                got: other.type_name().to_string(),
+            });
+        }
+        _ => {
+            return Err(RuntimeError::WrongArity {
+                expected: 2,
+                got: args.len(),
+            });
+        }
+    };
+    let workers = match args.get(1) {
+        None | Some(Value::Null) => default_workers(),
+        Some(Value::Number(n)) if n.fract() == 0.0 && *n >= 1.0 => *n as usize,
+        Some(other) => {
+            return Err(RuntimeError::InvalidOperation(format!(
+                "PARALLEL expects max_workers to be a positive integer, got {}",
+                other
+            )));
+        }
+    };
+    Ok((funcs, workers.min(funcs.len().max(1))))
+}
+
+/// 解析任务句柄（如 "task:1"）
+pub(crate) fn parse_handle(value: &Value) -> Result<usize, RuntimeError> {
+    match value {
+        Value::String(s) => s
+            .strip_prefix("task:")
+            .and_then(|id| id.parse().ok())
+            .ok_or_else(|| RuntimeError::InvalidOperation(format!("Invalid task handle '{}'", s))),
+        other => Err(RuntimeError::TypeErrorDetailed {
+            expected: "String".to_string(),
+            got: other.type_name().to_string(),
+        }),
+    }
+}
+
+/// 任务句柄
+pub(crate) fn handle(id: usize) -> Value {
+    Value::String(format!("task:{}", id))
+}
+
+/// 等待任务结果并转换为结果字典
+pub(crate) fn collect(
+    receiver: &mpsc::Receiver<TaskResult>,
+    env: &Rc<RefCell<Environment>>,
+) -> Value {
+    let result = receiver
+        .recv()
+        .unwrap_or_else(|_| Err("Task worker stopped unexpectedly".to_string()));
+    let mut dict = HashMap::new();
+    match result {
+        Ok(value) => {
+            dict.insert("ok".to_string(), Value::Boolean(true));
+            dict.insert("value".to_string(), value.into_value(env));
+        }
+        Err(message) => {
+            dict.insert("ok".to_string(), Value::Boolean(false));
+            dict.insert("error".to_string(), Value::String(message));
+        }
+    }
+    Value::Dict(dict)
+}
+
+/// 占位实现：SPAWN 需要复制 Aether 函数，由求值器处理
+pub fn spawn(_args: &[Value]) -> Result<Value, RuntimeError> {
+    Err(RuntimeError::InvalidOperation(
+        "SPAWN requires function evaluation context".to_string(),
+    ))
+}
+
+/// 占位实现：AWAIT_ALL 由求值器处理
+pub fn await_all(_args: &[Value]) -> Result<Value, RuntimeError> {
+    Err(RuntimeError::InvalidOperation(
+        "AWAIT_ALL requires function evaluation context".to_string(),
+    ))
+}
+
+/// 占位实现：PARALLEL 需要复制 Aether 函数，由求值器处理
+pub fn parallel(_args: &[Value]) -> Result<Value, RuntimeError> {
+    Err(RuntimeError::InvalidOperation(
+        "PARALLEL requires function evaluation context".to_string(),
+    ))
+}
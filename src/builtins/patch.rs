@@ -0,0 +1,372 @@
+// src/builtins/patch.rs
+//! Structured value diff / patch built-in functions
+//!
+//! `DIFF(a, b)` 比较两个值，返回结构化补丁（变更列表），`APPLY_PATCH(value, patch)`
+//! 把补丁应用到值上。补丁中的每项变更是一个字典：
+//!
+//! - `op`: `"add"` / `"remove"` / `"change"`
+//! - `path`: 路径数组，字典键为 String，数组下标为 Number；`[]` 表示整个值
+//! - `old`: 原值（`remove` / `change`）
+//! - `new`: 新值（`add` / `change`）
+//!
+//! 同样的能力在 Rust 侧以 [`diff_values`] / [`apply_changes`] 提供。
+
+use crate::evaluator::RuntimeError;
+use crate::value::Value;
+use std::collections::HashMap;
+use std::fmt;
+
+/// 变更类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    /// 新增的字典键或数组元素
+    Add,
+    /// 删除的字典键或数组元素
+    Remove,
+    /// 值被替换
+    Change,
+}
+
+impl ChangeKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ChangeKind::Add => "add",
+            ChangeKind::Remove => "remove",
+            ChangeKind::Change => "change",
+        }
+    }
+}
+
+/// 路径中的一段
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathSegment {
+    /// 字典键
+    Key(String),
+    /// 数组下标
+    Index(usize),
+}
+
+impl fmt::Display for PathSegment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathSegment::Key(key) => write!(f, ".{}", key),
+            PathSegment::Index(index) => write!(f, "[{}]", index),
+        }
+    }
+}
+
+/// 一项变更
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValueChange {
+    pub kind: ChangeKind,
+    pub path: Vec<PathSegment>,
+    /// 原值（`Remove` / `Change`）
+    pub old: Option<Value>,
+    /// 新值（`Add` / `Change`）
+    pub new: Option<Value>,
+}
+
+impl ValueChange {
+    /// 路径的可读形式，如 `$.items[2].name`
+    pub fn path_string(&self) -> String {
+        let mut path = "$".to_string();
+        for segment in &self.path {
+            path.push_str(&segment.to_string());
+        }
+        path
+    }
+
+    /// 转换为脚本中使用的字典形式
+    pub fn to_value(&self) -> Value {
+        let mut dict = HashMap::new();
+        dict.insert(
+            "op".to_string(),
+            Value::String(self.kind.as_str().to_string()),
+        );
+        dict.insert(
+            "path".to_string(),
+            Value::Array(
+                self.path
+                    .iter()
+                    .map(|segment| match segment {
+                        PathSegment::Key(key) => Value::String(key.clone()),
+                        PathSegment::Index(index) => Value::Number(*index as f64),
+                    })
+                    .collect(),
+            ),
+        );
+        if let Some(old) = &self.old {
+            dict.insert("old".to_string(), old.clone());
+        }
+        if let Some(new) = &self.new {
+            dict.insert("new".to_string(), new.clone());
+        }
+        Value::Dict(dict)
+    }
+
+    /// 从脚本中的字典形式解析
+    pub fn from_value(value: &Value) -> Result<Self, RuntimeError> {
+        let invalid = |message: &str| {
+            RuntimeError::InvalidOperation(format!("Invalid patch entry: {}", message))
+        };
+        let Value::Dict(dict) = value else {
+            return Err(invalid(&format!(
+                "expected Dict, got {}",
+                value.type_name()
+            )));
+        };
+        let kind = match dict.get("op") {
+            Some(Value::String(op)) if op == "add" => ChangeKind::Add,
+            Some(Value::String(op)) if op == "remove" => ChangeKind::Remove,
+            Some(Value::String(op)) if op == "change" => ChangeKind::Change,
+            _ => return Err(invalid("'op' must be \"add\", \"remove\" or \"change\"")),
+        };
+        let Some(Value::Array(segments)) = dict.get("path") else {
+            return Err(invalid("'path' must be an Array"));
+        };
+        let path = segments
+            .iter()
+            .map(|segment| match segment {
+                Value::String(key) => Ok(PathSegment::Key(key.clone())),
+                Value::Number(n) if *n >= 0.0 && n.fract() == 0.0 => {
+                    Ok(PathSegment::Index(*n as usize))
+                }
+                other => Err(invalid(&format!(
+                    "path segments must be String keys or non-negative integer indices, got {}",
+                    other
+                ))),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let new = dict.get("new").cloned();
+        if kind != ChangeKind::Remove && new.is_none() {
+            return Err(invalid("'new' is required for add and change"));
+        }
+        Ok(ValueChange {
+            kind,
+            path,
+            old: dict.get("old").cloned(),
+            new,
+        })
+    }
+}
+
+/// 比较两个值，返回把 `old` 变为 `new` 的变更列表
+///
+/// 字典按键比较（键按字典序输出），数组按下标比较；类型不同或标量不等时整体替换。
+/// 数组尾部的删除按下标从大到小排列，按顺序应用即可。
+pub fn diff_values(old: &Value, new: &Value) -> Vec<ValueChange> {
+    let mut changes = Vec::new();
+    diff_into(old, new, &mut Vec::new(), &mut changes);
+    changes
+}
+
+fn diff_into(
+    old: &Value,
+    new: &Value,
+    path: &mut Vec<PathSegment>,
+    changes: &mut Vec<ValueChange>,
+) {
+    match (old, new) {
+        (Value::Dict(a), Value::Dict(b)) => {
+            let mut keys: Vec<&String> = a.keys().chain(b.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                path.push(PathSegment::Key(key.clone()));
+                match (a.get(key), b.get(key)) {
+                    (Some(x), Some(y)) => diff_into(x, y, path, changes),
+                    (Some(x), None) => changes.push(ValueChange {
+                        kind: ChangeKind::Remove,
+                        path: path.clone(),
+                        old: Some(x.clone()),
+                        new: None,
+                    }),
+                    (None, Some(y)) => changes.push(ValueChange {
+                        kind: ChangeKind::Add,
+                        path: path.clone(),
+                        old: None,
+                        new: Some(y.clone()),
+                    }),
+                    (None, None) => {}
+                }
+                path.pop();
+            }
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            for (i, (x, y)) in a.iter().zip(b.iter()).enumerate() {
+                path.push(PathSegment::Index(i));
+                diff_into(x, y, path, changes);
+                path.pop();
+            }
+            for (i, x) in a.iter().enumerate().skip(b.len()).rev() {
+                path.push(PathSegment::Index(i));
+                changes.push(ValueChange {
+                    kind: ChangeKind::Remove,
+                    path: path.clone(),
+                    old: Some(x.clone()),
+                    new: None,
+                });
+                path.pop();
+            }
+            for (i, y) in b.iter().enumerate().skip(a.len()) {
+                path.push(PathSegment::Index(i));
+                changes.push(ValueChange {
+                    kind: ChangeKind::Add,
+                    path: path.clone(),
+                    old: None,
+                    new: Some(y.clone()),
+                });
+                path.pop();
+            }
+        }
+        _ if old.type_name() == new.type_name() && old == new => {}
+        _ => changes.push(ValueChange {
+            kind: ChangeKind::Change,
+            path: path.clone(),
+            old: Some(old.clone()),
+            new: Some(new.clone()),
+        }),
+    }
+}
+
+/// 按顺序把变更应用到值上，返回新值
+pub fn apply_changes(value: &Value, changes: &[ValueChange]) -> Result<Value, RuntimeError> {
+    let mut result = value.clone();
+    for change in changes {
+        apply_change(&mut result, change)?;
+    }
+    Ok(result)
+}
+
+fn apply_change(root: &mut Value, change: &ValueChange) -> Result<(), RuntimeError> {
+    let not_found = || {
+        RuntimeError::InvalidOperation(format!(
+            "Cannot apply {} at {}: path not found",
+            change.kind.as_str(),
+            change.path_string()
+        ))
+    };
+
+    let Some((last, parents)) = change.path.split_last() else {
+        return match (change.kind, &change.new) {
+            (ChangeKind::Change, Some(new)) => {
+                *root = new.clone();
+                Ok(())
+            }
+            _ => Err(not_found()),
+        };
+    };
+
+    let mut target = root;
+    for segment in parents {
+        target = match (target, segment) {
+            (Value::Dict(dict), PathSegment::Key(key)) => dict.get_mut(key),
+            (Value::Array(arr), PathSegment::Index(index)) => arr.get_mut(*index),
+            _ => None,
+        }
+        .ok_or_else(not_found)?;
+    }
+
+    let new = || change.new.clone().ok_or_else(not_found);
+    match (target, last, change.kind) {
+        (Value::Dict(dict), PathSegment::Key(key), ChangeKind::Add) => {
+            dict.insert(key.clone(), new()?);
+        }
+        (Value::Dict(dict), PathSegment::Key(key), ChangeKind::Change)
+            if dict.contains_key(key) =>
+        {
+            dict.insert(key.clone(), new()?);
+        }
+        (Value::Dict(dict), PathSegment::Key(key), ChangeKind::Remove) => {
+            dict.remove(key).ok_or_else(not_found)?;
+        }
+        (Value::Array(arr), PathSegment::Index(index), ChangeKind::Add) if *index <= arr.len() => {
+            arr.insert(*index, new()?);
+        }
+        (Value::Array(arr), PathSegment::Index(index), ChangeKind::Change)
+            if *index < arr.len() =>
+        {
+            arr[*index] = new()?;
+        }
+        (Value::Array(arr), PathSegment::Index(index), ChangeKind::Remove)
+            if *index < arr.len() =>
+        {
+            arr.remove(*index);
+        }
+        _ => return Err(not_found()),
+    }
+    Ok(())
+}
+
+/// 结构化比较 / 差分
+///
+/// # 功能
+/// 两个参数且不是 `DIFF(数组, 数字)` 形式时，比较两个值并返回结构化补丁；
+/// 否则保持原有的数值差分行为（见 `math::diff`）。
+///
+/// # 参数
+/// - `a`: Any - 原值
+/// - `b`: Any - 新值
+///
+/// # 返回值
+/// Array - 变更列表，每项为 `{"op", "path", "old", "new"}` 字典；相等时为空数组
+///
+/// # 示例
+/// ```aether
+/// DIFF({"a": 1, "b": 2}, {"a": 1, "b": 3, "c": 4})
+/// # [{"op": "change", "path": ["b"], "old": 2, "new": 3},
+/// #  {"op": "add", "path": ["c"], "new": 4}]
+/// ```
+pub fn diff(args: &[Value]) -> Result<Value, RuntimeError> {
+    match args {
+        [Value::Array(_), Value::Number(_)] | [_] => super::math::diff(args),
+        [old, new] => Ok(Value::Array(
+            diff_values(old, new)
+                .iter()
+                .map(ValueChange::to_value)
+                .collect(),
+        )),
+        _ => Err(RuntimeError::WrongArity {
+            expected: 2,
+            got: args.len(),
+        }),
+    }
+}
+
+/// 应用补丁
+///
+/// # 功能
+/// 把 `DIFF` 返回的补丁（或手写的同格式变更列表）按顺序应用到值上，原值不变。
+///
+/// # 参数
+/// - `value`: Any - 原值
+/// - `patch`: Array - 变更列表
+///
+/// # 返回值
+/// Any - 应用补丁后的新值；路径不存在时报错
+///
+/// # 示例
+/// ```aether
+/// Set OLD {"a": 1}
+/// Set NEW {"a": 2, "b": [1]}
+/// APPLY_PATCH(OLD, DIFF(OLD, NEW))     # {"a": 2, "b": [1]}
+/// ```
+pub fn apply_patch(args: &[Value]) -> Result<Value, RuntimeError> {
+    if args.len() != 2 {
+        return Err(RuntimeError::WrongArity {
+            expected: 2,
+            got: args.len(),
+        });
+    }
+    let Value::Array(entries) = &args[1] else {
+        return Err(RuntimeError::TypeErrorDetailed {
+            expected: "Array".to_string(),
+            got: args[1].type_name().to_string(),
+        });
+    };
+    let changes = entries
+        .iter()
+        .map(ValueChange::from_value)
+        .collect::<Result<Vec<_>, _>>()?;
+    apply_changes(&args[0], &changes)
+}
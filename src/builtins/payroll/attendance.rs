@@ -1,19 +1,10 @@
 // src/builtins/payroll/attendance.rs
 //! 考勤相关计算函数
 
+use super::money::{Money, dec, is_exact, money_value, ratio_value, to_money};
 use crate::evaluator::RuntimeError;
 use crate::value::Value;
-
-/// 辅助函数：安全地获取数字参数
-fn get_number(val: &Value) -> Result<f64, RuntimeError> {
-    match val {
-        Value::Number(n) => Ok(*n),
-        _ => Err(RuntimeError::TypeErrorDetailed {
-            expected: "Number".to_string(),
-            got: format!("{:?}", val),
-        }),
-    }
-}
+use num_traits::{One, Zero};
 
 /// 计算出勤率
 ///
@@ -31,17 +22,17 @@ pub fn calc_attendance_rate(args: &[Value]) -> Result<Value, RuntimeError> {
         });
     }
 
-    let actual_days = get_number(&args[0])?;
-    let required_days = get_number(&args[1])?;
+    let actual_days = to_money(&args[0])?;
+    let required_days = to_money(&args[1])?;
 
-    if required_days <= 0.0 {
+    if required_days <= Money::zero() {
         return Err(RuntimeError::InvalidOperation(
             "应出勤天数必须大于0".to_string(),
         ));
     }
 
-    let rate = (actual_days / required_days).clamp(0.0, 1.0);
-    Ok(Value::Number(rate))
+    let rate = (actual_days / required_days).clamp(Money::zero(), Money::one());
+    Ok(ratio_value(&rate, is_exact(args)))
 }
 
 /// 计算迟到扣款
@@ -60,10 +51,13 @@ pub fn calc_late_deduction(args: &[Value]) -> Result<Value, RuntimeError> {
         });
     }
 
-    let late_count = get_number(&args[0])?;
-    let deduction_per_time = get_number(&args[1])?;
+    let late_count = to_money(&args[0])?;
+    let deduction_per_time = to_money(&args[1])?;
 
-    Ok(Value::Number(late_count * deduction_per_time))
+    Ok(money_value(
+        &(late_count * deduction_per_time),
+        is_exact(args),
+    ))
 }
 
 /// 计算早退扣款
@@ -82,10 +76,13 @@ pub fn calc_early_leave_deduction(args: &[Value]) -> Result<Value, RuntimeError>
         });
     }
 
-    let early_leave_count = get_number(&args[0])?;
-    let deduction_per_time = get_number(&args[1])?;
+    let early_leave_count = to_money(&args[0])?;
+    let deduction_per_time = to_money(&args[1])?;
 
-    Ok(Value::Number(early_leave_count * deduction_per_time))
+    Ok(money_value(
+        &(early_leave_count * deduction_per_time),
+        is_exact(args),
+    ))
 }
 
 /// 计算缺勤扣款
@@ -104,10 +101,10 @@ pub fn calc_absent_deduction(args: &[Value]) -> Result<Value, RuntimeError> {
         });
     }
 
-    let absent_days = get_number(&args[0])?;
-    let daily_pay = get_number(&args[1])?;
+    let absent_days = to_money(&args[0])?;
+    let daily_pay = to_money(&args[1])?;
 
-    Ok(Value::Number(absent_days * daily_pay))
+    Ok(money_value(&(absent_days * daily_pay), is_exact(args)))
 }
 
 /// 计算请假扣款
@@ -127,15 +124,18 @@ pub fn calc_leave_deduction(args: &[Value]) -> Result<Value, RuntimeError> {
         });
     }
 
-    let leave_days = get_number(&args[0])?;
-    let daily_pay = get_number(&args[1])?;
+    let leave_days = to_money(&args[0])?;
+    let daily_pay = to_money(&args[1])?;
     let deduction_ratio = if args.len() > 2 {
-        get_number(&args[2])?
+        to_money(&args[2])?
     } else {
-        1.0 // 默认全额扣款
+        dec("1") // 默认全额扣款
     };
 
-    Ok(Value::Number(leave_days * daily_pay * deduction_ratio))
+    Ok(money_value(
+        &(leave_days * daily_pay * deduction_ratio),
+        is_exact(args),
+    ))
 }
 
 /// 计算病假工资
@@ -158,26 +158,29 @@ pub fn calc_sick_leave_pay(args: &[Value]) -> Result<Value, RuntimeError> {
         });
     }
 
-    let base_salary = get_number(&args[0])?;
-    let sick_days = get_number(&args[1])?;
-    let seniority = get_number(&args[2])?;
+    let base_salary = to_money(&args[0])?;
+    let sick_days = to_money(&args[1])?;
+    let seniority = to_money(&args[2])?;
 
     // 根据工龄确定病假工资比例
-    let ratio = if seniority < 2.0 {
-        0.60 // 不足2年，60%
-    } else if seniority < 4.0 {
-        0.70 // 2-4年，70%
-    } else if seniority < 6.0 {
-        0.80 // 4-6年，80%
-    } else if seniority < 8.0 {
-        0.90 // 6-8年，90%
+    let ratio = if seniority < dec("2") {
+        dec("0.6") // 不足2年，60%
+    } else if seniority < dec("4") {
+        dec("0.7") // 2-4年，70%
+    } else if seniority < dec("6") {
+        dec("0.8") // 4-6年，80%
+    } else if seniority < dec("8") {
+        dec("0.9") // 6-8年，90%
     } else {
-        1.00 // 8年以上，100%
+        dec("1") // 8年以上，100%
     };
 
     // 计算日薪并按比例支付
-    let daily_pay = base_salary / 21.75;
-    Ok(Value::Number(daily_pay * sick_days * ratio))
+    let daily_pay = base_salary / dec("21.75");
+    Ok(money_value(
+        &(daily_pay * sick_days * ratio),
+        is_exact(args),
+    ))
 }
 
 /// 计算无薪假扣款
@@ -196,10 +199,10 @@ pub fn calc_unpaid_leave_deduction(args: &[Value]) -> Result<Value, RuntimeError
         });
     }
 
-    let base_salary = get_number(&args[0])?;
-    let unpaid_days = get_number(&args[1])?;
+    let base_salary = to_money(&args[0])?;
+    let unpaid_days = to_money(&args[1])?;
 
     // 按日薪扣款
-    let daily_pay = base_salary / 21.75;
-    Ok(Value::Number(daily_pay * unpaid_days))
+    let daily_pay = base_salary / dec("21.75");
+    Ok(money_value(&(daily_pay * unpaid_days), is_exact(args)))
 }
@@ -1,19 +1,50 @@
 // src/builtins/payroll/basic.rs
 //! 基础薪酬计算函数
 
+use super::money::{Money, dec, is_exact, money_value, to_money};
+use super::{make_breakdown, split_options};
 use crate::evaluator::RuntimeError;
 use crate::value::Value;
+use num_traits::Zero;
 
-/// 辅助函数：安全地获取数字参数
-fn get_number(val: &Value) -> Result<f64, RuntimeError> {
-    match val {
-        Value::Number(n) => Ok(*n),
-        _ => Err(RuntimeError::TypeErrorDetailed {
-            expected: "Number".to_string(),
-            got: format!("{:?}", val),
-        }),
-    }
-}
+use crate::builtins::args::{ArgSpec, ArgType, Param};
+
+const CALC_HOURLY_PAY: ArgSpec = ArgSpec::new(
+    "CALC_HOURLY_PAY",
+    &[
+        Param::required("monthly_salary", ArgType::Numeric),
+        Param::optional("monthly_hours", ArgType::Numeric),
+    ],
+);
+const CALC_DAILY_PAY: ArgSpec = ArgSpec::new(
+    "CALC_DAILY_PAY",
+    &[
+        Param::required("monthly_salary", ArgType::Numeric),
+        Param::optional("monthly_days", ArgType::Numeric),
+    ],
+);
+const CALC_MONTHLY_FROM_HOURLY: ArgSpec = ArgSpec::new(
+    "CALC_MONTHLY_FROM_HOURLY",
+    &[
+        Param::required("hourly_rate", ArgType::Numeric),
+        Param::optional("monthly_hours", ArgType::Numeric),
+    ],
+);
+const CALC_ANNUAL_SALARY: ArgSpec = ArgSpec::new(
+    "CALC_ANNUAL_SALARY",
+    &[
+        Param::required("monthly_salary", ArgType::Numeric),
+        Param::optional("months", ArgType::Numeric),
+    ],
+);
+
+/// 本模块声明了参数规格的函数
+pub(crate) const ARG_SPECS: &[ArgSpec] = &[
+    CALC_HOURLY_PAY,
+    CALC_DAILY_PAY,
+    CALC_MONTHLY_FROM_HOURLY,
+    CALC_ANNUAL_SALARY,
+];
 
 /// 计算时薪
 ///
@@ -24,27 +55,25 @@ fn get_number(val: &Value) -> Result<f64, RuntimeError> {
 /// # 返回
 /// 时薪
 pub fn calc_hourly_pay(args: &[Value]) -> Result<Value, RuntimeError> {
-    if args.is_empty() {
-        return Err(RuntimeError::WrongArity {
-            expected: 1,
-            got: 0,
-        });
-    }
+    CALC_HOURLY_PAY.check(args)?;
 
-    let monthly_salary = get_number(&args[0])?;
+    let monthly_salary = to_money(&args[0])?;
     let monthly_hours = if args.len() > 1 {
-        get_number(&args[1])?
+        to_money(&args[1])?
     } else {
-        174.0 // 默认月工作小时数: 21.75天 * 8小时
+        dec("174") // 默认月工作小时数: 21.75天 * 8小时
     };
 
-    if monthly_hours <= 0.0 {
+    if monthly_hours <= Money::zero() {
         return Err(RuntimeError::InvalidOperation(
             "月工作小时数必须大于0".to_string(),
         ));
     }
 
-    Ok(Value::Number(monthly_salary / monthly_hours))
+    Ok(money_value(
+        &(monthly_salary / monthly_hours),
+        is_exact(args),
+    ))
 }
 
 /// 计算日薪
@@ -56,27 +85,25 @@ pub fn calc_hourly_pay(args: &[Value]) -> Result<Value, RuntimeError> {
 /// # 返回
 /// 日薪
 pub fn calc_daily_pay(args: &[Value]) -> Result<Value, RuntimeError> {
-    if args.is_empty() {
-        return Err(RuntimeError::WrongArity {
-            expected: 1,
-            got: 0,
-        });
-    }
+    CALC_DAILY_PAY.check(args)?;
 
-    let monthly_salary = get_number(&args[0])?;
+    let monthly_salary = to_money(&args[0])?;
     let monthly_days = if args.len() > 1 {
-        get_number(&args[1])?
+        to_money(&args[1])?
     } else {
-        21.75 // 法定计薪天数
+        dec("21.75") // 法定计薪天数
     };
 
-    if monthly_days <= 0.0 {
+    if monthly_days <= Money::zero() {
         return Err(RuntimeError::InvalidOperation(
             "月工作天数必须大于0".to_string(),
         ));
     }
 
-    Ok(Value::Number(monthly_salary / monthly_days))
+    Ok(money_value(
+        &(monthly_salary / monthly_days),
+        is_exact(args),
+    ))
 }
 
 /// 根据时薪计算月薪
@@ -88,21 +115,16 @@ pub fn calc_daily_pay(args: &[Value]) -> Result<Value, RuntimeError> {
 /// # 返回
 /// 月薪
 pub fn calc_monthly_from_hourly(args: &[Value]) -> Result<Value, RuntimeError> {
-    if args.is_empty() {
-        return Err(RuntimeError::WrongArity {
-            expected: 1,
-            got: 0,
-        });
-    }
+    CALC_MONTHLY_FROM_HOURLY.check(args)?;
 
-    let hourly_rate = get_number(&args[0])?;
+    let hourly_rate = to_money(&args[0])?;
     let monthly_hours = if args.len() > 1 {
-        get_number(&args[1])?
+        to_money(&args[1])?
     } else {
-        174.0
+        dec("174")
     };
 
-    Ok(Value::Number(hourly_rate * monthly_hours))
+    Ok(money_value(&(hourly_rate * monthly_hours), is_exact(args)))
 }
 
 /// 计算年薪
@@ -114,21 +136,16 @@ pub fn calc_monthly_from_hourly(args: &[Value]) -> Result<Value, RuntimeError> {
 /// # 返回
 /// 年薪
 pub fn calc_annual_salary(args: &[Value]) -> Result<Value, RuntimeError> {
-    if args.is_empty() {
-        return Err(RuntimeError::WrongArity {
-            expected: 1,
-            got: 0,
-        });
-    }
+    CALC_ANNUAL_SALARY.check(args)?;
 
-    let monthly_salary = get_number(&args[0])?;
+    let monthly_salary = to_money(&args[0])?;
     let months = if args.len() > 1 {
-        get_number(&args[1])?
+        to_money(&args[1])?
     } else {
-        12.0
+        dec("12")
     };
 
-    Ok(Value::Number(monthly_salary * months))
+    Ok(money_value(&(monthly_salary * months), is_exact(args)))
 }
 
 /// 根据出勤率计算基本工资
@@ -137,10 +154,12 @@ pub fn calc_annual_salary(args: &[Value]) -> Result<Value, RuntimeError> {
 /// - 基本工资
 /// - 实际出勤天数
 /// - 应出勤天数
+/// - 选项字典（可选，`{"breakdown": true}` 返回明细）
 ///
 /// # 返回
 /// 实际基本工资
 pub fn calc_base_salary(args: &[Value]) -> Result<Value, RuntimeError> {
+    let (args, breakdown) = split_options(args);
     if args.len() < 3 {
         return Err(RuntimeError::WrongArity {
             expected: 3,
@@ -148,18 +167,33 @@ pub fn calc_base_salary(args: &[Value]) -> Result<Value, RuntimeError> {
         });
     }
 
-    let base_salary = get_number(&args[0])?;
-    let actual_days = get_number(&args[1])?;
-    let required_days = get_number(&args[2])?;
+    let base_salary = to_money(&args[0])?;
+    let actual_days = to_money(&args[1])?;
+    let required_days = to_money(&args[2])?;
 
-    if required_days <= 0.0 {
+    if required_days <= Money::zero() {
         return Err(RuntimeError::InvalidOperation(
             "应出勤天数必须大于0".to_string(),
         ));
     }
 
-    let attendance_rate = actual_days / required_days;
-    Ok(Value::Number(base_salary * attendance_rate))
+    let exact = is_exact(args);
+    let attendance_rate = &actual_days / &required_days;
+    let result = money_value(&(&base_salary * &attendance_rate), exact);
+    if breakdown {
+        return Ok(make_breakdown(
+            result,
+            "基本工资 × 实际出勤天数 / 应出勤天数",
+            &[
+                ("base_salary", base_salary),
+                ("actual_days", actual_days),
+                ("required_days", required_days),
+            ],
+            &[("attendance_rate", attendance_rate)],
+            exact,
+        ));
+    }
+    Ok(result)
 }
 
 /// 计算应发工资
@@ -169,10 +203,12 @@ pub fn calc_base_salary(args: &[Value]) -> Result<Value, RuntimeError> {
 /// - 加班费
 /// - 奖金
 /// - 补贴
+/// - 选项字典（可选，`{"breakdown": true}` 返回明细）
 ///
 /// # 返回
 /// 应发工资总额
 pub fn calc_gross_salary(args: &[Value]) -> Result<Value, RuntimeError> {
+    let (args, breakdown) = split_options(args);
     if args.len() < 4 {
         return Err(RuntimeError::WrongArity {
             expected: 4,
@@ -180,12 +216,29 @@ pub fn calc_gross_salary(args: &[Value]) -> Result<Value, RuntimeError> {
         });
     }
 
-    let base = get_number(&args[0])?;
-    let overtime = get_number(&args[1])?;
-    let bonus = get_number(&args[2])?;
-    let allowance = get_number(&args[3])?;
+    let base = to_money(&args[0])?;
+    let overtime = to_money(&args[1])?;
+    let bonus = to_money(&args[2])?;
+    let allowance = to_money(&args[3])?;
 
-    Ok(Value::Number(base + overtime + bonus + allowance))
+    let exact = is_exact(args);
+    let gross = money_value(&(&base + &overtime + &bonus + &allowance), exact);
+    if breakdown {
+        let parts = [
+            ("base", base),
+            ("overtime", overtime),
+            ("bonus", bonus),
+            ("allowance", allowance),
+        ];
+        return Ok(make_breakdown(
+            gross,
+            "基本工资 + 加班费 + 奖金 + 补贴",
+            &parts,
+            &parts,
+            exact,
+        ));
+    }
+    Ok(gross)
 }
 
 /// 计算实发工资
@@ -196,10 +249,12 @@ pub fn calc_gross_salary(args: &[Value]) -> Result<Value, RuntimeError> {
 /// - 公积金
 /// - 个税
 /// - 其他扣除
+/// - 选项字典（可选，`{"breakdown": true}` 返回明细）
 ///
 /// # 返回
 /// 实发工资
 pub fn calc_net_salary(args: &[Value]) -> Result<Value, RuntimeError> {
+    let (args, breakdown) = split_options(args);
     if args.len() < 5 {
         return Err(RuntimeError::WrongArity {
             expected: 5,
@@ -207,12 +262,30 @@ pub fn calc_net_salary(args: &[Value]) -> Result<Value, RuntimeError> {
         });
     }
 
-    let gross = get_number(&args[0])?;
-    let social_insurance = get_number(&args[1])?;
-    let housing_fund = get_number(&args[2])?;
-    let tax = get_number(&args[3])?;
-    let other_deductions = get_number(&args[4])?;
+    let gross = to_money(&args[0])?;
+    let social_insurance = to_money(&args[1])?;
+    let housing_fund = to_money(&args[2])?;
+    let tax = to_money(&args[3])?;
+    let other_deductions = to_money(&args[4])?;
 
-    let net = gross - social_insurance - housing_fund - tax - other_deductions;
-    Ok(Value::Number(net.max(0.0)))
+    let exact = is_exact(args);
+    let total_deductions = &social_insurance + &housing_fund + &tax + &other_deductions;
+    let net = (&gross - &total_deductions).max(Money::zero());
+    let result = money_value(&net, exact);
+    if breakdown {
+        return Ok(make_breakdown(
+            result,
+            "应发工资 - 社保 - 公积金 - 个税 - 其他扣除（不低于0）",
+            &[
+                ("gross", gross),
+                ("social_insurance", social_insurance),
+                ("housing_fund", housing_fund),
+                ("tax", tax),
+                ("other_deductions", other_deductions),
+            ],
+            &[("total_deductions", total_deductions)],
+            exact,
+        ));
+    }
+    Ok(result)
 }
@@ -0,0 +1,330 @@
+// src/builtins/payroll/batch.rs
+//! 批量薪酬计算函数
+
+use super::money::{Money, dec, money_value, round_money, to_money};
+use super::{basic, insurance, tax};
+use crate::builtins::table::{csv_records, split_csv_record};
+use crate::evaluator::RuntimeError;
+use crate::value::Value;
+use num_traits::Zero;
+use std::collections::HashMap;
+
+/// 批量计算的全局配置（来自 config 字典）
+struct BatchConfig {
+    pension_rate: Money,
+    medical_rate: Money,
+    unemployment_rate: Money,
+    housing_rate: Money,
+    tax_threshold: Money,
+}
+
+impl BatchConfig {
+    fn from_value(val: &Value) -> Result<Self, RuntimeError> {
+        let dict = match val {
+            Value::Dict(d) => d,
+            Value::Null => &HashMap::new(),
+            _ => {
+                return Err(RuntimeError::TypeErrorDetailed {
+                    expected: "Dict".to_string(),
+                    got: format!("{:?}", val),
+                });
+            }
+        };
+
+        Ok(BatchConfig {
+            pension_rate: field_or(dict, "pension_rate", "0.08")?,
+            medical_rate: field_or(dict, "medical_rate", "0.02")?,
+            unemployment_rate: field_or(dict, "unemployment_rate", "0.005")?,
+            housing_rate: field_or(dict, "housing_rate", "0.12")?,
+            tax_threshold: field_or(dict, "tax_threshold", "5000")?,
+        })
+    }
+}
+
+/// 辅助函数：读取字典中的数字字段，缺失时使用默认值
+fn field_or(
+    dict: &HashMap<String, Value>,
+    key: &str,
+    default: &str,
+) -> Result<Money, RuntimeError> {
+    match dict.get(key) {
+        None | Some(Value::Null) => Ok(dec(default)),
+        Some(val @ (Value::Number(_) | Value::Fraction(_))) => to_money(val),
+        Some(other) => Err(RuntimeError::TypeErrorDetailed {
+            expected: format!("Number for field '{}'", key),
+            got: format!("{:?}", other),
+        }),
+    }
+}
+
+/// 辅助函数：以分数形式调用薪酬函数，保持计算精确
+fn call(
+    func: fn(&[Value]) -> Result<Value, RuntimeError>,
+    args: &[&Money],
+) -> Result<Money, RuntimeError> {
+    let args: Vec<Value> = args.iter().map(|m| Value::Fraction((*m).clone())).collect();
+    to_money(&func(&args)?)
+}
+
+/// 计算单个员工的薪资明细，返回 (明细, 各汇总项的精确金额)
+fn run_employee(
+    employee: &Value,
+    config: &BatchConfig,
+) -> Result<(HashMap<String, Value>, Vec<Money>), RuntimeError> {
+    let emp = match employee {
+        Value::Dict(d) => d,
+        _ => {
+            return Err(RuntimeError::TypeErrorDetailed {
+                expected: "Dict".to_string(),
+                got: format!("{:?}", employee),
+            });
+        }
+    };
+
+    let base_salary = match emp.get("base_salary") {
+        Some(val @ (Value::Number(_) | Value::Fraction(_))) => to_money(val)?,
+        Some(other) => {
+            return Err(RuntimeError::TypeErrorDetailed {
+                expected: "Number for field 'base_salary'".to_string(),
+                got: format!("{:?}", other),
+            });
+        }
+        None => {
+            return Err(RuntimeError::InvalidOperation(
+                "缺少必填字段 'base_salary'".to_string(),
+            ));
+        }
+    };
+
+    // 按出勤折算基本工资（未提供出勤数据时按全勤计算）
+    let required_days = field_or(emp, "required_days", "0")?;
+    let base = if required_days > Money::zero() {
+        let actual_days = match emp.get("actual_days") {
+            None | Some(Value::Null) => required_days.clone(),
+            Some(_) => field_or(emp, "actual_days", "0")?,
+        };
+        call(
+            basic::calc_base_salary,
+            &[&base_salary, &actual_days, &required_days],
+        )?
+    } else {
+        round_money(&base_salary)
+    };
+
+    let overtime = field_or(emp, "overtime_pay", "0")?;
+    let bonus = field_or(emp, "bonus", "0")?;
+    let allowance = field_or(emp, "allowance", "0")?;
+    let gross = call(
+        basic::calc_gross_salary,
+        &[&base, &overtime, &bonus, &allowance],
+    )?;
+
+    let social_base = match emp.get("social_base") {
+        None | Some(Value::Null) => base_salary.clone(),
+        Some(_) => field_or(emp, "social_base", "0")?,
+    };
+    let social_insurance = call(
+        insurance::calc_social_insurance,
+        &[
+            &social_base,
+            &config.pension_rate,
+            &config.medical_rate,
+            &config.unemployment_rate,
+            &Money::zero(),
+        ],
+    )?;
+    let housing_fund = call(
+        insurance::calc_housing_fund,
+        &[&social_base, &config.housing_rate],
+    )?;
+
+    let special_deduction = field_or(emp, "special_deduction", "0")?;
+    let taxable_income = round_money(
+        &(&gross - &social_insurance - &housing_fund - &config.tax_threshold - &special_deduction)
+            .max(Money::zero()),
+    );
+    let personal_tax = call(tax::calc_personal_tax, &[&taxable_income])?;
+
+    let other_deductions = field_or(emp, "other_deductions", "0")?;
+    let net = call(
+        basic::calc_net_salary,
+        &[
+            &gross,
+            &social_insurance,
+            &housing_fund,
+            &personal_tax,
+            &other_deductions,
+        ],
+    )?;
+
+    // 员工数据中含有 Fraction 时，明细也以 Fraction 返回
+    let exact = emp.values().any(|v| matches!(v, Value::Fraction(_)));
+
+    let mut row = HashMap::new();
+    if let Some(id) = emp.get("id") {
+        row.insert("id".to_string(), id.clone());
+    }
+    if let Some(name) = emp.get("name") {
+        row.insert("name".to_string(), name.clone());
+    }
+    for (key, amount) in [
+        ("base_salary", &base),
+        ("gross", &gross),
+        ("social_insurance", &social_insurance),
+        ("housing_fund", &housing_fund),
+        ("taxable_income", &taxable_income),
+        ("tax", &personal_tax),
+        ("other_deductions", &other_deductions),
+        ("net", &net),
+    ] {
+        row.insert(key.to_string(), money_value(amount, exact));
+    }
+
+    let totals = vec![gross, social_insurance, housing_fund, personal_tax, net];
+    Ok((row, totals))
+}
+
+/// 解析 CSV 文本为员工字典数组（首行为表头，数字列自动转换为 Number）
+///
+/// 支持双引号包裹的字段（字段内可包含逗号、换行和 `""`）。
+fn parse_employee_csv(content: &str) -> Vec<Value> {
+    let mut records = csv_records(content).into_iter();
+    let headers: Vec<String> = match records.next() {
+        Some(h) => split_csv_record(&h, ',')
+            .iter()
+            .map(|s| s.trim().to_string())
+            .collect(),
+        None => return Vec::new(),
+    };
+
+    records
+        .map(|record| {
+            let mut row = HashMap::new();
+            for (header, cell) in headers.iter().zip(split_csv_record(&record, ',')) {
+                let cell = cell.trim();
+                let value = match cell.parse::<f64>() {
+                    Ok(n) => Value::Number(n),
+                    Err(_) if cell.is_empty() => Value::Null,
+                    Err(_) => Value::String(cell.to_string()),
+                };
+                row.insert(header.clone(), value);
+            }
+            Value::Dict(row)
+        })
+        .collect()
+}
+
+/// 批量计算员工薪资
+///
+/// # 参数
+/// - 员工数组（每个元素为字典），字段：
+///   `base_salary`（必填）、`id`、`name`、`actual_days`、`required_days`、
+///   `overtime_pay`、`bonus`、`allowance`、`social_base`、
+///   `special_deduction`、`other_deductions`
+/// - 配置字典（可为 null），字段：`pension_rate`、`medical_rate`、
+///   `unemployment_rate`、`housing_rate`、`tax_threshold`
+///
+/// # 返回
+/// 字典：
+/// - `results`: 每位员工的薪资明细
+/// - `errors`: 出错的行（`index`、`id`、`message`），不会中断整批计算
+/// - `totals`: 成功行的汇总（gross/social_insurance/housing_fund/tax/net）
+/// - `count` / `error_count`
+pub fn payroll_run(args: &[Value]) -> Result<Value, RuntimeError> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(RuntimeError::WrongArity {
+            expected: 2,
+            got: args.len(),
+        });
+    }
+
+    let employees = match &args[0] {
+        Value::Array(arr) => arr.clone(),
+        Value::String(_) => {
+            return Err(RuntimeError::InvalidOperation(
+                "PAYROLL_RUN 从 CSV 文件读取需要启用文件系统权限".to_string(),
+            ));
+        }
+        other => {
+            return Err(RuntimeError::TypeErrorDetailed {
+                expected: "Array".to_string(),
+                got: format!("{:?}", other),
+            });
+        }
+    };
+
+    let config = BatchConfig::from_value(args.get(1).unwrap_or(&Value::Null))?;
+    run_batch(&employees, &config)
+}
+
+/// 批量计算员工薪资（支持 CSV 文件路径，需要文件系统权限）
+///
+/// 与 `payroll_run` 相同，但第一个参数也可以是 CSV 文件路径，
+/// 文件首行为表头，列名与员工字典字段一致。
+pub fn payroll_run_with_files(args: &[Value]) -> Result<Value, RuntimeError> {
+    match args.first() {
+        Some(Value::String(path)) => {
+            let content = match super::super::filesystem::read_file(&[Value::String(path.clone())])?
+            {
+                Value::String(s) => s,
+                _ => String::new(),
+            };
+            let mut forwarded = args.to_vec();
+            forwarded[0] = Value::Array(parse_employee_csv(&content));
+            payroll_run(&forwarded)
+        }
+        _ => payroll_run(args),
+    }
+}
+
+/// 汇总项名称，与 `run_employee` 返回的金额顺序一致
+const TOTAL_KEYS: [&str; 5] = ["gross", "social_insurance", "housing_fund", "tax", "net"];
+
+fn run_batch(employees: &[Value], config: &BatchConfig) -> Result<Value, RuntimeError> {
+    let mut results = Vec::new();
+    let mut errors = Vec::new();
+    let mut totals = vec![Money::zero(); TOTAL_KEYS.len()];
+    let mut exact = false;
+
+    for (index, employee) in employees.iter().enumerate() {
+        match run_employee(employee, config) {
+            Ok((row, amounts)) => {
+                for (total, amount) in totals.iter_mut().zip(amounts) {
+                    *total += amount;
+                }
+                exact |= row.values().any(|v| matches!(v, Value::Fraction(_)));
+                results.push(Value::Dict(row));
+            }
+            Err(e) => {
+                let mut err = HashMap::new();
+                err.insert("index".to_string(), Value::Number(index as f64));
+                let id = match employee {
+                    Value::Dict(d) => d.get("id").cloned().unwrap_or(Value::Null),
+                    _ => Value::Null,
+                };
+                err.insert("id".to_string(), id);
+                err.insert("message".to_string(), Value::String(e.to_string()));
+                errors.push(Value::Dict(err));
+            }
+        }
+    }
+
+    // 汇总基于各行已舍入的精确金额，保证总额等于明细之和
+    let mut totals_dict: HashMap<String, Value> = TOTAL_KEYS
+        .iter()
+        .zip(&totals)
+        .map(|(k, v)| (k.to_string(), money_value(v, exact)))
+        .collect();
+    totals_dict.insert("count".to_string(), Value::Number(results.len() as f64));
+
+    let mut out = HashMap::new();
+    out.insert("count".to_string(), Value::Number(results.len() as f64));
+    out.insert(
+        "error_count".to_string(),
+        Value::Number(errors.len() as f64),
+    );
+    out.insert("results".to_string(), Value::Array(results));
+    out.insert("errors".to_string(), Value::Array(errors));
+    out.insert("totals".to_string(), Value::Dict(totals_dict));
+    Ok(Value::Dict(out))
+}
@@ -0,0 +1,261 @@
+// src/builtins/payroll/calendar.rs
+//! 节假日日历
+//!
+//! 为薪酬日期函数（IS_HOLIDAY、IS_WORKDAY、CALC_WORKDAYS 等）提供真实的
+//! 节假日数据，包括调休上班日（周末补班）。
+//!
+//! 日历按年份存储在当前引擎的薪酬设置中（见 [`super::settings`]），可以通过
+//! `SET_HOLIDAY_CALENDAR` 手动设置，或通过 `LOAD_HOLIDAY_CALENDAR` 加载内置日历 /
+//! JSON 数据。
+
+use super::settings::with_settings;
+use crate::evaluator::RuntimeError;
+use crate::value::Value;
+use chrono::{Datelike, NaiveDate, Weekday};
+use std::collections::HashSet;
+
+/// 内置中国法定节假日日历（JSON）
+const CN_CALENDAR: &str = include_str!("calendars/cn.json");
+
+/// 日期格式
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// 单个年份的节假日数据
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct YearCalendar {
+    /// 法定节假日（放假）
+    holidays: HashSet<NaiveDate>,
+    /// 调休上班日（周末补班）
+    workdays: HashSet<NaiveDate>,
+}
+
+impl YearCalendar {
+    /// 稳定的文本表示（日期排序后），用于结果缓存的键
+    pub(crate) fn fingerprint(&self) -> String {
+        let sorted = |dates: &HashSet<NaiveDate>| {
+            let mut dates: Vec<String> = dates.iter().map(NaiveDate::to_string).collect();
+            dates.sort();
+            dates.join(",")
+        };
+        format!("{};{}", sorted(&self.holidays), sorted(&self.workdays))
+    }
+}
+
+/// 解析 "YYYY-MM-DD" 格式的日期
+pub(crate) fn parse_date(s: &str) -> Result<NaiveDate, RuntimeError> {
+    NaiveDate::parse_from_str(s.trim(), DATE_FORMAT).map_err(|_| {
+        RuntimeError::InvalidOperation(format!("无效的日期 '{}'，应为 YYYY-MM-DD 格式", s))
+    })
+}
+
+/// 判断日期是否为法定节假日
+pub(crate) fn date_is_holiday(date: NaiveDate) -> bool {
+    with_settings(|s| {
+        s.calendar
+            .get(&date.year())
+            .is_some_and(|y| y.holidays.contains(&date))
+    })
+}
+
+/// 判断日期是否为工作日（考虑节假日与调休上班日）
+pub(crate) fn date_is_workday(date: NaiveDate) -> bool {
+    with_settings(|s| {
+        if let Some(year) = s.calendar.get(&date.year()) {
+            if year.workdays.contains(&date) {
+                return true;
+            }
+            if year.holidays.contains(&date) {
+                return false;
+            }
+        }
+        !matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+    })
+}
+
+/// 解析日期字符串数组
+fn parse_date_list(val: &Value) -> Result<HashSet<NaiveDate>, RuntimeError> {
+    match val {
+        Value::Array(items) => items
+            .iter()
+            .map(|item| match item {
+                Value::String(s) => parse_date(s),
+                other => Err(RuntimeError::TypeErrorDetailed {
+                    expected: "String (YYYY-MM-DD)".to_string(),
+                    got: format!("{:?}", other),
+                }),
+            })
+            .collect(),
+        Value::Null => Ok(HashSet::new()),
+        other => Err(RuntimeError::TypeErrorDetailed {
+            expected: "Array".to_string(),
+            got: format!("{:?}", other),
+        }),
+    }
+}
+
+/// 设置某一年的日历，校验所有日期都属于该年份
+fn install_year(
+    year: i32,
+    holidays: HashSet<NaiveDate>,
+    workdays: HashSet<NaiveDate>,
+) -> Result<usize, RuntimeError> {
+    if let Some(date) = holidays.iter().chain(&workdays).find(|d| d.year() != year) {
+        return Err(RuntimeError::InvalidOperation(format!(
+            "日期 {} 不属于 {} 年",
+            date.format(DATE_FORMAT),
+            year
+        )));
+    }
+
+    let count = holidays.len();
+    with_settings(|s| {
+        s.calendar.insert(year, YearCalendar { holidays, workdays });
+    });
+    Ok(count)
+}
+
+/// 从 JSON 文本加载日历，格式：
+/// `{"2024": {"holidays": ["2024-01-01", ...], "workdays": ["2024-02-04", ...]}}`
+fn load_calendar_json(json: &str, only_year: Option<i32>) -> Result<Vec<i32>, RuntimeError> {
+    let parsed: serde_json::Value = serde_json::from_str(json)
+        .map_err(|e| RuntimeError::InvalidOperation(format!("日历 JSON 解析失败: {}", e)))?;
+    let years = parsed.as_object().ok_or_else(|| {
+        RuntimeError::InvalidOperation("日历 JSON 顶层必须是以年份为键的对象".to_string())
+    })?;
+
+    let mut loaded = Vec::new();
+    for (key, entry) in years {
+        let year: i32 = key
+            .parse()
+            .map_err(|_| RuntimeError::InvalidOperation(format!("无效的年份 '{}'", key)))?;
+        if only_year.is_some_and(|y| y != year) {
+            continue;
+        }
+
+        let read_list = |field: &str| -> Result<HashSet<NaiveDate>, RuntimeError> {
+            match entry.get(field) {
+                None => Ok(HashSet::new()),
+                Some(serde_json::Value::Array(items)) => items
+                    .iter()
+                    .map(|d| match d.as_str() {
+                        Some(s) => parse_date(s),
+                        None => Err(RuntimeError::InvalidOperation(format!(
+                            "{} 年的 {} 必须是日期字符串数组",
+                            year, field
+                        ))),
+                    })
+                    .collect(),
+                Some(_) => Err(RuntimeError::InvalidOperation(format!(
+                    "{} 年的 {} 必须是数组",
+                    year, field
+                ))),
+            }
+        };
+
+        install_year(year, read_list("holidays")?, read_list("workdays")?)?;
+        loaded.push(year);
+    }
+
+    loaded.sort();
+    Ok(loaded)
+}
+
+/// 设置某一年的节假日日历
+///
+/// # 参数
+/// - 年份
+/// - 节假日日期数组（"YYYY-MM-DD"）
+/// - 调休上班日数组（可选）
+///
+/// # 返回
+/// 设置的节假日天数
+pub fn set_holiday_calendar(args: &[Value]) -> Result<Value, RuntimeError> {
+    if args.len() < 2 || args.len() > 3 {
+        return Err(RuntimeError::WrongArity {
+            expected: 2,
+            got: args.len(),
+        });
+    }
+
+    let year = match &args[0] {
+        Value::Number(n) if n.fract() == 0.0 => *n as i32,
+        other => {
+            return Err(RuntimeError::TypeErrorDetailed {
+                expected: "Number (year)".to_string(),
+                got: format!("{:?}", other),
+            });
+        }
+    };
+
+    let holidays = parse_date_list(&args[1])?;
+    let workdays = match args.get(2) {
+        Some(val) => parse_date_list(val)?,
+        None => HashSet::new(),
+    };
+
+    let count = install_year(year, holidays, workdays)?;
+    Ok(Value::Number(count as f64))
+}
+
+/// 加载节假日日历
+///
+/// # 参数
+/// - 内置日历名称（"CN"）或 JSON 文本
+/// - 年份（可选，只加载指定年份）
+///
+/// # 返回
+/// 已加载的年份数组
+pub fn load_holiday_calendar(args: &[Value]) -> Result<Value, RuntimeError> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(RuntimeError::WrongArity {
+            expected: 1,
+            got: args.len(),
+        });
+    }
+
+    let source = match &args[0] {
+        Value::String(s) => s,
+        other => {
+            return Err(RuntimeError::TypeErrorDetailed {
+                expected: "String".to_string(),
+                got: format!("{:?}", other),
+            });
+        }
+    };
+
+    let only_year = match args.get(1) {
+        Some(Value::Number(n)) => Some(*n as i32),
+        Some(Value::Null) | None => None,
+        Some(other) => {
+            return Err(RuntimeError::TypeErrorDetailed {
+                expected: "Number (year)".to_string(),
+                got: format!("{:?}", other),
+            });
+        }
+    };
+
+    let json = if source.trim_start().starts_with('{') {
+        source.as_str()
+    } else {
+        match source.to_uppercase().as_str() {
+            "CN" => CN_CALENDAR,
+            _ => {
+                return Err(RuntimeError::InvalidOperation(format!(
+                    "未知的内置日历 '{}'，可选: CN",
+                    source
+                )));
+            }
+        }
+    };
+
+    let years = load_calendar_json(json, only_year)?;
+    Ok(Value::Array(
+        years.into_iter().map(|y| Value::Number(y as f64)).collect(),
+    ))
+}
+
+/// 清空当前引擎的节假日日历
+pub fn clear_holiday_calendar(_args: &[Value]) -> Result<Value, RuntimeError> {
+    with_settings(|s| s.calendar.clear());
+    Ok(Value::Null)
+}
@@ -1,8 +1,11 @@
 // src/builtins/payroll/datetime.rs
 //! 日期时间计算函数
 
+use super::calendar::{date_is_holiday, date_is_workday, parse_date};
 use crate::evaluator::RuntimeError;
 use crate::value::Value;
+use chrono::{Datelike, NaiveDate, Weekday};
+
 /// 辅助函数：安全地获取数字参数
 fn get_number(val: &Value) -> Result<f64, RuntimeError> {
     match val {
@@ -14,6 +17,38 @@ fn get_number(val: &Value) -> Result<f64, RuntimeError> {
     }
 }
 
+/// 辅助函数：若参数为日期字符串则解析为日期
+fn as_date(val: &Value) -> Result<Option<NaiveDate>, RuntimeError> {
+    match val {
+        Value::String(s) => parse_date(s).map(Some),
+        _ => Ok(None),
+    }
+}
+
+/// 辅助函数：统计日期区间（含首尾）内满足条件的天数
+fn count_days(
+    start: NaiveDate,
+    end: NaiveDate,
+    pred: impl Fn(NaiveDate) -> bool,
+) -> Result<Value, RuntimeError> {
+    if end < start {
+        return Err(RuntimeError::InvalidOperation(
+            "结束日期不能早于起始日期".to_string(),
+        ));
+    }
+    let count = start
+        .iter_days()
+        .take_while(|d| *d <= end)
+        .filter(|d| pred(*d))
+        .count();
+    Ok(Value::Number(count as f64))
+}
+
+/// 辅助函数：布尔结果转换为 1/0
+fn flag(b: bool) -> Value {
+    Value::Number(if b { 1.0 } else { 0.0 })
+}
+
 /// 计算自然天数（包含周末和节假日）
 ///
 /// # 参数
@@ -50,6 +85,9 @@ pub fn get_legal_pay_days(_args: &[Value]) -> Result<Value, RuntimeError> {
 /// - 总天数
 /// - 周末天数
 ///
+/// 也可以传入起止日期（"YYYY-MM-DD"），此时按节假日日历统计区间内的
+/// 工作日（扣除法定节假日，计入调休上班日）。
+///
 /// # 返回
 /// 工作日天数
 pub fn calc_workdays(args: &[Value]) -> Result<Value, RuntimeError> {
@@ -60,6 +98,10 @@ pub fn calc_workdays(args: &[Value]) -> Result<Value, RuntimeError> {
         });
     }
 
+    if let (Some(start), Some(end)) = (as_date(&args[0])?, as_date(&args[1])?) {
+        return count_days(start, end, date_is_workday);
+    }
+
     let total_days = get_number(&args[0])?;
     let weekend_days = get_number(&args[1])?;
 
@@ -82,12 +124,20 @@ pub fn calc_weekend_days(args: &[Value]) -> Result<Value, RuntimeError> {
         });
     }
 
-    let total_days = get_number(&args[0])? as i32;
-    let start_weekday = get_number(&args[1])? as i32;
+    let total_days = get_number(&args[0])?;
+    let start_weekday = get_number(&args[1])?;
+    if !total_days.is_finite() || !start_weekday.is_finite() {
+        return Err(RuntimeError::InvalidOperation(
+            "天数和起始星期必须是有限数值".to_string(),
+        ));
+    }
+    let total_days = total_days.max(0.0) as i64;
+    let start_weekday = start_weekday as i64;
 
-    let mut weekend_count = 0;
-    for i in 0..total_days {
-        let weekday = ((start_weekday - 1 + i) % 7) + 1;
+    // 整周各有两天周末，只需逐日检查剩余天数
+    let mut weekend_count = total_days / 7 * 2;
+    for i in 0..total_days % 7 {
+        let weekday = (start_weekday - 1 + i).rem_euclid(7) + 1;
         if weekday == 6 || weekday == 7 {
             // 周六或周日
             weekend_count += 1;
@@ -102,6 +152,9 @@ pub fn calc_weekend_days(args: &[Value]) -> Result<Value, RuntimeError> {
 /// # 参数
 /// - 当月节假日数组（作为字符串参数传入，实际使用时需解析）
 ///
+/// 也可以传入起止日期（"YYYY-MM-DD"），此时按节假日日历统计区间内的
+/// 法定节假日天数。
+///
 /// # 返回
 /// 节假日天数
 pub fn calc_holiday_days(args: &[Value]) -> Result<Value, RuntimeError> {
@@ -112,6 +165,12 @@ pub fn calc_holiday_days(args: &[Value]) -> Result<Value, RuntimeError> {
         });
     }
 
+    if args.len() > 1
+        && let (Some(start), Some(end)) = (as_date(&args[0])?, as_date(&args[1])?)
+    {
+        return count_days(start, end, date_is_holiday);
+    }
+
     // 简化实现：直接返回传入的天数
     let holiday_days = get_number(&args[0])?;
     Ok(Value::Number(holiday_days))
@@ -123,9 +182,16 @@ pub fn calc_holiday_days(args: &[Value]) -> Result<Value, RuntimeError> {
 /// - 星期几（1=周一，7=周日）
 /// - 是否为节假日（0=否，1=是）
 ///
+/// 也可以只传入日期（"YYYY-MM-DD"），此时按节假日日历判断，
+/// 调休上班日视为工作日。
+///
 /// # 返回
 /// 1=工作日，0=非工作日
 pub fn is_workday(args: &[Value]) -> Result<Value, RuntimeError> {
+    if let Some(date) = args.first().map(as_date).transpose()?.flatten() {
+        return Ok(flag(date_is_workday(date)));
+    }
+
     if args.len() < 2 {
         return Err(RuntimeError::WrongArity {
             expected: 2,
@@ -150,7 +216,7 @@ pub fn is_workday(args: &[Value]) -> Result<Value, RuntimeError> {
 /// 判断是否为周末
 ///
 /// # 参数
-/// - 星期几（1=周一，7=周日）
+/// - 星期几（1=周一，7=周日），或日期（"YYYY-MM-DD"）
 ///
 /// # 返回
 /// 1=周末，0=非周末
@@ -162,6 +228,10 @@ pub fn is_weekend(args: &[Value]) -> Result<Value, RuntimeError> {
         });
     }
 
+    if let Some(date) = as_date(&args[0])? {
+        return Ok(flag(matches!(date.weekday(), Weekday::Sat | Weekday::Sun)));
+    }
+
     let weekday = get_number(&args[0])? as i32;
     let result = if weekday == 6 || weekday == 7 { 1 } else { 0 };
 
@@ -171,12 +241,17 @@ pub fn is_weekend(args: &[Value]) -> Result<Value, RuntimeError> {
 /// 判断是否为法定节假日
 ///
 /// # 参数
-/// - 日期（当月第几天）
-/// - 节假日列表（以数组形式传入）
+/// - 日期（"YYYY-MM-DD"），按节假日日历判断
+///
+/// 兼容旧用法：`IS_HOLIDAY(当月第几天, 节假日标记)` 直接返回节假日标记。
 ///
 /// # 返回
 /// 1=节假日，0=非节假日
 pub fn is_holiday(args: &[Value]) -> Result<Value, RuntimeError> {
+    if let Some(date) = args.first().map(as_date).transpose()?.flatten() {
+        return Ok(flag(date_is_holiday(date)));
+    }
+
     if args.len() < 2 {
         return Err(RuntimeError::WrongArity {
             expected: 2,
@@ -245,9 +320,20 @@ pub fn calc_monthly_work_hours(args: &[Value]) -> Result<Value, RuntimeError> {
 
 /// 计算年度工作天数
 ///
+/// # 参数
+/// - 年份（可选）：传入时按节假日日历统计该年的实际工作日
+///
 /// # 返回
-/// 固定返回 (365 - 104) = 261天（扣除52周的周末）
-pub fn calc_annual_workdays(_args: &[Value]) -> Result<Value, RuntimeError> {
+/// 未传年份时固定返回 (365 - 104) = 261天（扣除52周的周末）
+pub fn calc_annual_workdays(args: &[Value]) -> Result<Value, RuntimeError> {
+    if let Some(year_val) = args.first() {
+        let year = get_number(year_val)? as i32;
+        let start = NaiveDate::from_ymd_opt(year, 1, 1)
+            .ok_or_else(|| RuntimeError::InvalidOperation(format!("无效的年份 {}", year)))?;
+        let end = NaiveDate::from_ymd_opt(year, 12, 31)
+            .ok_or_else(|| RuntimeError::InvalidOperation(format!("无效的年份 {}", year)))?;
+        return count_days(start, end, date_is_workday);
+    }
     Ok(Value::Number(261.0))
 }
 
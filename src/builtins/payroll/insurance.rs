@@ -1,20 +1,11 @@
 // src/builtins/payroll/insurance.rs
 //! 社保公积金计算函数
 
+use super::money::{dec, is_exact, money_value, round_money, to_money};
+use super::{make_breakdown, split_options};
 use crate::evaluator::RuntimeError;
 use crate::value::Value;
 
-/// 辅助函数：安全地获取数字参数
-fn get_number(val: &Value) -> Result<f64, RuntimeError> {
-    match val {
-        Value::Number(n) => Ok(*n),
-        _ => Err(RuntimeError::TypeErrorDetailed {
-            expected: "Number".to_string(),
-            got: format!("{:?}", val),
-        }),
-    }
-}
-
 /// 计算养老保险
 ///
 /// # 参数
@@ -31,14 +22,14 @@ pub fn calc_pension_insurance(args: &[Value]) -> Result<Value, RuntimeError> {
         });
     }
 
-    let base = get_number(&args[0])?;
+    let base = to_money(&args[0])?;
     let rate = if args.len() > 1 {
-        get_number(&args[1])?
+        to_money(&args[1])?
     } else {
-        0.08 // 默认8%
+        dec("0.08") // 默认8%
     };
 
-    Ok(Value::Number(base * rate))
+    Ok(money_value(&(base * rate), is_exact(args)))
 }
 
 /// 计算医疗保险
@@ -57,14 +48,14 @@ pub fn calc_medical_insurance(args: &[Value]) -> Result<Value, RuntimeError> {
         });
     }
 
-    let base = get_number(&args[0])?;
+    let base = to_money(&args[0])?;
     let rate = if args.len() > 1 {
-        get_number(&args[1])?
+        to_money(&args[1])?
     } else {
-        0.02 // 默认2%
+        dec("0.02") // 默认2%
     };
 
-    Ok(Value::Number(base * rate))
+    Ok(money_value(&(base * rate), is_exact(args)))
 }
 
 /// 计算失业保险
@@ -83,14 +74,14 @@ pub fn calc_unemployment_insurance(args: &[Value]) -> Result<Value, RuntimeError
         });
     }
 
-    let base = get_number(&args[0])?;
+    let base = to_money(&args[0])?;
     let rate = if args.len() > 1 {
-        get_number(&args[1])?
+        to_money(&args[1])?
     } else {
-        0.005 // 默认0.5%
+        dec("0.005") // 默认0.5%
     };
 
-    Ok(Value::Number(base * rate))
+    Ok(money_value(&(base * rate), is_exact(args)))
 }
 
 /// 计算住房公积金
@@ -109,14 +100,14 @@ pub fn calc_housing_fund(args: &[Value]) -> Result<Value, RuntimeError> {
         });
     }
 
-    let base = get_number(&args[0])?;
+    let base = to_money(&args[0])?;
     let rate = if args.len() > 1 {
-        get_number(&args[1])?
+        to_money(&args[1])?
     } else {
-        0.12 // 默认12%
+        dec("0.12") // 默认12%
     };
 
-    Ok(Value::Number(base * rate))
+    Ok(money_value(&(base * rate), is_exact(args)))
 }
 
 /// 计算社保公积金总额（养老+医疗+失业+公积金）
@@ -127,10 +118,12 @@ pub fn calc_housing_fund(args: &[Value]) -> Result<Value, RuntimeError> {
 /// - 医疗保险比例（可选，默认0.02）
 /// - 失业保险比例（可选，默认0.005）
 /// - 公积金比例（可选，默认0.12）
+/// - 选项字典（可选，`{"breakdown": true}` 返回明细）
 ///
 /// # 返回
 /// 社保公积金总额
 pub fn calc_social_insurance(args: &[Value]) -> Result<Value, RuntimeError> {
+    let (args, breakdown) = split_options(args);
     if args.is_empty() {
         return Err(RuntimeError::WrongArity {
             expected: 1,
@@ -138,35 +131,63 @@ pub fn calc_social_insurance(args: &[Value]) -> Result<Value, RuntimeError> {
         });
     }
 
-    let base = get_number(&args[0])?;
+    let base = to_money(&args[0])?;
 
     // 使用默认比例或自定义比例
     let pension_rate = if args.len() > 1 {
-        get_number(&args[1])?
+        to_money(&args[1])?
     } else {
-        0.08 // 8%
+        dec("0.08") // 8%
     };
 
     let medical_rate = if args.len() > 2 {
-        get_number(&args[2])?
+        to_money(&args[2])?
     } else {
-        0.02 // 2%
+        dec("0.02") // 2%
     };
 
     let unemployment_rate = if args.len() > 3 {
-        get_number(&args[3])?
+        to_money(&args[3])?
     } else {
-        0.005 // 0.5%
+        dec("0.005") // 0.5%
     };
 
     let housing_rate = if args.len() > 4 {
-        get_number(&args[4])?
+        to_money(&args[4])?
     } else {
-        0.12 // 12%
+        dec("0.12") // 12%
     };
 
-    let total = base * (pension_rate + medical_rate + unemployment_rate + housing_rate);
-    Ok(Value::Number(total))
+    // 各分项先按金额规则舍入再汇总，与逐项扣款的账务处理一致
+    let pension = round_money(&(&base * &pension_rate));
+    let medical = round_money(&(&base * &medical_rate));
+    let unemployment = round_money(&(&base * &unemployment_rate));
+    let housing_fund = round_money(&(&base * &housing_rate));
+    let total = &pension + &medical + &unemployment + &housing_fund;
+
+    let exact = is_exact(args);
+    let result = money_value(&total, exact);
+    if breakdown {
+        return Ok(make_breakdown(
+            result,
+            "缴费基数 × (养老 + 医疗 + 失业 + 公积金比例)",
+            &[
+                ("base", base),
+                ("pension_rate", pension_rate),
+                ("medical_rate", medical_rate),
+                ("unemployment_rate", unemployment_rate),
+                ("housing_rate", housing_rate),
+            ],
+            &[
+                ("pension", pension),
+                ("medical", medical),
+                ("unemployment", unemployment),
+                ("housing_fund", housing_fund),
+            ],
+            exact,
+        ));
+    }
+    Ok(result)
 }
 
 /// 汇总五险一金个人缴纳总额
@@ -185,10 +206,13 @@ pub fn calc_total_insurance_fund(args: &[Value]) -> Result<Value, RuntimeError>
         });
     }
 
-    let social_insurance = get_number(&args[0])?;
-    let housing_fund = get_number(&args[1])?;
+    let social_insurance = to_money(&args[0])?;
+    let housing_fund = to_money(&args[1])?;
 
-    Ok(Value::Number(social_insurance + housing_fund))
+    Ok(money_value(
+        &(social_insurance + housing_fund),
+        is_exact(args),
+    ))
 }
 
 /// 调整社保缴费基数（限制在上下限之间）
@@ -208,9 +232,9 @@ pub fn adjust_social_base(args: &[Value]) -> Result<Value, RuntimeError> {
         });
     }
 
-    let salary = get_number(&args[0])?;
-    let lower_limit = get_number(&args[1])?;
-    let upper_limit = get_number(&args[2])?;
+    let salary = to_money(&args[0])?;
+    let lower_limit = to_money(&args[1])?;
+    let upper_limit = to_money(&args[2])?;
 
     let adjusted = if salary < lower_limit {
         lower_limit
@@ -220,7 +244,7 @@ pub fn adjust_social_base(args: &[Value]) -> Result<Value, RuntimeError> {
         salary
     };
 
-    Ok(Value::Number(adjusted))
+    Ok(money_value(&adjusted, is_exact(args)))
 }
 
 /// 计算社保基数下限
@@ -239,14 +263,14 @@ pub fn calc_social_base_lower(args: &[Value]) -> Result<Value, RuntimeError> {
         });
     }
 
-    let avg_salary = get_number(&args[0])?;
+    let avg_salary = to_money(&args[0])?;
     let ratio = if args.len() > 1 {
-        get_number(&args[1])?
+        to_money(&args[1])?
     } else {
-        0.6
+        dec("0.6")
     };
 
-    Ok(Value::Number(avg_salary * ratio))
+    Ok(money_value(&(avg_salary * ratio), is_exact(args)))
 }
 
 /// 计算社保基数上限
@@ -265,14 +289,14 @@ pub fn calc_social_base_upper(args: &[Value]) -> Result<Value, RuntimeError> {
         });
     }
 
-    let avg_salary = get_number(&args[0])?;
+    let avg_salary = to_money(&args[0])?;
     let multiplier = if args.len() > 1 {
-        get_number(&args[1])?
+        to_money(&args[1])?
     } else {
-        3.0
+        dec("3")
     };
 
-    Ok(Value::Number(avg_salary * multiplier))
+    Ok(money_value(&(avg_salary * multiplier), is_exact(args)))
 }
 
 /// 计算工伤保险（个人不缴纳，企业缴纳，此函数供完整性）
@@ -291,14 +315,14 @@ pub fn calc_injury_insurance(args: &[Value]) -> Result<Value, RuntimeError> {
         });
     }
 
-    let base = get_number(&args[0])?;
+    let base = to_money(&args[0])?;
     let rate = if args.len() > 1 {
-        get_number(&args[1])?
+        to_money(&args[1])?
     } else {
-        0.002 // 0.2%
+        dec("0.002") // 0.2%
     };
 
-    Ok(Value::Number(base * rate))
+    Ok(money_value(&(base * rate), is_exact(args)))
 }
 
 /// 计算生育保险（个人不缴纳，企业缴纳，此函数供完整性）
@@ -317,12 +341,12 @@ pub fn calc_maternity_insurance(args: &[Value]) -> Result<Value, RuntimeError> {
         });
     }
 
-    let base = get_number(&args[0])?;
+    let base = to_money(&args[0])?;
     let rate = if args.len() > 1 {
-        get_number(&args[1])?
+        to_money(&args[1])?
     } else {
-        0.008 // 0.8%
+        dec("0.008") // 0.8%
     };
 
-    Ok(Value::Number(base * rate))
+    Ok(money_value(&(base * rate), is_exact(args)))
 }
@@ -12,19 +12,27 @@
 //! - 薪资折算转换
 //! - 日期时间计算
 //! - 统计分析
+//! - 批量计算
+//!
+//! 金额类函数基于分数精确计算，并按统一规则舍入（见 [`money`]）。
 
 use crate::evaluator::RuntimeError;
 use crate::value::Value;
+use money::{Money, ratio_value};
 use std::collections::HashMap;
 
 pub mod allowance;
 pub mod attendance;
 pub mod basic;
+pub mod batch;
 pub mod bonus;
+pub mod calendar;
 pub mod conversion;
 pub mod datetime;
 pub mod insurance;
+pub mod money;
 pub mod overtime;
+pub mod settings;
 pub mod statistics;
 pub mod tax;
 
@@ -32,14 +40,62 @@ pub mod tax;
 pub use allowance::*;
 pub use attendance::*;
 pub use basic::*;
+pub use batch::*;
 pub use bonus::*;
+pub use calendar::*;
 pub use conversion::*;
 pub use datetime::*;
 pub use insurance::*;
+pub use money::*;
 pub use overtime::*;
 pub use statistics::*;
 pub use tax::*;
 
+/// 从参数末尾取出可选的选项字典
+///
+/// 薪酬函数的参数都是数字，因此末尾的字典参数可以无歧义地作为选项，
+/// 目前支持 `{"breakdown": true}`：返回结构化明细而不是单个数字。
+///
+/// # 返回
+/// (去掉选项后的参数, 是否返回明细)
+pub(crate) fn split_options(args: &[Value]) -> (&[Value], bool) {
+    match args.last() {
+        Some(Value::Dict(opts)) => {
+            let breakdown = opts.get("breakdown").is_some_and(|v| v.is_truthy());
+            (&args[..args.len() - 1], breakdown)
+        }
+        _ => (args, false),
+    }
+}
+
+/// 构造薪酬明细字典
+///
+/// 明细包含最终结果、计算公式、输入参数以及中间值，便于核对计算过程。
+/// 输入和中间值不做舍入，保留精确值。
+pub(crate) fn make_breakdown(
+    result: Value,
+    formula: &str,
+    inputs: &[(&str, Money)],
+    components: &[(&str, Money)],
+    exact: bool,
+) -> Value {
+    let to_dict = |pairs: &[(&str, Money)]| {
+        Value::Dict(
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), ratio_value(v, exact)))
+                .collect(),
+        )
+    };
+
+    let mut dict = HashMap::new();
+    dict.insert("result".to_string(), result);
+    dict.insert("formula".to_string(), Value::String(formula.to_string()));
+    dict.insert("inputs".to_string(), to_dict(inputs));
+    dict.insert("components".to_string(), to_dict(components));
+    Value::Dict(dict)
+}
+
 /// 注册所有薪酬计算函数
 #[allow(clippy::type_complexity)]
 pub fn register_payroll_functions() -> HashMap<String, fn(&[Value]) -> Result<Value, RuntimeError>>
@@ -352,6 +408,20 @@ pub fn register_payroll_functions() -> HashMap<String, fn(&[Value]) -> Result<Va
         datetime::calc_annual_pay_days as fn(&[Value]) -> Result<Value, RuntimeError>,
     );
 
+    // 节假日日历 (3个)
+    functions.insert(
+        "SET_HOLIDAY_CALENDAR".to_string(),
+        calendar::set_holiday_calendar as fn(&[Value]) -> Result<Value, RuntimeError>,
+    );
+    functions.insert(
+        "LOAD_HOLIDAY_CALENDAR".to_string(),
+        calendar::load_holiday_calendar as fn(&[Value]) -> Result<Value, RuntimeError>,
+    );
+    functions.insert(
+        "CLEAR_HOLIDAY_CALENDAR".to_string(),
+        calendar::clear_holiday_calendar as fn(&[Value]) -> Result<Value, RuntimeError>,
+    );
+
     // 统计分析 (6个)
     functions.insert(
         "CALC_SALARY_AVERAGE".to_string(),
@@ -378,5 +448,17 @@ pub fn register_payroll_functions() -> HashMap<String, fn(&[Value]) -> Result<Va
         statistics::calc_salary_distribution as fn(&[Value]) -> Result<Value, RuntimeError>,
     );
 
+    // 金额舍入 (1个)
+    functions.insert(
+        "SET_PAYROLL_ROUNDING".to_string(),
+        money::set_payroll_rounding as fn(&[Value]) -> Result<Value, RuntimeError>,
+    );
+
+    // 批量计算 (1个)
+    functions.insert(
+        "PAYROLL_RUN".to_string(),
+        batch::payroll_run as fn(&[Value]) -> Result<Value, RuntimeError>,
+    );
+
     functions
 }
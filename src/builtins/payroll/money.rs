@@ -0,0 +1,416 @@
+// src/builtins/payroll/money.rs
+//! 薪酬金额的精确计算与舍入
+//!
+//! 薪酬函数内部使用 `Ratio<BigInt>` 精确计算，只在产出金额时按统一的
+//! 舍入规则（默认保留2位小数、四舍五入）舍入一次，避免 f64 在多个分项
+//! 之间累积误差。舍入规则按引擎保存（见 [`super::settings`]）。
+//!
+//! - 参数中包含 Fraction 时，结果以 Fraction 返回，可以继续精确计算
+//! - 否则结果以 Number 返回（数值等于舍入后的精确金额）
+
+use super::settings::with_settings;
+use crate::evaluator::RuntimeError;
+use crate::value::Value;
+use num_bigint::BigInt;
+use num_rational::Ratio;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+
+/// 精确金额类型
+pub(crate) type Money = Ratio<BigInt>;
+
+/// 金额舍入模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoundingMode {
+    /// 四舍五入（0.5 远离零舍入），会计默认规则
+    HalfUp,
+    /// 银行家舍入（0.5 舍入到偶数）
+    HalfEven,
+    /// 直接截断（向零舍入）
+    Down,
+    /// 只要有余数就进位（远离零舍入）
+    Up,
+}
+
+impl RoundingMode {
+    fn parse(name: &str) -> Option<Self> {
+        match name.to_uppercase().as_str() {
+            "HALF_UP" => Some(RoundingMode::HalfUp),
+            "HALF_EVEN" => Some(RoundingMode::HalfEven),
+            "DOWN" => Some(RoundingMode::Down),
+            "UP" => Some(RoundingMode::Up),
+            _ => None,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            RoundingMode::HalfUp => "HALF_UP",
+            RoundingMode::HalfEven => "HALF_EVEN",
+            RoundingMode::Down => "DOWN",
+            RoundingMode::Up => "UP",
+        }
+    }
+}
+
+/// 获取当前引擎的金额舍入规则
+pub fn payroll_rounding() -> (u32, RoundingMode) {
+    with_settings(|s| s.rounding)
+}
+
+/// 解析十进制字符串为精确分数（如 "21.75" → 87/4）
+pub(crate) fn dec(s: &str) -> Money {
+    let (negative, digits) = match s.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, s),
+    };
+    let (int_part, frac_part) = digits.split_once('.').unwrap_or((digits, ""));
+    let numer: BigInt = format!("{}{}", int_part, frac_part)
+        .parse()
+        .unwrap_or_else(|_| BigInt::zero());
+    let denom = num_traits::pow(BigInt::from(10), frac_part.len());
+    let value = Money::new(numer, denom);
+    if negative { -value } else { value }
+}
+
+/// 将参数转换为精确金额
+///
+/// Number 按其十进制表示精确转换（0.08 → 8/100，而不是 f64 的二进制近似值）。
+pub(crate) fn to_money(val: &Value) -> Result<Money, RuntimeError> {
+    match val {
+        Value::Fraction(f) => Ok(f.clone()),
+        Value::Number(n) if n.is_finite() => Ok(dec(&format!("{}", n))),
+        Value::Number(n) => Err(RuntimeError::InvalidOperation(format!(
+            "金额必须是有限数值，得到 {}",
+            n
+        ))),
+        _ => Err(RuntimeError::TypeErrorDetailed {
+            expected: "Number or Fraction".to_string(),
+            got: format!("{:?}", val),
+        }),
+    }
+}
+
+/// 按当前舍入规则舍入金额
+pub(crate) fn round_money(m: &Money) -> Money {
+    let (places, mode) = payroll_rounding();
+    round_with(m, places, mode)
+}
+
+/// 按指定的小数位数和舍入模式舍入金额
+pub(crate) fn round_with(m: &Money, places: u32, mode: RoundingMode) -> Money {
+    let scale = Money::from_integer(num_traits::pow(BigInt::from(10), places as usize));
+    let scaled = m * &scale;
+    let rounded = match mode {
+        RoundingMode::HalfUp => scaled.round(),
+        RoundingMode::Down => scaled.trunc(),
+        RoundingMode::Up => {
+            if scaled.is_integer() {
+                scaled
+            } else if scaled.is_negative() {
+                scaled.floor()
+            } else {
+                scaled.ceil()
+            }
+        }
+        RoundingMode::HalfEven => {
+            let floor = scaled.floor();
+            let diff = &scaled - &floor;
+            let half = Money::new(BigInt::one(), BigInt::from(2));
+            let floor_is_odd = !(floor.to_integer() % BigInt::from(2)).is_zero();
+            if diff > half || (diff == half && floor_is_odd) {
+                floor + Money::one()
+            } else {
+                floor
+            }
+        }
+    };
+    rounded / scale
+}
+
+/// 精确金额转换为 f64
+pub(crate) fn money_to_f64(m: &Money) -> f64 {
+    m.to_f64().unwrap_or(f64::NAN)
+}
+
+/// 参数中是否包含 Fraction（决定结果是否以 Fraction 返回）
+pub(crate) fn is_exact(args: &[Value]) -> bool {
+    args.iter().any(|v| matches!(v, Value::Fraction(_)))
+}
+
+/// 舍入后的金额结果
+pub(crate) fn money_value(m: &Money, exact: bool) -> Value {
+    let rounded = round_money(m);
+    if exact {
+        Value::Fraction(rounded)
+    } else {
+        Value::Number(money_to_f64(&rounded))
+    }
+}
+
+/// 不舍入的比率/数量结果（如出勤率、税率）
+pub(crate) fn ratio_value(m: &Money, exact: bool) -> Value {
+    if exact {
+        Value::Fraction(m.clone())
+    } else {
+        Value::Number(money_to_f64(m))
+    }
+}
+
+/// 设置当前引擎的薪酬金额舍入规则
+///
+/// # 参数
+/// - 小数位数（0-10，默认2即精确到分）
+/// - 舍入模式（可选）："HALF_UP"（默认）、"HALF_EVEN"、"DOWN"、"UP"
+///
+/// # 返回
+/// 之前的设置 `{"places": ..., "mode": ...}`
+pub fn set_payroll_rounding(args: &[Value]) -> Result<Value, RuntimeError> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(RuntimeError::WrongArity {
+            expected: 1,
+            got: args.len(),
+        });
+    }
+
+    let places = match &args[0] {
+        Value::Number(n) if n.fract() == 0.0 && (0.0..=10.0).contains(n) => *n as u32,
+        other => {
+            return Err(RuntimeError::InvalidOperation(format!(
+                "小数位数必须是0到10之间的整数，得到 {}",
+                other
+            )));
+        }
+    };
+
+    let (old_places, old_mode) = payroll_rounding();
+    let mode = match args.get(1) {
+        None => old_mode,
+        Some(Value::String(s)) => RoundingMode::parse(s).ok_or_else(|| {
+            RuntimeError::InvalidOperation(format!(
+                "未知的舍入模式 '{}'，可选 HALF_UP、HALF_EVEN、DOWN、UP",
+                s
+            ))
+        })?,
+        Some(other) => {
+            return Err(RuntimeError::TypeErrorDetailed {
+                expected: "String".to_string(),
+                got: format!("{:?}", other),
+            });
+        }
+    };
+
+    with_settings(|s| s.rounding = (places, mode));
+
+    let mut previous = std::collections::HashMap::new();
+    previous.insert("places".to_string(), Value::Number(old_places as f64));
+    previous.insert(
+        "mode".to_string(),
+        Value::String(old_mode.name().to_string()),
+    );
+    Ok(Value::Dict(previous))
+}
+
+/// 解析小数位数参数（0-10 的整数，缺省为 `default`）
+fn places_arg(arg: Option<&Value>, default: u32) -> Result<u32, RuntimeError> {
+    match arg {
+        None => Ok(default),
+        Some(Value::Number(n)) if n.fract() == 0.0 && (0.0..=10.0).contains(n) => Ok(*n as u32),
+        Some(other) => Err(RuntimeError::InvalidOperation(format!(
+            "小数位数必须是0到10之间的整数，得到 {}",
+            other
+        ))),
+    }
+}
+
+/// 按指定模式舍入：ROUND_xxx(value, places?)
+fn round_mode(args: &[Value], mode: RoundingMode) -> Result<Value, RuntimeError> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(RuntimeError::WrongArity {
+            expected: 1,
+            got: args.len(),
+        });
+    }
+    let value = to_money(&args[0])?;
+    let places = places_arg(args.get(1), 0)?;
+    let rounded = round_with(&value, places, mode);
+    Ok(ratio_value(&rounded, is_exact(&args[..1])))
+}
+
+/// 银行家舍入到指定小数位数
+///
+/// # 功能
+/// 按十进制精确舍入，恰好位于中间时舍入到偶数（0.125 → 0.12，0.135 → 0.14）。
+/// Number 按其十进制表示参与计算，不受 f64 二进制误差影响。
+///
+/// # 参数
+/// - `value`: Number | Fraction - 输入值
+/// - `places`: Number - 小数位数（0-10，可选，默认0）
+///
+/// # 返回值
+/// 与输入类型一致：Fraction 输入返回 Fraction，否则返回 Number
+///
+/// # 示例
+/// ```aether
+/// Set a ROUND_HALF_EVEN(2.5)            # 2
+/// Set b ROUND_HALF_EVEN(0.125, 2)       # 0.12
+/// Set c ROUND_HALF_EVEN(0.135, 2)       # 0.14
+/// ```
+pub fn round_half_even(args: &[Value]) -> Result<Value, RuntimeError> {
+    round_mode(args, RoundingMode::HalfEven)
+}
+
+/// 四舍五入到指定小数位数
+///
+/// # 功能
+/// 按十进制精确舍入，恰好位于中间时远离零舍入。
+/// 与 ROUND_TO 不同，`1.005` 会正确地舍入为 `1.01`。
+///
+/// # 参数
+/// - `value`: Number | Fraction - 输入值
+/// - `places`: Number - 小数位数（0-10，可选，默认0）
+///
+/// # 返回值
+/// 与输入类型一致：Fraction 输入返回 Fraction，否则返回 Number
+///
+/// # 示例
+/// ```aether
+/// Set a ROUND_HALF_UP(1.005, 2)         # 1.01
+/// Set b ROUND_HALF_UP(-2.5)             # -3
+/// ```
+pub fn round_half_up(args: &[Value]) -> Result<Value, RuntimeError> {
+    round_mode(args, RoundingMode::HalfUp)
+}
+
+/// 向零截断到指定小数位数
+///
+/// # 参数
+/// - `value`: Number | Fraction - 输入值
+/// - `places`: Number - 小数位数（0-10，可选，默认0）
+///
+/// # 返回值
+/// 与输入类型一致：Fraction 输入返回 Fraction，否则返回 Number
+///
+/// # 示例
+/// ```aether
+/// Set a ROUND_DOWN(3.789, 2)            # 3.78
+/// Set b ROUND_DOWN(-3.789, 2)           # -3.78
+/// ```
+pub fn round_down(args: &[Value]) -> Result<Value, RuntimeError> {
+    round_mode(args, RoundingMode::Down)
+}
+
+/// 远离零进位到指定小数位数
+///
+/// # 参数
+/// - `value`: Number | Fraction - 输入值
+/// - `places`: Number - 小数位数（0-10，可选，默认0）
+///
+/// # 返回值
+/// 与输入类型一致：Fraction 输入返回 Fraction，否则返回 Number
+///
+/// # 示例
+/// ```aether
+/// Set a ROUND_UP(3.781, 2)              # 3.79
+/// Set b ROUND_UP(-3.781, 2)             # -3.79
+/// ```
+pub fn round_up(args: &[Value]) -> Result<Value, RuntimeError> {
+    round_mode(args, RoundingMode::Up)
+}
+
+/// 按比例分摊金额，不丢失零头
+///
+/// # 功能
+/// 将总额按比例拆分为若干份，每份精确到指定小数位数，且各份之和严格等于总额。
+/// 先按比例向零截断，再把剩余的最小单位（如 1 分）依次分给截断余数最大的份额
+/// （余数相同时靠前的优先）。
+///
+/// # 参数
+/// - `total`: Number | Fraction - 总额（可以为负数，小数位数不能超过 `places`）
+/// - `ratios`: Array - 非负比例，总和必须大于0
+/// - `places`: Number - 小数位数（0-10，可选，默认2即精确到分）
+///
+/// # 返回值
+/// Array - 各份金额；总额为 Fraction 时元素为 Fraction，否则为 Number
+///
+/// # 示例
+/// ```aether
+/// Set a ALLOCATE(100, [1, 1, 1])        # [33.34, 33.33, 33.33]
+/// Set b ALLOCATE(0.05, [3, 7])          # [0.02, 0.03]
+/// Set c ALLOCATE(10, [1, 2], 0)         # [3, 7]
+/// ```
+pub fn allocate(args: &[Value]) -> Result<Value, RuntimeError> {
+    if args.len() < 2 || args.len() > 3 {
+        return Err(RuntimeError::WrongArity {
+            expected: 2,
+            got: args.len(),
+        });
+    }
+    let total = to_money(&args[0])?;
+    let ratios = match &args[1] {
+        Value::Array(items) => items.iter().map(to_money).collect::<Result<Vec<_>, _>>()?,
+        other => {
+            return Err(RuntimeError::TypeErrorDetailed {
+                expected: "Array".to_string(),
+                got: format!("{:?}", other),
+            });
+        }
+    };
+    let places = places_arg(args.get(2), 2)?;
+
+    if ratios.is_empty() {
+        return Err(RuntimeError::InvalidOperation(
+            "ALLOCATE 的比例列表不能为空".to_string(),
+        ));
+    }
+    if ratios.iter().any(|r| r.is_negative()) {
+        return Err(RuntimeError::InvalidOperation(
+            "ALLOCATE 的比例不能为负数".to_string(),
+        ));
+    }
+    let ratio_sum: Money = ratios.iter().cloned().sum();
+    if ratio_sum.is_zero() {
+        return Err(RuntimeError::InvalidOperation(
+            "ALLOCATE 的比例之和必须大于0".to_string(),
+        ));
+    }
+
+    // 以最小单位（如分）为整数计算，负数总额按绝对值分摊后再取反
+    let scale = Money::from_integer(num_traits::pow(BigInt::from(10), places as usize));
+    let scaled = total.abs() * &scale;
+    if !scaled.is_integer() {
+        return Err(RuntimeError::InvalidOperation(format!(
+            "ALLOCATE 的总额 {} 超出 {} 位小数精度",
+            args[0], places
+        )));
+    }
+    let units = scaled.to_integer();
+    let mut shares = Vec::with_capacity(ratios.len());
+    let mut remainders = Vec::with_capacity(ratios.len());
+    for ratio in &ratios {
+        let exact = Money::from_integer(units.clone()) * ratio / &ratio_sum;
+        let floor = exact.floor();
+        remainders.push(&exact - &floor);
+        shares.push(floor.to_integer());
+    }
+
+    // 截断造成的缺口小于份数，且只会分给余数大于0的份额
+    let allocated: BigInt = shares.iter().sum();
+    let leftover = (&units - allocated).to_usize().unwrap_or(0);
+    let mut order: Vec<usize> = (0..ratios.len()).collect();
+    order.sort_by(|&a, &b| remainders[b].cmp(&remainders[a]));
+    for &index in order.iter().take(leftover) {
+        shares[index] += BigInt::one();
+    }
+
+    let exact = is_exact(&args[..1]);
+    let sign = if total.is_negative() {
+        -Money::one()
+    } else {
+        Money::one()
+    };
+    let parts = shares
+        .into_iter()
+        .map(|share| ratio_value(&(Money::from_integer(share) / &scale * &sign), exact))
+        .collect();
+    Ok(Value::Array(parts))
+}
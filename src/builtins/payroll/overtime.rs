@@ -1,19 +1,10 @@
 // src/builtins/payroll/overtime.rs
 //! 加班费计算函数
 
+use super::money::{Money, dec, is_exact, money_value, to_money};
 use crate::evaluator::RuntimeError;
 use crate::value::Value;
-
-/// 辅助函数：安全地获取数字参数
-fn get_number(val: &Value) -> Result<f64, RuntimeError> {
-    match val {
-        Value::Number(n) => Ok(*n),
-        _ => Err(RuntimeError::TypeErrorDetailed {
-            expected: "Number".to_string(),
-            got: format!("{:?}", val),
-        }),
-    }
-}
+use num_traits::Zero;
 
 /// 计算加班费（通用）
 ///
@@ -32,17 +23,20 @@ pub fn calc_overtime_pay(args: &[Value]) -> Result<Value, RuntimeError> {
         });
     }
 
-    let hourly_rate = get_number(&args[0])?;
-    let hours = get_number(&args[1])?;
-    let multiplier = get_number(&args[2])?;
+    let hourly_rate = to_money(&args[0])?;
+    let hours = to_money(&args[1])?;
+    let multiplier = to_money(&args[2])?;
 
-    if hours < 0.0 {
+    if hours < Money::zero() {
         return Err(RuntimeError::InvalidOperation(
             "加班小时数不能为负数".to_string(),
         ));
     }
 
-    Ok(Value::Number(hourly_rate * hours * multiplier))
+    Ok(money_value(
+        &(hourly_rate * hours * multiplier),
+        is_exact(args),
+    ))
 }
 
 /// 计算平日加班费（1.5倍）
@@ -61,20 +55,23 @@ pub fn calc_weekday_overtime(args: &[Value]) -> Result<Value, RuntimeError> {
         });
     }
 
-    let monthly_salary = get_number(&args[0])?;
-    let hours = get_number(&args[1])?;
+    let monthly_salary = to_money(&args[0])?;
+    let hours = to_money(&args[1])?;
 
-    if hours < 0.0 {
+    if hours < Money::zero() {
         return Err(RuntimeError::InvalidOperation(
             "加班小时数不能为负数".to_string(),
         ));
     }
 
     // 计算时薪：月薪 ÷ 21.75天 ÷ 8小时
-    let hourly_rate = monthly_salary / 21.75 / 8.0;
+    let hourly_rate = monthly_salary / dec("21.75") / dec("8");
 
     // 平日加班费为1.5倍
-    Ok(Value::Number(hourly_rate * hours * 1.5))
+    Ok(money_value(
+        &(hourly_rate * hours * dec("1.5")),
+        is_exact(args),
+    ))
 }
 
 /// 计算周末加班费（2倍）
@@ -93,20 +90,23 @@ pub fn calc_weekend_overtime(args: &[Value]) -> Result<Value, RuntimeError> {
         });
     }
 
-    let monthly_salary = get_number(&args[0])?;
-    let hours = get_number(&args[1])?;
+    let monthly_salary = to_money(&args[0])?;
+    let hours = to_money(&args[1])?;
 
-    if hours < 0.0 {
+    if hours < Money::zero() {
         return Err(RuntimeError::InvalidOperation(
             "加班小时数不能为负数".to_string(),
         ));
     }
 
     // 计算时薪：月薪 ÷ 21.75天 ÷ 8小时
-    let hourly_rate = monthly_salary / 21.75 / 8.0;
+    let hourly_rate = monthly_salary / dec("21.75") / dec("8");
 
     // 周末加班费为2倍
-    Ok(Value::Number(hourly_rate * hours * 2.0))
+    Ok(money_value(
+        &(hourly_rate * hours * dec("2")),
+        is_exact(args),
+    ))
 }
 
 /// 计算法定节假日加班费（3倍）
@@ -125,20 +125,23 @@ pub fn calc_holiday_overtime(args: &[Value]) -> Result<Value, RuntimeError> {
         });
     }
 
-    let monthly_salary = get_number(&args[0])?;
-    let hours = get_number(&args[1])?;
+    let monthly_salary = to_money(&args[0])?;
+    let hours = to_money(&args[1])?;
 
-    if hours < 0.0 {
+    if hours < Money::zero() {
         return Err(RuntimeError::InvalidOperation(
             "加班小时数不能为负数".to_string(),
         ));
     }
 
     // 计算时薪：月薪 ÷ 21.75天 ÷ 8小时
-    let hourly_rate = monthly_salary / 21.75 / 8.0;
+    let hourly_rate = monthly_salary / dec("21.75") / dec("8");
 
     // 法定节假日加班费为3倍
-    Ok(Value::Number(hourly_rate * hours * 3.0))
+    Ok(money_value(
+        &(hourly_rate * hours * dec("3")),
+        is_exact(args),
+    ))
 }
 
 /// 汇总各类加班费
@@ -158,9 +161,9 @@ pub fn calc_total_overtime(args: &[Value]) -> Result<Value, RuntimeError> {
         });
     }
 
-    let weekday = get_number(&args[0])?;
-    let weekend = get_number(&args[1])?;
-    let holiday = get_number(&args[2])?;
+    let weekday = to_money(&args[0])?;
+    let weekend = to_money(&args[1])?;
+    let holiday = to_money(&args[2])?;
 
-    Ok(Value::Number(weekday + weekend + holiday))
+    Ok(money_value(&(weekday + weekend + holiday), is_exact(args)))
 }
@@ -0,0 +1,85 @@
+// src/builtins/payroll/settings.rs
+//! 引擎级的薪酬设置
+//!
+//! 金额舍入规则和节假日日历属于各自的引擎，脚本调用 `SET_PAYROLL_ROUNDING`、
+//! `LOAD_HOLIDAY_CALENDAR` 等函数只修改当前引擎的设置，不影响同一线程上的
+//! 其他引擎。求值期间设置通过线程局部存储传递给薪酬函数，作用域结束时
+//! 恢复（与 `ScopedSecrets` 相同）；不在求值中调用薪酬函数时使用默认设置。
+
+use super::calendar::YearCalendar;
+use super::money::RoundingMode;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// 一个引擎的薪酬设置
+#[derive(Debug, Clone, PartialEq)]
+pub struct PayrollSettings {
+    /// 金额舍入规则：(小数位数, 舍入模式)
+    pub rounding: (u32, RoundingMode),
+    /// 节假日日历（年份 -> 数据）
+    pub calendar: HashMap<i32, YearCalendar>,
+}
+
+impl Default for PayrollSettings {
+    fn default() -> Self {
+        Self {
+            rounding: (2, RoundingMode::HalfUp),
+            calendar: HashMap::new(),
+        }
+    }
+}
+
+impl PayrollSettings {
+    /// 稳定的文本表示，`eval_pure` 把它加入缓存键，设置改变后不会返回旧结果
+    pub(crate) fn fingerprint(&self) -> String {
+        let mut years: Vec<_> = self.calendar.iter().collect();
+        years.sort_by_key(|(year, _)| **year);
+        let calendar: Vec<String> = years
+            .into_iter()
+            .map(|(year, calendar)| format!("{}={}", year, calendar.fingerprint()))
+            .collect();
+        format!(
+            "rounding={}:{:?};calendar={}",
+            self.rounding.0,
+            self.rounding.1,
+            calendar.join("|")
+        )
+    }
+}
+
+/// 引擎持有的薪酬设置
+pub(crate) type SharedPayrollSettings = Rc<RefCell<PayrollSettings>>;
+
+// 线程局部的当前引擎设置（求值期间有效）
+thread_local! {
+    static ACTIVE_SETTINGS: RefCell<Option<SharedPayrollSettings>> = const { RefCell::new(None) };
+}
+
+/// 在作用域内设置当前引擎的薪酬设置（RAII 模式，结束时恢复之前的设置）
+pub(crate) struct ScopedPayrollSettings {
+    previous: Option<SharedPayrollSettings>,
+}
+
+impl ScopedPayrollSettings {
+    pub(crate) fn set(settings: SharedPayrollSettings) -> Self {
+        let previous = ACTIVE_SETTINGS.with(|s| s.borrow_mut().replace(settings));
+        Self { previous }
+    }
+}
+
+impl Drop for ScopedPayrollSettings {
+    fn drop(&mut self) {
+        ACTIVE_SETTINGS.with(|s| *s.borrow_mut() = self.previous.take());
+    }
+}
+
+/// 读取或修改当前引擎的薪酬设置
+///
+/// 不在求值中时作用于一份临时的默认设置，修改不会保留。
+pub(crate) fn with_settings<R>(f: impl FnOnce(&mut PayrollSettings) -> R) -> R {
+    match ACTIVE_SETTINGS.with(|s| s.borrow().clone()) {
+        Some(settings) => f(&mut settings.borrow_mut()),
+        None => f(&mut PayrollSettings::default()),
+    }
+}
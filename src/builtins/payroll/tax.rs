@@ -1,18 +1,43 @@
 // src/builtins/payroll/tax.rs
 //! 个人所得税计算函数
 
+use super::money::{Money, dec, is_exact, money_to_f64, money_value, ratio_value, to_money};
+use super::{make_breakdown, split_options};
 use crate::evaluator::RuntimeError;
 use crate::value::Value;
-
-/// 辅助函数：安全地获取数字参数
-fn get_number(val: &Value) -> Result<f64, RuntimeError> {
-    match val {
-        Value::Number(n) => Ok(*n),
-        _ => Err(RuntimeError::TypeErrorDetailed {
-            expected: "Number".to_string(),
-            got: format!("{:?}", val),
-        }),
-    }
+use num_traits::Zero;
+
+/// 综合所得年度税率表：(累计应纳税所得额上限, 税率, 速算扣除数)
+const ANNUAL_BRACKETS: [(&str, &str, &str); 6] = [
+    ("36000", "0.03", "0"),
+    ("144000", "0.10", "2520"),
+    ("300000", "0.20", "16920"),
+    ("420000", "0.25", "31920"),
+    ("660000", "0.30", "52920"),
+    ("960000", "0.35", "85920"),
+];
+
+/// 月度税率表（年终奖单独计税使用）
+const MONTHLY_BRACKETS: [(&str, &str, &str); 6] = [
+    ("3000", "0.03", "0"),
+    ("12000", "0.10", "210"),
+    ("25000", "0.20", "1410"),
+    ("35000", "0.25", "2660"),
+    ("55000", "0.30", "4410"),
+    ("80000", "0.35", "7160"),
+];
+
+/// 查找适用税率和速算扣除数（超过最高档时使用 45% 档）
+fn find_bracket(
+    amount: &Money,
+    brackets: &[(&str, &str, &str)],
+    top_deduction: &str,
+) -> (Money, Money) {
+    brackets
+        .iter()
+        .find(|(limit, _, _)| *amount <= dec(limit))
+        .map(|(_, rate, deduction)| (dec(rate), dec(deduction)))
+        .unwrap_or_else(|| (dec("0.45"), dec(top_deduction)))
 }
 
 /// 中国个人所得税税率表（2019年起）
@@ -29,10 +54,12 @@ fn get_number(val: &Value) -> Result<f64, RuntimeError> {
 ///
 /// # 参数
 /// - 应纳税所得额（累计）
+/// - 选项字典（可选，`{"breakdown": true}` 返回明细）
 ///
 /// # 返回
 /// 个人所得税
 pub fn calc_personal_tax(args: &[Value]) -> Result<Value, RuntimeError> {
+    let (args, breakdown) = split_options(args);
     if args.is_empty() {
         return Err(RuntimeError::WrongArity {
             expected: 1,
@@ -40,29 +67,27 @@ pub fn calc_personal_tax(args: &[Value]) -> Result<Value, RuntimeError> {
         });
     }
 
-    let taxable_income = get_number(&args[0])?;
-
-    if taxable_income <= 0.0 {
-        return Ok(Value::Number(0.0));
-    }
+    let taxable_income = to_money(&args[0])?;
 
-    let tax = if taxable_income <= 36000.0 {
-        taxable_income * 0.03
-    } else if taxable_income <= 144000.0 {
-        taxable_income * 0.10 - 2520.0
-    } else if taxable_income <= 300000.0 {
-        taxable_income * 0.20 - 16920.0
-    } else if taxable_income <= 420000.0 {
-        taxable_income * 0.25 - 31920.0
-    } else if taxable_income <= 660000.0 {
-        taxable_income * 0.30 - 52920.0
-    } else if taxable_income <= 960000.0 {
-        taxable_income * 0.35 - 85920.0
+    let (rate, quick_deduction) = if taxable_income <= Money::zero() {
+        (Money::zero(), Money::zero())
     } else {
-        taxable_income * 0.45 - 181920.0
+        find_bracket(&taxable_income, &ANNUAL_BRACKETS, "181920")
     };
 
-    Ok(Value::Number(tax.max(0.0)))
+    let exact = is_exact(args);
+    let tax = (&taxable_income * &rate - &quick_deduction).max(Money::zero());
+    let result = money_value(&tax, exact);
+    if breakdown {
+        return Ok(make_breakdown(
+            result,
+            "应纳税所得额 × 税率 - 速算扣除数",
+            &[("taxable_income", taxable_income)],
+            &[("rate", rate), ("quick_deduction", quick_deduction)],
+            exact,
+        ));
+    }
+    Ok(result)
 }
 
 /// 计算应纳税所得额
@@ -72,10 +97,12 @@ pub fn calc_personal_tax(args: &[Value]) -> Result<Value, RuntimeError> {
 /// - 社保
 /// - 公积金
 /// - 专项附加扣除（默认0）
+/// - 选项字典（可选，`{"breakdown": true}` 返回明细）
 ///
 /// # 返回
 /// 应纳税所得额
 pub fn calc_taxable_income(args: &[Value]) -> Result<Value, RuntimeError> {
+    let (args, breakdown) = split_options(args);
     if args.len() < 3 {
         return Err(RuntimeError::WrongArity {
             expected: 3,
@@ -83,19 +110,37 @@ pub fn calc_taxable_income(args: &[Value]) -> Result<Value, RuntimeError> {
         });
     }
 
-    let gross_salary = get_number(&args[0])?;
-    let social_insurance = get_number(&args[1])?;
-    let housing_fund = get_number(&args[2])?;
+    let gross_salary = to_money(&args[0])?;
+    let social_insurance = to_money(&args[1])?;
+    let housing_fund = to_money(&args[2])?;
     let special_deduction = if args.len() > 3 {
-        get_number(&args[3])?
+        to_money(&args[3])?
     } else {
-        0.0
+        Money::zero()
     };
 
     // 应纳税所得额 = 应发工资 - 社保 - 公积金 - 起征点(5000) - 专项附加扣除
-    let taxable = gross_salary - social_insurance - housing_fund - 5000.0 - special_deduction;
-
-    Ok(Value::Number(taxable.max(0.0)))
+    let threshold = dec("5000");
+    let taxable =
+        &gross_salary - &social_insurance - &housing_fund - &threshold - &special_deduction;
+
+    let exact = is_exact(args);
+    let result = money_value(&taxable.clone().max(Money::zero()), exact);
+    if breakdown {
+        return Ok(make_breakdown(
+            result,
+            "应发工资 - 社保 - 公积金 - 起征点 - 专项附加扣除（不低于0）",
+            &[
+                ("gross_salary", gross_salary),
+                ("social_insurance", social_insurance),
+                ("housing_fund", housing_fund),
+                ("special_deduction", special_deduction),
+            ],
+            &[("threshold", threshold), ("raw_taxable", taxable)],
+            exact,
+        ));
+    }
+    Ok(result)
 }
 
 /// 计算年终奖个税（单独计税）
@@ -113,33 +158,19 @@ pub fn calc_annual_bonus_tax(args: &[Value]) -> Result<Value, RuntimeError> {
         });
     }
 
-    let bonus = get_number(&args[0])?;
+    let bonus = to_money(&args[0])?;
+    let exact = is_exact(args);
 
-    if bonus <= 0.0 {
-        return Ok(Value::Number(0.0));
+    if bonus <= Money::zero() {
+        return Ok(money_value(&Money::zero(), exact));
     }
 
     // 年终奖除以12，找到适用税率
-    let monthly_avg = bonus / 12.0;
-
-    let (rate, deduction) = if monthly_avg <= 3000.0 {
-        (0.03, 0.0)
-    } else if monthly_avg <= 12000.0 {
-        (0.10, 210.0)
-    } else if monthly_avg <= 25000.0 {
-        (0.20, 1410.0)
-    } else if monthly_avg <= 35000.0 {
-        (0.25, 2660.0)
-    } else if monthly_avg <= 55000.0 {
-        (0.30, 4410.0)
-    } else if monthly_avg <= 80000.0 {
-        (0.35, 7160.0)
-    } else {
-        (0.45, 15160.0)
-    };
+    let monthly_avg = &bonus / dec("12");
+    let (rate, deduction) = find_bracket(&monthly_avg, &MONTHLY_BRACKETS, "15160");
 
-    let tax = bonus * rate - deduction;
-    Ok(Value::Number(tax.max(0.0)))
+    let tax = (bonus * rate - deduction).max(Money::zero());
+    Ok(money_value(&tax, exact))
 }
 
 /// 计算实际税率
@@ -158,17 +189,18 @@ pub fn calc_effective_tax_rate(args: &[Value]) -> Result<Value, RuntimeError> {
         });
     }
 
-    let tax = get_number(&args[0])?;
-    let total_income = get_number(&args[1])?;
+    let tax = to_money(&args[0])?;
+    let total_income = to_money(&args[1])?;
 
-    if total_income <= 0.0 {
+    if total_income <= Money::zero() {
         return Err(RuntimeError::InvalidOperation(
             "总收入必须大于0".to_string(),
         ));
     }
 
-    let rate = (tax / total_income) * 100.0;
-    Ok(Value::Number(rate))
+    // 税率不是金额，不做舍入
+    let rate = (tax / total_income) * dec("100");
+    Ok(ratio_value(&rate, is_exact(args)))
 }
 
 /// 从税后工资反推应发工资（简化版）
@@ -188,11 +220,11 @@ pub fn calc_gross_from_net(args: &[Value]) -> Result<Value, RuntimeError> {
         });
     }
 
-    let net_salary = get_number(&args[0])?;
-    let social_insurance = get_number(&args[1])?;
-    let housing_fund = get_number(&args[2])?;
+    let net_salary = money_to_f64(&to_money(&args[0])?);
+    let social_insurance = money_to_f64(&to_money(&args[1])?);
+    let housing_fund = money_to_f64(&to_money(&args[2])?);
 
-    // 简化算法：迭代逼近
+    // 简化算法：迭代逼近（近似值，最后按金额规则舍入）
     let mut gross = net_salary + social_insurance + housing_fund + 1000.0;
 
     for _ in 0..10 {
@@ -219,7 +251,10 @@ pub fn calc_gross_from_net(args: &[Value]) -> Result<Value, RuntimeError> {
         gross += diff * 0.5;
     }
 
-    Ok(Value::Number(gross))
+    Ok(money_value(
+        &to_money(&Value::Number(gross))?,
+        is_exact(args),
+    ))
 }
 
 /// 计算年度汇算清缴退税
@@ -238,9 +273,9 @@ pub fn calc_tax_refund(args: &[Value]) -> Result<Value, RuntimeError> {
         });
     }
 
-    let paid_tax = get_number(&args[0])?;
-    let due_tax = get_number(&args[1])?;
+    let paid_tax = to_money(&args[0])?;
+    let due_tax = to_money(&args[1])?;
 
     // 退税额 = 已缴税额 - 应缴税额
-    Ok(Value::Number(paid_tax - due_tax))
+    Ok(money_value(&(paid_tax - due_tax), is_exact(args)))
 }
@@ -0,0 +1,577 @@
+// src/builtins/plot.rs
+//! 图表内置函数（SVG）
+//!
+//! `PLOT_LINE`、`PLOT_BAR`、`PLOT_HIST` 返回 SVG 字符串，可直接写入报告或交给
+//! `IMAGE(svg, "image/svg+xml")` 在 Jupyter 中显示。
+//!
+//! 选项字典（均可省略）：
+//! - `title`：标题
+//! - `width` / `height`：画布尺寸（默认 640 x 400）
+//! - `x_label` / `y_label`：坐标轴名称
+//! - `color`：主颜色（默认 `#4e79a7`）
+//! - `labels`：`PLOT_LINE` 多条折线的图例名称
+//! - `bins`：`PLOT_HIST` 的分箱数（默认 10）
+//! - `file`：同时写入 SVG 文件（需要文件系统权限）
+
+use super::filesystem;
+use crate::evaluator::RuntimeError;
+use crate::value::Value;
+use num_traits::ToPrimitive;
+use std::fmt::Write;
+
+/// 多系列折线的配色
+const PALETTE: &[&str] = &[
+    "#4e79a7", "#f28e2b", "#e15759", "#76b7b2", "#59a14f", "#edc948", "#b07aa1", "#ff9da7",
+];
+
+const MARGIN_LEFT: f64 = 64.0;
+const MARGIN_RIGHT: f64 = 24.0;
+const MARGIN_TOP: f64 = 44.0;
+const MARGIN_BOTTOM: f64 = 56.0;
+
+/// 图表选项
+#[derive(Debug, Clone)]
+struct PlotOptions {
+    title: Option<String>,
+    width: f64,
+    height: f64,
+    x_label: Option<String>,
+    y_label: Option<String>,
+    color: String,
+    labels: Vec<String>,
+    bins: usize,
+    file: Option<String>,
+}
+
+impl PlotOptions {
+    fn parse(name: &str, value: Option<&Value>) -> Result<Self, RuntimeError> {
+        let mut options = PlotOptions {
+            title: None,
+            width: 640.0,
+            height: 400.0,
+            x_label: None,
+            y_label: None,
+            color: PALETTE[0].to_string(),
+            labels: Vec::new(),
+            bins: 10,
+            file: None,
+        };
+        let dict = match value {
+            None | Some(Value::Null) => return Ok(options),
+            Some(Value::Dict(dict)) => dict,
+            Some(other) => {
+                return Err(RuntimeError::TypeErrorDetailed {
+                    expected: "Dict (options)".to_string(),
+                    got: other.type_name().to_string(),
+                });
+            }
+        };
+
+        let string = |key: &str| -> Result<Option<String>, RuntimeError> {
+            match dict.get(key) {
+                None | Some(Value::Null) => Ok(None),
+                Some(Value::String(s)) => Ok(Some(s.clone())),
+                Some(other) => Err(RuntimeError::InvalidOperation(format!(
+                    "{}: option '{}' must be a String, got {}",
+                    name,
+                    key,
+                    other.type_name()
+                ))),
+            }
+        };
+        let positive = |key: &str, min: f64| -> Result<Option<f64>, RuntimeError> {
+            match dict.get(key) {
+                None | Some(Value::Null) => Ok(None),
+                Some(v) => match number(v) {
+                    Some(n) if n >= min && n.is_finite() => Ok(Some(n)),
+                    _ => Err(RuntimeError::InvalidOperation(format!(
+                        "{}: option '{}' must be a number >= {}",
+                        name, key, min
+                    ))),
+                },
+            }
+        };
+
+        options.title = string("title")?;
+        options.x_label = string("x_label")?;
+        options.y_label = string("y_label")?;
+        options.file = string("file")?;
+        if let Some(color) = string("color")? {
+            options.color = color;
+        }
+        if let Some(width) = positive("width", 100.0)? {
+            options.width = width;
+        }
+        if let Some(height) = positive("height", 100.0)? {
+            options.height = height;
+        }
+        if let Some(bins) = positive("bins", 1.0)? {
+            options.bins = bins as usize;
+        }
+        match dict.get("labels") {
+            None | Some(Value::Null) => {}
+            Some(Value::Array(labels)) => {
+                options.labels = labels.iter().map(|l| l.to_string()).collect();
+            }
+            Some(other) => {
+                return Err(RuntimeError::InvalidOperation(format!(
+                    "{}: option 'labels' must be an Array, got {}",
+                    name,
+                    other.type_name()
+                )));
+            }
+        }
+        Ok(options)
+    }
+}
+
+/// PLOT_LINE - 折线图
+///
+/// 用法: PLOT_LINE(xs, ys, opts?) -> SVG 字符串
+/// - `xs`: x 坐标数组
+/// - `ys`: y 坐标数组；多条折线时为数组的数组（每条与 xs 等长）
+pub fn plot_line(args: &[Value]) -> Result<Value, RuntimeError> {
+    render_line(args, false)
+}
+
+/// PLOT_BAR - 柱状图
+///
+/// 用法: PLOT_BAR(labels, values, opts?) -> SVG 字符串
+pub fn plot_bar(args: &[Value]) -> Result<Value, RuntimeError> {
+    render_bar(args, false)
+}
+
+/// PLOT_HIST - 直方图
+///
+/// 用法: PLOT_HIST(values, opts?) -> SVG 字符串
+/// 在最小值和最大值之间等宽分箱（`bins` 选项，默认 10）。
+pub fn plot_hist(args: &[Value]) -> Result<Value, RuntimeError> {
+    render_hist(args, false)
+}
+
+/// PLOT_LINE（支持 `file` 选项，需要文件系统权限）
+pub fn plot_line_with_files(args: &[Value]) -> Result<Value, RuntimeError> {
+    render_line(args, true)
+}
+
+/// PLOT_BAR（支持 `file` 选项，需要文件系统权限）
+pub fn plot_bar_with_files(args: &[Value]) -> Result<Value, RuntimeError> {
+    render_bar(args, true)
+}
+
+/// PLOT_HIST（支持 `file` 选项，需要文件系统权限）
+pub fn plot_hist_with_files(args: &[Value]) -> Result<Value, RuntimeError> {
+    render_hist(args, true)
+}
+
+fn render_line(args: &[Value], allow_files: bool) -> Result<Value, RuntimeError> {
+    check_arity(args, 2, 3)?;
+    let xs = numbers("PLOT_LINE", &args[0])?;
+    let series: Vec<Vec<f64>> = match &args[1] {
+        Value::Array(items) if !items.is_empty() && matches!(items[0], Value::Array(_)) => items
+            .iter()
+            .map(|s| numbers("PLOT_LINE", s))
+            .collect::<Result<_, _>>()?,
+        other => vec![numbers("PLOT_LINE", other)?],
+    };
+    if xs.is_empty() {
+        return Err(RuntimeError::InvalidOperation(
+            "PLOT_LINE: xs must not be empty".to_string(),
+        ));
+    }
+    if let Some(s) = series.iter().find(|s| s.len() != xs.len()) {
+        return Err(RuntimeError::InvalidOperation(format!(
+            "PLOT_LINE: xs has {} values but ys has {}",
+            xs.len(),
+            s.len()
+        )));
+    }
+    let options = PlotOptions::parse("PLOT_LINE", args.get(2))?;
+
+    let (x_min, x_max) = extent(xs.iter().copied());
+    let (y_min, y_max) = extent(series.iter().flatten().copied());
+    let mut chart = Chart::new(&options, (x_min, x_max), (y_min, y_max));
+    chart.x_ticks();
+    chart.y_ticks();
+    for (index, ys) in series.iter().enumerate() {
+        let color = series_color(&options, index);
+        let points: Vec<String> = xs
+            .iter()
+            .zip(ys)
+            .map(|(&x, &y)| format!("{:.2},{:.2}", chart.x(x), chart.y(y)))
+            .collect();
+        let _ = writeln!(
+            chart.body,
+            r#"<polyline fill="none" stroke="{}" stroke-width="2" points="{}"/>"#,
+            escape(&color),
+            points.join(" ")
+        );
+        if xs.len() <= 50 {
+            for (&x, &y) in xs.iter().zip(ys) {
+                let _ = writeln!(
+                    chart.body,
+                    r#"<circle cx="{:.2}" cy="{:.2}" r="3" fill="{}"/>"#,
+                    chart.x(x),
+                    chart.y(y),
+                    escape(&color)
+                );
+            }
+        }
+    }
+    if series.len() > 1 || !options.labels.is_empty() {
+        chart.legend(series.len());
+    }
+    finish("PLOT_LINE", chart, &options, allow_files)
+}
+
+fn render_bar(args: &[Value], allow_files: bool) -> Result<Value, RuntimeError> {
+    check_arity(args, 2, 3)?;
+    let Value::Array(labels) = &args[0] else {
+        return Err(RuntimeError::TypeErrorDetailed {
+            expected: "Array".to_string(),
+            got: args[0].type_name().to_string(),
+        });
+    };
+    let values = numbers("PLOT_BAR", &args[1])?;
+    if labels.len() != values.len() {
+        return Err(RuntimeError::InvalidOperation(format!(
+            "PLOT_BAR: {} labels but {} values",
+            labels.len(),
+            values.len()
+        )));
+    }
+    let options = PlotOptions::parse("PLOT_BAR", args.get(2))?;
+
+    let (y_min, y_max) = extent(values.iter().copied().chain([0.0]));
+    let mut chart = Chart::new(&options, (0.0, values.len().max(1) as f64), (y_min, y_max));
+    chart.y_ticks();
+    let band = chart.plot_width() / values.len().max(1) as f64;
+    let baseline = chart.y(0.0);
+    for (index, (label, &value)) in labels.iter().zip(&values).enumerate() {
+        let x = chart.x(index as f64) + band * 0.1;
+        let top = chart.y(value);
+        let _ = writeln!(
+            chart.body,
+            r#"<rect x="{:.2}" y="{:.2}" width="{:.2}" height="{:.2}" fill="{}"><title>{}: {}</title></rect>"#,
+            x,
+            top.min(baseline),
+            band * 0.8,
+            (baseline - top).abs(),
+            escape(&options.color),
+            escape(&label.to_string()),
+            format_number(value)
+        );
+        let _ = writeln!(
+            chart.body,
+            r#"<text x="{:.2}" y="{:.2}" text-anchor="middle">{}</text>"#,
+            x + band * 0.4,
+            chart.plot_bottom() + 16.0,
+            escape(&label.to_string())
+        );
+    }
+    finish("PLOT_BAR", chart, &options, allow_files)
+}
+
+fn render_hist(args: &[Value], allow_files: bool) -> Result<Value, RuntimeError> {
+    check_arity(args, 1, 2)?;
+    let values = numbers("PLOT_HIST", &args[0])?;
+    if values.is_empty() {
+        return Err(RuntimeError::InvalidOperation(
+            "PLOT_HIST: values must not be empty".to_string(),
+        ));
+    }
+    let options = PlotOptions::parse("PLOT_HIST", args.get(1))?;
+
+    let (min, max) = extent(values.iter().copied());
+    let bins = options.bins;
+    let width = (max - min) / bins as f64;
+    let mut counts = vec![0usize; bins];
+    for &v in &values {
+        let index = if width > 0.0 {
+            (((v - min) / width) as usize).min(bins - 1)
+        } else {
+            0
+        };
+        counts[index] += 1;
+    }
+
+    let peak = counts.iter().copied().max().unwrap_or(0) as f64;
+    let mut chart = Chart::new(&options, (min, max), (0.0, peak));
+    chart.x_ticks();
+    chart.y_ticks();
+    let band = chart.plot_width() / bins as f64;
+    for (index, &count) in counts.iter().enumerate() {
+        let top = chart.y(count as f64);
+        let left = min + width * index as f64;
+        let _ = writeln!(
+            chart.body,
+            r#"<rect x="{:.2}" y="{:.2}" width="{:.2}" height="{:.2}" fill="{}" stroke="white"><title>[{}, {}): {}</title></rect>"#,
+            chart.plot_left() + band * index as f64,
+            top,
+            band,
+            chart.plot_bottom() - top,
+            escape(&options.color),
+            format_number(left),
+            format_number(left + width),
+            count
+        );
+    }
+    finish("PLOT_HIST", chart, &options, allow_files)
+}
+
+/// 画布、坐标映射和已生成的图形元素
+struct Chart<'a> {
+    options: &'a PlotOptions,
+    x_range: (f64, f64),
+    y_range: (f64, f64),
+    body: String,
+}
+
+impl<'a> Chart<'a> {
+    fn new(options: &'a PlotOptions, x_range: (f64, f64), y_range: (f64, f64)) -> Self {
+        Chart {
+            options,
+            x_range: widen(x_range),
+            y_range: widen(y_range),
+            body: String::new(),
+        }
+    }
+
+    fn plot_left(&self) -> f64 {
+        MARGIN_LEFT
+    }
+
+    fn plot_bottom(&self) -> f64 {
+        self.options.height - MARGIN_BOTTOM
+    }
+
+    fn plot_width(&self) -> f64 {
+        self.options.width - MARGIN_LEFT - MARGIN_RIGHT
+    }
+
+    fn plot_height(&self) -> f64 {
+        self.options.height - MARGIN_TOP - MARGIN_BOTTOM
+    }
+
+    fn x(&self, value: f64) -> f64 {
+        let (min, max) = self.x_range;
+        self.plot_left() + (value - min) / (max - min) * self.plot_width()
+    }
+
+    fn y(&self, value: f64) -> f64 {
+        let (min, max) = self.y_range;
+        self.plot_bottom() - (value - min) / (max - min) * self.plot_height()
+    }
+
+    /// x 轴刻度（数值型坐标）
+    fn x_ticks(&mut self) {
+        for tick in nice_ticks(self.x_range.0, self.x_range.1) {
+            let x = self.x(tick);
+            let _ = writeln!(
+                self.body,
+                "<line x1=\"{x:.2}\" y1=\"{:.2}\" x2=\"{x:.2}\" y2=\"{:.2}\" stroke=\"#333\"/>\n<text x=\"{x:.2}\" y=\"{:.2}\" text-anchor=\"middle\">{}</text>",
+                self.plot_bottom(),
+                self.plot_bottom() + 5.0,
+                self.plot_bottom() + 18.0,
+                format_number(tick)
+            );
+        }
+    }
+
+    /// y 轴刻度和水平网格线
+    fn y_ticks(&mut self) {
+        for tick in nice_ticks(self.y_range.0, self.y_range.1) {
+            let y = self.y(tick);
+            let _ = writeln!(
+                self.body,
+                "<line x1=\"{:.2}\" y1=\"{y:.2}\" x2=\"{:.2}\" y2=\"{y:.2}\" stroke=\"#e0e0e0\"/>\n<text x=\"{:.2}\" y=\"{:.2}\" text-anchor=\"end\">{}</text>",
+                self.plot_left(),
+                self.plot_left() + self.plot_width(),
+                self.plot_left() - 8.0,
+                y + 4.0,
+                format_number(tick)
+            );
+        }
+    }
+
+    fn legend(&mut self, count: usize) {
+        for index in 0..count {
+            let label = self
+                .options
+                .labels
+                .get(index)
+                .cloned()
+                .unwrap_or_else(|| format!("series {}", index + 1));
+            let x = self.plot_left() + self.plot_width() - 120.0;
+            let y = MARGIN_TOP + 8.0 + index as f64 * 16.0;
+            let _ = writeln!(
+                self.body,
+                r#"<rect x="{:.2}" y="{:.2}" width="10" height="10" fill="{}"/><text x="{:.2}" y="{:.2}">{}</text>"#,
+                x,
+                y - 9.0,
+                escape(&series_color(self.options, index)),
+                x + 14.0,
+                y,
+                escape(&label)
+            );
+        }
+    }
+
+    fn render(self) -> String {
+        let (width, height) = (self.options.width, self.options.height);
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" font-family=\"sans-serif\" font-size=\"12\">\n<rect width=\"100%\" height=\"100%\" fill=\"white\"/>\n",
+            w = width,
+            h = height
+        );
+        if let Some(title) = &self.options.title {
+            let _ = writeln!(
+                svg,
+                r#"<text x="{:.2}" y="24" text-anchor="middle" font-size="16" font-weight="bold">{}</text>"#,
+                width / 2.0,
+                escape(title)
+            );
+        }
+        svg.push_str(&self.body);
+        let _ = writeln!(
+            svg,
+            "<line x1=\"{l:.2}\" y1=\"{b:.2}\" x2=\"{r:.2}\" y2=\"{b:.2}\" stroke=\"#333\"/>\n<line x1=\"{l:.2}\" y1=\"{t:.2}\" x2=\"{l:.2}\" y2=\"{b:.2}\" stroke=\"#333\"/>",
+            l = self.plot_left(),
+            r = self.plot_left() + self.plot_width(),
+            t = MARGIN_TOP,
+            b = self.plot_bottom()
+        );
+        if let Some(label) = &self.options.x_label {
+            let _ = writeln!(
+                svg,
+                r#"<text x="{:.2}" y="{:.2}" text-anchor="middle">{}</text>"#,
+                self.plot_left() + self.plot_width() / 2.0,
+                height - 12.0,
+                escape(label)
+            );
+        }
+        if let Some(label) = &self.options.y_label {
+            let _ = writeln!(
+                svg,
+                r#"<text x="16" y="{y:.2}" text-anchor="middle" transform="rotate(-90 16 {y:.2})">{}</text>"#,
+                escape(label),
+                y = MARGIN_TOP + self.plot_height() / 2.0
+            );
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+/// 生成 SVG，按需写入文件
+fn finish(
+    name: &str,
+    chart: Chart,
+    options: &PlotOptions,
+    allow_files: bool,
+) -> Result<Value, RuntimeError> {
+    let svg = chart.render();
+    if let Some(path) = &options.file {
+        if !allow_files {
+            return Err(RuntimeError::InvalidOperation(format!(
+                "{}: writing to a file requires filesystem permission",
+                name
+            )));
+        }
+        filesystem::write_file(&[Value::String(path.clone()), Value::String(svg.clone())])?;
+    }
+    Ok(Value::String(svg))
+}
+
+fn check_arity(args: &[Value], min: usize, max: usize) -> Result<(), RuntimeError> {
+    if args.len() < min || args.len() > max {
+        return Err(RuntimeError::WrongArity {
+            expected: min,
+            got: args.len(),
+        });
+    }
+    Ok(())
+}
+
+fn number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => Some(*n),
+        Value::Fraction(f) => f.to_f64(),
+        _ => None,
+    }
+}
+
+fn numbers(name: &str, value: &Value) -> Result<Vec<f64>, RuntimeError> {
+    let Value::Array(items) = value else {
+        return Err(RuntimeError::TypeErrorDetailed {
+            expected: "Array".to_string(),
+            got: value.type_name().to_string(),
+        });
+    };
+    items
+        .iter()
+        .map(|item| match number(item) {
+            Some(n) if n.is_finite() => Ok(n),
+            _ => Err(RuntimeError::InvalidOperation(format!(
+                "{}: expected finite numbers, got {}",
+                name, item
+            ))),
+        })
+        .collect()
+}
+
+fn extent(values: impl Iterator<Item = f64>) -> (f64, f64) {
+    values.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), v| {
+        (min.min(v), max.max(v))
+    })
+}
+
+/// 避免零宽度的坐标范围
+fn widen((min, max): (f64, f64)) -> (f64, f64) {
+    if !min.is_finite() || !max.is_finite() {
+        (0.0, 1.0)
+    } else if min == max {
+        (min - 1.0, max + 1.0)
+    } else {
+        (min, max)
+    }
+}
+
+/// 落在范围内的“整齐”刻度（步长为 1、2、5 乘以 10 的幂，约 5 个）
+fn nice_ticks(min: f64, max: f64) -> Vec<f64> {
+    let raw = (max - min) / 5.0;
+    let magnitude = 10f64.powf(raw.log10().floor());
+    let step = [1.0, 2.0, 5.0, 10.0]
+        .iter()
+        .map(|m| m * magnitude)
+        .find(|step| *step >= raw)
+        .unwrap_or(10.0 * magnitude);
+    let first = (min / step).ceil() as i64;
+    let last = (max / step).floor() as i64;
+    (first..=last).map(|i| i as f64 * step).collect()
+}
+
+fn series_color(options: &PlotOptions, index: usize) -> String {
+    if index == 0 {
+        options.color.clone()
+    } else {
+        PALETTE[index % PALETTE.len()].to_string()
+    }
+}
+
+/// 刻度和提示中的数字：去掉浮点误差和多余的零
+fn format_number(value: f64) -> String {
+    let rounded = (value * 1e9).round() / 1e9;
+    if rounded == 0.0 {
+        "0".to_string()
+    } else {
+        rounded.to_string()
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -3,7 +3,7 @@ use crate::evaluator::RuntimeError;
 use crate::value::Value;
 use num_bigint::BigInt;
 use num_rational::Ratio;
-use num_traits::{One, ToPrimitive, Zero};
+use num_traits::{One, Signed, ToPrimitive, Zero};
 
 /// 将数字转换为分数
 ///
@@ -270,93 +270,223 @@ pub fn denominator(args: &[Value]) -> Result<Value, RuntimeError> {
     }
 }
 
-/// 计算两个整数的最大公约数（Greatest Common Divisor）
+/// f64 能精确表示的最大整数（2^53）
+const MAX_SAFE_INTEGER: i64 = 9_007_199_254_740_992;
+
+/// 将整数值（整数 Number 或分母为 1 的 Fraction）转换为 BigInt
+fn to_big_integer(value: &Value, func: &str) -> Result<BigInt, RuntimeError> {
+    match value {
+        Value::Number(n) => crate::value::integer_ratio(*n)
+            .map(|r| r.to_integer())
+            .ok_or_else(|| {
+                RuntimeError::InvalidOperation(format!("{} requires integers, got {}", func, n))
+            }),
+        Value::Fraction(f) if f.is_integer() => Ok(f.to_integer()),
+        Value::Fraction(f) => Err(RuntimeError::InvalidOperation(format!(
+            "{} requires integers, got {}",
+            func, f
+        ))),
+        _ => Err(RuntimeError::TypeErrorDetailed {
+            expected: "Number or Fraction".to_string(),
+            got: format!("{:?}", value),
+        }),
+    }
+}
+
+/// 将有理数参数（整数 Number 或 Fraction）转换为精确分数
+fn to_exact_ratio(value: &Value, func: &str) -> Result<Ratio<BigInt>, RuntimeError> {
+    match value {
+        Value::Fraction(f) => Ok(f.clone()),
+        _ => to_big_integer(value, func).map(Ratio::from_integer),
+    }
+}
+
+/// 整数结果：在 2^53 以内返回 Number，否则返回精确的大整数（Fraction）
+fn integer_value(n: BigInt) -> Value {
+    if n.abs() <= BigInt::from(MAX_SAFE_INTEGER) {
+        Value::Number(n.to_f64().unwrap_or(f64::NAN))
+    } else {
+        Value::Fraction(Ratio::from_integer(n))
+    }
+}
+
+/// 有理数结果：整数按 `integer_value` 返回，否则返回 Fraction
+fn rational_value(r: Ratio<BigInt>) -> Value {
+    if r.is_integer() {
+        integer_value(r.to_integer())
+    } else {
+        Value::Fraction(r)
+    }
+}
+
+/// 大整数的最大公约数（欧几里得算法，结果非负）
+fn big_gcd(a: &BigInt, b: &BigInt) -> BigInt {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while !b.is_zero() {
+        let r = &a % &b;
+        a = b;
+        b = r;
+    }
+    a
+}
+
+/// 大整数的最小公倍数（结果非负）
+fn big_lcm(a: &BigInt, b: &BigInt) -> BigInt {
+    if a.is_zero() || b.is_zero() {
+        return BigInt::zero();
+    }
+    (a * b).abs() / big_gcd(a, b)
+}
+
+/// 检查参数个数
+fn expect_args(args: &[Value], count: usize) -> Result<(), RuntimeError> {
+    if args.len() != count {
+        return Err(RuntimeError::WrongArity {
+            expected: count,
+            got: args.len(),
+        });
+    }
+    Ok(())
+}
+
+/// 计算两个数的最大公约数（Greatest Common Divisor）
 ///
 /// 参数：
-/// - args[0]: 第一个整数
-/// - args[1]: 第二个整数
+/// - args[0]: 第一个整数或分数
+/// - args[1]: 第二个整数或分数
 ///
 /// 返回：
-/// - 两个数的最大公约数
+/// - 两个数的最大公约数（非负）
+///
+/// 支持任意大小的整数；分数按 gcd(a/b, c/d) = gcd(a, c) / lcm(b, d) 计算。
 pub fn gcd(args: &[Value]) -> Result<Value, RuntimeError> {
-    if args.len() != 2 {
-        return Err(RuntimeError::WrongArity {
-            expected: 2,
-            got: args.len(),
-        });
+    expect_args(args, 2)?;
+    let a = to_exact_ratio(&args[0], "GCD")?;
+    let b = to_exact_ratio(&args[1], "GCD")?;
+    let numer = big_gcd(a.numer(), b.numer());
+    let denom = big_lcm(a.denom(), b.denom());
+    Ok(rational_value(Ratio::new(numer, denom)))
+}
+
+/// 计算两个数的最小公倍数（Least Common Multiple）
+///
+/// 参数：
+/// - args[0]: 第一个整数或分数
+/// - args[1]: 第二个整数或分数
+///
+/// 返回：
+/// - 两个数的最小公倍数（非负）
+///
+/// 支持任意大小的整数；分数按 lcm(a/b, c/d) = lcm(a, c) / gcd(b, d) 计算。
+pub fn lcm(args: &[Value]) -> Result<Value, RuntimeError> {
+    expect_args(args, 2)?;
+    let a = to_exact_ratio(&args[0], "LCM")?;
+    let b = to_exact_ratio(&args[1], "LCM")?;
+    let numer = big_lcm(a.numer(), b.numer());
+    let denom = big_gcd(a.denom(), b.denom());
+    Ok(rational_value(Ratio::new(numer, denom)))
+}
+
+/// 模幂运算
+///
+/// 参数：
+/// - args[0]: 底数（整数）
+/// - args[1]: 指数（非负整数）
+/// - args[2]: 模数（非零整数）
+///
+/// 返回：
+/// - (base ^ exponent) mod modulus，支持任意大小的整数
+pub fn modpow(args: &[Value]) -> Result<Value, RuntimeError> {
+    expect_args(args, 3)?;
+    let base = to_big_integer(&args[0], "MODPOW")?;
+    let exponent = to_big_integer(&args[1], "MODPOW")?;
+    let modulus = to_big_integer(&args[2], "MODPOW")?;
+
+    if exponent.is_negative() {
+        return Err(RuntimeError::InvalidOperation(
+            "MODPOW exponent must be non-negative".to_string(),
+        ));
     }
-    let a = match &args[0] {
-        Value::Number(n) => *n as i64,
-        _ => {
-            return Err(RuntimeError::TypeErrorDetailed {
-                expected: "Number".to_string(),
-                got: format!("{:?}", args[0]),
-            });
-        }
-    };
-    let b = match &args[1] {
-        Value::Number(n) => *n as i64,
-        _ => {
-            return Err(RuntimeError::TypeErrorDetailed {
-                expected: "Number".to_string(),
-                got: format!("{:?}", args[1]),
-            });
-        }
-    };
-    // 欧几里得算法实现最大公约数计算
-    fn gcd_impl(mut a: i64, mut b: i64) -> i64 {
-        while b != 0 {
-            let temp = b;
-            b = a % b;
-            a = temp;
-        }
-        a.abs()
+    if modulus.is_zero() {
+        return Err(RuntimeError::DivisionByZero);
     }
-    Ok(Value::Number(gcd_impl(a, b) as f64))
+    Ok(integer_value(base.modpow(&exponent, &modulus)))
 }
 
-/// 计算两个整数的最小公倍数（Least Common Multiple）
+/// 整数平方根
 ///
 /// 参数：
-/// - args[0]: 第一个整数
-/// - args[1]: 第二个整数
+/// - args[0]: 非负整数
 ///
 /// 返回：
-/// - 两个数的最小公倍数
-pub fn lcm(args: &[Value]) -> Result<Value, RuntimeError> {
-    if args.len() != 2 {
-        return Err(RuntimeError::WrongArity {
-            expected: 2,
-            got: args.len(),
-        });
+/// - 不超过 √n 的最大整数，支持任意大小的整数
+pub fn isqrt(args: &[Value]) -> Result<Value, RuntimeError> {
+    expect_args(args, 1)?;
+    let n = to_big_integer(&args[0], "ISQRT")?;
+    if n.is_negative() {
+        return Err(RuntimeError::InvalidOperation(format!(
+            "Cannot take integer square root of negative number: {}",
+            n
+        )));
+    }
+    Ok(integer_value(n.sqrt()))
+}
+
+/// Miller-Rabin 测试使用的底数（对 n < 3.3 × 10^24 是确定性的）
+const PRIME_BASES: [u32; 13] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41];
+
+/// 素数判定
+///
+/// 参数：
+/// - args[0]: 整数
+///
+/// 返回：
+/// - 是否为素数
+///
+/// 使用固定底数的 Miller-Rabin 测试：n < 3.3 × 10^24 时结果是确定的，
+/// 更大的数为强概率素数测试。
+pub fn prime_test(args: &[Value]) -> Result<Value, RuntimeError> {
+    expect_args(args, 1)?;
+    let n = to_big_integer(&args[0], "PRIME_TEST")?;
+    Ok(Value::Boolean(is_probable_prime(&n)))
+}
+
+fn is_probable_prime(n: &BigInt) -> bool {
+    let two = BigInt::from(2);
+    if n < &two {
+        return false;
     }
-    let a = match &args[0] {
-        Value::Number(n) => *n as i64,
-        _ => {
-            return Err(RuntimeError::TypeErrorDetailed {
-                expected: "Number".to_string(),
-                got: format!("{:?}", args[0]),
-            });
+    for &p in &PRIME_BASES {
+        let p = BigInt::from(p);
+        if n == &p {
+            return true;
+        }
+        if (n % &p).is_zero() {
+            return false;
         }
-    };
-    let b = match &args[1] {
-        Value::Number(n) => *n as i64,
-        _ => {
-            return Err(RuntimeError::TypeErrorDetailed {
-                expected: "Number".to_string(),
-                got: format!("{:?}", args[1]),
-            });
+    }
+
+    // n - 1 = d * 2^s
+    let n_minus_one = n - BigInt::one();
+    let mut d = n_minus_one.clone();
+    let mut s = 0u32;
+    while (&d % &two).is_zero() {
+        d /= &two;
+        s += 1;
+    }
+
+    'bases: for &a in &PRIME_BASES {
+        let mut x = BigInt::from(a).modpow(&d, n);
+        if x.is_one() || x == n_minus_one {
+            continue;
         }
-    };
-    // 使用公式：lcm(a,b) = |a*b| / gcd(a,b)
-    fn gcd_impl(mut a: i64, mut b: i64) -> i64 {
-        while b != 0 {
-            let temp = b;
-            b = a % b;
-            a = temp;
+        for _ in 1..s {
+            x = x.modpow(&two, n);
+            if x == n_minus_one {
+                continue 'bases;
+            }
         }
-        a.abs()
+        return false;
     }
-    let result = (a.abs() * b.abs()) / gcd_impl(a, b);
-    Ok(Value::Number(result as f64))
+    true
 }
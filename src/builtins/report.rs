@@ -1,3 +1,646 @@
 // src/builtins/report.rs
-//! 占位：后续用于 Excel 公式兼容 / 转换能力。
-//! 当前未在 DSL 中提供报表或 Excel 操作接口。
+//! 报告生成内置函数
+//!
+//! `REPORT_BUILD(sections, opts?)` 把结构化的章节列表渲染为 Markdown 或 HTML
+//! （启用 `pdf` 特性时也可生成 PDF）。章节中的文本经过模板引擎渲染，
+//! 可以引用 `opts.data`（或章节自己的 `data`）中的字段。
+//!
+//! 章节是字典（字符串视为段落），按 `type` 区分：
+//! - `heading`：`text`、`level`（1-6，默认 2）
+//! - `text`：`text`，段落
+//! - `list`：`items`、`ordered`
+//! - `table`：`rows`（字典数组或 TABLE）、`columns`（可选，指定列和顺序）
+//! - `summary`：`data`，键值对两列表格（如薪资汇总）
+//! - `svg`：`svg`，内联图表（如 `PLOT_BAR` 的结果）
+//! - `image`：`src`、`alt`
+//!
+//! 选项：`format`（"markdown"（默认）、"html"、"pdf"）、`title`、`data`、
+//! `file`（同时写入文件，需要文件系统权限）。
+
+use super::filesystem;
+use super::template::render_template;
+use crate::evaluator::RuntimeError;
+use crate::value::Value;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Write;
+
+/// 报告的输出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Markdown,
+    Html,
+    #[cfg(feature = "pdf")]
+    Pdf,
+}
+
+/// 渲染前的报告内容块
+#[derive(Debug, Clone)]
+enum Block {
+    Heading(usize, String),
+    Paragraph(String),
+    List {
+        ordered: bool,
+        items: Vec<String>,
+    },
+    Table {
+        columns: Vec<String>,
+        rows: Vec<Vec<Value>>,
+    },
+    Svg(String),
+    Image {
+        src: String,
+        alt: String,
+    },
+}
+
+/// REPORT_BUILD - 生成报告
+///
+/// 用法: REPORT_BUILD(sections, opts?) -> String
+///
+/// # 示例
+/// ```aether
+/// Set REPORT REPORT_BUILD([
+///     {"type": "heading", "text": "{{month}} 薪资报告", "level": 1},
+///     "共 {{count}} 人",
+///     {"type": "table", "rows": RESULTS, "columns": ["name", "net"]},
+///     {"type": "summary", "data": TOTALS}
+/// ], {"format": "html", "data": {"month": "2024-05", "count": 12}})
+/// ```
+pub fn report_build(args: &[Value]) -> Result<Value, RuntimeError> {
+    build(args, false)
+}
+
+/// REPORT_BUILD（支持 `file` 选项，需要文件系统权限）
+pub fn report_build_with_files(args: &[Value]) -> Result<Value, RuntimeError> {
+    build(args, true)
+}
+
+fn build(args: &[Value], allow_files: bool) -> Result<Value, RuntimeError> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(RuntimeError::WrongArity {
+            expected: 1,
+            got: args.len(),
+        });
+    }
+    let Value::Array(sections) = &args[0] else {
+        return Err(RuntimeError::TypeErrorDetailed {
+            expected: "Array (sections)".to_string(),
+            got: args[0].type_name().to_string(),
+        });
+    };
+    let options = match args.get(1) {
+        None | Some(Value::Null) => HashMap::new(),
+        Some(Value::Dict(options)) => options.clone(),
+        Some(other) => {
+            return Err(RuntimeError::TypeErrorDetailed {
+                expected: "Dict (options)".to_string(),
+                got: other.type_name().to_string(),
+            });
+        }
+    };
+
+    let format = match options.get("format") {
+        None | Some(Value::Null) => Format::Markdown,
+        Some(Value::String(name)) => match name.to_lowercase().as_str() {
+            "markdown" | "md" => Format::Markdown,
+            "html" => Format::Html,
+            #[cfg(feature = "pdf")]
+            "pdf" => Format::Pdf,
+            #[cfg(not(feature = "pdf"))]
+            "pdf" => {
+                return Err(RuntimeError::InvalidOperation(
+                    "REPORT_BUILD: PDF output requires the 'pdf' feature".to_string(),
+                ));
+            }
+            other => {
+                return Err(RuntimeError::InvalidOperation(format!(
+                    "REPORT_BUILD: unknown format '{}', expected \"markdown\", \"html\" or \"pdf\"",
+                    other
+                )));
+            }
+        },
+        Some(other) => {
+            return Err(RuntimeError::TypeErrorDetailed {
+                expected: "String (format)".to_string(),
+                got: other.type_name().to_string(),
+            });
+        }
+    };
+    let data = match options.get("data") {
+        None | Some(Value::Null) => HashMap::new(),
+        Some(Value::Dict(data)) => data.clone(),
+        Some(other) => {
+            return Err(RuntimeError::TypeErrorDetailed {
+                expected: "Dict (data)".to_string(),
+                got: other.type_name().to_string(),
+            });
+        }
+    };
+
+    let title = match options.get("title") {
+        Some(Value::String(title)) => Some(render_text(title, &data)?),
+        _ => None,
+    };
+    let mut blocks = Vec::new();
+    if let Some(title) = &title {
+        blocks.push(Block::Heading(1, title.clone()));
+    }
+    for (index, section) in sections.iter().enumerate() {
+        blocks.push(section_block(index, section, &data)?);
+    }
+
+    let output = match format {
+        Format::Markdown => markdown(&blocks),
+        Format::Html => html(title.as_deref(), &blocks),
+        #[cfg(feature = "pdf")]
+        Format::Pdf => pdf::render(&blocks),
+    };
+
+    if let Some(path) = options.get("file") {
+        if !allow_files {
+            return Err(RuntimeError::InvalidOperation(
+                "REPORT_BUILD: writing to a file requires filesystem permission".to_string(),
+            ));
+        }
+        filesystem::write_file(&[path.clone(), Value::String(output.clone())])?;
+    }
+    Ok(Value::String(output))
+}
+
+/// 用模板引擎渲染文本（不含 `{{` 的文本原样返回）
+fn render_text(text: &str, data: &HashMap<String, Value>) -> Result<String, RuntimeError> {
+    if !text.contains("{{") {
+        return Ok(text.to_string());
+    }
+    match render_template(&[Value::String(text.to_string()), Value::Dict(data.clone())])? {
+        Value::String(s) => Ok(s),
+        other => Ok(other.to_string()),
+    }
+}
+
+fn section_block(
+    index: usize,
+    section: &Value,
+    data: &HashMap<String, Value>,
+) -> Result<Block, RuntimeError> {
+    let section = match section {
+        Value::String(text) => return Ok(Block::Paragraph(render_text(text, data)?)),
+        Value::Dict(section) => section,
+        other => {
+            return Err(RuntimeError::InvalidOperation(format!(
+                "REPORT_BUILD: section {} must be a Dict or String, got {}",
+                index,
+                other.type_name()
+            )));
+        }
+    };
+    let invalid = |message: &str| {
+        RuntimeError::InvalidOperation(format!("REPORT_BUILD: section {}: {}", index, message))
+    };
+
+    // 章节自己的 data 覆盖报告级 data
+    let mut scope = data.clone();
+    if let Some(Value::Dict(own)) = section.get("data") {
+        scope.extend(own.iter().map(|(k, v)| (k.clone(), v.clone())));
+    }
+    let text = |key: &str| -> Result<String, RuntimeError> {
+        match section.get(key) {
+            Some(Value::String(s)) => render_text(s, &scope),
+            Some(Value::Null) | None => Err(invalid(&format!("missing '{}'", key))),
+            Some(other) => Ok(other.to_string()),
+        }
+    };
+
+    let kind = match section.get("type") {
+        Some(Value::String(kind)) => kind.to_lowercase(),
+        None => "text".to_string(),
+        Some(_) => return Err(invalid("'type' must be a String")),
+    };
+    Ok(match kind.as_str() {
+        "heading" => {
+            let level = match section.get("level") {
+                Some(Value::Number(n)) if (1.0..=6.0).contains(n) => *n as usize,
+                None => 2,
+                Some(_) => return Err(invalid("'level' must be between 1 and 6")),
+            };
+            Block::Heading(level, text("text")?)
+        }
+        "text" | "paragraph" => Block::Paragraph(text("text")?),
+        "list" => {
+            let Some(Value::Array(items)) = section.get("items") else {
+                return Err(invalid("'items' must be an Array"));
+            };
+            let items = items
+                .iter()
+                .map(|item| match item {
+                    Value::String(s) => render_text(s, &scope),
+                    other => Ok(other.to_string()),
+                })
+                .collect::<Result<_, _>>()?;
+            Block::List {
+                ordered: section.get("ordered").is_some_and(Value::is_truthy),
+                items,
+            }
+        }
+        "table" => {
+            let (mut columns, rows) = match section.get("rows") {
+                Some(Value::Table(table)) => (table.columns().to_vec(), table.to_dicts()),
+                Some(Value::Array(rows)) => {
+                    let mut columns = BTreeSet::new();
+                    for row in rows {
+                        let Value::Dict(row) = row else {
+                            return Err(invalid("'rows' must contain Dicts"));
+                        };
+                        columns.extend(row.keys().cloned());
+                    }
+                    (columns.into_iter().collect(), rows.clone())
+                }
+                _ => return Err(invalid("'rows' must be an Array of Dicts or a TABLE")),
+            };
+            if let Some(Value::Array(selected)) = section.get("columns") {
+                columns = selected.iter().map(|c| c.to_string()).collect();
+            }
+            let rows = rows
+                .iter()
+                .map(|row| match row {
+                    Value::Dict(row) => columns
+                        .iter()
+                        .map(|c| row.get(c).cloned().unwrap_or(Value::Null))
+                        .collect(),
+                    _ => Vec::new(),
+                })
+                .collect();
+            Block::Table { columns, rows }
+        }
+        "summary" => {
+            let Some(Value::Dict(values)) = section.get("data") else {
+                return Err(invalid("'data' must be a Dict"));
+            };
+            let mut keys: Vec<&String> = values.keys().collect();
+            keys.sort();
+            Block::Table {
+                columns: vec!["Item".to_string(), "Value".to_string()],
+                rows: keys
+                    .into_iter()
+                    .map(|k| vec![Value::String(k.clone()), values[k].clone()])
+                    .collect(),
+            }
+        }
+        "svg" => Block::Svg(text("svg")?),
+        "image" => Block::Image {
+            src: text("src")?,
+            alt: text("alt").unwrap_or_default(),
+        },
+        other => return Err(invalid(&format!("unknown section type '{}'", other))),
+    })
+}
+
+fn cell_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+fn markdown(blocks: &[Block]) -> String {
+    let mut out = String::new();
+    for block in blocks {
+        match block {
+            Block::Heading(level, text) => {
+                let _ = writeln!(out, "{} {}", "#".repeat(*level), text);
+            }
+            Block::Paragraph(text) => {
+                let _ = writeln!(out, "{}", text);
+            }
+            Block::List { ordered, items } => {
+                for (i, item) in items.iter().enumerate() {
+                    if *ordered {
+                        let _ = writeln!(out, "{}. {}", i + 1, item);
+                    } else {
+                        let _ = writeln!(out, "- {}", item);
+                    }
+                }
+            }
+            Block::Table { columns, rows } => {
+                let escape = |s: &str| s.replace('|', "\\|").replace('\n', " ");
+                let header: Vec<String> = columns.iter().map(|c| escape(c)).collect();
+                let _ = writeln!(out, "| {} |", header.join(" | "));
+                let align: Vec<&str> = columns
+                    .iter()
+                    .enumerate()
+                    .map(|(i, _)| {
+                        if rows.iter().all(|row| is_numeric(&row[i])) && !rows.is_empty() {
+                            "---:"
+                        } else {
+                            "---"
+                        }
+                    })
+                    .collect();
+                let _ = writeln!(out, "| {} |", align.join(" | "));
+                for row in rows {
+                    let cells: Vec<String> = row.iter().map(|v| escape(&cell_text(v))).collect();
+                    let _ = writeln!(out, "| {} |", cells.join(" | "));
+                }
+            }
+            Block::Svg(svg) => {
+                let _ = writeln!(out, "{}", svg.trim_end());
+            }
+            Block::Image { src, alt } => {
+                let _ = writeln!(out, "![{}]({})", alt, src);
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn is_numeric(value: &Value) -> bool {
+    matches!(value, Value::Number(_) | Value::Fraction(_) | Value::Null)
+}
+
+fn html(title: Option<&str>, blocks: &[Block]) -> String {
+    let mut out = String::from("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    if let Some(title) = title {
+        let _ = writeln!(out, "<title>{}</title>", escape_html(title));
+    }
+    out.push_str(
+        "<style>\nbody { font-family: sans-serif; max-width: 960px; margin: 2em auto; }\n\
+         table { border-collapse: collapse; margin: 1em 0; }\n\
+         th, td { border: 1px solid #ccc; padding: 4px 8px; }\n\
+         td.num { text-align: right; }\n</style>\n</head>\n<body>\n",
+    );
+    for block in blocks {
+        match block {
+            Block::Heading(level, text) => {
+                let _ = writeln!(out, "<h{0}>{1}</h{0}>", level, escape_html(text));
+            }
+            Block::Paragraph(text) => {
+                let _ = writeln!(out, "<p>{}</p>", escape_html(text));
+            }
+            Block::List { ordered, items } => {
+                let tag = if *ordered { "ol" } else { "ul" };
+                let _ = writeln!(out, "<{}>", tag);
+                for item in items {
+                    let _ = writeln!(out, "<li>{}</li>", escape_html(item));
+                }
+                let _ = writeln!(out, "</{}>", tag);
+            }
+            Block::Table { columns, rows } => {
+                out.push_str("<table>\n<thead><tr>");
+                for column in columns {
+                    let _ = write!(out, "<th>{}</th>", escape_html(column));
+                }
+                out.push_str("</tr></thead>\n<tbody>\n");
+                for row in rows {
+                    out.push_str("<tr>");
+                    for cell in row {
+                        let class = if matches!(cell, Value::Number(_) | Value::Fraction(_)) {
+                            " class=\"num\""
+                        } else {
+                            ""
+                        };
+                        let _ = write!(out, "<td{}>{}</td>", class, escape_html(&cell_text(cell)));
+                    }
+                    out.push_str("</tr>\n");
+                }
+                out.push_str("</tbody>\n</table>\n");
+            }
+            Block::Svg(svg) => {
+                let _ = writeln!(out, "<figure>\n{}\n</figure>", svg.trim_end());
+            }
+            Block::Image { src, alt } => {
+                let _ = writeln!(
+                    out,
+                    "<img src=\"{}\" alt=\"{}\">",
+                    escape_html(src),
+                    escape_html(alt)
+                );
+            }
+        }
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// 最小 PDF 输出：A4 页面，标准字体（Helvetica / Courier），不压缩
+///
+/// 只支持 Latin-1 字符，其他字符显示为 `?`；图表和图片显示为占位文字。
+#[cfg(feature = "pdf")]
+mod pdf {
+    use super::{Block, cell_text};
+    use std::fmt::Write;
+
+    const PAGE_WIDTH: f64 = 595.0;
+    const PAGE_HEIGHT: f64 = 842.0;
+    const MARGIN: f64 = 50.0;
+
+    /// 字体资源名
+    const REGULAR: &str = "F1";
+    const BOLD: &str = "F2";
+    const MONO: &str = "F3";
+
+    struct Writer {
+        pages: Vec<String>,
+        current: String,
+        y: f64,
+    }
+
+    impl Writer {
+        fn new() -> Self {
+            Writer {
+                pages: Vec::new(),
+                current: String::new(),
+                y: PAGE_HEIGHT - MARGIN,
+            }
+        }
+
+        fn line(&mut self, font: &str, size: f64, indent: f64, text: &str) {
+            let leading = size * 1.4;
+            if self.y - leading < MARGIN {
+                self.pages.push(std::mem::take(&mut self.current));
+                self.y = PAGE_HEIGHT - MARGIN;
+            }
+            self.y -= leading;
+            let _ = writeln!(
+                self.current,
+                "BT /{} {} Tf {:.2} {:.2} Td ({}) Tj ET",
+                font,
+                size,
+                MARGIN + indent,
+                self.y,
+                escape(text)
+            );
+        }
+
+        /// 按近似字宽折行输出
+        fn wrapped(&mut self, font: &str, size: f64, indent: f64, text: &str) {
+            let max_chars = ((PAGE_WIDTH - 2.0 * MARGIN - indent) / (size * 0.5)) as usize;
+            for paragraph in text.lines() {
+                let mut line = String::new();
+                for word in paragraph.split_whitespace() {
+                    if !line.is_empty()
+                        && line.chars().count() + 1 + word.chars().count() > max_chars
+                    {
+                        self.line(font, size, indent, &line);
+                        line.clear();
+                    }
+                    if !line.is_empty() {
+                        line.push(' ');
+                    }
+                    line.push_str(word);
+                }
+                self.line(font, size, indent, &line);
+            }
+        }
+
+        fn gap(&mut self, height: f64) {
+            self.y -= height;
+        }
+
+        fn finish(mut self) -> Vec<String> {
+            self.pages.push(self.current);
+            self.pages
+        }
+    }
+
+    pub(super) fn render(blocks: &[Block]) -> String {
+        let mut writer = Writer::new();
+        for block in blocks {
+            match block {
+                Block::Heading(level, text) => {
+                    let size = [20.0, 16.0, 14.0, 12.0, 11.0, 10.0][level - 1];
+                    writer.gap(4.0);
+                    writer.wrapped(BOLD, size, 0.0, text);
+                }
+                Block::Paragraph(text) => writer.wrapped(REGULAR, 10.0, 0.0, text),
+                Block::List { ordered, items } => {
+                    for (i, item) in items.iter().enumerate() {
+                        let bullet = if *ordered {
+                            format!("{}.", i + 1)
+                        } else {
+                            "-".to_string()
+                        };
+                        writer.wrapped(REGULAR, 10.0, 12.0, &format!("{} {}", bullet, item));
+                    }
+                }
+                Block::Table { columns, rows } => {
+                    let cells: Vec<Vec<String>> = rows
+                        .iter()
+                        .map(|row| row.iter().map(cell_text).collect())
+                        .collect();
+                    let widths: Vec<usize> = columns
+                        .iter()
+                        .enumerate()
+                        .map(|(i, c)| {
+                            cells
+                                .iter()
+                                .map(|r| r[i].chars().count())
+                                .chain([c.chars().count()])
+                                .max()
+                                .unwrap_or(0)
+                        })
+                        .collect();
+                    let format_row = |row: &[String]| {
+                        row.iter()
+                            .zip(&widths)
+                            .map(|(cell, width)| format!("{:<width$}", cell, width = *width))
+                            .collect::<Vec<_>>()
+                            .join("  ")
+                    };
+                    writer.line(MONO, 9.0, 0.0, &format_row(columns));
+                    let rule: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+                    writer.line(MONO, 9.0, 0.0, &rule.join("  "));
+                    for row in &cells {
+                        writer.line(MONO, 9.0, 0.0, &format_row(row));
+                    }
+                }
+                Block::Svg(_) => writer.line(REGULAR, 10.0, 0.0, "[chart]"),
+                Block::Image { alt, .. } => {
+                    writer.line(REGULAR, 10.0, 0.0, &format!("[image: {}]", alt))
+                }
+            }
+            writer.gap(6.0);
+        }
+        document(&writer.finish())
+    }
+
+    /// 组装 PDF 对象和交叉引用表
+    fn document(pages: &[String]) -> String {
+        let mut objects: Vec<String> = vec![
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            String::new(), // 页面树，页面对象编号确定后填入
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+                .to_string(),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>"
+                .to_string(),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Courier /Encoding /WinAnsiEncoding >>"
+                .to_string(),
+        ];
+        let mut kids = Vec::new();
+        for content in pages {
+            let page_id = objects.len() + 1;
+            kids.push(format!("{} 0 R", page_id));
+            objects.push(format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /Font << /F1 3 0 R /F2 4 0 R /F3 5 0 R >> >> /Contents {} 0 R >>",
+                PAGE_WIDTH,
+                PAGE_HEIGHT,
+                page_id + 1
+            ));
+            objects.push(format!(
+                "<< /Length {} >>\nstream\n{}endstream",
+                content.len(),
+                content
+            ));
+        }
+        objects[1] = format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            kids.join(" "),
+            pages.len()
+        );
+
+        let mut out = String::from("%PDF-1.4\n");
+        let mut offsets = Vec::new();
+        for (index, object) in objects.iter().enumerate() {
+            offsets.push(out.len());
+            let _ = write!(out, "{} 0 obj\n{}\nendobj\n", index + 1, object);
+        }
+        let xref = out.len();
+        let _ = write!(out, "xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+        for offset in offsets {
+            let _ = writeln!(out, "{:010} 00000 n ", offset);
+        }
+        let _ = write!(
+            out,
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref
+        );
+        out
+    }
+
+    /// PDF 字符串转义；非 ASCII 字符用八进制（Latin-1）或 `?` 表示
+    fn escape(text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        for c in text.chars() {
+            match c {
+                '(' | ')' | '\\' => {
+                    out.push('\\');
+                    out.push(c);
+                }
+                ' '..='~' => out.push(c),
+                '\u{a0}'..='\u{ff}' => {
+                    let _ = write!(out, "\\{:03o}", c as u32);
+                }
+                _ => out.push('?'),
+            }
+        }
+        out
+    }
+}
@@ -0,0 +1,179 @@
+// src/builtins/resilience.rs
+//! 重试与超时
+//!
+//! 为调用不稳定资源（HTTP、文件）的脚本提供统一的容错写法：
+//! - `RETRY(fn, options?)` 失败时按指数退避重新调用
+//! - `WITH_TIMEOUT(fn, ms)` 限制一次调用的执行时间
+//!
+//! 两者都需要调用 Aether 函数，由求值器处理；本模块负责参数解析和退避时间计算。
+
+use crate::evaluator::RuntimeError;
+use crate::value::Value;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+/// 默认尝试次数（含第一次调用）
+const DEFAULT_ATTEMPTS: usize = 3;
+
+/// 默认首次重试前的等待时间
+const DEFAULT_BACKOFF_MS: u64 = 100;
+
+/// `jitter: true` 对应的抖动比例
+const DEFAULT_JITTER: f64 = 0.5;
+
+/// 单次等待的上限，避免指数退避溢出
+const MAX_BACKOFF_MS: u64 = 60 * 60 * 1000;
+
+/// RETRY 的选项
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct RetryOptions {
+    /// 最多调用次数（含第一次）
+    pub attempts: usize,
+    /// 首次重试前等待的毫秒数，之后每次翻倍
+    pub backoff_ms: u64,
+    /// 等待时间的随机浮动比例（0-1）
+    pub jitter: f64,
+}
+
+impl Default for RetryOptions {
+    fn default() -> Self {
+        RetryOptions {
+            attempts: DEFAULT_ATTEMPTS,
+            backoff_ms: DEFAULT_BACKOFF_MS,
+            jitter: 0.0,
+        }
+    }
+}
+
+impl RetryOptions {
+    /// 第 `retry` 次重试（从 1 开始）前的等待时间
+    pub fn delay(&self, retry: u32) -> Duration {
+        let base = self
+            .backoff_ms
+            .saturating_mul(1u64 << retry.saturating_sub(1).min(32))
+            .min(MAX_BACKOFF_MS) as f64;
+        let factor = if self.jitter > 0.0 {
+            1.0 + self.jitter * (2.0 * random_unit() - 1.0)
+        } else {
+            1.0
+        };
+        Duration::from_millis((base * factor).round() as u64)
+    }
+}
+
+/// [0, 1) 内的随机数（退避抖动不需要密码学强度）
+fn random_unit() -> f64 {
+    let bits = RandomState::new().build_hasher().finish();
+    (bits >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// 检查参数是否可调用
+fn check_callable(value: &Value) -> Result<(), RuntimeError> {
+    if matches!(value, Value::Function { .. } | Value::BuiltIn { .. }) {
+        Ok(())
+    } else {
+        Err(RuntimeError::TypeErrorDetailed {
+            expected: "Function".to_string(),
+            got: value.type_name().to_string(),
+        })
+    }
+}
+
+/// 读取非负整数选项
+fn option_integer(name: &str, value: &Value) -> Result<u64, RuntimeError> {
+    match value {
+        Value::Number(n) if n.fract() == 0.0 && *n >= 0.0 => Ok(*n as u64),
+        other => Err(RuntimeError::InvalidOperation(format!(
+            "Option '{}' must be a non-negative integer, got {}",
+            name, other
+        ))),
+    }
+}
+
+/// 校验 RETRY 的参数并解析选项
+pub(crate) fn parse_retry(args: &[Value]) -> Result<RetryOptions, RuntimeError> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(RuntimeError::WrongArity {
+            expected: 2,
+            got: args.len(),
+        });
+    }
+    check_callable(&args[0])?;
+
+    let mut options = RetryOptions::default();
+    let dict = match args.get(1) {
+        None | Some(Value::Null) => return Ok(options),
+        Some(Value::Dict(dict)) => dict,
+        Some(other) => {
+            return Err(RuntimeError::TypeErrorDetailed {
+                expected: "Dict".to_string(),
+                got: other.type_name().to_string(),
+            });
+        }
+    };
+
+    if let Some(value) = dict.get("attempts") {
+        options.attempts = option_integer("attempts", value)? as usize;
+        if options.attempts == 0 {
+            return Err(RuntimeError::InvalidOperation(
+                "Option 'attempts' must be at least 1".to_string(),
+            ));
+        }
+    }
+    if let Some(value) = dict.get("backoff_ms") {
+        options.backoff_ms = option_integer("backoff_ms", value)?;
+    }
+    match dict.get("jitter") {
+        None | Some(Value::Null) | Some(Value::Boolean(false)) => {}
+        Some(Value::Boolean(true)) => options.jitter = DEFAULT_JITTER,
+        Some(Value::Number(n)) if (0.0..=1.0).contains(n) => options.jitter = *n,
+        Some(other) => {
+            return Err(RuntimeError::InvalidOperation(format!(
+                "Option 'jitter' must be a Boolean or a number between 0 and 1, got {}",
+                other
+            )));
+        }
+    }
+    Ok(options)
+}
+
+/// 校验 WITH_TIMEOUT 的参数，返回超时毫秒数
+pub(crate) fn parse_timeout(args: &[Value]) -> Result<u64, RuntimeError> {
+    if args.len() != 2 {
+        return Err(RuntimeError::WrongArity {
+            expected: 2,
+            got: args.len(),
+        });
+    }
+    check_callable(&args[0])?;
+    match &args[1] {
+        Value::Number(n) if n.fract() == 0.0 && *n >= 0.0 => Ok(*n as u64),
+        other => Err(RuntimeError::InvalidOperation(format!(
+            "WITH_TIMEOUT expects a non-negative integer number of milliseconds, got {}",
+            other
+        ))),
+    }
+}
+
+/// WITH_TIMEOUT 超时时的错误
+pub(crate) fn timeout_error(ms: u64) -> RuntimeError {
+    RuntimeError::CustomError(format!(
+        "WITH_TIMEOUT: call did not finish within {} ms",
+        ms
+    ))
+}
+
+/// 占位实现：RETRY 需要调用 Aether 函数，由求值器处理
+pub fn retry(_args: &[Value]) -> Result<Value, RuntimeError> {
+    Err(RuntimeError::InvalidOperation(
+        "RETRY requires function evaluation context".to_string(),
+    ))
+}
+
+/// 占位实现：WITH_TIMEOUT 需要调用 Aether 函数，由求值器处理
+pub fn with_timeout(_args: &[Value]) -> Result<Value, RuntimeError> {
+    Err(RuntimeError::InvalidOperation(
+        "WITH_TIMEOUT requires function evaluation context".to_string(),
+    ))
+}
@@ -0,0 +1,366 @@
+// src/builtins/schedule.rs
+//! Cron 风格的定时调度
+//!
+//! 让需要周期执行的自动化脚本以常驻进程运行，而不依赖外部 cron：
+//! - `SCHEDULE(expr, fn)` 注册任务，返回任务编号
+//! - `UNSCHEDULE(id)` 取消任务
+//! - `RUN_SCHEDULER(options?)` 按时间依次执行到期的任务（CLI 在脚本结束后也会自动驱动），
+//!   返回执行统计，失败的任务列在 `failures` 中
+//!
+//! 嵌入到事件循环中的脚本使用不阻塞的定时器（见 [`crate::runtime::timers`]），由宿主驱动：
+//! - `SET_TIMEOUT(ms, fn)` / `SET_INTERVAL(ms, fn)` 注册一次性 / 周期定时器，返回编号
+//! - `DEBOUNCE(ms, fn)` 防抖：安静 `ms` 毫秒后只执行一次
+//! - `CLEAR_TIMER(id)` 取消定时器
+//!
+//! 任务保存在引擎上（需要调用 Aether 函数，由求值器处理），
+//! 本模块负责 cron 表达式的解析与下次执行时间的计算。
+//!
+//! 表达式支持标准 5 段（分 时 日 月 周）和带秒的 6 段（秒 分 时 日 月 周），
+//! 每段可以是 `*`、数值、范围 `a-b`、步长 `*/n` / `a-b/n`、列表 `a,b`，
+//! 月份和星期可以使用英文缩写（`JAN`、`MON`），星期的 0 和 7 都表示周日。
+//! 另外支持 `@yearly`、`@monthly`、`@weekly`、`@daily`、`@hourly` 简写。
+//! 时间按本地时区计算。
+
+use crate::evaluator::RuntimeError;
+use crate::value::Value;
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, Timelike};
+use std::collections::HashMap;
+
+/// 查找下次执行时间时最多向后搜索的年数（如 2 月 30 日永远不会到来）
+const MAX_SEARCH_YEARS: i32 = 5;
+
+const MONTH_NAMES: &[&str] = &[
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+
+const WEEKDAY_NAMES: &[&str] = &["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+/// 单个字段允许的取值（位图）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Field {
+    bits: u64,
+    /// 是否为 `*`（日和星期同时受限时按"或"匹配）
+    any: bool,
+}
+
+impl Field {
+    fn contains(&self, value: u32) -> bool {
+        self.bits & (1u64 << value) != 0
+    }
+
+    /// 解析字段，`names` 为从 `min` 开始的名称表
+    fn parse(text: &str, min: u32, max: u32, names: &[&str]) -> Result<Self, String> {
+        let value = |token: &str| -> Result<u32, String> {
+            let upper = token.to_uppercase();
+            if let Some(index) = names.iter().position(|name| *name == upper) {
+                return Ok(min + index as u32);
+            }
+            token
+                .parse::<u32>()
+                .ok()
+                .filter(|v| (min..=max).contains(v))
+                .ok_or_else(|| format!("'{}' is out of range {}-{}", token, min, max))
+        };
+
+        let mut bits = 0u64;
+        for item in text.split(',') {
+            let (range, step) = match item.split_once('/') {
+                Some((range, step)) => {
+                    let step = step
+                        .parse::<u32>()
+                        .ok()
+                        .filter(|s| *s > 0)
+                        .ok_or_else(|| format!("invalid step '{}'", step))?;
+                    (range, step)
+                }
+                None => (item, 1),
+            };
+            let (start, end) = match range {
+                "*" | "?" => (min, max),
+                _ => match range.split_once('-') {
+                    Some((a, b)) => (value(a)?, value(b)?),
+                    // `a/n` 表示从 a 开始到最大值
+                    None if item.contains('/') => (value(range)?, max),
+                    None => {
+                        let v = value(range)?;
+                        (v, v)
+                    }
+                },
+            };
+            if start > end {
+                return Err(format!("invalid range '{}'", range));
+            }
+            for v in (start..=end).step_by(step as usize) {
+                bits |= 1u64 << v;
+            }
+        }
+        Ok(Field {
+            bits,
+            any: text == "*" || text == "?",
+        })
+    }
+}
+
+/// 解析后的 cron 表达式
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    second: Field,
+    minute: Field,
+    hour: Field,
+    day: Field,
+    month: Field,
+    weekday: Field,
+}
+
+impl CronSchedule {
+    /// 解析 cron 表达式
+    pub fn parse(expr: &str) -> Result<Self, String> {
+        let expanded = match expr.trim().to_lowercase().as_str() {
+            "@yearly" | "@annually" => "0 0 1 1 *".to_string(),
+            "@monthly" => "0 0 1 * *".to_string(),
+            "@weekly" => "0 0 * * 0".to_string(),
+            "@daily" | "@midnight" => "0 0 * * *".to_string(),
+            "@hourly" => "0 * * * *".to_string(),
+            _ => expr.trim().to_string(),
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let (second, rest) = match fields.len() {
+            5 => ("0", &fields[..]),
+            6 => (fields[0], &fields[1..]),
+            n => {
+                return Err(format!(
+                    "invalid cron expression '{}': expected 5 or 6 fields, got {}",
+                    expr, n
+                ));
+            }
+        };
+        let wrap = |e: String| format!("invalid cron expression '{}': {}", expr, e);
+
+        let mut weekday = Field::parse(rest[4], 0, 7, WEEKDAY_NAMES).map_err(wrap)?;
+        // 7 也表示周日
+        if weekday.contains(7) {
+            weekday.bits = (weekday.bits | 1) & !(1u64 << 7);
+        }
+        Ok(CronSchedule {
+            second: Field::parse(second, 0, 59, &[]).map_err(wrap)?,
+            minute: Field::parse(rest[0], 0, 59, &[]).map_err(wrap)?,
+            hour: Field::parse(rest[1], 0, 23, &[]).map_err(wrap)?,
+            day: Field::parse(rest[2], 1, 31, &[]).map_err(wrap)?,
+            month: Field::parse(rest[3], 1, 12, MONTH_NAMES).map_err(wrap)?,
+            weekday,
+        })
+    }
+
+    /// 日期是否匹配（日和星期都受限时满足其一即可，与 cron 一致）
+    fn matches_date(&self, date: NaiveDate) -> bool {
+        let day = self.day.contains(date.day());
+        let weekday = self.weekday.contains(date.weekday().num_days_from_sunday());
+        if self.day.any || self.weekday.any {
+            day && weekday
+        } else {
+            day || weekday
+        }
+    }
+
+    /// 严格晚于 `after` 的下一个执行时间
+    pub fn next_after(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let mut t = after.with_nanosecond(0)? + Duration::seconds(1);
+        let limit = after.year() + MAX_SEARCH_YEARS;
+
+        while t.year() <= limit {
+            let date = t.date();
+            if !self.month.contains(t.month()) {
+                let (year, month) = if t.month() == 12 {
+                    (t.year() + 1, 1)
+                } else {
+                    (t.year(), t.month() + 1)
+                };
+                t = NaiveDate::from_ymd_opt(year, month, 1)?.and_hms_opt(0, 0, 0)?;
+            } else if !self.matches_date(date) {
+                t = date.succ_opt()?.and_hms_opt(0, 0, 0)?;
+            } else if !self.hour.contains(t.hour()) {
+                t = t.with_minute(0)?.with_second(0)? + Duration::hours(1);
+            } else if !self.minute.contains(t.minute()) {
+                t = t.with_second(0)? + Duration::minutes(1);
+            } else if !self.second.contains(t.second()) {
+                t += Duration::seconds(1);
+            } else {
+                return Some(t);
+            }
+        }
+        None
+    }
+}
+
+/// 已注册的定时任务
+#[derive(Debug, Clone)]
+pub(crate) struct ScheduledJob {
+    pub id: usize,
+    pub expr: String,
+    pub schedule: CronSchedule,
+    pub func: Value,
+    pub next_run: NaiveDateTime,
+}
+
+/// 当前本地时间
+pub(crate) fn now() -> NaiveDateTime {
+    chrono::Local::now().naive_local()
+}
+
+/// 校验 SCHEDULE 的参数，返回解析后的表达式和首次执行时间
+pub(crate) fn parse_job(
+    args: &[Value],
+) -> Result<(String, CronSchedule, NaiveDateTime), RuntimeError> {
+    if args.len() != 2 {
+        return Err(RuntimeError::WrongArity {
+            expected: 2,
+            got: args.len(),
+        });
+    }
+    let expr = match &args[0] {
+        Value::String(s) => s.clone(),
+        other => {
+            return Err(RuntimeError::TypeErrorDetailed {
+                expected: "String".to_string(),
+                got: other.type_name().to_string(),
+            });
+        }
+    };
+    if !matches!(args[1], Value::Function { .. } | Value::BuiltIn { .. }) {
+        return Err(RuntimeError::TypeErrorDetailed {
+            expected: "Function".to_string(),
+            got: args[1].type_name().to_string(),
+        });
+    }
+    let schedule = CronSchedule::parse(&expr).map_err(RuntimeError::InvalidOperation)?;
+    let next_run = schedule.next_after(now()).ok_or_else(|| {
+        RuntimeError::InvalidOperation(format!("cron expression '{}' never fires", expr))
+    })?;
+    Ok((expr, schedule, next_run))
+}
+
+/// 解析 RUN_SCHEDULER 的选项，返回最多执行的任务次数
+pub(crate) fn parse_run_options(args: &[Value]) -> Result<Option<usize>, RuntimeError> {
+    match args {
+        [] | [Value::Null] => Ok(None),
+        [Value::Dict(options)] => match options.get("max_runs") {
+            None | Some(Value::Null) => Ok(None),
+            Some(Value::Number(n)) if n.fract() == 0.0 && *n >= 1.0 => Ok(Some(*n as usize)),
+            Some(other) => Err(RuntimeError::InvalidOperation(format!(
+                "Option 'max_runs' must be a positive integer, got {}",
+                other
+            ))),
+        },
+        [other] => Err(RuntimeError::TypeErrorDetailed {
+            expected: "Dict".to_string(),
+            got: other.type_name().to_string(),
+        }),
+        _ => Err(RuntimeError::WrongArity {
+            expected: 1,
+            got: args.len(),
+        }),
+    }
+}
+
+/// RUN_SCHEDULER 的运行统计：`runs` 执行次数、`errors` 失败次数、
+/// `failures` 每次失败的 `{id, expr, error}`
+pub(crate) fn summary(runs: usize, failures: Vec<Value>) -> Value {
+    let mut dict = HashMap::new();
+    dict.insert("runs".to_string(), Value::Number(runs as f64));
+    dict.insert("errors".to_string(), Value::Number(failures.len() as f64));
+    dict.insert("failures".to_string(), Value::Array(failures));
+    Value::Dict(dict)
+}
+
+/// 一次失败的任务执行
+pub(crate) fn failure(id: usize, expr: &str, error: String) -> Value {
+    let mut dict = HashMap::new();
+    dict.insert("id".to_string(), Value::Number(id as f64));
+    dict.insert("expr".to_string(), Value::String(expr.to_string()));
+    dict.insert("error".to_string(), Value::String(error));
+    Value::Dict(dict)
+}
+
+/// 校验 SET_TIMEOUT / SET_INTERVAL / DEBOUNCE 的参数，返回延迟和回调
+pub(crate) fn parse_timer(args: &[Value]) -> Result<(std::time::Duration, Value), RuntimeError> {
+    if args.len() != 2 {
+        return Err(RuntimeError::WrongArity {
+            expected: 2,
+            got: args.len(),
+        });
+    }
+    let ms = match &args[0] {
+        Value::Number(n) if n.is_finite() && *n >= 0.0 => *n,
+        Value::Number(n) => {
+            return Err(RuntimeError::InvalidOperation(format!(
+                "Timer delay must be a non-negative number of milliseconds, got {}",
+                n
+            )));
+        }
+        other => {
+            return Err(RuntimeError::TypeErrorDetailed {
+                expected: "Number".to_string(),
+                got: other.type_name().to_string(),
+            });
+        }
+    };
+    if !matches!(args[1], Value::Function { .. } | Value::BuiltIn { .. }) {
+        return Err(RuntimeError::TypeErrorDetailed {
+            expected: "Function".to_string(),
+            got: args[1].type_name().to_string(),
+        });
+    }
+    let delay = std::time::Duration::try_from_secs_f64(ms / 1000.0).map_err(|_| {
+        RuntimeError::InvalidOperation(format!("Timer delay {} ms is too large", ms))
+    })?;
+    Ok((delay, args[1].clone()))
+}
+
+/// 占位实现：SCHEDULE 需要保存 Aether 函数，由求值器处理
+pub fn schedule(_args: &[Value]) -> Result<Value, RuntimeError> {
+    Err(RuntimeError::InvalidOperation(
+        "SCHEDULE requires function evaluation context".to_string(),
+    ))
+}
+
+/// 占位实现：UNSCHEDULE 由求值器处理
+pub fn unschedule(_args: &[Value]) -> Result<Value, RuntimeError> {
+    Err(RuntimeError::InvalidOperation(
+        "UNSCHEDULE requires function evaluation context".to_string(),
+    ))
+}
+
+/// 占位实现：RUN_SCHEDULER 需要调用 Aether 函数，由求值器处理
+pub fn run_scheduler(_args: &[Value]) -> Result<Value, RuntimeError> {
+    Err(RuntimeError::InvalidOperation(
+        "RUN_SCHEDULER requires function evaluation context".to_string(),
+    ))
+}
+
+/// 占位实现：SET_TIMEOUT 需要保存 Aether 函数，由求值器处理
+pub fn set_timeout(_args: &[Value]) -> Result<Value, RuntimeError> {
+    Err(RuntimeError::InvalidOperation(
+        "SET_TIMEOUT requires function evaluation context".to_string(),
+    ))
+}
+
+/// 占位实现：SET_INTERVAL 需要保存 Aether 函数，由求值器处理
+pub fn set_interval(_args: &[Value]) -> Result<Value, RuntimeError> {
+    Err(RuntimeError::InvalidOperation(
+        "SET_INTERVAL requires function evaluation context".to_string(),
+    ))
+}
+
+/// 占位实现：DEBOUNCE 需要保存 Aether 函数，由求值器处理
+pub fn debounce(_args: &[Value]) -> Result<Value, RuntimeError> {
+    Err(RuntimeError::InvalidOperation(
+        "DEBOUNCE requires function evaluation context".to_string(),
+    ))
+}
+
+/// 占位实现：CLEAR_TIMER 由求值器处理
+pub fn clear_timer(_args: &[Value]) -> Result<Value, RuntimeError> {
+    Err(RuntimeError::InvalidOperation(
+        "CLEAR_TIMER requires function evaluation context".to_string(),
+    ))
+}
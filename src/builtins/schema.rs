@@ -0,0 +1,530 @@
+// src/builtins/schema.rs
+//! Schema validation built-in functions
+//!
+//! `VALIDATE(value, schema)` 按类似 JSON Schema 的字典描述校验值，返回错误列表（空数组表示通过）。
+//! 支持的关键字：
+//!
+//! - 通用：`type`（字符串或字符串数组）、`enum`、`const`、`anyOf`、`allOf`
+//! - 字典：`required`、`properties`、`additionalProperties`（Boolean 或子模式）
+//! - 数组：`items`、`minItems`、`maxItems`、`uniqueItems`
+//! - 数字：`minimum`、`maximum`、`exclusiveMinimum`、`exclusiveMaximum`、`multipleOf`
+//! - 字符串：`minLength`、`maxLength`（按字符计）、`pattern`（正则，部分匹配，需要 `regex` 特性）、
+//!   `format`（`email` / `iban` / `date` / `date-time`）
+//!
+//! 类型名：`string`、`number`、`integer`、`boolean`、`null`、`array`、`object`（或 `dict`）、
+//! `set`、`table`、`function`、`any`。未知关键字会被忽略，格式错误的模式会报错。
+//!
+//! Rust 侧通过 [`validate_schema`] 使用同样的能力。
+
+use super::patch::{PathSegment, diff_values};
+use super::validation;
+use crate::evaluator::RuntimeError;
+use crate::value::Value;
+#[cfg(feature = "regex")]
+use regex::Regex;
+use std::collections::HashMap;
+
+/// 一条校验错误
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaViolation {
+    /// 出错的值在整体中的路径
+    pub path: Vec<PathSegment>,
+    /// 未通过的关键字，如 `required`、`minimum`
+    pub keyword: String,
+    /// 错误描述
+    pub message: String,
+}
+
+impl SchemaViolation {
+    /// 路径的可读形式，如 `$.items[2].name`
+    pub fn path_string(&self) -> String {
+        let mut path = "$".to_string();
+        for segment in &self.path {
+            path.push_str(&segment.to_string());
+        }
+        path
+    }
+
+    /// 转换为脚本中使用的字典形式 `{"path", "keyword", "message"}`
+    pub fn to_value(&self) -> Value {
+        let mut dict = HashMap::new();
+        dict.insert("path".to_string(), Value::String(self.path_string()));
+        dict.insert("keyword".to_string(), Value::String(self.keyword.clone()));
+        dict.insert("message".to_string(), Value::String(self.message.clone()));
+        Value::Dict(dict)
+    }
+}
+
+/// 按模式校验值，返回所有校验错误（为空表示通过）
+///
+/// 模式本身不合法（如关键字类型错误、正则无法编译）时返回 `Err`。
+pub fn validate_schema(
+    value: &Value,
+    schema: &Value,
+) -> Result<Vec<SchemaViolation>, RuntimeError> {
+    let mut validator = Validator {
+        #[cfg(feature = "regex")]
+        patterns: HashMap::new(),
+        path: Vec::new(),
+        violations: Vec::new(),
+    };
+    validator.check(value, schema)?;
+    Ok(validator.violations)
+}
+
+/// 模式格式错误
+fn schema_error(message: String) -> RuntimeError {
+    RuntimeError::InvalidOperation(format!("Invalid schema: {}", message))
+}
+
+/// 模式中非负整数关键字的值
+fn count_keyword(
+    schema: &HashMap<String, Value>,
+    keyword: &str,
+) -> Result<Option<usize>, RuntimeError> {
+    match schema.get(keyword) {
+        None => Ok(None),
+        Some(Value::Number(n)) if *n >= 0.0 && n.fract() == 0.0 => Ok(Some(*n as usize)),
+        Some(other) => Err(schema_error(format!(
+            "'{}' must be a non-negative integer, got {}",
+            keyword, other
+        ))),
+    }
+}
+
+/// 模式中数字关键字的值
+fn number_keyword(
+    schema: &HashMap<String, Value>,
+    keyword: &str,
+) -> Result<Option<f64>, RuntimeError> {
+    match schema.get(keyword) {
+        None => Ok(None),
+        Some(value) => value
+            .to_number()
+            .map(Some)
+            .ok_or_else(|| schema_error(format!("'{}' must be a Number, got {}", keyword, value))),
+    }
+}
+
+/// 模式中子模式数组关键字的值
+fn schemas_keyword<'a>(
+    schema: &'a HashMap<String, Value>,
+    keyword: &str,
+) -> Result<Option<&'a [Value]>, RuntimeError> {
+    match schema.get(keyword) {
+        None => Ok(None),
+        Some(Value::Array(items)) if !items.is_empty() => Ok(Some(items)),
+        Some(_) => Err(schema_error(format!(
+            "'{}' must be a non-empty Array of schemas",
+            keyword
+        ))),
+    }
+}
+
+/// 值是否属于模式中的类型名
+fn matches_type(value: &Value, type_name: &str) -> Result<bool, RuntimeError> {
+    Ok(match type_name {
+        "string" => matches!(value, Value::String(_)),
+        "number" => matches!(value, Value::Number(_) | Value::Fraction(_)),
+        "integer" => match value {
+            Value::Number(n) => n.is_finite() && n.fract() == 0.0,
+            Value::Fraction(f) => f.is_integer(),
+            _ => false,
+        },
+        "boolean" => matches!(value, Value::Boolean(_)),
+        "null" => matches!(value, Value::Null),
+        "array" => matches!(value, Value::Array(_)),
+        "object" | "dict" => matches!(value, Value::Dict(_)),
+        "set" => matches!(value, Value::Set(_)),
+        "table" => matches!(value, Value::Table(_)),
+        "datetime" => matches!(value, Value::DateTime(_)),
+        "function" => matches!(
+            value,
+            Value::Function { .. } | Value::BuiltIn { .. } | Value::Generator { .. }
+        ),
+        "any" => true,
+        other => return Err(schema_error(format!("unknown type '{}'", other))),
+    })
+}
+
+/// 字符串是否符合 `format`
+fn matches_format(text: &str, format: &str) -> Result<bool, RuntimeError> {
+    let as_bool = |result: Result<Value, RuntimeError>| matches!(result, Ok(Value::Boolean(true)));
+    let arg = [Value::String(text.to_string())];
+    Ok(match format {
+        "email" => as_bool(validation::validate_email(&arg)),
+        "iban" => as_bool(validation::validate_iban(&arg)),
+        "date" => chrono::NaiveDate::parse_from_str(text, "%Y-%m-%d").is_ok(),
+        "date-time" => chrono::DateTime::parse_from_rfc3339(text).is_ok(),
+        other => return Err(schema_error(format!("unknown format '{}'", other))),
+    })
+}
+
+/// 两个值是否结构相等（字典按内容比较）
+fn same_value(a: &Value, b: &Value) -> bool {
+    a.type_name() == b.type_name() && diff_values(a, b).is_empty()
+}
+
+struct Validator {
+    /// 已编译的 `pattern`，同一模式在数组元素间复用
+    #[cfg(feature = "regex")]
+    patterns: HashMap<String, Regex>,
+    path: Vec<PathSegment>,
+    violations: Vec<SchemaViolation>,
+}
+
+impl Validator {
+    fn fail(&mut self, keyword: &str, message: String) {
+        self.violations.push(SchemaViolation {
+            path: self.path.clone(),
+            keyword: keyword.to_string(),
+            message,
+        });
+    }
+
+    /// 在子模式上校验，只返回是否通过，不记录错误
+    fn passes(&mut self, value: &Value, schema: &Value) -> Result<bool, RuntimeError> {
+        let before = self.violations.len();
+        self.check(value, schema)?;
+        let passed = self.violations.len() == before;
+        self.violations.truncate(before);
+        Ok(passed)
+    }
+
+    fn check(&mut self, value: &Value, schema: &Value) -> Result<(), RuntimeError> {
+        let schema = match schema {
+            Value::Dict(dict) => dict,
+            other => {
+                return Err(schema_error(format!(
+                    "schema must be a Dict, got {}",
+                    other.type_name()
+                )));
+            }
+        };
+
+        if let Some(types) = schema.get("type") {
+            let names: Vec<&str> = match types {
+                Value::String(name) => vec![name.as_str()],
+                Value::Array(items) => items
+                    .iter()
+                    .map(|item| match item {
+                        Value::String(name) => Ok(name.as_str()),
+                        _ => Err(schema_error("'type' must contain only Strings".to_string())),
+                    })
+                    .collect::<Result<_, _>>()?,
+                _ => {
+                    return Err(schema_error(
+                        "'type' must be a String or an Array of Strings".to_string(),
+                    ));
+                }
+            };
+            let mut matched = false;
+            for name in &names {
+                matched |= matches_type(value, name)?;
+            }
+            if !matched {
+                self.fail(
+                    "type",
+                    format!("expected {}, got {}", names.join(" or "), value.type_name()),
+                );
+                // 类型不符时其他关键字没有意义
+                return Ok(());
+            }
+        }
+
+        if let Some(options) = schema.get("enum") {
+            let Value::Array(options) = options else {
+                return Err(schema_error("'enum' must be an Array".to_string()));
+            };
+            if !options.iter().any(|option| same_value(value, option)) {
+                let listed: Vec<String> = options.iter().map(|o| o.to_string()).collect();
+                self.fail(
+                    "enum",
+                    format!("{} is not one of [{}]", value, listed.join(", ")),
+                );
+            }
+        }
+
+        if let Some(expected) = schema.get("const")
+            && !same_value(value, expected)
+        {
+            self.fail("const", format!("expected {}, got {}", expected, value));
+        }
+
+        if let Some(branches) = schemas_keyword(schema, "anyOf")? {
+            let mut any = false;
+            for branch in branches {
+                if self.passes(value, branch)? {
+                    any = true;
+                    break;
+                }
+            }
+            if !any {
+                self.fail(
+                    "anyOf",
+                    "value does not match any of the schemas".to_string(),
+                );
+            }
+        }
+
+        if let Some(branches) = schemas_keyword(schema, "allOf")? {
+            for branch in branches {
+                self.check(value, branch)?;
+            }
+        }
+
+        match value {
+            Value::Dict(dict) => self.check_dict(dict, schema)?,
+            Value::Array(items) => self.check_array(items, schema)?,
+            Value::String(text) => self.check_string(text, schema)?,
+            Value::Number(_) | Value::Fraction(_) => self.check_number(value, schema)?,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn check_dict(
+        &mut self,
+        dict: &HashMap<String, Value>,
+        schema: &HashMap<String, Value>,
+    ) -> Result<(), RuntimeError> {
+        if let Some(required) = schema.get("required") {
+            let Value::Array(keys) = required else {
+                return Err(schema_error(
+                    "'required' must be an Array of Strings".to_string(),
+                ));
+            };
+            for key in keys {
+                let Value::String(key) = key else {
+                    return Err(schema_error(
+                        "'required' must be an Array of Strings".to_string(),
+                    ));
+                };
+                if !dict.contains_key(key) {
+                    self.fail("required", format!("missing required key '{}'", key));
+                }
+            }
+        }
+
+        let properties = match schema.get("properties") {
+            None => None,
+            Some(Value::Dict(properties)) => Some(properties),
+            Some(_) => return Err(schema_error("'properties' must be a Dict".to_string())),
+        };
+
+        let mut keys: Vec<&String> = dict.keys().collect();
+        keys.sort();
+        for key in keys {
+            let property = properties.and_then(|p| p.get(key));
+            self.path.push(PathSegment::Key(key.clone()));
+            match (property, schema.get("additionalProperties")) {
+                (Some(sub), _) => self.check(&dict[key], sub)?,
+                (None, None | Some(Value::Boolean(true))) => {}
+                (None, Some(Value::Boolean(false))) => {
+                    self.fail("additionalProperties", format!("unexpected key '{}'", key));
+                }
+                (None, Some(sub @ Value::Dict(_))) => self.check(&dict[key], sub)?,
+                (None, Some(_)) => {
+                    return Err(schema_error(
+                        "'additionalProperties' must be a Boolean or a schema".to_string(),
+                    ));
+                }
+            }
+            self.path.pop();
+        }
+        Ok(())
+    }
+
+    fn check_array(
+        &mut self,
+        items: &[Value],
+        schema: &HashMap<String, Value>,
+    ) -> Result<(), RuntimeError> {
+        if let Some(min) = count_keyword(schema, "minItems")?
+            && items.len() < min
+        {
+            self.fail(
+                "minItems",
+                format!("expected at least {} items, got {}", min, items.len()),
+            );
+        }
+        if let Some(max) = count_keyword(schema, "maxItems")?
+            && items.len() > max
+        {
+            self.fail(
+                "maxItems",
+                format!("expected at most {} items, got {}", max, items.len()),
+            );
+        }
+        if matches!(schema.get("uniqueItems"), Some(Value::Boolean(true))) {
+            for (i, item) in items.iter().enumerate() {
+                if let Some(first) = items[..i].iter().position(|x| same_value(x, item)) {
+                    self.path.push(PathSegment::Index(i));
+                    self.fail(
+                        "uniqueItems",
+                        format!("duplicate of item at index {}", first),
+                    );
+                    self.path.pop();
+                }
+            }
+        }
+        if let Some(item_schema) = schema.get("items") {
+            for (i, item) in items.iter().enumerate() {
+                self.path.push(PathSegment::Index(i));
+                self.check(item, item_schema)?;
+                self.path.pop();
+            }
+        }
+        Ok(())
+    }
+
+    fn check_string(
+        &mut self,
+        text: &str,
+        schema: &HashMap<String, Value>,
+    ) -> Result<(), RuntimeError> {
+        let length = text.chars().count();
+        if let Some(min) = count_keyword(schema, "minLength")?
+            && length < min
+        {
+            self.fail(
+                "minLength",
+                format!("expected at least {} characters, got {}", min, length),
+            );
+        }
+        if let Some(max) = count_keyword(schema, "maxLength")?
+            && length > max
+        {
+            self.fail(
+                "maxLength",
+                format!("expected at most {} characters, got {}", max, length),
+            );
+        }
+        match schema.get("pattern") {
+            None => {}
+            #[cfg(feature = "regex")]
+            Some(Value::String(pattern)) => {
+                if !self.patterns.contains_key(pattern) {
+                    let regex = Regex::new(pattern).map_err(|e| {
+                        schema_error(format!("invalid pattern '{}': {}", pattern, e))
+                    })?;
+                    self.patterns.insert(pattern.clone(), regex);
+                }
+                if !self.patterns[pattern].is_match(text) {
+                    self.fail(
+                        "pattern",
+                        format!("\"{}\" does not match pattern '{}'", text, pattern),
+                    );
+                }
+            }
+            #[cfg(not(feature = "regex"))]
+            Some(Value::String(_)) => {
+                return Err(schema_error(
+                    "'pattern' requires the regex feature".to_string(),
+                ));
+            }
+            Some(_) => return Err(schema_error("'pattern' must be a String".to_string())),
+        }
+        match schema.get("format") {
+            None => {}
+            Some(Value::String(format)) if !matches_format(text, format)? => {
+                self.fail("format", format!("\"{}\" is not a valid {}", text, format));
+            }
+            Some(Value::String(_)) => {}
+            Some(_) => return Err(schema_error("'format' must be a String".to_string())),
+        }
+        Ok(())
+    }
+
+    fn check_number(
+        &mut self,
+        value: &Value,
+        schema: &HashMap<String, Value>,
+    ) -> Result<(), RuntimeError> {
+        let Some(n) = value.to_number() else {
+            return Ok(());
+        };
+        if let Some(min) = number_keyword(schema, "minimum")?
+            && n < min
+        {
+            self.fail("minimum", format!("{} is less than {}", value, min));
+        }
+        if let Some(max) = number_keyword(schema, "maximum")?
+            && n > max
+        {
+            self.fail("maximum", format!("{} is greater than {}", value, max));
+        }
+        if let Some(min) = number_keyword(schema, "exclusiveMinimum")?
+            && n <= min
+        {
+            self.fail(
+                "exclusiveMinimum",
+                format!("{} must be greater than {}", value, min),
+            );
+        }
+        if let Some(max) = number_keyword(schema, "exclusiveMaximum")?
+            && n >= max
+        {
+            self.fail(
+                "exclusiveMaximum",
+                format!("{} must be less than {}", value, max),
+            );
+        }
+        if let Some(step) = number_keyword(schema, "multipleOf")? {
+            if step <= 0.0 {
+                return Err(schema_error("'multipleOf' must be positive".to_string()));
+            }
+            let quotient = n / step;
+            if (quotient - quotient.round()).abs() > 1e-9 {
+                self.fail(
+                    "multipleOf",
+                    format!("{} is not a multiple of {}", value, step),
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 按模式校验值
+///
+/// # 功能
+/// 使用类似 JSON Schema 的字典描述校验值（类型、必填键、取值范围、正则、嵌套数组等），
+/// 收集所有错误而不是在第一个错误处停止。支持的关键字见模块文档。
+///
+/// # 参数
+/// - `value`: Any - 待校验的值
+/// - `schema`: Dict - 模式
+///
+/// # 返回值
+/// Array - 错误列表，每项为 `{"path", "keyword", "message"}` 字典；通过时为空数组。
+/// 模式本身不合法时报错。
+///
+/// # 示例
+/// ```aether
+/// Set SCHEMA {
+///     "type": "object",
+///     "required": ["name", "age"],
+///     "properties": {
+///         "name": {"type": "string", "minLength": 1},
+///         "age": {"type": "integer", "minimum": 0},
+///         "tags": {"type": "array", "items": {"type": "string", "pattern": "^[a-z]+$"}}
+///     }
+/// }
+/// VALIDATE({"name": "", "tags": ["ok", "Bad"]}, SCHEMA)
+/// # [{"path": "$", "keyword": "required", "message": "missing required key 'age'"},
+/// #  {"path": "$.name", "keyword": "minLength", ...},
+/// #  {"path": "$.tags[1]", "keyword": "pattern", ...}]
+/// ```
+pub fn validate(args: &[Value]) -> Result<Value, RuntimeError> {
+    if args.len() != 2 {
+        return Err(RuntimeError::WrongArity {
+            expected: 2,
+            got: args.len(),
+        });
+    }
+    let violations = validate_schema(&args[0], &args[1])?;
+    Ok(Value::Array(
+        violations.iter().map(SchemaViolation::to_value).collect(),
+    ))
+}
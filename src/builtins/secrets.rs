@@ -0,0 +1,29 @@
+// src/builtins/secrets.rs
+//! 机密值内置函数
+//!
+//! 注意：SECRET 和 MARK_SECRET 在 evaluator 中有特殊处理：SECRET 通过宿主注册的
+//! 解析回调取值，两者登记的值都会在输出中脱敏。
+
+use crate::evaluator::RuntimeError;
+use crate::value::Value;
+
+/// SECRET - 从宿主的机密提供者读取机密
+///
+/// 用法: SECRET("api_key") -> String
+///
+/// 宿主未注册提供者或名称未知时报错；读取到的值在输出中显示为 `***`（同 MARK_SECRET）。
+pub fn secret(_args: &[Value]) -> Result<Value, RuntimeError> {
+    // 在 evaluator 中有特殊处理
+    Ok(Value::Null)
+}
+
+/// MARK_SECRET - 把值标记为敏感
+///
+/// 用法: MARK_SECRET(value) -> value
+///
+/// 值中的字符串和数字（包括数组、字典的元素）之后出现在 TRACE、错误信息、
+/// PRINT / DISPLAY 输出中时显示为 `***`。返回原值，不改变计算结果。
+pub fn mark_secret(_args: &[Value]) -> Result<Value, RuntimeError> {
+    // 在 evaluator 中有特殊处理
+    Ok(Value::Null)
+}
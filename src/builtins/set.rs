@@ -0,0 +1,187 @@
+// src/builtins/set.rs
+//! Set built-in functions
+//!
+//! 原生集合类型（`Value::Set`）基于哈希表实现，成员判断为 O(1)。
+//! 集合元素只能是标量值：Number、Fraction、String、Boolean、Null。
+
+use crate::evaluator::RuntimeError;
+use crate::value::{SetKey, Value};
+use std::collections::HashSet;
+
+/// 将值转换为集合元素
+fn to_key(value: &Value) -> Result<SetKey, RuntimeError> {
+    SetKey::from_value(value).ok_or_else(|| {
+        RuntimeError::InvalidOperation(format!(
+            "Set elements must be Number, Fraction, String, Boolean or Null, got {}",
+            value.type_name()
+        ))
+    })
+}
+
+/// 将 Set 或 Array 转换为集合（数组会自动去重）
+pub(crate) fn to_set(value: &Value) -> Result<HashSet<SetKey>, RuntimeError> {
+    match value {
+        Value::Set(set) => Ok(set.clone()),
+        Value::Array(arr) => arr.iter().map(to_key).collect(),
+        other => Err(RuntimeError::TypeErrorDetailed {
+            expected: "Set or Array".to_string(),
+            got: other.type_name().to_string(),
+        }),
+    }
+}
+
+/// 判断集合是否包含某个值
+pub(crate) fn set_contains(set: &HashSet<SetKey>, value: &Value) -> bool {
+    SetKey::from_value(value).is_some_and(|key| set.contains(&key))
+}
+
+/// 创建集合
+///
+/// # 功能
+/// 创建一个原生集合，重复元素自动去除。
+///
+/// # 参数
+/// - 无参数：空集合
+/// - 一个数组或集合：以其元素创建集合
+/// - 多个参数：每个参数作为一个元素
+///
+/// # 返回值
+/// Set - 新集合
+///
+/// # 示例
+/// ```aether
+/// Set S SET([1, 2, 2, 3])     # Set{1, 2, 3}
+/// Set T SET("a", "b")         # Set{a, b}
+/// Set E SET()                 # Set{}
+/// ```
+pub fn set(args: &[Value]) -> Result<Value, RuntimeError> {
+    match args {
+        [] => Ok(Value::Set(HashSet::new())),
+        [single @ (Value::Array(_) | Value::Set(_))] => Ok(Value::Set(to_set(single)?)),
+        items => Ok(Value::Set(
+            items.iter().map(to_key).collect::<Result<_, _>>()?,
+        )),
+    }
+}
+
+/// 并集
+///
+/// # 功能
+/// 返回包含所有参数中全部元素的新集合。
+///
+/// # 参数
+/// - 两个或多个集合（或数组）
+///
+/// # 返回值
+/// Set - 并集
+///
+/// # 示例
+/// ```aether
+/// UNION(SET([1, 2]), SET([2, 3]))     # Set{1, 2, 3}
+/// ```
+pub fn union(args: &[Value]) -> Result<Value, RuntimeError> {
+    if args.len() < 2 {
+        return Err(RuntimeError::WrongArity {
+            expected: 2,
+            got: args.len(),
+        });
+    }
+
+    let mut result = to_set(&args[0])?;
+    for other in &args[1..] {
+        result.extend(to_set(other)?);
+    }
+    Ok(Value::Set(result))
+}
+
+/// 交集
+///
+/// # 功能
+/// 返回所有参数共有元素组成的新集合。
+///
+/// # 参数
+/// - 两个或多个集合（或数组）
+///
+/// # 返回值
+/// Set - 交集
+///
+/// # 示例
+/// ```aether
+/// INTERSECT(SET([1, 2, 3]), SET([2, 3, 4]))     # Set{2, 3}
+/// ```
+pub fn intersect(args: &[Value]) -> Result<Value, RuntimeError> {
+    if args.len() < 2 {
+        return Err(RuntimeError::WrongArity {
+            expected: 2,
+            got: args.len(),
+        });
+    }
+
+    let mut result = to_set(&args[0])?;
+    for other in &args[1..] {
+        let other = to_set(other)?;
+        result.retain(|key| other.contains(key));
+    }
+    Ok(Value::Set(result))
+}
+
+/// 差集
+///
+/// # 功能
+/// 返回在第一个集合中、但不在其余任何集合中的元素。
+///
+/// # 参数
+/// - 两个或多个集合（或数组）
+///
+/// # 返回值
+/// Set - 差集
+///
+/// # 示例
+/// ```aether
+/// DIFFERENCE(SET([1, 2, 3]), SET([2]))     # Set{1, 3}
+/// ```
+pub fn difference(args: &[Value]) -> Result<Value, RuntimeError> {
+    if args.len() < 2 {
+        return Err(RuntimeError::WrongArity {
+            expected: 2,
+            got: args.len(),
+        });
+    }
+
+    let mut result = to_set(&args[0])?;
+    for other in &args[1..] {
+        let other = to_set(other)?;
+        result.retain(|key| !other.contains(key));
+    }
+    Ok(Value::Set(result))
+}
+
+/// 子集判断
+///
+/// # 功能
+/// 判断第一个集合是否是第二个集合的子集。
+///
+/// # 参数
+/// - `a`: Set/Array - 候选子集
+/// - `b`: Set/Array - 候选超集
+///
+/// # 返回值
+/// Boolean - a 的所有元素都在 b 中时为 True
+///
+/// # 示例
+/// ```aether
+/// SUBSET(SET([1, 2]), SET([1, 2, 3]))     # True
+/// SUBSET(SET([1, 4]), SET([1, 2, 3]))     # False
+/// ```
+pub fn subset(args: &[Value]) -> Result<Value, RuntimeError> {
+    if args.len() != 2 {
+        return Err(RuntimeError::WrongArity {
+            expected: 2,
+            got: args.len(),
+        });
+    }
+
+    let a = to_set(&args[0])?;
+    let b = to_set(&args[1])?;
+    Ok(Value::Boolean(a.is_subset(&b)))
+}
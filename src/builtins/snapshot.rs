@@ -0,0 +1,20 @@
+// src/builtins/snapshot.rs
+//! 快照测试内置函数
+//!
+//! 注意：SNAPSHOT_MATCH 在 evaluator 中有特殊处理，快照目录由宿主配置
+//! （`Aether::set_snapshot_dir` 或 `aether test`），见 `runtime::snapshot`。
+
+use crate::evaluator::RuntimeError;
+use crate::value::Value;
+
+/// SNAPSHOT_MATCH - 把值与磁盘上的快照比较
+///
+/// 用法: SNAPSHOT_MATCH("user_report", value) -> Dict
+///
+/// 返回 `{"status", "path", "expected"}`：status 为 `matched`、`created`（快照不存在，已写入）、
+/// `updated`（更新模式下已覆盖）或 `mismatch`（此时 expected 为快照中的值）。
+/// 通常通过 testing 标准库的 `EXPECT_SNAPSHOT(name, value)` 使用。
+pub fn snapshot_match(_args: &[Value]) -> Result<Value, RuntimeError> {
+    // 在 evaluator 中有特殊处理
+    Ok(Value::Null)
+}
@@ -197,8 +197,11 @@ pub fn contains(args: &[Value]) -> Result<Value, RuntimeError> {
         }
         // Dict contains key
         (Value::Dict(dict), Value::String(key)) => Ok(Value::Boolean(dict.contains_key(key))),
+        // Set contains element (O(1))
+        (Value::Set(set), item) => Ok(Value::Boolean(super::set::set_contains(set, item))),
         _ => Err(RuntimeError::TypeErrorDetailed {
-            expected: "(String, String) or (Array, Any) or (Dict, String)".to_string(),
+            expected: "(String, String) or (Array, Any) or (Dict, String) or (Set, Any)"
+                .to_string(),
             got: format!("{:?}, {:?}", args[0], args[1]),
         }),
     }
@@ -586,3 +589,284 @@ pub fn char_at(args: &[Value]) -> Result<Value, RuntimeError> {
         }),
     }
 }
+
+/// 读取两个字符串参数
+fn string_pair(args: &[Value]) -> Result<(&str, &str), RuntimeError> {
+    if args.len() != 2 {
+        return Err(RuntimeError::WrongArity {
+            expected: 2,
+            got: args.len(),
+        });
+    }
+
+    match (&args[0], &args[1]) {
+        (Value::String(a), Value::String(b)) => Ok((a, b)),
+        _ => Err(RuntimeError::TypeErrorDetailed {
+            expected: "String, String".to_string(),
+            got: format!("{}, {}", args[0].type_name(), args[1].type_name()),
+        }),
+    }
+}
+
+/// 按字符计算编辑距离（两行动态规划，O(n·m) 时间、O(m) 空间）
+fn levenshtein_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+/// Jaro-Winkler 相似度（0 到 1，共同前缀最多计 4 个字符，权重 0.1）
+fn jaro_winkler_similarity(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    let range = (a.len().max(b.len()) / 2).saturating_sub(1);
+    let mut a_matched = vec![false; a.len()];
+    let mut b_matched = vec![false; b.len()];
+    let mut matches = 0usize;
+    for (i, ca) in a.iter().enumerate() {
+        let start = i.saturating_sub(range);
+        let end = (i + range + 1).min(b.len());
+        for j in start..end {
+            if !b_matched[j] && b[j] == *ca {
+                a_matched[i] = true;
+                b_matched[j] = true;
+                matches += 1;
+                break;
+            }
+        }
+    }
+    if matches == 0 {
+        return 0.0;
+    }
+
+    // 匹配字符中顺序不同的对数的一半即换位数
+    let a_seq = a
+        .iter()
+        .zip(&a_matched)
+        .filter(|(_, m)| **m)
+        .map(|(c, _)| c);
+    let b_seq = b
+        .iter()
+        .zip(&b_matched)
+        .filter(|(_, m)| **m)
+        .map(|(c, _)| c);
+    let transpositions = a_seq.zip(b_seq).filter(|(x, y)| x != y).count() / 2;
+
+    let m = matches as f64;
+    let jaro = (m / a.len() as f64 + m / b.len() as f64 + (m - transpositions as f64) / m) / 3.0;
+    let prefix = a.iter().zip(&b).take(4).take_while(|(x, y)| x == y).count();
+    jaro + prefix as f64 * 0.1 * (1.0 - jaro)
+}
+
+/// 编辑距离
+///
+/// # 功能
+/// 计算两个字符串之间的 Levenshtein 编辑距离，即把一个字符串变成另一个
+/// 所需的最少单字符插入、删除、替换次数（按字符计算，支持中文）。
+///
+/// # 参数
+/// - `a`: String - 第一个字符串
+/// - `b`: String - 第二个字符串
+///
+/// # 返回值
+/// Number - 编辑距离
+///
+/// # 示例
+/// ```aether
+/// Set d Levenshtein("kitten", "sitting")     # 3
+/// Set d Levenshtein("北京市", "北京")          # 1
+/// ```
+pub fn levenshtein(args: &[Value]) -> Result<Value, RuntimeError> {
+    let (a, b) = string_pair(args)?;
+    Ok(Value::Number(levenshtein_distance(a, b) as f64))
+}
+
+/// Jaro-Winkler 相似度
+///
+/// # 功能
+/// 计算两个字符串的 Jaro-Winkler 相似度，共同前缀越长得分越高，
+/// 适合比较人名、短文本。
+///
+/// # 参数
+/// - `a`: String - 第一个字符串
+/// - `b`: String - 第二个字符串
+///
+/// # 返回值
+/// Number - 0 到 1 之间的相似度，1 表示完全相同
+///
+/// # 示例
+/// ```aether
+/// Set s Jaro_winkler("MARTHA", "MARHTA")     # 0.9611
+/// Set s Jaro_winkler("abc", "xyz")           # 0
+/// ```
+pub fn jaro_winkler(args: &[Value]) -> Result<Value, RuntimeError> {
+    let (a, b) = string_pair(args)?;
+    Ok(Value::Number(jaro_winkler_similarity(a, b)))
+}
+
+/// Soundex 编码
+///
+/// # 功能
+/// 计算英文单词的 American Soundex 语音编码（首字母 + 3 位数字），
+/// 发音相近的单词编码相同。非 ASCII 字母的字符会被忽略。
+///
+/// # 参数
+/// - `word`: String - 英文单词
+///
+/// # 返回值
+/// String - 4 位编码，不含字母时返回空字符串
+///
+/// # 示例
+/// ```aether
+/// Set a Soundex("Robert")      # "R163"
+/// Set b Soundex("Rupert")      # "R163"
+/// Set c Soundex("Tymczak")     # "T522"
+/// ```
+pub fn soundex(args: &[Value]) -> Result<Value, RuntimeError> {
+    if args.len() != 1 {
+        return Err(RuntimeError::WrongArity {
+            expected: 1,
+            got: args.len(),
+        });
+    }
+
+    let word = match &args[0] {
+        Value::String(s) => s,
+        other => {
+            return Err(RuntimeError::TypeErrorDetailed {
+                expected: "String".to_string(),
+                got: other.type_name().to_string(),
+            });
+        }
+    };
+
+    fn code(c: char) -> Option<char> {
+        match c {
+            'B' | 'F' | 'P' | 'V' => Some('1'),
+            'C' | 'G' | 'J' | 'K' | 'Q' | 'S' | 'X' | 'Z' => Some('2'),
+            'D' | 'T' => Some('3'),
+            'L' => Some('4'),
+            'M' | 'N' => Some('5'),
+            'R' => Some('6'),
+            _ => None,
+        }
+    }
+
+    let mut letters = word
+        .chars()
+        .filter(char::is_ascii_alphabetic)
+        .map(|c| c.to_ascii_uppercase());
+    let Some(first) = letters.next() else {
+        return Ok(Value::String(String::new()));
+    };
+
+    let mut result = String::from(first);
+    let mut last = code(first);
+    for c in letters {
+        let current = code(c);
+        if current.is_some() && current != last {
+            result.extend(current);
+            if result.len() == 4 {
+                break;
+            }
+        }
+        // H 和 W 不分隔相同编码，元音会分隔
+        if c != 'H' && c != 'W' {
+            last = current;
+        }
+    }
+    while result.len() < 4 {
+        result.push('0');
+    }
+    Ok(Value::String(result))
+}
+
+/// 模糊匹配
+///
+/// # 功能
+/// 在字符串数组中查找与目标相似的项，使用 Jaro-Winkler 相似度评分，
+/// 结果按得分从高到低排列（得分相同按原顺序）。
+///
+/// # 参数
+/// - `needle`: String - 要查找的字符串
+/// - `haystack`: Array - 候选字符串数组（非字符串元素会被跳过）
+/// - `threshold`: Number - 可选，最低相似度（0 到 1，默认 0.8）
+///
+/// # 返回值
+/// Array - 匹配项数组，每项为 `{"value": 候选, "index": 位置, "score": 相似度}`
+///
+/// # 示例
+/// ```aether
+/// Set names ["Jonathan", "Jon", "Johnathan", "Mary"]
+/// Set hits Fuzzy_match("Jonathon", names, 0.85)
+/// # [{value: Jonathan, index: 0, score: 0.95}, {value: Jon, index: 1, score: 0.854}]
+/// ```
+pub fn fuzzy_match(args: &[Value]) -> Result<Value, RuntimeError> {
+    if args.len() < 2 || args.len() > 3 {
+        return Err(RuntimeError::WrongArity {
+            expected: 3,
+            got: args.len(),
+        });
+    }
+
+    let (needle, haystack) = match (&args[0], &args[1]) {
+        (Value::String(n), Value::Array(h)) => (n, h),
+        _ => {
+            return Err(RuntimeError::TypeErrorDetailed {
+                expected: "String, Array".to_string(),
+                got: format!("{}, {}", args[0].type_name(), args[1].type_name()),
+            });
+        }
+    };
+    let threshold = match args.get(2) {
+        None => 0.8,
+        Some(Value::Number(t)) if (0.0..=1.0).contains(t) => *t,
+        Some(other) => {
+            return Err(RuntimeError::InvalidOperation(format!(
+                "Threshold must be a number between 0 and 1, got {}",
+                other
+            )));
+        }
+    };
+
+    let mut hits: Vec<(usize, &String, f64)> = haystack
+        .iter()
+        .enumerate()
+        .filter_map(|(index, candidate)| match candidate {
+            Value::String(s) => Some((index, s, jaro_winkler_similarity(needle, s))),
+            _ => None,
+        })
+        .filter(|(_, _, score)| *score >= threshold)
+        .collect();
+    hits.sort_by(|a, b| b.2.total_cmp(&a.2));
+
+    Ok(Value::Array(
+        hits.into_iter()
+            .map(|(index, value, score)| {
+                let mut hit = std::collections::HashMap::new();
+                hit.insert("value".to_string(), Value::String(value.clone()));
+                hit.insert("index".to_string(), Value::Number(index as f64));
+                hit.insert("score".to_string(), Value::Number(score));
+                Value::Dict(hit)
+            })
+            .collect(),
+    ))
+}
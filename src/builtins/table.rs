@@ -0,0 +1,839 @@
+// src/builtins/table.rs
+//! Table built-in functions
+//!
+//! 表格（`Value::Table`）按列存储，适合“字典数组”式的表格数据处理：
+//! 筛选、投影、分组聚合与连接都在列上完成，分组和连接使用哈希表，
+//! 不再需要对字典数组做 O(n) 的反复扫描。
+//!
+//! 单元格只能是标量值：Number、Fraction、String、Boolean、Null。
+
+use crate::evaluator::RuntimeError;
+use crate::value::{SetKey, Table, Value};
+use std::collections::{HashMap, HashSet};
+
+/// 读取表格参数
+pub(crate) fn expect_table(value: &Value) -> Result<&Table, RuntimeError> {
+    match value {
+        Value::Table(table) => Ok(table),
+        other => Err(RuntimeError::TypeErrorDetailed {
+            expected: "Table".to_string(),
+            got: other.type_name().to_string(),
+        }),
+    }
+}
+
+/// 表格构建错误
+fn table_error(message: String) -> RuntimeError {
+    RuntimeError::InvalidOperation(message)
+}
+
+/// 读取列名参数（单个字符串或字符串数组）
+fn column_names(value: &Value) -> Result<Vec<String>, RuntimeError> {
+    match value {
+        Value::String(s) => Ok(vec![s.clone()]),
+        Value::Array(items) => items
+            .iter()
+            .map(|item| match item {
+                Value::String(s) => Ok(s.clone()),
+                other => Err(RuntimeError::TypeErrorDetailed {
+                    expected: "String (column name)".to_string(),
+                    got: other.type_name().to_string(),
+                }),
+            })
+            .collect(),
+        other => Err(RuntimeError::TypeErrorDetailed {
+            expected: "String or Array of column names".to_string(),
+            got: other.type_name().to_string(),
+        }),
+    }
+}
+
+/// 列名转换为列位置，列不存在时报错
+fn column_indices(table: &Table, names: &[String]) -> Result<Vec<usize>, RuntimeError> {
+    names
+        .iter()
+        .map(|name| {
+            table
+                .column_index(name)
+                .ok_or_else(|| table_error(format!("Column '{}' not found", name)))
+        })
+        .collect()
+}
+
+/// 行在指定列上的键（用于分组与连接）
+fn row_key(table: &Table, row: usize, columns: &[usize]) -> Vec<SetKey> {
+    columns
+        .iter()
+        .map(|&c| SetKey::from_value(table.cell(row, c)).unwrap_or(SetKey::Null))
+        .collect()
+}
+
+/// 创建表格
+///
+/// # 功能
+/// 从字典数组（每个字典一行）或列字典（列名 → 数组）创建表格。
+///
+/// # 参数
+/// - `data`: Array/Dict - 行字典数组，或列名到列数组的字典
+/// - `columns`: Array - 列顺序（可选，默认按列名排序；行中缺少的列为 Null）
+///
+/// # 返回值
+/// Table - 新表格
+///
+/// # 示例
+/// ```aether
+/// Set T TABLE([{"name": "Alice", "age": 30}, {"name": "Bob", "age": 25}])
+/// Set T TABLE({"name": ["Alice", "Bob"], "age": [30, 25]}, ["name", "age"])
+/// ```
+pub fn table(args: &[Value]) -> Result<Value, RuntimeError> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(RuntimeError::WrongArity {
+            expected: 1,
+            got: args.len(),
+        });
+    }
+    let order = args.get(1).map(column_names).transpose()?;
+
+    let table = match &args[0] {
+        Value::Array(rows) => {
+            let dicts = rows
+                .iter()
+                .map(|row| match row {
+                    Value::Dict(dict) => Ok(dict),
+                    other => Err(RuntimeError::TypeErrorDetailed {
+                        expected: "Array of Dicts".to_string(),
+                        got: format!("Array containing {}", other.type_name()),
+                    }),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let columns = order.unwrap_or_else(|| {
+                let mut names: Vec<String> = dicts
+                    .iter()
+                    .flat_map(|d| d.keys().cloned())
+                    .collect::<HashSet<_>>()
+                    .into_iter()
+                    .collect();
+                names.sort();
+                names
+            });
+            let rows = dicts
+                .iter()
+                .map(|d| {
+                    columns
+                        .iter()
+                        .map(|c| d.get(c).cloned().unwrap_or(Value::Null))
+                        .collect()
+                })
+                .collect();
+            Table::from_rows(columns, rows)
+        }
+        Value::Dict(dict) => {
+            let columns = order.unwrap_or_else(|| {
+                let mut names: Vec<String> = dict.keys().cloned().collect();
+                names.sort();
+                names
+            });
+            let data = columns
+                .iter()
+                .map(|c| match dict.get(c) {
+                    Some(Value::Array(values)) => Ok(values.clone()),
+                    Some(other) => Err(RuntimeError::TypeErrorDetailed {
+                        expected: format!("Array for column '{}'", c),
+                        got: other.type_name().to_string(),
+                    }),
+                    None => Err(table_error(format!("Column '{}' not found", c))),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            Table::new(columns, data)
+        }
+        Value::Table(table) => match order {
+            Some(columns) => {
+                let indices = column_indices(table, &columns)?;
+                Ok(project(table, &indices))
+            }
+            None => Ok(table.clone()),
+        },
+        other => {
+            return Err(RuntimeError::TypeErrorDetailed {
+                expected: "Array of Dicts or Dict of Arrays".to_string(),
+                got: other.type_name().to_string(),
+            });
+        }
+    };
+
+    table.map(Value::Table).map_err(table_error)
+}
+
+/// 解析一行 CSV（支持双引号包裹的字段和 `""` 转义）
+pub(crate) fn split_csv_record(line: &str, delimiter: char) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            c if c == delimiter && !in_quotes => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+/// 将 CSV 文本切分为记录（引号内的换行属于字段内容）
+pub(crate) fn csv_records(content: &str) -> Vec<String> {
+    let mut records = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+
+    for line in content.lines() {
+        if !current.is_empty() || in_quotes {
+            current.push('\n');
+        }
+        current.push_str(line.strip_suffix('\r').unwrap_or(line));
+        in_quotes ^= line.matches('"').count() % 2 == 1;
+        if !in_quotes {
+            if !current.trim().is_empty() {
+                records.push(std::mem::take(&mut current));
+            }
+            current.clear();
+        }
+    }
+    if !current.trim().is_empty() {
+        records.push(current);
+    }
+    records
+}
+
+/// CSV 单元格转换为值：空白为 Null，数字转换为 Number
+fn csv_cell(cell: &str) -> Value {
+    let trimmed = cell.trim();
+    if trimmed.is_empty() {
+        return Value::Null;
+    }
+    match trimmed.parse::<f64>() {
+        Ok(n) if n.is_finite() => Value::Number(n),
+        _ => Value::String(cell.to_string()),
+    }
+}
+
+/// 从 CSV 文本创建表格
+///
+/// # 功能
+/// 解析 CSV 文本为表格。首行为表头，数字单元格自动转换为 Number，
+/// 空单元格为 Null。支持双引号包裹的字段（字段内可包含分隔符、换行和 `""`）。
+///
+/// # 参数
+/// - `text`: String - CSV 文本（读取文件请先使用 READ_FILE）
+/// - `options`: Dict - 可选：`delimiter`（分隔符，默认 ","）、
+///   `header`（首行是否为表头，默认 True；为 False 时列名为 col1、col2…）
+///
+/// # 返回值
+/// Table - 解析得到的表格
+///
+/// # 示例
+/// ```aether
+/// Set T TABLE_FROM_CSV("name,age\nAlice,30\nBob,25")
+/// Set T TABLE_FROM_CSV("a;b\n1;2", {"delimiter": ";"})
+/// ```
+pub fn table_from_csv(args: &[Value]) -> Result<Value, RuntimeError> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(RuntimeError::WrongArity {
+            expected: 1,
+            got: args.len(),
+        });
+    }
+
+    let text = match &args[0] {
+        Value::String(s) => s,
+        other => {
+            return Err(RuntimeError::TypeErrorDetailed {
+                expected: "String".to_string(),
+                got: other.type_name().to_string(),
+            });
+        }
+    };
+
+    let (delimiter, header) = match args.get(1) {
+        None | Some(Value::Null) => (',', true),
+        Some(Value::Dict(options)) => {
+            let delimiter = match options.get("delimiter") {
+                None => ',',
+                Some(Value::String(s)) if s.chars().count() == 1 => s.chars().next().unwrap_or(','),
+                Some(other) => {
+                    return Err(table_error(format!(
+                        "delimiter must be a single character, got {}",
+                        other
+                    )));
+                }
+            };
+            let header = options.get("header").is_none_or(Value::is_truthy);
+            (delimiter, header)
+        }
+        Some(other) => {
+            return Err(RuntimeError::TypeErrorDetailed {
+                expected: "Dict (options)".to_string(),
+                got: other.type_name().to_string(),
+            });
+        }
+    };
+
+    let mut records = csv_records(text)
+        .into_iter()
+        .map(|r| split_csv_record(&r, delimiter));
+    let first = records.next().unwrap_or_default();
+    let width = first.len();
+    let (columns, mut rows) = if header {
+        let columns = first.iter().map(|h| h.trim().to_string()).collect();
+        (columns, Vec::new())
+    } else {
+        let columns = (1..=width).map(|i| format!("col{}", i)).collect();
+        (columns, vec![first])
+    };
+    rows.extend(records);
+
+    let rows = rows
+        .into_iter()
+        .enumerate()
+        .map(|(index, mut row)| {
+            if row.len() > width {
+                return Err(table_error(format!(
+                    "CSV row {} has {} fields, expected {}",
+                    index + 1,
+                    row.len(),
+                    width
+                )));
+            }
+            // 行尾缺少的字段视为空
+            row.resize(width, String::new());
+            Ok(row.iter().map(|cell| csv_cell(cell)).collect())
+        })
+        .collect::<Result<Vec<Vec<Value>>, _>>()?;
+
+    Table::from_rows(columns, rows)
+        .map(Value::Table)
+        .map_err(table_error)
+}
+
+/// 按列位置投影出新表格
+fn project(table: &Table, indices: &[usize]) -> Table {
+    let columns = indices
+        .iter()
+        .map(|&i| table.columns()[i].clone())
+        .collect();
+    let data = indices
+        .iter()
+        .map(|&i| table.column(&table.columns()[i]).unwrap_or(&[]).to_vec())
+        .collect();
+    Table::new(columns, data).unwrap_or_default()
+}
+
+/// 选择列
+///
+/// # 功能
+/// 返回只包含指定列（按给定顺序）的新表格。
+///
+/// # 参数
+/// - `table`: Table - 源表格
+/// - `columns`: String/Array - 列名或列名数组
+///
+/// # 返回值
+/// Table - 投影后的表格
+///
+/// # 示例
+/// ```aether
+/// Set NAMES SELECT(T, ["name"])
+/// ```
+pub fn select(args: &[Value]) -> Result<Value, RuntimeError> {
+    if args.len() != 2 {
+        return Err(RuntimeError::WrongArity {
+            expected: 2,
+            got: args.len(),
+        });
+    }
+
+    let table = expect_table(&args[0])?;
+    let indices = column_indices(table, &column_names(&args[1])?)?;
+    Ok(Value::Table(project(table, &indices)))
+}
+
+/// 筛选行
+///
+/// # 功能
+/// 返回满足条件的行组成的新表格。条件可以是：
+/// - 字典：列名 → 值，所有列都相等的行被保留
+/// - 函数：以行字典调用，返回真值的行被保留
+///
+/// # 参数
+/// - `table`: Table - 源表格
+/// - `condition`: Dict/Function - 筛选条件
+///
+/// # 返回值
+/// Table - 筛选后的表格
+///
+/// # 示例
+/// ```aether
+/// Set SALES WHERE(T, {"dept": "sales"})
+/// Set ADULTS WHERE(T, Lambda ROW -> ROW["age"] >= 18)
+/// ```
+pub fn where_(args: &[Value]) -> Result<Value, RuntimeError> {
+    if args.len() != 2 {
+        return Err(RuntimeError::WrongArity {
+            expected: 2,
+            got: args.len(),
+        });
+    }
+
+    let table = expect_table(&args[0])?;
+    let conditions = match &args[1] {
+        Value::Dict(dict) => dict,
+        other => {
+            return Err(RuntimeError::TypeErrorDetailed {
+                expected: "Dict or Function (condition)".to_string(),
+                got: other.type_name().to_string(),
+            });
+        }
+    };
+
+    let conditions = conditions
+        .iter()
+        .map(|(name, expected)| {
+            table
+                .column_index(name)
+                .map(|i| (i, expected))
+                .ok_or_else(|| table_error(format!("Column '{}' not found", name)))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let keep: Vec<usize> = (0..table.row_count())
+        .filter(|&row| {
+            conditions
+                .iter()
+                .all(|(col, expected)| table.cell(row, *col).equals(expected))
+        })
+        .collect();
+    Ok(Value::Table(table.take_rows(&keep)))
+}
+
+/// 聚合函数
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Aggregate {
+    Sum,
+    Avg,
+    Min,
+    Max,
+    Count,
+    First,
+    Last,
+}
+
+impl Aggregate {
+    fn parse(name: &str) -> Option<Self> {
+        match name.to_uppercase().as_str() {
+            "SUM" => Some(Aggregate::Sum),
+            "AVG" | "MEAN" => Some(Aggregate::Avg),
+            "MIN" => Some(Aggregate::Min),
+            "MAX" => Some(Aggregate::Max),
+            "COUNT" => Some(Aggregate::Count),
+            "FIRST" => Some(Aggregate::First),
+            "LAST" => Some(Aggregate::Last),
+            _ => None,
+        }
+    }
+
+    fn suffix(&self) -> &'static str {
+        match self {
+            Aggregate::Sum => "sum",
+            Aggregate::Avg => "avg",
+            Aggregate::Min => "min",
+            Aggregate::Max => "max",
+            Aggregate::Count => "count",
+            Aggregate::First => "first",
+            Aggregate::Last => "last",
+        }
+    }
+
+    /// 对一组值（已按行顺序）计算聚合结果，Null 不参与计算
+    fn apply(&self, values: &[&Value]) -> Result<Value, RuntimeError> {
+        let present: Vec<&Value> = values
+            .iter()
+            .copied()
+            .filter(|v| !matches!(v, Value::Null))
+            .collect();
+        let numbers = || {
+            present
+                .iter()
+                .map(|v| match v {
+                    Value::Number(_) | Value::Fraction(_) => Ok(v.to_number().unwrap_or(f64::NAN)),
+                    other => Err(RuntimeError::TypeErrorDetailed {
+                        expected: "Number".to_string(),
+                        got: other.type_name().to_string(),
+                    }),
+                })
+                .collect::<Result<Vec<f64>, _>>()
+        };
+        let extreme = |wanted: std::cmp::Ordering| {
+            present
+                .iter()
+                .copied()
+                .reduce(|best, v| {
+                    if v.compare(best) == Some(wanted) {
+                        v
+                    } else {
+                        best
+                    }
+                })
+                .cloned()
+                .unwrap_or(Value::Null)
+        };
+
+        Ok(match self {
+            Aggregate::Sum => Value::Number(numbers()?.iter().sum()),
+            Aggregate::Avg => {
+                let numbers = numbers()?;
+                if numbers.is_empty() {
+                    Value::Null
+                } else {
+                    Value::Number(numbers.iter().sum::<f64>() / numbers.len() as f64)
+                }
+            }
+            Aggregate::Min => extreme(std::cmp::Ordering::Less),
+            Aggregate::Max => extreme(std::cmp::Ordering::Greater),
+            Aggregate::Count => Value::Number(present.len() as f64),
+            Aggregate::First => present.first().map_or(Value::Null, |v| (*v).clone()),
+            Aggregate::Last => present.last().map_or(Value::Null, |v| (*v).clone()),
+        })
+    }
+}
+
+/// 分组聚合
+///
+/// # 功能
+/// 按一列或多列分组，并对其他列计算聚合值。分组顺序为各组首次出现的顺序。
+/// 聚合结果列命名为 `列名_聚合名`（如 `salary_sum`），对 `"*"` 计数时为 `count`。
+///
+/// # 参数
+/// - `table`: Table - 源表格
+/// - `by`: String/Array - 分组列
+/// - `aggregations`: Dict - 列名 → 聚合名（或聚合名数组），
+///   可选 SUM、AVG、MIN、MAX、COUNT、FIRST、LAST；Null 不参与聚合
+///
+/// # 返回值
+/// Table - 分组列在前、聚合列在后（按列名排序）的表格
+///
+/// # 示例
+/// ```aether
+/// Set BY_DEPT GROUP_AGG(T, "dept", {"salary": ["SUM", "AVG"], "*": "COUNT"})
+/// # 列: dept, count, salary_sum, salary_avg
+/// ```
+pub fn group_agg(args: &[Value]) -> Result<Value, RuntimeError> {
+    if args.len() != 3 {
+        return Err(RuntimeError::WrongArity {
+            expected: 3,
+            got: args.len(),
+        });
+    }
+
+    let table = expect_table(&args[0])?;
+    let by_names = column_names(&args[1])?;
+    let by = column_indices(table, &by_names)?;
+    let specs = match &args[2] {
+        Value::Dict(dict) => dict,
+        other => {
+            return Err(RuntimeError::TypeErrorDetailed {
+                expected: "Dict (column -> aggregate)".to_string(),
+                got: other.type_name().to_string(),
+            });
+        }
+    };
+
+    // (输出列名, 源列位置（"*" 为 None）, 聚合函数)
+    let mut sources: Vec<&String> = specs.keys().collect();
+    sources.sort();
+    let mut outputs: Vec<(String, Option<usize>, Aggregate)> = Vec::new();
+    for source in sources {
+        let names = match &specs[source] {
+            Value::String(name) => vec![name.clone()],
+            other => column_names(other)?,
+        };
+        let column = if source == "*" {
+            None
+        } else {
+            Some(
+                table
+                    .column_index(source)
+                    .ok_or_else(|| table_error(format!("Column '{}' not found", source)))?,
+            )
+        };
+        for name in names {
+            let agg = Aggregate::parse(&name).ok_or_else(|| {
+                table_error(format!(
+                    "Unknown aggregate '{}', expected SUM, AVG, MIN, MAX, COUNT, FIRST or LAST",
+                    name
+                ))
+            })?;
+            let output = match column {
+                None if agg == Aggregate::Count => "count".to_string(),
+                None => {
+                    return Err(table_error(format!(
+                        "Only COUNT can be applied to \"*\", got {}",
+                        name
+                    )));
+                }
+                Some(_) => format!("{}_{}", source, agg.suffix()),
+            };
+            outputs.push((output, column, agg));
+        }
+    }
+
+    // 哈希分组，保留首次出现顺序
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    let mut index: HashMap<Vec<SetKey>, usize> = HashMap::new();
+    for row in 0..table.row_count() {
+        let key = row_key(table, row, &by);
+        let slot = *index.entry(key).or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+        groups[slot].push(row);
+    }
+
+    let mut columns = by_names;
+    columns.extend(outputs.iter().map(|(name, _, _)| name.clone()));
+    let rows = groups
+        .iter()
+        .map(|rows| {
+            let mut out: Vec<Value> = by.iter().map(|&c| table.cell(rows[0], c).clone()).collect();
+            for (_, column, agg) in &outputs {
+                let value = match column {
+                    Some(c) => {
+                        let values: Vec<&Value> = rows.iter().map(|&r| table.cell(r, *c)).collect();
+                        agg.apply(&values)?
+                    }
+                    None => Value::Number(rows.len() as f64),
+                };
+                out.push(value);
+            }
+            Ok(out)
+        })
+        .collect::<Result<Vec<_>, RuntimeError>>()?;
+
+    Table::from_rows(columns, rows)
+        .map(Value::Table)
+        .map_err(table_error)
+}
+
+/// 连接两个表格
+///
+/// # 功能
+/// 按键列对两个表格做哈希连接。结果包含左表所有列及右表的非键列，
+/// 与左表重名的右表列添加 `_right` 后缀。键为 Null 的行不会匹配。
+///
+/// # 参数
+/// - `left`: Table - 左表
+/// - `right`: Table - 右表
+/// - `on`: String/Array - 键列（两个表中都必须存在）
+/// - `how`: String - 可选，"INNER"（默认）或 "LEFT"（保留左表未匹配行，右表列为 Null）
+///
+/// # 返回值
+/// Table - 连接结果（按左表行顺序）
+///
+/// # 示例
+/// ```aether
+/// Set RESULT JOIN(EMPLOYEES, DEPTS, "dept_id")
+/// Set RESULT JOIN(EMPLOYEES, DEPTS, "dept_id", "LEFT")
+/// ```
+pub fn join(args: &[Value]) -> Result<Value, RuntimeError> {
+    if args.len() < 3 || args.len() > 4 {
+        return Err(RuntimeError::WrongArity {
+            expected: 3,
+            got: args.len(),
+        });
+    }
+
+    let left = expect_table(&args[0])?;
+    let right = expect_table(&args[1])?;
+    let on = column_names(&args[2])?;
+    let left_keys = column_indices(left, &on)?;
+    let right_keys = column_indices(right, &on)?;
+    let keep_unmatched = match args.get(3) {
+        None => false,
+        Some(Value::String(how)) => match how.to_uppercase().as_str() {
+            "INNER" => false,
+            "LEFT" => true,
+            _ => {
+                return Err(table_error(format!(
+                    "Unknown join type '{}', expected INNER or LEFT",
+                    how
+                )));
+            }
+        },
+        Some(other) => {
+            return Err(RuntimeError::TypeErrorDetailed {
+                expected: "String (join type)".to_string(),
+                got: other.type_name().to_string(),
+            });
+        }
+    };
+
+    let right_extra: Vec<usize> = (0..right.columns().len())
+        .filter(|c| !right_keys.contains(c))
+        .collect();
+    let mut columns = left.columns().to_vec();
+    for &c in &right_extra {
+        let name = &right.columns()[c];
+        if columns.contains(name) {
+            columns.push(format!("{}_right", name));
+        } else {
+            columns.push(name.clone());
+        }
+    }
+
+    let mut index: HashMap<Vec<SetKey>, Vec<usize>> = HashMap::new();
+    for row in 0..right.row_count() {
+        let key = row_key(right, row, &right_keys);
+        if !key.contains(&SetKey::Null) {
+            index.entry(key).or_default().push(row);
+        }
+    }
+
+    let left_row = |row: usize| (0..left.columns().len()).map(move |c| left.cell(row, c).clone());
+    let mut rows = Vec::new();
+    for row in 0..left.row_count() {
+        let key = row_key(left, row, &left_keys);
+        match index.get(&key).filter(|_| !key.contains(&SetKey::Null)) {
+            Some(matches) => {
+                for &other in matches {
+                    rows.push(
+                        left_row(row)
+                            .chain(right_extra.iter().map(|&c| right.cell(other, c).clone()))
+                            .collect(),
+                    );
+                }
+            }
+            None if keep_unmatched => {
+                rows.push(
+                    left_row(row)
+                        .chain(right_extra.iter().map(|_| Value::Null))
+                        .collect(),
+                );
+            }
+            None => {}
+        }
+    }
+
+    Table::from_rows(columns, rows)
+        .map(Value::Table)
+        .map_err(table_error)
+}
+
+/// 排序表格
+///
+/// # 功能
+/// 按一列或多列对行进行稳定排序，Null 和无法比较的值排在最后。
+///
+/// # 参数
+/// - `table`: Table - 源表格
+/// - `by`: String/Array - 排序列（依次比较）
+/// - `descending`: Boolean - 可选，是否降序（默认 False）
+///
+/// # 返回值
+/// Table - 排序后的表格
+///
+/// # 示例
+/// ```aether
+/// Set BY_AGE SORT(T, "age")
+/// Set RANKED SORT(T, ["dept", "salary"], True)
+/// ```
+pub fn sort(args: &[Value]) -> Result<Value, RuntimeError> {
+    if args.len() < 2 || args.len() > 3 {
+        return Err(RuntimeError::WrongArity {
+            expected: 2,
+            got: args.len(),
+        });
+    }
+
+    let table = expect_table(&args[0])?;
+    let by = column_indices(table, &column_names(&args[1])?)?;
+    let descending = args.get(2).is_some_and(Value::is_truthy);
+
+    let mut order: Vec<usize> = (0..table.row_count()).collect();
+    order.sort_by(|&a, &b| {
+        for &c in &by {
+            let (x, y) = (table.cell(a, c), table.cell(b, c));
+            let ordering = match (x, y) {
+                (Value::Null, Value::Null) => std::cmp::Ordering::Equal,
+                (Value::Null, _) => return std::cmp::Ordering::Greater,
+                (_, Value::Null) => return std::cmp::Ordering::Less,
+                _ => x.compare(y).unwrap_or(std::cmp::Ordering::Equal),
+            };
+            let ordering = if descending {
+                ordering.reverse()
+            } else {
+                ordering
+            };
+            if ordering != std::cmp::Ordering::Equal {
+                return ordering;
+            }
+        }
+        std::cmp::Ordering::Equal
+    });
+
+    Ok(Value::Table(table.take_rows(&order)))
+}
+
+/// 表格转换为字典数组
+///
+/// # 功能
+/// 将表格的每一行转换为一个字典（列名 → 值）。
+///
+/// # 参数
+/// - `table`: Table - 源表格
+///
+/// # 返回值
+/// Array - 行字典数组
+///
+/// # 示例
+/// ```aether
+/// Set ROWS TO_DICTS(T)         # [{name: Alice, age: 30}, ...]
+/// ```
+pub fn to_dicts(args: &[Value]) -> Result<Value, RuntimeError> {
+    if args.len() != 1 {
+        return Err(RuntimeError::WrongArity {
+            expected: 1,
+            got: args.len(),
+        });
+    }
+
+    Ok(Value::Array(expect_table(&args[0])?.to_dicts()))
+}
+
+/// 表格的列名
+///
+/// # 参数
+/// - `table`: Table - 源表格
+///
+/// # 返回值
+/// Array - 按顺序排列的列名
+pub fn columns(args: &[Value]) -> Result<Value, RuntimeError> {
+    if args.len() != 1 {
+        return Err(RuntimeError::WrongArity {
+            expected: 1,
+            got: args.len(),
+        });
+    }
+
+    Ok(Value::Array(
+        expect_table(&args[0])?
+            .columns()
+            .iter()
+            .map(|c| Value::String(c.clone()))
+            .collect(),
+    ))
+}
@@ -0,0 +1,551 @@
+// src/builtins/template.rs
+//! Text template built-in functions
+//!
+//! 原生实现的轻量模板引擎，语法与标准库 `text_template` 模块一致：
+//! - 变量：`{{name}}`、`{{user.name}}`、`{{items.0}}`
+//! - 过滤器：`{{name|upper|trim}}`
+//! - 条件：`{{if cond}}...{{elif cond}}...{{else}}...{{endif}}`
+//! - 循环：`{{for item in list}}...{{else}}...{{endfor}}`
+//!
+//! 模板先解析为语法树再渲染，支持任意嵌套，只遍历一次模板文本。
+
+use crate::evaluator::RuntimeError;
+use crate::value::Value;
+use std::collections::HashMap;
+
+/// 模板语法错误
+fn syntax_error(message: impl Into<String>) -> RuntimeError {
+    RuntimeError::InvalidOperation(format!("Template error: {}", message.into()))
+}
+
+/// 条件或比较中的操作数
+#[derive(Debug, Clone, PartialEq)]
+enum Operand {
+    Path(Vec<String>),
+    Literal(Value),
+}
+
+/// 条件表达式：`[not] operand [op operand]`
+#[derive(Debug, Clone, PartialEq)]
+struct Condition {
+    negate: bool,
+    left: Operand,
+    comparison: Option<(String, Operand)>,
+}
+
+/// 模板语法树节点
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Text(String),
+    Var {
+        path: Vec<String>,
+        filters: Vec<String>,
+    },
+    If {
+        branches: Vec<(Condition, Vec<Node>)>,
+        otherwise: Vec<Node>,
+    },
+    For {
+        item: String,
+        list: Vec<String>,
+        body: Vec<Node>,
+        empty: Vec<Node>,
+    },
+}
+
+/// 模板标签
+#[derive(Debug, Clone, PartialEq)]
+enum Tag {
+    Text(String),
+    Expr(String),
+}
+
+/// 将模板切分为文本和 `{{ }}` 标签
+fn lex(template: &str) -> Result<Vec<Tag>, RuntimeError> {
+    let mut tags = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        if start > 0 {
+            tags.push(Tag::Text(rest[..start].to_string()));
+        }
+        let after = &rest[start + 2..];
+        let end = after
+            .find("}}")
+            .ok_or_else(|| syntax_error("unclosed '{{'"))?;
+        tags.push(Tag::Expr(after[..end].trim().to_string()));
+        rest = &after[end + 2..];
+    }
+    if !rest.is_empty() {
+        tags.push(Tag::Text(rest.to_string()));
+    }
+    Ok(tags)
+}
+
+/// 解析点号路径（如 `user.name`）
+fn parse_path(text: &str) -> Result<Vec<String>, RuntimeError> {
+    let path: Vec<String> = text.split('.').map(|s| s.trim().to_string()).collect();
+    if path.iter().any(|p| p.is_empty()) {
+        return Err(syntax_error(format!("invalid variable '{}'", text)));
+    }
+    Ok(path)
+}
+
+/// 解析操作数：字符串、数字、布尔、Null 字面量或变量路径
+fn parse_operand(text: &str) -> Result<Operand, RuntimeError> {
+    let text = text.trim();
+    if let Some(inner) = text
+        .strip_prefix('"')
+        .and_then(|t| t.strip_suffix('"'))
+        .or_else(|| text.strip_prefix('\'').and_then(|t| t.strip_suffix('\'')))
+    {
+        return Ok(Operand::Literal(Value::String(inner.to_string())));
+    }
+    if let Ok(n) = text.parse::<f64>() {
+        return Ok(Operand::Literal(Value::Number(n)));
+    }
+    match text.to_lowercase().as_str() {
+        "true" => Ok(Operand::Literal(Value::Boolean(true))),
+        "false" => Ok(Operand::Literal(Value::Boolean(false))),
+        "null" => Ok(Operand::Literal(Value::Null)),
+        _ => parse_path(text).map(Operand::Path),
+    }
+}
+
+/// 解析条件表达式
+fn parse_condition(text: &str) -> Result<Condition, RuntimeError> {
+    let text = text.trim();
+    let (negate, text) = match text.strip_prefix("not ") {
+        Some(rest) => (true, rest.trim()),
+        None => (false, text),
+    };
+    if text.is_empty() {
+        return Err(syntax_error("empty condition"));
+    }
+
+    for op in ["==", "!=", ">=", "<=", ">", "<"] {
+        if let Some((left, right)) = text.split_once(op) {
+            return Ok(Condition {
+                negate,
+                left: parse_operand(left)?,
+                comparison: Some((op.to_string(), parse_operand(right)?)),
+            });
+        }
+    }
+    Ok(Condition {
+        negate,
+        left: parse_operand(text)?,
+        comparison: None,
+    })
+}
+
+/// 递归下降解析器
+struct Parser {
+    tags: Vec<Tag>,
+    pos: usize,
+}
+
+impl Parser {
+    /// 解析节点直到遇到指定的结束标签之一，返回节点和结束标签
+    fn parse_until(
+        &mut self,
+        terminators: &[&str],
+    ) -> Result<(Vec<Node>, Option<String>), RuntimeError> {
+        let mut nodes = Vec::new();
+        while self.pos < self.tags.len() {
+            let tag = self.tags[self.pos].clone();
+            self.pos += 1;
+            let expr = match tag {
+                Tag::Text(text) => {
+                    nodes.push(Node::Text(text));
+                    continue;
+                }
+                Tag::Expr(expr) => expr,
+            };
+
+            let keyword = expr.split_whitespace().next().unwrap_or("");
+            if terminators.contains(&keyword) {
+                return Ok((nodes, Some(expr)));
+            }
+            match keyword {
+                "if" => nodes.push(self.parse_if(&expr[2..])?),
+                "for" => nodes.push(self.parse_for(&expr[3..])?),
+                "elif" | "else" | "endif" | "endfor" => {
+                    return Err(syntax_error(format!("unexpected '{{{{{}}}}}'", expr)));
+                }
+                _ if expr.starts_with('!') => {} // 注释
+                _ => {
+                    let mut parts = expr.split('|');
+                    let path = parse_path(parts.next().unwrap_or(""))?;
+                    let filters = parts.map(|f| f.trim().to_lowercase()).collect();
+                    nodes.push(Node::Var { path, filters });
+                }
+            }
+        }
+        if terminators.is_empty() {
+            Ok((nodes, None))
+        } else {
+            Err(syntax_error(format!(
+                "missing '{{{{{}}}}}'",
+                terminators.last().unwrap_or(&"")
+            )))
+        }
+    }
+
+    fn parse_if(&mut self, condition: &str) -> Result<Node, RuntimeError> {
+        let mut branches = Vec::new();
+        let mut condition = parse_condition(condition)?;
+        loop {
+            let (body, end) = self.parse_until(&["elif", "else", "endif"])?;
+            branches.push((condition, body));
+            let end = end.unwrap_or_default();
+            if let Some(next) = end.strip_prefix("elif") {
+                condition = parse_condition(next)?;
+            } else if end == "else" {
+                let (otherwise, _) = self.parse_until(&["endif"])?;
+                return Ok(Node::If {
+                    branches,
+                    otherwise,
+                });
+            } else {
+                return Ok(Node::If {
+                    branches,
+                    otherwise: Vec::new(),
+                });
+            }
+        }
+    }
+
+    fn parse_for(&mut self, header: &str) -> Result<Node, RuntimeError> {
+        let (item, list) = header.split_once(" in ").ok_or_else(|| {
+            syntax_error(format!("expected 'for item in list', got 'for{}'", header))
+        })?;
+        let item = item.trim().to_string();
+        if item.is_empty() || item.contains('.') {
+            return Err(syntax_error(format!("invalid loop variable '{}'", item)));
+        }
+        let list = parse_path(list)?;
+
+        let (body, end) = self.parse_until(&["else", "endfor"])?;
+        let empty = if end.as_deref() == Some("else") {
+            self.parse_until(&["endfor"])?.0
+        } else {
+            Vec::new()
+        };
+        Ok(Node::For {
+            item,
+            list,
+            body,
+            empty,
+        })
+    }
+}
+
+/// 解析模板
+fn parse(template: &str) -> Result<Vec<Node>, RuntimeError> {
+    let mut parser = Parser {
+        tags: lex(template)?,
+        pos: 0,
+    };
+    Ok(parser.parse_until(&[])?.0)
+}
+
+/// 渲染选项
+#[derive(Debug, Clone, Copy, Default)]
+struct RenderOptions {
+    /// 变量输出时进行 HTML 转义（`raw` 过滤器除外）
+    escape_html: bool,
+    /// 变量不存在时报错，而不是输出空字符串
+    strict: bool,
+}
+
+/// HTML 转义
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// 渲染器：数据字典 + 循环变量作用域
+struct Renderer<'a> {
+    data: &'a HashMap<String, Value>,
+    scopes: Vec<HashMap<String, Value>>,
+    options: RenderOptions,
+}
+
+impl Renderer<'_> {
+    /// 按路径查找变量，循环变量优先
+    fn lookup(&self, path: &[String]) -> Option<Value> {
+        let (first, rest) = path.split_first()?;
+        let mut value = self
+            .scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(first))
+            .or_else(|| self.data.get(first))?
+            .clone();
+        for key in rest {
+            value = match value {
+                Value::Dict(dict) => dict.get(key)?.clone(),
+                Value::Array(items) => items.get(key.parse::<usize>().ok()?)?.clone(),
+                Value::Table(table) => Value::Array(table.column(key)?.to_vec()),
+                _ => return None,
+            };
+        }
+        Some(value)
+    }
+
+    fn resolve(&self, path: &[String]) -> Result<Value, RuntimeError> {
+        match self.lookup(path) {
+            Some(value) => Ok(value),
+            None if self.options.strict => Err(syntax_error(format!(
+                "variable '{}' not found",
+                path.join(".")
+            ))),
+            None => Ok(Value::Null),
+        }
+    }
+
+    fn operand(&self, operand: &Operand) -> Result<Value, RuntimeError> {
+        match operand {
+            Operand::Literal(value) => Ok(value.clone()),
+            Operand::Path(path) => self.resolve(path),
+        }
+    }
+
+    fn test(&self, condition: &Condition) -> Result<bool, RuntimeError> {
+        let left = self.operand(&condition.left)?;
+        let result = match &condition.comparison {
+            None => left.is_truthy(),
+            Some((op, right)) => {
+                let right = self.operand(right)?;
+                let ordering = left.compare(&right);
+                match op.as_str() {
+                    "==" => left.equals(&right),
+                    "!=" => !left.equals(&right),
+                    ">" => ordering == Some(std::cmp::Ordering::Greater),
+                    "<" => ordering == Some(std::cmp::Ordering::Less),
+                    ">=" => matches!(
+                        ordering,
+                        Some(std::cmp::Ordering::Greater | std::cmp::Ordering::Equal)
+                    ),
+                    _ => matches!(
+                        ordering,
+                        Some(std::cmp::Ordering::Less | std::cmp::Ordering::Equal)
+                    ),
+                }
+            }
+        };
+        Ok(result != condition.negate)
+    }
+
+    fn render(&mut self, nodes: &[Node], out: &mut String) -> Result<(), RuntimeError> {
+        for node in nodes {
+            match node {
+                Node::Text(text) => out.push_str(text),
+                Node::Var { path, filters } => {
+                    let value = self.resolve(path)?;
+                    out.push_str(&self.format(value, filters)?);
+                }
+                Node::If {
+                    branches,
+                    otherwise,
+                } => {
+                    let mut chosen = otherwise;
+                    for (condition, body) in branches {
+                        if self.test(condition)? {
+                            chosen = body;
+                            break;
+                        }
+                    }
+                    self.render(chosen, out)?;
+                }
+                Node::For {
+                    item,
+                    list,
+                    body,
+                    empty,
+                } => {
+                    let items = match self.resolve(list)? {
+                        Value::Array(items) => items,
+                        Value::Set(set) => crate::value::set_elements(&set),
+                        Value::Table(table) => table.to_dicts(),
+                        Value::Dict(dict) => {
+                            let mut keys: Vec<String> = dict.into_keys().collect();
+                            keys.sort();
+                            keys.into_iter().map(Value::String).collect()
+                        }
+                        Value::Null => Vec::new(),
+                        other => {
+                            return Err(syntax_error(format!(
+                                "cannot loop over {} '{}'",
+                                other.type_name(),
+                                list.join(".")
+                            )));
+                        }
+                    };
+                    if items.is_empty() {
+                        self.render(empty, out)?;
+                        continue;
+                    }
+
+                    let count = items.len();
+                    for (index, value) in items.into_iter().enumerate() {
+                        let mut scope = HashMap::new();
+                        scope.insert(item.clone(), value);
+                        scope.insert("loop_index".to_string(), Value::Number(index as f64));
+                        scope.insert("loop_first".to_string(), Value::Boolean(index == 0));
+                        scope.insert("loop_last".to_string(), Value::Boolean(index + 1 == count));
+                        self.scopes.push(scope);
+                        let result = self.render(body, out);
+                        self.scopes.pop();
+                        result?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 应用过滤器并转换为输出文本
+    fn format(&self, value: Value, filters: &[String]) -> Result<String, RuntimeError> {
+        let mut raw = false;
+        let mut escaped = false;
+        let mut text = match &value {
+            Value::Null => String::new(),
+            other => other.to_string(),
+        };
+        for filter in filters {
+            text = match filter.as_str() {
+                "upper" => text.to_uppercase(),
+                "lower" => text.to_lowercase(),
+                "title" => {
+                    let mut chars = text.chars();
+                    match chars.next() {
+                        Some(first) => first.to_uppercase().chain(chars).collect(),
+                        None => text,
+                    }
+                }
+                "trim" => text.trim().to_string(),
+                "length" => match &value {
+                    Value::Array(items) => items.len().to_string(),
+                    Value::Dict(dict) => dict.len().to_string(),
+                    _ => text.chars().count().to_string(),
+                },
+                "reverse" => text.chars().rev().collect(),
+                "json" => super::json::json_stringify(std::slice::from_ref(&value))?.to_string(),
+                "escape" => {
+                    escaped = true;
+                    escape_html(&text)
+                }
+                "raw" => {
+                    raw = true;
+                    text
+                }
+                other => return Err(syntax_error(format!("unknown filter '{}'", other))),
+            };
+        }
+        if self.options.escape_html && !raw && !escaped {
+            text = escape_html(&text);
+        }
+        Ok(text)
+    }
+}
+
+/// 渲染模板
+///
+/// # 功能
+/// 使用数据字典渲染文本模板。模板只解析一次，支持嵌套的条件和循环。
+///
+/// 语法：
+/// - `{{name}}`、`{{user.name}}`、`{{items.0}}`：变量（不存在时输出空字符串）
+/// - `{{name|upper}}`：过滤器，可链式使用：upper、lower、title、trim、length、
+///   reverse、json、escape（HTML 转义）、raw（不转义）
+/// - `{{if cond}}...{{elif cond}}...{{else}}...{{endif}}`：条件，
+///   cond 可以是变量（按真值判断）、`not 变量`，或与字面量/变量的比较
+///   （`==`、`!=`、`>`、`<`、`>=`、`<=`）
+/// - `{{for item in list}}...{{else}}...{{endfor}}`：循环（列表为空时渲染 else 部分），
+///   循环体内可用 `loop_index`、`loop_first`、`loop_last`
+/// - `{{! 注释 }}`：注释，不输出
+///
+/// # 参数
+/// - `template`: String - 模板文本
+/// - `data`: Dict - 数据字典
+/// - `options`: Dict - 可选：`escape`（"html" 时对所有变量做 HTML 转义，默认 "none"）、
+///   `strict`（为 True 时变量不存在会报错）
+///
+/// # 返回值
+/// String - 渲染结果
+///
+/// # 示例
+/// ```aether
+/// Set TPL "Hello {{name|title}}!{{for x in items}} {{x}}{{endfor}}"
+/// Set OUT RENDER_TEMPLATE(TPL, {"name": "alice", "items": [1, 2]})
+/// # "Hello Alice! 1 2"
+/// Set HTML RENDER_TEMPLATE("<p>{{msg}}</p>", {"msg": "<b>"}, {"escape": "html"})
+/// # "<p>&lt;b&gt;</p>"
+/// ```
+pub fn render_template(args: &[Value]) -> Result<Value, RuntimeError> {
+    if args.len() < 2 || args.len() > 3 {
+        return Err(RuntimeError::WrongArity {
+            expected: 2,
+            got: args.len(),
+        });
+    }
+
+    let (template, data) = match (&args[0], &args[1]) {
+        (Value::String(t), Value::Dict(d)) => (t, d),
+        _ => {
+            return Err(RuntimeError::TypeErrorDetailed {
+                expected: "String, Dict".to_string(),
+                got: format!("{}, {}", args[0].type_name(), args[1].type_name()),
+            });
+        }
+    };
+
+    let mut options = RenderOptions::default();
+    match args.get(2) {
+        None | Some(Value::Null) => {}
+        Some(Value::Dict(dict)) => {
+            options.escape_html = match dict.get("escape") {
+                None => false,
+                Some(Value::String(mode)) => match mode.to_lowercase().as_str() {
+                    "html" => true,
+                    "none" => false,
+                    _ => {
+                        return Err(RuntimeError::InvalidOperation(format!(
+                            "Unknown escape mode '{}', expected \"html\" or \"none\"",
+                            mode
+                        )));
+                    }
+                },
+                Some(other) => other.is_truthy(),
+            };
+            options.strict = dict.get("strict").is_some_and(Value::is_truthy);
+        }
+        Some(other) => {
+            return Err(RuntimeError::TypeErrorDetailed {
+                expected: "Dict (options)".to_string(),
+                got: other.type_name().to_string(),
+            });
+        }
+    }
+
+    let nodes = parse(template)?;
+    let mut renderer = Renderer {
+        data,
+        scopes: Vec::new(),
+        options,
+    };
+    let mut out = String::with_capacity(template.len());
+    renderer.render(&nodes, &mut out)?;
+    Ok(Value::String(out))
+}
@@ -15,11 +15,12 @@ use crate::value::Value;
 /// - `value`: 任意值
 ///
 /// # 返回值
-/// 类型名称字符串："Number", "String", "Boolean", "Null", "Array", "Dict", "Function", "Generator", "Lazy", "BuiltIn"
+/// 类型名称字符串："Number", "Integer", "String", "Boolean", "Null", "Array", "Dict", "DateTime", "Function", "Generator", "Lazy", "BuiltIn"
 ///
 /// # 示例
 /// ```aether
-/// Println(TypeOf(42))          # 输出: Number
+/// Println(TypeOf(42))          # 输出: Integer
+/// Println(TypeOf(4.2))         # 输出: Number
 /// Println(TypeOf("hello"))     # 输出: String
 /// Println(TypeOf([1, 2, 3]))   # 输出: Array
 /// Println(TypeOf(True))        # 输出: Boolean
@@ -34,12 +35,16 @@ pub fn type_of(args: &[Value]) -> Result<Value, RuntimeError> {
 
     let type_name = match &args[0] {
         Value::Number(_) => "Number",
+        Value::Int(_) => "Integer",
         Value::Fraction(_) => "Fraction",
         Value::String(_) => "String",
         Value::Boolean(_) => "Boolean",
         Value::Null => "Null",
         Value::Array(_) => "Array",
         Value::Dict(_) => "Dict",
+        Value::Set(_) => "Set",
+        Value::Table(_) => "Table",
+        Value::DateTime(_) => "DateTime",
         Value::Function { .. } => "Function",
         Value::Generator { .. } => "Generator",
         Value::Lazy { .. } => "Lazy",
@@ -132,6 +137,81 @@ pub fn to_number(args: &[Value]) -> Result<Value, RuntimeError> {
     }
 }
 
+/// 将值转换为整数
+///
+/// # 功能
+/// 将数字、字符串或布尔值转换为整数（Integer），小数部分向零截断。
+///
+/// # 参数
+/// - `value`: 要转换的值
+///
+/// # 返回值
+/// 整数类型的值
+///
+/// # 转换规则
+/// - Integer → 返回原值
+/// - Number / Fraction → 向零截断（超出 64 位整数范围则报错）
+/// - String → 解析为整数，不丢失精度（如 ID），带小数点的按数字截断
+/// - Boolean → true=1, false=0
+/// - 其他类型 → 报错
+///
+/// # 示例
+/// ```aether
+/// Set ID TO_INT("9007199254740993")   # 9007199254740993（精确）
+/// Set N TO_INT(-3.7)                  # -3
+/// Set B TO_INT(True)                  # 1
+/// ```
+pub fn to_int(args: &[Value]) -> Result<Value, RuntimeError> {
+    if args.len() != 1 {
+        return Err(RuntimeError::WrongArity {
+            expected: 1,
+            got: args.len(),
+        });
+    }
+
+    let out_of_range = || {
+        RuntimeError::InvalidOperation(format!(
+            "TO_INT: {} is out of the 64-bit integer range",
+            args[0]
+        ))
+    };
+    let truncate = |n: f64| {
+        // i64::MAX 无法精确表示为 f64，2^63 本身已越界
+        if n.is_finite() && n.trunc() >= i64::MIN as f64 && n.trunc() < i64::MAX as f64 {
+            Ok(Value::Int(n.trunc() as i64))
+        } else {
+            Err(out_of_range())
+        }
+    };
+    match &args[0] {
+        Value::Int(i) => Ok(Value::Int(*i)),
+        Value::Number(n) => truncate(*n),
+        Value::Fraction(f) => {
+            use num_traits::ToPrimitive;
+            f.trunc()
+                .to_integer()
+                .to_i64()
+                .map(Value::Int)
+                .ok_or_else(out_of_range)
+        }
+        Value::String(s) => match s.trim().parse::<i64>() {
+            Ok(i) => Ok(Value::Int(i)),
+            Err(_) => match s.trim().parse::<f64>() {
+                Ok(n) => truncate(n),
+                Err(_) => Err(RuntimeError::TypeErrorDetailed {
+                    expected: "parseable string".to_string(),
+                    got: format!("\"{}\"", s),
+                }),
+            },
+        },
+        Value::Boolean(b) => Ok(Value::Int(i64::from(*b))),
+        other => Err(RuntimeError::TypeErrorDetailed {
+            expected: "Integer, Number, Fraction, String or Boolean".to_string(),
+            got: other.type_name().to_string(),
+        }),
+    }
+}
+
 /// 获取集合的长度
 ///
 /// # 功能
@@ -163,8 +243,10 @@ pub fn len(args: &[Value]) -> Result<Value, RuntimeError> {
         Value::String(s) => Ok(Value::Number(s.len() as f64)),
         Value::Array(arr) => Ok(Value::Number(arr.len() as f64)),
         Value::Dict(dict) => Ok(Value::Number(dict.len() as f64)),
+        Value::Set(set) => Ok(Value::Number(set.len() as f64)),
+        Value::Table(table) => Ok(Value::Number(table.row_count() as f64)),
         other => Err(RuntimeError::TypeErrorDetailed {
-            expected: "String, Array or Dict".to_string(),
+            expected: "String, Array, Dict, Set or Table".to_string(),
             got: format!("{:?}", other),
         }),
     }
@@ -0,0 +1,401 @@
+// src/builtins/validation.rs
+//! 标识符校验
+//!
+//! 原生实现常见标识符的格式与校验位检查，供验证类脚本（stdlib/validation）使用：
+//! - `LUHN_CHECK` 银行卡号等使用的 Luhn 校验
+//! - `VALIDATE_ID_CN` 中国大陆 18 位居民身份证号（地区码、出生日期、ISO 7064 校验位）
+//! - `VALIDATE_IBAN` 国际银行账号（国家长度表 + MOD 97 校验）
+//! - `VALIDATE_EMAIL` 邮箱地址（按 RFC 5321/5322 的常用子集）
+//!
+//! 所有函数对格式不合法的输入返回 False，只有参数类型错误时才报错。
+
+use crate::evaluator::RuntimeError;
+use crate::value::Value;
+use chrono::{Datelike, Local, NaiveDate};
+
+/// 身份证前 17 位的加权因子
+const ID_CN_WEIGHTS: [u32; 17] = [7, 9, 10, 5, 8, 4, 2, 1, 6, 3, 7, 9, 10, 5, 8, 4, 2];
+
+/// 身份证校验位（按加权和模 11 索引）
+const ID_CN_CHECK_CHARS: [char; 11] = ['1', '0', 'X', '9', '8', '7', '6', '5', '4', '3', '2'];
+
+/// 身份证地区码的省级前两位
+const ID_CN_PROVINCES: &[u32] = &[
+    11, 12, 13, 14, 15, 21, 22, 23, 31, 32, 33, 34, 35, 36, 37, 41, 42, 43, 44, 45, 46, 50, 51, 52,
+    53, 54, 61, 62, 63, 64, 65, 71, 81, 82, 83,
+];
+
+/// 各国 IBAN 的固定长度
+const IBAN_LENGTHS: &[(&str, usize)] = &[
+    ("AD", 24),
+    ("AE", 23),
+    ("AL", 28),
+    ("AT", 20),
+    ("AZ", 28),
+    ("BA", 20),
+    ("BE", 16),
+    ("BG", 22),
+    ("BH", 22),
+    ("BR", 29),
+    ("BY", 28),
+    ("CH", 21),
+    ("CR", 22),
+    ("CY", 28),
+    ("CZ", 24),
+    ("DE", 22),
+    ("DK", 18),
+    ("DO", 28),
+    ("EE", 20),
+    ("EG", 29),
+    ("ES", 24),
+    ("FI", 18),
+    ("FO", 18),
+    ("FR", 27),
+    ("GB", 22),
+    ("GE", 22),
+    ("GI", 23),
+    ("GL", 18),
+    ("GR", 27),
+    ("GT", 28),
+    ("HR", 21),
+    ("HU", 28),
+    ("IE", 22),
+    ("IL", 23),
+    ("IQ", 23),
+    ("IS", 26),
+    ("IT", 27),
+    ("JO", 30),
+    ("KW", 30),
+    ("KZ", 20),
+    ("LB", 28),
+    ("LC", 32),
+    ("LI", 21),
+    ("LT", 20),
+    ("LU", 20),
+    ("LV", 21),
+    ("MC", 27),
+    ("MD", 24),
+    ("ME", 22),
+    ("MK", 19),
+    ("MR", 27),
+    ("MT", 31),
+    ("MU", 30),
+    ("NL", 18),
+    ("NO", 15),
+    ("PK", 24),
+    ("PL", 28),
+    ("PS", 29),
+    ("PT", 25),
+    ("QA", 29),
+    ("RO", 24),
+    ("RS", 22),
+    ("SA", 24),
+    ("SC", 31),
+    ("SE", 24),
+    ("SI", 19),
+    ("SK", 24),
+    ("SM", 27),
+    ("TL", 23),
+    ("TN", 24),
+    ("TR", 26),
+    ("UA", 29),
+    ("VA", 22),
+    ("VG", 24),
+    ("XK", 20),
+];
+
+/// 邮箱地址的最大长度（RFC 5321 路径长度限制）
+const EMAIL_MAX_LEN: usize = 254;
+
+/// 邮箱本地部分的最大长度
+const EMAIL_LOCAL_MAX_LEN: usize = 64;
+
+/// 域名标签的最大长度
+const DOMAIN_LABEL_MAX_LEN: usize = 63;
+
+/// 读取唯一的字符串参数
+fn string_arg(args: &[Value]) -> Result<&str, RuntimeError> {
+    if args.len() != 1 {
+        return Err(RuntimeError::WrongArity {
+            expected: 1,
+            got: args.len(),
+        });
+    }
+    match &args[0] {
+        Value::String(s) => Ok(s),
+        other => Err(RuntimeError::TypeErrorDetailed {
+            expected: "String".to_string(),
+            got: other.type_name().to_string(),
+        }),
+    }
+}
+
+/// Luhn 校验（数字串，不含分隔符）
+fn luhn_valid(digits: &str) -> bool {
+    if digits.len() < 2 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return false;
+    }
+    let sum: u32 = digits
+        .bytes()
+        .rev()
+        .enumerate()
+        .map(|(i, b)| {
+            let d = (b - b'0') as u32;
+            if !i.is_multiple_of(2) {
+                let doubled = d * 2;
+                if doubled > 9 { doubled - 9 } else { doubled }
+            } else {
+                d
+            }
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+/// Luhn 校验
+///
+/// # 功能
+/// 使用 Luhn（模 10）算法校验银行卡号、IMEI 等号码。
+/// 空格和连字符会被忽略，其他非数字字符视为无效。
+///
+/// # 参数
+/// - `number`: String | Number - 待校验的号码（超过 15 位时请使用字符串）
+///
+/// # 返回值
+/// Boolean - 校验是否通过
+///
+/// # 示例
+/// ```aether
+/// Set a LUHN_CHECK("4111 1111 1111 1111")   # true
+/// Set b LUHN_CHECK("4111-1111-1111-1112")   # false
+/// Set c LUHN_CHECK(79927398713)             # true
+/// ```
+pub fn luhn_check(args: &[Value]) -> Result<Value, RuntimeError> {
+    if args.len() != 1 {
+        return Err(RuntimeError::WrongArity {
+            expected: 1,
+            got: args.len(),
+        });
+    }
+    let number = match &args[0] {
+        Value::String(s) => s.clone(),
+        Value::Number(n) if *n >= 0.0 && n.fract() == 0.0 => format!("{:.0}", n),
+        Value::Number(_) => return Ok(Value::Boolean(false)),
+        other => {
+            return Err(RuntimeError::TypeErrorDetailed {
+                expected: "String or Number".to_string(),
+                got: other.type_name().to_string(),
+            });
+        }
+    };
+    let digits: String = number.chars().filter(|c| *c != ' ' && *c != '-').collect();
+    Ok(Value::Boolean(luhn_valid(&digits)))
+}
+
+/// 校验 18 位身份证号
+fn id_cn_valid(id: &str, today: NaiveDate) -> bool {
+    let chars: Vec<char> = id.chars().collect();
+    if chars.len() != 18 || !chars[..17].iter().all(|c| c.is_ascii_digit()) {
+        return false;
+    }
+    let digit = |i: usize| chars[i].to_digit(10).unwrap_or(0);
+    let number = |range: std::ops::Range<usize>| range.fold(0, |acc, i| acc * 10 + digit(i));
+
+    if !ID_CN_PROVINCES.contains(&number(0..2)) {
+        return false;
+    }
+
+    let birth = NaiveDate::from_ymd_opt(number(6..10) as i32, number(10..12), number(12..14));
+    match birth {
+        Some(date) if date.year() >= 1800 && date <= today => {}
+        _ => return false,
+    }
+
+    let sum: u32 = ID_CN_WEIGHTS
+        .iter()
+        .enumerate()
+        .map(|(i, w)| digit(i) * w)
+        .sum();
+    let expected = ID_CN_CHECK_CHARS[(sum % 11) as usize];
+    chars[17].to_ascii_uppercase() == expected
+}
+
+/// 校验中国大陆居民身份证号
+///
+/// # 功能
+/// 校验 18 位居民身份证号：省级地区码、出生日期（真实存在且不晚于今天）
+/// 以及按 GB 11643（ISO 7064 MOD 11-2）计算的校验位，校验位 `x` 不区分大小写。
+///
+/// # 参数
+/// - `id`: String - 身份证号
+///
+/// # 返回值
+/// Boolean - 校验是否通过
+///
+/// # 示例
+/// ```aether
+/// Set a VALIDATE_ID_CN("11010519491231002X")   # true
+/// Set b VALIDATE_ID_CN("110105194912310021")   # false（校验位错误）
+/// Set c VALIDATE_ID_CN("110105194902300021")   # false（日期不存在）
+/// ```
+pub fn validate_id_cn(args: &[Value]) -> Result<Value, RuntimeError> {
+    validate_id_cn_on(args, Local::now().date_naive())
+}
+
+/// VALIDATE_ID_CN，出生日期以 `today` 为上限（确定性模式下使用注入的时钟）
+pub(crate) fn validate_id_cn_on(args: &[Value], today: NaiveDate) -> Result<Value, RuntimeError> {
+    let id = string_arg(args)?;
+    Ok(Value::Boolean(id_cn_valid(id.trim(), today)))
+}
+
+/// 校验 IBAN（已去除空格并转为大写）
+fn iban_valid(iban: &str) -> bool {
+    if !iban.bytes().all(|b| b.is_ascii_alphanumeric()) || iban.len() < 4 {
+        return false;
+    }
+    let country = &iban[..2];
+    match IBAN_LENGTHS.iter().find(|(code, _)| *code == country) {
+        Some((_, len)) if *len == iban.len() => {}
+        _ => return false,
+    }
+    if !iban[2..4].bytes().all(|b| b.is_ascii_digit()) {
+        return false;
+    }
+
+    // 将前 4 位移到末尾，字母按 A=10 … Z=35 展开，逐位计算模 97
+    let rearranged = iban[4..].bytes().chain(iban[..4].bytes());
+    let mut remainder: u32 = 0;
+    for b in rearranged {
+        let value = if b.is_ascii_digit() {
+            (b - b'0') as u32
+        } else {
+            (b - b'A') as u32 + 10
+        };
+        remainder = if value >= 10 {
+            (remainder * 100 + value) % 97
+        } else {
+            (remainder * 10 + value) % 97
+        };
+    }
+    remainder == 1
+}
+
+/// 校验国际银行账号（IBAN）
+///
+/// # 功能
+/// 检查国家代码对应的固定长度以及 ISO 13616 的 MOD 97 校验位。
+/// 空格会被忽略，字母不区分大小写。
+///
+/// # 参数
+/// - `iban`: String - IBAN
+///
+/// # 返回值
+/// Boolean - 校验是否通过
+///
+/// # 示例
+/// ```aether
+/// Set a VALIDATE_IBAN("GB82 WEST 1234 5698 7654 32")   # true
+/// Set b VALIDATE_IBAN("GB82 WEST 1234 5698 7654 33")   # false
+/// ```
+pub fn validate_iban(args: &[Value]) -> Result<Value, RuntimeError> {
+    let iban: String = string_arg(args)?
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_ascii_uppercase();
+    Ok(Value::Boolean(iban_valid(&iban)))
+}
+
+/// 邮箱本地部分允许的非字母数字字符（RFC 5322 atext）
+fn is_atext(c: char) -> bool {
+    c.is_alphanumeric() || "!#$%&'*+-/=?^_`{|}~".contains(c)
+}
+
+/// 校验邮箱的本地部分（dot-atom 或带引号的字符串）
+fn email_local_valid(local: &str) -> bool {
+    if local.is_empty() || local.len() > EMAIL_LOCAL_MAX_LEN {
+        return false;
+    }
+    if let Some(quoted) = local
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+    {
+        let mut chars = quoted.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => match chars.next() {
+                    Some(escaped) if escaped == ' ' || escaped.is_ascii_graphic() => {}
+                    _ => return false,
+                },
+                '"' => return false,
+                c if c == ' ' || c.is_ascii_graphic() || !c.is_ascii() => {}
+                _ => return false,
+            }
+        }
+        return true;
+    }
+    local
+        .split('.')
+        .all(|atom| !atom.is_empty() && atom.chars().all(is_atext))
+}
+
+/// 校验邮箱的域名部分（主机名或 IP 地址字面量）
+fn email_domain_valid(domain: &str) -> bool {
+    if let Some(literal) = domain
+        .strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(']'))
+    {
+        return match literal.strip_prefix("IPv6:") {
+            Some(v6) => v6.parse::<std::net::Ipv6Addr>().is_ok(),
+            None => literal.parse::<std::net::Ipv4Addr>().is_ok(),
+        };
+    }
+
+    let labels: Vec<&str> = domain.split('.').collect();
+    if labels.len() < 2 {
+        return false;
+    }
+    let labels_valid = labels.iter().all(|label| {
+        !label.is_empty()
+            && label.len() <= DOMAIN_LABEL_MAX_LEN
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label.chars().all(|c| c.is_alphanumeric() || c == '-')
+    });
+    let tld = labels[labels.len() - 1];
+    labels_valid && !tld.chars().all(|c| c.is_ascii_digit())
+}
+
+/// 校验邮箱地址
+///
+/// # 功能
+/// 按 RFC 5321/5322 的常用子集校验邮箱地址：
+/// - 本地部分为点分原子（不能以点开头/结尾或出现连续的点）或带引号的字符串，最长 64 字节
+/// - 域名至少包含两级，每级 1-63 个字母、数字或连字符（不能以连字符开头/结尾），
+///   顶级域名不能全是数字；也可以是 `[192.0.2.1]`、`[IPv6:…]` 形式的地址字面量
+/// - 整个地址最长 254 字节；允许非 ASCII 字母（国际化邮箱）
+///
+/// 不支持注释和折叠空白等过时语法。
+///
+/// # 参数
+/// - `email`: String - 邮箱地址
+///
+/// # 返回值
+/// Boolean - 校验是否通过
+///
+/// # 示例
+/// ```aether
+/// Set a VALIDATE_EMAIL("first.last+tag@example.co.uk")   # true
+/// Set b VALIDATE_EMAIL("\"john doe\"@example.com")       # true
+/// Set c VALIDATE_EMAIL("john..doe@example.com")          # false
+/// Set d VALIDATE_EMAIL("user@-example.com")              # false
+/// ```
+pub fn validate_email(args: &[Value]) -> Result<Value, RuntimeError> {
+    let email = string_arg(args)?;
+    let valid = email.len() <= EMAIL_MAX_LEN
+        && match email.rsplit_once('@') {
+            Some((local, domain)) => email_local_valid(local) && email_domain_valid(domain),
+            None => false,
+        };
+    Ok(Value::Boolean(valid))
+}
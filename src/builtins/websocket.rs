@@ -0,0 +1,936 @@
+// src/builtins/websocket.rs
+//! WebSocket / Server-Sent Events 客户端
+//!
+//! 让监控类脚本直接消费流式 API，而不必反复轮询 HTTP_GET：
+//! - `WS_CONNECT` / `WS_SEND` / `WS_RECV` / `WS_CLOSE`：WebSocket（RFC 6455）客户端
+//! - `SSE_CONNECT` / `SSE_RECV` / `SSE_CLOSE`：Server-Sent Events 客户端
+//!
+//! 打开的连接属于创建它的引擎，脚本通过 CONNECT 返回的句柄字符串访问；句柄包含随机部分，
+//! 其他引擎无法使用或猜出。`reset_env` 或引擎被丢弃时关闭所有未关闭的连接。
+//! `ws://` / `http://` 使用明文 TCP，`wss://` / `https://` 使用 rustls（内置 webpki 根证书）。
+//! 握手校验使用 ring 的 SHA-1，`Sec-WebSocket-Key` 和帧掩码来自系统随机数。
+//!
+//! 需要启用网络权限和 `async` 特性。
+
+use super::json::{FractionJsonMode, value_to_json};
+use crate::evaluator::RuntimeError;
+use crate::value::Value;
+use base64::Engine as _;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io::{ErrorKind, Read, Write};
+use std::net::TcpStream;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// 握手（建立连接并读取响应头）的超时时间
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 响应头的最大长度
+const MAX_HEADER_LEN: usize = 64 * 1024;
+
+/// RFC 6455 握手使用的固定 GUID
+const WS_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+const OP_CONTINUATION: u8 = 0x0;
+const OP_TEXT: u8 = 0x1;
+const OP_BINARY: u8 = 0x2;
+const OP_CLOSE: u8 = 0x8;
+const OP_PING: u8 = 0x9;
+const OP_PONG: u8 = 0xA;
+
+/// 引擎打开的连接（句柄 -> 连接）
+#[derive(Default)]
+pub(crate) struct StreamConnections {
+    ws: HashMap<String, WsConnection>,
+    sse: HashMap<String, SseConnection>,
+}
+
+impl StreamConnections {
+    /// 关闭所有连接
+    pub(crate) fn clear(&mut self) {
+        for (_, conn) in self.ws.drain() {
+            conn.close();
+        }
+        for (_, conn) in self.sse.drain() {
+            conn.stream.shutdown();
+        }
+    }
+}
+
+impl Drop for StreamConnections {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+/// 引擎持有的连接
+pub(crate) type SharedStreamConnections = Rc<RefCell<StreamConnections>>;
+
+// 线程局部的当前引擎连接（求值期间有效）
+thread_local! {
+    static ACTIVE_CONNECTIONS: RefCell<Option<SharedStreamConnections>> = const { RefCell::new(None) };
+}
+
+/// 在作用域内设置当前引擎的连接（RAII 模式，结束时恢复之前的设置）
+pub(crate) struct ScopedStreamConnections {
+    previous: Option<SharedStreamConnections>,
+}
+
+impl ScopedStreamConnections {
+    pub(crate) fn set(connections: SharedStreamConnections) -> Self {
+        let previous = ACTIVE_CONNECTIONS.with(|c| c.borrow_mut().replace(connections));
+        Self { previous }
+    }
+}
+
+impl Drop for ScopedStreamConnections {
+    fn drop(&mut self) {
+        ACTIVE_CONNECTIONS.with(|c| *c.borrow_mut() = self.previous.take());
+    }
+}
+
+/// 读取或修改当前引擎的连接
+///
+/// 不在求值中时作用于一个临时的空表，打开的连接随即关闭。
+fn with_connections<R>(f: impl FnOnce(&mut StreamConnections) -> R) -> R {
+    match ACTIVE_CONNECTIONS.with(|c| c.borrow().clone()) {
+        Some(connections) => f(&mut connections.borrow_mut()),
+        None => f(&mut StreamConnections::default()),
+    }
+}
+
+/// 新连接的句柄，如 `ws:3f1c...`（128 位随机数）
+fn new_handle(prefix: &str) -> Result<String, RuntimeError> {
+    let token: String = random_bytes::<16>()?
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    Ok(format!("{}:{}", prefix, token))
+}
+
+fn error(message: impl Into<String>) -> RuntimeError {
+    RuntimeError::CustomError(message.into())
+}
+
+fn get_string(val: &Value) -> Result<String, RuntimeError> {
+    match val {
+        Value::String(s) => Ok(s.clone()),
+        _ => Err(RuntimeError::TypeErrorDetailed {
+            expected: "String".to_string(),
+            got: format!("{:?}", val),
+        }),
+    }
+}
+
+/// 系统随机数
+fn random_bytes<const N: usize>() -> Result<[u8; N], RuntimeError> {
+    ring::rand::generate(&ring::rand::SystemRandom::new())
+        .map(|random| random.expose())
+        .map_err(|_| error("Failed to generate random bytes"))
+}
+
+/// 计算 `Sec-WebSocket-Accept`（服务端对 `Sec-WebSocket-Key` 的应答）
+pub fn accept_key(key: &str) -> String {
+    let digest = ring::digest::digest(
+        &ring::digest::SHA1_FOR_LEGACY_USE_ONLY,
+        format!("{}{}", key, WS_GUID).as_bytes(),
+    );
+    base64::engine::general_purpose::STANDARD.encode(digest)
+}
+
+/// 解析后的 URL
+struct Endpoint {
+    secure: bool,
+    host: String,
+    port: u16,
+    /// 路径和查询字符串
+    target: String,
+}
+
+impl Endpoint {
+    /// 解析 URL，`schemes` 为 (明文协议, 加密协议)
+    fn parse(url: &str, schemes: (&str, &str)) -> Result<Self, RuntimeError> {
+        let (scheme, rest) = url
+            .split_once("://")
+            .ok_or_else(|| error(format!("Invalid URL '{}'", url)))?;
+        let secure = match scheme.to_lowercase() {
+            s if s == schemes.0 => false,
+            s if s == schemes.1 => true,
+            _ => {
+                return Err(error(format!(
+                    "Unsupported URL scheme '{}', expected {}:// or {}://",
+                    scheme, schemes.0, schemes.1
+                )));
+            }
+        };
+        let (authority, target) = match rest.find(['/', '?']) {
+            Some(i) if rest[i..].starts_with('/') => (&rest[..i], rest[i..].to_string()),
+            Some(i) => (&rest[..i], format!("/{}", &rest[i..])),
+            None => (rest, "/".to_string()),
+        };
+        let default_port = if secure { 443 } else { 80 };
+        let (host, port) = if let Some(v6) = authority.strip_prefix('[') {
+            let (host, after) = v6
+                .split_once(']')
+                .ok_or_else(|| error(format!("Invalid URL '{}'", url)))?;
+            let port = match after.strip_prefix(':') {
+                Some(p) => p.parse().ok(),
+                None if after.is_empty() => Some(default_port),
+                None => None,
+            };
+            (host.to_string(), port)
+        } else {
+            match authority.rsplit_once(':') {
+                Some((host, p)) => (host.to_string(), p.parse().ok()),
+                None => (authority.to_string(), Some(default_port)),
+            }
+        };
+        let port = port.ok_or_else(|| error(format!("Invalid port in URL '{}'", url)))?;
+        if host.is_empty() {
+            return Err(error(format!("Missing host in URL '{}'", url)));
+        }
+        Ok(Endpoint {
+            secure,
+            host,
+            port,
+            target,
+        })
+    }
+
+    /// Host 请求头
+    fn host_header(&self) -> String {
+        let host = if self.host.contains(':') {
+            format!("[{}]", self.host)
+        } else {
+            self.host.clone()
+        };
+        let default_port = if self.secure { 443 } else { 80 };
+        if self.port == default_port {
+            host
+        } else {
+            format!("{}:{}", host, self.port)
+        }
+    }
+}
+
+/// 底层连接（明文或 TLS）
+enum Transport {
+    Plain(TcpStream),
+    Tls(Box<rustls::StreamOwned<rustls::ClientConnection, TcpStream>>),
+}
+
+impl Transport {
+    fn connect(endpoint: &Endpoint) -> Result<Self, RuntimeError> {
+        let tcp = TcpStream::connect((endpoint.host.as_str(), endpoint.port)).map_err(|e| {
+            error(format!(
+                "Failed to connect to {}:{}: {}",
+                endpoint.host, endpoint.port, e
+            ))
+        })?;
+        let _ = tcp.set_nodelay(true);
+        if !endpoint.secure {
+            return Ok(Transport::Plain(tcp));
+        }
+
+        let roots = rustls::RootCertStore {
+            roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+        };
+        let config = rustls::ClientConfig::builder_with_provider(Arc::new(
+            rustls::crypto::ring::default_provider(),
+        ))
+        .with_safe_default_protocol_versions()
+        .map_err(|e| error(format!("TLS configuration error: {}", e)))?
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+        let server_name =
+            rustls::pki_types::ServerName::try_from(endpoint.host.clone()).map_err(|e| {
+                error(format!(
+                    "Invalid TLS server name '{}': {}",
+                    endpoint.host, e
+                ))
+            })?;
+        let conn = rustls::ClientConnection::new(Arc::new(config), server_name)
+            .map_err(|e| error(format!("TLS error: {}", e)))?;
+        Ok(Transport::Tls(Box::new(rustls::StreamOwned::new(
+            conn, tcp,
+        ))))
+    }
+
+    fn socket(&self) -> &TcpStream {
+        match self {
+            Transport::Plain(tcp) => tcp,
+            Transport::Tls(tls) => &tls.sock,
+        }
+    }
+
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            Transport::Plain(tcp) => tcp.read(buf),
+            Transport::Tls(tls) => tls.read(buf),
+        }
+    }
+
+    fn write_all(&mut self, data: &[u8]) -> Result<(), RuntimeError> {
+        match self {
+            Transport::Plain(tcp) => tcp.write_all(data).and_then(|_| tcp.flush()),
+            Transport::Tls(tls) => tls.write_all(data).and_then(|_| tls.flush()),
+        }
+        .map_err(|e| error(format!("Failed to send data: {}", e)))
+    }
+}
+
+/// 带接收缓冲区的连接
+struct Stream {
+    transport: Transport,
+    buf: Vec<u8>,
+}
+
+impl Stream {
+    /// 关闭底层连接
+    fn shutdown(&self) {
+        let _ = self.transport.socket().shutdown(std::net::Shutdown::Both);
+    }
+
+    /// 读取更多数据到缓冲区；到达截止时间时返回 Ok(false)
+    fn fill(&mut self, deadline: Option<Instant>) -> Result<bool, RuntimeError> {
+        let timeout = match deadline {
+            Some(deadline) => {
+                let now = Instant::now();
+                if now >= deadline {
+                    return Ok(false);
+                }
+                Some((deadline - now).max(Duration::from_millis(1)))
+            }
+            None => None,
+        };
+        self.transport
+            .socket()
+            .set_read_timeout(timeout)
+            .map_err(|e| error(format!("Failed to set read timeout: {}", e)))?;
+
+        let mut chunk = [0u8; 8192];
+        match self.transport.read(&mut chunk) {
+            Ok(0) => Err(error("Connection closed by peer")),
+            Ok(n) => {
+                self.buf.extend_from_slice(&chunk[..n]);
+                Ok(true)
+            }
+            Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => Ok(false),
+            Err(e) if e.kind() == ErrorKind::Interrupted => Ok(true),
+            Err(e) => Err(error(format!("Failed to receive data: {}", e))),
+        }
+    }
+
+    /// 发送 HTTP 请求并读取响应头，返回 (状态码, 小写的响应头)
+    fn http_request(
+        endpoint: &Endpoint,
+        headers: &[(String, String)],
+    ) -> Result<(Self, u16, HashMap<String, String>), RuntimeError> {
+        let mut stream = Stream {
+            transport: Transport::connect(endpoint)?,
+            buf: Vec::new(),
+        };
+        let mut request = format!(
+            "GET {} HTTP/1.1\r\nHost: {}\r\n",
+            endpoint.target,
+            endpoint.host_header()
+        );
+        for (name, value) in headers {
+            request.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        request.push_str("\r\n");
+        stream.transport.write_all(request.as_bytes())?;
+
+        let deadline = Instant::now() + HANDSHAKE_TIMEOUT;
+        let header_end = loop {
+            if let Some(pos) = stream.buf.windows(4).position(|w| w == b"\r\n\r\n") {
+                break pos;
+            }
+            if stream.buf.len() > MAX_HEADER_LEN {
+                return Err(error("Response headers too large"));
+            }
+            if !stream.fill(Some(deadline))? {
+                return Err(error("Timed out waiting for response headers"));
+            }
+        };
+
+        let head = String::from_utf8_lossy(&stream.buf[..header_end]).to_string();
+        stream.buf.drain(..header_end + 4);
+        let mut lines = head.split("\r\n");
+        let status = lines
+            .next()
+            .and_then(|line| line.split_whitespace().nth(1))
+            .and_then(|code| code.parse::<u16>().ok())
+            .ok_or_else(|| error("Invalid HTTP response"))?;
+        let response_headers = lines
+            .filter_map(|line| line.split_once(':'))
+            .map(|(name, value)| (name.trim().to_lowercase(), value.trim().to_string()))
+            .collect();
+        Ok((stream, status, response_headers))
+    }
+}
+
+/// 解析额外请求头参数（Dict: 名称 -> 值）
+fn headers_arg(arg: Option<&Value>) -> Result<Vec<(String, String)>, RuntimeError> {
+    match arg {
+        None | Some(Value::Null) => Ok(Vec::new()),
+        Some(Value::Dict(dict)) => {
+            let mut headers: Vec<(String, String)> = dict
+                .iter()
+                .map(|(name, value)| match value {
+                    Value::String(s) => (name.clone(), s.clone()),
+                    other => (name.clone(), other.to_string()),
+                })
+                .collect();
+            headers.sort();
+            if headers
+                .iter()
+                .any(|(name, value)| name.contains(['\r', '\n']) || value.contains(['\r', '\n']))
+            {
+                return Err(error(
+                    "Header names and values must not contain line breaks",
+                ));
+            }
+            Ok(headers)
+        }
+        Some(other) => Err(RuntimeError::TypeErrorDetailed {
+            expected: "Dict".to_string(),
+            got: format!("{:?}", other),
+        }),
+    }
+}
+
+/// 解析超时参数（秒），缺省或 Null 表示一直等待
+fn deadline_arg(arg: Option<&Value>) -> Result<Option<Instant>, RuntimeError> {
+    match arg {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::Number(secs)) if *secs >= 0.0 && secs.is_finite() => {
+            Ok(Some(Instant::now() + Duration::from_secs_f64(*secs)))
+        }
+        Some(other) => Err(RuntimeError::InvalidOperation(format!(
+            "Timeout must be a non-negative number of seconds, got {}",
+            other
+        ))),
+    }
+}
+
+/// 校验连接句柄（如 "ws:3f1c..."）的格式，返回句柄
+fn handle_id(handle: &Value, prefix: &str) -> Result<String, RuntimeError> {
+    let text = get_string(handle)?;
+    match text
+        .strip_prefix(prefix)
+        .and_then(|rest| rest.strip_prefix(':'))
+    {
+        Some(token) if !token.is_empty() => Ok(text),
+        _ => Err(error(format!("Invalid {} handle '{}'", prefix, text))),
+    }
+}
+
+fn check_arity(args: &[Value], min: usize, max: usize) -> Result<(), RuntimeError> {
+    if args.len() < min || args.len() > max {
+        return Err(RuntimeError::WrongArity {
+            expected: min,
+            got: args.len(),
+        });
+    }
+    Ok(())
+}
+
+// ============================================================================
+// WebSocket
+// ============================================================================
+
+/// 一个 WebSocket 帧
+struct Frame {
+    fin: bool,
+    opcode: u8,
+    payload: Vec<u8>,
+}
+
+/// 从缓冲区解析一个完整的帧，返回帧和消耗的字节数
+fn parse_frame(buf: &[u8]) -> Option<(Frame, usize)> {
+    if buf.len() < 2 {
+        return None;
+    }
+    let fin = buf[0] & 0x80 != 0;
+    let opcode = buf[0] & 0x0F;
+    let masked = buf[1] & 0x80 != 0;
+    let (len, mut offset) = match buf[1] & 0x7F {
+        126 => (u16::from_be_bytes([*buf.get(2)?, *buf.get(3)?]) as usize, 4),
+        127 => {
+            let bytes: [u8; 8] = buf.get(2..10)?.try_into().ok()?;
+            (u64::from_be_bytes(bytes) as usize, 10)
+        }
+        len => (len as usize, 2),
+    };
+    let mask = if masked {
+        let key: [u8; 4] = buf.get(offset..offset + 4)?.try_into().ok()?;
+        offset += 4;
+        Some(key)
+    } else {
+        None
+    };
+    let mut payload = buf.get(offset..offset.checked_add(len)?)?.to_vec();
+    if let Some(key) = mask {
+        for (i, byte) in payload.iter_mut().enumerate() {
+            *byte ^= key[i % 4];
+        }
+    }
+    Some((
+        Frame {
+            fin,
+            opcode,
+            payload,
+        },
+        offset + len,
+    ))
+}
+
+/// 编码客户端帧（客户端发送的帧必须加掩码）
+fn encode_frame(opcode: u8, payload: &[u8]) -> Result<Vec<u8>, RuntimeError> {
+    let mut frame = vec![0x80 | opcode];
+    match payload.len() {
+        len if len < 126 => frame.push(0x80 | len as u8),
+        len if len <= u16::MAX as usize => {
+            frame.push(0x80 | 126);
+            frame.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        len => {
+            frame.push(0x80 | 127);
+            frame.extend_from_slice(&(len as u64).to_be_bytes());
+        }
+    }
+    let mask: [u8; 4] = random_bytes()?;
+    frame.extend_from_slice(&mask);
+    frame.extend(payload.iter().enumerate().map(|(i, b)| b ^ mask[i % 4]));
+    Ok(frame)
+}
+
+/// WebSocket 连接
+struct WsConnection {
+    stream: Stream,
+    /// 分片消息的类型和已收到的内容
+    fragments: Option<(u8, Vec<u8>)>,
+    closed: bool,
+}
+
+impl WsConnection {
+    /// 发送一帧
+    fn send(&mut self, opcode: u8, payload: &[u8]) -> Result<(), RuntimeError> {
+        self.stream
+            .transport
+            .write_all(&encode_frame(opcode, payload)?)
+    }
+
+    /// 正常关闭连接（状态码 1000）
+    fn close(mut self) {
+        if !self.closed {
+            let _ = self.send(OP_CLOSE, &1000u16.to_be_bytes());
+        }
+        self.stream.shutdown();
+    }
+
+    /// 接收下一条完整的数据消息，超时返回 None
+    fn recv(&mut self, deadline: Option<Instant>) -> Result<Option<Value>, RuntimeError> {
+        loop {
+            if self.closed {
+                return Err(error("WebSocket connection is closed"));
+            }
+            let Some((frame, used)) = parse_frame(&self.stream.buf) else {
+                if !self.stream.fill(deadline)? {
+                    return Ok(None);
+                }
+                continue;
+            };
+            self.stream.buf.drain(..used);
+
+            match frame.opcode {
+                OP_PING => self.send(OP_PONG, &frame.payload)?,
+                OP_PONG => {}
+                OP_CLOSE => {
+                    let _ = self.send(OP_CLOSE, &frame.payload);
+                    self.closed = true;
+                }
+                OP_TEXT | OP_BINARY | OP_CONTINUATION => {
+                    let (opcode, mut data) = match (frame.opcode, self.fragments.take()) {
+                        (OP_CONTINUATION, Some((opcode, mut data))) => {
+                            data.extend_from_slice(&frame.payload);
+                            (opcode, data)
+                        }
+                        (OP_CONTINUATION, None) => {
+                            return Err(error("Unexpected WebSocket continuation frame"));
+                        }
+                        (opcode, _) => (opcode, frame.payload),
+                    };
+                    if !frame.fin {
+                        self.fragments = Some((opcode, std::mem::take(&mut data)));
+                        continue;
+                    }
+                    return Ok(Some(if opcode == OP_TEXT {
+                        Value::String(String::from_utf8_lossy(&data).into_owned())
+                    } else {
+                        Value::Array(data.into_iter().map(|b| Value::Number(b as f64)).collect())
+                    }));
+                }
+                other => {
+                    return Err(error(format!("Unsupported WebSocket opcode {}", other)));
+                }
+            }
+        }
+    }
+}
+
+fn with_ws<T>(
+    handle: &Value,
+    f: impl FnOnce(&mut WsConnection) -> Result<T, RuntimeError>,
+) -> Result<T, RuntimeError> {
+    let id = handle_id(handle, "ws")?;
+    with_connections(|conns| {
+        let conn = conns
+            .ws
+            .get_mut(&id)
+            .ok_or_else(|| error(format!("WebSocket connection {} is not open", id)))?;
+        f(conn)
+    })
+}
+
+/// 建立 WebSocket 连接
+///
+/// # 功能
+/// 连接 `ws://` 或 `wss://` 地址并完成握手（校验 `Sec-WebSocket-Accept`）。
+///
+/// # 参数
+/// - `url`: String - WebSocket 地址
+/// - `headers`: Dict - 额外的请求头（可选，如 `{"Authorization": "Bearer ..."}`）
+///
+/// # 返回值
+/// String - 连接句柄（如 `"ws:3f1c..."`），传给 WS_SEND / WS_RECV / WS_CLOSE
+///
+/// # 示例
+/// ```aether
+/// Set CONN WS_CONNECT("wss://stream.example.com/ticker")
+/// WS_SEND(CONN, {"subscribe": "BTC"})
+/// Set MSG WS_RECV(CONN, 5)
+/// ```
+///
+/// # 安全性
+/// 需要启用网络权限
+pub fn ws_connect(args: &[Value]) -> Result<Value, RuntimeError> {
+    check_arity(args, 1, 2)?;
+    let url = get_string(&args[0])?;
+    super::network::check_url_allowed(&url)?;
+    let endpoint = Endpoint::parse(&url, ("ws", "wss"))?;
+    let key = base64::engine::general_purpose::STANDARD.encode(random_bytes::<16>()?);
+
+    let mut headers = vec![
+        ("Upgrade".to_string(), "websocket".to_string()),
+        ("Connection".to_string(), "Upgrade".to_string()),
+        ("Sec-WebSocket-Key".to_string(), key.clone()),
+        ("Sec-WebSocket-Version".to_string(), "13".to_string()),
+    ];
+    headers.extend(headers_arg(args.get(1))?);
+
+    let (stream, status, response) = Stream::http_request(&endpoint, &headers)?;
+    if status != 101 {
+        return Err(error(format!(
+            "WebSocket handshake failed: server responded with status {}",
+            status
+        )));
+    }
+    if response.get("sec-websocket-accept") != Some(&accept_key(&key)) {
+        return Err(error(
+            "WebSocket handshake failed: invalid Sec-WebSocket-Accept",
+        ));
+    }
+
+    let handle = new_handle("ws")?;
+    let conn = WsConnection {
+        stream,
+        fragments: None,
+        closed: false,
+    };
+    with_connections(|conns| conns.ws.insert(handle.clone(), conn));
+    Ok(Value::String(handle))
+}
+
+/// 发送 WebSocket 消息
+///
+/// # 参数
+/// - `conn`: String - WS_CONNECT 返回的句柄
+/// - `message`: Any - String 按原样作为文本消息发送，其他值序列化为 JSON 文本
+///
+/// # 返回值
+/// 成功返回 true
+pub fn ws_send(args: &[Value]) -> Result<Value, RuntimeError> {
+    check_arity(args, 2, 2)?;
+    let text = match &args[1] {
+        Value::String(s) => s.clone(),
+        other => value_to_json(other, FractionJsonMode::Float)?.to_string(),
+    };
+    with_ws(&args[0], |conn| {
+        if conn.closed {
+            return Err(error("WebSocket connection is closed"));
+        }
+        conn.send(OP_TEXT, text.as_bytes())
+    })?;
+    Ok(Value::Boolean(true))
+}
+
+/// 接收 WebSocket 消息
+///
+/// # 功能
+/// 等待下一条完整的消息（自动拼接分片、应答 ping）。
+/// 服务端关闭连接时报错。
+///
+/// # 参数
+/// - `conn`: String - WS_CONNECT 返回的句柄
+/// - `timeout`: Number - 最长等待秒数（可选，默认一直等待）
+///
+/// # 返回值
+/// 文本消息返回 String，二进制消息返回字节数组，超时返回 Null
+///
+/// # 示例
+/// ```aether
+/// While (True) {
+///     Set MSG WS_RECV(CONN, 30)
+///     If (MSG != Null) {
+///         PRINTLN(JSON_PARSE(MSG))
+///     }
+/// }
+/// ```
+pub fn ws_recv(args: &[Value]) -> Result<Value, RuntimeError> {
+    check_arity(args, 1, 2)?;
+    let deadline = deadline_arg(args.get(1))?;
+    with_ws(&args[0], |conn| conn.recv(deadline)).map(|msg| msg.unwrap_or(Value::Null))
+}
+
+/// 关闭 WebSocket 连接
+///
+/// # 参数
+/// - `conn`: String - WS_CONNECT 返回的句柄
+///
+/// # 返回值
+/// Boolean - 连接之前是否处于打开状态
+pub fn ws_close(args: &[Value]) -> Result<Value, RuntimeError> {
+    check_arity(args, 1, 1)?;
+    let id = handle_id(&args[0], "ws")?;
+    let conn = with_connections(|conns| conns.ws.remove(&id));
+    let open = conn.is_some();
+    if let Some(conn) = conn {
+        conn.close();
+    }
+    Ok(Value::Boolean(open))
+}
+
+// ============================================================================
+// Server-Sent Events
+// ============================================================================
+
+/// SSE 连接
+struct SseConnection {
+    stream: Stream,
+    /// 响应体是否使用分块传输编码
+    chunked: bool,
+    /// 已解码的响应体（尚未解析成事件的部分）
+    body: Vec<u8>,
+    /// 分块编码已结束
+    finished: bool,
+    event: Option<String>,
+    data: Vec<String>,
+    last_id: Option<String>,
+}
+
+impl SseConnection {
+    /// 将缓冲区中完整的分块解码到响应体
+    fn decode_chunks(&mut self) -> Result<(), RuntimeError> {
+        if !self.chunked {
+            self.body.append(&mut self.stream.buf);
+            return Ok(());
+        }
+        while !self.finished {
+            let buf = &self.stream.buf;
+            let Some(line_end) = buf.windows(2).position(|w| w == b"\r\n") else {
+                return Ok(());
+            };
+            let size_text = String::from_utf8_lossy(&buf[..line_end]);
+            let size_text = size_text.split(';').next().unwrap_or("").trim();
+            let size = usize::from_str_radix(size_text, 16)
+                .map_err(|_| error(format!("Invalid chunk size '{}'", size_text)))?;
+            let start = line_end + 2;
+            if size == 0 {
+                self.finished = true;
+                self.stream.buf.clear();
+                return Ok(());
+            }
+            if buf.len() < start + size + 2 {
+                return Ok(());
+            }
+            self.body.extend_from_slice(&buf[start..start + size]);
+            self.stream.buf.drain(..start + size + 2);
+        }
+        Ok(())
+    }
+
+    /// 从响应体中取出一行（去掉行尾的 \r\n 或 \n）
+    fn take_line(&mut self) -> Option<String> {
+        let end = self.body.iter().position(|b| *b == b'\n')?;
+        let mut line: Vec<u8> = self.body.drain(..=end).collect();
+        line.pop();
+        if line.last() == Some(&b'\r') {
+            line.pop();
+        }
+        Some(String::from_utf8_lossy(&line).into_owned())
+    }
+
+    /// 处理一行，遇到空行且有数据时返回完整的事件
+    fn process_line(&mut self, line: &str) -> Option<Value> {
+        if line.is_empty() {
+            let event = self.event.take();
+            if self.data.is_empty() {
+                return None;
+            }
+            let mut dict = HashMap::new();
+            dict.insert(
+                "event".to_string(),
+                Value::String(event.unwrap_or_else(|| "message".to_string())),
+            );
+            dict.insert(
+                "data".to_string(),
+                Value::String(std::mem::take(&mut self.data).join("\n")),
+            );
+            dict.insert(
+                "id".to_string(),
+                self.last_id
+                    .clone()
+                    .map(Value::String)
+                    .unwrap_or(Value::Null),
+            );
+            return Some(Value::Dict(dict));
+        }
+        if line.starts_with(':') {
+            return None;
+        }
+        let (field, value) = line.split_once(':').unwrap_or((line, ""));
+        let value = value.strip_prefix(' ').unwrap_or(value);
+        match field {
+            "event" => self.event = Some(value.to_string()),
+            "data" => self.data.push(value.to_string()),
+            "id" if !value.contains('\0') => self.last_id = Some(value.to_string()),
+            _ => {}
+        }
+        None
+    }
+
+    /// 接收下一个事件，超时返回 None
+    fn recv(&mut self, deadline: Option<Instant>) -> Result<Option<Value>, RuntimeError> {
+        loop {
+            self.decode_chunks()?;
+            while let Some(line) = self.take_line() {
+                if let Some(event) = self.process_line(&line) {
+                    return Ok(Some(event));
+                }
+            }
+            if self.finished {
+                return Err(error("SSE stream ended"));
+            }
+            if !self.stream.fill(deadline)? {
+                return Ok(None);
+            }
+        }
+    }
+}
+
+/// 建立 Server-Sent Events 连接
+///
+/// # 参数
+/// - `url`: String - `http://` 或 `https://` 事件流地址
+/// - `headers`: Dict - 额外的请求头（可选）
+///
+/// # 返回值
+/// String - 连接句柄（如 `"sse:9b0e..."`），传给 SSE_RECV / SSE_CLOSE
+///
+/// # 示例
+/// ```aether
+/// Set EVENTS SSE_CONNECT("https://api.example.com/events")
+/// Set EVENT SSE_RECV(EVENTS, 10)
+/// ```
+///
+/// # 安全性
+/// 需要启用网络权限
+pub fn sse_connect(args: &[Value]) -> Result<Value, RuntimeError> {
+    check_arity(args, 1, 2)?;
+    let url = get_string(&args[0])?;
+    super::network::check_url_allowed(&url)?;
+    let endpoint = Endpoint::parse(&url, ("http", "https"))?;
+    let mut headers = vec![
+        ("Accept".to_string(), "text/event-stream".to_string()),
+        ("Cache-Control".to_string(), "no-cache".to_string()),
+    ];
+    headers.extend(headers_arg(args.get(1))?);
+
+    let (stream, status, response) = Stream::http_request(&endpoint, &headers)?;
+    if !(200..300).contains(&status) {
+        return Err(error(format!(
+            "SSE request failed: server responded with status {}",
+            status
+        )));
+    }
+    let chunked = response
+        .get("transfer-encoding")
+        .is_some_and(|v| v.to_lowercase().contains("chunked"));
+
+    let handle = new_handle("sse")?;
+    let conn = SseConnection {
+        stream,
+        chunked,
+        body: Vec::new(),
+        finished: false,
+        event: None,
+        data: Vec::new(),
+        last_id: None,
+    };
+    with_connections(|conns| conns.sse.insert(handle.clone(), conn));
+    Ok(Value::String(handle))
+}
+
+/// 接收下一个 SSE 事件
+///
+/// # 参数
+/// - `conn`: String - SSE_CONNECT 返回的句柄
+/// - `timeout`: Number - 最长等待秒数（可选，默认一直等待）
+///
+/// # 返回值
+/// Dict `{"event": 事件类型, "data": 数据, "id": 最近的事件 ID}`，超时返回 Null。
+/// 事件流结束后再调用会报错。
+pub fn sse_recv(args: &[Value]) -> Result<Value, RuntimeError> {
+    check_arity(args, 1, 2)?;
+    let deadline = deadline_arg(args.get(1))?;
+    let id = handle_id(&args[0], "sse")?;
+    with_connections(|conns| {
+        let conn = conns
+            .sse
+            .get_mut(&id)
+            .ok_or_else(|| error(format!("SSE connection {} is not open", id)))?;
+        conn.recv(deadline)
+            .map(|event| event.unwrap_or(Value::Null))
+    })
+}
+
+/// 关闭 SSE 连接
+///
+/// # 参数
+/// - `conn`: String - SSE_CONNECT 返回的句柄
+///
+/// # 返回值
+/// Boolean - 连接之前是否处于打开状态
+pub fn sse_close(args: &[Value]) -> Result<Value, RuntimeError> {
+    check_arity(args, 1, 1)?;
+    let id = handle_id(&args[0], "sse")?;
+    let conn = with_connections(|conns| conns.sse.remove(&id));
+    if let Some(conn) = &conn {
+        conn.stream.shutdown();
+    }
+    Ok(Value::Boolean(conn.is_some()))
+}
@@ -1,23 +1,144 @@
 // src/cache.rs
 //! AST缓存机制,减少重复解析
+//!
+//! 缓存的程序通过常量池共享语句节点和字符串字面量：由模板生成的大量相似脚本
+//! 往往包含相同的定义、函数体和常量，这些节点在池中只保存一份。
+//! 语句节点按去掉自身位置后的结构去重，位置信息留在各程序的 `Located` 包装中，
+//! 因此同一条语句出现在不同偏移处时仍然共享，错误信息中的行号也不受影响。
 
-use crate::ast::Program;
+use crate::ast::{Expr, Fold, Program, Stmt};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
 use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
 use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Weak};
+
+/// 缓存中的程序：语句节点与其他缓存程序共享
+pub type SharedProgram = Arc<[Stmt]>;
+
+/// 语句和字符串常量池
+///
+/// 递归地去重语句节点（包括函数体、循环体等嵌套语句）和字符串字面量。
+/// 语句只持有弱引用，缓存淘汰程序后不再被引用的语句随之释放；
+/// 字符串在清理时移除只剩池自身引用的条目。
+#[derive(Debug, Clone, Default)]
+struct ConstantPool {
+    /// 结构哈希 -> 具有该哈希的语句节点
+    statements: HashMap<u64, Vec<Weak<Stmt>>>,
+    /// 字符串字面量
+    strings: HashSet<Arc<str>>,
+    /// 本次插入处理的语句节点数
+    interned: usize,
+}
+
+impl ConstantPool {
+    /// 语句的结构哈希（AST 含浮点数，无法直接实现 Hash，使用 Debug 表示）
+    fn hash_stmt(stmt: &Stmt) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        format!("{:?}", stmt).hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// 把程序中的节点替换为池中的共享节点，返回程序和其中的语句节点数
+    fn intern_program(&mut self, program: Program) -> (SharedProgram, usize) {
+        self.interned = 0;
+        let shared = program
+            .into_iter()
+            .map(|stmt| self.fold_stmt(stmt))
+            .collect();
+        (shared, self.interned)
+    }
+
+    /// 返回池中与 `node`（不含自身位置）结构相同的语句，没有时加入池中
+    fn intern_node(&mut self, node: Stmt) -> Arc<Stmt> {
+        self.interned += 1;
+        let node = node.fold_children(self);
+        let bucket = self.statements.entry(Self::hash_stmt(&node)).or_default();
+        for existing in bucket.iter().filter_map(Weak::upgrade) {
+            if *existing == node {
+                return existing;
+            }
+        }
+        bucket.retain(|weak| weak.strong_count() > 0);
+        let shared = Arc::new(node);
+        bucket.push(Arc::downgrade(&shared));
+        shared
+    }
+
+    fn intern_str(&mut self, text: Arc<str>) -> Arc<str> {
+        match self.strings.get(&text) {
+            Some(existing) => Arc::clone(existing),
+            None => {
+                self.strings.insert(Arc::clone(&text));
+                text
+            }
+        }
+    }
+
+    /// 移除已释放的语句和不再被引用的字符串
+    fn prune(&mut self) {
+        self.statements.retain(|_, bucket| {
+            bucket.retain(|weak| weak.strong_count() > 0);
+            !bucket.is_empty()
+        });
+        self.strings.retain(|text| Arc::strong_count(text) > 1);
+    }
+
+    /// 池中仍被引用的语句数量
+    fn live(&self) -> usize {
+        self.statements
+            .values()
+            .flatten()
+            .filter(|weak| weak.strong_count() > 0)
+            .count()
+    }
+
+    fn clear(&mut self) {
+        self.statements.clear();
+        self.strings.clear();
+    }
+}
+
+impl Fold for ConstantPool {
+    fn fold_stmt(&mut self, stmt: Stmt) -> Stmt {
+        match stmt {
+            Stmt::Located { span, stmt } => Stmt::Located {
+                span,
+                stmt: self.intern_node(Arc::unwrap_or_clone(stmt)),
+            },
+            // 没有位置信息的语句（由代码构造）只共享其子节点
+            other => {
+                self.interned += 1;
+                other.fold_children(self)
+            }
+        }
+    }
+
+    fn fold_expr(&mut self, expr: Expr) -> Expr {
+        match expr {
+            Expr::String(text) => Expr::String(self.intern_str(text)),
+            other => other.fold_children(self),
+        }
+    }
+}
 
 /// AST缓存,用于存储已解析的程序
-#[derive(Debug)]
+///
+/// 克隆缓存只复制程序的引用，克隆后的缓存与原缓存共享语句。
+#[derive(Debug, Clone)]
 pub struct ASTCache {
-    /// 缓存存储: hash -> 解析后的AST
-    cache: HashMap<u64, Program>,
+    /// 缓存存储: hash -> (解析后的AST, 其中的语句节点数)
+    cache: HashMap<u64, (SharedProgram, usize)>,
+    /// 跨程序共享的语句节点和字符串
+    pool: ConstantPool,
     /// 缓存大小限制
     max_size: usize,
     /// 缓存命中统计
     hits: usize,
     /// 缓存未命中统计
     misses: usize,
+    /// 已按热点函数特化的程序: hash -> 特化时使用的热点函数
+    specialized: HashMap<u64, Vec<String>>,
 }
 
 impl ASTCache {
@@ -30,9 +151,11 @@ impl ASTCache {
     pub fn with_capacity(max_size: usize) -> Self {
         ASTCache {
             cache: HashMap::with_capacity(max_size.min(100)),
+            pool: ConstantPool::default(),
             max_size,
             hits: 0,
             misses: 0,
+            specialized: HashMap::new(),
         }
     }
 
@@ -45,18 +168,23 @@ impl ASTCache {
 
     /// 从缓存中获取AST
     pub fn get(&mut self, code: &str) -> Option<Program> {
+        self.get_shared(code).map(|program| program.to_vec())
+    }
+
+    /// 从缓存中获取共享的AST（不复制语句）
+    pub fn get_shared(&mut self, code: &str) -> Option<SharedProgram> {
         let hash = Self::hash_code(code);
-        if let Some(program) = self.cache.get(&hash) {
+        if let Some((program, _)) = self.cache.get(&hash) {
             self.hits += 1;
-            Some(program.clone())
+            Some(Arc::clone(program))
         } else {
             self.misses += 1;
             None
         }
     }
 
-    /// 将AST存入缓存
-    pub fn insert(&mut self, code: &str, program: Program) {
+    /// 将AST存入缓存，返回与其他缓存程序共享语句后的程序
+    pub fn insert(&mut self, code: &str, program: Program) -> SharedProgram {
         let hash = Self::hash_code(code);
 
         // 如果缓存已满,使用简单的FIFO策略清理
@@ -66,15 +194,51 @@ impl ASTCache {
             let keys_to_remove: Vec<u64> = self.cache.keys().take(to_remove).copied().collect();
             for key in keys_to_remove {
                 self.cache.remove(&key);
+                self.specialized.remove(&key);
             }
+            self.pool.prune();
         }
 
-        self.cache.insert(hash, program);
+        let (shared, statements) = self.pool.intern_program(program);
+        self.cache.insert(hash, (Arc::clone(&shared), statements));
+        self.specialized.remove(&hash);
+        shared
+    }
+
+    /// 缓存程序上次特化时使用的热点函数（从未特化时返回 None）
+    pub fn specialized_for(&self, code: &str) -> Option<&[String]> {
+        self.specialized
+            .get(&Self::hash_code(code))
+            .map(Vec::as_slice)
+    }
+
+    /// 记录对缓存程序的特化
+    ///
+    /// `program` 为特化后的程序时替换缓存条目并返回新程序；为 None 时只记录
+    /// 本次使用的热点函数，避免对同一组热点重复尝试。代码不在缓存中时不做任何事。
+    pub fn specialize(
+        &mut self,
+        code: &str,
+        hot: Vec<String>,
+        program: Option<Program>,
+    ) -> Option<SharedProgram> {
+        let hash = Self::hash_code(code);
+        if !self.cache.contains_key(&hash) {
+            return None;
+        }
+        self.specialized.insert(hash, hot);
+        let program = program?;
+        let (shared, statements) = self.pool.intern_program(program);
+        self.cache.insert(hash, (Arc::clone(&shared), statements));
+        self.pool.prune();
+        Some(shared)
     }
 
     /// 清空缓存
     pub fn clear(&mut self) {
         self.cache.clear();
+        self.pool.clear();
+        self.specialized.clear();
         self.hits = 0;
         self.misses = 0;
     }
@@ -91,6 +255,8 @@ impl ASTCache {
             } else {
                 0.0
             },
+            statements: self.cache.values().map(|(_, statements)| statements).sum(),
+            unique_statements: self.pool.live(),
         }
     }
 }
@@ -102,7 +268,7 @@ impl Default for ASTCache {
 }
 
 /// 缓存统计信息
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CacheStats {
     /// 当前缓存大小
     pub size: usize,
@@ -114,18 +280,26 @@ pub struct CacheStats {
     pub misses: usize,
     /// 缓存命中率
     pub hit_rate: f64,
+    /// 缓存程序中的语句总数（包括嵌套语句）
+    #[serde(default)]
+    pub statements: usize,
+    /// 去重后实际保存的语句数
+    #[serde(default)]
+    pub unique_statements: usize,
 }
 
 impl std::fmt::Display for CacheStats {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(
             f,
-            "Cache Stats: size={}/{}, hits={}, misses={}, hit_rate={:.2}%",
+            "Cache Stats: size={}/{}, hits={}, misses={}, hit_rate={:.2}%, statements={} ({} unique)",
             self.size,
             self.max_size,
             self.hits,
             self.misses,
-            self.hit_rate * 100.0
+            self.hit_rate * 100.0,
+            self.statements,
+            self.unique_statements
         )
     }
 }
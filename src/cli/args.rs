@@ -3,28 +3,120 @@ pub struct RunOptions {
     pub load_stdlib: bool,
     pub debug_mode: bool,
     pub debugger_mode: bool,
+    pub debug_listen: Option<String>,
     pub json_error: bool,
+    pub json_io: bool,
     pub metrics_mode: bool,
     pub metrics_json_mode: bool,
     pub metrics_json_pretty_mode: bool,
+    pub mem_report: bool,
     pub show_trace: bool,
     pub show_trace_stats: bool,
     pub trace_buffer_size: Option<usize>,
+    pub entry: Option<String>,
+    pub plugins: Vec<String>,
+    pub record_io: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(not(feature = "http-server"), allow(dead_code))]
+pub struct ServeArgs {
+    pub listen: String,
+    pub pool_size: Option<usize>,
+    pub policy: String,
+    pub root: Option<String>,
+    pub max_requests: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(not(feature = "kernel"), allow(dead_code))]
+pub struct KernelInstallArgs {
+    pub prefix: Option<String>,
+    pub display_name: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct LearnArgs {
+    pub lesson: Option<String>,
+    pub list: bool,
+    pub check: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct TestArgs {
+    pub paths: Vec<String>,
+    pub update_snapshots: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct MinifyArgs {
+    pub file: String,
+    pub rename_locals: bool,
+    pub output: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReplayArgs {
+    pub trace_file: String,
+    pub load_stdlib: bool,
 }
 
 #[derive(Debug, Clone)]
 pub enum CliCommand {
-    Repl,
+    Repl { no_io: bool },
     Help,
     Check { file: String },
-    Ast { file: String },
+    Ast { file: String, format: String },
     Run { file: String, options: RunOptions },
+    Serve { args: ServeArgs },
+    Kernel { connection_file: String },
+    KernelInstall { args: KernelInstallArgs },
+    Learn { args: LearnArgs },
+    Test { args: TestArgs },
+    Minify { args: MinifyArgs },
+    Replay { args: ReplayArgs },
     Error { message: String },
 }
 
 pub fn parse(args: &[String]) -> CliCommand {
     if args.len() <= 1 {
-        return CliCommand::Repl;
+        return CliCommand::Repl { no_io: false };
+    }
+
+    // `aether run <file>` is the same as `aether <file>`
+    if args[1] == "run" {
+        let mut rest = args.to_vec();
+        rest.remove(1);
+        if rest.len() <= 1 {
+            return CliCommand::Error {
+                message: "错误: 未指定脚本文件".to_string(),
+            };
+        }
+        return parse(&rest);
+    }
+
+    if args[1] == "serve" {
+        return parse_serve(args);
+    }
+
+    if args[1] == "kernel" {
+        return parse_kernel(args);
+    }
+
+    if args[1] == "learn" {
+        return parse_learn(args);
+    }
+
+    if args[1] == "test" {
+        return parse_test(args);
+    }
+
+    if args[1] == "minify" {
+        return parse_minify(args);
+    }
+
+    if args[1] == "replay" {
+        return parse_replay(args);
     }
 
     // Flags
@@ -33,17 +125,28 @@ pub fn parse(args: &[String]) -> CliCommand {
     let check_only = args.contains(&"--check".to_string());
     let debug_mode = args.contains(&"--debug".to_string());
     let debugger_mode = args.contains(&"--debugger".to_string());
+    let debug_listen = get_flag_value(args, "--debug-listen").map(|addr| {
+        // A bare port listens on localhost only; pass HOST:PORT to expose it
+        if addr.parse::<u16>().is_ok() {
+            format!("127.0.0.1:{}", addr)
+        } else {
+            addr
+        }
+    });
 
     let metrics_mode = args.contains(&"--metrics".to_string());
     let metrics_json_mode = args.contains(&"--metrics-json".to_string());
     let metrics_json_pretty_mode = args.contains(&"--metrics-json-pretty".to_string());
     let metrics_json_output = metrics_json_mode || metrics_json_pretty_mode;
 
+    let mem_report = args.contains(&"--mem-report".to_string());
     let show_trace = args.contains(&"--trace".to_string());
     let show_trace_stats = args.contains(&"--trace-stats".to_string());
     let trace_buffer_size = get_usize_flag_value(args, "--trace-buffer-size");
+    let entry = get_flag_value(args, "--entry");
 
     let json_error = args.contains(&"--json-error".to_string());
+    let json_io = args.contains(&"--json-io".to_string());
     let show_help = args.contains(&"--help".to_string()) || args.contains(&"-h".to_string());
 
     if show_help {
@@ -51,6 +154,9 @@ pub fn parse(args: &[String]) -> CliCommand {
     }
 
     let script_file = find_script_file(args);
+    if script_file.is_none() && args.contains(&"--no-io".to_string()) {
+        return CliCommand::Repl { no_io: true };
+    }
     let Some(file) = script_file else {
         return CliCommand::Error {
             message: "错误: 未指定脚本文件".to_string(),
@@ -66,6 +172,7 @@ pub fn parse(args: &[String]) -> CliCommand {
     if show_ast {
         return CliCommand::Ast {
             file: file.to_string(),
+            format: get_flag_value(args, "--format").unwrap_or_else(|| "debug".to_string()),
         };
     }
 
@@ -75,13 +182,126 @@ pub fn parse(args: &[String]) -> CliCommand {
             load_stdlib,
             debug_mode,
             debugger_mode,
+            debug_listen,
             json_error,
+            json_io,
             metrics_mode,
             metrics_json_mode: metrics_json_output,
             metrics_json_pretty_mode,
+            mem_report,
             show_trace,
             show_trace_stats,
             trace_buffer_size,
+            entry,
+            plugins: get_flag_values(args, "--plugin"),
+            record_io: get_flag_value(args, "--record-io"),
+        },
+    }
+}
+
+fn parse_serve(args: &[String]) -> CliCommand {
+    if args.contains(&"--help".to_string()) || args.contains(&"-h".to_string()) {
+        return CliCommand::Help;
+    }
+    CliCommand::Serve {
+        args: ServeArgs {
+            listen: get_flag_value(args, "--listen")
+                .unwrap_or_else(|| "127.0.0.1:7000".to_string()),
+            pool_size: get_usize_flag_value(args, "--pool"),
+            policy: get_flag_value(args, "--policy").unwrap_or_else(|| "safe".to_string()),
+            root: get_flag_value(args, "--root"),
+            max_requests: get_usize_flag_value(args, "--max-requests"),
+        },
+    }
+}
+
+fn parse_kernel(args: &[String]) -> CliCommand {
+    if args.contains(&"--help".to_string()) || args.contains(&"-h".to_string()) {
+        return CliCommand::Help;
+    }
+    if args.get(2).is_some_and(|a| a == "install") {
+        return CliCommand::KernelInstall {
+            args: KernelInstallArgs {
+                prefix: get_flag_value(args, "--prefix"),
+                display_name: get_flag_value(args, "--display-name")
+                    .unwrap_or_else(|| "Aether".to_string()),
+            },
+        };
+    }
+    match get_flag_value(args, "--connection-file").or_else(|| get_flag_value(args, "-f")) {
+        Some(connection_file) => CliCommand::Kernel { connection_file },
+        None => CliCommand::Error {
+            message:
+                "错误: aether kernel 需要 --connection-file <文件>（或使用 aether kernel install）"
+                    .to_string(),
+        },
+    }
+}
+
+fn parse_learn(args: &[String]) -> CliCommand {
+    if args.contains(&"--help".to_string()) || args.contains(&"-h".to_string()) {
+        return CliCommand::Help;
+    }
+    CliCommand::Learn {
+        args: LearnArgs {
+            lesson: args[2..].iter().find(|a| !a.starts_with('-')).cloned(),
+            list: args.contains(&"--list".to_string()),
+            check: args.contains(&"--check".to_string()),
+        },
+    }
+}
+
+fn parse_test(args: &[String]) -> CliCommand {
+    if args.contains(&"--help".to_string()) || args.contains(&"-h".to_string()) {
+        return CliCommand::Help;
+    }
+    CliCommand::Test {
+        args: TestArgs {
+            paths: args[2..]
+                .iter()
+                .filter(|a| !a.starts_with('-'))
+                .cloned()
+                .collect(),
+            update_snapshots: args.contains(&"--update-snapshots".to_string()),
+        },
+    }
+}
+
+fn parse_minify(args: &[String]) -> CliCommand {
+    if args.contains(&"--help".to_string()) || args.contains(&"-h".to_string()) {
+        return CliCommand::Help;
+    }
+    let output = get_flag_value(args, "-o").or_else(|| get_flag_value(args, "--output"));
+    let file = (2..args.len())
+        .find(|&i| !args[i].starts_with('-') && !matches!(args[i - 1].as_str(), "-o" | "--output"))
+        .map(|i| args[i].clone());
+    match file {
+        Some(file) => CliCommand::Minify {
+            args: MinifyArgs {
+                file,
+                rename_locals: args.contains(&"--rename-locals".to_string()),
+                output,
+            },
+        },
+        None => CliCommand::Error {
+            message: "错误: aether minify 需要指定脚本文件".to_string(),
+        },
+    }
+}
+
+fn parse_replay(args: &[String]) -> CliCommand {
+    if args.contains(&"--help".to_string()) || args.contains(&"-h".to_string()) {
+        return CliCommand::Help;
+    }
+    match args[2..].iter().find(|a| !a.starts_with('-')) {
+        Some(trace_file) => CliCommand::Replay {
+            args: ReplayArgs {
+                trace_file: trace_file.clone(),
+                load_stdlib: !args.contains(&"--no-stdlib".to_string()),
+            },
+        },
+        None => CliCommand::Error {
+            message: "错误: aether replay 需要指定录制文件".to_string(),
         },
     }
 }
@@ -94,13 +314,33 @@ fn get_usize_flag_value(args: &[String], flag: &str) -> Option<usize> {
     })
 }
 
+fn get_flag_values(args: &[String], flag: &str) -> Vec<String> {
+    args.windows(2)
+        .filter(|pair| pair[0] == flag)
+        .map(|pair| pair[1].clone())
+        .collect()
+}
+
+fn get_flag_value(args: &[String], flag: &str) -> Option<String> {
+    args.iter()
+        .position(|a| a == flag)
+        .and_then(|idx| args.get(idx + 1))
+        .cloned()
+}
+
 fn find_script_file(args: &[String]) -> Option<&str> {
     let mut i = 1;
     while i < args.len() {
         let arg = &args[i];
 
         // Flags with a following value
-        if arg == "--trace-buffer-size" {
+        if arg == "--trace-buffer-size"
+            || arg == "--entry"
+            || arg == "--format"
+            || arg == "--plugin"
+            || arg == "--debug-listen"
+            || arg == "--record-io"
+        {
             i += 2;
             continue;
         }
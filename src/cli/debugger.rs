@@ -1,27 +1,18 @@
 // src/cli/debugger.rs
 //! Debugger CLI implementation
 
-use aether::debugger::{CommandAction, DebuggerSession};
+use aether::debugger::{CommandAction, DebuggerSession, RemoteConnection, RemoteDebugServer};
 use std::cell::RefCell;
 use std::io::{self, Write};
 use std::rc::Rc;
 
 pub fn run_debugger(filename: &str) {
-    // Read source code
-    let source = match std::fs::read_to_string(filename) {
-        Ok(s) => s,
-        Err(e) => {
-            eprintln!("Error reading file {}: {}", filename, e);
-            std::process::exit(1);
-        }
-    };
+    let source = read_source(filename);
 
-    // Create a new evaluator for the debugger
-    use aether::environment::Environment;
+    // Create a new evaluator for the debugger (with built-ins, so `record` can run the program)
     use aether::evaluator::Evaluator;
 
-    let env = Rc::new(RefCell::new(Environment::new()));
-    let evaluator = Rc::new(RefCell::new(Evaluator::with_env(env)));
+    let evaluator = Rc::new(RefCell::new(Evaluator::new()));
 
     // Set source file in evaluator
     evaluator.borrow_mut().set_source_file(filename.to_string());
@@ -33,6 +24,7 @@ pub fn run_debugger(filename: &str) {
     println!("\nDebugger ready. Type 'help' for commands.");
     println!("Note: This is a basic implementation. For full debugging support,");
     println!("the Evaluator needs to be enhanced to support pausing and resuming.");
+    println!("Use 'record' to run the program once and step backwards through its history.");
 
     // Main debugger REPL loop
     loop {
@@ -109,3 +101,98 @@ fn show_current_context(session: &DebuggerSession, source: &str) {
         }
     }
 }
+
+/// Pause before the first statement and serve one remote debugger client over TCP
+pub fn run_remote_debugger(filename: &str, addr: &str) {
+    let source = read_source(filename);
+
+    use aether::evaluator::Evaluator;
+
+    let evaluator = Rc::new(RefCell::new(Evaluator::new()));
+    evaluator.borrow_mut().set_source_file(filename.to_string());
+    let mut session = DebuggerSession::new(evaluator);
+    session.set_source(source, filename.to_string());
+    session.state_mut().activate();
+
+    let server = match RemoteDebugServer::bind(addr) {
+        Ok(server) => server,
+        Err(e) => {
+            eprintln!("Error listening on {}: {}", addr, e);
+            std::process::exit(1);
+        }
+    };
+    let local = server
+        .local_addr()
+        .map(|a| a.to_string())
+        .unwrap_or_else(|_| addr.to_string());
+    eprintln!(
+        "Debugger listening on {} (paused before the first statement)",
+        local
+    );
+
+    let mut connection = match server.accept() {
+        Ok(connection) => connection,
+        Err(e) => {
+            eprintln!("Error accepting debugger connection: {}", e);
+            std::process::exit(1);
+        }
+    };
+    if let Ok(peer) = connection.peer_addr() {
+        eprintln!("Debugger attached from {}", peer);
+    }
+
+    let mut finished = false;
+    if let Err(e) = serve_remote(&mut connection, &mut session, &mut finished) {
+        eprintln!("Debugger connection error: {}", e);
+    }
+    eprintln!("Debugger detached");
+
+    // A detached program still runs, like one started without a debugger
+    if !finished {
+        let status = session.run_program();
+        if status.starts_with("Program stopped with error") {
+            eprintln!("{}", status);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn serve_remote(
+    connection: &mut RemoteConnection,
+    session: &mut DebuggerSession,
+    finished: &mut bool,
+) -> io::Result<()> {
+    connection.send(&session.banner())?;
+    connection.send(
+        "Program is paused before the first statement. \
+         Use 'continue' to run it or 'record' to run it with time-travel recording.",
+    )?;
+
+    loop {
+        let action = connection.serve(session)?;
+        // `record` runs the program too
+        *finished |= session.recording().is_some();
+        match action {
+            CommandAction::Continue if !*finished => {
+                let status = session.run_program();
+                *finished = true;
+                eprintln!("{}", status);
+                connection.send(&status)?;
+            }
+            CommandAction::Continue => connection.send(
+                "The program has already run. Use 'record' to replay it or 'quit' to detach.",
+            )?,
+            CommandAction::Quit | CommandAction::Stay => return Ok(()),
+        }
+    }
+}
+
+fn read_source(filename: &str) -> String {
+    match std::fs::read_to_string(filename) {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("Error reading file {}: {}", filename, e);
+            std::process::exit(1);
+        }
+    }
+}
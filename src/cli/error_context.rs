@@ -6,6 +6,21 @@ pub fn print_detailed_error(source: &str, error_msg: &str) {
     }
 }
 
+/// 打印解析错误：源码位置、期望的词法单元和修复建议
+pub fn print_parse_error(source: &str, error: &aether::ParseError) {
+    print_detailed_error(source, &error.to_string());
+
+    if error.expected_tokens().len() > 1 {
+        eprintln!("期望以下之一: {}", error.expected_tokens().join(", "));
+    }
+    for fix in error.fixes() {
+        eprintln!(
+            "修复建议: {}（行 {}, 列 {}）",
+            fix.message, fix.line, fix.column
+        );
+    }
+}
+
 pub fn extract_line_column(error_msg: &str) -> Option<(usize, usize)> {
     if let Some(line_start) = error_msg.find("line ")
         && let Some(line_end) = error_msg[line_start..].find(',')
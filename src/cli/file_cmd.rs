@@ -1,3 +1,4 @@
+use crate::cli::args::MinifyArgs;
 use crate::cli::error_context;
 use std::fs;
 
@@ -34,10 +35,11 @@ pub fn check_file(filename: &str) {
                     println!("  - {} 个词法单元", token_count);
                     println!("  - {} 条语句", program.len());
                     println!();
+                    print_lint_warnings(&code);
                 }
                 Err(e) => {
                     eprintln!("✗ 语法错误:");
-                    error_context::print_detailed_error(&code, &e.to_string());
+                    error_context::print_parse_error(&code, &e);
                     std::process::exit(1);
                 }
             }
@@ -49,13 +51,54 @@ pub fn check_file(filename: &str) {
     }
 }
 
-pub fn show_ast_for_file(filename: &str) {
+/// 打印语义检查警告（未定义的名称、未使用的函数、遮蔽内置函数）及汇总
+fn print_lint_warnings(code: &str) {
+    use aether::analysis::{LintKind, lint};
+
+    let Ok(warnings) = lint(code) else {
+        return;
+    };
+    if warnings.is_empty() {
+        println!("✓ 语义检查通过");
+        return;
+    }
+
+    for warning in &warnings {
+        let label = match warning.kind {
+            LintKind::UndefinedName => "未定义",
+            LintKind::UnusedFunction => "未使用",
+            LintKind::ShadowedBuiltin => "遮蔽内置函数",
+        };
+        println!("⚠ [{}] {}", label, warning);
+    }
+    let count = |kind| warnings.iter().filter(|w| w.kind == kind).count();
+    println!();
+    println!(
+        "共 {} 个警告: {} 个未定义的名称, {} 个未使用的函数, {} 个遮蔽内置函数的名称",
+        warnings.len(),
+        count(LintKind::UndefinedName),
+        count(LintKind::UnusedFunction),
+        count(LintKind::ShadowedBuiltin)
+    );
+}
+
+pub fn show_ast_for_file(filename: &str, format: &str) {
     match fs::read_to_string(filename) {
         Ok(code) => {
             use aether::Parser;
 
             let mut parser = Parser::new(&code);
             match parser.parse_program() {
+                Ok(program) if format == "json" => {
+                    println!("{}", aether::ast::program_to_json(&program));
+                }
+                Ok(program) if format == "dot" => {
+                    print!("{}", aether::ast::program_to_dot(&program));
+                }
+                Ok(_) if format != "debug" => {
+                    eprintln!("✗ 未知的 AST 格式 '{}'，可用: debug, json, dot", format);
+                    std::process::exit(1);
+                }
                 Ok(program) => {
                     println!("=== 抽象语法树 (AST) ===");
                     println!("文件: {}", filename);
@@ -66,7 +109,7 @@ pub fn show_ast_for_file(filename: &str) {
                 }
                 Err(e) => {
                     eprintln!("✗ 解析错误:");
-                    error_context::print_detailed_error(&code, &e.to_string());
+                    error_context::print_parse_error(&code, &e);
                     std::process::exit(1);
                 }
             }
@@ -77,3 +120,43 @@ pub fn show_ast_for_file(filename: &str) {
         }
     }
 }
+
+/// 压缩脚本，写到 `--output` 指定的文件或 stdout，并在 stderr 打印压缩比例
+pub fn minify_file(args: MinifyArgs) {
+    use aether::minify::{MinifyOptions, minify};
+
+    let code = match fs::read_to_string(&args.file) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("✗ 无法读取文件 '{}': {}", args.file, e);
+            std::process::exit(1);
+        }
+    };
+    let options = MinifyOptions {
+        rename_locals: args.rename_locals,
+    };
+    let compact = match minify(&code, &options) {
+        Ok(compact) => compact,
+        Err(e) => {
+            eprintln!("✗ 语法错误:");
+            error_context::print_parse_error(&code, &e);
+            std::process::exit(1);
+        }
+    };
+
+    match &args.output {
+        Some(path) => {
+            if let Err(e) = fs::write(path, format!("{}\n", compact)) {
+                eprintln!("✗ 无法写入文件 '{}': {}", path, e);
+                std::process::exit(1);
+            }
+        }
+        None => println!("{}", compact),
+    }
+    eprintln!(
+        "✓ {} -> {} 字节 ({:.1}%)",
+        code.len(),
+        compact.len(),
+        compact.len() as f64 * 100.0 / code.len().max(1) as f64
+    );
+}
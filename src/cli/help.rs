@@ -3,29 +3,90 @@ pub fn print_cli_help() {
     println!();
     println!("用法:");
     println!("  aether [选项] <脚本文件>");
+    println!("  aether [选项] <项目目录>   # 运行项目（读取 aether.json）");
+    println!("  aether run [选项] <脚本>   # 同 aether [选项] <脚本>");
+    println!("  aether serve [服务选项]   # 启动 HTTP/JSON 服务（需要 http-server 特性）");
+    println!("  aether kernel install     # 安装 Jupyter 内核规格");
+    println!("  aether learn [课程]       # 交互式入门教程（从第 1 课或指定课程开始）");
+    println!("  aether test [路径...]     # 运行 *_test.aether 测试文件（默认当前目录）");
+    println!("  aether minify <脚本>      # 输出压缩后的脚本（去掉注释和多余空白）");
+    println!("  aether replay <录制文件>  # 用录制的 IO 结果确定性地重新执行脚本");
     println!("  aether                    # 启动 REPL 交互模式");
+    println!("  aether --no-io            # 启动禁用 IO 的 REPL（可用 :grant 按需开启）");
     println!();
     println!("选项:");
     println!("  -h, --help               显示此帮助信息");
     println!("  --check                  只检查语法，不执行代码");
     println!("  --ast                    显示抽象语法树 (AST)");
+    println!(
+        "  --format <FMT>           与 --ast 一起使用: debug（默认）、json 或 dot（Graphviz）"
+    );
     println!("  --debug                  启用调试模式（打印额外运行信息）");
     println!("  --debugger               启动交互式调试器 (类似GDB)");
+    println!(
+        "  --debug-listen <PORT>    在开始处暂停并等待远程调试器通过 TCP 连接（端口默认只监听 127.0.0.1，可写 HOST:PORT）"
+    );
     println!("  --metrics                执行后打印性能指标（耗时/缓存/trace 统计）");
     println!("  --metrics-json           以 JSON 输出结果 + 性能指标（机器可读）");
     println!("  --metrics-json-pretty    以格式化 JSON 输出结果 + 性能指标（机器可读）");
+    println!(
+        "  --mem-report             执行后打印内存报告（按类型汇总、最大的集合、疑似闭包循环）"
+    );
     println!("  --no-stdlib              不自动加载标准库");
     println!("  --json-error             出错时输出结构化 JSON 错误（写到 stderr）");
+    println!(
+        "  --json-io                从 stdin 读取 JSON 到 INPUT_DATA，结果以 JSON 写到 stdout"
+    );
     println!("  --trace                  执行后打印 TRACE 缓冲区内容");
     println!("  --trace-stats            执行后打印 TRACE 统计信息");
     println!("  --trace-buffer-size <N>  设置 TRACE 缓冲区容量（条目数）");
+    println!("  --entry <NAME>           运行项目时选择入口（aether.json 中的具名入口或相对路径）");
+    println!("  --plugin <PATH>          加载内置函数包插件（.so/.dylib/.dll），可重复使用");
+    println!(
+        "  --record-io <FILE>       把文件/网络/KV/INPUT/NOW/RANDOM 等调用的结果录制到 FILE（JSON）"
+    );
+    println!();
+    println!("服务选项 (aether serve):");
+    println!("  --listen <ADDR>          监听地址（默认 127.0.0.1:7000）");
+    println!("  --pool <N>               工作线程数（默认 4，每个线程一个引擎）");
+    println!("  --policy <safe|full>     沙箱策略：safe 禁用 IO 并严格限制执行，full 允许所有 IO");
+    println!("  --root <DIR>             只允许读取 DIR 内的文件和模块（覆盖 --policy）");
+    println!("  --max-requests <N>       处理 N 个请求后退出");
+    println!(
+        "  接口: GET /health, GET /metrics, POST /eval {{\"code\", \"inputs\"}}, POST /compile {{\"code\"}}"
+    );
+    println!();
+    println!("Jupyter 内核选项 (aether kernel):");
+    println!("  install                  写入内核规格（kernel.json）到 Jupyter 数据目录");
+    println!("  --prefix <DIR>           安装到 DIR/share/jupyter/kernels（如虚拟环境）");
+    println!("  --display-name <NAME>    笔记本中显示的内核名称（默认 Aether）");
+    println!("  --connection-file <FILE> 由 Jupyter 启动内核时传入的连接文件");
+    println!();
+    println!("测试选项 (aether test):");
+    println!("  [路径...]                测试文件或目录（递归查找 *_test.aether）");
+    println!(
+        "  --update-snapshots       用当前值覆盖不一致的快照（__snapshots__/<测试文件>/<名称>.snap.json）"
+    );
+    println!();
+    println!("压缩选项 (aether minify):");
+    println!("  --rename-locals          把函数内的参数和局部变量改为短名称");
+    println!("  -o, --output <FILE>      写入 FILE（默认输出到 stdout）");
+    println!();
+    println!("教程选项 (aether learn):");
+    println!("  <N|名称>                 从指定课程开始（序号或名称，如 3 或 strings）");
+    println!("  --list                   列出所有课程");
+    println!("  --check                  运行所有课程并校验输出是否与预期一致");
     println!();
     println!("示例:");
     println!("  aether script.aether                                   # 运行脚本");
     println!("  aether --check script.aether                           # 检查语法");
     println!("  aether --ast script.aether                             # 查看 AST");
+    println!("  aether --ast --format dot script.aether | dot -Tsvg    # 用 Graphviz 绘制 AST");
     println!("  aether --debug script.aether                           # 调试模式运行");
     println!("  aether --debugger script.aether                        # 启动调试器");
+    println!(
+        "  aether run --debug-listen 9229 script.aether           # 等待远程调试（如 nc 127.0.0.1 9229）"
+    );
     println!("  aether --metrics script.aether                         # 运行并打印性能指标");
     println!(
         "  aether --metrics-json script.aether                    # JSON 输出（含结果与指标）"
@@ -33,9 +94,21 @@ pub fn print_cli_help() {
     println!(
         "  aether --metrics-json-pretty script.aether             # 格式化 JSON 输出（含结果与指标）"
     );
+    println!("  aether --mem-report script.aether                      # 运行并打印内存报告");
     println!("  aether --trace script.aether                           # 运行并打印 TRACE");
     println!("  aether --trace --trace-stats script.aether             # 运行并打印 TRACE + 统计");
     println!("  aether --trace-buffer-size 4096 --trace script.aether  # 调大缓冲区后打印 TRACE");
     println!("  aether --no-stdlib script.aether                       # 不加载标准库");
+    println!("  cat data.json | aether --json-io filter.aether         # 作为 JSON 管道过滤器");
+    println!("  aether serve --listen 127.0.0.1:7000                   # 启动 HTTP/JSON 服务");
+    println!("  aether kernel install                                  # 安装 Jupyter 内核");
+    println!("  aether learn                                           # 开始入门教程");
+    println!("  aether test tests/ --update-snapshots                  # 运行测试并更新快照");
+    println!("  aether minify --rename-locals app.aether -o app.min    # 压缩脚本并缩短局部名称");
+    println!("  aether run --record-io incident.json job.aether        # 运行并录制 IO");
+    println!("  aether replay incident.json                            # 在本地复现录制的执行");
+    println!("  aether my_project                                      # 运行项目的默认入口");
+    println!("  aether my_project --entry MAIN                         # 运行项目的具名入口");
+    println!("  aether --plugin ./libmypack.so script.aether           # 加载插件后运行脚本");
     println!();
 }
@@ -0,0 +1,68 @@
+use crate::cli::args::KernelInstallArgs;
+#[cfg(feature = "kernel")]
+use aether::kernel::{ConnectionInfo, Kernel, install_kernelspec};
+#[cfg(feature = "kernel")]
+use std::path::Path;
+
+#[cfg(feature = "kernel")]
+pub fn run_kernel(connection_file: &str) {
+    let info = match ConnectionInfo::from_file(connection_file) {
+        Ok(info) => info,
+        Err(e) => {
+            eprintln!("✗ {}", e);
+            std::process::exit(1);
+        }
+    };
+    if let Err(e) = Kernel::new(info).run() {
+        eprintln!("✗ {}", e);
+        std::process::exit(1);
+    }
+}
+
+#[cfg(feature = "kernel")]
+pub fn install(args: KernelInstallArgs) {
+    let executable = match std::env::current_exe() {
+        Ok(path) => path,
+        Err(e) => {
+            eprintln!("✗ 无法确定 aether 可执行文件路径: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let kernels_dir = args.prefix.map(|prefix| {
+        Path::new(&prefix)
+            .join("share")
+            .join("jupyter")
+            .join("kernels")
+    });
+    match install_kernelspec(kernels_dir.as_deref(), &executable, &args.display_name) {
+        Ok(dir) => {
+            println!(
+                "✓ 已安装 Jupyter 内核 '{}': {}",
+                args.display_name,
+                dir.display()
+            );
+            println!("  使用 jupyter notebook 或 jupyter lab 新建 Aether 笔记本");
+        }
+        Err(e) => {
+            eprintln!("✗ {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+#[cfg(not(feature = "kernel"))]
+pub fn run_kernel(_connection_file: &str) {
+    kernel_unavailable();
+}
+
+#[cfg(not(feature = "kernel"))]
+pub fn install(_args: KernelInstallArgs) {
+    kernel_unavailable();
+}
+
+#[cfg(not(feature = "kernel"))]
+fn kernel_unavailable() {
+    eprintln!("错误: aether kernel 需要启用 kernel 特性编译");
+    eprintln!("  cargo install aether-azathoth --features kernel");
+    std::process::exit(1);
+}
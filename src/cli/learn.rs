@@ -0,0 +1,174 @@
+use crate::cli::args::LearnArgs;
+use aether::tutorial::{self, Lesson};
+use aether::{Aether, Value};
+use std::io::{self, BufRead, Write};
+
+/// 在一课结束后用户选择的操作
+enum Next {
+    Forward,
+    Back,
+    Quit,
+}
+
+pub fn run_learn(args: LearnArgs) {
+    if args.list {
+        print_lessons();
+        return;
+    }
+    if args.check {
+        check_lessons();
+        return;
+    }
+
+    let lessons = tutorial::lessons();
+    let mut index = match &args.lesson {
+        Some(key) => match tutorial::find_lesson(key) {
+            Some(lesson) => lesson.number - 1,
+            None => {
+                eprintln!(
+                    "✗ 找不到课程: {}（使用 aether learn --list 查看所有课程）",
+                    key
+                );
+                std::process::exit(1);
+            }
+        },
+        None => 0,
+    };
+
+    println!("Aether 入门教程（共 {} 课）", lessons.len());
+    println!("每一课先运行示例代码，然后你可以在提示符下自己动手试试。");
+    println!();
+
+    let stdin = io::stdin();
+    let mut input = stdin.lock();
+    loop {
+        match run_lesson(&lessons[index], lessons.len(), &mut input) {
+            Next::Forward if index + 1 < lessons.len() => index += 1,
+            Next::Forward => {
+                println!(
+                    "🎉 恭喜完成全部课程！输入 aether 启动 REPL 继续探索，或运行 aether --help 查看更多用法。"
+                );
+                break;
+            }
+            Next::Back => index = index.saturating_sub(1),
+            Next::Quit => {
+                println!(
+                    "下次用 aether learn {} 从这一课继续。",
+                    lessons[index].number
+                );
+                break;
+            }
+        }
+    }
+}
+
+fn print_lessons() {
+    for lesson in tutorial::lessons() {
+        println!(
+            "  {:>2}. {:<12} {}",
+            lesson.number, lesson.name, lesson.title
+        );
+    }
+}
+
+/// 运行所有课程并校验输出（用于检查教程本身）
+fn check_lessons() {
+    let mut failed = 0;
+    for lesson in tutorial::lessons() {
+        let check = lesson.check();
+        if check.passed {
+            println!("✓ {:>2}. {}", lesson.number, lesson.title);
+            continue;
+        }
+        failed += 1;
+        println!("✗ {:>2}. {}", lesson.number, lesson.title);
+        if let Some(error) = &check.error {
+            println!("  错误: {}", error);
+        }
+        print_block("期望输出", &lesson.expected_output);
+        print_block("实际输出", &check.output);
+    }
+    if failed > 0 {
+        eprintln!("{} 课未通过", failed);
+        std::process::exit(1);
+    }
+}
+
+fn run_lesson(lesson: &Lesson, total: usize, input: &mut impl BufRead) -> Next {
+    println!(
+        "━━━ 第 {}/{} 课：{} ━━━",
+        lesson.number, total, lesson.title
+    );
+    for line in &lesson.intro {
+        println!("{}", line);
+    }
+    println!();
+    print_block("示例代码", &lesson.code());
+
+    let mut engine = Aether::new();
+    if read_line("按回车运行示例 › ", input).is_none() {
+        return Next::Quit;
+    }
+    run_example(lesson, &mut engine);
+
+    println!("现在轮到你了：输入代码试试（示例中的变量仍然可用）。");
+    println!("直接回车进入下一课，:run 重新运行示例，:prev 上一课，:quit 退出。");
+    loop {
+        let Some(line) = read_line(&format!("learn[{}]> ", lesson.number), input) else {
+            return Next::Quit;
+        };
+        match line.trim() {
+            "" | ":next" => return Next::Forward,
+            ":prev" => return Next::Back,
+            ":quit" | "exit" | "quit" => return Next::Quit,
+            ":run" => {
+                engine = Aether::new();
+                run_example(lesson, &mut engine);
+            }
+            code => {
+                let (output, result) = tutorial::run_captured(&mut engine, code);
+                print!("{}", output);
+                match result {
+                    Ok(Value::Null) => {}
+                    Ok(value) => println!("{}", engine.format_value(&value)),
+                    Err(error) => eprintln!("✗ {}", error),
+                }
+            }
+        }
+    }
+}
+
+/// 运行示例并报告输出是否符合预期
+fn run_example(lesson: &Lesson, engine: &mut Aether) {
+    let (output, result) = tutorial::run_captured(engine, lesson.source);
+    print_block("输出", &output);
+    match result {
+        Err(error) => println!("✗ 运行出错: {}", error),
+        Ok(_) if tutorial::outputs_match(&output, &lesson.expected_output) => {
+            println!("✓ 输出与预期一致")
+        }
+        Ok(_) => print_block("✗ 输出与预期不同，预期为", &lesson.expected_output),
+    }
+    println!();
+}
+
+fn print_block(label: &str, text: &str) {
+    println!("{}:", label);
+    for line in text.trim_end().lines() {
+        println!("    {}", line);
+    }
+}
+
+/// 打印提示并读取一行，输入结束时返回 None
+fn read_line(prompt: &str, input: &mut impl BufRead) -> Option<String> {
+    print!("{}", prompt);
+    io::stdout().flush().ok();
+    let mut line = String::new();
+    match input.read_line(&mut line) {
+        Ok(0) | Err(_) => {
+            println!();
+            None
+        }
+        Ok(_) => Some(line),
+    }
+}
@@ -3,9 +3,13 @@ mod debugger;
 mod error_context;
 mod file_cmd;
 mod help;
+mod kernel;
+mod learn;
 mod metrics;
 mod repl;
 mod runner;
+mod serve;
+mod test_cmd;
 
 use std::env;
 
@@ -13,11 +17,18 @@ pub fn run() {
     let args: Vec<String> = env::args().collect();
 
     match args::parse(&args) {
-        args::CliCommand::Repl => repl::run_repl(),
+        args::CliCommand::Repl { no_io } => repl::run_repl(no_io),
         args::CliCommand::Help => help::print_cli_help(),
         args::CliCommand::Check { file } => file_cmd::check_file(&file),
-        args::CliCommand::Ast { file } => file_cmd::show_ast_for_file(&file),
+        args::CliCommand::Ast { file, format } => file_cmd::show_ast_for_file(&file, &format),
         args::CliCommand::Run { file, options } => runner::run_file(&file, options),
+        args::CliCommand::Serve { args } => serve::run_server(args),
+        args::CliCommand::Kernel { connection_file } => kernel::run_kernel(&connection_file),
+        args::CliCommand::KernelInstall { args } => kernel::install(args),
+        args::CliCommand::Learn { args } => learn::run_learn(args),
+        args::CliCommand::Test { args } => test_cmd::run_tests(args),
+        args::CliCommand::Minify { args } => file_cmd::minify_file(args),
+        args::CliCommand::Replay { args } => runner::replay_file(args),
         args::CliCommand::Error { message } => {
             eprintln!("{}", message);
             eprintln!("使用 --help 查看帮助");
@@ -1,15 +1,22 @@
 use crate::cli::error_context;
 use aether::Aether;
+use aether::analysis::Permission;
 use std::io::{self, Write};
 
-pub fn run_repl() {
+pub fn run_repl(no_io: bool) {
     println!("Aether REPL v{}", env!("CARGO_PKG_VERSION"));
     println!("输入 'exit' 或 'quit' 退出");
     println!("输入 'help' 查看帮助");
     println!("输入 ':load stdlib' 加载标准库");
     println!();
 
-    let mut engine = Aether::with_all_permissions();
+    let mut engine = if no_io {
+        println!("IO 权限已禁用，使用 ':grant fs' / ':grant net' 按需开启");
+        println!();
+        Aether::new()
+    } else {
+        Aether::with_all_permissions()
+    };
     let mut stdlib_loaded = false;
     let mut line_number = 1;
 
@@ -55,6 +62,21 @@ pub fn run_repl() {
                         }
                         continue;
                     }
+                    ":permissions" => {
+                        print_permissions(&engine);
+                        continue;
+                    }
+                    cmd if cmd.starts_with(":grant ") || cmd.starts_with(":revoke ") => {
+                        let (grant, rest) = match cmd.strip_prefix(":grant ") {
+                            Some(rest) => (true, rest),
+                            None => (false, cmd.strip_prefix(":revoke ").unwrap()),
+                        };
+                        match apply_permission_command(&mut engine, grant, rest) {
+                            Ok(()) => print_permissions(&engine),
+                            Err(e) => eprintln!("✗ {}", e),
+                        }
+                        continue;
+                    }
                     "" => continue,
                     _ => {}
                 }
@@ -62,7 +84,7 @@ pub fn run_repl() {
                 match engine.eval(input) {
                     Ok(result) => {
                         if result != aether::Value::Null {
-                            println!("{}", result);
+                            println!("{}", engine.format_value(&result));
                         }
                     }
                     Err(e) => {
@@ -72,6 +94,9 @@ pub fn run_repl() {
                         {
                             error_context::print_source_context(input, line, col);
                         }
+                        if let Some(hint) = permission_hint(&e) {
+                            eprintln!("  提示: 输入 '{}' 开启所需权限后重试", hint);
+                        }
                     }
                 }
 
@@ -85,6 +110,86 @@ pub fn run_repl() {
     }
 }
 
+/// 处理 `:grant` / `:revoke` 命令，参数形如 `fs`、`net`、`net host=example.com`
+fn apply_permission_command(engine: &mut Aether, grant: bool, args: &str) -> Result<(), String> {
+    let mut parts = args.split_whitespace();
+    let target = parts.next().ok_or("缺少权限名称，可用: fs, net")?;
+    let hosts: Vec<String> = parts
+        .map(|part| {
+            part.strip_prefix("host=")
+                .map(str::to_string)
+                .ok_or_else(|| format!("无法识别的参数: {}", part))
+        })
+        .collect::<Result<_, _>>()?;
+
+    let mut permissions = engine.permissions().clone();
+    match target {
+        "fs" | "filesystem" => {
+            if !hosts.is_empty() {
+                return Err("host= 只适用于 net".to_string());
+            }
+            permissions.filesystem_enabled = grant;
+        }
+        "net" | "network" => {
+            let allowlist = aether::sandbox::get_network_allowlist();
+            if grant {
+                // 只授权指定主机：网络原先关闭时从空白名单开始，原先不限制主机时保持不限制
+                if hosts.is_empty() {
+                    aether::sandbox::set_network_allowlist(None);
+                } else if allowlist.is_some() || !permissions.network_enabled {
+                    let mut list = allowlist.unwrap_or_default();
+                    list.extend(hosts);
+                    aether::sandbox::set_network_allowlist(Some(list));
+                }
+                permissions.network_enabled = true;
+            } else if !hosts.is_empty() {
+                let list: Vec<String> = allowlist
+                    .ok_or("当前未限制网络主机，无法单独撤销主机")?
+                    .into_iter()
+                    .filter(|host| !hosts.contains(host))
+                    .collect();
+                aether::sandbox::set_network_allowlist(Some(list));
+            } else {
+                aether::sandbox::set_network_allowlist(None);
+                permissions.network_enabled = false;
+            }
+        }
+        other => return Err(format!("未知权限: {}，可用: fs, net", other)),
+    }
+    engine.set_permissions(permissions);
+    Ok(())
+}
+
+fn print_permissions(engine: &Aether) {
+    let permissions = engine.permissions();
+    let state = |enabled: bool| if enabled { "允许" } else { "禁止" };
+    println!("  fs:  {}", state(permissions.filesystem_enabled));
+    match aether::sandbox::get_network_allowlist() {
+        Some(hosts) if permissions.network_enabled => {
+            println!("  net: 允许（仅限 {}）", hosts.join(", "))
+        }
+        _ => println!("  net: {}", state(permissions.network_enabled)),
+    }
+}
+
+/// 根据权限错误给出对应的 `:grant` 命令
+fn permission_hint(error: &str) -> Option<String> {
+    if let Some(rest) = error.split("Host '").nth(1)
+        && let Some((host, _)) = rest.split_once("' is not in the network allowlist")
+    {
+        return Some(format!(":grant net host={}", host));
+    }
+    let name = error.split("Undefined variable: ").nth(1)?;
+    let name: String = name
+        .chars()
+        .take_while(|c| c.is_alphanumeric() || *c == '_')
+        .collect();
+    match Permission::required_by(&name)? {
+        Permission::Filesystem => Some(":grant fs".to_string()),
+        Permission::Network => Some(":grant net".to_string()),
+    }
+}
+
 fn print_help() {
     println!("Aether 语言帮助:");
     println!();
@@ -140,6 +245,11 @@ fn print_help() {
     println!("  :load validation         # 加载验证库");
     println!("  :load datetime           # 加载日期时间库");
     println!("  :load testing            # 加载测试框架");
+    println!("  :permissions             # 查看当前 IO 权限");
+    println!("  :grant fs                # 允许文件系统操作");
+    println!("  :grant net               # 允许网络操作");
+    println!("  :grant net host=example.com  # 只允许访问指定主机");
+    println!("  :revoke fs|net           # 撤销权限");
     println!("  exit, quit               # 退出 REPL");
     println!();
 }
@@ -1,7 +1,11 @@
-use crate::cli::{args::RunOptions, error_context, metrics};
-use aether::{Aether, FileSystemModuleResolver};
+use crate::cli::{
+    args::{ReplayArgs, RunOptions},
+    error_context, metrics,
+};
+use aether::{Aether, ExecutionTrace, FileSystemModuleResolver, Project, Value};
 use serde_json::json;
 use std::fs;
+use std::io::Read;
 
 pub fn run_file(filename: &str, options: RunOptions) {
     // Check if debugger mode is enabled
@@ -9,6 +13,10 @@ pub fn run_file(filename: &str, options: RunOptions) {
         crate::cli::debugger::run_debugger(filename);
         return;
     }
+    if let Some(addr) = &options.debug_listen {
+        crate::cli::debugger::run_remote_debugger(filename, addr);
+        return;
+    }
 
     let mut engine = if options.load_stdlib {
         match Aether::with_stdlib() {
@@ -23,6 +31,8 @@ pub fn run_file(filename: &str, options: RunOptions) {
         Aether::with_all_permissions()
     };
 
+    load_plugins(&mut engine, &options);
+
     if options.debug_mode {
         println!("=== 调试模式 ===");
         println!("文件: {}", filename);
@@ -39,6 +49,28 @@ pub fn run_file(filename: &str, options: RunOptions) {
 
     engine.set_module_resolver(Box::new(FileSystemModuleResolver::default()));
 
+    // 目录或 aether.json：按项目运行，依赖模块先按拓扑序加载
+    let project_entry;
+    let filename = if Project::is_project_path(filename) {
+        project_entry = prepare_project(&mut engine, filename, &options);
+        project_entry.as_str()
+    } else if options.entry.is_some() {
+        eprintln!("错误: --entry 只能用于项目目录或 aether.json");
+        std::process::exit(1);
+    } else {
+        filename
+    };
+
+    if options.json_io {
+        run_json_io(&mut engine, filename, &options);
+        return;
+    }
+
+    if let Some(trace_file) = &options.record_io {
+        run_recorded(&mut engine, filename, trace_file);
+        return;
+    }
+
     if let Some(size) = options.trace_buffer_size {
         engine.set_trace_buffer_size(size);
         if options.debug_mode {
@@ -67,7 +99,7 @@ pub fn run_file(filename: &str, options: RunOptions) {
                     println!("=== 执行结果 ===");
                 }
                 if result != aether::Value::Null {
-                    println!("{}", result);
+                    println!("{}", engine.format_value(&result));
                 }
 
                 if options.metrics_mode {
@@ -124,7 +156,7 @@ pub fn run_file(filename: &str, options: RunOptions) {
                 println!("=== 执行结果 ===");
             }
             if result != aether::Value::Null {
-                println!("{}", result);
+                println!("{}", engine.format_value(&result));
             }
 
             if options.metrics_mode {
@@ -158,6 +190,14 @@ pub fn run_file(filename: &str, options: RunOptions) {
                 println!();
             }
 
+            if engine.has_pending_schedule() {
+                drive_scheduler(&mut engine, filename);
+            }
+
+            if engine.has_pending_timers() {
+                drive_timers(&mut engine);
+            }
+
             if options.show_trace_stats {
                 let stats = engine.trace_stats();
                 println!("=== TRACE STATS ===");
@@ -168,6 +208,10 @@ pub fn run_file(filename: &str, options: RunOptions) {
                 println!("by_category: {:?}", stats.by_category);
                 println!();
             }
+
+            if options.mem_report {
+                println!("{}", engine.memory_report());
+            }
         }
         Err(e) => {
             if options.metrics_json_mode {
@@ -187,7 +231,226 @@ pub fn run_file(filename: &str, options: RunOptions) {
                 eprintln!("{}", e);
             }
 
+            if options.mem_report {
+                eprintln!("{}", engine.memory_report());
+            }
+            std::process::exit(1);
+        }
+    }
+}
+
+/// `--json-io`：stdin 的 JSON 绑定为 `INPUT_DATA`，脚本的最终值以 JSON 写到 stdout
+fn run_json_io(engine: &mut Aether, filename: &str, options: &RunOptions) {
+    let mut input = String::new();
+    if let Err(e) = std::io::stdin().read_to_string(&mut input) {
+        eprintln!("✗ 无法读取标准输入: {}", e);
+        std::process::exit(1);
+    }
+    if let Err(e) = engine.set_input_json(&input) {
+        eprintln!("✗ {}", e);
+        std::process::exit(1);
+    }
+
+    let result = if options.json_error {
+        engine.eval_file_report(filename).map_err(|report| {
+            eprintln!("{}", report.to_json_pretty());
+        })
+    } else {
+        engine.eval_file(filename).map_err(|e| {
+            eprintln!("✗ 运行时错误:");
+            match fs::read_to_string(filename) {
+                Ok(code) => error_context::print_detailed_error(&code, &e),
+                Err(_) => eprintln!("{}", e),
+            }
+        })
+    };
+    let Ok(result) = result else {
+        std::process::exit(1);
+    };
+
+    match engine.to_json_output(&result, false) {
+        Ok(json) => println!("{}", json),
+        Err(e) => {
+            eprintln!("✗ {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// `--record-io`：运行脚本并把 IO 录制写到 `trace_file`，供 `aether replay` 复现
+fn run_recorded(engine: &mut Aether, filename: &str, trace_file: &str) {
+    let trace = match engine.eval_file_recorded(filename) {
+        Ok(trace) => trace,
+        Err(e) => {
+            eprintln!("✗ {}", e);
+            std::process::exit(1);
+        }
+    };
+    if let Err(e) = fs::write(trace_file, trace.to_json()) {
+        eprintln!("✗ 无法写入录制文件 '{}': {}", trace_file, e);
+        std::process::exit(1);
+    }
+    eprintln!(
+        "✓ 已录制 {} 个 IO 事件到 {}",
+        trace.events.len(),
+        trace_file
+    );
+
+    match (&trace.result, &trace.error) {
+        (_, Some(e)) => {
+            eprintln!("✗ 运行时错误:");
+            error_context::print_detailed_error(&trace.script, e);
+            std::process::exit(1);
+        }
+        (Some(result), None) if *result != aether::Value::Null.to_string() => {
+            println!("{}", result)
+        }
+        _ => {}
+    }
+}
+
+/// `aether replay`：用录制的 IO 结果重新执行脚本，并报告结果是否与录制时一致
+pub fn replay_file(args: ReplayArgs) {
+    let trace = match fs::read_to_string(&args.trace_file)
+        .map_err(|e| format!("无法读取录制文件 '{}': {}", args.trace_file, e))
+        .and_then(|text| ExecutionTrace::from_json(&text))
+    {
+        Ok(trace) => trace,
+        Err(e) => {
+            eprintln!("✗ {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let mut engine = if args.load_stdlib {
+        Aether::with_stdlib().unwrap_or_else(|e| {
+            eprintln!("警告: 标准库加载失败: {}", e);
+            Aether::with_all_permissions()
+        })
+    } else {
+        Aether::with_all_permissions()
+    };
+    engine.set_module_resolver(Box::new(FileSystemModuleResolver::default()));
+
+    let outcome = engine.replay(&trace);
+    match &outcome {
+        Ok(result) if *result != aether::Value::Null => {
+            println!("{}", engine.format_value(result))
+        }
+        Ok(_) => {}
+        Err(e) => {
+            eprintln!("✗ 运行时错误:");
+            error_context::print_detailed_error(&trace.script, e);
+        }
+    }
+
+    let reproduced = trace.reproduces(&outcome);
+    if reproduced {
+        eprintln!("✓ 已回放 {} 个 IO 事件，结果与录制一致", trace.events.len());
+    } else {
+        eprintln!("✗ 回放结果与录制不一致");
+        match (&trace.result, &trace.error) {
+            (_, Some(e)) => eprintln!("  录制时的错误: {}", e),
+            (Some(result), None) => eprintln!("  录制时的结果: {}", result),
+            _ => {}
+        }
+    }
+    if outcome.is_err() || !reproduced {
+        std::process::exit(1);
+    }
+}
+
+/// 打开项目并预加载入口依赖的模块，返回入口文件路径
+fn prepare_project(engine: &mut Aether, path: &str, options: &RunOptions) -> String {
+    let result = Project::open(path)
+        .and_then(|project| engine.prepare_project(&project, options.entry.as_deref()));
+    match result {
+        Ok(entry) => {
+            let entry = entry.display().to_string();
+            if options.debug_mode {
+                println!("项目入口: {}", entry);
+                println!();
+            }
+            entry
+        }
+        Err(e) => {
+            if options.json_error {
+                let payload = json!({ "ok": false, "error": e });
+                eprintln!("{:#}", payload);
+            } else {
+                eprintln!("✗ 项目加载失败:");
+                eprintln!("{}", e);
+            }
+            std::process::exit(1);
+        }
+    }
+}
+
+/// 脚本注册了定时任务但没有自己运行调度器时，由 CLI 以常驻进程驱动
+fn drive_scheduler(engine: &mut Aether, filename: &str) {
+    eprintln!("调度器已启动，按 Ctrl+C 退出");
+    match engine.run_scheduler() {
+        Ok(Value::Dict(summary)) => {
+            if let Some(Value::Array(failures)) = summary.get("failures") {
+                for failure in failures {
+                    if let Value::Dict(f) = failure
+                        && let (Some(id), Some(expr), Some(error)) =
+                            (f.get("id"), f.get("expr"), f.get("error"))
+                    {
+                        eprintln!("✗ 定时任务 #{} ({}) 失败: {}", id, expr, error);
+                    }
+                }
+            }
+        }
+        Ok(_) => {}
+        Err(e) => {
+            eprintln!("✗ 运行时错误:");
+            if let Ok(code) = fs::read_to_string(filename) {
+                error_context::print_detailed_error(&code, &e);
+            } else {
+                eprintln!("{}", e);
+            }
             std::process::exit(1);
         }
     }
 }
+
+/// 脚本注册了定时器（SET_TIMEOUT / SET_INTERVAL / DEBOUNCE）时，由 CLI 充当事件循环，
+/// 直到没有定时器为止；回调失败只报告错误，不退出
+fn drive_timers(engine: &mut Aether) {
+    while let Some(delay) = engine.next_timer_delay() {
+        if !delay.is_zero() {
+            std::thread::sleep(delay);
+        }
+        for error in engine.pump().errors {
+            eprintln!("✗ {}", error);
+        }
+    }
+}
+
+/// 加载 `--plugin` 指定的内置函数包插件，失败时退出
+#[cfg(feature = "plugins")]
+fn load_plugins(engine: &mut Aether, options: &RunOptions) {
+    for path in &options.plugins {
+        match engine.load_plugin(path) {
+            Ok(info) => {
+                if options.debug_mode {
+                    println!("插件 {}: {}", info.name, info.functions.join(", "));
+                }
+            }
+            Err(e) => {
+                eprintln!("错误: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "plugins"))]
+fn load_plugins(_engine: &mut Aether, options: &RunOptions) {
+    if let Some(path) = options.plugins.first() {
+        eprintln!("错误: 加载插件 {} 需要启用 plugins 特性编译", path);
+        eprintln!("  cargo install aether-azathoth --features plugins");
+        std::process::exit(1);
+    }
+}
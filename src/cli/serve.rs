@@ -0,0 +1,46 @@
+use crate::cli::args::ServeArgs;
+
+#[cfg(feature = "http-server")]
+pub fn run_server(args: ServeArgs) {
+    use aether::SandboxConfig;
+    use aether::server::{Server, ServerConfig};
+
+    let sandbox = match (&args.root, args.policy.as_str()) {
+        (Some(root), _) => SandboxConfig::sandboxed(root.into()),
+        (None, "safe") => SandboxConfig::dsl_safe(),
+        (None, "full") => SandboxConfig::cli_full_access(),
+        (None, other) => {
+            eprintln!("错误: 未知的沙箱策略 '{}'（可选: safe, full）", other);
+            std::process::exit(1);
+        }
+    };
+    let mut config = ServerConfig {
+        listen: args.listen,
+        sandbox,
+        max_requests: args.max_requests,
+        ..ServerConfig::default()
+    };
+    if let Some(size) = args.pool_size {
+        config.pool_size = size;
+    }
+
+    let mut server = match Server::bind(config) {
+        Ok(server) => server,
+        Err(e) => {
+            eprintln!("✗ {}", e);
+            std::process::exit(1);
+        }
+    };
+    if let Ok(addr) = server.local_addr() {
+        eprintln!("Aether 服务已启动: http://{}", addr);
+        eprintln!("  GET /health  GET /metrics  POST /eval  POST /compile");
+    }
+    server.run();
+}
+
+#[cfg(not(feature = "http-server"))]
+pub fn run_server(_args: ServeArgs) {
+    eprintln!("错误: aether serve 需要启用 http-server 特性编译");
+    eprintln!("  cargo install aether-azathoth --features http-server");
+    std::process::exit(1);
+}
@@ -0,0 +1,119 @@
+use crate::cli::args::TestArgs;
+use aether::{Aether, FileSystemModuleResolver, SnapshotStats};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 测试文件名后缀
+const TEST_SUFFIX: &str = "_test.aether";
+
+/// 快照目录名（位于测试文件所在目录，每个测试文件一个子目录）
+const SNAPSHOT_DIR: &str = "__snapshots__";
+
+pub fn run_tests(args: TestArgs) {
+    let roots = if args.paths.is_empty() {
+        vec![".".to_string()]
+    } else {
+        args.paths.clone()
+    };
+
+    let mut files = Vec::new();
+    for root in &roots {
+        let path = Path::new(root);
+        if path.is_dir() {
+            collect_test_files(path, &mut files);
+        } else if path.is_file() {
+            files.push(path.to_path_buf());
+        } else {
+            eprintln!("✗ 找不到测试路径: {}", root);
+            std::process::exit(1);
+        }
+    }
+    if files.is_empty() {
+        eprintln!("✗ 未找到测试文件（*{}）", TEST_SUFFIX);
+        std::process::exit(1);
+    }
+
+    let mut failed_files = 0;
+    let mut totals = SnapshotStats::default();
+    for file in &files {
+        println!("▶ {}", file.display());
+        let (passed, stats) = run_test_file(file, args.update_snapshots);
+        if !passed {
+            failed_files += 1;
+        }
+        totals.matched += stats.matched;
+        totals.created += stats.created;
+        totals.updated += stats.updated;
+        totals.failed += stats.failed;
+    }
+
+    println!();
+    println!(
+        "{}/{} 个测试文件通过",
+        files.len() - failed_files,
+        files.len()
+    );
+    println!(
+        "快照: {} 一致, {} 新建, {} 更新, {} 不一致",
+        totals.matched, totals.created, totals.updated, totals.failed
+    );
+    if totals.failed > 0 {
+        println!("如果改动符合预期，使用 aether test --update-snapshots 接受新的快照");
+    }
+    if failed_files > 0 {
+        std::process::exit(1);
+    }
+}
+
+/// 运行一个测试文件，返回是否通过和快照统计
+fn run_test_file(file: &Path, update_snapshots: bool) -> (bool, SnapshotStats) {
+    let mut engine = match Aether::with_stdlib() {
+        Ok(engine) => engine,
+        Err(e) => {
+            eprintln!("✗ 标准库加载失败: {}", e);
+            std::process::exit(1);
+        }
+    };
+    engine.set_module_resolver(Box::new(FileSystemModuleResolver::default()));
+
+    let stem = file
+        .file_name()
+        .and_then(|name| name.to_str())
+        .map(|name| name.trim_end_matches(".aether"))
+        .unwrap_or("test");
+    let dir = file.parent().unwrap_or(Path::new(".")).join(SNAPSHOT_DIR);
+    engine.set_snapshot_dir(dir.join(stem));
+    engine.set_update_snapshots(update_snapshots);
+
+    let result = engine.eval_file(file);
+    let stats = engine.snapshot_stats();
+    match result {
+        Ok(_) => (stats.failed == 0, stats),
+        Err(e) => {
+            eprintln!("  ✗ {}", e);
+            (false, stats)
+        }
+    }
+}
+
+/// 递归收集目录中的测试文件（跳过隐藏目录、target 和快照目录），按路径排序
+fn collect_test_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let mut paths: Vec<PathBuf> = entries.flatten().map(|entry| entry.path()).collect();
+    paths.sort();
+    for path in paths {
+        let name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or_default();
+        if path.is_dir() {
+            if !name.starts_with('.') && name != "target" && name != SNAPSHOT_DIR {
+                collect_test_files(&path, files);
+            }
+        } else if name.ends_with(TEST_SUFFIX) {
+            files.push(path);
+        }
+    }
+}
@@ -0,0 +1,398 @@
+// src/compile/mod.rs
+//! Bytecode backend
+//!
+//! Lowers an (optimized) program into a flat instruction list that the stack-based
+//! VM in [`vm`] executes without recursing through `Evaluator::eval_expression`.
+//! Loops, conditionals, assignments and the common expression forms are compiled;
+//! every other statement or expression is embedded as-is and handed to the tree
+//! walker, so both backends always accept the same programs. Function bodies are
+//! still run by the tree walker.
+
+mod vm;
+
+pub use vm::run;
+
+use crate::ast::{BinOp, Expr, SourceSpan, Stmt, UnaryOp};
+use crate::value::Value;
+
+/// How the evaluator executes top-level programs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Backend {
+    /// Walk the AST statement by statement
+    #[default]
+    Interpreter,
+    /// Compile the program to bytecode and run it on the VM
+    Bytecode,
+}
+
+/// A single VM instruction
+///
+/// Jump targets are instruction indices within the same [`Chunk`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Op {
+    /// Statement boundary: set the current line, count a step and check the clock
+    Step(Option<usize>),
+    /// Push a constant
+    Const(Value),
+    /// Push the value of a variable
+    Load(String),
+    /// Pop a value and bind it to a name (`Set`); it becomes the statement result
+    Store(String),
+    /// Pop a value into the statement result
+    SetResult,
+    /// Pop two operands and push the result of an arithmetic or comparison operator
+    Binary(BinOp),
+    /// Pop an operand and push the result of a unary operator
+    Unary(UnaryOp),
+    /// Unconditional jump
+    Jump(usize),
+    /// `And`: jump keeping the top value when it is falsy, otherwise pop it
+    JumpIfFalsy(usize),
+    /// `Or`: jump keeping the top value when it is truthy, otherwise pop it
+    JumpIfTruthy(usize),
+    /// Pop a condition of `construct` (If/Elif/While) and jump when it is false
+    JumpIfFalse {
+        target: usize,
+        construct: &'static str,
+    },
+    /// Pop `argc` arguments and the callee, push the call result
+    Call { argc: usize, name: Option<String> },
+    /// Pop `n` values into an array
+    MakeArray(usize),
+    /// Pop one value per key into a dict
+    MakeDict(Vec<String>),
+    /// Pop an index and an object, push `object[index]`
+    Index,
+    /// Start a While loop whose condition follows; `exit` is its `ExitLoop`
+    EnterWhile { exit: usize, looped: usize },
+    /// Pop the iterable of a For loop and start iterating it
+    EnterFor {
+        exit: usize,
+        looped: usize,
+        indexed: bool,
+    },
+    /// Count an iteration of the innermost While loop
+    LoopIteration,
+    /// Bind the next item of the innermost For loop, or jump to its `ExitLoop`
+    NextItem {
+        var: String,
+        index_var: Option<String>,
+    },
+    /// Leave the innermost loop
+    ExitLoop,
+    /// Jump to the end of the innermost loop
+    Break,
+    /// Jump to the next iteration of the innermost loop
+    Continue,
+    /// Pop a value and return it from the program
+    Return,
+    /// Run a statement with the tree walker; its value becomes the statement result
+    Exec(Box<Stmt>),
+    /// Evaluate an expression with the tree walker and push the value
+    Eval(Box<Expr>),
+}
+
+/// A compiled program
+#[derive(Debug, Clone, Default)]
+pub struct Chunk {
+    ops: Vec<Op>,
+    /// Source span of the statement each instruction belongs to
+    spans: Vec<Option<SourceSpan>>,
+    /// Loop statements, reported when a loop exceeds its iteration limit
+    loops: Vec<Stmt>,
+}
+
+impl Chunk {
+    /// Instructions in execution order
+    pub fn ops(&self) -> &[Op] {
+        &self.ops
+    }
+
+    /// Number of statements and expressions left to the tree walker
+    pub fn fallback_count(&self) -> usize {
+        self.ops
+            .iter()
+            .filter(|op| matches!(op, Op::Exec(_) | Op::Eval(_)))
+            .count()
+    }
+}
+
+/// Compile a program to bytecode
+pub fn compile<'a>(program: impl IntoIterator<Item = &'a Stmt>) -> Chunk {
+    let mut compiler = Compiler::default();
+    for stmt in program {
+        compiler.statement(stmt);
+    }
+    compiler.chunk
+}
+
+#[derive(Default)]
+struct Compiler {
+    chunk: Chunk,
+    /// Span of the innermost located statement being compiled
+    span: Option<SourceSpan>,
+}
+
+impl Compiler {
+    fn emit(&mut self, op: Op) -> usize {
+        self.chunk.ops.push(op);
+        self.chunk.spans.push(self.span);
+        self.chunk.ops.len() - 1
+    }
+
+    fn here(&self) -> usize {
+        self.chunk.ops.len()
+    }
+
+    /// Point the jump emitted at `at` to `target`
+    fn patch(&mut self, at: usize, target: usize) {
+        match &mut self.chunk.ops[at] {
+            Op::Jump(t)
+            | Op::JumpIfFalsy(t)
+            | Op::JumpIfTruthy(t)
+            | Op::JumpIfFalse { target: t, .. }
+            | Op::EnterWhile { exit: t, .. }
+            | Op::EnterFor { exit: t, .. } => *t = target,
+            op => unreachable!("cannot patch {:?}", op),
+        }
+    }
+
+    /// Whether the VM compiles `stmt` itself rather than delegating it
+    fn compiles(stmt: &Stmt) -> bool {
+        matches!(
+            stmt,
+            Stmt::Set { .. }
+                | Stmt::Expression(_)
+                | Stmt::While { .. }
+                | Stmt::For { .. }
+                | Stmt::ForIndexed { .. }
+                | Stmt::Break
+                | Stmt::Continue
+                | Stmt::Return(_)
+        )
+    }
+
+    fn statement(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::Located { span, stmt: inner } if Self::compiles(inner) => {
+                let outer = self.span.replace(*span);
+                self.emit(Op::Step(Some(span.line)));
+                self.node(inner);
+                self.span = outer;
+            }
+            stmt if Self::compiles(stmt) => {
+                self.emit(Op::Step(None));
+                self.node(stmt);
+            }
+            // The tree walker counts the step and records the position itself
+            other => {
+                self.emit(Op::Exec(Box::new(other.clone())));
+            }
+        }
+    }
+
+    fn block(&mut self, body: &[Stmt]) {
+        for stmt in body {
+            self.statement(stmt);
+        }
+    }
+
+    fn node(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::Set { name, value } => {
+                self.expression(value);
+                self.emit(Op::Store(name.clone()));
+            }
+            Stmt::Expression(Expr::If {
+                condition,
+                then_branch,
+                elif_branches,
+                else_branch,
+            }) => {
+                // A branch that runs no statements evaluates to Null
+                self.emit(Op::Const(Value::Null));
+                self.emit(Op::SetResult);
+
+                let mut ends = Vec::new();
+                let branches = std::iter::once((condition.as_ref(), then_branch, "If")).chain(
+                    elif_branches
+                        .iter()
+                        .map(|(cond, body)| (cond, body, "Elif")),
+                );
+                for (cond, body, construct) in branches {
+                    self.expression(cond);
+                    let skip = self.emit(Op::JumpIfFalse {
+                        target: 0,
+                        construct,
+                    });
+                    self.block(body);
+                    ends.push(self.emit(Op::Jump(0)));
+                    self.patch(skip, self.here());
+                }
+                if let Some(body) = else_branch {
+                    self.block(body);
+                }
+                let end = self.here();
+                for jump in ends {
+                    self.patch(jump, end);
+                }
+            }
+            Stmt::Expression(expr) => {
+                self.expression(expr);
+                self.emit(Op::SetResult);
+            }
+            Stmt::While { condition, body } => {
+                let looped = self.loop_stmt(stmt);
+                let enter = self.emit(Op::EnterWhile { exit: 0, looped });
+                // The condition check is a step of its own, as in the tree walker
+                let head = self.emit(Op::Step(None));
+                self.expression(condition);
+                let done = self.emit(Op::JumpIfFalse {
+                    target: 0,
+                    construct: "While",
+                });
+                self.emit(Op::LoopIteration);
+                self.block(body);
+                self.emit(Op::Jump(head));
+                let exit = self.emit(Op::ExitLoop);
+                self.patch(enter, exit);
+                self.patch(done, exit);
+            }
+            Stmt::For {
+                var,
+                iterable,
+                body,
+            } => self.for_loop(stmt, iterable, var, None, body),
+            Stmt::ForIndexed {
+                index_var,
+                value_var,
+                iterable,
+                body,
+            } => self.for_loop(stmt, iterable, value_var, Some(index_var), body),
+            Stmt::Break => {
+                self.emit(Op::Break);
+            }
+            Stmt::Continue => {
+                self.emit(Op::Continue);
+            }
+            Stmt::Return(expr) => {
+                self.expression(expr);
+                self.emit(Op::Return);
+            }
+            other => unreachable!("statement is not compiled: {:?}", other),
+        }
+    }
+
+    fn for_loop(
+        &mut self,
+        stmt: &Stmt,
+        iterable: &Expr,
+        var: &str,
+        index_var: Option<&String>,
+        body: &[Stmt],
+    ) {
+        let looped = self.loop_stmt(stmt);
+        self.expression(iterable);
+        let enter = self.emit(Op::EnterFor {
+            exit: 0,
+            looped,
+            indexed: index_var.is_some(),
+        });
+        let head = self.emit(Op::NextItem {
+            var: var.to_string(),
+            index_var: index_var.cloned(),
+        });
+        self.block(body);
+        self.emit(Op::Jump(head));
+        let exit = self.emit(Op::ExitLoop);
+        self.patch(enter, exit);
+    }
+
+    /// Register a loop statement for iteration-limit errors
+    fn loop_stmt(&mut self, stmt: &Stmt) -> usize {
+        self.chunk.loops.push(stmt.clone());
+        self.chunk.loops.len() - 1
+    }
+
+    fn expression(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Number(n) => {
+                self.emit(Op::Const(Value::Number(*n)));
+            }
+            Expr::Integer(n) => {
+                self.emit(Op::Const(Value::Int(*n)));
+            }
+            Expr::String(s) => {
+                self.emit(Op::Const(Value::String(s.to_string())));
+            }
+            Expr::Boolean(b) => {
+                self.emit(Op::Const(Value::Boolean(*b)));
+            }
+            Expr::Null => {
+                self.emit(Op::Const(Value::Null));
+            }
+            Expr::Identifier(name) => {
+                self.emit(Op::Load(name.clone()));
+            }
+            Expr::Binary { left, op, right } => {
+                self.expression(left);
+                let short_circuit = match op {
+                    BinOp::And => Some(Op::JumpIfFalsy(0)),
+                    BinOp::Or => Some(Op::JumpIfTruthy(0)),
+                    _ => None,
+                };
+                match short_circuit {
+                    Some(jump) => {
+                        let at = self.emit(jump);
+                        self.expression(right);
+                        self.patch(at, self.here());
+                    }
+                    None => {
+                        self.expression(right);
+                        self.emit(Op::Binary(op.clone()));
+                    }
+                }
+            }
+            Expr::Unary { op, expr } => {
+                self.expression(expr);
+                self.emit(Op::Unary(op.clone()));
+            }
+            Expr::Call { func, args } => {
+                let name = match func.as_ref() {
+                    Expr::Identifier(name) => Some(name.clone()),
+                    _ => None,
+                };
+                self.expression(func);
+                for arg in args {
+                    self.expression(arg);
+                }
+                self.emit(Op::Call {
+                    argc: args.len(),
+                    name,
+                });
+            }
+            Expr::Array(elements) => {
+                for element in elements {
+                    self.expression(element);
+                }
+                self.emit(Op::MakeArray(elements.len()));
+            }
+            Expr::Dict(pairs) => {
+                for (_, value) in pairs {
+                    self.expression(value);
+                }
+                self.emit(Op::MakeDict(
+                    pairs.iter().map(|(key, _)| key.clone()).collect(),
+                ));
+            }
+            Expr::Index { object, index } => {
+                self.expression(object);
+                self.expression(index);
+                self.emit(Op::Index);
+            }
+            other => {
+                self.emit(Op::Eval(Box::new(other.clone())));
+            }
+        }
+    }
+}
@@ -0,0 +1,260 @@
+// src/compile/vm.rs
+//! Stack-based VM executing compiled [`Chunk`]s
+
+use super::{Chunk, Op};
+use crate::evaluator::{EvalResult, Evaluator, RuntimeError};
+use crate::value::Value;
+
+/// A loop being executed
+struct LoopFrame {
+    /// Where `Continue` jumps: the loop head
+    next: usize,
+    /// The loop's `ExitLoop`, where `Break` jumps
+    exit: usize,
+    /// Index of the loop statement in `Chunk::loops`
+    looped: usize,
+    /// Iterations started so far
+    iterations: usize,
+    /// Items a For loop has not reached yet
+    items: std::vec::IntoIter<Value>,
+    /// Operand stack height when the loop started
+    height: usize,
+}
+
+/// Statements run between two reads of the clock
+///
+/// Reading the clock costs more than most statements, so the wall-clock limit is
+/// checked every `CLOCK_INTERVAL` statements instead of before each one.
+const CLOCK_INTERVAL: u32 = 128;
+
+struct Vm {
+    stack: Vec<Value>,
+    loops: Vec<LoopFrame>,
+    /// Value of the most recently completed statement
+    result: Value,
+    /// Statements left until the next clock check
+    clock_countdown: u32,
+}
+
+/// Run a compiled program, returning the value of its last statement
+///
+/// Variables, limits, warnings and the call stack live in `evaluator`, so the
+/// program observes exactly the state the tree walker would.
+pub fn run(evaluator: &mut Evaluator, chunk: &Chunk) -> EvalResult {
+    let mut vm = Vm {
+        stack: Vec::new(),
+        loops: Vec::new(),
+        result: Value::Null,
+        clock_countdown: 0,
+    };
+    let mut pc = 0;
+    while pc < chunk.ops.len() {
+        match vm.execute(evaluator, chunk, pc) {
+            Ok(next) => pc = next,
+            // Break/Continue raised by a delegated statement inside a loop
+            Err(RuntimeError::Break) if !vm.loops.is_empty() => pc = vm.unwind(|frame| frame.exit),
+            Err(RuntimeError::Continue) if !vm.loops.is_empty() => {
+                pc = vm.unwind(|frame| frame.next)
+            }
+            Err(err) => {
+                return Err(match chunk.spans[pc] {
+                    Some(span) => err.located(span),
+                    None => err,
+                });
+            }
+        }
+    }
+    Ok(vm.result)
+}
+
+impl Vm {
+    fn push(&mut self, value: Value) {
+        self.stack.push(value);
+    }
+
+    fn pop(&mut self) -> Value {
+        self.stack.pop().expect("VM stack underflow")
+    }
+
+    fn pop_n(&mut self, n: usize) -> Vec<Value> {
+        let at = self.stack.len() - n;
+        self.stack.split_off(at)
+    }
+
+    fn top(&self) -> &Value {
+        self.stack.last().expect("VM stack underflow")
+    }
+
+    fn innermost(&mut self) -> &mut LoopFrame {
+        self.loops
+            .last_mut()
+            .expect("loop instruction outside a loop")
+    }
+
+    /// Drop operands pushed inside the innermost loop and pick a jump target in it
+    fn unwind(&mut self, target: impl Fn(&LoopFrame) -> usize) -> usize {
+        let frame = self.innermost();
+        let (height, pc) = (frame.height, target(frame));
+        self.stack.truncate(height);
+        pc
+    }
+
+    /// Check the wall-clock limit every `CLOCK_INTERVAL` calls
+    fn tick_clock(&mut self, evaluator: &Evaluator) -> Result<(), RuntimeError> {
+        if self.clock_countdown == 0 {
+            self.clock_countdown = CLOCK_INTERVAL;
+            evaluator.check_timeout()?;
+        }
+        self.clock_countdown -= 1;
+        Ok(())
+    }
+
+    fn enter_loop(&mut self, pc: usize, exit: usize, looped: usize, items: Vec<Value>) {
+        self.result = Value::Null;
+        self.loops.push(LoopFrame {
+            next: pc + 1,
+            exit,
+            looped,
+            iterations: 0,
+            items: items.into_iter(),
+            height: self.stack.len(),
+        });
+    }
+
+    /// Execute the instruction at `pc`, returning the next one to run
+    fn execute(
+        &mut self,
+        evaluator: &mut Evaluator,
+        chunk: &Chunk,
+        pc: usize,
+    ) -> Result<usize, RuntimeError> {
+        match &chunk.ops[pc] {
+            Op::Step(line) => {
+                if let Some(line) = line {
+                    evaluator.set_current_line(*line);
+                }
+                evaluator.eval_step()?;
+                self.tick_clock(evaluator)?;
+            }
+            Op::Const(value) => self.push(value.clone()),
+            Op::Load(name) => {
+                let value = evaluator.lookup(name)?;
+                self.push(value);
+            }
+            Op::Store(name) => {
+                let value = self.pop();
+                evaluator.assign(name, value.clone())?;
+                self.result = value;
+            }
+            Op::SetResult => self.result = self.pop(),
+            Op::Binary(op) => {
+                let right = self.pop();
+                let left = self.pop();
+                let value = evaluator.binary(&left, op, &right)?;
+                self.push(value);
+            }
+            Op::Unary(op) => {
+                let operand = self.pop();
+                let value = evaluator.eval_unary_op(op, &operand)?;
+                self.push(value);
+            }
+            Op::Jump(target) => return Ok(*target),
+            Op::JumpIfFalsy(target) => {
+                if !self.top().is_truthy() {
+                    return Ok(*target);
+                }
+                self.pop();
+            }
+            Op::JumpIfTruthy(target) => {
+                if self.top().is_truthy() {
+                    return Ok(*target);
+                }
+                self.pop();
+            }
+            Op::JumpIfFalse { target, construct } => {
+                let cond = self.pop();
+                if !evaluator.condition(&cond, construct)? {
+                    return Ok(*target);
+                }
+            }
+            Op::Call { argc, name } => {
+                let args = self.pop_n(*argc);
+                let func = self.pop();
+                let value = evaluator.call_function(name.as_deref(), &func, args)?;
+                self.push(value);
+            }
+            Op::MakeArray(len) => {
+                let items = self.pop_n(*len);
+                self.push(Value::Array(items));
+            }
+            Op::MakeDict(keys) => {
+                let values = self.pop_n(keys.len());
+                self.push(Value::Dict(keys.iter().cloned().zip(values).collect()));
+            }
+            Op::Index => {
+                let index = self.pop();
+                let object = self.pop();
+                self.push(Evaluator::index_value(object, index)?);
+            }
+            Op::EnterWhile { exit, looped } => self.enter_loop(pc, *exit, *looped, Vec::new()),
+            Op::EnterFor {
+                exit,
+                looped,
+                indexed,
+            } => {
+                let iterable = match self.pop() {
+                    // Sets iterate in their deterministic (sorted) order, tables by row
+                    Value::Set(set) if !indexed => Value::Array(crate::value::set_elements(&set)),
+                    Value::Table(table) if !indexed => Value::Array(table.to_dicts()),
+                    other => other,
+                };
+                let Value::Array(items) = iterable else {
+                    return Err(RuntimeError::TypeError(format!(
+                        "Cannot iterate over {}",
+                        iterable.type_name()
+                    )));
+                };
+                self.enter_loop(pc, *exit, *looped, items);
+            }
+            Op::LoopIteration => {
+                let frame = self.innermost();
+                frame.iterations += 1;
+                evaluator.check_loop_iteration(frame.iterations, &chunk.loops[frame.looped])?;
+            }
+            Op::NextItem { var, index_var } => {
+                let frame = self.innermost();
+                let Some(item) = frame.items.next() else {
+                    return Ok(frame.exit);
+                };
+                frame.iterations += 1;
+                evaluator.check_loop_iteration(frame.iterations, &chunk.loops[frame.looped])?;
+                if let Some(index_var) = index_var {
+                    evaluator.set_var(index_var, Value::Int(frame.iterations as i64 - 1))?;
+                }
+                evaluator.set_var(var, item)?;
+            }
+            Op::ExitLoop => {
+                self.loops.pop();
+            }
+            Op::Break => match self.loops.last() {
+                Some(frame) => return Ok(frame.exit),
+                None => return Err(Evaluator::outside_loop("Break")),
+            },
+            Op::Continue => match self.loops.last() {
+                Some(frame) => return Ok(frame.next),
+                None => return Err(Evaluator::outside_loop("Continue")),
+            },
+            Op::Return => return Err(RuntimeError::Return(self.pop())),
+            Op::Exec(stmt) => {
+                evaluator.set_loop_depth(self.loops.len());
+                self.result = evaluator.eval_statement(stmt)?;
+            }
+            Op::Eval(expr) => {
+                evaluator.set_loop_depth(self.loops.len());
+                let value = evaluator.eval_expression(expr)?;
+                self.push(value);
+            }
+        }
+        Ok(pc + 1)
+    }
+}
@@ -2,9 +2,14 @@
 //! Interactive debugger for Aether
 
 mod breakpoint;
+mod recording;
+mod remote;
 mod session;
 mod state;
 
 pub use breakpoint::{Breakpoint, BreakpointType};
+pub(crate) use recording::Recorder;
+pub use recording::{DEFAULT_MAX_RECORDED_STEPS, ExecutionRecording, RecordedStep, VariableChange};
+pub use remote::{PROMPT, RemoteConnection, RemoteDebugServer, serve_commands};
 pub use session::{CommandAction, DebuggerSession};
 pub use state::{DebuggerState, ExecutionMode};
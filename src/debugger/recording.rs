@@ -0,0 +1,299 @@
+// src/debugger/recording.rs
+//! Execution recording for time-travel debugging
+//!
+//! While recording is enabled the evaluator compares the data variables visible before
+//! and after every statement with the previous observation. Only the differences are
+//! kept, each attributed to the statement that made it, so the debugger can replay them
+//! forwards or backwards and show the value any variable had at any step without
+//! re-running the program.
+
+use crate::ast::{Expr, Stmt};
+use crate::builtins::patch::diff_values;
+use crate::value::Value;
+use std::collections::BTreeMap;
+
+/// Default maximum number of steps kept by a recording
+pub const DEFAULT_MAX_RECORDED_STEPS: usize = 100_000;
+
+/// A variable whose value changed while a step executed
+#[derive(Debug, Clone, PartialEq)]
+pub struct VariableChange {
+    /// Variable name
+    pub name: String,
+    /// Value before the step (`None` if the variable was not visible yet)
+    pub old: Option<Value>,
+    /// Value after the step (`None` if the variable went out of scope)
+    pub new: Option<Value>,
+}
+
+/// One executed statement and the variable changes it caused
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedStep {
+    /// Position in the recording (starts at 0)
+    pub index: usize,
+    /// Short description of the statement, e.g. `Set TOTAL` or `For EMP`
+    pub statement: String,
+    /// Innermost function on the call stack (`None` at top level)
+    pub function: Option<String>,
+    /// Call stack depth when the statement started
+    pub depth: usize,
+    /// Net variable changes made while this statement ran (excluding nested statements)
+    pub changes: Vec<VariableChange>,
+}
+
+/// A recorded execution: the starting variables plus the changes made by each statement
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExecutionRecording {
+    initial: BTreeMap<String, Value>,
+    steps: Vec<RecordedStep>,
+    /// Every change in the order it happened
+    timeline: Vec<TimedChange>,
+    truncated: bool,
+}
+
+/// A change in the timeline: observed before step `at` started, caused by step `step`
+#[derive(Debug, Clone, PartialEq)]
+struct TimedChange {
+    at: usize,
+    step: usize,
+    change: VariableChange,
+}
+
+impl ExecutionRecording {
+    /// Number of recorded steps
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// Whether no statement was recorded
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// All recorded steps in execution order
+    pub fn steps(&self) -> &[RecordedStep] {
+        &self.steps
+    }
+
+    /// Get a step by index
+    pub fn step(&self, index: usize) -> Option<&RecordedStep> {
+        self.steps.get(index)
+    }
+
+    /// Whether recording stopped early because the step limit was reached
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Variables visible when step `index` started (`index == len()` gives the final state)
+    pub fn variables_before(&self, index: usize) -> BTreeMap<String, Value> {
+        let mut vars = self.initial.clone();
+        for timed in self.timeline.iter().take_while(|timed| timed.at <= index) {
+            let change = &timed.change;
+            match &change.new {
+                Some(value) => vars.insert(change.name.clone(), value.clone()),
+                None => vars.remove(&change.name),
+            };
+        }
+        vars
+    }
+
+    /// Value of a variable when step `index` started
+    pub fn value_before(&self, index: usize, name: &str) -> Option<Value> {
+        self.variables_before(index).remove(name)
+    }
+
+    /// Every change of a variable as `(step index, change)`, in the order it happened
+    pub fn history(&self, name: &str) -> Vec<(usize, &VariableChange)> {
+        self.timeline
+            .iter()
+            .filter(|timed| timed.change.name == name)
+            .map(|timed| (timed.step, &timed.change))
+            .collect()
+    }
+}
+
+/// Builds a recording while the evaluator runs
+///
+/// The evaluator calls [`Recorder::begin`] before and [`Recorder::end`] after every
+/// statement. Changes seen at either point are attributed to the innermost statement
+/// still running, so `Set TOTAL (TOTAL + BONUS(X))` owns the change to `TOTAL` even
+/// though the statements inside `BONUS` were recorded in between.
+#[derive(Debug)]
+pub(crate) struct Recorder {
+    recording: ExecutionRecording,
+    last: BTreeMap<String, Value>,
+    /// Steps that have begun but not ended, innermost last
+    running: Vec<usize>,
+    max_steps: usize,
+}
+
+impl Recorder {
+    pub(crate) fn new(max_steps: usize) -> Self {
+        Recorder {
+            recording: ExecutionRecording::default(),
+            last: BTreeMap::new(),
+            running: Vec::new(),
+            max_steps,
+        }
+    }
+
+    /// Whether the step limit has been reached (no further changes are recorded)
+    pub(crate) fn is_full(&self) -> bool {
+        self.recording.truncated
+    }
+
+    /// Record the start of a statement with the variables visible at that point
+    ///
+    /// Returns whether a step was opened; only then must [`Recorder::end`] follow.
+    pub(crate) fn begin(
+        &mut self,
+        vars: BTreeMap<String, Value>,
+        statement: &Stmt,
+        function: Option<String>,
+        depth: usize,
+    ) -> bool {
+        if self.is_full() {
+            return false;
+        }
+        self.observe(vars);
+        if self.recording.steps.len() >= self.max_steps {
+            self.recording.truncated = true;
+            return false;
+        }
+        let index = self.recording.steps.len();
+        self.recording.steps.push(RecordedStep {
+            index,
+            statement: describe(statement),
+            function,
+            depth,
+            changes: Vec::new(),
+        });
+        self.running.push(index);
+        true
+    }
+
+    /// Record the end of the innermost running statement (`None` once the recorder is full)
+    pub(crate) fn end(&mut self, vars: Option<BTreeMap<String, Value>>) {
+        if let Some(vars) = vars
+            && !self.is_full()
+        {
+            self.observe(vars);
+        }
+        self.running.pop();
+    }
+
+    /// Record the final variables and return the recording
+    pub(crate) fn finish(mut self, vars: BTreeMap<String, Value>) -> ExecutionRecording {
+        if !self.is_full() {
+            self.observe(vars);
+        }
+        self.recording
+    }
+
+    /// Attribute the differences since the previous observation to the innermost running step
+    fn observe(&mut self, vars: BTreeMap<String, Value>) {
+        let recording = &mut self.recording;
+        if recording.steps.is_empty() {
+            recording.initial = vars.clone();
+            self.last = vars;
+            return;
+        }
+
+        let at = recording.steps.len();
+        let step = self.running.last().copied().unwrap_or(at - 1);
+        for change in changes_between(&self.last, &vars) {
+            merge_change(&mut recording.steps[step].changes, &change);
+            recording.timeline.push(TimedChange { at, step, change });
+        }
+        self.last = vars;
+    }
+}
+
+/// Fold a change into a step's net changes (first old value, last new value)
+fn merge_change(changes: &mut Vec<VariableChange>, change: &VariableChange) {
+    match changes.iter().position(|c| c.name == change.name) {
+        Some(pos) => {
+            let existing = &mut changes[pos];
+            existing.new = change.new.clone();
+            if same_value(&existing.old, &existing.new) {
+                changes.remove(pos);
+            }
+        }
+        None => changes.push(change.clone()),
+    }
+}
+
+fn same_value(a: &Option<Value>, b: &Option<Value>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => diff_values(a, b).is_empty(),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+fn changes_between(
+    old: &BTreeMap<String, Value>,
+    new: &BTreeMap<String, Value>,
+) -> Vec<VariableChange> {
+    let mut changes = Vec::new();
+    for (name, old_value) in old {
+        match new.get(name) {
+            Some(new_value) if diff_values(old_value, new_value).is_empty() => {}
+            new_value => changes.push(VariableChange {
+                name: name.clone(),
+                old: Some(old_value.clone()),
+                new: new_value.cloned(),
+            }),
+        }
+    }
+    for (name, new_value) in new {
+        if !old.contains_key(name) {
+            changes.push(VariableChange {
+                name: name.clone(),
+                old: None,
+                new: Some(new_value.clone()),
+            });
+        }
+    }
+    changes.sort_by(|a, b| a.name.cmp(&b.name));
+    changes
+}
+
+/// Short, single-line description of a statement
+fn describe(stmt: &Stmt) -> String {
+    match stmt {
+        Stmt::Set { name, .. } => format!("Set {}", name),
+        Stmt::SetIndex { object, .. } => match object.as_ref() {
+            Expr::Identifier(name) => format!("Set {}[...]", name),
+            _ => "Set [...]".to_string(),
+        },
+        Stmt::FuncDef { name, .. } => format!("Func {}", name),
+        Stmt::GeneratorDef { name, .. } => format!("Generator {}", name),
+        Stmt::LazyDef { name, .. } => format!("Lazy {}", name),
+        Stmt::Return(_) => "Return".to_string(),
+        Stmt::Yield(_) => "Yield".to_string(),
+        Stmt::Break => "Break".to_string(),
+        Stmt::Continue => "Continue".to_string(),
+        Stmt::While { .. } => "While".to_string(),
+        Stmt::For { var, .. } => format!("For {}", var),
+        Stmt::ForIndexed {
+            index_var,
+            value_var,
+            ..
+        } => format!("For {}, {}", index_var, value_var),
+        Stmt::Switch { .. } => "Switch".to_string(),
+        Stmt::Import { path, .. } => format!("Import {}", path),
+        Stmt::Export(name) => format!("Export {}", name),
+        Stmt::Throw(_) => "Throw".to_string(),
+        Stmt::Try { .. } => "Try".to_string(),
+        Stmt::Pragma { name, value } => format!("#{} {}", name, value),
+        Stmt::Expression(Expr::Call { func, .. }) => match func.as_ref() {
+            Expr::Identifier(name) => format!("{}(...)", name),
+            _ => "Call".to_string(),
+        },
+        Stmt::Expression(Expr::If { .. }) => "If".to_string(),
+        Stmt::Expression(_) => "Expression".to_string(),
+        Stmt::Located { stmt, .. } => describe(stmt),
+    }
+}
@@ -0,0 +1,111 @@
+// src/debugger/remote.rs
+//! Remote debugging over TCP
+//!
+//! A script started with `aether run --debug-listen 9229 script.aether` pauses before its
+//! first statement and waits for one debugger client. The wire protocol is the debugger's
+//! own command language: the client sends one command per line and every reply ends with
+//! the `(aether-debug) ` prompt, so `nc`, `telnet` or an editor extension can drive the
+//! same [`DebuggerSession`] the local `--debugger` REPL uses.
+
+use crate::debugger::session::{CommandAction, DebuggerSession};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+
+/// Prompt sent after every reply; clients read up to it to frame responses
+pub const PROMPT: &str = "(aether-debug) ";
+
+/// Listens for a remote debugger client
+pub struct RemoteDebugServer {
+    listener: TcpListener,
+}
+
+impl RemoteDebugServer {
+    /// Bind to an address such as `127.0.0.1:9229` (port 0 picks a free port)
+    pub fn bind<A: ToSocketAddrs>(addr: A) -> io::Result<Self> {
+        Ok(RemoteDebugServer {
+            listener: TcpListener::bind(addr)?,
+        })
+    }
+
+    /// The address actually bound
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Block until a client connects
+    pub fn accept(&self) -> io::Result<RemoteConnection> {
+        let (stream, _) = self.listener.accept()?;
+        RemoteConnection::new(stream)
+    }
+}
+
+/// A connected debugger client
+pub struct RemoteConnection {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+impl RemoteConnection {
+    /// Wrap an accepted stream
+    pub fn new(stream: TcpStream) -> io::Result<Self> {
+        Ok(RemoteConnection {
+            reader: BufReader::new(stream.try_clone()?),
+            writer: stream,
+        })
+    }
+
+    /// Address of the client
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        self.writer.peer_addr()
+    }
+
+    /// Send a message to the client (a trailing newline is added if missing)
+    pub fn send(&mut self, text: &str) -> io::Result<()> {
+        write_message(&mut self.writer, text)
+    }
+
+    /// Handle commands until one resumes execution or ends the session
+    ///
+    /// Returns [`CommandAction::Continue`] or [`CommandAction::Quit`]; a closed
+    /// connection counts as `Quit`.
+    pub fn serve(&mut self, session: &mut DebuggerSession) -> io::Result<CommandAction> {
+        serve_commands(session, &mut self.reader, &mut self.writer)
+    }
+}
+
+/// Run the command protocol over any reader/writer pair
+pub fn serve_commands(
+    session: &mut DebuggerSession,
+    reader: &mut impl BufRead,
+    writer: &mut impl Write,
+) -> io::Result<CommandAction> {
+    loop {
+        writer.write_all(PROMPT.as_bytes())?;
+        writer.flush()?;
+
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(CommandAction::Quit);
+        }
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let (msg, action) = session.handle_command(line);
+        if !msg.is_empty() {
+            write_message(writer, &msg)?;
+        }
+        if action != CommandAction::Stay {
+            return Ok(action);
+        }
+    }
+}
+
+fn write_message(writer: &mut impl Write, text: &str) -> io::Result<()> {
+    writer.write_all(text.as_bytes())?;
+    if !text.ends_with('\n') {
+        writer.write_all(b"\n")?;
+    }
+    writer.flush()
+}
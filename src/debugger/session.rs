@@ -2,6 +2,7 @@
 //! Debugger session and command processing
 
 use crate::debugger::breakpoint::BreakpointType;
+use crate::debugger::recording::{DEFAULT_MAX_RECORDED_STEPS, ExecutionRecording};
 use crate::debugger::state::{DebuggerState, ExecutionMode};
 use crate::evaluator::Evaluator;
 use std::cell::RefCell;
@@ -24,6 +25,10 @@ pub struct DebuggerSession {
     state: DebuggerState,
     source_code: Option<String>,
     source_file: Option<String>,
+    /// Recorded execution being replayed (set by `record`)
+    recording: Option<ExecutionRecording>,
+    /// Replay position: index of the step about to run (`len()` is the end of the recording)
+    cursor: usize,
 }
 
 impl DebuggerSession {
@@ -34,6 +39,8 @@ impl DebuggerSession {
             state: DebuggerState::new(),
             source_code: None,
             source_file: None,
+            recording: None,
+            cursor: 0,
         }
     }
 
@@ -46,11 +53,39 @@ impl DebuggerSession {
     /// Start the debugger session
     pub fn start(&mut self) {
         self.state.activate();
-        println!("Aether Debugger v1.0");
+        println!("{}", self.banner());
+    }
+
+    /// Greeting shown when a session starts (locally or to a remote client)
+    pub fn banner(&self) -> String {
+        let mut banner = String::from("Aether Debugger v1.0\n");
         if let Some(file) = &self.source_file {
-            println!("Debugging: {}", file);
+            banner.push_str(&format!("Debugging: {}\n", file));
         }
-        println!("Type 'help' for available commands\n");
+        banner.push_str("Type 'help' for available commands\n");
+        banner
+    }
+
+    /// Run the loaded program to completion in the session's evaluator
+    ///
+    /// Returns a status line; globals stay available to `print` afterwards.
+    pub fn run_program(&mut self) -> String {
+        let Some(source) = self.source_code.clone() else {
+            return "No source code available".to_string();
+        };
+        match self.eval_source(&source) {
+            Ok(_) => "Program exited normally".to_string(),
+            Err(e) => format!("Program stopped with error: {}", e),
+        }
+    }
+
+    fn eval_source(&self, source: &str) -> Result<crate::value::Value, String> {
+        let mut evaluator = self.evaluator.borrow_mut();
+        let program = evaluator
+            .parser(source)
+            .parse_program()
+            .map_err(|e| format!("Parse error: {}", e))?;
+        evaluator.eval_program(&program).map_err(|e| e.to_string())
     }
 
     /// Get a mutable reference to the debugger state
@@ -63,6 +98,16 @@ impl DebuggerSession {
         &self.state
     }
 
+    /// Get the recorded execution being replayed, if any
+    pub fn recording(&self) -> Option<&ExecutionRecording> {
+        self.recording.as_ref()
+    }
+
+    /// Get the replay position (index of the step about to run)
+    pub fn replay_position(&self) -> usize {
+        self.cursor
+    }
+
     /// Handle a debugger command, returning (message, action)
     pub fn handle_command(&mut self, cmd: &str) -> (String, CommandAction) {
         let parts: Vec<&str> = cmd.split_whitespace().collect();
@@ -87,6 +132,11 @@ impl DebuggerSession {
             "backtrace" | "bt" => self.cmd_backtrace(args),
             "frame" => self.cmd_frame(args),
             "list" | "l" => self.cmd_list(args),
+            "record" => self.cmd_record(args),
+            "back" | "reverse-step" | "rs" => self.cmd_back(args),
+            "forward" | "fw" => self.cmd_forward(args),
+            "goto" => self.cmd_goto(args),
+            "history" => self.cmd_history(args),
             "help" | "h" | "?" => self.cmd_help(args),
             "quit" | "q" => self.cmd_quit(args),
             _ => (
@@ -250,13 +300,24 @@ impl DebuggerSession {
                 }
                 (result, CommandAction::Stay)
             }
-            "locals" => {
+            "locals" => match &self.recording {
+                Some(recording) => {
+                    let vars = recording.variables_before(self.cursor);
+                    if vars.is_empty() {
+                        return ("No variables".to_string(), CommandAction::Stay);
+                    }
+                    let mut result = format!("Variables at step {}:\n", self.cursor);
+                    for (name, value) in vars {
+                        result.push_str(&format!("  {} = {}\n", name, value));
+                    }
+                    (result, CommandAction::Stay)
+                }
                 // TODO: Need to add API to Evaluator to get all variables
-                (
+                None => (
                     "Local variables: Not yet implemented".to_string(),
                     CommandAction::Stay,
-                )
-            }
+                ),
+            },
             "args" => (
                 "Arguments: Not yet implemented".to_string(),
                 CommandAction::Stay,
@@ -322,6 +383,19 @@ impl DebuggerSession {
         }
 
         let var_name = args[0];
+        if let Some(recording) = &self.recording {
+            return match recording.value_before(self.cursor, var_name) {
+                Some(value) => (
+                    format!("{} = {} (at step {})", var_name, value, self.cursor),
+                    CommandAction::Stay,
+                ),
+                None => (
+                    format!("Variable '{}' not set at step {}", var_name, self.cursor),
+                    CommandAction::Stay,
+                ),
+            };
+        }
+
         let evaluator = self.evaluator.borrow();
 
         match evaluator.get_global(var_name) {
@@ -350,7 +424,7 @@ impl DebuggerSession {
 
         let mut result = String::from("Call stack:\n");
         for (i, frame) in call_stack.iter().take(max_frames).enumerate() {
-            result.push_str(&format!("#{} {}\n", i, frame.signature));
+            result.push_str(&format!("#{} {}\n", i, frame));
         }
         drop(evaluator);
         (result, CommandAction::Stay)
@@ -403,6 +477,147 @@ impl DebuggerSession {
         }
     }
 
+    fn cmd_record(&mut self, args: &[&str]) -> (String, CommandAction) {
+        let Some(source) = self.source_code.clone() else {
+            return ("No source code available".to_string(), CommandAction::Stay);
+        };
+        let max_steps = match args.first() {
+            Some(arg) => match arg.parse::<usize>() {
+                Ok(n) if n > 0 => n,
+                _ => return ("Usage: record [max_steps]".to_string(), CommandAction::Stay),
+            },
+            None => DEFAULT_MAX_RECORDED_STEPS,
+        };
+
+        self.evaluator.borrow_mut().start_recording(max_steps);
+        let result = self.eval_source(&source);
+        let recording = self
+            .evaluator
+            .borrow_mut()
+            .take_recording()
+            .unwrap_or_default();
+
+        let mut msg = format!("Recorded {} steps", recording.len());
+        if recording.is_truncated() {
+            msg.push_str(" (step limit reached, later statements were not recorded)");
+        }
+        msg.push('\n');
+        if let Err(e) = result {
+            msg.push_str(&format!("Program stopped with error: {}\n", e));
+        }
+        self.cursor = recording.len();
+        self.recording = Some(recording);
+        msg.push_str(&self.describe_position());
+        (msg, CommandAction::Stay)
+    }
+
+    fn cmd_back(&mut self, args: &[&str]) -> (String, CommandAction) {
+        if self.recording.is_none() {
+            return (NO_RECORDING.to_string(), CommandAction::Stay);
+        }
+        let count = args
+            .first()
+            .and_then(|a| a.parse::<usize>().ok())
+            .unwrap_or(1);
+        self.cursor = self.cursor.saturating_sub(count);
+        (self.describe_position(), CommandAction::Stay)
+    }
+
+    fn cmd_forward(&mut self, args: &[&str]) -> (String, CommandAction) {
+        let Some(recording) = &self.recording else {
+            return (NO_RECORDING.to_string(), CommandAction::Stay);
+        };
+        let count = args
+            .first()
+            .and_then(|a| a.parse::<usize>().ok())
+            .unwrap_or(1);
+        self.cursor = (self.cursor + count).min(recording.len());
+        (self.describe_position(), CommandAction::Stay)
+    }
+
+    fn cmd_goto(&mut self, args: &[&str]) -> (String, CommandAction) {
+        let Some(recording) = &self.recording else {
+            return (NO_RECORDING.to_string(), CommandAction::Stay);
+        };
+        match args.first().map(|a| a.parse::<usize>()) {
+            Some(Ok(step)) if step <= recording.len() => {
+                self.cursor = step;
+                (self.describe_position(), CommandAction::Stay)
+            }
+            Some(Ok(step)) => (
+                format!(
+                    "Step {} out of range (recording has {} steps)",
+                    step,
+                    recording.len()
+                ),
+                CommandAction::Stay,
+            ),
+            _ => ("Usage: goto <step>".to_string(), CommandAction::Stay),
+        }
+    }
+
+    fn cmd_history(&mut self, args: &[&str]) -> (String, CommandAction) {
+        let Some(recording) = &self.recording else {
+            return (NO_RECORDING.to_string(), CommandAction::Stay);
+        };
+        let Some(var_name) = args.first() else {
+            return (
+                "Usage: history <variable_name>".to_string(),
+                CommandAction::Stay,
+            );
+        };
+
+        let history = recording.history(var_name);
+        if history.is_empty() {
+            return (
+                format!("No recorded changes to '{}'", var_name),
+                CommandAction::Stay,
+            );
+        }
+        let mut result = format!("History of {}:\n", var_name);
+        for (index, change) in history {
+            let step = &recording.steps()[index];
+            let location = match &step.function {
+                Some(function) => format!(" (in {})", function),
+                None => String::new(),
+            };
+            result.push_str(&format!(
+                "  #{:<5} {}{}: {} -> {}\n",
+                index,
+                step.statement,
+                location,
+                format_slot(&change.old),
+                format_slot(&change.new)
+            ));
+        }
+        (result, CommandAction::Stay)
+    }
+
+    /// Describe the replay position and the changes the statement there makes
+    fn describe_position(&self) -> String {
+        let Some(recording) = &self.recording else {
+            return NO_RECORDING.to_string();
+        };
+        let Some(step) = recording.step(self.cursor) else {
+            return format!("At end of recording ({} steps)", recording.len());
+        };
+
+        let mut result = format!("Step {}/{}", step.index, recording.len());
+        if let Some(function) = &step.function {
+            result.push_str(&format!(" in {} (depth {})", function, step.depth));
+        }
+        result.push_str(&format!(": {}\n", step.statement));
+        for change in &step.changes {
+            result.push_str(&format!(
+                "  {}: {} -> {}\n",
+                change.name,
+                format_slot(&change.old),
+                format_slot(&change.new)
+            ));
+        }
+        result
+    }
+
     fn cmd_help(&mut self, _args: &[&str]) -> (String, CommandAction) {
         (HELP_TEXT.to_string(), CommandAction::Stay)
     }
@@ -412,6 +627,16 @@ impl DebuggerSession {
     }
 }
 
+const NO_RECORDING: &str = "No recording. Use 'record' to run the program with recording enabled.";
+
+/// Display a recorded value, or `<unset>` when the variable was not visible
+fn format_slot(value: &Option<crate::value::Value>) -> String {
+    match value {
+        Some(value) => value.to_string(),
+        None => "<unset>".to_string(),
+    }
+}
+
 const HELP_TEXT: &str = r#"
 Aether Debugger Commands
 
@@ -438,6 +663,15 @@ Stack & Variables:
 Source:
   list [N]           List N lines of source (default 10)
 
+Time Travel:
+  record [N]         Run the program recording variable changes (at most N steps)
+  back [N]           Step N statements backwards in the recording (alias: rs)
+  forward [N]        Step N statements forwards in the recording (alias: fw)
+  goto N             Jump to recorded step N
+  history VAR        Show every recorded change of VAR
+  print VAR          While replaying, show VAR as it was at the current step
+  info locals        While replaying, show all variables at the current step
+
 Miscellaneous:
   help               Show this help message
   quit               Exit debugger
@@ -450,6 +684,8 @@ Examples:
   (aether-debug) step               # Step into
   (aether-debug) print X            # Show variable X
   (aether-debug) backtrace          # Show call stack
+  (aether-debug) record             # Record a run, then step backwards
+  (aether-debug) history TOTAL      # See how TOTAL changed over time
 "#;
 
 #[cfg(test)]
@@ -0,0 +1,140 @@
+// src/dialect.rs
+//! 关键字方言
+//!
+//! 允许嵌入方把关键字映射为领域用户熟悉的写法（如中文 `设置` / `如果`，
+//! 或 `let` / `if`）。方言只在词法分析阶段把别名替换为标准关键字，
+//! 生成的 AST 与标准语法完全相同，原有关键字也始终可用。
+
+use crate::token::Token;
+use std::collections::HashMap;
+
+/// 关键字方言表：别名 -> 标准关键字
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Dialect {
+    aliases: HashMap<String, String>,
+}
+
+impl Dialect {
+    /// 创建空方言（与标准语法相同）
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 中文关键字方言
+    ///
+    /// # 示例
+    /// ```
+    /// use aether::{Aether, Dialect, Value};
+    ///
+    /// let mut engine = Aether::new().with_dialect(Dialect::chinese());
+    /// let result = engine.eval("设置 X 5\n如果 (X > 3) { \"大\" } 否则 { \"小\" }").unwrap();
+    /// assert_eq!(result, Value::String("大".to_string()));
+    /// ```
+    pub fn chinese() -> Self {
+        let pairs = [
+            ("设置", "Set"),
+            ("函数", "Func"),
+            ("匿名", "Lambda"),
+            ("生成器", "Generator"),
+            ("惰性", "Lazy"),
+            ("如果", "If"),
+            ("否则如果", "Elif"),
+            ("否则", "Else"),
+            ("当", "While"),
+            ("遍历", "For"),
+            ("在", "In"),
+            ("选择", "Switch"),
+            ("情况", "Case"),
+            ("默认", "Default"),
+            ("返回", "Return"),
+            ("产出", "Yield"),
+            ("跳出", "Break"),
+            ("继续", "Continue"),
+            ("导入", "Import"),
+            ("来自", "From"),
+            ("作为", "As"),
+            ("导出", "Export"),
+            ("抛出", "Throw"),
+            ("尝试", "Try"),
+            ("捕获", "Catch"),
+            ("最终", "Finally"),
+            ("且", "And"),
+            ("或", "Or"),
+            ("非", "Not"),
+            ("真", "True"),
+            ("假", "False"),
+            ("空", "Null"),
+        ];
+        let mut dialect = Dialect::new();
+        for (alias, keyword) in pairs {
+            dialect
+                .add_keyword(alias, keyword)
+                .expect("built-in dialect entries are valid");
+        }
+        dialect
+    }
+
+    /// 添加关键字别名（构建器形式）
+    ///
+    /// # 示例
+    /// ```
+    /// use aether::Dialect;
+    ///
+    /// let dialect = Dialect::new()
+    ///     .with_keyword("let", "Set").unwrap()
+    ///     .with_keyword("if", "If").unwrap();
+    /// assert_eq!(dialect.resolve("let"), "Set");
+    /// ```
+    pub fn with_keyword(mut self, alias: &str, keyword: &str) -> Result<Self, String> {
+        self.add_keyword(alias, keyword)?;
+        Ok(self)
+    }
+
+    /// 添加关键字别名
+    ///
+    /// `alias` 必须是合法的标识符且不能是标准关键字，
+    /// `keyword` 必须是标准关键字（如 `Set`、`If`、`True`）。
+    pub fn add_keyword(&mut self, alias: &str, keyword: &str) -> Result<(), String> {
+        if matches!(Token::lookup_keyword(keyword), Token::Identifier(_)) {
+            return Err(format!("'{}' is not an Aether keyword", keyword));
+        }
+        let mut chars = alias.chars();
+        let valid = chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+            && chars.all(|c| c.is_alphanumeric() || c == '_');
+        if !valid {
+            return Err(format!(
+                "Dialect alias '{}' is not a valid identifier",
+                alias
+            ));
+        }
+        if !matches!(Token::lookup_keyword(alias), Token::Identifier(_)) {
+            return Err(format!(
+                "Dialect alias '{}' would shadow a standard keyword",
+                alias
+            ));
+        }
+        self.aliases.insert(alias.to_string(), keyword.to_string());
+        Ok(())
+    }
+
+    /// 把标识符解析为标准写法（不是别名时原样返回）
+    pub fn resolve<'a>(&'a self, ident: &'a str) -> &'a str {
+        self.aliases.get(ident).map(String::as_str).unwrap_or(ident)
+    }
+
+    /// 已配置的别名（别名，标准关键字），按别名排序
+    pub fn aliases(&self) -> Vec<(&str, &str)> {
+        let mut aliases: Vec<(&str, &str)> = self
+            .aliases
+            .iter()
+            .map(|(alias, keyword)| (alias.as_str(), keyword.as_str()))
+            .collect();
+        aliases.sort();
+        aliases
+    }
+
+    /// 是否没有任何别名
+    pub fn is_empty(&self) -> bool {
+        self.aliases.is_empty()
+    }
+}
@@ -6,8 +6,82 @@
 //! **注意**：由于使用 thread_local，每个线程有独立的引擎实例。
 //! 如需多线程共享引擎池，请使用 `EnginePool`。
 
+use crate::builtins::IOPermissions;
+use crate::runtime::ExecutionLimits;
+use crate::sandbox::{MetricsCollector, MetricsSnapshot};
+use crate::stdlib;
 use crate::{Aether, Value};
 use std::cell::RefCell;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// 全局引擎的初始化配置
+///
+/// 通过 `GlobalEngine::configure()` 设置一次，之后每个线程首次使用全局引擎时
+/// 按此配置创建引擎实例。
+#[derive(Debug, Clone, Default)]
+pub struct GlobalEngineConfig {
+    /// IO 权限
+    pub permissions: IOPermissions,
+    /// 执行限制（None 表示使用引擎默认值）
+    pub limits: Option<ExecutionLimits>,
+    /// 预加载的标准库模块（如 "string_utils"）
+    pub stdlib_modules: Vec<String>,
+}
+
+/// 全局配置（进程内只能设置一次）
+static GLOBAL_CONFIG: OnceLock<GlobalEngineConfig> = OnceLock::new();
+
+/// 全局引擎的执行指标（所有线程共享）
+static GLOBAL_METRICS: OnceLock<MetricsCollector> = OnceLock::new();
+
+/// 是否已有线程创建了全局引擎（创建之后不能再修改配置）
+static ENGINE_CREATED: AtomicBool = AtomicBool::new(false);
+
+/// 线程局部的全局引擎及其基线变量
+struct GlobalState {
+    engine: Aether,
+    /// 预加载标准库后定义的全局变量，清空环境后恢复
+    baseline: Vec<(String, Value)>,
+}
+
+impl GlobalState {
+    fn create() -> Self {
+        ENGINE_CREATED.store(true, Ordering::SeqCst);
+        let config = GLOBAL_CONFIG.get().cloned().unwrap_or_default();
+
+        let mut engine = Aether::with_permissions(config.permissions);
+        if let Some(limits) = config.limits {
+            engine.set_limits(limits);
+        }
+        // 模块名已在 configure() 中校验
+        for module in &config.stdlib_modules {
+            let _ = engine.load_stdlib_module(module);
+        }
+
+        let baseline = engine
+            .evaluator
+            .global_variables()
+            .into_iter()
+            .filter_map(|var| {
+                let value = engine.evaluator.get_global(&var.name)?;
+                Some((var.name, value))
+            })
+            .collect();
+
+        GlobalState { engine, baseline }
+    }
+
+    /// 清空环境并恢复预加载的标准库定义
+    fn reset(&mut self) {
+        self.engine.evaluator.reset_env();
+        for (name, value) in &self.baseline {
+            self.engine
+                .evaluator
+                .set_global(name.clone(), value.clone());
+        }
+    }
+}
 
 thread_local! {
     /// 线程局部 Aether 引擎单例
@@ -18,7 +92,7 @@ thread_local! {
     /// - 每个线程只创建一次引擎实例
     /// - AST 缓存在多次调用间累积（可达142x加速）
     /// - 内置函数注册表复用
-    static THREAD_LOCAL_AETHER: RefCell<Aether> = RefCell::new(Aether::new());
+    static THREAD_LOCAL_AETHER: RefCell<GlobalState> = RefCell::new(GlobalState::create());
 }
 
 /// 全局单例引擎
@@ -75,6 +149,57 @@ thread_local! {
 pub struct GlobalEngine;
 
 impl GlobalEngine {
+    /// 配置全局引擎（权限、执行限制、预加载的标准库模块）
+    ///
+    /// 配置在每个线程首次使用全局引擎时生效，因此必须在任何线程调用
+    /// `GlobalEngine` 的其他方法之前调用，且只能调用一次。
+    /// 预加载的标准库在 `eval_isolated()` / `clear_env()` 清空环境后仍然可用。
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use aether::builtins::IOPermissions;
+    /// use aether::engine::GlobalEngine;
+    /// use aether::runtime::ExecutionLimits;
+    ///
+    /// GlobalEngine::configure(
+    ///     IOPermissions::default(),
+    ///     ExecutionLimits::default(),
+    ///     &["string_utils"],
+    /// )
+    /// .unwrap();
+    /// ```
+    pub fn configure(
+        permissions: IOPermissions,
+        limits: ExecutionLimits,
+        stdlib_modules: &[&str],
+    ) -> Result<(), String> {
+        if let Some(unknown) = stdlib_modules
+            .iter()
+            .find(|m| stdlib::get_module(m).is_none())
+        {
+            return Err(format!("Unknown stdlib module: {}", unknown));
+        }
+        if ENGINE_CREATED.load(Ordering::SeqCst) {
+            return Err(
+                "GlobalEngine is already initialized; configure it before first use".to_string(),
+            );
+        }
+
+        GLOBAL_CONFIG
+            .set(GlobalEngineConfig {
+                permissions,
+                limits: Some(limits),
+                stdlib_modules: stdlib_modules.iter().map(|m| m.to_string()).collect(),
+            })
+            .map_err(|_| "GlobalEngine is already configured".to_string())
+    }
+
+    /// 获取全局引擎的配置（未调用 `configure()` 时返回 None）
+    pub fn config() -> Option<&'static GlobalEngineConfig> {
+        GLOBAL_CONFIG.get()
+    }
+
     /// 使用全局引擎执行代码（隔离环境）
     ///
     /// 每次执行前清空环境变量，确保不同执行间的隔离性。
@@ -93,14 +218,14 @@ impl GlobalEngine {
     ///
     /// 每个线程有独立的引擎实例，无需担心线程安全问题。
     pub fn eval_isolated(code: &str) -> Result<Value, String> {
-        THREAD_LOCAL_AETHER.with(|engine| {
-            let mut engine = engine.borrow_mut();
+        THREAD_LOCAL_AETHER.with(|state| {
+            let mut state = state.borrow_mut();
 
             // 重置环境（保证隔离性）
-            engine.evaluator.reset_env();
+            state.reset();
 
             // 执行代码（使用缓存）
-            engine.eval(code)
+            super::eval_with_metrics(&mut state.engine, code, Self::metrics())
         })
     }
 
@@ -125,7 +250,9 @@ impl GlobalEngine {
     /// GlobalEngine::clear_env();
     /// ```
     pub fn eval(code: &str) -> Result<Value, String> {
-        THREAD_LOCAL_AETHER.with(|engine| engine.borrow_mut().eval(code))
+        THREAD_LOCAL_AETHER.with(|state| {
+            super::eval_with_metrics(&mut state.borrow_mut().engine, code, Self::metrics())
+        })
     }
 
     /// 清空全局引擎的环境变量
@@ -133,8 +260,8 @@ impl GlobalEngine {
     /// 用于手动清理 `eval()` 累积的变量。
     /// `eval_isolated()` 会自动清空，无需调用此方法。
     pub fn clear_env() {
-        THREAD_LOCAL_AETHER.with(|engine| {
-            engine.borrow_mut().evaluator.reset_env();
+        THREAD_LOCAL_AETHER.with(|state| {
+            state.borrow_mut().reset();
         });
     }
 
@@ -145,8 +272,8 @@ impl GlobalEngine {
     ///
     /// **注意**：清理后性能会下降，直到缓存重新建立。
     pub fn clear_cache() {
-        THREAD_LOCAL_AETHER.with(|engine| {
-            engine.borrow_mut().clear_cache();
+        THREAD_LOCAL_AETHER.with(|state| {
+            state.borrow_mut().engine.clear_cache();
         });
     }
 
@@ -154,7 +281,29 @@ impl GlobalEngine {
     ///
     /// 返回缓存命中率、命中次数、未命中次数等信息。
     pub fn cache_stats() -> Option<crate::cache::CacheStats> {
-        THREAD_LOCAL_AETHER.with(|engine| Some(engine.borrow().cache_stats()))
+        THREAD_LOCAL_AETHER.with(|state| Some(state.borrow().engine.cache_stats()))
+    }
+
+    /// 全局引擎的指标收集器（所有线程共享，默认禁用）
+    ///
+    /// 启用后每次执行都会记录耗时、步数、缓存命中和是否成功。
+    ///
+    /// ```rust
+    /// use aether::engine::GlobalEngine;
+    ///
+    /// GlobalEngine::metrics().enable();
+    /// GlobalEngine::eval_isolated("(1 + 2)").unwrap();
+    /// let snapshot = GlobalEngine::metrics_snapshot();
+    /// assert!(snapshot.execution.execution_count >= 1);
+    /// ```
+    pub fn metrics() -> &'static MetricsCollector {
+        GLOBAL_METRICS.get_or_init(MetricsCollector::new)
+    }
+
+    /// 获取指标快照（p50/p95 延迟、错误率等；缓存统计为当前线程的引擎）
+    pub fn metrics_snapshot() -> MetricsSnapshot {
+        THREAD_LOCAL_AETHER
+            .with(|state| super::metrics_snapshot(&state.borrow().engine, Self::metrics()))
     }
 
     /// 配置优化选项
@@ -165,9 +314,10 @@ impl GlobalEngine {
     /// - `dead_code`: 死代码消除
     /// - `tail_recursion`: 尾递归优化
     pub fn set_optimization(constant_folding: bool, dead_code: bool, tail_recursion: bool) {
-        THREAD_LOCAL_AETHER.with(|engine| {
-            engine
+        THREAD_LOCAL_AETHER.with(|state| {
+            state
                 .borrow_mut()
+                .engine
                 .set_optimization(constant_folding, dead_code, tail_recursion);
         });
     }
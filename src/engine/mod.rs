@@ -78,6 +78,41 @@ pub mod global;
 pub mod pool;
 pub mod scoped;
 
-pub use global::GlobalEngine;
-pub use pool::{EnginePool, PooledEngine};
+pub use global::{GlobalEngine, GlobalEngineConfig};
+pub use pool::{EngineFactory, EnginePool, EngineValidator, PooledEngine, RecyclePolicy};
 pub use scoped::ScopedEngine;
+
+use crate::sandbox::{EvalReport, MetricsCollector, MetricsSnapshot};
+use crate::{Aether, Value};
+use std::time::Instant;
+
+/// 执行代码，并在指标收集器启用时记录本次执行的资源报告
+pub(crate) fn eval_with_metrics(
+    engine: &mut Aether,
+    code: &str,
+    metrics: &MetricsCollector,
+) -> Result<Value, String> {
+    if !metrics.is_enabled() {
+        return engine.eval(code);
+    }
+
+    let hits_before = engine.cache_stats().hits;
+    let start = Instant::now();
+    let result = engine.eval(code);
+    metrics.record_eval(EvalReport {
+        duration: start.elapsed(),
+        steps: engine.step_count(),
+        cache_hit: engine.cache_stats().hits > hits_before,
+        success: result.is_ok(),
+    });
+    result
+}
+
+/// 结合引擎当前状态生成指标快照
+pub(crate) fn metrics_snapshot(engine: &Aether, metrics: &MetricsCollector) -> MetricsSnapshot {
+    metrics.snapshot(
+        engine.trace_records().len(),
+        engine.evaluator.module_cache_size(),
+        &engine.cache_stats(),
+    )
+}
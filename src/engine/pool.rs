@@ -5,8 +5,102 @@
 //!
 //! **注意**：由于 Aether 使用 `Rc`（非线程安全），引擎池是线程局部的。
 //! 每个线程有独立的引擎池，线程间不共享。
+//! 批量执行（`eval_all`）会在工作线程中各自创建引擎，只在线程间传递脚本与结果数据。
 
+use crate::sandbox::{MetricsCollector, MetricsSnapshot};
+use crate::value::{SetKey, Table};
 use crate::{Aether, Value};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// 引擎归还时的校验钩子，返回 false 表示需要回收
+pub type EngineValidator = Box<dyn Fn(&Aether) -> bool>;
+
+/// 创建池中引擎的工厂（如按沙箱配置设置权限和执行限制）
+pub type EngineFactory = Arc<dyn Fn() -> Aether + Send + Sync>;
+
+/// 池化引擎的回收策略
+///
+/// 长期运行的池中，引擎会逐渐累积状态（AST 缓存、模块缓存等）。
+/// 满足任一条件的引擎在归还时会被丢弃并替换为新实例：
+///
+/// - 使用次数达到 `max_uses`
+/// - 存活时间超过 `max_age`
+/// - 校验钩子返回 false（如环境变量过多）
+///
+/// # 示例
+///
+/// ```rust
+/// use aether::engine::{EnginePool, RecyclePolicy};
+/// use std::time::Duration;
+///
+/// let policy = RecyclePolicy::new()
+///     .with_max_uses(1000)
+///     .with_max_age(Duration::from_secs(600))
+///     .with_validator(|engine| engine.variables().len() < 10_000);
+/// let pool = EnginePool::with_policy(4, policy);
+/// ```
+#[derive(Default)]
+pub struct RecyclePolicy {
+    /// 最大使用次数（None 表示不限）
+    pub max_uses: Option<usize>,
+    /// 最大存活时间（None 表示不限）
+    pub max_age: Option<Duration>,
+    /// 归还时运行的校验钩子
+    pub validator: Option<EngineValidator>,
+}
+
+impl RecyclePolicy {
+    /// 创建不回收的默认策略
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置最大使用次数
+    pub fn with_max_uses(mut self, uses: usize) -> Self {
+        self.max_uses = Some(uses);
+        self
+    }
+
+    /// 设置最大存活时间
+    pub fn with_max_age(mut self, age: Duration) -> Self {
+        self.max_age = Some(age);
+        self
+    }
+
+    /// 设置归还时的校验钩子
+    pub fn with_validator(mut self, validator: impl Fn(&Aether) -> bool + 'static) -> Self {
+        self.validator = Some(Box::new(validator));
+        self
+    }
+
+    /// 判断引擎是否需要回收
+    fn should_recycle(&self, engine: &Aether, slot: &SlotInfo) -> bool {
+        self.max_uses.is_some_and(|max| slot.uses >= max)
+            || self
+                .max_age
+                .is_some_and(|max| slot.created_at.elapsed() >= max)
+            || self.validator.as_ref().is_some_and(|valid| !valid(engine))
+    }
+}
+
+/// 池中每个引擎的使用记录
+#[derive(Debug, Clone, Copy)]
+struct SlotInfo {
+    uses: usize,
+    created_at: Instant,
+}
+
+impl SlotInfo {
+    fn new() -> Self {
+        Self {
+            uses: 0,
+            created_at: Instant::now(),
+        }
+    }
+}
 
 /// 线程局部引擎池
 ///
@@ -56,6 +150,11 @@ use crate::{Aether, Value};
 pub struct EnginePool {
     engines: Vec<Aether>,
     available: Vec<bool>,
+    slots: Vec<SlotInfo>,
+    policy: RecyclePolicy,
+    factory: EngineFactory,
+    recycled: usize,
+    metrics: Arc<MetricsCollector>,
 }
 
 impl EnginePool {
@@ -74,15 +173,55 @@ impl EnginePool {
     /// let pool = EnginePool::new(4);
     /// ```
     pub fn new(capacity: usize) -> Self {
+        Self::with_policy(capacity, RecyclePolicy::default())
+    }
+
+    /// 创建带回收策略的引擎池
+    ///
+    /// 引擎归还时按策略检查，需要回收的引擎会被替换为新实例。
+    pub fn with_policy(capacity: usize, policy: RecyclePolicy) -> Self {
+        Self::with_factory(capacity, policy, Arc::new(Aether::new))
+    }
+
+    /// 创建使用自定义工厂的引擎池
+    ///
+    /// 预创建、池满时的临时引擎、回收后的替换引擎和 `eval_all` 的工作引擎都由工厂创建。
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use aether::engine::{EnginePool, RecyclePolicy};
+    /// use aether::{Aether, ExecutionLimits};
+    /// use std::sync::Arc;
+    ///
+    /// let factory = Arc::new(|| Aether::new().with_limits(ExecutionLimits::strict()));
+    /// let mut pool = EnginePool::with_factory(4, RecyclePolicy::new(), factory);
+    /// assert!(pool.acquire().eval("While (True) { Set X 1 }").is_err());
+    /// ```
+    pub fn with_factory(capacity: usize, policy: RecyclePolicy, factory: EngineFactory) -> Self {
         let mut engines = Vec::with_capacity(capacity);
         let available = vec![true; capacity];
 
         // 预创建引擎实例
         for _ in 0..capacity {
-            engines.push(Aether::new());
+            engines.push(factory());
         }
 
-        Self { engines, available }
+        Self {
+            engines,
+            available,
+            slots: vec![SlotInfo::new(); capacity],
+            policy,
+            factory,
+            recycled: 0,
+            metrics: Arc::new(MetricsCollector::new()),
+        }
+    }
+
+    /// 使用指定的指标收集器（多个池可以共享同一个收集器汇总指标）
+    pub fn with_metrics(mut self, metrics: Arc<MetricsCollector>) -> Self {
+        self.metrics = metrics;
+        self
     }
 
     /// 从池中获取引擎（自动归还）
@@ -107,25 +246,67 @@ impl EnginePool {
                     engine: Some(engine),
                     pool_index: Some(i),
                     pool: self as *mut Self,
+                    metrics: Arc::clone(&self.metrics),
                 };
             }
         }
 
         // 池中无可用引擎，创建临时引擎
-        let engine = Aether::new();
+        let engine = (self.factory)();
         PooledEngine {
             engine: Some(engine),
             pool_index: None,
             pool: std::ptr::null_mut(),
+            metrics: Arc::clone(&self.metrics),
         }
     }
 
-    /// 归还引擎到池中
+    /// 归还引擎到池中（按回收策略决定是否替换为新实例）
     fn return_engine(&mut self, index: usize, engine: Aether) {
-        self.engines[index] = engine;
+        self.slots[index].uses += 1;
+        if self.policy.should_recycle(&engine, &self.slots[index]) {
+            drop(engine);
+            self.engines[index] = (self.factory)();
+            self.slots[index] = SlotInfo::new();
+            self.recycled += 1;
+        } else {
+            self.engines[index] = engine;
+        }
         self.available[index] = true;
     }
 
+    /// 池的指标收集器（默认禁用）
+    ///
+    /// 启用后池中引擎（包括 `eval_all`）的每次执行都会记录耗时、步数、
+    /// 缓存命中和是否成功。
+    pub fn metrics(&self) -> &MetricsCollector {
+        &self.metrics
+    }
+
+    /// 获取指标快照（p50/p95 延迟、错误率等），缓存统计为池中所有引擎之和
+    pub fn metrics_snapshot(&self) -> MetricsSnapshot {
+        let mut ast_cache = crate::cache::CacheStats::default();
+        for engine in &self.engines {
+            let stats = engine.cache_stats();
+            ast_cache.size += stats.size;
+            ast_cache.max_size += stats.max_size;
+            ast_cache.hits += stats.hits;
+            ast_cache.misses += stats.misses;
+            ast_cache.statements += stats.statements;
+            ast_cache.unique_statements += stats.unique_statements;
+        }
+        let lookups = ast_cache.hits + ast_cache.misses;
+        if lookups > 0 {
+            ast_cache.hit_rate = ast_cache.hits as f64 / lookups as f64;
+        }
+        self.metrics.snapshot(0, 0, &ast_cache)
+    }
+
+    /// 获取已回收（替换为新实例）的引擎次数
+    pub fn recycled(&self) -> usize {
+        self.recycled
+    }
+
     /// 获取池的容量
     pub fn capacity(&self) -> usize {
         self.engines.len()
@@ -137,6 +318,158 @@ impl EnginePool {
     }
 }
 
+/// 可以跨线程传递的执行结果
+///
+/// `Value` 中的函数、生成器等持有 `Rc` 环境，不能离开创建它们的线程，
+/// 因此批量执行的结果只保留纯数据部分。
+enum SendValue {
+    Number(f64),
+    Int(i64),
+    Fraction(num_rational::Ratio<num_bigint::BigInt>),
+    String(String),
+    Boolean(bool),
+    Null,
+    Array(Vec<SendValue>),
+    Dict(Vec<(String, SendValue)>),
+    Set(HashSet<SetKey>),
+    Table(Vec<String>, Vec<Vec<SendValue>>),
+    DateTime(chrono::DateTime<chrono::FixedOffset>),
+    BuiltIn { name: String, arity: usize },
+}
+
+impl SendValue {
+    fn from_value(value: Value) -> Result<Self, String> {
+        Ok(match value {
+            Value::Number(n) => SendValue::Number(n),
+            Value::Int(i) => SendValue::Int(i),
+            Value::Fraction(f) => SendValue::Fraction(f),
+            Value::String(s) => SendValue::String(s),
+            Value::Boolean(b) => SendValue::Boolean(b),
+            Value::Null => SendValue::Null,
+            Value::Array(items) => SendValue::Array(
+                items
+                    .into_iter()
+                    .map(SendValue::from_value)
+                    .collect::<Result<_, _>>()?,
+            ),
+            Value::Dict(map) => SendValue::Dict(
+                map.into_iter()
+                    .map(|(k, v)| Ok((k, SendValue::from_value(v)?)))
+                    .collect::<Result<_, String>>()?,
+            ),
+            Value::Set(set) => SendValue::Set(set),
+            Value::DateTime(dt) => SendValue::DateTime(dt),
+            Value::Table(table) => {
+                let (columns, data) = table.into_parts();
+                let data = data
+                    .into_iter()
+                    .map(|col| col.into_iter().map(SendValue::from_value).collect())
+                    .collect::<Result<_, _>>()?;
+                SendValue::Table(columns, data)
+            }
+            Value::BuiltIn { name, arity } => SendValue::BuiltIn { name, arity },
+            other => {
+                return Err(format!(
+                    "Cannot return a {} from a batch evaluation",
+                    other.type_name()
+                ));
+            }
+        })
+    }
+
+    fn into_value(self) -> Value {
+        match self {
+            SendValue::Number(n) => Value::Number(n),
+            SendValue::Int(i) => Value::Int(i),
+            SendValue::Fraction(f) => Value::Fraction(f),
+            SendValue::String(s) => Value::String(s),
+            SendValue::Boolean(b) => Value::Boolean(b),
+            SendValue::Null => Value::Null,
+            SendValue::Array(items) => {
+                Value::Array(items.into_iter().map(SendValue::into_value).collect())
+            }
+            SendValue::Dict(entries) => Value::Dict(
+                entries
+                    .into_iter()
+                    .map(|(k, v)| (k, v.into_value()))
+                    .collect::<HashMap<_, _>>(),
+            ),
+            SendValue::Set(set) => Value::Set(set),
+            SendValue::DateTime(dt) => Value::DateTime(dt),
+            SendValue::Table(columns, data) => {
+                let data = data
+                    .into_iter()
+                    .map(|col| col.into_iter().map(SendValue::into_value).collect())
+                    .collect();
+                // 单元格来自合法的表格，重建不会失败
+                Value::Table(Table::new(columns, data).unwrap_or_default())
+            }
+            SendValue::BuiltIn { name, arity } => Value::BuiltIn { name, arity },
+        }
+    }
+}
+
+impl EnginePool {
+    /// 并发执行多个脚本，结果顺序与输入一致
+    ///
+    /// 启动最多 `capacity()` 个工作线程，每个线程创建自己的引擎，
+    /// 空闲的线程从共享队列中领取下一个脚本，长短脚本混合时负载依然均衡。
+    /// 每个脚本在干净的环境中执行，适合批量规则求值。
+    ///
+    /// 结果只能是数据值：脚本返回函数、生成器或惰性值时，对应结果为错误。
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use aether::engine::EnginePool;
+    /// use aether::Value;
+    ///
+    /// let pool = EnginePool::new(4);
+    /// let results = pool.eval_all(vec!["(1 + 1)", "(2 * 3)", "UNDEFINED"]);
+    /// assert_eq!(results[0], Ok(Value::Number(2.0)));
+    /// assert_eq!(results[1], Ok(Value::Number(6.0)));
+    /// assert!(results[2].is_err());
+    /// ```
+    pub fn eval_all(&self, scripts: Vec<&str>) -> Vec<Result<Value, String>> {
+        let workers = self.capacity().clamp(1, scripts.len().max(1));
+        let next = AtomicUsize::new(0);
+        let metrics: &MetricsCollector = &self.metrics;
+        let factory: &(dyn Fn() -> Aether + Send + Sync) = &*self.factory;
+        let results: Mutex<Vec<Option<Result<SendValue, String>>>> =
+            Mutex::new((0..scripts.len()).map(|_| None).collect());
+
+        std::thread::scope(|scope| {
+            for _ in 0..workers {
+                scope.spawn(|| {
+                    let mut engine = factory();
+                    loop {
+                        let index = next.fetch_add(1, Ordering::SeqCst);
+                        let Some(code) = scripts.get(index) else {
+                            break;
+                        };
+                        engine.evaluator.reset_env();
+                        let result = super::eval_with_metrics(&mut engine, code, metrics)
+                            .and_then(SendValue::from_value);
+                        if let Ok(mut results) = results.lock() {
+                            results[index] = Some(result);
+                        }
+                    }
+                });
+            }
+        });
+
+        results
+            .into_inner()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|result| match result {
+                Some(result) => result.map(SendValue::into_value),
+                None => Err("Batch worker terminated unexpectedly".to_string()),
+            })
+            .collect()
+    }
+}
+
 /// 自动归还的引擎（RAII模式）
 ///
 /// 当此对象离开作用域时，引擎会自动归还到池中。
@@ -144,6 +477,7 @@ pub struct PooledEngine {
     engine: Option<Aether>,
     pool_index: Option<usize>,
     pool: *mut EnginePool,
+    metrics: Arc<MetricsCollector>,
 }
 
 impl PooledEngine {
@@ -158,7 +492,27 @@ impl PooledEngine {
     /// - `Ok(Value)`: 执行结果
     /// - `Err(String)`: 错误信息
     pub fn eval(&mut self, code: &str) -> Result<Value, String> {
-        self.engine.as_mut().unwrap().eval(code)
+        super::eval_with_metrics(self.engine.as_mut().unwrap(), code, &self.metrics)
+    }
+
+    /// 设置全局变量（如请求输入），下次获取引擎时随环境一起清空
+    pub fn set_global(&mut self, name: &str, value: Value) {
+        self.engine.as_mut().unwrap().set_global(name, value);
+    }
+
+    /// 调用当前环境中定义的函数
+    pub fn call(&mut self, name: &str, args: Vec<Value>) -> Result<Value, String> {
+        self.engine.as_mut().unwrap().call(name, args)
+    }
+
+    /// 获取本次执行记录的 TRACE 事件
+    pub fn trace_records(&self) -> Vec<crate::runtime::TraceEntry> {
+        self.engine.as_ref().unwrap().trace_records()
+    }
+
+    /// 获取当前引擎的指标快照
+    pub fn metrics_snapshot(&self) -> MetricsSnapshot {
+        super::metrics_snapshot(self.engine.as_ref().unwrap(), &self.metrics)
     }
 
     /// 获取AST缓存统计信息
@@ -258,6 +612,99 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_pool_recycles_by_max_uses() {
+        let mut pool = EnginePool::with_policy(1, RecyclePolicy::new().with_max_uses(3));
+
+        for _ in 0..7 {
+            let mut engine = pool.acquire();
+            engine.eval("(1 + 1)").unwrap();
+        }
+        assert_eq!(pool.recycled(), 2);
+        assert_eq!(pool.available(), 1);
+    }
+
+    #[test]
+    fn test_pool_validator_runs_on_release() {
+        let policy = RecyclePolicy::new().with_validator(|engine| engine.variables().len() < 3);
+        let mut pool = EnginePool::with_policy(1, policy);
+
+        {
+            let mut engine = pool.acquire();
+            engine.eval("Set A 1\nSet B 2").unwrap();
+        }
+        assert_eq!(pool.recycled(), 0);
+
+        {
+            let mut engine = pool.acquire();
+            engine.eval("Set A 1\nSet B 2\nSet C 3").unwrap();
+        }
+        assert_eq!(pool.recycled(), 1);
+
+        let mut engine = pool.acquire();
+        assert!(engine.eval("A").is_err());
+    }
+
+    #[test]
+    fn test_pool_factory_configures_every_engine() {
+        let factory: EngineFactory =
+            Arc::new(|| Aether::new().with_limits(crate::ExecutionLimits::strict()));
+        let mut pool = EnginePool::with_factory(1, RecyclePolicy::new().with_max_uses(1), factory);
+
+        for _ in 0..3 {
+            let mut engine = pool.acquire();
+            assert!(engine.eval("While (True) { Set X 1 }").is_err());
+        }
+        assert_eq!(pool.recycled(), 3);
+
+        let results = pool.eval_all(vec!["While (True) { Set X 1 }"]);
+        assert!(results[0].is_err());
+    }
+
+    #[test]
+    fn test_pool_eval_all_preserves_order() {
+        let pool = EnginePool::new(3);
+        let scripts: Vec<String> = (0..20).map(|i| format!("Set X {}\n(X * 2)", i)).collect();
+        let results = pool.eval_all(scripts.iter().map(|s| s.as_str()).collect());
+
+        assert_eq!(results.len(), 20);
+        for (i, result) in results.iter().enumerate() {
+            assert_eq!(result, &Ok(Value::Number(i as f64 * 2.0)));
+        }
+
+        let results = pool.eval_all(vec![
+            "[1, {\"a\": TO_FRACTION(0.5)}]",
+            "Func F() { Return 1 }\nF",
+        ]);
+        assert_eq!(results[0].as_ref().unwrap().to_string(), "[1, {a: 1/2}]");
+        assert!(results[1].is_err());
+        assert!(pool.eval_all(vec![]).is_empty());
+    }
+
+    #[test]
+    fn test_pool_records_metrics() {
+        let mut pool = EnginePool::new(2);
+        pool.metrics().enable();
+
+        for _ in 0..3 {
+            let mut engine = pool.acquire();
+            engine.eval("(1 + 1)").unwrap();
+        }
+        {
+            let mut engine = pool.acquire();
+            assert!(engine.eval("UNDEFINED_NAME").is_err());
+        }
+        pool.eval_all(vec!["(2 + 2)"]);
+
+        let exec = pool.metrics_snapshot().execution;
+        assert_eq!(exec.execution_count, 5);
+        assert_eq!(exec.error_count, 1);
+        assert!((exec.error_rate - 0.2).abs() < 1e-9);
+        assert!(exec.cache_hits >= 1);
+        assert!(exec.total_steps > 0);
+        assert!(exec.p95_duration >= exec.p50_duration);
+    }
+
     #[test]
     fn test_pool_auto_return() {
         let mut pool = EnginePool::new(2);
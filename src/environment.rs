@@ -4,7 +4,7 @@
 
 use crate::value::Value;
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::rc::Rc;
 
 /// 环境池,用于复用环境对象
@@ -39,6 +39,7 @@ impl EnvironmentPool {
         if self.pool.len() < self.max_size {
             env.clear();
             env.parent = None;
+            env.library = false;
             self.pool.push(env);
         }
     }
@@ -55,6 +56,17 @@ impl Default for EnvironmentPool {
     }
 }
 
+/// Snapshot of a single variable, used for introspection (`VARS()`, `engine.variables()`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct VariableInfo {
+    /// Variable name
+    pub name: String,
+    /// Type name as reported by `TYPE()`
+    pub type_name: String,
+    /// Current value
+    pub value: Value,
+}
+
 /// Environment for storing variables
 #[derive(Debug, Clone)]
 pub struct Environment {
@@ -63,6 +75,10 @@ pub struct Environment {
 
     /// Parent environment (for nested scopes)
     parent: Option<Rc<RefCell<Environment>>>,
+
+    /// Whether this scope marks library code: functions defined by the standard library
+    /// capture such a scope, so code running in them can be told apart from the script's own
+    library: bool,
 }
 
 impl Environment {
@@ -71,6 +87,7 @@ impl Environment {
         Environment {
             store: HashMap::with_capacity(16), // 预分配容量减少rehash
             parent: None,
+            library: false,
         }
     }
 
@@ -79,14 +96,43 @@ impl Environment {
         Environment {
             store: HashMap::with_capacity(8), // 子环境通常变量较少
             parent: Some(parent),
+            library: false,
+        }
+    }
+
+    /// Create an empty library scope in front of `parent`; lookups pass through to the parent
+    pub fn library(parent: Rc<RefCell<Environment>>) -> Self {
+        Environment {
+            store: HashMap::new(),
+            parent: Some(parent),
+            library: true,
         }
     }
 
+    /// Whether this scope or one of its parents is a library scope
+    pub fn is_library(&self) -> bool {
+        self.library
+            || self
+                .parent
+                .as_ref()
+                .is_some_and(|p| p.borrow().is_library())
+    }
+
     /// Set a variable in the current scope
     pub fn set(&mut self, name: String, value: Value) {
         self.store.insert(name, value);
     }
 
+    /// Set a variable in the current scope, reusing the key when the name is already bound
+    pub fn set_by_ref(&mut self, name: &str, value: Value) {
+        match self.store.get_mut(name) {
+            Some(slot) => *slot = value,
+            None => {
+                self.store.insert(name.to_string(), value);
+            }
+        }
+    }
+
     /// Get a variable from this scope or parent scopes (优化路径)
     pub fn get(&self, name: &str) -> Option<Value> {
         // 快速路径: 直接在当前作用域查找
@@ -104,6 +150,11 @@ impl Environment {
         self.parent.as_ref()?.borrow().get(name)
     }
 
+    /// Remove a variable from the current scope only
+    pub fn remove(&mut self, name: &str) -> Option<Value> {
+        self.store.remove(name)
+    }
+
     /// Check if a variable exists in this scope or parent scopes
     pub fn has(&self, name: &str) -> bool {
         self.store.contains_key(name) || self.parent.as_ref().is_some_and(|p| p.borrow().has(name))
@@ -129,6 +180,83 @@ impl Environment {
         self.store.keys().cloned().collect()
     }
 
+    /// Get the parent scope, if any
+    pub fn parent(&self) -> Option<Rc<RefCell<Environment>>> {
+        self.parent.clone()
+    }
+
+    /// List user-defined variables in this scope only (built-in function bindings are skipped),
+    /// sorted by name
+    pub fn variables(&self) -> Vec<VariableInfo> {
+        let mut vars: Vec<VariableInfo> = self
+            .store
+            .iter()
+            .filter(|(name, value)| !Self::is_builtin_binding(name, value))
+            .map(|(name, value)| VariableInfo {
+                name: name.clone(),
+                type_name: value.type_name().to_string(),
+                value: value.clone(),
+            })
+            .collect();
+        vars.sort_by(|a, b| a.name.cmp(&b.name));
+        vars
+    }
+
+    /// List user-defined variables visible from this scope (inner scopes shadow outer ones),
+    /// sorted by name
+    pub fn visible_variables(&self) -> Vec<VariableInfo> {
+        let mut vars = self.variables();
+        if let Some(parent) = &self.parent {
+            for var in parent.borrow().visible_variables() {
+                if !self.store.contains_key(&var.name) {
+                    vars.push(var);
+                }
+            }
+            vars.sort_by(|a, b| a.name.cmp(&b.name));
+        }
+        vars
+    }
+
+    /// Data values visible from this scope (inner scopes shadow outer ones), keyed by name
+    ///
+    /// Functions, generators, lazy values and built-ins are skipped; used by the
+    /// debugger's execution recording to compare scopes between statements.
+    pub fn visible_data(&self) -> BTreeMap<String, Value> {
+        let mut data = match &self.parent {
+            Some(parent) => parent.borrow().visible_data(),
+            None => BTreeMap::new(),
+        };
+        for (name, value) in &self.store {
+            match value {
+                Value::Function { .. }
+                | Value::Generator { .. }
+                | Value::Lazy { .. }
+                | Value::BuiltIn { .. } => {
+                    data.remove(name);
+                }
+                _ => {
+                    data.insert(name.clone(), value.clone());
+                }
+            }
+        }
+        data
+    }
+
+    /// Visit the user bindings of this scope only (no parents, built-in bindings skipped),
+    /// without cloning their values
+    pub fn for_each_binding(&self, mut f: impl FnMut(&str, &Value)) {
+        for (name, value) in &self.store {
+            if !Self::is_builtin_binding(name, value) {
+                f(name, value);
+            }
+        }
+    }
+
+    /// Whether a binding is the engine-registered built-in of the same name
+    fn is_builtin_binding(name: &str, value: &Value) -> bool {
+        matches!(value, Value::BuiltIn { name: builtin, .. } if builtin == name)
+    }
+
     /// Clear all variables in this scope (not parent scopes)
     pub fn clear(&mut self) {
         self.store.clear();
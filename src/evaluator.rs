@@ -1,11 +1,12 @@
 // src/evaluator.rs
 //! Evaluator for executing Aether AST
 
-use crate::ast::{BinOp, Expr, Program, Stmt, UnaryOp};
+use crate::ast::{BinOp, Expr, Program, SourceSpan, Stmt, UnaryOp};
 use crate::builtins::BuiltInRegistry;
-use crate::environment::Environment;
+use crate::environment::{Environment, VariableInfo};
 use crate::module_system::{
     DisabledModuleResolver, ModuleContext, ModuleResolveError, ModuleResolver, ResolvedModule,
+    STDLIB_SPECIFIER_PREFIX,
 };
 use crate::value::{GeneratorState, Value};
 use serde_json::{Value as JsonValue, json};
@@ -13,11 +14,24 @@ use std::cell::RefCell;
 use std::collections::HashMap;
 use std::collections::VecDeque;
 use std::rc::Rc;
+use std::sync::Arc;
 
+/// A function call in progress
 #[derive(Debug, Clone, PartialEq)]
 pub struct CallFrame {
     pub name: String,
     pub signature: String,
+    /// 1-based line of the statement that made the call, if known
+    pub line: Option<usize>,
+}
+
+impl std::fmt::Display for CallFrame {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "{} called at line {}", self.signature, line),
+            None => write!(f, "{}", self.signature),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -169,6 +183,14 @@ pub enum RuntimeError {
     /// Wrong number of arguments
     WrongArity { expected: usize, got: usize },
 
+    /// Wrong number of arguments, with the callee and its signature
+    WrongArityDetailed {
+        callee: String,
+        signature: String,
+        expected: usize,
+        got: usize,
+    },
+
     /// Return statement (used for control flow)
     Return(Value),
 
@@ -193,6 +215,12 @@ pub enum RuntimeError {
         call_stack: Vec<CallFrame>,
     },
 
+    /// Attach the source position of the statement that raised an error.
+    Located {
+        error: Box<RuntimeError>,
+        span: SourceSpan,
+    },
+
     /// Execution limit exceeded
     ExecutionLimit(crate::runtime::ExecutionLimitError),
 
@@ -221,6 +249,18 @@ impl std::fmt::Display for RuntimeError {
                     expected, got
                 )
             }
+            RuntimeError::WrongArityDetailed {
+                signature,
+                expected,
+                got,
+                ..
+            } => {
+                write!(
+                    f,
+                    "Wrong number of arguments for {}: expected {}, got {}",
+                    signature, expected, got
+                )
+            }
             RuntimeError::Return(val) => write!(f, "Return: {}", val),
             RuntimeError::Yield(val) => write!(f, "Yield: {}", val),
             RuntimeError::Break => write!(f, "Break outside of loop"),
@@ -274,15 +314,22 @@ impl std::fmt::Display for RuntimeError {
             RuntimeError::WithCallStack { error, call_stack } => {
                 write!(f, "{}", error)?;
                 if !call_stack.is_empty() {
-                    let frames = call_stack
-                        .iter()
-                        .map(|fr| fr.signature.as_str())
-                        .collect::<Vec<_>>()
-                        .join(" -> ");
-                    write!(f, "\nCall stack: {}", frames)?;
+                    // Outermost call first, numbered like the debugger's backtrace
+                    write!(f, "\nCall stack:")?;
+                    for (i, frame) in call_stack.iter().enumerate() {
+                        write!(f, "\n  #{} {}", i, frame)?;
+                    }
                 }
                 Ok(())
             }
+            RuntimeError::Located { error, span } => {
+                // The position belongs to the first line (multi-line errors add context below)
+                let message = error.to_string();
+                match message.split_once('\n') {
+                    Some((first, rest)) => write!(f, "{} at {}\n{}", first, span, rest),
+                    None => write!(f, "{} at {}", message, span),
+                }
+            }
             RuntimeError::CustomError(msg) => write!(f, "{}", msg),
             RuntimeError::ExecutionLimit(e) => write!(f, "{}", e),
             RuntimeError::DebugPause => write!(f, "Debugger pause"),
@@ -305,6 +352,9 @@ pub struct ErrorReport {
     pub message: String,
     pub import_chain: Vec<String>,
     pub call_stack: Vec<CallFrame>,
+    /// 1-based source line and column where the error was raised, if known
+    pub line: Option<usize>,
+    pub column: Option<usize>,
 }
 
 impl ErrorReport {
@@ -315,6 +365,8 @@ impl ErrorReport {
             message: message.into(),
             import_chain: Vec::new(),
             call_stack: Vec::new(),
+            line: None,
+            column: None,
         }
     }
 
@@ -325,14 +377,23 @@ impl ErrorReport {
             message: message.into(),
             import_chain: Vec::new(),
             call_stack: Vec::new(),
+            line: None,
+            column: None,
         }
     }
 
+    /// Attach a source position (line, column)
+    pub fn with_position(mut self, position: Option<(usize, usize)>) -> Self {
+        self.line = position.map(|(line, _)| line);
+        self.column = position.map(|(_, column)| column);
+        self
+    }
+
     pub fn to_json_value(&self) -> JsonValue {
         let call_stack = self
             .call_stack
             .iter()
-            .map(|fr| json!({"name": fr.name, "signature": fr.signature}))
+            .map(|fr| json!({"name": fr.name, "signature": fr.signature, "line": fr.line}))
             .collect::<Vec<_>>();
 
         json!({
@@ -341,6 +402,8 @@ impl ErrorReport {
             "message": self.message,
             "import_chain": self.import_chain,
             "call_stack": call_stack,
+            "line": self.line,
+            "column": self.column,
         })
     }
 
@@ -356,16 +419,61 @@ impl RuntimeError {
         let mut current = self;
         let mut frames: Vec<CallFrame> = Vec::new();
 
-        while let RuntimeError::WithCallStack { error, call_stack } = current {
-            if frames.is_empty() {
-                frames = call_stack.clone();
+        loop {
+            match current {
+                RuntimeError::WithCallStack { error, call_stack } => {
+                    if frames.is_empty() {
+                        frames = call_stack.clone();
+                    }
+                    current = error.as_ref();
+                }
+                RuntimeError::Located { error, .. } => current = error.as_ref(),
+                _ => break,
             }
-            current = error.as_ref();
         }
 
         (current, frames)
     }
 
+    /// Calls that were in progress when the error was raised, outermost first
+    ///
+    /// Empty for errors raised outside any function call.
+    pub fn with_trace(&self) -> &[CallFrame] {
+        match self {
+            RuntimeError::WithCallStack { call_stack, .. } => call_stack,
+            RuntimeError::Located { error, .. } => error.with_trace(),
+            _ => &[],
+        }
+    }
+
+    /// Source position of the statement that raised the error, if known
+    pub fn location(&self) -> Option<SourceSpan> {
+        match self {
+            RuntimeError::WithCallStack { error, .. } => error.location(),
+            RuntimeError::Located { error, span } => error.location().or(Some(*span)),
+            _ => None,
+        }
+    }
+
+    /// Record that the error was raised by the statement at `span`
+    ///
+    /// The innermost statement wins: errors that already carry a position, and control
+    /// flow signals, are returned unchanged.
+    pub(crate) fn located(self, span: SourceSpan) -> RuntimeError {
+        match self {
+            RuntimeError::WithCallStack { error, call_stack } => RuntimeError::WithCallStack {
+                error: Box::new(error.located(span)),
+                call_stack,
+            },
+            RuntimeError::Located { .. } => self,
+            error if Evaluator::is_control_flow_error(&error) => error,
+            error => RuntimeError::Located {
+                error: Box::new(error),
+                span,
+            },
+        }
+    }
+
     fn kind_name(&self) -> String {
         match self {
             RuntimeError::UndefinedVariable(_) => "UndefinedVariable",
@@ -373,7 +481,9 @@ impl RuntimeError {
             RuntimeError::InvalidOperation(_) => "InvalidOperation",
             RuntimeError::DivisionByZero => "DivisionByZero",
             RuntimeError::NotCallable(_) => "NotCallable",
-            RuntimeError::WrongArity { .. } => "WrongArity",
+            RuntimeError::WrongArity { .. } | RuntimeError::WrongArityDetailed { .. } => {
+                "WrongArity"
+            }
             RuntimeError::Return(_) => "Return",
             RuntimeError::Yield(_) => "Yield",
             RuntimeError::Break => "Break",
@@ -391,6 +501,7 @@ impl RuntimeError {
                 ImportErrorKind::ParseFailed => "ParseFailed",
             },
             RuntimeError::WithCallStack { .. } => "WithCallStack",
+            RuntimeError::Located { error, .. } => return error.kind_name(),
             RuntimeError::ExecutionLimit(_) => "ExecutionLimit",
             RuntimeError::CustomError(_) => "CustomError",
             RuntimeError::DebugPause => "DebugPause",
@@ -400,7 +511,9 @@ impl RuntimeError {
 
     fn base_message(&self) -> String {
         match self {
-            RuntimeError::WithCallStack { error, .. } => error.base_message(),
+            RuntimeError::WithCallStack { error, .. } | RuntimeError::Located { error, .. } => {
+                error.base_message()
+            }
             RuntimeError::ImportError(e) => {
                 let msg = match e.kind {
                     ImportErrorKind::ImportDisabled => "Import is disabled".to_string(),
@@ -460,14 +573,19 @@ impl RuntimeError {
             message: base.base_message(),
             import_chain,
             call_stack,
+            line: None,
+            column: None,
         }
+        .with_position(self.location().map(|span| (span.line, span.column)))
     }
 }
 
 /// Evaluator for Aether programs
 pub struct Evaluator {
-    /// Global environment
+    /// Current environment (the global scope outside of function calls)
     env: Rc<RefCell<Environment>>,
+    /// This evaluator's global scope (a child of the shared scope for forked evaluators)
+    globals: Rc<RefCell<Environment>>,
     /// Built-in function registry
     registry: BuiltInRegistry,
     /// In-memory trace buffer (for DSL-safe debugging; no stdout/files/network)
@@ -480,11 +598,13 @@ pub struct Evaluator {
     trace_buffer_size: usize,
 
     /// Module resolver (Import/Export). Defaults to disabled for DSL safety.
-    module_resolver: Box<dyn ModuleResolver>,
+    module_resolver: Rc<dyn ModuleResolver>,
     /// Module export cache: module_id -> exports
     module_cache: HashMap<String, HashMap<String, Value>>,
     /// Module load stack for cycle detection
     module_stack: Vec<String>,
+    /// Whether standard library source is being loaded into the global environment
+    loading_library: bool,
     /// Current module export table stack (only when evaluating an imported module)
     export_stack: Vec<HashMap<String, Value>>,
     /// Optional base directory context for resolving relative imports (e.g. eval_file)
@@ -499,27 +619,118 @@ pub struct Evaluator {
     current_source_file: Option<String>,
     /// Current line number being executed (for debugger)
     current_line: std::cell::Cell<usize>,
+    /// Per-statement variable deltas for time-travel debugging (see `start_recording`)
+    recording: Option<crate::debugger::Recorder>,
+    /// Recorded IO results: captured while recording, substituted while replaying
+    io_tape: Option<crate::runtime::replay::IoTape>,
     /// Step counter (for step limit enforcement)
     step_counter: std::cell::Cell<usize>,
     /// Call stack depth counter (for recursion depth limit enforcement)
     call_stack_depth: std::cell::Cell<usize>,
+    /// Loops whose body is running in the current function or program
+    ///
+    /// `Break` and `Continue` are only valid while this is non-zero.
+    loop_depth: usize,
     /// Execution start time (for timeout enforcement)
     start_time: std::cell::Cell<Option<std::time::Instant>>,
+    /// Global names that scripts may read but not redefine (see `seal_globals`)
+    sealed_names: std::collections::HashSet<String>,
+    /// How values are stringified during evaluation (PRINT, TO_STRING)
+    display_options: crate::runtime::DisplayOptions,
+    /// Resolve builtins regardless of case (`sum` -> `SUM`) when a name is undefined
+    case_insensitive_builtins: bool,
+    /// Alternative names for builtins (`alias` -> canonical name)
+    builtin_aliases: HashMap<String, String>,
+    /// Lint messages for names that only resolved through aliases/case folding
+    builtin_name_warnings: Vec<String>,
+    /// Experimental feature flags enabled by the host (see `enable_experimental`)
+    enabled_features: std::collections::HashSet<String>,
+    /// Warnings recorded the first time a deprecated builtin is called
+    deprecation_warnings: Vec<String>,
+    /// Jobs registered with SCHEDULE (run by RUN_SCHEDULER)
+    scheduled_jobs: Vec<crate::builtins::schedule::ScheduledJob>,
+    /// Id for the next SCHEDULE job
+    next_job_id: usize,
+    /// Whether RUN_SCHEDULER has been called (the CLI only drives pending schedules)
+    scheduler_started: bool,
+    /// Timers registered with SET_TIMEOUT / SET_INTERVAL / DEBOUNCE and by the host (run by pump)
+    timers: crate::runtime::timers::TimerQueue,
+    /// Deadlines of active WITH_TIMEOUT calls (deadline, limit in ms), innermost last
+    call_deadlines: Vec<(std::time::Instant, u64)>,
+    /// Elastic worker pool for SPAWN (created on first use)
+    task_pool: Option<crate::builtins::parallel::WorkerPool>,
+    /// Results of spawned tasks not yet collected by AWAIT_ALL
+    pending_tasks: HashMap<usize, std::sync::mpsc::Receiver<crate::builtins::parallel::TaskResult>>,
+    /// Next SPAWN task id
+    next_task_id: usize,
+    /// Limits on SPAWN/PARALLEL tasks
+    concurrency_limits: crate::runtime::ConcurrencyLimits,
+    /// Task slots and time used, shared with worker engines
+    task_budget: Arc<crate::builtins::parallel::TaskBudget>,
+    /// Channels created by CHANNEL, shared with worker engines and closed when this engine drops
+    channels: crate::builtins::channel::Channels,
+    /// Host handlers for `@TAG { ... }` extension blocks, keyed by tag
+    extension_handlers: HashMap<String, crate::runtime::ExtensionHandler>,
+    /// Host callback resolving `SECRET("name")`
+    secrets_provider: Option<crate::runtime::SecretsProvider>,
+    /// Secret values registered by SECRET / MARK_SECRET, masked in output
+    secret_values: crate::runtime::secrets::SecretList,
+    /// Payroll rounding and holiday calendar set by scripts; private to this engine
+    #[cfg(feature = "payroll")]
+    payroll_settings: crate::builtins::payroll::settings::SharedPayrollSettings,
+    /// Message catalogs and current locale (LOAD_MESSAGES / SET_LOCALE)
+    i18n_settings: crate::builtins::i18n::SharedI18nSettings,
+    /// Key-value stores opened with KV_OPEN
+    kv_stores: crate::builtins::kv::SharedKvStores,
+    /// Streaming export writers opened with CSV_WRITER_OPEN / XLSX_WRITER_OPEN
+    export_writers: crate::builtins::export::SharedExportWriters,
+    /// WebSocket / SSE connections opened with WS_CONNECT / SSE_CONNECT
+    #[cfg(feature = "async")]
+    stream_connections: crate::builtins::websocket::SharedStreamConnections,
+    /// Snapshot directory configured by the host for SNAPSHOT_MATCH (shared with forks)
+    snapshots: Option<Rc<RefCell<crate::runtime::snapshot::SnapshotState>>>,
+    /// Keyword dialect used when parsing scripts and imported modules
+    dialect: Option<Arc<crate::dialect::Dialect>>,
+    /// Strict mode: non-Boolean conditions and shadowing builtins are errors
+    strict: bool,
+    /// Non-fatal issues reported by the parser, optimizer and evaluator (see `take_warnings`)
+    warnings: Vec<crate::runtime::Warning>,
+    /// Whether calls to named user functions are counted (profile-guided optimization)
+    profile_calls: bool,
+    /// Call counts per user function name, collected while `profile_calls` is on
+    function_calls: HashMap<String, usize>,
+    /// Deterministic mode: seeded RANDOM, injected clock, nondeterministic builtins rejected
+    deterministic: Option<crate::runtime::DeterministicConfig>,
+    /// Generator behind RANDOM() (reseeded per run in deterministic mode)
+    rng: crate::runtime::deterministic::SplitMix64,
+    /// Default language version for scripts without a `#language` pragma
+    language_version: crate::runtime::LanguageVersion,
+    /// Version requested by the running program's `#language` pragma
+    script_language: Option<crate::runtime::LanguageVersion>,
+    /// How top-level programs are executed (tree walking or bytecode VM)
+    backend: crate::compile::Backend,
 }
 
 impl Evaluator {
     /// Default maximum number of trace entries to keep in buffer
     const DEFAULT_TRACE_BUFFER_SIZE: usize = 1024;
 
+    /// Steps between two estimates of the total memory held by variables
+    ///
+    /// Walking every reachable value is expensive, so `max_memory_bytes` is checked
+    /// against the whole environment periodically; single values are checked as they
+    /// are produced (see `check_memory`).
+    const MEMORY_CHECK_INTERVAL: usize = 256;
+
     fn register_builtins_into_env(registry: &BuiltInRegistry, env: &mut Environment) {
         for name in registry.names() {
-            let arity = registry.get(&name).map(|(_, a)| a).unwrap_or(0);
+            let arity = registry.arity(&name).unwrap_or(0);
             env.set(name.clone(), Value::BuiltIn { name, arity });
         }
     }
 
     /// Check and increment step counter
-    fn eval_step(&self) -> Result<(), RuntimeError> {
+    pub(crate) fn eval_step(&self) -> Result<(), RuntimeError> {
         let steps = self.step_counter.get();
 
         if let Some(limit) = self.limits.max_steps
@@ -531,9 +742,84 @@ impl Evaluator {
         }
 
         self.step_counter.set(steps + 1);
+
+        if let Some(limit) = self.limits.max_memory_bytes
+            && steps.is_multiple_of(Self::MEMORY_CHECK_INTERVAL)
+        {
+            let report = crate::runtime::memory::inspect(&self.globals, &self.env);
+            // Builtin bindings are not allocated by the script
+            let builtins = report.by_type.get("BuiltIn").map_or(0, |usage| usage.bytes);
+            // Values buffered in channels are held for the script too
+            let bytes = report.total_bytes - builtins + self.channels.table().buffered_bytes();
+            if bytes > limit {
+                return Err(Self::memory_limit_error(
+                    crate::runtime::MemoryResource::Bytes,
+                    bytes,
+                    limit,
+                ));
+            }
+        }
         Ok(())
     }
 
+    /// Check a value returned by a call or bound to a variable against the memory limits
+    ///
+    /// Arrays and dicts are checked by their own length; with `max_memory_bytes` the
+    /// estimated size of the value including its elements must fit in the budget.
+    pub(crate) fn check_memory(&self, value: &Value) -> Result<(), RuntimeError> {
+        use crate::runtime::MemoryResource;
+
+        let limits = &self.limits;
+        match value {
+            Value::Array(items) => {
+                if let Some(limit) = limits.max_array_len
+                    && items.len() > limit
+                {
+                    return Err(Self::memory_limit_error(
+                        MemoryResource::ArrayLength,
+                        items.len(),
+                        limit,
+                    ));
+                }
+            }
+            Value::Dict(map) => {
+                if let Some(limit) = limits.max_dict_entries
+                    && map.len() > limit
+                {
+                    return Err(Self::memory_limit_error(
+                        MemoryResource::DictEntries,
+                        map.len(),
+                        limit,
+                    ));
+                }
+            }
+            _ => {}
+        }
+        if let Some(limit) = limits.max_memory_bytes {
+            let bytes = crate::runtime::memory::value_bytes(value);
+            if bytes > limit {
+                return Err(Self::memory_limit_error(
+                    MemoryResource::Bytes,
+                    bytes,
+                    limit,
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn memory_limit_error(
+        resource: crate::runtime::MemoryResource,
+        size: usize,
+        limit: usize,
+    ) -> RuntimeError {
+        RuntimeError::ExecutionLimit(crate::runtime::ExecutionLimitError::MemoryLimitExceeded {
+            resource,
+            size,
+            limit,
+        })
+    }
+
     /// Reset execution step counter (host-facing).
     ///
     /// This is intended to be called at the start of a *top-level* evaluation.
@@ -548,6 +834,11 @@ impl Evaluator {
         self.step_counter.get()
     }
 
+    /// Number of modules held in the module cache
+    pub fn module_cache_size(&self) -> usize {
+        self.module_cache.len()
+    }
+
     /// Set the current source file (for debugger)
     pub fn set_source_file(&mut self, file: String) {
         self.current_source_file = Some(file);
@@ -578,8 +869,113 @@ impl Evaluator {
         self.call_stack_depth.get()
     }
 
+    /// Summarize the values reachable from the global and current scopes (memory debugging)
+    pub fn memory_report(&self) -> crate::runtime::MemoryReport {
+        crate::runtime::memory::inspect(&self.globals, &self.env)
+    }
+
+    /// Start recording variable changes before every statement (for the debugger)
+    ///
+    /// At most `max_steps` statements are kept; an existing recording is discarded.
+    pub fn start_recording(&mut self, max_steps: usize) {
+        self.recording = Some(crate::debugger::Recorder::new(max_steps));
+    }
+
+    /// Whether execution is being recorded
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    /// Stop recording and return what was recorded
+    pub fn take_recording(&mut self) -> Option<crate::debugger::ExecutionRecording> {
+        let recorder = self.recording.take()?;
+        let vars = self.env.borrow().visible_data();
+        Some(recorder.finish(vars))
+    }
+
+    /// Start recording the arguments and results of IO, clock and random builtins
+    ///
+    /// An existing recording or replay is discarded.
+    pub fn start_io_recording(&mut self) {
+        self.io_tape = Some(crate::runtime::replay::IoTape::Recording(Vec::new()));
+    }
+
+    /// Replay `events` in order instead of calling the recorded builtins
+    pub fn start_io_replay(&mut self, events: Vec<crate::runtime::IoEvent>) {
+        self.io_tape = Some(crate::runtime::replay::IoTape::Replaying { events, next: 0 });
+    }
+
+    /// Stop recording or replaying; returns the recorded events, or the events a replay
+    /// did not consume
+    pub fn take_io_events(&mut self) -> Option<Vec<crate::runtime::IoEvent>> {
+        self.io_tape.take().map(|tape| tape.into_events())
+    }
+
+    /// Whether a call to `name` is answered from the replayed IO events
+    fn replays_io(&self, name: &str) -> bool {
+        self.io_tape
+            .as_ref()
+            .is_some_and(|tape| tape.is_replaying())
+            && crate::runtime::replay::is_recorded(name)
+    }
+
+    /// The recorded result of the next IO call
+    fn replay_io(&mut self, name: &str, args: &[Value]) -> EvalResult {
+        let args = self.io_args(args);
+        match self.io_tape.as_mut() {
+            Some(tape) => tape.replay(name, args),
+            None => unreachable!("replay_io() called without a replay"),
+        }
+    }
+
+    /// Append a call to the IO recording (no-op unless recording)
+    fn record_io(&mut self, name: &str, args: &[Value], result: &EvalResult) {
+        if self.io_tape.as_ref().is_none_or(|tape| tape.is_replaying())
+            || !crate::runtime::replay::is_recorded(name)
+        {
+            return;
+        }
+        let args = self.io_args(args);
+        let outcome = result.as_ref().map_err(|e| self.redact(&e.to_string()));
+        if let Some(tape) = self.io_tape.as_mut() {
+            tape.record(name, args, outcome);
+        }
+    }
+
+    /// Recorded form of IO arguments, with secret values masked
+    fn io_args(&self, args: &[Value]) -> Vec<serde_json::Value> {
+        args.iter()
+            .map(|arg| {
+                crate::runtime::replay::map_strings(crate::runtime::replay::to_json(arg), &|s| {
+                    self.redact(s)
+                })
+            })
+            .collect()
+    }
+
+    /// Evaluate a statement while recording the variable changes it makes
+    fn eval_statement_recorded(&mut self, stmt: &Stmt) -> EvalResult {
+        let function = self.call_stack.last().map(|frame| frame.name.clone());
+        let depth = self.call_stack_depth.get();
+        let begun = match self.recording.as_mut() {
+            Some(recorder) if !recorder.is_full() => {
+                let vars = self.env.borrow().visible_data();
+                recorder.begin(vars, stmt, function, depth)
+            }
+            _ => false,
+        };
+
+        let result = self.exec_statement(stmt);
+
+        if begun && let Some(recorder) = self.recording.as_mut() {
+            let vars = (!recorder.is_full()).then(|| self.env.borrow().visible_data());
+            recorder.end(vars);
+        }
+        result
+    }
+
     /// Check execution timeout
-    fn check_timeout(&self) -> Result<(), RuntimeError> {
+    pub(crate) fn check_timeout(&self) -> Result<(), RuntimeError> {
         if let Some(limit_ms) = self.limits.max_duration_ms
             && let Some(start) = self.start_time.get()
         {
@@ -593,9 +989,124 @@ impl Evaluator {
                 ));
             }
         }
+        if let Some((_, limit_ms)) = self
+            .call_deadlines
+            .iter()
+            .find(|(deadline, _)| std::time::Instant::now() >= *deadline)
+        {
+            return Err(crate::builtins::resilience::timeout_error(*limit_ms));
+        }
         Ok(())
     }
 
+    /// Set how many loops the bytecode VM is running, so delegated statements can use
+    /// `Break` and `Continue`
+    pub(crate) fn set_loop_depth(&mut self, depth: usize) {
+        self.loop_depth = depth;
+    }
+
+    /// Error for a `Break` or `Continue` that is not inside a loop
+    pub(crate) fn outside_loop(keyword: &str) -> RuntimeError {
+        RuntimeError::InvalidOperation(format!("{} outside of loop", keyword))
+    }
+
+    /// Run one iteration of a loop body, returning whether the loop should stop
+    ///
+    /// `Continue` ends the iteration early and `Break` also stops the loop; both
+    /// may come from any statement nested in the body.
+    fn eval_loop_body(&mut self, body: &[Stmt], result: &mut Value) -> Result<bool, RuntimeError> {
+        self.loop_depth += 1;
+        let mut outcome = Ok(false);
+        for stmt in body {
+            match self.eval_statement(stmt) {
+                Ok(val) => *result = val,
+                Err(RuntimeError::Break) => {
+                    outcome = Ok(true);
+                    break;
+                }
+                Err(RuntimeError::Continue) => break,
+                Err(e) => {
+                    outcome = Err(e);
+                    break;
+                }
+            }
+        }
+        self.loop_depth -= 1;
+        outcome
+    }
+
+    /// Check loop limits before starting iteration number `iterations` (1-based) of `stmt`
+    pub(crate) fn check_loop_iteration(
+        &self,
+        iterations: usize,
+        stmt: &Stmt,
+    ) -> Result<(), RuntimeError> {
+        match self.limits.max_loop_iterations {
+            Some(limit) if iterations > limit => Err(RuntimeError::ExecutionLimit(
+                self.loop_limit_error(iterations, limit, stmt),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Describe the loop that hit the iteration limit: its header, enclosing function
+    /// and the current values of the variables driving it
+    fn loop_limit_error(
+        &self,
+        iterations: usize,
+        limit: usize,
+        stmt: &Stmt,
+    ) -> crate::runtime::ExecutionLimitError {
+        let (header, names) = match stmt {
+            Stmt::While { condition, .. } => (
+                format!("While ({})", condition),
+                condition.referenced_names(),
+            ),
+            Stmt::For { var, iterable, .. } => {
+                let mut names = vec![var.clone()];
+                names.extend(iterable.referenced_names());
+                (format!("For {} In {}", var, iterable), names)
+            }
+            Stmt::ForIndexed {
+                index_var,
+                value_var,
+                iterable,
+                ..
+            } => {
+                let mut names = vec![index_var.clone(), value_var.clone()];
+                names.extend(iterable.referenced_names());
+                (
+                    format!("For {}, {} In {}", index_var, value_var, iterable),
+                    names,
+                )
+            }
+            _ => (String::from("loop"), Vec::new()),
+        };
+
+        // Keep large collections readable in the message
+        let options = crate::runtime::DisplayOptions::new()
+            .with_max_items(5)
+            .with_max_depth(2);
+        let variables = names
+            .into_iter()
+            .filter_map(|name| {
+                let value = self.env.borrow().get(&name)?;
+                match value {
+                    Value::BuiltIn { .. } | Value::Function { .. } => None,
+                    value => Some((name, value.display_with(&options))),
+                }
+            })
+            .collect();
+
+        crate::runtime::ExecutionLimitError::LoopIterationLimitExceeded {
+            iterations,
+            limit,
+            header,
+            function: self.call_stack.last().map(|frame| frame.name.clone()),
+            variables,
+        }
+    }
+
     /// Enter function call (check recursion depth)
     fn enter_call(&self) -> Result<(), RuntimeError> {
         if let Some(limit) = self.limits.max_recursion_depth {
@@ -621,6 +1132,243 @@ impl Evaluator {
     /// Set execution limits (public API)
     pub fn set_limits(&mut self, limits: crate::runtime::ExecutionLimits) {
         self.limits = limits;
+        // Workers capture the limits when started
+        self.task_pool = None;
+    }
+
+    /// Set limits on SPAWN/PARALLEL tasks and reset the task budget (public API)
+    pub fn set_concurrency_limits(&mut self, limits: crate::runtime::ConcurrencyLimits) {
+        self.set_concurrency_budget(limits, Arc::default());
+    }
+
+    /// Get limits on SPAWN/PARALLEL tasks (public API)
+    pub fn concurrency_limits(&self) -> &crate::runtime::ConcurrencyLimits {
+        &self.concurrency_limits
+    }
+
+    /// Share a parent engine's task budget (used by worker engines)
+    pub(crate) fn set_concurrency_budget(
+        &mut self,
+        limits: crate::runtime::ConcurrencyLimits,
+        budget: Arc<crate::builtins::parallel::TaskBudget>,
+    ) {
+        self.concurrency_limits = limits;
+        self.task_budget = budget;
+        // Workers capture the configuration when started
+        self.task_pool = None;
+    }
+
+    /// Use a parent engine's channels (used by worker engines)
+    pub(crate) fn set_channels(&mut self, table: Arc<crate::builtins::channel::ChannelTable>) {
+        self.channels = crate::builtins::channel::Channels::shared(table);
+    }
+
+    /// Set the keyword dialect (None restores the standard keywords)
+    pub fn set_dialect(&mut self, dialect: Option<crate::dialect::Dialect>) {
+        self.dialect = dialect.filter(|d| !d.is_empty()).map(Arc::new);
+    }
+
+    /// Get the keyword dialect, if any
+    pub fn dialect(&self) -> Option<&crate::dialect::Dialect> {
+        self.dialect.as_deref()
+    }
+
+    /// Create a parser honoring the configured dialect
+    pub fn parser(&self, source: &str) -> crate::parser::Parser {
+        match &self.dialect {
+            Some(dialect) => crate::parser::Parser::with_dialect(source, Arc::clone(dialect)),
+            None => crate::parser::Parser::new(source),
+        }
+    }
+
+    /// Register a handler for `@TAG { ... }` extension blocks (public API)
+    pub fn register_extension(&mut self, tag: &str, handler: crate::runtime::ExtensionHandler) {
+        self.extension_handlers.insert(tag.to_string(), handler);
+    }
+
+    /// Remove the handler for an extension tag, returning whether one was registered
+    pub fn unregister_extension(&mut self, tag: &str) -> bool {
+        self.extension_handlers.remove(tag).is_some()
+    }
+
+    /// Set (or clear) the host callback used by `SECRET("name")`
+    pub fn set_secrets_provider(&mut self, provider: Option<crate::runtime::SecretsProvider>) {
+        self.secrets_provider = provider;
+    }
+
+    /// Resolve a secret through the host provider and remember it for redaction
+    fn resolve_secret(&mut self, args: &[Value]) -> Result<Value, RuntimeError> {
+        let name = match args {
+            [Value::String(name)] => name,
+            [other] => {
+                return Err(RuntimeError::TypeErrorDetailed {
+                    expected: "String (secret name)".to_string(),
+                    got: other.type_name().to_string(),
+                });
+            }
+            _ => return Err(self.builtin_arity_error("SECRET", 1, args.len())),
+        };
+        let provider = self.secrets_provider.clone().ok_or_else(|| {
+            RuntimeError::CustomError("SECRET: no secrets provider registered".to_string())
+        })?;
+        let value = provider(name).ok_or_else(|| {
+            RuntimeError::CustomError(format!("SECRET: unknown secret '{}'", name))
+        })?;
+        self.add_secret(value.clone());
+        Ok(Value::String(value))
+    }
+
+    /// Set (or clear) the directory used by `SNAPSHOT_MATCH`, resetting its statistics
+    pub fn set_snapshot_dir(&mut self, dir: Option<std::path::PathBuf>) {
+        let update = self.snapshot_update();
+        self.snapshots = dir.map(|dir| {
+            let mut state = crate::runtime::snapshot::SnapshotState::new(dir);
+            state.update = update;
+            Rc::new(RefCell::new(state))
+        });
+    }
+
+    /// Whether mismatching snapshots are overwritten instead of failing
+    pub fn set_snapshot_update(&mut self, update: bool) {
+        if let Some(state) = &self.snapshots {
+            state.borrow_mut().update = update;
+        }
+    }
+
+    fn snapshot_update(&self) -> bool {
+        self.snapshots.as_ref().is_some_and(|s| s.borrow().update)
+    }
+
+    /// Counts of matched / created / updated / failed snapshots since the directory was set
+    pub fn snapshot_stats(&self) -> crate::runtime::SnapshotStats {
+        self.snapshots
+            .as_ref()
+            .map(|s| s.borrow().stats)
+            .unwrap_or_default()
+    }
+
+    /// SNAPSHOT_MATCH(name, value): compare the value with its stored snapshot
+    fn builtin_snapshot_match(&mut self, args: &[Value]) -> EvalResult {
+        let (name, value) = match args {
+            [Value::String(name), value] => (name, value),
+            [other, _] => {
+                return Err(RuntimeError::TypeErrorDetailed {
+                    expected: "String (snapshot name)".to_string(),
+                    got: other.type_name().to_string(),
+                });
+            }
+            _ => return Err(self.builtin_arity_error("SNAPSHOT_MATCH", 2, args.len())),
+        };
+        let state = self.snapshots.as_ref().ok_or_else(|| {
+            RuntimeError::CustomError(
+                "SNAPSHOT_MATCH: no snapshot directory configured (run with `aether test` or call set_snapshot_dir)"
+                    .to_string(),
+            )
+        })?;
+        let (outcome, path) = state.borrow_mut().check(name, value)?;
+
+        let mut result = HashMap::new();
+        result.insert(
+            "status".to_string(),
+            Value::String(outcome.as_str().to_string()),
+        );
+        result.insert(
+            "path".to_string(),
+            Value::String(path.display().to_string()),
+        );
+        if let crate::runtime::SnapshotOutcome::Mismatch { expected } = outcome {
+            result.insert("expected".to_string(), expected);
+        }
+        Ok(Value::Dict(result))
+    }
+
+    /// Remember a secret value so it is masked in output
+    fn add_secret(&self, value: String) {
+        let mut secrets = self.secret_values.borrow_mut();
+        if !value.is_empty() && !secrets.contains(&value) {
+            // 较长的值先替换，避免其中包含的较短机密先被替换后留下部分明文
+            secrets.push(value);
+            secrets.sort_by_key(|s| std::cmp::Reverse(s.len()));
+        }
+    }
+
+    /// MARK_SECRET(value): register the value's strings / numbers as secrets
+    fn mark_secret(&self, args: &[Value]) -> Result<Value, RuntimeError> {
+        let [value] = args else {
+            return Err(self.builtin_arity_error("MARK_SECRET", 1, args.len()));
+        };
+        fn collect(evaluator: &Evaluator, value: &Value) {
+            match value {
+                Value::String(s) => evaluator.add_secret(s.clone()),
+                Value::Number(_) | Value::Int(_) | Value::Fraction(_) => {
+                    evaluator.add_secret(value.to_string())
+                }
+                Value::Array(items) => items.iter().for_each(|v| collect(evaluator, v)),
+                Value::Dict(map) => map.values().for_each(|v| collect(evaluator, v)),
+                _ => {}
+            }
+        }
+        collect(self, value);
+        Ok(value.clone())
+    }
+
+    /// Make this evaluator's secrets visible to output builtins until the guard is dropped
+    pub(crate) fn scoped_secrets(&self) -> crate::runtime::secrets::ScopedSecrets {
+        crate::runtime::secrets::ScopedSecrets::set(Rc::clone(&self.secret_values))
+    }
+
+    /// Make this evaluator's payroll settings visible to payroll builtins until the guard
+    /// is dropped
+    #[cfg(feature = "payroll")]
+    fn scoped_payroll(&self) -> crate::builtins::payroll::settings::ScopedPayrollSettings {
+        crate::builtins::payroll::settings::ScopedPayrollSettings::set(Rc::clone(
+            &self.payroll_settings,
+        ))
+    }
+
+    /// Make this evaluator's message catalogs and locale visible to i18n builtins until the
+    /// guard is dropped
+    fn scoped_i18n(&self) -> crate::builtins::i18n::ScopedI18nSettings {
+        crate::builtins::i18n::ScopedI18nSettings::set(Rc::clone(&self.i18n_settings))
+    }
+
+    /// Make the key-value stores this evaluator opened visible to KV builtins until the guard
+    /// is dropped
+    fn scoped_kv(&self) -> crate::builtins::kv::ScopedKvStores {
+        crate::builtins::kv::ScopedKvStores::set(Rc::clone(&self.kv_stores))
+    }
+
+    /// Make the export writers this evaluator opened visible to WRITER_ builtins until the
+    /// guard is dropped
+    fn scoped_export(&self) -> crate::builtins::export::ScopedExportWriters {
+        crate::builtins::export::ScopedExportWriters::set(Rc::clone(&self.export_writers))
+    }
+
+    /// Make the WebSocket / SSE connections this evaluator opened visible to WS_ and SSE_
+    /// builtins until the guard is dropped
+    #[cfg(feature = "async")]
+    fn scoped_connections(&self) -> crate::builtins::websocket::ScopedStreamConnections {
+        crate::builtins::websocket::ScopedStreamConnections::set(Rc::clone(
+            &self.stream_connections,
+        ))
+    }
+
+    /// Stable text of the engine settings that pure builtins read (payroll rounding and
+    /// holiday calendars); part of the `eval_pure` cache key
+    pub(crate) fn settings_fingerprint(&self) -> String {
+        #[cfg(feature = "payroll")]
+        {
+            self.payroll_settings.borrow().fingerprint()
+        }
+        #[cfg(not(feature = "payroll"))]
+        {
+            String::new()
+        }
+    }
+
+    /// Mask registered secret values in text (errors, host-facing output)
+    pub fn redact(&self, text: &str) -> String {
+        crate::runtime::secrets::redact_text(text, &self.secret_values.borrow())
     }
 
     /// Get execution limits (public API)
@@ -628,6 +1376,394 @@ impl Evaluator {
         &self.limits
     }
 
+    /// Set value display options (public API)
+    pub fn set_display_options(&mut self, options: crate::runtime::DisplayOptions) {
+        self.display_options = options;
+    }
+
+    /// Get value display options (public API)
+    pub fn display_options(&self) -> &crate::runtime::DisplayOptions {
+        &self.display_options
+    }
+
+    /// Enable or disable case-insensitive builtin resolution (public API)
+    pub fn set_case_insensitive_builtins(&mut self, enabled: bool) {
+        self.case_insensitive_builtins = enabled;
+    }
+
+    /// Register an alias for a builtin function (public API)
+    pub fn alias_builtin(
+        &mut self,
+        alias: impl Into<String>,
+        target: &str,
+    ) -> Result<(), RuntimeError> {
+        if !self.registry.has(target) {
+            return Err(RuntimeError::UndefinedVariable(target.to_string()));
+        }
+        self.builtin_aliases
+            .insert(alias.into(), target.to_string());
+        Ok(())
+    }
+
+    /// IO permissions this evaluator currently has
+    pub fn permissions(&self) -> &crate::builtins::IOPermissions {
+        self.registry.permissions()
+    }
+
+    /// Change IO permissions on a live evaluator
+    ///
+    /// Newly allowed builtins become visible in the global scope (names the script
+    /// has redefined are left alone); revoked builtins are removed from it.
+    pub fn set_permissions(&mut self, permissions: crate::builtins::IOPermissions) {
+        let registry = self.registry.with_new_permissions(permissions);
+        let mut globals = self.globals.borrow_mut();
+        for name in self.registry.names() {
+            if !registry.has(&name)
+                && matches!(globals.get(&name), Some(Value::BuiltIn { name: ref n, .. }) if *n == name)
+            {
+                globals.remove(&name);
+            }
+        }
+        for name in registry.names() {
+            let defined = globals.get(&name);
+            if defined.is_none()
+                || matches!(defined, Some(Value::BuiltIn { name: ref n, .. }) if *n == name)
+            {
+                let arity = registry.arity(&name).unwrap_or(0);
+                globals.set(name.clone(), Value::BuiltIn { name, arity });
+            }
+        }
+        drop(globals);
+        self.registry = registry;
+    }
+
+    /// Register a native (stateful) function and expose it in the global scope
+    pub fn register_native(&mut self, name: &str, func: crate::builtins::NativeFn, arity: usize) {
+        self.registry.register_native(name, func, arity);
+        self.globals.borrow_mut().set(
+            name.to_string(),
+            Value::BuiltIn {
+                name: name.to_string(),
+                arity,
+            },
+        );
+    }
+
+    /// Default language version for scripts without a `#language` pragma
+    pub fn set_language_version(&mut self, version: crate::runtime::LanguageVersion) {
+        self.language_version = version;
+    }
+
+    /// Default language version for scripts without a `#language` pragma
+    pub fn language_version(&self) -> crate::runtime::LanguageVersion {
+        self.language_version
+    }
+
+    /// Select how top-level programs are executed
+    pub fn set_backend(&mut self, backend: crate::compile::Backend) {
+        self.backend = backend;
+    }
+
+    /// Execution backend used for top-level programs
+    pub fn backend(&self) -> crate::compile::Backend {
+        self.backend
+    }
+
+    /// Language version in effect for the running program
+    fn language(&self) -> crate::runtime::LanguageVersion {
+        self.script_language.unwrap_or(self.language_version)
+    }
+
+    /// Apply a `#NAME value` pragma
+    fn apply_pragma(&mut self, name: &str, value: &str) -> Result<(), RuntimeError> {
+        match name {
+            "language" => {
+                let version = crate::runtime::LanguageVersion::parse(value)
+                    .map_err(RuntimeError::InvalidOperation)?;
+                self.script_language = Some(version);
+                Ok(())
+            }
+            _ => Err(RuntimeError::InvalidOperation(format!(
+                "Unknown pragma '#{}'",
+                name
+            ))),
+        }
+    }
+
+    /// Whether a name is a registered builtin or a builtin alias
+    pub fn is_builtin(&self, name: &str) -> bool {
+        self.registry.has(name) || self.builtin_aliases.contains_key(name)
+    }
+
+    /// Whether a name is a native function registered by the host or a plugin
+    pub fn is_native(&self, name: &str) -> bool {
+        self.registry.has_native(name)
+    }
+
+    /// Whether a builtin (or builtin alias) is marked pure in the registry
+    pub fn is_pure_builtin(&self, name: &str) -> bool {
+        let name = self.builtin_aliases.get(name).map_or(name, String::as_str);
+        self.registry.is_pure(name)
+    }
+
+    /// Mark a builtin or native function as pure, allowing it in `eval_pure` (public API)
+    pub fn mark_builtin_pure(&mut self, name: &str) -> Result<(), RuntimeError> {
+        if !self.registry.has(name) {
+            return Err(RuntimeError::UndefinedVariable(name.to_string()));
+        }
+        if self.registry.is_io(name) {
+            return Err(RuntimeError::InvalidOperation(format!(
+                "{} reads or writes external state and cannot be marked pure",
+                name
+            )));
+        }
+        self.registry.mark_pure(name);
+        Ok(())
+    }
+
+    /// Whether a value contains a string registered as a secret (SECRET / MARK_SECRET)
+    pub(crate) fn contains_secret(&self, value: &Value) -> bool {
+        crate::runtime::secrets::contains_secret(value, &self.secret_values.borrow())
+    }
+
+    /// Metadata of every registered builtin and native function, sorted by name
+    pub fn builtin_metadata(&self) -> Vec<crate::builtins::metadata::BuiltinMetadata> {
+        self.registry.metadata()
+    }
+
+    /// Lint messages for builtin names that were resolved via alias or case folding
+    pub fn builtin_name_warnings(&self) -> &[String] {
+        &self.builtin_name_warnings
+    }
+
+    /// Clear builtin name lint messages
+    pub fn clear_builtin_name_warnings(&mut self) {
+        self.builtin_name_warnings.clear();
+    }
+
+    /// Mark a builtin as deprecated, optionally naming its replacement (public API)
+    pub fn deprecate_builtin(
+        &mut self,
+        name: &str,
+        replacement: Option<&str>,
+    ) -> Result<(), RuntimeError> {
+        if self.registry.deprecate(name, replacement) {
+            Ok(())
+        } else {
+            Err(RuntimeError::UndefinedVariable(name.to_string()))
+        }
+    }
+
+    /// Gate a builtin behind an experimental feature flag (public API)
+    pub fn mark_builtin_experimental(
+        &mut self,
+        name: &str,
+        feature: &str,
+    ) -> Result<(), RuntimeError> {
+        if self.registry.mark_experimental(name, feature) {
+            Ok(())
+        } else {
+            Err(RuntimeError::UndefinedVariable(name.to_string()))
+        }
+    }
+
+    /// Enable or disable strict mode (public API)
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    /// Mark the code evaluated from now on as standard library source
+    ///
+    /// Functions defined while this is set are library code: they may redefine builtins
+    /// in strict mode and are not held to the script's language version.
+    pub(crate) fn set_loading_library(&mut self, loading: bool) {
+        self.loading_library = loading;
+    }
+
+    /// Whether strict mode is enabled (public API)
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// Enable (Some) or disable (None) deterministic mode (public API)
+    pub fn set_deterministic(&mut self, config: Option<crate::runtime::DeterministicConfig>) {
+        self.deterministic = config;
+        self.rng = match &self.deterministic {
+            Some(config) => crate::runtime::deterministic::SplitMix64::new(config.seed),
+            None => crate::runtime::deterministic::SplitMix64::from_time(),
+        };
+    }
+
+    /// Deterministic mode configuration, if enabled (public API)
+    pub fn deterministic(&self) -> Option<&crate::runtime::DeterministicConfig> {
+        self.deterministic.as_ref()
+    }
+
+    /// Restart RANDOM() from the configured seed (no-op outside deterministic mode)
+    pub fn restart_random_sequence(&mut self) {
+        if let Some(config) = &self.deterministic {
+            self.rng = crate::runtime::deterministic::SplitMix64::new(config.seed);
+        }
+    }
+
+    /// Current time as a Unix timestamp (the injected clock in deterministic mode)
+    fn now_timestamp(&self) -> f64 {
+        match &self.deterministic {
+            Some(config) => config.now,
+            None => std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs_f64())
+                .unwrap_or(0.0),
+        }
+    }
+
+    /// Enable/disable counting calls to named user functions (public API)
+    pub fn set_call_profiling(&mut self, enabled: bool) {
+        self.profile_calls = enabled;
+    }
+
+    /// Whether user function calls are being counted (public API)
+    pub fn is_call_profiling(&self) -> bool {
+        self.profile_calls
+    }
+
+    /// Call counts per user function collected while profiling (public API)
+    pub fn function_call_counts(&self) -> &HashMap<String, usize> {
+        &self.function_calls
+    }
+
+    /// Reset collected call counts (public API)
+    pub fn clear_function_call_counts(&mut self) {
+        self.function_calls.clear();
+    }
+
+    /// Names of user functions called at least `threshold` times, sorted (public API)
+    pub fn hot_functions(&self, threshold: usize) -> Vec<String> {
+        let mut hot: Vec<String> = self
+            .function_calls
+            .iter()
+            .filter(|(_, count)| **count >= threshold)
+            .map(|(name, _)| name.clone())
+            .collect();
+        hot.sort();
+        hot
+    }
+
+    /// Enable an experimental feature flag (public API)
+    pub fn enable_experimental(&mut self, feature: impl Into<String>) {
+        self.enabled_features.insert(feature.into());
+    }
+
+    /// Disable an experimental feature flag (public API)
+    pub fn disable_experimental(&mut self, feature: &str) {
+        self.enabled_features.remove(feature);
+    }
+
+    /// Check whether an experimental feature flag is enabled
+    pub fn is_experimental_enabled(&self, feature: &str) -> bool {
+        self.enabled_features.contains(feature)
+    }
+
+    /// Warnings for deprecated builtins that have been called
+    pub fn deprecation_warnings(&self) -> &[String] {
+        &self.deprecation_warnings
+    }
+
+    /// Clear deprecation warnings
+    pub fn clear_deprecation_warnings(&mut self) {
+        self.deprecation_warnings.clear();
+    }
+
+    /// Record a warning (identical warnings are only recorded once until taken)
+    pub fn warn(&mut self, kind: crate::runtime::WarningKind, message: impl Into<String>) {
+        let warning = crate::runtime::Warning::new(kind, message);
+        if !self.warnings.contains(&warning) {
+            self.warnings.push(warning);
+        }
+    }
+
+    /// Warnings recorded since the last `take_warnings`
+    pub fn warnings(&self) -> &[crate::runtime::Warning] {
+        &self.warnings
+    }
+
+    /// Take and clear the recorded warnings
+    pub fn take_warnings(&mut self) -> Vec<crate::runtime::Warning> {
+        std::mem::take(&mut self.warnings)
+    }
+
+    /// Enforce experimental gates and record deprecation warnings before a builtin call.
+    fn check_builtin_status(&mut self, name: &str) -> Result<(), RuntimeError> {
+        if self.deterministic.is_some() && self.registry.is_io(name) {
+            return Err(RuntimeError::InvalidOperation(format!(
+                "Deterministic mode: '{}' is not allowed because its result depends on external state or timing",
+                name
+            )));
+        }
+
+        // The version pin applies to the script's own calls; the stdlib and imported
+        // modules are written against the latest language. Builtins run in their
+        // caller's environment.
+        let since = crate::runtime::LanguageVersion::builtin_since(name);
+        if since > self.language() && !self.in_library_code() {
+            return Err(RuntimeError::InvalidOperation(format!(
+                "Builtin '{}' requires language {} (script targets {})",
+                name,
+                since,
+                self.language()
+            )));
+        }
+
+        if let Some(feature) = self.registry.experimental_feature(name)
+            && !self.enabled_features.contains(feature)
+        {
+            return Err(RuntimeError::InvalidOperation(format!(
+                "Builtin '{}' is experimental; enable it with enable_experimental(\"{}\")",
+                name, feature
+            )));
+        }
+
+        if let Some(deprecation) = self.registry.deprecation(name) {
+            let warning = match &deprecation.replacement {
+                Some(replacement) => {
+                    format!("'{}' is deprecated; use '{}' instead", name, replacement)
+                }
+                None => format!("'{}' is deprecated", name),
+            };
+            if !self.deprecation_warnings.contains(&warning) {
+                self.deprecation_warnings.push(warning.clone());
+            }
+            self.warn(crate::runtime::WarningKind::Deprecation, warning);
+        }
+        Ok(())
+    }
+
+    /// Fallback lookup for undefined names: alias table first, then case-insensitive match.
+    fn resolve_builtin_name(&mut self, name: &str) -> Option<Value> {
+        let canonical = match self.builtin_aliases.get(name) {
+            Some(target) => target.clone(),
+            None if self.case_insensitive_builtins => {
+                let upper = name.to_uppercase();
+                if upper == name || !self.registry.has(&upper) {
+                    return None;
+                }
+                upper
+            }
+            None => return None,
+        };
+
+        let value = self.env.borrow().get(&canonical)?;
+        let warning = format!(
+            "'{}' resolved to builtin '{}'; use the canonical name",
+            name, canonical
+        );
+        if !self.builtin_name_warnings.contains(&warning) {
+            self.builtin_name_warnings.push(warning.clone());
+        }
+        self.warn(crate::runtime::WarningKind::BuiltinName, warning);
+        Some(value)
+    }
+
     fn is_control_flow_error(err: &RuntimeError) -> bool {
         matches!(
             err,
@@ -639,6 +1775,55 @@ impl Evaluator {
         )
     }
 
+    /// The value bound to the Catch variable, or `None` when `err` must keep propagating
+    ///
+    /// `Throw` binds the thrown value as-is; other runtime errors are bound as a Dict with
+    /// `kind` and `message` (plus `line` and `column` when the position is known). Control
+    /// flow, execution limits and debugger pauses are not catchable, so a script cannot
+    /// swallow a limit imposed by the host.
+    fn caught_value(err: &RuntimeError) -> Option<Value> {
+        let (inner, _) = err.peel_call_stack();
+        if Self::is_control_flow_error(inner) || matches!(inner, RuntimeError::ExecutionLimit(_)) {
+            return None;
+        }
+        Some(match inner {
+            RuntimeError::Throw(value) => value.clone(),
+            other => {
+                let mut caught = HashMap::from([
+                    ("kind".to_string(), Value::String(other.kind_name())),
+                    ("message".to_string(), Value::String(other.base_message())),
+                ]);
+                if let Some(span) = err.location() {
+                    caught.insert("line".to_string(), Value::Number(span.line as f64));
+                    caught.insert("column".to_string(), Value::Number(span.column as f64));
+                }
+                Value::Dict(caught)
+            }
+        })
+    }
+
+    /// Evaluate the statements of a block, returning the value of the last one
+    fn eval_block(&mut self, body: &[Stmt]) -> EvalResult {
+        let mut result = Value::Null;
+        for stmt in body {
+            result = self.eval_statement(stmt)?;
+        }
+        Ok(result)
+    }
+
+    /// Build an arity error naming a builtin and its registry signature
+    fn builtin_arity_error(&self, name: &str, expected: usize, got: usize) -> RuntimeError {
+        RuntimeError::WrongArityDetailed {
+            callee: name.to_string(),
+            signature: self
+                .registry
+                .signature(name)
+                .unwrap_or_else(|| format!("{}(...)", name)),
+            expected,
+            got,
+        }
+    }
+
     fn attach_call_stack_if_absent(&self, err: RuntimeError) -> RuntimeError {
         if Self::is_control_flow_error(&err) {
             return err;
@@ -677,6 +1862,7 @@ impl Evaluator {
         Self::register_builtins_into_env(&registry, &mut env.borrow_mut());
 
         Evaluator {
+            globals: Rc::clone(&env),
             env,
             registry,
             trace: VecDeque::new(),
@@ -684,9 +1870,10 @@ impl Evaluator {
             trace_entries: VecDeque::new(),
             trace_buffer_size,
 
-            module_resolver: Box::new(DisabledModuleResolver),
+            module_resolver: Rc::new(DisabledModuleResolver),
             module_cache: HashMap::new(),
             module_stack: Vec::new(),
+            loading_library: false,
             export_stack: Vec::new(),
             import_base_stack: Vec::new(),
 
@@ -695,9 +1882,51 @@ impl Evaluator {
             limits: crate::runtime::ExecutionLimits::default(),
             current_source_file: None,
             current_line: std::cell::Cell::new(0),
+            recording: None,
+            io_tape: None,
             step_counter: std::cell::Cell::new(0),
             call_stack_depth: std::cell::Cell::new(0),
+            loop_depth: 0,
             start_time: std::cell::Cell::new(None),
+            sealed_names: std::collections::HashSet::new(),
+            display_options: crate::runtime::DisplayOptions::default(),
+            case_insensitive_builtins: false,
+            builtin_aliases: HashMap::new(),
+            builtin_name_warnings: Vec::new(),
+            enabled_features: std::collections::HashSet::new(),
+            deprecation_warnings: Vec::new(),
+            scheduled_jobs: Vec::new(),
+            timers: Default::default(),
+            next_job_id: 1,
+            scheduler_started: false,
+            call_deadlines: Vec::new(),
+            task_pool: None,
+            pending_tasks: HashMap::new(),
+            next_task_id: 1,
+            concurrency_limits: crate::runtime::ConcurrencyLimits::default(),
+            task_budget: Arc::default(),
+            channels: Default::default(),
+            profile_calls: false,
+            function_calls: HashMap::new(),
+            deterministic: None,
+            rng: crate::runtime::deterministic::SplitMix64::from_time(),
+            extension_handlers: HashMap::new(),
+            secrets_provider: None,
+            secret_values: Default::default(),
+            #[cfg(feature = "payroll")]
+            payroll_settings: Default::default(),
+            i18n_settings: Default::default(),
+            kv_stores: Default::default(),
+            export_writers: Default::default(),
+            #[cfg(feature = "async")]
+            stream_connections: Default::default(),
+            snapshots: None,
+            dialect: None,
+            strict: false,
+            warnings: Vec::new(),
+            language_version: crate::runtime::LanguageVersion::LATEST,
+            script_language: None,
+            backend: crate::compile::Backend::default(),
         }
     }
 
@@ -705,6 +1934,7 @@ impl Evaluator {
     pub fn with_env(env: Rc<RefCell<Environment>>) -> Self {
         let registry = BuiltInRegistry::new();
         Evaluator {
+            globals: Rc::clone(&env),
             env,
             registry,
             trace: VecDeque::new(),
@@ -712,9 +1942,10 @@ impl Evaluator {
             trace_entries: VecDeque::new(),
             trace_buffer_size: Self::DEFAULT_TRACE_BUFFER_SIZE,
 
-            module_resolver: Box::new(DisabledModuleResolver),
+            module_resolver: Rc::new(DisabledModuleResolver),
             module_cache: HashMap::new(),
             module_stack: Vec::new(),
+            loading_library: false,
             export_stack: Vec::new(),
             import_base_stack: Vec::new(),
 
@@ -723,9 +1954,136 @@ impl Evaluator {
             limits: crate::runtime::ExecutionLimits::default(),
             current_source_file: None,
             current_line: std::cell::Cell::new(0),
+            recording: None,
+            io_tape: None,
             step_counter: std::cell::Cell::new(0),
             call_stack_depth: std::cell::Cell::new(0),
+            loop_depth: 0,
             start_time: std::cell::Cell::new(None),
+            sealed_names: std::collections::HashSet::new(),
+            display_options: crate::runtime::DisplayOptions::default(),
+            case_insensitive_builtins: false,
+            builtin_aliases: HashMap::new(),
+            builtin_name_warnings: Vec::new(),
+            enabled_features: std::collections::HashSet::new(),
+            deprecation_warnings: Vec::new(),
+            scheduled_jobs: Vec::new(),
+            timers: Default::default(),
+            next_job_id: 1,
+            scheduler_started: false,
+            call_deadlines: Vec::new(),
+            task_pool: None,
+            pending_tasks: HashMap::new(),
+            next_task_id: 1,
+            concurrency_limits: crate::runtime::ConcurrencyLimits::default(),
+            task_budget: Arc::default(),
+            channels: Default::default(),
+            profile_calls: false,
+            function_calls: HashMap::new(),
+            deterministic: None,
+            rng: crate::runtime::deterministic::SplitMix64::from_time(),
+            extension_handlers: HashMap::new(),
+            secrets_provider: None,
+            secret_values: Default::default(),
+            #[cfg(feature = "payroll")]
+            payroll_settings: Default::default(),
+            i18n_settings: Default::default(),
+            kv_stores: Default::default(),
+            export_writers: Default::default(),
+            #[cfg(feature = "async")]
+            stream_connections: Default::default(),
+            snapshots: None,
+            dialect: None,
+            strict: false,
+            warnings: Vec::new(),
+            language_version: crate::runtime::LanguageVersion::LATEST,
+            script_language: None,
+            backend: crate::compile::Backend::default(),
+        }
+    }
+
+    /// Create an evaluator whose global scope is a child of this evaluator's globals
+    ///
+    /// Everything defined here (builtins, preloaded stdlib, host globals) is shared
+    /// without copying. Definitions made in the fork go into its own scope and never
+    /// change this evaluator; redefining a shared name shadows it in the fork only.
+    /// Configuration (permissions, limits, dialect, strict mode, extension handlers,
+    /// secrets provider, module resolver and loaded modules) is copied; traces, warnings, schedules
+    /// and spawned tasks start empty.
+    pub fn fork(&self) -> Self {
+        let env = Rc::new(RefCell::new(Environment::with_parent(Rc::clone(&self.env))));
+        Evaluator {
+            globals: Rc::clone(&env),
+            env,
+            registry: self.registry.clone(),
+            trace: VecDeque::new(),
+            trace_seq: 0,
+            trace_entries: VecDeque::new(),
+            trace_buffer_size: self.trace_buffer_size,
+
+            module_resolver: Rc::clone(&self.module_resolver),
+            module_cache: self.module_cache.clone(),
+            module_stack: Vec::new(),
+            loading_library: false,
+            export_stack: Vec::new(),
+            import_base_stack: Vec::new(),
+
+            call_stack: Vec::new(),
+
+            limits: self.limits.clone(),
+            current_source_file: None,
+            current_line: std::cell::Cell::new(0),
+            recording: None,
+            io_tape: None,
+            step_counter: std::cell::Cell::new(0),
+            call_stack_depth: std::cell::Cell::new(0),
+            loop_depth: 0,
+            start_time: std::cell::Cell::new(None),
+            sealed_names: self.sealed_names.clone(),
+            display_options: self.display_options.clone(),
+            case_insensitive_builtins: self.case_insensitive_builtins,
+            builtin_aliases: self.builtin_aliases.clone(),
+            builtin_name_warnings: Vec::new(),
+            enabled_features: self.enabled_features.clone(),
+            deprecation_warnings: Vec::new(),
+            scheduled_jobs: Vec::new(),
+            timers: Default::default(),
+            next_job_id: 1,
+            scheduler_started: false,
+            call_deadlines: Vec::new(),
+            task_pool: None,
+            pending_tasks: HashMap::new(),
+            next_task_id: 1,
+            concurrency_limits: self.concurrency_limits.clone(),
+            task_budget: Arc::default(),
+            // Handles in the shared environment keep working in the child
+            channels: crate::builtins::channel::Channels::shared(Arc::clone(self.channels.table())),
+            profile_calls: self.profile_calls,
+            function_calls: HashMap::new(),
+            deterministic: self.deterministic.clone(),
+            rng: match &self.deterministic {
+                Some(config) => crate::runtime::deterministic::SplitMix64::new(config.seed),
+                None => crate::runtime::deterministic::SplitMix64::from_time(),
+            },
+            extension_handlers: self.extension_handlers.clone(),
+            secrets_provider: self.secrets_provider.clone(),
+            secret_values: Rc::clone(&self.secret_values),
+            // The child starts from the parent's settings but changes stay in the child
+            #[cfg(feature = "payroll")]
+            payroll_settings: Rc::new(RefCell::new(self.payroll_settings.borrow().clone())),
+            i18n_settings: Rc::new(RefCell::new(self.i18n_settings.borrow().clone())),
+            kv_stores: Rc::new(RefCell::new(self.kv_stores.borrow().clone())),
+            // Open writers stay with the engine that opened them
+            export_writers: Default::default(),
+            #[cfg(feature = "async")]
+            stream_connections: Default::default(),
+            snapshots: self.snapshots.clone(),
+            dialect: self.dialect.clone(),
+            strict: self.strict,
+            warnings: Vec::new(),
+            language_version: self.language_version,
+            script_language: None,
+            backend: self.backend,
         }
     }
 
@@ -736,7 +2094,7 @@ impl Evaluator {
 
     /// Configure the module resolver used for `Import/Export`.
     pub fn set_module_resolver(&mut self, resolver: Box<dyn ModuleResolver>) {
-        self.module_resolver = resolver;
+        self.module_resolver = Rc::from(resolver);
     }
 
     /// Push a base directory context for resolving relative imports.
@@ -757,6 +2115,7 @@ impl Evaluator {
     /// Append a trace entry (host-readable; no IO side effects).
     pub fn trace_push(&mut self, msg: String) {
         self.trace_seq = self.trace_seq.saturating_add(1);
+        let msg = self.redact(&msg);
         let entry = format!("#{} {}", self.trace_seq, msg);
 
         if self.trace.len() >= self.trace_buffer_size {
@@ -766,8 +2125,19 @@ impl Evaluator {
     }
 
     /// Push a structured trace entry (Stage 3.2)
-    fn trace_push_entry(&mut self, entry: crate::runtime::TraceEntry) {
+    fn trace_push_entry(&mut self, mut entry: crate::runtime::TraceEntry) {
         self.trace_seq = self.trace_seq.saturating_add(1);
+        if !self.secret_values.borrow().is_empty() {
+            let secrets = self.secret_values.borrow();
+            entry.values = entry
+                .values
+                .iter()
+                .map(|v| crate::runtime::secrets::redact_value(v, &secrets))
+                .collect();
+            entry.label = entry
+                .label
+                .map(|l| crate::runtime::secrets::redact_text(&l, &secrets));
+        }
 
         // Add to structured entries
         if self.trace_entries.len() >= self.trace_buffer_size {
@@ -896,6 +2266,7 @@ impl Evaluator {
     pub fn reset_env(&mut self) {
         // Create new environment
         self.env = Rc::new(RefCell::new(Environment::new()));
+        self.globals = Rc::clone(&self.env);
 
         // Avoid leaking trace across pooled executions
         self.trace.clear();
@@ -910,10 +2281,55 @@ impl Evaluator {
         // Avoid leaking call stack across pooled executions
         self.call_stack.clear();
 
+        // Sealed names refer to the old environment
+        self.sealed_names.clear();
+
+        // Scheduled jobs hold closures over the old environment
+        self.scheduled_jobs.clear();
+        self.scheduler_started = false;
+        self.timers.clear();
+
+        // Rounding, holiday calendars, message catalogs and the locale set by scripts
+        // belong to the old session
+        #[cfg(feature = "payroll")]
+        {
+            *self.payroll_settings.borrow_mut() = Default::default();
+        }
+        *self.i18n_settings.borrow_mut() = Default::default();
+
+        // Key-value stores, export writers and streaming connections opened by scripts
+        // are closed
+        self.kv_stores.borrow_mut().clear();
+        self.export_writers.borrow_mut().clear();
+        #[cfg(feature = "async")]
+        self.stream_connections.borrow_mut().clear();
+
         // Re-register built-in functions
         Self::register_builtins_into_env(&self.registry, &mut self.env.borrow_mut());
     }
 
+    /// The global environment handle
+    pub(crate) fn global_env(&self) -> Rc<RefCell<Environment>> {
+        Rc::clone(&self.env)
+    }
+
+    /// Call a function value as a fresh execution (used by worker engines):
+    /// step and duration limits are counted from this call
+    pub(crate) fn call_value(&mut self, func: &Value, args: Vec<Value>) -> EvalResult {
+        self.reset_step_counter();
+        if self.limits.max_duration_ms.is_some() {
+            self.start_time.set(Some(std::time::Instant::now()));
+        }
+        #[cfg(feature = "payroll")]
+        let _payroll = self.scoped_payroll();
+        let _i18n = self.scoped_i18n();
+        let _kv = self.scoped_kv();
+        let _export = self.scoped_export();
+        #[cfg(feature = "async")]
+        let _connections = self.scoped_connections();
+        self.call_function(None, func, args)
+    }
+
     /// Set a global variable from the host (without requiring `eval`).
     pub fn set_global(&mut self, name: impl Into<String>, value: Value) {
         self.env.borrow_mut().set(name.into(), value);
@@ -924,6 +2340,109 @@ impl Evaluator {
         self.env.borrow().get(name)
     }
 
+    /// Seal every name currently defined in the global scope (built-ins included).
+    ///
+    /// Afterwards scripts can still read and call these names, but any attempt to
+    /// redefine them (`Set`, `Func`, index assignment, `Import` bindings) fails.
+    /// Hosts can still overwrite them via `set_global`.
+    pub fn seal_globals(&mut self) {
+        // Forked evaluators also seal the names they share with their parent
+        let mut scope = Some(Rc::clone(&self.globals));
+        while let Some(env) = scope {
+            self.sealed_names.extend(env.borrow().keys());
+            scope = env.borrow().parent();
+        }
+    }
+
+    /// Remove all seals added by `seal_globals`.
+    pub fn unseal_globals(&mut self) {
+        self.sealed_names.clear();
+    }
+
+    /// Whether a name is sealed.
+    pub fn is_sealed(&self, name: &str) -> bool {
+        self.sealed_names.contains(name)
+    }
+
+    /// Reject script-level definitions of sealed names.
+    ///
+    /// Only bindings that land in the global scope are rejected; function locals
+    /// (and module scopes) may reuse a sealed name without touching the global.
+    fn check_not_sealed(&self, name: &str) -> Result<(), RuntimeError> {
+        if self.sealed_names.contains(name) && Rc::ptr_eq(&self.env, &self.globals) {
+            return Err(RuntimeError::InvalidOperation(format!(
+                "Cannot redefine sealed global '{}'",
+                name
+            )));
+        }
+        Ok(())
+    }
+
+    /// Check a name before binding it (sealed globals; builtins in strict mode)
+    ///
+    /// Modules run in their own environment, so a module may redefine a builtin
+    /// (as the stdlib does with SORT) without affecting the importing script; locals
+    /// of stdlib functions (such as `PI` in the sorting helpers) are not checked either.
+    fn check_binding(&self, name: &str) -> Result<(), RuntimeError> {
+        self.check_not_sealed(name)?;
+        if self.strict && self.registry.has(name) && !self.in_library_code() {
+            return Err(RuntimeError::InvalidOperation(format!(
+                "Strict mode: '{}' shadows a built-in function",
+                name
+            )));
+        }
+        Ok(())
+    }
+
+    /// Whether the running code belongs to the standard library or a module being imported,
+    /// rather than to the script itself.
+    ///
+    /// Library code is recognised by where it was defined, not by name: functions the
+    /// standard library defines capture a library scope (see `closure_env`), so a script
+    /// function that happens to share a stdlib name is still the script's code.
+    fn in_library_code(&self) -> bool {
+        self.loading_library || !self.module_stack.is_empty() || self.env.borrow().is_library()
+    }
+
+    /// Environment captured by a function or lambda defined here
+    ///
+    /// While standard library source is loaded it is wrapped in a library scope that
+    /// marks the function, and everything it runs, as library code.
+    fn closure_env(&self) -> Rc<RefCell<Environment>> {
+        let stdlib_module = self
+            .module_stack
+            .last()
+            .is_some_and(|module| module.starts_with(STDLIB_SPECIFIER_PREFIX));
+        if self.loading_library || stdlib_module {
+            Rc::new(RefCell::new(Environment::library(Rc::clone(&self.env))))
+        } else {
+            Rc::clone(&self.env)
+        }
+    }
+
+    /// Truthiness of a condition; strict mode only accepts Boolean values
+    pub(crate) fn condition(&self, value: &Value, construct: &str) -> Result<bool, RuntimeError> {
+        match value {
+            Value::Boolean(b) => Ok(*b),
+            other if self.strict => Err(RuntimeError::TypeError(format!(
+                "Strict mode: {} condition must be Boolean, got {}",
+                construct,
+                other.type_name()
+            ))),
+            other => Ok(other.is_truthy()),
+        }
+    }
+
+    /// List user-defined variables visible from the current scope (built-ins are skipped).
+    pub fn variables(&self) -> Vec<VariableInfo> {
+        self.env.borrow().visible_variables()
+    }
+
+    /// List user-defined variables in the global (outermost) scope.
+    pub fn global_variables(&self) -> Vec<VariableInfo> {
+        self.globals.borrow().visible_variables()
+    }
+
     /// Enter a child scope (new environment whose parent is the current env).
     ///
     /// Returns the previous environment handle; pass it back to `restore_env()`.
@@ -941,29 +2460,81 @@ impl Evaluator {
 
     /// Evaluate a program
     pub fn eval_program(&mut self, program: &Program) -> EvalResult {
+        self.eval_statements(program.iter())
+    }
+
+    /// Evaluate a program held in the AST cache (statements shared with other cached programs)
+    pub fn eval_shared(&mut self, program: &crate::cache::SharedProgram) -> EvalResult {
+        self.eval_statements(program.iter())
+    }
+
+    fn eval_statements<'a>(&mut self, program: impl Iterator<Item = &'a Stmt>) -> EvalResult {
         // Record start time for timeout checking
         if self.limits.max_duration_ms.is_some() {
             self.start_time.set(Some(std::time::Instant::now()));
         }
 
-        let mut result = Value::Null;
-
-        for stmt in program {
-            result = self.eval_statement(stmt)?;
+        // Make display options visible to builtins for the duration of the program
+        let mut display = self.display_options.clone();
+        if self.deterministic.is_some() {
+            display.sort_dict_keys = true;
+        }
+        let _display = crate::runtime::ScopedDisplayOptions::set(display);
+        let _secrets = self.scoped_secrets();
+        #[cfg(feature = "payroll")]
+        let _payroll = self.scoped_payroll();
+        let _i18n = self.scoped_i18n();
+        let _kv = self.scoped_kv();
+        let _export = self.scoped_export();
+        #[cfg(feature = "async")]
+        let _connections = self.scoped_connections();
+
+        // A `#language` pragma only applies to the program that contains it
+        let outer_language = self.script_language.take();
+        // Loops around an imported module do not extend into it
+        let outer_loops = std::mem::take(&mut self.loop_depth);
+        let mut result = Ok(Value::Null);
+        // Recording needs per-statement hooks, which only the tree walker provides
+        if self.backend == crate::compile::Backend::Bytecode && self.recording.is_none() {
+            let chunk = crate::compile::compile(program);
+            result = crate::compile::run(self, &chunk);
+        } else {
+            for stmt in program {
+                result = self.eval_statement(stmt);
+                if result.is_err() {
+                    break;
+                }
+            }
         }
+        self.script_language = outer_language;
+        self.loop_depth = outer_loops;
 
-        Ok(result)
+        result
     }
 
     /// Evaluate a statement
     pub fn eval_statement(&mut self, stmt: &Stmt) -> EvalResult {
+        if self.recording.is_some() {
+            return self.eval_statement_recorded(stmt);
+        }
+        self.exec_statement(stmt)
+    }
+
+    fn exec_statement(&mut self, stmt: &Stmt) -> EvalResult {
+        if let Stmt::Located { span, stmt } = stmt {
+            self.current_line.set(span.line);
+            return self.exec_statement(stmt).map_err(|e| e.located(*span));
+        }
+
         // Check execution limits before each statement
         self.eval_step()?;
         self.check_timeout()?;
 
         match stmt {
             Stmt::Set { name, value } => {
+                self.check_binding(name)?;
                 let val = self.eval_expression(value)?;
+                self.check_memory(&val)?;
                 self.env.borrow_mut().set(name.clone(), val.clone());
                 Ok(val)
             }
@@ -978,6 +2549,7 @@ impl Evaluator {
 
                 // For simple identifier objects, we can modify in place
                 if let Expr::Identifier(name) = object.as_ref() {
+                    self.check_not_sealed(name)?;
                     // Get the object from environment
                     let obj = self
                         .env
@@ -985,8 +2557,9 @@ impl Evaluator {
                         .get(name)
                         .ok_or_else(|| RuntimeError::UndefinedVariable(name.clone()))?;
 
-                    // Evaluate the index
-                    let idx_val = self.eval_expression(index)?;
+                    // Evaluate the index (integer indices behave like numeric ones)
+                    let mut idx_val = self.eval_expression(index)?;
+                    idx_val.widen_integers();
 
                     // Modify based on object type
                     let new_obj = match (obj, idx_val) {
@@ -1016,6 +2589,7 @@ impl Evaluator {
                     };
 
                     // Update the variable in environment
+                    self.check_memory(&new_obj)?;
                     self.env.borrow_mut().set(name.clone(), new_obj);
                     Ok(val)
                 } else {
@@ -1028,21 +2602,23 @@ impl Evaluator {
             }
 
             Stmt::FuncDef { name, params, body } => {
+                self.check_binding(name)?;
                 let func = Value::Function {
                     name: Some(name.clone()),
                     params: params.clone(),
                     body: body.clone(),
-                    env: Rc::clone(&self.env),
+                    env: self.closure_env(),
                 };
                 self.env.borrow_mut().set(name.clone(), func.clone());
                 Ok(func)
             }
 
             Stmt::GeneratorDef { name, params, body } => {
+                self.check_binding(name)?;
                 let r#gen = Value::Generator {
                     params: params.clone(),
                     body: body.clone(),
-                    env: Rc::clone(&self.env),
+                    env: self.closure_env(),
                     state: GeneratorState::NotStarted,
                 };
                 self.env.borrow_mut().set(name.clone(), r#gen.clone());
@@ -1050,6 +2626,7 @@ impl Evaluator {
             }
 
             Stmt::LazyDef { name, expr } => {
+                self.check_binding(name)?;
                 let lazy = Value::Lazy {
                     expr: expr.clone(),
                     env: Rc::clone(&self.env),
@@ -1069,33 +2646,29 @@ impl Evaluator {
                 Err(RuntimeError::Yield(val))
             }
 
+            Stmt::Break if self.loop_depth == 0 => Err(Self::outside_loop("Break")),
             Stmt::Break => Err(RuntimeError::Break),
 
+            Stmt::Continue if self.loop_depth == 0 => Err(Self::outside_loop("Continue")),
             Stmt::Continue => Err(RuntimeError::Continue),
 
             Stmt::While { condition, body } => {
                 let mut result = Value::Null;
+                let mut iterations = 0;
 
                 loop {
+                    // Each condition check counts as a step, so a loop with an empty
+                    // body still runs into the step and time limits
+                    self.eval_step()?;
+                    self.check_timeout()?;
                     let cond = self.eval_expression(condition)?;
-                    if !cond.is_truthy() {
+                    if !self.condition(&cond, "While")? {
                         break;
                     }
+                    iterations += 1;
+                    self.check_loop_iteration(iterations, stmt)?;
 
-                    let mut should_break = false;
-                    for stmt in body {
-                        match self.eval_statement(stmt) {
-                            Ok(val) => result = val,
-                            Err(RuntimeError::Break) => {
-                                should_break = true;
-                                break;
-                            }
-                            Err(RuntimeError::Continue) => break,
-                            Err(e) => return Err(e),
-                        }
-                    }
-
-                    if should_break {
+                    if self.eval_loop_body(body, &mut result)? {
                         break;
                     }
                 }
@@ -1108,26 +2681,22 @@ impl Evaluator {
                 iterable,
                 body,
             } => {
+                self.check_binding(var)?;
                 let iter_val = self.eval_expression(iterable)?;
+                // Sets iterate in their deterministic (sorted) order, tables by row
+                let iter_val = match iter_val {
+                    Value::Set(set) => Value::Array(crate::value::set_elements(&set)),
+                    Value::Table(table) => Value::Array(table.to_dicts()),
+                    other => other,
+                };
                 let mut result = Value::Null;
 
                 match iter_val {
                     Value::Array(arr) => {
-                        let mut should_break = false;
-                        for item in arr {
+                        for (i, item) in arr.into_iter().enumerate() {
+                            self.check_loop_iteration(i + 1, stmt)?;
                             self.env.borrow_mut().set(var.clone(), item);
-                            for stmt in body {
-                                match self.eval_statement(stmt) {
-                                    Ok(val) => result = val,
-                                    Err(RuntimeError::Break) => {
-                                        should_break = true;
-                                        break;
-                                    }
-                                    Err(RuntimeError::Continue) => break,
-                                    Err(e) => return Err(e),
-                                }
-                            }
-                            if should_break {
+                            if self.eval_loop_body(body, &mut result)? {
                                 break;
                             }
                         }
@@ -1149,29 +2718,20 @@ impl Evaluator {
                 iterable,
                 body,
             } => {
+                self.check_binding(index_var)?;
+                self.check_binding(value_var)?;
                 let iter_val = self.eval_expression(iterable)?;
                 let mut result = Value::Null;
 
                 match iter_val {
                     Value::Array(arr) => {
-                        let mut should_break = false;
                         for (idx, item) in arr.iter().enumerate() {
+                            self.check_loop_iteration(idx + 1, stmt)?;
                             self.env
                                 .borrow_mut()
-                                .set(index_var.clone(), Value::Number(idx as f64));
+                                .set(index_var.clone(), Value::Int(idx as i64));
                             self.env.borrow_mut().set(value_var.clone(), item.clone());
-                            for stmt in body {
-                                match self.eval_statement(stmt) {
-                                    Ok(val) => result = val,
-                                    Err(RuntimeError::Break) => {
-                                        should_break = true;
-                                        break;
-                                    }
-                                    Err(RuntimeError::Continue) => break,
-                                    Err(e) => return Err(e),
-                                }
-                            }
-                            if should_break {
+                            if self.eval_loop_body(body, &mut result)? {
                                 break;
                             }
                         }
@@ -1225,12 +2785,45 @@ impl Evaluator {
 
             Stmt::Export(name) => self.eval_export(name),
 
+            Stmt::Pragma { name, value } => {
+                self.apply_pragma(name, value)?;
+                Ok(Value::Null)
+            }
+
             Stmt::Throw(expr) => {
                 let val = self.eval_expression(expr)?;
                 Err(RuntimeError::Throw(val))
             }
 
+            Stmt::Try {
+                body,
+                catch,
+                finally,
+            } => {
+                let mut outcome = self.eval_block(body);
+
+                if let Some((var, handler)) = catch
+                    && let Err(err) = &outcome
+                    && let Some(caught) = Self::caught_value(err)
+                {
+                    // A rejected binding is the new outcome; Finally still runs
+                    outcome = self.check_binding(var).and_then(|()| {
+                        self.env.borrow_mut().set(var.clone(), caught);
+                        self.eval_block(handler)
+                    });
+                }
+
+                // Finally always runs; an error raised there replaces the pending outcome
+                if let Some(cleanup) = finally {
+                    self.eval_block(cleanup)?;
+                }
+
+                outcome
+            }
+
             Stmt::Expression(expr) => self.eval_expression(expr),
+
+            Stmt::Located { .. } => unreachable!("located statements are unwrapped above"),
         }
     }
 
@@ -1238,6 +2831,7 @@ impl Evaluator {
     pub fn eval_expression(&mut self, expr: &Expr) -> EvalResult {
         match expr {
             Expr::Number(n) => Ok(Value::Number(*n)),
+            Expr::Integer(n) => Ok(Value::Int(*n)),
 
             Expr::BigInteger(s) => {
                 // 将大整数字符串转换为 Fraction (分母为1的分数)
@@ -1253,17 +2847,13 @@ impl Evaluator {
                 }
             }
 
-            Expr::String(s) => Ok(Value::String(s.clone())),
+            Expr::String(s) => Ok(Value::String(s.to_string())),
 
             Expr::Boolean(b) => Ok(Value::Boolean(*b)),
 
             Expr::Null => Ok(Value::Null),
 
-            Expr::Identifier(name) => self
-                .env
-                .borrow()
-                .get(name)
-                .ok_or_else(|| RuntimeError::UndefinedVariable(name.clone())),
+            Expr::Identifier(name) => self.lookup(name),
 
             Expr::Binary { left, op, right } => {
                 // Short-circuit evaluation for And and Or
@@ -1292,7 +2882,7 @@ impl Evaluator {
                     _ => {
                         let left_val = self.eval_expression(left)?;
                         let right_val = self.eval_expression(right)?;
-                        self.eval_binary_op(&left_val, op, &right_val)
+                        self.binary(&left_val, op, &right_val)
                     }
                 }
             }
@@ -1333,40 +2923,7 @@ impl Evaluator {
             Expr::Index { object, index } => {
                 let obj_val = self.eval_expression(object)?;
                 let idx_val = self.eval_expression(index)?;
-
-                match (obj_val, idx_val) {
-                    (Value::Array(arr), Value::Number(n)) => {
-                        let idx = n as usize;
-                        arr.get(idx).cloned().ok_or_else(|| {
-                            RuntimeError::InvalidOperation(format!("Index {} out of bounds", idx))
-                        })
-                    }
-                    (Value::String(s), Value::Number(n)) => {
-                        let idx = n as usize;
-                        let chars: Vec<char> = s.chars().collect();
-                        chars
-                            .get(idx)
-                            .cloned()
-                            .map(|ch| Value::String(ch.to_string()))
-                            .ok_or_else(|| {
-                                RuntimeError::InvalidOperation(format!(
-                                    "Index {} out of bounds (string length: {})",
-                                    idx,
-                                    chars.len()
-                                ))
-                            })
-                    }
-                    (Value::Dict(dict), Value::String(key)) => {
-                        dict.get(&key).cloned().ok_or_else(|| {
-                            RuntimeError::InvalidOperation(format!("Key '{}' not found", key))
-                        })
-                    }
-                    (obj, idx) => Err(RuntimeError::TypeError(format!(
-                        "Cannot index {} with {}",
-                        obj.type_name(),
-                        idx.type_name()
-                    ))),
-                }
+                Self::index_value(obj_val, idx_val)
             }
 
             Expr::If {
@@ -1377,7 +2934,7 @@ impl Evaluator {
             } => {
                 let cond = self.eval_expression(condition)?;
 
-                if cond.is_truthy() {
+                if self.condition(&cond, "If")? {
                     let mut result = Value::Null;
                     for stmt in then_branch {
                         result = self.eval_statement(stmt)?;
@@ -1387,7 +2944,7 @@ impl Evaluator {
 
                 for (elif_cond, elif_body) in elif_branches {
                     let cond = self.eval_expression(elif_cond)?;
-                    if cond.is_truthy() {
+                    if self.condition(&cond, "Elif")? {
                         let mut result = Value::Null;
                         for stmt in elif_body {
                             result = self.eval_statement(stmt)?;
@@ -1404,33 +2961,197 @@ impl Evaluator {
                     return Ok(result);
                 }
 
-                Ok(Value::Null)
-            }
+                Ok(Value::Null)
+            }
+
+            Expr::Lambda { params, body } => {
+                // Create a closure by capturing the current environment
+                Ok(Value::Function {
+                    name: None,
+                    params: params.clone(),
+                    body: body.clone(),
+                    env: self.closure_env(),
+                })
+            }
+
+            Expr::Extension { tag, payload } => {
+                if self.language() < crate::runtime::LanguageVersion::V1_1 {
+                    return Err(RuntimeError::InvalidOperation(format!(
+                        "Extension blocks ('@{}') require language 1.1 (script targets {})",
+                        tag,
+                        self.language()
+                    )));
+                }
+                let handler = self.extension_handlers.get(tag).cloned().ok_or_else(|| {
+                    RuntimeError::CustomError(format!(
+                        "No handler registered for extension '@{}'",
+                        tag
+                    ))
+                })?;
+                let context = crate::runtime::ExtensionContext::new(tag, Rc::clone(&self.env));
+                handler(payload, &context)
+                    .map_err(|message| RuntimeError::CustomError(format!("@{}: {}", tag, message)))
+            }
+        }
+    }
+
+    /// Value bound to `name`, falling back to builtins reached through aliases or case folding
+    pub(crate) fn lookup(&mut self, name: &str) -> EvalResult {
+        let found = self.env.borrow().get(name);
+        match found {
+            Some(val) => Ok(val),
+            None => self
+                .resolve_builtin_name(name)
+                .ok_or_else(|| RuntimeError::UndefinedVariable(name.to_string())),
+        }
+    }
+
+    /// Bind `name` in the current scope as `Set` does
+    pub(crate) fn assign(&mut self, name: &str, value: Value) -> Result<(), RuntimeError> {
+        self.check_binding(name)?;
+        self.check_memory(&value)?;
+        self.env.borrow_mut().set_by_ref(name, value);
+        Ok(())
+    }
+
+    /// Bind a loop variable in the current scope (sealed and strict-mode checks, no memory check)
+    pub(crate) fn set_var(&mut self, name: &str, value: Value) -> Result<(), RuntimeError> {
+        self.check_binding(name)?;
+        self.env.borrow_mut().set_by_ref(name, value);
+        Ok(())
+    }
+
+    /// Apply a non-short-circuit binary operator, warning when precision is lost
+    pub(crate) fn binary(&mut self, left: &Value, op: &BinOp, right: &Value) -> EvalResult {
+        let result = self.eval_binary_op(left, op, right)?;
+        self.check_precision(left, op, right, &result);
+        Ok(result)
+    }
 
-            Expr::Lambda { params, body } => {
-                // Create a closure by capturing the current environment
-                Ok(Value::Function {
-                    name: None,
-                    params: params.clone(),
-                    body: body.clone(),
-                    env: Rc::clone(&self.env),
+    /// Read `obj[idx]` (array/string position, dict key, table column or row)
+    pub(crate) fn index_value(obj_val: Value, mut idx_val: Value) -> EvalResult {
+        // Integer indices behave like numeric ones
+        idx_val.widen_integers();
+
+        match (obj_val, idx_val) {
+            (Value::Array(arr), Value::Number(n)) => {
+                let idx = n as usize;
+                arr.get(idx).cloned().ok_or_else(|| {
+                    RuntimeError::InvalidOperation(format!("Index {} out of bounds", idx))
+                })
+            }
+            (Value::String(s), Value::Number(n)) => {
+                let idx = n as usize;
+                let chars: Vec<char> = s.chars().collect();
+                chars
+                    .get(idx)
+                    .cloned()
+                    .map(|ch| Value::String(ch.to_string()))
+                    .ok_or_else(|| {
+                        RuntimeError::InvalidOperation(format!(
+                            "Index {} out of bounds (string length: {})",
+                            idx,
+                            chars.len()
+                        ))
+                    })
+            }
+            (Value::Dict(dict), Value::String(key)) => dict
+                .get(&key)
+                .cloned()
+                .ok_or_else(|| RuntimeError::InvalidOperation(format!("Key '{}' not found", key))),
+            // 表格按列名取列，按行号取行
+            (Value::Table(table), Value::String(column)) => table
+                .column(&column)
+                .map(|values| Value::Array(values.to_vec()))
+                .ok_or_else(|| {
+                    RuntimeError::InvalidOperation(format!("Column '{}' not found", column))
+                }),
+            (Value::Table(table), Value::Number(n)) => {
+                let idx = n as usize;
+                table.row(idx).ok_or_else(|| {
+                    RuntimeError::InvalidOperation(format!("Row {} out of bounds", idx))
                 })
             }
+            (obj, idx) => Err(RuntimeError::TypeError(format!(
+                "Cannot index {} with {}",
+                obj.type_name(),
+                idx.type_name()
+            ))),
+        }
+    }
+
+    /// Warn when arithmetic silently loses precision
+    fn check_precision(&mut self, left: &Value, op: &BinOp, right: &Value, result: &Value) {
+        const MAX_SAFE_INTEGER: f64 = 9_007_199_254_740_992.0; // 2^53
+        if !matches!(
+            op,
+            BinOp::Add | BinOp::Subtract | BinOp::Multiply | BinOp::Divide | BinOp::Modulo
+        ) {
+            return;
+        }
+        let Value::Number(n) = result else {
+            return;
+        };
+        match (left, right) {
+            (Value::Fraction(_), Value::Number(_)) | (Value::Number(_), Value::Fraction(_)) => {
+                self.warn(
+                    crate::runtime::WarningKind::Precision,
+                    format!(
+                        "Fraction converted to a floating-point Number in '{}'; the exact value is lost",
+                        op
+                    ),
+                );
+            }
+            (Value::Number(_) | Value::Int(_), Value::Number(_) | Value::Int(_))
+                if n.is_finite() && n.abs() > MAX_SAFE_INTEGER =>
+            {
+                self.warn(
+                    crate::runtime::WarningKind::Precision,
+                    format!(
+                        "Result of '{}' exceeds 2^53 and may be inexact; use big integer literals for exact arithmetic",
+                        op
+                    ),
+                );
+            }
+            _ => {}
         }
     }
 
     /// Evaluate binary operation
     fn eval_binary_op(&self, left: &Value, op: &BinOp, right: &Value) -> EvalResult {
+        let arithmetic = matches!(
+            op,
+            BinOp::Add | BinOp::Subtract | BinOp::Multiply | BinOp::Divide | BinOp::Modulo
+        );
+        match (left, right) {
+            (Value::Int(a), Value::Int(b)) if arithmetic => {
+                return Self::eval_integer_op(*a, op, *b);
+            }
+            // Mixed with an Integer: the Integer takes the other operand's type
+            (Value::Int(i), other @ (Value::Number(_) | Value::Fraction(_)))
+            | (other @ (Value::Number(_) | Value::Fraction(_)), Value::Int(i))
+                if arithmetic =>
+            {
+                let promoted = match other {
+                    Value::Fraction(_) => Value::Fraction(crate::value::int_ratio(*i)),
+                    _ => Value::Number(*i as f64),
+                };
+                return if matches!(left, Value::Int(_)) {
+                    self.eval_binary_op(&promoted, op, right)
+                } else {
+                    self.eval_binary_op(left, op, &promoted)
+                };
+            }
+            _ => {}
+        }
+
         match op {
             BinOp::Add => match (left, right) {
                 (Value::Number(a), Value::Number(b)) => Ok(Value::Number(a + b)),
                 (Value::String(a), Value::String(b)) => Ok(Value::String(format!("{}{}", a, b))),
                 (Value::Fraction(a), Value::Fraction(b)) => Ok(Value::Fraction(a + b)),
                 (Value::Number(a), Value::Fraction(b)) | (Value::Fraction(b), Value::Number(a)) => {
-                    use num_bigint::BigInt;
-                    use num_rational::Ratio;
-                    if a.fract() == 0.0 {
-                        let a_frac = Ratio::new(BigInt::from(*a as i64), BigInt::from(1));
+                    if let Some(a_frac) = crate::value::integer_ratio(*a) {
                         Ok(Value::Fraction(a_frac + b))
                     } else {
                         // 浮点数和分数混合运算，转换为浮点数
@@ -1451,10 +3172,7 @@ impl Evaluator {
                 (Value::Number(a), Value::Number(b)) => Ok(Value::Number(a - b)),
                 (Value::Fraction(a), Value::Fraction(b)) => Ok(Value::Fraction(a - b)),
                 (Value::Number(a), Value::Fraction(b)) => {
-                    use num_bigint::BigInt;
-                    use num_rational::Ratio;
-                    if a.fract() == 0.0 {
-                        let a_frac = Ratio::new(BigInt::from(*a as i64), BigInt::from(1));
+                    if let Some(a_frac) = crate::value::integer_ratio(*a) {
                         Ok(Value::Fraction(a_frac - b))
                     } else {
                         use num_traits::ToPrimitive;
@@ -1464,10 +3182,7 @@ impl Evaluator {
                     }
                 }
                 (Value::Fraction(a), Value::Number(b)) => {
-                    use num_bigint::BigInt;
-                    use num_rational::Ratio;
-                    if b.fract() == 0.0 {
-                        let b_frac = Ratio::new(BigInt::from(*b as i64), BigInt::from(1));
+                    if let Some(b_frac) = crate::value::integer_ratio(*b) {
                         Ok(Value::Fraction(a - b_frac))
                     } else {
                         use num_traits::ToPrimitive;
@@ -1511,10 +3226,7 @@ impl Evaluator {
                 }
                 (Value::Fraction(a), Value::Fraction(b)) => Ok(Value::Fraction(a * b)),
                 (Value::Number(a), Value::Fraction(b)) | (Value::Fraction(b), Value::Number(a)) => {
-                    use num_bigint::BigInt;
-                    use num_rational::Ratio;
-                    if a.fract() == 0.0 {
-                        let a_frac = Ratio::new(BigInt::from(*a as i64), BigInt::from(1));
+                    if let Some(a_frac) = crate::value::integer_ratio(*a) {
                         Ok(Value::Fraction(a_frac * b))
                     } else {
                         Err(RuntimeError::TypeError(
@@ -1546,13 +3258,10 @@ impl Evaluator {
                     }
                 }
                 (Value::Number(a), Value::Fraction(b)) => {
-                    use num_bigint::BigInt;
-                    use num_rational::Ratio;
                     use num_traits::Zero;
                     if b.is_zero() {
                         Err(RuntimeError::DivisionByZero)
-                    } else if a.fract() == 0.0 {
-                        let a_frac = Ratio::new(BigInt::from(*a as i64), BigInt::from(1));
+                    } else if let Some(a_frac) = crate::value::integer_ratio(*a) {
                         Ok(Value::Fraction(a_frac / b))
                     } else {
                         use num_traits::ToPrimitive;
@@ -1562,12 +3271,9 @@ impl Evaluator {
                     }
                 }
                 (Value::Fraction(a), Value::Number(b)) => {
-                    use num_bigint::BigInt;
-                    use num_rational::Ratio;
                     if *b == 0.0 {
                         Err(RuntimeError::DivisionByZero)
-                    } else if b.fract() == 0.0 {
-                        let b_frac = Ratio::new(BigInt::from(*b as i64), BigInt::from(1));
+                    } else if let Some(b_frac) = crate::value::integer_ratio(*b) {
                         Ok(Value::Fraction(a / b_frac))
                     } else {
                         use num_traits::ToPrimitive;
@@ -1591,6 +3297,47 @@ impl Evaluator {
                         Ok(Value::Number(a % b))
                     }
                 }
+                (Value::Fraction(_), _) | (_, Value::Fraction(_)) => {
+                    use num_traits::{ToPrimitive, Zero};
+                    let exact = |v: &Value| match v {
+                        Value::Fraction(f) => Some(f.clone()),
+                        Value::Number(n) => crate::value::integer_ratio(*n),
+                        _ => None,
+                    };
+                    match (exact(left), exact(right)) {
+                        (Some(a), Some(b)) => {
+                            if b.is_zero() {
+                                return Err(RuntimeError::DivisionByZero);
+                            }
+                            // 与 Number 的 % 一致：余数符号与被除数相同
+                            let quotient = (&a / &b).trunc();
+                            Ok(Value::Fraction(a - b * quotient))
+                        }
+                        _ => match (left, right) {
+                            (
+                                Value::Number(_) | Value::Fraction(_),
+                                Value::Number(_) | Value::Fraction(_),
+                            ) => {
+                                let to_f64 = |v: &Value| match v {
+                                    Value::Number(n) => *n,
+                                    Value::Fraction(f) => f.to_f64().unwrap_or(f64::NAN),
+                                    _ => f64::NAN,
+                                };
+                                let b = to_f64(right);
+                                if b == 0.0 {
+                                    Err(RuntimeError::DivisionByZero)
+                                } else {
+                                    Ok(Value::Number(to_f64(left) % b))
+                                }
+                            }
+                            _ => Err(RuntimeError::TypeError(format!(
+                                "Cannot modulo {} by {}",
+                                left.type_name(),
+                                right.type_name()
+                            ))),
+                        },
+                    }
+                }
                 _ => Err(RuntimeError::TypeError(format!(
                     "Cannot modulo {} by {}",
                     left.type_name(),
@@ -1656,11 +3403,44 @@ impl Evaluator {
         }
     }
 
+    /// Integer arithmetic
+    ///
+    /// Results stay Integers while they fit in i64 and become exact big integers
+    /// (Fractions) beyond that. Division yields an Integer only when it is exact.
+    fn eval_integer_op(a: i64, op: &BinOp, b: i64) -> EvalResult {
+        use crate::value::int_ratio;
+        use num_bigint::BigInt;
+        use num_rational::Ratio;
+        let exact = |result: Option<i64>,
+                     wide: fn(Ratio<BigInt>, Ratio<BigInt>) -> Ratio<BigInt>| {
+            result.map_or_else(
+                || Value::Fraction(wide(int_ratio(a), int_ratio(b))),
+                Value::Int,
+            )
+        };
+        match op {
+            BinOp::Add => Ok(exact(a.checked_add(b), |x, y| x + y)),
+            BinOp::Subtract => Ok(exact(a.checked_sub(b), |x, y| x - y)),
+            BinOp::Multiply => Ok(exact(a.checked_mul(b), |x, y| x * y)),
+            BinOp::Divide if b == 0 => Err(RuntimeError::DivisionByZero),
+            BinOp::Divide if a.wrapping_rem(b) == 0 => Ok(exact(a.checked_div(b), |x, y| x / y)),
+            BinOp::Divide => Ok(Value::Number(a as f64 / b as f64)),
+            BinOp::Modulo if b == 0 => Err(RuntimeError::DivisionByZero),
+            // Remainder has the sign of the dividend, like Number's %
+            BinOp::Modulo => Ok(Value::Int(a.wrapping_rem(b))),
+            _ => unreachable!("eval_integer_op called with non-arithmetic operator {}", op),
+        }
+    }
+
     /// Evaluate unary operation
-    fn eval_unary_op(&self, op: &UnaryOp, val: &Value) -> EvalResult {
+    pub(crate) fn eval_unary_op(&self, op: &UnaryOp, val: &Value) -> EvalResult {
         match op {
             UnaryOp::Minus => match val {
                 Value::Number(n) => Ok(Value::Number(-n)),
+                Value::Int(i) => Ok(i
+                    .checked_neg()
+                    .map_or_else(|| Value::Fraction(-crate::value::int_ratio(*i)), Value::Int)),
+                Value::Fraction(f) => Ok(Value::Fraction(-f)),
                 _ => Err(RuntimeError::TypeError(format!(
                     "Cannot negate {}",
                     val.type_name()
@@ -1671,8 +3451,23 @@ impl Evaluator {
         }
     }
 
-    /// Call a function with arguments
-    fn call_function(
+    /// Call a function with arguments, checking the result against the memory limits
+    pub(crate) fn call_function(
+        &mut self,
+        name_hint: Option<&str>,
+        func: &Value,
+        args: Vec<Value>,
+    ) -> EvalResult {
+        // The callee's statements move the current line; later calls made by the
+        // same statement must still report its line as their call site
+        let line = self.current_line.get();
+        let result = self.dispatch_call(name_hint, func, args)?;
+        self.current_line.set(line);
+        self.check_memory(&result)?;
+        Ok(result)
+    }
+
+    fn dispatch_call(
         &mut self,
         name_hint: Option<&str>,
         func: &Value,
@@ -1681,6 +3476,15 @@ impl Evaluator {
         // Check recursion depth limit
         self.enter_call()?;
 
+        if self.profile_calls
+            && let Value::Function {
+                name: Some(name), ..
+            } = func
+        {
+            *self.function_calls.entry(name.clone()).or_default() += 1;
+        }
+
+        let line = Some(self.current_line.get()).filter(|&line| line > 0);
         let frame = match func {
             Value::Function { name, params, .. } => {
                 let display_name = name_hint
@@ -1691,10 +3495,11 @@ impl Evaluator {
                 CallFrame {
                     name: display_name.clone(),
                     signature,
+                    line,
                 }
             }
             Value::BuiltIn { name, .. } => {
-                let arity = self.registry.get(name).map(|(_, a)| a).unwrap_or(0);
+                let arity = self.registry.arity(name).unwrap_or(0);
                 let params = if arity == 0 {
                     String::new()
                 } else {
@@ -1707,12 +3512,17 @@ impl Evaluator {
                 CallFrame {
                     name: name.clone(),
                     signature,
+                    line,
                 }
             }
             other => {
                 let name = name_hint.unwrap_or("<call>").to_string();
                 let signature = format!("{}(<{}>)", name, other.type_name());
-                CallFrame { name, signature }
+                CallFrame {
+                    name,
+                    signature,
+                    line,
+                }
             }
         };
 
@@ -1723,7 +3533,14 @@ impl Evaluator {
                 params, body, env, ..
             } => {
                 if params.len() != args.len() {
-                    let err = RuntimeError::WrongArity {
+                    let callee = self
+                        .call_stack
+                        .last()
+                        .map(|fr| fr.name.clone())
+                        .unwrap_or_default();
+                    let err = RuntimeError::WrongArityDetailed {
+                        signature: format!("{}({})", callee, params.join(", ")),
+                        callee,
                         expected: params.len(),
                         got: args.len(),
                     };
@@ -1744,6 +3561,8 @@ impl Evaluator {
                 // Execute function body
                 let prev_env = Rc::clone(&self.env);
                 self.env = func_env;
+                // Loops around the call site cannot be left from inside the function
+                let outer_loops = std::mem::take(&mut self.loop_depth);
 
                 let mut result = Value::Null;
                 for stmt in body {
@@ -1755,6 +3574,7 @@ impl Evaluator {
                         }
                         Err(e) => {
                             self.env = prev_env;
+                            self.loop_depth = outer_loops;
                             let e = self.attach_call_stack_if_absent(e);
                             let _ = self.call_stack.pop();
                             self.exit_call();
@@ -1764,21 +3584,33 @@ impl Evaluator {
                 }
 
                 self.env = prev_env;
+                self.loop_depth = outer_loops;
                 let _ = self.call_stack.pop();
                 self.exit_call();
                 Ok(result)
             }
 
             Value::BuiltIn { name, .. } => {
+                if let Err(err) = self.check_builtin_status(name) {
+                    let err = self.attach_call_stack_if_absent(err);
+                    let _ = self.call_stack.pop();
+                    self.exit_call();
+                    return Err(err);
+                }
+
+                // Builtins that predate Integer receive equal Numbers instead
+                let mut args = args;
+                if !self.registry.accepts_integers(name) {
+                    args.iter_mut().for_each(Value::widen_integers);
+                }
+
                 // Special handling for TRACE functions
                 let res = match name.as_str() {
+                    _ if self.replays_io(name) => self.replay_io(name, &args),
                     "TRACE" => {
                         if args.is_empty() {
                             return {
-                                let err = RuntimeError::WrongArity {
-                                    expected: 1,
-                                    got: 0,
-                                };
+                                let err = self.builtin_arity_error(name, 1, 0);
                                 let err = self.attach_call_stack_if_absent(err);
                                 let _ = self.call_stack.pop();
                                 self.exit_call();
@@ -1816,10 +3648,7 @@ impl Evaluator {
                         // Usage: TRACE_DEBUG("category", value1, value2, ...)
                         if args.len() < 2 {
                             return {
-                                let err = RuntimeError::WrongArity {
-                                    expected: 2,
-                                    got: args.len(),
-                                };
+                                let err = self.builtin_arity_error(name, 2, args.len());
                                 let err = self.attach_call_stack_if_absent(err);
                                 let _ = self.call_stack.pop();
                                 self.exit_call();
@@ -1862,12 +3691,77 @@ impl Evaluator {
 
                         Ok(Value::Null)
                     }
+                    "VARS" | "GLOBALS" => {
+                        let vars = if name == "VARS" {
+                            self.variables()
+                        } else {
+                            self.global_variables()
+                        };
+                        Ok(Value::Dict(
+                            vars.into_iter()
+                                .map(|v| (v.name, Value::String(v.type_name)))
+                                .collect(),
+                        ))
+                    }
                     "MAP" => self.builtin_map(&args),
                     "FILTER" => self.builtin_filter(&args),
+                    "WHERE"
+                        if matches!(
+                            args.get(1),
+                            Some(Value::Function { .. } | Value::BuiltIn { .. })
+                        ) =>
+                    {
+                        self.builtin_where(&args)
+                    }
+                    "ROLLING"
+                        if matches!(
+                            args.get(2),
+                            Some(Value::Function { .. } | Value::BuiltIn { .. })
+                        ) =>
+                    {
+                        self.builtin_rolling(&args)
+                    }
                     "REDUCE" => self.builtin_reduce(&args),
+                    "SCHEDULE" => self.builtin_schedule(&args),
+                    "UNSCHEDULE" => self.builtin_unschedule(&args),
+                    "RUN_SCHEDULER" => self.builtin_run_scheduler(&args),
+                    "SET_TIMEOUT" | "SET_INTERVAL" | "DEBOUNCE" => {
+                        self.builtin_set_timer(name, &args)
+                    }
+                    "CLEAR_TIMER" => self.builtin_clear_timer(&args),
+                    "NOW" => Ok(Value::Number(self.now_timestamp())),
+                    "SECRET" => self.resolve_secret(&args),
+                    "MARK_SECRET" => self.mark_secret(&args),
+                    "SNAPSHOT_MATCH" => self.builtin_snapshot_match(&args),
+                    "RANDOM" => Ok(Value::Number(self.rng.next_f64())),
+                    "KEYS" | "VALUES"
+                        if self.deterministic.is_some()
+                            && matches!(args.first(), Some(Value::Dict(_))) =>
+                    {
+                        self.builtin_sorted_dict_view(name, &args)
+                    }
+                    "VALIDATE_ID_CN" if self.deterministic.is_some() => {
+                        let today =
+                            chrono::DateTime::from_timestamp(self.now_timestamp() as i64, 0)
+                                .unwrap_or_default()
+                                .date_naive();
+                        crate::builtins::validation::validate_id_cn_on(&args, today)
+                    }
+                    "RETRY" => self.builtin_retry(&args),
+                    "WITH_TIMEOUT" => self.builtin_with_timeout(&args),
+                    "SPAWN" => self.builtin_spawn(&args),
+                    "CHANNEL" | "SEND" | "RECV" | "CHANNEL_CLOSE" => {
+                        self.builtin_channel(name, &args)
+                    }
+                    "AWAIT_ALL" => self.builtin_await_all(&args),
+                    "PARALLEL" => self.builtin_parallel(&args),
+                    #[cfg(feature = "http-server")]
+                    "HTTP_SERVE" => self.builtin_http_serve(&args),
                     _ => {
                         // Get the built-in function from the registry
-                        if let Some((func, _arity)) = self.registry.get(name) {
+                        if let Some((func, _arity)) = self.registry.get_native(name) {
+                            func(&args)
+                        } else if let Some((func, _arity)) = self.registry.get(name) {
                             // Call the built-in function
                             func(&args)
                         } else {
@@ -1879,6 +3773,15 @@ impl Evaluator {
                     }
                 };
 
+                // Name the builtin in arity errors
+                let res = res.map_err(|e| match e {
+                    RuntimeError::WrongArity { expected, got } => {
+                        self.builtin_arity_error(name, expected, got)
+                    }
+                    other => other,
+                });
+                self.record_io(name, &args, &res);
+
                 let _ = self.call_stack.pop();
                 self.exit_call();
                 match res {
@@ -1959,6 +3862,450 @@ impl Evaluator {
         Ok(Value::Array(result))
     }
 
+    // 实现 WHERE 内置函数（谓词为函数时，逐行以字典调用）
+    fn builtin_where(&mut self, args: &[Value]) -> EvalResult {
+        if args.len() != 2 {
+            return Err(RuntimeError::WrongArity {
+                expected: 2,
+                got: args.len(),
+            });
+        }
+
+        let table = crate::builtins::table::expect_table(&args[0])?;
+        let predicate = &args[1];
+
+        let mut keep = Vec::new();
+        for index in 0..table.row_count() {
+            let row = table.row(index).unwrap_or(Value::Null);
+            if self.call_function(None, predicate, vec![row])?.is_truthy() {
+                keep.push(index);
+            }
+        }
+
+        Ok(Value::Table(table.take_rows(&keep)))
+    }
+
+    // 实现 ROLLING 内置函数（聚合为函数时，以每个窗口数组调用）
+    fn builtin_rolling(&mut self, args: &[Value]) -> EvalResult {
+        let windows = crate::builtins::math::rolling_windows(args)?;
+        let func = &args[2];
+
+        let mut result = Vec::with_capacity(windows.len());
+        for window in windows {
+            result.push(self.call_function(None, func, vec![window])?);
+        }
+
+        Ok(Value::Array(result))
+    }
+
+    // 实现 SCHEDULE 内置函数（注册定时任务，返回任务编号）
+    fn builtin_schedule(&mut self, args: &[Value]) -> EvalResult {
+        let (expr, schedule, next_run) = crate::builtins::schedule::parse_job(args)?;
+        let id = self.next_job_id;
+        self.next_job_id += 1;
+        self.scheduled_jobs
+            .push(crate::builtins::schedule::ScheduledJob {
+                id,
+                expr,
+                schedule,
+                func: args[1].clone(),
+                next_run,
+            });
+        Ok(Value::Number(id as f64))
+    }
+
+    // 实现 UNSCHEDULE 内置函数（取消任务，返回任务是否存在）
+    fn builtin_unschedule(&mut self, args: &[Value]) -> EvalResult {
+        let id = match args {
+            [Value::Number(n)] => *n,
+            [other] => {
+                return Err(RuntimeError::TypeErrorDetailed {
+                    expected: "Number".to_string(),
+                    got: other.type_name().to_string(),
+                });
+            }
+            _ => {
+                return Err(RuntimeError::WrongArity {
+                    expected: 1,
+                    got: args.len(),
+                });
+            }
+        };
+        let before = self.scheduled_jobs.len();
+        self.scheduled_jobs.retain(|job| job.id as f64 != id);
+        Ok(Value::Boolean(self.scheduled_jobs.len() != before))
+    }
+
+    // 实现 RUN_SCHEDULER 内置函数（等待并执行到期任务，直到没有任务或达到 max_runs）
+    fn builtin_run_scheduler(&mut self, args: &[Value]) -> EvalResult {
+        use crate::builtins::schedule;
+
+        let max_runs = schedule::parse_run_options(args)?;
+        self.scheduler_started = true;
+
+        let mut runs = 0;
+        let mut failures = Vec::new();
+        while let Some(next) = self.scheduled_jobs.iter().map(|job| job.next_run).min() {
+            if max_runs.is_some_and(|max| runs >= max) {
+                break;
+            }
+            let wait = (next - schedule::now()).to_std().unwrap_or_default();
+            if !wait.is_zero() {
+                std::thread::sleep(wait);
+            }
+
+            // 任务执行期间可能注册或取消其他任务，先记录本轮到期的任务
+            let now = schedule::now();
+            let due: Vec<usize> = self
+                .scheduled_jobs
+                .iter()
+                .filter(|job| job.next_run <= now)
+                .map(|job| job.id)
+                .collect();
+            for id in due {
+                if max_runs.is_some_and(|max| runs >= max) {
+                    break;
+                }
+                let Some(job) = self.scheduled_jobs.iter_mut().find(|job| job.id == id) else {
+                    continue;
+                };
+                // 错过的执行不补跑，从当前时间计算下次执行时间
+                let func = job.func.clone();
+                let expr = job.expr.clone();
+                match job.schedule.next_after(now.max(job.next_run)) {
+                    Some(next_run) => job.next_run = next_run,
+                    None => self.scheduled_jobs.retain(|job| job.id != id),
+                }
+                runs += 1;
+                // 单个任务失败不影响调度器继续运行，错误随统计返回
+                if let Err(e) = self.call_function(None, &func, vec![]) {
+                    let error = self.redact(&e.to_string());
+                    failures.push(schedule::failure(id, &expr, error));
+                }
+            }
+        }
+
+        Ok(schedule::summary(runs, failures))
+    }
+
+    /// Whether jobs were registered with SCHEDULE but RUN_SCHEDULER was never called
+    pub fn has_pending_schedule(&self) -> bool {
+        !self.scheduled_jobs.is_empty() && !self.scheduler_started
+    }
+
+    /// Run registered jobs until none remain (see RUN_SCHEDULER)
+    pub fn run_scheduler(&mut self) -> EvalResult {
+        self.builtin_run_scheduler(&[])
+    }
+
+    // 实现 SET_TIMEOUT / SET_INTERVAL / DEBOUNCE 内置函数（注册定时器，返回编号，由宿主 pump 执行）
+    fn builtin_set_timer(&mut self, name: &str, args: &[Value]) -> EvalResult {
+        let (delay, callback) = crate::builtins::schedule::parse_timer(args)?;
+        let label = match &callback {
+            Value::Function { name: Some(n), .. } | Value::BuiltIn { name: n, .. } => n.clone(),
+            _ if name == "DEBOUNCE" => {
+                return Err(RuntimeError::InvalidOperation(
+                    "DEBOUNCE requires a named function (debounced calls are grouped by name)"
+                        .to_string(),
+                ));
+            }
+            _ => "<lambda>".to_string(),
+        };
+        let id = match name {
+            "SET_INTERVAL" if delay.is_zero() => {
+                return Err(RuntimeError::InvalidOperation(
+                    "SET_INTERVAL interval must be greater than 0 ms".to_string(),
+                ));
+            }
+            "SET_INTERVAL" => self.timers.add(Some(callback), label, delay, Some(delay)),
+            "DEBOUNCE" => self.timers.debounce(Some(callback), label, delay),
+            _ => self.timers.add(Some(callback), label, delay, None),
+        };
+        Ok(Value::Number(id as f64))
+    }
+
+    // 实现 CLEAR_TIMER 内置函数（取消定时器，返回定时器是否存在）
+    fn builtin_clear_timer(&mut self, args: &[Value]) -> EvalResult {
+        match args {
+            [Value::Number(n)] => Ok(Value::Boolean(
+                n.fract() == 0.0 && *n >= 1.0 && self.timers.cancel(*n as usize),
+            )),
+            [other] => Err(RuntimeError::TypeErrorDetailed {
+                expected: "Number".to_string(),
+                got: other.type_name().to_string(),
+            }),
+            _ => Err(RuntimeError::WrongArity {
+                expected: 1,
+                got: args.len(),
+            }),
+        }
+    }
+
+    /// Timers registered by scripts and the host (driven by `Aether::pump`)
+    pub(crate) fn timers_mut(&mut self) -> &mut crate::runtime::timers::TimerQueue {
+        &mut self.timers
+    }
+
+    /// Whether any timer is still pending
+    pub fn has_pending_timers(&self) -> bool {
+        !self.timers.is_empty()
+    }
+
+    // KEYS / VALUES 在确定性模式下按键名排序，结果不受哈希顺序影响
+    fn builtin_sorted_dict_view(&self, name: &str, args: &[Value]) -> EvalResult {
+        let [Value::Dict(dict)] = args else {
+            return Err(self.builtin_arity_error(name, 1, args.len()));
+        };
+        let mut entries: Vec<(&String, &Value)> = dict.iter().collect();
+        entries.sort_by(|a, b| a.0.cmp(b.0));
+        Ok(Value::Array(
+            entries
+                .into_iter()
+                .map(|(key, value)| {
+                    if name == "KEYS" {
+                        Value::String(key.clone())
+                    } else {
+                        value.clone()
+                    }
+                })
+                .collect(),
+        ))
+    }
+
+    // 实现 RETRY 内置函数（失败时按指数退避重新调用）
+    fn builtin_retry(&mut self, args: &[Value]) -> EvalResult {
+        let options = crate::builtins::resilience::parse_retry(args)?;
+        let mut retry = 0;
+        loop {
+            match self.call_function(None, &args[0], vec![]) {
+                Ok(value) => return Ok(value),
+                // 执行限制是宿主设置的硬上限，重试没有意义
+                Err(e) if matches!(e.peel_call_stack().0, RuntimeError::ExecutionLimit(_)) => {
+                    return Err(e);
+                }
+                Err(e) if retry + 1 >= options.attempts => return Err(e),
+                Err(_) => {
+                    retry += 1;
+                    std::thread::sleep(options.delay(retry as u32));
+                }
+            }
+        }
+    }
+
+    // 实现 WITH_TIMEOUT 内置函数（在语句边界检查截止时间，超时报错）
+    fn builtin_with_timeout(&mut self, args: &[Value]) -> EvalResult {
+        let limit_ms = crate::builtins::resilience::parse_timeout(args)?;
+        let deadline = std::time::Instant::now() + std::time::Duration::from_millis(limit_ms);
+
+        self.call_deadlines.push((deadline, limit_ms));
+        let result = self.call_function(None, &args[0], vec![]);
+        self.call_deadlines.pop();
+
+        // 无法中断的原生调用（如网络请求）在返回后仍按超时处理
+        match result {
+            Ok(_) if std::time::Instant::now() > deadline => {
+                Err(crate::builtins::resilience::timeout_error(limit_ms))
+            }
+            other => other,
+        }
+    }
+
+    /// Configuration for worker engines (same permissions and limits as this one)
+    fn worker_config(&self) -> crate::builtins::parallel::WorkerConfig {
+        crate::builtins::parallel::WorkerConfig {
+            permissions: self.registry.permissions().clone(),
+            limits: self.limits.clone(),
+            concurrency: self.concurrency_limits.clone(),
+            budget: Arc::clone(&self.task_budget),
+            channels: Arc::clone(self.channels.table()),
+            sandbox: crate::sandbox::SandboxContext::capture(),
+        }
+    }
+
+    /// The earliest point at which this run must stop: `max_duration_ms` or a WITH_TIMEOUT deadline
+    fn deadline(&self) -> Option<std::time::Instant> {
+        let run = self
+            .limits
+            .max_duration_ms
+            .zip(self.start_time.get())
+            .map(|(limit_ms, start)| start + std::time::Duration::from_millis(limit_ms));
+        run.into_iter()
+            .chain(self.call_deadlines.iter().map(|(deadline, _)| *deadline))
+            .min()
+    }
+
+    // 实现通道内置函数（通道属于引擎，阻塞等待受引擎的时间限制约束）
+    fn builtin_channel(&self, name: &str, args: &[Value]) -> EvalResult {
+        let channels = self.channels.table();
+        let result = match name {
+            "CHANNEL" => channels.create(args),
+            "SEND" => channels.send(args, self.deadline(), |bytes| {
+                match self.limits.max_memory_bytes {
+                    Some(limit) if bytes > limit => Err(Self::memory_limit_error(
+                        crate::runtime::MemoryResource::Bytes,
+                        bytes,
+                        limit,
+                    )),
+                    _ => Ok(()),
+                }
+            }),
+            "RECV" => channels.recv(args, self.deadline()),
+            _ => channels.close(args),
+        };
+        // A wait cut short by the engine deadline reports the timeout
+        self.check_timeout()?;
+        result
+    }
+
+    // 实现 SPAWN 内置函数（在后台工作线程上启动任务，返回任务句柄）
+    fn builtin_spawn(&mut self, args: &[Value]) -> EvalResult {
+        use crate::builtins::parallel;
+
+        if args.len() != 1 {
+            return Err(RuntimeError::WrongArity {
+                expected: 1,
+                got: args.len(),
+            });
+        }
+        let task = parallel::Task {
+            func: parallel::check_task(&args[0])?,
+            globals: parallel::capture_globals(&args[0]),
+            counted: true,
+        };
+        self.task_budget.acquire(&self.concurrency_limits)?;
+        // 宿主在两次 SPAWN 之间修改了沙箱上下文时，换用新的线程池，
+        // 旧线程执行完已提交的任务后退出
+        let sandbox = crate::sandbox::SandboxContext::capture();
+        if self
+            .task_pool
+            .as_ref()
+            .is_none_or(|pool| *pool.sandbox() != sandbox)
+        {
+            self.task_pool = Some(parallel::WorkerPool::elastic(self.worker_config()));
+        }
+        let receiver = self
+            .task_pool
+            .as_ref()
+            .map(|pool| pool.submit(task))
+            .expect("task pool initialized");
+
+        let id = self.next_task_id;
+        self.next_task_id += 1;
+        self.pending_tasks.insert(id, receiver);
+        Ok(parallel::handle(id))
+    }
+
+    // 实现 AWAIT_ALL 内置函数（按顺序等待任务，返回每个任务的结果）
+    fn builtin_await_all(&mut self, args: &[Value]) -> EvalResult {
+        use crate::builtins::parallel;
+
+        let handles = match args {
+            [Value::Array(handles)] => handles.clone(),
+            [single @ Value::String(_)] => vec![single.clone()],
+            [other] => {
+                return Err(RuntimeError::TypeErrorDetailed {
+                    expected: "Array".to_string(),
+                    got: other.type_name().to_string(),
+                });
+            }
+            _ => {
+                return Err(RuntimeError::WrongArity {
+                    expected: 1,
+                    got: args.len(),
+                });
+            }
+        };
+        // 先校验全部句柄，避免等待到一半才报错
+        let ids = handles
+            .iter()
+            .map(parallel::parse_handle)
+            .collect::<Result<Vec<_>, _>>()?;
+        if let Some(id) = ids.iter().find(|id| !self.pending_tasks.contains_key(id)) {
+            return Err(RuntimeError::InvalidOperation(format!(
+                "Task task:{} does not exist or was already awaited",
+                id
+            )));
+        }
+
+        let env = Rc::clone(&self.env);
+        let results = ids
+            .iter()
+            .filter_map(|id| self.pending_tasks.remove(id))
+            .map(|receiver| parallel::collect(&receiver, &env))
+            .collect();
+        Ok(Value::Array(results))
+    }
+
+    // 实现 PARALLEL 内置函数（用固定大小的线程池执行一组函数）
+    fn builtin_parallel(&mut self, args: &[Value]) -> EvalResult {
+        use crate::builtins::parallel;
+
+        let (funcs, workers) = parallel::parse_parallel(args)?;
+        let tasks = funcs
+            .iter()
+            .map(|func| {
+                Ok(parallel::Task {
+                    func: parallel::check_task(func)?,
+                    globals: parallel::capture_globals(func),
+                    counted: false,
+                })
+            })
+            .collect::<Result<Vec<_>, RuntimeError>>()?;
+        if tasks.is_empty() {
+            return Ok(Value::Array(Vec::new()));
+        }
+
+        // 工作线程占用任务槽位，槽位不足时以较少的线程执行
+        let workers = self
+            .task_budget
+            .acquire_up_to(workers, &self.concurrency_limits)?;
+        let pool = parallel::WorkerPool::new(workers, self.worker_config());
+        let receivers: Vec<_> = tasks.into_iter().map(|task| pool.submit(task)).collect();
+        let env = Rc::clone(&self.env);
+        let results = receivers
+            .iter()
+            .map(|receiver| parallel::collect(receiver, &env))
+            .collect();
+        self.task_budget.release(workers);
+        Ok(Value::Array(results))
+    }
+
+    // 实现 HTTP_SERVE 内置函数（逐个请求调用处理函数）
+    #[cfg(feature = "http-server")]
+    fn builtin_http_serve(&mut self, args: &[Value]) -> EvalResult {
+        use crate::builtins::http_server;
+
+        let (listener, options) = http_server::bind(args)?;
+        let handler = &args[1];
+
+        let mut requests = 0;
+        let mut errors = 0;
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else {
+                continue;
+            };
+            requests += 1;
+
+            let response = match http_server::read_request(&mut stream) {
+                Ok(request) => self
+                    .call_function(None, handler, vec![request])
+                    .and_then(|result| http_server::build_response(&result)),
+                Err(message) => Ok((400, Vec::new(), format!("Bad Request: {}", message))),
+            };
+            let (status, headers, body) = response.unwrap_or_else(|_| {
+                errors += 1;
+                (500, Vec::new(), "Internal Server Error".to_string())
+            });
+            http_server::write_response(&mut stream, status, &headers, &body);
+
+            if options.max_requests.is_some_and(|max| requests >= max) {
+                break;
+            }
+        }
+
+        Ok(http_server::summary(requests, errors))
+    }
+
     // 实现 REDUCE 内置函数
     fn builtin_reduce(&mut self, args: &[Value]) -> EvalResult {
         if args.len() != 3 {
@@ -2001,7 +4348,7 @@ impl Evaluator {
             call_args.push(accumulator);
             call_args.push(item.clone());
             if arg_count >= 3 {
-                call_args.push(Value::Number(idx as f64));
+                call_args.push(Value::Int(idx as i64));
             }
 
             if arg_count < 2 {
@@ -2047,29 +4394,60 @@ impl Evaluator {
 
         let chain_for_resolve = self.import_chain_with(specifier.to_string());
 
-        let resolved = self
-            .module_resolver
-            .resolve(specifier, from_ctx)
-            .map_err(|e| {
-                RuntimeError::ImportError(Box::new(ImportError::from_resolve_error(
-                    specifier,
-                    e,
-                    chain_for_resolve,
-                )))
-            })?;
+        let resolved = if let Some(name) = specifier.strip_prefix(STDLIB_SPECIFIER_PREFIX) {
+            // Embedded stdlib modules are always available, regardless of the resolver.
+            crate::stdlib::get_module(name)
+                .map(|source| ResolvedModule {
+                    module_id: specifier.to_string(),
+                    source: source.to_string(),
+                    base_dir: None,
+                })
+                .ok_or(ModuleResolveError::NotFound(specifier.to_string()))
+        } else {
+            self.module_resolver.resolve(specifier, from_ctx)
+        }
+        .map_err(|e| {
+            RuntimeError::ImportError(Box::new(ImportError::from_resolve_error(
+                specifier,
+                e,
+                chain_for_resolve,
+            )))
+        })?;
 
         let exports = self.load_module(resolved)?;
 
         if let Some(ns) = namespace {
+            self.check_not_sealed(ns)?;
+            if exports.is_empty() {
+                self.warn(
+                    crate::runtime::WarningKind::Import,
+                    format!(
+                        "Module '{}' exports nothing; the import has no effect",
+                        specifier
+                    ),
+                );
+            }
             self.env.borrow_mut().set(ns.clone(), Value::Dict(exports));
             return Ok(Value::Null);
         }
 
+        let mut bound: Vec<String> = Vec::new();
         for (i, name) in names.iter().enumerate() {
             let alias = aliases
                 .get(i)
                 .and_then(|a| a.clone())
                 .unwrap_or_else(|| name.clone());
+            self.check_not_sealed(&alias)?;
+            if bound.contains(&alias) {
+                self.warn(
+                    crate::runtime::WarningKind::Import,
+                    format!(
+                        "'{}' is imported more than once from '{}'; the duplicate is ignored",
+                        alias, specifier
+                    ),
+                );
+                continue;
+            }
             let v = exports.get(name).cloned().ok_or_else(|| {
                 RuntimeError::ImportError(Box::new(ImportError::not_exported(
                     specifier,
@@ -2077,9 +4455,55 @@ impl Evaluator {
                     self.import_chain_with(specifier.to_string()),
                 )))
             })?;
-            self.env.borrow_mut().set(alias, v);
+            let existing = self.env.borrow().get(&alias);
+            if let Some(existing) = existing
+                && !Self::same_binding(&existing, &v)
+            {
+                self.warn(
+                    crate::runtime::WarningKind::Import,
+                    format!(
+                        "Import of '{}' from '{}' replaces an existing {}",
+                        alias,
+                        specifier,
+                        existing.type_name()
+                    ),
+                );
+            }
+            self.env.borrow_mut().set(alias.clone(), v);
+            bound.push(alias);
+        }
+
+        Ok(Value::Null)
+    }
+
+    /// Whether re-binding `old` to `new` leaves the name unchanged (re-importing a cached module)
+    fn same_binding(old: &Value, new: &Value) -> bool {
+        match (old, new) {
+            (
+                Value::Function {
+                    name: a, env: ea, ..
+                },
+                Value::Function {
+                    name: b, env: eb, ..
+                },
+            ) => a == b && Rc::ptr_eq(ea, eb),
+            _ => old == new,
         }
+    }
 
+    /// Import a module and bind all of its exports under `namespace` as a Dict.
+    ///
+    /// `specifier` may be `stdlib:<name>` for embedded stdlib modules.
+    pub fn import_namespace(&mut self, specifier: &str, namespace: &str) -> EvalResult {
+        self.eval_import(&[], specifier, &[], Some(&namespace.to_string()))
+    }
+
+    /// Load a resolved module into the module cache without binding any names.
+    ///
+    /// Later `Import`s of the same module id reuse the cached exports, so the module
+    /// body runs only once. Used to load multi-file projects in dependency order.
+    pub fn preload_module(&mut self, resolved: ResolvedModule) -> EvalResult {
+        self.load_module(resolved)?;
         Ok(Value::Null)
     }
 
@@ -2119,7 +4543,7 @@ impl Evaluator {
         self.module_stack.push(resolved.module_id.clone());
 
         // Parse module
-        let mut parser = crate::parser::Parser::new(&resolved.source);
+        let mut parser = self.parser(&resolved.source);
         let program = match parser.parse_program() {
             Ok(p) => p,
             Err(e) => {
@@ -2129,12 +4553,18 @@ impl Evaluator {
                 )));
             }
         };
+        for warning in parser.take_warnings() {
+            self.warn(
+                crate::runtime::WarningKind::Parse,
+                format!("{}: {}", resolved.module_id, warning),
+            );
+        }
 
         // Evaluate in an isolated environment with builtins registered.
         let prev_env = Rc::clone(&self.env);
         let module_env = Rc::new(RefCell::new(Environment::new()));
         Self::register_builtins_into_env(&self.registry, &mut module_env.borrow_mut());
-        self.env = module_env;
+        self.env = Rc::clone(&module_env);
 
         // Push module import base (for relative imports inside the module)
         self.import_base_stack.push(ModuleContext {
@@ -2148,8 +4578,23 @@ impl Evaluator {
         let eval_res = self.eval_program(&program);
 
         // Pop stacks and restore env (must happen even on error)
-        let exports = self.export_stack.pop().unwrap_or_default();
+        let mut exports = self.export_stack.pop().unwrap_or_default();
         self.import_base_stack.pop();
+
+        // Stdlib modules have no `Export` statements; they export their functions except
+        // `_`-prefixed helpers (see `stdlib::get_exports`). Constants and state stay private.
+        if exports.is_empty()
+            && let Some(public) = resolved
+                .module_id
+                .strip_prefix(STDLIB_SPECIFIER_PREFIX)
+                .and_then(crate::stdlib::get_exports)
+        {
+            let env = module_env.borrow();
+            exports = public
+                .iter()
+                .filter_map(|name| env.get(name).map(|value| (name.to_string(), value)))
+                .collect();
+        }
         self.env = prev_env;
 
         // Pop module stack
@@ -197,6 +197,7 @@ fn value_to_string(value: &Value) -> String {
                 n.to_string()
             }
         }
+        Value::Int(i) => i.to_string(),
         Value::String(s) => s.clone(),
         Value::Boolean(b) => b.to_string(),
         Value::Array(arr) => {
@@ -210,6 +211,7 @@ fn value_to_string(value: &Value) -> String {
                 .collect();
             format!("{{{}}}", items.join(", "))
         }
+        Value::Set(_) | Value::Table(_) | Value::DateTime(_) => value.to_string(),
         Value::Null => "null".to_string(),
         Value::Function { .. } => "<function>".to_string(),
         Value::BuiltIn { name, .. } => format!("<builtin: {}>", name),
@@ -226,6 +228,7 @@ fn value_to_json(value: &Value) -> String {
             // 直接返回数字的 JSON 表示
             json!(n).to_string()
         }
+        Value::Int(i) => json!(i).to_string(),
         Value::String(s) => json!(s).to_string(),
         Value::Boolean(b) => json!(b).to_string(),
         Value::Array(arr) => {
@@ -239,6 +242,7 @@ fn value_to_json(value: &Value) -> String {
             }
             json!(obj).to_string()
         }
+        Value::Set(_) | Value::Table(_) | Value::DateTime(_) => json_from_value(value).to_string(),
         Value::Null => "null".to_string(),
         Value::Function { .. } => json!("<function>").to_string(),
         Value::BuiltIn { name, .. } => json!(format!("<builtin: {}>", name)).to_string(),
@@ -252,6 +256,7 @@ fn value_to_json(value: &Value) -> String {
 fn json_from_value(value: &Value) -> serde_json::Value {
     match value {
         Value::Number(n) => json!(n),
+        Value::Int(i) => json!(i),
         Value::String(s) => json!(s),
         Value::Boolean(b) => json!(b),
         Value::Array(arr) => {
@@ -265,6 +270,19 @@ fn json_from_value(value: &Value) -> serde_json::Value {
             }
             json!(obj)
         }
+        Value::Set(set) => {
+            let items: Vec<serde_json::Value> = crate::value::set_elements(set)
+                .iter()
+                .map(json_from_value)
+                .collect();
+            json!(items)
+        }
+        Value::Table(table) => {
+            let rows: Vec<serde_json::Value> =
+                table.to_dicts().iter().map(json_from_value).collect();
+            json!(rows)
+        }
+        Value::DateTime(dt) => json!(dt.to_rfc3339()),
         Value::Null => json!(null),
         Value::Function { .. } => json!("<function>"),
         Value::BuiltIn { name, .. } => json!(format!("<builtin: {}>", name)),
@@ -640,7 +658,11 @@ pub unsafe extern "C" fn aether_set_limits(handle: *mut AetherHandle, limits: *c
             } else {
                 Some(limits_ref.max_duration_ms as u64)
             },
-            max_memory_bytes: None,
+            // Not exposed through AetherLimits; keep the engine's current settings
+            max_loop_iterations: engine.limits().max_loop_iterations,
+            max_array_len: engine.limits().max_array_len,
+            max_dict_entries: engine.limits().max_dict_entries,
+            max_memory_bytes: engine.limits().max_memory_bytes,
         };
 
         engine.set_limits(rust_limits);
@@ -0,0 +1,167 @@
+// src/kernel/message.rs
+//! Jupyter 消息（协议 5.3）
+//!
+//! 线上格式为多帧消息：`[路由标识..., "<IDS|MSG>", 签名, header, parent_header, metadata, content, buffers...]`。
+//! 签名是对 header、parent_header、metadata、content 四帧的 HMAC-SHA256（十六进制），
+//! 连接文件中的 key 为空时签名为空字符串。
+
+use ring::{hmac, rand::SecureRandom, rand::SystemRandom};
+use serde_json::{Value as JsonValue, json};
+
+/// 协议版本
+pub const PROTOCOL_VERSION: &str = "5.3";
+
+/// 路由标识与消息体之间的分隔帧
+const DELIMITER: &[u8] = b"<IDS|MSG>";
+
+/// 一条 Jupyter 消息
+#[derive(Debug, Clone, PartialEq)]
+pub struct Message {
+    /// 路由标识（回复时原样带回）
+    pub identities: Vec<Vec<u8>>,
+    pub header: JsonValue,
+    pub parent_header: JsonValue,
+    pub metadata: JsonValue,
+    pub content: JsonValue,
+    pub buffers: Vec<Vec<u8>>,
+}
+
+impl Message {
+    /// 创建新消息（生成 msg_id 和时间戳）
+    pub fn new(msg_type: &str, session: &str, content: JsonValue) -> Self {
+        Message {
+            identities: Vec::new(),
+            header: json!({
+                "msg_id": new_id(),
+                "session": session,
+                "username": "aether",
+                "date": chrono::Utc::now().to_rfc3339(),
+                "msg_type": msg_type,
+                "version": PROTOCOL_VERSION,
+            }),
+            parent_header: json!({}),
+            metadata: json!({}),
+            content,
+            buffers: Vec::new(),
+        }
+    }
+
+    /// 消息类型
+    pub fn msg_type(&self) -> &str {
+        self.header["msg_type"].as_str().unwrap_or_default()
+    }
+
+    /// 创建对本消息的回复或广播（设置 parent_header，带回路由标识）
+    pub fn child(&self, msg_type: &str, session: &str, content: JsonValue) -> Self {
+        let mut message = Message::new(msg_type, session, content);
+        message.parent_header = self.header.clone();
+        message.identities = self.identities.clone();
+        message
+    }
+
+    /// 从多帧消息解析，并用 `key` 校验签名
+    pub fn from_frames(frames: Vec<Vec<u8>>, key: &[u8]) -> Result<Self, String> {
+        let delimiter = frames
+            .iter()
+            .position(|frame| frame == DELIMITER)
+            .ok_or("missing <IDS|MSG> delimiter")?;
+        let mut frames = frames.into_iter();
+        let identities: Vec<Vec<u8>> = frames.by_ref().take(delimiter).collect();
+        frames.next();
+
+        let mut next = || frames.next().ok_or("truncated message");
+        let signature = next()?;
+        let parts = [next()?, next()?, next()?, next()?];
+        let buffers = frames.collect();
+
+        if !key.is_empty() {
+            let tag = decode_hex(&signature).ok_or("malformed signature")?;
+            let key = hmac::Key::new(hmac::HMAC_SHA256, key);
+            let data = parts.concat();
+            hmac::verify(&key, &data, &tag).map_err(|_| "invalid message signature")?;
+        }
+
+        let parse = |bytes: &[u8]| {
+            serde_json::from_slice::<JsonValue>(bytes).map_err(|e| format!("invalid JSON: {}", e))
+        };
+        Ok(Message {
+            identities,
+            header: parse(&parts[0])?,
+            parent_header: parse(&parts[1])?,
+            metadata: parse(&parts[2])?,
+            content: parse(&parts[3])?,
+            buffers,
+        })
+    }
+
+    /// 序列化为多帧消息并签名
+    pub fn to_frames(&self, key: &[u8]) -> Vec<Vec<u8>> {
+        let parts = [
+            self.header.to_string().into_bytes(),
+            self.parent_header.to_string().into_bytes(),
+            self.metadata.to_string().into_bytes(),
+            self.content.to_string().into_bytes(),
+        ];
+        let signature = sign(key, &parts);
+
+        let mut frames = self.identities.clone();
+        frames.push(DELIMITER.to_vec());
+        frames.push(signature.into_bytes());
+        frames.extend(parts);
+        frames.extend(self.buffers.iter().cloned());
+        frames
+    }
+}
+
+/// 计算四个消息帧的签名
+fn sign(key: &[u8], parts: &[Vec<u8>]) -> String {
+    if key.is_empty() {
+        return String::new();
+    }
+    let key = hmac::Key::new(hmac::HMAC_SHA256, key);
+    let mut context = hmac::Context::with_key(&key);
+    for part in parts {
+        context.update(part);
+    }
+    context
+        .sign()
+        .as_ref()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// 生成 UUID v4 形式的随机标识
+pub fn new_id() -> String {
+    let mut bytes = [0u8; 16];
+    // 系统随机源不可用时退回时间戳，标识只需在会话内唯一
+    if SystemRandom::new().fill(&mut bytes).is_err() {
+        let nanos = chrono::Utc::now()
+            .timestamp_nanos_opt()
+            .unwrap_or_default()
+            .to_be_bytes();
+        bytes[..8].copy_from_slice(&nanos);
+    }
+    bytes[6] = (bytes[6] & 0x0F) | 0x40;
+    bytes[8] = (bytes[8] & 0x3F) | 0x80;
+    let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
+
+fn decode_hex(bytes: &[u8]) -> Option<Vec<u8>> {
+    let text = std::str::from_utf8(bytes).ok()?;
+    if !text.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok())
+        .collect()
+}
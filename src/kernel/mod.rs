@@ -0,0 +1,341 @@
+// src/kernel/mod.rs
+//! Jupyter 内核
+//!
+//! `aether kernel install` 把内核规格（kernel.json）写入 Jupyter 数据目录，之后 Jupyter
+//! 以 `aether kernel --connection-file <文件>` 启动内核。内核监听连接文件中的五个端口：
+//!
+//! | 通道 | 套接字 | 说明 |
+//! |------|--------|------|
+//! | shell   | ROUTER | 执行、补全、内核信息等请求 |
+//! | control | ROUTER | 关闭、中断 |
+//! | iopub   | PUB    | 状态、输出、结果广播 |
+//! | stdin   | ROUTER | 不使用（不支持 INPUT） |
+//! | hb      | REP    | 心跳，原样返回 |
+//!
+//! 传输使用内置的 ZMTP 3.0 实现（仅 tcp 传输和 NULL 安全机制），消息用连接文件中的
+//! key 做 HMAC-SHA256 签名。请求处理见 [`KernelSession`]。
+
+mod message;
+mod session;
+pub mod zmtp;
+
+pub use message::{Message, PROTOCOL_VERSION};
+pub use session::{Handled, KernelSession};
+
+use crate::Aether;
+use serde::Deserialize;
+use serde_json::json;
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use zmtp::{Connection, SocketType};
+
+/// 内核规格目录名
+pub const KERNEL_NAME: &str = "aether";
+
+/// Jupyter 连接文件
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ConnectionInfo {
+    #[serde(default = "default_transport")]
+    pub transport: String,
+    pub ip: String,
+    pub shell_port: u16,
+    pub iopub_port: u16,
+    pub stdin_port: u16,
+    pub control_port: u16,
+    pub hb_port: u16,
+    #[serde(default)]
+    pub key: String,
+    #[serde(default = "default_signature_scheme")]
+    pub signature_scheme: String,
+}
+
+fn default_transport() -> String {
+    "tcp".to_string()
+}
+
+fn default_signature_scheme() -> String {
+    "hmac-sha256".to_string()
+}
+
+impl ConnectionInfo {
+    /// 读取连接文件
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(|e| {
+            format!(
+                "Kernel error: cannot read connection file '{}': {}",
+                path.display(),
+                e
+            )
+        })?;
+        let info: ConnectionInfo = serde_json::from_str(&text).map_err(|e| {
+            format!(
+                "Kernel error: invalid connection file '{}': {}",
+                path.display(),
+                e
+            )
+        })?;
+        if info.transport != "tcp" {
+            return Err(format!(
+                "Kernel error: unsupported transport '{}' (only tcp)",
+                info.transport
+            ));
+        }
+        if !info.key.is_empty() && info.signature_scheme != "hmac-sha256" {
+            return Err(format!(
+                "Kernel error: unsupported signature scheme '{}' (only hmac-sha256)",
+                info.signature_scheme
+            ));
+        }
+        Ok(info)
+    }
+
+    fn addr(&self, port: u16) -> String {
+        format!("{}:{}", self.ip, port)
+    }
+}
+
+/// 来自 shell/control 通道的请求
+struct Request {
+    frames: Vec<Vec<u8>>,
+    reply_to: Connection,
+}
+
+/// 运行中的内核
+pub struct Kernel {
+    info: ConnectionInfo,
+    session: KernelSession,
+    session_id: String,
+}
+
+impl Kernel {
+    /// 创建内核（默认引擎：所有 IO 权限并加载标准库，与 CLI 一致）
+    pub fn new(info: ConnectionInfo) -> Self {
+        Self::with_engine(
+            info,
+            Aether::with_stdlib().unwrap_or_else(|_| Aether::new()),
+        )
+    }
+
+    /// 使用指定引擎创建内核
+    pub fn with_engine(info: ConnectionInfo, engine: Aether) -> Self {
+        Kernel {
+            info,
+            session: KernelSession::new(engine),
+            session_id: message::new_id(),
+        }
+    }
+
+    /// 监听各通道并处理请求，直到收到 shutdown_request
+    pub fn run(&mut self) -> Result<(), String> {
+        let bind = |port: u16| {
+            let addr = self.info.addr(port);
+            TcpListener::bind(&addr)
+                .map_err(|e| format!("Kernel error: cannot bind {}: {}", addr, e))
+        };
+        let shell = bind(self.info.shell_port)?;
+        let control = bind(self.info.control_port)?;
+        let iopub = bind(self.info.iopub_port)?;
+        let stdin = bind(self.info.stdin_port)?;
+        let heartbeat = bind(self.info.hb_port)?;
+
+        let (sender, requests) = mpsc::channel();
+        spawn_router(shell, sender.clone());
+        spawn_router(control, sender);
+        spawn_acceptor(stdin, SocketType::Router, |mut connection| {
+            while connection.recv().is_ok() {}
+        });
+        spawn_acceptor(heartbeat, SocketType::Rep, |mut connection| {
+            while let Ok(frames) = connection.recv() {
+                if connection.send(&frames).is_err() {
+                    break;
+                }
+            }
+        });
+        let subscribers = Arc::new(Mutex::new(Vec::new()));
+        let publish_to = Arc::clone(&subscribers);
+        spawn_acceptor(iopub, SocketType::Pub, move |connection| {
+            if let Ok(mut reader) = connection.try_clone() {
+                publish_to.lock().unwrap().push(connection);
+                // 读取并丢弃订阅消息，直到对端断开
+                while reader.recv().is_ok() {}
+            }
+        });
+
+        let publisher = Publisher {
+            subscribers,
+            key: self.info.key.as_bytes().to_vec(),
+            session: self.session_id.clone(),
+        };
+        publisher.publish(None, "status", json!({ "execution_state": "starting" }));
+
+        for request in requests {
+            if self.serve(request, &publisher) {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// 处理一个请求，返回是否应退出
+    fn serve(&mut self, mut request: Request, publisher: &Publisher) -> bool {
+        let key = self.info.key.as_bytes();
+        let Ok(message) = Message::from_frames(request.frames, key) else {
+            // 签名错误或格式错误的消息直接丢弃
+            return false;
+        };
+
+        publisher.publish(
+            Some(&message),
+            "status",
+            json!({ "execution_state": "busy" }),
+        );
+        let handled = self.session.handle(message.msg_type(), &message.content);
+        let mut shutdown = false;
+        if let Some(handled) = handled {
+            for (msg_type, content) in handled.broadcasts {
+                publisher.publish(Some(&message), &msg_type, content);
+            }
+            let reply = message.child(&handled.reply_type, &self.session_id, handled.reply);
+            let _ = request.reply_to.send(&reply.to_frames(key));
+            shutdown = handled.shutdown;
+        }
+        publisher.publish(
+            Some(&message),
+            "status",
+            json!({ "execution_state": "idle" }),
+        );
+        shutdown
+    }
+}
+
+/// iopub 广播
+struct Publisher {
+    subscribers: Arc<Mutex<Vec<Connection>>>,
+    key: Vec<u8>,
+    session: String,
+}
+
+impl Publisher {
+    fn publish(&self, parent: Option<&Message>, msg_type: &str, content: serde_json::Value) {
+        let mut message = match parent {
+            Some(parent) => parent.child(msg_type, &self.session, content),
+            None => Message::new(msg_type, &self.session, content),
+        };
+        // iopub 的路由标识是订阅主题
+        message.identities = vec![format!("kernel.{}.{}", self.session, msg_type).into_bytes()];
+        let frames = message.to_frames(&self.key);
+        self.subscribers
+            .lock()
+            .unwrap()
+            .retain_mut(|subscriber| subscriber.send(&frames).is_ok());
+    }
+}
+
+/// 接受连接并在单独的线程中完成握手后交给 `serve`
+fn spawn_acceptor<F>(listener: TcpListener, socket_type: SocketType, serve: F)
+where
+    F: Fn(Connection) + Send + Sync + 'static,
+{
+    let serve = Arc::new(serve);
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let serve = Arc::clone(&serve);
+            thread::spawn(move || {
+                if let Ok(connection) = Connection::handshake(stream, socket_type) {
+                    serve(connection);
+                }
+            });
+        }
+    });
+}
+
+/// ROUTER 通道：把收到的消息连同回复用的连接送到主线程
+fn spawn_router(listener: TcpListener, sender: Sender<Request>) {
+    let sender = Mutex::new(sender);
+    spawn_acceptor(listener, SocketType::Router, move |mut connection| {
+        let sender = sender.lock().unwrap().clone();
+        while let Ok(frames) = connection.recv() {
+            let Ok(reply_to) = connection.try_clone() else {
+                break;
+            };
+            if sender.send(Request { frames, reply_to }).is_err() {
+                break;
+            }
+        }
+    });
+}
+
+/// 写入内核规格，返回内核目录
+///
+/// `kernels_dir` 为 None 时写入当前用户的 Jupyter 数据目录（见 [`user_kernels_dir`]）。
+///
+/// # 示例
+/// ```no_run
+/// use aether::kernel::install_kernelspec;
+///
+/// let exe = std::env::current_exe().unwrap();
+/// let dir = install_kernelspec(None, &exe, "Aether").unwrap();
+/// println!("installed to {}", dir.display());
+/// ```
+pub fn install_kernelspec(
+    kernels_dir: Option<&Path>,
+    executable: &Path,
+    display_name: &str,
+) -> Result<PathBuf, String> {
+    let kernels_dir = match kernels_dir {
+        Some(dir) => dir.to_path_buf(),
+        None => {
+            user_kernels_dir().ok_or("Kernel error: cannot locate the Jupyter data directory")?
+        }
+    };
+    let dir = kernels_dir.join(KERNEL_NAME);
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Kernel error: cannot create '{}': {}", dir.display(), e))?;
+
+    let spec = json!({
+        "argv": [
+            executable.to_string_lossy(),
+            "kernel",
+            "--connection-file",
+            "{connection_file}",
+        ],
+        "display_name": display_name,
+        "language": "aether",
+        "interrupt_mode": "message",
+    });
+    let path = dir.join("kernel.json");
+    let text = serde_json::to_string_pretty(&spec).unwrap_or_default();
+    std::fs::write(&path, text)
+        .map_err(|e| format!("Kernel error: cannot write '{}': {}", path.display(), e))?;
+    Ok(dir)
+}
+
+/// 当前用户的 Jupyter 内核目录
+///
+/// 优先使用 `JUPYTER_DATA_DIR`；否则 Linux 为 `$XDG_DATA_HOME/jupyter/kernels`
+/// （默认 `~/.local/share`），macOS 为 `~/Library/Jupyter/kernels`，
+/// Windows 为 `%APPDATA%\jupyter\kernels`。
+pub fn user_kernels_dir() -> Option<PathBuf> {
+    let env = |name: &str| {
+        std::env::var_os(name)
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+    };
+    if let Some(dir) = env("JUPYTER_DATA_DIR") {
+        return Some(dir.join("kernels"));
+    }
+    let data_dir = if cfg!(windows) {
+        env("APPDATA")?.join("jupyter")
+    } else if cfg!(target_os = "macos") {
+        env("HOME")?.join("Library").join("Jupyter")
+    } else {
+        env("XDG_DATA_HOME")
+            .or_else(|| env("HOME").map(|home| home.join(".local").join("share")))?
+            .join("jupyter")
+    };
+    Some(data_dir.join("kernels"))
+}
@@ -0,0 +1,353 @@
+// src/kernel/session.rs
+//! 内核会话：处理 shell/control 请求，与传输无关
+//!
+//! 每个单元格在同一个引擎上增量求值，变量和函数在单元格之间保留。
+//! PRINT/PRINTLN 的输出转为 `stream` 消息，`DISPLAY` 的值转为 `display_data`，
+//! 单元格的结果（非 Null）转为 `execute_result`。
+
+use super::message::PROTOCOL_VERSION;
+use crate::Aether;
+use crate::analysis::builtin_names;
+use crate::builtins::display::as_image;
+use crate::builtins::io::{self, CapturedOutput};
+use crate::value::Value;
+use serde_json::{Map, Value as JsonValue, json};
+use std::collections::BTreeSet;
+
+/// 一个请求的处理结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct Handled {
+    /// 回复的消息类型（如 `execute_reply`）
+    pub reply_type: String,
+    /// 回复内容
+    pub reply: JsonValue,
+    /// 处理期间要在 iopub 上广播的消息（消息类型，内容）
+    pub broadcasts: Vec<(String, JsonValue)>,
+    /// 是否应在回复后退出
+    pub shutdown: bool,
+}
+
+impl Handled {
+    fn reply(reply_type: &str, reply: JsonValue) -> Self {
+        Handled {
+            reply_type: reply_type.to_string(),
+            reply,
+            broadcasts: Vec::new(),
+            shutdown: false,
+        }
+    }
+}
+
+/// 内核会话
+pub struct KernelSession {
+    engine: Aether,
+    execution_count: u64,
+}
+
+impl KernelSession {
+    /// 在给定引擎上创建会话
+    pub fn new(engine: Aether) -> Self {
+        KernelSession {
+            engine,
+            execution_count: 0,
+        }
+    }
+
+    /// 已执行（记入历史）的单元格数
+    pub fn execution_count(&self) -> u64 {
+        self.execution_count
+    }
+
+    /// 会话使用的引擎
+    pub fn engine(&mut self) -> &mut Aether {
+        &mut self.engine
+    }
+
+    /// 处理一个请求；不支持的消息类型返回 None
+    pub fn handle(&mut self, msg_type: &str, content: &JsonValue) -> Option<Handled> {
+        let handled = match msg_type {
+            "kernel_info_request" => Handled::reply("kernel_info_reply", kernel_info()),
+            "execute_request" => self.execute(content),
+            "is_complete_request" => {
+                let code = content["code"].as_str().unwrap_or_default();
+                Handled::reply("is_complete_reply", json!({ "status": is_complete(code) }))
+            }
+            "complete_request" => {
+                let code = content["code"].as_str().unwrap_or_default();
+                let cursor = content["cursor_pos"]
+                    .as_u64()
+                    .map_or(code.chars().count(), |c| c as usize);
+                Handled::reply("complete_reply", self.complete(code, cursor))
+            }
+            "comm_info_request" => {
+                Handled::reply("comm_info_reply", json!({ "status": "ok", "comms": {} }))
+            }
+            "interrupt_request" => Handled::reply("interrupt_reply", json!({ "status": "ok" })),
+            "shutdown_request" => {
+                let restart = content["restart"].as_bool().unwrap_or(false);
+                let mut handled = Handled::reply(
+                    "shutdown_reply",
+                    json!({ "status": "ok", "restart": restart }),
+                );
+                handled.shutdown = true;
+                handled
+            }
+            _ => return None,
+        };
+        Some(handled)
+    }
+
+    /// 执行一个单元格
+    fn execute(&mut self, content: &JsonValue) -> Handled {
+        let code = content["code"].as_str().unwrap_or_default();
+        let silent = content["silent"].as_bool().unwrap_or(false);
+        if !silent && content["store_history"].as_bool().unwrap_or(true) {
+            self.execution_count += 1;
+        }
+        let count = self.execution_count;
+
+        let mut broadcasts = Vec::new();
+        if !silent {
+            broadcasts.push((
+                "execute_input".to_string(),
+                json!({ "code": code, "execution_count": count }),
+            ));
+        }
+
+        io::begin_capture();
+        let result = self.engine.eval(code);
+        let outputs = io::end_capture();
+
+        if !silent {
+            for output in outputs {
+                broadcasts.push(match output {
+                    CapturedOutput::Stdout(text) => (
+                        "stream".to_string(),
+                        json!({ "name": "stdout", "text": text }),
+                    ),
+                    CapturedOutput::Display(value) => (
+                        "display_data".to_string(),
+                        json!({ "data": self.mime_bundle(&value), "metadata": {}, "transient": {} }),
+                    ),
+                });
+            }
+        }
+
+        let reply = match result {
+            Ok(value) => {
+                if !silent && value != Value::Null {
+                    broadcasts.push((
+                        "execute_result".to_string(),
+                        json!({
+                            "execution_count": count,
+                            "data": self.mime_bundle(&value),
+                            "metadata": {},
+                        }),
+                    ));
+                }
+                json!({
+                    "status": "ok",
+                    "execution_count": count,
+                    "payload": [],
+                    "user_expressions": {},
+                })
+            }
+            Err(message) => {
+                let (ename, evalue) = split_error(&message);
+                let error = json!({
+                    "ename": ename,
+                    "evalue": evalue,
+                    "traceback": [message],
+                });
+                if !silent {
+                    broadcasts.push(("error".to_string(), error.clone()));
+                }
+                let mut reply = error;
+                reply["status"] = json!("error");
+                reply["execution_count"] = json!(count);
+                reply
+            }
+        };
+
+        Handled {
+            reply_type: "execute_reply".to_string(),
+            reply,
+            broadcasts,
+            shutdown: false,
+        }
+    }
+
+    /// 补全光标前的标识符（内置函数和已定义的变量）
+    fn complete(&self, code: &str, cursor: usize) -> JsonValue {
+        let before: Vec<char> = code.chars().take(cursor).collect();
+        let start = before
+            .iter()
+            .rposition(|c| !(c.is_alphanumeric() || *c == '_'))
+            .map_or(0, |i| i + 1);
+        let prefix: String = before[start..].iter().collect::<String>().to_uppercase();
+
+        let mut matches = BTreeSet::new();
+        if !prefix.is_empty() {
+            let variables = self.engine.variables();
+            let names = builtin_names()
+                .iter()
+                .map(String::as_str)
+                .chain(variables.iter().map(|v| v.name.as_str()));
+            for name in names {
+                if name.to_uppercase().starts_with(&prefix) {
+                    matches.insert(name.to_string());
+                }
+            }
+        }
+        json!({
+            "status": "ok",
+            "matches": matches.into_iter().collect::<Vec<_>>(),
+            "cursor_start": start,
+            "cursor_end": before.len(),
+            "metadata": {},
+        })
+    }
+
+    /// 值的 MIME 输出：图片、HTML 表格（表格和字典数组）和纯文本
+    pub fn mime_bundle(&self, value: &Value) -> JsonValue {
+        let mut bundle = Map::new();
+        if let Some((mime, data)) = as_image(value) {
+            bundle.insert(mime.to_string(), json!(data));
+            bundle.insert("text/plain".to_string(), json!(format!("<{} image>", mime)));
+            return JsonValue::Object(bundle);
+        }
+        if let Some(html) = html_table(value) {
+            bundle.insert("text/html".to_string(), json!(html));
+        }
+        bundle.insert(
+            "text/plain".to_string(),
+            json!(self.engine.format_value(value)),
+        );
+        JsonValue::Object(bundle)
+    }
+}
+
+/// `kernel_info_reply` 内容
+fn kernel_info() -> JsonValue {
+    json!({
+        "status": "ok",
+        "protocol_version": PROTOCOL_VERSION,
+        "implementation": "aether",
+        "implementation_version": env!("CARGO_PKG_VERSION"),
+        "language_info": {
+            "name": "aether",
+            "version": env!("CARGO_PKG_VERSION"),
+            "mimetype": "text/x-aether",
+            "file_extension": ".aether",
+        },
+        "banner": format!("Aether v{}", env!("CARGO_PKG_VERSION")),
+        "help_links": [],
+    })
+}
+
+/// 判断代码是否完整：括号未闭合时为 incomplete，语法错误为 invalid
+fn is_complete(code: &str) -> &'static str {
+    if crate::Parser::new(code).parse_program().is_ok() {
+        return "complete";
+    }
+    let mut depth = 0i64;
+    let mut in_string = false;
+    let mut escaped = false;
+    for c in code.chars() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth -= 1,
+            _ => {}
+        }
+    }
+    if depth > 0 || in_string {
+        "incomplete"
+    } else {
+        "invalid"
+    }
+}
+
+/// 把 "Parse error: ..." / "Runtime error: ..." 拆成（错误名，错误信息）
+fn split_error(message: &str) -> (&'static str, &str) {
+    if let Some(rest) = message.strip_prefix("Parse error: ") {
+        ("ParseError", rest)
+    } else if let Some(rest) = message.strip_prefix("Runtime error: ") {
+        ("RuntimeError", rest)
+    } else {
+        ("Error", message)
+    }
+}
+
+/// 表格或字典数组渲染为 HTML 表格
+fn html_table(value: &Value) -> Option<String> {
+    let (columns, rows): (Vec<String>, Vec<Vec<Value>>) = match value {
+        Value::Table(table) => {
+            let rows = (0..table.row_count())
+                .map(|r| {
+                    (0..table.columns().len())
+                        .map(|c| table.cell(r, c).clone())
+                        .collect()
+                })
+                .collect();
+            (table.columns().to_vec(), rows)
+        }
+        Value::Array(items) if !items.is_empty() => {
+            let mut columns = BTreeSet::new();
+            for item in items {
+                let Value::Dict(dict) = item else {
+                    return None;
+                };
+                columns.extend(dict.keys().cloned());
+            }
+            let columns: Vec<String> = columns.into_iter().collect();
+            let rows = items
+                .iter()
+                .map(|item| match item {
+                    Value::Dict(dict) => columns
+                        .iter()
+                        .map(|c| dict.get(c).cloned().unwrap_or(Value::Null))
+                        .collect(),
+                    _ => Vec::new(),
+                })
+                .collect();
+            (columns, rows)
+        }
+        _ => return None,
+    };
+
+    let mut html = String::from("<table>\n<thead><tr>");
+    for column in &columns {
+        html.push_str(&format!("<th>{}</th>", escape_html(column)));
+    }
+    html.push_str("</tr></thead>\n<tbody>\n");
+    for row in rows {
+        html.push_str("<tr>");
+        for cell in row {
+            let text = match cell {
+                Value::Null => String::new(),
+                other => other.to_string(),
+            };
+            html.push_str(&format!("<td>{}</td>", escape_html(&text)));
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</tbody>\n</table>");
+    Some(html)
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,183 @@
+// src/kernel/zmtp.rs
+//! ZMTP 3.0 的最小实现（NULL 安全机制）
+//!
+//! Jupyter 前端通过 ZeroMQ 连接内核。内核一侧每个通道只需要点对点收发多帧消息：
+//! ROUTER（shell、control、stdin）、PUB（iopub）和 REP（heartbeat）。
+//! 连接建立后双方交换 64 字节问候和 READY 命令，之后是带 MORE/LONG 标志的帧。
+//! 订阅消息和其他命令帧会被读取后忽略（iopub 向所有订阅者发送全部消息）。
+
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+
+const FLAG_MORE: u8 = 0x01;
+const FLAG_LONG: u8 = 0x02;
+const FLAG_COMMAND: u8 = 0x04;
+
+/// 单帧大小上限，防止异常长度导致过量分配
+const MAX_FRAME: u64 = 256 * 1024 * 1024;
+
+/// ZeroMQ 套接字类型（写入 READY 命令的 Socket-Type 属性）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SocketType {
+    Router,
+    Dealer,
+    Pub,
+    Sub,
+    Rep,
+    Req,
+}
+
+impl SocketType {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SocketType::Router => "ROUTER",
+            SocketType::Dealer => "DEALER",
+            SocketType::Pub => "PUB",
+            SocketType::Sub => "SUB",
+            SocketType::Rep => "REP",
+            SocketType::Req => "REQ",
+        }
+    }
+}
+
+/// 一个已完成握手的 ZMTP 连接
+#[derive(Debug)]
+pub struct Connection {
+    stream: TcpStream,
+}
+
+impl Connection {
+    /// 在已建立的 TCP 连接上完成 ZMTP 握手
+    pub fn handshake(mut stream: TcpStream, socket_type: SocketType) -> io::Result<Self> {
+        stream.set_nodelay(true)?;
+        stream.write_all(&greeting())?;
+
+        let mut peer = [0u8; 64];
+        stream.read_exact(&mut peer)?;
+        if peer[0] != 0xFF || peer[9] != 0x7F {
+            return Err(invalid("not a ZMTP peer"));
+        }
+        if peer[10] < 3 {
+            return Err(invalid("ZMTP 3.0 or later is required"));
+        }
+        if !peer[12..32].starts_with(b"NULL") {
+            return Err(invalid("only the NULL security mechanism is supported"));
+        }
+
+        let mut connection = Connection { stream };
+        connection.write_frame(&ready(socket_type), FLAG_COMMAND)?;
+        match connection.read_frame()? {
+            (body, flags) if flags & FLAG_COMMAND != 0 && body.starts_with(b"\x05READY") => {
+                Ok(connection)
+            }
+            _ => Err(invalid("expected READY command")),
+        }
+    }
+
+    /// 连接到 ZMTP 对端（用于客户端和测试）
+    pub fn connect(addr: &str, socket_type: SocketType) -> io::Result<Self> {
+        Self::handshake(TcpStream::connect(addr)?, socket_type)
+    }
+
+    /// 复制连接（读写可在不同线程进行）
+    pub fn try_clone(&self) -> io::Result<Self> {
+        Ok(Connection {
+            stream: self.stream.try_clone()?,
+        })
+    }
+
+    /// 读取一条多帧消息（跳过命令帧）
+    pub fn recv(&mut self) -> io::Result<Vec<Vec<u8>>> {
+        let mut frames = Vec::new();
+        loop {
+            let (body, flags) = self.read_frame()?;
+            if flags & FLAG_COMMAND != 0 {
+                continue;
+            }
+            frames.push(body);
+            if flags & FLAG_MORE == 0 {
+                return Ok(frames);
+            }
+        }
+    }
+
+    /// 发送一条多帧消息
+    pub fn send(&mut self, frames: &[Vec<u8>]) -> io::Result<()> {
+        let mut buffer = Vec::new();
+        for (index, frame) in frames.iter().enumerate() {
+            let more = if index + 1 < frames.len() {
+                FLAG_MORE
+            } else {
+                0
+            };
+            encode_frame(&mut buffer, frame, more);
+        }
+        self.stream.write_all(&buffer)
+    }
+
+    fn read_frame(&mut self) -> io::Result<(Vec<u8>, u8)> {
+        let mut flags = [0u8; 1];
+        self.stream.read_exact(&mut flags)?;
+        let flags = flags[0];
+        let size = if flags & FLAG_LONG != 0 {
+            let mut size = [0u8; 8];
+            self.stream.read_exact(&mut size)?;
+            u64::from_be_bytes(size)
+        } else {
+            let mut size = [0u8; 1];
+            self.stream.read_exact(&mut size)?;
+            size[0] as u64
+        };
+        if size > MAX_FRAME {
+            return Err(invalid("frame too large"));
+        }
+        let mut body = vec![0u8; size as usize];
+        self.stream.read_exact(&mut body)?;
+        Ok((body, flags))
+    }
+
+    fn write_frame(&mut self, body: &[u8], flags: u8) -> io::Result<()> {
+        let mut buffer = Vec::new();
+        encode_frame(&mut buffer, body, flags);
+        self.stream.write_all(&buffer)
+    }
+}
+
+fn encode_frame(buffer: &mut Vec<u8>, body: &[u8], flags: u8) {
+    if body.len() > 255 {
+        buffer.push(flags | FLAG_LONG);
+        buffer.extend_from_slice(&(body.len() as u64).to_be_bytes());
+    } else {
+        buffer.push(flags);
+        buffer.push(body.len() as u8);
+    }
+    buffer.extend_from_slice(body);
+}
+
+/// 64 字节问候：签名、版本 3.0、NULL 机制（NULL 机制不使用 as-server 标志）
+fn greeting() -> [u8; 64] {
+    let mut greeting = [0u8; 64];
+    greeting[0] = 0xFF;
+    greeting[9] = 0x7F;
+    greeting[10] = 3;
+    greeting[11] = 0;
+    greeting[12..16].copy_from_slice(b"NULL");
+    greeting
+}
+
+/// READY 命令体：命令名和 Socket-Type 属性
+fn ready(socket_type: SocketType) -> Vec<u8> {
+    let mut body = vec![5];
+    body.extend_from_slice(b"READY");
+    let name = b"Socket-Type";
+    let value = socket_type.as_str().as_bytes();
+    body.push(name.len() as u8);
+    body.extend_from_slice(name);
+    body.extend_from_slice(&(value.len() as u32).to_be_bytes());
+    body.extend_from_slice(value);
+    body
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
@@ -3,7 +3,9 @@
 //!
 //! Converts source code into a stream of tokens
 
-use crate::token::Token;
+use crate::dialect::Dialect;
+use crate::token::{Span, Token};
+use std::sync::Arc;
 
 /// Lexer state
 pub struct Lexer {
@@ -14,6 +16,10 @@ pub struct Lexer {
     line: usize,          // current line number (for error reporting)
     column: usize,        // current column number (for error reporting)
     had_whitespace_before_token: bool, // whether whitespace was skipped before current token
+    token_start: usize,   // offset of the first char of the last token returned
+    token_line: usize,    // line of the first char of the last token returned
+    token_column: usize,  // column of the first char of the last token returned
+    dialect: Option<Arc<Dialect>>, // keyword aliases configured by the embedder
 }
 
 impl Lexer {
@@ -27,11 +33,22 @@ impl Lexer {
             line: 1,
             column: 0,
             had_whitespace_before_token: false,
+            token_start: 0,
+            token_line: 1,
+            token_column: 1,
+            dialect: None,
         };
         lexer.read_char(); // Initialize by reading the first character
         lexer
     }
 
+    /// Create a lexer that maps dialect keyword aliases to standard keywords
+    pub fn with_dialect(input: &str, dialect: Arc<Dialect>) -> Self {
+        let mut lexer = Lexer::new(input);
+        lexer.dialect = Some(dialect);
+        lexer
+    }
+
     /// Get current line number
     pub fn line(&self) -> usize {
         self.line
@@ -42,6 +59,66 @@ impl Lexer {
         self.column
     }
 
+    /// Character offset just past the last token returned
+    pub fn offset(&self) -> usize {
+        self.position.min(self.input.len())
+    }
+
+    /// Tokenize `code` with source spans, keeping comments and whitespace as trivia tokens
+    ///
+    /// Concatenating the source text of every span reproduces `code`, so editors and
+    /// highlighters can classify each character (see `Token::kind`). `EOF` is not included.
+    ///
+    /// # Example
+    /// ```
+    /// use aether::{Lexer, Token};
+    ///
+    /// let tokens = Lexer::tokenize_with_spans("Set X 1 // one");
+    /// assert_eq!(tokens[0].0, Token::Set);
+    /// assert_eq!(tokens[1].0, Token::Whitespace(" ".to_string()));
+    /// assert_eq!(tokens.last().unwrap().0, Token::Comment("// one".to_string()));
+    /// assert_eq!((tokens[2].1.start, tokens[2].1.end, tokens[2].1.column), (4, 5, 5));
+    /// ```
+    pub fn tokenize_with_spans(code: &str) -> Vec<(Token, Span)> {
+        let mut lexer = Lexer::new(code);
+        let mut tokens = Vec::new();
+        let mut cursor = SpanCursor::new();
+        loop {
+            let token = lexer.next_token();
+            let (start, end) = (lexer.token_start, lexer.offset());
+            for (trivia, trivia_start, trivia_end) in
+                split_trivia(&lexer.input, cursor.offset, start)
+            {
+                let span = cursor.span(&lexer.input, trivia_start, trivia_end);
+                tokens.push((trivia, span));
+            }
+            if token == Token::EOF {
+                break;
+            }
+            let span = cursor.span(&lexer.input, start, end.max(start));
+            tokens.push((token, span));
+        }
+        tokens
+    }
+
+    /// Source span of the last token returned
+    pub fn token_span(&self) -> Span {
+        Span {
+            start: self.token_start,
+            end: self.offset(),
+            line: self.token_line,
+            column: self.token_column,
+        }
+    }
+
+    /// 1-based line and column of a character offset
+    pub fn line_column_at(&self, offset: usize) -> (usize, usize) {
+        let before = &self.input[..offset.min(self.input.len())];
+        let line = before.iter().filter(|c| **c == '\n').count() + 1;
+        let column = before.iter().rev().take_while(|c| **c != '\n').count() + 1;
+        (line, column)
+    }
+
     /// Check if whitespace was skipped before the last token
     pub fn had_whitespace(&self) -> bool {
         self.had_whitespace_before_token
@@ -90,6 +167,9 @@ impl Lexer {
     pub fn next_token(&mut self) -> Token {
         let had_ws = self.skip_whitespace();
         self.had_whitespace_before_token = had_ws;
+        self.token_start = self.position.min(self.input.len());
+        self.token_line = self.line;
+        self.token_column = self.column;
 
         let token = match self.ch {
             // Operators
@@ -188,6 +268,12 @@ impl Lexer {
                 }
             }
 
+            // Extension block: @TAG { payload }
+            '@' => return self.read_extension_block(),
+
+            // Pragma: #NAME value
+            '#' => return self.read_pragma(),
+
             // Newline (statement separator)
             '\n' => Token::Newline,
 
@@ -257,7 +343,10 @@ impl Lexer {
         }
 
         let ident: String = self.input[start..self.position].iter().collect();
-        Token::lookup_keyword(&ident)
+        match &self.dialect {
+            Some(dialect) => Token::lookup_keyword(dialect.resolve(&ident)),
+            None => Token::lookup_keyword(&ident),
+        }
     }
 
     /// Read a number (integer or float)
@@ -278,9 +367,13 @@ impl Lexer {
 
         let num_str: String = self.input[start..self.position].iter().collect();
 
-        // 如果是整数且位数较多（超过15位，接近f64精度极限），作为大整数处理
-        if !has_dot && num_str.len() > 15 {
-            return Token::BigInteger(num_str);
+        // 整数字面量为 Integer，超出 i64 范围的作为大整数处理
+        if !has_dot {
+            return match num_str.parse::<i64>() {
+                Ok(num) => Token::Integer(num),
+                Err(_) if num_str.chars().all(|c| c.is_ascii_digit()) => Token::BigInteger(num_str),
+                Err(_) => Token::Illegal('0'), // Invalid number
+            };
         }
 
         match num_str.parse::<f64>() {
@@ -359,6 +452,79 @@ impl Lexer {
         }
     }
 
+    /// Read a pragma `#NAME value`; the value runs to the end of the line (trimmed)
+    fn read_pragma(&mut self) -> Token {
+        self.read_char(); // Skip '#'
+
+        let name_start = self.position;
+        while self.ch.is_alphanumeric() || self.ch == '_' {
+            self.read_char();
+        }
+        let name: String = self.input[name_start..self.position].iter().collect();
+        if name.is_empty() {
+            return Token::Illegal('#');
+        }
+
+        let value_start = self.position;
+        while self.ch != '\n' && self.ch != '\0' {
+            self.read_char();
+        }
+        let value: String = self.input[value_start..self.position].iter().collect();
+
+        Token::Pragma {
+            name,
+            value: value.trim().to_string(),
+        }
+    }
+
+    /// Read an extension block `@TAG { payload }`
+    ///
+    /// The payload is kept as raw text (trimmed); nested braces must be balanced.
+    fn read_extension_block(&mut self) -> Token {
+        self.read_char(); // Skip '@'
+
+        let tag_start = self.position;
+        while self.ch.is_alphanumeric() || self.ch == '_' {
+            self.read_char();
+        }
+        let tag: String = self.input[tag_start..self.position].iter().collect();
+        if tag.is_empty() {
+            return Token::Illegal('@');
+        }
+
+        while self.ch == ' ' || self.ch == '\t' {
+            self.read_char();
+        }
+        if self.ch != '{' {
+            return Token::Illegal('@');
+        }
+        self.read_char(); // Skip '{'
+
+        let start = self.position;
+        let mut depth = 1;
+        loop {
+            match self.ch {
+                '\0' => return Token::Illegal('@'), // Unterminated block
+                '{' => depth += 1,
+                '}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                _ => {}
+            }
+            self.read_char();
+        }
+        let payload: String = self.input[start..self.position].iter().collect();
+        self.read_char(); // Skip closing '}'
+
+        Token::Extension {
+            tag,
+            payload: payload.trim().to_string(),
+        }
+    }
+
     /// Process escape sequences in strings
     fn process_escapes(&self, s: &str) -> String {
         let mut result = String::new();
@@ -415,3 +581,77 @@ impl Lexer {
         result
     }
 }
+
+/// Tracks line/column while spans are produced in source order
+struct SpanCursor {
+    offset: usize,
+    line: usize,
+    column: usize,
+}
+
+impl SpanCursor {
+    fn new() -> Self {
+        SpanCursor {
+            offset: 0,
+            line: 1,
+            column: 1,
+        }
+    }
+
+    fn span(&mut self, input: &[char], start: usize, end: usize) -> Span {
+        for ch in &input[self.offset..start] {
+            self.advance(*ch);
+        }
+        let (line, column) = (self.line, self.column);
+        for ch in &input[start..end] {
+            self.advance(*ch);
+        }
+        self.offset = end;
+        Span {
+            start,
+            end,
+            line,
+            column,
+        }
+    }
+
+    fn advance(&mut self, ch: char) {
+        if ch == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+    }
+}
+
+/// Split the text between two tokens into comment and whitespace trivia
+fn split_trivia(input: &[char], start: usize, end: usize) -> Vec<(Token, usize, usize)> {
+    let mut trivia = Vec::new();
+    let mut pos = start;
+    while pos < end {
+        let from = pos;
+        let token_end = if input[pos] == '/' && input.get(pos + 1) == Some(&'/') {
+            end
+        } else if input[pos] == '/' && input.get(pos + 1) == Some(&'*') {
+            let mut close = pos + 2;
+            while close + 1 < end && !(input[close] == '*' && input[close + 1] == '/') {
+                close += 1;
+            }
+            (close + 2).min(end)
+        } else {
+            while pos < end && input[pos] != '/' {
+                pos += 1;
+            }
+            pos.max(from + 1)
+        };
+        pos = token_end;
+        let text: String = input[from..token_end].iter().collect();
+        if text.starts_with("//") || text.starts_with("/*") {
+            trivia.push((Token::Comment(text), from, token_end));
+        } else {
+            trivia.push((Token::Whitespace(text), from, token_end));
+        }
+    }
+    trivia
+}
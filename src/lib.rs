@@ -168,21 +168,37 @@
 //! aether script.aether
 //! ```
 
+pub mod analysis;
 pub mod ast;
 pub mod builtins;
 pub mod cache;
+pub mod compile;
 pub mod debugger;
+pub mod dialect;
 pub mod engine;
 pub mod environment;
 pub mod evaluator;
+#[cfg(feature = "kernel")]
+pub mod kernel;
 pub mod lexer;
+pub mod minify;
 pub mod module_system;
 pub mod optimizer;
 pub mod parser;
+pub mod plugin;
+pub mod project;
+pub mod result_cache;
+#[cfg(feature = "grpc")]
+pub mod rpc;
 pub mod runtime;
 pub mod sandbox;
+#[cfg(feature = "http-server")]
+pub mod server;
+#[cfg(feature = "signing")]
+pub mod signing;
 pub mod stdlib;
 pub mod token;
+pub mod tutorial;
 pub mod value;
 
 // FFI 和语言绑定
@@ -194,5 +210,5 @@ pub mod wasm;
 mod api;
 mod prelude;
 
-pub use api::Aether;
+pub use api::{Aether, BenchmarkReport, EnvExport, RulePolicy, RuleResult, RulesetReport};
 pub use prelude::*;
@@ -0,0 +1,358 @@
+// src/minify.rs
+//! 脚本压缩
+//!
+//! [`minify`] 把脚本压缩成语义相同的紧凑源代码，便于嵌入体积受限的载体
+//! （wasm 包、二维码中的配置等）：
+//!
+//! - 删除注释，去掉多余的空白和空行，只在两个记号会粘连时保留一个空格
+//! - 保留语义相关的空白：`Set A [1, 2]`（数组字面量）与 `Set A[0] 1`（下标赋值）不同
+//! - 可选地把顶层函数内部的局部名称（参数、循环变量、`Set` 的局部变量、嵌套 Lambda
+//!   的参数）改为短名称
+//!
+//! 压缩在记号层面进行，字符串、数字和扩展块按原文输出。全局变量、函数名和字典键
+//! 从不改名，宿主按名称读取或调用的内容不受影响。通过 `VARS()` 或字符串按名称访问
+//! 局部变量的脚本不应启用改名。
+
+use crate::builtins::{BuiltInRegistry, IOPermissions};
+use crate::lexer::Lexer;
+use crate::parser::{ParseError, Parser};
+use crate::token::Token;
+use std::collections::{HashMap, HashSet};
+
+/// 压缩选项
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MinifyOptions {
+    /// 把顶层函数内部的局部名称改为短名称
+    pub rename_locals: bool,
+}
+
+impl MinifyOptions {
+    /// 启用局部名称改名
+    pub fn with_rename_locals(mut self) -> Self {
+        self.rename_locals = true;
+        self
+    }
+}
+
+/// 压缩脚本，脚本无法解析时返回解析错误
+///
+/// # 示例
+///
+/// ```
+/// use aether::minify::{MinifyOptions, minify};
+///
+/// let code = "// 计算总价\nFunc TOTAL(PRICE, QTY) {\n    Return PRICE * QTY\n}\n\nTOTAL(2, 3)\n";
+/// let compact = minify(code, &MinifyOptions::default()).unwrap();
+/// assert_eq!(compact, "Func TOTAL(PRICE,QTY){Return PRICE*QTY}\nTOTAL(2,3)");
+///
+/// let renamed = minify(code, &MinifyOptions::default().with_rename_locals()).unwrap();
+/// assert_eq!(renamed, "Func TOTAL(A,B){Return A*B}\nTOTAL(2,3)");
+/// ```
+pub fn minify(code: &str, options: &MinifyOptions) -> Result<String, ParseError> {
+    Parser::new(code).parse_program()?;
+
+    let chars: Vec<char> = code.chars().collect();
+    let mut tokens: Vec<Lexeme> = Vec::new();
+    let mut trivia_before = false;
+    for (token, span) in Lexer::tokenize_with_spans(code) {
+        if token.is_trivia() {
+            trivia_before = true;
+            continue;
+        }
+        tokens.push(Lexeme {
+            text: chars[span.start..span.end].iter().collect(),
+            token,
+            space_before: trivia_before,
+        });
+        trivia_before = false;
+    }
+
+    if options.rename_locals {
+        rename_locals(&mut tokens);
+    }
+    Ok(emit(&tokens))
+}
+
+/// 一个非空白记号及其原文
+struct Lexeme {
+    token: Token,
+    text: String,
+    /// 原文中记号前是否有空白或注释
+    space_before: bool,
+}
+
+/// 输出压缩后的源代码
+fn emit(tokens: &[Lexeme]) -> String {
+    let mut out = String::new();
+    let mut prev: Option<&Lexeme> = None;
+    let mut pending_newline = false;
+
+    for lexeme in tokens {
+        if lexeme.token == Token::Newline {
+            // 开括号和逗号之后、以及开头的换行都不影响解析
+            pending_newline |= prev.is_some_and(|p| {
+                !matches!(
+                    p.token,
+                    Token::LeftBrace | Token::LeftParen | Token::LeftBracket | Token::Comma
+                )
+            });
+            continue;
+        }
+
+        if let Some(p) = prev {
+            let closing = matches!(
+                lexeme.token,
+                Token::RightBrace | Token::RightParen | Token::RightBracket
+            );
+            // 编译指示一直延续到行尾，之后必须换行
+            if matches!(p.token, Token::Pragma { .. }) || (pending_newline && !closing) {
+                out.push('\n');
+            } else if needs_space(p, lexeme) {
+                out.push(' ');
+            }
+        }
+        pending_newline = false;
+        out.push_str(&lexeme.text);
+        prev = Some(lexeme);
+    }
+    out
+}
+
+/// 两个相邻记号之间是否需要保留空格
+fn needs_space(prev: &Lexeme, next: &Lexeme) -> bool {
+    // `NAME [..]` 是数组字面量，`NAME[..]` 是下标
+    if next.token == Token::LeftBracket && next.space_before {
+        return true;
+    }
+    // 拼接后重新分词，结果不同（如 `Set X` -> `SetX`、`- -` 无影响）就需要空格
+    let joined = format!("{}{}", prev.text, next.text);
+    let mut lexer = Lexer::new(&joined);
+    lexer.next_token() != prev.token || lexer.next_token() != next.token || {
+        lexer.next_token() != Token::EOF
+    }
+}
+
+/// 把每个顶层函数内的局部名称改为短名称
+fn rename_locals(tokens: &mut [Lexeme]) {
+    let keys = dict_key_positions(tokens);
+    let is_name = |i: usize, tokens: &[Lexeme]| -> Option<String> {
+        match &tokens[i].token {
+            Token::Identifier(name) if !keys.contains(&i) => Some(name.clone()),
+            _ => None,
+        }
+    };
+
+    let builtins: HashSet<String> = BuiltInRegistry::with_permissions(IOPermissions::allow_all())
+        .names()
+        .into_iter()
+        .collect();
+    let mut reserved = builtins.clone();
+    let mut total: HashMap<String, usize> = HashMap::new();
+    for i in 0..tokens.len() {
+        if let Token::Identifier(name) = &tokens[i].token {
+            reserved.insert(name.clone());
+            if is_name(i, tokens).is_some() {
+                *total.entry(name.clone()).or_default() += 1;
+            }
+        }
+    }
+
+    for (name, start, end) in top_level_functions(tokens) {
+        let bindings = binding_positions(tokens, start, end);
+
+        let mut inside: HashMap<String, usize> = HashMap::new();
+        let mut first_is_binding: HashMap<String, bool> = HashMap::new();
+        for i in start..=end {
+            if let Some(local) = is_name(i, tokens) {
+                *inside.entry(local.clone()).or_default() += 1;
+                first_is_binding
+                    .entry(local)
+                    .or_insert_with(|| bindings.contains(&i));
+            }
+        }
+
+        let mut candidates: Vec<&String> = first_is_binding
+            .iter()
+            .filter(|(local, first)| {
+                **first
+                    && **local != name
+                    && inside[*local] == total[*local]
+                    && !builtins.contains(*local)
+            })
+            .map(|(local, _)| local)
+            .collect();
+        // 按首次出现的位置分配短名称，输出稳定
+        candidates.sort_by_key(|local| {
+            (start..=end)
+                .find(|&i| is_name(i, tokens).as_deref() == Some(local.as_str()))
+                .unwrap_or(end)
+        });
+
+        let mut short_names = ShortNames::default();
+        let renames: HashMap<String, String> = candidates
+            .into_iter()
+            .map(|local| (local.clone(), short_names.next(&reserved)))
+            .collect();
+        for i in start..=end {
+            if let Some(local) = is_name(i, tokens)
+                && let Some(short) = renames.get(&local)
+            {
+                tokens[i].text = short.clone();
+                tokens[i].token = Token::Identifier(short.clone());
+            }
+        }
+    }
+}
+
+/// 顶层 `Func NAME(...) { ... }` / `Generator NAME(...) { ... }`：(函数名, 参数表 `(` 位置, 函数体 `}` 位置)
+fn top_level_functions(tokens: &[Lexeme]) -> Vec<(String, usize, usize)> {
+    let mut functions = Vec::new();
+    let mut depth = 0usize;
+    let mut i = 0;
+    while i < tokens.len() {
+        match &tokens[i].token {
+            Token::Func | Token::Generator if depth == 0 => {
+                if let (Some(Token::Identifier(name)), Some(Token::LeftParen)) = (
+                    tokens.get(i + 1).map(|t| &t.token),
+                    tokens.get(i + 2).map(|t| &t.token),
+                ) && let Some(params_end) = matching(tokens, i + 2)
+                    && let Some(open) =
+                        (params_end + 1..tokens.len()).find(|&j| tokens[j].token != Token::Newline)
+                    && tokens[open].token == Token::LeftBrace
+                    && let Some(end) = matching(tokens, open)
+                {
+                    functions.push((name.clone(), i + 2, end));
+                    i = end + 1;
+                    continue;
+                }
+            }
+            Token::LeftParen | Token::LeftBrace | Token::LeftBracket => depth += 1,
+            Token::RightParen | Token::RightBrace | Token::RightBracket => {
+                depth = depth.saturating_sub(1)
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    functions
+}
+
+/// 与 `open` 处的开括号匹配的闭括号位置
+fn matching(tokens: &[Lexeme], open: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    for (i, lexeme) in tokens.iter().enumerate().skip(open) {
+        match lexeme.token {
+            Token::LeftParen | Token::LeftBrace | Token::LeftBracket => depth += 1,
+            Token::RightParen | Token::RightBrace | Token::RightBracket => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// 函数内绑定名称的位置：参数、嵌套函数名及其参数、Lambda 参数、`Set` / `Lazy` 目标、循环变量、`Catch` 变量
+fn binding_positions(tokens: &[Lexeme], start: usize, end: usize) -> HashSet<usize> {
+    let mut bindings = HashSet::new();
+    let params = |open: usize, bindings: &mut HashSet<usize>| {
+        if let Some(close) = matching(tokens, open) {
+            bindings.extend(
+                (open + 1..close).filter(|&j| matches!(tokens[j].token, Token::Identifier(_))),
+            );
+        }
+    };
+
+    params(start, &mut bindings);
+    for i in start + 1..end {
+        let next = tokens.get(i + 1).map(|t| &t.token);
+        match &tokens[i].token {
+            Token::Func | Token::Generator | Token::Lambda => match next {
+                Some(Token::Identifier(_)) => {
+                    bindings.insert(i + 1);
+                    if tokens.get(i + 2).map(|t| &t.token) == Some(&Token::LeftParen) {
+                        params(i + 2, &mut bindings);
+                    }
+                }
+                Some(Token::LeftParen) => params(i + 1, &mut bindings),
+                _ => {}
+            },
+            Token::Set => {
+                // `Set NAME[i] v` 修改已有的变量，不是绑定
+                let indexed = tokens
+                    .get(i + 2)
+                    .is_some_and(|t| t.token == Token::LeftBracket && !t.space_before);
+                if matches!(next, Some(Token::Identifier(_))) && !indexed {
+                    bindings.insert(i + 1);
+                }
+            }
+            Token::Lazy if matches!(next, Some(Token::Identifier(_))) => {
+                bindings.insert(i + 1);
+            }
+            Token::Catch if next == Some(&Token::LeftParen) => {
+                bindings.insert(i + 2);
+            }
+            Token::For => {
+                let mut j = i + 1;
+                while j < end && tokens[j].token != Token::In {
+                    if matches!(tokens[j].token, Token::Identifier(_)) {
+                        bindings.insert(j);
+                    }
+                    j += 1;
+                }
+            }
+            _ => {}
+        }
+    }
+    bindings
+}
+
+/// 字典字面量中作为键的标识符位置（`{KEY: ...}`、`, KEY: ...`）
+fn dict_key_positions(tokens: &[Lexeme]) -> HashSet<usize> {
+    let mut keys = HashSet::new();
+    for i in 0..tokens.len() {
+        if !matches!(tokens[i].token, Token::Identifier(_))
+            || tokens.get(i + 1).map(|t| &t.token) != Some(&Token::Colon)
+        {
+            continue;
+        }
+        let before = tokens[..i]
+            .iter()
+            .rev()
+            .find(|t| t.token != Token::Newline)
+            .map(|t| &t.token);
+        if matches!(before, Some(Token::LeftBrace | Token::Comma)) {
+            keys.insert(i);
+        }
+    }
+    keys
+}
+
+/// 依次生成 `A`、`B`、...、`Z`、`AA`、`AB`、... 跳过已占用的名称
+#[derive(Default)]
+struct ShortNames {
+    counter: usize,
+}
+
+impl ShortNames {
+    fn next(&mut self, reserved: &HashSet<String>) -> String {
+        loop {
+            let mut n = self.counter;
+            self.counter += 1;
+            let mut name = String::new();
+            loop {
+                name.insert(0, (b'A' + (n % 26) as u8) as char);
+                if n < 26 {
+                    break;
+                }
+                n = n / 26 - 1;
+            }
+            if !reserved.contains(&name) {
+                return name;
+            }
+        }
+    }
+}
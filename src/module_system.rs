@@ -1,5 +1,11 @@
 use std::path::{Path, PathBuf};
 
+/// Specifier prefix for embedded stdlib modules, e.g. `Import SET From "stdlib:set"`.
+///
+/// Stdlib modules are resolved by the evaluator itself, so they stay importable even
+/// when filesystem imports are disabled.
+pub const STDLIB_SPECIFIER_PREFIX: &str = "stdlib:";
+
 #[derive(Debug, Clone)]
 pub struct ModuleContext {
     pub module_id: String,
@@ -160,3 +166,46 @@ impl ModuleResolver for FileSystemModuleResolver {
         })
     }
 }
+
+/// Resolver for multi-file projects (see `crate::project::Project`).
+///
+/// - `./x` and `../x` resolve relative to the importing module
+/// - any other relative specifier resolves relative to the project root (the manifest directory)
+///
+/// Resolved modules must stay under the project root.
+#[derive(Debug, Clone)]
+pub struct ProjectModuleResolver {
+    files: FileSystemModuleResolver,
+}
+
+impl ProjectModuleResolver {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        ProjectModuleResolver {
+            files: FileSystemModuleResolver {
+                root_dir: Some(root.into()),
+                allow_absolute: false,
+            },
+        }
+    }
+
+    fn is_relative_to_importer(specifier: &str) -> bool {
+        specifier.starts_with("./") || specifier.starts_with("../")
+    }
+}
+
+impl ModuleResolver for ProjectModuleResolver {
+    fn resolve(
+        &self,
+        specifier: &str,
+        from: Option<&ModuleContext>,
+    ) -> Result<ResolvedModule, ModuleResolveError> {
+        if Self::is_relative_to_importer(specifier) {
+            return self.files.resolve(specifier, from);
+        }
+        let root = ModuleContext {
+            module_id: String::new(),
+            base_dir: self.files.root_dir.clone(),
+        };
+        self.files.resolve(specifier, Some(&root))
+    }
+}
@@ -1,7 +1,10 @@
 // src/optimizer.rs
-//! 代码优化器 - 包含尾递归优化、常量折叠等
+//! 代码优化器 - 包含尾递归优化、常量折叠、热点函数内联等
 
 use crate::ast::{BinOp, Expr, Program, Stmt, UnaryOp};
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
 /// 代码优化器
 pub struct Optimizer {
@@ -11,6 +14,12 @@ pub struct Optimizer {
     pub constant_folding: bool,
     /// 是否启用死代码消除
     pub dead_code_elimination: bool,
+    /// 是否按调用次数内联热点函数（见 `inline_hot_functions`）
+    pub profile_guided: bool,
+    /// 调用次数达到该值的函数视为热点
+    pub hot_call_threshold: usize,
+    /// 优化过程中发现的问题（如被移除的死代码）
+    warnings: RefCell<Vec<String>>,
 }
 
 impl Optimizer {
@@ -20,9 +29,21 @@ impl Optimizer {
             tail_recursion: true,
             constant_folding: true,
             dead_code_elimination: true,
+            profile_guided: false,
+            hot_call_threshold: 100,
+            warnings: RefCell::new(Vec::new()),
         }
     }
 
+    /// 取出优化过程中记录的警告
+    pub fn take_warnings(&self) -> Vec<String> {
+        std::mem::take(&mut *self.warnings.borrow_mut())
+    }
+
+    fn warn(&self, message: &str) {
+        self.warnings.borrow_mut().push(message.to_string());
+    }
+
     /// 优化整个程序
     pub fn optimize_program(&self, program: &Program) -> Program {
         let mut optimized = program.clone();
@@ -56,6 +77,9 @@ impl Optimizer {
     /// 折叠语句中的常量
     fn fold_stmt(&self, stmt: Stmt) -> Stmt {
         match stmt {
+            Stmt::Located { span, stmt } => {
+                Stmt::located(span, self.fold_stmt(Arc::unwrap_or_clone(stmt)))
+            }
             Stmt::Set { name, value } => Stmt::Set {
                 name,
                 value: self.fold_expr(value),
@@ -96,6 +120,21 @@ impl Optimizer {
                 iterable: self.fold_expr(iterable),
                 body: body.into_iter().map(|s| self.fold_stmt(s)).collect(),
             },
+            Stmt::Try {
+                body,
+                catch,
+                finally,
+            } => Stmt::Try {
+                body: body.into_iter().map(|s| self.fold_stmt(s)).collect(),
+                catch: catch.map(|(var, handler)| {
+                    (
+                        var,
+                        handler.into_iter().map(|s| self.fold_stmt(s)).collect(),
+                    )
+                }),
+                finally: finally
+                    .map(|cleanup| cleanup.into_iter().map(|s| self.fold_stmt(s)).collect()),
+            },
             Stmt::Expression(expr) => Stmt::Expression(self.fold_expr(expr)),
             other => other,
         }
@@ -117,6 +156,27 @@ impl Optimizer {
                     return Expr::Number(result);
                 }
 
+                // 整数之间精确折叠；溢出或除不尽时留给运行时处理
+                if let (Expr::Integer(l), Expr::Integer(r)) = (&left, &right)
+                    && let Some(result) = Self::eval_const_integer(*l, &op, *r)
+                {
+                    return Expr::Integer(result);
+                }
+
+                // 整数与浮点数混合时按浮点数折叠
+                if let (Expr::Integer(i), Expr::Number(n)) | (Expr::Number(n), Expr::Integer(i)) =
+                    (&left, &right)
+                {
+                    let (l, r) = if matches!(left, Expr::Integer(_)) {
+                        (*i as f64, *n)
+                    } else {
+                        (*n, *i as f64)
+                    };
+                    if let Some(result) = Self::eval_const_binary(l, &op, r) {
+                        return Expr::Number(result);
+                    }
+                }
+
                 Expr::Binary {
                     left: Box::new(left),
                     op,
@@ -135,6 +195,17 @@ impl Optimizer {
                     }
                 }
 
+                if let Expr::Integer(n) = expr {
+                    match op {
+                        UnaryOp::Minus => {
+                            if let Some(negated) = n.checked_neg() {
+                                return Expr::Integer(negated);
+                            }
+                        }
+                        UnaryOp::Not => return Expr::Boolean(n == 0),
+                    }
+                }
+
                 if let (UnaryOp::Not, Expr::Boolean(b)) = (&op, &expr) {
                     return Expr::Boolean(!b);
                 }
@@ -176,6 +247,20 @@ impl Optimizer {
         }
     }
 
+    /// 计算整数常量二元运算，结果无法用 i64 精确表示时返回 None
+    fn eval_const_integer(left: i64, op: &BinOp, right: i64) -> Option<i64> {
+        match op {
+            BinOp::Add => left.checked_add(right),
+            BinOp::Subtract => left.checked_sub(right),
+            BinOp::Multiply => left.checked_mul(right),
+            BinOp::Divide if right != 0 && left.checked_rem(right) == Some(0) => {
+                left.checked_div(right)
+            }
+            BinOp::Modulo if right != 0 => left.checked_rem(right),
+            _ => None,
+        }
+    }
+
     /// 死代码消除
     fn eliminate_dead_code(&self, program: Program) -> Program {
         program
@@ -187,10 +272,16 @@ impl Optimizer {
     /// 消除死语句
     fn eliminate_dead_stmt(&self, stmt: Stmt) -> Option<Stmt> {
         match stmt {
+            // 保留源码位置，被删除的语句连同位置一起删除
+            Stmt::Located { span, stmt } => self
+                .eliminate_dead_stmt(Arc::unwrap_or_clone(stmt))
+                .map(|stmt| Stmt::located(span, stmt)),
+
             // While循环的常量条件
             Stmt::While { condition, body } => {
                 if let Expr::Boolean(false) = condition {
                     // 永远不执行的循环可以删除
+                    self.warn("While condition is always False; the loop was removed");
                     return None;
                 }
 
@@ -240,6 +331,9 @@ impl Optimizer {
             } => {
                 if let Expr::Boolean(true) = *condition {
                     // 条件永远为真,简化为then分支
+                    if !elif_branches.is_empty() || else_branch.is_some() {
+                        self.warn("If condition is always True; the other branches were removed");
+                    }
                     return Expr::If {
                         condition: Box::new(Expr::Boolean(true)),
                         then_branch,
@@ -250,6 +344,7 @@ impl Optimizer {
 
                 if let Expr::Boolean(false) = *condition {
                     // 条件永远为假,检查elif或else
+                    self.warn("If condition is always False; its branch was removed");
                     if let Some(else_body) = else_branch {
                         // 简化为else块
                         return Expr::If {
@@ -303,6 +398,10 @@ impl Optimizer {
     /// 优化尾递归语句
     fn optimize_tail_recursive_stmt(&self, stmt: Stmt) -> Stmt {
         match stmt {
+            Stmt::Located { span, stmt } => Stmt::located(
+                span,
+                self.optimize_tail_recursive_stmt(Arc::unwrap_or_clone(stmt)),
+            ),
             Stmt::FuncDef { name, params, body } => {
                 // 检查函数体是否包含尾递归
                 if self.is_tail_recursive(&name, &body) {
@@ -339,7 +438,7 @@ impl Optimizer {
 
     /// 检查语句是否包含尾递归
     fn stmt_has_tail_recursion(&self, func_name: &str, stmt: &Stmt) -> bool {
-        match stmt {
+        match stmt.node() {
             Stmt::Return(expr) => self.is_tail_call(func_name, expr),
             Stmt::Expression(expr) => self.expr_has_tail_recursion(func_name, expr),
             Stmt::While { body, .. } => self.has_tail_recursion_in_body(func_name, body),
@@ -413,7 +512,7 @@ impl Optimizer {
     /// 检查分支是否以尾调用结束
     fn branch_ends_with_tail_call(&self, func_name: &str, branch: &[Stmt]) -> bool {
         if let Some(last_stmt) = branch.last() {
-            match last_stmt {
+            match last_stmt.node() {
                 Stmt::Return(expr) => self.is_tail_call(func_name, expr),
                 Stmt::Expression(expr) => {
                     // 表达式可能是If表达式
@@ -473,6 +572,16 @@ impl Optimizer {
 
         for stmt in body {
             match stmt {
+                // 转换后的语句沿用原语句的源码位置
+                Stmt::Located { span, stmt } => loop_body.extend(
+                    self.transform_body_to_loop(
+                        func_name,
+                        params,
+                        vec![Arc::unwrap_or_clone(stmt)],
+                    )
+                    .into_iter()
+                    .map(|s| Stmt::located(span, s)),
+                ),
                 Stmt::Return(expr) => {
                     // 检查是否为尾递归调用
                     if let Some(new_args) = self.extract_tail_call_args(func_name, &expr) {
@@ -589,12 +698,478 @@ impl Optimizer {
     }
 }
 
+/// 可以内联的函数：`Func NAME(PARAMS) { Return EXPR }`
+struct InlineCandidate {
+    /// 定义所在的顶层语句位置
+    position: usize,
+    params: Vec<String>,
+    body: Expr,
+    /// 函数体中引用的非参数名称，调用处的局部变量不能与之同名
+    free: Vec<String>,
+}
+
+impl Optimizer {
+    /// 内联热点函数
+    ///
+    /// `hot` 为调用次数达到阈值的函数名。满足以下条件的调用会被替换为函数体表达式：
+    /// - 函数在顶层定义，只定义一次，程序中没有其他语句重新绑定该名称
+    /// - 函数体只有一条 `Return 表达式`，表达式不含 If/Lambda，也不递归调用自身
+    /// - 调用参数都是字面量或变量（不会因为替换而重复求值或丢失副作用）
+    /// - 调用位于顶层或顶层函数体中，且调用处的局部变量不会遮蔽函数体引用的外部名称
+    ///
+    /// 内联后的调用不再出现在调用栈和递归深度中。没有可内联的调用时返回 None。
+    pub fn inline_hot_functions(&self, program: &Program, hot: &[String]) -> Option<Program> {
+        let mut bindings: HashMap<String, usize> = HashMap::new();
+        Self::count_bindings(program, &mut bindings);
+
+        let candidates: HashMap<String, InlineCandidate> = program
+            .iter()
+            .enumerate()
+            .filter_map(|(position, stmt)| match stmt.node() {
+                Stmt::FuncDef { name, params, body }
+                    if hot.contains(name) && bindings.get(name) == Some(&1) =>
+                {
+                    Self::inline_candidate(position, name, params, body).map(|c| (name.clone(), c))
+                }
+                _ => None,
+            })
+            .collect();
+        if candidates.is_empty() {
+            return None;
+        }
+
+        // 顶层循环和分支中绑定的名称可能位于子作用域，与函数看到的全局变量不同
+        let mut block_locals = HashSet::new();
+        for stmt in program {
+            if !matches!(
+                stmt.node(),
+                Stmt::FuncDef { .. }
+                    | Stmt::GeneratorDef { .. }
+                    | Stmt::Set { .. }
+                    | Stmt::LazyDef { .. }
+                    | Stmt::Import { .. }
+            ) {
+                Self::collect_locals(std::slice::from_ref(stmt), &mut block_locals);
+            }
+        }
+
+        let mut inlined = 0;
+        let program: Program = program
+            .iter()
+            .enumerate()
+            .map(|(position, stmt)| {
+                // 只内联在调用语句之前定义的函数，之前的调用可能引用同名的旧定义
+                let candidates: HashMap<&str, &InlineCandidate> = candidates
+                    .iter()
+                    .filter(|(_, c)| c.position < position)
+                    .map(|(name, c)| (name.as_str(), c))
+                    .collect();
+                (stmt, candidates)
+            })
+            .map(|(stmt, candidates)| match stmt.node() {
+                Stmt::FuncDef { name, params, body } => {
+                    let mut locals: HashSet<String> = params.iter().cloned().collect();
+                    Self::collect_locals(body, &mut locals);
+                    let func = Stmt::FuncDef {
+                        name: name.clone(),
+                        params: params.clone(),
+                        body: body
+                            .iter()
+                            .map(|s| Self::inline_stmt(s, &candidates, &locals, &mut inlined))
+                            .collect(),
+                    };
+                    match stmt.span() {
+                        Some(span) => Stmt::located(span, func),
+                        None => func,
+                    }
+                }
+                _ => Self::inline_stmt(stmt, &candidates, &block_locals, &mut inlined),
+            })
+            .collect();
+
+        if inlined == 0 {
+            return None;
+        }
+        Some(if self.constant_folding {
+            self.fold_constants(program)
+        } else {
+            program
+        })
+    }
+
+    fn inline_candidate(
+        position: usize,
+        name: &str,
+        params: &[String],
+        body: &[Stmt],
+    ) -> Option<InlineCandidate> {
+        let [stmt] = body else {
+            return None;
+        };
+        let Stmt::Return(expr) = stmt.node() else {
+            return None;
+        };
+        if !Self::is_inlinable_expr(expr) {
+            return None;
+        }
+        let free: Vec<String> = expr
+            .referenced_names()
+            .into_iter()
+            .filter(|n| !params.contains(n))
+            .collect();
+        if free.iter().any(|n| n == name) {
+            return None;
+        }
+        Some(InlineCandidate {
+            position,
+            params: params.to_vec(),
+            body: expr.clone(),
+            free,
+        })
+    }
+
+    /// 只由表达式组成（不含语句块）的函数体才能安全地代入
+    fn is_inlinable_expr(expr: &Expr) -> bool {
+        match expr {
+            Expr::Number(_)
+            | Expr::Integer(_)
+            | Expr::BigInteger(_)
+            | Expr::String(_)
+            | Expr::Boolean(_)
+            | Expr::Null
+            | Expr::Identifier(_) => true,
+            Expr::Binary { left, right, .. } => {
+                Self::is_inlinable_expr(left) && Self::is_inlinable_expr(right)
+            }
+            Expr::Unary { expr, .. } => Self::is_inlinable_expr(expr),
+            Expr::Call { func, args } => {
+                Self::is_inlinable_expr(func) && args.iter().all(Self::is_inlinable_expr)
+            }
+            Expr::Array(items) => items.iter().all(Self::is_inlinable_expr),
+            Expr::Dict(pairs) => pairs.iter().all(|(_, v)| Self::is_inlinable_expr(v)),
+            Expr::Index { object, index } => {
+                Self::is_inlinable_expr(object) && Self::is_inlinable_expr(index)
+            }
+            Expr::If { .. } | Expr::Lambda { .. } | Expr::Extension { .. } => false,
+        }
+    }
+
+    /// 统计程序中（包括嵌套块）每个名称被绑定的次数
+    fn count_bindings(stmts: &[Stmt], bindings: &mut HashMap<String, usize>) {
+        for stmt in stmts {
+            match stmt.node() {
+                Stmt::Set { name, .. } | Stmt::LazyDef { name, .. } => {
+                    *bindings.entry(name.clone()).or_default() += 1;
+                }
+                Stmt::FuncDef { name, body, .. } | Stmt::GeneratorDef { name, body, .. } => {
+                    *bindings.entry(name.clone()).or_default() += 1;
+                    Self::count_bindings(body, bindings);
+                }
+                Stmt::Import {
+                    names,
+                    aliases,
+                    namespace,
+                    ..
+                } => {
+                    for (i, name) in names.iter().enumerate() {
+                        let bound = aliases.get(i).cloned().flatten().unwrap_or(name.clone());
+                        *bindings.entry(bound).or_default() += 1;
+                    }
+                    if let Some(ns) = namespace {
+                        *bindings.entry(ns.clone()).or_default() += 1;
+                    }
+                }
+                Stmt::For { var, body, .. } => {
+                    *bindings.entry(var.clone()).or_default() += 1;
+                    Self::count_bindings(body, bindings);
+                }
+                Stmt::ForIndexed {
+                    index_var,
+                    value_var,
+                    body,
+                    ..
+                } => {
+                    *bindings.entry(index_var.clone()).or_default() += 1;
+                    *bindings.entry(value_var.clone()).or_default() += 1;
+                    Self::count_bindings(body, bindings);
+                }
+                Stmt::While { body, .. } => Self::count_bindings(body, bindings),
+                Stmt::Switch { cases, default, .. } => {
+                    for (_, body) in cases {
+                        Self::count_bindings(body, bindings);
+                    }
+                    if let Some(body) = default {
+                        Self::count_bindings(body, bindings);
+                    }
+                }
+                Stmt::Try {
+                    body,
+                    catch,
+                    finally,
+                } => {
+                    Self::count_bindings(body, bindings);
+                    if let Some((var, handler)) = catch {
+                        *bindings.entry(var.clone()).or_default() += 1;
+                        Self::count_bindings(handler, bindings);
+                    }
+                    if let Some(cleanup) = finally {
+                        Self::count_bindings(cleanup, bindings);
+                    }
+                }
+                Stmt::Expression(Expr::If {
+                    then_branch,
+                    elif_branches,
+                    else_branch,
+                    ..
+                }) => {
+                    Self::count_bindings(then_branch, bindings);
+                    for (_, body) in elif_branches {
+                        Self::count_bindings(body, bindings);
+                    }
+                    if let Some(body) = else_branch {
+                        Self::count_bindings(body, bindings);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// 函数体中创建的局部名称（Set、循环变量、内部定义）
+    fn collect_locals(stmts: &[Stmt], locals: &mut HashSet<String>) {
+        let mut bindings = HashMap::new();
+        Self::count_bindings(stmts, &mut bindings);
+        locals.extend(bindings.into_keys());
+    }
+
+    fn inline_stmt(
+        stmt: &Stmt,
+        candidates: &HashMap<&str, &InlineCandidate>,
+        locals: &HashSet<String>,
+        inlined: &mut usize,
+    ) -> Stmt {
+        let expr =
+            |e: &Expr, inlined: &mut usize| Self::inline_expr(e, candidates, locals, inlined);
+        let block = |b: &[Stmt], inlined: &mut usize| -> Vec<Stmt> {
+            b.iter()
+                .map(|s| Self::inline_stmt(s, candidates, locals, inlined))
+                .collect()
+        };
+        match stmt {
+            Stmt::Located { span, stmt } => {
+                Stmt::located(*span, Self::inline_stmt(stmt, candidates, locals, inlined))
+            }
+            Stmt::Set { name, value } => Stmt::Set {
+                name: name.clone(),
+                value: expr(value, inlined),
+            },
+            Stmt::SetIndex {
+                object,
+                index,
+                value,
+            } => Stmt::SetIndex {
+                object: Box::new(expr(object, inlined)),
+                index: Box::new(expr(index, inlined)),
+                value: expr(value, inlined),
+            },
+            Stmt::Return(value) => Stmt::Return(expr(value, inlined)),
+            Stmt::Yield(value) => Stmt::Yield(expr(value, inlined)),
+            Stmt::Throw(value) => Stmt::Throw(expr(value, inlined)),
+            Stmt::Expression(value) => Stmt::Expression(expr(value, inlined)),
+            Stmt::While { condition, body } => Stmt::While {
+                condition: expr(condition, inlined),
+                body: block(body, inlined),
+            },
+            Stmt::For {
+                var,
+                iterable,
+                body,
+            } => Stmt::For {
+                var: var.clone(),
+                iterable: expr(iterable, inlined),
+                body: block(body, inlined),
+            },
+            Stmt::ForIndexed {
+                index_var,
+                value_var,
+                iterable,
+                body,
+            } => Stmt::ForIndexed {
+                index_var: index_var.clone(),
+                value_var: value_var.clone(),
+                iterable: expr(iterable, inlined),
+                body: block(body, inlined),
+            },
+            Stmt::Switch {
+                expr: subject,
+                cases,
+                default,
+            } => Stmt::Switch {
+                expr: expr(subject, inlined),
+                cases: cases
+                    .iter()
+                    .map(|(case, body)| (expr(case, inlined), block(body, inlined)))
+                    .collect(),
+                default: default.as_ref().map(|body| block(body, inlined)),
+            },
+            Stmt::Try {
+                body,
+                catch,
+                finally,
+            } => Stmt::Try {
+                body: block(body, inlined),
+                catch: catch
+                    .as_ref()
+                    .map(|(var, handler)| (var.clone(), block(handler, inlined))),
+                finally: finally.as_ref().map(|cleanup| block(cleanup, inlined)),
+            },
+            // 嵌套函数、生成器和惰性值有自己的作用域，保持原样
+            other => other.clone(),
+        }
+    }
+
+    fn inline_expr(
+        expr: &Expr,
+        candidates: &HashMap<&str, &InlineCandidate>,
+        locals: &HashSet<String>,
+        inlined: &mut usize,
+    ) -> Expr {
+        let recurse =
+            |e: &Expr, inlined: &mut usize| Self::inline_expr(e, candidates, locals, inlined);
+        match expr {
+            Expr::Call { func, args } => {
+                let args: Vec<Expr> = args.iter().map(|a| recurse(a, inlined)).collect();
+                if let Expr::Identifier(name) = func.as_ref()
+                    && let Some(candidate) = candidates.get(name.as_str())
+                    && candidate.params.len() == args.len()
+                    && !locals.contains(name)
+                    && !candidate.free.iter().any(|n| locals.contains(n))
+                    && args.iter().all(|a| {
+                        matches!(
+                            a,
+                            Expr::Number(_)
+                                | Expr::Integer(_)
+                                | Expr::BigInteger(_)
+                                | Expr::String(_)
+                                | Expr::Boolean(_)
+                                | Expr::Null
+                                | Expr::Identifier(_)
+                        )
+                    })
+                {
+                    *inlined += 1;
+                    let substitutions: HashMap<&str, &Expr> = candidate
+                        .params
+                        .iter()
+                        .map(String::as_str)
+                        .zip(args.iter())
+                        .collect();
+                    return Self::substitute(&candidate.body, &substitutions);
+                }
+                Expr::Call {
+                    func: Box::new(recurse(func, inlined)),
+                    args,
+                }
+            }
+            Expr::Binary { left, op, right } => Expr::Binary {
+                left: Box::new(recurse(left, inlined)),
+                op: op.clone(),
+                right: Box::new(recurse(right, inlined)),
+            },
+            Expr::Unary { op, expr } => Expr::Unary {
+                op: op.clone(),
+                expr: Box::new(recurse(expr, inlined)),
+            },
+            Expr::Array(items) => Expr::Array(items.iter().map(|i| recurse(i, inlined)).collect()),
+            Expr::Dict(pairs) => Expr::Dict(
+                pairs
+                    .iter()
+                    .map(|(k, v)| (k.clone(), recurse(v, inlined)))
+                    .collect(),
+            ),
+            Expr::Index { object, index } => Expr::Index {
+                object: Box::new(recurse(object, inlined)),
+                index: Box::new(recurse(index, inlined)),
+            },
+            Expr::If {
+                condition,
+                then_branch,
+                elif_branches,
+                else_branch,
+            } => {
+                let block = |b: &[Stmt], inlined: &mut usize| -> Vec<Stmt> {
+                    b.iter()
+                        .map(|s| Self::inline_stmt(s, candidates, locals, inlined))
+                        .collect()
+                };
+                Expr::If {
+                    condition: Box::new(recurse(condition, inlined)),
+                    then_branch: block(then_branch, inlined),
+                    elif_branches: elif_branches
+                        .iter()
+                        .map(|(c, b)| (recurse(c, inlined), block(b, inlined)))
+                        .collect(),
+                    else_branch: else_branch.as_ref().map(|b| block(b, inlined)),
+                }
+            }
+            other => other.clone(),
+        }
+    }
+
+    /// 将函数体中的参数替换为调用参数
+    fn substitute(expr: &Expr, substitutions: &HashMap<&str, &Expr>) -> Expr {
+        let sub = |e: &Expr| Self::substitute(e, substitutions);
+        match expr {
+            Expr::Identifier(name) => substitutions
+                .get(name.as_str())
+                .map(|arg| (*arg).clone())
+                .unwrap_or_else(|| expr.clone()),
+            Expr::Binary { left, op, right } => Expr::Binary {
+                left: Box::new(sub(left)),
+                op: op.clone(),
+                right: Box::new(sub(right)),
+            },
+            Expr::Unary { op, expr } => Expr::Unary {
+                op: op.clone(),
+                expr: Box::new(sub(expr)),
+            },
+            Expr::Call { func, args } => Expr::Call {
+                func: Box::new(sub(func)),
+                args: args.iter().map(sub).collect(),
+            },
+            Expr::Array(items) => Expr::Array(items.iter().map(sub).collect()),
+            Expr::Dict(pairs) => {
+                Expr::Dict(pairs.iter().map(|(k, v)| (k.clone(), sub(v))).collect())
+            }
+            Expr::Index { object, index } => Expr::Index {
+                object: Box::new(sub(object)),
+                index: Box::new(sub(index)),
+            },
+            other => other.clone(),
+        }
+    }
+}
+
 impl Default for Optimizer {
     fn default() -> Self {
         Self::new()
     }
 }
 
+/// 复制优化选项，不复制尚未取出的警告
+impl Clone for Optimizer {
+    fn clone(&self) -> Self {
+        Optimizer {
+            tail_recursion: self.tail_recursion,
+            constant_folding: self.constant_folding,
+            dead_code_elimination: self.dead_code_elimination,
+            profile_guided: self.profile_guided,
+            hot_call_threshold: self.hot_call_threshold,
+            warnings: RefCell::new(Vec::new()),
+        }
+    }
+}
+
 // 此处保留之测试代码都为测试私有函数者
 #[cfg(test)]
 mod tests {
@@ -3,18 +3,21 @@
 //!
 //! Converts a stream of tokens into an Abstract Syntax Tree (AST)
 
-use crate::ast::{BinOp, Expr, Program, Stmt, UnaryOp};
+use crate::ast::{BinOp, Expr, Program, SourceSpan, Stmt, UnaryOp};
 use crate::lexer::Lexer;
-use crate::token::Token;
+use crate::token::{Span, Token};
 
 /// Parse errors with location information
 #[derive(Debug, Clone, PartialEq)]
 pub enum ParseError {
     UnexpectedToken {
-        expected: String,
+        /// Every token (or token class, e.g. "identifier") that would have been accepted
+        expected: Vec<String>,
         found: Token,
         line: usize,
         column: usize,
+        /// Machine-applicable fixes, e.g. inserting a missing closing `}`
+        fixes: Vec<FixIt>,
     },
     UnexpectedEOF {
         line: usize,
@@ -47,11 +50,15 @@ impl std::fmt::Display for ParseError {
                 found,
                 line,
                 column,
+                ..
             } => {
                 write!(
                     f,
                     "Parse error at line {}, column {}: Expected {}, found {:?}",
-                    line, column, expected, found
+                    line,
+                    column,
+                    expected.join(" or "),
+                    found
                 )
             }
             ParseError::UnexpectedEOF { line, column } => {
@@ -102,6 +109,69 @@ impl std::fmt::Display for ParseError {
 
 impl std::error::Error for ParseError {}
 
+impl ParseError {
+    /// Tokens that would have been accepted where the error occurred (empty if not applicable)
+    pub fn expected_tokens(&self) -> &[String] {
+        match self {
+            ParseError::UnexpectedToken { expected, .. } => expected,
+            _ => &[],
+        }
+    }
+
+    /// 1-based line and column where the error was found (`None` if not applicable)
+    pub fn position(&self) -> Option<(usize, usize)> {
+        match self {
+            ParseError::UnexpectedToken { line, column, .. }
+            | ParseError::UnexpectedEOF { line, column }
+            | ParseError::InvalidExpression { line, column, .. }
+            | ParseError::InvalidStatement { line, column, .. }
+            | ParseError::InvalidIdentifier { line, column, .. } => Some((*line, *column)),
+            ParseError::InvalidNumber(_) => None,
+        }
+    }
+
+    /// Suggested fixes that can be applied to the source mechanically
+    pub fn fixes(&self) -> &[FixIt] {
+        match self {
+            ParseError::UnexpectedToken { fixes, .. } => fixes,
+            _ => &[],
+        }
+    }
+}
+
+/// A machine-applicable fix for a parse error: insert `insert` before `line`/`column` (1-based)
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixIt {
+    /// Human-readable description, e.g. "insert missing '}'"
+    pub message: String,
+    pub line: usize,
+    pub column: usize,
+    /// Text to insert
+    pub insert: String,
+}
+
+impl FixIt {
+    /// Apply the fix to `source`; a column past the end of its line inserts at the line end
+    pub fn apply(&self, source: &str) -> String {
+        let mut offset = 0;
+        for (index, line) in source.split_inclusive('\n').enumerate() {
+            if index + 1 == self.line {
+                let content = line.trim_end_matches(['\n', '\r']);
+                offset += content
+                    .char_indices()
+                    .nth(self.column.saturating_sub(1))
+                    .map(|(i, _)| i)
+                    .unwrap_or(content.len());
+                break;
+            }
+            offset += line.len();
+        }
+        let mut fixed = source.to_string();
+        fixed.insert_str(offset, &self.insert);
+        fixed
+    }
+}
+
 /// Operator precedence (higher number = higher precedence)
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
 enum Precedence {
@@ -117,47 +187,78 @@ enum Precedence {
     Index = 9,      // array[index]
 }
 
+/// Maximum nesting of expressions and blocks
+///
+/// Parsing and evaluation recurse once per level, so unbounded nesting (e.g. thousands of
+/// `(`) would overflow the stack instead of reporting an error.
+pub const MAX_NESTING_DEPTH: usize = 64;
+
 /// Parser state
 pub struct Parser {
     lexer: Lexer,
     current_token: Token,
     peek_token: Token,
-    current_line: usize,
-    current_column: usize,
+    current_span: Span,           // source span of current_token
+    peek_span: Span,              // source span of peek_token
     current_had_whitespace: bool, // whether whitespace preceded current_token
     peek_had_whitespace: bool,    // whether whitespace preceded peek_token
+    depth: usize,                 // current nesting of expressions and blocks
+    previous_end: usize,          // char offset just past the token before current_token
+    content_end: usize,           // char offset just past the last consumed non-newline token
+    warnings: Vec<String>,        // non-fatal issues found while parsing
 }
 
 impl Parser {
     /// Create a new parser from source code
     pub fn new(input: &str) -> Self {
-        let mut lexer = Lexer::new(input);
+        Self::from_lexer(Lexer::new(input))
+    }
+
+    /// Create a parser that accepts the dialect's keyword aliases
+    pub fn with_dialect(input: &str, dialect: std::sync::Arc<crate::dialect::Dialect>) -> Self {
+        Self::from_lexer(Lexer::with_dialect(input, dialect))
+    }
+
+    fn from_lexer(mut lexer: Lexer) -> Self {
         let current = lexer.next_token();
         let current_ws = lexer.had_whitespace();
+        let current_span = lexer.token_span();
         let peek = lexer.next_token();
         let peek_ws = lexer.had_whitespace();
-        let line = lexer.line();
-        let column = lexer.column();
+        let peek_span = lexer.token_span();
 
         Parser {
             lexer,
             current_token: current,
             peek_token: peek,
-            current_line: line,
-            current_column: column,
+            current_span,
+            peek_span,
             current_had_whitespace: current_ws,
             peek_had_whitespace: peek_ws,
+            depth: 0,
+            previous_end: 0,
+            content_end: 0,
+            warnings: Vec::new(),
         }
     }
 
+    /// Take the non-fatal issues found while parsing (e.g. duplicate dictionary keys)
+    pub fn take_warnings(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.warnings)
+    }
+
     /// Advance to the next token
     fn next_token(&mut self) {
+        if self.current_token != Token::Newline {
+            self.content_end = self.current_span.end;
+        }
         self.current_token = self.peek_token.clone();
         self.current_had_whitespace = self.peek_had_whitespace;
+        self.previous_end = self.current_span.end;
+        self.current_span = self.peek_span;
         self.peek_token = self.lexer.next_token();
         self.peek_had_whitespace = self.lexer.had_whitespace();
-        self.current_line = self.lexer.line();
-        self.current_column = self.lexer.column();
+        self.peek_span = self.lexer.token_span();
     }
 
     /// Skip newline tokens (they're optional in many places)
@@ -173,12 +274,63 @@ impl Parser {
             self.next_token();
             Ok(())
         } else {
-            Err(ParseError::UnexpectedToken {
-                expected: format!("{:?}", expected),
-                found: self.current_token.clone(),
-                line: self.current_line,
-                column: self.current_column,
+            Err(self.unexpected_one_of(&[expected]))
+        }
+    }
+
+    /// Expect the closing delimiter of a comma-separated list (array, dict, call arguments)
+    fn expect_list_end(&mut self, closing: Token) -> Result<(), ParseError> {
+        if self.current_token == closing {
+            self.next_token();
+            Ok(())
+        } else {
+            Err(self.unexpected_one_of(&[Token::Comma, closing]))
+        }
+    }
+
+    /// Error for an unexpected current token, where `expected` describes what was wanted
+    fn unexpected(&self, expected: &str) -> ParseError {
+        ParseError::UnexpectedToken {
+            expected: vec![expected.to_string()],
+            found: self.current_token.clone(),
+            line: self.current_span.line,
+            column: self.current_span.column,
+            fixes: Vec::new(),
+        }
+    }
+
+    /// Error for an unexpected current token when any of `expected` would have been accepted
+    ///
+    /// When a closing delimiter is missing at the end of a line or file, a fix inserting it
+    /// right after the preceding token is attached.
+    fn unexpected_one_of(&self, expected: &[Token]) -> ParseError {
+        let names: Vec<String> = expected
+            .iter()
+            .map(|token| format!("{:?}", token))
+            .collect();
+        let (fix_line, fix_column) = self.lexer.line_column_at(self.previous_end);
+        let fixes = expected
+            .iter()
+            .filter_map(|token| match token {
+                Token::RightBrace => Some("}"),
+                Token::RightParen => Some(")"),
+                Token::RightBracket => Some("]"),
+                _ => None,
+            })
+            .filter(|_| matches!(self.current_token, Token::EOF | Token::Newline))
+            .map(|closing| FixIt {
+                message: format!("insert missing '{}'", closing),
+                line: fix_line,
+                column: fix_column,
+                insert: closing.to_string(),
             })
+            .collect();
+        ParseError::UnexpectedToken {
+            expected: names,
+            found: self.current_token.clone(),
+            line: self.current_span.line,
+            column: self.current_span.column,
+            fixes,
         }
     }
 
@@ -195,8 +347,8 @@ impl Parser {
             return Err(ParseError::InvalidIdentifier {
                 name: name.to_string(),
                 reason: "标识符不能以数字开头".to_string(),
-                line: self.current_line,
-                column: self.current_column,
+                line: self.current_span.line,
+                column: self.current_span.column,
             });
         }
 
@@ -210,8 +362,8 @@ impl Parser {
                 return Err(ParseError::InvalidIdentifier {
                     name: name.to_string(),
                     reason: "参数名只能包含字母、数字和下划线".to_string(),
-                    line: self.current_line,
-                    column: self.current_column,
+                    line: self.current_span.line,
+                    column: self.current_span.column,
                 });
             }
         } else {
@@ -226,8 +378,8 @@ impl Parser {
                     reason:
                         "变量名和函数名必须使用全大写字母和下划线（例如：MY_VAR, CALCULATE_SUM）"
                             .to_string(),
-                    line: self.current_line,
-                    column: self.current_column,
+                    line: self.current_span.line,
+                    column: self.current_span.column,
                 });
             }
         }
@@ -278,8 +430,76 @@ impl Parser {
         Ok(statements)
     }
 
-    /// Parse a statement
+    /// Parse a source that must consist of exactly one expression (e.g. a formula field)
+    ///
+    /// Leading and trailing newlines and a trailing `;` are allowed; statements such as
+    /// `Set` or `Func`, or anything after the expression, are rejected.
+    pub fn parse_single_expression(&mut self) -> Result<Expr, ParseError> {
+        self.skip_newlines();
+
+        match &self.current_token {
+            Token::EOF => {
+                return Err(ParseError::UnexpectedEOF {
+                    line: self.current_span.line,
+                    column: self.current_span.column,
+                });
+            }
+            Token::Set
+            | Token::Func
+            | Token::Generator
+            | Token::Lazy
+            | Token::Return
+            | Token::Yield
+            | Token::Break
+            | Token::Continue
+            | Token::While
+            | Token::For
+            | Token::Switch
+            | Token::Import
+            | Token::Export
+            | Token::Throw
+            | Token::Try
+            | Token::Pragma { .. } => {
+                return Err(ParseError::InvalidStatement {
+                    message: format!(
+                        "expected a single expression, found statement {:?}",
+                        self.current_token
+                    ),
+                    line: self.current_span.line,
+                    column: self.current_span.column,
+                });
+            }
+            _ => {}
+        }
+
+        let expr = self.parse_expression(Precedence::Lowest)?;
+        if self.current_token == Token::Semicolon {
+            self.next_token();
+        }
+        self.skip_newlines();
+
+        if self.current_token != Token::EOF {
+            return Err(self.unexpected("end of expression"));
+        }
+        Ok(expr)
+    }
+
+    /// Parse a statement, recording where it appears in the source
     fn parse_statement(&mut self) -> Result<Stmt, ParseError> {
+        let start = self.current_span;
+        let stmt = self.parse_statement_node()?;
+        Ok(Stmt::located(self.span_from(start), stmt))
+    }
+
+    /// Span from the start of `start` to the end of the last consumed token
+    fn span_from(&self, start: Span) -> SourceSpan {
+        SourceSpan {
+            end: self.content_end.max(start.end),
+            ..SourceSpan::from(start)
+        }
+    }
+
+    fn parse_statement_node(&mut self) -> Result<Stmt, ParseError> {
         match &self.current_token {
             Token::Set => self.parse_set_statement(),
             Token::Func => self.parse_func_definition(),
@@ -295,6 +515,8 @@ impl Parser {
             Token::Import => self.parse_import_statement(),
             Token::Export => self.parse_export_statement(),
             Token::Throw => self.parse_throw_statement(),
+            Token::Try => self.parse_try_statement(),
+            Token::Pragma { .. } => self.parse_pragma(),
             _ => self.parse_expression_statement(),
         }
     }
@@ -313,12 +535,7 @@ impl Parser {
                 n.clone()
             }
             _ => {
-                return Err(ParseError::UnexpectedToken {
-                    expected: "identifier".to_string(),
-                    found: self.current_token.clone(),
-                    line: self.current_line,
-                    column: self.current_column,
-                });
+                return Err(self.unexpected("identifier"));
             }
         };
 
@@ -352,12 +569,7 @@ impl Parser {
 
             // Expect ']'
             if self.current_token != Token::RightBracket {
-                return Err(ParseError::UnexpectedToken {
-                    expected: "']' for index access".to_string(),
-                    found: self.current_token.clone(),
-                    line: self.current_line,
-                    column: self.current_column,
-                });
+                return Err(self.unexpected("']' for index access"));
             }
 
             self.next_token(); // skip ']'
@@ -397,12 +609,7 @@ impl Parser {
                 name.clone()
             }
             _ => {
-                return Err(ParseError::UnexpectedToken {
-                    expected: "identifier".to_string(),
-                    found: self.current_token.clone(),
-                    line: self.current_line,
-                    column: self.current_column,
-                });
+                return Err(self.unexpected("identifier"));
             }
         };
 
@@ -429,12 +636,7 @@ impl Parser {
         let name = match &self.current_token {
             Token::Identifier(name) => name.clone(),
             _ => {
-                return Err(ParseError::UnexpectedToken {
-                    expected: "identifier".to_string(),
-                    found: self.current_token.clone(),
-                    line: self.current_line,
-                    column: self.current_column,
-                });
+                return Err(self.unexpected("identifier"));
             }
         };
 
@@ -461,12 +663,7 @@ impl Parser {
         let name = match &self.current_token {
             Token::Identifier(name) => name.clone(),
             _ => {
-                return Err(ParseError::UnexpectedToken {
-                    expected: "identifier".to_string(),
-                    found: self.current_token.clone(),
-                    line: self.current_line,
-                    column: self.current_column,
-                });
+                return Err(self.unexpected("identifier"));
             }
         };
 
@@ -567,12 +764,7 @@ impl Parser {
         let first_var = match &self.current_token {
             Token::Identifier(name) => name.clone(),
             _ => {
-                return Err(ParseError::UnexpectedToken {
-                    expected: "identifier".to_string(),
-                    found: self.current_token.clone(),
-                    line: self.current_line,
-                    column: self.current_column,
-                });
+                return Err(self.unexpected("identifier"));
             }
         };
 
@@ -585,12 +777,7 @@ impl Parser {
             let second_var = match &self.current_token {
                 Token::Identifier(name) => name.clone(),
                 _ => {
-                    return Err(ParseError::UnexpectedToken {
-                        expected: "identifier".to_string(),
-                        found: self.current_token.clone(),
-                        line: self.current_line,
-                        column: self.current_column,
-                    });
+                    return Err(self.unexpected("identifier"));
                 }
             };
 
@@ -713,12 +900,7 @@ impl Parser {
                 let name = match &self.current_token {
                     Token::Identifier(n) => n.clone(),
                     _ => {
-                        return Err(ParseError::UnexpectedToken {
-                            expected: "identifier".to_string(),
-                            found: self.current_token.clone(),
-                            line: self.current_line,
-                            column: self.current_column,
-                        });
+                        return Err(self.unexpected("identifier"));
                     }
                 };
 
@@ -755,12 +937,7 @@ impl Parser {
             let name = match &self.current_token {
                 Token::Identifier(n) => n.clone(),
                 _ => {
-                    return Err(ParseError::UnexpectedToken {
-                        expected: "identifier".to_string(),
-                        found: self.current_token.clone(),
-                        line: self.current_line,
-                        column: self.current_column,
-                    });
+                    return Err(self.unexpected("identifier"));
                 }
             };
             self.next_token();
@@ -789,12 +966,7 @@ impl Parser {
         let path = match &self.current_token {
             Token::String(p) => p.clone(),
             _ => {
-                return Err(ParseError::UnexpectedToken {
-                    expected: "string".to_string(),
-                    found: self.current_token.clone(),
-                    line: self.current_line,
-                    column: self.current_column,
-                });
+                return Err(self.unexpected("string"));
             }
         };
 
@@ -819,12 +991,7 @@ impl Parser {
         let name = match &self.current_token {
             Token::Identifier(n) => n.clone(),
             _ => {
-                return Err(ParseError::UnexpectedToken {
-                    expected: "identifier".to_string(),
-                    found: self.current_token.clone(),
-                    line: self.current_line,
-                    column: self.current_column,
-                });
+                return Err(self.unexpected("identifier"));
             }
         };
 
@@ -837,6 +1004,20 @@ impl Parser {
         Ok(Stmt::Export(name))
     }
 
+    /// Parse: #NAME value
+    fn parse_pragma(&mut self) -> Result<Stmt, ParseError> {
+        let Token::Pragma { name, value } = self.current_token.clone() else {
+            return Err(self.unexpected("pragma"));
+        };
+        self.next_token();
+
+        if self.current_token == Token::Newline || self.current_token == Token::Semicolon {
+            self.next_token();
+        }
+
+        Ok(Stmt::Pragma { name, value })
+    }
+
     /// Parse: Throw expr
     fn parse_throw_statement(&mut self) -> Result<Stmt, ParseError> {
         self.next_token(); // skip 'Throw'
@@ -850,6 +1031,66 @@ impl Parser {
         Ok(Stmt::Throw(expr))
     }
 
+    /// Parse: Try { body } Catch (ERR) { handler } Finally { cleanup }
+    fn parse_try_statement(&mut self) -> Result<Stmt, ParseError> {
+        self.next_token(); // skip 'Try'
+        self.skip_newlines();
+        self.expect_token(Token::LeftBrace)?;
+
+        let body = self.parse_block()?;
+
+        self.expect_token(Token::RightBrace)?;
+        self.skip_newlines();
+
+        let catch = if self.current_token == Token::Catch {
+            self.next_token();
+            self.expect_token(Token::LeftParen)?;
+
+            let var = match &self.current_token {
+                Token::Identifier(name) => name.clone(),
+                _ => return Err(self.unexpected("identifier")),
+            };
+            self.next_token();
+
+            self.expect_token(Token::RightParen)?;
+            self.skip_newlines();
+            self.expect_token(Token::LeftBrace)?;
+
+            let handler = self.parse_block()?;
+
+            self.expect_token(Token::RightBrace)?;
+            self.skip_newlines();
+
+            Some((var, handler))
+        } else {
+            None
+        };
+
+        let finally = if self.current_token == Token::Finally {
+            self.next_token();
+            self.skip_newlines();
+            self.expect_token(Token::LeftBrace)?;
+
+            let cleanup = self.parse_block()?;
+
+            self.expect_token(Token::RightBrace)?;
+
+            Some(cleanup)
+        } else {
+            None
+        };
+
+        if catch.is_none() && finally.is_none() {
+            return Err(self.unexpected_one_of(&[Token::Catch, Token::Finally]));
+        }
+
+        Ok(Stmt::Try {
+            body,
+            catch,
+            finally,
+        })
+    }
+
     /// Parse expression as statement
     fn parse_expression_statement(&mut self) -> Result<Stmt, ParseError> {
         let expr = self.parse_expression(Precedence::Lowest)?;
@@ -887,6 +1128,34 @@ impl Parser {
 
     /// Parse a block of statements: { stmt1 stmt2 ... }
     fn parse_block(&mut self) -> Result<Vec<Stmt>, ParseError> {
+        self.nested(Self::parse_block_inner)
+    }
+
+    /// Run `parse` one nesting level deeper, failing past `MAX_NESTING_DEPTH`
+    fn nested<T>(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Result<T, ParseError>,
+    ) -> Result<T, ParseError> {
+        self.check_depth()?;
+        let depth = self.depth;
+        self.depth += 1;
+        let result = parse(self);
+        self.depth = depth;
+        result
+    }
+
+    fn check_depth(&self) -> Result<(), ParseError> {
+        if self.depth >= MAX_NESTING_DEPTH {
+            return Err(ParseError::InvalidExpression {
+                message: format!("nesting deeper than {} levels", MAX_NESTING_DEPTH),
+                line: self.current_span.line,
+                column: self.current_span.column,
+            });
+        }
+        Ok(())
+    }
+
+    fn parse_block_inner(&mut self) -> Result<Vec<Stmt>, ParseError> {
         let mut statements = Vec::new();
 
         self.skip_newlines();
@@ -901,8 +1170,23 @@ impl Parser {
 
     /// Parse an expression using Pratt parsing
     fn parse_expression(&mut self, precedence: Precedence) -> Result<Expr, ParseError> {
-        let mut left = self.parse_prefix()?;
+        self.nested(|parser| parser.parse_expression_inner(precedence))
+    }
 
+    fn parse_expression_inner(&mut self, precedence: Precedence) -> Result<Expr, ParseError> {
+        let left = self.parse_prefix()?;
+        let depth = self.depth;
+        let result = self.parse_infix_chain(left, precedence);
+        self.depth = depth;
+        result
+    }
+
+    /// Parse the infix operators, calls and indexing that follow a prefix expression
+    fn parse_infix_chain(
+        &mut self,
+        mut left: Expr,
+        precedence: Precedence,
+    ) -> Result<Expr, ParseError> {
         // After parse_prefix, current_token is at the first token after the prefix expression
         while precedence < self.current_precedence()
             && self.current_token != Token::Newline
@@ -914,6 +1198,9 @@ impl Parser {
             && self.current_token != Token::Comma
             && self.current_token != Token::Colon
         {
+            // Each operator wraps `left` one level deeper in the AST
+            self.check_depth()?;
+            self.depth += 1;
             left = self.parse_infix(left)?;
         }
 
@@ -928,6 +1215,11 @@ impl Parser {
                 self.next_token();
                 Ok(Expr::Number(num))
             }
+            Token::Integer(n) => {
+                let num = *n;
+                self.next_token();
+                Ok(Expr::Integer(num))
+            }
             Token::BigInteger(s) => {
                 let big_int_str = s.clone();
                 self.next_token();
@@ -936,7 +1228,7 @@ impl Parser {
             Token::String(s) => {
                 let string = s.clone();
                 self.next_token();
-                Ok(Expr::String(string))
+                Ok(Expr::String(string.into()))
             }
             Token::Boolean(b) => {
                 let bool_val = *b;
@@ -960,10 +1252,18 @@ impl Parser {
             Token::If => self.parse_if_expression(),
             Token::Func => self.parse_lambda_expression(),
             Token::Lambda => self.parse_lambda_arrow_expression(),
+            Token::Extension { tag, payload } => {
+                let expr = Expr::Extension {
+                    tag: tag.clone(),
+                    payload: payload.clone(),
+                };
+                self.next_token();
+                Ok(expr)
+            }
             _ => Err(ParseError::InvalidExpression {
                 message: "Unexpected token in expression".to_string(),
-                line: self.current_line,
-                column: self.current_column,
+                line: self.current_span.line,
+                column: self.current_span.column,
             }),
         }
     }
@@ -1002,12 +1302,7 @@ impl Parser {
             self.next_token(); // move past ')'
             Ok(expr)
         } else {
-            Err(ParseError::UnexpectedToken {
-                expected: "RightParen".to_string(),
-                found: self.current_token.clone(),
-                line: self.current_line,
-                column: self.current_column,
-            })
+            Err(self.unexpected_one_of(&[Token::RightParen]))
         }
     }
     /// Parse array literal: [1, 2, 3]
@@ -1031,7 +1326,7 @@ impl Parser {
             }
         }
 
-        self.expect_token(Token::RightBracket)?;
+        self.expect_list_end(Token::RightBracket)?;
 
         Ok(Expr::Array(elements))
     }
@@ -1049,15 +1344,17 @@ impl Parser {
                 Token::Identifier(k) => k.clone(),
                 Token::String(k) => k.clone(),
                 _ => {
-                    return Err(ParseError::UnexpectedToken {
-                        expected: "identifier or string".to_string(),
-                        found: self.current_token.clone(),
-                        line: self.current_line,
-                        column: self.current_column,
-                    });
+                    return Err(self.unexpected("identifier or string"));
                 }
             };
 
+            if pairs.iter().any(|(existing, _)| existing == &key) {
+                self.warnings.push(format!(
+                    "line {}: duplicate key '{}' in dictionary literal; the earlier value is ignored",
+                    self.current_span.line, key
+                ));
+            }
+
             self.next_token();
             self.expect_token(Token::Colon)?;
 
@@ -1075,7 +1372,7 @@ impl Parser {
             }
         }
 
-        self.expect_token(Token::RightBrace)?;
+        self.expect_list_end(Token::RightBrace)?;
 
         Ok(Expr::Dict(pairs))
     }
@@ -1108,8 +1405,8 @@ impl Parser {
             _ => {
                 return Err(ParseError::InvalidExpression {
                     message: "Invalid binary operator".to_string(),
-                    line: self.current_line,
-                    column: self.current_column,
+                    line: self.current_span.line,
+                    column: self.current_span.column,
                 });
             }
         };
@@ -1141,7 +1438,7 @@ impl Parser {
             }
         }
 
-        self.expect_token(Token::RightParen)?;
+        self.expect_list_end(Token::RightParen)?;
 
         Ok(Expr::call(func, args))
     }
@@ -1252,12 +1549,7 @@ impl Parser {
                     vec![param]
                 }
                 _ => {
-                    return Err(ParseError::UnexpectedToken {
-                        expected: "identifier or '('".to_string(),
-                        found: self.current_token.clone(),
-                        line: self.current_line,
-                        column: self.current_column,
-                    });
+                    return Err(self.unexpected("identifier or '('"));
                 }
             }
         };
@@ -1266,10 +1558,11 @@ impl Parser {
         self.expect_token(Token::Arrow)?;
 
         // Parse the expression body
+        let start = self.current_span;
         let expr = self.parse_expression(Precedence::Lowest)?;
 
         // Wrap the expression in a Return statement
-        let body = vec![Stmt::Return(expr)];
+        let body = vec![Stmt::located(self.span_from(start), Stmt::Return(expr))];
 
         Ok(Expr::Lambda { params, body })
     }
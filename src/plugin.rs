@@ -0,0 +1,223 @@
+// src/plugin.rs
+//! 内置函数包插件（动态库）的稳定 C ABI
+//!
+//! 插件是导出 [`AETHER_PLUGIN_ENTRY`] 符号的 `.so` / `.dylib` / `.dll`，
+//! 可以用任何能导出 C 函数的语言编写，无需依赖 aether crate：
+//!
+//! ```c
+//! const AetherPluginDescriptor *aether_plugin_descriptor(void);
+//! ```
+//!
+//! 描述符中列出插件提供的函数。参数和返回值都以 UTF-8 JSON 字符串传递
+//! （与 `JSON_STRINGIFY` 相同，分数/大整数使用 `{"$fraction": "1/3"}` 标记）：
+//!
+//! - 参数：JSON 数组，如 `[1, "a"]`
+//! - 返回：`{"ok": <值>}` 或 `{"error": "<消息>"}`，字符串由插件分配，
+//!   宿主用完后调用描述符中的 `free_string` 释放
+//!
+//! ABI 只在 [`AETHER_PLUGIN_ABI_VERSION`] 变化时才会改变，
+//! 加载时版本不一致的插件会被拒绝。
+
+use std::os::raw::c_char;
+
+/// 当前插件 ABI 版本
+pub const AETHER_PLUGIN_ABI_VERSION: u32 = 1;
+
+/// 插件必须导出的入口符号名
+pub const AETHER_PLUGIN_ENTRY: &str = "aether_plugin_descriptor";
+
+/// 插件提供的单个函数
+#[repr(C)]
+pub struct AetherPluginFunction {
+    /// 函数名（NUL 结尾的 UTF-8，脚本中以此名调用）
+    pub name: *const c_char,
+    /// 参数个数（用于签名与帮助信息，实际参数个数由插件自行校验）
+    pub arity: usize,
+    /// 调用函数：接收 JSON 参数数组，返回插件分配的 JSON 结果
+    pub call: unsafe extern "C" fn(args_json: *const c_char) -> *mut c_char,
+}
+
+/// 插件描述符，由入口函数返回，须在插件加载期间保持有效
+#[repr(C)]
+pub struct AetherPluginDescriptor {
+    /// 插件编译时的 ABI 版本，必须等于 `AETHER_PLUGIN_ABI_VERSION`
+    pub abi_version: u32,
+    /// 插件名（NUL 结尾的 UTF-8）
+    pub name: *const c_char,
+    /// 函数表
+    pub functions: *const AetherPluginFunction,
+    /// 函数表长度
+    pub function_count: usize,
+    /// 释放 `call` 返回的字符串
+    pub free_string: unsafe extern "C" fn(s: *mut c_char),
+}
+
+/// 已加载插件的信息
+#[derive(Debug, Clone, PartialEq)]
+pub struct PluginInfo {
+    /// 插件名
+    pub name: String,
+    /// 插件提供的函数名
+    pub functions: Vec<String>,
+}
+
+#[cfg(feature = "plugins")]
+pub(crate) use loader::load;
+
+#[cfg(feature = "plugins")]
+mod loader {
+    use super::*;
+    use crate::builtins::NativeFn;
+    use crate::builtins::json::{FractionJsonMode, json_to_value, value_to_json};
+    use crate::evaluator::RuntimeError;
+    use crate::value::Value;
+    use libloading::Library;
+    use std::ffi::{CStr, CString};
+    use std::path::Path;
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    type EntryFn = unsafe extern "C" fn() -> *const AetherPluginDescriptor;
+
+    /// 插件中的函数 `(名称, 函数, 参数个数)`
+    type PluginFunctions = Vec<(String, NativeFn, usize)>;
+
+    /// 插件中的一个函数，持有动态库以保证调用期间不被卸载
+    struct PluginFunction {
+        _library: Arc<Library>,
+        plugin: String,
+        name: String,
+        call: unsafe extern "C" fn(*const c_char) -> *mut c_char,
+        free_string: unsafe extern "C" fn(*mut c_char),
+    }
+
+    impl PluginFunction {
+        fn invoke(&self, args: &[Value]) -> Result<Value, RuntimeError> {
+            let error = |message: String| {
+                RuntimeError::CustomError(format!(
+                    "插件 {} 的函数 {} 出错: {}",
+                    self.plugin, self.name, message
+                ))
+            };
+
+            let args = args
+                .iter()
+                .map(|arg| value_to_json(arg, FractionJsonMode::Tagged))
+                .collect::<Result<Vec<_>, _>>()?;
+            let args = CString::new(serde_json::Value::Array(args).to_string())
+                .map_err(|e| error(e.to_string()))?;
+
+            // SAFETY: `call` 与 `free_string` 来自仍处于加载状态的插件（`library` 被持有），
+            // 返回的指针按 ABI 约定是插件分配、以 NUL 结尾的字符串
+            let output = unsafe {
+                let raw = (self.call)(args.as_ptr());
+                if raw.is_null() {
+                    return Err(error("返回了空指针".to_string()));
+                }
+                let text = CStr::from_ptr(raw).to_string_lossy().into_owned();
+                (self.free_string)(raw);
+                text
+            };
+
+            let output: serde_json::Value =
+                serde_json::from_str(&output).map_err(|e| error(format!("无效的 JSON: {}", e)))?;
+            match output {
+                serde_json::Value::Object(mut result) => {
+                    if let Some(message) = result.remove("error") {
+                        return Err(error(match message {
+                            serde_json::Value::String(s) => s,
+                            other => other.to_string(),
+                        }));
+                    }
+                    match result.remove("ok") {
+                        Some(value) => json_to_value(&value),
+                        None => Err(error("结果缺少 ok 或 error 字段".to_string())),
+                    }
+                }
+                _ => Err(error("结果必须是 JSON 对象".to_string())),
+            }
+        }
+    }
+
+    /// 读取 NUL 结尾的 UTF-8 字符串
+    ///
+    /// # Safety
+    /// `ptr` 必须为空或指向有效的 NUL 结尾字符串
+    unsafe fn read_str(ptr: *const c_char, what: &str) -> Result<String, String> {
+        if ptr.is_null() {
+            return Err(format!("{}为空指针", what));
+        }
+        // SAFETY: 由调用方保证
+        unsafe { CStr::from_ptr(ptr) }
+            .to_str()
+            .map(|s| s.to_string())
+            .map_err(|_| format!("{}不是有效的 UTF-8", what))
+    }
+
+    /// 加载插件，返回插件信息和其中的函数
+    pub(crate) fn load(path: &Path) -> Result<(PluginInfo, PluginFunctions), String> {
+        let display = path.display();
+        // SAFETY: 加载动态库会执行其初始化代码，插件由用户显式指定并视为可信
+        let library = unsafe { Library::new(path) }
+            .map_err(|e| format!("无法加载插件 {}: {}", display, e))?;
+        // SAFETY: 入口符号按 ABI 约定具有 `EntryFn` 签名
+        let descriptor = unsafe {
+            let entry = library
+                .get::<EntryFn>(AETHER_PLUGIN_ENTRY.as_bytes())
+                .map_err(|_| {
+                    format!(
+                        "{} 不是 Aether 插件（缺少 {}）",
+                        display, AETHER_PLUGIN_ENTRY
+                    )
+                })?;
+            entry()
+        };
+        if descriptor.is_null() {
+            return Err(format!("插件 {} 返回了空描述符", display));
+        }
+        // SAFETY: 非空描述符须在插件加载期间有效
+        let descriptor = unsafe { &*descriptor };
+        if descriptor.abi_version != AETHER_PLUGIN_ABI_VERSION {
+            return Err(format!(
+                "插件 {} 的 ABI 版本为 {}，当前支持的版本为 {}",
+                display, descriptor.abi_version, AETHER_PLUGIN_ABI_VERSION
+            ));
+        }
+
+        // SAFETY: 描述符字段按 ABI 约定有效
+        let plugin = unsafe { read_str(descriptor.name, "插件名") }?;
+        let entries: &[AetherPluginFunction] = if descriptor.functions.is_null()
+            || descriptor.function_count == 0
+        {
+            &[]
+        } else {
+            // SAFETY: 函数表长度由描述符给出
+            unsafe { std::slice::from_raw_parts(descriptor.functions, descriptor.function_count) }
+        };
+
+        let library = Arc::new(library);
+        let mut functions = Vec::with_capacity(entries.len());
+        for entry in entries {
+            // SAFETY: 同上
+            let name = unsafe { read_str(entry.name, "函数名") }?;
+            if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                return Err(format!("插件 {} 的函数名无效: {:?}", plugin, name));
+            }
+            let function = PluginFunction {
+                _library: Arc::clone(&library),
+                plugin: plugin.clone(),
+                name: name.clone(),
+                call: entry.call,
+                free_string: descriptor.free_string,
+            };
+            let native: NativeFn = Rc::new(move |args| function.invoke(args));
+            functions.push((name, native, entry.arity));
+        }
+
+        let info = PluginInfo {
+            name: plugin,
+            functions: functions.iter().map(|(name, _, _)| name.clone()).collect(),
+        };
+        Ok((info, functions))
+    }
+}
@@ -2,21 +2,36 @@
 // Kept in a separate module to keep lib.rs smaller.
 
 pub use crate::ast::{Expr, Program, Stmt};
+pub use crate::builtins::metadata::BuiltinMetadata;
+pub use crate::builtins::patch::{
+    ChangeKind, PathSegment, ValueChange, apply_changes, diff_values,
+};
+pub use crate::builtins::schema::{SchemaViolation, validate_schema};
 pub use crate::builtins::{BuiltInRegistry, IOPermissions};
-pub use crate::cache::{ASTCache, CacheStats};
-pub use crate::environment::Environment;
-pub use crate::evaluator::{ErrorReport, EvalResult, Evaluator, RuntimeError};
+pub use crate::cache::{ASTCache, CacheStats, SharedProgram};
+pub use crate::compile::Backend;
+pub use crate::dialect::Dialect;
+pub use crate::environment::{Environment, VariableInfo};
+pub use crate::evaluator::{CallFrame, ErrorReport, EvalResult, Evaluator, RuntimeError};
 pub use crate::lexer::Lexer;
-pub use crate::module_system::{DisabledModuleResolver, FileSystemModuleResolver, ModuleResolver};
+pub use crate::module_system::{
+    DisabledModuleResolver, FileSystemModuleResolver, ModuleResolver, ProjectModuleResolver,
+};
 pub use crate::optimizer::Optimizer;
-pub use crate::parser::{ParseError, Parser};
+pub use crate::parser::{FixIt, ParseError, Parser};
+pub use crate::plugin::PluginInfo;
+pub use crate::project::Project;
 pub use crate::runtime::{
-    ExecutionLimitError, ExecutionLimits, TraceEntry, TraceFilter, TraceLevel, TraceStats,
+    ConcurrencyLimits, DeterministicConfig, DisplayOptions, ExecutionLimitError, ExecutionLimits,
+    ExecutionTrace, ExtensionContext, ExtensionHandler, IoEvent, LanguageVersion, LargeValue,
+    MemoryReport, MemoryResource, PumpReport, ScopedDisplayOptions, SecretsProvider,
+    SnapshotOutcome, SnapshotStats, SuspectedCycle, TraceEntry, TraceFilter, TraceLevel,
+    TraceStats, TypeUsage, Warning, WarningKind,
 };
 pub use crate::sandbox::{
-    ExecutionMetrics, MetricsCollector, MetricsSnapshot, ModuleCacheManager, ModuleCacheStats,
-    ModuleMetrics, PathRestriction, PathValidationError, PathValidator, SandboxConfig,
-    SandboxPolicy, ScopedValidator,
+    EvalReport, ExecutionMetrics, MetricsCollector, MetricsSnapshot, ModuleCacheManager,
+    ModuleCacheStats, ModuleMetrics, PathRestriction, PathValidationError, PathValidator,
+    SandboxConfig, SandboxPolicy, ScopedValidator,
 };
-pub use crate::token::Token;
+pub use crate::token::{Span, Token, TokenKind};
 pub use crate::value::Value;
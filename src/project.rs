@@ -0,0 +1,186 @@
+// src/project.rs
+//! 多文件项目
+//!
+//! 项目是一个包含清单文件 `aether.json` 的目录：
+//!
+//! ```json
+//! {
+//!     "name": "billing",
+//!     "entry": "main.aether",
+//!     "entries": { "MAIN": "main.aether", "report": "bin/report.aether" }
+//! }
+//! ```
+//!
+//! - `entry`：默认入口（缺省为 `main.aether`）
+//! - `entries`：具名入口，`aether run <目录> --entry report` 时按名称查找；
+//!   找不到名称时把参数当作相对项目根目录的路径
+//!
+//! 项目中的 `Import` 以清单所在目录为根解析（`./`、`../` 开头的仍相对当前模块），
+//! 且不能引用项目目录之外的文件。没有清单的目录也可以作为项目运行，使用默认入口。
+
+use crate::module_system::ProjectModuleResolver;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// 项目清单文件名
+pub const MANIFEST_FILE: &str = "aether.json";
+
+/// 未指定入口时使用的文件
+pub const DEFAULT_ENTRY: &str = "main.aether";
+
+/// 项目清单（`aether.json`）
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectManifest {
+    /// 项目名称
+    #[serde(default)]
+    pub name: Option<String>,
+    /// 默认入口
+    #[serde(default)]
+    pub entry: Option<String>,
+    /// 具名入口
+    #[serde(default)]
+    pub entries: BTreeMap<String, String>,
+}
+
+/// 已打开的项目
+#[derive(Debug, Clone)]
+pub struct Project {
+    root: PathBuf,
+    manifest: ProjectManifest,
+}
+
+impl Project {
+    /// 打开项目：`path` 为项目目录或清单文件
+    ///
+    /// # 示例
+    /// ```no_run
+    /// use aether::{Aether, Project};
+    ///
+    /// let project = Project::open("examples/billing").unwrap();
+    /// let mut engine = Aether::with_all_permissions();
+    /// let result = engine.run_project(&project, Some("report")).unwrap();
+    /// ```
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let (root, manifest_path) = if path.is_dir() {
+            (path.to_path_buf(), path.join(MANIFEST_FILE))
+        } else {
+            let root = path.parent().unwrap_or(Path::new(".")).to_path_buf();
+            (root, path.to_path_buf())
+        };
+        let root = root
+            .canonicalize()
+            .map_err(|e| format!("Project error: cannot open '{}': {}", path.display(), e))?;
+
+        let manifest = if manifest_path.is_file() {
+            let text = std::fs::read_to_string(&manifest_path).map_err(|e| {
+                format!(
+                    "Project error: cannot read '{}': {}",
+                    manifest_path.display(),
+                    e
+                )
+            })?;
+            serde_json::from_str(&text).map_err(|e| {
+                format!(
+                    "Project error: invalid manifest '{}': {}",
+                    manifest_path.display(),
+                    e
+                )
+            })?
+        } else if path.is_dir() {
+            ProjectManifest::default()
+        } else {
+            return Err(format!(
+                "Project error: manifest '{}' not found",
+                manifest_path.display()
+            ));
+        };
+
+        Ok(Project { root, manifest })
+    }
+
+    /// 路径是否像一个项目（目录或清单文件）
+    pub fn is_project_path(path: impl AsRef<Path>) -> bool {
+        let path = path.as_ref();
+        path.is_dir() || path.file_name().is_some_and(|name| name == MANIFEST_FILE)
+    }
+
+    /// 项目根目录（清单所在目录，已规范化）
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// 项目清单
+    pub fn manifest(&self) -> &ProjectManifest {
+        &self.manifest
+    }
+
+    /// 项目名称（清单未指定时为目录名）
+    pub fn name(&self) -> String {
+        self.manifest.name.clone().unwrap_or_else(|| {
+            self.root
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        })
+    }
+
+    /// 入口文件路径
+    ///
+    /// `entry` 为 None 时使用清单的默认入口；否则先按具名入口查找，再当作相对根目录的路径。
+    pub fn entry_path(&self, entry: Option<&str>) -> Result<PathBuf, String> {
+        let relative = match entry {
+            None => self
+                .manifest
+                .entry
+                .clone()
+                .unwrap_or_else(|| DEFAULT_ENTRY.to_string()),
+            Some(name) => self
+                .manifest
+                .entries
+                .get(name)
+                .cloned()
+                .unwrap_or_else(|| name.to_string()),
+        };
+
+        let mut path = self.root.join(&relative);
+        if path.extension().is_none() {
+            path.set_extension("aether");
+        }
+        let path = path.canonicalize().map_err(|_| match entry {
+            Some(name) if !self.manifest.entries.is_empty() => format!(
+                "Project error: unknown entry '{}' (available: {})",
+                name,
+                self.manifest
+                    .entries
+                    .keys()
+                    .cloned()
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            _ => format!("Project error: entry file '{}' not found", path.display()),
+        })?;
+        if !path.starts_with(&self.root) {
+            return Err(format!(
+                "Project error: entry '{}' is outside the project",
+                relative
+            ));
+        }
+        Ok(path)
+    }
+
+    /// 项目的模块解析器
+    pub fn resolver(&self) -> ProjectModuleResolver {
+        ProjectModuleResolver::new(self.root.clone())
+    }
+
+    /// 相对项目根目录的显示路径（用于错误信息）
+    pub(crate) fn display_path(&self, module_id: &str) -> String {
+        Path::new(module_id)
+            .strip_prefix(&self.root)
+            .map(|p| p.display().to_string())
+            .unwrap_or_else(|_| module_id.to_string())
+    }
+}
@@ -0,0 +1,133 @@
+// src/result_cache.rs
+//! 纯脚本的结果缓存
+//!
+//! 纯脚本（见 `analysis::purity_violations`）的结果只取决于代码和输入绑定，
+//! `Aether::eval_pure` 以（程序哈希，输入哈希）为键缓存结果，重复求值时直接返回。
+//! 存储可以替换为宿主实现的 `ResultStore`（如进程间共享的缓存）。
+
+use crate::value::Value;
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// 结果缓存的键
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ResultKey {
+    /// 源代码的哈希
+    pub program: u64,
+    /// 输入绑定（按名称排序后的规范 JSON）的哈希
+    pub inputs: u64,
+}
+
+impl ResultKey {
+    /// 由源代码和输入的规范表示计算键
+    pub fn new(code: &str, canonical_inputs: &str) -> Self {
+        let hash = |text: &str| {
+            let mut hasher = DefaultHasher::new();
+            text.hash(&mut hasher);
+            hasher.finish()
+        };
+        ResultKey {
+            program: hash(code),
+            inputs: hash(canonical_inputs),
+        }
+    }
+}
+
+/// 结果存储
+pub trait ResultStore {
+    /// 读取缓存的结果
+    fn get(&mut self, key: &ResultKey) -> Option<Value>;
+    /// 保存结果
+    fn insert(&mut self, key: ResultKey, value: Value);
+    /// 清空存储
+    fn clear(&mut self);
+}
+
+/// 内存中的结果存储（默认）
+#[derive(Debug)]
+pub struct MemoryResultStore {
+    entries: HashMap<ResultKey, Value>,
+    /// 最多保存的结果数
+    max_size: usize,
+    hits: usize,
+    misses: usize,
+}
+
+impl MemoryResultStore {
+    /// 创建默认容量（1000 条）的存储
+    pub fn new() -> Self {
+        Self::with_capacity(1000)
+    }
+
+    /// 创建指定容量的存储
+    pub fn with_capacity(max_size: usize) -> Self {
+        MemoryResultStore {
+            entries: HashMap::new(),
+            max_size: max_size.max(1),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// 当前保存的结果数
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// 是否为空
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// （命中次数，未命中次数）
+    pub fn hit_stats(&self) -> (usize, usize) {
+        (self.hits, self.misses)
+    }
+}
+
+impl Default for MemoryResultStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ResultStore for MemoryResultStore {
+    fn get(&mut self, key: &ResultKey) -> Option<Value> {
+        let value = self.entries.get(key).cloned();
+        if value.is_some() {
+            self.hits += 1;
+        } else {
+            self.misses += 1;
+        }
+        value
+    }
+
+    fn insert(&mut self, key: ResultKey, value: Value) {
+        // 与 AST 缓存相同：满时清理最早的 10%
+        if self.entries.len() >= self.max_size && !self.entries.contains_key(&key) {
+            let to_remove = (self.max_size / 10).max(1);
+            let keys: Vec<ResultKey> = self.entries.keys().take(to_remove).copied().collect();
+            for key in keys {
+                self.entries.remove(&key);
+            }
+        }
+        self.entries.insert(key, value);
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.hits = 0;
+        self.misses = 0;
+    }
+}
+
+/// 值是否可以缓存（不含捕获环境的函数、生成器和惰性值）
+pub(crate) fn is_cacheable(value: &Value) -> bool {
+    match value {
+        Value::Function { .. } | Value::Generator { .. } | Value::Lazy { .. } => false,
+        Value::Array(items) => items.iter().all(is_cacheable),
+        Value::Dict(map) => map.values().all(is_cacheable),
+        _ => true,
+    }
+}
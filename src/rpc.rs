@@ -0,0 +1,465 @@
+// src/rpc.rs
+//! gRPC 求值服务（`grpc` 特性）
+//!
+//! 服务定义见 `proto/aether/v1/aether.proto`，与 `aether serve` 的 HTTP/JSON 接口对应：
+//! Eval、Compile、CallFunction、StreamTrace。
+//!
+//! - [`EvalService`]：与传输无关的服务实现，消息类型与 proto 中的消息逐字段对应
+//! - [`GrpcEvaluator`]：tonic 服务端，代码由构建脚本从 proto 生成（见 [`pb`]）
+//!
+//! 引擎不是 `Send`，`GrpcEvaluator` 把请求交给固定在工作线程上的 `EvalService` 处理：
+//!
+//! ```no_run
+//! use aether::SandboxConfig;
+//! use aether::rpc::GrpcEvaluator;
+//!
+//! # async fn serve() -> Result<(), Box<dyn std::error::Error>> {
+//! let evaluator = GrpcEvaluator::new(4, SandboxConfig::dsl_safe());
+//! tonic::transport::Server::builder()
+//!     .add_service(evaluator.into_server())
+//!     .serve("127.0.0.1:7001".parse()?)
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::analysis::{RequirementsReport, builtin_names};
+use crate::builtins::json::{FractionJsonMode, json_to_value, value_to_json};
+use crate::engine::{EnginePool, PooledEngine, RecyclePolicy};
+use crate::sandbox::SandboxConfig;
+use crate::value::Value;
+use crate::{Aether, Parser};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, mpsc};
+use std::thread;
+use tokio::sync::oneshot;
+use tokio_stream::Stream;
+use tonic::{Request, Response, Status};
+
+/// 由 `proto/aether/v1/aether.proto` 生成的消息、服务端和客户端
+pub mod pb {
+    tonic::include_proto!("aether.v1");
+}
+
+/// `Eval` / `StreamTrace` 请求
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EvalRequest {
+    pub code: String,
+    /// JSON 对象：变量名 -> 值（可为空）
+    pub inputs_json: String,
+}
+
+/// 错误信息
+#[derive(Debug, Clone, PartialEq)]
+pub struct RpcError {
+    /// EvalError / ParseError / BadRequest / UnserializableResult
+    pub kind: String,
+    pub message: String,
+}
+
+impl RpcError {
+    fn new(kind: &str, message: impl Into<String>) -> Self {
+        RpcError {
+            kind: kind.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+/// `Eval` / `CallFunction` 响应
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EvalResponse {
+    pub ok: bool,
+    /// 结果的 JSON 文本
+    pub result_json: String,
+    pub error: Option<RpcError>,
+}
+
+impl EvalResponse {
+    fn failed(error: RpcError) -> Self {
+        EvalResponse {
+            ok: false,
+            result_json: String::new(),
+            error: Some(error),
+        }
+    }
+
+    fn from_result(result: Result<Value, String>) -> Self {
+        let value = match result {
+            Ok(value) => value,
+            Err(e) => return Self::failed(RpcError::new("EvalError", e)),
+        };
+        match value_to_json(&value, FractionJsonMode::Float) {
+            Ok(json) => EvalResponse {
+                ok: true,
+                result_json: json.to_string(),
+                error: None,
+            },
+            Err(e) => Self::failed(RpcError::new("UnserializableResult", e.to_string())),
+        }
+    }
+}
+
+/// `Compile` 请求
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CompileRequest {
+    pub code: String,
+}
+
+/// `Compile` 响应
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CompileResponse {
+    pub ok: bool,
+    pub statements: u64,
+    /// `RequirementsReport` 的 JSON 文本
+    pub requirements_json: String,
+    pub error: Option<RpcError>,
+}
+
+/// `CallFunction` 请求
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CallFunctionRequest {
+    /// 定义函数的脚本
+    pub setup_code: String,
+    pub function: String,
+    /// JSON 数组：调用参数（可为空）
+    pub args_json: String,
+}
+
+/// 一条 TRACE 记录
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TraceRecord {
+    pub level: String,
+    pub category: String,
+    pub label: String,
+    /// JSON 数组：TRACE 的参数
+    pub values_json: String,
+    pub location: String,
+}
+
+/// `StreamTrace` 流中的一条消息
+#[derive(Debug, Clone, PartialEq)]
+pub enum TraceEvent {
+    Trace(TraceRecord),
+    /// 流的最后一条消息
+    Result(EvalResponse),
+}
+
+/// 与传输无关的求值服务
+///
+/// # 示例
+/// ```
+/// use aether::SandboxConfig;
+/// use aether::rpc::{EvalRequest, EvalService};
+///
+/// let mut service = EvalService::new(2, SandboxConfig::dsl_safe());
+/// let response = service.eval(&EvalRequest {
+///     code: "(X * 2)".to_string(),
+///     inputs_json: r#"{"X": 21}"#.to_string(),
+/// });
+/// assert!(response.ok);
+/// assert_eq!(response.result_json, "42");
+/// ```
+pub struct EvalService {
+    pool: EnginePool,
+}
+
+impl EvalService {
+    /// 创建服务：引擎池大小和引擎的沙箱配置
+    pub fn new(pool_size: usize, sandbox: SandboxConfig) -> Self {
+        let pool = EnginePool::with_factory(
+            pool_size.max(1),
+            RecyclePolicy::new().with_max_uses(1000),
+            Arc::new(move || Aether::with_sandbox(&sandbox)),
+        );
+        pool.metrics().enable();
+        EvalService { pool }
+    }
+
+    /// 引擎池（指标、容量等）
+    pub fn pool(&self) -> &EnginePool {
+        &self.pool
+    }
+
+    /// Eval：在干净的环境中求值
+    pub fn eval(&mut self, request: &EvalRequest) -> EvalResponse {
+        match self.acquire_with_inputs(&request.inputs_json) {
+            Ok(mut engine) => EvalResponse::from_result(engine.eval(&request.code)),
+            Err(error) => EvalResponse::failed(error),
+        }
+    }
+
+    /// Compile：只解析，不执行
+    pub fn compile(&self, request: &CompileRequest) -> CompileResponse {
+        match Parser::new(&request.code).parse_program() {
+            Ok(program) => {
+                let requirements = RequirementsReport::from_program(&program, |name| {
+                    builtin_names().contains(name)
+                });
+                CompileResponse {
+                    ok: true,
+                    statements: program.len() as u64,
+                    requirements_json: serde_json::to_string(&requirements).unwrap_or_default(),
+                    error: None,
+                }
+            }
+            Err(e) => CompileResponse {
+                error: Some(RpcError::new("ParseError", e.to_string())),
+                ..CompileResponse::default()
+            },
+        }
+    }
+
+    /// CallFunction：执行 setup 脚本后调用其中定义的函数
+    pub fn call_function(&mut self, request: &CallFunctionRequest) -> EvalResponse {
+        let args = match parse_json_array(&request.args_json) {
+            Ok(args) => args,
+            Err(error) => return EvalResponse::failed(error),
+        };
+        let mut engine = self.pool.acquire();
+        if let Err(e) = engine.eval(&request.setup_code) {
+            return EvalResponse::failed(RpcError::new("EvalError", e));
+        }
+        EvalResponse::from_result(engine.call(&request.function, args))
+    }
+
+    /// StreamTrace：求值并返回 TRACE 事件，最后一条为结果
+    pub fn stream_trace(&mut self, request: &EvalRequest) -> Vec<TraceEvent> {
+        let mut engine = match self.acquire_with_inputs(&request.inputs_json) {
+            Ok(engine) => engine,
+            Err(error) => return vec![TraceEvent::Result(EvalResponse::failed(error))],
+        };
+        let result = engine.eval(&request.code);
+        let mut events: Vec<TraceEvent> = engine
+            .trace_records()
+            .into_iter()
+            .map(|entry| {
+                let values = entry
+                    .values
+                    .iter()
+                    .map(|v| {
+                        value_to_json(v, FractionJsonMode::Float)
+                            .unwrap_or_else(|_| serde_json::Value::String(v.to_string()))
+                    })
+                    .collect::<Vec<_>>();
+                TraceEvent::Trace(TraceRecord {
+                    level: entry.level.as_str().to_string(),
+                    category: entry.category,
+                    label: entry.label.unwrap_or_default(),
+                    values_json: serde_json::Value::Array(values).to_string(),
+                    location: entry.location.unwrap_or_default(),
+                })
+            })
+            .collect();
+        events.push(TraceEvent::Result(EvalResponse::from_result(result)));
+        events
+    }
+
+    fn acquire_with_inputs(&mut self, inputs_json: &str) -> Result<PooledEngine, RpcError> {
+        let mut inputs = Vec::new();
+        if !inputs_json.trim().is_empty() {
+            let parsed: serde_json::Value = serde_json::from_str(inputs_json)
+                .map_err(|e| RpcError::new("BadRequest", format!("invalid inputs_json: {}", e)))?;
+            let serde_json::Value::Object(object) = parsed else {
+                return Err(RpcError::new(
+                    "BadRequest",
+                    "inputs_json must be a JSON object",
+                ));
+            };
+            for (name, value) in object {
+                let value = json_to_value(&value)
+                    .map_err(|e| RpcError::new("BadRequest", e.to_string()))?;
+                inputs.push((name, value));
+            }
+        }
+        let mut engine = self.pool.acquire();
+        for (name, value) in inputs {
+            engine.set_global(&name, value);
+        }
+        Ok(engine)
+    }
+}
+
+fn parse_json_array(text: &str) -> Result<Vec<Value>, RpcError> {
+    if text.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    let parsed: serde_json::Value = serde_json::from_str(text)
+        .map_err(|e| RpcError::new("BadRequest", format!("invalid args_json: {}", e)))?;
+    let serde_json::Value::Array(items) = parsed else {
+        return Err(RpcError::new(
+            "BadRequest",
+            "args_json must be a JSON array",
+        ));
+    };
+    items
+        .iter()
+        .map(|item| json_to_value(item).map_err(|e| RpcError::new("BadRequest", e.to_string())))
+        .collect()
+}
+
+/// 工作线程上执行的任务
+type Job = Box<dyn FnOnce(&mut EvalService) + Send>;
+
+/// tonic gRPC 服务
+///
+/// 创建时启动指定数量的工作线程，每个线程持有自己的 [`EvalService`]；
+/// 服务被丢弃后工作线程处理完剩余请求退出。
+pub struct GrpcEvaluator {
+    jobs: mpsc::Sender<Job>,
+}
+
+impl GrpcEvaluator {
+    /// 创建服务：工作线程数和引擎的沙箱配置
+    pub fn new(workers: usize, sandbox: SandboxConfig) -> Self {
+        let (jobs, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        for _ in 0..workers.max(1) {
+            let receiver = Arc::clone(&receiver);
+            let sandbox = sandbox.clone();
+            thread::spawn(move || {
+                let mut service = EvalService::new(1, sandbox);
+                loop {
+                    let job = match receiver.lock() {
+                        Ok(receiver) => receiver.recv(),
+                        Err(_) => break,
+                    };
+                    let Ok(job) = job else {
+                        break;
+                    };
+                    job(&mut service);
+                }
+            });
+        }
+        GrpcEvaluator { jobs }
+    }
+
+    /// 包装为可以注册到 `tonic::transport::Server` 的服务
+    pub fn into_server(self) -> pb::evaluator_server::EvaluatorServer<Self> {
+        pb::evaluator_server::EvaluatorServer::new(self)
+    }
+
+    /// 在空闲的工作线程上执行，等待结果
+    async fn run<R: Send + 'static>(
+        &self,
+        f: impl FnOnce(&mut EvalService) -> R + Send + 'static,
+    ) -> Result<R, Status> {
+        let (reply, result) = oneshot::channel();
+        self.jobs
+            .send(Box::new(move |service| {
+                let _ = reply.send(f(service));
+            }))
+            .map_err(|_| Status::unavailable("evaluator workers have stopped"))?;
+        result
+            .await
+            .map_err(|_| Status::internal("evaluator worker terminated"))
+    }
+}
+
+#[tonic::async_trait]
+impl pb::evaluator_server::Evaluator for GrpcEvaluator {
+    async fn eval(
+        &self,
+        request: Request<pb::EvalRequest>,
+    ) -> Result<Response<pb::EvalResponse>, Status> {
+        let request = EvalRequest::from(request.into_inner());
+        let response = self.run(move |service| service.eval(&request)).await?;
+        Ok(Response::new(response.into()))
+    }
+
+    async fn compile(
+        &self,
+        request: Request<pb::CompileRequest>,
+    ) -> Result<Response<pb::CompileResponse>, Status> {
+        let request = CompileRequest {
+            code: request.into_inner().code,
+        };
+        let response = self.run(move |service| service.compile(&request)).await?;
+        Ok(Response::new(response.into()))
+    }
+
+    async fn call_function(
+        &self,
+        request: Request<pb::CallFunctionRequest>,
+    ) -> Result<Response<pb::EvalResponse>, Status> {
+        let request = request.into_inner();
+        let request = CallFunctionRequest {
+            setup_code: request.setup_code,
+            function: request.function,
+            args_json: request.args_json,
+        };
+        let response = self
+            .run(move |service| service.call_function(&request))
+            .await?;
+        Ok(Response::new(response.into()))
+    }
+
+    type StreamTraceStream = Pin<Box<dyn Stream<Item = Result<pb::TraceEvent, Status>> + Send>>;
+
+    async fn stream_trace(
+        &self,
+        request: Request<pb::EvalRequest>,
+    ) -> Result<Response<Self::StreamTraceStream>, Status> {
+        let request = EvalRequest::from(request.into_inner());
+        let events = self
+            .run(move |service| service.stream_trace(&request))
+            .await?;
+        let events = events.into_iter().map(pb::TraceEvent::from).map(Ok);
+        Ok(Response::new(Box::pin(tokio_stream::iter(events))))
+    }
+}
+
+impl From<pb::EvalRequest> for EvalRequest {
+    fn from(request: pb::EvalRequest) -> Self {
+        EvalRequest {
+            code: request.code,
+            inputs_json: request.inputs_json,
+        }
+    }
+}
+
+impl From<RpcError> for pb::Error {
+    fn from(error: RpcError) -> Self {
+        pb::Error {
+            kind: error.kind,
+            message: error.message,
+        }
+    }
+}
+
+impl From<EvalResponse> for pb::EvalResponse {
+    fn from(response: EvalResponse) -> Self {
+        pb::EvalResponse {
+            ok: response.ok,
+            result_json: response.result_json,
+            error: response.error.map(Into::into),
+        }
+    }
+}
+
+impl From<CompileResponse> for pb::CompileResponse {
+    fn from(response: CompileResponse) -> Self {
+        pb::CompileResponse {
+            ok: response.ok,
+            statements: response.statements,
+            requirements_json: response.requirements_json,
+            error: response.error.map(Into::into),
+        }
+    }
+}
+
+impl From<TraceEvent> for pb::TraceEvent {
+    fn from(event: TraceEvent) -> Self {
+        let event = match event {
+            TraceEvent::Trace(record) => pb::trace_event::Event::Trace(pb::TraceRecord {
+                level: record.level,
+                category: record.category,
+                label: record.label,
+                values_json: record.values_json,
+                location: record.location,
+            }),
+            TraceEvent::Result(response) => pb::trace_event::Event::Result(response.into()),
+        };
+        pb::TraceEvent { event: Some(event) }
+    }
+}
@@ -0,0 +1,79 @@
+//! 确定性执行模式
+//!
+//! 开启后同一段脚本在相同输入下每次求值得到完全相同的结果，便于审计回放和结果缓存：
+//! - `RANDOM()` 使用固定种子，每次顶层求值都从同一序列开始
+//! - `NOW()` 和依赖当前日期的内置函数使用注入的时钟
+//! - 字典的键按字典序输出（`KEYS`、`VALUES`、打印和字符串转换）
+//! - 读写外部状态或依赖真实时间的内置函数（文件、网络、机密、KV、调度、超时、并行任务等）被禁止，
+//!   即注册表中标记为 IO 的函数（见 `BuiltInRegistry::is_io`），包括所有需要权限才注册的函数
+
+/// 确定性模式配置
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeterministicConfig {
+    /// `RANDOM()` 的种子
+    pub seed: u64,
+    /// 注入的当前时间（Unix 时间戳，秒）
+    pub now: f64,
+}
+
+impl DeterministicConfig {
+    /// 使用指定种子创建配置，时钟固定在 Unix 纪元（1970-01-01T00:00:00Z）
+    pub fn new(seed: u64) -> Self {
+        DeterministicConfig { seed, now: 0.0 }
+    }
+
+    /// 设置注入的当前时间（Unix 时间戳，秒）
+    ///
+    /// # 示例
+    /// ```
+    /// use aether::{Aether, DeterministicConfig, Value};
+    ///
+    /// let config = DeterministicConfig::new(42).with_clock(1_700_000_000.0);
+    /// let mut engine = Aether::new().with_deterministic(config);
+    /// assert_eq!(engine.eval("NOW()").unwrap(), Value::Number(1_700_000_000.0));
+    /// ```
+    pub fn with_clock(mut self, now: f64) -> Self {
+        self.now = now;
+        self
+    }
+}
+
+impl Default for DeterministicConfig {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+/// `RANDOM()` 使用的伪随机数生成器（SplitMix64）
+#[derive(Debug, Clone)]
+pub(crate) struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    pub(crate) fn new(seed: u64) -> Self {
+        SplitMix64 { state: seed }
+    }
+
+    /// 以当前时间为种子
+    pub(crate) fn from_time() -> Self {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+        Self::new(nanos)
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// [0, 1) 区间内均匀分布的浮点数
+    pub(crate) fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
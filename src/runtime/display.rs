@@ -0,0 +1,128 @@
+//! 值的显示/格式化配置
+//!
+//! 控制 `Value` 转换为字符串时的表现（PRINT、TO_STRING、REPL 回显），
+//! 包括浮点精度、分数显示方式、字典键排序以及超大结构的截断。
+//!
+//! 配置保存在引擎上，求值期间通过线程局部存储传递给内置函数，
+//! 与沙箱的 `ScopedValidator` 相同，避免修改内置函数签名。
+
+use std::cell::RefCell;
+
+/// 值的显示配置
+///
+/// 默认配置与未配置时的输出完全一致。
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DisplayOptions {
+    /// 浮点数最多保留的小数位数（去除末尾的 0）
+    /// None 表示使用最短的精确表示
+    pub float_precision: Option<usize>,
+
+    /// 分数以小数形式显示（如 `0.5` 而不是 `1/2`）
+    pub fraction_as_decimal: bool,
+
+    /// 字典按键名排序显示（保证输出稳定）
+    pub sort_dict_keys: bool,
+
+    /// 最大嵌套深度，超出部分显示为 `[...]` / `{...}`
+    /// None 表示无限制
+    pub max_depth: Option<usize>,
+
+    /// 数组/字典/集合最多显示的元素个数，其余显示为 `...`
+    /// None 表示无限制
+    pub max_items: Option<usize>,
+}
+
+impl DisplayOptions {
+    /// 创建默认配置
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置浮点精度
+    pub fn with_float_precision(mut self, places: usize) -> Self {
+        self.float_precision = Some(places);
+        self
+    }
+
+    /// 分数以小数形式显示
+    pub fn with_fraction_as_decimal(mut self, enabled: bool) -> Self {
+        self.fraction_as_decimal = enabled;
+        self
+    }
+
+    /// 字典按键名排序
+    pub fn with_sorted_dict_keys(mut self, enabled: bool) -> Self {
+        self.sort_dict_keys = enabled;
+        self
+    }
+
+    /// 设置最大嵌套深度
+    pub fn with_max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// 设置每个容器最多显示的元素个数
+    pub fn with_max_items(mut self, items: usize) -> Self {
+        self.max_items = Some(items);
+        self
+    }
+
+    /// 格式化浮点数
+    pub fn format_number(&self, n: f64) -> String {
+        if n.fract() == 0.0 {
+            return format!("{:.0}", n);
+        }
+        match self.float_precision {
+            Some(places) => {
+                let s = format!("{:.*}", places, n);
+                let s = if s.contains('.') {
+                    s.trim_end_matches('0').trim_end_matches('.')
+                } else {
+                    s.as_str()
+                };
+                // 舍入后为 -0 时显示 0
+                if s == "-0" {
+                    "0".to_string()
+                } else {
+                    s.to_string()
+                }
+            }
+            None => format!("{}", n),
+        }
+    }
+}
+
+// 线程局部的显示配置（求值期间有效）
+thread_local! {
+    static DISPLAY_OPTIONS: RefCell<DisplayOptions> = RefCell::new(DisplayOptions::default());
+}
+
+/// 获取当前线程的显示配置
+pub fn current_display_options() -> DisplayOptions {
+    DISPLAY_OPTIONS.with(|o| o.borrow().clone())
+}
+
+/// 设置当前线程的显示配置
+pub fn set_display_options(options: DisplayOptions) {
+    DISPLAY_OPTIONS.with(|o| *o.borrow_mut() = options);
+}
+
+/// 在作用域内设置显示配置（RAII 模式，结束时恢复之前的配置）
+pub struct ScopedDisplayOptions {
+    previous: DisplayOptions,
+}
+
+impl ScopedDisplayOptions {
+    /// 设置显示配置并在作用域结束时自动恢复
+    pub fn set(options: DisplayOptions) -> Self {
+        let previous = DISPLAY_OPTIONS.with(|o| std::mem::replace(&mut *o.borrow_mut(), options));
+        Self { previous }
+    }
+}
+
+impl Drop for ScopedDisplayOptions {
+    fn drop(&mut self) {
+        set_display_options(std::mem::take(&mut self.previous));
+    }
+}
@@ -0,0 +1,48 @@
+//! 扩展块处理器
+//!
+//! 词法分析器把 `@TAG { ... }` 识别为扩展块，解析为 `Expr::Extension`，
+//! payload 保留为原始文本。宿主程序按标签注册处理器，求值时由处理器
+//! 解释 payload（如内嵌 SQL、模板、查询语言），无需修改解析器和求值器。
+//!
+//! ```ignore
+//! engine.register_extension("SQL", |payload, ctx| {
+//!     let table = ctx.get("TABLE");
+//!     run_query(payload, table).map_err(|e| e.to_string())
+//! });
+//! engine.eval("Set ROWS @SQL { SELECT * FROM users }")?;
+//! ```
+
+use crate::environment::Environment;
+use crate::value::Value;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// 扩展块求值时传给处理器的上下文
+///
+/// 处理器可以读取扩展块所在作用域中的变量。
+pub struct ExtensionContext {
+    tag: String,
+    env: Rc<RefCell<Environment>>,
+}
+
+impl ExtensionContext {
+    pub(crate) fn new(tag: &str, env: Rc<RefCell<Environment>>) -> Self {
+        ExtensionContext {
+            tag: tag.to_string(),
+            env,
+        }
+    }
+
+    /// 扩展块的标签（不含 `@`）
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    /// 读取当前作用域中的变量
+    pub fn get(&self, name: &str) -> Option<Value> {
+        self.env.borrow().get(name)
+    }
+}
+
+/// 扩展块处理器：接收原始 payload 和上下文，返回求值结果或错误信息
+pub type ExtensionHandler = Rc<dyn Fn(&str, &ExtensionContext) -> Result<Value, String>>;
@@ -0,0 +1,191 @@
+//! 语言版本与兼容模式
+//!
+//! 脚本可以在开头用 `#language 1.0` 声明目标版本，宿主也可以通过
+//! `Aether::set_language_version` 设置默认版本。低于某语法/内置函数引入版本时，
+//! 使用它们会报错，而不是让旧脚本在语言演进后悄悄改变含义。
+//!
+//! 版本限制只作用于脚本自身的调用：标准库函数和导入的模块按最新版本编写，
+//! 它们内部使用的新内置函数不受脚本声明的版本影响。
+
+use std::fmt;
+
+/// Aether 语言版本
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LanguageVersion {
+    /// 初始版本
+    V1_0,
+    /// 扩展块 `@TAG { ... }`，以及表格、集合、并发、KV 存储、国际化等内置函数
+    V1_1,
+}
+
+impl LanguageVersion {
+    /// 当前最新版本（引擎默认）
+    pub const LATEST: LanguageVersion = LanguageVersion::V1_1;
+
+    /// 所有已发布的版本，按时间顺序
+    pub const ALL: [LanguageVersion; 2] = [LanguageVersion::V1_0, LanguageVersion::V1_1];
+
+    /// 解析 `1.0` 形式的版本号
+    pub fn parse(text: &str) -> Result<Self, String> {
+        Self::ALL
+            .into_iter()
+            .find(|version| version.as_str() == text.trim())
+            .ok_or_else(|| {
+                format!(
+                    "Unsupported language version '{}' (supported: {})",
+                    text.trim(),
+                    Self::ALL.map(|v| v.as_str()).join(", ")
+                )
+            })
+    }
+
+    /// 版本号文本
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LanguageVersion::V1_0 => "1.0",
+            LanguageVersion::V1_1 => "1.1",
+        }
+    }
+
+    /// 引入该内置函数的语言版本
+    pub fn builtin_since(name: &str) -> LanguageVersion {
+        if BUILTINS_SINCE_1_1.contains(&name) {
+            LanguageVersion::V1_1
+        } else {
+            LanguageVersion::V1_0
+        }
+    }
+}
+
+impl Default for LanguageVersion {
+    fn default() -> Self {
+        Self::LATEST
+    }
+}
+
+impl fmt::Display for LanguageVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// 1.1 新增的内置函数
+const BUILTINS_SINCE_1_1: &[&str] = &[
+    "ALLOCATE",
+    "APPLY_PATCH",
+    "AWAIT_ALL",
+    "BSEARCH",
+    "CHANNEL",
+    "CHANNEL_CLOSE",
+    "CLEAR_HOLIDAY_CALENDAR",
+    "CLEAR_MESSAGES",
+    "CLEAR_TIMER",
+    "COLUMNS",
+    "COMPARE_LOCALE",
+    "CSV_WRITER_OPEN",
+    "CUMPROD",
+    "CUMSUM",
+    "DATE_ADD",
+    "DATE_DIFF",
+    "DATE_FORMAT",
+    "DATE_PARSE",
+    "DATE_TIMESTAMP",
+    "DATE_TO_TZ",
+    "DEBOUNCE",
+    "DIFF",
+    "DIFFERENCE",
+    "DISPLAY",
+    "DOC",
+    "EIGENVALUES",
+    "EIGENVECTORS",
+    "FUZZY_MATCH",
+    "GET_LOCALE",
+    "GLOBALS",
+    "GROUP_AGG",
+    "HTTP_SERVE",
+    "IDENTITY_MATRIX",
+    "IMAGE",
+    "INSERT_SORTED",
+    "INTERSECT",
+    "ISQRT",
+    "JARO_WINKLER",
+    "KV_DELETE",
+    "KV_GET",
+    "KV_KEYS",
+    "KV_OPEN",
+    "KV_SET",
+    "LEVENSHTEIN",
+    "LOAD_HOLIDAY_CALENDAR",
+    "LOAD_MESSAGES",
+    "LOWER_BOUND",
+    "LUHN_CHECK",
+    "MARK_SECRET",
+    "MODPOW",
+    "NOW",
+    "ONES",
+    "PARALLEL",
+    "PAYROLL_RUN",
+    "PLOT_BAR",
+    "PLOT_HIST",
+    "PLOT_LINE",
+    "PLURAL_CATEGORY",
+    "PRIME_TEST",
+    "RANDOM",
+    "RANK",
+    "RECV",
+    "RENDER_TEMPLATE",
+    "REPORT_BUILD",
+    "RESHAPE",
+    "RETRY",
+    "ROLLING",
+    "ROUND_DOWN",
+    "ROUND_HALF_EVEN",
+    "ROUND_HALF_UP",
+    "ROUND_UP",
+    "RUNNING_SUM",
+    "RUN_SCHEDULER",
+    "S3_GET",
+    "S3_LIST",
+    "S3_PUT",
+    "SCHEDULE",
+    "SECRET",
+    "SELECT",
+    "SEND",
+    "SEND_EMAIL",
+    "SET",
+    "SET_HOLIDAY_CALENDAR",
+    "SET_INTERVAL",
+    "SET_LOCALE",
+    "SET_PAYROLL_ROUNDING",
+    "SET_TIMEOUT",
+    "SNAPSHOT_MATCH",
+    "SOLVE",
+    "SORT_LOCALE",
+    "SOUNDEX",
+    "SPAWN",
+    "SSE_CLOSE",
+    "SSE_CONNECT",
+    "SSE_RECV",
+    "SUBSET",
+    "TABLE",
+    "TABLE_FROM_CSV",
+    "TO_DICTS",
+    "TRANSLATE",
+    "UNION",
+    "UNSCHEDULE",
+    "UPPER_BOUND",
+    "VALIDATE",
+    "VALIDATE_IBAN",
+    "VALIDATE_ID_CN",
+    "VARS",
+    "WHERE",
+    "WITH_TIMEOUT",
+    "WRITER_CLOSE",
+    "WRITER_ROW",
+    "WS_CLOSE",
+    "WS_CONNECT",
+    "WS_RECV",
+    "WS_SEND",
+    "XLSX_WRITER_OPEN",
+    "ZEROS",
+];
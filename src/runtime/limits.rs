@@ -6,10 +6,10 @@ use std::fmt;
 
 /// 执行限制配置
 ///
-/// 用于控制脚本执行的资源消耗，包括步数、递归深度、执行时长和内存使用。
+/// 用于控制脚本执行的资源消耗，包括步数、递归深度、执行时长、循环次数和内存使用。
 #[derive(Debug, Clone, PartialEq)]
 pub struct ExecutionLimits {
-    /// 最大执行步数（指令计数）
+    /// 最大执行步数（指令计数：每条语句和每次检查 While 条件各计一步）
     /// None 表示无限制
     pub max_steps: Option<usize>,
 
@@ -21,8 +21,20 @@ pub struct ExecutionLimits {
     /// None 表示无限制
     pub max_duration_ms: Option<u64>,
 
-    /// 最大内存分配（字节）
-    /// None 表示无限制（暂未实现，预留）
+    /// 单个 While / For 循环的最大迭代次数（每次执行循环语句单独计数）
+    /// None 表示无限制
+    pub max_loop_iterations: Option<usize>,
+
+    /// 单个数组的最大长度
+    /// None 表示无限制
+    pub max_array_len: Option<usize>,
+
+    /// 单个字典的最大条目数
+    /// None 表示无限制
+    pub max_dict_entries: Option<usize>,
+
+    /// 最大内存分配（字节，按 `Aether::memory_report` 的方式估算）
+    /// None 表示无限制
     pub max_memory_bytes: Option<usize>,
 }
 
@@ -32,6 +44,9 @@ impl Default for ExecutionLimits {
             max_steps: Some(1_000_000),      // 默认100万步
             max_recursion_depth: Some(1000), // 默认1000层
             max_duration_ms: Some(30_000),   // 默认30秒
+            max_loop_iterations: None,
+            max_array_len: None,
+            max_dict_entries: None,
             max_memory_bytes: None,
         }
     }
@@ -44,6 +59,9 @@ impl ExecutionLimits {
             max_steps: None,
             max_recursion_depth: None,
             max_duration_ms: None,
+            max_loop_iterations: None,
+            max_array_len: None,
+            max_dict_entries: None,
             max_memory_bytes: None,
         }
     }
@@ -54,6 +72,9 @@ impl ExecutionLimits {
             max_steps: Some(100_000),       // 10万步
             max_recursion_depth: Some(100), // 100层
             max_duration_ms: Some(5_000),   // 5秒
+            max_loop_iterations: None,
+            max_array_len: None,
+            max_dict_entries: None,
             max_memory_bytes: None,
         }
     }
@@ -64,11 +85,60 @@ impl ExecutionLimits {
             max_steps: Some(10_000_000),     // 1000万步
             max_recursion_depth: Some(5000), // 5000层
             max_duration_ms: Some(300_000),  // 5分钟
+            max_loop_iterations: None,
+            max_array_len: None,
+            max_dict_entries: None,
             max_memory_bytes: None,
         }
     }
 }
 
+/// 并发任务限制
+///
+/// 约束 SPAWN / PARALLEL 启动的任务，防止租户脚本通过大量任务耗尽宿主的线程和时间。
+/// 任务中再启动的任务与调用方共享同一份计数和时间预算。
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ConcurrencyLimits {
+    /// 同时存在的最大任务数（SPAWN 后尚未结束的任务和 PARALLEL 的工作线程）
+    /// None 表示无限制
+    pub max_concurrent_tasks: Option<usize>,
+
+    /// 每个任务的最大执行步数
+    /// None 表示沿用引擎的 `max_steps`
+    pub max_task_steps: Option<usize>,
+
+    /// 所有任务累计的最大执行时长（毫秒），在引擎生命周期内累计
+    /// None 表示无限制
+    pub max_total_task_ms: Option<u64>,
+}
+
+impl ConcurrencyLimits {
+    /// 创建无限制的配置
+    pub fn unrestricted() -> Self {
+        Self::default()
+    }
+
+    /// 创建严格限制的配置（用于 DSL 安全模式）
+    pub fn strict() -> Self {
+        Self {
+            max_concurrent_tasks: Some(4),
+            max_task_steps: Some(100_000),
+            max_total_task_ms: Some(5_000),
+        }
+    }
+}
+
+/// 内存限制的种类
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemoryResource {
+    /// 单个数组的长度（`max_array_len`）
+    ArrayLength,
+    /// 单个字典的条目数（`max_dict_entries`）
+    DictEntries,
+    /// 估算的内存总量（`max_memory_bytes`）
+    Bytes,
+}
+
 /// 执行限制错误
 ///
 /// 当脚本超出配置的资源限制时返回此错误。
@@ -83,8 +153,32 @@ pub enum ExecutionLimitError {
     /// 执行时长超出
     DurationExceeded { duration_ms: u64, limit: u64 },
 
-    /// 内存限制超出（暂未实现）
-    MemoryLimitExceeded { bytes: usize, limit: usize },
+    /// 单个循环的迭代次数超出
+    LoopIterationLimitExceeded {
+        iterations: usize,
+        limit: usize,
+        /// 循环头部，如 `While (N > 0)`
+        header: String,
+        /// 循环所在的函数（顶层代码为 None）
+        function: Option<String>,
+        /// 循环条件中变量的当前值（名称，显示文本）
+        variables: Vec<(String, String)>,
+    },
+
+    /// 内存限制超出
+    MemoryLimitExceeded {
+        /// 超出的是哪一项限制
+        resource: MemoryResource,
+        /// 实际大小（元素数、条目数或估算字节数）
+        size: usize,
+        limit: usize,
+    },
+
+    /// 并发任务数超出
+    TaskLimitExceeded { tasks: usize, limit: usize },
+
+    /// 任务累计执行时长超出
+    TaskTimeBudgetExceeded { elapsed_ms: u64, limit: u64 },
 }
 
 impl fmt::Display for ExecutionLimitError {
@@ -105,10 +199,60 @@ impl fmt::Display for ExecutionLimitError {
                 "Execution duration limit exceeded: {} ms (limit: {} ms)",
                 duration_ms, limit
             ),
-            ExecutionLimitError::MemoryLimitExceeded { bytes, limit } => write!(
+            ExecutionLimitError::LoopIterationLimitExceeded {
+                iterations,
+                limit,
+                header,
+                function,
+                variables,
+            } => {
+                write!(
+                    f,
+                    "Loop iteration limit exceeded: {} iterations (limit: {}) in `{}`",
+                    iterations, limit, header
+                )?;
+                if let Some(function) = function {
+                    write!(f, " inside {}", function)?;
+                }
+                if !variables.is_empty() {
+                    let values: Vec<String> = variables
+                        .iter()
+                        .map(|(name, value)| format!("{} = {}", name, value))
+                        .collect();
+                    write!(f, "; {}", values.join(", "))?;
+                }
+                Ok(())
+            }
+            ExecutionLimitError::MemoryLimitExceeded {
+                resource,
+                size,
+                limit,
+            } => match resource {
+                MemoryResource::ArrayLength => write!(
+                    f,
+                    "Memory limit exceeded: array of {} elements (limit: {})",
+                    size, limit
+                ),
+                MemoryResource::DictEntries => write!(
+                    f,
+                    "Memory limit exceeded: dict of {} entries (limit: {})",
+                    size, limit
+                ),
+                MemoryResource::Bytes => write!(
+                    f,
+                    "Memory limit exceeded: {} bytes (limit: {} bytes)",
+                    size, limit
+                ),
+            },
+            ExecutionLimitError::TaskLimitExceeded { tasks, limit } => write!(
                 f,
-                "Memory limit exceeded: {} bytes (limit: {} bytes)",
-                bytes, limit
+                "Concurrent task limit exceeded: {} tasks (limit: {})",
+                tasks, limit
+            ),
+            ExecutionLimitError::TaskTimeBudgetExceeded { elapsed_ms, limit } => write!(
+                f,
+                "Task time budget exceeded: {} ms used (limit: {} ms)",
+                elapsed_ms, limit
             ),
         }
     }
@@ -126,6 +270,9 @@ mod tests {
         assert_eq!(limits.max_steps, Some(1_000_000));
         assert_eq!(limits.max_recursion_depth, Some(1000));
         assert_eq!(limits.max_duration_ms, Some(30_000));
+        assert_eq!(limits.max_loop_iterations, None);
+        assert_eq!(limits.max_array_len, None);
+        assert_eq!(limits.max_dict_entries, None);
         assert_eq!(limits.max_memory_bytes, None);
     }
 
@@ -156,6 +303,19 @@ mod tests {
         assert_eq!(limits.max_memory_bytes, None);
     }
 
+    #[test]
+    fn test_concurrency_limits() {
+        let limits = ConcurrencyLimits::default();
+        assert_eq!(limits.max_concurrent_tasks, None);
+        assert_eq!(limits.max_task_steps, None);
+        assert_eq!(limits.max_total_task_ms, None);
+
+        let limits = ConcurrencyLimits::strict();
+        assert_eq!(limits.max_concurrent_tasks, Some(4));
+        assert_eq!(limits.max_task_steps, Some(100_000));
+        assert_eq!(limits.max_total_task_ms, Some(5_000));
+    }
+
     #[test]
     fn test_error_display() {
         let err = ExecutionLimitError::StepLimitExceeded {
@@ -178,5 +338,15 @@ mod tests {
         };
         assert!(err.to_string().contains("5000"));
         assert!(err.to_string().contains("1000"));
+
+        let err = ExecutionLimitError::MemoryLimitExceeded {
+            resource: MemoryResource::ArrayLength,
+            size: 1001,
+            limit: 1000,
+        };
+        assert_eq!(
+            err.to_string(),
+            "Memory limit exceeded: array of 1001 elements (limit: 1000)"
+        );
     }
 }
@@ -0,0 +1,385 @@
+//! 内存检查
+//!
+//! [`crate::Aether::memory_report`] 从全局环境（以及当前作用域链）出发遍历所有可达的值：
+//! 按类型汇总数量和估算字节数，列出最大的数组/字典/集合/表，并找出闭包与其捕获环境
+//! 之间的 Rc 循环。引用计数无法回收这种循环，长时间运行的脚本如果在函数里反复定义
+//! 闭包，内存就会持续增长。
+//!
+//! 字节数是估算值（值本身的大小加上字符串、集合等占用的堆内存），用于比较和定位问题，
+//! 不等于进程的实际内存占用。
+
+use crate::ast::{Expr, Stmt};
+use crate::environment::Environment;
+use crate::value::{SetKey, Value};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::mem::size_of;
+use std::rc::Rc;
+
+/// 报告中列出的最大集合数量
+const LARGEST_LIMIT: usize = 10;
+
+/// 某一类型的值的数量和估算字节数
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TypeUsage {
+    /// 值的个数（包括嵌套在集合中的元素）
+    pub count: usize,
+    /// 估算字节数（不含子元素）
+    pub bytes: usize,
+}
+
+/// 一个较大的集合
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LargeValue {
+    /// 从变量名出发的路径，如 `CACHE.items[3]`；闭包捕获的变量写作 `MAKE::COUNTS`
+    pub path: String,
+    /// 类型名（`Array` / `Dict` / `Set` / `Table`）
+    pub type_name: &'static str,
+    /// 元素个数（表为行数）
+    pub len: usize,
+    /// 估算字节数（含所有子元素）
+    pub bytes: usize,
+}
+
+/// 疑似 Rc 循环：闭包保存在它自己捕获的环境（或其上层作用域）中
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SuspectedCycle {
+    /// 闭包所在的路径
+    pub path: String,
+    /// 闭包名
+    pub function: String,
+    /// 因循环而无法释放的作用域中的变量个数
+    pub variables: usize,
+}
+
+/// 内存检查报告
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MemoryReport {
+    /// 可达值的总个数
+    pub total_values: usize,
+    /// 估算总字节数
+    pub total_bytes: usize,
+    /// 按类型汇总
+    pub by_type: BTreeMap<&'static str, TypeUsage>,
+    /// 最大的集合（按估算字节数降序）
+    pub largest: Vec<LargeValue>,
+    /// 当前作用域链的深度（顶层为 1）
+    pub env_depth: usize,
+    /// 被闭包捕获而保留下来的非全局作用域个数
+    pub captured_envs: usize,
+    /// 疑似 Rc 循环
+    pub suspected_cycles: Vec<SuspectedCycle>,
+}
+
+impl fmt::Display for MemoryReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "=== MEMORY ===")?;
+        writeln!(
+            f,
+            "values: {} (~{})",
+            self.total_values,
+            format_bytes(self.total_bytes)
+        )?;
+        writeln!(
+            f,
+            "env_depth: {}, captured_envs: {}",
+            self.env_depth, self.captured_envs
+        )?;
+        writeln!(f, "by_type:")?;
+        for (type_name, usage) in &self.by_type {
+            writeln!(
+                f,
+                "  {:<10} {:>8}  ~{}",
+                type_name,
+                usage.count,
+                format_bytes(usage.bytes)
+            )?;
+        }
+        if !self.largest.is_empty() {
+            writeln!(f, "largest:")?;
+            for value in &self.largest {
+                writeln!(
+                    f,
+                    "  {:<24} {:<6} {:>8} items  ~{}",
+                    value.path,
+                    value.type_name,
+                    value.len,
+                    format_bytes(value.bytes)
+                )?;
+            }
+        }
+        if !self.suspected_cycles.is_empty() {
+            writeln!(f, "suspected_cycles:")?;
+            for cycle in &self.suspected_cycles {
+                writeln!(
+                    f,
+                    "  {}: closure {} is stored in the scope it captures ({} variables kept alive)",
+                    cycle.path, cycle.function, cycle.variables
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn format_bytes(bytes: usize) -> String {
+    match bytes {
+        b if b >= 1 << 20 => format!("{:.1} MB", b as f64 / (1 << 20) as f64),
+        b if b >= 1 << 10 => format!("{:.1} KB", b as f64 / (1 << 10) as f64),
+        b => format!("{} B", b),
+    }
+}
+
+/// 从全局环境和当前作用域出发检查所有可达的值
+pub(crate) fn inspect(
+    globals: &Rc<RefCell<Environment>>,
+    current: &Rc<RefCell<Environment>>,
+) -> MemoryReport {
+    let mut inspector = Inspector {
+        globals: env_id(globals),
+        visited_envs: HashSet::new(),
+        report: MemoryReport::default(),
+    };
+
+    let mut depth = 0;
+    let mut scope = Some(Rc::clone(current));
+    while let Some(env) = scope {
+        depth += 1;
+        scope = env.borrow().parent();
+    }
+    inspector.report.env_depth = depth;
+
+    inspector.visited_envs.insert(inspector.globals);
+    inspector.visit_scope("", globals);
+    inspector.visit_chain("", current);
+
+    let mut report = inspector.report;
+    sort_largest(&mut report.largest);
+    report
+}
+
+struct Inspector {
+    globals: usize,
+    visited_envs: HashSet<usize>,
+    report: MemoryReport,
+}
+
+impl Inspector {
+    /// 访问一个作用域的所有变量，返回估算字节数
+    fn visit_scope(&mut self, prefix: &str, env: &Rc<RefCell<Environment>>) -> usize {
+        let scope_id = env_id(env);
+        let mut bytes = 0;
+        let mut variables = 0;
+        let mut cycles = Vec::new();
+        env.borrow().for_each_binding(|name, value| {
+            variables += 1;
+            let path = format!("{}{}", prefix, name);
+            if scope_id != self.globals
+                && let Some(captured) = closure_env(value)
+                && self.reaches(captured, scope_id)
+            {
+                let function = match value {
+                    Value::Function {
+                        name: Some(function),
+                        ..
+                    } => function.clone(),
+                    _ => name.to_string(),
+                };
+                cycles.push((path.clone(), function));
+            }
+            bytes += self.visit(&path, value);
+        });
+        self.report
+            .suspected_cycles
+            .extend(cycles.into_iter().map(|(path, function)| SuspectedCycle {
+                path,
+                function,
+                variables,
+            }));
+        bytes
+    }
+
+    /// 访问从 `env` 到全局环境之前的每个尚未访问的作用域
+    fn visit_chain(&mut self, prefix: &str, env: &Rc<RefCell<Environment>>) -> usize {
+        let mut bytes = 0;
+        let mut scope = Some(Rc::clone(env));
+        while let Some(env) = scope {
+            if self.visited_envs.insert(env_id(&env)) {
+                self.report.captured_envs += 1;
+                bytes += self.visit_scope(prefix, &env);
+            }
+            scope = env.borrow().parent();
+        }
+        bytes
+    }
+
+    /// 作用域 `target` 是否可以从 `env` 沿父链到达（即 `env` 引用着 `target`）
+    fn reaches(&self, env: &Rc<RefCell<Environment>>, target: usize) -> bool {
+        let mut scope = Some(Rc::clone(env));
+        while let Some(env) = scope {
+            let id = env_id(&env);
+            if id == target {
+                return true;
+            }
+            if id == self.globals {
+                return false;
+            }
+            scope = env.borrow().parent();
+        }
+        false
+    }
+
+    /// 访问一个值及其子元素，返回估算字节数（含子元素）
+    fn visit(&mut self, path: &str, value: &Value) -> usize {
+        let own = own_bytes(value);
+        let usage = self.report.by_type.entry(value.type_name()).or_default();
+        usage.count += 1;
+        usage.bytes += own;
+        self.report.total_values += 1;
+        self.report.total_bytes += own;
+
+        let mut bytes = own;
+        let len = match value {
+            Value::Array(items) => {
+                for (i, item) in items.iter().enumerate() {
+                    let child = child_path(item, || format!("{}[{}]", path, i));
+                    bytes += self.visit(&child, item);
+                }
+                Some(items.len())
+            }
+            Value::Dict(map) => {
+                for (key, item) in map {
+                    let child = child_path(item, || format!("{}.{}", path, key));
+                    bytes += self.visit(&child, item);
+                }
+                Some(map.len())
+            }
+            Value::Set(set) => Some(set.len()),
+            Value::Table(table) => {
+                for row in 0..table.row_count() {
+                    for (col, column) in table.columns().iter().enumerate() {
+                        let cell = table.cell(row, col);
+                        let child = child_path(cell, || format!("{}[{}].{}", path, row, column));
+                        bytes += self.visit(&child, cell);
+                    }
+                }
+                Some(table.row_count())
+            }
+            Value::Lazy {
+                env,
+                cached: Some(cached),
+                ..
+            } => {
+                bytes += self.visit(&format!("{}<cached>", path), cached);
+                bytes += self.visit_chain(&format!("{}::", path), env);
+                None
+            }
+            Value::Function { env, .. }
+            | Value::Generator { env, .. }
+            | Value::Lazy { env, .. } => {
+                bytes += self.visit_chain(&format!("{}::", path), env);
+                None
+            }
+            _ => None,
+        };
+
+        if let Some(len) = len {
+            self.report.largest.push(LargeValue {
+                path: path.to_string(),
+                type_name: value.type_name(),
+                len,
+                bytes,
+            });
+            if self.report.largest.len() > LARGEST_LIMIT * 4 {
+                sort_largest(&mut self.report.largest);
+            }
+        }
+        bytes
+    }
+}
+
+fn sort_largest(largest: &mut Vec<LargeValue>) {
+    largest.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.path.cmp(&b.path)));
+    largest.truncate(LARGEST_LIMIT);
+}
+
+/// 只有集合和闭包需要路径（出现在报告中），标量元素不构造路径
+fn child_path(value: &Value, path: impl FnOnce() -> String) -> String {
+    match value {
+        Value::Array(_)
+        | Value::Dict(_)
+        | Value::Set(_)
+        | Value::Table(_)
+        | Value::Function { .. }
+        | Value::Generator { .. }
+        | Value::Lazy { .. } => path(),
+        _ => String::new(),
+    }
+}
+
+fn closure_env(value: &Value) -> Option<&Rc<RefCell<Environment>>> {
+    match value {
+        Value::Function { env, .. } | Value::Generator { env, .. } | Value::Lazy { env, .. } => {
+            Some(env)
+        }
+        _ => None,
+    }
+}
+
+fn env_id(env: &Rc<RefCell<Environment>>) -> usize {
+    Rc::as_ptr(env) as usize
+}
+
+/// 值及其元素占用的估算字节数（不含闭包捕获的作用域），用于检查 `max_memory_bytes`
+pub(crate) fn value_bytes(value: &Value) -> usize {
+    let children = match value {
+        Value::Array(items) => items.iter().map(value_bytes).sum(),
+        Value::Dict(map) => map.values().map(value_bytes).sum(),
+        Value::Table(table) => (0..table.row_count())
+            .flat_map(|row| (0..table.columns().len()).map(move |col| (row, col)))
+            .map(|(row, col)| value_bytes(table.cell(row, col)))
+            .sum(),
+        Value::Lazy {
+            cached: Some(cached),
+            ..
+        } => value_bytes(cached),
+        _ => 0,
+    };
+    own_bytes(value) + children
+}
+
+/// 值本身占用的估算字节数（不含子元素）
+fn own_bytes(value: &Value) -> usize {
+    let heap = match value {
+        Value::String(s) => s.capacity(),
+        Value::Fraction(f) => ((f.numer().bits() + f.denom().bits()) / 8) as usize,
+        Value::Array(items) => items.capacity() * size_of::<Value>(),
+        Value::Dict(map) => map
+            .keys()
+            .map(|key| size_of::<(String, Value)>() + key.capacity())
+            .sum(),
+        Value::Set(set) => set
+            .iter()
+            .map(|key| {
+                size_of::<SetKey>()
+                    + match key {
+                        SetKey::String(s) => s.capacity(),
+                        _ => 0,
+                    }
+            })
+            .sum(),
+        Value::Table(table) => table
+            .columns()
+            .iter()
+            .map(|column| size_of::<String>() + column.capacity())
+            .sum(),
+        Value::Function { params, body, .. } | Value::Generator { params, body, .. } => {
+            params.iter().map(|p| p.capacity()).sum::<usize>() + body.len() * size_of::<Stmt>()
+        }
+        Value::Lazy { .. } => size_of::<Expr>(),
+        Value::BuiltIn { name, .. } => name.capacity(),
+        _ => 0,
+    };
+    size_of::<Value>() + heap
+}
@@ -1,9 +1,29 @@
 //! 运行时限制和能力
 //!
-//! 本模块提供执行限制、调试器和 TRACE 系统等运行时能力。
+//! 本模块提供执行限制、调试器、TRACE 系统、警告通道、机密脱敏、快照测试、内存检查、确定性执行模式、执行回放和宿主驱动的定时器等运行时能力。
 
+pub mod deterministic;
+pub mod display;
+pub mod extension;
+pub mod language;
 pub mod limits;
+pub mod memory;
+pub mod replay;
+pub mod secrets;
+pub mod snapshot;
+pub mod timers;
 pub mod trace;
+pub mod warnings;
 
-pub use limits::{ExecutionLimitError, ExecutionLimits};
+pub use deterministic::DeterministicConfig;
+pub use display::{DisplayOptions, ScopedDisplayOptions};
+pub use extension::{ExtensionContext, ExtensionHandler};
+pub use language::LanguageVersion;
+pub use limits::{ConcurrencyLimits, ExecutionLimitError, ExecutionLimits, MemoryResource};
+pub use memory::{LargeValue, MemoryReport, SuspectedCycle, TypeUsage};
+pub use replay::{ExecutionTrace, IoEvent};
+pub use secrets::SecretsProvider;
+pub use snapshot::{SnapshotOutcome, SnapshotStats};
+pub use timers::PumpReport;
 pub use trace::{TraceEntry, TraceFilter, TraceLevel, TraceStats};
+pub use warnings::{Warning, WarningKind};
@@ -0,0 +1,267 @@
+//! 执行回放
+//!
+//! 录制模式下，依赖外部世界的内置函数（文件、网络、KV、S3、邮件、`INPUT`、`NOW`、`RANDOM`）
+//! 每次调用的参数和结果按顺序记录下来，连同脚本源码和最终结果保存为 [`ExecutionTrace`]（JSON）。
+//! 回放时重新执行同一脚本，这些内置函数不再访问外部世界，而是按顺序返回录制的结果
+//! （包括录制时的错误），线上事故因此可以在本地确定性地复现。回放不会真的写文件、
+//! 发请求或发邮件。
+//!
+//! 回放要求脚本以录制时的顺序和参数调用这些内置函数，否则报告 `Replay diverged` 错误并
+//! 指出第一个分歧的调用。`SPAWN` / `PARALLEL` 任务中的调用和导入的模块文件不在录制范围内，
+//! `SECRET` 的值从不写入录制文件，参数中出现的机密值会被脱敏。
+
+use crate::builtins::json::{FractionJsonMode, json_to_value, value_to_json};
+use crate::evaluator::RuntimeError;
+use crate::value::Value;
+use serde::{Deserialize, Serialize};
+
+/// 录制文件格式版本
+pub const TRACE_FORMAT_VERSION: u32 = 1;
+
+/// 录制和回放的内置函数
+pub const RECORDED_BUILTINS: &[&str] = &[
+    "INPUT",
+    "NOW",
+    "RANDOM",
+    "READ_FILE",
+    "WRITE_FILE",
+    "APPEND_FILE",
+    "DELETE_FILE",
+    "FILE_EXISTS",
+    "LIST_DIR",
+    "CREATE_DIR",
+    "KV_OPEN",
+    "KV_GET",
+    "KV_SET",
+    "KV_DELETE",
+    "KV_KEYS",
+    "CSV_WRITER_OPEN",
+    "XLSX_WRITER_OPEN",
+    "WRITER_ROW",
+    "WRITER_CLOSE",
+    "HTTP_GET",
+    "HTTP_POST",
+    "HTTP_PUT",
+    "HTTP_DELETE",
+    "SEND_EMAIL",
+    "S3_GET",
+    "S3_PUT",
+    "S3_LIST",
+    "WS_CONNECT",
+    "WS_SEND",
+    "WS_RECV",
+    "WS_CLOSE",
+    "SSE_CONNECT",
+    "SSE_RECV",
+    "SSE_CLOSE",
+];
+
+/// 内置函数的结果是否被录制和回放
+pub fn is_recorded(name: &str) -> bool {
+    RECORDED_BUILTINS.contains(&name)
+}
+
+/// 一次内置函数调用的录制
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IoEvent {
+    /// 内置函数名
+    pub function: String,
+    /// 参数（JSON，机密值已脱敏）
+    pub args: Vec<serde_json::Value>,
+    /// 返回值（JSON），出错时为 `None`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<serde_json::Value>,
+    /// 错误信息
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl IoEvent {
+    /// 录制时的结果
+    pub fn outcome(&self) -> Result<Value, String> {
+        if let Some(error) = &self.error {
+            return Err(error.clone());
+        }
+        match &self.result {
+            Some(json) => json_to_value(json).map_err(|e| e.to_string()),
+            None => Ok(Value::Null),
+        }
+    }
+}
+
+/// 一次执行的录制：脚本、按顺序的 IO 事件和最终结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExecutionTrace {
+    /// 格式版本
+    pub version: u32,
+    /// 脚本文件路径（从源码字符串录制时为 `None`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    /// 脚本源码
+    pub script: String,
+    /// IO 事件，按调用顺序
+    pub events: Vec<IoEvent>,
+    /// 录制时的结果（显示文本）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<String>,
+    /// 录制时的错误
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ExecutionTrace {
+    pub(crate) fn new(
+        script: String,
+        events: Vec<IoEvent>,
+        outcome: &Result<Value, String>,
+    ) -> Self {
+        let (result, error) = match outcome {
+            Ok(value) => (Some(value.to_string()), None),
+            Err(e) => (None, Some(e.clone())),
+        };
+        ExecutionTrace {
+            version: TRACE_FORMAT_VERSION,
+            file: None,
+            script,
+            events,
+            result,
+            error,
+        }
+    }
+
+    /// 序列化为缩进的 JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("execution trace is always serializable")
+    }
+
+    /// 从 JSON 读取录制
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        let trace: ExecutionTrace =
+            serde_json::from_str(text).map_err(|e| format!("Invalid execution trace: {}", e))?;
+        if trace.version != TRACE_FORMAT_VERSION {
+            return Err(format!(
+                "Unsupported execution trace version {} (expected {})",
+                trace.version, TRACE_FORMAT_VERSION
+            ));
+        }
+        Ok(trace)
+    }
+
+    /// 回放结果是否与录制时的结果一致
+    pub fn reproduces(&self, outcome: &Result<Value, String>) -> bool {
+        match outcome {
+            Ok(value) => self.error.is_none() && self.result.as_deref() == Some(&value.to_string()),
+            Err(e) => self.error.as_deref() == Some(e.as_str()),
+        }
+    }
+}
+
+/// 求值器上的录制或回放状态
+#[derive(Debug)]
+pub(crate) enum IoTape {
+    Recording(Vec<IoEvent>),
+    Replaying { events: Vec<IoEvent>, next: usize },
+}
+
+impl IoTape {
+    pub(crate) fn is_replaying(&self) -> bool {
+        matches!(self, IoTape::Replaying { .. })
+    }
+
+    /// 记录一次调用
+    pub(crate) fn record(
+        &mut self,
+        function: &str,
+        args: Vec<serde_json::Value>,
+        outcome: Result<&Value, String>,
+    ) {
+        if let IoTape::Recording(events) = self {
+            let (result, error) = match outcome {
+                Ok(value) => (Some(to_json(value)), None),
+                Err(e) => (None, Some(e)),
+            };
+            events.push(IoEvent {
+                function: function.to_string(),
+                args,
+                result,
+                error,
+            });
+        }
+    }
+
+    /// 取出下一个录制的结果，调用与录制不一致时报告分歧
+    pub(crate) fn replay(
+        &mut self,
+        function: &str,
+        args: Vec<serde_json::Value>,
+    ) -> Result<Value, RuntimeError> {
+        let IoTape::Replaying { events, next } = self else {
+            unreachable!("replay() called while recording")
+        };
+        let index = *next;
+        let diverged = |detail: String| {
+            RuntimeError::CustomError(format!(
+                "Replay diverged at IO event #{}: {}",
+                index + 1,
+                detail
+            ))
+        };
+
+        let Some(event) = events.get(index) else {
+            return Err(diverged(format!(
+                "script called {} but the trace has only {} IO events",
+                function,
+                events.len()
+            )));
+        };
+        if event.function != function {
+            return Err(diverged(format!(
+                "script called {} but the trace recorded {}",
+                function, event.function
+            )));
+        }
+        if event.args != args {
+            return Err(diverged(format!(
+                "{} called with {} but the trace recorded {}",
+                function,
+                serde_json::Value::Array(args),
+                serde_json::Value::Array(event.args.clone())
+            )));
+        }
+
+        *next += 1;
+        event.outcome().map_err(RuntimeError::CustomError)
+    }
+
+    /// 录制的事件；回放时为尚未消费的事件
+    pub(crate) fn into_events(self) -> Vec<IoEvent> {
+        match self {
+            IoTape::Recording(events) => events,
+            IoTape::Replaying { mut events, next } => events.split_off(next.min(events.len())),
+        }
+    }
+}
+
+/// 值的 JSON 表示，无法转换的值（函数等）记录为显示文本
+pub(crate) fn to_json(value: &Value) -> serde_json::Value {
+    value_to_json(value, FractionJsonMode::Tagged)
+        .unwrap_or_else(|_| serde_json::Value::String(value.to_string()))
+}
+
+/// 对 JSON 中的每个字符串（不含对象的键）应用 `f`
+pub(crate) fn map_strings(
+    json: serde_json::Value,
+    f: &dyn Fn(&str) -> String,
+) -> serde_json::Value {
+    use serde_json::Value as Json;
+    match json {
+        Json::String(s) => Json::String(f(&s)),
+        Json::Array(items) => Json::Array(items.into_iter().map(|v| map_strings(v, f)).collect()),
+        Json::Object(map) => Json::Object(
+            map.into_iter()
+                .map(|(k, v)| (k, map_strings(v, f)))
+                .collect(),
+        ),
+        other => other,
+    }
+}
@@ -0,0 +1,100 @@
+//! 机密值：宿主提供的机密解析回调和输出脱敏
+//!
+//! 脚本通过 `SECRET("name")` 向宿主请求机密，宿主用
+//! [`crate::Aether::set_secrets_provider`] 注册解析回调。默认不从环境变量读取；
+//! 需要时由宿主显式调用 [`crate::Aether::use_env_secrets`]。
+//! 脚本也可以用 `MARK_SECRET(v)` 把任意值标记为敏感。
+//!
+//! 登记的值出现在 TRACE 条目、错误信息以及 PRINT / DISPLAY 输出中时替换为 `***`。
+//! 求值期间机密列表通过线程局部存储传递给内置函数，与 `ScopedDisplayOptions` 相同。
+
+use crate::value::Value;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// 机密解析回调：名称 -> 机密值（未知名称返回 None）
+pub type SecretsProvider = Rc<dyn Fn(&str) -> Option<String>>;
+
+/// 脱敏后的占位文本
+pub const REDACTED: &str = "***";
+
+/// 把文本中出现的机密值替换为 [`REDACTED`]
+pub fn redact_text(text: &str, secrets: &[String]) -> String {
+    let mut text = text.to_string();
+    for secret in secrets {
+        if !secret.is_empty() && text.contains(secret.as_str()) {
+            text = text.replace(secret.as_str(), REDACTED);
+        }
+    }
+    text
+}
+
+/// 递归脱敏值中的字符串（数组和字典的元素也会处理）
+pub fn redact_value(value: &Value, secrets: &[String]) -> Value {
+    match value {
+        Value::String(s) => Value::String(redact_text(s, secrets)),
+        Value::Array(items) => {
+            Value::Array(items.iter().map(|v| redact_value(v, secrets)).collect())
+        }
+        Value::Dict(map) => Value::Dict(
+            map.iter()
+                .map(|(k, v)| (k.clone(), redact_value(v, secrets)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+/// 值中的字符串（包括数组和字典的元素）是否包含机密值
+pub fn contains_secret(value: &Value, secrets: &[String]) -> bool {
+    match value {
+        Value::String(s) => secrets
+            .iter()
+            .any(|secret| !secret.is_empty() && s.contains(secret.as_str())),
+        Value::Array(items) => items.iter().any(|v| contains_secret(v, secrets)),
+        Value::Dict(map) => map.values().any(|v| contains_secret(v, secrets)),
+        _ => false,
+    }
+}
+
+/// 引擎共享的机密值列表（SECRET / MARK_SECRET 登记，输出时脱敏）
+pub(crate) type SecretList = Rc<RefCell<Vec<String>>>;
+
+// 线程局部的机密值列表（求值期间有效），供 PRINT / DISPLAY 等内置函数脱敏
+thread_local! {
+    static ACTIVE_SECRETS: RefCell<Option<SecretList>> = const { RefCell::new(None) };
+}
+
+/// 在作用域内设置当前引擎的机密值列表（RAII 模式，结束时恢复之前的列表）
+pub(crate) struct ScopedSecrets {
+    previous: Option<SecretList>,
+}
+
+impl ScopedSecrets {
+    pub(crate) fn set(secrets: SecretList) -> Self {
+        let previous = ACTIVE_SECRETS.with(|s| s.borrow_mut().replace(secrets));
+        Self { previous }
+    }
+}
+
+impl Drop for ScopedSecrets {
+    fn drop(&mut self) {
+        ACTIVE_SECRETS.with(|s| *s.borrow_mut() = self.previous.take());
+    }
+}
+
+/// 用当前求值中登记的机密值脱敏文本（未登记机密时原样返回）
+pub(crate) fn redact_output(text: &str) -> String {
+    ACTIVE_SECRETS.with(|s| match s.borrow().as_ref() {
+        Some(secrets) if !secrets.borrow().is_empty() => redact_text(text, &secrets.borrow()),
+        _ => text.to_string(),
+    })
+}
+
+/// 用当前求值中登记的机密值脱敏值
+pub(crate) fn redact_output_value(value: &Value) -> Value {
+    ACTIVE_SECRETS.with(|s| match s.borrow().as_ref() {
+        Some(secrets) if !secrets.borrow().is_empty() => redact_value(value, &secrets.borrow()),
+        _ => value.clone(),
+    })
+}
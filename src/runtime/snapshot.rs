@@ -0,0 +1,158 @@
+//! 快照测试
+//!
+//! 脚本用 `EXPECT_SNAPSHOT(name, value)`（testing 标准库，底层为 `SNAPSHOT_MATCH`）
+//! 把值与磁盘上的快照比较。快照以规范化 JSON 保存（键排序、缩进两格、分数使用
+//! `{"$fraction": "1/3"}` 标记），每个名称一个 `<name>.snap.json` 文件。
+//!
+//! 快照目录由宿主通过 [`crate::Aether::set_snapshot_dir`] 显式指定，未指定时调用会报错，
+//! 脚本不能借此绕过文件系统权限。快照不存在时自动创建；更新模式下不一致的快照被覆盖。
+
+use crate::builtins::json::{FractionJsonMode, json_to_value, value_to_json};
+use crate::evaluator::RuntimeError;
+use crate::value::Value;
+use std::path::{Path, PathBuf};
+
+/// 快照比较结果统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SnapshotStats {
+    /// 与快照一致
+    pub matched: usize,
+    /// 新建的快照
+    pub created: usize,
+    /// 更新模式下被覆盖的快照
+    pub updated: usize,
+    /// 与快照不一致
+    pub failed: usize,
+}
+
+/// 一次快照比较的结果
+#[derive(Debug, Clone, PartialEq)]
+pub enum SnapshotOutcome {
+    /// 与快照一致
+    Matched,
+    /// 快照不存在，已创建
+    Created,
+    /// 与快照不一致，已按更新模式覆盖
+    Updated,
+    /// 与快照不一致，附带快照中的值
+    Mismatch { expected: Value },
+}
+
+impl SnapshotOutcome {
+    /// 结果名称：`matched` / `created` / `updated` / `mismatch`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SnapshotOutcome::Matched => "matched",
+            SnapshotOutcome::Created => "created",
+            SnapshotOutcome::Updated => "updated",
+            SnapshotOutcome::Mismatch { .. } => "mismatch",
+        }
+    }
+}
+
+/// 快照目录、更新模式和统计
+#[derive(Debug, Clone)]
+pub(crate) struct SnapshotState {
+    pub(crate) dir: PathBuf,
+    pub(crate) update: bool,
+    pub(crate) stats: SnapshotStats,
+}
+
+impl SnapshotState {
+    pub(crate) fn new(dir: PathBuf) -> Self {
+        SnapshotState {
+            dir,
+            update: false,
+            stats: SnapshotStats::default(),
+        }
+    }
+
+    /// 比较并按需写入快照，返回结果和快照文件路径
+    pub(crate) fn check(
+        &mut self,
+        name: &str,
+        value: &Value,
+    ) -> Result<(SnapshotOutcome, PathBuf), RuntimeError> {
+        let path = snapshot_path(&self.dir, name)?;
+        let actual = serialize(value)?;
+        let io_error = |e: std::io::Error| {
+            RuntimeError::CustomError(format!("Snapshot '{}': {}: {}", name, path.display(), e))
+        };
+
+        let outcome = match std::fs::read_to_string(&path) {
+            Ok(stored) if stored == actual => SnapshotOutcome::Matched,
+            Ok(_) if self.update => {
+                std::fs::write(&path, &actual).map_err(io_error)?;
+                SnapshotOutcome::Updated
+            }
+            Ok(stored) => {
+                let json: serde_json::Value = serde_json::from_str(&stored).map_err(|e| {
+                    RuntimeError::CustomError(format!(
+                        "Snapshot '{}' is not valid JSON ({}), rerun with snapshot updates enabled",
+                        name, e
+                    ))
+                })?;
+                SnapshotOutcome::Mismatch {
+                    expected: json_to_value(&json)?,
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                std::fs::create_dir_all(&self.dir).map_err(io_error)?;
+                std::fs::write(&path, &actual).map_err(io_error)?;
+                SnapshotOutcome::Created
+            }
+            Err(e) => return Err(io_error(e)),
+        };
+
+        match outcome {
+            SnapshotOutcome::Matched => self.stats.matched += 1,
+            SnapshotOutcome::Created => self.stats.created += 1,
+            SnapshotOutcome::Updated => self.stats.updated += 1,
+            SnapshotOutcome::Mismatch { .. } => self.stats.failed += 1,
+        }
+        Ok((outcome, path))
+    }
+}
+
+/// 值的规范化快照文本（键排序的缩进 JSON，以换行结尾）
+pub fn serialize(value: &Value) -> Result<String, RuntimeError> {
+    let json = integral_numbers(value_to_json(value, FractionJsonMode::Tagged)?);
+    let mut text = serde_json::to_string_pretty(&json)
+        .map_err(|e| RuntimeError::CustomError(format!("Snapshot serialization failed: {}", e)))?;
+    text.push('\n');
+    Ok(text)
+}
+
+/// 把整数值的浮点数写成整数（`3` 而不是 `3.0`），让快照更易读
+fn integral_numbers(json: serde_json::Value) -> serde_json::Value {
+    use serde_json::Value as Json;
+    match json {
+        Json::Number(n) => match n.as_f64() {
+            Some(f) if f.fract() == 0.0 && f.abs() < 9.0e15 => Json::from(f as i64),
+            _ => Json::Number(n),
+        },
+        Json::Array(items) => Json::Array(items.into_iter().map(integral_numbers).collect()),
+        Json::Object(map) => Json::Object(
+            map.into_iter()
+                .map(|(k, v)| (k, integral_numbers(v)))
+                .collect(),
+        ),
+        other => other,
+    }
+}
+
+/// 快照名对应的文件，名称只能包含字母、数字、`_`、`-` 和 `.`
+fn snapshot_path(dir: &Path, name: &str) -> Result<PathBuf, RuntimeError> {
+    let valid = !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'));
+    if !valid {
+        return Err(RuntimeError::InvalidOperation(format!(
+            "Invalid snapshot name '{}': use letters, digits, '_', '-' or '.'",
+            name
+        )));
+    }
+    Ok(dir.join(format!("{}.snap.json", name)))
+}
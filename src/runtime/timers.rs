@@ -0,0 +1,195 @@
+//! 宿主驱动的定时器
+//!
+//! 嵌入 GUI 事件循环或 wasm 时，脚本不能阻塞等待（`RUN_SCHEDULER`），宿主也不便为引擎启动线程。
+//! 定时器只记录到期时间，由宿主在自己的事件循环中调用 [`crate::Aether::pump`] 执行到期的回调，
+//! 并用 [`crate::Aether::next_timer_delay`] 得知距下次需要唤醒还有多久：
+//! - 周期定时器：`Aether::on_timer` / `SET_INTERVAL(ms, fn)`
+//! - 一次性定时器：`Aether::on_timeout` / `SET_TIMEOUT(ms, fn)`
+//! - 防抖：`Aether::debounce` / `DEBOUNCE(ms, fn)`，同一函数在等待期间再次触发时重新计时，
+//!   安静 `ms` 毫秒后只执行一次
+//!
+//! 定时器时钟是单调时钟加上宿主通过 [`crate::Aether::advance_timers`] 推进的时间。
+//! wasm 中没有单调时钟，时间只由宿主推进（如在 `requestAnimationFrame` 中传入帧间隔）。
+
+use crate::value::Value;
+use std::time::Duration;
+
+/// `Aether::pump` / `Aether::advance_timers` 的结果
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PumpReport {
+    /// 成功执行的回调数
+    pub runs: usize,
+    /// 失败的回调：`Timer #编号 (函数名) failed: 错误`，失败的周期定时器仍会继续触发
+    pub errors: Vec<String>,
+}
+
+impl PumpReport {
+    /// 是否所有回调都执行成功
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// 一个已注册的定时器
+#[derive(Debug, Clone)]
+struct Timer {
+    id: usize,
+    /// 回调；`None` 表示到期时按 `label` 查找全局函数（宿主注册的定时器）
+    callback: Option<Value>,
+    /// 函数名（错误信息和防抖使用）
+    label: String,
+    /// 周期定时器的间隔
+    interval: Option<Duration>,
+    /// 防抖定时器
+    debounced: bool,
+    deadline: Duration,
+}
+
+/// 到期的回调
+#[derive(Debug, Clone)]
+pub(crate) struct DueTimer {
+    pub(crate) id: usize,
+    pub(crate) callback: Option<Value>,
+    pub(crate) label: String,
+}
+
+/// 引擎上的定时器队列
+#[derive(Debug, Clone)]
+pub(crate) struct TimerQueue {
+    timers: Vec<Timer>,
+    next_id: usize,
+    /// 宿主推进的时间
+    offset: Duration,
+    #[cfg(not(target_arch = "wasm32"))]
+    origin: std::time::Instant,
+}
+
+impl Default for TimerQueue {
+    fn default() -> Self {
+        TimerQueue {
+            timers: Vec::new(),
+            next_id: 1,
+            offset: Duration::ZERO,
+            #[cfg(not(target_arch = "wasm32"))]
+            origin: std::time::Instant::now(),
+        }
+    }
+}
+
+impl TimerQueue {
+    /// 定时器时钟的当前时间
+    fn now(&self) -> Duration {
+        #[cfg(not(target_arch = "wasm32"))]
+        return self.offset.saturating_add(self.origin.elapsed());
+        #[cfg(target_arch = "wasm32")]
+        return self.offset;
+    }
+
+    /// 注册定时器，`interval` 为 `Some` 时周期触发，返回编号
+    pub(crate) fn add(
+        &mut self,
+        callback: Option<Value>,
+        label: String,
+        delay: Duration,
+        interval: Option<Duration>,
+    ) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.timers.push(Timer {
+            id,
+            callback,
+            label,
+            interval,
+            debounced: false,
+            deadline: self.now().saturating_add(delay),
+        });
+        id
+    }
+
+    /// 防抖：同名的防抖定时器尚未触发时推迟它（沿用编号），否则注册新的一次性定时器
+    pub(crate) fn debounce(
+        &mut self,
+        callback: Option<Value>,
+        label: String,
+        delay: Duration,
+    ) -> usize {
+        let deadline = self.now().saturating_add(delay);
+        if let Some(timer) = self
+            .timers
+            .iter_mut()
+            .find(|timer| timer.debounced && timer.label == label)
+        {
+            timer.deadline = deadline;
+            timer.callback = callback;
+            return timer.id;
+        }
+        let id = self.add(callback, label, delay, None);
+        if let Some(timer) = self.timers.last_mut() {
+            timer.debounced = true;
+        }
+        id
+    }
+
+    /// 取消定时器，返回定时器是否存在
+    pub(crate) fn cancel(&mut self, id: usize) -> bool {
+        let before = self.timers.len();
+        self.timers.retain(|timer| timer.id != id);
+        self.timers.len() != before
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.timers.clear();
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.timers.is_empty()
+    }
+
+    /// 推进宿主时钟
+    pub(crate) fn advance(&mut self, elapsed: Duration) {
+        self.offset = self.offset.saturating_add(elapsed);
+    }
+
+    /// 距最早的定时器到期还有多久（已到期为零），没有定时器时为 `None`
+    pub(crate) fn next_delay(&self) -> Option<Duration> {
+        let now = self.now();
+        self.timers
+            .iter()
+            .map(|timer| timer.deadline.saturating_sub(now))
+            .min()
+    }
+
+    /// 取出到期的定时器（按到期时间排序）：一次性定时器被移除，周期定时器计算下次到期时间。
+    /// 错过的周期不补跑；回调中新注册的定时器最早在下一次 pump 时触发。
+    pub(crate) fn take_due(&mut self) -> Vec<DueTimer> {
+        let now = self.now();
+        let mut due: Vec<&mut Timer> = self
+            .timers
+            .iter_mut()
+            .filter(|timer| timer.deadline <= now)
+            .collect();
+        due.sort_by_key(|timer| (timer.deadline, timer.id));
+
+        let fired: Vec<DueTimer> = due
+            .into_iter()
+            .map(|timer| {
+                if let Some(interval) = timer.interval {
+                    let next = timer.deadline.saturating_add(interval);
+                    timer.deadline = if next > now {
+                        next
+                    } else {
+                        now.saturating_add(interval)
+                    };
+                }
+                DueTimer {
+                    id: timer.id,
+                    callback: timer.callback.clone(),
+                    label: timer.label.clone(),
+                }
+            })
+            .collect();
+        self.timers
+            .retain(|timer| timer.interval.is_some() || timer.deadline > now);
+        fired
+    }
+}
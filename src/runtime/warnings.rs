@@ -0,0 +1,60 @@
+//! 警告通道
+//!
+//! 解析器、优化器和求值器把不影响执行结果的问题（重复的字典键、被移除的死代码、
+//! 被覆盖的导入、弃用的内置函数、大数运算精度损失）记录为警告，而不是让求值失败。
+//! 宿主程序通过 `Aether::take_warnings()` 取出并自行展示。
+
+use std::fmt;
+
+/// 警告类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WarningKind {
+    /// 解析阶段发现的问题（如字典字面量中的重复键）
+    Parse,
+    /// 优化器移除了永远不会执行的代码
+    DeadCode,
+    /// 导入的名称被忽略或覆盖了已有变量
+    Import,
+    /// 调用了已弃用的内置函数
+    Deprecation,
+    /// 内置函数名通过别名或大小写回退解析
+    BuiltinName,
+    /// 数值运算损失了精度
+    Precision,
+}
+
+impl WarningKind {
+    /// 类别名称（用于显示和机器可读输出）
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WarningKind::Parse => "parse",
+            WarningKind::DeadCode => "dead-code",
+            WarningKind::Import => "import",
+            WarningKind::Deprecation => "deprecation",
+            WarningKind::BuiltinName => "builtin-name",
+            WarningKind::Precision => "precision",
+        }
+    }
+}
+
+/// 一条警告
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Warning {
+    pub kind: WarningKind,
+    pub message: String,
+}
+
+impl Warning {
+    pub fn new(kind: WarningKind, message: impl Into<String>) -> Self {
+        Warning {
+            kind,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "warning[{}]: {}", self.kind.as_str(), self.message)
+    }
+}
@@ -3,7 +3,7 @@
 //! 提供统一的沙箱配置入口，简化权限和安全管理。
 
 use crate::builtins::IOPermissions;
-use crate::runtime::ExecutionLimits;
+use crate::runtime::{ConcurrencyLimits, ExecutionLimits};
 use std::collections::HashSet;
 use std::path::PathBuf;
 
@@ -49,6 +49,9 @@ pub struct SandboxConfig {
 
     /// 执行限制配置
     pub execution_limits: ExecutionLimits,
+
+    /// 并发任务限制（SPAWN / PARALLEL）
+    pub concurrency_limits: ConcurrencyLimits,
 }
 
 impl Default for SandboxConfig {
@@ -63,6 +66,7 @@ impl Default for SandboxConfig {
             max_module_cache_size: 100,
             module_cache_ttl_secs: 0,
             execution_limits: ExecutionLimits::default(),
+            concurrency_limits: ConcurrencyLimits::default(),
         }
     }
 }
@@ -72,6 +76,7 @@ impl SandboxConfig {
     pub fn dsl_safe() -> Self {
         Self {
             execution_limits: ExecutionLimits::strict(),
+            concurrency_limits: ConcurrencyLimits::strict(),
             ..Default::default()
         }
     }
@@ -139,6 +144,7 @@ mod tests {
         assert_eq!(config.module_policy, SandboxPolicy::Disabled);
         assert!(!config.io_permissions.filesystem_enabled);
         assert!(!config.io_permissions.network_enabled);
+        assert_eq!(config.concurrency_limits, ConcurrencyLimits::strict());
     }
 
     #[test]
@@ -1,6 +1,6 @@
 //! 沙箱上下文（线程局部存储）
 //!
-//! 使用线程局部存储来传递 PathValidator 给内置函数，避免修改函数签名。
+//! 使用线程局部存储来传递 PathValidator、网络主机和 SMTP 服务器白名单给内置函数，避免修改函数签名。
 
 use super::PathValidator;
 use std::cell::RefCell;
@@ -8,6 +8,70 @@ use std::cell::RefCell;
 // 线程局部的沙箱上下文
 thread_local! {
     static FILESYSTEM_VALIDATOR: RefCell<Option<PathValidator>> = const { RefCell::new(None) };
+    static SMTP_ALLOWLIST: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+    static NETWORK_ALLOWLIST: RefCell<Option<Vec<String>>> = const { RefCell::new(None) };
+    #[cfg(feature = "s3")]
+    static S3_CREDENTIALS: RefCell<Option<S3Credentials>> = const { RefCell::new(None) };
+}
+
+/// S3（或兼容的对象存储）访问凭据，只能由宿主程序注入
+#[cfg(feature = "s3")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct S3Credentials {
+    /// 服务地址，如 `http://127.0.0.1:9000`；为 None 时使用 `https://s3.{region}.amazonaws.com`
+    pub endpoint: Option<String>,
+    /// 区域（签名使用），如 `us-east-1`
+    pub region: String,
+    /// Access Key ID
+    pub access_key_id: String,
+    /// Secret Access Key
+    pub secret_access_key: String,
+    /// 临时凭据的会话令牌
+    pub session_token: Option<String>,
+}
+
+#[cfg(feature = "s3")]
+impl S3Credentials {
+    /// 使用 AWS 默认端点创建凭据
+    pub fn new(
+        region: impl Into<String>,
+        access_key_id: impl Into<String>,
+        secret_access_key: impl Into<String>,
+    ) -> Self {
+        Self {
+            endpoint: None,
+            region: region.into(),
+            access_key_id: access_key_id.into(),
+            secret_access_key: secret_access_key.into(),
+            session_token: None,
+        }
+    }
+
+    /// 使用自定义端点（MinIO 等 S3 兼容服务）
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = Some(endpoint.into());
+        self
+    }
+
+    /// 设置会话令牌
+    pub fn with_session_token(mut self, token: impl Into<String>) -> Self {
+        self.session_token = Some(token.into());
+        self
+    }
+}
+
+/// 设置 S3_GET / S3_PUT / S3_LIST 使用的凭据（线程局部）
+///
+/// 脚本无法读取或修改凭据；未设置时这些函数会报错。
+#[cfg(feature = "s3")]
+pub fn set_s3_credentials(credentials: Option<S3Credentials>) {
+    S3_CREDENTIALS.with(|c| *c.borrow_mut() = credentials);
+}
+
+/// 获取 S3 凭据（线程局部）
+#[cfg(feature = "s3")]
+pub fn get_s3_credentials() -> Option<S3Credentials> {
+    S3_CREDENTIALS.with(|c| c.borrow().clone())
 }
 
 /// 设置文件系统路径验证器（线程局部）
@@ -20,6 +84,110 @@ pub fn get_filesystem_validator() -> Option<PathValidator> {
     FILESYSTEM_VALIDATOR.with(|v| v.borrow().clone())
 }
 
+/// 限制 HTTP / WebSocket / SSE 内置函数可以访问的主机（线程局部）
+///
+/// `None`（默认）表示不限制主机，只受网络权限控制；每项为主机名，
+/// `*.example.com` 匹配所有子域名。
+pub fn set_network_allowlist(hosts: Option<Vec<String>>) {
+    NETWORK_ALLOWLIST.with(|list| *list.borrow_mut() = hosts);
+}
+
+/// 获取网络主机白名单（线程局部）
+pub fn get_network_allowlist() -> Option<Vec<String>> {
+    NETWORK_ALLOWLIST.with(|list| list.borrow().clone())
+}
+
+/// 检查主机是否在网络白名单中（未设置白名单时总是允许）
+pub fn is_network_host_allowed(host: &str) -> bool {
+    NETWORK_ALLOWLIST.with(|list| match list.borrow().as_ref() {
+        None => true,
+        Some(hosts) => hosts.iter().any(|entry| match entry.strip_prefix("*.") {
+            Some(domain) => host
+                .to_ascii_lowercase()
+                .ends_with(&format!(".{}", domain.to_ascii_lowercase())),
+            None => entry.eq_ignore_ascii_case(host),
+        }),
+    })
+}
+
+/// 设置允许 SEND_EMAIL 连接的 SMTP 服务器（线程局部）
+///
+/// 每项为 `host`（任意端口）或 `host:port`；默认为空，即不允许发送邮件。
+pub fn set_smtp_allowlist(servers: Vec<String>) {
+    SMTP_ALLOWLIST.with(|list| *list.borrow_mut() = servers);
+}
+
+/// 获取 SMTP 服务器白名单（线程局部）
+pub fn get_smtp_allowlist() -> Vec<String> {
+    SMTP_ALLOWLIST.with(|list| list.borrow().clone())
+}
+
+/// 检查 SMTP 服务器是否在白名单中（主机名不区分大小写）
+pub fn is_smtp_server_allowed(host: &str, port: u16) -> bool {
+    SMTP_ALLOWLIST.with(|list| {
+        list.borrow()
+            .iter()
+            .any(|entry| match entry.rsplit_once(':') {
+                Some((allowed_host, allowed_port)) => {
+                    allowed_host.eq_ignore_ascii_case(host) && allowed_port == port.to_string()
+                }
+                None => entry.eq_ignore_ascii_case(host),
+            })
+    })
+}
+
+/// 当前线程的完整沙箱上下文：路径验证器、网络和 SMTP 白名单以及 S3 凭据
+///
+/// 新线程不会继承线程局部的上下文，在其他线程上执行脚本（如 SPAWN / PARALLEL 的
+/// 工作线程）时，需要捕获调用方线程的上下文并在新线程中安装。
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SandboxContext {
+    filesystem_validator: Option<PathValidator>,
+    network_allowlist: Option<Vec<String>>,
+    smtp_allowlist: Vec<String>,
+    #[cfg(feature = "s3")]
+    s3_credentials: Option<S3Credentials>,
+}
+
+impl SandboxContext {
+    /// 捕获当前线程的沙箱上下文
+    pub fn capture() -> Self {
+        Self {
+            filesystem_validator: get_filesystem_validator(),
+            network_allowlist: get_network_allowlist(),
+            smtp_allowlist: get_smtp_allowlist(),
+            #[cfg(feature = "s3")]
+            s3_credentials: get_s3_credentials(),
+        }
+    }
+
+    /// 在当前线程安装上下文，作用域结束时恢复原来的上下文
+    pub fn install(&self) -> ScopedSandbox {
+        let previous = Self::capture();
+        self.clone().apply();
+        ScopedSandbox { previous }
+    }
+
+    fn apply(self) {
+        set_filesystem_validator(self.filesystem_validator);
+        set_network_allowlist(self.network_allowlist);
+        set_smtp_allowlist(self.smtp_allowlist);
+        #[cfg(feature = "s3")]
+        set_s3_credentials(self.s3_credentials);
+    }
+}
+
+/// 由 [`SandboxContext::install`] 返回，丢弃时恢复线程原来的沙箱上下文
+pub struct ScopedSandbox {
+    previous: SandboxContext,
+}
+
+impl Drop for ScopedSandbox {
+    fn drop(&mut self) {
+        std::mem::take(&mut self.previous).apply();
+    }
+}
+
 /// 在作用域内设置验证器（RAII 模式）
 pub struct ScopedValidator {
     _private: (),
@@ -3,6 +3,7 @@
 //! 收集运行时指标，支持监控和调试。
 
 use crate::cache::CacheStats;
+use std::collections::VecDeque;
 use std::sync::RwLock;
 use std::time::{Duration, Instant};
 
@@ -19,6 +20,18 @@ pub struct ExecutionMetrics {
     pub min_duration: Duration,
     /// 最大执行时间
     pub max_duration: Duration,
+    /// 中位数执行时间（最近的执行样本）
+    pub p50_duration: Duration,
+    /// 95 分位执行时间（最近的执行样本）
+    pub p95_duration: Duration,
+    /// 执行失败次数
+    pub error_count: usize,
+    /// 错误率（失败次数 / 执行次数）
+    pub error_rate: f64,
+    /// 累计执行步数
+    pub total_steps: usize,
+    /// 命中 AST 缓存的执行次数
+    pub cache_hits: usize,
 }
 
 impl Default for ExecutionMetrics {
@@ -29,10 +42,25 @@ impl Default for ExecutionMetrics {
             average_duration: Duration::ZERO,
             min_duration: Duration::MAX,
             max_duration: Duration::ZERO,
+            p50_duration: Duration::ZERO,
+            p95_duration: Duration::ZERO,
+            error_count: 0,
+            error_rate: 0.0,
+            total_steps: 0,
+            cache_hits: 0,
         }
     }
 }
 
+/// 计算分位数（样本已排序，`q` 取 0.0 ~ 1.0）
+fn percentile(sorted: &[Duration], q: f64) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let rank = (q * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
 /// 模块加载指标
 #[derive(Debug, Clone)]
 pub struct ModuleMetrics {
@@ -72,6 +100,19 @@ pub struct MetricsSnapshot {
     pub ast_cache: CacheStats,
 }
 
+/// 单次执行的资源报告
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EvalReport {
+    /// 执行时间
+    pub duration: Duration,
+    /// 执行步数
+    pub steps: usize,
+    /// 是否命中 AST 缓存
+    pub cache_hit: bool,
+    /// 是否执行成功
+    pub success: bool,
+}
+
 /// 指标收集器
 pub struct MetricsCollector {
     /// 是否启用
@@ -84,6 +125,8 @@ pub struct MetricsCollector {
     modules: RwLock<ModuleMetrics>,
     /// 各模块的加载次数
     module_loads: RwLock<std::collections::HashMap<String, usize>>,
+    /// 最近的执行时间样本（用于计算分位数）
+    samples: RwLock<VecDeque<Duration>>,
 }
 
 impl MetricsCollector {
@@ -95,9 +138,13 @@ impl MetricsCollector {
             execution: RwLock::new(ExecutionMetrics::default()),
             modules: RwLock::new(ModuleMetrics::default()),
             module_loads: RwLock::new(std::collections::HashMap::new()),
+            samples: RwLock::new(VecDeque::new()),
         }
     }
 
+    /// 保留的执行时间样本数量（超出后丢弃最早的样本）
+    pub const MAX_SAMPLES: usize = 1024;
+
     /// 启用指标收集
     pub fn enable(&self) {
         *self.enabled.write().unwrap() = true;
@@ -129,17 +176,48 @@ impl MetricsCollector {
 
         let start = self.execution_start.write().unwrap().take();
         if let Some(start_time) = start {
-            let duration = start_time.elapsed();
-
-            let mut exec = self.execution.write().unwrap();
-            exec.execution_count += 1;
-            exec.total_duration += duration;
-            exec.average_duration = exec.total_duration / exec.execution_count as u32;
-            exec.min_duration = exec.min_duration.min(duration);
-            exec.max_duration = exec.max_duration.max(duration);
+            self.record_duration(start_time.elapsed());
         }
     }
 
+    /// 记录一次完整执行（时间、步数、缓存命中、是否成功）
+    pub fn record_eval(&self, report: EvalReport) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        self.record_duration(report.duration);
+
+        let mut exec = self.execution.write().unwrap();
+        exec.total_steps += report.steps;
+        if report.cache_hit {
+            exec.cache_hits += 1;
+        }
+        if !report.success {
+            exec.error_count += 1;
+        }
+        exec.error_rate = exec.error_count as f64 / exec.execution_count as f64;
+    }
+
+    /// 累计执行时间并保存样本
+    fn record_duration(&self, duration: Duration) {
+        {
+            let mut samples = self.samples.write().unwrap();
+            if samples.len() >= Self::MAX_SAMPLES {
+                samples.pop_front();
+            }
+            samples.push_back(duration);
+        }
+
+        let mut exec = self.execution.write().unwrap();
+        exec.execution_count += 1;
+        exec.total_duration += duration;
+        exec.average_duration = exec.total_duration / exec.execution_count as u32;
+        exec.min_duration = exec.min_duration.min(duration);
+        exec.max_duration = exec.max_duration.max(duration);
+        exec.error_rate = exec.error_count as f64 / exec.execution_count as f64;
+    }
+
     /// 记录模块加载
     pub fn record_module_load(&self, module_id: &str, cached: bool) {
         if !self.is_enabled() {
@@ -168,8 +246,14 @@ impl MetricsCollector {
         module_cache_size: usize,
         ast_cache: &CacheStats,
     ) -> MetricsSnapshot {
+        let mut execution = self.execution.read().unwrap().clone();
+        let mut samples: Vec<Duration> = self.samples.read().unwrap().iter().copied().collect();
+        samples.sort();
+        execution.p50_duration = percentile(&samples, 0.5);
+        execution.p95_duration = percentile(&samples, 0.95);
+
         MetricsSnapshot {
-            execution: self.execution.read().unwrap().clone(),
+            execution,
             modules: self.modules.read().unwrap().clone(),
             trace_entries,
             module_cache_size,
@@ -182,6 +266,7 @@ impl MetricsCollector {
         *self.execution.write().unwrap() = ExecutionMetrics::default();
         *self.modules.write().unwrap() = ModuleMetrics::default();
         self.module_loads.write().unwrap().clear();
+        self.samples.write().unwrap().clear();
     }
 
     /// 获取模块加载次数（用于调试）
@@ -241,6 +326,7 @@ mod tests {
                 hits: 0,
                 misses: 0,
                 hit_rate: 0.0,
+                ..Default::default()
             },
         );
 
@@ -252,6 +338,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_eval_reports_percentiles_and_errors() {
+        let collector = MetricsCollector::new();
+        collector.enable();
+
+        for ms in 1..=20 {
+            collector.record_eval(EvalReport {
+                duration: Duration::from_millis(ms),
+                steps: 10,
+                cache_hit: ms > 1,
+                success: ms % 5 != 0,
+            });
+        }
+
+        let snapshot = collector.snapshot(
+            0,
+            0,
+            &CacheStats {
+                size: 0,
+                max_size: 0,
+                hits: 0,
+                misses: 0,
+                hit_rate: 0.0,
+                ..Default::default()
+            },
+        );
+        let exec = snapshot.execution;
+        assert_eq!(exec.execution_count, 20);
+        assert_eq!(exec.p50_duration, Duration::from_millis(10));
+        assert_eq!(exec.p95_duration, Duration::from_millis(19));
+        assert_eq!(exec.error_count, 4);
+        assert!((exec.error_rate - 0.2).abs() < 1e-9);
+        assert_eq!(exec.total_steps, 200);
+        assert_eq!(exec.cache_hits, 19);
+    }
+
     #[test]
     fn test_module_metrics() {
         let collector = MetricsCollector::new();
@@ -271,6 +393,7 @@ mod tests {
                 hits: 0,
                 misses: 0,
                 hit_rate: 0.0,
+                ..Default::default()
             },
         );
 
@@ -298,6 +421,7 @@ mod tests {
                 hits: 0,
                 misses: 0,
                 hit_rate: 0.0,
+                ..Default::default()
             },
         );
 
@@ -326,6 +450,7 @@ mod tests {
                 hits: 0,
                 misses: 0,
                 hit_rate: 0.0,
+                ..Default::default()
             },
         );
 
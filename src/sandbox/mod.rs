@@ -13,7 +13,13 @@ pub mod module_cache;
 pub mod path_validator;
 
 pub use config::{SandboxConfig, SandboxPolicy};
-pub use context::{ScopedValidator, get_filesystem_validator, set_filesystem_validator};
-pub use metrics::{ExecutionMetrics, MetricsCollector, MetricsSnapshot, ModuleMetrics};
+#[cfg(feature = "s3")]
+pub use context::{S3Credentials, get_s3_credentials, set_s3_credentials};
+pub use context::{
+    SandboxContext, ScopedSandbox, ScopedValidator, get_filesystem_validator,
+    get_network_allowlist, get_smtp_allowlist, is_network_host_allowed, is_smtp_server_allowed,
+    set_filesystem_validator, set_network_allowlist, set_smtp_allowlist,
+};
+pub use metrics::{EvalReport, ExecutionMetrics, MetricsCollector, MetricsSnapshot, ModuleMetrics};
 pub use module_cache::{ModuleCacheManager, ModuleCacheStats};
 pub use path_validator::{PathRestriction, PathValidationError, PathValidator};
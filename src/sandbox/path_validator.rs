@@ -59,7 +59,7 @@ impl std::fmt::Display for PathValidationError {
 impl std::error::Error for PathValidationError {}
 
 /// 路径限制规则
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct PathRestriction {
     /// 根目录（路径必须在此之下）
     pub root_dir: PathBuf,
@@ -83,7 +83,7 @@ impl Default for PathRestriction {
 }
 
 /// 路径验证器
-#[derive(Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct PathValidator {
     restriction: PathRestriction,
 }
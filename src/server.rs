@@ -0,0 +1,360 @@
+// src/server.rs
+//! HTTP/JSON 服务模式（`aether serve`）
+//!
+//! 让非 Rust 服务无需链接本 crate 即可调用 Aether。请求由引擎池中的引擎处理，
+//! 引擎按 `SandboxConfig` 设置 IO 权限、执行限制和模块导入策略。
+//!
+//! | 方法 | 路径 | 说明 |
+//! |------|------|------|
+//! | GET  | `/health`  | 存活检查 |
+//! | GET  | `/metrics` | 执行次数、延迟分位数、错误率、缓存统计 |
+//! | POST | `/eval`    | `{"code": "...", "inputs": {...}}` 求值，`inputs` 绑定为全局变量 |
+//! | POST | `/compile` | `{"code": "..."}` 只解析，返回语句数和脚本需要的权限/内置函数 |
+//!
+//! 响应都是 JSON：成功为 `{"ok": true, ...}`，失败为 `{"ok": false, "error": {...}}`。
+//! 请求格式错误返回 400，脚本错误返回 422。每个连接一个请求。
+//!
+//! `run` 启动 `pool_size` 个工作线程并发处理连接。引擎不能跨线程传递，
+//! 每个工作线程用同一个工厂创建自己的引擎；请求计数和执行指标由所有线程共享。
+//!
+//! 需要 `http-server` 特性。
+
+use crate::analysis::{RequirementsReport, builtin_names};
+use crate::builtins::http_server;
+use crate::builtins::json::{FractionJsonMode, json_to_value, value_to_json};
+use crate::engine::{EngineFactory, EnginePool, RecyclePolicy};
+use crate::sandbox::{MetricsCollector, SandboxConfig};
+use crate::value::Value;
+use crate::{Aether, Parser};
+use serde_json::{Value as JsonValue, json};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, mpsc};
+use std::time::Instant;
+
+/// 服务配置
+#[derive(Debug, Clone)]
+pub struct ServerConfig {
+    /// 监听地址（默认 `127.0.0.1:7000`）
+    pub listen: String,
+    /// 工作线程数（每个线程持有一个引擎）
+    pub pool_size: usize,
+    /// 每个引擎处理多少个请求后替换为新实例（None 表示不替换）
+    pub recycle_after: Option<usize>,
+    /// 引擎的沙箱配置（默认 `SandboxConfig::dsl_safe()`）
+    pub sandbox: SandboxConfig,
+    /// 处理指定数量的请求后停止（None 表示一直运行）
+    pub max_requests: Option<usize>,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        ServerConfig {
+            listen: "127.0.0.1:7000".to_string(),
+            pool_size: 4,
+            recycle_after: Some(1000),
+            sandbox: SandboxConfig::dsl_safe(),
+            max_requests: None,
+        }
+    }
+}
+
+/// HTTP/JSON 服务
+///
+/// # 示例
+/// ```no_run
+/// use aether::server::{Server, ServerConfig};
+///
+/// let mut server = Server::bind(ServerConfig::default()).unwrap();
+/// println!("listening on {}", server.local_addr().unwrap());
+/// server.run();
+/// ```
+pub struct Server {
+    listener: TcpListener,
+    shared: Shared,
+    /// `handle` 在调用线程上使用的引擎
+    pool: EnginePool,
+}
+
+/// 所有工作线程共享的服务状态
+struct Shared {
+    config: ServerConfig,
+    factory: EngineFactory,
+    metrics: Arc<MetricsCollector>,
+    started: Instant,
+    requests: AtomicUsize,
+    busy: AtomicUsize,
+    recycled: AtomicUsize,
+}
+
+impl Server {
+    /// 按配置准备引擎工厂并绑定监听地址
+    pub fn bind(config: ServerConfig) -> Result<Self, String> {
+        let listener = TcpListener::bind(&config.listen)
+            .map_err(|e| format!("Failed to listen on {}: {}", config.listen, e))?;
+        let sandbox = config.sandbox.clone();
+        let metrics = Arc::new(MetricsCollector::new());
+        metrics.enable();
+        let shared = Shared {
+            config,
+            factory: Arc::new(move || Aether::with_sandbox(&sandbox)),
+            metrics,
+            started: Instant::now(),
+            requests: AtomicUsize::new(0),
+            busy: AtomicUsize::new(0),
+            recycled: AtomicUsize::new(0),
+        };
+        let pool = shared.worker_pool();
+
+        Ok(Server {
+            listener,
+            shared,
+            pool,
+        })
+    }
+
+    /// 实际监听的地址（监听端口 0 时用于获取分配的端口）
+    pub fn local_addr(&self) -> Result<SocketAddr, String> {
+        self.listener.local_addr().map_err(|e| e.to_string())
+    }
+
+    /// 已处理的请求数
+    pub fn requests(&self) -> usize {
+        self.shared.requests.load(Ordering::SeqCst)
+    }
+
+    /// 并发处理请求，直到达到 `max_requests`（未设置时一直运行）
+    ///
+    /// 当前线程负责接受连接，连接交给空闲的工作线程处理；
+    /// 达到 `max_requests` 后等待已接受的请求处理完再返回。
+    pub fn run(&mut self) {
+        let Ok(listener) = self.listener.try_clone() else {
+            return;
+        };
+        let shared = &self.shared;
+        let (sender, receiver) = mpsc::channel::<TcpStream>();
+        let receiver = Mutex::new(receiver);
+
+        std::thread::scope(|scope| {
+            for _ in 0..shared.workers() {
+                scope.spawn(|| {
+                    let mut pool = shared.worker_pool();
+                    loop {
+                        let stream = match receiver.lock() {
+                            Ok(receiver) => receiver.recv(),
+                            Err(_) => break,
+                        };
+                        let Ok(mut stream) = stream else {
+                            break;
+                        };
+                        shared.serve(&mut pool, &mut stream);
+                    }
+                });
+            }
+
+            for stream in listener.incoming() {
+                let Ok(stream) = stream else {
+                    continue;
+                };
+                let requests = shared.requests.fetch_add(1, Ordering::SeqCst) + 1;
+                if sender.send(stream).is_err() {
+                    break;
+                }
+                if shared
+                    .config
+                    .max_requests
+                    .is_some_and(|max| requests >= max)
+                {
+                    break;
+                }
+            }
+            // 关闭队列，工作线程处理完剩余连接后退出
+            drop(sender);
+        });
+    }
+
+    /// 处理一个请求，返回（状态码，JSON 响应体）
+    pub fn handle(&mut self, method: &str, path: &str, body: &str) -> (u16, JsonValue) {
+        self.shared.handle(&mut self.pool, method, path, body)
+    }
+}
+
+impl Shared {
+    fn workers(&self) -> usize {
+        self.config.pool_size.max(1)
+    }
+
+    /// 为一个工作线程创建引擎池（单个引擎，指标与其他线程共享）
+    fn worker_pool(&self) -> EnginePool {
+        let mut policy = RecyclePolicy::new();
+        if let Some(uses) = self.config.recycle_after {
+            policy = policy.with_max_uses(uses);
+        }
+        EnginePool::with_factory(1, policy, Arc::clone(&self.factory))
+            .with_metrics(Arc::clone(&self.metrics))
+    }
+
+    /// 读取一个连接上的请求并写回响应
+    fn serve(&self, pool: &mut EnginePool, stream: &mut TcpStream) {
+        let (status, body) = match http_server::read_request(stream) {
+            Ok(Value::Dict(request)) => {
+                let field = |name: &str| match request.get(name) {
+                    Some(Value::String(s)) => s.as_str(),
+                    _ => "",
+                };
+                self.handle(pool, field("method"), field("path"), field("body"))
+            }
+            Ok(_) => bad_request("invalid request"),
+            Err(message) => bad_request(&message),
+        };
+        let headers = [("Content-Type".to_string(), "application/json".to_string())];
+        http_server::write_response(stream, status, &headers, &body.to_string());
+    }
+
+    fn handle(
+        &self,
+        pool: &mut EnginePool,
+        method: &str,
+        path: &str,
+        body: &str,
+    ) -> (u16, JsonValue) {
+        match (method, path) {
+            ("GET", "/health") => (
+                200,
+                json!({
+                    "ok": true,
+                    "status": "ok",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "uptime_ms": self.started.elapsed().as_millis() as u64,
+                }),
+            ),
+            ("GET", "/metrics") => (200, self.metrics(pool)),
+            ("POST", "/eval") => self.eval(pool, body),
+            ("POST", "/compile") => compile(body),
+            (_, "/health" | "/metrics" | "/eval" | "/compile") => (
+                405,
+                error_body("MethodNotAllowed", &format!("{} {}", method, path)),
+            ),
+            _ => (
+                404,
+                error_body("NotFound", &format!("no route for {}", path)),
+            ),
+        }
+    }
+
+    fn eval(&self, pool: &mut EnginePool, body: &str) -> (u16, JsonValue) {
+        let request = match parse_body(body) {
+            Ok(request) => request,
+            Err(response) => return response,
+        };
+        let mut inputs = Vec::new();
+        match request.get("inputs") {
+            None | Some(JsonValue::Null) => {}
+            Some(JsonValue::Object(object)) => {
+                for (name, value) in object {
+                    match json_to_value(value) {
+                        Ok(value) => inputs.push((name.clone(), value)),
+                        Err(e) => return bad_request(&e.to_string()),
+                    }
+                }
+            }
+            Some(_) => return bad_request("'inputs' must be an object"),
+        }
+        let Some(code) = request.get("code").and_then(JsonValue::as_str) else {
+            return bad_request("missing string field 'code'");
+        };
+
+        self.busy.fetch_add(1, Ordering::SeqCst);
+        let recycled = pool.recycled();
+        let result = {
+            let mut engine = pool.acquire();
+            for (name, value) in inputs {
+                engine.set_global(&name, value);
+            }
+            engine.eval(code)
+        };
+        // 引擎在归还时才会被回收
+        self.recycled
+            .fetch_add(pool.recycled() - recycled, Ordering::SeqCst);
+        self.busy.fetch_sub(1, Ordering::SeqCst);
+
+        match result {
+            Ok(value) => match value_to_json(&value, FractionJsonMode::Float) {
+                Ok(result) => (200, json!({ "ok": true, "result": result })),
+                Err(e) => (422, error_body("UnserializableResult", &e.to_string())),
+            },
+            Err(e) => (422, error_body("EvalError", &e)),
+        }
+    }
+
+    /// 执行统计覆盖所有工作线程；`ast_cache` 为处理本请求的引擎的缓存统计
+    fn metrics(&self, pool: &EnginePool) -> JsonValue {
+        let snapshot = pool.metrics_snapshot();
+        let execution = &snapshot.execution;
+        let ms = |d: std::time::Duration| d.as_secs_f64() * 1000.0;
+        json!({
+            "ok": true,
+            "requests": self.requests.load(Ordering::SeqCst),
+            "uptime_ms": self.started.elapsed().as_millis() as u64,
+            "pool": {
+                "capacity": self.workers(),
+                "available": self.workers().saturating_sub(self.busy.load(Ordering::SeqCst)),
+                "recycled": self.recycled.load(Ordering::SeqCst),
+            },
+            "execution": {
+                "count": execution.execution_count,
+                "errors": execution.error_count,
+                "error_rate": execution.error_rate,
+                "total_steps": execution.total_steps,
+                "cache_hits": execution.cache_hits,
+                "avg_ms": ms(execution.average_duration),
+                "p50_ms": ms(execution.p50_duration),
+                "p95_ms": ms(execution.p95_duration),
+                "max_ms": ms(execution.max_duration),
+            },
+            "ast_cache": snapshot.ast_cache,
+        })
+    }
+}
+
+/// 解析 `/compile`：只做语法检查和静态分析，不执行
+fn compile(body: &str) -> (u16, JsonValue) {
+    let request = match parse_body(body) {
+        Ok(request) => request,
+        Err(response) => return response,
+    };
+    let Some(code) = request.get("code").and_then(JsonValue::as_str) else {
+        return bad_request("missing string field 'code'");
+    };
+    match Parser::new(code).parse_program() {
+        Ok(program) => {
+            let requirements =
+                RequirementsReport::from_program(&program, |name| builtin_names().contains(name));
+            (
+                200,
+                json!({
+                    "ok": true,
+                    "statements": program.len(),
+                    "requirements": requirements,
+                }),
+            )
+        }
+        Err(e) => (422, error_body("ParseError", &e.to_string())),
+    }
+}
+
+fn parse_body(body: &str) -> Result<serde_json::Map<String, JsonValue>, (u16, JsonValue)> {
+    match serde_json::from_str(body) {
+        Ok(JsonValue::Object(object)) => Ok(object),
+        Ok(_) => Err(bad_request("request body must be a JSON object")),
+        Err(e) => Err(bad_request(&format!("invalid JSON: {}", e))),
+    }
+}
+
+fn bad_request(message: &str) -> (u16, JsonValue) {
+    (400, error_body("BadRequest", message))
+}
+
+fn error_body(kind: &str, message: &str) -> JsonValue {
+    json!({ "ok": false, "error": { "kind": kind, "message": message } })
+}
@@ -0,0 +1,338 @@
+// src/signing.rs
+//! 脚本签名与信任级别
+//!
+//! 运维方为审核过的脚本生成分离的签名（或登记脚本哈希），引擎在求值前校验签名，
+//! 并按签名对应的信任级别决定脚本可以获得的 IO 权限。
+//!
+//! 支持三种分离签名，文本格式如下（可保存为 `.sig` 文件）：
+//! - `sha256:<hex>`：脚本内容的 SHA-256，需事先通过 `approve_hash` 登记
+//! - `hmac-sha256:<key_id>:<hex>`：使用共享密钥的 HMAC-SHA256
+//! - `ed25519:<key_id>:<hex>`：Ed25519 签名，策略中只保存公钥
+//!
+//! 签名覆盖脚本的原始字节，换行符或空白的变化都会使签名失效。
+
+use crate::builtins::IOPermissions;
+use ring::{digest, hmac, signature};
+use std::collections::HashMap;
+use std::fmt;
+
+/// 信任级别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TrustLevel {
+    /// 未签名的脚本（默认无 IO 权限）
+    Untrusted,
+    /// 经过审核的脚本（默认只允许文件系统）
+    Standard,
+    /// 完全信任的脚本（默认允许文件系统和网络）
+    Privileged,
+}
+
+impl TrustLevel {
+    /// 级别名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TrustLevel::Untrusted => "untrusted",
+            TrustLevel::Standard => "standard",
+            TrustLevel::Privileged => "privileged",
+        }
+    }
+}
+
+impl fmt::Display for TrustLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// 分离签名
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScriptSignature {
+    /// 脚本内容的 SHA-256 摘要
+    Sha256(Vec<u8>),
+    /// HMAC-SHA256 标签
+    HmacSha256 { key_id: String, tag: Vec<u8> },
+    /// Ed25519 签名
+    Ed25519 { key_id: String, signature: Vec<u8> },
+}
+
+impl ScriptSignature {
+    /// 解析签名文本（见模块文档中的格式）
+    pub fn parse(text: &str) -> Result<Self, SignatureError> {
+        let text = text.trim();
+        let malformed = || SignatureError::Malformed(text.to_string());
+        let mut parts = text.splitn(3, ':');
+        let scheme = parts.next().ok_or_else(malformed)?;
+        match scheme {
+            "sha256" => {
+                let hex = parts.next().ok_or_else(malformed)?;
+                if parts.next().is_some() {
+                    return Err(malformed());
+                }
+                Ok(ScriptSignature::Sha256(
+                    decode_hex(hex).ok_or_else(malformed)?,
+                ))
+            }
+            "hmac-sha256" | "ed25519" => {
+                let key_id = parts
+                    .next()
+                    .filter(|k| !k.is_empty())
+                    .ok_or_else(malformed)?;
+                let bytes =
+                    decode_hex(parts.next().ok_or_else(malformed)?).ok_or_else(malformed)?;
+                Ok(if scheme == "ed25519" {
+                    ScriptSignature::Ed25519 {
+                        key_id: key_id.to_string(),
+                        signature: bytes,
+                    }
+                } else {
+                    ScriptSignature::HmacSha256 {
+                        key_id: key_id.to_string(),
+                        tag: bytes,
+                    }
+                })
+            }
+            _ => Err(malformed()),
+        }
+    }
+}
+
+impl fmt::Display for ScriptSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScriptSignature::Sha256(hash) => write!(f, "sha256:{}", encode_hex(hash)),
+            ScriptSignature::HmacSha256 { key_id, tag } => {
+                write!(f, "hmac-sha256:{}:{}", key_id, encode_hex(tag))
+            }
+            ScriptSignature::Ed25519 { key_id, signature } => {
+                write!(f, "ed25519:{}:{}", key_id, encode_hex(signature))
+            }
+        }
+    }
+}
+
+/// 签名校验错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignatureError {
+    /// 签名文本格式错误
+    Malformed(String),
+    /// 策略不接受未签名的脚本
+    Unsigned,
+    /// 哈希未登记
+    UnknownHash,
+    /// 策略中没有该密钥
+    UnknownKey(String),
+    /// 签名与脚本内容不匹配（脚本被修改或签名错误）
+    Mismatch,
+}
+
+impl fmt::Display for SignatureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SignatureError::Malformed(text) => write!(f, "Malformed script signature '{}'", text),
+            SignatureError::Unsigned => write!(f, "Script is not signed"),
+            SignatureError::UnknownHash => write!(f, "Script hash is not approved"),
+            SignatureError::UnknownKey(key_id) => write!(f, "Unknown signing key '{}'", key_id),
+            SignatureError::Mismatch => {
+                write!(f, "Script signature does not match the script content")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SignatureError {}
+
+/// 策略中登记的密钥
+#[derive(Debug, Clone)]
+enum TrustedKey {
+    Hmac(Vec<u8>),
+    Ed25519(Vec<u8>),
+}
+
+/// 信任策略：登记的哈希和密钥、各信任级别对应的权限
+///
+/// # 示例
+/// ```
+/// use aether::signing::{TrustLevel, TrustPolicy, sign_hmac};
+///
+/// let mut policy = TrustPolicy::new();
+/// policy.add_hmac_key("ops", b"secret", TrustLevel::Privileged);
+///
+/// let script = "Set X 1";
+/// let signature = sign_hmac("ops", b"secret", script);
+/// assert_eq!(policy.verify(script, Some(&signature)).unwrap(), TrustLevel::Privileged);
+/// assert!(policy.verify("Set X 2", Some(&signature)).is_err());
+/// ```
+#[derive(Debug, Clone)]
+pub struct TrustPolicy {
+    hashes: HashMap<Vec<u8>, TrustLevel>,
+    keys: HashMap<String, (TrustedKey, TrustLevel)>,
+    presets: HashMap<TrustLevel, IOPermissions>,
+    allow_unsigned: bool,
+}
+
+impl TrustPolicy {
+    /// 创建空策略：未签名的脚本以 `Untrusted` 级别运行
+    pub fn new() -> Self {
+        let presets = HashMap::from([
+            (TrustLevel::Untrusted, IOPermissions::deny_all()),
+            (
+                TrustLevel::Standard,
+                IOPermissions {
+                    filesystem_enabled: true,
+                    network_enabled: false,
+                },
+            ),
+            (TrustLevel::Privileged, IOPermissions::allow_all()),
+        ]);
+        TrustPolicy {
+            hashes: HashMap::new(),
+            keys: HashMap::new(),
+            presets,
+            allow_unsigned: true,
+        }
+    }
+
+    /// 拒绝未签名的脚本（默认以 `Untrusted` 级别运行）
+    pub fn require_signature(mut self) -> Self {
+        self.allow_unsigned = false;
+        self
+    }
+
+    /// 登记审核过的脚本内容哈希（SHA-256，十六进制）
+    pub fn approve_hash(
+        &mut self,
+        sha256_hex: &str,
+        level: TrustLevel,
+    ) -> Result<(), SignatureError> {
+        let hash = decode_hex(sha256_hex)
+            .filter(|hash| hash.len() == digest::SHA256_OUTPUT_LEN)
+            .ok_or_else(|| SignatureError::Malformed(sha256_hex.to_string()))?;
+        self.hashes.insert(hash, level);
+        Ok(())
+    }
+
+    /// 登记 HMAC-SHA256 共享密钥
+    pub fn add_hmac_key(&mut self, key_id: &str, secret: &[u8], level: TrustLevel) {
+        self.keys.insert(
+            key_id.to_string(),
+            (TrustedKey::Hmac(secret.to_vec()), level),
+        );
+    }
+
+    /// 登记 Ed25519 公钥（32 字节）
+    pub fn add_ed25519_key(&mut self, key_id: &str, public_key: &[u8], level: TrustLevel) {
+        self.keys.insert(
+            key_id.to_string(),
+            (TrustedKey::Ed25519(public_key.to_vec()), level),
+        );
+    }
+
+    /// 设置信任级别对应的权限
+    pub fn set_permissions(&mut self, level: TrustLevel, permissions: IOPermissions) {
+        self.presets.insert(level, permissions);
+    }
+
+    /// 信任级别对应的权限
+    pub fn permissions_for(&self, level: TrustLevel) -> IOPermissions {
+        self.presets.get(&level).cloned().unwrap_or_default()
+    }
+
+    /// 校验签名并返回脚本的信任级别
+    ///
+    /// 没有签名时返回 `Untrusted`（策略要求签名时返回错误）；
+    /// 签名存在但无法验证时总是返回错误，不会降级为 `Untrusted`。
+    pub fn verify(
+        &self,
+        script: &str,
+        signature: Option<&str>,
+    ) -> Result<TrustLevel, SignatureError> {
+        let Some(signature) = signature else {
+            return if self.allow_unsigned {
+                Ok(TrustLevel::Untrusted)
+            } else {
+                Err(SignatureError::Unsigned)
+            };
+        };
+        self.verify_signature(script, &ScriptSignature::parse(signature)?)
+    }
+
+    /// 校验已解析的签名
+    pub fn verify_signature(
+        &self,
+        script: &str,
+        signature: &ScriptSignature,
+    ) -> Result<TrustLevel, SignatureError> {
+        match signature {
+            ScriptSignature::Sha256(expected) => {
+                if sha256(script) != *expected {
+                    return Err(SignatureError::Mismatch);
+                }
+                self.hashes
+                    .get(expected)
+                    .copied()
+                    .ok_or(SignatureError::UnknownHash)
+            }
+            ScriptSignature::HmacSha256 { key_id, tag } => match self.keys.get(key_id) {
+                Some((TrustedKey::Hmac(secret), level)) => {
+                    let key = hmac::Key::new(hmac::HMAC_SHA256, secret);
+                    hmac::verify(&key, script.as_bytes(), tag)
+                        .map(|_| *level)
+                        .map_err(|_| SignatureError::Mismatch)
+                }
+                _ => Err(SignatureError::UnknownKey(key_id.clone())),
+            },
+            ScriptSignature::Ed25519 { key_id, signature } => match self.keys.get(key_id) {
+                Some((TrustedKey::Ed25519(public_key), level)) => {
+                    signature::UnparsedPublicKey::new(&signature::ED25519, public_key)
+                        .verify(script.as_bytes(), signature)
+                        .map(|_| *level)
+                        .map_err(|_| SignatureError::Mismatch)
+                }
+                _ => Err(SignatureError::UnknownKey(key_id.clone())),
+            },
+        }
+    }
+}
+
+impl Default for TrustPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 脚本内容的 SHA-256 摘要
+fn sha256(script: &str) -> Vec<u8> {
+    digest::digest(&digest::SHA256, script.as_bytes())
+        .as_ref()
+        .to_vec()
+}
+
+/// 脚本内容的 SHA-256（十六进制），用于 `approve_hash`
+pub fn sha256_hex(script: &str) -> String {
+    encode_hex(&sha256(script))
+}
+
+/// 生成 HMAC-SHA256 签名文本
+pub fn sign_hmac(key_id: &str, secret: &[u8], script: &str) -> String {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret);
+    let tag = hmac::sign(&key, script.as_bytes());
+    ScriptSignature::HmacSha256 {
+        key_id: key_id.to_string(),
+        tag: tag.as_ref().to_vec(),
+    }
+    .to_string()
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.is_empty() || !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
@@ -94,6 +94,24 @@ pub fn get_module(name: &str) -> Option<&'static str> {
     }
 }
 
+/// 获取指定模块公开的函数（未知模块返回 None）
+///
+/// 标准库模块没有 `Export` 语句，模块中定义的函数除以 `_` 开头的辅助函数
+/// （如 `_HEAP_SWAP`、`_QUICK_SORT_HELPER`）外都公开。命名空间导入
+/// （`Import X From "stdlib:<name>"`、`load_stdlib_module_as`）只绑定这些函数，
+/// 常量和状态不导出。
+pub fn get_exports(name: &str) -> Option<Vec<&'static str>> {
+    let index = ALL_MODULES.iter().position(|(module, _)| *module == name)?;
+    Some(
+        module_exports()[index]
+            .functions
+            .iter()
+            .map(String::as_str)
+            .filter(|function| !function.starts_with('_'))
+            .collect(),
+    )
+}
+
 /// 获取所有标准库代码（合并为一个字符串）
 pub fn get_all_stdlib() -> String {
     let mut result = String::new();
@@ -114,8 +132,78 @@ pub fn get_all_stdlib() -> String {
 pub fn preload_stdlib(engine: &mut crate::Aether) -> Result<(), String> {
     for (name, code) in ALL_MODULES {
         engine
-            .eval(code)
+            .eval_stdlib(code)
             .map_err(|e| format!("Failed to load stdlib module '{}': {}", name, e))?;
     }
     Ok(())
 }
+
+/// 脚本用到的标准库模块（包括这些模块依赖的其他模块），按 `ALL_MODULES` 的顺序
+///
+/// 根据调用图找出脚本读取但没有定义的函数，再查找定义这些函数的模块，
+/// 用于只加载需要的模块而不是预加载全部标准库。
+///
+/// # 示例
+/// ```
+/// use aether::stdlib;
+///
+/// let modules = stdlib::required_modules("STR_TRIM(\"  hi  \")").unwrap();
+/// assert_eq!(modules, vec!["string_utils"]);
+/// ```
+pub fn required_modules(code: &str) -> Result<Vec<&'static str>, String> {
+    let graph = crate::analysis::call_graph(code).map_err(|e| format!("Parse error: {}", e))?;
+    let mut wanted: Vec<String> = std::iter::once(&graph.main)
+        .chain(&graph.functions)
+        .flat_map(|node| node.reads.iter().cloned())
+        .collect();
+
+    let exports = module_exports();
+    let mut needed = std::collections::BTreeSet::new();
+    while let Some(name) = wanted.pop() {
+        for (index, module) in exports.iter().enumerate() {
+            if module.functions.contains(&name) && needed.insert(index) {
+                wanted.extend(module.dependencies.iter().cloned());
+            }
+        }
+    }
+    Ok(needed
+        .into_iter()
+        .map(|index| ALL_MODULES[index].0)
+        .collect())
+}
+
+/// 名称是否为某个标准库模块定义的函数
+pub fn is_stdlib_function(name: &str) -> bool {
+    module_exports()
+        .iter()
+        .any(|module| module.functions.iter().any(|function| function == name))
+}
+
+/// 标准库模块定义的函数及其读取的外部名称
+struct ModuleExports {
+    functions: Vec<String>,
+    dependencies: Vec<String>,
+}
+
+/// 按 `ALL_MODULES` 的顺序分析每个标准库模块（只分析一次）
+fn module_exports() -> &'static [ModuleExports] {
+    static EXPORTS: std::sync::OnceLock<Vec<ModuleExports>> = std::sync::OnceLock::new();
+    EXPORTS.get_or_init(|| {
+        ALL_MODULES
+            .iter()
+            .map(|(_, code)| match crate::analysis::call_graph(code) {
+                Ok(graph) => ModuleExports {
+                    functions: graph.functions.iter().map(|f| f.name.clone()).collect(),
+                    dependencies: std::iter::once(&graph.main)
+                        .chain(&graph.functions)
+                        .flat_map(|node| node.reads.iter().cloned())
+                        .collect(),
+                },
+                Err(_) => ModuleExports {
+                    functions: Vec::new(),
+                    dependencies: Vec::new(),
+                },
+            })
+            .collect()
+    })
+}
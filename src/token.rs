@@ -20,6 +20,34 @@ impl std::fmt::Display for Position {
     }
 }
 
+/// Source range of a token: char offsets `start..end` and the 1-based line/column of `start`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Highlighting class of a token
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenKind {
+    Keyword,
+    Identifier,
+    Number,
+    String,
+    /// `True`, `False` and `Null`
+    Constant,
+    Operator,
+    Punctuation,
+    Newline,
+    Extension,
+    Comment,
+    Whitespace,
+    Illegal,
+    Eof,
+}
+
 /// Token with position information
 #[derive(Debug, Clone, PartialEq)]
 pub struct TokenWithPos {
@@ -63,10 +91,14 @@ pub enum Token {
     As,
     Export,
     Throw,
+    Try,
+    Catch,
+    Finally,
 
     // Identifiers and literals - 全大写标识符
     Identifier(String),
     Number(f64),
+    Integer(i64),
     BigInteger(String), // 大整数字面量，保留原始字符串
     String(String),
     Boolean(bool),
@@ -109,8 +141,22 @@ pub enum Token {
 
     // Special
     Arrow, // ->
+    /// 扩展块 `@TAG { payload }`，payload 为原始文本，交给宿主注册的处理器求值
+    Extension {
+        tag: String,
+        payload: String,
+    },
+    /// 编译指示 `#NAME value`（到行尾），如 `#language 1.0`
+    Pragma {
+        name: String,
+        value: String,
+    },
     Illegal(char),
     EOF,
+
+    // Trivia - only produced by `Lexer::tokenize_with_spans`, never seen by the parser
+    Comment(String),    // `// ...` or `/* ... */`, including the delimiters
+    Whitespace(String), // spaces, tabs and carriage returns
 }
 
 impl Token {
@@ -142,6 +188,9 @@ impl Token {
             "as" => Token::As,
             "Export" => Token::Export,
             "Throw" => Token::Throw,
+            "Try" => Token::Try,
+            "Catch" => Token::Catch,
+            "Finally" => Token::Finally,
 
             // Logical operators as keywords
             "And" => Token::And,
@@ -186,8 +235,12 @@ impl Token {
             Token::As => "As",
             Token::Export => "Export",
             Token::Throw => "Throw",
+            Token::Try => "Try",
+            Token::Catch => "Catch",
+            Token::Finally => "Finally",
             Token::Identifier(_) => "Identifier",
             Token::Number(_) => "Number",
+            Token::Integer(_) => "Integer",
             Token::BigInteger(_) => "BigInteger",
             Token::String(_) => "String",
             Token::Boolean(_) => "Boolean",
@@ -218,8 +271,85 @@ impl Token {
             Token::Semicolon => ";",
             Token::Newline => "\\n",
             Token::Arrow => "->",
+            Token::Extension { .. } => "Extension",
+            Token::Pragma { .. } => "Pragma",
             Token::Illegal(_) => "Illegal",
             Token::EOF => "EOF",
+            Token::Comment(_) => "Comment",
+            Token::Whitespace(_) => "Whitespace",
+        }
+    }
+
+    /// Whether the token is a comment or whitespace
+    pub fn is_trivia(&self) -> bool {
+        matches!(self, Token::Comment(_) | Token::Whitespace(_))
+    }
+
+    /// Highlighting class of the token
+    pub fn kind(&self) -> TokenKind {
+        match self {
+            Token::Set
+            | Token::Func
+            | Token::Lambda
+            | Token::Generator
+            | Token::Lazy
+            | Token::If
+            | Token::Elif
+            | Token::Else
+            | Token::While
+            | Token::For
+            | Token::In
+            | Token::Switch
+            | Token::Case
+            | Token::Default
+            | Token::Return
+            | Token::Yield
+            | Token::Break
+            | Token::Continue
+            | Token::Import
+            | Token::From
+            | Token::As
+            | Token::Export
+            | Token::Throw
+            | Token::Try
+            | Token::Catch
+            | Token::Finally => TokenKind::Keyword,
+            Token::Identifier(_) => TokenKind::Identifier,
+            Token::Number(_) | Token::Integer(_) | Token::BigInteger(_) => TokenKind::Number,
+            Token::String(_) => TokenKind::String,
+            Token::Boolean(_) | Token::Null => TokenKind::Constant,
+            Token::Plus
+            | Token::Minus
+            | Token::Multiply
+            | Token::Divide
+            | Token::Modulo
+            | Token::Equal
+            | Token::NotEqual
+            | Token::Less
+            | Token::LessEqual
+            | Token::Greater
+            | Token::GreaterEqual
+            | Token::And
+            | Token::Or
+            | Token::Not
+            | Token::Assign
+            | Token::Arrow => TokenKind::Operator,
+            Token::LeftParen
+            | Token::RightParen
+            | Token::LeftBrace
+            | Token::RightBrace
+            | Token::LeftBracket
+            | Token::RightBracket
+            | Token::Comma
+            | Token::Colon
+            | Token::Semicolon => TokenKind::Punctuation,
+            Token::Newline => TokenKind::Newline,
+            Token::Extension { .. } => TokenKind::Extension,
+            Token::Pragma { .. } => TokenKind::Keyword,
+            Token::Illegal(_) => TokenKind::Illegal,
+            Token::EOF => TokenKind::Eof,
+            Token::Comment(_) => TokenKind::Comment,
+            Token::Whitespace(_) => TokenKind::Whitespace,
         }
     }
 }
@@ -0,0 +1,187 @@
+// src/tutorial.rs
+//! 内置教程（`aether learn`）
+//!
+//! 每一课是嵌入二进制的一个 `.aether` 文件（见 `lessons/`），格式为：
+//!
+//! ```text
+//! // # 标题
+//! // 说明文字（开头连续的注释行）
+//!
+//! PRINTLN("代码")
+//!
+//! //> 期望输出（每行一条，以 `//>` 开头）
+//! ```
+//!
+//! 说明和期望输出都写在注释里，因此每一课本身就是可以直接运行的脚本。
+//! 课程的输出与期望输出自动比较，`aether learn --check` 用它校验所有课程。
+
+use crate::Aether;
+use crate::builtins::io::{self, CapturedOutput};
+use crate::value::Value;
+
+/// 所有课程的源码 `(名称, 代码)`，按学习顺序排列
+const LESSON_SOURCES: &[(&str, &str)] = &[
+    ("hello", include_str!("../lessons/01_hello.aether")),
+    ("variables", include_str!("../lessons/02_variables.aether")),
+    ("strings", include_str!("../lessons/03_strings.aether")),
+    (
+        "conditions",
+        include_str!("../lessons/04_conditions.aether"),
+    ),
+    ("loops", include_str!("../lessons/05_loops.aether")),
+    (
+        "collections",
+        include_str!("../lessons/06_collections.aether"),
+    ),
+    ("functions", include_str!("../lessons/07_functions.aether")),
+    (
+        "validation",
+        include_str!("../lessons/08_validation.aether"),
+    ),
+];
+
+/// 一课
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lesson {
+    /// 序号（从 1 开始）
+    pub number: usize,
+    /// 名称，如 `hello`
+    pub name: &'static str,
+    /// 标题
+    pub title: String,
+    /// 说明文字
+    pub intro: Vec<String>,
+    /// 完整源码（含注释）
+    pub source: &'static str,
+    /// 期望输出
+    pub expected_output: String,
+}
+
+impl Lesson {
+    /// 解析课程源码
+    pub fn parse(number: usize, name: &'static str, source: &'static str) -> Self {
+        let mut title = name.to_string();
+        let mut intro = Vec::new();
+        let mut in_header = true;
+        let mut expected = Vec::new();
+
+        for line in source.lines() {
+            let trimmed = line.trim();
+            if let Some(output) = trimmed.strip_prefix("//>") {
+                expected.push(output.strip_prefix(' ').unwrap_or(output).to_string());
+                continue;
+            }
+            if !in_header {
+                continue;
+            }
+            match trimmed.strip_prefix("//") {
+                Some(comment) => {
+                    let comment = comment.trim();
+                    match comment.strip_prefix("# ") {
+                        Some(heading) => title = heading.trim().to_string(),
+                        None => intro.push(comment.to_string()),
+                    }
+                }
+                None => in_header = false,
+            }
+        }
+
+        let mut expected_output = expected.join("\n");
+        if !expected.is_empty() {
+            expected_output.push('\n');
+        }
+        Lesson {
+            number,
+            name,
+            title,
+            intro,
+            source,
+            expected_output,
+        }
+    }
+
+    /// 去掉说明和期望输出后的代码
+    pub fn code(&self) -> String {
+        let code: Vec<&str> = self
+            .source
+            .lines()
+            .skip_while(|line| line.trim().starts_with("//") || line.trim().is_empty())
+            .filter(|line| !line.trim().starts_with("//>"))
+            .collect();
+        code.join("\n").trim_end().to_string()
+    }
+
+    /// 在新引擎中运行本课并与期望输出比较
+    pub fn check(&self) -> LessonCheck {
+        let mut engine = Aether::new();
+        let (output, result) = run_captured(&mut engine, self.source);
+        let error = result.err();
+        LessonCheck {
+            passed: error.is_none() && outputs_match(&output, &self.expected_output),
+            output,
+            error,
+        }
+    }
+}
+
+/// 课程的运行结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct LessonCheck {
+    /// 输出是否与期望一致且没有出错
+    pub passed: bool,
+    /// 实际输出
+    pub output: String,
+    /// 运行错误
+    pub error: Option<String>,
+}
+
+/// 所有课程，按学习顺序排列
+pub fn lessons() -> Vec<Lesson> {
+    LESSON_SOURCES
+        .iter()
+        .enumerate()
+        .map(|(i, (name, source))| Lesson::parse(i + 1, name, source))
+        .collect()
+}
+
+/// 按序号或名称查找课程
+pub fn find_lesson(key: &str) -> Option<Lesson> {
+    let lessons = lessons();
+    match key.parse::<usize>() {
+        Ok(number) => lessons.into_iter().find(|l| l.number == number),
+        Err(_) => lessons.into_iter().find(|l| l.name == key),
+    }
+}
+
+/// 在引擎中运行代码并捕获输出，返回 `(输出, 求值结果)`
+///
+/// `DISPLAY` 的值按引擎的显示设置格式化为一行文本。
+pub fn run_captured(engine: &mut Aether, code: &str) -> (String, Result<Value, String>) {
+    io::begin_capture();
+    let result = engine.eval(code);
+    let captured = io::end_capture();
+
+    let mut output = String::new();
+    for item in captured {
+        match item {
+            CapturedOutput::Stdout(text) => output.push_str(&text),
+            CapturedOutput::Display(value) => {
+                output.push_str(&engine.format_value(&value));
+                output.push('\n');
+            }
+        }
+    }
+    (output, result)
+}
+
+/// 比较输出（忽略行尾空白和末尾空行）
+pub fn outputs_match(actual: &str, expected: &str) -> bool {
+    let normalize = |text: &str| -> Vec<String> {
+        let mut lines: Vec<String> = text.lines().map(|l| l.trim_end().to_string()).collect();
+        while lines.last().is_some_and(|l| l.is_empty()) {
+            lines.pop();
+        }
+        lines
+    };
+    normalize(actual) == normalize(expected)
+}
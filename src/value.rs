@@ -3,11 +3,13 @@
 
 use crate::ast::{Expr, Stmt};
 use crate::environment::Environment;
+use crate::runtime::display::{DisplayOptions, current_display_options};
+use chrono::{DateTime, FixedOffset};
 use num_bigint::BigInt;
 use num_rational::Ratio;
-use num_traits::Zero;
+use num_traits::{FromPrimitive, Zero};
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 use std::rc::Rc;
 
@@ -17,6 +19,9 @@ pub enum Value {
     /// Numeric value (f64)
     Number(f64),
 
+    /// Integer value (exact, from integer literals)
+    Int(i64),
+
     /// Rational number (exact fraction)
     Fraction(Ratio<BigInt>),
 
@@ -35,6 +40,15 @@ pub enum Value {
     /// Dictionary (key-value map)
     Dict(HashMap<String, Value>),
 
+    /// Set of unique scalar values (O(1) membership)
+    Set(HashSet<SetKey>),
+
+    /// Column-oriented table
+    Table(Table),
+
+    /// Point in time with a fixed UTC offset
+    DateTime(DateTime<FixedOffset>),
+
     /// Function (closure)
     Function {
         name: Option<String>,
@@ -62,6 +76,303 @@ pub enum Value {
     BuiltIn { name: String, arity: usize },
 }
 
+/// Exact big-integer form of an integral Number
+///
+/// Returns None for fractional, NaN or infinite values. Unlike `as i64`,
+/// integers beyond the i64 range are converted without truncation.
+pub fn integer_ratio(n: f64) -> Option<Ratio<BigInt>> {
+    if !n.is_finite() || n.fract() != 0.0 {
+        return None;
+    }
+    BigInt::from_f64(n).map(Ratio::from_integer)
+}
+
+/// Exact rational form of an Integer
+pub fn int_ratio(i: i64) -> Ratio<BigInt> {
+    Ratio::from_integer(BigInt::from(i))
+}
+
+/// Compare a Number with a Fraction exactly (NaN is unordered)
+fn compare_number_fraction(a: f64, b: &Ratio<BigInt>) -> Option<std::cmp::Ordering> {
+    if a.is_nan() {
+        return None;
+    }
+    if a.is_infinite() {
+        return Some(if a > 0.0 {
+            std::cmp::Ordering::Greater
+        } else {
+            std::cmp::Ordering::Less
+        });
+    }
+    Ratio::<BigInt>::from_float(a).map(|a| a.cmp(b))
+}
+
+/// Join container parts, truncating to `max_items` with a trailing `...`
+fn join_limited(
+    parts: impl Iterator<Item = String>,
+    len: usize,
+    options: &DisplayOptions,
+) -> String {
+    let limit = options.max_items.unwrap_or(usize::MAX);
+    let mut shown: Vec<String> = parts.take(limit).collect();
+    if len > limit {
+        shown.push("...".to_string());
+    }
+    shown.join(", ")
+}
+
+/// Hashable set element
+///
+/// Only scalar values can be stored in a `Value::Set`. Numbers are stored by
+/// their bit pattern (with `-0.0` normalized to `0.0`); NaN is rejected.
+/// Numeric elements are equal when their values are, as with `Value::equals`:
+/// `Fraction(2)` and `Number(2)` are the same member.
+#[derive(Debug, Clone)]
+pub enum SetKey {
+    Null,
+    Boolean(bool),
+    Number(u64),
+    Fraction(Ratio<BigInt>),
+    String(String),
+}
+
+impl SetKey {
+    /// Convert a value into a set element, if it is hashable
+    pub fn from_value(value: &Value) -> Option<SetKey> {
+        match value {
+            Value::Null => Some(SetKey::Null),
+            Value::Boolean(b) => Some(SetKey::Boolean(*b)),
+            Value::Number(n) if n.is_nan() => None,
+            Value::Number(n) => Some(SetKey::Number(
+                if *n == 0.0 { 0.0f64 } else { *n }.to_bits(),
+            )),
+            // Integers share keys with equal Numbers; beyond 2^53 they are kept exact
+            Value::Int(i) if i.unsigned_abs() <= 1 << 53 => {
+                Some(SetKey::Number((*i as f64).to_bits()))
+            }
+            Value::Int(i) => Some(SetKey::Fraction(int_ratio(*i))),
+            Value::Fraction(f) => Some(SetKey::Fraction(f.clone())),
+            Value::String(s) => Some(SetKey::String(s.clone())),
+            _ => None,
+        }
+    }
+
+    /// Bit pattern of a numeric element whose value is exactly an f64
+    ///
+    /// Fractions such as 1/3 have no exact f64 and only equal other fractions.
+    fn number_bits(&self) -> Option<u64> {
+        match self {
+            SetKey::Number(bits) => Some(*bits),
+            SetKey::Fraction(f) => {
+                let n = num_traits::ToPrimitive::to_f64(f)?;
+                (Ratio::<BigInt>::from_float(n).as_ref() == Some(f))
+                    .then(|| if n == 0.0 { 0.0f64 } else { n }.to_bits())
+            }
+            _ => None,
+        }
+    }
+
+    /// Convert the set element back into a value
+    pub fn to_value(&self) -> Value {
+        match self {
+            SetKey::Null => Value::Null,
+            SetKey::Boolean(b) => Value::Boolean(*b),
+            SetKey::Number(bits) => Value::Number(f64::from_bits(*bits)),
+            SetKey::Fraction(f) => Value::Fraction(f.clone()),
+            SetKey::String(s) => Value::String(s.clone()),
+        }
+    }
+
+    /// Deterministic ordering used when listing set elements:
+    /// Null < Boolean < Number/Fraction (by value) < String
+    fn sort_cmp(&self, other: &SetKey) -> std::cmp::Ordering {
+        fn rank(key: &SetKey) -> u8 {
+            match key {
+                SetKey::Null => 0,
+                SetKey::Boolean(_) => 1,
+                SetKey::Number(_) | SetKey::Fraction(_) => 2,
+                SetKey::String(_) => 3,
+            }
+        }
+        rank(self).cmp(&rank(other)).then_with(|| {
+            let (a, b) = (self.to_value(), other.to_value());
+            match (a.compare(&b), a.to_number(), b.to_number()) {
+                (Some(ord), _, _) => ord,
+                (None, Some(x), Some(y)) => x.total_cmp(&y),
+                _ => std::cmp::Ordering::Equal,
+            }
+        })
+    }
+}
+
+impl PartialEq for SetKey {
+    fn eq(&self, other: &SetKey) -> bool {
+        match (self, other) {
+            (SetKey::Null, SetKey::Null) => true,
+            (SetKey::Boolean(a), SetKey::Boolean(b)) => a == b,
+            (SetKey::Fraction(a), SetKey::Fraction(b)) => a == b,
+            (SetKey::Number(_) | SetKey::Fraction(_), SetKey::Number(_) | SetKey::Fraction(_)) => {
+                self.number_bits().is_some() && self.number_bits() == other.number_bits()
+            }
+            (SetKey::String(a), SetKey::String(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for SetKey {}
+
+impl std::hash::Hash for SetKey {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        // Equal numbers hash alike whether stored as Number or Fraction
+        if let Some(bits) = self.number_bits() {
+            return (2u8, bits).hash(state);
+        }
+        match self {
+            SetKey::Null => 0u8.hash(state),
+            SetKey::Boolean(b) => (1u8, b).hash(state),
+            SetKey::Number(bits) => (2u8, bits).hash(state),
+            SetKey::Fraction(f) => (3u8, f).hash(state),
+            SetKey::String(s) => (4u8, s).hash(state),
+        }
+    }
+}
+
+/// List the elements of a set in a deterministic (sorted) order
+pub fn set_elements(set: &HashSet<SetKey>) -> Vec<Value> {
+    let mut keys: Vec<&SetKey> = set.iter().collect();
+    keys.sort_by(|a, b| a.sort_cmp(b));
+    keys.into_iter().map(SetKey::to_value).collect()
+}
+
+/// Column-oriented table
+///
+/// Stores one vector per column, all of the same length. Cells are scalar
+/// values (Number, Fraction, String, Boolean, Null) so rows can be hashed
+/// for grouping and joining.
+#[derive(Debug, Clone, Default)]
+pub struct Table {
+    columns: Vec<String>,
+    data: Vec<Vec<Value>>,
+}
+
+impl Table {
+    /// Build a table from column names and column data
+    pub fn new(columns: Vec<String>, data: Vec<Vec<Value>>) -> Result<Self, String> {
+        if columns.len() != data.len() {
+            return Err(format!(
+                "Table has {} column names but {} columns of data",
+                columns.len(),
+                data.len()
+            ));
+        }
+        let mut seen = HashSet::new();
+        if let Some(dup) = columns.iter().find(|c| !seen.insert(c.as_str())) {
+            return Err(format!("Duplicate table column '{}'", dup));
+        }
+        let rows = data.first().map_or(0, Vec::len);
+        if let Some((name, col)) = columns.iter().zip(&data).find(|(_, c)| c.len() != rows) {
+            return Err(format!(
+                "Column '{}' has {} values, expected {}",
+                name,
+                col.len(),
+                rows
+            ));
+        }
+        if let Some(cell) = data
+            .iter()
+            .flatten()
+            .find(|v| SetKey::from_value(v).is_none())
+        {
+            return Err(format!(
+                "Table cells must be Number, Fraction, String, Boolean or Null, got {}",
+                cell.type_name()
+            ));
+        }
+        Ok(Table { columns, data })
+    }
+
+    /// Build a table from row-major data
+    pub fn from_rows(columns: Vec<String>, rows: Vec<Vec<Value>>) -> Result<Self, String> {
+        let mut data = vec![Vec::with_capacity(rows.len()); columns.len()];
+        for (index, row) in rows.into_iter().enumerate() {
+            if row.len() != columns.len() {
+                return Err(format!(
+                    "Row {} has {} values, expected {}",
+                    index,
+                    row.len(),
+                    columns.len()
+                ));
+            }
+            for (col, cell) in data.iter_mut().zip(row) {
+                col.push(cell);
+            }
+        }
+        Table::new(columns, data)
+    }
+
+    /// Column names in order
+    pub fn columns(&self) -> &[String] {
+        &self.columns
+    }
+
+    /// Number of rows
+    pub fn row_count(&self) -> usize {
+        self.data.first().map_or(0, Vec::len)
+    }
+
+    /// Position of a column
+    pub fn column_index(&self, name: &str) -> Option<usize> {
+        self.columns.iter().position(|c| c == name)
+    }
+
+    /// Values of a column
+    pub fn column(&self, name: &str) -> Option<&[Value]> {
+        self.column_index(name).map(|i| self.data[i].as_slice())
+    }
+
+    /// Cell value by row and column position
+    pub fn cell(&self, row: usize, column: usize) -> &Value {
+        &self.data[column][row]
+    }
+
+    /// One row as a Dict
+    pub fn row(&self, index: usize) -> Option<Value> {
+        if index >= self.row_count() {
+            return None;
+        }
+        Some(Value::Dict(
+            self.columns
+                .iter()
+                .zip(&self.data)
+                .map(|(name, col)| (name.clone(), col[index].clone()))
+                .collect(),
+        ))
+    }
+
+    /// All rows as an array of Dicts
+    pub fn to_dicts(&self) -> Vec<Value> {
+        (0..self.row_count()).filter_map(|i| self.row(i)).collect()
+    }
+
+    /// New table containing the given rows (in the given order)
+    pub fn take_rows(&self, indices: &[usize]) -> Table {
+        Table {
+            columns: self.columns.clone(),
+            data: self
+                .data
+                .iter()
+                .map(|col| indices.iter().map(|&i| col[i].clone()).collect())
+                .collect(),
+        }
+    }
+
+    /// Decompose into column names and column data
+    pub fn into_parts(self) -> (Vec<String>, Vec<Vec<Value>>) {
+        (self.columns, self.data)
+    }
+}
+
 /// Generator execution state
 #[derive(Debug, Clone)]
 pub enum GeneratorState {
@@ -82,10 +393,13 @@ impl Value {
             Value::Boolean(b) => *b,
             Value::Null => false,
             Value::Number(n) => *n != 0.0,
+            Value::Int(i) => *i != 0,
             Value::Fraction(f) => !f.is_zero(),
             Value::String(s) => !s.is_empty(),
             Value::Array(arr) => !arr.is_empty(),
             Value::Dict(dict) => !dict.is_empty(),
+            Value::Set(set) => !set.is_empty(),
+            Value::Table(table) => table.row_count() > 0,
             _ => true,
         }
     }
@@ -94,12 +408,16 @@ impl Value {
     pub fn type_name(&self) -> &'static str {
         match self {
             Value::Number(_) => "Number",
+            Value::Int(_) => "Integer",
             Value::Fraction(_) => "Fraction",
             Value::String(_) => "String",
             Value::Boolean(_) => "Boolean",
             Value::Null => "Null",
             Value::Array(_) => "Array",
             Value::Dict(_) => "Dict",
+            Value::Set(_) => "Set",
+            Value::Table(_) => "Table",
+            Value::DateTime(_) => "DateTime",
             Value::Function { .. } => "Function",
             Value::Generator { .. } => "Generator",
             Value::Lazy { .. } => "Lazy",
@@ -111,6 +429,7 @@ impl Value {
     pub fn to_number(&self) -> Option<f64> {
         match self {
             Value::Number(n) => Some(*n),
+            Value::Int(i) => Some(*i as f64),
             Value::Fraction(f) => Some(
                 f.numer().to_string().parse::<f64>().ok()?
                     / f.denom().to_string().parse::<f64>().ok()?,
@@ -122,21 +441,47 @@ impl Value {
         }
     }
 
+    /// Replace Integers, including those nested in containers, with equal Numbers
+    ///
+    /// Builtins that predate `Int` only understand `Number`. Integers beyond 2^53
+    /// become integral Fractions instead, so the exact value is kept.
+    pub fn widen_integers(&mut self) {
+        match self {
+            Value::Int(i) if i.unsigned_abs() <= 1 << 53 => *self = Value::Number(*i as f64),
+            Value::Int(i) => *self = Value::Fraction(int_ratio(*i)),
+            Value::Array(items) => items.iter_mut().for_each(Value::widen_integers),
+            Value::Dict(map) => map.values_mut().for_each(Value::widen_integers),
+            Value::Table(table) => table
+                .data
+                .iter_mut()
+                .flatten()
+                .for_each(Value::widen_integers),
+            _ => {}
+        }
+    }
+
     /// Convert to string
     #[allow(clippy::inherent_to_string_shadow_display)]
     pub fn to_string(&self) -> String {
+        self.display_with(&current_display_options())
+    }
+
+    /// Convert to string using explicit display options
+    pub fn display_with(&self, options: &DisplayOptions) -> String {
+        self.format_at(options, 0)
+    }
+
+    fn format_at(&self, options: &DisplayOptions, depth: usize) -> String {
+        // Collapse containers nested deeper than the configured limit
+        let too_deep = options.max_depth.is_some_and(|max| depth >= max);
         match self {
-            Value::Number(n) => {
-                // Format number nicely (remove .0 for integers)
-                if n.fract() == 0.0 {
-                    format!("{:.0}", n)
-                } else {
-                    format!("{}", n)
-                }
-            }
+            Value::Number(n) => options.format_number(*n),
+            Value::Int(i) => i.to_string(),
             Value::Fraction(f) => {
                 if f.is_integer() {
                     format!("{}", f.numer())
+                } else if options.fraction_as_decimal {
+                    options.format_number(self.to_number().unwrap_or(f64::NAN))
                 } else {
                     format!("{}/{}", f.numer(), f.denom())
                 }
@@ -144,17 +489,34 @@ impl Value {
             Value::String(s) => s.clone(),
             Value::Boolean(b) => b.to_string(),
             Value::Null => "Null".to_string(),
+            Value::Array(_) if too_deep => "[...]".to_string(),
             Value::Array(arr) => {
-                let elements: Vec<String> = arr.iter().map(|v| v.to_string()).collect();
-                format!("[{}]", elements.join(", "))
+                let elements = arr.iter().map(|v| v.format_at(options, depth + 1));
+                format!("[{}]", join_limited(elements, arr.len(), options))
             }
+            Value::Dict(_) if too_deep => "{...}".to_string(),
             Value::Dict(dict) => {
-                let pairs: Vec<String> = dict
+                let mut entries: Vec<(&String, &Value)> = dict.iter().collect();
+                if options.sort_dict_keys {
+                    entries.sort_by(|a, b| a.0.cmp(b.0));
+                }
+                let pairs = entries
                     .iter()
-                    .map(|(k, v)| format!("{}: {}", k, v.to_string()))
-                    .collect();
-                format!("{{{}}}", pairs.join(", "))
+                    .map(|(k, v)| format!("{}: {}", k, v.format_at(options, depth + 1)));
+                format!("{{{}}}", join_limited(pairs, dict.len(), options))
             }
+            Value::Set(_) if too_deep => "Set{...}".to_string(),
+            Value::Set(set) => {
+                let elements = set_elements(set);
+                let parts = elements.iter().map(|v| v.format_at(options, depth + 1));
+                format!("Set{{{}}}", join_limited(parts, set.len(), options))
+            }
+            Value::DateTime(dt) => dt.to_rfc3339(),
+            Value::Table(table) => format!(
+                "Table[{}]({} rows)",
+                table.columns().join(", "),
+                table.row_count()
+            ),
             Value::Function { name, params, .. } => {
                 if let Some(n) = name {
                     format!("<Function {} ({})>", n, params.join(", "))
@@ -177,12 +539,28 @@ impl Value {
         match (self, other) {
             (Value::Number(a), Value::Number(b)) => (a - b).abs() < f64::EPSILON,
             (Value::Fraction(a), Value::Fraction(b)) => a == b,
+            (Value::Number(a), Value::Fraction(b)) | (Value::Fraction(b), Value::Number(a)) => {
+                compare_number_fraction(*a, b) == Some(std::cmp::Ordering::Equal)
+            }
+            (Value::Int(_), Value::Int(_) | Value::Number(_) | Value::Fraction(_))
+            | (Value::Number(_) | Value::Fraction(_), Value::Int(_)) => {
+                self.compare(other) == Some(std::cmp::Ordering::Equal)
+            }
             (Value::String(a), Value::String(b)) => a == b,
             (Value::Boolean(a), Value::Boolean(b)) => a == b,
             (Value::Null, Value::Null) => true,
+            // The same instant is equal regardless of the offset it is shown in
+            (Value::DateTime(a), Value::DateTime(b)) => a == b,
             (Value::Array(a), Value::Array(b)) => {
                 a.len() == b.len() && a.iter().zip(b.iter()).all(|(x, y)| x.equals(y))
             }
+            (Value::Set(a), Value::Set(b)) => a == b,
+            (Value::Table(a), Value::Table(b)) => {
+                a.columns() == b.columns()
+                    && a.row_count() == b.row_count()
+                    && (0..a.columns().len())
+                        .all(|c| (0..a.row_count()).all(|r| a.cell(r, c).equals(b.cell(r, c))))
+            }
             _ => false,
         }
     }
@@ -192,8 +570,20 @@ impl Value {
         match (self, other) {
             (Value::Number(a), Value::Number(b)) => a.partial_cmp(b),
             (Value::Fraction(a), Value::Fraction(b)) => Some(a.cmp(b)),
+            (Value::Number(a), Value::Fraction(b)) => compare_number_fraction(*a, b),
+            (Value::Fraction(a), Value::Number(b)) => {
+                compare_number_fraction(*b, a).map(std::cmp::Ordering::reverse)
+            }
+            (Value::Int(a), Value::Int(b)) => Some(a.cmp(b)),
+            (Value::Int(a), Value::Number(b)) => {
+                compare_number_fraction(*b, &int_ratio(*a)).map(std::cmp::Ordering::reverse)
+            }
+            (Value::Number(a), Value::Int(b)) => compare_number_fraction(*a, &int_ratio(*b)),
+            (Value::Int(a), Value::Fraction(b)) => Some(int_ratio(*a).cmp(b)),
+            (Value::Fraction(a), Value::Int(b)) => Some(a.cmp(&int_ratio(*b))),
             (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
             (Value::Boolean(a), Value::Boolean(b)) => Some(a.cmp(b)),
+            (Value::DateTime(a), Value::DateTime(b)) => Some(a.cmp(b)),
             _ => None,
         }
     }
@@ -50,6 +50,34 @@ impl Aether {
         }
     }
 
+    /// Call the global function `name` every `interval_ms` milliseconds; returns the timer id
+    ///
+    /// Timers only fire when the host calls `advanceTimers` (e.g. from `requestAnimationFrame`).
+    #[wasm_bindgen(js_name = onTimer)]
+    pub fn on_timer(&mut self, interval_ms: f64, name: &str) -> Result<usize, JsValue> {
+        self.engine
+            .on_timer(duration_from_ms(interval_ms), name)
+            .map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Cancel a timer registered by the host or by a script
+    #[wasm_bindgen(js_name = cancelTimer)]
+    pub fn cancel_timer(&mut self, id: usize) -> bool {
+        self.engine.cancel_timer(id)
+    }
+
+    /// Advance the timer clock by `elapsed_ms` and run due callbacks
+    ///
+    /// Returns the number of callbacks that ran; failures are logged to the console.
+    #[wasm_bindgen(js_name = advanceTimers)]
+    pub fn advance_timers(&mut self, elapsed_ms: f64) -> usize {
+        let report = self.engine.advance_timers(duration_from_ms(elapsed_ms));
+        for error in &report.errors {
+            log(error);
+        }
+        report.runs
+    }
+
     /// Get the version of the Aether engine
     #[wasm_bindgen]
     pub fn version() -> String {
@@ -57,10 +85,22 @@ impl Aether {
     }
 }
 
+/// Milliseconds from JavaScript (negative, NaN and infinite values are treated as 0)
+fn duration_from_ms(ms: f64) -> std::time::Duration {
+    if ms.is_finite() && ms > 0.0 {
+        std::time::Duration::from_secs_f64(ms / 1000.0)
+    } else {
+        std::time::Duration::ZERO
+    }
+}
+
 /// Convert Aether Value to JavaScript value
 fn value_to_js(value: &Value) -> JsValue {
     match value {
         Value::Number(n) => JsValue::from_f64(*n),
+        // JS numbers are exact only up to 2^53
+        Value::Int(i) if i.unsigned_abs() <= 1 << 53 => JsValue::from_f64(*i as f64),
+        Value::Int(i) => JsValue::from_str(&i.to_string()),
         Value::String(s) => JsValue::from_str(s),
         Value::Boolean(b) => JsValue::from_bool(*b),
         Value::Array(arr) => {
@@ -77,6 +117,21 @@ fn value_to_js(value: &Value) -> JsValue {
             }
             obj.into()
         }
+        Value::Set(set) => {
+            let js_arr = js_sys::Array::new();
+            for v in crate::value::set_elements(set) {
+                js_arr.push(&value_to_js(&v));
+            }
+            js_arr.into()
+        }
+        Value::Table(table) => {
+            let js_arr = js_sys::Array::new();
+            for row in table.to_dicts() {
+                js_arr.push(&value_to_js(&row));
+            }
+            js_arr.into()
+        }
+        Value::DateTime(dt) => JsValue::from_str(&dt.to_rfc3339()),
         Value::Null => JsValue::NULL,
         Value::Function { .. } => JsValue::from_str("<function>"),
         Value::BuiltIn { name, .. } => JsValue::from_str(&format!("<builtin: {}>", name)),
@@ -0,0 +1,206 @@
+// tests/analysis_tests.rs
+//! 静态分析（运行需求报告、调用图）测试
+
+use aether::analysis::{CallGraph, LintKind, Permission, analyze, call_graph, lint};
+use aether::{Aether, Dialect};
+
+#[test]
+fn pure_script_needs_nothing() {
+    let report = analyze("Func DOUBLE(X) { Return X * 2 }\nMAP([1, 2], DOUBLE)").unwrap();
+    assert!(report.permissions.is_empty());
+    assert_eq!(report.builtins, vec!["MAP"]);
+    assert!(report.unresolved.is_empty());
+    assert!(report.is_pure());
+}
+
+// HTTP_POST 只在启用 io 特性时注册
+#[cfg(feature = "io")]
+#[test]
+fn permissions_are_collected_from_nested_code() {
+    use aether::IOPermissions;
+
+    let code = r#"
+Func SYNC(PATH) {
+    Set HANDLER Lambda (BODY) -> HTTP_POST(ENDPOINT, BODY)
+    If (FILE_EXISTS(PATH)) { HANDLER(READ_FILE(PATH)) }
+}
+"#;
+    let report = analyze(code).unwrap();
+    assert_eq!(
+        report.permissions,
+        vec![Permission::Filesystem, Permission::Network]
+    );
+    assert_eq!(
+        report.builtins,
+        vec!["FILE_EXISTS", "HTTP_POST", "READ_FILE"]
+    );
+    assert_eq!(report.impure_builtins, report.builtins);
+    assert_eq!(report.unresolved, vec!["ENDPOINT"]);
+    assert!(!report.is_pure());
+
+    let fs_only = IOPermissions {
+        filesystem_enabled: true,
+        network_enabled: false,
+    };
+    assert_eq!(
+        report.missing_permissions(&fs_only),
+        vec![Permission::Network]
+    );
+    assert!(
+        report
+            .missing_permissions(&IOPermissions::allow_all())
+            .is_empty()
+    );
+}
+
+#[test]
+fn modules_and_extensions_are_listed() {
+    let report =
+        analyze("Import {PARSE} From \"./csv\"\nSet Q @SQL { SELECT 1 }\nPARSE(Q)").unwrap();
+    assert_eq!(report.modules, vec!["./csv"]);
+    assert_eq!(report.extensions, vec!["SQL"]);
+    assert!(report.unresolved.is_empty());
+}
+
+#[test]
+fn report_serializes_to_json() {
+    let report = analyze("PRINTLN(READ_FILE(\"a.txt\"))").unwrap();
+    let json = serde_json::to_value(&report).unwrap();
+    assert_eq!(json["permissions"], serde_json::json!(["filesystem"]));
+    assert_eq!(
+        json["impure_builtins"],
+        serde_json::json!(["PRINTLN", "READ_FILE"])
+    );
+    assert!(report.to_string().contains("permissions: filesystem"));
+}
+
+#[test]
+fn parse_errors_are_reported() {
+    assert!(analyze("Set (").is_err());
+}
+
+#[test]
+fn engine_analysis_uses_dialect_and_globals() {
+    let mut engine = Aether::new().with_dialect(Dialect::chinese());
+    engine.set_global("LIMIT", aether::Value::Number(3.0));
+    let report = engine
+        .analyze("设置 X READ_FILE(NAME)\n如果 (LEN(X) > LIMIT) { 真 }")
+        .unwrap();
+    assert_eq!(report.permissions, vec![Permission::Filesystem]);
+    assert_eq!(report.builtins, vec!["LEN", "READ_FILE"]);
+    assert_eq!(report.unresolved, vec!["NAME"]);
+}
+
+const PAYROLL: &str = r#"
+Func GROSS(BASE) { Return BASE + BONUS(BASE) }
+Func BONUS(BASE) { Return BASE * RATE }
+Func NET(BASE) {
+    Set G GROSS(BASE)
+    Return G - TAX(G)
+}
+Func TAX(AMOUNT) { Return ROUND(AMOUNT * 0.1) }
+Func LEGACY(X) { Return LEGACY_HELPER(X) }
+Func LEGACY_HELPER(X) { Return X }
+Set RATE 0.2
+Set RESULT MAP(SALARIES, NET)
+"#;
+
+#[test]
+fn call_graph_records_calls_builtins_and_variables() {
+    let graph = call_graph(PAYROLL).unwrap();
+    assert_eq!(graph.main.name, CallGraph::MAIN);
+    assert_eq!(graph.main.calls, vec!["NET"]);
+    assert_eq!(graph.main.builtins, vec!["MAP"]);
+    assert_eq!(graph.main.reads, vec!["SALARIES"]);
+    assert_eq!(graph.main.writes, vec!["RATE", "RESULT"]);
+
+    let net = graph.node("NET").unwrap();
+    assert_eq!(net.params, vec!["BASE"]);
+    assert_eq!(net.calls, vec!["GROSS", "TAX"]);
+    assert_eq!(net.writes, vec!["G"]);
+    assert!(net.reads.is_empty());
+
+    assert_eq!(graph.node("BONUS").unwrap().reads, vec!["RATE"]);
+    assert_eq!(graph.node("TAX").unwrap().builtins, vec!["ROUND"]);
+}
+
+#[test]
+fn unused_functions_and_reachability() {
+    let graph = call_graph(PAYROLL).unwrap();
+    assert_eq!(graph.unused_functions(), vec!["LEGACY", "LEGACY_HELPER"]);
+    assert_eq!(graph.callers("GROSS"), vec!["NET"]);
+    assert_eq!(
+        graph
+            .reachable_from(&["NET"])
+            .into_iter()
+            .collect::<Vec<_>>(),
+        vec!["BONUS", "GROSS", "TAX"]
+    );
+    assert_eq!(
+        graph
+            .builtins_reachable_from(&[CallGraph::MAIN])
+            .into_iter()
+            .collect::<Vec<_>>(),
+        vec!["MAP", "ROUND"]
+    );
+}
+
+#[test]
+fn recursion_and_redefinition() {
+    let graph =
+        call_graph("Func F(N) { Return F(N - 1) }\nFunc G() { Return 1 }\nFunc G() { Return 2 }")
+            .unwrap();
+    assert_eq!(graph.node("F").unwrap().calls, vec!["F"]);
+    assert_eq!(graph.functions.len(), 2);
+    assert_eq!(graph.unused_functions(), vec!["F", "G"]);
+}
+
+#[test]
+fn required_stdlib_modules() {
+    assert_eq!(
+        aether::stdlib::required_modules("STR_TRIM(X)").unwrap(),
+        vec!["string_utils"]
+    );
+    assert!(
+        aether::stdlib::required_modules("1 + 2")
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn engine_call_graph_uses_dialect() {
+    let engine = Aether::new().with_dialect(Dialect::chinese());
+    let graph = engine
+        .call_graph("函数 DOUBLE(X) { 返回 X * 2 }\nDOUBLE(3)")
+        .unwrap();
+    assert_eq!(graph.main.calls, vec!["DOUBLE"]);
+}
+
+#[test]
+fn lint_reports_undefined_unused_and_shadowed_names() {
+    let code = r#"
+Func MAIN_HELPER(X) { Return X + OFFSET }
+Func DEAD() { Return 0 }
+Set LEN 3
+PRINTLN(MAIN_HELPER(LEN))
+PRINTLN(STR_TRIM("  hi  "))
+"#;
+    let warnings = lint(code).unwrap();
+    let found: Vec<(LintKind, &str)> = warnings.iter().map(|w| (w.kind, w.name.as_str())).collect();
+    assert_eq!(
+        found,
+        vec![
+            (LintKind::UndefinedName, "OFFSET"),
+            (LintKind::UnusedFunction, "DEAD"),
+            (LintKind::ShadowedBuiltin, "LEN"),
+        ]
+    );
+    assert_eq!(warnings[1].to_string(), "function 'DEAD' is never used");
+}
+
+#[test]
+fn lint_is_quiet_for_clean_scripts() {
+    let code = "Func DOUBLE(X) { Return X * 2 }\nPRINTLN(MAP([1, 2], DOUBLE))";
+    assert!(lint(code).unwrap().is_empty());
+}
@@ -1,6 +1,6 @@
 use aether::{
-    Expr,
-    ast::{BinOp, UnaryOp},
+    Expr, Parser,
+    ast::{BinOp, UnaryOp, program_to_dot, program_to_json},
 };
 
 #[test]
@@ -52,3 +52,57 @@ fn test_unaryop_display() {
     assert_eq!(format!("{}", UnaryOp::Minus), "-");
     assert_eq!(format!("{}", UnaryOp::Not), "!");
 }
+
+#[test]
+fn test_expr_display_and_referenced_names() {
+    // (A + 1) * LEN(ITEMS[I])
+    let expr = Expr::binary(
+        Expr::binary(
+            Expr::Identifier("A".to_string()),
+            BinOp::Add,
+            Expr::Number(1.0),
+        ),
+        BinOp::Multiply,
+        Expr::call(
+            Expr::Identifier("LEN".to_string()),
+            vec![Expr::index(
+                Expr::Identifier("ITEMS".to_string()),
+                Expr::Identifier("I".to_string()),
+            )],
+        ),
+    );
+    assert_eq!(expr.to_string(), "(A + 1) * LEN(ITEMS[I])");
+    assert_eq!(expr.referenced_names(), vec!["A", "LEN", "ITEMS", "I"]);
+
+    let expr = Expr::binary(
+        Expr::Identifier("X".to_string()),
+        BinOp::NotEqual,
+        Expr::String("done".into()),
+    );
+    assert_eq!(expr.to_string(), "X != \"done\"");
+}
+
+#[test]
+fn test_program_to_json() {
+    let program = Parser::new("Set X (1 + 2)").parse_program().unwrap();
+    let json: serde_json::Value = serde_json::from_str(&program_to_json(&program)).unwrap();
+    assert_eq!(json[0]["Located"]["span"]["line"], 1);
+    let stmt = &json[0]["Located"]["stmt"];
+    assert_eq!(stmt["Set"]["name"], "X");
+    assert_eq!(stmt["Set"]["value"]["Binary"]["op"], "Add");
+    assert_eq!(stmt["Set"]["value"]["Binary"]["left"]["Integer"], 1);
+}
+
+#[test]
+fn test_program_to_dot() {
+    let program = Parser::new("Func F(A) { Return A * 2 }\nPRINTLN(F(\"hi\"))")
+        .parse_program()
+        .unwrap();
+    let dot = program_to_dot(&program);
+    assert!(dot.starts_with("digraph AST {"));
+    assert!(dot.trim_end().ends_with('}'));
+    assert!(dot.contains("FuncDef\\nname: F\\nparams: A"));
+    assert!(dot.contains("Binary\\nop: Multiply"));
+    assert!(dot.contains("[label=\"String\\nhi\"]"));
+    assert!(dot.contains("[label=\"body[0]\"]"));
+}
@@ -95,28 +95,30 @@ fn test_big_integer_division() {
 fn test_small_numbers_still_use_float() {
     let mut engine = Aether::new();
 
-    // 小数字应该仍然使用 f64
+    // 小整数使用 i64 整数，带小数点的仍然使用 f64
     let result = engine.eval("(123456 * 789012)").unwrap();
+    assert!(matches!(result, Value::Int(97408265472)), "{:?}", result);
 
-    match result {
-        Value::Number(n) => {
-            assert_eq!(n, 97408265472.0);
-        }
-        _ => panic!("Expected Number for small integers, got {:?}", result),
-    }
+    let result = engine.eval("(123456.0 * 789012)").unwrap();
+    assert!(
+        matches!(result, Value::Number(n) if n == 97408265472.0),
+        "{:?}",
+        result
+    );
 }
 
 #[test]
 fn test_bigint_threshold() {
     let mut engine = Aether::new();
 
-    // 15位数字应该还是用浮点数
-    let result1 = engine.eval("(123456789012345 * 2)").unwrap();
-    assert!(matches!(result1, Value::Number(_)));
+    // i64 范围内保持整数
+    let result1 = engine.eval("(1234567890123456 * 2)").unwrap();
+    assert!(matches!(result1, Value::Int(2469135780246912)));
 
-    // 16位以上应该用大整数
-    let result2 = engine.eval("(1234567890123456 * 2)").unwrap();
+    // 超出 i64 范围自动转为大整数
+    let result2 = engine.eval("(9223372036854775807 * 2)").unwrap();
     assert!(matches!(result2, Value::Fraction(_)));
+    assert_eq!(result2.to_string(), "18446744073709551614");
 }
 
 #[test]
@@ -139,3 +141,160 @@ fn test_mixed_bigint_operations() {
         _ => panic!("Expected Fraction, got {:?}", result),
     }
 }
+
+#[test]
+fn test_big_integer_modulo_and_comparison() {
+    let mut engine = Aether::new();
+    engine
+        .eval(
+            "Set A 123456789012345678901234567891
+         Set B 1000000000000000000000",
+        )
+        .unwrap();
+
+    assert_eq!(engine.eval("(A % 7)").unwrap().to_string(), "1");
+    assert_eq!(
+        engine.eval("(A % B)").unwrap().to_string(),
+        "12345678901234567891"
+    );
+    assert_eq!(
+        engine.eval("((0 - A) % B)").unwrap().to_string(),
+        "-12345678901234567891"
+    );
+    assert!(engine.eval("(A % 0)").is_err());
+
+    assert_eq!(engine.eval("(A > B)").unwrap(), Value::Boolean(true));
+    assert_eq!(engine.eval("(5 < A)").unwrap(), Value::Boolean(true));
+    assert_eq!(engine.eval("(A >= 5.5)").unwrap(), Value::Boolean(true));
+    assert_eq!(
+        engine.eval("(TO_FRACTION(2) == 2)").unwrap(),
+        Value::Boolean(true)
+    );
+}
+
+#[test]
+fn test_big_integer_pow_and_factorial() {
+    let mut engine = Aether::new();
+
+    assert_eq!(
+        engine.eval("POW(2, 100)").unwrap().to_string(),
+        "1267650600228229401496703205376"
+    );
+    // 结果在安全整数范围内时仍为 Number
+    assert_eq!(engine.eval("POW(2, 10)").unwrap(), Value::Number(1024.0));
+    assert_eq!(
+        engine
+            .eval("POW(TO_FRACTION(2) / 3, 3)")
+            .unwrap()
+            .to_string(),
+        "8/27"
+    );
+    assert_eq!(
+        engine
+            .eval("POW(1000000000000000000000, 2)")
+            .unwrap()
+            .to_string(),
+        "1000000000000000000000000000000000000000000"
+    );
+    // 大于 i64 的 Number 与 Fraction 混合运算不截断
+    assert_eq!(
+        engine
+            .eval("(POW(10, 20) * TO_FRACTION(3))")
+            .unwrap()
+            .to_string(),
+        "300000000000000000000"
+    );
+
+    let result = engine.eval("FACTORIAL(171)").unwrap();
+    let text = result.to_string();
+    assert!(matches!(result, Value::Fraction(_)));
+    assert_eq!(text.len(), 310);
+    assert!(text.starts_with("1241018070217667823424840524103103992616605577501693185388951803611996075221691752992751978120487585576464959501670387052809889858690710767331242032218484364310473577889968548278290754541561964852153468318044293239598173696899657235903947616152278558180061176365108428800000000000000000000000000000000000000000"));
+    assert_eq!(engine.eval("FACTORIAL(5)").unwrap(), Value::Number(120.0));
+
+    assert_eq!(
+        engine
+            .eval("ABS(0 - 99999999999999999999)")
+            .unwrap()
+            .to_string(),
+        "99999999999999999999"
+    );
+}
+
+#[test]
+fn test_number_theory_builtins() {
+    let mut engine = Aether::new();
+    let eval = |engine: &mut Aether, code: &str| engine.eval(code).unwrap().to_string();
+
+    assert_eq!(eval(&mut engine, "GCD(12, 18)"), "6");
+    assert_eq!(eval(&mut engine, "LCM(4, 6)"), "12");
+    assert_eq!(
+        eval(
+            &mut engine,
+            "GCD(123456789012345678901234567890, 987654321098765432109876543210)"
+        ),
+        "9000000000900000000090"
+    );
+    assert_eq!(
+        eval(&mut engine, "LCM(10000000000000000000, 15)"),
+        "30000000000000000000"
+    );
+    assert_eq!(
+        eval(&mut engine, "GCD(TO_FRACTION(1) / 2, TO_FRACTION(3) / 4)"),
+        "1/4"
+    );
+    assert_eq!(
+        eval(&mut engine, "LCM(TO_FRACTION(1) / 2, TO_FRACTION(3) / 4)"),
+        "3/2"
+    );
+    assert!(engine.eval("GCD(4.5, 3)").is_err());
+
+    assert_eq!(eval(&mut engine, "MODPOW(4, 13, 497)"), "445");
+    assert_eq!(
+        eval(&mut engine, "MODPOW(2, 1000000, 1000000007)"),
+        "235042059"
+    );
+    assert!(engine.eval("MODPOW(2, 3, 0)").is_err());
+    assert!(engine.eval("MODPOW(2, -1, 5)").is_err());
+
+    assert_eq!(eval(&mut engine, "ISQRT(17)"), "4");
+    assert_eq!(
+        eval(&mut engine, "ISQRT(1000000000000000000000000)"),
+        "1000000000000"
+    );
+    assert!(engine.eval("ISQRT(-1)").is_err());
+
+    assert_eq!(eval(&mut engine, "PRIME_TEST(97)"), "true");
+    assert_eq!(eval(&mut engine, "PRIME_TEST(91)"), "false");
+    assert_eq!(eval(&mut engine, "PRIME_TEST(1)"), "false");
+    // 2^61 - 1 是梅森素数，3215031751 是强伪素数（底数 2、3、5、7）
+    assert_eq!(eval(&mut engine, "PRIME_TEST(2305843009213693951)"), "true");
+    assert_eq!(eval(&mut engine, "PRIME_TEST(3215031751)"), "false");
+}
+
+#[test]
+fn test_big_integer_negation() {
+    let mut engine = Aether::new();
+
+    let negated = engine
+        .eval("Set B 123456789012345678901234567890\n-B")
+        .unwrap();
+    assert_eq!(negated, engine.eval("0 - B").unwrap());
+    match negated {
+        Value::Fraction(frac) => {
+            assert_eq!(frac.numer().to_string(), "-123456789012345678901234567890");
+            assert_eq!(frac.denom().to_string(), "1");
+        }
+        other => panic!("Expected Fraction, got {:?}", other),
+    }
+
+    // i64::MIN 的绝对值超出 Int 范围，取反后仍然精确
+    match engine.eval("-9223372036854775808").unwrap() {
+        Value::Fraction(frac) => assert_eq!(frac.numer().to_string(), "-9223372036854775808"),
+        other => panic!("Expected Fraction, got {:?}", other),
+    }
+    assert_eq!(
+        engine.eval("-(-9223372036854775807 - 1)").unwrap(),
+        engine.eval("9223372036854775807 + 1").unwrap()
+    );
+}
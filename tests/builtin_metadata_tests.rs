@@ -0,0 +1,145 @@
+// tests/builtin_metadata_tests.rs
+//! 内置函数元数据（分类、权限、纯度、参数个数范围）测试
+
+use aether::analysis::{Permission, analyze};
+use aether::builtins::{BuiltInRegistry, IOPermissions};
+use aether::{Aether, Value};
+use std::rc::Rc;
+
+#[test]
+fn every_builtin_has_a_category() {
+    let registry = BuiltInRegistry::with_permissions(IOPermissions::allow_all());
+    let uncategorized: Vec<String> = registry
+        .metadata()
+        .into_iter()
+        .filter(|meta| meta.category.is_none())
+        .map(|meta| meta.name)
+        .collect();
+    assert!(uncategorized.is_empty(), "{:?}", uncategorized);
+}
+
+#[test]
+fn metadata_is_sorted_and_matches_registered_functions() {
+    let registry = BuiltInRegistry::new();
+    let metadata = registry.metadata();
+    let names: Vec<&str> = metadata.iter().map(|meta| meta.name.as_str()).collect();
+    let mut sorted = names.clone();
+    sorted.sort();
+    assert_eq!(names, sorted);
+    assert_eq!(names.len(), registry.names().len());
+    // 未授予权限的 IO 函数不出现
+    assert!(!names.contains(&"READ_FILE"));
+    assert!(registry.metadata_for("READ_FILE").is_none());
+}
+
+#[test]
+fn argument_ranges() {
+    let registry = BuiltInRegistry::new();
+
+    let range = registry.metadata_for("RANGE").unwrap();
+    assert_eq!((range.min_args, range.max_args), (1, Some(3)));
+    assert!(range.accepts(2));
+    assert!(!range.accepts(0));
+    assert!(!range.accepts(4));
+
+    let set = registry.metadata_for("SET").unwrap();
+    assert_eq!((set.min_args, set.max_args), (0, None));
+    assert!(set.accepts(100));
+
+    let sqrt = registry.metadata_for("SQRT").unwrap();
+    assert_eq!((sqrt.min_args, sqrt.max_args), (1, Some(1)));
+    assert_eq!(sqrt.signature, registry.signature("SQRT").unwrap());
+}
+
+#[test]
+fn permissions_and_purity() {
+    let registry = BuiltInRegistry::with_permissions(IOPermissions::allow_all());
+
+    let read = registry.metadata_for("READ_FILE").unwrap();
+    assert_eq!(read.permission, Some(Permission::Filesystem));
+    assert!(!read.pure);
+    assert_eq!(
+        registry.metadata_for("KV_GET").unwrap().permission,
+        Some(Permission::Filesystem)
+    );
+
+    // 启用文件系统后会换成支持文件选项的实现，但不需要权限也能调用
+    let plot = registry.metadata_for("PLOT_LINE").unwrap();
+    assert_eq!(plot.permission, None);
+
+    let sqrt = registry.metadata_for("SQRT").unwrap();
+    assert!(sqrt.pure);
+    assert_eq!(sqrt.category, Some("数学函数 - 基础"));
+    assert!(!registry.metadata_for("NOW").unwrap().pure);
+    assert!(!registry.metadata_for("PRINTLN").unwrap().pure);
+
+    if cfg!(feature = "io") {
+        let http = registry.metadata_for("HTTP_POST").unwrap();
+        assert_eq!(http.permission, Some(Permission::Network));
+        assert_eq!((http.min_args, http.max_args), (2, Some(3)));
+    }
+}
+
+#[test]
+fn analyzer_uses_registry_permissions() {
+    let report = analyze(r#"Set DB KV_OPEN("data.json")"#).unwrap();
+    assert_eq!(report.permissions, vec![Permission::Filesystem]);
+    assert_eq!(Permission::required_by("PLOT_BAR"), None);
+    assert_eq!(Permission::required_by("NOT_A_BUILTIN"), None);
+}
+
+#[test]
+fn docs_deprecation_and_natives() {
+    let mut registry = BuiltInRegistry::new();
+    registry.deprecate("STRLEN", Some("LEN"));
+    registry.mark_experimental("GAMMA", "special-math");
+    registry.register_native("HOST_LOOKUP", Rc::new(|_args: &[Value]| Ok(Value::Null)), 2);
+
+    let fraction = registry.metadata_for("TO_FRACTION").unwrap();
+    assert!(fraction.summary.is_some());
+    assert_eq!(fraction.doc_link.as_deref(), Some(r#"DOC("TO_FRACTION")"#));
+
+    let strlen = registry.metadata_for("STRLEN").unwrap();
+    assert_eq!(
+        strlen.deprecation.unwrap().replacement.as_deref(),
+        Some("LEN")
+    );
+    assert_eq!(
+        registry
+            .metadata_for("GAMMA")
+            .unwrap()
+            .experimental
+            .as_deref(),
+        Some("special-math")
+    );
+
+    let native = registry.metadata_for("HOST_LOOKUP").unwrap();
+    assert!(native.native);
+    assert!(!native.pure);
+    assert_eq!(native.category, None);
+    assert_eq!((native.min_args, native.max_args), (2, Some(2)));
+}
+
+#[test]
+fn engine_exposes_metadata_as_json() {
+    let engine = Aether::new();
+    let metadata = engine.builtin_metadata();
+    let json = serde_json::to_value(&metadata).unwrap();
+    let sqrt = json
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|meta| meta["name"] == "SQRT")
+        .unwrap();
+    assert_eq!(sqrt["pure"], true);
+    assert_eq!(sqrt["permission"], serde_json::Value::Null);
+    assert_eq!(sqrt["min_args"], 1);
+}
+
+#[test]
+fn help_skips_categories_without_documented_functions() {
+    let mut engine = Aether::new();
+    let listing = engine.eval("HELP()").unwrap().to_string();
+    assert!(listing.contains("【精确计算】"));
+    assert!(!listing.contains("【薪资 - 考勤】"));
+}
@@ -9,7 +9,7 @@
 //! - 基础数学函数测试
 //! - 字典操作函数测试
 
-use aether::builtins::{array, dict, io, math, string, types};
+use aether::builtins::{args, array, dict, help, io, math, string, types};
 use aether::value::Value;
 
 // ============================================================================
@@ -174,6 +174,43 @@ fn test_sort() {
     );
 }
 
+fn nums(values: &[f64]) -> Value {
+    Value::Array(values.iter().map(|n| Value::Number(*n)).collect())
+}
+
+#[test]
+fn test_bsearch_and_bounds() {
+    let arr = nums(&[1.0, 2.0, 2.0, 3.0, 7.0]);
+    let call =
+        |f: fn(&[Value]) -> Result<Value, _>, x: f64| f(&[arr.clone(), Value::Number(x)]).unwrap();
+
+    assert_eq!(call(array::bsearch, 2.0), Value::Number(1.0));
+    assert_eq!(call(array::bsearch, 7.0), Value::Number(4.0));
+    assert_eq!(call(array::bsearch, 5.0), Value::Number(-1.0));
+    assert_eq!(call(array::lower_bound, 2.0), Value::Number(1.0));
+    assert_eq!(call(array::upper_bound, 2.0), Value::Number(3.0));
+    assert_eq!(call(array::lower_bound, 9.0), Value::Number(5.0));
+    assert_eq!(call(array::upper_bound, 0.0), Value::Number(0.0));
+    assert_eq!(
+        call(array::insert_sorted, 4.0),
+        nums(&[1.0, 2.0, 2.0, 3.0, 4.0, 7.0])
+    );
+    assert_eq!(
+        array::insert_sorted(&[Value::Array(vec![]), Value::Number(1.0)]).unwrap(),
+        nums(&[1.0])
+    );
+
+    let words = Value::Array(vec![
+        Value::String("apple".to_string()),
+        Value::String("kiwi".to_string()),
+    ]);
+    assert_eq!(
+        array::bsearch(&[words.clone(), Value::String("kiwi".to_string())]).unwrap(),
+        Value::Number(1.0)
+    );
+    assert!(array::bsearch(&[words, Value::Number(1.0)]).is_err());
+}
+
 #[test]
 fn test_sum() {
     let arr = Value::Array(vec![
@@ -295,6 +332,71 @@ fn test_repeat() {
     );
 }
 
+fn str_value(s: &str) -> Value {
+    Value::String(s.to_string())
+}
+
+#[test]
+fn test_levenshtein() {
+    let distance = |a: &str, b: &str| string::levenshtein(&[str_value(a), str_value(b)]).unwrap();
+    assert_eq!(distance("kitten", "sitting"), Value::Number(3.0));
+    assert_eq!(distance("", "abc"), Value::Number(3.0));
+    assert_eq!(distance("same", "same"), Value::Number(0.0));
+    assert_eq!(distance("北京市", "北京"), Value::Number(1.0));
+}
+
+#[test]
+fn test_jaro_winkler() {
+    let similarity =
+        |a: &str, b: &str| match string::jaro_winkler(&[str_value(a), str_value(b)]).unwrap() {
+            Value::Number(n) => n,
+            other => panic!("Expected number, got {:?}", other),
+        };
+    assert!((similarity("MARTHA", "MARHTA") - 0.961111).abs() < 1e-4);
+    assert!((similarity("DIXON", "DICKSONX") - 0.813333).abs() < 1e-4);
+    assert_eq!(similarity("abc", "xyz"), 0.0);
+    assert_eq!(similarity("", ""), 1.0);
+}
+
+#[test]
+fn test_soundex() {
+    let code = |w: &str| string::soundex(&[str_value(w)]).unwrap();
+    assert_eq!(code("Robert"), str_value("R163"));
+    assert_eq!(code("Rupert"), str_value("R163"));
+    assert_eq!(code("Ashcraft"), str_value("A261"));
+    assert_eq!(code("Tymczak"), str_value("T522"));
+    assert_eq!(code("Pfister"), str_value("P236"));
+    assert_eq!(code("Lee"), str_value("L000"));
+    assert_eq!(code("123"), str_value(""));
+}
+
+#[test]
+fn test_fuzzy_match() {
+    let haystack = Value::Array(vec![
+        str_value("Jonathan"),
+        str_value("Mary"),
+        Value::Number(1.0),
+        str_value("Johnathan"),
+    ]);
+    let hits = string::fuzzy_match(&[str_value("Jonathon"), haystack.clone(), Value::Number(0.8)])
+        .unwrap();
+    let Value::Array(hits) = hits else {
+        panic!("Expected array");
+    };
+    let values: Vec<String> = hits
+        .iter()
+        .map(|hit| match hit {
+            Value::Dict(d) => d["value"].to_string(),
+            other => panic!("Expected dict, got {:?}", other),
+        })
+        .collect();
+    assert_eq!(values, vec!["Jonathan", "Johnathan"]);
+
+    assert!(string::fuzzy_match(&[str_value("x"), haystack.clone(), Value::Number(2.0)]).is_err());
+    let none = string::fuzzy_match(&[str_value("zzz"), haystack]).unwrap();
+    assert_eq!(none, Value::Array(Vec::new()));
+}
+
 #[test]
 fn test_split() {
     let s = Value::String("a,b,c".to_string());
@@ -373,6 +475,61 @@ fn test_pow() {
 // 字典函数测试
 // ============================================================================
 
+#[test]
+fn test_arg_spec_validation() {
+    let err = math::abs(&[Value::String("x".to_string())]).unwrap_err();
+    assert!(err.to_string().contains("parameter 'x' of ABS"), "{}", err);
+    assert!(math::pow(&[Value::Number(2.0)]).is_err());
+    assert!(math::abs(&[Value::Number(1.0), Value::Number(2.0)]).is_err());
+
+    let spec = args::spec_for("POW").unwrap();
+    assert_eq!(
+        spec.signature(),
+        "POW(base: Number|Fraction, exponent: Number|Fraction)"
+    );
+    #[cfg(feature = "payroll")]
+    assert_eq!(
+        args::spec_for("CALC_HOURLY_PAY").unwrap().signature(),
+        "CALC_HOURLY_PAY(monthly_salary: Number|Fraction, monthly_hours?: Number|Fraction)"
+    );
+
+    match help::help(&[Value::String("abs".to_string())]).unwrap() {
+        Value::String(text) => assert!(text.contains("ABS(x: Number|Fraction)"), "{}", text),
+        other => panic!("unexpected {:?}", other),
+    }
+}
+
+#[test]
+fn test_doc_structured() {
+    let Value::Dict(doc) = help::doc(&[Value::String("pow".to_string())]).unwrap() else {
+        panic!("DOC should return a dict");
+    };
+    assert_eq!(doc["name"], Value::String("POW".to_string()));
+    assert_eq!(
+        doc["category"],
+        Value::String("数学函数 - 基础".to_string())
+    );
+    assert_eq!(
+        doc["signature"],
+        Value::String("POW(base: Number|Fraction, exponent: Number|Fraction)".to_string())
+    );
+    let Value::Array(params) = &doc["params"] else {
+        panic!("params should be an array");
+    };
+    assert_eq!(params.len(), 2);
+    let Value::Dict(first) = &params[0] else {
+        panic!("param should be a dict");
+    };
+    assert_eq!(first["type"], Value::String("Number|Fraction".to_string()));
+    assert!(matches!(&doc["examples"], Value::Array(lines) if !lines.is_empty()));
+
+    assert_eq!(
+        help::doc(&[Value::String("NO_SUCH_FN".to_string())]).unwrap(),
+        Value::Null
+    );
+    assert!(matches!(help::doc(&[]).unwrap(), Value::Array(names) if !names.is_empty()));
+}
+
 #[test]
 fn test_keys() {
     use std::collections::HashMap;
@@ -406,3 +563,12 @@ fn test_has() {
         Value::Boolean(false)
     );
 }
+
+#[test]
+fn test_registry_follows_enabled_features() {
+    use aether::builtins::{BuiltInRegistry, IOPermissions};
+    let registry = BuiltInRegistry::with_permissions(IOPermissions::allow_all());
+    assert!(registry.has("SUM"));
+    assert_eq!(registry.has("CALC_HOURLY_PAY"), cfg!(feature = "payroll"));
+    assert_eq!(registry.has("HTTP_GET"), cfg!(feature = "io"));
+}
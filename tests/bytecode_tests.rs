@@ -0,0 +1,145 @@
+// tests/bytecode_tests.rs
+//! 字节码后端测试：编译结果、与解释器的一致性、错误位置和执行限制
+
+use aether::compile::{Op, compile};
+use aether::{Aether, Backend, ExecutionLimits, Parser, Value};
+
+fn run(backend: Backend, code: &str) -> Result<Value, String> {
+    Aether::new().with_execution_backend(backend).eval(code)
+}
+
+/// 两种后端的结果（包括错误信息）必须完全相同
+fn assert_same(code: &str) -> Result<Value, String> {
+    let interpreted = run(Backend::Interpreter, code);
+    let compiled = run(Backend::Bytecode, code);
+    assert_eq!(interpreted, compiled, "backends disagree on:\n{}", code);
+    compiled
+}
+
+#[test]
+fn loops_and_assignments_compile_without_fallback() {
+    let program = Parser::new(
+        "Set TOTAL 0\nFor I In [1, 2, 3] {\n    If (I % 2 == 0) { Continue }\n    Set TOTAL TOTAL + I\n}\nTOTAL",
+    )
+    .parse_program()
+    .unwrap();
+    let chunk = compile(&program);
+    assert_eq!(chunk.fallback_count(), 0, "{:#?}", chunk.ops());
+    assert!(chunk.ops().contains(&Op::Continue));
+
+    // 函数定义等其他语句交给解释器执行
+    let program = Parser::new("Func F(X) { Return X }\nF(1)")
+        .parse_program()
+        .unwrap();
+    assert_eq!(compile(&program).fallback_count(), 1);
+}
+
+#[test]
+fn backend_is_configurable() {
+    let mut engine = Aether::new();
+    assert_eq!(engine.execution_backend(), Backend::Interpreter);
+    engine.set_execution_backend(Backend::Bytecode);
+    assert_eq!(engine.execution_backend(), Backend::Bytecode);
+    assert_eq!(engine.eval("Set X 2\nX * 21").unwrap(), Value::Int(42));
+}
+
+#[test]
+fn programs_evaluate_identically() {
+    let scripts = [
+        "Set I 0\nWhile (I < 10) {\n    Set I I + 1\n}\nI",
+        // 循环的值是最后执行的语句的值，未执行时为 Null
+        "While (False) { 1 }",
+        "Set X 0\nWhile (X < 3) { Set X X + 1\n X * 10 }",
+        "If (1 > 2) { \"a\" } Elif (2 > 1) { \"b\" } Else { \"c\" }",
+        "If (False) { 1 }",
+        "If (True) { }",
+        "Set R []\nFor I, X In [\"a\", \"b\"] { Set R PUSH(R, [I, X]) }\nR",
+        "Set R []\nFor X In SET([3, 1, 2]) { Set R PUSH(R, X) }\nR",
+        "Set OUT 0\nFor I In RANGE(0, 10) {\n    For J In RANGE(0, 10) {\n        If (J > I) { Break }\n        Set OUT OUT + 1\n    }\n}\nOUT",
+        "Set D {\"a\": [1, {\"b\": 2}]}\nD[\"a\"][1][\"b\"] + LEN(D)",
+        "Set A False\n[A && MISSING, True || MISSING, 0 || \"x\"]",
+        "Func SQUARE(N) { Return N * N }\nMAP([1, 2, 3], SQUARE)",
+        "Set ADD Lambda(A, B) -> A + B\nADD(2, 3)",
+        "Set XS [1, 2, 3]\nSet XS[0] 10\nXS",
+        // 解释器执行的 Try 中的 Break 作用于字节码循环
+        "Set N 0\nWhile (True) {\n    Set N N + 1\n    Try {\n        If (N == 3) { Break }\n    } Catch (E) { 0 }\n}\nN",
+        "Set N 0\nFor X In [1, 2, 3, 4] {\n    Switch (X) { Case 2: Continue }\n    Set N N + X\n}\nN",
+        "Func F() {\n    For X In [1, 2, 3] {\n        If (X == 2) { Return X }\n    }\n}\nF()",
+        "9223372036854775807 + 1",
+        "1 / 3 + 0.5",
+    ];
+    for code in scripts {
+        if let Err(err) = assert_same(code) {
+            panic!("{} failed: {}", code, err);
+        }
+    }
+}
+
+#[test]
+fn errors_report_the_same_message_and_position() {
+    let scripts = [
+        "Set A 1\nSet B 2\nSet C A / 0",
+        "Set I 0\nWhile (I < 5) {\n    Set I I + 1\n    If (I == 3) {\n        Set X MISSING\n    }\n}",
+        "For X In 5 { X }",
+        "Func INNER(X) {\n    Return X + MISSING\n}\nSet Y 1\nINNER(Y)",
+        "[1, 2][5]",
+        "Break",
+        "Return 5\n6",
+    ];
+    for code in scripts {
+        let result = assert_same(code);
+        assert!(result.is_err(), "{} should fail", code);
+    }
+
+    let err = run(
+        Backend::Bytecode,
+        "Set I 0\nWhile (I < 5) {\n    Set I I + 1\n    If (I == 3) {\n        Set X MISSING\n    }\n}",
+    )
+    .unwrap_err();
+    assert!(err.contains("at line 5, column 9"), "{}", err);
+}
+
+#[test]
+fn limits_and_strict_mode_apply_to_compiled_code() {
+    let limits = ExecutionLimits {
+        max_steps: Some(100),
+        ..ExecutionLimits::default()
+    };
+    let err = Aether::new()
+        .with_limits(limits)
+        .with_execution_backend(Backend::Bytecode)
+        .eval("While (True) { Set X 1 }")
+        .unwrap_err();
+    assert!(err.to_lowercase().contains("step limit"), "{}", err);
+
+    let limits = ExecutionLimits {
+        max_loop_iterations: Some(10),
+        ..ExecutionLimits::default()
+    };
+    let err = Aether::new()
+        .with_limits(limits)
+        .with_execution_backend(Backend::Bytecode)
+        .eval("Set I 0\nWhile (I < 100) { Set I I + 1 }")
+        .unwrap_err();
+    assert!(err.contains("While (I < 100)"), "{}", err);
+
+    let err = Aether::new()
+        .with_strict(true)
+        .with_execution_backend(Backend::Bytecode)
+        .eval("While (1) { Break }")
+        .unwrap_err();
+    assert!(err.contains("While condition must be Boolean"), "{}", err);
+}
+
+#[test]
+fn variables_persist_across_evaluations() {
+    let mut engine = Aether::new().with_execution_backend(Backend::Bytecode);
+    engine
+        .eval("Set COUNT 0\nFunc BUMP() { Set COUNT COUNT + 1\nReturn COUNT }")
+        .unwrap();
+    engine
+        .eval("For I In RANGE(0, 5) { Set COUNT COUNT + I }")
+        .unwrap();
+    assert_eq!(engine.eval("COUNT").unwrap(), Value::Int(10));
+    assert_eq!(engine.eval("I").unwrap(), Value::Number(4.0));
+}
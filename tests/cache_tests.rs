@@ -1,4 +1,5 @@
-use aether::{ASTCache, Program};
+use aether::{ASTCache, Aether, Expr, Parser, Program, Stmt, Value};
+use std::sync::Arc;
 
 #[test]
 fn test_cache_basic() {
@@ -30,3 +31,113 @@ fn test_cache_capacity() {
     // 缓存大小应该被限制
     assert!(cache.stats().size <= 5);
 }
+
+fn parse(code: &str) -> Program {
+    Parser::new(code).parse_program().unwrap()
+}
+
+#[test]
+fn test_cache_shares_identical_statements() {
+    let preamble = "Func TAX(X) {\n    Return X * 0.2\n}\nSet RATE 0.2\n";
+    let mut cache = ASTCache::new();
+    let mut programs = Vec::new();
+    for i in 0..50 {
+        let code = format!("{}TAX({})", preamble, i);
+        programs.push(cache.insert(&code, parse(&code)));
+    }
+
+    let stats = cache.stats();
+    assert_eq!(stats.size, 50);
+    // 每个程序：函数定义、函数体中的 Return、Set、调用
+    assert_eq!(stats.statements, 200);
+    // 三条共享的前导语句 + 50 条不同的调用
+    assert_eq!(stats.unique_statements, 53);
+    assert!(Arc::ptr_eq(node(&programs[0][0]), node(&programs[49][0])));
+    assert!(!Arc::ptr_eq(node(&programs[0][2]), node(&programs[49][2])));
+
+    // get 仍然返回完整的独立副本
+    let code = format!("{}TAX(3)", preamble);
+    assert_eq!(cache.get(&code).unwrap(), parse(&code));
+}
+
+/// 缓存语句中（可能共享的）语句节点
+fn node(stmt: &Stmt) -> &Arc<Stmt> {
+    match stmt {
+        Stmt::Located { stmt, .. } => stmt,
+        other => panic!("expected a located statement, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_cache_shares_statements_at_shifted_offsets() {
+    let mut cache = ASTCache::new();
+    let mut programs = Vec::new();
+    for prefix in ["\"a\"", "\"abcd\"", "\"abcdefghij\""] {
+        let code = format!(
+            "Set LABEL {}\nFunc TAX(X) {{\n    Return X * 0.2\n}}\nSet RATE 0.2",
+            prefix
+        );
+        programs.push(cache.insert(&code, parse(&code)));
+    }
+
+    let stats = cache.stats();
+    assert_eq!(stats.statements, 12);
+    // 三个不同的 LABEL + 三个函数定义（函数体位置不同）+ 共享的 Return + RATE
+    assert_eq!(stats.unique_statements, 8, "{}", stats);
+    assert!(Arc::ptr_eq(node(&programs[0][2]), node(&programs[2][2])));
+    // 函数体内的语句同样共享，位置信息保留在各自的程序中
+    let Stmt::FuncDef { body: first, .. } = node(&programs[0][1]).as_ref() else {
+        panic!("expected a function definition");
+    };
+    let Stmt::FuncDef { body: last, .. } = node(&programs[2][1]).as_ref() else {
+        panic!("expected a function definition");
+    };
+    assert!(Arc::ptr_eq(node(&first[0]), node(&last[0])));
+    assert_ne!(first[0].span(), last[0].span());
+    assert_eq!(programs[2][2].span().unwrap().line, 5);
+}
+
+#[test]
+fn test_cache_shares_string_literals() {
+    let mut cache = ASTCache::new();
+    let text = |program: &[Stmt], index: usize| match node(&program[index]).as_ref() {
+        Stmt::Set {
+            value: Expr::String(text),
+            ..
+        } => Arc::clone(text),
+        other => panic!("expected a string assignment, got {:?}", other),
+    };
+    let first = cache.insert("A", parse("Set A \"payroll\"\nSet B 1"));
+    let second = cache.insert("B", parse("Set B 2\nSet C \"payroll\""));
+    assert!(Arc::ptr_eq(&text(&first, 0), &text(&second, 1)));
+}
+
+#[test]
+fn test_cache_releases_evicted_statements() {
+    let mut cache = ASTCache::with_capacity(10);
+    for i in 0..100 {
+        let code = format!("Set X{} {}", i, i);
+        cache.insert(&code, parse(&code));
+    }
+    let stats = cache.stats();
+    assert!(stats.size <= 10);
+    assert_eq!(stats.unique_statements, stats.statements);
+
+    cache.clear();
+    assert_eq!(cache.stats().unique_statements, 0);
+}
+
+#[test]
+fn test_engine_evaluates_shared_programs() {
+    let mut engine = Aether::new();
+    for i in 0..5 {
+        let code = format!("Func DOUBLE(X) {{ Return X * 2 }}\nDOUBLE({})", i);
+        assert_eq!(engine.eval(&code).unwrap(), Value::Number((i * 2) as f64));
+        // 命中缓存时结果相同
+        assert_eq!(engine.eval(&code).unwrap(), Value::Number((i * 2) as f64));
+    }
+    let stats = engine.cache_stats();
+    assert_eq!(stats.hits, 5);
+    // 函数定义和函数体共享，5 条调用各不相同
+    assert_eq!(stats.unique_statements, 7);
+}
@@ -1,4 +1,4 @@
-use aether::Aether;
+use aether::{Aether, Evaluator, Parser};
 
 #[test]
 fn call_stack_includes_builtins_and_function_signature() {
@@ -32,3 +32,51 @@ MAP(A, BAD)
     // User function signature (name + params)
     assert!(err.contains("BAD(X)"), "unexpected error: {err}");
 }
+
+#[test]
+fn backtrace_lists_each_call_with_its_call_site() {
+    let mut engine = Aether::new();
+
+    let err = engine
+        .eval(
+            r#"Func INNER(X) {
+    Return (X / 0)
+}
+Func OUTER(X) {
+    Set Y X + 1
+    Return INNER(Y)
+}
+OUTER(1)"#,
+        )
+        .unwrap_err();
+
+    assert!(
+        err.contains("Call stack:\n  #0 OUTER(X) called at line 8\n  #1 INNER(X) called at line 6"),
+        "unexpected error: {err}"
+    );
+}
+
+#[test]
+fn with_trace_exposes_the_frames() {
+    let code = r#"Func FIRST() {
+    Return 1
+}
+Func FAIL(N) {
+    Return N + MISSING
+}
+Set A FIRST() + FAIL(2)"#;
+    let program = Parser::new(code).parse_program().unwrap();
+    let err = Evaluator::new().eval_program(&program).unwrap_err();
+
+    // FIRST 返回后，FAIL 的调用位置仍是同一条语句
+    let trace = err.with_trace();
+    assert_eq!(trace.len(), 1);
+    assert_eq!(trace[0].name, "FAIL");
+    assert_eq!(trace[0].signature, "FAIL(N)");
+    assert_eq!(trace[0].line, Some(7));
+
+    // 顶层错误没有调用栈
+    let program = Parser::new("1 / 0").parse_program().unwrap();
+    let err = Evaluator::new().eval_program(&program).unwrap_err();
+    assert!(err.with_trace().is_empty());
+}
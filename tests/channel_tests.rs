@@ -0,0 +1,191 @@
+// tests/channel_tests.rs
+//! CHANNEL / SEND / RECV 测试
+
+use aether::{Aether, ExecutionLimits, Value};
+use std::time::{Duration, Instant};
+
+#[test]
+fn channel_is_fifo_within_one_engine() {
+    let mut engine = Aether::new();
+    let result = engine
+        .eval(
+            r#"
+            Set CH CHANNEL()
+            SEND(CH, 1)
+            SEND(CH, {"name": "two"})
+            [RECV(CH), RECV(CH)["name"], RECV(CH, 10)]
+            "#,
+        )
+        .unwrap();
+    assert_eq!(
+        result,
+        Value::Array(vec![
+            Value::Number(1.0),
+            Value::String("two".to_string()),
+            Value::Null
+        ])
+    );
+}
+
+#[test]
+fn producer_and_consumer_tasks_share_a_channel() {
+    let mut engine = Aether::new();
+    let result = engine
+        .eval(
+            r#"
+            Set JOBS CHANNEL(2)
+            Set DONE CHANNEL()
+            Func PRODUCE() {
+                For I In RANGE(1, 6) {
+                    SEND(JOBS, I)
+                }
+                CHANNEL_CLOSE(JOBS)
+                Return True
+            }
+            Func CONSUME() {
+                Set TOTAL 0
+                Set ITEM RECV(JOBS, 5000)
+                While (ITEM != Null) {
+                    Set TOTAL (TOTAL + ITEM)
+                    Set ITEM RECV(JOBS, 5000)
+                }
+                SEND(DONE, TOTAL)
+                Return TOTAL
+            }
+            Set TASKS [SPAWN(CONSUME), SPAWN(PRODUCE)]
+            Set RESULTS AWAIT_ALL(TASKS)
+            [RESULTS[0]["value"], RECV(DONE, 1000)]
+            "#,
+        )
+        .unwrap();
+    assert_eq!(
+        result,
+        Value::Array(vec![Value::Number(15.0), Value::Number(15.0)])
+    );
+}
+
+#[test]
+fn closed_channel_rejects_send_and_drains() {
+    let mut engine = Aether::new();
+    let result = engine
+        .eval(
+            r#"
+            Set CH CHANNEL(5)
+            SEND(CH, "last")
+            Set FIRST CHANNEL_CLOSE(CH)
+            Set SECOND CHANNEL_CLOSE(CH)
+            [FIRST, SECOND, RECV(CH), RECV(CH)]
+            "#,
+        )
+        .unwrap();
+    assert_eq!(
+        result,
+        Value::Array(vec![
+            Value::Boolean(true),
+            Value::Boolean(false),
+            Value::String("last".to_string()),
+            Value::Null
+        ])
+    );
+    assert!(engine.eval(r#"SEND(CH, 1)"#).is_err());
+}
+
+#[test]
+fn channel_argument_errors() {
+    let mut engine = Aether::new();
+    assert!(engine.eval("CHANNEL(0)").is_err());
+    assert!(engine.eval(r#"RECV("chan:999999", 1)"#).is_err());
+    assert!(engine.eval(r#"RECV("queue", 1)"#).is_err());
+    assert!(
+        engine
+            .eval("Set CH CHANNEL()\nSEND(CH, Lambda () -> 1)")
+            .is_err()
+    );
+}
+
+#[test]
+fn channels_belong_to_their_engine() {
+    let mut owner = Aether::new();
+    let handle = owner.eval("Set CH CHANNEL()\nSEND(CH, 1)\nCH").unwrap();
+    let Value::String(handle) = handle else {
+        panic!("expected a handle, got {:?}", handle);
+    };
+
+    let mut other = Aether::new();
+    let err = other
+        .eval(&format!(r#"RECV("{}", 10)"#, handle))
+        .unwrap_err();
+    assert!(err.contains("does not exist"), "{}", err);
+    assert_eq!(owner.eval("RECV(CH, 10)").unwrap(), Value::Number(1.0));
+}
+
+#[test]
+fn drained_closed_channel_behaves_as_closed() {
+    let mut engine = Aether::new();
+    let result = engine
+        .eval(
+            r#"
+            Set CH CHANNEL()
+            SEND(CH, 1)
+            CHANNEL_CLOSE(CH)
+            [RECV(CH), RECV(CH), CHANNEL_CLOSE(CH)]
+            "#,
+        )
+        .unwrap();
+    assert_eq!(
+        result,
+        Value::Array(vec![Value::Number(1.0), Value::Null, Value::Boolean(false)])
+    );
+    let err = engine.eval("SEND(CH, 2)").unwrap_err();
+    assert!(err.contains("closed channel"), "{}", err);
+}
+
+#[test]
+fn buffered_values_count_toward_memory_limit() {
+    let limits = ExecutionLimits {
+        max_memory_bytes: Some(4096),
+        ..ExecutionLimits::default()
+    };
+    let mut engine = Aether::new().with_limits(limits);
+    let err = engine
+        .eval(
+            r#"
+            Set CH CHANNEL()
+            For I In RANGE(0, 100) {
+                SEND(CH, "0123456789012345678901234567890123456789")
+            }
+            "#,
+        )
+        .unwrap_err();
+    assert!(err.contains("Memory limit exceeded"), "{}", err);
+}
+
+#[test]
+fn recv_without_timeout_stops_at_engine_deadline() {
+    let limits = ExecutionLimits {
+        max_duration_ms: Some(200),
+        ..ExecutionLimits::default()
+    };
+    let mut engine = Aether::new().with_limits(limits);
+    let start = Instant::now();
+    let err = engine.eval("Set CH CHANNEL()\nRECV(CH)").unwrap_err();
+    assert!(err.contains("duration limit exceeded"), "{}", err);
+    assert!(start.elapsed() < Duration::from_secs(5));
+
+    // 满的通道上的 SEND 同样受限
+    let start = Instant::now();
+    let err = engine
+        .eval("Set CH CHANNEL(1)\nSEND(CH, 1)\nSEND(CH, 2)")
+        .unwrap_err();
+    assert!(err.contains("duration limit exceeded"), "{}", err);
+    assert!(start.elapsed() < Duration::from_secs(5));
+}
+
+#[test]
+fn recv_stops_at_with_timeout_deadline() {
+    let mut engine = Aether::new();
+    let err = engine
+        .eval("Set CH CHANNEL()\nWITH_TIMEOUT(Lambda () -> RECV(CH), 100)")
+        .unwrap_err();
+    assert!(err.contains("did not finish within 100 ms"), "{}", err);
+}
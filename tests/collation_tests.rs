@@ -0,0 +1,77 @@
+// tests/collation_tests.rs
+//! SORT_LOCALE / COMPARE_LOCALE 测试（需要 collation 特性）
+#![cfg(feature = "collation")]
+
+use aether::{Aether, Value};
+
+fn eval(code: &str) -> Value {
+    Aether::new().eval(code).unwrap()
+}
+
+fn strings(items: &[&str]) -> Value {
+    Value::Array(items.iter().map(|s| Value::String(s.to_string())).collect())
+}
+
+#[test]
+fn chinese_names_sort_by_pinyin() {
+    assert_eq!(
+        eval(r#"SORT_LOCALE(["张伟", "李娜", "王芳", "安然"], "zh-CN")"#),
+        strings(&["安然", "李娜", "王芳", "张伟"])
+    );
+    // 按码点比较时 "张" 排在 "李" 之前
+    assert_eq!(eval(r#""张伟" < "李娜""#), Value::Boolean(true));
+}
+
+#[test]
+fn accented_names_sort_with_their_base_letters() {
+    assert_eq!(
+        eval(r#"SORT_LOCALE(["Zoe", "Émile", "adam", "Eric"], "fr")"#),
+        strings(&["adam", "Émile", "Eric", "Zoe"])
+    );
+}
+
+#[test]
+fn compare_locale_follows_language_rules() {
+    assert_eq!(
+        eval(r#"COMPARE_LOCALE("ä", "z", "de")"#),
+        Value::Number(-1.0)
+    );
+    assert_eq!(
+        eval(r#"COMPARE_LOCALE("ä", "z", "sv")"#),
+        Value::Number(1.0)
+    );
+    assert_eq!(
+        eval(r#"COMPARE_LOCALE("abc", "abc", "en")"#),
+        Value::Number(0.0)
+    );
+}
+
+#[test]
+fn locale_defaults_to_current_locale() {
+    let mut engine = Aether::new();
+    engine.eval(r#"SET_LOCALE("sv")"#).unwrap();
+    assert_eq!(
+        engine.eval(r#"COMPARE_LOCALE("ä", "z")"#).unwrap(),
+        Value::Number(1.0)
+    );
+    engine.eval(r#"SET_LOCALE("de")"#).unwrap();
+    assert_eq!(
+        engine.eval(r#"SORT_LOCALE(["z", "ä"])"#).unwrap(),
+        strings(&["ä", "z"])
+    );
+    engine.eval(r#"SET_LOCALE("en")"#).unwrap();
+}
+
+#[test]
+fn invalid_arguments_are_rejected() {
+    let mut engine = Aether::new();
+    for code in [
+        r#"SORT_LOCALE(["a", 1], "en")"#,
+        r#"SORT_LOCALE("abc", "en")"#,
+        r#"SORT_LOCALE(["a"], "not a locale!")"#,
+        r#"COMPARE_LOCALE("a", 1, "en")"#,
+        r#"COMPARE_LOCALE("a")"#,
+    ] {
+        assert!(engine.eval(code).is_err(), "{} should fail", code);
+    }
+}
@@ -0,0 +1,155 @@
+// tests/datetime_tests.rs
+//! 日期时间类型测试：解析、格式化、加减、相差、时区转换与比较
+
+use aether::{Aether, Value};
+
+fn eval(code: &str) -> Value {
+    Aether::new().eval(code).unwrap()
+}
+
+fn s(text: &str) -> Value {
+    Value::String(text.to_string())
+}
+
+#[test]
+fn parse_and_format() {
+    assert_eq!(
+        eval(r#"TYPE(DATE_PARSE("2024-01-31T09:30:00+08:00"))"#),
+        s("DateTime")
+    );
+    assert_eq!(
+        eval(r#"TO_STRING(DATE_PARSE("2024-01-31T09:30:00+08:00"))"#),
+        s("2024-01-31T09:30:00+08:00")
+    );
+    // 没有偏移的文本按 UTC 解释
+    assert_eq!(
+        eval(r#"TO_STRING(DATE_PARSE("2024-01-31 09:30"))"#),
+        s("2024-01-31T09:30:00+00:00")
+    );
+    assert_eq!(
+        eval(r#"DATE_FORMAT(DATE_PARSE("31/01/2024", "%d/%m/%Y"), "%Y年%m月%d日")"#),
+        s("2024年01月31日")
+    );
+    // 时间戳（秒）和日期字符串可以直接传给日期函数
+    assert_eq!(
+        eval(r#"DATE_FORMAT(86400, "%Y-%m-%d %H:%M")"#),
+        s("1970-01-02 00:00")
+    );
+    assert_eq!(
+        eval(r#"DATE_TIMESTAMP("1970-01-02")"#),
+        Value::Number(86400.0)
+    );
+
+    let mut engine = Aether::new();
+    let err = engine.eval(r#"DATE_PARSE("31.01.2024")"#).unwrap_err();
+    assert!(
+        err.contains("Cannot parse '31.01.2024' as a date"),
+        "{}",
+        err
+    );
+    assert!(engine.eval(r#"DATE_FORMAT("2024-01-31", "%Q")"#).is_err());
+}
+
+#[test]
+fn add_and_diff() {
+    assert_eq!(
+        eval(r#"TO_STRING(DATE_ADD("2024-01-31", 1, "months"))"#),
+        s("2024-02-29T00:00:00+00:00")
+    );
+    assert_eq!(
+        eval(r#"TO_STRING(DATE_ADD("2024-03-01T00:00:00+08:00", -90, "minutes"))"#),
+        s("2024-02-29T22:30:00+08:00")
+    );
+    assert_eq!(
+        eval(r#"TO_STRING(DATE_ADD("2024-01-01", 10))"#),
+        s("2024-01-11T00:00:00+00:00")
+    );
+    assert!(
+        Aether::new()
+            .eval(r#"DATE_ADD("2024-01-01", 1.5, "months")"#)
+            .is_err()
+    );
+
+    assert_eq!(
+        eval(r#"DATE_DIFF("2024-01-01", "2024-03-15")"#),
+        Value::Int(74)
+    );
+    assert_eq!(
+        eval(r#"DATE_DIFF("2024-03-15", "2024-01-01", "weeks")"#),
+        Value::Int(-10)
+    );
+    assert_eq!(
+        eval(r#"DATE_DIFF("2024-01-31", "2024-02-29", "months")"#),
+        Value::Int(1)
+    );
+    assert_eq!(
+        eval(r#"DATE_DIFF("2024-01-31", "2024-02-28", "months")"#),
+        Value::Int(0)
+    );
+    assert_eq!(
+        eval(r#"DATE_DIFF("2020-02-29", "2024-02-28", "years")"#),
+        Value::Int(3)
+    );
+    assert!(
+        Aether::new()
+            .eval(r#"DATE_DIFF("2024-01-01", "2024-01-02", "fortnights")"#)
+            .is_err()
+    );
+}
+
+#[test]
+fn time_zone_conversion_keeps_the_instant() {
+    assert_eq!(
+        eval(r#"TO_STRING(DATE_TO_TZ("2024-01-31T01:00:00Z", "+08:00"))"#),
+        s("2024-01-31T09:00:00+08:00")
+    );
+    assert_eq!(
+        eval(r#"TO_STRING(DATE_TO_TZ("2024-01-31T01:00:00Z", -5.5))"#),
+        s("2024-01-30T19:30:00-05:30")
+    );
+    assert_eq!(
+        eval(
+            r#"Set D DATE_PARSE("2024-01-31T01:00:00Z")
+DATE_TO_TZ(D, "+0800") == D"#
+        ),
+        Value::Boolean(true)
+    );
+    assert!(
+        Aether::new()
+            .eval(r#"DATE_TO_TZ("2024-01-31", "Asia/Shanghai")"#)
+            .is_err()
+    );
+}
+
+#[test]
+fn datetimes_compare_by_instant() {
+    assert_eq!(
+        eval(r#"DATE_PARSE("2024-01-31T09:00:00+08:00") < DATE_PARSE("2024-01-31T02:00:00Z")"#),
+        Value::Boolean(true)
+    );
+    assert_eq!(
+        eval(r#"DATE_PARSE("2024-01-31") >= DATE_PARSE("2024-01-31T08:00:00+08:00")"#),
+        Value::Boolean(true)
+    );
+    assert_eq!(
+        eval(
+            r#"Set CUTOFF DATE_PARSE("2024-01-01")
+Set DATES MAP(["2024-03-01", "2023-12-31", "2024-01-15"], DATE_PARSE)
+MAP(FILTER(DATES, Lambda(D) -> D >= CUTOFF), Lambda(D) -> DATE_FORMAT(D, "%m-%d"))"#
+        ),
+        Value::Array(vec![s("03-01"), s("01-15")])
+    );
+    assert!(
+        Aether::new()
+            .eval(r#"DATE_PARSE("2024-01-01") < 5"#)
+            .is_err()
+    );
+}
+
+#[test]
+fn datetimes_serialize_as_rfc3339() {
+    assert_eq!(
+        eval(r#"JSON_STRINGIFY({"at": DATE_PARSE("2024-01-31T09:30:00+08:00")})"#),
+        s(r#"{"at":"2024-01-31T09:30:00+08:00"}"#)
+    );
+}
@@ -0,0 +1,168 @@
+use aether::debugger::{CommandAction, DebuggerSession};
+use aether::evaluator::Evaluator;
+use aether::{Aether, Value};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+const PAYROLL: &str = r#"
+Set SALARIES [100, 200, 300]
+Func BONUS(S) {
+    Set B (S * 0.1)
+    Return B
+}
+Set TOTAL 0
+For S In SALARIES {
+    Set TOTAL (TOTAL + S + BONUS(S))
+}
+"#;
+
+fn record(code: &str) -> aether::debugger::ExecutionRecording {
+    let mut engine = Aether::new();
+    engine.start_recording();
+    assert!(engine.is_recording());
+    engine.eval(code).unwrap();
+    let recording = engine.take_recording().unwrap();
+    assert!(!engine.is_recording());
+    recording
+}
+
+fn numbers(values: Vec<Option<Value>>) -> Vec<Option<f64>> {
+    values
+        .into_iter()
+        .map(|v| match v {
+            Some(v @ (Value::Number(_) | Value::Int(_))) => v.to_number(),
+            None => None,
+            other => panic!("unexpected value {:?}", other),
+        })
+        .collect()
+}
+
+#[test]
+fn test_history_tracks_every_change_in_order() {
+    let recording = record(PAYROLL);
+    let history = recording.history("TOTAL");
+    let new_values = numbers(history.iter().map(|(_, c)| c.new.clone()).collect());
+    assert_eq!(
+        new_values,
+        vec![Some(0.0), Some(110.0), Some(330.0), Some(660.0)]
+    );
+
+    // Changes made after a nested call returns belong to the statement that made them
+    for (step, _) in &history {
+        assert!(recording.steps()[*step].statement.starts_with("Set TOTAL"));
+        assert_eq!(recording.steps()[*step].function, None);
+    }
+}
+
+#[test]
+fn test_values_can_be_read_at_any_step() {
+    let recording = record(PAYROLL);
+    let (second_update, _) = recording.history("TOTAL")[2];
+
+    assert_eq!(
+        recording.value_before(second_update, "TOTAL"),
+        Some(Value::Number(110.0))
+    );
+    assert_eq!(
+        recording.variables_before(recording.len())["TOTAL"],
+        Value::Number(660.0)
+    );
+    assert_eq!(recording.value_before(0, "TOTAL"), None);
+}
+
+#[test]
+fn test_steps_inside_functions_record_function_and_depth() {
+    let recording = record(PAYROLL);
+    let inside: Vec<_> = recording
+        .steps()
+        .iter()
+        .filter(|step| step.statement == "Set B")
+        .collect();
+    assert_eq!(inside.len(), 3);
+    for step in inside {
+        assert_eq!(step.function.as_deref(), Some("BONUS"));
+        assert!(step.depth >= 1);
+        assert_eq!(step.changes.len(), 1);
+        assert_eq!(step.changes[0].old, None);
+    }
+}
+
+#[test]
+fn test_recording_keeps_steps_before_an_error() {
+    let mut engine = Aether::new();
+    engine.start_recording();
+    assert!(
+        engine
+            .eval("Set X 1\nSet X 2\nSet Y (X / UNKNOWN)")
+            .is_err()
+    );
+    let recording = engine.take_recording().unwrap();
+    assert_eq!(recording.len(), 3);
+    assert_eq!(recording.value_before(2, "X"), Some(Value::Number(2.0)));
+}
+
+#[test]
+fn test_recording_step_limit_truncates() {
+    let mut engine = Aether::new();
+    engine.start_recording_with_limit(2);
+    engine.eval("Set A 1\nSet B 2\nSet C 3").unwrap();
+    let recording = engine.take_recording().unwrap();
+    assert_eq!(recording.len(), 2);
+    assert!(recording.is_truncated());
+    assert_eq!(recording.value_before(2, "B"), Some(Value::Number(2.0)));
+    assert_eq!(recording.value_before(2, "C"), None);
+}
+
+#[test]
+fn test_no_recording_unless_started() {
+    let mut engine = Aether::new();
+    engine.eval("Set A 1").unwrap();
+    assert!(engine.take_recording().is_none());
+}
+
+fn session(source: &str) -> DebuggerSession {
+    let evaluator = Rc::new(RefCell::new(Evaluator::new()));
+    let mut session = DebuggerSession::new(evaluator);
+    session.set_source(source.to_string(), "payroll.aether".to_string());
+    session
+}
+
+#[test]
+fn test_debugger_record_and_step_backwards() {
+    let mut session = session(PAYROLL);
+    let (msg, action) = session.handle_command("back");
+    assert!(msg.contains("No recording"));
+    assert_eq!(action, CommandAction::Stay);
+
+    let (msg, _) = session.handle_command("record");
+    assert!(msg.contains("Recorded"), "{}", msg);
+    let len = session.recording().unwrap().len();
+    assert_eq!(session.replay_position(), len);
+
+    let (msg, _) = session.handle_command("print TOTAL");
+    assert!(msg.contains("TOTAL = 660"), "{}", msg);
+
+    // Walk back past the BONUS call to the last update of TOTAL
+    let (msg, _) = session.handle_command("back 1");
+    assert!(msg.contains("in BONUS (depth 1): Return"), "{}", msg);
+    let (msg, _) = session.handle_command("rs 2");
+    assert!(msg.contains("Set TOTAL"), "{}", msg);
+    assert!(msg.contains("TOTAL: 330 -> 660"), "{}", msg);
+    let (msg, _) = session.handle_command("p TOTAL");
+    assert!(msg.contains("TOTAL = 330"), "{}", msg);
+
+    let (msg, _) = session.handle_command("goto 0");
+    assert!(msg.contains("Step 0/"), "{}", msg);
+    let (msg, _) = session.handle_command("forward 2");
+    assert!(msg.starts_with("Step 2/"), "{}", msg);
+
+    let (msg, _) = session.handle_command("history TOTAL");
+    assert!(msg.contains("0 -> 110"), "{}", msg);
+    assert!(msg.contains("110 -> 330"), "{}", msg);
+
+    let (msg, _) = session.handle_command("info locals");
+    assert!(msg.contains("SALARIES = [100, 200, 300]"), "{}", msg);
+
+    let (msg, _) = session.handle_command(&format!("goto {}", len + 1));
+    assert!(msg.contains("out of range"), "{}", msg);
+}
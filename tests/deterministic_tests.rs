@@ -0,0 +1,153 @@
+// tests/deterministic_tests.rs
+//! 确定性执行模式测试
+
+use aether::{Aether, DeterministicConfig, IOPermissions, Value};
+
+fn deterministic(seed: u64) -> Aether {
+    Aether::with_permissions(IOPermissions::allow_all())
+        .with_deterministic(DeterministicConfig::new(seed).with_clock(1_700_000_000.0))
+}
+
+#[test]
+fn random_is_in_unit_interval() {
+    let mut engine = Aether::new();
+    assert!(!engine.is_deterministic());
+    for _ in 0..20 {
+        match engine.eval("RANDOM()").unwrap() {
+            Value::Number(n) => assert!((0.0..1.0).contains(&n), "{}", n),
+            other => panic!("expected Number, got {:?}", other),
+        }
+    }
+}
+
+#[test]
+fn random_sequence_replays_for_the_same_seed() {
+    let code = "[RANDOM(), RANDOM(), RANDOM()]";
+    let mut a = deterministic(42);
+    let mut b = deterministic(42);
+    let first = a.eval(code).unwrap();
+    assert_eq!(a.eval(code).unwrap(), first);
+    assert_eq!(b.eval(code).unwrap(), first);
+
+    let mut other = deterministic(43);
+    assert_ne!(other.eval(code).unwrap(), first);
+}
+
+#[test]
+fn now_uses_injected_clock() {
+    let mut engine = deterministic(1);
+    assert_eq!(
+        engine.eval("NOW()").unwrap(),
+        Value::Number(1_700_000_000.0)
+    );
+
+    let mut live = Aether::new();
+    match live.eval("NOW()").unwrap() {
+        Value::Number(n) => assert!(n > 1_700_000_000.0),
+        other => panic!("expected Number, got {:?}", other),
+    }
+}
+
+#[test]
+#[cfg(feature = "io")]
+fn nondeterministic_builtins_are_rejected() {
+    let mut engine = deterministic(1);
+    for code in [
+        "READ_FILE(\"Cargo.toml\")",
+        "HTTP_GET(\"http://localhost\")",
+        "SPAWN(Lambda () -> 1)",
+        "WITH_TIMEOUT(100, Lambda () -> 1)",
+        "CHANNEL()",
+    ] {
+        let err = engine.eval(code).unwrap_err();
+        assert!(err.contains("Deterministic mode"), "{}: {}", code, err);
+    }
+    // 纯计算不受影响
+    assert_eq!(engine.eval("SUM([1, 2, 3])").unwrap(), Value::Number(6.0));
+}
+
+#[test]
+fn file_writing_builtins_are_rejected() {
+    let mut engine = deterministic(1);
+    let dir = std::env::temp_dir();
+    for (name, call) in [
+        ("WRITE_FILE", r#"WRITE_FILE("PATH", "x")"#),
+        ("APPEND_FILE", r#"APPEND_FILE("PATH", "x")"#),
+        (
+            "PLOT_LINE",
+            r#"PLOT_LINE([1, 2], [3, 4], {"file": "PATH"})"#,
+        ),
+        ("PLOT_BAR", r#"PLOT_BAR(["a"], [1], {"file": "PATH"})"#),
+        ("PLOT_HIST", r#"PLOT_HIST([1, 2, 3], {"file": "PATH"})"#),
+        (
+            "REPORT_BUILD",
+            r#"REPORT_BUILD(["hello"], {"file": "PATH"})"#,
+        ),
+    ] {
+        let path = dir.join(format!(
+            "aether_deterministic_{}_{}",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let code = call.replace("PATH", &path.display().to_string().replace('\\', "\\\\"));
+        let err = engine.eval(&code).unwrap_err();
+        assert!(err.contains("Deterministic mode"), "{}: {}", name, err);
+        assert!(!path.exists(), "{}", name);
+    }
+}
+
+#[cfg(feature = "payroll")]
+#[test]
+fn payroll_csv_input_is_rejected() {
+    let path = std::env::temp_dir().join(format!(
+        "aether_deterministic_payroll_{}.csv",
+        std::process::id()
+    ));
+    std::fs::write(&path, "id,base_salary\n1,10000\n").unwrap();
+    let mut engine = deterministic(1);
+    let code = format!(
+        r#"PAYROLL_RUN("{}", {{}})"#,
+        path.display().to_string().replace('\\', "\\\\")
+    );
+    let err = engine.eval(&code).unwrap_err();
+    let _ = std::fs::remove_file(&path);
+    assert!(err.contains("Deterministic mode"), "{}", err);
+}
+
+#[test]
+fn dict_keys_are_sorted() {
+    let mut engine = deterministic(1);
+    let code = "Set D {\"b\": 2, \"c\": 3, \"a\": 1, \"e\": 5, \"d\": 4}";
+    engine.eval(code).unwrap();
+    assert_eq!(
+        engine.eval("JOIN(KEYS(D), \",\")").unwrap(),
+        Value::String("a,b,c,d,e".to_string())
+    );
+    assert_eq!(
+        engine.eval("VALUES(D)").unwrap(),
+        Value::Array((1..=5).map(|n| Value::Number(n as f64)).collect())
+    );
+    assert_eq!(
+        engine.eval("TO_STRING(D)").unwrap(),
+        Value::String("{a: 1, b: 2, c: 3, d: 4, e: 5}".to_string())
+    );
+}
+
+#[test]
+fn id_validation_uses_injected_clock() {
+    // 出生日期 2024-01-01 晚于注入时钟（2023-11-14），视为无效
+    let future_birth = "VALIDATE_ID_CN(\"110101202401010019\")";
+    let mut engine = deterministic(1);
+    assert_eq!(engine.eval(future_birth).unwrap(), Value::Boolean(false));
+    let mut live = Aether::new();
+    assert_eq!(live.eval(future_birth).unwrap(), Value::Boolean(true));
+}
+
+#[test]
+fn disabling_restores_builtins() {
+    let mut engine = deterministic(1);
+    assert!(engine.eval("CHANNEL()").is_err());
+    engine.set_deterministic(None);
+    assert!(engine.eval("CHANNEL()").is_ok());
+}
@@ -0,0 +1,84 @@
+// tests/dialect_tests.rs
+//! 关键字方言测试
+
+use aether::ast::eq_ignoring_spans;
+use aether::{Aether, Dialect, Parser, Value};
+
+#[test]
+fn chinese_dialect_runs_control_flow() {
+    let mut engine = Aether::new().with_dialect(Dialect::chinese());
+    let result = engine
+        .eval(
+            r#"
+            设置 TOTAL 0
+            遍历 I 在 RANGE(1, 5) {
+                如果 (I == 2 或 I == 4) {
+                    设置 TOTAL (TOTAL + I)
+                } 否则 {
+                    继续
+                }
+            }
+            函数 DOUBLE(N) {
+                返回 N * 2
+            }
+            [DOUBLE(TOTAL), 真, 空]
+            "#,
+        )
+        .unwrap();
+    assert_eq!(
+        result,
+        Value::Array(vec![Value::Number(12.0), Value::Boolean(true), Value::Null])
+    );
+}
+
+#[test]
+fn custom_aliases_keep_standard_keywords() {
+    let dialect = Dialect::new()
+        .with_keyword("let", "Set")
+        .unwrap()
+        .with_keyword("if", "If")
+        .unwrap()
+        .with_keyword("else", "Else")
+        .unwrap();
+    let mut engine = Aether::new().with_dialect(dialect);
+    let result = engine
+        .eval("let X 3\nSet Y 4\nif (X < Y) { \"lt\" } else { \"ge\" }")
+        .unwrap();
+    assert_eq!(result, Value::String("lt".to_string()));
+}
+
+#[test]
+fn dialect_produces_identical_ast() {
+    let dialect = std::sync::Arc::new(Dialect::chinese());
+    let standard = Parser::new("Set X 1\nIf (X > 0) { Return True }")
+        .parse_program()
+        .unwrap();
+    let localized = Parser::with_dialect("设置 X 1\n如果 (X > 0) { 返回 真 }", dialect)
+        .parse_program()
+        .unwrap();
+    // 关键字长度不同，只有位置信息不同
+    assert_ne!(standard, localized);
+    assert!(eq_ignoring_spans(&standard, &localized));
+}
+
+#[test]
+fn switching_dialect_invalidates_cached_parses() {
+    let code = "let X 1\nX";
+    let mut engine = Aether::new();
+    assert!(engine.eval(code).is_err());
+
+    engine.set_dialect(Dialect::new().with_keyword("let", "Set").unwrap());
+    assert_eq!(engine.eval(code).unwrap(), Value::Number(1.0));
+
+    engine.clear_dialect();
+    assert!(engine.dialect().is_none());
+    assert!(engine.eval(code).is_err());
+}
+
+#[test]
+fn invalid_aliases_are_rejected() {
+    assert!(Dialect::new().with_keyword("let", "Lettuce").is_err());
+    assert!(Dialect::new().with_keyword("If", "Set").is_err());
+    assert!(Dialect::new().with_keyword("two words", "Set").is_err());
+    assert!(Dialect::new().with_keyword("", "Set").is_err());
+}
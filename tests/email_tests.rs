@@ -0,0 +1,156 @@
+// tests/email_tests.rs
+//! SEND_EMAIL 测试：权限、服务器白名单和与模拟 SMTP 服务器的完整会话
+
+#![cfg(feature = "io")]
+
+use aether::analysis::is_impure_builtin;
+use aether::sandbox::set_smtp_allowlist;
+use aether::{Aether, DeterministicConfig, Value};
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::thread;
+
+/// 模拟 SMTP 服务器：接受一个会话，返回收到的命令和 DATA 内容
+fn fake_smtp_server() -> (u16, thread::JoinHandle<(Vec<String>, String)>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let handle = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut writer = stream.try_clone().unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut commands = Vec::new();
+        let mut data = String::new();
+        writer.write_all(b"220 fake ESMTP\r\n").unwrap();
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line).unwrap() == 0 {
+                break;
+            }
+            let line = line.trim_end().to_string();
+            commands.push(line.clone());
+            let reply: &[u8] = match line.split(' ').next().unwrap() {
+                "EHLO" => b"250-fake\r\n250 AUTH PLAIN\r\n",
+                "AUTH" => b"235 ok\r\n",
+                "DATA" => {
+                    writer.write_all(b"354 go ahead\r\n").unwrap();
+                    loop {
+                        let mut line = String::new();
+                        reader.read_line(&mut line).unwrap();
+                        if line == ".\r\n" {
+                            break;
+                        }
+                        data.push_str(&line);
+                    }
+                    b"250 queued\r\n"
+                }
+                "QUIT" => {
+                    writer.write_all(b"221 bye\r\n").unwrap();
+                    break;
+                }
+                _ => b"250 ok\r\n",
+            };
+            writer.write_all(reply).unwrap();
+        }
+        (commands, data)
+    });
+    (port, handle)
+}
+
+fn message(port: u16) -> String {
+    format!(
+        r#"{{
+            "server": "127.0.0.1:{}",
+            "security": "none",
+            "from": "payroll@example.com",
+            "to": ["hr@example.com"],
+            "bcc": "audit@example.com",
+            "subject": "五月报告",
+            "body": ".hidden\nline",
+            "username": "bot",
+            "password": "pw",
+            "attachments": [{{"filename": "report.md", "content": "| a |"}}]
+        }}"#,
+        port
+    )
+}
+
+#[test]
+fn requires_network_permission() {
+    let mut engine = Aether::new();
+    assert!(engine.eval(r#"SEND_EMAIL({})"#).is_err());
+}
+
+#[test]
+fn deterministic_mode_rejects_send_email() {
+    assert!(is_impure_builtin("SEND_EMAIL"));
+    let mut engine = Aether::with_all_permissions().with_deterministic(DeterministicConfig::new(1));
+    let err = engine
+        .eval(&format!("SEND_EMAIL({})", message(25)))
+        .unwrap_err();
+    assert!(err.contains("Deterministic mode"), "{}", err);
+}
+
+#[test]
+fn rejects_servers_outside_the_allowlist() {
+    set_smtp_allowlist(vec!["smtp.example.com:587".to_string()]);
+    let mut engine = Aether::with_all_permissions();
+    let err = engine
+        .eval(&format!("SEND_EMAIL({})", message(25)))
+        .unwrap_err();
+    assert!(err.contains("not in the allowlist"), "{}", err);
+    set_smtp_allowlist(Vec::new());
+}
+
+#[test]
+fn delivers_message_over_smtp() {
+    let (port, server) = fake_smtp_server();
+    set_smtp_allowlist(vec!["127.0.0.1".to_string()]);
+    let mut engine = Aether::with_all_permissions();
+    let result = engine
+        .eval(&format!("SEND_EMAIL({})", message(port)))
+        .unwrap();
+    set_smtp_allowlist(Vec::new());
+
+    let Value::Dict(result) = result else {
+        panic!("expected Dict");
+    };
+    assert_eq!(
+        result["accepted"],
+        Value::Array(vec![
+            Value::String("hr@example.com".to_string()),
+            Value::String("audit@example.com".to_string()),
+        ])
+    );
+
+    let (commands, data) = server.join().unwrap();
+    assert_eq!(commands[0], "EHLO aether");
+    // "\0bot\0pw" 的 base64
+    assert_eq!(commands[1], "AUTH PLAIN AGJvdABwdw==");
+    assert!(commands.contains(&"MAIL FROM:<payroll@example.com>".to_string()));
+    assert!(commands.contains(&"RCPT TO:<audit@example.com>".to_string()));
+    assert!(data.contains("To: hr@example.com\r\n"));
+    assert!(
+        !data.contains("audit@example.com"),
+        "Bcc must not be in headers"
+    );
+    assert!(data.contains("Subject: =?UTF-8?B?"));
+    assert!(data.contains("Content-Type: multipart/mixed"));
+    assert!(data.contains("filename=\"report.md\""));
+    assert!(data.contains("Content-Type: text/markdown"));
+}
+
+#[test]
+fn validates_message_fields() {
+    set_smtp_allowlist(vec!["127.0.0.1".to_string()]);
+    let mut engine = Aether::with_all_permissions();
+    for code in [
+        r#"SEND_EMAIL({"server": "127.0.0.1:1", "from": "a@b.c"})"#,
+        r#"SEND_EMAIL({"server": "127.0.0.1", "from": "a@b.c", "to": "x@y.z"})"#,
+        r#"SEND_EMAIL({"server": "127.0.0.1:1", "from": "nobody", "to": "x@y.z"})"#,
+        r#"SEND_EMAIL({"server": "127.0.0.1:1", "from": "a@b.c", "to": "x@y.z", "security": "ftp"})"#,
+        r#"SEND_EMAIL({"server": "127.0.0.1:1", "from": "a@b.c", "to": "x@y.z", "username": "u"})"#,
+    ] {
+        assert!(engine.eval(code).is_err(), "{}", code);
+    }
+    set_smtp_allowlist(Vec::new());
+}
@@ -1,6 +1,6 @@
 use std::{cell::RefCell, rc::Rc};
 
-use aether::{Environment, Value};
+use aether::{Aether, Environment, Value};
 
 #[test]
 fn test_environment_set_get() {
@@ -104,3 +104,120 @@ fn test_environment_clear() {
     assert_eq!(env.get("y"), None);
     assert_eq!(env.keys().len(), 0);
 }
+
+#[test]
+fn test_environment_visible_variables_shadowing() {
+    let parent = Rc::new(RefCell::new(Environment::new()));
+    parent.borrow_mut().set("X".to_string(), Value::Number(1.0));
+    parent.borrow_mut().set("Y".to_string(), Value::Number(2.0));
+
+    let mut child = Environment::with_parent(Rc::clone(&parent));
+    child.set("X".to_string(), Value::String("inner".to_string()));
+
+    let vars = child.visible_variables();
+    let names: Vec<_> = vars.iter().map(|v| v.name.as_str()).collect();
+    assert_eq!(names, vec!["X", "Y"]);
+    assert_eq!(vars[0].type_name, "String");
+    assert_eq!(child.variables().len(), 1);
+}
+
+#[test]
+fn test_engine_variables_skip_builtins() {
+    let mut engine = Aether::new();
+    engine
+        .eval("Set COUNT 3\nFunc DOUBLE(x) { Return x * 2 }")
+        .unwrap();
+
+    let vars = engine.variables();
+    let names: Vec<_> = vars.iter().map(|v| v.name.as_str()).collect();
+    assert_eq!(names, vec!["COUNT", "DOUBLE"]);
+    assert_eq!(vars[0].value, Value::Number(3.0));
+    assert_eq!(vars[1].type_name, "Function");
+}
+
+#[test]
+fn test_vars_and_globals_builtins() {
+    let mut engine = Aether::new();
+    let code = r#"
+        Set TOTAL 10
+        Func SCOPE(n) {
+            Set LOCAL_VAL n
+            Return [VARS(), GLOBALS()]
+        }
+        SCOPE("x")
+    "#;
+    let result = engine.eval(code).unwrap();
+    let Value::Array(items) = result else {
+        panic!("expected array");
+    };
+    let Value::Dict(vars) = &items[0] else {
+        panic!("expected dict");
+    };
+    let Value::Dict(globals) = &items[1] else {
+        panic!("expected dict");
+    };
+
+    assert_eq!(vars.get("n"), Some(&Value::String("String".to_string())));
+    assert_eq!(
+        vars.get("LOCAL_VAL"),
+        Some(&Value::String("String".to_string()))
+    );
+    assert_eq!(
+        vars.get("TOTAL"),
+        Some(&Value::String("Integer".to_string()))
+    );
+    assert!(!vars.contains_key("PRINTLN"));
+    assert!(globals.contains_key("TOTAL"));
+    assert!(globals.contains_key("SCOPE"));
+    assert!(!globals.contains_key("LOCAL_VAL"));
+}
+
+#[test]
+fn test_export_import_env_json_roundtrip() {
+    let mut engine = Aether::new();
+    engine
+        .eval(
+            r#"Set NAME "alice"
+Set SCORES [1, 2, 3]
+Set PROFILE {"age": 30, "active": True}
+Func HELPER(x) { Return x }"#,
+        )
+        .unwrap();
+
+    let export = engine.export_env_json().unwrap();
+    assert_eq!(export.warnings.len(), 1);
+    assert!(export.warnings[0].contains("HELPER"));
+
+    let mut other = Aether::new();
+    assert_eq!(other.import_env_json(&export.json).unwrap(), 3);
+    assert_eq!(
+        other.eval(r#"[NAME, SCORES[2], PROFILE["age"]]"#).unwrap(),
+        Value::Array(vec![
+            Value::String("alice".to_string()),
+            Value::Number(3.0),
+            Value::Number(30.0),
+        ])
+    );
+    assert!(other.eval("HELPER(1)").is_err());
+}
+
+#[test]
+fn test_export_env_json_preserves_fractions() {
+    let mut engine = Aether::new();
+    engine
+        .eval("Set RATE TO_FRACTION(1/3)\nSet BIG 123456789012345678901234567890")
+        .unwrap();
+
+    let export = engine.export_env_json().unwrap();
+    let mut other = Aether::new();
+    other.import_env_json(&export.json).unwrap();
+    assert_eq!(other.eval("RATE").unwrap(), engine.eval("RATE").unwrap());
+    assert_eq!(other.eval("BIG").unwrap(), engine.eval("BIG").unwrap());
+}
+
+#[test]
+fn test_import_env_json_rejects_non_object() {
+    let mut engine = Aether::new();
+    assert!(engine.import_env_json("[1, 2]").is_err());
+    assert!(engine.import_env_json("not json").is_err());
+}
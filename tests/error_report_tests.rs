@@ -40,4 +40,44 @@ MAP(A, BAD)
         signatures.iter().any(|s| s.contains("BAD(X)")),
         "missing BAD(X) frame: {signatures:?}"
     );
+    // Both calls are made by the MAP statement
+    assert!(frames.iter().all(|f| f["line"] == 7), "{frames:?}");
+}
+
+#[test]
+fn wrong_arity_names_user_function_and_params() {
+    let mut engine = Aether::new();
+    let report = engine
+        .eval_report(
+            r#"
+Func ADD(A, B) {
+    Return (A + B)
+}
+ADD(1)
+"#,
+        )
+        .unwrap_err();
+
+    assert_eq!(report.kind, "WrongArity");
+    assert!(
+        report.message.contains("ADD(A, B)") && report.message.contains("expected 2, got 1"),
+        "{}",
+        report.message
+    );
+}
+
+#[test]
+fn wrong_arity_names_builtin_signature() {
+    let mut engine = Aether::new();
+
+    let report = engine.eval_report("LEN([1], [2])").unwrap_err();
+    assert_eq!(report.kind, "WrongArity");
+    assert!(report.message.contains("LEN(value)"), "{}", report.message);
+
+    let report = engine.eval_report("TO_FRACTION()").unwrap_err();
+    assert!(
+        report.message.contains("TO_FRACTION(value)"),
+        "{}",
+        report.message
+    );
 }
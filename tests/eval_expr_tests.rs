@@ -0,0 +1,116 @@
+// tests/eval_expr_tests.rs
+//! 以数据上下文求值单个表达式（eval_expr）测试
+
+use aether::{Aether, Value};
+use std::collections::HashMap;
+
+fn context(pairs: &[(&str, Value)]) -> Value {
+    Value::Dict(
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.clone()))
+            .collect::<HashMap<_, _>>(),
+    )
+}
+
+#[test]
+fn context_keys_are_variables() {
+    let mut engine = Aether::new();
+    let row = context(&[
+        ("PRICE", Value::Number(12.5)),
+        ("QTY", Value::Number(4.0)),
+        ("discount", Value::Number(0.1)),
+    ]);
+    let total = engine
+        .eval_expr("PRICE * QTY * (1 - discount)", &row)
+        .unwrap();
+    assert_eq!(total, Value::Number(45.0));
+}
+
+#[test]
+fn same_formula_with_different_contexts() {
+    let mut engine = Aether::new();
+    let formula = r#"If (SCORE >= 60) { "pass" } Else { "fail" }"#;
+    let results: Vec<Value> = [75.0, 40.0]
+        .iter()
+        .map(|score| {
+            engine
+                .eval_expr(formula, &context(&[("SCORE", Value::Number(*score))]))
+                .unwrap()
+        })
+        .collect();
+    assert_eq!(
+        results,
+        vec![
+            Value::String("pass".to_string()),
+            Value::String("fail".to_string())
+        ]
+    );
+    assert_eq!(engine.cache_stats().hits, 1);
+}
+
+#[test]
+fn builtins_globals_and_nested_values() {
+    let mut engine = Aether::new();
+    engine.eval("Set RATE 2").unwrap();
+    let order = context(&[(
+        "ITEMS",
+        Value::Array(vec![Value::Number(1.0), Value::Number(2.0)]),
+    )]);
+    assert_eq!(
+        engine.eval_expr("SUM(ITEMS) * RATE", &order).unwrap(),
+        Value::Number(6.0)
+    );
+    assert_eq!(
+        engine.eval_expr("LEN([1, 2, 3])", &Value::Null).unwrap(),
+        Value::Number(3.0)
+    );
+}
+
+#[test]
+fn context_does_not_leak_into_the_engine() {
+    let mut engine = Aether::new();
+    engine.eval("Set X 1").unwrap();
+    let shadow = context(&[("X", Value::Number(5.0)), ("Y", Value::Number(2.0))]);
+    assert_eq!(
+        engine.eval_expr("X + Y", &shadow).unwrap(),
+        Value::Number(7.0)
+    );
+    assert_eq!(engine.eval("X").unwrap(), Value::Number(1.0));
+    assert!(engine.eval("Y").is_err());
+}
+
+#[test]
+fn only_a_single_expression_is_accepted() {
+    let mut engine = Aether::new();
+    for source in [
+        "Set X 1",
+        "Func F() { Return 1 }",
+        "1 + 1\n2 + 2",
+        "",
+        "   \n",
+    ] {
+        let err = engine.eval_expr(source, &Value::Null).unwrap_err();
+        assert!(err.starts_with("Parse error"), "{}: {}", source, err);
+    }
+    assert_eq!(
+        engine.eval_expr("\n  1 + 2;\n", &Value::Null).unwrap(),
+        Value::Number(3.0)
+    );
+}
+
+#[test]
+fn errors_for_bad_context_and_runtime_failures() {
+    let mut engine = Aether::new();
+    let err = engine
+        .eval_expr("1", &Value::Array(Vec::new()))
+        .unwrap_err();
+    assert!(err.contains("must be a Dict"), "{}", err);
+
+    let err = engine.eval_expr("MISSING + 1", &Value::Null).unwrap_err();
+    assert!(err.starts_with("Runtime error"), "{}", err);
+
+    // 程序缓存中的同名代码不会绕过表达式检查
+    engine.eval("Set Z 3").unwrap();
+    assert!(engine.eval_expr("Set Z 3", &Value::Null).is_err());
+}
@@ -2,7 +2,7 @@
 //!
 //! 测试步数限制、递归深度限制、执行超时等
 
-use aether::{Aether, ExecutionLimits};
+use aether::{Aether, Backend, ExecutionLimits};
 
 #[test]
 fn test_step_limit_prevents_infinite_loop() {
@@ -11,6 +11,9 @@ fn test_step_limit_prevents_infinite_loop() {
         max_steps: Some(10),
         max_recursion_depth: None,
         max_duration_ms: None,
+        max_loop_iterations: None,
+        max_array_len: None,
+        max_dict_entries: None,
         max_memory_bytes: None,
     };
 
@@ -47,6 +50,9 @@ fn test_recursion_depth_limit() {
         max_steps: None,
         max_recursion_depth: Some(5),
         max_duration_ms: None,
+        max_loop_iterations: None,
+        max_array_len: None,
+        max_dict_entries: None,
         max_memory_bytes: None,
     };
 
@@ -83,6 +89,9 @@ fn test_execution_timeout() {
         max_steps: None,
         max_recursion_depth: None,
         max_duration_ms: Some(100),
+        max_loop_iterations: None,
+        max_array_len: None,
+        max_dict_entries: None,
         max_memory_bytes: None,
     };
 
@@ -123,6 +132,9 @@ fn test_custom_limits() {
         max_steps: Some(1000),
         max_recursion_depth: Some(50),
         max_duration_ms: Some(5000),
+        max_loop_iterations: None,
+        max_array_len: None,
+        max_dict_entries: None,
         max_memory_bytes: None,
     };
 
@@ -142,6 +154,9 @@ fn test_set_limits() {
         max_steps: Some(100),
         max_recursion_depth: None,
         max_duration_ms: None,
+        max_loop_iterations: None,
+        max_array_len: None,
+        max_dict_entries: None,
         max_memory_bytes: None,
     };
 
@@ -153,6 +168,9 @@ fn test_set_limits() {
         max_steps: Some(200),
         max_recursion_depth: Some(10),
         max_duration_ms: None,
+        max_loop_iterations: None,
+        max_array_len: None,
+        max_dict_entries: None,
         max_memory_bytes: None,
     };
 
@@ -213,6 +231,9 @@ fn test_normal_execution_with_limits() {
         max_steps: Some(1000),
         max_recursion_depth: Some(100),
         max_duration_ms: Some(5000),
+        max_loop_iterations: None,
+        max_array_len: None,
+        max_dict_entries: None,
         max_memory_bytes: None,
     };
 
@@ -275,6 +296,9 @@ fn test_step_counter_with_simple_code() {
         max_steps: Some(5), // 只允许 5 步
         max_recursion_depth: None,
         max_duration_ms: None,
+        max_loop_iterations: None,
+        max_array_len: None,
+        max_dict_entries: None,
         max_memory_bytes: None,
     };
 
@@ -304,3 +328,185 @@ fn test_step_counter_with_simple_code() {
     let result2 = engine.eval(code2);
     assert!(result2.is_err(), "Should fail due to step limit");
 }
+
+fn loop_limited(iterations: usize) -> Aether {
+    Aether::new().with_limits(ExecutionLimits {
+        max_loop_iterations: Some(iterations),
+        ..ExecutionLimits::default()
+    })
+}
+
+#[test]
+fn test_loop_iteration_limit_stops_while() {
+    let mut engine = loop_limited(100);
+    let err = engine.eval("While (True) { Set X 1 }").unwrap_err();
+    assert!(
+        err.contains("Loop iteration limit exceeded: 101 iterations (limit: 100)"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn test_loop_iteration_limit_applies_to_for_loops() {
+    let mut engine = loop_limited(3);
+    assert!(engine.eval("For I In RANGE(0, 4) { Set X I }").is_err());
+    assert!(engine.eval("For I, V In [1, 2, 3, 4] { Set X V }").is_err());
+
+    // 恰好达到上限的循环可以正常结束
+    let result = engine
+        .eval(
+            r#"
+            Set TOTAL 0
+            For I In RANGE(0, 3) { Set TOTAL (TOTAL + I) }
+            Set N 0
+            While (N < 3) { Set N (N + 1) }
+            TOTAL + N
+            "#,
+        )
+        .unwrap();
+    assert_eq!(result.to_string(), "6");
+}
+
+#[test]
+fn test_loop_iteration_limit_is_per_loop() {
+    let mut engine = loop_limited(5);
+    let result = engine
+        .eval(
+            r#"
+            Set COUNT 0
+            For I In RANGE(0, 5) {
+                For J In RANGE(0, 5) {
+                    Set COUNT (COUNT + 1)
+                }
+            }
+            COUNT
+            "#,
+        )
+        .unwrap();
+    assert_eq!(result.to_string(), "25");
+}
+
+#[test]
+fn test_empty_loop_body_respects_duration_limit() {
+    let mut engine = Aether::new().with_limits(ExecutionLimits {
+        max_steps: None,
+        max_duration_ms: Some(50),
+        ..ExecutionLimits::default()
+    });
+    let err = engine.eval("While (True) { }").unwrap_err();
+    assert!(err.contains("duration limit exceeded"), "{}", err);
+}
+
+#[test]
+fn test_empty_loop_body_respects_step_limit() {
+    // 每次检查循环条件都计为一步，空循环体也会耗尽步数
+    for backend in [Backend::Interpreter, Backend::Bytecode] {
+        let mut engine =
+            Aether::new()
+                .with_execution_backend(backend)
+                .with_limits(ExecutionLimits {
+                    max_steps: Some(50),
+                    max_duration_ms: None,
+                    ..ExecutionLimits::default()
+                });
+        let err = engine.eval("While (True) { }").unwrap_err();
+        assert!(
+            err.contains("step limit exceeded"),
+            "{:?}: {}",
+            backend,
+            err
+        );
+    }
+}
+
+#[test]
+fn test_limit_errors_are_reported_as_their_own_kind() {
+    for backend in [Backend::Interpreter, Backend::Bytecode] {
+        let mut engine =
+            Aether::new()
+                .with_execution_backend(backend)
+                .with_limits(ExecutionLimits {
+                    max_steps: None,
+                    max_duration_ms: Some(20),
+                    ..ExecutionLimits::default()
+                });
+        let report = engine
+            .eval_report("Set I 0\nWhile (True) {\n    Set I I + 1\n}")
+            .unwrap_err();
+        assert_eq!(report.kind, "ExecutionLimit", "{:?}", backend);
+        assert!(
+            report.message.contains("duration limit exceeded"),
+            "{:?}: {}",
+            backend,
+            report.message
+        );
+    }
+}
+
+#[test]
+fn test_eval_with_limits_overrides_and_restores() {
+    let mut engine = Aether::new();
+    let tight = ExecutionLimits {
+        max_steps: Some(3),
+        ..ExecutionLimits::default()
+    };
+    let err = engine
+        .eval_with_limits("Set A 1\nSet B 2\nSet C 3\nSet D 4", tight.clone())
+        .unwrap_err();
+    assert!(err.contains("step limit exceeded"), "{}", err);
+    assert_eq!(engine.limits(), &ExecutionLimits::default());
+
+    assert!(engine.eval("Set A 1\nSet B 2\nSet C 3\nSet D 4").is_ok());
+    assert!(engine.eval_with_limits("Set A 1", tight).is_ok());
+}
+
+#[test]
+fn test_eval_with_limits_recursion_depth() {
+    let mut engine = Aether::new();
+    engine
+        .eval("Func DEPTH(N) { If (N < 1) { Return 0 } Return DEPTH(N - 1) + 1 }")
+        .unwrap();
+    let shallow = ExecutionLimits {
+        max_recursion_depth: Some(10),
+        ..ExecutionLimits::default()
+    };
+    let err = engine.eval_with_limits("DEPTH(15)", shallow).unwrap_err();
+    assert!(err.contains("Recursion depth limit exceeded"), "{}", err);
+    assert_eq!(engine.eval("DEPTH(15)").unwrap().to_string(), "15");
+}
+
+#[test]
+fn test_loop_limit_error_names_the_loop_and_its_variables() {
+    let mut engine = loop_limited(10);
+    let err = engine
+        .eval(
+            r#"
+            Func COUNTDOWN(N) {
+                Set STEP 0
+                While (N > STEP) {
+                    Set N (N + 1)
+                }
+                Return N
+            }
+            COUNTDOWN(5)
+            "#,
+        )
+        .unwrap_err();
+    assert!(err.contains("in `While (N > STEP)`"), "{}", err);
+    assert!(err.contains("inside COUNTDOWN"), "{}", err);
+    assert!(err.contains("N = 15, STEP = 0"), "{}", err);
+}
+
+#[test]
+fn test_loop_limit_error_for_loops_shows_loop_variable() {
+    let mut engine = loop_limited(3);
+    let err = engine
+        .eval("Set ITEMS RANGE(0, 100)\nFor I In ITEMS { Set X I }")
+        .unwrap_err();
+    assert!(err.contains("in `For I In ITEMS`"), "{}", err);
+    assert!(err.contains("I = 2"), "{}", err);
+    // 大数组只显示前几个元素
+    assert!(err.contains("ITEMS = [0, 1, 2, 3, 4, ...]"), "{}", err);
+    assert!(!err.contains("inside"), "{}", err);
+}
@@ -0,0 +1,179 @@
+// tests/export_tests.rs
+//! 流式导出测试（CSV_WRITER_OPEN / XLSX_WRITER_OPEN / WRITER_ROW / WRITER_CLOSE）
+
+use aether::{Aether, Value};
+use std::path::PathBuf;
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("aether_export_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn csv_writer_streams_rows_with_quoting() {
+    let dir = temp_dir("csv");
+    let path = dir.join("out.csv");
+    let mut engine = Aether::with_all_permissions();
+    let rows = engine
+        .eval(&format!(
+            r#"Set W CSV_WRITER_OPEN("{}", {{"headers": ["id", "name", "note"]}})
+WRITER_ROW(W, [1, "Alice", "says \"hi\""])
+WRITER_ROW(W, {{"id": 2.5, "name": "Bob, Jr."}})
+WRITER_ROW(W, [3, Null, True])
+WRITER_CLOSE(W)"#,
+            path.display()
+        ))
+        .unwrap();
+    assert_eq!(rows, Value::Number(3.0));
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "id,name,note\r\n1,Alice,\"says \"\"hi\"\"\"\r\n2.5,\"Bob, Jr.\",\r\n3,,true\r\n"
+    );
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn dict_rows_define_headers_when_none_are_given() {
+    let dir = temp_dir("dict_headers");
+    let path = dir.join("out.csv");
+    let mut engine = Aether::with_all_permissions();
+    engine
+        .eval(&format!(
+            r#"Set W CSV_WRITER_OPEN("{}", {{"delimiter": ";"}})
+WRITER_ROW(W, {{"b": 1, "a": 2}})
+WRITER_ROW(W, {{"a": 3}})
+WRITER_CLOSE(W)"#,
+            path.display()
+        ))
+        .unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "a;b\r\n2;1\r\n3;\r\n"
+    );
+
+    let err = engine
+        .eval(&format!(
+            r#"Set W CSV_WRITER_OPEN("{}", {{"headers": ["a"]}})
+WRITER_ROW(W, {{"a": 1, "c": 2}})"#,
+            path.display()
+        ))
+        .unwrap_err();
+    assert!(err.contains("column 'c'"), "{}", err);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn many_rows_are_written_incrementally() {
+    let dir = temp_dir("many");
+    let path = dir.join("big.csv");
+    let mut engine = Aether::with_all_permissions();
+    let rows = engine
+        .eval(&format!(
+            r#"Set W CSV_WRITER_OPEN("{}", {{"headers": ["n", "square"]}})
+For I In RANGE(0, 20000) {{
+    WRITER_ROW(W, [I, I * I])
+}}
+WRITER_CLOSE(W)"#,
+            path.display()
+        ))
+        .unwrap();
+    assert_eq!(rows, Value::Number(20000.0));
+    let content = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(content.lines().count(), 20001);
+    assert_eq!(content.lines().last(), Some("19999,399960001"));
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn xlsx_writer_produces_a_zip_workbook() {
+    let dir = temp_dir("xlsx");
+    let path = dir.join("report.xlsx");
+    let mut engine = Aether::with_all_permissions();
+    let rows = engine
+        .eval(&format!(
+            r#"Set W XLSX_WRITER_OPEN("{}", {{"sheet": "工资 & 奖金", "headers": ["工号", "实发"]}})
+WRITER_ROW(W, ["E001", 8650.5])
+WRITER_ROW(W, ["<E002>", Null])
+WRITER_CLOSE(W)"#,
+            path.display()
+        ))
+        .unwrap();
+    assert_eq!(rows, Value::Number(2.0));
+
+    let bytes = std::fs::read(&path).unwrap();
+    assert!(bytes.starts_with(b"PK\x03\x04"));
+    // 中央目录结束记录：6 个条目
+    let eocd = bytes.len() - 22;
+    assert_eq!(&bytes[eocd..eocd + 4], b"PK\x05\x06");
+    assert_eq!(u16::from_le_bytes([bytes[eocd + 10], bytes[eocd + 11]]), 6);
+
+    // 条目不压缩，XML 直接出现在文件中
+    let text = String::from_utf8_lossy(&bytes);
+    assert!(text.contains(r#"<sheet name="工资 &amp; 奖金" sheetId="1" r:id="rId1"/>"#));
+    assert!(text.contains(r#"<c r="B2"><v>8650.5</v></c>"#));
+    assert!(text.contains("&lt;E002&gt;"));
+    assert!(text.contains(r#"<row r="3"><c r="A3" t="inlineStr">"#));
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn writer_errors() {
+    let dir = temp_dir("errors");
+    let path = dir.join("out.xlsx");
+    let mut engine = Aether::with_all_permissions();
+    engine
+        .eval(&format!(r#"Set W XLSX_WRITER_OPEN("{}")"#, path.display()))
+        .unwrap();
+    for code in [
+        "WRITER_ROW(W, [[1, 2]])",
+        "WRITER_ROW(W, 5)",
+        "WRITER_ROW(\"csv:999\", [1])",
+        "WRITER_ROW(\"nope\", [1])",
+        &format!(
+            r#"XLSX_WRITER_OPEN("{}", {{"sheet": "a/b"}})"#,
+            dir.join("bad.xlsx").display()
+        ),
+        &format!(
+            r#"CSV_WRITER_OPEN("{}", {{"delimiter": "ab"}})"#,
+            dir.join("bad.csv").display()
+        ),
+    ] {
+        assert!(engine.eval(code).is_err(), "{} should fail", code);
+    }
+    assert_eq!(engine.eval("WRITER_CLOSE(W)").unwrap(), Value::Number(0.0));
+    assert!(engine.eval("WRITER_CLOSE(W)").is_err());
+
+    // 需要文件系统权限
+    assert!(Aether::new().eval(r#"CSV_WRITER_OPEN("x.csv")"#).is_err());
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn writers_belong_to_the_engine_that_opened_them() {
+    let dir = temp_dir("engine_scope");
+    let path = dir.join("out.csv");
+    let mut owner = Aether::with_all_permissions();
+    let handle = owner
+        .eval(&format!(r#"CSV_WRITER_OPEN("{}")"#, path.display()))
+        .unwrap();
+    let Value::String(handle) = handle else {
+        panic!("expected a handle, got {:?}", handle);
+    };
+    assert!(handle.starts_with("csv:") && handle.len() > "csv:".len() + 16);
+
+    // 其他引擎不能写入或关闭这个写入器
+    let mut other = Aether::with_all_permissions();
+    let row = format!(r#"WRITER_ROW("{}", [1])"#, handle);
+    let close = format!(r#"WRITER_CLOSE("{}")"#, handle);
+    assert!(other.eval(&row).is_err());
+    assert!(other.eval(&close).is_err());
+    assert_eq!(owner.eval(&row).unwrap(), Value::Number(1.0));
+
+    // reset_env 关闭未关闭的写入器
+    owner.reset_env();
+    assert!(owner.eval(&row).is_err());
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "1\r\n");
+    let _ = std::fs::remove_dir_all(&dir);
+}
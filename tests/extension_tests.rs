@@ -0,0 +1,99 @@
+// tests/extension_tests.rs
+//! @TAG { ... } 扩展块测试
+
+use aether::{Aether, Value};
+
+fn engine_with_echo() -> Aether {
+    let mut engine = Aether::new();
+    engine.register_extension("ECHO", |payload, _ctx| {
+        Ok(Value::String(payload.to_string()))
+    });
+    engine
+}
+
+#[test]
+fn handler_receives_trimmed_payload() {
+    let mut engine = engine_with_echo();
+    let result = engine.eval("@ECHO {   SELECT 1   }").unwrap();
+    assert_eq!(result, Value::String("SELECT 1".to_string()));
+}
+
+#[test]
+fn payload_keeps_nested_braces_and_newlines() {
+    let mut engine = engine_with_echo();
+    let result = engine
+        .eval("Set Q @ECHO {\n  {\"a\": {\"b\": 1}}\n}\nQ")
+        .unwrap();
+    assert_eq!(result, Value::String("{\"a\": {\"b\": 1}}".to_string()));
+}
+
+#[test]
+fn extension_is_an_expression() {
+    let mut engine = Aether::new();
+    engine.register_extension("COUNT", |payload, _ctx| {
+        Ok(Value::Number(payload.split_whitespace().count() as f64))
+    });
+    let result = engine
+        .eval("Set N (@COUNT { a b c } + 1)\n[N, LEN([@COUNT { x }])]")
+        .unwrap();
+    assert_eq!(
+        result,
+        Value::Array(vec![Value::Number(4.0), Value::Number(1.0)])
+    );
+}
+
+#[test]
+fn handler_reads_variables_in_scope() {
+    let mut engine = Aether::new();
+    engine.register_extension("VAR", |payload, ctx| {
+        assert_eq!(ctx.tag(), "VAR");
+        ctx.get(payload)
+            .ok_or_else(|| format!("unknown variable {}", payload))
+    });
+    let result = engine
+        .eval(
+            r#"
+            Set OUTER 1
+            Func READ(LOCAL) {
+                Return [@VAR { LOCAL }, @VAR { OUTER }]
+            }
+            READ(2)
+            "#,
+        )
+        .unwrap();
+    assert_eq!(
+        result,
+        Value::Array(vec![Value::Number(2.0), Value::Number(1.0)])
+    );
+}
+
+#[test]
+fn handler_errors_are_catchable_runtime_errors() {
+    let mut engine = Aether::new();
+    engine.register_extension("FAIL", |_payload, _ctx| Err("boom".to_string()));
+    let err = engine.eval("@FAIL { x }").unwrap_err();
+    assert!(err.contains("@FAIL: boom"), "{}", err);
+}
+
+#[test]
+fn unknown_and_unregistered_tags_fail() {
+    let mut engine = engine_with_echo();
+    let err = engine.eval("@SQL { SELECT 1 }").unwrap_err();
+    assert!(
+        err.contains("No handler registered for extension '@SQL'"),
+        "{}",
+        err
+    );
+
+    assert!(engine.unregister_extension("ECHO"));
+    assert!(!engine.unregister_extension("ECHO"));
+    assert!(engine.eval("@ECHO { hi }").is_err());
+}
+
+#[test]
+fn malformed_blocks_are_parse_errors() {
+    let mut engine = engine_with_echo();
+    assert!(engine.eval("@ECHO hi").is_err());
+    assert!(engine.eval("@ { hi }").is_err());
+    assert!(engine.eval("@ECHO { unterminated").is_err());
+}
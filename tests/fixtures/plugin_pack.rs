@@ -0,0 +1,81 @@
+// tests/fixtures/plugin_pack.rs
+//! 测试用插件：只依赖 std，按 C ABI 导出描述符（见 src/plugin.rs）
+//!
+//! 由 tests/plugin_tests.rs 用 `rustc --crate-type cdylib` 编译
+
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+
+#[repr(C)]
+pub struct AetherPluginFunction {
+    name: *const c_char,
+    arity: usize,
+    call: unsafe extern "C" fn(*const c_char) -> *mut c_char,
+}
+
+#[repr(C)]
+pub struct AetherPluginDescriptor {
+    abi_version: u32,
+    name: *const c_char,
+    functions: *const AetherPluginFunction,
+    function_count: usize,
+    free_string: unsafe extern "C" fn(*mut c_char),
+}
+
+/// 以 `--cfg wrong_abi` 编译时模拟 ABI 版本不匹配的插件
+#[cfg(not(wrong_abi))]
+const ABI_VERSION: u32 = 1;
+#[cfg(wrong_abi)]
+const ABI_VERSION: u32 = 999;
+
+unsafe impl Sync for AetherPluginFunction {}
+unsafe impl Sync for AetherPluginDescriptor {}
+
+fn reply(json: String) -> *mut c_char {
+    CString::new(json).unwrap().into_raw()
+}
+
+/// PACK_DOUBLE(n)：参数为 `[n]`
+unsafe extern "C" fn pack_double(args: *const c_char) -> *mut c_char {
+    let args = unsafe { CStr::from_ptr(args) }.to_str().unwrap();
+    match args.trim_matches(|c| c == '[' || c == ']').parse::<f64>() {
+        Ok(n) => reply(format!("{{\"ok\": {}}}", n * 2.0)),
+        Err(_) => reply("{\"error\": \"PACK_DOUBLE 需要一个数字\"}".to_string()),
+    }
+}
+
+/// PACK_ECHO(...)：原样返回参数数组
+unsafe extern "C" fn pack_echo(args: *const c_char) -> *mut c_char {
+    let args = unsafe { CStr::from_ptr(args) }.to_str().unwrap();
+    reply(format!("{{\"ok\": {}}}", args))
+}
+
+unsafe extern "C" fn free_string(s: *mut c_char) {
+    drop(unsafe { CString::from_raw(s) });
+}
+
+static FUNCTIONS: [AetherPluginFunction; 2] = [
+    AetherPluginFunction {
+        name: c"PACK_DOUBLE".as_ptr(),
+        arity: 1,
+        call: pack_double,
+    },
+    AetherPluginFunction {
+        name: c"PACK_ECHO".as_ptr(),
+        arity: 1,
+        call: pack_echo,
+    },
+];
+
+static DESCRIPTOR: AetherPluginDescriptor = AetherPluginDescriptor {
+    abi_version: ABI_VERSION,
+    name: c"test-pack".as_ptr(),
+    functions: FUNCTIONS.as_ptr(),
+    function_count: FUNCTIONS.len(),
+    free_string,
+};
+
+#[unsafe(no_mangle)]
+pub extern "C" fn aether_plugin_descriptor() -> *const AetherPluginDescriptor {
+    &DESCRIPTOR
+}
@@ -0,0 +1,101 @@
+// tests/fork_tests.rs
+//! 引擎快速复制（fork）测试
+
+use aether::{Aether, Value};
+
+fn template() -> Aether {
+    let mut engine = Aether::new();
+    engine
+        .eval("Set RATE 2\nFunc PRICE(QTY) { Return QTY * RATE }")
+        .unwrap();
+    engine
+}
+
+#[test]
+fn fork_shares_template_definitions() {
+    let template = template();
+    let mut fork = template.fork();
+    assert_eq!(fork.eval("PRICE(5)").unwrap(), Value::Number(10.0));
+    assert_eq!(fork.eval("LEN([1, 2, 3])").unwrap(), Value::Number(3.0));
+}
+
+#[test]
+fn fork_definitions_do_not_leak() {
+    let mut template = template();
+    let mut a = template.fork();
+    let mut b = template.fork();
+
+    a.eval("Set USER \"a\"\nSet RATE 10").unwrap();
+    b.eval("Set USER \"b\"").unwrap();
+
+    assert_eq!(a.eval("USER").unwrap(), Value::String("a".to_string()));
+    assert_eq!(b.eval("USER").unwrap(), Value::String("b".to_string()));
+    assert_eq!(a.eval("RATE").unwrap(), Value::Number(10.0));
+    assert_eq!(b.eval("RATE").unwrap(), Value::Number(2.0));
+    assert!(template.eval("USER").is_err());
+    assert_eq!(template.eval("RATE").unwrap(), Value::Number(2.0));
+}
+
+#[test]
+fn index_assignment_copies_shared_values() {
+    let mut template = Aether::new();
+    template.eval("Set ITEMS [1, 2, 3]").unwrap();
+    let mut fork = template.fork();
+    fork.eval("Set ITEMS[0] 99").unwrap();
+    assert_eq!(fork.eval("ITEMS[0]").unwrap(), Value::Number(99.0));
+    assert_eq!(template.eval("ITEMS[0]").unwrap(), Value::Number(1.0));
+}
+
+#[test]
+fn shared_functions_keep_their_definition_scope() {
+    let template = template();
+    let mut fork = template.fork();
+    // PRICE 在模板中定义，读取的是模板中的 RATE
+    fork.eval("Set RATE 100").unwrap();
+    assert_eq!(fork.eval("PRICE(1)").unwrap(), Value::Number(2.0));
+    // 副本中定义的函数读取副本的变量
+    fork.eval("Func LOCAL_PRICE(QTY) { Return QTY * RATE }")
+        .unwrap();
+    assert_eq!(fork.eval("LOCAL_PRICE(1)").unwrap(), Value::Number(100.0));
+}
+
+#[test]
+fn fork_copies_configuration() {
+    let mut template = Aether::new().with_strict(true);
+    template.seal_globals();
+    let mut fork = template.fork();
+    assert!(fork.is_strict());
+    assert!(fork.eval("If (1) { 2 }").is_err());
+    assert!(fork.eval("Set LEN 1").is_err());
+}
+
+#[test]
+fn global_variables_include_shared_scope() {
+    let template = template();
+    let mut fork = template.fork();
+    fork.eval("Set USER \"ada\"").unwrap();
+    let export = fork.export_env_json().unwrap();
+    assert!(export.json.contains("\"USER\":\"ada\""), "{}", export.json);
+    assert!(export.json.contains("\"RATE\":2"), "{}", export.json);
+}
+
+#[test]
+fn fork_of_stdlib_engine_does_not_reload() {
+    let template = Aether::with_stdlib().unwrap();
+    let mut fork = template.fork();
+    assert_eq!(
+        fork.eval("STR_TRIM(\"  hi  \")").unwrap(),
+        Value::String("hi".to_string())
+    );
+}
+
+#[test]
+fn reset_env_replaces_global_scope() {
+    let mut engine = Aether::new();
+    engine.eval("Set OLD 1").unwrap();
+    engine.reset_env();
+    engine.eval("Set NEW 2").unwrap();
+    let export = engine.export_env_json().unwrap();
+    assert!(export.json.contains("\"NEW\":2"), "{}", export.json);
+    assert!(!export.json.contains("OLD"), "{}", export.json);
+}
@@ -0,0 +1,51 @@
+// tests/global_engine_tests.rs
+//! 全局引擎配置测试
+//!
+//! `GlobalEngine::configure` 是进程级配置，因此放在独立的测试文件中。
+
+use aether::Value;
+use aether::builtins::IOPermissions;
+use aether::engine::GlobalEngine;
+use aether::runtime::ExecutionLimits;
+
+#[test]
+fn configure_applies_to_every_thread_engine() {
+    assert!(
+        GlobalEngine::configure(
+            IOPermissions::default(),
+            ExecutionLimits::default(),
+            &["nope"]
+        )
+        .is_err()
+    );
+
+    let limits = ExecutionLimits {
+        max_steps: Some(10_000),
+        ..ExecutionLimits::default()
+    };
+    GlobalEngine::configure(IOPermissions::default(), limits, &["string_utils"]).unwrap();
+    assert!(
+        GlobalEngine::configure(IOPermissions::default(), ExecutionLimits::default(), &[]).is_err()
+    );
+    assert_eq!(
+        GlobalEngine::config().unwrap().stdlib_modules,
+        vec!["string_utils".to_string()]
+    );
+
+    // 预加载的标准库在隔离执行之间保留，脚本变量不保留
+    let trimmed = GlobalEngine::eval_isolated("Set X 1\nSTR_TRIM(\"  hi  \")").unwrap();
+    assert_eq!(trimmed, Value::String("hi".to_string()));
+    assert!(GlobalEngine::eval_isolated("X").is_err());
+    assert!(GlobalEngine::eval_isolated("STR_TRIM(\" a \")").is_ok());
+
+    // 执行限制生效
+    assert!(GlobalEngine::eval_isolated("While (True) { Set Y 1 }").is_err());
+
+    // 其他线程的引擎使用相同配置
+    std::thread::spawn(|| {
+        assert!(GlobalEngine::eval_isolated("STR_TRIM(\" b \")").is_ok());
+        assert!(GlobalEngine::cache_stats().is_some());
+    })
+    .join()
+    .unwrap();
+}
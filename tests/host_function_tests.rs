@@ -0,0 +1,108 @@
+// tests/host_function_tests.rs
+//! 宿主函数注册测试（Aether::register_function）
+
+use aether::{Aether, RuntimeError, Value};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+#[test]
+fn closures_can_capture_host_state() {
+    let prices: HashMap<String, f64> = [("apple".to_string(), 3.5), ("pear".to_string(), 4.0)]
+        .into_iter()
+        .collect();
+    let calls = Rc::new(RefCell::new(0));
+    let counter = Rc::clone(&calls);
+
+    let mut engine = Aether::new();
+    engine
+        .register_function("PRICE_OF", 1, move |args| {
+            *counter.borrow_mut() += 1;
+            match &args[0] {
+                Value::String(item) => Ok(prices
+                    .get(item)
+                    .map(|p| Value::Number(*p))
+                    .unwrap_or(Value::Null)),
+                other => Err(RuntimeError::TypeErrorDetailed {
+                    expected: "String".to_string(),
+                    got: other.type_name().to_string(),
+                }),
+            }
+        })
+        .unwrap();
+
+    let total = engine
+        .eval(r#"PRICE_OF("apple") * 2 + PRICE_OF("pear")"#)
+        .unwrap();
+    assert_eq!(total, Value::Number(11.0));
+    assert_eq!(engine.eval(r#"PRICE_OF("kiwi")"#).unwrap(), Value::Null);
+    assert_eq!(*calls.borrow(), 3);
+}
+
+#[test]
+fn host_functions_are_first_class_values() {
+    let mut engine = Aether::new();
+    engine
+        .register_function("DOUBLE", 1, |args| match &args[0] {
+            Value::Int(n) => Ok(Value::Int(n * 2)),
+            Value::Number(n) => Ok(Value::Number(n * 2.0)),
+            _ => Ok(Value::Null),
+        })
+        .unwrap();
+    assert_eq!(
+        engine.eval("MAP([1, 2, 3], DOUBLE)").unwrap(),
+        Value::Array(vec![
+            Value::Number(2.0),
+            Value::Number(4.0),
+            Value::Number(6.0)
+        ])
+    );
+}
+
+#[test]
+fn arity_is_checked_and_errors_can_be_caught() {
+    let mut engine = Aether::new();
+    engine
+        .register_function("FAIL", 0, |_| {
+            Err(RuntimeError::CustomError(
+                "upstream unavailable".to_string(),
+            ))
+        })
+        .unwrap();
+
+    let err = engine.eval("FAIL(1)").unwrap_err();
+    assert!(err.contains("FAIL"), "{}", err);
+
+    let caught = engine
+        .eval(r#"Try { FAIL() } Catch (ERR) { ERR["message"] }"#)
+        .unwrap();
+    assert_eq!(caught, Value::String("upstream unavailable".to_string()));
+}
+
+#[test]
+fn registration_rejects_builtins_and_invalid_names() {
+    let mut engine = Aether::new();
+    for name in ["LEN", "MAP", "", "1ST", "A-B"] {
+        let result = engine.register_function(name, 0, |_| Ok(Value::Null));
+        assert!(result.is_err(), "{:?} should be rejected", name);
+    }
+
+    // 重复注册宿主函数替换之前的实现
+    engine
+        .register_function("VERSION", 0, |_| Ok(Value::Number(1.0)))
+        .unwrap();
+    engine
+        .register_function("VERSION", 0, |_| Ok(Value::Number(2.0)))
+        .unwrap();
+    assert_eq!(engine.eval("VERSION()").unwrap(), Value::Number(2.0));
+}
+
+#[test]
+fn host_functions_survive_permission_changes() {
+    let mut engine = Aether::new();
+    engine
+        .register_function("ANSWER", 0, |_| Ok(Value::Number(42.0)))
+        .unwrap();
+    engine.set_permissions(aether::IOPermissions::allow_all());
+    assert_eq!(engine.eval("ANSWER()").unwrap(), Value::Number(42.0));
+}
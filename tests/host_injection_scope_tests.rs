@@ -56,3 +56,136 @@ fn can_inject_rust_dict_as_global() {
     // Not leaked
     assert!(engine.eval("DATA").is_err());
 }
+
+#[test]
+fn sealed_globals_cannot_be_redefined_by_scripts() {
+    let mut engine = Aether::new();
+    engine
+        .eval(
+            r#"
+Func CALC_PERSONAL_TAX (X) {
+    Return (X * 0.1)
+}
+Set RATE 0.03
+"#,
+        )
+        .unwrap();
+    engine.seal_globals();
+    assert!(engine.is_sealed("CALC_PERSONAL_TAX"));
+    assert!(engine.is_sealed("RATE"));
+
+    // Still readable and callable
+    assert_eq!(
+        engine.eval("CALC_PERSONAL_TAX(100)").unwrap(),
+        Value::Number(10.0)
+    );
+
+    // Redefinition attempts fail
+    let err = engine
+        .eval("Func CALC_PERSONAL_TAX (X) { Return 0 }")
+        .unwrap_err();
+    assert!(err.contains("sealed"), "{}", err);
+    assert!(engine.eval("Set RATE 0").is_err());
+    assert!(engine.eval("For RATE In [1] { 0 }").is_err());
+    assert!(engine.eval("For I, RATE In [1] { 0 }").is_err());
+    assert!(engine.eval("For RATE, V In [1] { 0 }").is_err());
+    assert!(
+        engine
+            .eval(r#"Try { Throw("x") } Catch (RATE) { 0 }"#)
+            .is_err()
+    );
+    assert_eq!(engine.eval("RATE").unwrap(), Value::Number(0.03));
+
+    // Function locals may reuse a sealed name without touching the global
+    assert_eq!(
+        engine
+            .eval("Func F () { Set RATE 0\nReturn RATE }\nF()")
+            .unwrap(),
+        Value::Number(0.0)
+    );
+    assert_eq!(
+        engine
+            .eval("Func G () { For RATE In [7] { 0 }\nReturn RATE }\nG()")
+            .unwrap(),
+        Value::Number(7.0)
+    );
+    assert_eq!(engine.eval("RATE").unwrap(), Value::Number(0.03));
+
+    // New names remain assignable
+    assert_eq!(engine.eval("Set Y 1\nY").unwrap(), Value::Number(1.0));
+
+    // Host can still update sealed globals
+    engine.set_global("RATE", Value::Number(0.05));
+    assert_eq!(engine.eval("RATE").unwrap(), Value::Number(0.05));
+
+    engine.unseal_globals();
+    assert!(engine.eval("Set RATE 0").is_ok());
+}
+
+#[test]
+fn sealed_loop_variables_in_bytecode_backend() {
+    let mut engine = Aether::new().with_execution_backend(aether::Backend::Bytecode);
+    engine.eval("Set LIMIT 10").unwrap();
+    engine.seal_globals();
+
+    assert!(engine.eval("For LIMIT In [99] { 0 }").is_err());
+    assert!(engine.eval("For I, LIMIT In [99] { 0 }").is_err());
+    assert_eq!(engine.eval("LIMIT").unwrap(), Value::Number(10.0));
+}
+
+#[test]
+fn case_insensitive_builtins_and_aliases() {
+    // 默认严格区分大小写
+    assert!(Aether::new().eval("sum([1, 2])").is_err());
+
+    let mut engine = Aether::new().with_case_insensitive_builtins();
+    assert_eq!(engine.eval("sum([1, 2, 3])").unwrap(), Value::Number(6.0));
+    assert_eq!(engine.eval("Len([1, 2])").unwrap(), Value::Number(2.0));
+    // 已定义的名称优先于回退解析
+    engine.set_global("max", Value::Number(5.0));
+    assert_eq!(engine.eval("max").unwrap(), Value::Number(5.0));
+    assert!(engine.eval("undefined_name").is_err());
+
+    let warnings = engine.builtin_name_warnings();
+    assert_eq!(warnings.len(), 2);
+    assert!(warnings[0].contains("'sum'") && warnings[0].contains("'SUM'"));
+
+    let mut engine = Aether::new();
+    engine.alias("size", "LEN").unwrap();
+    engine.alias("average", "MEAN").unwrap();
+    assert_eq!(engine.eval("size([1, 2, 3])").unwrap(), Value::Number(3.0));
+    assert_eq!(engine.eval("average([1, 3])").unwrap(), Value::Number(2.0));
+    assert!(engine.alias("nope", "NOT_A_BUILTIN").is_err());
+    engine.clear_builtin_name_warnings();
+    assert!(engine.builtin_name_warnings().is_empty());
+}
+
+#[test]
+fn deprecated_and_experimental_builtins() {
+    let mut engine = Aether::new();
+    engine.deprecate_builtin("SUM", Some("TOTAL")).unwrap();
+    engine.deprecate_builtin("MEAN", None).unwrap();
+    assert!(engine.deprecate_builtin("NOT_A_BUILTIN", None).is_err());
+
+    // 弃用的函数照常执行，每个函数只警告一次
+    assert_eq!(engine.eval("SUM([1, 2])").unwrap(), Value::Number(3.0));
+    assert_eq!(engine.eval("SUM([3])").unwrap(), Value::Number(3.0));
+    engine.eval("MEAN([1, 3])").unwrap();
+    let warnings = engine.deprecation_warnings();
+    assert_eq!(warnings.len(), 2);
+    assert!(warnings[0].contains("'SUM'") && warnings[0].contains("'TOTAL'"));
+    engine.clear_deprecation_warnings();
+    assert!(engine.deprecation_warnings().is_empty());
+
+    engine.mark_experimental("REVERSE", "arrays_v2").unwrap();
+    let err = engine.eval("REVERSE([1, 2])").unwrap_err();
+    assert!(
+        err.contains("enable_experimental(\"arrays_v2\")"),
+        "{}",
+        err
+    );
+    engine.enable_experimental("arrays_v2");
+    assert!(engine.eval("REVERSE([1, 2])").is_ok());
+    engine.disable_experimental("arrays_v2");
+    assert!(engine.eval("REVERSE([1, 2])").is_err());
+}
@@ -0,0 +1,102 @@
+// tests/http_server_tests.rs
+//! HTTP_SERVE 测试（需要 http-server 特性）
+#![cfg(feature = "http-server")]
+
+use aether::{Aether, Value};
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+use std::time::Duration;
+
+fn free_port() -> u16 {
+    TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port()
+}
+
+/// 发送原始请求并返回完整响应
+fn send(port: u16, request: &str) -> String {
+    let mut stream = (0..100)
+        .find_map(|_| {
+            TcpStream::connect(("127.0.0.1", port))
+                .map_err(|_| thread::sleep(Duration::from_millis(20)))
+                .ok()
+        })
+        .expect("server did not start");
+    stream.write_all(request.as_bytes()).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    response
+}
+
+#[test]
+fn http_serve_dispatches_requests_to_handler() {
+    let port = free_port();
+    let server = thread::spawn(move || {
+        let mut engine = Aether::with_all_permissions();
+        let code = format!(
+            r#"
+            Func HANDLE(REQ) {{
+                If (REQ["path"] == "/hook") {{
+                    Return {{"status": 201, "body": {{"got": JSON_PARSE(REQ["body"]), "who": REQ["query"]["name"]}}}}
+                }}
+                If (REQ["path"] == "/text") {{
+                    Return "hello " + REQ["headers"]["x-user"]
+                }}
+                If (REQ["path"] == "/boom") {{
+                    Return 1 / "x"
+                }}
+                Return {{"status": 404, "body": "not found", "headers": {{"X-Reason": "missing"}}}}
+            }}
+            HTTP_SERVE({}, HANDLE, {{"max_requests": 4}})
+            "#,
+            port
+        );
+        match engine.eval(&code).unwrap() {
+            Value::Dict(summary) => (
+                summary["requests"].to_string(),
+                summary["errors"].to_string(),
+            ),
+            other => panic!("expected summary dict, got {:?}", other),
+        }
+    });
+
+    let body = r#"{"id": 7}"#;
+    let created = send(
+        port,
+        &format!(
+            "POST /hook?name=a%20b HTTP/1.1\r\nHost: x\r\nContent-Length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        ),
+    );
+    assert!(created.starts_with("HTTP/1.1 201 Created\r\n"));
+    assert!(created.contains("Content-Type: application/json"));
+    assert!(created.ends_with(r#"{"got":{"id":7},"who":"a b"}"#));
+
+    let text = send(port, "GET /text HTTP/1.1\r\nX-User: ann\r\n\r\n");
+    assert!(text.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(text.ends_with("\r\n\r\nhello ann"));
+
+    let failed = send(port, "GET /boom HTTP/1.1\r\n\r\n");
+    assert!(failed.starts_with("HTTP/1.1 500 Internal Server Error\r\n"));
+
+    let missing = send(port, "GET /other HTTP/1.1\r\n\r\n");
+    assert!(missing.starts_with("HTTP/1.1 404 Not Found\r\n"));
+    assert!(missing.contains("X-Reason: missing"));
+
+    let (requests, errors) = server.join().unwrap();
+    assert_eq!((requests.as_str(), errors.as_str()), ("4", "1"));
+}
+
+#[test]
+fn http_serve_requires_network_permission_and_function() {
+    let mut engine = Aether::new();
+    assert!(engine.eval("HTTP_SERVE(0, Lambda R -> R)").is_err());
+
+    let mut engine = Aether::with_all_permissions();
+    assert!(engine.eval("HTTP_SERVE(0, 1)").is_err());
+    assert!(engine.eval("HTTP_SERVE(70000, Lambda R -> R)").is_err());
+}
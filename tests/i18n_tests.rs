@@ -0,0 +1,114 @@
+// tests/i18n_tests.rs
+//! 国际化内置函数测试
+
+use aether::{Aether, Value};
+
+const CATALOG: &str = r#"
+CLEAR_MESSAGES()
+SET_LOCALE("en")
+LOAD_MESSAGES("{\"en\": {\"greeting\": \"Hello, {name}!\", \"slip\": {\"title\": \"Payslip\"}, \"items\": {\"one\": \"{count} item\", \"other\": \"{count} items\"}}, \"zh-CN\": {\"greeting\": \"你好，{name}！\", \"items\": {\"other\": \"{count} 件\"}}, \"ru\": {\"files\": {\"one\": \"{count} файл\", \"few\": \"{count} файла\", \"many\": \"{count} файлов\", \"other\": \"{count} файла\"}}}")
+"#;
+
+fn eval(code: &str) -> Value {
+    Aether::new()
+        .eval(&format!("{}\n{}", CATALOG, code))
+        .unwrap()
+}
+
+fn text(s: &str) -> Value {
+    Value::String(s.to_string())
+}
+
+#[test]
+fn translate_with_params_and_fallback() {
+    assert_eq!(
+        eval(r#"TRANSLATE("greeting", {"name": "Ann"})"#),
+        text("Hello, Ann!")
+    );
+    assert_eq!(
+        eval(r#"TRANSLATE("greeting", "zh_cn", {"name": "张三"})"#),
+        text("你好，张三！")
+    );
+    // 嵌套键展开，找不到的语言回退到 en，找不到的键返回键本身
+    assert_eq!(eval(r#"TRANSLATE("slip.title", "zh-CN")"#), text("Payslip"));
+    assert_eq!(eval(r#"TRANSLATE("missing.key")"#), text("missing.key"));
+    // 未提供的参数保持原样
+    assert_eq!(eval(r#"TRANSLATE("greeting")"#), text("Hello, {name}!"));
+}
+
+#[test]
+fn translate_plural_forms() {
+    assert_eq!(eval(r#"TRANSLATE("items", {"count": 1})"#), text("1 item"));
+    assert_eq!(eval(r#"TRANSLATE("items", {"count": 5})"#), text("5 items"));
+    assert_eq!(
+        eval(r#"TRANSLATE("items", "zh-CN", {"count": 1})"#),
+        text("1 件")
+    );
+    assert_eq!(
+        eval(r#"TRANSLATE("files", "ru", {"count": 21})"#),
+        text("21 файл")
+    );
+    assert_eq!(
+        eval(r#"TRANSLATE("files", "ru", {"count": 3})"#),
+        text("3 файла")
+    );
+    assert_eq!(
+        eval(r#"TRANSLATE("files", "ru", {"count": 11})"#),
+        text("11 файлов")
+    );
+}
+
+#[test]
+fn plural_rules_and_locale() {
+    assert_eq!(eval(r#"PLURAL_CATEGORY(1, "en")"#), text("one"));
+    assert_eq!(eval(r#"PLURAL_CATEGORY(1.5, "en")"#), text("other"));
+    assert_eq!(eval(r#"PLURAL_CATEGORY(0, "fr")"#), text("one"));
+    assert_eq!(eval(r#"PLURAL_CATEGORY(22, "pl")"#), text("few"));
+    assert_eq!(eval(r#"PLURAL_CATEGORY(2, "ar")"#), text("two"));
+    assert_eq!(eval(r#"PLURAL_CATEGORY(1, "zh")"#), text("other"));
+
+    assert_eq!(eval(r#"SET_LOCALE("zh-CN")"#), text("en"));
+    assert_eq!(eval("SET_LOCALE(\"zh-CN\")\nGET_LOCALE()"), text("zh-CN"));
+}
+
+#[test]
+fn load_messages_validation() {
+    let mut engine = Aether::new();
+    assert_eq!(
+        engine
+            .eval(r#"LOAD_MESSAGES({"bye": "Tschüss"}, "de")"#)
+            .unwrap(),
+        Value::Number(1.0)
+    );
+    assert_eq!(
+        engine.eval(r#"TRANSLATE("bye", "de-AT")"#).unwrap(),
+        text("Tschüss")
+    );
+    assert!(engine.eval(r#"LOAD_MESSAGES("not json")"#).is_err());
+    assert!(
+        engine
+            .eval(r#"LOAD_MESSAGES({"n": {"one": "x"}}, "en")"#)
+            .is_err()
+    );
+}
+
+#[test]
+fn catalogs_and_locale_belong_to_the_engine() {
+    let mut first = Aether::new();
+    first
+        .eval(
+            r#"SET_LOCALE("fr")
+LOAD_MESSAGES({"hi": "bonjour"}, "fr")"#,
+        )
+        .unwrap();
+    assert_eq!(first.eval(r#"TRANSLATE("hi")"#).unwrap(), text("bonjour"));
+
+    let mut second = Aether::new();
+    assert_eq!(second.eval("GET_LOCALE()").unwrap(), text("en"));
+    assert_eq!(second.eval(r#"TRANSLATE("hi")"#).unwrap(), text("hi"));
+
+    // reset_env 清空消息目录并恢复默认语言
+    first.reset_env();
+    assert_eq!(first.eval("GET_LOCALE()").unwrap(), text("en"));
+    assert_eq!(first.eval(r#"TRANSLATE("hi", "fr")"#).unwrap(), text("hi"));
+}
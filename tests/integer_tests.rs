@@ -0,0 +1,115 @@
+// tests/integer_tests.rs
+//! 整数类型测试：字面量、运算提升规则、TYPE 与内置函数交互
+
+use aether::{Aether, Value};
+
+fn eval(code: &str) -> Value {
+    Aether::new().eval(code).unwrap()
+}
+
+fn string(s: &str) -> Value {
+    Value::String(s.to_string())
+}
+
+#[test]
+fn literals_without_a_dot_are_integers() {
+    assert!(matches!(eval("42"), Value::Int(42)));
+    assert!(matches!(eval("-7"), Value::Int(-7)));
+    assert!(matches!(eval("42.0"), Value::Number(_)));
+    assert_eq!(eval("TYPE(1)"), string("Integer"));
+    assert_eq!(eval("TYPE(1.5)"), string("Number"));
+    // 整数与数值相等的浮点数比较相等
+    assert_eq!(eval("1 == 1.0"), Value::Boolean(true));
+    assert_eq!(eval("[1, 2] == [1.0, 2.0]"), Value::Boolean(true));
+}
+
+#[test]
+fn integer_arithmetic_is_exact() {
+    // 超过 2^53 的 ID 不会丢失精度
+    assert!(matches!(
+        eval("9007199254740993 + 0"),
+        Value::Int(9007199254740993)
+    ));
+    assert!(matches!(eval("7 % 3"), Value::Int(1)));
+    assert!(matches!(eval("-7 % 3"), Value::Int(-1)));
+    assert!(matches!(eval("6 / 3"), Value::Int(2)));
+    assert!(matches!(eval("7 / 2"), Value::Number(n) if n == 3.5));
+    assert!(Aether::new().eval("1 / 0").is_err());
+    assert!(Aether::new().eval("1 % 0").is_err());
+}
+
+#[test]
+fn overflow_promotes_to_big_integers() {
+    let result = eval("9223372036854775807 + 1");
+    assert!(matches!(result, Value::Fraction(_)), "{:?}", result);
+    assert_eq!(result.to_string(), "9223372036854775808");
+    assert_eq!(
+        eval("-9223372036854775807 - 1 - 1").to_string(),
+        "-9223372036854775809"
+    );
+}
+
+#[test]
+fn mixed_operands_follow_the_wider_type() {
+    assert!(matches!(eval("1 + 0.5"), Value::Number(n) if n == 1.5));
+    assert!(matches!(eval("0.5 * 4"), Value::Number(n) if n == 2.0));
+    assert_eq!(eval("TYPE(1 + 0.5)"), string("Number"));
+    assert!(matches!(
+        eval("12345678901234567890123 + 1"),
+        Value::Fraction(_)
+    ));
+    assert_eq!(eval("1 < 1.5"), Value::Boolean(true));
+}
+
+#[test]
+fn integers_work_with_numeric_builtins_and_indexing() {
+    assert!(matches!(eval("SQRT(16)"), Value::Number(n) if n == 4.0));
+    assert!(matches!(eval("LEN([1, 2, 3])"), Value::Number(n) if n == 3.0));
+    assert!(matches!(eval("[10, 20, 30][1]"), Value::Int(20)));
+    assert!(matches!(eval("\"abc\"[2]"), Value::String(s) if s == "c"));
+    assert!(matches!(
+        eval("REDUCE([1, 2, 3], Lambda(A, B) -> A + B, 0)"),
+        Value::Int(6)
+    ));
+    assert_eq!(
+        eval("Set XS [0, 0]\nSet XS[1] 5\nXS"),
+        Value::Array(vec![Value::Int(0), Value::Int(5)])
+    );
+}
+
+#[test]
+fn to_int_truncates_and_parses() {
+    assert!(matches!(eval("TO_INT(3.9)"), Value::Int(3)));
+    assert!(matches!(eval("TO_INT(-3.9)"), Value::Int(-3)));
+    assert!(matches!(eval("TO_INT(\"42\")"), Value::Int(42)));
+    assert!(matches!(eval("TO_INT(True)"), Value::Int(1)));
+    assert!(Aether::new().eval("TO_INT(\"abc\")").is_err());
+}
+
+#[test]
+fn json_keeps_integers_apart_from_floats() {
+    assert_eq!(
+        eval("JSON_STRINGIFY([1, 1.5, 9007199254740993])"),
+        string("[1,1.5,9007199254740993]")
+    );
+    assert!(matches!(
+        eval("JSON_PARSE(\"9007199254740993\")"),
+        Value::Int(9007199254740993)
+    ));
+    assert_eq!(eval("TYPE(JSON_PARSE(\"2.5\"))"), string("Number"));
+}
+
+#[test]
+fn optimizer_folds_integer_constants_exactly() {
+    let mut engine = Aether::new();
+    assert!(matches!(
+        engine
+            .eval("Set BIG 4611686018427387903 * 2 + 1\nBIG")
+            .unwrap(),
+        Value::Int(i64::MAX)
+    ));
+    assert!(matches!(
+        engine.eval("9223372036854775807 * 2").unwrap(),
+        Value::Fraction(_)
+    ));
+}
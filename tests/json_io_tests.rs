@@ -0,0 +1,79 @@
+use aether::{Aether, Value};
+
+#[test]
+fn test_input_data_is_bound_from_json() {
+    let mut engine = Aether::new();
+    engine
+        .set_input_json(r#"{"user": {"name": "alice", "age": 30}, "tags": ["a", "b"]}"#)
+        .unwrap();
+
+    assert_eq!(
+        engine.eval(r#"INPUT_DATA["user"]["name"]"#).unwrap(),
+        Value::String("alice".to_string())
+    );
+    assert_eq!(
+        engine.eval(r#"LEN(INPUT_DATA["tags"])"#).unwrap(),
+        Value::Number(2.0)
+    );
+}
+
+#[test]
+fn test_empty_input_binds_null() {
+    let mut engine = Aether::new();
+    engine.set_input_json("  \n").unwrap();
+    assert_eq!(engine.eval("INPUT_DATA").unwrap(), Value::Null);
+}
+
+#[test]
+fn test_invalid_input_is_rejected() {
+    let mut engine = Aether::new();
+    let err = engine.set_input_json("{not json").unwrap_err();
+    assert!(err.contains("Invalid JSON input"), "{}", err);
+}
+
+#[test]
+fn test_result_is_emitted_as_json_with_sorted_keys() {
+    let mut engine = Aether::new();
+    engine.set_input_json(r#"[3, 1, 2]"#).unwrap();
+    let result = engine
+        .eval(r#"{"sorted": SORT(INPUT_DATA), "ok": True, "none": Null, "label": "x"}"#)
+        .unwrap();
+
+    assert_eq!(
+        engine.to_json_output(&result, false).unwrap(),
+        r#"{"label":"x","none":null,"ok":true,"sorted":[1.0,2.0,3.0]}"#
+    );
+    let pretty = engine.to_json_output(&result, true).unwrap();
+    assert!(pretty.contains("\n  \"label\": \"x\""), "{}", pretty);
+}
+
+#[test]
+fn test_round_trip_through_filter_script() {
+    let mut engine = Aether::new();
+    engine
+        .set_input_json(r#"{"orders": [{"qty": 2, "price": 5}, {"qty": 1, "price": 7.5}]}"#)
+        .unwrap();
+    let result = engine
+        .eval(
+            r#"
+Set TOTAL 0
+For O In INPUT_DATA["orders"] {
+    Set TOTAL (TOTAL + O["qty"] * O["price"])
+}
+{"total": TOTAL}
+"#,
+        )
+        .unwrap();
+    assert_eq!(
+        engine.to_json_output(&result, false).unwrap(),
+        r#"{"total":17.5}"#
+    );
+}
+
+#[test]
+fn test_non_json_result_is_an_error() {
+    let mut engine = Aether::new();
+    let result = engine.eval("Func F() { Return 1 }\nF").unwrap();
+    let err = engine.to_json_output(&result, false).unwrap_err();
+    assert!(err.contains("cannot be converted to JSON"), "{}", err);
+}
@@ -0,0 +1,113 @@
+// tests/json_tests.rs
+//! JSON_PARSE / JSON_STRINGIFY 测试（含分数与大整数的无损序列化）
+
+use aether::{Aether, Value};
+
+fn eval(code: &str) -> Value {
+    Aether::new().eval(code).unwrap()
+}
+
+fn s(text: &str) -> Value {
+    Value::String(text.to_string())
+}
+
+#[test]
+fn stringify_fraction_modes() {
+    assert_eq!(eval("JSON_STRINGIFY(TO_FRACTION(0.5))"), s("0.5"));
+    assert_eq!(
+        eval(r#"JSON_STRINGIFY(TO_FRACTION(0.5), {"fraction": "tagged"})"#),
+        s(r#"{"$fraction":"1/2"}"#)
+    );
+    assert_eq!(
+        eval(r#"JSON_STRINGIFY([TO_FRACTION(0.25)], {"fraction": "string"})"#),
+        s(r#"["1/4"]"#)
+    );
+    assert_eq!(
+        eval(r#"JSON_STRINGIFY(123456789012345678901234567890, {"fraction": "tagged"})"#),
+        s(r#"{"$bigint":"123456789012345678901234567890"}"#)
+    );
+    assert!(
+        Aether::new()
+            .eval(r#"JSON_STRINGIFY(1, {"fraction": "exact"})"#)
+            .is_err()
+    );
+}
+
+#[test]
+fn tagged_values_round_trip() {
+    let result = eval(
+        r#"
+Set DATA {"rate": TO_FRACTION(1/3), "big": 98765432109876543210987654321, "n": 1}
+Set BACK JSON_PARSE(JSON_STRINGIFY(DATA, {"fraction": "tagged", "indent": 2}))
+[BACK["rate"] == DATA["rate"], BACK["big"] == DATA["big"], TYPE(BACK["rate"])]
+"#,
+    );
+    assert_eq!(
+        result,
+        Value::Array(vec![
+            Value::Boolean(true),
+            Value::Boolean(true),
+            s("Fraction")
+        ])
+    );
+}
+
+#[test]
+fn parse_rejects_invalid_tags() {
+    let mut engine = Aether::new();
+    assert!(
+        engine
+            .eval(r#"JSON_PARSE("{\"$fraction\": \"1/0\"}")"#)
+            .is_err()
+    );
+    assert!(
+        engine
+            .eval(r#"JSON_PARSE("{\"$bigint\": \"abc\"}")"#)
+            .is_err()
+    );
+    // 多个键或非字符串值时按普通字典处理
+    assert_eq!(
+        engine
+            .eval(r#"LEN(JSON_PARSE("{\"$fraction\": \"1/2\", \"x\": 1}"))"#)
+            .unwrap(),
+        Value::Number(2.0)
+    );
+}
+
+#[test]
+fn json_types_map_to_values() {
+    let parsed = eval(
+        r#"JSON_PARSE("{\"name\": \"Alice\", \"tags\": [1, 2.5, null, true], \"meta\": {}}")"#,
+    );
+    let Value::Dict(map) = parsed else {
+        panic!("expected Dict, got {:?}", parsed);
+    };
+    assert_eq!(map["name"], s("Alice"));
+    assert_eq!(
+        map["tags"],
+        Value::Array(vec![
+            Value::Int(1),
+            Value::Number(2.5),
+            Value::Null,
+            Value::Boolean(true)
+        ])
+    );
+    assert!(matches!(&map["meta"], Value::Dict(meta) if meta.is_empty()));
+    assert!(Aether::new().eval(r#"JSON_PARSE("{bad")"#).is_err());
+}
+
+#[test]
+fn stringify_pretty_flag() {
+    assert_eq!(
+        eval(r#"JSON_STRINGIFY({"a": [1, Null]}, True)"#),
+        s("{\n  \"a\": [\n    1,\n    null\n  ]\n}")
+    );
+    assert_eq!(
+        eval(r#"JSON_STRINGIFY({"a": [1, Null]}, False)"#),
+        s(r#"{"a":[1,null]}"#)
+    );
+    assert_eq!(
+        eval(r#"JSON_STRINGIFY({"a": 1}, True)"#),
+        eval(r#"JSON_STRINGIFY({"a": 1}, 2)"#)
+    );
+}
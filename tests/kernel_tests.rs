@@ -0,0 +1,261 @@
+// tests/kernel_tests.rs
+//! Jupyter 内核测试：会话逻辑、消息签名、内核规格安装和 ZMTP 端到端通信
+#![cfg(feature = "kernel")]
+
+use aether::kernel::zmtp::{Connection, SocketType};
+use aether::kernel::{ConnectionInfo, Kernel, KernelSession, Message, install_kernelspec};
+use aether::{Aether, Value};
+use serde_json::{Value as JsonValue, json};
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+fn session() -> KernelSession {
+    KernelSession::new(Aether::new())
+}
+
+fn execute(session: &mut KernelSession, code: &str) -> aether::kernel::Handled {
+    session
+        .handle("execute_request", &json!({ "code": code, "silent": false }))
+        .unwrap()
+}
+
+fn broadcast<'a>(handled: &'a aether::kernel::Handled, msg_type: &str) -> Vec<&'a JsonValue> {
+    handled
+        .broadcasts
+        .iter()
+        .filter(|(t, _)| t == msg_type)
+        .map(|(_, content)| content)
+        .collect()
+}
+
+#[test]
+fn cells_share_state_and_count_executions() {
+    let mut session = session();
+    let first = execute(&mut session, "Set X 20");
+    assert_eq!(first.reply["status"], "ok");
+    assert_eq!(first.reply["execution_count"], 1);
+
+    let second = execute(&mut session, "(X + 22)");
+    assert_eq!(second.reply["execution_count"], 2);
+    let result = broadcast(&second, "execute_result");
+    assert_eq!(result[0]["data"]["text/plain"], "42");
+    assert_eq!(session.execution_count(), 2);
+}
+
+#[test]
+fn output_is_streamed_in_order() {
+    let mut session = session();
+    let handled = execute(
+        &mut session,
+        "PRINTLN(\"before\")\nDISPLAY([1, 2])\nPRINT(\"after\")",
+    );
+    let kinds: Vec<&str> = handled
+        .broadcasts
+        .iter()
+        .map(|(t, _)| t.as_str())
+        .filter(|t| *t != "execute_input")
+        .collect();
+    assert_eq!(kinds, ["stream", "display_data", "stream"]);
+    let streams = broadcast(&handled, "stream");
+    assert_eq!(streams[0]["text"], "before\n");
+    assert_eq!(streams[1]["text"], "after");
+    assert!(broadcast(&handled, "execute_result").is_empty());
+}
+
+#[test]
+fn dict_arrays_render_as_html_tables() {
+    let mut session = session();
+    let handled = execute(
+        &mut session,
+        r#"[{"name": "a<b", "qty": 1}, {"name": "c", "qty": 2}]"#,
+    );
+    let html = broadcast(&handled, "execute_result")[0]["data"]["text/html"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    assert!(html.contains("<th>name</th><th>qty</th>"), "{}", html);
+    assert!(html.contains("<td>a&lt;b</td><td>1</td>"), "{}", html);
+}
+
+#[test]
+fn images_render_with_their_mime_type() {
+    let mut session = session();
+    let handled = execute(
+        &mut session,
+        r#"DISPLAY(IMAGE("<svg></svg>", "image/svg+xml"))"#,
+    );
+    let display = broadcast(&handled, "display_data");
+    assert_eq!(display[0]["data"]["image/svg+xml"], "<svg></svg>");
+}
+
+#[test]
+fn errors_are_reported_with_names() {
+    let mut session = session();
+    let handled = execute(&mut session, "UNDEFINED_THING + 1");
+    assert_eq!(handled.reply["status"], "error");
+    assert_eq!(handled.reply["ename"], "RuntimeError");
+    assert_eq!(broadcast(&handled, "error").len(), 1);
+
+    let handled = execute(&mut session, "Set X (");
+    assert_eq!(handled.reply["ename"], "ParseError");
+}
+
+#[test]
+fn completeness_and_completion() {
+    let mut session = session();
+    let status = |session: &mut KernelSession, code: &str| {
+        session
+            .handle("is_complete_request", &json!({ "code": code }))
+            .unwrap()
+            .reply["status"]
+            .clone()
+    };
+    assert_eq!(status(&mut session, "Set X 1"), "complete");
+    assert_eq!(
+        status(&mut session, "Func F(A) {\n  Return A"),
+        "incomplete"
+    );
+    assert_eq!(status(&mut session, "Set X )"), "invalid");
+
+    execute(&mut session, "Set PRICE_TOTAL 3");
+    let reply = session
+        .handle(
+            "complete_request",
+            &json!({ "code": "Set Y PRI", "cursor_pos": 9 }),
+        )
+        .unwrap()
+        .reply;
+    let matches: Vec<&str> = reply["matches"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(JsonValue::as_str)
+        .collect();
+    assert!(matches.contains(&"PRINTLN"));
+    assert!(matches.contains(&"PRICE_TOTAL"));
+    assert_eq!(reply["cursor_start"], 6);
+}
+
+#[test]
+fn display_and_image_work_outside_the_kernel() {
+    let mut engine = Aether::new();
+    let image = engine.eval(r#"IMAGE("iVBORw0KGgo=")"#).unwrap();
+    let Value::Dict(image) = image else {
+        panic!("IMAGE should return a Dict");
+    };
+    assert_eq!(image["mime"], Value::String("image/png".to_string()));
+    assert_eq!(engine.eval("DISPLAY(1)").unwrap(), Value::Null);
+    assert!(engine.eval(r#"IMAGE("x", "text/plain")"#).is_err());
+}
+
+#[test]
+fn messages_are_signed_and_verified() {
+    let message = Message::new("execute_request", "session", json!({ "code": "1" }));
+    let frames = message.to_frames(b"secret");
+    let parsed = Message::from_frames(frames.clone(), b"secret").unwrap();
+    assert_eq!(parsed, message);
+
+    assert!(Message::from_frames(frames.clone(), b"other").is_err());
+    let mut tampered = frames;
+    let content = tampered.len() - 1;
+    tampered[content] = br#"{"code":"2"}"#.to_vec();
+    assert!(Message::from_frames(tampered, b"secret").is_err());
+}
+
+#[test]
+fn install_writes_kernel_spec() {
+    let dir = std::env::temp_dir().join(format!("aether_kernelspec_{}", std::process::id()));
+    let exe = PathBuf::from("/usr/local/bin/aether");
+    let kernel_dir = install_kernelspec(Some(&dir), &exe, "Aether Test").unwrap();
+    let spec: JsonValue =
+        serde_json::from_str(&std::fs::read_to_string(kernel_dir.join("kernel.json")).unwrap())
+            .unwrap();
+    assert_eq!(spec["display_name"], "Aether Test");
+    assert_eq!(spec["language"], "aether");
+    assert_eq!(
+        spec["argv"],
+        json!([
+            "/usr/local/bin/aether",
+            "kernel",
+            "--connection-file",
+            "{connection_file}"
+        ])
+    );
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+fn free_port() -> u16 {
+    TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port()
+}
+
+fn connect(port: u16, socket_type: SocketType) -> Connection {
+    let addr = format!("127.0.0.1:{}", port);
+    for _ in 0..100 {
+        if let Ok(connection) = Connection::connect(&addr, socket_type) {
+            return connection;
+        }
+        thread::sleep(Duration::from_millis(20));
+    }
+    panic!("kernel did not start listening on {}", addr);
+}
+
+#[test]
+fn kernel_speaks_zmtp() {
+    let dir = std::env::temp_dir().join(format!("aether_kernel_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let (shell, iopub, stdin, control, hb) = (
+        free_port(),
+        free_port(),
+        free_port(),
+        free_port(),
+        free_port(),
+    );
+    let connection_file = dir.join("connection.json");
+    std::fs::write(
+        &connection_file,
+        json!({
+            "transport": "tcp",
+            "ip": "127.0.0.1",
+            "shell_port": shell,
+            "iopub_port": iopub,
+            "stdin_port": stdin,
+            "control_port": control,
+            "hb_port": hb,
+            "key": "test-key",
+            "signature_scheme": "hmac-sha256",
+        })
+        .to_string(),
+    )
+    .unwrap();
+    let info = ConnectionInfo::from_file(&connection_file).unwrap();
+
+    let kernel = thread::spawn(move || Kernel::with_engine(info, Aether::new()).run());
+
+    let mut heartbeat = connect(hb, SocketType::Req);
+    heartbeat.send(&[Vec::new(), b"ping".to_vec()]).unwrap();
+    assert_eq!(heartbeat.recv().unwrap(), [Vec::new(), b"ping".to_vec()]);
+
+    let mut shell = connect(shell, SocketType::Dealer);
+    let key = b"test-key";
+    let request = Message::new("execute_request", "client", json!({ "code": "(6 * 7)" }));
+    shell.send(&request.to_frames(key)).unwrap();
+    let reply = Message::from_frames(shell.recv().unwrap(), key).unwrap();
+    assert_eq!(reply.msg_type(), "execute_reply");
+    assert_eq!(reply.content["status"], "ok");
+    assert_eq!(reply.parent_header["msg_id"], request.header["msg_id"]);
+
+    let mut control = connect(control, SocketType::Dealer);
+    let shutdown = Message::new("shutdown_request", "client", json!({ "restart": false }));
+    control.send(&shutdown.to_frames(key)).unwrap();
+    let reply = Message::from_frames(control.recv().unwrap(), key).unwrap();
+    assert_eq!(reply.msg_type(), "shutdown_reply");
+
+    kernel.join().unwrap().unwrap();
+    let _ = std::fs::remove_dir_all(&dir);
+}
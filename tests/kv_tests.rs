@@ -0,0 +1,136 @@
+// tests/kv_tests.rs
+//! 持久化键值存储测试
+
+use aether::{Aether, Value};
+
+fn store_path(name: &str) -> std::path::PathBuf {
+    let path = std::env::temp_dir().join(format!("aether_kv_{}_{}.json", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+#[test]
+fn kv_requires_filesystem_permission() {
+    let mut engine = Aether::new();
+    assert!(engine.eval(r#"KV_OPEN("state.json")"#).is_err());
+}
+
+#[test]
+fn kv_set_get_delete_keys() {
+    let path = store_path("basic");
+    let mut engine = Aether::with_all_permissions();
+    let code = format!(
+        r#"
+        Set DB KV_OPEN("{}")
+        KV_SET(DB, "count", 3)
+        KV_SET(DB, "name", "aether")
+        KV_SET(DB, "tmp", [1, 2])
+        Set REMOVED KV_DELETE(DB, "tmp")
+        Set MISSING KV_DELETE(DB, "tmp")
+        [KV_GET(DB, "count"), KV_GET(DB, "absent"), KV_GET(DB, "absent", 0), REMOVED, MISSING, KV_KEYS(DB)]
+        "#,
+        path.display()
+    );
+    let result = engine.eval(&code).unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    assert_eq!(
+        result,
+        Value::Array(vec![
+            Value::Number(3.0),
+            Value::Null,
+            Value::Number(0.0),
+            Value::Boolean(true),
+            Value::Boolean(false),
+            Value::Array(vec![
+                Value::String("count".to_string()),
+                Value::String("name".to_string()),
+            ]),
+        ])
+    );
+}
+
+#[test]
+fn kv_persists_between_runs() {
+    let path = store_path("persist");
+    let run = format!(
+        r#"
+        Set DB KV_OPEN("{}")
+        Set RUNS KV_GET(DB, "runs", 0) + 1
+        KV_SET(DB, "runs", RUNS)
+        KV_SET(DB, "share", TO_FRACTION(1) / 3)
+        RUNS
+        "#,
+        path.display()
+    );
+
+    assert_eq!(
+        Aether::with_all_permissions().eval(&run).unwrap(),
+        Value::Number(1.0)
+    );
+    assert_eq!(
+        Aether::with_all_permissions().eval(&run).unwrap(),
+        Value::Number(2.0)
+    );
+
+    let mut engine = Aether::with_all_permissions();
+    let share = engine
+        .eval(&format!(
+            r#"TYPE(KV_GET(KV_OPEN("{}"), "share"))"#,
+            path.display()
+        ))
+        .unwrap();
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(share, Value::String("Fraction".to_string()));
+}
+
+#[test]
+fn kv_rejects_unopened_store_and_invalid_file() {
+    let path = store_path("invalid");
+    let mut engine = Aether::with_all_permissions();
+    assert!(
+        engine
+            .eval(&format!(r#"KV_GET("{}", "key")"#, path.display()))
+            .is_err()
+    );
+
+    std::fs::write(&path, "[1, 2, 3]").unwrap();
+    let result = engine.eval(&format!(r#"KV_OPEN("{}")"#, path.display()));
+    let _ = std::fs::remove_file(&path);
+    assert!(result.is_err());
+}
+
+#[test]
+fn kv_stores_belong_to_the_engine_and_see_other_writers() {
+    let path = store_path("shared");
+    let open = format!(r#"Set DB KV_OPEN("{}")"#, path.display());
+    let mut first = Aether::with_all_permissions();
+    let mut second = Aether::with_all_permissions();
+    first.eval(&open).unwrap();
+    second.eval(&open).unwrap();
+
+    // 另一个引擎的写入不会被覆盖，读取看到的是文件的最新内容
+    first.eval(r#"KV_SET(DB, "a", 1)"#).unwrap();
+    second.eval(r#"KV_SET(DB, "b", 2)"#).unwrap();
+    first.eval(r#"KV_SET(DB, "c", 3)"#).unwrap();
+    let keys = second.eval("KV_KEYS(DB)").unwrap();
+
+    // 未打开存储的引擎不能使用句柄，reset_env 关闭已打开的存储
+    let handle = format!(r#"KV_GET("{}", "a")"#, path.display());
+    let unopened = Aether::with_all_permissions().eval(&handle);
+    first.reset_env();
+    let after_reset = first.eval(&handle);
+    let _ = std::fs::remove_file(&path);
+
+    assert_eq!(
+        keys,
+        Value::Array(
+            ["a", "b", "c"]
+                .iter()
+                .map(|k| Value::String(k.to_string()))
+                .collect()
+        )
+    );
+    assert!(unopened.is_err());
+    assert!(after_reset.is_err());
+}
@@ -0,0 +1,112 @@
+// tests/language_version_tests.rs
+//! `#language` 编译指示与引擎语言版本设置测试
+
+use aether::{Aether, LanguageVersion, Value};
+
+#[test]
+fn latest_version_is_the_default() {
+    let engine = Aether::new();
+    assert_eq!(engine.language_version(), LanguageVersion::LATEST);
+    assert_eq!(
+        LanguageVersion::parse("1.0").unwrap(),
+        LanguageVersion::V1_0
+    );
+    assert!(LanguageVersion::parse("9.9").is_err());
+}
+
+#[test]
+fn pragma_hides_newer_builtins() {
+    let mut engine = Aether::new();
+    let err = engine.eval("#language 1.0\nCUMSUM([1, 2])").unwrap_err();
+    assert!(err.contains("requires language 1.1"), "{}", err);
+
+    // 1.0 的内置函数不受影响
+    assert_eq!(
+        engine.eval("#language 1.0\nSUM([1, 2])").unwrap(),
+        Value::Number(3.0)
+    );
+    // 编译指示只作用于所在脚本
+    assert!(engine.eval("CUMSUM([1, 2])").is_ok());
+}
+
+#[test]
+fn pragma_gates_newer_syntax() {
+    let mut engine = Aether::new();
+    engine.register_extension("UPPER", |payload, _| {
+        Ok(Value::String(payload.to_uppercase()))
+    });
+    let err = engine.eval("#language 1.0\n@UPPER { hi }").unwrap_err();
+    assert!(err.contains("require language 1.1"), "{}", err);
+    assert_eq!(
+        engine.eval("#language 1.1\n@UPPER { hi }").unwrap(),
+        Value::String("HI".to_string())
+    );
+}
+
+#[test]
+fn engine_setting_applies_without_pragma() {
+    let mut engine = Aether::new().with_language_version(LanguageVersion::V1_0);
+    assert!(engine.eval("SET([1])").is_err());
+    assert!(engine.eval("#language 1.1\nSET([1])").is_ok());
+    // 用户定义的同名函数在旧版本下照常工作
+    assert_eq!(
+        engine.eval("Func NOW() { Return 42 }\nNOW()").unwrap(),
+        Value::Number(42.0)
+    );
+}
+
+#[test]
+fn stdlib_runs_under_language_1_0() {
+    let calls = [
+        "SET_TO_ARRAY(SET_UNION([1, 2], [2, 3]))",
+        "SET_SIZE(SET_FROM_ARRAY([1, 1, 2]))",
+        "VALIDATE_ID_CARD_CN(\"11010519491231002X\")",
+        "VALIDATE_CREDIT_CARD(\"4111111111111111\")",
+        "VALIDATE_EMAIL(\"ada@example.com\")",
+        "TEMPLATE_RENDER(\"Hi {{NAME}}\", {\"NAME\": \"Ada\"})",
+    ];
+    let mut latest = Aether::new();
+    latest.load_all_stdlib().unwrap();
+    let mut pinned = Aether::new().with_language_version(LanguageVersion::V1_0);
+    pinned.load_all_stdlib().unwrap();
+    let mut pragma = Aether::new();
+    pragma.load_all_stdlib().unwrap();
+
+    for call in calls {
+        let expected = latest.eval(call).unwrap();
+        assert_eq!(pinned.eval(call).unwrap(), expected, "{}", call);
+        let code = format!("#language 1.0\n{}", call);
+        assert_eq!(pragma.eval(&code).unwrap(), expected, "{}", call);
+    }
+
+    // 脚本自己的调用仍受版本限制，包括在脚本定义的函数中
+    let err = pinned.eval("SET([1])").unwrap_err();
+    assert!(err.contains("requires language 1.1"), "{}", err);
+    let err = pinned
+        .eval("Func WRAP(X) { Return UNION(X, X) }\nWRAP(SET([1]))")
+        .unwrap_err();
+    assert!(err.contains("requires language 1.1"), "{}", err);
+
+    // 与标准库函数同名的脚本函数也是脚本自己的代码
+    let err = pinned
+        .eval("Func ARR_UNIQUE(A) { Return SET(A) }\nARR_UNIQUE([1])")
+        .unwrap_err();
+    assert!(err.contains("requires language 1.1"), "{}", err);
+}
+
+#[test]
+fn unknown_pragmas_and_versions_are_errors() {
+    let mut engine = Aether::new();
+    assert!(
+        engine
+            .eval("#language 2.0\n1")
+            .unwrap_err()
+            .contains("Unsupported language version")
+    );
+    assert!(
+        engine
+            .eval("#optimize fast\n1")
+            .unwrap_err()
+            .contains("Unknown pragma")
+    );
+}
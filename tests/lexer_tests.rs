@@ -1,4 +1,4 @@
-use aether::{Lexer, Token};
+use aether::{Lexer, Token, TokenKind};
 
 #[test]
 fn test_basic_tokens() {
@@ -7,7 +7,7 @@ fn test_basic_tokens() {
 
     assert_eq!(lexer.next_token(), Token::Set);
     assert_eq!(lexer.next_token(), Token::Identifier("X".to_string()));
-    assert_eq!(lexer.next_token(), Token::Number(10.0));
+    assert_eq!(lexer.next_token(), Token::Integer(10));
     assert_eq!(lexer.next_token(), Token::EOF);
 }
 
@@ -60,10 +60,18 @@ fn test_numbers() {
     let input = "123 45.67 0.5";
     let mut lexer = Lexer::new(input);
 
-    assert_eq!(lexer.next_token(), Token::Number(123.0));
+    assert_eq!(lexer.next_token(), Token::Integer(123));
     assert_eq!(lexer.next_token(), Token::Number(45.67));
     assert_eq!(lexer.next_token(), Token::Number(0.5));
     assert_eq!(lexer.next_token(), Token::EOF);
+
+    // Integer literals beyond i64 become big integers
+    let mut lexer = Lexer::new("9223372036854775807 9223372036854775808");
+    assert_eq!(lexer.next_token(), Token::Integer(i64::MAX));
+    assert_eq!(
+        lexer.next_token(),
+        Token::BigInteger("9223372036854775808".to_string())
+    );
 }
 
 #[test]
@@ -125,11 +133,11 @@ fn test_line_comment() {
 
     assert_eq!(lexer.next_token(), Token::Set);
     assert_eq!(lexer.next_token(), Token::Identifier("X".to_string()));
-    assert_eq!(lexer.next_token(), Token::Number(10.0));
+    assert_eq!(lexer.next_token(), Token::Integer(10));
     assert_eq!(lexer.next_token(), Token::Newline);
     assert_eq!(lexer.next_token(), Token::Set);
     assert_eq!(lexer.next_token(), Token::Identifier("Y".to_string()));
-    assert_eq!(lexer.next_token(), Token::Number(20.0));
+    assert_eq!(lexer.next_token(), Token::Integer(20));
 }
 
 #[test]
@@ -139,7 +147,7 @@ fn test_block_comment() {
 
     assert_eq!(lexer.next_token(), Token::Set);
     assert_eq!(lexer.next_token(), Token::Identifier("X".to_string()));
-    assert_eq!(lexer.next_token(), Token::Number(10.0));
+    assert_eq!(lexer.next_token(), Token::Integer(10));
 }
 
 #[test]
@@ -149,7 +157,7 @@ fn test_newlines() {
 
     assert_eq!(lexer.next_token(), Token::Set);
     assert_eq!(lexer.next_token(), Token::Identifier("X".to_string()));
-    assert_eq!(lexer.next_token(), Token::Number(10.0));
+    assert_eq!(lexer.next_token(), Token::Integer(10));
     assert_eq!(lexer.next_token(), Token::Newline);
     assert_eq!(lexer.line(), 2);
     assert_eq!(lexer.next_token(), Token::Set);
@@ -183,3 +191,42 @@ fn test_complex_expression() {
     assert_eq!(lexer.next_token(), Token::Newline);
     assert_eq!(lexer.next_token(), Token::RightBrace);
 }
+
+#[test]
+fn test_tokenize_with_spans_covers_source() {
+    let input = "Set NAME \"hi\" /* note */\n  PRINTLN(NAME) // done\n";
+    let tokens = Lexer::tokenize_with_spans(input);
+    let chars: Vec<char> = input.chars().collect();
+
+    let mut rebuilt = String::new();
+    let mut offset = 0;
+    for (_, span) in &tokens {
+        assert_eq!(span.start, offset);
+        rebuilt.extend(&chars[span.start..span.end]);
+        offset = span.end;
+    }
+    assert_eq!(rebuilt, input);
+
+    let kinds: Vec<TokenKind> = tokens.iter().map(|(token, _)| token.kind()).collect();
+    assert_eq!(
+        &kinds[..8],
+        [
+            TokenKind::Keyword,
+            TokenKind::Whitespace,
+            TokenKind::Identifier,
+            TokenKind::Whitespace,
+            TokenKind::String,
+            TokenKind::Whitespace,
+            TokenKind::Comment,
+            TokenKind::Newline,
+        ]
+    );
+
+    let (token, span) = &tokens[9];
+    assert_eq!(*token, Token::Identifier("PRINTLN".to_string()));
+    assert_eq!((span.line, span.column), (2, 3));
+    assert_eq!(
+        tokens.iter().filter(|(token, _)| token.is_trivia()).count(),
+        7
+    );
+}
@@ -23,3 +23,17 @@ fn test_cache_usage() {
     assert_eq!(stats.hits, 1);
     assert_eq!(stats.misses, 1);
 }
+
+#[test]
+fn test_benchmark_reports_timings_and_result() {
+    let mut engine = Aether::new();
+    let report = engine
+        .benchmark("Func SQUARE(X) { Return X * X }\nSQUARE(12)")
+        .unwrap();
+    assert_eq!(report.result, Value::Number(144.0));
+    assert!(report.iterations >= 3);
+    assert!(report.min <= report.mean && report.mean <= report.max);
+    assert!(report.to_string().contains("iterations"));
+
+    assert!(engine.benchmark("Set (").is_err());
+}
@@ -0,0 +1,113 @@
+// tests/loop_control_tests.rs
+//! 循环控制测试：Break/Continue 在各层嵌套中的行为，以及在循环外使用时报错
+
+use aether::{Aether, Backend, Value};
+
+fn eval(backend: Backend, code: &str) -> Result<Value, String> {
+    Aether::new().with_execution_backend(backend).eval(code)
+}
+
+const BACKENDS: [Backend; 2] = [Backend::Interpreter, Backend::Bytecode];
+
+#[test]
+fn break_and_continue_apply_to_the_innermost_loop() {
+    let code = r#"Set PAIRS []
+For I In RANGE(0, 4) {
+    If (I == 1) { Continue }
+    Set J 0
+    While (True) {
+        Set J J + 1
+        If (J > I) { Break }
+        If (J == 2) { Continue }
+        Set PAIRS PUSH(PAIRS, [I, J])
+    }
+    If (I == 2) { Break }
+}
+PAIRS"#;
+    let pair = |i: f64, j: f64| Value::Array(vec![Value::Number(i), Value::Number(j)]);
+    for backend in BACKENDS {
+        assert_eq!(
+            eval(backend, code).unwrap(),
+            Value::Array(vec![pair(2.0, 1.0)]),
+            "{:?}",
+            backend
+        );
+    }
+}
+
+#[test]
+fn loop_control_reaches_through_nested_blocks() {
+    let code = r#"Set TOTAL 0
+For I, X In [5, 6, 7, 8, 9] {
+    Switch (X) {
+        Case 6: Continue
+        Case 8: Break
+    }
+    Try {
+        If (I > 0) {
+            If (X == 7) { Continue }
+        }
+    } Catch (E) { 0 }
+    Set TOTAL TOTAL + X
+}
+TOTAL"#;
+    for backend in BACKENDS {
+        assert_eq!(eval(backend, code).unwrap(), Value::Number(5.0));
+    }
+}
+
+#[test]
+fn break_outside_a_loop_is_an_error() {
+    for backend in BACKENDS {
+        let err = eval(backend, "Set X 1\nBreak").unwrap_err();
+        assert!(err.contains("Break outside of loop at line 2"), "{}", err);
+
+        let err = eval(backend, "If (True) {\n    Continue\n}").unwrap_err();
+        assert!(
+            err.contains("Continue outside of loop at line 2"),
+            "{}",
+            err
+        );
+    }
+}
+
+#[test]
+fn functions_cannot_leave_the_callers_loop() {
+    let code = r#"Func STOP() {
+    Break
+}
+Set N 0
+While (N < 3) {
+    Set N N + 1
+    STOP()
+}
+N"#;
+    for backend in BACKENDS {
+        let err = eval(backend, code).unwrap_err();
+        assert!(err.contains("Break outside of loop at line 2"), "{}", err);
+        assert!(err.contains("STOP() called at line 7"), "{}", err);
+    }
+
+    // 函数自己的循环不受调用位置影响
+    let code = r#"Func FIRST_NEG(XS) {
+    Set FOUND Null
+    For X In XS {
+        If (X < 0) {
+            Set FOUND X
+            Break
+        }
+    }
+    Return FOUND
+}
+Set OUT []
+For XS In [[1, -2, -3], [4]] {
+    Set OUT PUSH(OUT, FIRST_NEG(XS))
+}
+OUT"#;
+    for backend in BACKENDS {
+        assert_eq!(
+            eval(backend, code).unwrap(),
+            Value::Array(vec![Value::Number(-2.0), Value::Null])
+        );
+    }
+}
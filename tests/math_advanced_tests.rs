@@ -347,6 +347,139 @@ fn test_matmul() {
     assert_eq!(result, expected);
 }
 
+fn matrix(rows: &[&[f64]]) -> Value {
+    Value::Array(
+        rows.iter()
+            .map(|row| Value::Array(row.iter().map(|n| Value::Number(*n)).collect()))
+            .collect(),
+    )
+}
+
+fn numbers(value: &Value) -> Vec<f64> {
+    match value {
+        Value::Array(items) => items
+            .iter()
+            .map(|v| match v {
+                Value::Number(n) => *n,
+                other => panic!("Expected number, got {:?}", other),
+            })
+            .collect(),
+        other => panic!("Expected array, got {:?}", other),
+    }
+}
+
+fn assert_close(actual: &[f64], expected: &[f64]) {
+    assert_eq!(
+        actual.len(),
+        expected.len(),
+        "{:?} vs {:?}",
+        actual,
+        expected
+    );
+    for (a, e) in actual.iter().zip(expected) {
+        assert!((a - e).abs() < 1e-6, "{:?} vs {:?}", actual, expected);
+    }
+}
+
+#[test]
+fn test_matrix_constructors() {
+    assert_eq!(
+        math::identity_matrix(&[Value::Number(2.0)]).unwrap(),
+        matrix(&[&[1.0, 0.0], &[0.0, 1.0]])
+    );
+    assert_eq!(
+        math::zeros(&[Value::Number(3.0)]).unwrap(),
+        Value::Array(vec![Value::Number(0.0); 3])
+    );
+    assert_eq!(
+        math::ones(&[Value::Number(2.0), Value::Number(3.0)]).unwrap(),
+        matrix(&[&[1.0, 1.0, 1.0], &[1.0, 1.0, 1.0]])
+    );
+
+    let flat = Value::Array((1..=6).map(|n| Value::Number(n as f64)).collect());
+    let reshaped = math::reshape(&[flat, Value::Number(2.0), Value::Number(3.0)]).unwrap();
+    assert_eq!(reshaped, matrix(&[&[1.0, 2.0, 3.0], &[4.0, 5.0, 6.0]]));
+    let back = math::reshape(&[reshaped, Value::Number(3.0), Value::Number(2.0)]).unwrap();
+    assert_eq!(back, matrix(&[&[1.0, 2.0], &[3.0, 4.0], &[5.0, 6.0]]));
+
+    let bad = math::reshape(&[
+        matrix(&[&[1.0, 2.0]]),
+        Value::Number(3.0),
+        Value::Number(1.0),
+    ]);
+    assert!(bad.is_err());
+}
+
+#[test]
+fn test_solve() {
+    let a = matrix(&[&[2.0, 1.0], &[1.0, 3.0]]);
+    let b = Value::Array(vec![Value::Number(3.0), Value::Number(5.0)]);
+    let x = math::solve(&[a.clone(), b]).unwrap();
+    assert_close(&numbers(&x), &[0.8, 1.4]);
+
+    // 右侧为矩阵时同时求解多组
+    let x = math::solve(&[a, matrix(&[&[3.0, 2.0], &[5.0, 1.0]])]).unwrap();
+    let Value::Array(rows) = x else {
+        panic!("Expected matrix");
+    };
+    assert_close(&numbers(&rows[0]), &[0.8, 1.0]);
+    assert_close(&numbers(&rows[1]), &[1.4, 0.0]);
+
+    let singular = matrix(&[&[1.0, 2.0], &[2.0, 4.0]]);
+    let b = Value::Array(vec![Value::Number(1.0), Value::Number(2.0)]);
+    assert!(math::solve(&[singular, b]).is_err());
+}
+
+#[test]
+fn test_rank() {
+    let rank = |rows: &[&[f64]]| math::rank(&[matrix(rows)]).unwrap();
+    assert_eq!(rank(&[&[1.0, 0.0], &[0.0, 1.0]]), Value::Number(2.0));
+    assert_eq!(rank(&[&[1.0, 2.0], &[2.0, 4.0]]), Value::Number(1.0));
+    assert_eq!(rank(&[&[0.0, 0.0], &[0.0, 0.0]]), Value::Number(0.0));
+    assert_eq!(
+        rank(&[&[1.0, 2.0, 3.0], &[4.0, 5.0, 6.0]]),
+        Value::Number(2.0)
+    );
+}
+
+#[test]
+fn test_eigen_symmetric() {
+    let m = matrix(&[&[2.0, 1.0], &[1.0, 2.0]]);
+    let values = math::eigenvalues(std::slice::from_ref(&m)).unwrap();
+    assert_close(&numbers(&values), &[3.0, 1.0]);
+
+    let Value::Array(vectors) = math::eigenvectors(&[m]).unwrap() else {
+        panic!("Expected array");
+    };
+    let s = std::f64::consts::FRAC_1_SQRT_2;
+    assert_close(&numbers(&vectors[0]), &[s, s]);
+    let second = numbers(&vectors[1]);
+    assert!((second[0].abs() - s).abs() < 1e-6);
+    assert!((second[0] + second[1]).abs() < 1e-6);
+}
+
+#[test]
+fn test_eigen_general() {
+    // 非对称矩阵走 QR 迭代
+    let m = matrix(&[&[4.0, 1.0], &[2.0, 3.0]]);
+    let values = math::eigenvalues(std::slice::from_ref(&m)).unwrap();
+    assert_close(&numbers(&values), &[5.0, 2.0]);
+
+    // A·v = λ·v
+    let Value::Array(vectors) = math::eigenvectors(&[m]).unwrap() else {
+        panic!("Expected array");
+    };
+    for (lambda, vector) in [5.0, 2.0].iter().zip(&vectors) {
+        let v = numbers(vector);
+        let av = [4.0 * v[0] + v[1], 2.0 * v[0] + 3.0 * v[1]];
+        assert_close(&av, &[lambda * v[0], lambda * v[1]]);
+    }
+
+    // 旋转矩阵只有复数特征值
+    let rotation = matrix(&[&[0.0, -1.0], &[1.0, 0.0]]);
+    assert!(math::eigenvalues(&[rotation]).is_err());
+}
+
 // ============================================================================
 // 常数测试
 // ============================================================================
@@ -473,3 +473,73 @@ fn test_linear_regression_errors() {
         Ok(v) => panic!("Expected error for insufficient points, got: {:?}", v),
     }
 }
+
+fn numbers(values: &[f64]) -> Value {
+    Value::Array(values.iter().map(|n| Value::Number(*n)).collect())
+}
+
+/// 测试累计和、累计积与差分
+#[test]
+fn test_cumulative_and_diff() {
+    let data = numbers(&[1.0, 2.0, 3.0, 4.0]);
+    assert_eq!(
+        to_vec_f64(&math::cumsum(std::slice::from_ref(&data)).unwrap()),
+        vec![1.0, 3.0, 6.0, 10.0]
+    );
+    assert_eq!(
+        to_vec_f64(&math::cumprod(std::slice::from_ref(&data)).unwrap()),
+        vec![1.0, 2.0, 6.0, 24.0]
+    );
+    assert_eq!(
+        to_vec_f64(&math::diff(std::slice::from_ref(&data)).unwrap()),
+        vec![1.0, 1.0, 1.0]
+    );
+    assert_eq!(
+        to_vec_f64(
+            &math::diff(&[numbers(&[1.0, 2.0, 4.0, 8.0, 16.0]), Value::Number(2.0)]).unwrap()
+        ),
+        vec![3.0, 6.0, 12.0]
+    );
+    assert_eq!(
+        math::cumsum(&[numbers(&[])]).unwrap(),
+        Value::Array(Vec::new())
+    );
+    assert!(math::cumsum(&[Value::Array(vec![Value::String("x".into())])]).is_err());
+}
+
+/// 测试滚动窗口聚合
+#[test]
+fn test_rolling() {
+    let data = numbers(&[3.0, 1.0, 4.0, 1.0, 5.0, 9.0]);
+    let rolling = |window: f64, agg: &str| {
+        to_vec_f64(
+            &math::rolling(&[
+                data.clone(),
+                Value::Number(window),
+                Value::String(agg.into()),
+            ])
+            .unwrap(),
+        )
+    };
+    assert_eq!(rolling(3.0, "SUM"), vec![8.0, 6.0, 10.0, 15.0]);
+    assert_eq!(rolling(2.0, "MEAN"), vec![2.0, 2.5, 2.5, 3.0, 7.0]);
+    assert_eq!(rolling(3.0, "MIN"), vec![1.0, 1.0, 1.0, 1.0]);
+    assert_eq!(rolling(3.0, "MAX"), vec![4.0, 4.0, 5.0, 9.0]);
+    assert!(rolling(7.0, "SUM").is_empty());
+    assert!(math::rolling(&[data, Value::Number(0.0), Value::String("SUM".into())]).is_err());
+}
+
+/// 测试以函数聚合的滚动窗口与精确累计
+#[test]
+fn test_rolling_with_function() {
+    let mut engine = aether::Aether::new();
+    let result = engine
+        .eval("ROLLING([1, 5, 2, 8], 2, Lambda W -> MAX(W) - MIN(W))")
+        .unwrap();
+    assert_eq!(to_vec_f64(&result), vec![4.0, 3.0, 6.0]);
+
+    let result = engine
+        .eval("Set THIRD FRAC_DIV(1, 3)\nCUMSUM([THIRD, THIRD, 1])")
+        .unwrap();
+    assert_eq!(result.to_string(), "[1/3, 2/3, 5/3]");
+}
@@ -0,0 +1,116 @@
+// tests/memory_limits_tests.rs
+//! 内存限制测试：数组长度、字典条目数和估算内存总量
+
+use aether::{Aether, Backend, ExecutionLimitError, ExecutionLimits, MemoryResource, Value};
+
+fn engine(backend: Backend, limits: ExecutionLimits) -> Aether {
+    Aether::new()
+        .with_limits(limits)
+        .with_execution_backend(backend)
+}
+
+#[test]
+fn push_in_a_loop_stops_at_the_array_limit() {
+    let limits = ExecutionLimits {
+        max_array_len: Some(1000),
+        ..ExecutionLimits::default()
+    };
+    for backend in [Backend::Interpreter, Backend::Bytecode] {
+        let err = engine(backend, limits.clone())
+            .eval("Set XS []\nWhile (True) { Set XS PUSH(XS, 1) }")
+            .unwrap_err();
+        assert!(
+            err.contains("Memory limit exceeded: array of 1001 elements (limit: 1000)"),
+            "{:?}: {}",
+            backend,
+            err
+        );
+        assert!(err.contains("line 2"), "{}", err);
+    }
+
+    // 上限以内正常执行
+    let mut engine = engine(Backend::Interpreter, limits);
+    assert_eq!(
+        engine.eval("LEN(RANGE(0, 1000))").unwrap(),
+        Value::Number(1000.0)
+    );
+    assert!(engine.eval("RANGE(0, 1001)").is_err());
+}
+
+#[test]
+fn dict_entries_are_limited_for_index_assignment() {
+    let limits = ExecutionLimits {
+        max_dict_entries: Some(10),
+        ..ExecutionLimits::default()
+    };
+    for backend in [Backend::Interpreter, Backend::Bytecode] {
+        let err = engine(backend, limits.clone())
+            .eval("Set D {}\nFor I In RANGE(0, 100) { Set D[TO_STRING(I)] I }")
+            .unwrap_err();
+        assert!(
+            err.contains("dict of 11 entries (limit: 10)"),
+            "{:?}: {}",
+            backend,
+            err
+        );
+    }
+}
+
+#[test]
+fn total_memory_estimate_is_limited() {
+    let limits = ExecutionLimits {
+        max_memory_bytes: Some(64 * 1024),
+        ..ExecutionLimits::default()
+    };
+
+    // 单个值翻倍增长，在超出预算的那次赋值时停止
+    for backend in [Backend::Interpreter, Backend::Bytecode] {
+        let err = engine(backend, limits.clone())
+            .eval("Set S \"x\"\nWhile (True) { Set S S + S }")
+            .unwrap_err();
+        assert!(err.contains("Memory limit exceeded"), "{}", err);
+        assert!(err.contains("(limit: 65536 bytes)"), "{}", err);
+    }
+
+    // 许多各自不大的值合计超出预算，在定期检查全部变量时发现
+    let err = engine(Backend::Interpreter, limits.clone())
+        .eval(
+            "Set A RANGE(0, 100)\nSet B RANGE(0, 100)\nSet C RANGE(0, 100)\nSet D RANGE(0, 100)\nSet N 0\nWhile (N < 1000) { Set N N + 1 }",
+        )
+        .unwrap_err();
+    assert!(err.contains("Memory limit exceeded"), "{}", err);
+    assert!(err.contains("line 6"), "{}", err);
+
+    let mut engine = engine(Backend::Interpreter, limits);
+    assert_eq!(
+        engine.eval("LEN(RANGE(0, 100))").unwrap(),
+        Value::Number(100.0)
+    );
+}
+
+#[test]
+fn memory_errors_are_execution_limit_errors() {
+    let mut engine = Aether::new().with_limits(ExecutionLimits {
+        max_array_len: Some(3),
+        ..ExecutionLimits::default()
+    });
+
+    // Try/Catch 无法捕获执行限制错误
+    let err = engine
+        .eval("Try { Set XS [1, 2, 3, 4] } Catch (E) { 0 }")
+        .unwrap_err();
+    assert!(err.contains("Memory limit exceeded"), "{}", err);
+
+    let report = engine.eval_report("Set XS [1, 2, 3, 4]").unwrap_err();
+    assert_eq!(report.kind, "ExecutionLimit");
+
+    let err = ExecutionLimitError::MemoryLimitExceeded {
+        resource: MemoryResource::DictEntries,
+        size: 5,
+        limit: 4,
+    };
+    assert_eq!(
+        err.to_string(),
+        "Memory limit exceeded: dict of 5 entries (limit: 4)"
+    );
+}
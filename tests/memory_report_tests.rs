@@ -0,0 +1,95 @@
+use aether::Aether;
+
+#[test]
+fn test_counts_values_by_type() {
+    let mut engine = Aether::new();
+    engine
+        .eval(r#"Set DATA {"names": ["a", "b"], "total": 3}"#)
+        .unwrap();
+    let report = engine.memory_report();
+
+    assert_eq!(report.by_type["Dict"].count, 1);
+    assert_eq!(report.by_type["Array"].count, 1);
+    assert_eq!(report.by_type["String"].count, 2);
+    assert_eq!(report.by_type["Integer"].count, 1);
+    assert_eq!(report.total_values, 5);
+    assert!(report.total_bytes >= report.by_type["Dict"].bytes);
+    // Built-in bindings are not user values
+    assert!(!report.by_type.contains_key("BuiltIn"));
+}
+
+#[test]
+fn test_largest_collections_with_paths() {
+    let mut engine = Aether::new();
+    engine
+        .eval(
+            r#"
+            Set SMALL [1]
+            Set CACHE {"items": RANGE(0, 200), "meta": {"tags": [1, 2]}}
+            "#,
+        )
+        .unwrap();
+    let report = engine.memory_report();
+
+    assert_eq!(report.largest[0].path, "CACHE");
+    assert_eq!(report.largest[1].path, "CACHE.items");
+    assert_eq!(report.largest[1].type_name, "Array");
+    assert_eq!(report.largest[1].len, 200);
+    assert!(report.largest[0].bytes > report.largest[1].bytes);
+    assert!(report.largest.iter().any(|v| v.path == "CACHE.meta.tags"));
+}
+
+#[test]
+fn test_detects_closure_cycles() {
+    let mut engine = Aether::new();
+    engine
+        .eval(
+            r#"
+            Func MAKE_COUNTER() {
+                Set COUNT [1, 2, 3]
+                Func INC() {
+                    Return LEN(COUNT) + 1
+                }
+                Return INC
+            }
+            Set COUNTER MAKE_COUNTER()
+            "#,
+        )
+        .unwrap();
+    let report = engine.memory_report();
+
+    assert_eq!(report.captured_envs, 1);
+    assert_eq!(report.suspected_cycles.len(), 1);
+    let cycle = &report.suspected_cycles[0];
+    assert_eq!(cycle.path, "COUNTER::INC");
+    assert_eq!(cycle.function, "INC");
+    assert_eq!(cycle.variables, 2);
+    // Values kept alive by the closure are counted with their path
+    assert!(report.largest.iter().any(|v| v.path == "COUNTER::COUNT"));
+}
+
+#[test]
+fn test_global_functions_are_not_cycles() {
+    let mut engine = Aether::new();
+    engine
+        .eval("Func ADD(A, B) { Return A + B }\nSet X ADD(1, 2)")
+        .unwrap();
+    let report = engine.memory_report();
+
+    assert!(report.suspected_cycles.is_empty());
+    assert_eq!(report.captured_envs, 0);
+    assert_eq!(report.env_depth, 1);
+    assert_eq!(report.by_type["Function"].count, 1);
+}
+
+#[test]
+fn test_report_display() {
+    let mut engine = Aether::new();
+    engine.eval("Set ROWS [[1, 2], [3, 4]]").unwrap();
+    let text = engine.memory_report().to_string();
+
+    assert!(text.starts_with("=== MEMORY ==="));
+    assert!(text.contains("values: 7"));
+    assert!(text.contains("ROWS[0]"));
+    assert!(!text.contains("suspected_cycles"));
+}
@@ -0,0 +1,102 @@
+// tests/minify_tests.rs
+//! 脚本压缩（minify）测试
+
+use aether::ast::eq_ignoring_spans;
+use aether::minify::{MinifyOptions, minify};
+use aether::{Aether, Parser};
+
+const PAYROLL: &str = r#"
+// 计算应发工资
+/* 税率表按年更新 */
+Set RATE 0.1
+
+Func NET_PAY(BASE_SALARY, OVERTIME_HOURS) {
+    Set HOURLY BASE_SALARY / 174
+    Set EXTRA HOURLY * 1.5 * OVERTIME_HOURS
+    Set GROSS BASE_SALARY + EXTRA
+    Set ITEMS [GROSS, EXTRA]
+    Set ITEMS[1] ROUND(EXTRA)
+    Set TOTAL 0
+    For INDEX, ITEM In ITEMS {
+        Set TOTAL TOTAL + ITEM * (INDEX + 1)
+    }
+    Set SUMMARY {GROSS: GROSS, TAX: GROSS * RATE}
+    Set DOUBLE MAP([1, 2], Lambda VALUE -> VALUE * 2)
+    Return [SUMMARY["TAX"], TOTAL, DOUBLE]
+}
+
+NET_PAY(8700, 10)
+"#;
+
+fn eval(code: &str) -> String {
+    Aether::new().eval(code).unwrap().to_string()
+}
+
+#[test]
+fn strips_comments_and_whitespace_without_changing_the_ast() {
+    let compact = minify(PAYROLL, &MinifyOptions::default()).unwrap();
+    assert!(!compact.contains("//") && !compact.contains("/*"));
+    assert!(!compact.contains("\n\n") && !compact.contains("    "));
+    assert!(compact.len() * 4 < PAYROLL.len() * 3, "{}", compact);
+    assert!(eq_ignoring_spans(
+        &Parser::new(&compact).parse_program().unwrap(),
+        &Parser::new(PAYROLL).parse_program().unwrap()
+    ));
+}
+
+#[test]
+fn renamed_script_computes_the_same_result() {
+    let options = MinifyOptions::default().with_rename_locals();
+    let renamed = minify(PAYROLL, &options).unwrap();
+    assert_eq!(eval(&renamed), eval(PAYROLL));
+    assert!(renamed.len() < minify(PAYROLL, &MinifyOptions::default()).unwrap().len());
+    for local in ["BASE_SALARY", "OVERTIME_HOURS", "HOURLY", "INDEX", "VALUE"] {
+        assert!(!renamed.contains(local), "{} in {}", local, renamed);
+    }
+    // 函数名、全局变量、内置函数和字典键保持不变
+    for kept in [
+        "NET_PAY", "RATE", "ROUND", "MAP", "GROSS:", "TAX:", "\"TAX\"",
+    ] {
+        assert!(renamed.contains(kept), "{} missing in {}", kept, renamed);
+    }
+}
+
+#[test]
+fn array_literal_and_index_assignment_keep_their_spacing() {
+    let code = "Set A [1, 2]\nSet A[0] 5\nSet B A [0]\nA";
+    let compact = minify(code, &MinifyOptions::default()).unwrap();
+    assert_eq!(compact, "Set A [1,2]\nSet A[0]5\nSet B A [0]\nA");
+    assert_eq!(eval(&compact), eval(code));
+}
+
+#[test]
+fn keeps_required_separators_and_newlines() {
+    let code = "Set X  -1\nSet Y  X - -2\nIf (X < Y) {\n  \"yes\"\n} Else {\n  \"no\"\n}\n";
+    let compact = minify(code, &MinifyOptions::default()).unwrap();
+    assert_eq!(compact, "Set X-1\nSet Y X--2\nIf(X<Y){\"yes\"}Else{\"no\"}");
+    assert_eq!(eval(&compact), eval(code));
+}
+
+#[test]
+fn names_shared_with_the_outside_are_not_renamed() {
+    // COUNTER 在函数外定义，宿主注入的 LIMIT 只被读取，都不能改名
+    let code = "Set COUNTER 0\nFunc BUMP(STEP) {\n  Set COUNTER COUNTER + STEP\n  Return COUNTER + LIMIT\n}\n";
+    let renamed = minify(code, &MinifyOptions::default().with_rename_locals()).unwrap();
+    assert_eq!(
+        renamed,
+        "Set COUNTER 0\nFunc BUMP(A){Set COUNTER COUNTER+A\nReturn COUNTER+LIMIT}"
+    );
+}
+
+#[test]
+fn short_names_avoid_existing_identifiers() {
+    let code = "Set A 1\nFunc F(X, Y) {\n  Return X + Y + A\n}\nF(2, 3)";
+    let renamed = minify(code, &MinifyOptions::default().with_rename_locals()).unwrap();
+    assert_eq!(renamed, "Set A 1\nFunc F(B,C){Return B+C+A}\nF(2,3)");
+    assert_eq!(eval(&renamed), "6");
+}
+
+#[test]
+fn invalid_scripts_are_rejected() {
+    assert!(minify("Set X (1 +", &MinifyOptions::default()).is_err());
+}
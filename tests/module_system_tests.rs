@@ -216,3 +216,132 @@ fn dsl_default_disables_import() {
         "unexpected error: {err}"
     );
 }
+
+#[test]
+fn stdlib_module_imports_into_namespace() {
+    let mut engine = Aether::new();
+    let result = engine
+        .eval(
+            r#"
+Import SETS From "stdlib:set"
+Set S SETS["SET_FROM_ARRAY"]([1, 1, 2, 3])
+LEN(S)
+"#,
+        )
+        .unwrap();
+    assert_eq!(result, Value::Number(3.0));
+
+    // 模块定义不会泄漏到全局环境
+    assert!(engine.eval("SET_FROM_ARRAY([1])").is_err());
+}
+
+#[test]
+fn stdlib_named_import_and_unknown_module() {
+    let mut engine = Aether::new();
+    let result = engine
+        .eval(
+            r#"
+Import {STACK_NEW, STACK_PUSH} From "stdlib:stack"
+LEN(STACK_PUSH(STACK_NEW(), 7))
+"#,
+        )
+        .unwrap();
+    assert_eq!(result, Value::Number(1.0));
+
+    let err = engine
+        .eval(r#"Import NOPE From "stdlib:nope""#)
+        .unwrap_err();
+    assert!(err.contains("Module not found"), "unexpected error: {err}");
+}
+
+#[test]
+fn load_stdlib_module_as_namespace_api() {
+    let mut engine = Aether::new();
+    engine.load_stdlib_module_as("set", "SETS").unwrap();
+
+    let globals: Vec<_> = engine.globals().into_iter().map(|v| v.name).collect();
+    assert_eq!(globals, vec!["SETS".to_string()]);
+    assert!(engine.load_stdlib_module_as("missing", "X").is_err());
+}
+
+#[test]
+fn stdlib_namespace_exports_only_functions() {
+    let mut engine = Aether::new();
+    let result = engine
+        .eval(
+            r#"
+Import T From "stdlib:testing"
+[HAS(T, "ASSERT_TRUE"), HAS(T, "TEST_TOTAL"), HAS(T, "TEST_FAILURES")]
+"#,
+        )
+        .unwrap();
+    assert_eq!(
+        result,
+        Value::Array(vec![
+            Value::Boolean(true),
+            Value::Boolean(false),
+            Value::Boolean(false),
+        ])
+    );
+
+    let err = engine
+        .eval(r#"Import {CLI_COLOR_RED} From "stdlib:cli_utils""#)
+        .unwrap_err();
+    assert!(err.contains("not exported"), "unexpected error: {err}");
+}
+
+#[test]
+fn stdlib_namespace_hides_helper_functions() {
+    let mut engine = Aether::new();
+    let result = engine
+        .eval(
+            r#"
+Import H From "stdlib:heap"
+Import S From "stdlib:sorting"
+Set HEAP H["MIN_HEAP_FROM_ARRAY"]([5, 1, 3])
+[H["MIN_HEAP_PEEK"](HEAP), HAS(H, "_HEAP_SWAP"), HAS(S, "_QUICK_SORT_HELPER"), S["QUICK_SORT"]([3, 1, 2])]
+"#,
+        )
+        .unwrap();
+    assert_eq!(
+        result,
+        Value::Array(vec![
+            Value::Number(1.0),
+            Value::Boolean(false),
+            Value::Boolean(false),
+            Value::Array(vec![
+                Value::Number(1.0),
+                Value::Number(2.0),
+                Value::Number(3.0),
+            ]),
+        ])
+    );
+}
+
+#[test]
+fn stdlib_exports_are_the_public_module_functions() {
+    let heap = aether::stdlib::get_exports("heap").unwrap();
+    assert!(heap.contains(&"MIN_HEAP_FROM_ARRAY"));
+    assert!(!heap.contains(&"_HEAP_SWAP"));
+    assert!(aether::stdlib::get_exports("no_such_module").is_none());
+    for (name, _) in aether::stdlib::ALL_MODULES {
+        let exports = aether::stdlib::get_exports(name).unwrap();
+        assert!(!exports.is_empty(), "{name} exports nothing");
+        let mut engine = Aether::new();
+        engine.load_stdlib_module_as(name, "M").unwrap();
+        assert_eq!(
+            engine.eval("LEN(KEYS(M))").unwrap(),
+            Value::Number(exports.len() as f64),
+            "{name}"
+        );
+        for export in exports {
+            assert!(!export.starts_with('_'), "{name} exports {export}");
+            let code = format!(r#"HAS(M, "{}")"#, export);
+            assert_eq!(
+                engine.eval(&code).unwrap(),
+                Value::Boolean(true),
+                "{name} does not define {export}"
+            );
+        }
+    }
+}
@@ -0,0 +1,61 @@
+// tests/panic_free_tests.rs
+//! 模糊测试发现的 panic 回归测试：这些输入应返回错误或正常结果，而不是 panic
+
+use aether::Aether;
+use aether::parser::{MAX_NESTING_DEPTH, Parser};
+
+fn eval(code: &str) -> Result<String, String> {
+    let mut engine = Aether::new();
+    engine.eval(code).map(|value| value.to_string())
+}
+
+#[test]
+fn nan_values_do_not_panic_sorting_builtins() {
+    for call in [
+        "MEDIAN([1, NAN, 2])",
+        "QUANTILE([NAN, 1, 2], 0.5)",
+        "SORT([3, NAN, 1])",
+        "CALC_SALARY_MEDIAN([NAN, 3000])",
+    ] {
+        let code = format!("Set NAN TO_NUMBER(\"NaN\")\n{}", call);
+        let _ = eval(&code);
+    }
+}
+
+#[test]
+fn clamp_rejects_nan_bounds() {
+    assert!(eval("CLAMP(1, TO_NUMBER(\"NaN\"), 2)").is_err());
+}
+
+#[test]
+fn range_rejects_infinite_bounds() {
+    assert!(eval("RANGE(TO_NUMBER(\"inf\"))").is_err());
+}
+
+#[test]
+fn matmul_rejects_ragged_rows() {
+    let err = eval("MATMUL([[1, 2], [3]], [[1], [2]])").unwrap_err();
+    assert!(err.contains("same length"), "{}", err);
+}
+
+#[test]
+fn weekend_days_rejects_non_finite_input() {
+    assert!(eval("CALC_WEEKEND_DAYS(TO_NUMBER(\"inf\"), 0)").is_err());
+}
+
+#[test]
+fn deep_nesting_is_a_parse_error() {
+    let code = format!("{}1{}", "(".repeat(50_000), ")".repeat(50_000));
+    let err = Parser::new(&code).parse_program().unwrap_err();
+    assert!(err.to_string().contains("nesting"), "{}", err);
+
+    let chain = vec!["1"; 20_000].join(" + ");
+    assert!(eval(&format!("Set X {}", chain)).is_err());
+
+    let ok = format!(
+        "{}1{}",
+        "[".repeat(MAX_NESTING_DEPTH / 2),
+        "]".repeat(MAX_NESTING_DEPTH / 2)
+    );
+    assert!(eval(&ok).is_ok());
+}
@@ -0,0 +1,310 @@
+// tests/parallel_tests.rs
+//! SPAWN / AWAIT_ALL / PARALLEL 测试
+
+use aether::runtime::{ConcurrencyLimits, ExecutionLimits};
+use aether::{Aether, Value};
+
+fn dict_get(value: &Value, key: &str) -> Value {
+    match value {
+        Value::Dict(dict) => dict.get(key).cloned().unwrap_or(Value::Null),
+        other => panic!("expected Dict, got {:?}", other),
+    }
+}
+
+#[test]
+fn parallel_returns_results_in_order() {
+    let mut engine = Aether::new();
+    let result = engine
+        .eval(
+            r#"
+            Set BASE 10
+            Func SQUARE_PLUS(N) {
+                Return N * N + BASE
+            }
+            PARALLEL([
+                Lambda () -> SQUARE_PLUS(1),
+                Lambda () -> SQUARE_PLUS(2),
+                Lambda () -> SQUARE_PLUS(3)
+            ], 2)
+            "#,
+        )
+        .unwrap();
+    let Value::Array(results) = result else {
+        panic!("expected Array");
+    };
+    let values: Vec<Value> = results.iter().map(|r| dict_get(r, "value")).collect();
+    assert_eq!(
+        values,
+        vec![
+            Value::Number(11.0),
+            Value::Number(14.0),
+            Value::Number(19.0)
+        ]
+    );
+    assert!(
+        results
+            .iter()
+            .all(|r| dict_get(r, "ok") == Value::Boolean(true))
+    );
+}
+
+#[test]
+fn parallel_reports_errors_per_task() {
+    let mut engine = Aether::new();
+    let result = engine
+        .eval(r#"PARALLEL([Lambda () -> 1 / 0, Lambda () -> "fine"])"#)
+        .unwrap();
+    let Value::Array(results) = result else {
+        panic!("expected Array");
+    };
+    assert_eq!(dict_get(&results[0], "ok"), Value::Boolean(false));
+    assert!(matches!(dict_get(&results[0], "error"), Value::String(_)));
+    assert_eq!(dict_get(&results[1], "ok"), Value::Boolean(true));
+    assert_eq!(
+        dict_get(&results[1], "value"),
+        Value::String("fine".to_string())
+    );
+}
+
+#[test]
+fn spawn_and_await_all_collect_results() {
+    let mut engine = Aether::new();
+    let result = engine
+        .eval(
+            r#"
+            Func BUSY(N) {
+                Set TOTAL 0
+                For I In RANGE(0, 1000) {
+                    Set TOTAL (TOTAL + N)
+                }
+                Return TOTAL
+            }
+            Set A SPAWN(Lambda () -> BUSY(1))
+            Set B SPAWN(Lambda () -> BUSY(2))
+            Set RESULTS AWAIT_ALL([A, B])
+            [RESULTS[0]["value"], RESULTS[1]["value"]]
+            "#,
+        )
+        .unwrap();
+    assert_eq!(
+        result,
+        Value::Array(vec![Value::Number(1000.0), Value::Number(2000.0)])
+    );
+}
+
+#[test]
+fn tasks_do_not_mutate_caller_state() {
+    let mut engine = Aether::new();
+    let result = engine
+        .eval(
+            r#"
+            Set ITEMS [1, 2]
+            Func ADD_ITEM() {
+                Set ITEMS PUSH(ITEMS, 3)
+                Return LEN(ITEMS)
+            }
+            Set RESULTS AWAIT_ALL([SPAWN(ADD_ITEM)])
+            [RESULTS[0]["value"], LEN(ITEMS)]
+            "#,
+        )
+        .unwrap();
+    assert_eq!(
+        result,
+        Value::Array(vec![Value::Number(3.0), Value::Number(2.0)])
+    );
+}
+
+#[test]
+fn await_all_rejects_unknown_and_reused_handles() {
+    let mut engine = Aether::new();
+    assert!(engine.eval(r#"AWAIT_ALL(["task:99"])"#).is_err());
+    engine
+        .eval("Set T SPAWN(Lambda () -> 1)\nAWAIT_ALL([T])")
+        .unwrap();
+    assert!(engine.eval("AWAIT_ALL([T])").is_err());
+    assert!(engine.eval("SPAWN(1)").is_err());
+    assert!(engine.eval("PARALLEL([Lambda () -> 1], 0)").is_err());
+}
+
+#[test]
+fn workers_inherit_permissions() {
+    let mut engine = Aether::new();
+    let result = engine
+        .eval(r#"PARALLEL([Lambda () -> READ_FILE("Cargo.toml")])"#)
+        .unwrap();
+    let Value::Array(results) = result else {
+        panic!("expected Array");
+    };
+    assert_eq!(dict_get(&results[0], "ok"), Value::Boolean(false));
+}
+
+fn limited(limits: ConcurrencyLimits) -> Aether {
+    Aether::new().with_concurrency_limits(limits)
+}
+
+#[test]
+fn concurrent_task_limit_applies_to_spawn() {
+    let mut engine = limited(ConcurrencyLimits {
+        max_concurrent_tasks: Some(2),
+        ..Default::default()
+    });
+    engine
+        .eval(
+            r#"
+            Set CH CHANNEL()
+            Set A SPAWN(Lambda () -> RECV(CH, 5000))
+            Set B SPAWN(Lambda () -> RECV(CH, 5000))
+            "#,
+        )
+        .unwrap();
+    let err = engine.eval("SPAWN(Lambda () -> 1)").unwrap_err();
+    assert!(err.contains("Concurrent task limit"), "{}", err);
+
+    // 任务结束后槽位被归还
+    engine
+        .eval("SEND(CH, 1)\nSEND(CH, 2)\nAWAIT_ALL([A, B])")
+        .unwrap();
+    assert!(engine.eval("AWAIT_ALL([SPAWN(Lambda () -> 1)])").is_ok());
+}
+
+#[test]
+fn nested_tasks_share_the_task_limit() {
+    let mut engine = limited(ConcurrencyLimits {
+        max_concurrent_tasks: Some(1),
+        ..Default::default()
+    });
+    let result = engine
+        .eval(
+            r#"
+            Set OUTER SPAWN(Lambda () -> SPAWN(Lambda () -> 1))
+            AWAIT_ALL([OUTER])[0]
+            "#,
+        )
+        .unwrap();
+    assert_eq!(dict_get(&result, "ok"), Value::Boolean(false));
+    match dict_get(&result, "error") {
+        Value::String(message) => assert!(message.contains("Concurrent task limit"), "{}", message),
+        other => panic!("expected error message, got {:?}", other),
+    }
+}
+
+#[test]
+fn parallel_runs_with_fewer_workers_under_the_limit() {
+    let mut engine = limited(ConcurrencyLimits {
+        max_concurrent_tasks: Some(2),
+        ..Default::default()
+    });
+    let result = engine
+        .eval(
+            r#"
+            Set RESULTS PARALLEL([
+                Lambda () -> 1, Lambda () -> 2, Lambda () -> 3,
+                Lambda () -> 4, Lambda () -> 5
+            ], 5)
+            MAP(RESULTS, Lambda R -> R["value"])
+            "#,
+        )
+        .unwrap();
+    assert_eq!(
+        result,
+        Value::Array((1..=5).map(|n| Value::Number(n as f64)).collect())
+    );
+}
+
+#[test]
+fn per_task_step_limit_stops_runaway_tasks() {
+    let mut engine = limited(ConcurrencyLimits {
+        max_task_steps: Some(100),
+        ..Default::default()
+    });
+    let result = engine
+        .eval(
+            r#"
+            Func SPIN() {
+                While (1 == 1) {
+                    Set X 1
+                }
+            }
+            PARALLEL([SPIN])[0]
+            "#,
+        )
+        .unwrap();
+    assert_eq!(dict_get(&result, "ok"), Value::Boolean(false));
+    match dict_get(&result, "error") {
+        Value::String(message) => assert!(message.contains("step limit"), "{}", message),
+        other => panic!("expected error message, got {:?}", other),
+    }
+}
+
+#[test]
+fn cumulative_task_time_budget_is_enforced() {
+    let mut engine = limited(ConcurrencyLimits {
+        max_total_task_ms: Some(1),
+        ..Default::default()
+    });
+    engine.set_limits(ExecutionLimits::unrestricted());
+    engine
+        .eval(
+            r#"
+            Func SPIN() {
+                While (1 == 1) {
+                    Set X 1
+                }
+            }
+            AWAIT_ALL([SPAWN(SPIN)])
+            "#,
+        )
+        .unwrap();
+    let err = engine.eval("SPAWN(Lambda () -> 1)").unwrap_err();
+    assert!(err.contains("Task time budget"), "{}", err);
+
+    // 重新设置限制会重置预算
+    engine.set_concurrency_limits(ConcurrencyLimits::default());
+    assert!(engine.eval("SPAWN(Lambda () -> 1)").is_ok());
+}
+
+#[test]
+fn workers_inherit_the_sandbox_context() {
+    use aether::{IOPermissions, PathValidator, ScopedValidator};
+
+    let base = std::env::temp_dir().join(format!("aether_parallel_sandbox_{}", std::process::id()));
+    let root = base.join("root");
+    std::fs::create_dir_all(&root).unwrap();
+    let outside = base.join("outside.txt");
+    std::fs::write(&outside, "secret").unwrap();
+
+    let mut engine = Aether::with_permissions(IOPermissions {
+        filesystem_enabled: true,
+        ..Default::default()
+    });
+    let _scope = ScopedValidator::set(PathValidator::with_root_dir(root));
+    engine
+        .eval(&format!(
+            "Set R Lambda () -> READ_FILE({:?})",
+            outside.display().to_string()
+        ))
+        .unwrap();
+
+    let err = engine.eval("R()").unwrap_err();
+    assert!(err.contains("Path validation failed"), "{}", err);
+    for code in ["AWAIT_ALL([SPAWN(R)])", "PARALLEL([R])"] {
+        let Value::Array(results) = engine.eval(code).unwrap() else {
+            panic!("expected Array");
+        };
+        assert_eq!(
+            dict_get(&results[0], "ok"),
+            Value::Boolean(false),
+            "{}",
+            code
+        );
+        let error = dict_get(&results[0], "error").to_string();
+        assert!(
+            error.contains("Path validation failed"),
+            "{}: {}",
+            code,
+            error
+        );
+    }
+
+    std::fs::remove_dir_all(&base).unwrap();
+}
@@ -7,10 +7,10 @@ fn test_parse_set_statement() {
     let program = parser.parse_program().unwrap();
 
     assert_eq!(program.len(), 1);
-    match &program[0] {
+    match program[0].node() {
         Stmt::Set { name, value } => {
             assert_eq!(name, "X");
-            assert_eq!(*value, Expr::Number(10.0));
+            assert_eq!(*value, Expr::Integer(10));
         }
         _ => panic!("Expected Set statement"),
     }
@@ -23,19 +23,19 @@ fn test_parse_arithmetic() {
     let program = parser.parse_program().unwrap();
 
     assert_eq!(program.len(), 1);
-    match &program[0] {
+    match program[0].node() {
         Stmt::Set { name, value } => {
             assert_eq!(name, "X");
             // Should be: 5 + (3 * 2) due to precedence
             match value {
                 Expr::Binary { left, op, right } => {
-                    assert_eq!(**left, Expr::Number(5.0));
+                    assert_eq!(**left, Expr::Integer(5));
                     assert_eq!(*op, BinOp::Add);
                     match &**right {
                         Expr::Binary { left, op, right } => {
-                            assert_eq!(**left, Expr::Number(3.0));
+                            assert_eq!(**left, Expr::Integer(3));
                             assert_eq!(*op, BinOp::Multiply);
-                            assert_eq!(**right, Expr::Number(2.0));
+                            assert_eq!(**right, Expr::Integer(2));
                         }
                         _ => panic!("Expected binary expression"),
                     }
@@ -58,7 +58,7 @@ fn test_parse_function_definition() {
     let program = parser.parse_program().unwrap();
 
     assert_eq!(program.len(), 1);
-    match &program[0] {
+    match program[0].node() {
         Stmt::FuncDef { name, params, body } => {
             assert_eq!(name, "ADD");
             assert_eq!(params, &vec!["A".to_string(), "B".to_string()]);
@@ -75,12 +75,12 @@ fn test_parse_function_call() {
     let program = parser.parse_program().unwrap();
 
     assert_eq!(program.len(), 1);
-    match &program[0] {
+    match program[0].node() {
         Stmt::Expression(Expr::Call { func, args }) => {
             assert_eq!(**func, Expr::Identifier("ADD".to_string()));
             assert_eq!(args.len(), 2);
-            assert_eq!(args[0], Expr::Number(5.0));
-            assert_eq!(args[1], Expr::Number(3.0));
+            assert_eq!(args[0], Expr::Integer(5));
+            assert_eq!(args[1], Expr::Integer(3));
         }
         _ => panic!("Expected function call"),
     }
@@ -93,15 +93,15 @@ fn test_parse_array_literal() {
     let program = parser.parse_program().unwrap();
 
     assert_eq!(program.len(), 1);
-    match &program[0] {
+    match program[0].node() {
         Stmt::Set { name, value } => {
             assert_eq!(name, "ARR");
             match value {
                 Expr::Array(elements) => {
                     assert_eq!(elements.len(), 3);
-                    assert_eq!(elements[0], Expr::Number(1.0));
-                    assert_eq!(elements[1], Expr::Number(2.0));
-                    assert_eq!(elements[2], Expr::Number(3.0));
+                    assert_eq!(elements[0], Expr::Integer(1));
+                    assert_eq!(elements[1], Expr::Integer(2));
+                    assert_eq!(elements[2], Expr::Integer(3));
                 }
                 _ => panic!("Expected array"),
             }
@@ -123,7 +123,7 @@ fn test_parse_if_expression() {
     let program = parser.parse_program().unwrap();
 
     assert_eq!(program.len(), 1);
-    match &program[0] {
+    match program[0].node() {
         Stmt::Expression(Expr::If {
             condition,
             then_branch,
@@ -155,7 +155,7 @@ fn test_parse_for_loop() {
     }
 
     assert_eq!(program.len(), 1);
-    match &program[0] {
+    match program[0].node() {
         Stmt::For {
             var,
             iterable,
@@ -168,3 +168,30 @@ fn test_parse_for_loop() {
         _ => panic!("Expected For statement"),
     }
 }
+
+#[test]
+fn test_parse_error_expected_tokens() {
+    let err = Parser::new("Set X [1, 2").parse_program().unwrap_err();
+    assert_eq!(err.expected_tokens(), ["Comma", "RightBracket"]);
+
+    let err = Parser::new("Set 1 2").parse_program().unwrap_err();
+    assert_eq!(err.expected_tokens(), ["identifier"]);
+    assert!(err.fixes().is_empty());
+}
+
+#[test]
+fn test_parse_error_fix_inserts_missing_delimiter() {
+    let source = "Func F() {\n    Return 1\n";
+    let err = Parser::new(source).parse_program().unwrap_err();
+    let fix = &err.fixes()[0];
+    assert_eq!(fix.insert, "}");
+    let fixed = fix.apply(source);
+    assert_eq!(fixed, "Func F() {\n    Return 1\n}");
+    assert!(Parser::new(&fixed).parse_program().is_ok());
+
+    let source = "PRINTLN(1, 2\nSet Y 2";
+    let err = Parser::new(source).parse_program().unwrap_err();
+    let fix = &err.fixes()[0];
+    assert_eq!((fix.line, fix.column), (1, 13));
+    assert_eq!(fix.apply(source), "PRINTLN(1, 2)\nSet Y 2");
+}
@@ -0,0 +1,100 @@
+// tests/patch_tests.rs
+//! DIFF / APPLY_PATCH 结构化比较测试
+
+use aether::{Aether, ChangeKind, PathSegment, Value, apply_changes, diff_values};
+
+#[test]
+fn diff_reports_added_removed_and_changed_paths() {
+    let mut engine = Aether::new();
+    let patch = engine
+        .eval(
+            r#"
+            Set OLD {"name": "a", "tags": [1, 2, 3], "cfg": {"debug": True}}
+            Set NEW {"name": "b", "tags": [1, 5], "cfg": {"debug": True, "port": 80}}
+            DIFF(OLD, NEW)
+            "#,
+        )
+        .unwrap();
+    let Value::Array(entries) = patch else {
+        panic!("expected array");
+    };
+    let summary: Vec<String> = entries
+        .iter()
+        .map(|entry| {
+            let Value::Dict(dict) = entry else {
+                panic!("expected dict")
+            };
+            format!("{} {}", dict["op"], dict["path"])
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            "add [cfg, port]",
+            "change [name]",
+            "change [tags, 1]",
+            "remove [tags, 2]",
+        ]
+    );
+}
+
+#[test]
+fn apply_patch_round_trips_diff() {
+    let mut engine = Aether::new();
+    let result = engine
+        .eval(
+            r#"
+            Set OLD {"items": [1, {"x": 1}, 3], "gone": Null}
+            Set NEW {"items": [1, {"x": 2, "y": 3}], "extra": "hi"}
+            LEN(DIFF(APPLY_PATCH(OLD, DIFF(OLD, NEW)), NEW)) == 0
+            "#,
+        )
+        .unwrap();
+    assert_eq!(result, Value::Boolean(true));
+
+    // 相等的值没有变更，标量整体替换
+    assert_eq!(engine.eval("DIFF([1], [1])").unwrap(), Value::Array(vec![]));
+    assert_eq!(
+        engine.eval("APPLY_PATCH(1, DIFF(1, \"one\"))").unwrap(),
+        Value::String("one".to_string())
+    );
+}
+
+#[test]
+fn numeric_diff_is_unchanged() {
+    let mut engine = Aether::new();
+    assert_eq!(
+        engine.eval("DIFF([1, 2, 4, 8], 2)").unwrap(),
+        Value::Array(vec![Value::Number(3.0), Value::Number(6.0)])
+    );
+    assert_eq!(
+        engine.eval("DIFF([10, 12, 11])").unwrap(),
+        Value::Array(vec![Value::Number(2.0), Value::Number(-1.0)])
+    );
+}
+
+#[test]
+fn apply_patch_rejects_missing_paths() {
+    let mut engine = Aether::new();
+    let err = engine
+        .eval(r#"APPLY_PATCH({"a": 1}, [{"op": "remove", "path": ["b"]}])"#)
+        .unwrap_err();
+    assert!(err.contains("$.b"), "{}", err);
+    assert!(
+        engine
+            .eval(r#"APPLY_PATCH({}, [{"op": "rename", "path": []}])"#)
+            .is_err()
+    );
+}
+
+#[test]
+fn rust_api_matches_builtins() {
+    let old = Value::Array(vec![Value::Number(1.0)]);
+    let new = Value::Array(vec![Value::Number(1.0), Value::Number(2.0)]);
+    let changes = diff_values(&old, &new);
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].kind, ChangeKind::Add);
+    assert_eq!(changes[0].path, vec![PathSegment::Index(1)]);
+    assert_eq!(changes[0].path_string(), "$[1]");
+    assert_eq!(apply_changes(&old, &changes).unwrap(), new);
+}
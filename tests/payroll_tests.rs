@@ -0,0 +1,495 @@
+// tests/payroll_tests.rs
+//! 薪酬批量计算集成测试
+
+#![cfg(feature = "payroll")]
+
+use aether::{Aether, Value};
+
+#[test]
+fn test_payroll_run_collects_results_and_totals() {
+    let mut engine = Aether::new();
+    let code = r#"
+        Set EMPLOYEES [
+            {"id": 1, "base_salary": 10000},
+            {"id": 2, "base_salary": 20000, "bonus": 1000}
+        ]
+        Set RESULT PAYROLL_RUN(EMPLOYEES, {"housing_rate": 0.12})
+        [RESULT["count"], RESULT["error_count"], RESULT["totals"]["gross"]]
+    "#;
+    let result = engine.eval(code).unwrap();
+    assert_eq!(
+        result,
+        Value::Array(vec![
+            Value::Number(2.0),
+            Value::Number(0.0),
+            Value::Number(31000.0),
+        ])
+    );
+}
+
+#[test]
+fn test_payroll_run_employee_breakdown() {
+    let mut engine = Aether::new();
+    let code = r#"
+        Set RESULT PAYROLL_RUN([{"id": "E1", "base_salary": 10000}], Null)
+        Set ROW RESULT["results"][0]
+        [ROW["social_insurance"], ROW["housing_fund"], ROW["tax"], ROW["net"]]
+    "#;
+    let result = engine.eval(code).unwrap();
+    // 社保 10.5% = 1050，公积金 12% = 1200，应纳税所得额 2750，税 82.5
+    assert_eq!(
+        result,
+        Value::Array(vec![
+            Value::Number(1050.0),
+            Value::Number(1200.0),
+            Value::Number(82.5),
+            Value::Number(7667.5),
+        ])
+    );
+}
+
+#[test]
+fn test_payroll_run_row_errors_do_not_abort_batch() {
+    let mut engine = Aether::new();
+    let code = r#"
+        Set RESULT PAYROLL_RUN([
+            {"id": 1, "base_salary": 8000},
+            {"id": 2},
+            {"id": 3, "base_salary": "abc"},
+            {"id": 4, "base_salary": 6000}
+        ], {})
+        [RESULT["count"], RESULT["error_count"], RESULT["errors"][0]["id"], RESULT["errors"][1]["index"]]
+    "#;
+    let result = engine.eval(code).unwrap();
+    assert_eq!(
+        result,
+        Value::Array(vec![
+            Value::Number(2.0),
+            Value::Number(2.0),
+            Value::Number(2.0),
+            Value::Number(2.0),
+        ])
+    );
+}
+
+#[test]
+fn test_payroll_run_csv_requires_filesystem() {
+    let mut engine = Aether::new();
+    assert!(engine.eval(r#"PAYROLL_RUN("employees.csv", {})"#).is_err());
+}
+
+#[test]
+fn test_payroll_run_from_csv() {
+    let path = std::env::temp_dir().join("aether_payroll_run_test.csv");
+    std::fs::write(
+        &path,
+        "id,name,base_salary,bonus\n1,Alice,10000,0\n2,Bob,,500\n",
+    )
+    .unwrap();
+
+    let mut engine = Aether::with_all_permissions();
+    let code = format!(
+        r#"Set RESULT PAYROLL_RUN("{}", {{}})
+        [RESULT["count"], RESULT["error_count"], RESULT["results"][0]["name"]]"#,
+        path.display()
+    );
+    let result = engine.eval(&code).unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    assert_eq!(
+        result,
+        Value::Array(vec![
+            Value::Number(1.0),
+            Value::Number(1.0),
+            Value::String("Alice".to_string()),
+        ])
+    );
+}
+
+#[test]
+fn test_payroll_functions_plain_result_by_default() {
+    let mut engine = Aether::new();
+    let result = engine
+        .eval("CALC_NET_SALARY(10000, 1050, 1200, 82.5, 0)")
+        .unwrap();
+    assert_eq!(result, Value::Number(7667.5));
+}
+
+#[test]
+fn test_net_salary_breakdown() {
+    let mut engine = Aether::new();
+    let code = r#"
+        Set D CALC_NET_SALARY(10000, 1050, 1200, 82.5, 0, {"breakdown": True})
+        [D["result"], D["inputs"]["gross"], D["components"]["total_deductions"]]
+    "#;
+    let result = engine.eval(code).unwrap();
+    assert_eq!(
+        result,
+        Value::Array(vec![
+            Value::Number(7667.5),
+            Value::Number(10000.0),
+            Value::Number(2332.5),
+        ])
+    );
+}
+
+#[test]
+fn test_personal_tax_breakdown_reports_bracket() {
+    let mut engine = Aether::new();
+    let code = r#"
+        Set D CALC_PERSONAL_TAX(50000, {"breakdown": True})
+        [D["result"], D["components"]["rate"], D["components"]["quick_deduction"]]
+    "#;
+    let result = engine.eval(code).unwrap();
+    assert_eq!(
+        result,
+        Value::Array(vec![
+            Value::Number(2480.0),
+            Value::Number(0.1),
+            Value::Number(2520.0),
+        ])
+    );
+}
+
+#[test]
+fn test_social_insurance_breakdown_components() {
+    let mut engine = Aether::new();
+    let code = r#"
+        Set D CALC_SOCIAL_INSURANCE(10000, {"breakdown": True})
+        [D["result"], D["components"]["pension"], D["components"]["housing_fund"]]
+    "#;
+    let result = engine.eval(code).unwrap();
+    assert_eq!(
+        result,
+        Value::Array(vec![
+            Value::Number(2250.0),
+            Value::Number(800.0),
+            Value::Number(1200.0),
+        ])
+    );
+}
+
+#[test]
+fn test_payroll_amounts_round_half_up_to_cents() {
+    let mut engine = Aether::new();
+    // 10000 / 174 = 57.4712...
+    assert_eq!(
+        engine.eval("CALC_HOURLY_PAY(10000)").unwrap(),
+        Value::Number(57.47)
+    );
+    // 0.125 * 100 = 12.5 分 → 四舍五入到 0.13
+    assert_eq!(
+        engine.eval("CALC_PENSION_INSURANCE(1.5625, 0.08)").unwrap(),
+        Value::Number(0.13)
+    );
+}
+
+#[test]
+fn test_payroll_components_sum_without_drift() {
+    let mut engine = Aether::new();
+    // f64 下 0.1 + 0.2 != 0.3，精确计算后应发工资应为 0.6
+    let result = engine.eval("CALC_GROSS_SALARY(0.1, 0.2, 0.3, 0)").unwrap();
+    assert_eq!(result, Value::Number(0.6));
+}
+
+#[test]
+fn test_payroll_fraction_inputs_return_fraction() {
+    let mut engine = Aether::new();
+    let code = r#"
+        Set GROSS CALC_GROSS_SALARY(TO_FRACTION(10000), 0, 0, 0)
+        [TYPE(GROSS), TYPE(CALC_NET_SALARY(GROSS, 1050, 1200, 82.5, 0))]
+    "#;
+    let result = engine.eval(code).unwrap();
+    assert_eq!(
+        result,
+        Value::Array(vec![
+            Value::String("Fraction".to_string()),
+            Value::String("Fraction".to_string()),
+        ])
+    );
+}
+
+#[test]
+fn test_set_payroll_rounding_modes() {
+    let mut engine = Aether::new();
+    let code = r#"
+        Set PREV SET_PAYROLL_ROUNDING(2, "HALF_EVEN")
+        Set A CALC_PENSION_INSURANCE(1.5625, 0.08)
+        SET_PAYROLL_ROUNDING(0, "DOWN")
+        Set B CALC_HOURLY_PAY(10000)
+        SET_PAYROLL_ROUNDING(2, "HALF_UP")
+        [PREV["mode"], A, B]
+    "#;
+    let result = engine.eval(code).unwrap();
+    assert_eq!(
+        result,
+        Value::Array(vec![
+            Value::String("HALF_UP".to_string()),
+            Value::Number(0.12),
+            Value::Number(57.0),
+        ])
+    );
+    assert!(
+        engine
+            .eval(r#"SET_PAYROLL_ROUNDING(2, "NEAREST")"#)
+            .is_err()
+    );
+}
+
+#[test]
+fn test_payroll_rounding_is_per_engine() {
+    let mut first = Aether::new();
+    first.eval(r#"SET_PAYROLL_ROUNDING(0, "DOWN")"#).unwrap();
+    // 设置在同一引擎的后续求值中保留
+    assert_eq!(
+        first.eval("CALC_HOURLY_PAY(10000)").unwrap(),
+        Value::Number(57.0)
+    );
+
+    // 同一线程上的其他引擎不受影响
+    let mut second = Aether::new();
+    assert_eq!(
+        second.eval("CALC_HOURLY_PAY(10000)").unwrap(),
+        Value::Number(57.47)
+    );
+
+    // reset_env 恢复默认规则
+    first.reset_env();
+    assert_eq!(
+        first.eval("CALC_HOURLY_PAY(10000)").unwrap(),
+        Value::Number(57.47)
+    );
+}
+
+#[test]
+fn test_payroll_run_totals_equal_sum_of_rows() {
+    let mut engine = Aether::new();
+    let code = r#"
+        Set RESULT PAYROLL_RUN([
+            {"id": 1, "base_salary": 10000.01},
+            {"id": 2, "base_salary": 12345.67},
+            {"id": 3, "base_salary": 9999.99}
+        ], {})
+        Set ROWS RESULT["results"]
+        Set SUM_NET TO_FRACTION(0)
+        For ROW In ROWS {
+            Set SUM_NET SUM_NET + TO_FRACTION(ROW["net"])
+        }
+        [TO_FLOAT(SUM_NET), RESULT["totals"]["net"]]
+    "#;
+    let result = engine.eval(code).unwrap();
+    match result {
+        Value::Array(items) => assert_eq!(items[0], items[1]),
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn test_set_holiday_calendar_with_makeup_workdays() {
+    let mut engine = Aether::new();
+    let code = r#"
+        SET_HOLIDAY_CALENDAR(2024, ["2024-10-01", "2024-10-02", "2024-10-03"], ["2024-10-12"])
+        [IS_HOLIDAY("2024-10-01"), IS_WORKDAY("2024-10-02"), IS_WORKDAY("2024-10-12"), IS_WORKDAY("2024-10-08")]
+    "#;
+    let result = engine.eval(code).unwrap();
+    assert_eq!(
+        result,
+        Value::Array(vec![
+            Value::Number(1.0),
+            Value::Number(0.0),
+            Value::Number(1.0),
+            Value::Number(1.0),
+        ])
+    );
+}
+
+#[test]
+fn test_builtin_cn_calendar_workdays() {
+    let mut engine = Aether::new();
+    let code = r#"
+        Set YEARS LOAD_HOLIDAY_CALENDAR("CN", 2024)
+        [YEARS, CALC_WORKDAYS("2024-10-01", "2024-10-31"), CALC_HOLIDAY_DAYS("2024-10-01", "2024-10-31")]
+    "#;
+    let result = engine.eval(code).unwrap();
+    // 2024年10月：23个周一至周五，扣除国庆5天，加上10月12日补班
+    assert_eq!(
+        result,
+        Value::Array(vec![
+            Value::Array(vec![Value::Number(2024.0)]),
+            Value::Number(19.0),
+            Value::Number(7.0),
+        ])
+    );
+}
+
+#[test]
+fn test_load_holiday_calendar_from_json() {
+    let mut engine = Aether::new();
+    let code = r#"
+        LOAD_HOLIDAY_CALENDAR("{\"2030\": {\"holidays\": [\"2030-01-01\"]}}")
+        [IS_HOLIDAY("2030-01-01"), IS_HOLIDAY("2030-01-02")]
+    "#;
+    let result = engine.eval(code).unwrap();
+    assert_eq!(
+        result,
+        Value::Array(vec![Value::Number(1.0), Value::Number(0.0)])
+    );
+}
+
+#[test]
+fn test_holiday_calendar_is_per_engine() {
+    let mut first = Aether::new();
+    first.eval(r#"LOAD_HOLIDAY_CALENDAR("CN", 2024)"#).unwrap();
+    assert_eq!(
+        first
+            .eval(r#"CALC_WORKDAYS("2024-10-01", "2024-10-31")"#)
+            .unwrap(),
+        Value::Number(19.0)
+    );
+
+    // 同一线程上的其他引擎看不到这份日历
+    let mut second = Aether::new();
+    assert_eq!(
+        second
+            .eval(r#"CALC_WORKDAYS("2024-10-01", "2024-10-31")"#)
+            .unwrap(),
+        Value::Number(23.0)
+    );
+
+    // reset_env 清空日历
+    first.reset_env();
+    assert_eq!(
+        first
+            .eval(r#"CALC_WORKDAYS("2024-10-01", "2024-10-31")"#)
+            .unwrap(),
+        Value::Number(23.0)
+    );
+}
+
+#[test]
+fn test_holiday_calendar_rejects_dates_outside_year() {
+    let mut engine = Aether::new();
+    assert!(
+        engine
+            .eval(r#"SET_HOLIDAY_CALENDAR(2024, ["2025-01-01"])"#)
+            .is_err()
+    );
+    assert!(engine.eval(r#"IS_HOLIDAY("2024-13-01")"#).is_err());
+}
+
+#[test]
+fn test_legacy_numeric_date_functions_still_work() {
+    let mut engine = Aether::new();
+    let result = engine
+        .eval("[CALC_WORKDAYS(30, 8), IS_WORKDAY(3, 0), IS_HOLIDAY(1, 1), CALC_ANNUAL_WORKDAYS()]")
+        .unwrap();
+    assert_eq!(
+        result,
+        Value::Array(vec![
+            Value::Number(22.0),
+            Value::Number(1.0),
+            Value::Number(1.0),
+            Value::Number(261.0),
+        ])
+    );
+}
+
+#[test]
+fn test_explicit_rounding_modes() {
+    let mut engine = Aether::new();
+    let code = r#"
+        [
+            ROUND_HALF_EVEN(2.5), ROUND_HALF_EVEN(0.125, 2), ROUND_HALF_EVEN(0.135, 2),
+            ROUND_HALF_UP(1.005, 2), ROUND_HALF_UP(-2.5),
+            ROUND_DOWN(3.789, 2), ROUND_DOWN(-3.789, 2),
+            ROUND_UP(3.781, 2), ROUND_UP(-3.781, 2)
+        ]
+    "#;
+    let result = engine.eval(code).unwrap();
+    assert_eq!(
+        result,
+        Value::Array(
+            [2.0, 0.12, 0.14, 1.01, -3.0, 3.78, -3.78, 3.79, -3.79]
+                .into_iter()
+                .map(Value::Number)
+                .collect()
+        )
+    );
+    assert_eq!(
+        engine
+            .eval("TYPE(ROUND_HALF_EVEN(TO_FRACTION(1) / 3, 2))")
+            .unwrap(),
+        Value::String("Fraction".to_string())
+    );
+    assert!(engine.eval("ROUND_UP(1.5, -1)").is_err());
+    assert!(engine.eval(r#"ROUND_DOWN("1.5")"#).is_err());
+}
+
+#[test]
+fn test_allocate_distributes_remainder() {
+    let mut engine = Aether::new();
+    let numbers =
+        |values: &[f64]| Value::Array(values.iter().copied().map(Value::Number).collect());
+
+    assert_eq!(
+        engine.eval("ALLOCATE(100, [1, 1, 1])").unwrap(),
+        numbers(&[33.34, 33.33, 33.33])
+    );
+    assert_eq!(
+        engine.eval("ALLOCATE(0.05, [3, 7])").unwrap(),
+        numbers(&[0.02, 0.03])
+    );
+    assert_eq!(
+        engine.eval("ALLOCATE(-100, [1, 1, 1])").unwrap(),
+        numbers(&[-33.34, -33.33, -33.33])
+    );
+    assert_eq!(
+        engine.eval("ALLOCATE(10, [1, 0, 2], 0)").unwrap(),
+        numbers(&[3.0, 0.0, 7.0])
+    );
+    let code = r#"
+        Set PARTS ALLOCATE(TO_FRACTION(1000), [0.2, 0.3, 0.5, 1.7])
+        Set TOTAL TO_FRACTION(0)
+        For PART In PARTS {
+            Set TOTAL FRAC_ADD(TOTAL, PART)
+        }
+        TOTAL
+    "#;
+    assert_eq!(
+        engine.eval(code).unwrap(),
+        engine.eval("TO_FRACTION(1000)").unwrap()
+    );
+
+    assert!(engine.eval("ALLOCATE(100, [])").is_err());
+    assert!(engine.eval("ALLOCATE(100, [0, 0])").is_err());
+    assert!(engine.eval("ALLOCATE(100, [1, -1])").is_err());
+    assert!(engine.eval("ALLOCATE(100.005, [1, 1])").is_err());
+}
+
+#[test]
+fn test_payroll_run_from_csv_with_quoted_fields() {
+    let path = std::env::temp_dir().join("aether_payroll_run_quoted_test.csv");
+    std::fs::write(
+        &path,
+        "id,name,base_salary\n1,\"Smith, John\",10000\n2,\"Li \"\"Lee\"\"\",8000\n",
+    )
+    .unwrap();
+
+    let mut engine = Aether::with_all_permissions();
+    let code = format!(
+        r#"Set RESULT PAYROLL_RUN("{}")
+        [RESULT["count"], RESULT["results"][0]["name"], RESULT["results"][1]["name"]]"#,
+        path.display()
+    );
+    let result = engine.eval(&code).unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    assert_eq!(
+        result,
+        Value::Array(vec![
+            Value::Number(2.0),
+            Value::String("Smith, John".to_string()),
+            Value::String("Li \"Lee\"".to_string()),
+        ])
+    );
+}
@@ -0,0 +1,80 @@
+// tests/plot_tests.rs
+//! SVG 图表内置函数测试
+
+use aether::{Aether, Value};
+
+fn svg(engine: &mut Aether, code: &str) -> String {
+    match engine.eval(code).unwrap() {
+        Value::String(svg) => svg,
+        other => panic!("expected SVG string, got {:?}", other),
+    }
+}
+
+#[test]
+fn line_chart_draws_one_polyline_per_series() {
+    let mut engine = Aether::new();
+    let single = svg(&mut engine, "PLOT_LINE([1, 2, 3], [2, 4, 3])");
+    assert!(single.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+    assert_eq!(single.matches("<polyline").count(), 1);
+    assert_eq!(single.matches("<circle").count(), 3);
+
+    let multi = svg(
+        &mut engine,
+        r#"PLOT_LINE([1, 2], [[1, 2], [2, 1]], {"labels": ["in", "out"], "title": "Flow"})"#,
+    );
+    assert_eq!(multi.matches("<polyline").count(), 2);
+    assert!(multi.contains(">in</text>") && multi.contains(">out</text>"));
+    assert!(multi.contains(">Flow</text>"));
+}
+
+#[test]
+fn bar_chart_labels_and_escapes() {
+    let mut engine = Aether::new();
+    let chart = svg(
+        &mut engine,
+        r#"PLOT_BAR(["Q1", "R&D"], [10, -5], {"width": 300, "height": 200, "color": "teal"})"#,
+    );
+    assert!(chart.contains("width=\"300\" height=\"200\""));
+    assert!(chart.contains(">R&amp;D</text>"));
+    assert_eq!(chart.matches("fill=\"teal\"").count(), 2);
+}
+
+#[test]
+fn histogram_counts_values_into_bins() {
+    let mut engine = Aether::new();
+    let chart = svg(
+        &mut engine,
+        r#"PLOT_HIST([1, 1, 2, 3, 3, 3, 4], {"bins": 3})"#,
+    );
+    assert_eq!(chart.matches("<rect x=").count(), 3);
+    assert!(chart.contains("<title>[1, 2): 2</title>"), "{}", chart);
+    assert!(chart.contains("<title>[3, 4): 4</title>"), "{}", chart);
+}
+
+#[test]
+fn invalid_input_is_rejected() {
+    let mut engine = Aether::new();
+    assert!(engine.eval("PLOT_LINE([1, 2], [1])").is_err());
+    assert!(engine.eval(r#"PLOT_BAR(["a"], ["x"])"#).is_err());
+    assert!(engine.eval("PLOT_HIST([])").is_err());
+    assert!(engine.eval(r#"PLOT_HIST([1], {"bins": 0})"#).is_err());
+}
+
+#[test]
+fn writing_files_requires_permission() {
+    let path = std::env::temp_dir().join(format!("aether_plot_{}.svg", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let code = format!(
+        r#"PLOT_HIST([1, 2, 3], {{"file": "{}"}})"#,
+        path.display().to_string().replace('\\', "\\\\")
+    );
+
+    let mut sandboxed = Aether::new();
+    assert!(sandboxed.eval(&code).is_err());
+    assert!(!path.exists());
+
+    let mut engine = Aether::with_all_permissions();
+    let returned = svg(&mut engine, &code);
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), returned);
+    let _ = std::fs::remove_file(&path);
+}
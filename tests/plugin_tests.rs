@@ -0,0 +1,80 @@
+// tests/plugin_tests.rs
+//! 插件 ABI 测试：编译 tests/fixtures/plugin_pack.rs 为动态库后加载（需要 plugins 特性）
+
+#![cfg(feature = "plugins")]
+
+use aether::plugin::AETHER_PLUGIN_ABI_VERSION;
+use aether::{Aether, Value};
+use std::path::PathBuf;
+use std::process::Command;
+
+/// 用 rustc 把测试插件编译为动态库，返回库文件路径
+fn build_plugin(name: &str, extra_args: &[&str]) -> PathBuf {
+    let out_dir =
+        std::env::temp_dir().join(format!("aether_plugin_{}_{}", name, std::process::id()));
+    std::fs::create_dir_all(&out_dir).unwrap();
+    let status = Command::new(std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string()))
+        .args([
+            "--edition",
+            "2024",
+            "--crate-type",
+            "cdylib",
+            "--crate-name",
+            name,
+        ])
+        .args(extra_args)
+        .arg("--out-dir")
+        .arg(&out_dir)
+        .arg(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/plugin_pack.rs"
+        ))
+        .status()
+        .expect("rustc should run");
+    assert!(status.success());
+    out_dir.join(format!(
+        "{}{}{}",
+        std::env::consts::DLL_PREFIX,
+        name,
+        std::env::consts::DLL_SUFFIX
+    ))
+}
+
+#[test]
+fn plugin_functions_are_callable_from_scripts() {
+    assert_eq!(AETHER_PLUGIN_ABI_VERSION, 1);
+    let mut engine = Aether::new();
+    let info = engine.load_plugin(build_plugin("pack_ok", &[])).unwrap();
+    assert_eq!(info.name, "test-pack");
+    assert_eq!(info.functions, vec!["PACK_DOUBLE", "PACK_ECHO"]);
+
+    assert_eq!(engine.eval("PACK_DOUBLE(21)").unwrap(), Value::Number(42.0));
+    assert_eq!(
+        engine.eval(r#"PACK_ECHO("a")"#).unwrap(),
+        Value::Array(vec![Value::String("a".to_string())])
+    );
+    // 插件函数可以作为值传递
+    assert_eq!(
+        engine.eval("MAP([1, 2], PACK_DOUBLE)").unwrap(),
+        Value::Array(vec![Value::Number(2.0), Value::Number(4.0)])
+    );
+
+    let err = engine.eval(r#"PACK_DOUBLE("x")"#).unwrap_err();
+    assert!(err.contains("需要一个数字"), "{}", err);
+}
+
+#[test]
+fn plugins_with_another_abi_version_are_rejected() {
+    let mut engine = Aether::new();
+    let err = engine
+        .load_plugin(build_plugin("pack_wrong_abi", &["--cfg", "wrong_abi"]))
+        .unwrap_err();
+    assert!(err.contains("ABI"), "{}", err);
+    assert!(engine.eval("PACK_DOUBLE(1)").is_err());
+}
+
+#[test]
+fn missing_plugin_reports_an_error() {
+    let mut engine = Aether::new();
+    assert!(engine.load_plugin("/nonexistent/libpack.so").is_err());
+}
@@ -0,0 +1,121 @@
+// tests/profile_guided_tests.rs
+//! 基于调用次数的函数内联测试
+
+use aether::{Aether, Value};
+
+fn profiled(threshold: usize) -> Aether {
+    let mut engine = Aether::new().with_profile_guided(true);
+    engine.set_hot_call_threshold(threshold);
+    engine
+}
+
+fn calls(engine: &Aether, name: &str) -> usize {
+    engine
+        .function_call_counts()
+        .get(name)
+        .copied()
+        .unwrap_or(0)
+}
+
+const LOOP: &str = r#"
+Func SCALE(X) { Return X * FACTOR + 1 }
+Set FACTOR 3
+Set TOTAL 0
+For I In RANGE(0, 10) {
+    Set TOTAL TOTAL + SCALE(I)
+}
+TOTAL
+"#;
+
+#[test]
+fn profiling_is_off_by_default() {
+    let mut engine = Aether::new();
+    assert!(!engine.is_profile_guided());
+    engine.eval(LOOP).unwrap();
+    assert!(engine.function_call_counts().is_empty());
+}
+
+#[test]
+fn counts_user_function_calls() {
+    let mut engine = profiled(1000);
+    assert_eq!(engine.eval(LOOP).unwrap(), Value::Number(145.0));
+    assert_eq!(calls(&engine, "SCALE"), 10);
+    engine.eval(LOOP).unwrap();
+    assert_eq!(calls(&engine, "SCALE"), 20);
+}
+
+#[test]
+fn hot_function_is_inlined_on_next_eval() {
+    let mut engine = profiled(5);
+    let code = "Func SQUARE(X) { Return X * X }\nSet A 2\nSet B 0\nWhile (B < 20) { Set B B + SQUARE(A) }\nB";
+    assert_eq!(engine.eval(code).unwrap(), Value::Number(20.0));
+    assert_eq!(calls(&engine, "SQUARE"), 5);
+
+    // 第二次求值使用内联后的程序，结果不变且不再调用 SQUARE
+    assert_eq!(engine.eval(code).unwrap(), Value::Number(20.0));
+    assert_eq!(calls(&engine, "SQUARE"), 5);
+    assert_eq!(engine.eval(code).unwrap(), Value::Number(20.0));
+    assert_eq!(calls(&engine, "SQUARE"), 5);
+}
+
+#[test]
+fn calls_inside_functions_are_inlined() {
+    let mut engine = profiled(2);
+    let code =
+        "Func DOUBLE(X) { Return X * 2 }\nFunc QUAD(Y) { Return DOUBLE(DOUBLE(Y)) }\nQUAD(3)";
+    assert_eq!(engine.eval(code).unwrap(), Value::Number(12.0));
+    assert_eq!(calls(&engine, "DOUBLE"), 2);
+    assert_eq!(engine.eval(code).unwrap(), Value::Number(12.0));
+    // DOUBLE(Y) 被内联；外层 DOUBLE(...) 的参数不是简单值，保留调用
+    assert_eq!(calls(&engine, "DOUBLE"), 3);
+}
+
+#[test]
+fn shadowed_free_variables_prevent_inlining() {
+    let mut engine = profiled(1);
+    let code = r#"
+Set K 10
+Func ADD_K(X) { Return X + K }
+Func USE(V) {
+    Set K 100
+    Return ADD_K(V)
+}
+USE(1) + USE(2)
+"#;
+    assert_eq!(engine.eval(code).unwrap(), Value::Number(23.0));
+    assert_eq!(engine.eval(code).unwrap(), Value::Number(23.0));
+    assert_eq!(calls(&engine, "ADD_K"), 4);
+}
+
+#[test]
+fn recursive_and_redefined_functions_are_not_inlined() {
+    let mut engine = profiled(1);
+    let recursive = "Func SELF(N) { Return TYPE(SELF) }\nSELF(1) + SELF(2)";
+    engine.eval(recursive).unwrap();
+    engine.eval(recursive).unwrap();
+    assert_eq!(calls(&engine, "SELF"), 4);
+
+    let redefined = "Func G(X) { Return X + 1 }\nSet A G(1)\nFunc G(X) { Return X + 2 }\nA + G(1)";
+    assert_eq!(engine.eval(redefined).unwrap(), Value::Number(5.0));
+    assert_eq!(engine.eval(redefined).unwrap(), Value::Number(5.0));
+    assert_eq!(calls(&engine, "G"), 4);
+}
+
+#[test]
+fn calls_before_the_definition_are_not_inlined() {
+    let mut engine = profiled(1);
+    engine.eval("Func H(X) { Return X + 100 }").unwrap();
+    let code = "Set A H(1)\nFunc H(X) { Return X + 1 }\nA + H(1)";
+    assert_eq!(engine.eval(code).unwrap(), Value::Number(103.0));
+    engine.eval("Func H(X) { Return X + 100 }").unwrap();
+    assert_eq!(engine.eval(code).unwrap(), Value::Number(103.0));
+}
+
+#[test]
+fn disabling_stops_counting() {
+    let mut engine = profiled(1);
+    engine.eval(LOOP).unwrap();
+    engine.set_profile_guided(false);
+    engine.eval("Func F(X) { Return X }\nF(1)").unwrap();
+    assert_eq!(calls(&engine, "F"), 0);
+}
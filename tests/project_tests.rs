@@ -0,0 +1,190 @@
+use aether::{Aether, Project, Value};
+use std::path::PathBuf;
+
+struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    fn new(prefix: &str) -> Self {
+        use std::time::{SystemTime, UNIX_EPOCH};
+
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let pid = std::process::id();
+
+        let mut path = std::env::temp_dir();
+        path.push(format!("{prefix}_{pid}_{nanos}"));
+        std::fs::create_dir_all(&path).unwrap();
+
+        Self { path }
+    }
+
+    fn write(&self, rel: &str, content: &str) -> PathBuf {
+        let p = self.path.join(rel);
+        if let Some(parent) = p.parent() {
+            std::fs::create_dir_all(parent).unwrap();
+        }
+        std::fs::write(&p, content).unwrap();
+        p
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
+
+fn sample_project(prefix: &str) -> TempDir {
+    let dir = TempDir::new(prefix);
+    dir.write(
+        "aether.json",
+        r#"{ "name": "demo", "entry": "main", "entries": { "MAIN": "main.aether", "report": "bin/report" } }"#,
+    );
+    dir.write(
+        "main.aether",
+        r#"
+Import {GREET} From "lib/greet"
+Import {DOUBLE} From "lib/math"
+GREET("main") + " " + TO_STRING(DOUBLE(21))
+"#,
+    );
+    dir.write(
+        "lib/greet.aether",
+        r#"
+Import {DOUBLE} From "./math"
+TRACE("load greet")
+Func GREET(N) { Return "hi " + N + " " + TO_STRING(DOUBLE(1)) }
+Export GREET
+"#,
+    );
+    dir.write(
+        "lib/math.aether",
+        r#"
+TRACE("load math")
+Func DOUBLE(X) { Return X * 2 }
+Export DOUBLE
+"#,
+    );
+    dir.write(
+        "bin/report.aether",
+        r#"
+Import {DOUBLE} From "lib/math"
+DOUBLE(50)
+"#,
+    );
+    dir
+}
+
+#[test]
+fn test_run_project_default_entry() {
+    let dir = sample_project("aether_project_default");
+    let project = Project::open(&dir.path).unwrap();
+    assert_eq!(project.name(), "demo");
+
+    let mut engine = Aether::new();
+    let result = engine.run_project(&project, None).unwrap();
+    assert_eq!(result, Value::String("hi main 2 42".to_string()));
+}
+
+#[test]
+fn test_modules_load_once_in_dependency_order() {
+    let dir = sample_project("aether_project_order");
+    let project = Project::open(&dir.path).unwrap();
+
+    let mut engine = Aether::new();
+    engine.run_project(&project, Some("MAIN")).unwrap();
+
+    let trace = engine.take_trace();
+    assert_eq!(trace.len(), 2, "{:?}", trace);
+    assert!(trace[0].contains("load math"), "{:?}", trace);
+    assert!(trace[1].contains("load greet"), "{:?}", trace);
+}
+
+#[test]
+fn test_named_entry_and_relative_path_entry() {
+    let dir = sample_project("aether_project_entries");
+    let project = Project::open(dir.path.join("aether.json")).unwrap();
+
+    let mut engine = Aether::new();
+    assert_eq!(
+        engine.run_project(&project, Some("report")).unwrap(),
+        Value::Number(100.0)
+    );
+
+    let mut engine = Aether::new();
+    assert_eq!(
+        engine
+            .run_project(&project, Some("bin/report.aether"))
+            .unwrap(),
+        Value::Number(100.0)
+    );
+}
+
+#[test]
+fn test_unknown_entry_lists_available_entries() {
+    let dir = sample_project("aether_project_unknown");
+    let project = Project::open(&dir.path).unwrap();
+
+    let err = Aether::new()
+        .run_project(&project, Some("nope"))
+        .unwrap_err();
+    assert!(err.contains("unknown entry 'nope'"), "{}", err);
+    assert!(err.contains("MAIN, report"), "{}", err);
+}
+
+#[test]
+fn test_directory_without_manifest_uses_main() {
+    let dir = TempDir::new("aether_project_no_manifest");
+    dir.write("main.aether", "Import {ONE} From \"one\"\n(ONE + 1)");
+    dir.write("one.aether", "Set ONE 1\nExport ONE");
+
+    let project = Project::open(&dir.path).unwrap();
+    assert_eq!(
+        Aether::new().run_project(&project, None).unwrap(),
+        Value::Number(2.0)
+    );
+}
+
+#[test]
+fn test_circular_import_is_reported_before_running() {
+    let dir = TempDir::new("aether_project_cycle");
+    dir.write(
+        "main.aether",
+        "TRACE(\"main ran\")\nImport {A} From \"a\"\nA",
+    );
+    dir.write("a.aether", "Import {B} From \"b\"\nSet A 1\nExport A");
+    dir.write("b.aether", "Import {A} From \"a\"\nSet B 2\nExport B");
+
+    let project = Project::open(&dir.path).unwrap();
+    let mut engine = Aether::new();
+    let err = engine.run_project(&project, None).unwrap_err();
+    assert!(
+        err.contains("Circular import: a.aether -> b.aether -> a.aether"),
+        "{}",
+        err
+    );
+    assert!(engine.take_trace().is_empty());
+}
+
+#[test]
+fn test_imports_cannot_escape_project_root() {
+    let outer = TempDir::new("aether_project_escape");
+    outer.write("secret.aether", "Set S 1\nExport S");
+    outer.write("app/main.aether", "Import {S} From \"../secret\"\nS");
+
+    let project = Project::open(outer.path.join("app")).unwrap();
+    let err = Aether::new().run_project(&project, None).unwrap_err();
+    assert!(err.contains("denied"), "{}", err);
+}
+
+#[test]
+fn test_invalid_manifest() {
+    let dir = TempDir::new("aether_project_bad_manifest");
+    dir.write("aether.json", r#"{ "entry": 3 }"#);
+    let err = Project::open(&dir.path).unwrap_err();
+    assert!(err.contains("invalid manifest"), "{}", err);
+}
@@ -0,0 +1,276 @@
+// tests/pure_eval_tests.rs
+//! 纯脚本结果缓存测试
+
+use aether::result_cache::{MemoryResultStore, ResultKey, ResultStore};
+use aether::{Aether, Value};
+use std::cell::Cell;
+use std::rc::Rc;
+
+/// 记录命中次数的存储
+struct CountingStore {
+    inner: MemoryResultStore,
+    hits: Rc<Cell<usize>>,
+    inserts: Rc<Cell<usize>>,
+}
+
+impl ResultStore for CountingStore {
+    fn get(&mut self, key: &ResultKey) -> Option<Value> {
+        let value = self.inner.get(key);
+        if value.is_some() {
+            self.hits.set(self.hits.get() + 1);
+        }
+        value
+    }
+
+    fn insert(&mut self, key: ResultKey, value: Value) {
+        self.inserts.set(self.inserts.get() + 1);
+        self.inner.insert(key, value);
+    }
+
+    fn clear(&mut self) {
+        self.inner.clear();
+    }
+}
+
+fn counting_engine() -> (Aether, Rc<Cell<usize>>, Rc<Cell<usize>>) {
+    let hits = Rc::new(Cell::new(0));
+    let inserts = Rc::new(Cell::new(0));
+    let mut engine = Aether::new();
+    engine.set_result_store(CountingStore {
+        inner: MemoryResultStore::new(),
+        hits: Rc::clone(&hits),
+        inserts: Rc::clone(&inserts),
+    });
+    (engine, hits, inserts)
+}
+
+const RULE: &str = r#"
+Func BONUS(SALARY, RATE) { Return SALARY * RATE }
+If (LEVEL > 3) { BONUS(SALARY, 0.2) } Else { BONUS(SALARY, 0.1) }
+"#;
+
+#[test]
+fn repeated_inputs_hit_the_cache() {
+    let (mut engine, hits, inserts) = counting_engine();
+    let inputs = [
+        ("LEVEL", Value::Number(4.0)),
+        ("SALARY", Value::Number(1000.0)),
+    ];
+    assert_eq!(
+        engine.eval_pure(RULE, &inputs).unwrap(),
+        Value::Number(200.0)
+    );
+    assert_eq!((hits.get(), inserts.get()), (0, 1));
+    assert_eq!(
+        engine.eval_pure(RULE, &inputs).unwrap(),
+        Value::Number(200.0)
+    );
+    assert_eq!((hits.get(), inserts.get()), (1, 1));
+
+    // 输入顺序不影响缓存键
+    let reordered = [
+        ("SALARY", Value::Number(1000.0)),
+        ("LEVEL", Value::Number(4.0)),
+    ];
+    engine.eval_pure(RULE, &reordered).unwrap();
+    assert_eq!(hits.get(), 2);
+
+    // 不同输入重新执行
+    let other = [
+        ("LEVEL", Value::Number(1.0)),
+        ("SALARY", Value::Number(1000.0)),
+    ];
+    assert_eq!(
+        engine.eval_pure(RULE, &other).unwrap(),
+        Value::Number(100.0)
+    );
+    assert_eq!((hits.get(), inserts.get()), (2, 2));
+}
+
+#[test]
+fn impure_scripts_are_rejected() {
+    let mut engine = Aether::new();
+    for (code, reason) in [
+        ("PRINT(1)", "impure builtin 'PRINT'"),
+        ("RANDOM()", "impure builtin 'RANDOM'"),
+        ("READ_FILE(\"x\")", "impure builtin 'READ_FILE'"),
+        ("Import {A} From \"lib\"", "imports module 'lib'"),
+        ("UNKNOWN + 1", "reads 'UNKNOWN'"),
+        ("MAP([1], PRINTLN)", "impure builtin 'PRINTLN'"),
+    ] {
+        let err = engine.eval_pure(code, &[]).unwrap_err();
+        assert!(err.contains("Script is not pure"), "{}", err);
+        assert!(err.contains(reason), "{}: {}", code, err);
+    }
+}
+
+#[test]
+fn engine_globals_are_not_inputs() {
+    let mut engine = Aether::new();
+    engine.eval("Set RATE 2").unwrap();
+    let err = engine.eval_pure("RATE * 3", &[]).unwrap_err();
+    assert!(err.contains("reads 'RATE'"), "{}", err);
+}
+
+#[test]
+fn pure_eval_leaves_no_bindings() {
+    let mut engine = Aether::new();
+    engine
+        .eval_pure("Set TEMP X + 1\nTEMP", &[("X", Value::Number(1.0))])
+        .unwrap();
+    assert!(engine.eval("TEMP").is_err());
+    assert!(engine.eval("X").is_err());
+}
+
+#[test]
+fn errors_and_functions_are_not_cached() {
+    let (mut engine, _hits, inserts) = counting_engine();
+    assert!(
+        engine
+            .eval_pure(
+                "X / 0 + Y[5]",
+                &[("X", Value::Number(1.0)), ("Y", Value::Array(vec![]))]
+            )
+            .is_err()
+    );
+    engine.eval_pure("Lambda (A) -> A + 1", &[]).unwrap();
+    assert_eq!(inserts.get(), 0);
+}
+
+#[test]
+fn forks_share_the_result_store() {
+    let (engine, hits, _inserts) = counting_engine();
+    let inputs = [
+        ("LEVEL", Value::Number(4.0)),
+        ("SALARY", Value::Number(10.0)),
+    ];
+    engine.fork().eval_pure(RULE, &inputs).unwrap();
+    engine.fork().eval_pure(RULE, &inputs).unwrap();
+    assert_eq!(hits.get(), 1);
+}
+
+#[test]
+fn clear_result_cache_forces_reevaluation() {
+    let (mut engine, hits, inserts) = counting_engine();
+    let inputs = [
+        ("LEVEL", Value::Number(4.0)),
+        ("SALARY", Value::Number(10.0)),
+    ];
+    engine.eval_pure(RULE, &inputs).unwrap();
+    engine.clear_result_cache();
+    engine.eval_pure(RULE, &inputs).unwrap();
+    assert_eq!((hits.get(), inserts.get()), (0, 2));
+}
+
+#[test]
+fn builtins_are_impure_unless_marked_pure() {
+    let (mut engine, _hits, inserts) = counting_engine();
+    for (code, name) in [
+        (r#"SECRET("API_KEY")"#, "SECRET"),
+        (r#"MARK_SECRET("token")"#, "MARK_SECRET"),
+        ("PLOT_BAR([1, 2])", "PLOT_BAR"),
+        ("HOST_LOOKUP(1)", "HOST_LOOKUP"),
+    ] {
+        engine
+            .register_function("HOST_LOOKUP", 1, |args| Ok(args[0].clone()))
+            .unwrap();
+        let err = engine.eval_pure(code, &[]).unwrap_err();
+        assert!(
+            err.contains(&format!("impure builtin '{}'", name)),
+            "{}: {}",
+            code,
+            err
+        );
+    }
+    assert_eq!(inserts.get(), 0);
+
+    // 宿主明确标记后才能使用
+    engine.mark_pure("HOST_LOOKUP").unwrap();
+    assert_eq!(
+        engine.eval_pure("HOST_LOOKUP(2)", &[]).unwrap(),
+        Value::Number(2.0)
+    );
+    assert_eq!(inserts.get(), 1);
+    assert!(engine.mark_pure("NO_SUCH_FUNCTION").is_err());
+    // 读写外部状态的函数不能标记为纯函数
+    assert!(engine.mark_pure("SECRET").is_err());
+    assert!(engine.eval_pure(r#"SECRET("key")"#, &[]).is_err());
+}
+
+#[cfg(feature = "payroll")]
+#[test]
+fn payroll_setters_are_impure() {
+    let mut engine = Aether::new();
+    let err = engine
+        .eval_pure(r#"SET_PAYROLL_ROUNDING(0, "DOWN")"#, &[])
+        .unwrap_err();
+    assert!(
+        err.contains("impure builtin 'SET_PAYROLL_ROUNDING'"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn results_containing_secrets_are_not_cached() {
+    let (mut engine, hits, inserts) = counting_engine();
+    engine.eval(r#"MARK_SECRET("s3cr3t")"#).unwrap();
+    let inputs = [("TOKEN", Value::String("s3cr3t".to_string()))];
+    engine.eval_pure(r#""Bearer " + TOKEN"#, &inputs).unwrap();
+    engine.eval_pure(r#""Bearer " + TOKEN"#, &inputs).unwrap();
+    assert_eq!((hits.get(), inserts.get()), (0, 0));
+}
+
+#[test]
+fn registry_metadata_drives_purity() {
+    let engine = Aether::with_all_permissions();
+    for meta in engine.builtin_metadata() {
+        assert!(!(meta.pure && meta.io), "{}", meta.name);
+        assert_eq!(
+            meta.pure,
+            !aether::analysis::is_impure_builtin(&meta.name),
+            "{}",
+            meta.name
+        );
+        // 需要权限才注册的函数都读写外部状态
+        if meta.permission.is_some() {
+            assert!(meta.io, "{}", meta.name);
+        }
+    }
+    let meta = |name: &str| {
+        engine
+            .builtin_metadata()
+            .into_iter()
+            .find(|meta| meta.name == name)
+            .unwrap()
+    };
+    assert!(meta("ABS").pure);
+    assert!(!meta("PRINTLN").pure);
+    assert!(meta("SECRET").io);
+}
+
+#[cfg(feature = "payroll")]
+#[test]
+fn payroll_settings_are_part_of_the_cache_key() {
+    let mut engine = Aether::new();
+    let pay = "CALC_DAILY_PAY(SALARY)";
+    let inputs = [("SALARY", Value::Number(10000.0))];
+    assert_eq!(
+        engine.eval_pure(pay, &inputs).unwrap(),
+        Value::Number(459.77)
+    );
+    engine.eval(r#"SET_PAYROLL_ROUNDING(0, "DOWN")"#).unwrap();
+    assert_eq!(
+        engine.eval_pure(pay, &inputs).unwrap(),
+        Value::Number(459.0)
+    );
+
+    let holiday = r#"IS_HOLIDAY(DAY)"#;
+    let inputs = [("DAY", Value::String("2024-10-01".to_string()))];
+    let before = engine.eval_pure(holiday, &inputs).unwrap();
+    engine
+        .eval(r#"SET_HOLIDAY_CALENDAR(2024, ["2024-10-01"])"#)
+        .unwrap();
+    let after = engine.eval_pure(holiday, &inputs).unwrap();
+    assert_ne!(before, after);
+}
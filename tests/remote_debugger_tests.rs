@@ -0,0 +1,79 @@
+use aether::debugger::{CommandAction, DebuggerSession, PROMPT, RemoteDebugServer, serve_commands};
+use aether::evaluator::Evaluator;
+use std::cell::RefCell;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::rc::Rc;
+
+fn session(source: &str) -> DebuggerSession {
+    let evaluator = Rc::new(RefCell::new(Evaluator::new()));
+    let mut session = DebuggerSession::new(evaluator);
+    session.set_source(source.to_string(), "remote.aether".to_string());
+    session
+}
+
+#[test]
+fn test_serve_commands_stops_at_continue() {
+    let mut session = session("Set X 1");
+    let mut input = "break 3\n\ninfo breakpoints\ncontinue\nprint X\n".as_bytes();
+    let mut output = Vec::new();
+
+    let action = serve_commands(&mut session, &mut input, &mut output).unwrap();
+    assert_eq!(action, CommandAction::Continue);
+
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.starts_with(PROMPT));
+    assert!(output.contains("Breakpoint 1 set at remote.aether:3"));
+    assert!(output.contains("Continuing..."));
+    // Commands after `continue` are left for the next call
+    assert!(!output.contains("X ="));
+    assert_eq!(input, "print X\n".as_bytes());
+}
+
+#[test]
+fn test_serve_commands_treats_end_of_input_as_quit() {
+    let mut session = session("Set X 1");
+    let mut output = Vec::new();
+    let action = serve_commands(&mut session, &mut "help\n".as_bytes(), &mut output).unwrap();
+    assert_eq!(action, CommandAction::Quit);
+    assert!(String::from_utf8(output).unwrap().contains("Time Travel"));
+}
+
+#[test]
+fn test_remote_client_drives_session_over_tcp() {
+    let server = RemoteDebugServer::bind("127.0.0.1:0").unwrap();
+    let addr = server.local_addr().unwrap();
+
+    let client = std::thread::spawn(move || {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream
+            .write_all(b"print TOTAL\ncontinue\nprint TOTAL\nhistory TOTAL\nquit\n")
+            .unwrap();
+        let mut transcript = String::new();
+        stream.read_to_string(&mut transcript).unwrap();
+        transcript
+    });
+
+    let mut session = session("Set TOTAL 0\nFor X In [1, 2, 3] { Set TOTAL (TOTAL + X) }");
+    let mut connection = server.accept().unwrap();
+    connection.send(&session.banner()).unwrap();
+
+    // Paused at start: nothing has run yet
+    assert_eq!(
+        connection.serve(&mut session).unwrap(),
+        CommandAction::Continue
+    );
+    let status = session.run_program();
+    assert_eq!(status, "Program exited normally");
+    connection.send(&status).unwrap();
+    assert_eq!(connection.serve(&mut session).unwrap(), CommandAction::Quit);
+    drop(connection);
+
+    let transcript = client.join().unwrap();
+    assert!(transcript.starts_with("Aether Debugger"), "{}", transcript);
+    assert!(transcript.contains("Variable 'TOTAL' not found"));
+    assert!(transcript.contains("Program exited normally"));
+    assert!(transcript.contains("TOTAL = 6"));
+    assert!(transcript.contains("No recording"));
+    assert!(transcript.contains("Exiting debugger..."));
+}
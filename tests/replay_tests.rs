@@ -0,0 +1,131 @@
+// tests/replay_tests.rs
+//! 执行录制与回放测试
+
+use aether::{Aether, ExecutionTrace, Value};
+use std::path::{Path, PathBuf};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("aether_replay_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn script(dir: &Path) -> String {
+    format!(
+        r#"Set CONFIG READ_FILE("{input}")
+Set LIMIT TO_NUMBER(TRIM(CONFIG))
+WRITE_FILE("{output}", "done")
+Set JITTER RANDOM()
+[LIMIT * 2, JITTER, NOW()]"#,
+        input = dir.join("limit.txt").display(),
+        output = dir.join("out.txt").display()
+    )
+}
+
+#[test]
+fn replay_substitutes_recorded_io_results() {
+    let dir = temp_dir("substitute");
+    std::fs::write(dir.join("limit.txt"), "21\n").unwrap();
+
+    let mut engine = Aether::with_all_permissions();
+    let trace = engine.eval_recorded(&script(&dir));
+    let functions: Vec<&str> = trace.events.iter().map(|e| e.function.as_str()).collect();
+    assert_eq!(functions, ["READ_FILE", "WRITE_FILE", "RANDOM", "NOW"]);
+    assert!(trace.error.is_none(), "{:?}", trace.error);
+
+    // 外部世界已经改变：输入文件不同，输出文件被删除
+    std::fs::write(dir.join("limit.txt"), "99\n").unwrap();
+    std::fs::remove_file(dir.join("out.txt")).unwrap();
+
+    let trace = ExecutionTrace::from_json(&trace.to_json()).unwrap();
+    let replayed = Aether::with_all_permissions().replay(&trace);
+    assert!(trace.reproduces(&replayed), "{:?}", replayed);
+    match replayed.unwrap() {
+        Value::Array(items) => assert_eq!(items[0], Value::Number(42.0)),
+        other => panic!("unexpected result {}", other),
+    }
+    // 回放不产生真实的副作用
+    assert!(!dir.join("out.txt").exists());
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn recorded_errors_are_reproduced() {
+    let dir = temp_dir("errors");
+    let code = format!(
+        "Set TEXT READ_FILE(\"{}\")\nTEXT",
+        dir.join("missing.txt").display()
+    );
+    let trace = Aether::with_all_permissions().eval_recorded(&code);
+    assert!(trace.events[0].error.is_some());
+    let error = trace.error.clone().unwrap();
+
+    // 回放时文件存在也不会被读取
+    std::fs::write(dir.join("missing.txt"), "now present").unwrap();
+    let replayed = Aether::with_all_permissions().replay(&trace);
+    assert_eq!(replayed, Err(error));
+    assert!(trace.reproduces(&replayed));
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn divergent_scripts_are_reported() {
+    let mut trace = Aether::new().eval_recorded("[RANDOM(), NOW()]");
+    assert_eq!(trace.events.len(), 2);
+
+    let mut reordered = trace.clone();
+    reordered.script = "[NOW(), RANDOM()]".to_string();
+    let err = Aether::new().replay(&reordered).unwrap_err();
+    assert!(
+        err.contains(
+            "Replay diverged at IO event #1: script called NOW but the trace recorded RANDOM"
+        ),
+        "{}",
+        err
+    );
+
+    trace.script = "RANDOM()".to_string();
+    let err = Aether::new().replay(&trace).unwrap_err();
+    assert!(
+        err.contains("1 recorded IO events left (next: NOW)"),
+        "{}",
+        err
+    );
+
+    trace.script = "[RANDOM(), NOW(), NOW()]".to_string();
+    let err = Aether::new().replay(&trace).unwrap_err();
+    assert!(err.contains("the trace has only 2 IO events"), "{}", err);
+}
+
+#[test]
+fn recording_is_opt_in_and_secrets_are_masked() {
+    let mut engine = Aether::new();
+    assert_eq!(engine.take_io_events(), None);
+    engine.eval("RANDOM()").unwrap();
+    assert_eq!(engine.take_io_events(), None);
+
+    let mut engine = Aether::with_all_permissions();
+    engine
+        .eval("Set TOKEN MARK_SECRET(\"s3cr3t-token\")")
+        .unwrap();
+    engine.start_io_recording();
+    let _ = engine.eval("FILE_EXISTS(\"/tmp/\" + TOKEN)");
+    let events = engine.take_io_events().unwrap();
+    assert_eq!(events.len(), 1);
+    assert!(!events[0].args[0].to_string().contains("s3cr3t-token"));
+}
+
+#[test]
+fn trace_files_are_versioned() {
+    let trace = Aether::new().eval_recorded("1 + 1");
+    assert_eq!(trace.result.as_deref(), Some("2"));
+    let json = trace.to_json().replace("\"version\": 1", "\"version\": 99");
+    let err = ExecutionTrace::from_json(&json).unwrap_err();
+    assert!(
+        err.contains("Unsupported execution trace version 99"),
+        "{}",
+        err
+    );
+    assert!(ExecutionTrace::from_json("{}").is_err());
+}
@@ -0,0 +1,120 @@
+// tests/report_tests.rs
+//! REPORT_BUILD 报告生成测试
+
+use aether::{Aether, Value};
+
+fn report(engine: &mut Aether, code: &str) -> String {
+    match engine.eval(code).unwrap() {
+        Value::String(report) => report,
+        other => panic!("expected report string, got {:?}", other),
+    }
+}
+
+#[test]
+fn markdown_report_renders_sections_and_templates() {
+    let mut engine = Aether::new();
+    let output = report(
+        &mut engine,
+        r#"REPORT_BUILD([
+            {"type": "heading", "text": "{{month}} Payroll", "level": 1},
+            "Employees: {{count}}",
+            {"type": "list", "items": ["a", "b"], "ordered": True},
+            {"type": "table", "rows": [{"name": "A|B", "net": 100}, {"name": "C", "net": 200}], "columns": ["name", "net"]},
+            {"type": "summary", "data": {"total": 300}}
+        ], {"data": {"month": "2024-05", "count": 2}})"#,
+    );
+    assert!(output.starts_with("# 2024-05 Payroll\n"), "{}", output);
+    assert!(output.contains("Employees: 2\n"));
+    assert!(output.contains("1. a\n2. b\n"));
+    assert!(output.contains("| name | net |\n| --- | ---: |\n| A\\|B | 100 |\n"));
+    assert!(output.contains("| Item | Value |\n| --- | ---: |\n| total | 300 |\n"));
+}
+
+#[test]
+fn html_report_escapes_text_and_inlines_charts() {
+    let mut engine = Aether::new();
+    let output = report(
+        &mut engine,
+        r#"REPORT_BUILD([
+            "R&D <costs>",
+            {"type": "svg", "svg": PLOT_BAR(["a"], [1])},
+            {"type": "table", "rows": TABLE([{"x": 1}])}
+        ], {"format": "html", "title": "Report"})"#,
+    );
+    assert!(output.starts_with("<!DOCTYPE html>"));
+    assert!(output.contains("<title>Report</title>"));
+    assert!(output.contains("<h1>Report</h1>"));
+    assert!(output.contains("<p>R&amp;D &lt;costs&gt;</p>"));
+    assert!(output.contains("<figure>\n<svg"));
+    assert!(output.contains("<th>x</th>") && output.contains("<td class=\"num\">1</td>"));
+}
+
+#[test]
+fn invalid_sections_are_rejected() {
+    let mut engine = Aether::new();
+    assert!(engine.eval("REPORT_BUILD(1)").is_err());
+    assert!(engine.eval("REPORT_BUILD([1])").is_err());
+    assert!(engine.eval(r#"REPORT_BUILD([{"type": "chart"}])"#).is_err());
+    assert!(
+        engine
+            .eval(r#"REPORT_BUILD([{"type": "heading"}])"#)
+            .is_err()
+    );
+    assert!(
+        engine
+            .eval(r#"REPORT_BUILD([], {"format": "docx"})"#)
+            .is_err()
+    );
+}
+
+#[cfg(not(feature = "pdf"))]
+#[test]
+fn pdf_requires_feature() {
+    let mut engine = Aether::new();
+    let err = engine
+        .eval(r#"REPORT_BUILD(["x"], {"format": "pdf"})"#)
+        .unwrap_err();
+    assert!(err.contains("'pdf' feature"), "{}", err);
+}
+
+#[cfg(feature = "pdf")]
+#[test]
+fn pdf_report_is_a_valid_document() {
+    let mut engine = Aether::new();
+    let output = report(
+        &mut engine,
+        r#"REPORT_BUILD(["Net (total)", {"type": "table", "rows": [{"a": 1}]}], {"format": "pdf", "title": "T"})"#,
+    );
+    assert!(output.starts_with("%PDF-1.4\n"));
+    assert!(output.ends_with("%%EOF\n"));
+    assert!(output.contains("(Net \\(total\\)) Tj"));
+    let startxref: usize = output
+        .rsplit("startxref\n")
+        .next()
+        .unwrap()
+        .lines()
+        .next()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!(output[startxref..].starts_with("xref\n"));
+}
+
+#[test]
+fn writing_files_requires_permission() {
+    let path = std::env::temp_dir().join(format!("aether_report_{}.md", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let code = format!(
+        r#"REPORT_BUILD(["hello"], {{"file": "{}"}})"#,
+        path.display().to_string().replace('\\', "\\\\")
+    );
+
+    let mut sandboxed = Aether::new();
+    assert!(sandboxed.eval(&code).is_err());
+    assert!(!path.exists());
+
+    let mut engine = Aether::with_all_permissions();
+    let returned = report(&mut engine, &code);
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), returned);
+    let _ = std::fs::remove_file(&path);
+}
@@ -0,0 +1,157 @@
+// tests/resilience_tests.rs
+//! RETRY / WITH_TIMEOUT 测试
+
+use aether::{Aether, Value};
+use std::time::{Duration, Instant};
+
+fn store_path(name: &str) -> std::path::PathBuf {
+    let path =
+        std::env::temp_dir().join(format!("aether_retry_{}_{}.json", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+/// 第 N 次调用才成功的函数（调用次数记录在 KV 存储中）
+fn flaky_script(path: &std::path::Path, succeed_on: usize, options: &str) -> String {
+    format!(
+        r#"
+        Set DB KV_OPEN("{}")
+        Func FLAKY() {{
+            Set CALLS KV_GET(DB, "calls", 0) + 1
+            KV_SET(DB, "calls", CALLS)
+            If (CALLS < {}) {{
+                Return 1 / 0
+            }}
+            Return "ok after " + TO_STRING(CALLS)
+        }}
+        RETRY(FLAKY, {})
+        "#,
+        path.display(),
+        succeed_on,
+        options
+    )
+}
+
+#[test]
+fn retry_returns_first_success() {
+    let path = store_path("success");
+    let mut engine = Aether::with_all_permissions();
+    let result = engine
+        .eval(&flaky_script(
+            &path,
+            3,
+            r#"{"attempts": 5, "backoff_ms": 1}"#,
+        ))
+        .unwrap();
+    assert_eq!(result, Value::String("ok after 3".to_string()));
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn retry_gives_up_after_attempts_with_last_error() {
+    let path = store_path("give_up");
+    let mut engine = Aether::with_all_permissions();
+    let err = engine
+        .eval(&flaky_script(
+            &path,
+            10,
+            r#"{"attempts": 2, "backoff_ms": 1}"#,
+        ))
+        .unwrap_err();
+    assert!(err.to_lowercase().contains("division"), "{}", err);
+
+    let db = path.display().to_string().replace('\\', "\\\\");
+    let calls = engine
+        .eval(&format!(r#"KV_GET(KV_OPEN("{}"), "calls")"#, db))
+        .unwrap();
+    assert_eq!(calls, Value::Number(2.0));
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn retry_backs_off_exponentially() {
+    let path = store_path("backoff");
+    let mut engine = Aether::with_all_permissions();
+    let start = Instant::now();
+    engine
+        .eval(&flaky_script(
+            &path,
+            3,
+            r#"{"attempts": 3, "backoff_ms": 40, "jitter": 0.25}"#,
+        ))
+        .unwrap();
+    // 40ms + 80ms，抖动最多 -25%
+    assert!(start.elapsed() >= Duration::from_millis(90));
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn retry_validates_options() {
+    let mut engine = Aether::new();
+    assert!(engine.eval("RETRY(1)").is_err());
+    assert!(
+        engine
+            .eval(r#"RETRY(Lambda () -> 1, {"attempts": 0})"#)
+            .is_err()
+    );
+    assert!(
+        engine
+            .eval(r#"RETRY(Lambda () -> 1, {"jitter": 2})"#)
+            .is_err()
+    );
+    assert_eq!(
+        engine.eval("RETRY(Lambda () -> 42)").unwrap(),
+        Value::Number(42.0)
+    );
+}
+
+#[test]
+fn with_timeout_passes_through_fast_calls() {
+    let mut engine = Aether::new();
+    assert_eq!(
+        engine.eval("WITH_TIMEOUT(Lambda () -> 7, 1000)").unwrap(),
+        Value::Number(7.0)
+    );
+}
+
+#[test]
+fn with_timeout_stops_long_running_function() {
+    let mut engine = Aether::new();
+    let start = Instant::now();
+    let err = engine
+        .eval(
+            r#"
+            Func SPIN() {
+                Set I 0
+                While (1 == 1) {
+                    Set I (I + 1)
+                }
+            }
+            WITH_TIMEOUT(SPIN, 50)
+            "#,
+        )
+        .unwrap_err();
+    assert!(err.contains("WITH_TIMEOUT"), "{}", err);
+    assert!(start.elapsed() < Duration::from_secs(5));
+
+    // 超时后引擎可以继续使用
+    assert_eq!(engine.eval("1 + 1").unwrap(), Value::Number(2.0));
+}
+
+#[test]
+fn retry_wraps_timeouts() {
+    let mut engine = Aether::new();
+    let err = engine
+        .eval(
+            r#"
+            Func SPIN() {
+                While (1 == 1) {
+                    Set X 1
+                }
+            }
+            RETRY(Lambda () -> WITH_TIMEOUT(SPIN, 10), {"attempts": 2, "backoff_ms": 0})
+            "#,
+        )
+        .unwrap_err();
+    assert!(err.contains("within 10 ms"), "{}", err);
+}
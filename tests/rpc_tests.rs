@@ -0,0 +1,189 @@
+// tests/rpc_tests.rs
+//! gRPC 求值服务测试（需要 grpc 特性）
+#![cfg(feature = "grpc")]
+
+use aether::SandboxConfig;
+use aether::rpc::{CallFunctionRequest, CompileRequest, EvalRequest, EvalService, TraceEvent};
+use serde_json::Value as JsonValue;
+
+fn service() -> EvalService {
+    EvalService::new(2, SandboxConfig::dsl_safe())
+}
+
+fn eval_request(code: &str, inputs_json: &str) -> EvalRequest {
+    EvalRequest {
+        code: code.to_string(),
+        inputs_json: inputs_json.to_string(),
+    }
+}
+
+#[test]
+fn eval_binds_inputs() {
+    let mut service = service();
+    let response = service.eval(&eval_request(
+        "(PRICE * QTY)",
+        r#"{"PRICE": 2.5, "QTY": 4}"#,
+    ));
+    assert!(response.ok, "{:?}", response.error);
+    assert_eq!(response.result_json, "10.0");
+}
+
+#[test]
+fn eval_reports_errors_by_kind() {
+    let mut service = service();
+    let response = service.eval(&eval_request("(1 / 0)", ""));
+    assert!(!response.ok);
+    assert_eq!(response.error.unwrap().kind, "EvalError");
+
+    let response = service.eval(&eval_request("1", "[1, 2]"));
+    assert_eq!(response.error.unwrap().kind, "BadRequest");
+}
+
+#[test]
+fn eval_runs_in_sandbox() {
+    let mut service = service();
+    let response = service.eval(&eval_request(r#"READ_FILE("/etc/hostname")"#, ""));
+    assert!(!response.ok);
+}
+
+#[test]
+fn compile_returns_requirements() {
+    let service = service();
+    let response = service.compile(&CompileRequest {
+        code: "Set X 1\nPRINTLN(X)".to_string(),
+    });
+    assert!(response.ok);
+    assert_eq!(response.statements, 2);
+    let requirements: JsonValue = serde_json::from_str(&response.requirements_json).unwrap();
+    assert!(requirements.is_object());
+
+    let response = service.compile(&CompileRequest {
+        code: "Set X (".to_string(),
+    });
+    assert!(!response.ok);
+    assert_eq!(response.error.unwrap().kind, "ParseError");
+}
+
+#[test]
+fn call_function_after_setup() {
+    let mut service = service();
+    let response = service.call_function(&CallFunctionRequest {
+        setup_code: "Func SCALE(X, F) { Return X * F }".to_string(),
+        function: "SCALE".to_string(),
+        args_json: "[3, 7]".to_string(),
+    });
+    assert!(response.ok, "{:?}", response.error);
+    assert_eq!(response.result_json, "21");
+
+    let response = service.call_function(&CallFunctionRequest {
+        setup_code: String::new(),
+        function: "SCALE".to_string(),
+        args_json: String::new(),
+    });
+    assert!(!response.ok, "functions must not leak between calls");
+}
+
+#[test]
+fn stream_trace_ends_with_result() {
+    let mut service = service();
+    let events = service.stream_trace(&eval_request(
+        "TRACE_INFO(\"pricing\", \"base\", X)\n(X + 1)",
+        r#"{"X": 41}"#,
+    ));
+    assert_eq!(events.len(), 2);
+    let TraceEvent::Trace(record) = &events[0] else {
+        panic!("expected trace record, got {:?}", events[0]);
+    };
+    assert_eq!(record.level, "INFO");
+    assert_eq!(record.category, "pricing");
+    let TraceEvent::Result(result) = &events[1] else {
+        panic!("expected result, got {:?}", events[1]);
+    };
+    assert!(result.ok);
+    assert_eq!(result.result_json, "42");
+}
+
+#[tokio::test]
+async fn grpc_round_trip_over_tonic() {
+    use aether::rpc::GrpcEvaluator;
+    use aether::rpc::pb;
+    use aether::rpc::pb::evaluator_client::EvaluatorClient;
+    use tokio_stream::StreamExt;
+    use tokio_stream::wrappers::TcpListenerStream;
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = GrpcEvaluator::new(2, SandboxConfig::dsl_safe()).into_server();
+    tokio::spawn(
+        tonic::transport::Server::builder()
+            .add_service(server)
+            .serve_with_incoming(TcpListenerStream::new(listener)),
+    );
+    let mut client = EvaluatorClient::connect(format!("http://{}", addr))
+        .await
+        .unwrap();
+
+    let response = client
+        .eval(pb::EvalRequest {
+            code: "(X * 2)".to_string(),
+            inputs_json: r#"{"X": 21}"#.to_string(),
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(response.ok, "{:?}", response.error);
+    assert_eq!(response.result_json, "42");
+
+    let response = client
+        .eval(pb::EvalRequest {
+            code: "UNDEFINED_NAME".to_string(),
+            inputs_json: String::new(),
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(!response.ok);
+    assert_eq!(response.error.unwrap().kind, "EvalError");
+
+    let response = client
+        .compile(pb::CompileRequest {
+            code: "Set X 1\n(X + 1)".to_string(),
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(response.ok);
+    assert_eq!(response.statements, 2);
+
+    let response = client
+        .call_function(pb::CallFunctionRequest {
+            setup_code: "Func SCALE(X) { Return X * 3 }".to_string(),
+            function: "SCALE".to_string(),
+            args_json: "[7]".to_string(),
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(response.result_json, "21");
+
+    let events: Vec<pb::TraceEvent> = client
+        .stream_trace(pb::EvalRequest {
+            code: "TRACE_INFO(\"pricing\", \"base\", X)\n(X + 1)".to_string(),
+            inputs_json: r#"{"X": 41}"#.to_string(),
+        })
+        .await
+        .unwrap()
+        .into_inner()
+        .map(Result::unwrap)
+        .collect()
+        .await;
+    assert_eq!(events.len(), 2);
+    let Some(pb::trace_event::Event::Trace(record)) = &events[0].event else {
+        panic!("expected trace record, got {:?}", events[0]);
+    };
+    assert_eq!(record.category, "pricing");
+    let Some(pb::trace_event::Event::Result(result)) = &events[1].event else {
+        panic!("expected result, got {:?}", events[1]);
+    };
+    assert_eq!(result.result_json, "42");
+}
@@ -0,0 +1,126 @@
+// tests/ruleset_tests.rs
+//! 规则引擎模式（eval_ruleset）测试
+
+use aether::{Aether, RulePolicy, Value};
+use std::collections::HashMap;
+
+fn applicant(age: f64, income: f64) -> Value {
+    let mut map = HashMap::new();
+    map.insert("AGE".to_string(), Value::Number(age));
+    map.insert("INCOME".to_string(), Value::Number(income));
+    Value::Dict(map)
+}
+
+const VALIDATION: &[(&str, &str)] = &[
+    ("ADULT", "AGE >= 18"),
+    ("HAS_INCOME", "INCOME > 0"),
+    ("NOT_RETIRED", "AGE < 65"),
+];
+
+#[test]
+fn evaluates_every_rule_by_default() {
+    let mut engine = Aether::new();
+    let report = engine
+        .eval_ruleset(VALIDATION, &applicant(16.0, 1000.0))
+        .unwrap();
+    assert_eq!(report.results.len(), 3);
+    assert_eq!(report.get("ADULT"), Some(&Ok(Value::Boolean(false))));
+    assert_eq!(report.get("HAS_INCOME"), Some(&Ok(Value::Boolean(true))));
+    assert!(report.skipped.is_empty());
+    assert_eq!(report.stopped_at, None);
+    assert!(!report.has_errors());
+}
+
+#[test]
+fn later_rules_see_earlier_results() {
+    let mut engine = Aether::new();
+    let rules = vec![
+        ("BASE".to_string(), "INCOME * 0.1".to_string()),
+        (
+            "BONUS".to_string(),
+            "If (AGE > 30) { 50 } Else { 0 }".to_string(),
+        ),
+        ("TOTAL".to_string(), "BASE + BONUS".to_string()),
+    ];
+    let report = engine
+        .eval_ruleset(&rules, &applicant(40.0, 2000.0))
+        .unwrap();
+    assert_eq!(report.values()["TOTAL"], Value::Number(250.0));
+}
+
+#[test]
+fn per_rule_errors_do_not_stop_other_rules() {
+    let mut engine = Aether::new();
+    let rules = [
+        ("BROKEN_SYNTAX", "AGE >"),
+        ("STATEMENT", "Set X 1"),
+        ("MISSING", "UNKNOWN_FIELD * 2"),
+        ("OK", "AGE + 1"),
+    ];
+    let report = engine.eval_ruleset(&rules, &applicant(30.0, 0.0)).unwrap();
+    let errors = report.errors();
+    assert_eq!(errors.len(), 3);
+    assert!(errors[0].1.starts_with("Parse error"), "{:?}", errors);
+    assert!(errors[1].1.starts_with("Parse error"), "{:?}", errors);
+    assert!(errors[2].1.starts_with("Runtime error"), "{:?}", errors);
+    assert_eq!(report.get("OK"), Some(&Ok(Value::Number(31.0))));
+}
+
+#[test]
+fn stop_on_false_short_circuits_validation() {
+    let mut engine = Aether::new();
+    let report = engine
+        .eval_ruleset_with_policy(
+            VALIDATION,
+            &applicant(16.0, 1000.0),
+            RulePolicy::StopOnFalse,
+        )
+        .unwrap();
+    assert_eq!(report.results.len(), 1);
+    assert_eq!(report.stopped_at.as_deref(), Some("ADULT"));
+    assert_eq!(report.skipped, vec!["HAS_INCOME", "NOT_RETIRED"]);
+    assert_eq!(report.get("HAS_INCOME"), None);
+}
+
+#[test]
+fn stop_on_true_picks_the_first_matching_rule() {
+    let mut engine = Aether::new();
+    let tiers = [
+        ("GOLD", "INCOME >= 10000"),
+        ("SILVER", "INCOME >= 5000"),
+        ("BRONZE", "INCOME >= 0"),
+    ];
+    let report = engine
+        .eval_ruleset_with_policy(&tiers, &applicant(30.0, 6000.0), RulePolicy::StopOnTrue)
+        .unwrap();
+    assert_eq!(report.stopped_at.as_deref(), Some("SILVER"));
+    assert_eq!(report.skipped, vec!["BRONZE"]);
+}
+
+#[test]
+fn stop_on_error() {
+    let mut engine = Aether::new();
+    let rules = [("A", "1"), ("B", "1 / NOPE"), ("C", "3")];
+    let report = engine
+        .eval_ruleset_with_policy(&rules, &Value::Null, RulePolicy::StopOnError)
+        .unwrap();
+    assert_eq!(report.stopped_at.as_deref(), Some("B"));
+    assert_eq!(report.skipped, vec!["C"]);
+    assert!(report.to_string().contains("stopped at B"));
+}
+
+#[test]
+fn context_and_results_do_not_leak() {
+    let mut engine = Aether::new();
+    engine.eval("Set LIMIT 100").unwrap();
+    let rules = [("UNDER", "INCOME < LIMIT")];
+    let report = engine.eval_ruleset(&rules, &applicant(20.0, 50.0)).unwrap();
+    assert_eq!(report.get("UNDER"), Some(&Ok(Value::Boolean(true))));
+    assert!(engine.eval("UNDER").is_err());
+    assert!(engine.eval("INCOME").is_err());
+
+    let err = engine
+        .eval_ruleset(&rules, &Value::Number(1.0))
+        .unwrap_err();
+    assert!(err.contains("must be a Dict"), "{}", err);
+}
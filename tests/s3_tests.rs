@@ -0,0 +1,198 @@
+// tests/s3_tests.rs
+//! S3_GET / S3_PUT / S3_LIST 测试（需要 s3 特性），使用本地模拟的 S3 端点
+
+#![cfg(feature = "s3")]
+
+use aether::analysis::is_impure_builtin;
+use aether::sandbox::{S3Credentials, set_s3_credentials};
+use aether::{Aether, DeterministicConfig, Value};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// 收到的请求：(方法, 路径和查询, 头部)
+type Requests = Arc<Mutex<Vec<(String, String, HashMap<String, String>)>>>;
+
+/// 模拟 S3：对象保存在内存中，支持 PUT / GET 和 list-type=2 列表
+fn fake_s3() -> (String, Requests) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let endpoint = format!("http://{}", listener.local_addr().unwrap());
+    let requests: Requests = Arc::new(Mutex::new(Vec::new()));
+    let objects: Arc<Mutex<HashMap<String, Vec<u8>>>> = Arc::new(Mutex::new(HashMap::new()));
+    let log = requests.clone();
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let log = log.clone();
+            let objects = objects.clone();
+            thread::spawn(move || serve(stream, log, objects));
+        }
+    });
+    (endpoint, requests)
+}
+
+fn serve(stream: TcpStream, log: Requests, objects: Arc<Mutex<HashMap<String, Vec<u8>>>>) {
+    let mut writer = stream.try_clone().unwrap();
+    let mut reader = BufReader::new(stream);
+    loop {
+        let mut request_line = String::new();
+        if reader.read_line(&mut request_line).unwrap_or(0) == 0 {
+            return;
+        }
+        let mut parts = request_line.split_whitespace();
+        let method = parts.next().unwrap().to_string();
+        let target = parts.next().unwrap().to_string();
+        let mut headers = HashMap::new();
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            let (name, value) = line.split_once(':').unwrap();
+            headers.insert(name.to_lowercase(), value.trim().to_string());
+        }
+        let length: usize = headers
+            .get("content-length")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(0);
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).unwrap();
+        log.lock()
+            .unwrap()
+            .push((method.clone(), target.clone(), headers.clone()));
+
+        let (path, query) = target.split_once('?').unwrap_or((&target, ""));
+        let (status, extra, response) = if !headers
+            .get("authorization")
+            .is_some_and(|a| a.starts_with("AWS4-HMAC-SHA256 Credential=AKID/"))
+        {
+            (
+                403,
+                "",
+                b"<Error><Code>AccessDenied</Code></Error>".to_vec(),
+            )
+        } else if method == "PUT" {
+            objects.lock().unwrap().insert(path.to_string(), body);
+            (200, "ETag: \"abc123\"\r\n", Vec::new())
+        } else if query.contains("list-type=2") {
+            let mut keys: Vec<String> = objects
+                .lock()
+                .unwrap()
+                .iter()
+                .filter_map(|(k, v)| {
+                    k.strip_prefix(&format!("{}/", path)).map(|key| {
+                        format!(
+                            "<Contents><Key>{}</Key><Size>{}</Size></Contents>",
+                            key.replace("%20", " "),
+                            v.len()
+                        )
+                    })
+                })
+                .collect();
+            keys.sort();
+            let xml = format!(
+                "<ListBucketResult><IsTruncated>false</IsTruncated>{}</ListBucketResult>",
+                keys.join("")
+            );
+            (200, "", xml.into_bytes())
+        } else {
+            match objects.lock().unwrap().get(path) {
+                Some(data) => (200, "", data.clone()),
+                None => (
+                    404,
+                    "",
+                    b"<Error><Code>NoSuchKey</Code><Message>missing</Message></Error>".to_vec(),
+                ),
+            }
+        };
+        let head = format!(
+            "HTTP/1.1 {} X\r\nContent-Length: {}\r\n{}\r\n",
+            status,
+            response.len(),
+            extra
+        );
+        writer.write_all(head.as_bytes()).unwrap();
+        writer.write_all(&response).unwrap();
+    }
+}
+
+#[test]
+fn requires_host_credentials() {
+    set_s3_credentials(None);
+    let mut engine = Aether::with_all_permissions();
+    let err = engine.eval(r#"S3_GET("bucket", "a.txt")"#).unwrap_err();
+    assert!(err.contains("no S3 credentials"), "{}", err);
+
+    let mut sandboxed = Aether::new();
+    assert!(sandboxed.eval(r#"S3_GET("bucket", "a.txt")"#).is_err());
+}
+
+#[test]
+fn deterministic_mode_rejects_s3_builtins() {
+    let mut engine = Aether::with_all_permissions().with_deterministic(DeterministicConfig::new(1));
+    for code in [
+        r#"S3_GET("bucket", "a.txt")"#,
+        r#"S3_PUT("bucket", "a.txt", "data")"#,
+        r#"S3_LIST("bucket")"#,
+    ] {
+        let err = engine.eval(code).unwrap_err();
+        assert!(err.contains("Deterministic mode"), "{}: {}", code, err);
+    }
+    for name in ["S3_GET", "S3_PUT", "S3_LIST"] {
+        assert!(is_impure_builtin(name), "{}", name);
+    }
+}
+
+#[test]
+fn put_get_and_list_objects() {
+    let (endpoint, requests) = fake_s3();
+    set_s3_credentials(Some(
+        S3Credentials::new("eu-west-1", "AKID", "SECRET").with_endpoint(&endpoint),
+    ));
+    let mut engine = Aether::with_all_permissions();
+
+    let put = engine
+        .eval(r#"S3_PUT("reports", "2024/may report.md", "May 1", "text/markdown")"#)
+        .unwrap();
+    let Value::Dict(put) = put else {
+        panic!("expected Dict");
+    };
+    assert_eq!(put["etag"], Value::String("abc123".to_string()));
+    assert_eq!(put["size"], Value::Number(5.0));
+
+    assert_eq!(
+        engine
+            .eval(r#"S3_GET("reports", "2024/may report.md")"#)
+            .unwrap(),
+        Value::String("May 1".to_string())
+    );
+
+    let listed = engine.eval(r#"S3_LIST("reports", "2024/")"#).unwrap();
+    let Value::Array(listed) = listed else {
+        panic!("expected Array");
+    };
+    assert_eq!(listed.len(), 1);
+    let Value::Dict(object) = &listed[0] else {
+        panic!("expected Dict");
+    };
+    assert_eq!(
+        object["key"],
+        Value::String("2024/may report.md".to_string())
+    );
+
+    let err = engine.eval(r#"S3_GET("reports", "nope")"#).unwrap_err();
+    assert!(err.contains("404") && err.contains("NoSuchKey"), "{}", err);
+    set_s3_credentials(None);
+
+    let requests = requests.lock().unwrap();
+    let (method, target, headers) = &requests[0];
+    assert_eq!(method, "PUT");
+    assert_eq!(target, "/reports/2024/may%20report.md");
+    assert_eq!(headers["content-type"], "text/markdown");
+    assert!(headers["authorization"].contains("/eu-west-1/s3/aws4_request"));
+    assert!(headers.contains_key("x-amz-date"));
+    assert_eq!(requests[2].1, "/reports?list-type=2&prefix=2024%2F");
+}
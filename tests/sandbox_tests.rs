@@ -3,7 +3,7 @@
 //! 测试路径验证、沙箱配置和文件系统安全
 
 use aether::{
-    Aether, IOPermissions, PathRestriction, PathValidator, SandboxConfig, ScopedValidator,
+    Aether, IOPermissions, PathRestriction, PathValidator, SandboxConfig, ScopedValidator, Value,
 };
 use std::collections::HashSet;
 use std::fs;
@@ -210,6 +210,7 @@ fn test_path_validator_extension_whitelist() {
 }
 
 #[test]
+#[allow(clippy::unnecessary_unwrap)]
 fn test_no_validator_allows_anything() {
     // 不设置验证器时，IO 权限单独控制访问
 
@@ -249,3 +250,49 @@ fn test_no_validator_allows_anything() {
     // 清理
     let _ = fs::remove_file(&test_file);
 }
+
+#[test]
+fn test_with_sandbox_applies_permissions_and_limits() {
+    let mut config = SandboxConfig::dsl_safe();
+    config.execution_limits.max_steps = Some(100);
+    let mut engine = Aether::with_sandbox(&config);
+
+    assert!(engine.eval(r#"READ_FILE("Cargo.toml")"#).is_err());
+    assert!(
+        engine
+            .eval("Set I 0\nWhile (I < 100000) { Set I (I + 1) }")
+            .is_err()
+    );
+}
+
+#[test]
+fn test_set_permissions_on_live_engine() {
+    let mut engine = Aether::new();
+    engine.eval("Set X 42").unwrap();
+    assert!(engine.eval(r#"FILE_EXISTS("Cargo.toml")"#).is_err());
+
+    engine.set_permissions(IOPermissions {
+        filesystem_enabled: true,
+        network_enabled: false,
+    });
+    assert_eq!(
+        engine.eval(r#"FILE_EXISTS("Cargo.toml")"#).unwrap(),
+        Value::Boolean(true)
+    );
+    assert_eq!(engine.eval("X").unwrap(), Value::Number(42.0));
+
+    engine.set_permissions(IOPermissions::deny_all());
+    assert!(engine.eval(r#"FILE_EXISTS("Cargo.toml")"#).is_err());
+}
+
+#[test]
+#[cfg(feature = "io")]
+fn test_network_allowlist_rejects_other_hosts() {
+    let mut engine = Aether::with_all_permissions();
+    aether::sandbox::set_network_allowlist(Some(vec!["example.com".to_string()]));
+    let err = engine
+        .eval(r#"HTTP_GET("http://blocked.invalid/")"#)
+        .unwrap_err();
+    aether::sandbox::set_network_allowlist(None);
+    assert!(err.contains("not in the network allowlist"), "{}", err);
+}
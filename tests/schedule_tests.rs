@@ -0,0 +1,166 @@
+// tests/schedule_tests.rs
+//! Cron 风格定时调度测试
+
+use aether::builtins::schedule::CronSchedule;
+use aether::{Aether, Value};
+use chrono::{NaiveDate, NaiveDateTime};
+
+fn at(y: i32, m: u32, d: u32, h: u32, min: u32, s: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(y, m, d)
+        .unwrap()
+        .and_hms_opt(h, min, s)
+        .unwrap()
+}
+
+#[test]
+fn cron_next_after_steps_and_ranges() {
+    let every_five = CronSchedule::parse("*/5 * * * *").unwrap();
+    assert_eq!(
+        every_five.next_after(at(2024, 3, 1, 10, 2, 30)),
+        Some(at(2024, 3, 1, 10, 5, 0))
+    );
+    assert_eq!(
+        every_five.next_after(at(2024, 3, 1, 10, 55, 0)),
+        Some(at(2024, 3, 1, 11, 0, 0))
+    );
+
+    let weekdays = CronSchedule::parse("30 9 * * MON-FRI").unwrap();
+    // 2024-03-02 是周六，下一次是周一
+    assert_eq!(
+        weekdays.next_after(at(2024, 3, 2, 8, 0, 0)),
+        Some(at(2024, 3, 4, 9, 30, 0))
+    );
+
+    let seconds = CronSchedule::parse("*/10 * * * * *").unwrap();
+    assert_eq!(
+        seconds.next_after(at(2024, 3, 1, 0, 0, 5)),
+        Some(at(2024, 3, 1, 0, 0, 10))
+    );
+}
+
+#[test]
+fn cron_macros_and_sunday_alias() {
+    let monthly = CronSchedule::parse("@monthly").unwrap();
+    assert_eq!(
+        monthly.next_after(at(2024, 1, 31, 12, 0, 0)),
+        Some(at(2024, 2, 1, 0, 0, 0))
+    );
+    assert_eq!(
+        CronSchedule::parse("0 0 * * 7").unwrap(),
+        CronSchedule::parse("0 0 * * SUN").unwrap()
+    );
+    // 2 月 30 日永远不会到来
+    let never = CronSchedule::parse("0 0 30 2 *").unwrap();
+    assert_eq!(never.next_after(at(2024, 1, 1, 0, 0, 0)), None);
+}
+
+#[test]
+fn cron_rejects_invalid_expressions() {
+    assert!(CronSchedule::parse("* * * *").is_err());
+    assert!(CronSchedule::parse("60 * * * *").is_err());
+    assert!(CronSchedule::parse("*/0 * * * *").is_err());
+    assert!(CronSchedule::parse("5-1 * * * *").is_err());
+
+    let mut engine = Aether::new();
+    assert!(engine.eval(r#"SCHEDULE("bad", Lambda () -> 1)"#).is_err());
+    assert!(engine.eval(r#"SCHEDULE("* * * * *", 1)"#).is_err());
+}
+
+#[test]
+fn run_scheduler_executes_jobs_until_max_runs() {
+    let mut engine = Aether::new();
+    let result = engine
+        .eval(
+            r#"
+            Func TICK() {
+                Return 1
+            }
+            SCHEDULE("* * * * * *", TICK)
+            Set SUMMARY RUN_SCHEDULER({"max_runs": 2})
+            [SUMMARY["runs"], SUMMARY["errors"]]
+            "#,
+        )
+        .unwrap();
+    assert_eq!(
+        result,
+        Value::Array(vec![Value::Number(2.0), Value::Number(0.0)])
+    );
+    assert!(!engine.has_pending_schedule());
+}
+
+#[test]
+fn failing_jobs_are_counted_and_unschedule_stops_scheduler() {
+    let mut engine = Aether::new();
+    let result = engine
+        .eval(
+            r#"
+            Func FAIL() {
+                UNSCHEDULE(JOB)
+                Return 1 / 0
+            }
+            Set JOB SCHEDULE("* * * * * *", FAIL)
+            Set SUMMARY RUN_SCHEDULER()
+            [SUMMARY["runs"], SUMMARY["errors"], UNSCHEDULE(JOB)]
+            "#,
+        )
+        .unwrap();
+    assert_eq!(
+        result,
+        Value::Array(vec![
+            Value::Number(1.0),
+            Value::Number(1.0),
+            Value::Boolean(false)
+        ])
+    );
+}
+
+#[test]
+fn pending_schedule_is_driven_by_host() {
+    let mut engine = Aether::new();
+    engine
+        .eval(
+            r#"
+            Func ONCE() {
+                UNSCHEDULE(JOB)
+            }
+            Set JOB SCHEDULE("* * * * * *", ONCE)
+            "#,
+        )
+        .unwrap();
+    assert!(engine.has_pending_schedule());
+
+    let summary = engine.run_scheduler().unwrap();
+    assert!(!engine.has_pending_schedule());
+    match summary {
+        Value::Dict(dict) => assert_eq!(dict.get("runs"), Some(&Value::Number(1.0))),
+        other => panic!("expected summary dict, got {:?}", other),
+    }
+}
+
+#[test]
+fn failing_jobs_are_returned_in_summary() {
+    let mut engine = Aether::new();
+    let result = engine
+        .eval(
+            r#"
+            Func FAIL() {
+                UNSCHEDULE(JOB)
+                Throw("token " + SECRET_VALUE)
+            }
+            Set SECRET_VALUE MARK_SECRET("hunter2")
+            Set JOB SCHEDULE("* * * * * *", FAIL)
+            Set FAILURES RUN_SCHEDULER()["failures"]
+            [LEN(FAILURES), FAILURES[0]["id"], FAILURES[0]["expr"], FAILURES[0]["error"]]
+            "#,
+        )
+        .unwrap();
+    let Value::Array(items) = result else {
+        panic!("expected array, got {:?}", result);
+    };
+    assert_eq!(items[0], Value::Number(1.0));
+    assert_eq!(items[1], Value::Number(1.0));
+    assert_eq!(items[2], Value::String("* * * * * *".to_string()));
+    let error = items[3].to_string();
+    assert!(error.contains("token"), "{}", error);
+    assert!(!error.contains("hunter2"), "{}", error);
+}
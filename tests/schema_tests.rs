@@ -0,0 +1,152 @@
+// tests/schema_tests.rs
+//! VALIDATE 模式校验测试
+
+use aether::{Aether, PathSegment, Value, validate_schema};
+
+/// 运行脚本，返回每条错误的 "路径 关键字"
+fn violations(code: &str) -> Vec<String> {
+    match Aether::new().eval(code).unwrap() {
+        Value::Array(errors) => errors
+            .iter()
+            .map(|error| {
+                let Value::Dict(dict) = error else {
+                    panic!("expected Dict, got {:?}", error)
+                };
+                format!("{} {}", dict["path"], dict["keyword"])
+            })
+            .collect(),
+        other => panic!("expected Array, got {:?}", other),
+    }
+}
+
+#[test]
+fn valid_value_has_no_errors() {
+    let errors = violations(
+        r#"
+        Set SCHEMA {
+            "type": "object",
+            "required": ["name", "age"],
+            "properties": {
+                "name": {"type": "string", "minLength": 1, "maxLength": 10},
+                "age": {"type": "integer", "minimum": 0, "maximum": 150},
+                "email": {"type": "string", "format": "email"},
+                "role": {"enum": ["admin", "user"]}
+            },
+            "additionalProperties": False
+        }
+        VALIDATE({"name": "张三", "age": 30, "email": "a@example.com", "role": "user"}, SCHEMA)
+        "#,
+    );
+    assert!(errors.is_empty(), "{:?}", errors);
+}
+
+// 使用 pattern 关键字，需要 regex 特性
+#[cfg(feature = "regex")]
+#[test]
+fn collects_all_errors_with_paths() {
+    let errors = violations(
+        r#"
+        Set SCHEMA {
+            "type": "object",
+            "required": ["name", "age"],
+            "properties": {
+                "name": {"type": "string", "minLength": 1},
+                "score": {"type": "number", "exclusiveMaximum": 100, "multipleOf": 0.5},
+                "tags": {
+                    "type": "array",
+                    "maxItems": 3,
+                    "uniqueItems": True,
+                    "items": {"type": "string", "pattern": "^[a-z]+$"}
+                }
+            },
+            "additionalProperties": False
+        }
+        VALIDATE({"name": "", "score": 100.25, "tags": ["ok", "Bad", "ok", 1], "x": 1}, SCHEMA)
+        "#,
+    );
+    assert_eq!(
+        errors,
+        vec![
+            "$ required",
+            "$.name minLength",
+            "$.score exclusiveMaximum",
+            "$.score multipleOf",
+            "$.tags maxItems",
+            "$.tags[2] uniqueItems",
+            "$.tags[1] pattern",
+            "$.tags[3] type",
+            "$.x additionalProperties",
+        ]
+    );
+}
+
+#[test]
+fn nested_arrays_report_element_paths() {
+    let errors = violations(
+        r#"
+        Set MATRIX {"type": "array", "items": {"type": "array", "minItems": 2, "items": {"type": "number"}}}
+        VALIDATE([[1, 2], [3], [4, "x"]], MATRIX)
+        "#,
+    );
+    assert_eq!(errors, vec!["$[1] minItems", "$[2][1] type"]);
+}
+
+// 使用 pattern 关键字，需要 regex 特性
+#[cfg(feature = "regex")]
+#[test]
+fn combinators_report_single_error() {
+    let mut engine = Aether::new();
+    engine
+        .eval(r#"Set ID {"anyOf": [{"type": "integer", "minimum": 1}, {"type": "string", "pattern": "^ID-"}]}"#)
+        .unwrap();
+    assert_eq!(
+        engine.eval("LEN(VALIDATE(5, ID))").unwrap(),
+        Value::Number(0.0)
+    );
+    assert_eq!(
+        engine.eval(r#"LEN(VALIDATE("ID-7", ID))"#).unwrap(),
+        Value::Number(0.0)
+    );
+    assert_eq!(
+        engine.eval("LEN(VALIDATE(0, ID))").unwrap(),
+        Value::Number(1.0)
+    );
+    assert_eq!(
+        engine
+            .eval(r#"LEN(VALIDATE(Null, {"type": ["null", "string"]}))"#)
+            .unwrap(),
+        Value::Number(0.0)
+    );
+    assert_eq!(
+        engine
+            .eval(r#"LEN(VALIDATE({"a": 1}, {"const": {"a": 1}}))"#)
+            .unwrap(),
+        Value::Number(0.0)
+    );
+}
+
+#[test]
+fn invalid_schema_is_an_error() {
+    let mut engine = Aether::new();
+    assert!(engine.eval(r#"VALIDATE(1, {"type": "float"})"#).is_err());
+    assert!(engine.eval(r#"VALIDATE("a", {"pattern": "("})"#).is_err());
+    assert!(engine.eval(r#"VALIDATE([], {"minItems": -1})"#).is_err());
+    assert!(engine.eval("VALIDATE(1, 2)").is_err());
+}
+
+#[test]
+fn rust_api() {
+    let schema = Value::Dict(
+        [(
+            "items".to_string(),
+            Value::Dict([("minimum".to_string(), Value::Number(0.0))].into()),
+        )]
+        .into(),
+    );
+    let value = Value::Array(vec![Value::Number(1.0), Value::Number(-1.0)]);
+    let errors = validate_schema(&value, &schema).unwrap();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].path, vec![PathSegment::Index(1)]);
+    assert_eq!(errors[0].keyword, "minimum");
+    assert_eq!(errors[0].path_string(), "$[1]");
+}
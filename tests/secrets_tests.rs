@@ -0,0 +1,132 @@
+// tests/secrets_tests.rs
+//! SECRET 机密提供者测试：宿主回调、环境变量显式开启和 TRACE 脱敏
+
+use aether::analysis::is_impure_builtin;
+use aether::{Aether, DeterministicConfig, Value};
+
+fn engine_with_secrets() -> Aether {
+    let mut engine = Aether::new();
+    engine.set_secrets_provider(|name| match name {
+        "api_key" => Some("tok-123".to_string()),
+        _ => None,
+    });
+    engine
+}
+
+#[test]
+fn secrets_resolve_through_host_provider() {
+    let mut engine = engine_with_secrets();
+    assert_eq!(
+        engine.eval(r#"SECRET("api_key")"#).unwrap(),
+        Value::String("tok-123".to_string())
+    );
+    let err = engine.eval(r#"SECRET("missing")"#).unwrap_err();
+    assert!(err.contains("unknown secret 'missing'"), "{}", err);
+    assert!(engine.eval("SECRET(1)").is_err());
+
+    engine.clear_secrets_provider();
+    let err = engine.eval(r#"SECRET("api_key")"#).unwrap_err();
+    assert!(err.contains("no secrets provider"), "{}", err);
+}
+
+#[test]
+fn environment_is_not_read_by_default() {
+    // SAFETY: 测试使用独有的变量名
+    unsafe { std::env::set_var("AETHER_TEST_SECRET_DB_PASSWORD", "hunter2") };
+    let mut engine = Aether::new();
+    assert!(engine.eval(r#"SECRET("db_password")"#).is_err());
+
+    engine.use_env_secrets("AETHER_TEST_SECRET_");
+    assert_eq!(
+        engine.eval(r#"SECRET("db_password")"#).unwrap(),
+        Value::String("hunter2".to_string())
+    );
+}
+
+#[test]
+fn secrets_are_redacted_in_trace_output() {
+    let mut engine = engine_with_secrets();
+    engine
+        .eval(
+            r#"
+            Set KEY SECRET("api_key")
+            TRACE("auth", "Bearer " + KEY)
+            TRACE_INFO("http", {"token": KEY, "retries": 3})
+        "#,
+        )
+        .unwrap();
+
+    let records = engine.trace_records();
+    let Value::Dict(payload) = &records[0].values[0] else {
+        panic!("expected Dict payload");
+    };
+    assert_eq!(payload["token"], Value::String("***".to_string()));
+    assert_eq!(payload["retries"], Value::Number(3.0));
+
+    let trace = engine.take_trace();
+    assert_eq!(trace[0], "#1 [auth] Bearer ***");
+    assert!(trace.iter().all(|line| !line.contains("tok-123")));
+}
+
+#[test]
+fn marked_values_are_redacted_in_errors_and_output() {
+    let mut engine = Aether::new();
+    let err = engine
+        .eval(
+            r#"
+            Set PASSWORD MARK_SECRET("pw-999")
+            TRACE("login", PASSWORD)
+            TO_NUMBER(PASSWORD)
+        "#,
+        )
+        .unwrap_err();
+    assert!(!err.contains("pw-999"), "{}", err);
+    assert!(err.contains("***"), "{}", err);
+    assert_eq!(engine.take_trace(), vec!["#1 [login] ***"]);
+
+    // MARK_SECRET 返回原值，计算不受影响
+    assert_eq!(
+        engine
+            .eval(r#"LEN(MARK_SECRET({"salary": 8500, "note": "x"}))"#)
+            .unwrap(),
+        Value::Number(2.0)
+    );
+    let report = engine.eval_report("TO_NUMBER(\"8500!\")").unwrap_err();
+    assert!(!report.message.contains("8500"), "{}", report.message);
+}
+
+#[test]
+#[cfg(feature = "kernel")]
+fn print_and_display_capture_is_redacted() {
+    use aether::kernel::KernelSession;
+    use serde_json::json;
+
+    let mut session = KernelSession::new(engine_with_secrets());
+    let handled = session
+        .handle(
+            "execute_request",
+            &json!({ "code": "Set K SECRET(\"api_key\")\nPRINTLN(\"key=\" + K)\nDISPLAY([K])" }),
+        )
+        .unwrap();
+    let text = serde_json::to_string(&handled.broadcasts).unwrap();
+    assert!(!text.contains("tok-123"), "{}", text);
+    assert!(text.contains("key=***"), "{}", text);
+}
+
+#[test]
+fn secrets_are_impure_and_forbidden_in_deterministic_mode() {
+    let mut engine = engine_with_secrets().with_deterministic(DeterministicConfig::new(1));
+    for name in ["SECRET", "MARK_SECRET"] {
+        assert!(is_impure_builtin(name), "{}", name);
+        let meta = engine
+            .builtin_metadata()
+            .into_iter()
+            .find(|meta| meta.name == name)
+            .unwrap();
+        assert!(meta.io && !meta.pure, "{}", name);
+    }
+    for code in [r#"SECRET("api_key")"#, r#"MARK_SECRET("tok-123")"#] {
+        let err = engine.eval(code).unwrap_err();
+        assert!(err.contains("Deterministic mode"), "{}: {}", code, err);
+    }
+}
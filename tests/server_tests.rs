@@ -0,0 +1,201 @@
+// tests/server_tests.rs
+//! `aether serve` 服务测试（需要 http-server 特性）
+#![cfg(feature = "http-server")]
+
+use aether::SandboxConfig;
+use aether::server::{Server, ServerConfig};
+use serde_json::Value as JsonValue;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+fn server(sandbox: SandboxConfig) -> Server {
+    Server::bind(ServerConfig {
+        listen: "127.0.0.1:0".to_string(),
+        pool_size: 2,
+        sandbox,
+        ..ServerConfig::default()
+    })
+    .unwrap()
+}
+
+#[test]
+fn health_reports_version() {
+    let mut server = server(SandboxConfig::dsl_safe());
+    let (status, body) = server.handle("GET", "/health", "");
+    assert_eq!(status, 200);
+    assert_eq!(body["status"], "ok");
+    assert_eq!(body["version"], env!("CARGO_PKG_VERSION"));
+}
+
+#[test]
+fn eval_binds_inputs_and_returns_json() {
+    let mut server = server(SandboxConfig::dsl_safe());
+    let (status, body) = server.handle(
+        "POST",
+        "/eval",
+        r#"{"code": "{\"total\": SUM(ITEMS), \"who\": USER}", "inputs": {"ITEMS": [1, 2, 3], "USER": "ada"}}"#,
+    );
+    assert_eq!(status, 200, "{}", body);
+    assert_eq!(body["ok"], true);
+    assert_eq!(body["result"]["total"], 6.0);
+    assert_eq!(body["result"]["who"], "ada");
+
+    // 每次请求都在干净的环境中执行
+    let (status, body) = server.handle("POST", "/eval", r#"{"code": "USER"}"#);
+    assert_eq!(status, 422);
+    assert_eq!(body["error"]["kind"], "EvalError");
+}
+
+#[test]
+fn sandbox_policy_applies_to_pooled_engines() {
+    let mut server = server(SandboxConfig::dsl_safe());
+    let (status, body) = server.handle(
+        "POST",
+        "/eval",
+        r#"{"code": "READ_FILE(\"/etc/hostname\")"}"#,
+    );
+    assert_eq!(status, 422);
+    assert_eq!(body["ok"], false);
+
+    // dsl_safe 使用严格的执行限制
+    let (status, _) = server.handle("POST", "/eval", r#"{"code": "While (True) { Set X 1 }"}"#);
+    assert_eq!(status, 422);
+}
+
+#[test]
+fn bad_requests_are_rejected() {
+    let mut server = server(SandboxConfig::dsl_safe());
+    assert_eq!(server.handle("POST", "/eval", "not json").0, 400);
+    assert_eq!(server.handle("POST", "/eval", r#"{"code": 1}"#).0, 400);
+    assert_eq!(
+        server
+            .handle("POST", "/eval", r#"{"code": "1", "inputs": [1]}"#)
+            .0,
+        400
+    );
+    assert_eq!(server.handle("GET", "/eval", "").0, 405);
+    assert_eq!(server.handle("GET", "/nope", "").0, 404);
+}
+
+#[test]
+fn compile_reports_requirements_without_running() {
+    let mut server = server(SandboxConfig::dsl_safe());
+    let (status, body) = server.handle(
+        "POST",
+        "/compile",
+        r#"{"code": "Set PAGE HTTP_GET(\"https://example.com\")\nLEN(PAGE)"}"#,
+    );
+    assert_eq!(status, 200, "{}", body);
+    assert_eq!(body["statements"], 2);
+    assert_eq!(body["requirements"]["permissions"][0], "network");
+
+    let (status, body) = server.handle("POST", "/compile", r#"{"code": "Set X ("}"#);
+    assert_eq!(status, 422);
+    assert_eq!(body["error"]["kind"], "ParseError");
+}
+
+#[test]
+fn metrics_count_evaluations() {
+    let mut server = server(SandboxConfig::dsl_safe());
+    server.handle("POST", "/eval", r#"{"code": "(1 + 1)"}"#);
+    server.handle("POST", "/eval", r#"{"code": "UNDEFINED_NAME"}"#);
+
+    let (status, body) = server.handle("GET", "/metrics", "");
+    assert_eq!(status, 200);
+    assert_eq!(body["execution"]["count"], 2);
+    assert_eq!(body["execution"]["errors"], 1);
+    assert_eq!(body["pool"]["capacity"], 2);
+}
+
+#[test]
+fn serves_requests_over_http() {
+    let mut server = Server::bind(ServerConfig {
+        listen: "127.0.0.1:0".to_string(),
+        max_requests: Some(1),
+        ..ServerConfig::default()
+    })
+    .unwrap();
+    let port = server.local_addr().unwrap().port();
+    // Server 持有非 Send 的引擎，在当前线程运行，客户端放到子线程
+    let client = thread::spawn(move || {
+        let body = r#"{"code": "(20 + 22)"}"#;
+        let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+        write!(
+            stream,
+            "POST /eval HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    });
+    server.run();
+    assert_eq!(server.requests(), 1);
+
+    let response = client.join().unwrap();
+    assert!(response.starts_with("HTTP/1.1 200 OK"), "{}", response);
+    assert!(response.contains("Content-Type: application/json"));
+    let json: JsonValue = serde_json::from_str(response.split("\r\n\r\n").nth(1).unwrap()).unwrap();
+    assert_eq!(json["result"], 42.0);
+}
+
+fn post_eval(port: u16, code: &str) -> String {
+    let body = format!(r#"{{"code": "{}"}}"#, code);
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    write!(
+        stream,
+        "POST /eval HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{}",
+        body.len(),
+        body
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    response
+}
+
+#[test]
+fn slow_connections_do_not_block_other_requests() {
+    let mut server = Server::bind(ServerConfig {
+        listen: "127.0.0.1:0".to_string(),
+        pool_size: 2,
+        max_requests: Some(2),
+        ..ServerConfig::default()
+    })
+    .unwrap();
+    let port = server.local_addr().unwrap().port();
+    let (done, finished) = mpsc::channel();
+
+    // 第一个连接只发送一半请求，等第二个请求完成后才发送剩余部分
+    let slow = thread::spawn(move || {
+        let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+        let body = r#"{"code": "1"}"#;
+        write!(stream, "POST /eval HTTP/1.1\r\nHost: localhost\r\n").unwrap();
+        let fast_response = finished.recv_timeout(Duration::from_secs(5)).ok();
+        write!(stream, "Content-Length: {}\r\n\r\n{}", body.len(), body).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        (fast_response, response)
+    });
+    let fast = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(100));
+        done.send(post_eval(port, "(20 + 22)")).unwrap();
+    });
+    server.run();
+    fast.join().unwrap();
+    assert_eq!(server.requests(), 2);
+
+    let (fast_response, slow_response) = slow.join().unwrap();
+    let fast_response = fast_response.expect("second request was blocked by the first");
+    assert!(fast_response.contains("42"), "{}", fast_response);
+    assert!(
+        slow_response.starts_with("HTTP/1.1 200 OK"),
+        "{}",
+        slow_response
+    );
+}
@@ -0,0 +1,133 @@
+// tests/set_tests.rs
+//! 原生集合类型（Value::Set）测试
+
+use aether::{Aether, Value};
+
+fn eval(code: &str) -> Value {
+    Aether::new().eval(code).unwrap()
+}
+
+#[test]
+fn set_constructor_deduplicates() {
+    assert_eq!(eval("LEN(SET([1, 2, 2, 3, 3, 3]))"), Value::Number(3.0));
+    assert_eq!(eval(r#"LEN(SET("a", "b", "a"))"#), Value::Number(2.0));
+    assert_eq!(eval("LEN(SET())"), Value::Number(0.0));
+    assert_eq!(eval("TYPE(SET())"), Value::String("Set".to_string()));
+    assert_eq!(
+        eval("TO_STRING(SET([3, 1, 2]))"),
+        Value::String("Set{1, 2, 3}".to_string())
+    );
+}
+
+#[test]
+fn set_membership() {
+    assert_eq!(eval("HAS(SET([1, 2, 3]), 2)"), Value::Boolean(true));
+    assert_eq!(eval("HAS(SET([1, 2, 3]), 4)"), Value::Boolean(false));
+    assert_eq!(eval(r#"CONTAINS(SET(["x"]), "x")"#), Value::Boolean(true));
+    // 数组等不可哈希的值永远不是成员
+    assert_eq!(eval("HAS(SET([1]), [1])"), Value::Boolean(false));
+}
+
+#[test]
+fn set_operations() {
+    assert_eq!(
+        eval("UNION(SET([1, 2]), SET([2, 3]), [4]) == SET([1, 2, 3, 4])"),
+        Value::Boolean(true)
+    );
+    assert_eq!(
+        eval("INTERSECT(SET([1, 2, 3]), SET([2, 3, 4])) == SET([2, 3])"),
+        Value::Boolean(true)
+    );
+    assert_eq!(
+        eval("DIFFERENCE(SET([1, 2, 3]), SET([2])) == SET([1, 3])"),
+        Value::Boolean(true)
+    );
+    assert_eq!(
+        eval("SUBSET(SET([1, 2]), SET([1, 2, 3]))"),
+        Value::Boolean(true)
+    );
+    assert_eq!(
+        eval("SUBSET(SET([1, 4]), SET([1, 2, 3]))"),
+        Value::Boolean(false)
+    );
+}
+
+#[test]
+fn set_iteration_is_sorted() {
+    let code = r#"
+Set TOTAL []
+For X In SET([3, 1, 2]) {
+    Set TOTAL PUSH(TOTAL, X)
+}
+TOTAL
+"#;
+    assert_eq!(
+        eval(code),
+        Value::Array(vec![
+            Value::Number(1.0),
+            Value::Number(2.0),
+            Value::Number(3.0)
+        ])
+    );
+    assert_eq!(
+        eval("JSON_STRINGIFY(SET([2, 1]))"),
+        Value::String("[1.0,2.0]".to_string())
+    );
+}
+
+#[test]
+fn set_rejects_unhashable_elements() {
+    let mut engine = Aether::new();
+    assert!(engine.eval("SET([[1, 2]])").is_err());
+    assert!(engine.eval(r#"SET({"a": 1}, 2)"#).is_err());
+}
+
+#[test]
+fn stdlib_set_module_uses_native_sets() {
+    let mut engine = Aether::new().with_stdlib_set().unwrap();
+    let result = engine
+        .eval(
+            r#"
+Set S SET_FROM_ARRAY([1, 2, 2, 3])
+Set S SET_ADD(S, 4)
+Set S SET_REMOVE(S, 1)
+SET_TO_STRING(S)
+"#,
+        )
+        .unwrap();
+    assert_eq!(result, Value::String("{2, 3, 4}".to_string()));
+    assert_eq!(
+        engine.eval("SET_IS_DISJOINT(SET([1]), SET([2]))").unwrap(),
+        Value::Boolean(true)
+    );
+}
+
+#[test]
+fn numeric_members_compare_by_value() {
+    assert_eq!(eval("LEN(SET([2, TO_FRACTION(2)]))"), Value::Number(1.0));
+    assert_eq!(eval("HAS(SET([2]), TO_FRACTION(2))"), Value::Boolean(true));
+    assert_eq!(eval("HAS(SET([TO_FRACTION(2)]), 2)"), Value::Boolean(true));
+    assert_eq!(
+        eval("HAS(SET([0.5]), TO_FRACTION(0.5))"),
+        Value::Boolean(true)
+    );
+    assert_eq!(
+        eval("LEN(DIFFERENCE(SET([1, 2, 3]), SET([TO_FRACTION(2)])))"),
+        Value::Number(2.0)
+    );
+}
+
+#[test]
+fn stdlib_set_module_accepts_arrays_and_sets() {
+    let mut engine = Aether::new().with_stdlib_set().unwrap();
+    for code in [
+        "SET_CONTAINS([1, 2, 3], 2)",
+        "SET_CONTAINS(SET([1, 2, 3]), 2)",
+        "SET_SIZE([1, 1, 2]) == 2",
+        "SET_SIZE(SET([1, 1, 2])) == 2",
+        "SET_IS_EMPTY(SET())",
+        "SET_TO_ARRAY([3, 1, 1]) == [1, 3]",
+    ] {
+        assert_eq!(engine.eval(code).unwrap(), Value::Boolean(true), "{}", code);
+    }
+}
@@ -0,0 +1,175 @@
+#![cfg(feature = "signing")]
+
+use aether::signing::{SignatureError, TrustLevel, TrustPolicy, sha256_hex, sign_hmac};
+use aether::{Aether, IOPermissions, Value};
+use ring::signature::{Ed25519KeyPair, KeyPair};
+
+const SCRIPT: &str = "Set X 20\n(X + 1)";
+
+#[test]
+fn test_approved_hash_maps_to_trust_level() {
+    let mut policy = TrustPolicy::new();
+    policy
+        .approve_hash(&sha256_hex(SCRIPT), TrustLevel::Standard)
+        .unwrap();
+
+    let signature = format!("sha256:{}", sha256_hex(SCRIPT));
+    assert_eq!(
+        policy.verify(SCRIPT, Some(&signature)).unwrap(),
+        TrustLevel::Standard
+    );
+
+    let other = "Set X 1";
+    let other_signature = format!("sha256:{}", sha256_hex(other));
+    assert_eq!(
+        policy.verify(other, Some(&other_signature)),
+        Err(SignatureError::UnknownHash)
+    );
+}
+
+#[test]
+fn test_hmac_signature_rejects_tampered_script() {
+    let mut policy = TrustPolicy::new();
+    policy.add_hmac_key("ops", b"shared-secret", TrustLevel::Privileged);
+
+    let signature = sign_hmac("ops", b"shared-secret", SCRIPT);
+    assert_eq!(
+        policy.verify(SCRIPT, Some(&signature)).unwrap(),
+        TrustLevel::Privileged
+    );
+
+    let tampered = "Set X 20\n(X + 2)";
+    assert_eq!(
+        policy.verify(tampered, Some(&signature)),
+        Err(SignatureError::Mismatch)
+    );
+
+    let forged = sign_hmac("ops", b"wrong-secret", SCRIPT);
+    assert_eq!(
+        policy.verify(SCRIPT, Some(&forged)),
+        Err(SignatureError::Mismatch)
+    );
+}
+
+#[test]
+fn test_ed25519_signature() {
+    let rng = ring::rand::SystemRandom::new();
+    let pkcs8 = Ed25519KeyPair::generate_pkcs8(&rng).unwrap();
+    let key_pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+
+    let mut policy = TrustPolicy::new();
+    policy.add_ed25519_key(
+        "release",
+        key_pair.public_key().as_ref(),
+        TrustLevel::Standard,
+    );
+
+    let hex: String = key_pair
+        .sign(SCRIPT.as_bytes())
+        .as_ref()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    let signature = format!("ed25519:release:{}", hex);
+
+    assert_eq!(
+        policy.verify(SCRIPT, Some(&signature)).unwrap(),
+        TrustLevel::Standard
+    );
+    assert_eq!(
+        policy.verify("Set X 0", Some(&signature)),
+        Err(SignatureError::Mismatch)
+    );
+}
+
+#[test]
+fn test_unknown_key_and_malformed_signature() {
+    let policy = TrustPolicy::new();
+    let signature = sign_hmac("nobody", b"secret", SCRIPT);
+    assert_eq!(
+        policy.verify(SCRIPT, Some(&signature)),
+        Err(SignatureError::UnknownKey("nobody".to_string()))
+    );
+    assert!(matches!(
+        policy.verify(SCRIPT, Some("md5:abc")),
+        Err(SignatureError::Malformed(_))
+    ));
+    assert!(matches!(
+        policy.verify(SCRIPT, Some("sha256:zz")),
+        Err(SignatureError::Malformed(_))
+    ));
+}
+
+#[test]
+fn test_unsigned_scripts() {
+    let policy = TrustPolicy::new();
+    assert_eq!(policy.verify(SCRIPT, None).unwrap(), TrustLevel::Untrusted);
+
+    let strict = TrustPolicy::new().require_signature();
+    assert_eq!(strict.verify(SCRIPT, None), Err(SignatureError::Unsigned));
+}
+
+#[test]
+fn test_permission_presets() {
+    let mut policy = TrustPolicy::new();
+    assert_eq!(
+        policy.permissions_for(TrustLevel::Untrusted),
+        IOPermissions::deny_all()
+    );
+    let standard = policy.permissions_for(TrustLevel::Standard);
+    assert!(standard.filesystem_enabled && !standard.network_enabled);
+    assert_eq!(
+        policy.permissions_for(TrustLevel::Privileged),
+        IOPermissions::allow_all()
+    );
+
+    policy.set_permissions(TrustLevel::Standard, IOPermissions::deny_all());
+    assert_eq!(
+        policy.permissions_for(TrustLevel::Standard),
+        IOPermissions::deny_all()
+    );
+}
+
+#[test]
+fn test_eval_signed_runs_verified_script() {
+    let mut policy = TrustPolicy::new();
+    policy.add_hmac_key("ops", b"secret", TrustLevel::Standard);
+    let signature = sign_hmac("ops", b"secret", SCRIPT);
+
+    let mut engine = Aether::with_trust_level(&policy, TrustLevel::Standard);
+    assert_eq!(
+        engine
+            .eval_signed(SCRIPT, Some(&signature), &policy)
+            .unwrap(),
+        Value::Number(21.0)
+    );
+
+    let err = engine
+        .eval_signed("Set X 1\n(X + 1)", Some(&signature), &policy)
+        .unwrap_err();
+    assert!(err.contains("does not match"), "{}", err);
+}
+
+#[test]
+fn test_eval_signed_refuses_over_privileged_engine() {
+    let mut policy = TrustPolicy::new();
+    policy.add_hmac_key("ops", b"secret", TrustLevel::Standard);
+    let signature = sign_hmac("ops", b"secret", SCRIPT);
+
+    // Standard 级别不允许网络
+    let mut engine = Aether::with_all_permissions();
+    let err = engine
+        .eval_signed(SCRIPT, Some(&signature), &policy)
+        .unwrap_err();
+    assert!(err.contains("network"), "{}", err);
+
+    // 未签名的脚本只能在无 IO 权限的引擎中运行
+    let mut sandboxed = Aether::new();
+    assert_eq!(
+        sandboxed.eval_signed(SCRIPT, None, &policy).unwrap(),
+        Value::Number(21.0)
+    );
+    let mut fs_engine = Aether::with_trust_level(&policy, TrustLevel::Standard);
+    let err = fs_engine.eval_signed(SCRIPT, None, &policy).unwrap_err();
+    assert!(err.contains("filesystem"), "{}", err);
+}
@@ -0,0 +1,129 @@
+// tests/snapshot_tests.rs
+//! 快照测试（SNAPSHOT_MATCH / EXPECT_SNAPSHOT）
+
+use aether::{Aether, SnapshotStats, Value};
+use std::path::PathBuf;
+
+fn snapshot_dir(name: &str) -> PathBuf {
+    let dir =
+        std::env::temp_dir().join(format!("aether_snapshots_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+fn status(engine: &mut Aether, code: &str) -> String {
+    match engine.eval(code).unwrap() {
+        Value::Dict(result) => result["status"].to_string(),
+        other => panic!("expected Dict, got {:?}", other),
+    }
+}
+
+#[test]
+fn snapshot_is_created_then_matched() {
+    let dir = snapshot_dir("create");
+    let mut engine = Aether::new();
+    engine.set_snapshot_dir(&dir);
+
+    let code = r#"SNAPSHOT_MATCH("report", {"b": [1, 2.5], "a": 1 / 3, "c": Null})"#;
+    assert_eq!(status(&mut engine, code), "created");
+    assert_eq!(status(&mut engine, code), "matched");
+
+    // 规范化：键排序、整数不带小数、分数带标记
+    let stored = std::fs::read_to_string(dir.join("report.snap.json")).unwrap();
+    assert!(stored.find("\"a\"").unwrap() < stored.find("\"b\"").unwrap());
+    assert!(stored.contains("    1,\n"), "{}", stored);
+    assert!(stored.ends_with("}\n"));
+
+    assert_eq!(
+        engine.snapshot_stats(),
+        SnapshotStats {
+            matched: 1,
+            created: 1,
+            updated: 0,
+            failed: 0
+        }
+    );
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn mismatch_reports_expected_value_and_update_overwrites() {
+    let dir = snapshot_dir("mismatch");
+    let mut engine = Aether::new();
+    engine.set_snapshot_dir(&dir);
+    engine.eval(r#"SNAPSHOT_MATCH("data", [1, 2])"#).unwrap();
+
+    let result = engine.eval(r#"SNAPSHOT_MATCH("data", [1, 3])"#).unwrap();
+    let Value::Dict(result) = result else {
+        panic!("expected Dict")
+    };
+    assert_eq!(result["status"], Value::String("mismatch".to_string()));
+    assert_eq!(
+        result["expected"],
+        Value::Array(vec![Value::Number(1.0), Value::Number(2.0)])
+    );
+    assert_eq!(engine.snapshot_stats().failed, 1);
+
+    engine.set_update_snapshots(true);
+    assert_eq!(
+        status(&mut engine, r#"SNAPSHOT_MATCH("data", [1, 3])"#),
+        "updated"
+    );
+    engine.set_update_snapshots(false);
+    assert_eq!(
+        status(&mut engine, r#"SNAPSHOT_MATCH("data", [1, 3])"#),
+        "matched"
+    );
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn expect_snapshot_uses_testing_framework() {
+    let dir = snapshot_dir("expect");
+    let mut engine = Aether::new();
+    engine.load_stdlib_module("testing").unwrap();
+    engine.set_snapshot_dir(&dir);
+
+    assert_eq!(
+        engine
+            .eval(r#"EXPECT_SNAPSHOT("user", {"name": "alice", "tags": ["a"]})"#)
+            .unwrap(),
+        Value::Boolean(true)
+    );
+    assert_eq!(
+        engine
+            .eval(r#"EXPECT_SNAPSHOT("user", {"name": "bob", "tags": ["a"]})"#)
+            .unwrap(),
+        Value::Boolean(false)
+    );
+    assert_eq!(
+        engine
+            .eval(r#"EXPECT_SNAPSHOT("user", "changed type")"#)
+            .unwrap(),
+        Value::Boolean(false)
+    );
+    assert_eq!(engine.snapshot_stats().failed, 2);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn snapshots_require_a_configured_directory_and_safe_names() {
+    let mut engine = Aether::new();
+    let err = engine.eval(r#"SNAPSHOT_MATCH("x", 1)"#).unwrap_err();
+    assert!(err.contains("no snapshot directory configured"), "{}", err);
+
+    let dir = snapshot_dir("names");
+    engine.set_snapshot_dir(&dir);
+    assert!(engine.eval(r#"SNAPSHOT_MATCH("../escape", 1)"#).is_err());
+    assert!(engine.eval(r#"SNAPSHOT_MATCH("", 1)"#).is_err());
+    assert!(
+        engine
+            .eval(r#"SNAPSHOT_MATCH("f", Lambda X -> X)"#)
+            .is_err()
+    );
+    assert!(!dir.join("../escape.snap.json").exists());
+
+    engine.clear_snapshot_dir();
+    assert!(engine.eval(r#"SNAPSHOT_MATCH("x", 1)"#).is_err());
+    assert_eq!(engine.snapshot_stats(), SnapshotStats::default());
+}
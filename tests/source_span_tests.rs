@@ -0,0 +1,125 @@
+// tests/source_span_tests.rs
+//! 源码位置测试：语句的 span、运行时错误和解析错误中的行号/列号
+
+use aether::ast::eq_ignoring_spans;
+use aether::{Aether, Parser, Stmt, Value};
+
+#[test]
+fn statements_carry_their_source_span() {
+    let program = Parser::new("Set X 1\n\nFunc F(A) {\n    Return A\n}")
+        .parse_program()
+        .unwrap();
+    assert_eq!(program.len(), 2);
+
+    let set = program[0].span().unwrap();
+    assert_eq!((set.line, set.column), (1, 1));
+    assert_eq!((set.start, set.end), (0, 7));
+
+    let func = program[1].span().unwrap();
+    assert_eq!((func.line, func.column), (3, 1));
+    let Stmt::FuncDef { body, .. } = program[1].node() else {
+        panic!("expected FuncDef");
+    };
+    let ret = body[0].span().unwrap();
+    assert_eq!((ret.line, ret.column), (4, 5));
+}
+
+#[test]
+fn spans_are_compared_unless_ignored() {
+    let compact = Parser::new("Set X 1 + 2").parse_program().unwrap();
+    let spaced = Parser::new("\n\n   Set   X   1 + 2")
+        .parse_program()
+        .unwrap();
+    assert_ne!(compact, spaced);
+    assert_eq!(compact[0].node(), spaced[0].node());
+    assert!(eq_ignoring_spans(&compact, &spaced));
+
+    // 嵌套语句的位置同样被忽略
+    let compact = Parser::new("Func F() { Return 1 }")
+        .parse_program()
+        .unwrap();
+    let spaced = Parser::new("Func F() {\n    Return 1\n}")
+        .parse_program()
+        .unwrap();
+    assert_ne!(compact[0].node(), spaced[0].node());
+    assert!(eq_ignoring_spans(&compact, &spaced));
+    assert!(!eq_ignoring_spans(
+        &compact,
+        &Parser::new("Func F() { Return 2 }")
+            .parse_program()
+            .unwrap()
+    ));
+}
+
+#[test]
+fn runtime_errors_report_the_failing_line() {
+    let err = Aether::new()
+        .eval("Set A 1\nSet B 2\nSet C A / 0")
+        .unwrap_err();
+    assert!(err.contains("at line 3, column 1"), "{}", err);
+}
+
+#[test]
+fn errors_inside_functions_point_at_the_inner_statement() {
+    let code = r#"Func INNER(X) {
+    Set Y 1
+    Return X + MISSING
+}
+
+INNER(1)"#;
+    let mut engine = Aether::new();
+    let err = engine.eval(code).unwrap_err();
+    assert!(err.contains("at line 3, column 5"), "{}", err);
+    assert!(err.contains("INNER"), "call stack missing: {}", err);
+
+    let report = engine.eval_report(code).unwrap_err();
+    assert_eq!(report.kind, "UndefinedVariable");
+    assert_eq!((report.line, report.column), (Some(3), Some(5)));
+    let json: serde_json::Value = serde_json::from_str(&report.to_json_pretty()).unwrap();
+    assert_eq!(json["line"], 3);
+    assert_eq!(json["column"], 5);
+}
+
+#[test]
+fn parse_errors_report_their_position() {
+    let err = Parser::new("Set X 1\nSet Y [1, 2")
+        .parse_program()
+        .unwrap_err();
+    assert_eq!(err.position().map(|(line, _)| line), Some(2));
+
+    let report = Aether::new()
+        .eval_report("Set X 1\nSet Y [1, 2")
+        .unwrap_err();
+    assert_eq!(report.phase, "parse");
+    assert_eq!(report.line, Some(2));
+}
+
+#[test]
+fn caught_errors_include_line_and_column() {
+    let code = r#"Try {
+    Set X 1
+    Set Y X / 0
+} Catch (ERR) {
+    [ERR["line"], ERR["column"]]
+}"#;
+    assert_eq!(
+        Aether::new().eval(code).unwrap(),
+        Value::Array(vec![Value::Number(3.0), Value::Number(5.0)])
+    );
+}
+
+#[test]
+fn optimized_programs_keep_their_spans() {
+    // 常量折叠和尾递归优化后，错误仍然指向原始位置
+    let code = r#"Func COUNT(N, ACC) {
+    If (N == 0) {
+        Return ACC + MISSING
+    }
+    Return COUNT(N - 1, ACC + 1)
+}
+
+Set LIMIT 2 * 5
+COUNT(LIMIT, 0)"#;
+    let err = Aether::new().eval(code).unwrap_err();
+    assert!(err.contains("at line 3, column 9"), "{}", err);
+}
@@ -242,7 +242,7 @@ fn test_selective_module_loading() {
     let _ = engine.eval(r#"ARR_SUM([1, 2, 3])"#).expect("Should work");
 
     // 这个应该失败（没有加载验证库）
-    let result = engine.eval(r#"VALIDATE_EMAIL("test@test.com")"#);
+    let result = engine.eval(r#"VALIDATE_PHONE_CN("13812345678")"#);
     assert!(
         result.is_err(),
         "Should fail because validation module not loaded"
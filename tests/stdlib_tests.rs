@@ -1,7 +1,9 @@
+use aether::builtins::BuiltInRegistry;
 use aether::stdlib::{
     ARRAY_UTILS, DATETIME, HEAP, QUEUE, SET, SORTING, STACK, STRING_UTILS, TESTING, VALIDATION,
-    get_module,
+    get_module, is_stdlib_function,
 };
+use aether::{Aether, Value};
 
 #[test]
 fn test_module_exists() {
@@ -31,3 +33,25 @@ fn test_get_module() {
     assert!(get_module("sorting").is_some());
     assert!(get_module("unknown").is_none());
 }
+
+#[test]
+fn test_builtin_names_do_not_collide_with_stdlib_functions() {
+    // 标准库有意重新实现的内置函数（行为与内置版本一致）
+    const OVERRIDES: &[&str] = &["SORT", "REPEAT"];
+    let collisions: Vec<String> = BuiltInRegistry::new()
+        .names()
+        .into_iter()
+        .filter(|name| is_stdlib_function(name) && !OVERRIDES.contains(&name.as_str()))
+        .collect();
+    assert!(collisions.is_empty(), "{:?}", collisions);
+}
+
+#[test]
+fn test_identity_matrix_and_stdlib_identity_coexist() {
+    let mut engine = Aether::new().with_stdlib_functional().unwrap();
+    assert_eq!(engine.eval("IDENTITY(5)").unwrap(), Value::Number(5.0));
+    assert_eq!(
+        engine.eval("IDENTITY_MATRIX(2)").unwrap(),
+        engine.eval("[[1, 0], [0, 1]]").unwrap()
+    );
+}
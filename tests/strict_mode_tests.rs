@@ -0,0 +1,112 @@
+// tests/strict_mode_tests.rs
+//! 严格模式测试
+
+use aether::{Aether, Value};
+
+fn strict() -> Aether {
+    Aether::new().with_strict(true)
+}
+
+#[test]
+fn strict_mode_is_off_by_default() {
+    let mut engine = Aether::new();
+    assert!(!engine.is_strict());
+    assert_eq!(
+        engine.eval("Set LEN 3\nIf (LEN) { \"yes\" }").unwrap(),
+        Value::String("yes".to_string())
+    );
+}
+
+#[test]
+fn non_boolean_conditions_are_errors() {
+    let mut engine = strict();
+    let err = engine.eval("If (1) { 2 }").unwrap_err();
+    assert!(
+        err.contains("If condition must be Boolean, got Integer"),
+        "{}",
+        err
+    );
+    assert!(
+        engine
+            .eval("Set X 1\nIf (X == 2) { 1 } Elif (\"x\") { 2 }")
+            .is_err()
+    );
+    assert!(engine.eval("Set N 3\nWhile (N) { Set N (N - 1) }").is_err());
+}
+
+#[test]
+fn boolean_conditions_still_work() {
+    let mut engine = strict();
+    let result = engine
+        .eval(
+            r#"
+            Set N 3
+            Set STEPS 0
+            While (N > 0) {
+                Set N (N - 1)
+                Set STEPS (STEPS + 1)
+            }
+            If (STEPS == 2) { "two" } Elif (STEPS == 3) { "three" } Else { "other" }
+            "#,
+        )
+        .unwrap();
+    assert_eq!(result, Value::String("three".to_string()));
+}
+
+#[test]
+fn shadowing_builtins_is_an_error() {
+    let mut engine = strict();
+    let err = engine.eval("Set LEN 3").unwrap_err();
+    assert!(err.contains("'LEN' shadows a built-in function"), "{}", err);
+    assert!(engine.eval("Func PRINTLN(X) { Return X }").is_err());
+    assert!(engine.eval("Func F() { Set MAP 1 }\nF()").is_err());
+    assert!(engine.eval("Set MY_LEN 3").is_ok());
+}
+
+#[test]
+fn string_number_concatenation_is_an_error() {
+    let mut engine = strict();
+    assert!(engine.eval(r#""a" + 1"#).is_err());
+    assert!(engine.eval(r#"1 + "a""#).is_err());
+}
+
+#[test]
+fn strict_mode_can_be_turned_off() {
+    let mut engine = strict();
+    assert!(engine.eval("If (1) { 2 }").is_err());
+    engine.set_strict(false);
+    assert_eq!(engine.eval("If (1) { 2 }").unwrap(), Value::Number(2.0));
+}
+
+#[test]
+fn stdlib_loads_in_strict_mode() {
+    let mut engine = strict();
+    engine.load_all_stdlib().unwrap();
+    assert!(engine.is_strict());
+    assert_eq!(
+        engine.eval("SORT([3, 1, 2])").unwrap(),
+        engine.eval("[1, 2, 3]").unwrap()
+    );
+    // 脚本自己的定义仍然受检查
+    assert!(engine.eval("Set SORT 1").is_err());
+
+    let mut engine = strict().with_stdlib_sorting().unwrap();
+    assert!(engine.is_strict());
+    engine.load_stdlib_module_as("cli_utils", "CLI").unwrap();
+    assert_eq!(
+        engine.eval(r#"CLI["REPEAT"]("ab", 2)"#).unwrap(),
+        Value::String("abab".to_string())
+    );
+}
+
+#[test]
+fn script_functions_with_stdlib_names_are_checked() {
+    let mut engine = strict();
+    engine.load_all_stdlib().unwrap();
+    let code = "Func ARR_UNIQUE(A) { Set LEN 5 Return LEN }\nARR_UNIQUE(1)";
+    let err = engine.eval(code).unwrap_err();
+    assert!(err.contains("shadows a built-in function"), "{}", err);
+
+    let mut engine = strict();
+    assert!(engine.eval(code).is_err());
+}
@@ -0,0 +1,150 @@
+// tests/table_tests.rs
+//! 表格类型（Value::Table）测试
+
+use aether::{Aether, Value};
+
+const EMPLOYEES: &str = r#"
+Set T TABLE_FROM_CSV("name,dept,salary\nAlice,eng,100\nBob,sales,80\nCarol,eng,120\nDan,sales,\n\"Eve, Jr.\",ops,90")
+"#;
+
+fn eval(code: &str) -> Value {
+    Aether::new()
+        .eval(&format!("{}\n{}", EMPLOYEES, code))
+        .unwrap()
+}
+
+fn text(code: &str) -> String {
+    eval(code).to_string()
+}
+
+#[test]
+fn table_from_csv_and_basics() {
+    assert_eq!(eval("TYPE(T)"), Value::String("Table".to_string()));
+    assert_eq!(eval("LEN(T)"), Value::Number(5.0));
+    assert_eq!(text("COLUMNS(T)"), "[name, dept, salary]");
+    assert_eq!(text("T"), "Table[name, dept, salary](5 rows)");
+    // 数字自动转换，空单元格为 Null，引号内可包含分隔符
+    assert_eq!(text(r#"T["salary"]"#), "[100, 80, 120, Null, 90]");
+    assert_eq!(
+        eval(r#"T[4]["name"]"#),
+        Value::String("Eve, Jr.".to_string())
+    );
+
+    let options = r#"TABLE_FROM_CSV("1;2\n3;4", {"delimiter": ";", "header": False})"#;
+    assert_eq!(text(&format!("COLUMNS({})", options)), "[col1, col2]");
+    assert_eq!(text(&format!("{}[\"col2\"]", options)), "[2, 4]");
+}
+
+#[test]
+fn table_constructors() {
+    let rows = r#"TABLE([{"a": 1, "b": "x"}, {"a": 2}])"#;
+    assert_eq!(text(&format!("COLUMNS({})", rows)), "[a, b]");
+    assert_eq!(text(&format!("{}[\"b\"]", rows)), "[x, Null]");
+
+    let cols = r#"TABLE({"b": [1, 2], "a": [3, 4]}, ["b", "a"])"#;
+    assert_eq!(text(&format!("COLUMNS({})", cols)), "[b, a]");
+    assert_eq!(
+        eval(r#"TABLE([{"a": 1}]) == TABLE({"a": [1]})"#),
+        Value::Boolean(true)
+    );
+
+    let mut engine = Aether::new();
+    assert!(engine.eval(r#"TABLE({"a": [1, 2], "b": [1]})"#).is_err());
+    assert!(engine.eval(r#"TABLE({"a": [[1]]})"#).is_err());
+}
+
+#[test]
+fn table_select_where_sort() {
+    assert_eq!(
+        text(r#"COLUMNS(SELECT(T, ["salary", "name"]))"#),
+        "[salary, name]"
+    );
+    assert_eq!(
+        text(r#"WHERE(T, {"dept": "eng"})["name"]"#),
+        "[Alice, Carol]"
+    );
+    assert_eq!(
+        text(r#"WHERE(T, Lambda ROW -> ROW["salary"] != Null And ROW["salary"] < 100)["name"]"#),
+        "[Bob, Eve, Jr.]"
+    );
+    assert_eq!(
+        text(r#"SORT(T, "salary")["name"]"#),
+        "[Bob, Eve, Jr., Alice, Carol, Dan]"
+    );
+    assert_eq!(
+        text(r#"SORT(T, ["dept", "salary"], True)["name"]"#),
+        "[Bob, Dan, Eve, Jr., Carol, Alice]"
+    );
+    // 数组的 SORT 不受影响
+    assert_eq!(text("SORT([3, 1, 2])"), "[1, 2, 3]");
+
+    let mut engine = Aether::new();
+    engine.eval(EMPLOYEES).unwrap();
+    assert!(engine.eval(r#"SELECT(T, "missing")"#).is_err());
+}
+
+#[test]
+fn table_group_agg() {
+    let grouped = r#"GROUP_AGG(T, "dept", {"salary": ["SUM", "MAX"], "*": "COUNT"})"#;
+    assert_eq!(
+        text(&format!("COLUMNS({})", grouped)),
+        "[dept, count, salary_sum, salary_max]"
+    );
+    assert_eq!(text(&format!("{}[\"dept\"]", grouped)), "[eng, sales, ops]");
+    assert_eq!(text(&format!("{}[\"count\"]", grouped)), "[2, 2, 1]");
+    assert_eq!(
+        text(&format!("{}[\"salary_sum\"]", grouped)),
+        "[220, 80, 90]"
+    );
+    assert_eq!(
+        text(&format!("{}[\"salary_max\"]", grouped)),
+        "[120, 80, 90]"
+    );
+    assert_eq!(
+        text(r#"GROUP_AGG(T, "dept", {"salary": "COUNT"})["salary_count"]"#),
+        "[2, 1, 1]"
+    );
+}
+
+#[test]
+fn table_join_and_to_dicts() {
+    let setup = r#"
+Set D TABLE([{"dept": "eng", "floor": 3}, {"dept": "sales", "floor": 1}])
+"#;
+    let inner = format!("{}JOIN(T, D, \"dept\")", setup);
+    assert_eq!(
+        text(&format!("{}[\"name\"]", inner)),
+        "[Alice, Bob, Carol, Dan]"
+    );
+    assert_eq!(text(&format!("{}[\"floor\"]", inner)), "[3, 1, 3, 1]");
+
+    let left = format!("{}JOIN(T, D, \"dept\", \"LEFT\")", setup);
+    assert_eq!(text(&format!("{}[\"floor\"]", left)), "[3, 1, 3, 1, Null]");
+
+    // 右表中与左表重名的非键列加 _right 后缀
+    let clash = r#"JOIN(T, TABLE([{"dept": "ops", "name": "Ops"}]), "dept")"#;
+    assert_eq!(
+        text(&format!("COLUMNS({})", clash)),
+        "[name, dept, salary, name_right]"
+    );
+
+    assert_eq!(
+        eval(r#"TO_DICTS(SELECT(T, "name"))[0]["name"]"#),
+        Value::String("Alice".to_string())
+    );
+    assert_eq!(text(r#"JOIN(["a", "b"], "-")"#), "a-b");
+}
+
+#[test]
+fn table_iterates_rows() {
+    let code = r#"
+Set TOTAL 0
+For ROW In T {
+    If (ROW["salary"] != Null) {
+        Set TOTAL (TOTAL + ROW["salary"])
+    }
+}
+TOTAL
+"#;
+    assert_eq!(eval(code), Value::Number(390.0));
+}
@@ -1,7 +1,7 @@
 // tests/tail_recursion_tests.rs
 //! 尾递归优化集成测试
 
-use aether::{Aether, Value};
+use aether::Aether;
 
 #[test]
 fn test_tail_recursive_factorial() {
@@ -20,7 +20,7 @@ fn test_tail_recursive_factorial() {
     let mut engine = Aether::new();
     let result = engine.eval(code).expect("Failed to evaluate");
 
-    if let Value::Number(n) = result {
+    if let Some(n) = result.to_number() {
         assert_eq!(n, 3628800.0); // 10! = 3628800
     } else {
         panic!("Expected number result, got {:?}", result);
@@ -44,7 +44,7 @@ fn test_tail_recursive_sum() {
     let mut engine = Aether::new();
     let result = engine.eval(code).expect("Failed to evaluate");
 
-    if let Value::Number(n) = result {
+    if let Some(n) = result.to_number() {
         assert_eq!(n, 5050.0); // sum(1..100) = 5050
     } else {
         panic!("Expected number result, got {:?}", result);
@@ -68,7 +68,7 @@ fn test_tail_recursive_fibonacci() {
     let mut engine = Aether::new();
     let result = engine.eval(code).expect("Failed to evaluate");
 
-    if let Value::Number(n) = result {
+    if let Some(n) = result.to_number() {
         assert_eq!(n, 55.0); // fib(10) = 55
     } else {
         panic!("Expected number result, got {:?}", result);
@@ -95,7 +95,7 @@ fn test_large_tail_recursion() {
         .eval(code)
         .expect("Failed to evaluate large recursion");
 
-    if let Value::Number(n) = result {
+    if let Some(n) = result.to_number() {
         assert_eq!(n, 500500.0); // sum(1..1000) = 500500
     } else {
         panic!("Expected number result, got {:?}", result);
@@ -122,7 +122,7 @@ fn test_very_large_tail_recursion() {
         .eval(code)
         .expect("Should handle 5000 iterations without stack overflow");
 
-    if let Value::Number(n) = result {
+    if let Some(n) = result.to_number() {
         assert_eq!(n, 5000.0);
     } else {
         panic!("Expected number result, got {:?}", result);
@@ -147,7 +147,7 @@ fn test_non_tail_recursion_still_works() {
     let mut engine = Aether::new();
     let result = engine.eval(code).expect("Failed to evaluate");
 
-    if let Value::Number(n) = result {
+    if let Some(n) = result.to_number() {
         assert_eq!(n, 120.0); // 5! = 120
     } else {
         panic!("Expected number result, got {:?}", result);
@@ -176,7 +176,7 @@ fn test_conditional_tail_recursion() {
     let result = engine.eval(code).expect("Failed to evaluate");
 
     // Collatz序列: 27需要111步到达1
-    if let Value::Number(n) = result {
+    if let Some(n) = result.to_number() {
         assert_eq!(n, 111.0);
     } else {
         panic!("Expected number result, got {:?}", result);
@@ -201,7 +201,7 @@ fn test_mutual_recursion_helper() {
     let mut engine = Aether::new();
     let result = engine.eval(code).expect("Failed to evaluate");
 
-    if let Value::Number(n) = result {
+    if let Some(n) = result.to_number() {
         assert_eq!(n, 6.0); // gcd(48, 18) = 6
     } else {
         panic!("Expected number result, got {:?}", result);
@@ -226,7 +226,7 @@ fn test_tail_recursion_with_accumulator() {
     let mut engine = Aether::new();
     let result = engine.eval(code).expect("Failed to evaluate");
 
-    if let Value::Number(n) = result {
+    if let Some(n) = result.to_number() {
         assert_eq!(n, 100.0);
     } else {
         panic!("Expected number result, got {:?}", result);
@@ -0,0 +1,71 @@
+// tests/template_tests.rs
+//! RENDER_TEMPLATE 模板引擎测试
+
+use aether::Aether;
+
+fn render(code: &str) -> Result<String, String> {
+    Aether::new().eval(code).map(|v| v.to_string())
+}
+
+#[test]
+fn template_variables_and_filters() {
+    assert_eq!(
+        render(r#"RENDER_TEMPLATE("Hi {{name|title}} ({{user.age}}), {{missing}}!", {"name": "alice", "user": {"age": 30}})"#)
+            .unwrap(),
+        "Hi Alice (30), !"
+    );
+    assert_eq!(
+        render(r#"RENDER_TEMPLATE("{{items.1|upper}} {{items|length}} {{items|json}}", {"items": ["a", "b"]})"#)
+            .unwrap(),
+        r#"B 2 ["a","b"]"#
+    );
+    assert!(render(r#"RENDER_TEMPLATE("{{x|bogus}}", {"x": 1})"#).is_err());
+}
+
+#[test]
+fn template_conditionals() {
+    let tpl = r#""{{if score >= 90}}A{{elif score >= 60}}pass{{else}}fail{{endif}}""#;
+    for (score, expected) in [(95, "A"), (70, "pass"), (10, "fail")] {
+        let code = format!("RENDER_TEMPLATE({}, {{\"score\": {}}})", tpl, score);
+        assert_eq!(render(&code).unwrap(), expected);
+    }
+    assert_eq!(
+        render(r#"RENDER_TEMPLATE("{{if not admin}}guest{{endif}}{{if role == 'ops'}}!{{endif}}", {"admin": False, "role": "ops"})"#)
+            .unwrap(),
+        "guest!"
+    );
+}
+
+#[test]
+fn template_nested_loops() {
+    let code = r#"
+Set TPL "{{for g in groups}}{{g.name}}:{{for m in g.members}}{{m}}{{if not loop_last}},{{endif}}{{endfor}};{{endfor}}{{for x in none}}x{{else}}empty{{endfor}}"
+Set DATA {"groups": [{"name": "a", "members": [1, 2, 3]}, {"name": "b", "members": [4]}], "none": []}
+RENDER_TEMPLATE(TPL, DATA)
+"#;
+    assert_eq!(render(code).unwrap(), "a:1,2,3;b:4;empty");
+}
+
+#[test]
+fn template_escaping_and_strict_mode() {
+    assert_eq!(
+        render(
+            r#"RENDER_TEMPLATE("<p>{{msg}}</p>{{msg|raw}}", {"msg": "<b>&"}, {"escape": "html"})"#
+        )
+        .unwrap(),
+        "<p>&lt;b&gt;&amp;</p><b>&"
+    );
+    assert_eq!(
+        render(r#"RENDER_TEMPLATE("{{msg|escape}}", {"msg": "'q'"})"#).unwrap(),
+        "&#39;q&#39;"
+    );
+    let err = render(r#"RENDER_TEMPLATE("{{nope}}", {}, {"strict": True})"#).unwrap_err();
+    assert!(err.contains("nope"), "{}", err);
+}
+
+#[test]
+fn template_syntax_errors() {
+    assert!(render(r#"RENDER_TEMPLATE("{{if x}}open", {})"#).is_err());
+    assert!(render(r#"RENDER_TEMPLATE("{{endfor}}", {})"#).is_err());
+    assert!(render(r#"RENDER_TEMPLATE("{{x", {})"#).is_err());
+}
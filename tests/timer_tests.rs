@@ -0,0 +1,164 @@
+// tests/timer_tests.rs
+//! 宿主驱动的定时器测试（on_timer / pump / SET_INTERVAL / DEBOUNCE）
+
+use aether::{Aether, Value};
+use std::time::Duration;
+
+fn ms(n: u64) -> Duration {
+    Duration::from_millis(n)
+}
+
+/// 定时器间隔远大于测试本身的耗时，时间完全由 advance_timers 推进
+const MINUTE: u64 = 60_000;
+
+/// 回调通过 TRACE 留下的记录（去掉 `#序号 ` 前缀）
+fn traced(engine: &mut Aether) -> Vec<String> {
+    engine
+        .take_trace()
+        .into_iter()
+        .map(|line| {
+            line.split_once(' ')
+                .map(|(_, msg)| msg.to_string())
+                .unwrap_or(line)
+        })
+        .collect()
+}
+
+#[test]
+fn host_timers_fire_only_when_pumped() {
+    let mut engine = Aether::new();
+    engine.eval("Func TICK() { TRACE(\"tick\") }").unwrap();
+    let id = engine.on_timer(ms(MINUTE), "TICK").unwrap();
+    assert!(engine.has_pending_timers());
+
+    assert_eq!(engine.pump().runs, 0);
+    assert_eq!(engine.advance_timers(ms(MINUTE / 2)).runs, 0);
+    assert_eq!(engine.advance_timers(ms(MINUTE / 2)).runs, 1);
+    assert_eq!(engine.advance_timers(ms(MINUTE)).runs, 1);
+    // 错过的周期不补跑
+    assert_eq!(engine.advance_timers(ms(MINUTE * 5)).runs, 1);
+    assert_eq!(traced(&mut engine).len(), 3);
+
+    assert!(engine.cancel_timer(id));
+    assert!(!engine.cancel_timer(id));
+    assert!(!engine.has_pending_timers());
+    assert_eq!(engine.next_timer_delay(), None);
+}
+
+#[test]
+fn host_timers_validate_the_callback() {
+    let mut engine = Aether::new();
+    engine.eval("Set LIMIT 3").unwrap();
+    assert_eq!(
+        engine.on_timer(ms(10), "MISSING").unwrap_err(),
+        "Undefined function: MISSING"
+    );
+    assert!(
+        engine
+            .on_timer(ms(10), "LIMIT")
+            .unwrap_err()
+            .contains("not a function")
+    );
+    engine.eval("Func F() { Return 1 }").unwrap();
+    assert!(engine.on_timer(Duration::ZERO, "F").is_err());
+}
+
+#[test]
+fn script_timers_run_in_deadline_order() {
+    let mut engine = Aether::new();
+    engine
+        .eval(
+            r#"Func LATE() { TRACE("late") }
+Func EARLY() { TRACE("early") }
+Func TICK() { TRACE("tick") }
+SET_TIMEOUT(2000, LATE)
+SET_TIMEOUT(1000, EARLY)
+Set EVERY SET_INTERVAL(1500, TICK)"#,
+        )
+        .unwrap();
+    let next = engine.next_timer_delay().unwrap();
+    assert!(next <= ms(1000) && next > ms(500), "{:?}", next);
+
+    let report = engine.advance_timers(ms(MINUTE));
+    assert_eq!(report.runs, 3);
+    assert!(report.is_ok(), "{:?}", report.errors);
+    assert_eq!(traced(&mut engine), ["early", "tick", "late"]);
+
+    // 一次性定时器已移除，周期定时器仍在
+    assert_eq!(
+        engine.eval("CLEAR_TIMER(EVERY)").unwrap(),
+        Value::Boolean(true)
+    );
+    assert_eq!(
+        engine.eval("CLEAR_TIMER(EVERY)").unwrap(),
+        Value::Boolean(false)
+    );
+    assert!(!engine.has_pending_timers());
+}
+
+#[test]
+fn debounce_collapses_bursts_into_one_call() {
+    let mut engine = Aether::new();
+    engine.eval("Func SAVE() { TRACE(\"save\") }").unwrap();
+
+    let first = engine.debounce(ms(MINUTE), "SAVE").unwrap();
+    engine.advance_timers(ms(MINUTE / 2));
+    let second = engine.eval("DEBOUNCE(60000, SAVE)").unwrap();
+    assert_eq!(second, Value::Number(first as f64));
+
+    // 第二次触发重新计时：距第一次已满一分钟，但仍未执行
+    assert_eq!(engine.advance_timers(ms(MINUTE / 2)).runs, 0);
+    assert_eq!(engine.advance_timers(ms(MINUTE / 2)).runs, 1);
+    assert_eq!(traced(&mut engine), ["save"]);
+    assert!(!engine.has_pending_timers());
+
+    let err = engine.eval("DEBOUNCE(10, Lambda () -> 1)").unwrap_err();
+    assert!(err.contains("named function"), "{}", err);
+}
+
+#[test]
+fn failing_callbacks_are_reported_and_intervals_keep_running() {
+    let mut engine = Aether::new();
+    engine
+        .eval("Func FLAKY() {\n    TRACE(\"run\")\n    Return MISSING + 1\n}")
+        .unwrap();
+    engine.eval("SET_INTERVAL(1000, FLAKY)").unwrap();
+
+    let report = engine.advance_timers(ms(1000));
+    assert_eq!(report.runs, 0);
+    assert_eq!(report.errors.len(), 1);
+    assert!(
+        report.errors[0].starts_with("Timer #1 (FLAKY) failed:"),
+        "{}",
+        report.errors[0]
+    );
+
+    assert_eq!(engine.advance_timers(ms(1000)).errors.len(), 1);
+    assert_eq!(traced(&mut engine), ["run", "run"]);
+}
+
+#[test]
+fn host_timers_use_the_current_definition() {
+    let mut engine = Aether::new();
+    engine.eval("Func STEP() { TRACE(\"old\") }").unwrap();
+    engine.on_timeout(ms(MINUTE), "STEP").unwrap();
+    engine.eval("Func STEP() { TRACE(\"new\") }").unwrap();
+    assert_eq!(engine.advance_timers(ms(MINUTE)).runs, 1);
+    assert_eq!(traced(&mut engine), ["new"]);
+}
+
+#[test]
+fn timer_arguments_are_checked() {
+    let mut engine = Aether::new();
+    engine.eval("Func F() { Return 1 }").unwrap();
+    for code in [
+        "SET_TIMEOUT(-1, F)",
+        "SET_TIMEOUT(10, 5)",
+        "SET_TIMEOUT(\"10\", F)",
+        "SET_INTERVAL(0, F)",
+        "CLEAR_TIMER(\"1\")",
+    ] {
+        assert!(engine.eval(code).is_err(), "{} should fail", code);
+    }
+    assert!(!engine.has_pending_timers());
+}
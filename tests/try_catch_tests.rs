@@ -0,0 +1,248 @@
+// tests/try_catch_tests.rs
+//! Try / Catch / Finally 语句测试
+
+use aether::minify::{MinifyOptions, minify};
+use aether::{Aether, Dialect, ExecutionLimits, Value};
+
+fn eval(code: &str) -> Value {
+    Aether::new().eval(code).unwrap()
+}
+
+fn string(s: &str) -> Value {
+    Value::String(s.to_string())
+}
+
+#[test]
+fn catch_binds_the_thrown_value() {
+    let code = r#"
+Try {
+    Throw {"code": 42}
+    "unreachable"
+} Catch (ERR) {
+    ERR["code"]
+}
+"#;
+    assert_eq!(eval(code), Value::Number(42.0));
+}
+
+#[test]
+fn runtime_errors_are_caught_as_kind_and_message() {
+    let mut engine = Aether::new();
+    engine
+        .eval(
+            r#"
+Try {
+    Set X 1 / 0
+} Catch (ERR) {
+    Set KIND ERR["kind"]
+}
+Try {
+    MISSING + 1
+} Catch (ERR) {
+    Set MESSAGE ERR["message"]
+}
+"#,
+        )
+        .unwrap();
+    assert_eq!(engine.eval("KIND").unwrap(), string("DivisionByZero"));
+    let message = engine.eval("MESSAGE").unwrap().to_string();
+    assert!(message.contains("MISSING"), "{}", message);
+}
+
+#[test]
+fn errors_thrown_inside_functions_are_caught_by_the_caller() {
+    let code = r#"
+Func PARSE_AGE(TEXT) {
+    If (TEXT == "") {
+        Throw "empty age"
+    }
+    Return TO_NUMBER(TEXT)
+}
+
+Func SAFE_AGE(TEXT) {
+    Try {
+        Return PARSE_AGE(TEXT)
+    } Catch (ERR) {
+        Return -1
+    }
+}
+
+[SAFE_AGE("30"), SAFE_AGE("")]
+"#;
+    assert_eq!(
+        eval(code),
+        Value::Array(vec![Value::Number(30.0), Value::Number(-1.0)])
+    );
+}
+
+#[test]
+fn finally_runs_on_success_error_and_return() {
+    let mut engine = Aether::new();
+    engine
+        .eval(
+            r#"
+Func EARLY() {
+    Try {
+        Return "body"
+    } Finally {
+        TRACE("cleanup after return")
+    }
+}
+
+Try {
+    TRACE("ok")
+} Finally {
+    TRACE("cleanup after success")
+}
+
+Try {
+    Throw "boom"
+} Catch (ERR) {
+    TRACE("caught " + ERR)
+} Finally {
+    TRACE("cleanup after error")
+}
+
+EARLY()
+"#,
+        )
+        .unwrap();
+    let trace: Vec<String> = engine
+        .take_trace()
+        .into_iter()
+        .map(|line| {
+            line.split_once(' ')
+                .map(|(_, msg)| msg.to_string())
+                .unwrap()
+        })
+        .collect();
+    assert_eq!(
+        trace,
+        vec![
+            "ok",
+            "cleanup after success",
+            "caught boom",
+            "cleanup after error",
+            "cleanup after return",
+        ]
+    );
+}
+
+#[test]
+fn try_finally_without_catch_propagates_the_error() {
+    let mut engine = Aether::new();
+    let err = engine
+        .eval(
+            r#"
+Try {
+    Throw "still failing"
+} Finally {
+    TRACE("cleanup")
+}
+"#,
+        )
+        .unwrap_err();
+    assert!(err.contains("still failing"), "{}", err);
+    assert_eq!(engine.take_trace().len(), 1);
+
+    // Catch 中再次抛出的错误继续向外传播
+    let err = engine
+        .eval(
+            r#"
+Try {
+    Throw "first"
+} Catch (ERR) {
+    Throw "rethrown: " + ERR
+}
+"#,
+        )
+        .unwrap_err();
+    assert!(err.contains("rethrown: first"), "{}", err);
+}
+
+#[test]
+fn loop_control_is_not_caught() {
+    let code = r#"
+Set TOTAL 0
+For I In [1, 2, 3, 4] {
+    Try {
+        If (I == 2) {
+            Continue
+        }
+        If (I == 4) {
+            Break
+        }
+        Set TOTAL TOTAL + I
+    } Catch (ERR) {
+        Set TOTAL -100
+    }
+}
+TOTAL
+"#;
+    assert_eq!(eval(code), Value::Number(4.0));
+}
+
+#[test]
+fn execution_limits_cannot_be_caught() {
+    let limits = ExecutionLimits {
+        max_steps: Some(200),
+        ..ExecutionLimits::default()
+    };
+    let mut engine = Aether::new().with_limits(limits);
+    let err = engine
+        .eval(
+            r#"
+Try {
+    While (True) {
+        Set X 1
+    }
+} Catch (ERR) {
+    "swallowed"
+}
+"#,
+        )
+        .unwrap_err();
+    assert!(err.to_lowercase().contains("step limit"), "{}", err);
+}
+
+#[test]
+fn try_requires_catch_or_finally() {
+    for code in [
+        "Try { 1 }",
+        "Try { 1 } Catch { 2 }",
+        "Try { 1 } Catch (1) { 2 }",
+    ] {
+        assert!(Aether::new().eval(code).is_err(), "{} should fail", code);
+    }
+}
+
+#[test]
+fn chinese_dialect_keywords() {
+    let mut engine = Aether::new().with_dialect(Dialect::chinese());
+    assert_eq!(
+        engine
+            .eval("尝试 { 抛出 \"坏数据\" } 捕获 (ERR) { ERR } 最终 { 1 }")
+            .unwrap(),
+        string("坏数据")
+    );
+}
+
+#[test]
+fn minifier_renames_the_catch_variable_consistently() {
+    let code = r#"
+Func SAFE_DIV(LEFT, RIGHT) {
+    Try {
+        Return LEFT / RIGHT
+    } Catch (FAILURE) {
+        Return FAILURE["kind"]
+    }
+}
+SAFE_DIV(1, 0)
+"#;
+    let minified = minify(code, &MinifyOptions::default().with_rename_locals()).unwrap();
+    assert!(!minified.contains("FAILURE"), "{}", minified);
+    assert_eq!(
+        Aether::new().eval(&minified).unwrap(),
+        string("DivisionByZero")
+    );
+}
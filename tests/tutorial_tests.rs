@@ -0,0 +1,60 @@
+// tests/tutorial_tests.rs
+//! 内置教程（aether learn）测试
+
+use aether::tutorial::{self, Lesson};
+use aether::{Aether, Value};
+
+#[test]
+fn every_lesson_produces_its_expected_output() {
+    let lessons = tutorial::lessons();
+    assert!(!lessons.is_empty());
+    for lesson in lessons {
+        assert!(!lesson.expected_output.is_empty(), "{}", lesson.name);
+        let check = lesson.check();
+        assert!(
+            check.passed,
+            "lesson {} failed: {:?}\nexpected:\n{}\nactual:\n{}",
+            lesson.name, check.error, lesson.expected_output, check.output
+        );
+    }
+}
+
+#[test]
+fn lesson_format_is_parsed() {
+    let lesson = Lesson::parse(
+        1,
+        "demo",
+        "// # 示例\n// 第一行说明\n// 第二行说明\n\nPRINTLN(1 + 1)\n// 普通注释\n\n//> 2\n",
+    );
+    assert_eq!(lesson.title, "示例");
+    assert_eq!(lesson.intro, vec!["第一行说明", "第二行说明"]);
+    assert_eq!(lesson.expected_output, "2\n");
+    assert_eq!(lesson.code(), "PRINTLN(1 + 1)\n// 普通注释");
+    assert!(lesson.check().passed);
+
+    let wrong = Lesson::parse(2, "wrong", "PRINTLN(3)\n//> 2\n");
+    let check = wrong.check();
+    assert!(!check.passed);
+    assert_eq!(check.output, "3\n");
+}
+
+#[test]
+fn lessons_can_be_found_by_number_or_name() {
+    assert_eq!(tutorial::find_lesson("1").unwrap().name, "hello");
+    assert_eq!(tutorial::find_lesson("strings").unwrap().number, 3);
+    assert!(tutorial::find_lesson("999").is_none());
+    assert!(tutorial::find_lesson("nope").is_none());
+}
+
+#[test]
+fn run_captured_keeps_state_and_returns_value() {
+    let mut engine = Aether::new();
+    let (output, result) = tutorial::run_captured(&mut engine, "Set X 2\nPRINTLN(\"hi\")\nX * 3");
+    assert_eq!(output, "hi\n");
+    assert_eq!(result, Ok(Value::Number(6.0)));
+
+    let (_, result) = tutorial::run_captured(&mut engine, "X + UNKNOWN");
+    assert!(result.is_err());
+    assert!(tutorial::outputs_match("a  \nb\n\n", "a\nb"));
+    assert!(!tutorial::outputs_match("a\n", "b\n"));
+}
@@ -0,0 +1,110 @@
+// tests/validation_tests.rs
+//! 标识符校验内置函数测试
+
+use aether::{Aether, Value};
+
+fn check(code: &str) -> bool {
+    match Aether::new().eval(code).unwrap() {
+        Value::Boolean(b) => b,
+        other => panic!("expected Boolean, got {:?}", other),
+    }
+}
+
+#[test]
+fn luhn_check_accepts_separators_and_numbers() {
+    assert!(check(r#"LUHN_CHECK("4111 1111 1111 1111")"#));
+    assert!(check(r#"LUHN_CHECK("5500-0000-0000-0004")"#));
+    assert!(check("LUHN_CHECK(79927398713)"));
+    assert!(!check(r#"LUHN_CHECK("4111 1111 1111 1112")"#));
+    assert!(!check(r#"LUHN_CHECK("4111a111")"#));
+    assert!(!check(r#"LUHN_CHECK("0")"#));
+    assert!(Aether::new().eval("LUHN_CHECK([1])").is_err());
+}
+
+#[test]
+fn validate_id_cn_checks_region_date_and_checksum() {
+    assert!(check(r#"VALIDATE_ID_CN("11010519491231002X")"#));
+    assert!(check(r#"VALIDATE_ID_CN("11010519491231002x")"#));
+    assert!(!check(r#"VALIDATE_ID_CN("110105194912310021")"#));
+    assert!(!check(r#"VALIDATE_ID_CN("110105194902300021")"#));
+    assert!(!check(r#"VALIDATE_ID_CN("99010519491231002X")"#));
+    assert!(!check(r#"VALIDATE_ID_CN("1101051949123100")"#));
+    assert!(!check(r#"VALIDATE_ID_CN("21010530001231002X")"#));
+}
+
+#[test]
+fn validate_iban_checks_length_and_mod97() {
+    assert!(check(r#"VALIDATE_IBAN("GB82 WEST 1234 5698 7654 32")"#));
+    assert!(check(r#"VALIDATE_IBAN("de89370400440532013000")"#));
+    assert!(!check(r#"VALIDATE_IBAN("GB82 WEST 1234 5698 7654 33")"#));
+    assert!(!check(r#"VALIDATE_IBAN("GB82 WEST 1234 5698 7654")"#));
+    assert!(!check(r#"VALIDATE_IBAN("ZZ82WEST12345698765432")"#));
+    assert!(!check(r#"VALIDATE_IBAN("GB82-WEST-1234-5698-7654-32")"#));
+}
+
+#[test]
+fn validate_email_follows_rfc_subset() {
+    for valid in [
+        "a@b.co",
+        "first.last+tag@example.co.uk",
+        "user_name@sub-domain.example.org",
+        r#"\"john doe\"@example.com"#,
+        "admin@[192.0.2.1]",
+        "用户@例子.中国",
+    ] {
+        assert!(
+            check(&format!(r#"VALIDATE_EMAIL("{}")"#, valid)),
+            "{}",
+            valid
+        );
+    }
+    for invalid in [
+        "plainaddress",
+        "@example.com",
+        "user@",
+        "john..doe@example.com",
+        ".user@example.com",
+        "user.@example.com",
+        "user@localhost",
+        "user@-example.com",
+        "user@example..com",
+        "user@example.123",
+        "user name@example.com",
+        "user@[999.0.0.1]",
+    ] {
+        assert!(
+            !check(&format!(r#"VALIDATE_EMAIL("{}")"#, invalid)),
+            "{}",
+            invalid
+        );
+    }
+    let long_local = "a".repeat(65);
+    assert!(!check(&format!(
+        r#"VALIDATE_EMAIL("{}@example.com")"#,
+        long_local
+    )));
+    assert!(Aether::new().eval("VALIDATE_EMAIL(1)").is_err());
+}
+
+#[test]
+fn validation_stdlib_uses_native_checks() {
+    let mut engine = Aether::new().with_stdlib_validation().unwrap();
+    let code = r#"
+        [
+            VALIDATE_EMAIL("a@b.com"),
+            VALIDATE_ID_CARD_CN("11010519491231002X"),
+            VALIDATE_ID_CARD_CN("110105194912310021"),
+            VALIDATE_CREDIT_CARD("4111111111111111"),
+            VALIDATE_CREDIT_CARD("4111111111111112")
+        ]
+    "#;
+    assert_eq!(
+        engine.eval(code).unwrap(),
+        Value::Array(
+            [true, true, false, true, false]
+                .into_iter()
+                .map(Value::Boolean)
+                .collect()
+        )
+    );
+}
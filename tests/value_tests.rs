@@ -82,3 +82,61 @@ fn test_array_equality() {
     assert!(arr1.equals(&arr2));
     assert!(!arr1.equals(&arr3));
 }
+
+#[test]
+fn test_display_options_formatting() {
+    use aether::DisplayOptions;
+
+    let opts = DisplayOptions::new()
+        .with_float_precision(2)
+        .with_sorted_dict_keys(true)
+        .with_max_items(2)
+        .with_max_depth(2);
+
+    assert_eq!(Value::Number(1.0 / 3.0).display_with(&opts), "0.33");
+    assert_eq!(Value::Number(2.5).display_with(&opts), "2.5");
+    assert_eq!(Value::Number(4.0).display_with(&opts), "4");
+
+    let arr = Value::Array(vec![
+        Value::Number(1.0),
+        Value::Number(2.0),
+        Value::Number(3.0),
+    ]);
+    assert_eq!(arr.display_with(&opts), "[1, 2, ...]");
+
+    let nested = Value::Array(vec![Value::Array(vec![Value::Array(vec![])])]);
+    assert_eq!(nested.display_with(&opts), "[[[...]]]");
+
+    let mut dict = std::collections::HashMap::new();
+    dict.insert("b".to_string(), Value::Number(2.0));
+    dict.insert("a".to_string(), Value::Number(1.0));
+    assert_eq!(Value::Dict(dict).display_with(&opts), "{a: 1, b: 2}");
+}
+
+#[test]
+fn test_display_options_apply_to_engine() {
+    use aether::{Aether, DisplayOptions};
+
+    let mut engine = Aether::new().with_display_options(
+        DisplayOptions::new()
+            .with_fraction_as_decimal(true)
+            .with_float_precision(3),
+    );
+    assert_eq!(
+        engine.eval("TO_STRING(TO_FRACTION(0.5))").unwrap(),
+        Value::String("0.5".to_string())
+    );
+    assert_eq!(
+        engine.eval("TO_STRING(2 / 3)").unwrap(),
+        Value::String("0.667".to_string())
+    );
+    assert_eq!(engine.format_value(&Value::Number(0.12345)), "0.123");
+
+    // 默认配置保持原有输出
+    let mut plain = Aether::new();
+    assert_eq!(
+        plain.eval("TO_STRING(TO_FRACTION(0.5))").unwrap(),
+        Value::String("1/2".to_string())
+    );
+    assert_eq!(Value::Number(0.12345).to_string(), "0.12345");
+}
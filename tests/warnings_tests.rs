@@ -0,0 +1,100 @@
+// tests/warnings_tests.rs
+//! 警告通道测试
+
+use aether::{Aether, Value, WarningKind};
+
+fn kinds(engine: &mut Aether) -> Vec<WarningKind> {
+    engine.take_warnings().into_iter().map(|w| w.kind).collect()
+}
+
+#[test]
+fn clean_scripts_produce_no_warnings() {
+    let mut engine = Aether::new();
+    engine.eval("Set X [1, 2, 3]\nSUM(X) + 1").unwrap();
+    assert!(engine.take_warnings().is_empty());
+}
+
+#[test]
+fn duplicate_dictionary_keys_are_parse_warnings() {
+    let mut engine = Aether::new();
+    let result = engine.eval(r#"{"a": 1, "b": 2, "a": 3}["a"]"#).unwrap();
+    assert_eq!(result, Value::Number(3.0));
+    let warnings = engine.take_warnings();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].kind, WarningKind::Parse);
+    assert!(warnings[0].message.contains("duplicate key 'a'"));
+    assert!(warnings[0].to_string().starts_with("warning[parse]:"));
+}
+
+#[test]
+fn removed_dead_code_is_reported() {
+    let mut engine = Aether::new();
+    engine
+        .eval("While (False) { PRINTLN(1) }\nIf (True) { 1 } Else { 2 }")
+        .unwrap();
+    assert_eq!(
+        kinds(&mut engine),
+        vec![WarningKind::DeadCode, WarningKind::DeadCode]
+    );
+}
+
+#[test]
+fn deprecated_builtins_are_reported_once() {
+    let mut engine = Aether::new();
+    engine.deprecate_builtin("LEN", Some("SIZE")).unwrap();
+    engine.eval("LEN([1])\nLEN([2])").unwrap();
+    let warnings = engine.take_warnings();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].kind, WarningKind::Deprecation);
+    assert!(warnings[0].message.contains("'SIZE'"));
+    // 旧接口仍然可用
+    assert_eq!(engine.deprecation_warnings().len(), 1);
+}
+
+#[test]
+fn precision_loss_is_reported() {
+    let mut engine = Aether::new();
+    engine.eval("12345678901234567890123 + 0.5").unwrap();
+    assert_eq!(kinds(&mut engine), vec![WarningKind::Precision]);
+
+    engine.eval("Set N 100000000.0\nN * N").unwrap();
+    assert_eq!(kinds(&mut engine), vec![WarningKind::Precision]);
+
+    engine.eval("12345678901234567890123 + 1").unwrap();
+    assert!(engine.take_warnings().is_empty());
+}
+
+#[test]
+fn duplicate_and_overriding_imports_are_reported() {
+    let mut engine = Aether::new();
+    engine
+        .eval(r#"Import {STACK_NEW, STACK_NEW} From "stdlib:stack""#)
+        .unwrap();
+    let warnings = engine.take_warnings();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].kind, WarningKind::Import);
+    assert!(warnings[0].message.contains("more than once"));
+
+    // 重复导入相同的值不算覆盖
+    engine
+        .eval(r#"Import {STACK_NEW} From "stdlib:stack""#)
+        .unwrap();
+    assert!(engine.take_warnings().is_empty());
+
+    engine
+        .eval("Set STACK_PUSH 1\nImport {STACK_PUSH} From \"stdlib:stack\"")
+        .unwrap();
+    let warnings = engine.take_warnings();
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].message.contains("replaces an existing Integer"));
+}
+
+#[test]
+fn warnings_accumulate_until_taken() {
+    let mut engine = Aether::new();
+    engine.eval(r#"{"k": 1, "k": 2}"#).unwrap();
+    engine.eval("12345678901234567890123 - 0.5").unwrap();
+    assert_eq!(engine.warnings().len(), 2);
+    assert_eq!(engine.take_warnings().len(), 2);
+    assert!(engine.warnings().is_empty());
+}
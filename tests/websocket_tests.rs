@@ -0,0 +1,269 @@
+// tests/websocket_tests.rs
+//! WebSocket / SSE 客户端测试（需要 async 特性）
+#![cfg(feature = "async")]
+
+use aether::builtins::websocket::accept_key;
+use aether::{Aether, Value};
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+
+/// 读取请求头，返回完整的请求头文本
+fn read_request(stream: &mut TcpStream) -> String {
+    let mut data = Vec::new();
+    let mut byte = [0u8; 1];
+    while !data.ends_with(b"\r\n\r\n") {
+        stream.read_exact(&mut byte).unwrap();
+        data.push(byte[0]);
+    }
+    String::from_utf8(data).unwrap()
+}
+
+/// 读取一个客户端帧（客户端帧必须带掩码），返回 (opcode, payload)
+fn read_client_frame(stream: &mut TcpStream) -> (u8, Vec<u8>) {
+    let mut head = [0u8; 2];
+    stream.read_exact(&mut head).unwrap();
+    assert!(head[1] & 0x80 != 0, "client frames must be masked");
+    let len = match head[1] & 0x7F {
+        126 => {
+            let mut ext = [0u8; 2];
+            stream.read_exact(&mut ext).unwrap();
+            u16::from_be_bytes(ext) as usize
+        }
+        len => len as usize,
+    };
+    let mut mask = [0u8; 4];
+    stream.read_exact(&mut mask).unwrap();
+    let mut payload = vec![0u8; len];
+    stream.read_exact(&mut payload).unwrap();
+    for (i, b) in payload.iter_mut().enumerate() {
+        *b ^= mask[i % 4];
+    }
+    (head[0] & 0x0F, payload)
+}
+
+fn server_frame(fin: bool, opcode: u8, payload: &[u8]) -> Vec<u8> {
+    let mut frame = vec![if fin { 0x80 } else { 0 } | opcode, payload.len() as u8];
+    frame.extend_from_slice(payload);
+    frame
+}
+
+/// 接受一个 WebSocket 连接并完成握手
+fn accept_websocket(listener: &TcpListener) -> TcpStream {
+    let (mut stream, _) = listener.accept().unwrap();
+    let request = read_request(&mut stream);
+    let key = request
+        .lines()
+        .find_map(|line| line.strip_prefix("Sec-WebSocket-Key: "))
+        .unwrap()
+        .to_string();
+    write!(
+        stream,
+        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+        accept_key(&key)
+    )
+    .unwrap();
+    stream
+}
+
+#[test]
+fn websocket_accept_key_matches_rfc_example() {
+    assert_eq!(
+        accept_key("dGhlIHNhbXBsZSBub25jZQ=="),
+        "s3pPLMBiTxaQ9kYGzzhZRbK+xOo="
+    );
+}
+
+#[test]
+fn websocket_handshake_echo_and_fragments() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let server = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let request = read_request(&mut stream);
+        let key = request
+            .lines()
+            .find_map(|line| line.strip_prefix("Sec-WebSocket-Key: "))
+            .unwrap()
+            .to_string();
+        assert!(request.contains("X-Token: secret"));
+        write!(
+            stream,
+            "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+            accept_key(&key)
+        )
+        .unwrap();
+
+        // 回显文本消息，先发 ping 再发分片消息
+        let (opcode, payload) = read_client_frame(&mut stream);
+        assert_eq!(opcode, 0x1);
+        stream.write_all(&server_frame(true, 0x9, b"hb")).unwrap();
+        stream
+            .write_all(&server_frame(false, 0x1, b"echo: "))
+            .unwrap();
+        stream
+            .write_all(&server_frame(true, 0x0, &payload))
+            .unwrap();
+
+        let (opcode, payload) = read_client_frame(&mut stream);
+        assert_eq!((opcode, payload.as_slice()), (0xA, b"hb".as_slice()));
+
+        let (opcode, payload) = read_client_frame(&mut stream);
+        assert_eq!(opcode, 0x1);
+        assert_eq!(payload, br#"{"op":"sub"}"#);
+
+        let (opcode, _) = read_client_frame(&mut stream);
+        assert_eq!(opcode, 0x8);
+    });
+
+    let mut engine = Aether::with_all_permissions();
+    let code = format!(
+        r#"
+        Set CONN WS_CONNECT("ws://127.0.0.1:{}/feed", {{"X-Token": "secret"}})
+        WS_SEND(CONN, "hello")
+        Set MSG WS_RECV(CONN, 5)
+        WS_SEND(CONN, {{"op": "sub"}})
+        Set IDLE WS_RECV(CONN, 0.05)
+        Set CLOSED WS_CLOSE(CONN)
+        [MSG, IDLE, CLOSED, WS_CLOSE(CONN)]
+        "#,
+        port
+    );
+    let result = engine.eval(&code).unwrap();
+    server.join().unwrap();
+
+    assert_eq!(
+        result,
+        Value::Array(vec![
+            Value::String("echo: hello".to_string()),
+            Value::Null,
+            Value::Boolean(true),
+            Value::Boolean(false),
+        ])
+    );
+}
+
+#[test]
+fn websocket_connections_belong_to_the_engine_that_opened_them() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let server = thread::spawn(move || {
+        let mut stream = accept_websocket(&listener);
+        let (opcode, payload) = read_client_frame(&mut stream);
+        assert_eq!((opcode, payload.as_slice()), (0x1, b"mine".as_slice()));
+        // reset_env 正常关闭连接
+        let (opcode, payload) = read_client_frame(&mut stream);
+        assert_eq!((opcode, payload.as_slice()), (0x8, [0x03, 0xE8].as_slice()));
+    });
+
+    let mut owner = Aether::with_all_permissions();
+    let handle = owner
+        .eval(&format!(r#"WS_CONNECT("ws://127.0.0.1:{}")"#, port))
+        .unwrap();
+    let Value::String(handle) = handle else {
+        panic!("expected a handle, got {:?}", handle);
+    };
+    assert!(handle.starts_with("ws:") && handle.len() == "ws:".len() + 32);
+
+    // 其他引擎不能使用或关闭这个连接
+    let mut other = Aether::with_all_permissions();
+    assert!(
+        other
+            .eval(&format!(r#"WS_SEND("{}", "theirs")"#, handle))
+            .unwrap_err()
+            .contains("not open")
+    );
+    assert_eq!(
+        other.eval(&format!(r#"WS_CLOSE("{}")"#, handle)).unwrap(),
+        Value::Boolean(false)
+    );
+    owner
+        .eval(&format!(r#"WS_SEND("{}", "mine")"#, handle))
+        .unwrap();
+
+    owner.reset_env();
+    server.join().unwrap();
+    assert!(
+        owner
+            .eval(&format!(r#"WS_SEND("{}", "again")"#, handle))
+            .is_err()
+    );
+}
+
+#[test]
+fn websocket_rejects_bad_handshake() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let server = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        read_request(&mut stream);
+        stream
+            .write_all(b"HTTP/1.1 101 Switching Protocols\r\nSec-WebSocket-Accept: wrong\r\n\r\n")
+            .unwrap();
+    });
+
+    let mut engine = Aether::with_all_permissions();
+    let result = engine.eval(&format!(r#"WS_CONNECT("ws://127.0.0.1:{}")"#, port));
+    server.join().unwrap();
+    assert!(result.unwrap_err().contains("Sec-WebSocket-Accept"));
+    assert!(engine.eval(r#"WS_CONNECT("http://127.0.0.1:1")"#).is_err());
+}
+
+#[test]
+fn sse_receives_chunked_events() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let server = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let request = read_request(&mut stream);
+        assert!(request.contains("Accept: text/event-stream"));
+        stream
+            .write_all(b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nTransfer-Encoding: chunked\r\n\r\n")
+            .unwrap();
+        let body = ": keep-alive\n\nid: 7\nevent: price\ndata: {\"p\": 1}\ndata: line2\n\ndata: plain\r\n\r\n";
+        // 拆成两个分块，事件跨越分块边界
+        let (a, b) = body.split_at(20);
+        for part in [a, b] {
+            write!(stream, "{:x}\r\n{}\r\n", part.len(), part).unwrap();
+        }
+        stream.write_all(b"0\r\n\r\n").unwrap();
+    });
+
+    let mut engine = Aether::with_all_permissions();
+    let code = format!(
+        r#"
+        Set EVENTS SSE_CONNECT("http://127.0.0.1:{}/events")
+        Set FIRST SSE_RECV(EVENTS, 5)
+        Set SECOND SSE_RECV(EVENTS, 5)
+        [FIRST["event"], FIRST["data"], FIRST["id"], SECOND["event"], SECOND["data"], SECOND["id"]]
+        "#,
+        port
+    );
+    let result = engine.eval(&code).unwrap();
+    let ended = engine.eval("SSE_RECV(EVENTS, 5)");
+    server.join().unwrap();
+
+    assert_eq!(
+        result,
+        Value::Array(vec![
+            Value::String("price".to_string()),
+            Value::String("{\"p\": 1}\nline2".to_string()),
+            Value::String("7".to_string()),
+            Value::String("message".to_string()),
+            Value::String("plain".to_string()),
+            Value::String("7".to_string()),
+        ])
+    );
+    assert!(ended.unwrap_err().contains("ended"));
+    assert_eq!(
+        engine.eval("SSE_CLOSE(EVENTS)").unwrap(),
+        Value::Boolean(true)
+    );
+}
+
+#[test]
+fn streaming_clients_require_network_permission() {
+    let mut engine = Aether::new();
+    assert!(engine.eval(r#"WS_CONNECT("ws://127.0.0.1:1")"#).is_err());
+    assert!(engine.eval(r#"SSE_CONNECT("http://127.0.0.1:1")"#).is_err());
+}